@@ -0,0 +1,395 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::monitoring::MetricsCollector;
+
+/// Per-user and global spend limits for LLM calls
+pub struct AiBudgetManager {
+    config: AiBudgetConfig,
+    state: RwLock<BudgetState>,
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+/// Budget configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiBudgetConfig {
+    /// Interactive calls (e.g. /analyze) each user gets per UTC day
+    pub per_user_daily_calls: u32,
+    /// Global token ceiling per UTC day
+    pub global_daily_tokens: u64,
+    /// Global cost ceiling per UTC day in USD
+    pub global_daily_cost_usd: f64,
+    /// Fraction of the global budget held back for system-priority calls
+    pub system_reserve_fraction: f64,
+    pub prompt_cost_per_1k_tokens: f64,
+    pub completion_cost_per_1k_tokens: f64,
+}
+
+/// Who is asking for the call; system work outranks casual use when the budget is tight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AiPriority {
+    /// Automated evaluation (signal generation)
+    System,
+    /// User-triggered requests such as /analyze
+    Interactive,
+}
+
+/// Result of a budget check
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetDecision {
+    Allowed,
+    UserQuotaExceeded {
+        used: u32,
+        limit: u32,
+        resets_at: DateTime<Utc>,
+    },
+    GlobalBudgetExhausted {
+        resets_at: DateTime<Utc>,
+    },
+}
+
+/// Snapshot of current spend for the admin view
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetUsage {
+    pub day: NaiveDate,
+    pub tokens_used: u64,
+    pub token_budget: u64,
+    pub cost_usd: f64,
+    pub cost_budget_usd: f64,
+    pub calls_by_priority: HashMap<AiPriority, u64>,
+    pub denied_calls: u64,
+    pub active_users: usize,
+    pub resets_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    day: NaiveDate,
+    user_calls: HashMap<String, u32>,
+    tokens_used: u64,
+    cost_usd: f64,
+    calls_by_priority: HashMap<AiPriority, u64>,
+    denied_calls: u64,
+}
+
+impl Default for AiBudgetConfig {
+    fn default() -> Self {
+        Self {
+            per_user_daily_calls: 20,
+            global_daily_tokens: 2_000_000,
+            global_daily_cost_usd: 5.0,
+            system_reserve_fraction: 0.2,
+            // Groq Llama 3.1 70B list pricing
+            prompt_cost_per_1k_tokens: 0.00059,
+            completion_cost_per_1k_tokens: 0.00079,
+        }
+    }
+}
+
+impl AiBudgetConfig {
+    /// Build configuration from environment variables
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            per_user_daily_calls: std::env::var("AI_USER_DAILY_CALLS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.per_user_daily_calls),
+            global_daily_tokens: std::env::var("AI_GLOBAL_DAILY_TOKENS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.global_daily_tokens),
+            global_daily_cost_usd: std::env::var("AI_GLOBAL_DAILY_COST_USD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.global_daily_cost_usd),
+            ..defaults
+        }
+    }
+}
+
+impl BudgetState {
+    fn new(day: NaiveDate) -> Self {
+        Self {
+            day,
+            user_calls: HashMap::new(),
+            tokens_used: 0,
+            cost_usd: 0.0,
+            calls_by_priority: HashMap::new(),
+            denied_calls: 0,
+        }
+    }
+}
+
+impl AiBudgetManager {
+    pub fn new(config: AiBudgetConfig, metrics: Option<Arc<MetricsCollector>>) -> Self {
+        info!(
+            "🧮 AI budget: {} calls/user/day, {} tokens and ${:.2} per day globally",
+            config.per_user_daily_calls, config.global_daily_tokens, config.global_daily_cost_usd
+        );
+
+        Self {
+            config,
+            state: RwLock::new(BudgetState::new(Utc::now().date_naive())),
+            metrics,
+        }
+    }
+
+    /// Check whether a call may proceed and, if so, count it against the user's quota
+    pub async fn authorize(&self, user_id: Option<&str>, priority: AiPriority) -> BudgetDecision {
+        self.authorize_at(user_id, priority, Utc::now()).await
+    }
+
+    pub(crate) async fn authorize_at(
+        &self,
+        user_id: Option<&str>,
+        priority: AiPriority,
+        now: DateTime<Utc>,
+    ) -> BudgetDecision {
+        let mut state = self.state.write().await;
+        Self::roll_day(&mut state, now);
+        let resets_at = Self::next_reset(now);
+
+        let used_fraction = self.used_fraction(&state);
+        let ceiling = match priority {
+            AiPriority::System => 1.0,
+            AiPriority::Interactive => 1.0 - self.config.system_reserve_fraction,
+        };
+
+        if used_fraction >= ceiling {
+            state.denied_calls += 1;
+            warn!("🧮 AI budget exhausted for {:?} calls ({:.0}% used)", priority, used_fraction * 100.0);
+            return BudgetDecision::GlobalBudgetExhausted { resets_at };
+        }
+
+        if priority == AiPriority::Interactive {
+            if let Some(user_id) = user_id {
+                let used = state.user_calls.get(user_id).copied().unwrap_or(0);
+                if used >= self.config.per_user_daily_calls {
+                    state.denied_calls += 1;
+                    return BudgetDecision::UserQuotaExceeded {
+                        used,
+                        limit: self.config.per_user_daily_calls,
+                        resets_at,
+                    };
+                }
+                state.user_calls.insert(user_id.to_string(), used + 1);
+            }
+        }
+
+        *state.calls_by_priority.entry(priority).or_insert(0) += 1;
+        BudgetDecision::Allowed
+    }
+
+    /// Record token usage reported by the API and return the estimated cost
+    pub async fn record_usage(&self, feature: &str, model: &str, prompt_tokens: u32, completion_tokens: u32) -> f64 {
+        self.record_usage_at(feature, model, prompt_tokens, completion_tokens, Utc::now()).await
+    }
+
+    pub(crate) async fn record_usage_at(
+        &self,
+        feature: &str,
+        model: &str,
+        prompt_tokens: u32,
+        completion_tokens: u32,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        let cost = self.estimate_cost(prompt_tokens, completion_tokens);
+
+        {
+            let mut state = self.state.write().await;
+            Self::roll_day(&mut state, now);
+            state.tokens_used += (prompt_tokens + completion_tokens) as u64;
+            state.cost_usd += cost;
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_ai_usage(feature, model, prompt_tokens, completion_tokens, cost);
+        }
+
+        cost
+    }
+
+    /// Estimated USD cost for a call
+    pub fn estimate_cost(&self, prompt_tokens: u32, completion_tokens: u32) -> f64 {
+        prompt_tokens as f64 / 1000.0 * self.config.prompt_cost_per_1k_tokens
+            + completion_tokens as f64 / 1000.0 * self.config.completion_cost_per_1k_tokens
+    }
+
+    /// Current spend versus budget
+    pub async fn usage(&self) -> BudgetUsage {
+        let now = Utc::now();
+        let mut state = self.state.write().await;
+        Self::roll_day(&mut state, now);
+
+        BudgetUsage {
+            day: state.day,
+            tokens_used: state.tokens_used,
+            token_budget: self.config.global_daily_tokens,
+            cost_usd: state.cost_usd,
+            cost_budget_usd: self.config.global_daily_cost_usd,
+            calls_by_priority: state.calls_by_priority.clone(),
+            denied_calls: state.denied_calls,
+            active_users: state.user_calls.len(),
+            resets_at: Self::next_reset(now),
+        }
+    }
+
+    /// Remaining interactive calls for a user today
+    pub async fn remaining_calls(&self, user_id: &str) -> u32 {
+        let state = self.state.read().await;
+        if state.day != Utc::now().date_naive() {
+            return self.config.per_user_daily_calls;
+        }
+        self.config.per_user_daily_calls
+            .saturating_sub(state.user_calls.get(user_id).copied().unwrap_or(0))
+    }
+
+    pub fn config(&self) -> &AiBudgetConfig {
+        &self.config
+    }
+
+    fn used_fraction(&self, state: &BudgetState) -> f64 {
+        let token_fraction = if self.config.global_daily_tokens > 0 {
+            state.tokens_used as f64 / self.config.global_daily_tokens as f64
+        } else {
+            1.0
+        };
+        let cost_fraction = if self.config.global_daily_cost_usd > 0.0 {
+            state.cost_usd / self.config.global_daily_cost_usd
+        } else {
+            1.0
+        };
+        token_fraction.max(cost_fraction)
+    }
+
+    fn roll_day(state: &mut BudgetState, now: DateTime<Utc>) {
+        let today = now.date_naive();
+        if state.day != today {
+            info!("🧮 Resetting AI budget for {}", today);
+            *state = BudgetState::new(today);
+        }
+    }
+
+    fn next_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+        let tomorrow = now.date_naive() + Duration::days(1);
+        Utc.from_utc_datetime(&tomorrow.and_hms_opt(0, 0, 0).expect("midnight is valid"))
+    }
+}
+
+impl BudgetDecision {
+    /// Friendly explanation for the user
+    pub fn user_message(&self) -> Option<String> {
+        match self {
+            BudgetDecision::Allowed => None,
+            BudgetDecision::UserQuotaExceeded { limit, resets_at, .. } => Some(format!(
+                "🧮 You've used your {} free analyses today. Your quota resets at {} ({}).",
+                limit,
+                resets_at.format("%H:%M UTC"),
+                format_until(*resets_at - Utc::now())
+            )),
+            BudgetDecision::GlobalBudgetExhausted { resets_at } => Some(format!(
+                "🧮 AI analysis is paused until {} — showing heuristic analysis instead.",
+                resets_at.format("%H:%M UTC")
+            )),
+        }
+    }
+}
+
+fn format_until(delta: Duration) -> String {
+    let hours = delta.num_hours();
+    let minutes = delta.num_minutes() % 60;
+    if hours > 0 {
+        format!("in {}h {}m", hours, minutes)
+    } else {
+        format!("in {}m", minutes.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(calls: u32, tokens: u64) -> AiBudgetManager {
+        AiBudgetManager::new(
+            AiBudgetConfig {
+                per_user_daily_calls: calls,
+                global_daily_tokens: tokens,
+                global_daily_cost_usd: 1000.0,
+                ..AiBudgetConfig::default()
+            },
+            None,
+        )
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_user_quota_exhaustion_and_reset() {
+        let budget = manager(2, 1_000_000);
+        let now = at(10, 9);
+        // Prime the state to the test day
+        budget.record_usage_at("test", "m", 0, 0, now).await;
+
+        assert_eq!(budget.authorize_at(Some("alice"), AiPriority::Interactive, now).await, BudgetDecision::Allowed);
+        assert_eq!(budget.authorize_at(Some("alice"), AiPriority::Interactive, now).await, BudgetDecision::Allowed);
+
+        let denied = budget.authorize_at(Some("alice"), AiPriority::Interactive, now).await;
+        assert_eq!(denied, BudgetDecision::UserQuotaExceeded { used: 2, limit: 2, resets_at: at(11, 0) });
+
+        // Other users are unaffected
+        assert_eq!(budget.authorize_at(Some("bob"), AiPriority::Interactive, now).await, BudgetDecision::Allowed);
+
+        // Quota comes back at UTC midnight
+        let next_day = at(11, 0);
+        assert_eq!(budget.authorize_at(Some("alice"), AiPriority::Interactive, next_day).await, BudgetDecision::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_global_exhaustion_engages_fallback() {
+        let budget = manager(100, 1_000);
+        let now = at(10, 12);
+
+        budget.record_usage_at("analyze", "m", 700, 300, now).await;
+
+        let decision = budget.authorize_at(Some("alice"), AiPriority::Interactive, now).await;
+        assert_eq!(decision, BudgetDecision::GlobalBudgetExhausted { resets_at: at(11, 0) });
+        assert!(decision.user_message().unwrap().contains("heuristic"));
+
+        let decision = budget.authorize_at(None, AiPriority::System, now).await;
+        assert_eq!(decision, BudgetDecision::GlobalBudgetExhausted { resets_at: at(11, 0) });
+
+        // Spend resets with the day
+        assert_eq!(budget.authorize_at(Some("alice"), AiPriority::Interactive, at(11, 0)).await, BudgetDecision::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_system_priority_uses_reserve() {
+        let budget = manager(100, 1_000);
+        let now = at(10, 12);
+
+        // 85% used: past the interactive ceiling (80%) but inside the system reserve
+        budget.record_usage_at("signals", "m", 850, 0, now).await;
+
+        assert!(matches!(
+            budget.authorize_at(Some("alice"), AiPriority::Interactive, now).await,
+            BudgetDecision::GlobalBudgetExhausted { .. }
+        ));
+        assert_eq!(budget.authorize_at(None, AiPriority::System, now).await, BudgetDecision::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_cost_estimation() {
+        let budget = manager(10, 1_000);
+        let cost = budget.estimate_cost(1000, 1000);
+        let expected = budget.config().prompt_cost_per_1k_tokens + budget.config().completion_cost_per_1k_tokens;
+        assert!((cost - expected).abs() < 1e-12);
+    }
+}
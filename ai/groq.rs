@@ -1,14 +1,155 @@
+use crate::cache::CacheManager;
 use crate::errors::{BotError, Result};
+use crate::middleware::{CallOutcome, CircuitBreaker, CircuitBreakerConfig};
+use crate::monitoring::MetricsCollector;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tracing::{info, debug};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::{info, debug, warn};
+
+use super::budget::{AiBudgetManager, AiPriority, BudgetDecision};
+
+pub(crate) const GROQ_API_URL: &str = "https://api.groq.com/openai/v1";
+const GROQ_MODEL: &str = "llama-3.1-70b-instruct";
+/// Tried when the primary model errors or times out
+const GROQ_FALLBACK_MODEL: &str = "llama-3.1-8b-instant";
+/// Part of the cache key, so changing the prompt doesn't serve answers to the old one
+const PROMPT_VERSION: &str = "v2";
+const REQUEST_TIMEOUT_SECS: u64 = 20;
+
+const ANALYSIS_SYSTEM_PROMPT: &str = r#"You are a cryptocurrency market analyst specializing in Solana tokens.
+Analyze tokens based on available market data and sentiment. Provide clear, actionable insights.
+Respond with a single JSON object and nothing else, using exactly these fields:
+{"signal": "BUY" | "HOLD" | "SELL",
+ "confidence": number between 0 and 1,
+ "summary": string of at most 50 words,
+ "key_factors": array of 1 to 5 short strings,
+ "risk_flags": array of short strings, empty if none}"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AnalysisSignal {
+    Buy,
+    Hold,
+    Sell,
+}
+
+impl AnalysisSignal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnalysisSignal::Buy => "BUY",
+            AnalysisSignal::Hold => "HOLD",
+            AnalysisSignal::Sell => "SELL",
+        }
+    }
+}
+
+impl fmt::Display for AnalysisSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketAnalysis {
     pub summary: String,
-    pub signal: String,
+    pub signal: AnalysisSignal,
+    /// 0 to 1
     pub confidence: f64,
     pub key_factors: Vec<String>,
+    #[serde(default)]
+    pub risk_flags: Vec<String>,
+    /// Model that answered, or "heuristic"
+    #[serde(default)]
+    pub model_used: String,
+    /// Served from the analysis cache rather than a fresh call
+    #[serde(default)]
+    pub cached: bool,
+    #[serde(default = "Utc::now")]
+    pub analyzed_at: DateTime<Utc>,
+}
+
+impl MarketAnalysis {
+    /// "cached 4m ago" for answers served from the cache
+    pub fn cache_note(&self) -> Option<String> {
+        if !self.cached {
+            return None;
+        }
+        let minutes = (Utc::now() - self.analyzed_at).num_minutes();
+        Some(if minutes < 1 {
+            "cached just now".to_string()
+        } else {
+            format!("cached {}m ago", minutes)
+        })
+    }
+}
+
+/// Why a model's answer didn't match the analysis schema
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum AnalysisSchemaError {
+    #[error("response is not a valid analysis object: {0}")]
+    InvalidJson(String),
+    #[error("unknown signal {0:?}, expected BUY, HOLD or SELL")]
+    UnknownSignal(String),
+    #[error("confidence {0} is outside 0 to 1")]
+    ConfidenceOutOfRange(f64),
+    #[error("summary is empty")]
+    EmptySummary,
+    #[error("key_factors is empty")]
+    NoKeyFactors,
+}
+
+/// Analysis object as the model returns it, before validation
+#[derive(Debug, Deserialize)]
+struct AnalysisResponse {
+    signal: String,
+    confidence: f64,
+    summary: String,
+    key_factors: Vec<String>,
+    #[serde(default)]
+    risk_flags: Vec<String>,
+}
+
+/// Parse and check a JSON-mode answer against the analysis schema
+pub fn validate_analysis(content: &str) -> std::result::Result<MarketAnalysis, AnalysisSchemaError> {
+    let response: AnalysisResponse = serde_json::from_str(content.trim())
+        .map_err(|e| AnalysisSchemaError::InvalidJson(e.to_string()))?;
+
+    let signal = match response.signal.trim().to_uppercase().as_str() {
+        "BUY" => AnalysisSignal::Buy,
+        "HOLD" => AnalysisSignal::Hold,
+        "SELL" => AnalysisSignal::Sell,
+        _ => return Err(AnalysisSchemaError::UnknownSignal(response.signal)),
+    };
+    if !(0.0..=1.0).contains(&response.confidence) {
+        return Err(AnalysisSchemaError::ConfidenceOutOfRange(response.confidence));
+    }
+    let summary = response.summary.trim().to_string();
+    if summary.is_empty() {
+        return Err(AnalysisSchemaError::EmptySummary);
+    }
+    let non_empty = |items: Vec<String>| -> Vec<String> {
+        items.into_iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+    };
+    let key_factors = non_empty(response.key_factors);
+    if key_factors.is_empty() {
+        return Err(AnalysisSchemaError::NoKeyFactors);
+    }
+
+    Ok(MarketAnalysis {
+        summary,
+        signal,
+        confidence: response.confidence,
+        key_factors,
+        risk_flags: non_empty(response.risk_flags),
+        model_used: String::new(),
+        cached: false,
+        analyzed_at: Utc::now(),
+    })
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -19,9 +160,23 @@ struct GroqRequest {
     max_tokens: u32,
     top_p: f64,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ResponseFormat {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+impl ResponseFormat {
+    fn json_object() -> Self {
+        Self { kind: "json_object".to_string() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Message {
     role: String,
     content: String,
@@ -47,9 +202,29 @@ struct Usage {
     total_tokens: u32,
 }
 
+/// Analysis result after the budget check
+#[derive(Debug, Clone)]
+pub enum AnalysisOutcome {
+    /// LLM-backed analysis
+    Ai(MarketAnalysis),
+    /// Budget denied the call; heuristic analysis returned instead
+    Heuristic {
+        analysis: MarketAnalysis,
+        decision: BudgetDecision,
+    },
+}
+
 pub struct GroqAnalyzer {
     api_key: String,
     client: Client,
+    budget: Option<Arc<AiBudgetManager>>,
+    base_url: String,
+    /// Primary first, then fallbacks in order
+    models: Vec<String>,
+    timeout: Duration,
+    cache: Option<Arc<CacheManager>>,
+    breaker: Arc<CircuitBreaker>,
+    metrics: Option<Arc<MetricsCollector>>,
 }
 
 impl GroqAnalyzer {
@@ -57,144 +232,278 @@ impl GroqAnalyzer {
         Self {
             api_key,
             client: Client::new(),
+            budget: None,
+            base_url: GROQ_API_URL.to_string(),
+            models: vec![GROQ_MODEL.to_string(), GROQ_FALLBACK_MODEL.to_string()],
+            timeout: Duration::from_secs(REQUEST_TIMEOUT_SECS),
+            cache: None,
+            breaker: Arc::new(CircuitBreaker::new("groq".to_string(), CircuitBreakerConfig::default())),
+            metrics: None,
+        }
+    }
+    
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+    
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.models[0] = model.into();
+        self
+    }
+    
+    /// Model retried when the primary fails; `None` disables the fallback
+    pub fn with_fallback_model(mut self, model: Option<String>) -> Self {
+        self.models.truncate(1);
+        self.models.extend(model);
+        self
+    }
+    
+    /// How long each model gets before the next one is tried
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    /// Reuse token analyses for the cache's AI analysis TTL
+    pub fn with_cache(mut self, cache: Arc<CacheManager>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+    
+    /// Meter every call against the given budget
+    pub fn with_budget(mut self, budget: Arc<AiBudgetManager>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+    
+    /// Use a configured circuit breaker, e.g. one reporting to the alert manager
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+    
+    /// Record request latencies
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    pub fn budget(&self) -> Option<&Arc<AiBudgetManager>> {
+        self.budget.as_ref()
+    }
+    
+    /// Analyze a token if the budget allows, otherwise fall back to heuristics
+    pub async fn analyze_token_with_budget(
+        &self,
+        token: &str,
+        user_id: Option<&str>,
+        priority: AiPriority,
+    ) -> Result<AnalysisOutcome> {
+        // A cached answer costs nothing, so it doesn't need the budget
+        if let Some(analysis) = self.cached_analysis(token).await {
+            return Ok(AnalysisOutcome::Ai(analysis));
+        }
+        
+        if let Some(budget) = &self.budget {
+            let decision = budget.authorize(user_id, priority).await;
+            if decision != BudgetDecision::Allowed {
+                debug!("AI call for {} denied by budget: {:?}", token, decision);
+                return Ok(AnalysisOutcome::Heuristic {
+                    analysis: Self::heuristic_analysis(token),
+                    decision,
+                });
+            }
         }
+        
+        self.analyze_token(token).await.map(AnalysisOutcome::Ai)
+    }
+    
+    /// Neutral analysis used when AI calls are unavailable
+    pub fn heuristic_analysis(token: &str) -> MarketAnalysis {
+        MarketAnalysis {
+            summary: format!(
+                "AI analysis for {} is temporarily unavailable. No strong directional signal from heuristics.",
+                token
+            ),
+            signal: AnalysisSignal::Hold,
+            confidence: 0.5,
+            key_factors: vec![
+                "Heuristic fallback".to_string(),
+                "Check volume and liquidity manually".to_string(),
+            ],
+            risk_flags: Vec::new(),
+            model_used: "heuristic".to_string(),
+            cached: false,
+            analyzed_at: Utc::now(),
+        }
+    }
+    
+    async fn cached_analysis(&self, token: &str) -> Option<MarketAnalysis> {
+        let mut analysis: MarketAnalysis = self.cache.as_ref()?.get_ai_analysis(token, PROMPT_VERSION).await?;
+        analysis.cached = true;
+        Some(analysis)
     }
     
     pub async fn analyze_token(&self, token: &str) -> Result<MarketAnalysis> {
-        let system_prompt = r#"You are a cryptocurrency market analyst specializing in Solana tokens.
-        Analyze tokens based on available market data and sentiment.
-        Provide clear, actionable insights.
-        Response format: Summary|Signal|Confidence|Factor1,Factor2,Factor3"#;
+        if let Some(analysis) = self.cached_analysis(token).await {
+            debug!("Serving cached analysis for {}", token);
+            return Ok(analysis);
+        }
         
         let user_prompt = format!(
-            "Analyze {} token for trading. Consider market trends, volume, and sentiment. \
-            Provide: 1) Market summary (50 words), 2) Trading signal (BUY/HOLD/SELL), \
-            3) Confidence percentage (0-100), 4) Three key factors affecting the token. \
-            Format response as: Summary|Signal|Confidence|Factor1,Factor2,Factor3",
+            "Analyze the {} token for trading. Consider market trends, volume, and sentiment.",
             token
         );
         
         debug!("Analyzing token: {}", token);
         
-        let request = GroqRequest {
-            model: "llama-3.1-70b-instruct".to_string(), // Updated to Llama 3.1
-            messages: vec![
-                Message {
+        let mut messages = vec![
+            Message {
+                role: "system".to_string(),
+                content: ANALYSIS_SYSTEM_PROMPT.to_string(),
+            },
+            Message {
+                role: "user".to_string(),
+                content: user_prompt,
+            },
+        ];
+        let (content, model) = self.complete("analyze_token", messages.clone(), 0.3, 300, true).await?;
+        
+        let mut analysis = match validate_analysis(&content) {
+            Ok(mut analysis) => {
+                analysis.model_used = model;
+                analysis
+            }
+            Err(e) => {
+                // One retry, telling the model what was wrong with its answer
+                warn!("Groq analysis for {} failed validation ({}), retrying", token, e);
+                messages.push(Message {
+                    role: "assistant".to_string(),
+                    content,
+                });
+                messages.push(Message {
                     role: "system".to_string(),
-                    content: system_prompt.to_string(),
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: user_prompt,
-                },
-            ],
-            temperature: 0.3,
-            max_tokens: 200,
-            top_p: 0.9,
-            stream: false,
+                    content: format!(
+                        "Your previous answer was rejected: {}. Reply again with only the JSON object described above.",
+                        e
+                    ),
+                });
+                let (content, model) = self.complete("analyze_token", messages, 0.1, 300, true).await?;
+                let mut analysis = validate_analysis(&content).map_err(|e| {
+                    BotError::ai_parse(format!("Groq analysis for {} still invalid after retry: {}", token, e))
+                })?;
+                analysis.model_used = model;
+                analysis
+            }
         };
-        
-        let response = self.client
-            .post("https://api.groq.com/openai/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(BotError::external_api(format!("Groq API error: {}", error_text)));
-        }
-        
-        let groq_response: GroqResponse = response.json().await?;
-        
-        let content = groq_response.choices
-            .first()
-            .map(|c| &c.message.content)
-            .ok_or_else(|| BotError::external_api("No response from Groq"))?;
-        
-        let analysis = self.parse_analysis(content)?;
+        analysis.analyzed_at = Utc::now();
         
         info!(
-            "Analysis complete for {}: Signal={}, Confidence={}%",
-            token, analysis.signal, analysis.confidence
+            "Analysis complete for {} on {}: Signal={}, Confidence={}%",
+            token, analysis.model_used, analysis.signal, analysis.confidence
         );
         
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.cache_ai_analysis(token, PROMPT_VERSION, &analysis).await {
+                warn!("Failed to cache analysis for {}: {:?}", token, e);
+            }
+        }
+        
         Ok(analysis)
     }
     
     pub async fn analyze_market_conditions(&self) -> Result<String> {
-        let request = GroqRequest {
-            model: "llama-3.1-70b-instruct".to_string(), // Updated to Llama 3.1
-            messages: vec![
-                Message {
-                    role: "system".to_string(),
-                    content: "You are a crypto market analyst. Provide brief market updates.".to_string(),
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: "Provide a brief Solana market update in 50 words.".to_string(),
-                },
-            ],
-            temperature: 0.5,
-            max_tokens: 100,
-            top_p: 0.9,
-            stream: false,
-        };
-        
-        let response = self.client
-            .post("https://api.groq.com/openai/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request)
-            .send()
-            .await?;
+        if let Some(budget) = &self.budget {
+            if budget.authorize(None, AiPriority::Interactive).await != BudgetDecision::Allowed {
+                warn!("AI budget exhausted, skipping market conditions update");
+                return Ok("Market conditions normal.".to_string());
+            }
+        }
         
-        let groq_response: GroqResponse = response.json().await?;
+        let messages = vec![
+            Message {
+                role: "system".to_string(),
+                content: "You are a crypto market analyst. Provide brief market updates.".to_string(),
+            },
+            Message {
+                role: "user".to_string(),
+                content: "Provide a brief Solana market update in 50 words.".to_string(),
+            },
+        ];
+        let (content, _) = self.complete("market_conditions", messages, 0.5, 100, false).await?;
         
-        Ok(groq_response.choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .unwrap_or_else(|| "Market conditions normal.".to_string()))
+        Ok(content)
     }
     
-    fn parse_analysis(&self, content: &str) -> Result<MarketAnalysis> {
-        let parts: Vec<&str> = content.split('|').collect();
-        
-        if parts.len() < 4 {
-            return Ok(MarketAnalysis {
-                summary: content.to_string(),
-                signal: "HOLD".to_string(),
-                confidence: 50.0,
-                key_factors: vec!["Market volatility".to_string()],
-            });
+    /// Ask each model in turn until one answers; returns the answer and the model
+    async fn complete(
+        &self,
+        feature: &str,
+        messages: Vec<Message>,
+        temperature: f64,
+        max_tokens: u32,
+        json_mode: bool,
+    ) -> Result<(String, String)> {
+        let mut last_error = None;
+        for model in &self.models {
+            let request = GroqRequest {
+                model: model.clone(),
+                messages: messages.clone(),
+                temperature,
+                max_tokens,
+                top_p: 0.9,
+                stream: false,
+                response_format: json_mode.then(ResponseFormat::json_object),
+            };
+            match self.send(feature, &request).await {
+                Ok(content) => return Ok((content, model.clone())),
+                Err(e) => {
+                    warn!("Groq {} failed on {}: {}", feature, model, e);
+                    last_error = Some(e);
+                }
+            }
         }
+        Err(last_error.unwrap_or_else(|| BotError::external_api("No Groq models configured")))
+    }
+    
+    async fn send(&self, feature: &str, request: &GroqRequest) -> Result<String> {
+        let http_request = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .timeout(self.timeout)
+            .json(request);
+        let started = Instant::now();
+        let response = self.breaker
+            .call(async { http_request.send().await.map_err(BotError::from) }, CallOutcome::of_response)
+            .await;
+        if let Some(metrics) = &self.metrics {
+            let success = matches!(&response, Ok(response) if response.status().is_success());
+            metrics.record_api_call("groq_chat", "POST", success, started.elapsed().as_secs_f64() * 1000.0);
+        }
+        let response = response?;
         
-        let summary = parts[0].trim().to_string();
-        let signal = parts[1].trim().to_uppercase();
-        let confidence = parts[2]
-            .trim()
-            .replace('%', "")
-            .parse::<f64>()
-            .unwrap_or(50.0)
-            .clamp(0.0, 100.0);
-        
-        let factors: Vec<String> = parts.get(3)
-            .map(|f| f.split(',')
-                .map(|s| s.trim().to_string())
-                .collect())
-            .unwrap_or_else(|| vec!["Market conditions".to_string()]);
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await?;
+            return Err(BotError::external_api(format!("Groq API error ({}): {}", status, error_text)));
+        }
         
-        let valid_signal = match signal.as_str() {
-            "BUY" | "SELL" | "HOLD" => signal,
-            _ => "HOLD".to_string(),
-        };
+        let groq_response: GroqResponse = response.json().await?;
+        self.record_usage(feature, &request.model, &groq_response.usage).await;
         
-        Ok(MarketAnalysis {
-            summary,
-            signal: valid_signal,
-            confidence,
-            key_factors: factors,
-        })
+        groq_response.choices
+            .into_iter()
+            .next()
+            .map(|c| c.message.content)
+            .ok_or_else(|| BotError::external_api("No response from Groq"))
+    }
+    
+    async fn record_usage(&self, feature: &str, model: &str, usage: &Usage) {
+        if let Some(budget) = &self.budget {
+            let cost = budget.record_usage(feature, model, usage.prompt_tokens, usage.completion_tokens).await;
+            debug!("Groq {} used {} tokens (~${:.5})", feature, usage.total_tokens, cost);
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,16 @@
 mod groq;
+mod budget;
 mod signals;
+mod signal_outcomes;
 
-pub use groq::{GroqAnalyzer, MarketAnalysis};
-pub use signals::{SignalGenerator, TradingSignal, SignalType, SignalStrength};
\ No newline at end of file
+pub use groq::{validate_analysis, AnalysisSchemaError, AnalysisSignal, GroqAnalyzer, MarketAnalysis, AnalysisOutcome};
+pub(crate) use groq::GROQ_API_URL;
+pub use budget::{AiBudgetManager, AiBudgetConfig, AiPriority, BudgetDecision, BudgetUsage};
+pub use signals::{
+    SignalGenerator, TradingSignal, SignalType, SignalStrength, SignalPerformance,
+    TechnicalIndicators, VolumeTrend, HolderTrend, MarketConditions,
+};
+pub use signal_outcomes::{
+    evaluate_signal, OutcomeCounts, SignalOutcome, SignalOutcomeTracker, SignalPriceHistory, SignalStats,
+    SignalStatus, TrackedSignal,
+};
@@ -0,0 +1,365 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::api::{HistoricalPriceRequest, JupiterPriceV3Client, Timeframe};
+use crate::db::Database;
+use crate::errors::{BotError, Result};
+
+use super::signals::{SignalPerformance, SignalType, TradingSignal};
+
+/// How often the evaluator looks for signals whose window has closed
+const EVALUATION_INTERVAL_SECS: u64 = 5 * 60;
+/// Five-minute history reaches back about 83 hours; past this a window can't be priced
+const MAX_PRICING_DELAY_HOURS: i64 = 72;
+
+/// How a signal's window played out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignalOutcome {
+    HitTarget,
+    HitStop,
+    /// Neither level was reached before the signal expired
+    ExpiredFlat,
+}
+
+/// Stored state of a tracked signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignalStatus {
+    Pending,
+    Evaluated(SignalOutcome),
+    /// No price history covered the window
+    Unpriced,
+}
+
+impl SignalStatus {
+    /// Stored form in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Evaluated(SignalOutcome::HitTarget) => "hit_target",
+            Self::Evaluated(SignalOutcome::HitStop) => "hit_stop",
+            Self::Evaluated(SignalOutcome::ExpiredFlat) => "expired_flat",
+            Self::Unpriced => "unpriced",
+        }
+    }
+}
+
+/// A signal with its entry, levels and, once its window closes, how it did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedSignal {
+    pub signal_id: String,
+    pub signal: TradingSignal,
+    pub status: SignalStatus,
+    pub performance: Option<SignalPerformance>,
+}
+
+/// Hits, stops and returns over a set of evaluated signals
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeCounts {
+    pub evaluated: u32,
+    pub hit_target: u32,
+    pub hit_stop: u32,
+    pub expired_flat: u32,
+    /// Sum of directional returns, in percent
+    pub total_return_percent: f64,
+}
+
+impl OutcomeCounts {
+    fn record(&mut self, performance: &SignalPerformance) {
+        self.evaluated += 1;
+        match performance.outcome {
+            SignalOutcome::HitTarget => self.hit_target += 1,
+            SignalOutcome::HitStop => self.hit_stop += 1,
+            SignalOutcome::ExpiredFlat => self.expired_flat += 1,
+        }
+        self.total_return_percent += performance.return_percent;
+    }
+
+    /// Share of evaluated signals that reached their target, in percent
+    pub fn success_rate(&self) -> f64 {
+        if self.evaluated == 0 {
+            return 0.0;
+        }
+        self.hit_target as f64 / self.evaluated as f64 * 100.0
+    }
+
+    pub fn average_return(&self) -> f64 {
+        if self.evaluated == 0 {
+            return 0.0;
+        }
+        self.total_return_percent / self.evaluated as f64
+    }
+}
+
+/// Aggregate track record, persisted as signals are evaluated
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalStats {
+    /// Every signal tracked, evaluated or not
+    pub total_signals: u32,
+    pub overall: OutcomeCounts,
+    /// In the order each type was first evaluated
+    pub by_type: Vec<(SignalType, OutcomeCounts)>,
+}
+
+impl SignalStats {
+    fn record(&mut self, signal_type: &SignalType, performance: &SignalPerformance) {
+        self.overall.record(performance);
+        match self.by_type.iter_mut().find(|(t, _)| t == signal_type) {
+            Some((_, counts)) => counts.record(performance),
+            None => {
+                let mut counts = OutcomeCounts::default();
+                counts.record(performance);
+                self.by_type.push((signal_type.clone(), counts));
+            }
+        }
+    }
+}
+
+/// Prices a signal's window after the fact
+#[async_trait]
+pub trait SignalPriceHistory: Send + Sync {
+    /// USD prices for `mint` from `from` through `to`, oldest first
+    async fn prices_between(&self, mint: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<(DateTime<Utc>, f64)>>;
+}
+
+#[async_trait]
+impl SignalPriceHistory for JupiterPriceV3Client {
+    async fn prices_between(&self, mint: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<(DateTime<Utc>, f64)>> {
+        // The endpoint serves the most recent candles, so ask for enough to reach back to `from`
+        let candles = ((Utc::now() - from).num_minutes() / 5 + 1).clamp(1, 1000) as u32;
+        let response = self.get_historical_prices(HistoricalPriceRequest {
+            id: mint.to_string(),
+            vs_token: None,
+            timeframe: Timeframe::FiveMinutes,
+            limit: Some(candles),
+        }).await?;
+
+        let mut points: Vec<(DateTime<Utc>, f64)> = response.data.into_iter()
+            .filter(|point| point.timestamp >= from && point.timestamp <= to)
+            .map(|point| (point.timestamp, point.price_usd))
+            .collect();
+        points.sort_by_key(|(at, _)| *at);
+        Ok(points)
+    }
+}
+
+/// Longs profit from a rise, shorts from a fall; holds aren't tracked
+fn is_long(signal_type: &SignalType) -> Option<bool> {
+    match signal_type {
+        SignalType::StrongBuy | SignalType::Buy | SignalType::Accumulate => Some(true),
+        SignalType::StrongSell | SignalType::Sell | SignalType::Distribute => Some(false),
+        SignalType::Hold => None,
+    }
+}
+
+/// Walk the window's prices until the target or stop is touched
+///
+/// A touched level counts as filled at that level. Returns `None` for holds and for
+/// windows with no prices.
+pub fn evaluate_signal(signal: &TradingSignal, prices: &[(DateTime<Utc>, f64)]) -> Option<SignalPerformance> {
+    let long = is_long(&signal.signal_type)?;
+    if signal.entry_price <= 0.0 {
+        return None;
+    }
+    let directional = |price: f64| {
+        let change = (price - signal.entry_price) / signal.entry_price * 100.0;
+        if long { change } else { -change }
+    };
+    let reached = |price: f64, level: f64| if long { price >= level } else { price <= level };
+    let fell_through = |price: f64, level: f64| if long { price <= level } else { price >= level };
+
+    let window: Vec<&(DateTime<Utc>, f64)> = prices.iter()
+        .filter(|(at, _)| *at >= signal.generated_at && *at <= signal.expires_at)
+        .collect();
+    let &&(last_at, last_price) = window.last()?;
+
+    let mut max_profit: f64 = 0.0;
+    let mut max_drawdown: f64 = 0.0;
+    let mut result = (SignalOutcome::ExpiredFlat, last_price, last_at);
+    for &&(at, price) in &window {
+        max_profit = max_profit.max(directional(price));
+        max_drawdown = max_drawdown.min(directional(price));
+        if let Some(target) = signal.target_price.filter(|target| reached(price, *target)) {
+            result = (SignalOutcome::HitTarget, target, at);
+            break;
+        }
+        if let Some(stop) = signal.stop_loss.filter(|stop| fell_through(price, *stop)) {
+            result = (SignalOutcome::HitStop, stop, at);
+            break;
+        }
+    }
+
+    let (outcome, exit_price, closed_at) = result;
+    Some(SignalPerformance {
+        signal_id: signal.signal_id(),
+        hit_target: outcome == SignalOutcome::HitTarget,
+        hit_stop_loss: outcome == SignalOutcome::HitStop,
+        max_profit_percent: max_profit,
+        max_drawdown_percent: max_drawdown,
+        duration_hours: (closed_at - signal.generated_at).num_minutes() as f64 / 60.0,
+        outcome,
+        exit_price,
+        return_percent: directional(exit_price),
+    })
+}
+
+/// Records generated signals and scores them once their timeframe has passed
+pub struct SignalOutcomeTracker {
+    history: Arc<dyn SignalPriceHistory>,
+    database: Option<Arc<Database>>,
+    pending: RwLock<HashMap<String, TradingSignal>>,
+    stats: RwLock<SignalStats>,
+}
+
+impl SignalOutcomeTracker {
+    pub fn new(history: Arc<dyn SignalPriceHistory>) -> Self {
+        Self {
+            history,
+            database: None,
+            pending: RwLock::new(HashMap::new()),
+            stats: RwLock::new(SignalStats::default()),
+        }
+    }
+
+    /// Persist signals and the aggregate stats
+    pub fn with_database(mut self, database: Arc<Database>) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// Restore pending signals and the stored track record
+    pub async fn load(&self) -> Result<usize> {
+        let Some(database) = &self.database else { return Ok(0) };
+
+        if let Some(row) = database.get_signal_stats().await? {
+            match serde_json::from_str(&row) {
+                Ok(stats) => *self.stats.write().await = stats,
+                Err(e) => warn!("🔮 Ignoring unreadable stored signal stats: {}", e),
+            }
+        }
+
+        let rows = database.get_signals_by_status(&[SignalStatus::Pending.as_str()]).await?;
+        let mut pending = self.pending.write().await;
+        for row in rows {
+            match serde_json::from_str::<TrackedSignal>(&row) {
+                Ok(tracked) => {
+                    pending.insert(tracked.signal_id, tracked.signal);
+                }
+                Err(e) => warn!("🔮 Skipping unreadable stored signal: {}", e),
+            }
+        }
+        info!("🔮 Restored {} pending signals", pending.len());
+        Ok(pending.len())
+    }
+
+    /// Start tracking a freshly generated signal; holds have nothing to score
+    pub async fn track(&self, signal: &TradingSignal) -> Result<()> {
+        if is_long(&signal.signal_type).is_none() {
+            return Ok(());
+        }
+        let signal_id = signal.signal_id();
+        if self.pending.write().await.insert(signal_id.clone(), signal.clone()).is_some() {
+            return Ok(());
+        }
+        self.stats.write().await.total_signals += 1;
+
+        self.store(&TrackedSignal {
+            signal_id,
+            signal: signal.clone(),
+            status: SignalStatus::Pending,
+            performance: None,
+        }).await?;
+        self.store_stats().await
+    }
+
+    /// Score every pending signal whose window closed by `now`
+    pub async fn evaluate_due(&self, now: DateTime<Utc>) -> Result<Vec<TrackedSignal>> {
+        let due: Vec<(String, TradingSignal)> = self.pending.read().await.iter()
+            .filter(|(_, signal)| signal.expires_at <= now)
+            .map(|(id, signal)| (id.clone(), signal.clone()))
+            .collect();
+
+        let mut evaluated = Vec::new();
+        for (signal_id, signal) in due {
+            let prices = match self.history.prices_between(&signal.token_address, signal.generated_at, signal.expires_at).await {
+                Ok(prices) => prices,
+                Err(e) => {
+                    debug!("🔮 No price history for {} yet: {}", signal.symbol, e);
+                    Vec::new()
+                }
+            };
+
+            let (status, performance) = match evaluate_signal(&signal, &prices) {
+                Some(performance) => (SignalStatus::Evaluated(performance.outcome), Some(performance)),
+                None if now - signal.expires_at > Duration::hours(MAX_PRICING_DELAY_HOURS) => {
+                    warn!("🔮 Giving up on {} signal {}: no prices for its window", signal.symbol, signal_id);
+                    (SignalStatus::Unpriced, None)
+                }
+                // Try again on the next pass
+                None => continue,
+            };
+
+            self.pending.write().await.remove(&signal_id);
+            if let Some(performance) = &performance {
+                self.stats.write().await.record(&signal.signal_type, performance);
+            }
+            let tracked = TrackedSignal { signal_id, signal, status, performance };
+            self.store(&tracked).await?;
+            evaluated.push(tracked);
+        }
+
+        if !evaluated.is_empty() {
+            self.store_stats().await?;
+            info!("🔮 Evaluated {} expired signals", evaluated.len());
+        }
+        Ok(evaluated)
+    }
+
+    pub async fn stats(&self) -> SignalStats {
+        self.stats.read().await.clone()
+    }
+
+    pub async fn pending_count(&self) -> usize {
+        self.pending.read().await.len()
+    }
+
+    /// Restore stored state, then score expired signals in the background
+    pub fn spawn_evaluator(self: &Arc<Self>) {
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = tracker.load().await {
+                warn!("🔮 Failed to restore tracked signals: {}", e);
+            }
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(EVALUATION_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                if let Err(e) = tracker.evaluate_due(Utc::now()).await {
+                    warn!("🔮 Signal evaluation failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn store(&self, tracked: &TrackedSignal) -> Result<()> {
+        let Some(database) = &self.database else { return Ok(()) };
+        let data = serde_json::to_string(tracked)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize signal {}: {}", tracked.signal_id, e)))?;
+        database.upsert_signal(
+            &tracked.signal_id,
+            &tracked.signal.token_address,
+            tracked.status.as_str(),
+            &data,
+        ).await
+    }
+
+    async fn store_stats(&self) -> Result<()> {
+        let Some(database) = &self.database else { return Ok(()) };
+        let data = serde_json::to_string(&*self.stats.read().await)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize signal stats: {}", e)))?;
+        database.upsert_signal_stats(&data).await
+    }
+}
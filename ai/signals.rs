@@ -6,10 +6,13 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn, debug};
 
-use super::groq::{GroqAnalyzer, MarketAnalysis};
+use super::groq::{AnalysisSignal, GroqAnalyzer, MarketAnalysis, AnalysisOutcome};
+use super::budget::AiPriority;
+use super::signal_outcomes::{SignalOutcome, SignalOutcomeTracker, SignalStats};
 use crate::market::aggregator::MarketDataAggregator;
 use crate::market::types::{TokenMarketData, TrendingToken, MarketTrend};
 use crate::utils::formatting::{format_market_cap, format_volume};
+use crate::utils::i18n::{fmt_datetime, fmt_number, DateStyle, NumberKind};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingSignal {
@@ -30,7 +33,14 @@ pub struct TradingSignal {
     pub expires_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+impl TradingSignal {
+    /// Stable across restarts: the token and the second it was generated
+    pub fn signal_id(&self) -> String {
+        format!("{}:{}", self.token_address, self.generated_at.timestamp())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum SignalType {
     Buy,
     Sell,
@@ -41,6 +51,20 @@ pub enum SignalType {
     Distribute,
 }
 
+impl SignalType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::StrongBuy => "STRONG BUY",
+            Self::Buy => "BUY",
+            Self::Accumulate => "ACCUMULATE",
+            Self::Hold => "HOLD",
+            Self::Distribute => "DISTRIBUTE",
+            Self::Sell => "SELL",
+            Self::StrongSell => "STRONG SELL",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SignalStrength {
     VeryStrong,
@@ -82,6 +106,7 @@ pub struct MarketConditions {
     pub correlation_with_sol: f64,
 }
 
+/// How a signal did over its window; profit and drawdown are in its direction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignalPerformance {
     pub signal_id: String,
@@ -89,7 +114,11 @@ pub struct SignalPerformance {
     pub hit_stop_loss: bool,
     pub max_profit_percent: f64,
     pub max_drawdown_percent: f64,
+    /// Until the target or stop was touched, or the whole window
     pub duration_hours: f64,
+    pub outcome: SignalOutcome,
+    pub exit_price: f64,
+    pub return_percent: f64,
 }
 
 /// AI-powered signal generator combining technical analysis with LLM insights
@@ -97,7 +126,7 @@ pub struct SignalGenerator {
     market_aggregator: Arc<MarketDataAggregator>,
     ai_analyzer: Arc<GroqAnalyzer>,
     signal_cache: Arc<RwLock<SignalCache>>,
-    performance_tracker: Arc<RwLock<PerformanceTracker>>,
+    outcomes: Option<Arc<SignalOutcomeTracker>>,
 }
 
 struct SignalCache {
@@ -106,13 +135,6 @@ struct SignalCache {
     last_update: DateTime<Utc>,
 }
 
-struct PerformanceTracker {
-    signal_performance: HashMap<String, SignalPerformance>,
-    success_rate: f64,
-    average_return: f64,
-    total_signals: u32,
-}
-
 impl SignalGenerator {
     pub fn new(
         market_aggregator: Arc<MarketDataAggregator>,
@@ -126,15 +148,16 @@ impl SignalGenerator {
                 historical_signals: Vec::new(),
                 last_update: Utc::now(),
             })),
-            performance_tracker: Arc::new(RwLock::new(PerformanceTracker {
-                signal_performance: HashMap::new(),
-                success_rate: 0.0,
-                average_return: 0.0,
-                total_signals: 0,
-            })),
+            outcomes: None,
         }
     }
 
+    /// Record generated signals so their outcomes feed the performance stats
+    pub fn with_outcomes(mut self, outcomes: Arc<SignalOutcomeTracker>) -> Self {
+        self.outcomes = Some(outcomes);
+        self
+    }
+
     /// Generate trading signals for trending tokens
     pub async fn generate_signals(&self, limit: usize) -> Result<Vec<TradingSignal>> {
         info!("Generating AI-powered trading signals");
@@ -164,6 +187,15 @@ impl SignalGenerator {
             cache.active_signals.insert(signal.token_address.clone(), signal.clone());
         }
         cache.last_update = Utc::now();
+        drop(cache);
+        
+        if let Some(outcomes) = &self.outcomes {
+            for signal in &signals {
+                if let Err(e) = outcomes.track(signal).await {
+                    warn!("Failed to record {} signal for tracking: {}", signal.symbol, e);
+                }
+            }
+        }
         
         info!("Generated {} trading signals", signals.len());
         Ok(signals)
@@ -177,9 +209,14 @@ impl SignalGenerator {
     ) -> Result<TradingSignal> {
         debug!("Analyzing {} for signal generation", token.symbol);
         
-        // Get AI analysis
-        let ai_insights = match self.ai_analyzer.analyze_token(&token.symbol).await {
-            Ok(analysis) => Some(analysis),
+        // Get AI analysis; signal evaluation is system priority and falls back to
+        // technicals alone when the budget denies the call
+        let ai_insights = match self.ai_analyzer
+            .analyze_token_with_budget(&token.symbol, None, AiPriority::System)
+            .await
+        {
+            Ok(AnalysisOutcome::Ai(analysis)) => Some(analysis),
+            Ok(AnalysisOutcome::Heuristic { .. }) => None,
             Err(e) => {
                 warn!("AI analysis failed for {}: {}", token.symbol, e);
                 None
@@ -356,12 +393,12 @@ impl SignalGenerator {
         
         // AI insights factor
         if let Some(ai) = ai_insights {
-            let ai_score = match ai.signal.as_str() {
-                "BUY" => 20.0,
-                "SELL" => -20.0,
-                _ => 0.0,
+            let ai_score = match ai.signal {
+                AnalysisSignal::Buy => 20.0,
+                AnalysisSignal::Sell => -20.0,
+                AnalysisSignal::Hold => 0.0,
             };
-            score += ai_score * (ai.confidence / 100.0);
+            score += ai_score * ai.confidence;
             factors += 1;
         }
         
@@ -481,8 +518,8 @@ impl SignalGenerator {
         
         // AI insights reasoning
         if let Some(ai) = ai_insights {
-            if ai.confidence > 70.0 {
-                reasons.push(format!("AI analysis: {} ({:.0}% confidence)", ai.signal, ai.confidence));
+            if ai.confidence > 0.7 {
+                reasons.push(format!("AI analysis: {} ({:.0}% confidence)", ai.signal, ai.confidence * 100.0));
             }
         }
         
@@ -529,67 +566,16 @@ impl SignalGenerator {
         Ok(active)
     }
 
-    /// Track signal performance
-    pub async fn track_signal_performance(
-        &self,
-        signal_id: &str,
-        current_price: f64,
-    ) -> Result<()> {
-        let cache = self.signal_cache.read().await;
-        
-        if let Some(signal) = cache.active_signals.get(signal_id) {
-            let mut tracker = self.performance_tracker.write().await;
-            
-            let performance = tracker
-                .signal_performance
-                .entry(signal_id.to_string())
-                .or_insert(SignalPerformance {
-                    signal_id: signal_id.to_string(),
-                    hit_target: false,
-                    hit_stop_loss: false,
-                    max_profit_percent: 0.0,
-                    max_drawdown_percent: 0.0,
-                    duration_hours: 0.0,
-                });
-            
-            let price_change_percent = ((current_price - signal.entry_price) / signal.entry_price) * 100.0;
-            
-            // Update max profit/drawdown
-            if price_change_percent > performance.max_profit_percent {
-                performance.max_profit_percent = price_change_percent;
-            }
-            if price_change_percent < performance.max_drawdown_percent {
-                performance.max_drawdown_percent = price_change_percent;
-            }
-            
-            // Check if target or stop loss hit
-            if let Some(target) = signal.target_price {
-                if current_price >= target {
-                    performance.hit_target = true;
-                }
-            }
-            if let Some(stop) = signal.stop_loss {
-                if current_price <= stop {
-                    performance.hit_stop_loss = true;
-                }
-            }
-            
-            // Update duration
-            let duration = Utc::now().signed_duration_since(signal.generated_at);
-            performance.duration_hours = duration.num_hours() as f64;
-        }
-        
-        Ok(())
-    }
-
-    /// Get signal performance statistics
-    pub async fn get_performance_stats(&self) -> Result<(f64, f64, u32)> {
-        let tracker = self.performance_tracker.read().await;
-        Ok((tracker.success_rate, tracker.average_return, tracker.total_signals))
+    /// Track record of evaluated signals, overall and per signal type
+    pub async fn get_performance_stats(&self) -> Result<SignalStats> {
+        Ok(match &self.outcomes {
+            Some(outcomes) => outcomes.stats().await,
+            None => SignalStats::default(),
+        })
     }
 
-    /// Format signal for display
-    pub fn format_signal(signal: &TradingSignal) -> String {
+    /// Format signal for display with the user's number and date conventions
+    pub fn format_signal(signal: &TradingSignal, lang: &str) -> String {
         let signal_emoji = match signal.signal_type {
             SignalType::StrongBuy => "🚀",
             SignalType::Buy | SignalType::Accumulate => "📈",
@@ -622,21 +608,29 @@ impl SignalGenerator {
         );
         
         message.push_str(&format!("📊 Strength: {}\n", strength_emoji));
-        message.push_str(&format!("🎯 Confidence: {:.0}%\n", signal.confidence));
-        message.push_str(&format!("💵 Entry Price: ${:.6}\n", signal.entry_price));
+        message.push_str(&format!("🎯 Confidence: {}\n", fmt_number(lang, signal.confidence, NumberKind::Percent(0))));
+        message.push_str(&format!("💵 Entry Price: {}\n", fmt_number(lang, signal.entry_price, NumberKind::Usd)));
         
         if let Some(target) = signal.target_price {
             let target_percent = ((target - signal.entry_price) / signal.entry_price) * 100.0;
-            message.push_str(&format!("🎯 Target: ${:.6} ({:+.1}%)\n", target, target_percent));
+            message.push_str(&format!(
+                "🎯 Target: {} ({})\n",
+                fmt_number(lang, target, NumberKind::Usd),
+                fmt_number(lang, target_percent, NumberKind::Change)
+            ));
         }
         
         if let Some(stop) = signal.stop_loss {
             let stop_percent = ((stop - signal.entry_price) / signal.entry_price) * 100.0;
-            message.push_str(&format!("🛑 Stop Loss: ${:.6} ({:.1}%)\n", stop, stop_percent));
+            message.push_str(&format!(
+                "🛑 Stop Loss: {} ({})\n",
+                fmt_number(lang, stop, NumberKind::Usd),
+                fmt_number(lang, stop_percent, NumberKind::Change)
+            ));
         }
         
         if signal.risk_reward_ratio > 0.0 {
-            message.push_str(&format!("⚖️ Risk/Reward: 1:{:.1}\n", signal.risk_reward_ratio));
+            message.push_str(&format!("⚖️ Risk/Reward: 1:{}\n", fmt_number(lang, signal.risk_reward_ratio, NumberKind::Decimal(1))));
         }
         
         message.push_str(&format!("\n💡 {}\n", signal.reasoning));
@@ -646,7 +640,7 @@ impl SignalGenerator {
         }
         
         message.push_str(&format!("\n⏰ Valid until: {}", 
-            signal.expires_at.format("%H:%M UTC")));
+            fmt_datetime(lang, chrono_tz::Tz::UTC, signal.expires_at, DateStyle::Time)));
         
         message
     }
@@ -0,0 +1,524 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, error, info};
+
+use crate::api::pump_fun::PumpFunClient;
+use crate::errors::Result;
+use super::market_events::{
+    BondingMigrationEvent,
+    EventDetails,
+    EventSeverity,
+    EventSource,
+    EventType,
+    MarketEvent,
+    MarketEventMonitor,
+    RiskLevel,
+};
+
+/// Progress changes smaller than this count as no movement
+const PROGRESS_EPSILON: f64 = 0.01;
+
+/// Bonding tracker configuration
+#[derive(Debug, Clone)]
+pub struct BondingConfig {
+    /// Progress percentages that notify holders; reaching 100% is the migration itself
+    pub milestones: Vec<f64>,
+    /// No progress for this long counts as a stall
+    pub stall_window: Duration,
+    /// Drop in percentage points from the recent peak that counts as a reversal
+    pub reversal_drop: f64,
+    /// How far back the reversal peak is looked for
+    pub reversal_window: Duration,
+    pub poll_interval: Duration,
+    pub history_retention: Duration,
+}
+
+impl Default for BondingConfig {
+    fn default() -> Self {
+        Self {
+            milestones: vec![75.0, 90.0, 100.0],
+            stall_window: Duration::hours(6),
+            reversal_drop: 10.0,
+            reversal_window: Duration::hours(1),
+            poll_interval: Duration::minutes(2),
+            history_retention: Duration::days(7),
+        }
+    }
+}
+
+impl BondingConfig {
+    /// `BONDING_MILESTONES` (e.g. "75,90,100"), `BONDING_STALL_HOURS`, `BONDING_REVERSAL_DROP`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(milestones) = std::env::var("BONDING_MILESTONES") {
+            let parsed: Vec<f64> = milestones
+                .split(',')
+                .filter_map(|m| m.trim().parse().ok())
+                .filter(|m| *m > 0.0 && *m <= 100.0)
+                .collect();
+            if !parsed.is_empty() {
+                config.milestones = parsed;
+            }
+        }
+        if let Some(hours) = std::env::var("BONDING_STALL_HOURS").ok().and_then(|h| h.parse().ok()) {
+            config.stall_window = Duration::hours(hours);
+        }
+        if let Some(drop) = std::env::var("BONDING_REVERSAL_DROP").ok().and_then(|d| d.parse().ok()) {
+            config.reversal_drop = drop;
+        }
+        config
+    }
+}
+
+/// Where sells of a token are routed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradingVenue {
+    /// Still on the pump.fun bonding curve
+    PumpFunCurve,
+    /// Migrated; routed through Jupiter to Raydium and other AMMs
+    Jupiter,
+}
+
+impl TradingVenue {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PumpFunCurve => "pump.fun bonding curve",
+            Self::Jupiter => "Raydium via Jupiter",
+        }
+    }
+}
+
+/// Bonding curve state read from pump.fun
+#[derive(Debug, Clone)]
+pub struct BondingCurveState {
+    pub symbol: String,
+    /// 0-100
+    pub progress: f64,
+    pub complete: bool,
+}
+
+/// Reads bonding curve state; `None` for tokens that aren't on pump.fun
+#[async_trait::async_trait]
+pub trait BondingCurveSource: Send + Sync {
+    async fn curve_state(&self, mint: &str) -> Result<Option<BondingCurveState>>;
+}
+
+#[async_trait::async_trait]
+impl BondingCurveSource for PumpFunClient {
+    async fn curve_state(&self, mint: &str) -> Result<Option<BondingCurveState>> {
+        // Non pump.fun tokens simply 404 here
+        Ok(self.get_token(mint).await.ok().map(|token| BondingCurveState {
+            symbol: token.symbol,
+            progress: token.bonding_curve_progress,
+            complete: token.bonding_curve_progress >= 100.0,
+        }))
+    }
+}
+
+/// One progress observation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProgressPoint {
+    pub progress: f64,
+    pub at: DateTime<Utc>,
+}
+
+/// Progress history of one pump.fun token
+#[derive(Debug, Clone)]
+pub struct BondingProgress {
+    pub mint: String,
+    pub symbol: String,
+    pub history: Vec<ProgressPoint>,
+    pub last_change_at: DateTime<Utc>,
+    pub migrated_at: Option<DateTime<Utc>>,
+}
+
+impl BondingProgress {
+    pub fn current(&self) -> f64 {
+        self.history.last().map(|p| p.progress).unwrap_or(0.0)
+    }
+
+    /// Change over the last `window`, in percentage points
+    pub fn change_over(&self, window: Duration, now: DateTime<Utc>) -> Option<f64> {
+        let start = self.history.iter().find(|p| now - p.at <= window)?;
+        Some(self.current() - start.progress)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BondingAlertKind {
+    Milestone { percent: f64 },
+    Migrated,
+    Stalled { since: DateTime<Utc> },
+    Reversal { peak: f64, current: f64 },
+}
+
+/// Bonding alert for a single holder or watcher
+#[derive(Debug, Clone)]
+pub struct BondingNotification {
+    pub user_id: i64,
+    pub mint: String,
+    pub kind: BondingAlertKind,
+    pub message: String,
+}
+
+/// Tracks bonding-curve progress of held and watchlisted pump.fun tokens
+///
+/// Holders are notified once per milestone, when progress stalls or reverses
+/// sharply, and on migration, which also switches the token's venue so sells
+/// route through Jupiter instead of the curve.
+#[derive(Clone)]
+pub struct BondingTracker {
+    config: BondingConfig,
+    source: Option<Arc<dyn BondingCurveSource>>,
+    curves: Arc<RwLock<HashMap<String, BondingProgress>>>,
+    holders: Arc<RwLock<HashMap<String, HashSet<i64>>>>,
+    watchers: Arc<RwLock<HashMap<String, HashSet<i64>>>>,
+    /// Mints the source reported as not on pump.fun
+    not_pump: Arc<RwLock<HashSet<String>>>,
+    /// (mint, user, alert key) already delivered
+    sent: Arc<RwLock<HashSet<(String, i64, String)>>>,
+    venues: Arc<RwLock<HashMap<String, TradingVenue>>>,
+    market_events: Option<Arc<MarketEventMonitor>>,
+    notification_tx: broadcast::Sender<BondingNotification>,
+}
+
+impl BondingTracker {
+    pub fn new(
+        config: BondingConfig,
+        source: Option<Arc<dyn BondingCurveSource>>,
+        market_events: Option<Arc<MarketEventMonitor>>,
+    ) -> Self {
+        info!("🎢 Initializing bonding curve tracker");
+        let (notification_tx, _) = broadcast::channel(1000);
+
+        Self {
+            config,
+            source,
+            curves: Arc::new(RwLock::new(HashMap::new())),
+            holders: Arc::new(RwLock::new(HashMap::new())),
+            watchers: Arc::new(RwLock::new(HashMap::new())),
+            not_pump: Arc::new(RwLock::new(HashSet::new())),
+            sent: Arc::new(RwLock::new(HashSet::new())),
+            venues: Arc::new(RwLock::new(HashMap::new())),
+            market_events,
+            notification_tx,
+        }
+    }
+
+    pub fn config(&self) -> &BondingConfig {
+        &self.config
+    }
+
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<BondingNotification> {
+        self.notification_tx.subscribe()
+    }
+
+    /// Start polling curve state for tracked mints
+    pub async fn start(&self) {
+        let tracker = self.clone();
+        let interval_secs = self.config.poll_interval.num_seconds().max(30) as u64;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = tracker.poll(Utc::now()).await {
+                    error!("🎢 Error polling bonding curves: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Record the mints a user currently holds
+    pub async fn set_holdings(&self, user_id: i64, mints: &[String]) {
+        let mut holders = self.holders.write().await;
+        for users in holders.values_mut() {
+            users.remove(&user_id);
+        }
+        for mint in mints {
+            holders.entry(mint.clone()).or_insert_with(HashSet::new).insert(user_id);
+        }
+        holders.retain(|_, users| !users.is_empty());
+    }
+
+    /// Follow a token's bonding progress without holding it
+    pub async fn watch(&self, user_id: i64, mint: &str) {
+        self.not_pump.write().await.remove(mint);
+        self.watchers.write().await.entry(mint.to_string()).or_insert_with(HashSet::new).insert(user_id);
+    }
+
+    pub async fn unwatch(&self, user_id: i64, mint: &str) -> bool {
+        let mut watchers = self.watchers.write().await;
+        let removed = watchers.get_mut(mint).is_some_and(|users| users.remove(&user_id));
+        watchers.retain(|_, users| !users.is_empty());
+        removed
+    }
+
+    /// Drop a user's holdings and watchlist; returns how many mints they followed
+    pub async fn forget_user(&self, user_id: i64) -> usize {
+        let mut removed = 0;
+        for map in [&self.holders, &self.watchers] {
+            let mut map = map.write().await;
+            for users in map.values_mut() {
+                if users.remove(&user_id) {
+                    removed += 1;
+                }
+            }
+            map.retain(|_, users| !users.is_empty());
+        }
+        self.sent.write().await.retain(|(_, user, _)| *user != user_id);
+        removed
+    }
+
+    /// Pump.fun tokens a user holds or watches, with their progress
+    pub async fn user_curves(&self, user_id: i64) -> Vec<BondingProgress> {
+        let mints = self.mints_for_user(user_id).await;
+        let curves = self.curves.read().await;
+        let mut owned: Vec<BondingProgress> = mints.iter().filter_map(|m| curves.get(m).cloned()).collect();
+        owned.sort_by(|a, b| b.current().partial_cmp(&a.current()).unwrap_or(std::cmp::Ordering::Equal));
+        owned
+    }
+
+    pub async fn progress(&self, mint: &str) -> Option<BondingProgress> {
+        self.curves.read().await.get(mint).cloned()
+    }
+
+    /// Venue sells of `mint` should use; anything not on a live curve goes through Jupiter
+    pub async fn venue(&self, mint: &str) -> TradingVenue {
+        self.venues.read().await.get(mint).copied().unwrap_or(TradingVenue::Jupiter)
+    }
+
+    /// Position detail line, e.g. "🎢 Bonding 82.4% (+3.1 pts 24h)"
+    pub async fn position_line(&self, mint: &str, now: DateTime<Utc>) -> Option<String> {
+        let curve = self.progress(mint).await?;
+        Some(Self::describe(&curve, now))
+    }
+
+    pub fn describe(curve: &BondingProgress, now: DateTime<Utc>) -> String {
+        if curve.migrated_at.is_some() {
+            return format!("🎓 Migrated, trading on {}", TradingVenue::Jupiter.label());
+        }
+        match curve.change_over(Duration::hours(24), now) {
+            Some(change) if change.abs() >= PROGRESS_EPSILON => {
+                format!("🎢 Bonding {:.1}% ({:+.1} pts 24h)", curve.current(), change)
+            }
+            _ => format!("🎢 Bonding {:.1}%", curve.current()),
+        }
+    }
+
+    /// Read every tracked curve once
+    pub async fn poll(&self, now: DateTime<Utc>) -> Result<Vec<BondingNotification>> {
+        let Some(source) = &self.source else {
+            return Ok(Vec::new());
+        };
+
+        let mut notifications = Vec::new();
+        for mint in self.tracked_mints().await {
+            match source.curve_state(&mint).await {
+                Ok(Some(state)) => notifications.extend(self.record(&mint, state, now).await?),
+                Ok(None) => {
+                    self.not_pump.write().await.insert(mint);
+                }
+                Err(e) => debug!("🎢 Curve read failed for {}: {}", mint, e),
+            }
+        }
+        Ok(notifications)
+    }
+
+    /// Apply one curve observation and notify holders of anything it crossed
+    pub async fn record(&self, mint: &str, state: BondingCurveState, now: DateTime<Utc>) -> Result<Vec<BondingNotification>> {
+        let progress = state.progress.clamp(0.0, 100.0);
+        let (curve, newly_migrated) = {
+            let mut curves = self.curves.write().await;
+            let curve = curves.entry(mint.to_string()).or_insert_with(|| BondingProgress {
+                mint: mint.to_string(),
+                symbol: state.symbol.clone(),
+                history: Vec::new(),
+                last_change_at: now,
+                migrated_at: None,
+            });
+
+            if curve.history.last().is_some_and(|last| (progress - last.progress).abs() >= PROGRESS_EPSILON) {
+                curve.last_change_at = now;
+            }
+            curve.history.push(ProgressPoint { progress, at: now });
+            let retention = self.config.history_retention;
+            curve.history.retain(|p| now - p.at <= retention);
+
+            let newly_migrated = curve.migrated_at.is_none() && (state.complete || progress >= 100.0);
+            if newly_migrated {
+                curve.migrated_at = Some(now);
+            }
+            (curve.clone(), newly_migrated)
+        };
+
+        let mut alerts = Vec::new();
+        if newly_migrated {
+            self.venues.write().await.insert(mint.to_string(), TradingVenue::Jupiter);
+            info!("🎓 {} migrated off the bonding curve", curve.symbol);
+            if let Some(monitor) = &self.market_events {
+                monitor.publish_event(Self::migration_event(&curve, now)).await?;
+            }
+            alerts.push(("migrated".to_string(), BondingAlertKind::Migrated));
+        } else if curve.migrated_at.is_none() {
+            self.venues.write().await.insert(mint.to_string(), TradingVenue::PumpFunCurve);
+            alerts.extend(self.curve_alerts(&curve, now));
+        }
+
+        let users = self.users_for_mint(mint).await;
+        let mut notifications = Vec::new();
+        let mut sent = self.sent.write().await;
+        for (key, kind) in alerts {
+            for user_id in &users {
+                if !sent.insert((mint.to_string(), *user_id, key.clone())) {
+                    continue;
+                }
+                // Lower milestones are implied by a higher one or by migration
+                let implied = match &kind {
+                    BondingAlertKind::Milestone { percent } => Some(*percent),
+                    BondingAlertKind::Migrated => Some(100.0),
+                    _ => None,
+                };
+                if let Some(ceiling) = implied {
+                    for milestone in self.config.milestones.iter().filter(|m| **m <= ceiling) {
+                        sent.insert((mint.to_string(), *user_id, Self::milestone_key(*milestone)));
+                    }
+                }
+                notifications.push(BondingNotification {
+                    user_id: *user_id,
+                    mint: mint.to_string(),
+                    message: Self::message(&curve, &kind),
+                    kind: kind.clone(),
+                });
+            }
+        }
+        drop(sent);
+
+        for notification in &notifications {
+            // No receivers simply means the bot isn't forwarding yet
+            let _ = self.notification_tx.send(notification.clone());
+        }
+        Ok(notifications)
+    }
+
+    /// Milestone, stall and reversal alerts for a curve still bonding, keyed for dedup
+    fn curve_alerts(&self, curve: &BondingProgress, now: DateTime<Utc>) -> Vec<(String, BondingAlertKind)> {
+        let mut alerts = Vec::new();
+        let progress = curve.current();
+
+        // Only the highest milestone crossed is announced; lower ones are marked sent with it
+        if let Some(percent) = self.config.milestones.iter()
+            .copied()
+            .filter(|m| *m < 100.0 && progress >= *m)
+            .fold(None, |top: Option<f64>, m| Some(top.map_or(m, |t| t.max(m))))
+        {
+            alerts.push((Self::milestone_key(percent), BondingAlertKind::Milestone { percent }));
+        }
+
+        if now - curve.last_change_at >= self.config.stall_window {
+            alerts.push((
+                format!("stall:{}", curve.last_change_at.timestamp()),
+                BondingAlertKind::Stalled { since: curve.last_change_at },
+            ));
+        }
+
+        let window = self.config.reversal_window;
+        if let Some(peak) = curve.history.iter()
+            .filter(|p| now - p.at <= window)
+            .max_by(|a, b| a.progress.partial_cmp(&b.progress).unwrap_or(std::cmp::Ordering::Equal))
+        {
+            if peak.progress - progress >= self.config.reversal_drop {
+                alerts.push((
+                    format!("reversal:{}", peak.at.timestamp()),
+                    BondingAlertKind::Reversal { peak: peak.progress, current: progress },
+                ));
+            }
+        }
+
+        alerts
+    }
+
+    fn milestone_key(percent: f64) -> String {
+        format!("milestone:{}", percent)
+    }
+
+    fn message(curve: &BondingProgress, kind: &BondingAlertKind) -> String {
+        match kind {
+            BondingAlertKind::Milestone { percent } => format!(
+                "🎢 {} bonding curve passed {:.0}% ({:.1}% now). Migration to Raydium usually moves price.",
+                curve.symbol, percent, curve.current()
+            ),
+            BondingAlertKind::Migrated => format!(
+                "🎓 {} completed its bonding curve and migrated. Sells now route through {}.",
+                curve.symbol, TradingVenue::Jupiter.label()
+            ),
+            BondingAlertKind::Stalled { since } => format!(
+                "⏸️ {} bonding progress stuck at {:.1}% since {}.",
+                curve.symbol, curve.current(), since.format("%Y-%m-%d %H:%M UTC")
+            ),
+            BondingAlertKind::Reversal { peak, current } => format!(
+                "⚠️ {} bonding progress fell from {:.1}% to {:.1}%: large sells back into the curve.",
+                curve.symbol, peak, current
+            ),
+        }
+    }
+
+    fn migration_event(curve: &BondingProgress, now: DateTime<Utc>) -> MarketEvent {
+        MarketEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            event_type: EventType::BondingMigration(BondingMigrationEvent {
+                mint: curve.mint.clone(),
+                final_progress: curve.current(),
+                migrated_at: now,
+            }),
+            symbol: curve.symbol.clone(),
+            timestamp: now,
+            severity: EventSeverity::High,
+            source: EventSource::OnChain,
+            details: EventDetails {
+                description: format!("{} completed its pump.fun bonding curve and migrated to Raydium", curve.symbol),
+                impact_assessment: "Migration usually brings a sharp price move and new liquidity".to_string(),
+                recommended_actions: vec![
+                    "Review position size".to_string(),
+                    "Check stop-loss levels".to_string(),
+                ],
+                risk_level: RiskLevel::High,
+                confidence: 1.0,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    async fn users_for_mint(&self, mint: &str) -> HashSet<i64> {
+        let mut users = self.holders.read().await.get(mint).cloned().unwrap_or_default();
+        if let Some(watchers) = self.watchers.read().await.get(mint) {
+            users.extend(watchers);
+        }
+        users
+    }
+
+    async fn mints_for_user(&self, user_id: i64) -> Vec<String> {
+        let mut mints: HashSet<String> = HashSet::new();
+        for (mint, users) in self.holders.read().await.iter().chain(self.watchers.read().await.iter()) {
+            if users.contains(&user_id) {
+                mints.insert(mint.clone());
+            }
+        }
+        mints.into_iter().collect()
+    }
+
+    /// Held or watched mints still worth polling
+    async fn tracked_mints(&self) -> Vec<String> {
+        let not_pump = self.not_pump.read().await;
+        let curves = self.curves.read().await;
+        let mut mints: HashSet<String> = self.holders.read().await.keys().cloned().collect();
+        mints.extend(self.watchers.read().await.keys().cloned());
+        mints.into_iter()
+            .filter(|m| !not_pump.contains(m))
+            .filter(|m| curves.get(m).map_or(true, |c| c.migrated_at.is_none()))
+            .collect()
+    }
+}
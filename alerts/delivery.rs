@@ -0,0 +1,454 @@
+use chrono::{DateTime, Utc};
+use lettre::{
+    message::Mailbox,
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message as EmailMessage, Tokio1Executor,
+};
+use reqwest::{Client, StatusCode};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, error, info, warn};
+
+use crate::errors::{BotError, Result};
+use super::price_alerts::{AlertDeliveryMethod, AlertNotification, DeliveryStatus};
+
+/// Failed webhook deliveries kept for inspection
+const MAX_DEAD_LETTERS: usize = 1000;
+
+/// What kind of alert a payload carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    PriceAlert,
+    MarketEvent,
+    /// The daily summary of watchlist movers
+    WatchlistDigest,
+}
+
+/// One alert as handed to every dispatcher; webhooks receive it as JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertPayload {
+    pub kind: AlertKind,
+    /// Alert id, or event id for market events
+    pub alert_id: String,
+    pub user_id: i64,
+    pub title: String,
+    pub message: String,
+    pub symbol: String,
+    pub price: Option<Decimal>,
+    pub condition: Option<String>,
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Sends alerts over one transport
+#[async_trait::async_trait]
+pub trait AlertDispatcher: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Whether this dispatcher delivers `method`
+    fn supports(&self, method: &AlertDeliveryMethod) -> bool;
+
+    async fn dispatch(&self, method: &AlertDeliveryMethod, payload: &AlertPayload) -> Result<()>;
+}
+
+/// Hands Telegram alerts to the bot, which owns the chat connection
+pub struct TelegramDispatcher {
+    notification_tx: broadcast::Sender<AlertNotification>,
+}
+
+impl Default for TelegramDispatcher {
+    fn default() -> Self {
+        let (notification_tx, _) = broadcast::channel(1000);
+        Self { notification_tx }
+    }
+}
+
+impl TelegramDispatcher {
+    pub fn subscribe(&self) -> broadcast::Receiver<AlertNotification> {
+        self.notification_tx.subscribe()
+    }
+}
+
+#[async_trait::async_trait]
+impl AlertDispatcher for TelegramDispatcher {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn supports(&self, method: &AlertDeliveryMethod) -> bool {
+        matches!(method, AlertDeliveryMethod::Telegram { .. })
+    }
+
+    async fn dispatch(&self, method: &AlertDeliveryMethod, payload: &AlertPayload) -> Result<()> {
+        let AlertDeliveryMethod::Telegram { chat_id } = method else {
+            return Err(BotError::validation("Not a Telegram delivery".to_string()).into());
+        };
+
+        let notification = AlertNotification {
+            user_id: payload.user_id,
+            chat_id: *chat_id,
+            alert_id: payload.alert_id.clone(),
+            message: payload.message.clone(),
+        };
+        self.notification_tx.send(notification)
+            .map_err(|_| BotError::internal("Telegram forwarder not running".to_string()))?;
+        Ok(())
+    }
+}
+
+/// Attempts and backoff for webhook deliveries
+#[derive(Debug, Clone)]
+pub struct WebhookRetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for WebhookRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// A webhook delivery that failed every attempt
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub url: String,
+    pub payload: AlertPayload,
+    pub attempts: u32,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// POSTs alerts as JSON, retrying transient failures with exponential backoff
+///
+/// Generic webhooks get the `AlertPayload`; Discord and Slack get their own
+/// message shape. Deliveries that never succeed go to the dead-letter log.
+pub struct WebhookDispatcher {
+    client: Client,
+    retry: WebhookRetryPolicy,
+    dead_letters: RwLock<VecDeque<DeadLetter>>,
+}
+
+impl Default for WebhookDispatcher {
+    fn default() -> Self {
+        Self::new(WebhookRetryPolicy::default())
+    }
+}
+
+impl WebhookDispatcher {
+    pub fn new(retry: WebhookRetryPolicy) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(retry.timeout)
+                .build()
+                .unwrap_or_default(),
+            retry,
+            dead_letters: RwLock::new(VecDeque::new()),
+        }
+    }
+
+    /// Deliveries that failed every attempt, oldest first
+    pub async fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.read().await.iter().cloned().collect()
+    }
+
+    fn body(method: &AlertDeliveryMethod, payload: &AlertPayload) -> serde_json::Value {
+        match method {
+            AlertDeliveryMethod::Discord { .. } => serde_json::json!({ "content": payload.message }),
+            AlertDeliveryMethod::Slack { .. } => serde_json::json!({ "text": payload.message }),
+            _ => serde_json::to_value(payload).unwrap_or_default(),
+        }
+    }
+
+    /// One POST; the flag says whether trying again could help
+    async fn post(&self, url: &str, body: &serde_json::Value) -> std::result::Result<(), (String, bool)> {
+        let response = self.client.post(url).json(body).send().await
+            .map_err(|e| (e.to_string(), true))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let retryable = status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS;
+        Err((format!("HTTP {}", status), retryable))
+    }
+
+    async fn dead_letter(&self, letter: DeadLetter) {
+        error!("📮 Dead letter: alert {} to {} failed after {} attempt(s): {}",
+            letter.payload.alert_id, letter.url, letter.attempts, letter.error);
+
+        let mut dead_letters = self.dead_letters.write().await;
+        dead_letters.push_back(letter);
+        if dead_letters.len() > MAX_DEAD_LETTERS {
+            dead_letters.pop_front();
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AlertDispatcher for WebhookDispatcher {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn supports(&self, method: &AlertDeliveryMethod) -> bool {
+        matches!(method,
+            AlertDeliveryMethod::Webhook { .. } | AlertDeliveryMethod::Discord { .. } | AlertDeliveryMethod::Slack { .. })
+    }
+
+    async fn dispatch(&self, method: &AlertDeliveryMethod, payload: &AlertPayload) -> Result<()> {
+        let url = match method {
+            AlertDeliveryMethod::Webhook { url } => url,
+            AlertDeliveryMethod::Discord { webhook_url } | AlertDeliveryMethod::Slack { webhook_url } => webhook_url,
+            _ => return Err(BotError::validation("Not a webhook delivery".to_string()).into()),
+        };
+        let body = Self::body(method, payload);
+
+        let mut backoff = self.retry.initial_backoff;
+        let mut attempts = 0;
+        let error = loop {
+            attempts += 1;
+            match self.post(url, &body).await {
+                Ok(()) => {
+                    debug!("📮 Alert {} delivered to {}", payload.alert_id, url);
+                    return Ok(());
+                }
+                Err((error, retryable)) => {
+                    if !retryable || attempts >= self.retry.max_attempts.max(1) {
+                        break error;
+                    }
+                    warn!("📮 Webhook {} failed ({}), retrying in {:?}", url, error, backoff);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.retry.max_backoff);
+                }
+            }
+        };
+
+        self.dead_letter(DeadLetter {
+            url: url.clone(),
+            payload: payload.clone(),
+            attempts,
+            error: error.clone(),
+            failed_at: Utc::now(),
+        }).await;
+        Err(BotError::external_api(format!("Webhook failed after {} attempt(s): {}", attempts, error)).into())
+    }
+}
+
+/// How the connection to the SMTP relay is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Plain connection, for a relay on a trusted network
+    None,
+    StartTls,
+    Tls,
+}
+
+impl SmtpSecurity {
+    fn default_port(&self) -> u16 {
+        match self {
+            Self::None => 25,
+            Self::StartTls => 587,
+            Self::Tls => 465,
+        }
+    }
+}
+
+/// SMTP relay used for email alerts
+#[derive(Debug, Clone)]
+pub struct SmtpRelayConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub security: SmtpSecurity,
+}
+
+impl SmtpRelayConfig {
+    /// Relay from `SMTP_HOST` and `SMTP_FROM`, if both are set
+    ///
+    /// `SMTP_SECURITY` is `starttls` (default), `tls` or `none`; `SMTP_PORT`
+    /// defaults to the usual port for it.
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("SMTP_HOST").ok().filter(|h| !h.is_empty())?;
+        let from = std::env::var("SMTP_FROM").ok().filter(|f| !f.is_empty())?;
+        let security = match std::env::var("SMTP_SECURITY").unwrap_or_default().to_lowercase().as_str() {
+            "none" => SmtpSecurity::None,
+            "tls" => SmtpSecurity::Tls,
+            _ => SmtpSecurity::StartTls,
+        };
+
+        Some(Self {
+            host,
+            port: std::env::var("SMTP_PORT").ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or_else(|| security.default_port()),
+            username: std::env::var("SMTP_USERNAME").ok().filter(|u| !u.is_empty()),
+            password: std::env::var("SMTP_PASSWORD").ok().filter(|p| !p.is_empty()),
+            from,
+            security,
+        })
+    }
+}
+
+/// Emails alerts through an SMTP relay
+pub struct EmailDispatcher {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl EmailDispatcher {
+    pub fn new(config: SmtpRelayConfig) -> Result<Self> {
+        let from: Mailbox = config.from.parse()
+            .map_err(|e| BotError::config(format!("Invalid SMTP_FROM {}: {}", config.from, e)))?;
+
+        let builder = match config.security {
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .map_err(|e| BotError::config(format!("Invalid SMTP relay {}: {}", config.host, e)))?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .map_err(|e| BotError::config(format!("Invalid SMTP relay {}: {}", config.host, e)))?,
+        };
+        let builder = match (config.username, config.password) {
+            (Some(username), Some(password)) => builder.credentials(Credentials::new(username, password)),
+            _ => builder,
+        };
+
+        info!("📧 Email alerts via {}:{}", config.host, config.port);
+        Ok(Self {
+            transport: builder.port(config.port).build(),
+            from,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AlertDispatcher for EmailDispatcher {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn supports(&self, method: &AlertDeliveryMethod) -> bool {
+        matches!(method, AlertDeliveryMethod::Email { .. })
+    }
+
+    async fn dispatch(&self, method: &AlertDeliveryMethod, payload: &AlertPayload) -> Result<()> {
+        let AlertDeliveryMethod::Email { address } = method else {
+            return Err(BotError::validation("Not an email delivery".to_string()).into());
+        };
+        let to: Mailbox = address.parse()
+            .map_err(|e| BotError::validation(format!("Invalid email address {}: {}", address, e)))?;
+
+        let email = EmailMessage::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(payload.title.clone())
+            .body(payload.message.clone())
+            .map_err(|e| BotError::internal(format!("Failed to build alert email: {}", e)))?;
+
+        self.transport.send(email).await
+            .map_err(|e| BotError::external_api(format!("SMTP relay rejected the alert: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Routes alerts to the dispatcher for each delivery method
+///
+/// Also keeps each user's preferred delivery methods, which apply to market
+/// events and are added to the methods a price alert was created with.
+pub struct AlertDelivery {
+    telegram: Arc<TelegramDispatcher>,
+    dispatchers: Vec<Arc<dyn AlertDispatcher>>,
+    preferences: RwLock<HashMap<i64, Vec<AlertDeliveryMethod>>>,
+}
+
+impl Default for AlertDelivery {
+    fn default() -> Self {
+        let telegram = Arc::new(TelegramDispatcher::default());
+        Self {
+            dispatchers: vec![telegram.clone()],
+            telegram,
+            preferences: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl AlertDelivery {
+    /// Telegram and webhooks, plus email when an SMTP relay is configured
+    pub fn from_env() -> Self {
+        let delivery = Self::default().with_dispatcher(Arc::new(WebhookDispatcher::default()));
+        match SmtpRelayConfig::from_env().map(EmailDispatcher::new) {
+            Some(Ok(email)) => delivery.with_dispatcher(Arc::new(email)),
+            Some(Err(e)) => {
+                warn!("📧 Email alerts disabled: {}", e);
+                delivery
+            }
+            None => delivery,
+        }
+    }
+
+    pub fn with_dispatcher(mut self, dispatcher: Arc<dyn AlertDispatcher>) -> Self {
+        self.dispatchers.push(dispatcher);
+        self
+    }
+
+    /// Alerts for the bot to send to Telegram chats
+    pub fn subscribe_telegram(&self) -> broadcast::Receiver<AlertNotification> {
+        self.telegram.subscribe()
+    }
+
+    /// Methods the user chose, if they ever did
+    pub async fn preferred_methods(&self, user_id: i64) -> Option<Vec<AlertDeliveryMethod>> {
+        self.preferences.read().await.get(&user_id).cloned()
+    }
+
+    /// Methods the user chose, or their Telegram chat
+    pub async fn user_methods(&self, user_id: i64) -> Vec<AlertDeliveryMethod> {
+        self.preferred_methods(user_id).await
+            .unwrap_or_else(|| vec![AlertDeliveryMethod::Telegram { chat_id: user_id }])
+    }
+
+    pub async fn set_user_methods(&self, user_id: i64, methods: Vec<AlertDeliveryMethod>) {
+        self.preferences.write().await.insert(user_id, methods);
+    }
+
+    /// Drop a user's delivery methods; true when any were set
+    pub async fn forget_user(&self, user_id: i64) -> bool {
+        self.preferences.write().await.remove(&user_id).is_some()
+    }
+
+    /// Deliver over every method, keyed by `AlertDeliveryMethod::label`
+    pub async fn deliver(
+        &self,
+        methods: &[AlertDeliveryMethod],
+        payload: &AlertPayload,
+    ) -> HashMap<String, DeliveryStatus> {
+        let mut statuses = HashMap::new();
+        for method in methods {
+            let status = match self.dispatchers.iter().find(|d| d.supports(method)) {
+                Some(dispatcher) => match dispatcher.dispatch(method, payload).await {
+                    Ok(()) => DeliveryStatus::Sent,
+                    Err(e) => {
+                        warn!("📮 {} delivery of {} failed: {}", dispatcher.name(), payload.alert_id, e);
+                        DeliveryStatus::Failed(e.to_string())
+                    }
+                },
+                None => DeliveryStatus::Failed(format!("{} delivery is not configured", method.channel_name())),
+            };
+            statuses.insert(method.label(), status);
+        }
+        statuses
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc, Duration};
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
@@ -11,6 +12,8 @@ use crate::errors::{BotError, Result};
 use crate::websocket::{PriceStreamManager, PriceUpdate};
 use crate::telemetry::TelemetryService;
 use crate::db::Database;
+use super::delivery::{AlertDelivery, AlertKind, AlertPayload};
+use super::price_alerts::DeliveryStatus;
 
 /// Market event monitoring system
 #[derive(Clone)]
@@ -18,6 +21,7 @@ pub struct MarketEventMonitor {
     database: Arc<Database>,
     telemetry: Option<Arc<TelemetryService>>,
     price_stream: Arc<PriceStreamManager>,
+    delivery: Arc<AlertDelivery>,
     event_queue: Arc<RwLock<mpsc::UnboundedSender<MarketEvent>>>,
     event_history: Arc<RwLock<VecDeque<EventHistory>>>,
     event_subscribers: Arc<RwLock<HashMap<String, Vec<EventSubscription>>>>,
@@ -38,6 +42,18 @@ pub struct MarketEvent {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+impl MarketEvent {
+    /// Subscription keys the event is delivered under: its symbol, plus the
+    /// wallet for whale moves
+    pub fn subscription_keys(&self) -> Vec<&str> {
+        let mut keys = vec![self.symbol.as_str()];
+        if let EventType::Whale(whale) = &self.event_type {
+            keys.push(whale.whale_address.as_str());
+        }
+        keys
+    }
+}
+
 /// Event types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EventType {
@@ -51,6 +67,8 @@ pub enum EventType {
     MarketManipulation(ManipulationEvent),
     TechnicalBreakout(TechnicalEvent),
     CorrelationBreak(CorrelationEvent),
+    Scheduled(ScheduledTokenEvent),
+    BondingMigration(BondingMigrationEvent),
 }
 
 /// Volatility event
@@ -173,7 +191,7 @@ pub struct WhaleEvent {
     pub historical_accuracy: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WhaleAction {
     Buy,
     Sell,
@@ -240,6 +258,23 @@ pub struct CorrelationEvent {
     pub implications: Vec<String>,
 }
 
+/// Scheduled token event (unlock, migration, launch) reaching its time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTokenEvent {
+    pub calendar_event_id: String,
+    pub mint: String,
+    pub label: String,
+    pub scheduled_at: DateTime<Utc>,
+}
+
+/// Pump.fun token completing its bonding curve and migrating to Raydium
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BondingMigrationEvent {
+    pub mint: String,
+    pub final_progress: f64,
+    pub migrated_at: DateTime<Utc>,
+}
+
 /// Event severity levels
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EventSeverity {
@@ -261,6 +296,7 @@ pub enum EventSource {
     Social,
     Technical,
     AI,
+    Calendar,
 }
 
 /// Event details
@@ -343,12 +379,26 @@ pub struct NotificationSettings {
     pub include_charts: bool,
 }
 
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            cooldown: Duration::zero(),
+            max_per_hour: 60,
+            aggregate_similar: false,
+            include_charts: false,
+        }
+    }
+}
+
 /// Event history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventHistory {
     pub event: MarketEvent,
     pub occurred_at: DateTime<Utc>,
     pub notified_users: Vec<i64>,
+    /// Per user and delivery method, keyed "<user_id> <method>"
+    #[serde(default)]
+    pub delivery_status: HashMap<String, DeliveryStatus>,
     pub follow_up_actions: Vec<String>,
     pub outcome: Option<EventOutcome>,
 }
@@ -436,16 +486,91 @@ pub enum Trend {
     Stable,
 }
 
+/// Price updates kept per symbol by the anomaly detector
+const MAX_HISTORY_PER_SYMBOL: usize = 2000;
+
 /// Anomaly detector
 pub struct AnomalyDetector {
     historical_data: Arc<RwLock<HashMap<String, VecDeque<PriceUpdate>>>>,
     detection_models: Vec<Box<dyn AnomalyModel>>,
+    flash_crashes: RwLock<FlashCrashDetector>,
+}
+
+/// What counts as a flash crash, and as recovering from one
+#[derive(Debug, Clone)]
+pub struct FlashCrashConfig {
+    /// Drop from the highest price in `window`, in percent
+    pub drop_percentage: f64,
+    pub window: Duration,
+    /// Share of the drop the price must win back to count as recovered
+    pub recovery_retrace: f64,
+    /// Stop watching for a recovery after this long
+    pub recovery_timeout: Duration,
+}
+
+impl Default for FlashCrashConfig {
+    fn default() -> Self {
+        Self {
+            drop_percentage: 8.0,
+            window: Duration::minutes(2),
+            recovery_retrace: 0.5,
+            recovery_timeout: Duration::hours(1),
+        }
+    }
+}
+
+/// A crash still waiting for its recovery
+#[derive(Debug, Clone)]
+struct ActiveCrash {
+    event_id: String,
+    started_at: DateTime<Utc>,
+    start_price: Decimal,
+    low_price: Decimal,
+    low_at: DateTime<Utc>,
+}
+
+impl ActiveCrash {
+    fn drop_percentage(&self) -> f64 {
+        ((self.start_price - self.low_price) / self.start_price * Decimal::from(100)).to_f64().unwrap_or(0.0)
+    }
+
+    fn event(&self, recovery: Option<(Decimal, DateTime<Utc>)>) -> FlashCrashEvent {
+        FlashCrashEvent {
+            start_price: self.start_price,
+            low_price: self.low_price,
+            recovery_price: recovery.map(|(price, _)| price),
+            drop_percentage: self.drop_percentage(),
+            duration: self.low_at - self.started_at,
+            recovery_time: recovery.map(|(_, at)| at - self.low_at),
+            triggered_stops: 0,
+            liquidations: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SymbolCrashState {
+    active: Option<ActiveCrash>,
+    /// Prices before this belong to a crash already reported
+    rearmed_at: Option<DateTime<Utc>>,
+}
+
+/// Finds sharp drops in a symbol's recent prices and follows them to recovery
+#[derive(Debug, Default)]
+struct FlashCrashDetector {
+    config: FlashCrashConfig,
+    symbols: HashMap<String, SymbolCrashState>,
 }
 
 /// Anomaly detection model trait
+///
+/// `detect` sees a symbol's most recent updates, oldest first, and judges the
+/// newest one against those before it.
 pub trait AnomalyModel: Send + Sync {
     fn detect(&self, data: &[PriceUpdate]) -> Option<Vec<Anomaly>>;
     fn model_name(&self) -> String;
+    /// Updates the model needs to look back over
+    fn lookback(&self) -> usize;
 }
 
 #[derive(Debug, Clone)]
@@ -461,6 +586,7 @@ impl MarketEventMonitor {
     pub fn new(
         database: Arc<Database>,
         price_stream: Arc<PriceStreamManager>,
+        delivery: Arc<AlertDelivery>,
         telemetry: Option<Arc<TelemetryService>>,
     ) -> Self {
         info!("📊 Initializing market event monitor");
@@ -471,11 +597,12 @@ impl MarketEventMonitor {
             database,
             telemetry,
             price_stream,
+            delivery,
             event_queue: Arc::new(RwLock::new(tx)),
             event_history: Arc::new(RwLock::new(VecDeque::with_capacity(10000))),
             event_subscribers: Arc::new(RwLock::new(HashMap::new())),
             market_conditions: Arc::new(RwLock::new(HashMap::new())),
-            anomaly_detector: Arc::new(AnomalyDetector::new()),
+            anomaly_detector: Arc::new(AnomalyDetector::default()),
         };
         
         // Start event processor
@@ -487,6 +614,12 @@ impl MarketEventMonitor {
         monitor
     }
     
+    /// Flash crash thresholds; replaces any price history gathered so far
+    pub fn with_flash_crash_config(mut self, config: FlashCrashConfig) -> Self {
+        self.anomaly_detector = Arc::new(AnomalyDetector::new(config));
+        self
+    }
+    
     /// Start monitoring for market events
     pub async fn start_monitoring(&self) -> Result<()> {
         info!("📊 Starting market event monitoring");
@@ -538,10 +671,14 @@ impl MarketEventMonitor {
     }
     
     /// Analyze market update for events
-    async fn analyze_market_update(&self, update: &PriceUpdate) -> Result<()> {
+    pub async fn analyze_market_update(&self, update: &PriceUpdate) -> Result<()> {
         // Update market condition
         self.update_market_condition(&update.symbol, update).await?;
         
+        if let Some(event) = self.anomaly_detector.check_flash_crash(update).await {
+            self.queue_event(event).await?;
+        }
+        
         // Get current condition
         let conditions = self.market_conditions.read().await;
         let condition = conditions.get(&update.symbol);
@@ -694,6 +831,11 @@ impl MarketEventMonitor {
         Ok(None)
     }
     
+    /// Publish an event produced outside the monitor (e.g. the token calendar)
+    pub async fn publish_event(&self, event: MarketEvent) -> Result<()> {
+        self.queue_event(event).await
+    }
+    
     /// Queue event for processing
     async fn queue_event(&self, event: MarketEvent) -> Result<()> {
         info!("📊 Queueing event: {:?} for {}", event.event_type, event.symbol);
@@ -717,6 +859,7 @@ impl MarketEventMonitor {
     async fn process_event(&self, event: MarketEvent) -> Result<()> {
         // Record in history
         self.record_event_history(&event).await?;
+        self.apply_crash_recovery(&event).await;
         
         // Find matching subscriptions
         let subscribers = self.find_matching_subscribers(&event).await?;
@@ -750,9 +893,10 @@ impl MarketEventMonitor {
     async fn find_matching_subscribers(&self, event: &MarketEvent) -> Result<Vec<i64>> {
         let subscriptions = self.event_subscribers.read().await;
         let mut matching_users = Vec::new();
+        let keys = event.subscription_keys();
         
         for (symbol, subs) in subscriptions.iter() {
-            if symbol == "*" || symbol == &event.symbol {
+            if symbol == "*" || keys.contains(&symbol.as_str()) {
                 for sub in subs {
                     if sub.min_severity <= event.severity {
                         // Check filters
@@ -761,7 +905,7 @@ impl MarketEventMonitor {
                             matches = matches && self.check_filter(filter, event).await;
                         }
                         
-                        if matches {
+                        if matches && !matching_users.contains(&sub.user_id) {
                             matching_users.push(sub.user_id);
                         }
                     }
@@ -773,9 +917,13 @@ impl MarketEventMonitor {
     }
     
     /// Check if event matches filter
-    async fn check_filter(&self, _filter: &EventFilter, _event: &MarketEvent) -> bool {
-        // Would implement filter matching logic
-        true
+    async fn check_filter(&self, filter: &EventFilter, event: &MarketEvent) -> bool {
+        match (filter, &event.event_type) {
+            (EventFilter::MinVolume(min), EventType::Whale(whale)) => whale.value_usd >= *min,
+            (EventFilter::MinVolume(min), EventType::VolumeAnomaly(volume)) => volume.current_volume >= *min,
+            // Would implement the remaining filters
+            _ => true,
+        }
     }
     
     /// Determine delivery method
@@ -797,14 +945,48 @@ impl MarketEventMonitor {
         }
     }
     
-    /// Send notifications
+    /// Send notifications over each subscriber's delivery methods
+    ///
+    /// Outcomes, failures included, are added to the event's history entry.
     async fn send_notifications(&self, notification: EventNotification) -> Result<()> {
-        warn!("📊 Sending {} notifications for event: {:?}",
+        let event = &notification.event;
+        info!("📊 Sending {} notifications for event: {:?}",
             notification.subscribers.len(),
-            notification.event.event_type
+            event.event_type
+        );
+        
+        let message = format!(
+            "📊 {:?} market event · {}\n{}\n{}",
+            event.severity,
+            event.symbol,
+            event.details.description,
+            event.details.impact_assessment
         );
         
-        // Would integrate with notification system
+        let mut delivery_status = HashMap::new();
+        for user_id in &notification.subscribers {
+            let payload = AlertPayload {
+                kind: AlertKind::MarketEvent,
+                alert_id: event.event_id.clone(),
+                user_id: *user_id,
+                title: format!("Market event: {}", event.symbol),
+                message: message.clone(),
+                symbol: event.symbol.clone(),
+                price: None,
+                condition: Some(event.details.description.clone()),
+                triggered_at: event.timestamp,
+            };
+            let methods = self.delivery.user_methods(*user_id).await;
+            for (method, status) in self.delivery.deliver(&methods, &payload).await {
+                delivery_status.insert(format!("{} {}", user_id, method), status);
+            }
+        }
+        
+        let mut history = self.event_history.write().await;
+        if let Some(entry) = history.iter_mut().rev().find(|h| h.event.event_id == event.event_id) {
+            entry.notified_users = notification.subscribers.clone();
+            entry.delivery_status = delivery_status;
+        }
         
         Ok(())
     }
@@ -959,6 +1141,7 @@ impl MarketEventMonitor {
             event: event.clone(),
             occurred_at: event.timestamp,
             notified_users: vec![],
+            delivery_status: HashMap::new(),
             follow_up_actions: vec![],
             outcome: None,
         };
@@ -974,6 +1157,21 @@ impl MarketEventMonitor {
         Ok(())
     }
     
+    /// Fill in the recovery on the history entry of the crash it follows
+    async fn apply_crash_recovery(&self, event: &MarketEvent) {
+        let (EventType::FlashCrash(recovered), Some(crash_id)) =
+            (&event.event_type, event.metadata.get("crash_event_id").and_then(|v| v.as_str()))
+        else {
+            return;
+        };
+        
+        let mut history = self.event_history.write().await;
+        if let Some(entry) = history.iter_mut().rev().find(|h| h.event.event_id == crash_id) {
+            entry.event.event_type = EventType::FlashCrash(recovered.clone());
+            entry.follow_up_actions.push(format!("Recovered: {}", event.event_id));
+        }
+    }
+    
     // Helper methods
     async fn load_subscriptions(&self) -> Result<()> {
         // Would load from database
@@ -993,8 +1191,10 @@ impl MarketEventMonitor {
     ) -> Result<()> {
         let mut subscriptions = self.event_subscribers.write().await;
         
+        // A user's new subscription to a symbol replaces their old one
         for symbol in &subscription.symbols {
             let subs = subscriptions.entry(symbol.clone()).or_insert_with(Vec::new);
+            subs.retain(|s| s.user_id != user_id);
             subs.push(subscription.clone());
         }
         
@@ -1003,6 +1203,25 @@ impl MarketEventMonitor {
         Ok(())
     }
     
+    /// Drop the user's subscription to `symbol`; false when there was none
+    pub async fn unsubscribe(&self, user_id: i64, symbol: &str) -> bool {
+        let mut subscriptions = self.event_subscribers.write().await;
+        let Some(subs) = subscriptions.get_mut(symbol) else { return false };
+        
+        let before = subs.len();
+        subs.retain(|s| s.user_id != user_id);
+        let removed = subs.len() < before;
+        if subs.is_empty() {
+            subscriptions.remove(symbol);
+        }
+        removed
+    }
+    
+    /// Subscriptions registered under `symbol`
+    pub async fn subscriptions_for(&self, symbol: &str) -> Vec<EventSubscription> {
+        self.event_subscribers.read().await.get(symbol).cloned().unwrap_or_default()
+    }
+    
     /// Get event history
     pub async fn get_history(&self, limit: Option<usize>) -> Vec<EventHistory> {
         let history = self.event_history.read().await;
@@ -1016,18 +1235,364 @@ impl MarketEventMonitor {
     }
 }
 
+impl Default for AnomalyDetector {
+    fn default() -> Self {
+        Self::new(FlashCrashConfig::default())
+    }
+}
+
 impl AnomalyDetector {
-    fn new() -> Self {
+    pub fn new(flash_crash: FlashCrashConfig) -> Self {
         Self {
             historical_data: Arc::new(RwLock::new(HashMap::new())),
             detection_models: vec![
-                // Would add actual anomaly detection models
+                Box::new(ReturnZScoreModel::default()),
+                Box::new(VolumeSpikeModel::default()),
             ],
+            flash_crashes: RwLock::new(FlashCrashDetector { config: flash_crash, symbols: HashMap::new() }),
+        }
+    }
+    
+    /// Add an update to the symbol's price history
+    async fn record(&self, update: &PriceUpdate) {
+        let mut data = self.historical_data.write().await;
+        let history = data.entry(update.symbol.clone()).or_insert_with(VecDeque::new);
+        history.push_back(update.clone());
+        if history.len() > MAX_HISTORY_PER_SYMBOL {
+            history.pop_front();
         }
     }
     
-    async fn detect_anomalies(&self, _symbol: &str) -> Option<Vec<Anomaly>> {
-        // Would implement anomaly detection
-        None
+    /// Record the update and check it for a flash crash or a recovery from one
+    ///
+    /// Returns the crash when it is first seen and a follow-up `Info` event
+    /// when the price retraces enough of the drop; nothing in between.
+    pub async fn check_flash_crash(&self, update: &PriceUpdate) -> Option<MarketEvent> {
+        self.record(update).await;
+        
+        let data = self.historical_data.read().await;
+        let history = data.get(&update.symbol)?;
+        self.flash_crashes.write().await.observe(history, update)
+    }
+    
+    /// Replace the statistical models run by `detect_anomalies`
+    pub fn with_models(mut self, models: Vec<Box<dyn AnomalyModel>>) -> Self {
+        self.detection_models = models;
+        self
+    }
+    
+    /// Updates held for the symbol, at most `MAX_HISTORY_PER_SYMBOL`
+    pub async fn history_len(&self, symbol: &str) -> usize {
+        self.historical_data.read().await.get(symbol).map_or(0, VecDeque::len)
     }
-}
\ No newline at end of file
+    
+    /// Run every model over the symbol's recorded history
+    ///
+    /// Updates are recorded by `check_flash_crash`; each model judges the newest.
+    pub async fn detect_anomalies(&self, symbol: &str) -> Option<Vec<Anomaly>> {
+        let lookback = self.detection_models.iter().map(|m| m.lookback()).max()?;
+        let recent: Vec<PriceUpdate> = {
+            let data = self.historical_data.read().await;
+            let history = data.get(symbol)?;
+            history.iter().skip(history.len().saturating_sub(lookback)).cloned().collect()
+        };
+        
+        let mut anomalies = Vec::new();
+        for model in &self.detection_models {
+            let window = &recent[recent.len().saturating_sub(model.lookback())..];
+            if let Some(found) = model.detect(window) {
+                debug!("📊 {} flagged {} anomalies on {}", model.model_name(), found.len(), symbol);
+                anomalies.extend(found);
+            }
+        }
+        
+        if anomalies.is_empty() { None } else { Some(anomalies) }
+    }
+}
+
+impl FlashCrashDetector {
+    fn observe(&mut self, history: &VecDeque<PriceUpdate>, update: &PriceUpdate) -> Option<MarketEvent> {
+        let config = self.config.clone();
+        let state = self.symbols.entry(update.symbol.clone()).or_default();
+        let (price, at) = (update.price, update.timestamp);
+        
+        if let Some(crash) = state.active.as_mut() {
+            if price < crash.low_price {
+                crash.low_price = price;
+                crash.low_at = at;
+                return None;
+            }
+            
+            let drop = crash.start_price - crash.low_price;
+            let retraced = if drop > Decimal::ZERO {
+                ((price - crash.low_price) / drop).to_f64().unwrap_or(0.0)
+            } else {
+                1.0
+            };
+            if retraced >= config.recovery_retrace {
+                let crash = state.active.take()?;
+                state.rearmed_at = Some(at);
+                info!("📊 {} recovered from flash crash {} at {}", update.symbol, crash.event_id, price);
+                return Some(Self::recovery_event(&update.symbol, &crash, price, at));
+            }
+            
+            if at - crash.started_at > config.recovery_timeout {
+                info!("📊 {} did not recover from flash crash {}", update.symbol, crash.event_id);
+                state.active = None;
+                state.rearmed_at = Some(at);
+            }
+            return None;
+        }
+        
+        let since = match state.rearmed_at {
+            Some(rearmed_at) => rearmed_at.max(at - config.window),
+            None => at - config.window,
+        };
+        let peak = history.iter()
+            .filter(|u| u.timestamp >= since && u.timestamp <= at)
+            .max_by_key(|u| u.price)?;
+        if peak.price <= Decimal::ZERO || peak.price <= price {
+            return None;
+        }
+        
+        let crash = ActiveCrash {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            started_at: peak.timestamp,
+            start_price: peak.price,
+            low_price: price,
+            low_at: at,
+        };
+        if crash.drop_percentage() < config.drop_percentage {
+            return None;
+        }
+        
+        warn!("📊 Flash crash on {}: -{:.1}% from {} to {}",
+            update.symbol, crash.drop_percentage(), crash.start_price, price);
+        let event = Self::crash_event(&update.symbol, &crash, config.drop_percentage);
+        state.active = Some(crash);
+        Some(event)
+    }
+    
+    /// Medium at the threshold, High from twice it, Critical from three times
+    fn severity(drop_percentage: f64, threshold: f64) -> EventSeverity {
+        if drop_percentage >= threshold * 3.0 {
+            EventSeverity::Critical
+        } else if drop_percentage >= threshold * 2.0 {
+            EventSeverity::High
+        } else {
+            EventSeverity::Medium
+        }
+    }
+    
+    fn crash_event(symbol: &str, crash: &ActiveCrash, threshold: f64) -> MarketEvent {
+        let details = crash.event(None);
+        let severity = Self::severity(details.drop_percentage, threshold);
+        let risk_level = match severity {
+            EventSeverity::Critical => RiskLevel::Extreme,
+            EventSeverity::High => RiskLevel::High,
+            _ => RiskLevel::Moderate,
+        };
+        
+        MarketEvent {
+            event_id: crash.event_id.clone(),
+            symbol: symbol.to_string(),
+            timestamp: crash.low_at,
+            severity,
+            source: EventSource::PriceData,
+            details: EventDetails {
+                description: format!(
+                    "Flash crash: -{:.1}% in {} ({} → {})",
+                    details.drop_percentage,
+                    format_span(details.duration),
+                    crash.start_price,
+                    crash.low_price
+                ),
+                impact_assessment: "Liquidity is thin or sellers are forced; fills may slip badly until the price settles".to_string(),
+                recommended_actions: vec![
+                    "Avoid market orders until the price stabilises".to_string(),
+                    "Check stop-losses that may have been hit at the low".to_string(),
+                ],
+                risk_level,
+                confidence: 0.9,
+            },
+            event_type: EventType::FlashCrash(details),
+            metadata: HashMap::new(),
+        }
+    }
+    
+    fn recovery_event(symbol: &str, crash: &ActiveCrash, price: Decimal, at: DateTime<Utc>) -> MarketEvent {
+        let details = crash.event(Some((price, at)));
+        let mut metadata = HashMap::new();
+        metadata.insert("crash_event_id".to_string(), serde_json::Value::String(crash.event_id.clone()));
+        
+        MarketEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            symbol: symbol.to_string(),
+            timestamp: at,
+            severity: EventSeverity::Info,
+            source: EventSource::PriceData,
+            details: EventDetails {
+                description: format!(
+                    "Recovered from a -{:.1}% flash crash: back to {} after {}",
+                    details.drop_percentage,
+                    price,
+                    format_span(at - crash.low_at)
+                ),
+                impact_assessment: "The price has won back at least half of the drop".to_string(),
+                recommended_actions: vec![],
+                risk_level: RiskLevel::Low,
+                confidence: 0.9,
+            },
+            event_type: EventType::FlashCrash(details),
+            metadata,
+        }
+    }
+}
+
+/// "45s", "3m 20s"
+fn format_span(span: Duration) -> String {
+    let seconds = span.num_seconds().max(0);
+    match (seconds / 60, seconds % 60) {
+        (0, s) => format!("{}s", s),
+        (m, 0) => format!("{}m", m),
+        (m, s) => format!("{}m {}s", m, s),
+    }
+}
+
+/// Smallest return standard deviation the z-score model divides by
+const MIN_RETURN_STD_DEV: f64 = 0.001;
+/// Smallest volume spread, as a share of the median, the volume model divides by
+const MIN_VOLUME_SPREAD: f64 = 0.01;
+
+/// Flags a return more than `threshold` standard deviations from the mean of
+/// the previous `window` returns
+#[derive(Debug, Clone)]
+pub struct ReturnZScoreModel {
+    pub window: usize,
+    pub threshold: f64,
+}
+
+impl Default for ReturnZScoreModel {
+    fn default() -> Self {
+        Self { window: 50, threshold: 3.0 }
+    }
+}
+
+impl AnomalyModel for ReturnZScoreModel {
+    fn detect(&self, data: &[PriceUpdate]) -> Option<Vec<Anomaly>> {
+        let prices: Vec<f64> = data.iter().filter_map(|u| u.price.to_f64()).collect();
+        let returns: Vec<f64> = prices.windows(2)
+            .filter(|pair| pair[0] > 0.0)
+            .map(|pair| pair[1] / pair[0] - 1.0)
+            .collect();
+        let (latest, baseline) = returns.split_last()?;
+        if baseline.len() < self.window {
+            return None;
+        }
+        let baseline = &baseline[baseline.len() - self.window..];
+        
+        let mean = baseline.iter().sum::<f64>() / baseline.len() as f64;
+        let variance = baseline.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (baseline.len() - 1) as f64;
+        // Flat prices have no spread; a move off them is still measured in 0.1% steps
+        let std_dev = variance.sqrt().max(MIN_RETURN_STD_DEV);
+        let z_score = (latest - mean) / std_dev;
+        if z_score.abs() <= self.threshold {
+            return None;
+        }
+        
+        Some(vec![Anomaly {
+            anomaly_type: if z_score > 0.0 { "return_spike".to_string() } else { "return_drop".to_string() },
+            confidence: confidence(z_score.abs()),
+            severity: latest.abs(),
+            details: HashMap::from([
+                ("z_score".to_string(), z_score),
+                ("return".to_string(), *latest),
+                ("mean_return".to_string(), mean),
+                ("std_dev".to_string(), std_dev),
+                ("price".to_string(), *prices.last()?),
+            ]),
+        }])
+    }
+    
+    fn model_name(&self) -> String {
+        "return_zscore".to_string()
+    }
+    
+    /// `window` baseline returns plus the one being judged
+    fn lookback(&self) -> usize {
+        self.window + 2
+    }
+}
+
+/// Flags traded volume far above the median of the previous `window`
+/// updates, measured in median absolute deviations (modified z-score)
+#[derive(Debug, Clone)]
+pub struct VolumeSpikeModel {
+    pub window: usize,
+    pub threshold: f64,
+}
+
+impl Default for VolumeSpikeModel {
+    fn default() -> Self {
+        Self { window: 50, threshold: 3.5 }
+    }
+}
+
+impl AnomalyModel for VolumeSpikeModel {
+    fn detect(&self, data: &[PriceUpdate]) -> Option<Vec<Anomaly>> {
+        let (latest, earlier) = data.split_last()?;
+        let volume = latest.volume?.to_f64()?;
+        let mut baseline: Vec<f64> = earlier.iter().filter_map(|u| u.volume?.to_f64()).collect();
+        if baseline.len() < self.window {
+            return None;
+        }
+        let mut baseline = baseline.split_off(baseline.len() - self.window);
+        
+        let median_volume = median(&mut baseline);
+        let mut deviations: Vec<f64> = baseline.iter().map(|v| (v - median_volume).abs()).collect();
+        if median_volume <= 0.0 {
+            return None;
+        }
+        // MAD / 0.6745 matches the standard deviation on normal data; a steady
+        // baseline has no MAD, so never measure in less than 1% of the median
+        let spread = (median(&mut deviations) / 0.6745).max(median_volume * MIN_VOLUME_SPREAD);
+        let modified_z = (volume - median_volume) / spread;
+        if modified_z <= self.threshold {
+            return None;
+        }
+        
+        Some(vec![Anomaly {
+            anomaly_type: "volume_spike".to_string(),
+            confidence: confidence(modified_z),
+            severity: volume / median_volume - 1.0,
+            details: HashMap::from([
+                ("z_score".to_string(), modified_z),
+                ("volume".to_string(), volume),
+                ("median_volume".to_string(), median_volume),
+                ("volume_ratio".to_string(), volume / median_volume),
+            ]),
+        }])
+    }
+    
+    fn model_name(&self) -> String {
+        "volume_mad".to_string()
+    }
+    
+    fn lookback(&self) -> usize {
+        self.window + 1
+    }
+}
+
+/// 0.75 at three deviations, approaching 1 as the statistic grows
+fn confidence(statistic: f64) -> f64 {
+    statistic / (statistic + 1.0)
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    match values.len() {
+        0 => 0.0,
+        n if n % 2 == 0 => (values[n / 2 - 1] + values[n / 2]) / 2.0,
+        n => values[n / 2],
+    }
+}
@@ -1,5 +1,9 @@
 mod price_alerts;
+mod delivery;
 mod market_events;
+mod token_calendar;
+mod bonding_tracker;
+mod whale_watcher;
 
 pub use price_alerts::{
     PriceAlertManager,
@@ -12,13 +16,33 @@ pub use price_alerts::{
     AlertDeliveryMethod,
     AlertHistory,
     AlertStatistics,
+    AlertNotification,
+    DeliveryStatus,
+    TriggeredAlert,
     PriceThreshold,
+    PriceComparison,
     PercentageChange,
+    ChangeTimeframe,
+    ChangeType,
     MovingAverageCondition,
     VolumeCondition,
     TechnicalIndicatorAlert,
 };
 
+pub use delivery::{
+    AlertDelivery,
+    AlertDispatcher,
+    AlertKind,
+    AlertPayload,
+    TelegramDispatcher,
+    WebhookDispatcher,
+    WebhookRetryPolicy,
+    DeadLetter,
+    EmailDispatcher,
+    SmtpRelayConfig,
+    SmtpSecurity,
+};
+
 pub use market_events::{
     MarketEventMonitor,
     MarketEvent,
@@ -33,4 +57,46 @@ pub use market_events::{
     VolatilityEvent,
     LiquidityEvent,
     NewsEvent,
-};
\ No newline at end of file
+    ScheduledTokenEvent,
+    BondingMigrationEvent,
+    FlashCrashEvent,
+    FlashCrashConfig,
+    AnomalyDetector,
+    AnomalyModel,
+    Anomaly,
+    ReturnZScoreModel,
+    VolumeSpikeModel,
+    WhaleEvent,
+    WhaleAction,
+};
+
+pub use whale_watcher::{
+    WhaleWatcher,
+    WhaleWatchConfig,
+    WhaleActivity,
+};
+
+pub use token_calendar::{
+    TokenCalendar,
+    TokenCalendarEvent,
+    CalendarEventKind,
+    CalendarSourceKind,
+    CalendarConfig,
+    CalendarNotification,
+    CalendarNotificationType,
+    CalendarEventSource,
+    CuratedFileSource,
+    UnlocksApiSource,
+    PumpFunMigrationSource,
+};
+pub use bonding_tracker::{
+    BondingTracker,
+    BondingConfig,
+    BondingCurveSource,
+    BondingCurveState,
+    BondingProgress,
+    BondingNotification,
+    BondingAlertKind,
+    ProgressPoint,
+    TradingVenue,
+};
@@ -1,16 +1,30 @@
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Utc, Duration, NaiveTime, TimeZone};
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{RwLock, broadcast, mpsc};
 use tracing::{info, debug, warn, error};
 
+use crate::api::jupiter_price_v3::JupiterPriceV3Client;
 use crate::errors::{BotError, Result};
 use crate::websocket::{PriceStreamManager, PriceUpdate};
 use crate::telemetry::TelemetryService;
 use crate::db::Database;
+use super::delivery::{AlertDelivery, AlertKind, AlertPayload};
+
+/// Repeating alerts without their own cooldown wait this long between triggers
+const DEFAULT_REPEAT_COOLDOWN_MINS: i64 = 15;
+/// Symbols without a stream update for this long are polled
+const STREAM_STALE_SECS: i64 = 30;
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 10;
+/// Jupiter prices at most this many mints per request
+const MAX_POLL_BATCH: usize = 100;
+/// Longest percentage-change window kept in memory
+const MAX_HISTORY_DAYS: i64 = 7;
+const MAX_SAMPLES_PER_SYMBOL: usize = 10_000;
 
 /// Comprehensive price alert management system
 #[derive(Clone)]
@@ -21,8 +35,14 @@ pub struct PriceAlertManager {
     active_alerts: Arc<RwLock<HashMap<String, PriceAlert>>>,
     alert_history: Arc<RwLock<VecDeque<AlertHistory>>>,
     alert_stats: Arc<RwLock<AlertStatistics>>,
-    delivery_channels: Arc<RwLock<HashMap<String, Arc<dyn AlertDeliveryChannel>>>>,
+    delivery: Arc<AlertDelivery>,
     alert_queue: Arc<RwLock<mpsc::UnboundedSender<TriggeredAlert>>>,
+    price_client: Option<Arc<JupiterPriceV3Client>>,
+    poll_interval: std::time::Duration,
+    /// Recent prices per symbol for crossing and percentage-change conditions
+    price_history: Arc<RwLock<HashMap<String, VecDeque<PriceSample>>>>,
+    /// Symbols with a stream subscription and when it last delivered a price
+    streamed: Arc<RwLock<HashMap<String, Option<DateTime<Utc>>>>>,
 }
 
 /// Price alert configuration
@@ -287,6 +307,8 @@ pub enum AlertStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlertHistory {
     pub alert_id: String,
+    #[serde(default)]
+    pub user_id: i64,
     pub triggered_at: DateTime<Utc>,
     pub trigger_price: Decimal,
     pub condition_met: String,
@@ -295,7 +317,7 @@ pub struct AlertHistory {
     pub metadata: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DeliveryStatus {
     Sent,
     Failed(String),
@@ -317,18 +339,187 @@ pub struct AlertStatistics {
 
 /// Triggered alert for processing
 #[derive(Debug, Clone)]
-struct TriggeredAlert {
+pub struct TriggeredAlert {
     pub alert: PriceAlert,
     pub trigger_price: Decimal,
     pub condition_details: String,
     pub timestamp: DateTime<Utc>,
 }
 
-/// Alert delivery channel trait
-#[async_trait::async_trait]
-pub trait AlertDeliveryChannel: Send + Sync {
-    async fn deliver(&self, alert: &TriggeredAlert, message: String) -> Result<()>;
-    fn channel_name(&self) -> String;
+/// Triggered alert addressed to a Telegram chat
+#[derive(Debug, Clone)]
+pub struct AlertNotification {
+    pub user_id: i64,
+    pub chat_id: i64,
+    pub alert_id: String,
+    pub message: String,
+}
+
+/// One observed price
+#[derive(Debug, Clone, Copy)]
+struct PriceSample {
+    at: DateTime<Utc>,
+    price: Decimal,
+}
+
+impl PriceAlert {
+    /// Minimum time between triggers; repeating alerts get a default one
+    pub fn effective_cooldown(&self) -> Option<Duration> {
+        self.cooldown_period.or_else(|| {
+            matches!(self.trigger_type, AlertTriggerType::Repeating)
+                .then(|| Duration::minutes(DEFAULT_REPEAT_COOLDOWN_MINS))
+        })
+    }
+
+    pub fn in_cooldown(&self, at: DateTime<Utc>) -> bool {
+        match (self.last_triggered, self.effective_cooldown()) {
+            (Some(last_triggered), Some(cooldown)) => at - last_triggered < cooldown,
+            _ => false,
+        }
+    }
+
+    /// Symbol for messages; chart alerts are keyed by mint
+    pub fn display_symbol(&self) -> &str {
+        self.metadata.get("display_symbol").map(String::as_str).unwrap_or(&self.symbol)
+    }
+}
+
+impl PriceThreshold {
+    /// Whether `price` meets the threshold; crossings also need the price seen before it
+    pub fn is_met(&self, previous: Option<Decimal>, price: Decimal) -> bool {
+        let target = self.target_price;
+        match &self.comparison {
+            PriceComparison::Above => price >= target,
+            PriceComparison::Below => price <= target,
+            PriceComparison::Equals => {
+                let tolerance = self.tolerance.unwrap_or(Decimal::from_str("0.01").unwrap());
+                (price - target).abs() <= tolerance
+            },
+            PriceComparison::CrossingAbove => previous.is_some_and(|p| p < target) && price >= target,
+            PriceComparison::CrossingBelow => previous.is_some_and(|p| p > target) && price <= target,
+            PriceComparison::Between(low, high) => price >= *low && price <= *high,
+            PriceComparison::Outside(low, high) => price < *low || price > *high,
+        }
+    }
+}
+
+impl ChangeTimeframe {
+    /// Start of the window that ends at `now`
+    pub fn window_start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::Minutes(minutes) => now - Duration::minutes(*minutes as i64),
+            Self::Hours(hours) => now - Duration::hours(*hours as i64),
+            Self::Days(days) => now - Duration::days(*days as i64),
+            Self::SinceOpen => Utc.from_utc_datetime(&now.date_naive().and_time(NaiveTime::MIN)),
+            Self::Custom(duration) => now - *duration,
+        }
+    }
+}
+
+impl PercentageChange {
+    /// Percent move from `reference` to `price`, if it is large enough in the watched direction
+    pub fn is_met(&self, reference: Decimal, price: Decimal) -> Option<f64> {
+        if reference <= Decimal::ZERO {
+            return None;
+        }
+        let change = ((price - reference) / reference * Decimal::from(100)).to_f64()?;
+        let met = match self.change_type {
+            ChangeType::Increase => change >= self.threshold_percentage,
+            ChangeType::Decrease => -change >= self.threshold_percentage,
+            ChangeType::AbsoluteChange => change.abs() >= self.threshold_percentage,
+        };
+        met.then_some(change)
+    }
+}
+
+impl AlertStatus {
+    /// Statuses that are watched and restored on startup
+    pub const MONITORED: [AlertStatus; 2] = [AlertStatus::Active, AlertStatus::Paused];
+
+    /// Stored form in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Triggered => "triggered",
+            Self::Paused => "paused",
+            Self::Expired => "expired",
+            Self::Disabled => "disabled",
+            Self::Error(_) => "error",
+        }
+    }
+}
+
+impl AlertDeliveryMethod {
+    pub fn channel_name(&self) -> &'static str {
+        match self {
+            Self::Telegram { .. } => "telegram",
+            Self::Email { .. } => "email",
+            Self::SMS { .. } => "sms",
+            Self::Push { .. } => "push",
+            Self::Webhook { .. } => "webhook",
+            Self::InApp => "in_app",
+            Self::Discord { .. } => "discord",
+            Self::Slack { .. } => "slack",
+        }
+    }
+
+    /// Method and destination, e.g. "email me@example.com"
+    pub fn label(&self) -> String {
+        match self {
+            Self::Telegram { chat_id } => format!("telegram {}", chat_id),
+            Self::Email { address } => format!("email {}", address),
+            Self::SMS { phone_number } => format!("sms {}", phone_number),
+            Self::Push { device_token } => format!("push {}", device_token),
+            Self::Webhook { url } => format!("webhook {}", url),
+            Self::InApp => "in_app".to_string(),
+            Self::Discord { webhook_url } => format!("discord {}", webhook_url),
+            Self::Slack { webhook_url } => format!("slack {}", webhook_url),
+        }
+    }
+}
+
+impl std::fmt::Display for ChangeTimeframe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Minutes(minutes) => write!(f, "in {}m", minutes),
+            Self::Hours(hours) => write!(f, "in {}h", hours),
+            Self::Days(days) => write!(f, "in {}d", days),
+            Self::SinceOpen => write!(f, "since open"),
+            Self::Custom(duration) => write!(f, "in {}m", duration.num_minutes()),
+        }
+    }
+}
+
+impl std::fmt::Display for AlertCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PriceThreshold(threshold) => {
+                let target = threshold.target_price.normalize();
+                match &threshold.comparison {
+                    PriceComparison::Above => write!(f, "Price at or above {}", target),
+                    PriceComparison::Below => write!(f, "Price at or below {}", target),
+                    PriceComparison::Equals => write!(f, "Price at {}", target),
+                    PriceComparison::CrossingAbove => write!(f, "Price crossed above {}", target),
+                    PriceComparison::CrossingBelow => write!(f, "Price crossed below {}", target),
+                    PriceComparison::Between(low, high) => write!(f, "Price between {} and {}", low.normalize(), high.normalize()),
+                    PriceComparison::Outside(low, high) => write!(f, "Price outside {} to {}", low.normalize(), high.normalize()),
+                }
+            },
+            Self::PercentageChange(change) => write!(
+                f,
+                "{} {}% {}",
+                match change.change_type {
+                    ChangeType::Increase => "Up",
+                    ChangeType::Decrease => "Down",
+                    ChangeType::AbsoluteChange => "Moved",
+                },
+                change.threshold_percentage,
+                change.timeframe
+            ),
+            Self::Volume(volume) => write!(f, "Volume above {}", volume.threshold),
+            other => write!(f, "{:?}", other),
+        }
+    }
 }
 
 impl PriceAlertManager {
@@ -336,6 +527,7 @@ impl PriceAlertManager {
     pub fn new(
         database: Arc<Database>,
         price_stream: Arc<PriceStreamManager>,
+        delivery: Arc<AlertDelivery>,
         telemetry: Option<Arc<TelemetryService>>,
     ) -> Self {
         info!("🔔 Initializing price alert manager");
@@ -346,11 +538,15 @@ impl PriceAlertManager {
             database,
             telemetry,
             price_stream,
+            price_client: None,
+            poll_interval: std::time::Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS),
             active_alerts: Arc::new(RwLock::new(HashMap::new())),
             alert_history: Arc::new(RwLock::new(VecDeque::with_capacity(10000))),
             alert_stats: Arc::new(RwLock::new(AlertStatistics::default())),
-            delivery_channels: Arc::new(RwLock::new(HashMap::new())),
+            delivery,
             alert_queue: Arc::new(RwLock::new(tx)),
+            price_history: Arc::new(RwLock::new(HashMap::new())),
+            streamed: Arc::new(RwLock::new(HashMap::new())),
         };
         
         // Start alert processor
@@ -362,18 +558,50 @@ impl PriceAlertManager {
         manager
     }
     
+    /// Poll Jupiter for symbols the price stream doesn't cover
+    pub fn with_price_client(mut self, price_client: Arc<JupiterPriceV3Client>) -> Self {
+        self.price_client = Some(price_client);
+        self
+    }
+    
+    pub fn with_poll_interval(mut self, poll_interval: std::time::Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+    
+    /// Triggered alerts addressed to Telegram chats
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<AlertNotification> {
+        self.delivery.subscribe_telegram()
+    }
+    
+    /// Dispatchers and per-user delivery methods
+    pub fn delivery(&self) -> Arc<AlertDelivery> {
+        self.delivery.clone()
+    }
+    
     /// Start monitoring for alerts
+    ///
+    /// Restores stored alerts, subscribes to stream prices for their symbols
+    /// and, with a price client, polls the symbols the stream doesn't serve.
     pub async fn start_monitoring(&self) -> Result<()> {
         info!("🔔 Starting price alert monitoring");
         
-        // Load active alerts from database
         self.load_active_alerts().await?;
         
-        // Subscribe to price updates for all monitored symbols
-        let symbols = self.get_monitored_symbols().await;
+        for symbol in self.get_monitored_symbols().await {
+            self.monitor_symbol(&symbol).await;
+        }
         
-        for symbol in symbols {
-            self.monitor_symbol(&symbol).await?;
+        if self.price_client.is_some() {
+            let manager = self.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(manager.poll_interval).await;
+                    if let Err(e) = manager.poll_prices().await {
+                        warn!("🔔 Alert price poll failed: {}", e);
+                    }
+                }
+            });
         }
         
         Ok(())
@@ -381,12 +609,12 @@ impl PriceAlertManager {
     
     /// Create a new price alert
     pub async fn create_alert(&self, mut alert: PriceAlert) -> Result<String> {
-        let _span = self.telemetry.as_ref().map(|t| 
+        let _span = self.telemetry.as_ref().map(|t|
             t.create_span("create_price_alert")
         );
         
         // Validate alert
-        self.validate_alert(&alert)?;
+        self.validate_alert(&alert).await?;
         
         // Set defaults
         alert.alert_id = uuid::Uuid::new_v4().to_string();
@@ -398,25 +626,74 @@ impl PriceAlertManager {
         self.store_alert(&alert).await?;
         
         // Add to active alerts
-        let mut alerts = self.active_alerts.write().await;
-        alerts.insert(alert.alert_id.clone(), alert.clone());
+        self.active_alerts.write().await.insert(alert.alert_id.clone(), alert.clone());
         
         // Update statistics
-        let mut stats = self.alert_stats.write().await;
-        stats.total_alerts_created += 1;
-        stats.active_alerts += 1;
-        *stats.alerts_by_symbol.entry(alert.symbol.clone()).or_insert(0) += 1;
+        {
+            let mut stats = self.alert_stats.write().await;
+            stats.total_alerts_created += 1;
+            stats.active_alerts += 1;
+            *stats.alerts_by_symbol.entry(alert.symbol.clone()).or_insert(0) += 1;
+        }
         
         // Start monitoring if not already
-        self.monitor_symbol(&alert.symbol).await?;
+        self.monitor_symbol(&alert.symbol).await;
         
         info!("🔔 Created alert: {} for {}", alert.alert_id, alert.symbol);
         
         Ok(alert.alert_id)
     }
     
-    /// Monitor a symbol for alerts
-    async fn monitor_symbol(&self, symbol: &str) -> Result<()> {
+    /// Create or replace an alert under the id it already carries
+    ///
+    /// Used by imports that need stable ids; trigger history of an existing
+    /// alert is kept. Returns true when the alert is new.
+    pub async fn upsert_alert(&self, mut alert: PriceAlert) -> Result<bool> {
+        self.validate_alert(&alert).await?;
+        if alert.alert_id.is_empty() {
+            return Err(BotError::validation("Alert id is required".to_string()).into());
+        }
+        
+        let mut alerts = self.active_alerts.write().await;
+        let created = match alerts.get(&alert.alert_id) {
+            Some(existing) => {
+                alert.created_at = existing.created_at;
+                alert.last_triggered = existing.last_triggered;
+                alert.trigger_count = existing.trigger_count;
+                false
+            }
+            None => true,
+        };
+        
+        self.store_alert(&alert).await?;
+        alerts.insert(alert.alert_id.clone(), alert.clone());
+        drop(alerts);
+        
+        if created {
+            let mut stats = self.alert_stats.write().await;
+            stats.total_alerts_created += 1;
+            stats.active_alerts += 1;
+            *stats.alerts_by_symbol.entry(alert.symbol.clone()).or_insert(0) += 1;
+        }
+        self.monitor_symbol(&alert.symbol).await;
+        
+        debug!("🔔 Upserted alert: {} for {}", alert.alert_id, alert.symbol);
+        
+        Ok(created)
+    }
+    
+    /// Subscribe to stream prices for a symbol once
+    ///
+    /// Symbols the stream can't serve are left to the price poll.
+    async fn monitor_symbol(&self, symbol: &str) {
+        {
+            let mut streamed = self.streamed.write().await;
+            if streamed.contains_key(symbol) {
+                return;
+            }
+            streamed.insert(symbol.to_string(), None);
+        }
+        
         let manager = self.clone();
         let symbol = symbol.to_string();
         
@@ -430,144 +707,250 @@ impl PriceAlertManager {
             aggregation_interval: Some(std::time::Duration::from_secs(1)),
         };
         
-        let mut price_receiver = self.price_stream.subscribe_prices(subscription).await?;
+        let mut price_receiver = match self.price_stream.subscribe_prices(subscription).await {
+            Ok(receiver) => receiver,
+            Err(e) => {
+                debug!("🔔 No price stream for {}, polling instead: {}", symbol, e);
+                self.streamed.write().await.remove(&symbol);
+                return;
+            }
+        };
         
         // Spawn monitoring task
         tokio::spawn(async move {
-            while let Ok(price_update) = price_receiver.recv().await {
-                if let Err(e) = manager.check_alerts_for_price(&price_update).await {
-                    error!("🔔 Error checking alerts: {}", e);
+            loop {
+                match price_receiver.recv().await {
+                    Ok(price_update) => {
+                        if let Err(e) = manager.check_alerts_for_price(&price_update).await {
+                            error!("🔔 Error checking alerts: {}", e);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("🔔 Alert monitor for {} skipped {} price updates", symbol, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
                 }
             }
+            manager.streamed.write().await.remove(&symbol);
         });
-        
-        Ok(())
     }
     
     /// Check alerts for a price update
     async fn check_alerts_for_price(&self, price_update: &PriceUpdate) -> Result<()> {
-        let alerts = self.active_alerts.read().await;
+        if let Some(last_update) = self.streamed.write().await.get_mut(&price_update.symbol) {
+            *last_update = Some(Utc::now());
+        }
         
-        for alert in alerts.values() {
-            if alert.symbol != price_update.symbol || !alert.enabled {
-                continue;
+        self.observe_price(&price_update.symbol, price_update.price, price_update.volume, price_update.timestamp).await?;
+        Ok(())
+    }
+    
+    /// Price monitored symbols without a recent stream update from Jupiter
+    ///
+    /// Returns how many symbols were priced.
+    pub async fn poll_prices(&self) -> Result<usize> {
+        let Some(price_client) = &self.price_client else {
+            return Ok(0);
+        };
+        
+        let now = Utc::now();
+        let stale_after = Duration::seconds(STREAM_STALE_SECS);
+        let symbols: Vec<String> = {
+            let monitored = self.get_monitored_symbols().await;
+            let streamed = self.streamed.read().await;
+            monitored.into_iter()
+                .filter(|symbol| !matches!(streamed.get(symbol), Some(Some(seen)) if now - *seen < stale_after))
+                .collect()
+        };
+        
+        let mut priced = 0;
+        for batch in symbols.chunks(MAX_POLL_BATCH) {
+            let response = price_client.get_prices(batch.to_vec()).await?;
+            for (symbol, data) in response.prices {
+                let Some(price) = Decimal::from_f64_retain(data.usd_price).filter(|p| *p > Decimal::ZERO) else {
+                    continue;
+                };
+                self.observe_price(&symbol, price, None, now).await?;
+                priced += 1;
             }
+        }
+        
+        Ok(priced)
+    }
+    
+    /// Evaluate a symbol's alerts against a new price
+    ///
+    /// Crossing and percentage-change conditions compare with the prices seen
+    /// before this one. Returns the ids of the alerts that fired.
+    pub async fn observe_price(
+        &self,
+        symbol: &str,
+        price: Decimal,
+        volume: Option<Decimal>,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<String>> {
+        let (due, expired) = {
+            let alerts = self.active_alerts.read().await;
+            let history = self.price_history.read().await;
+            let samples = history.get(symbol);
+            let previous = samples.and_then(|s| s.back()).map(|s| s.price);
             
-            // Check if alert is in cooldown
-            if let Some(last_triggered) = alert.last_triggered {
-                if let Some(cooldown) = alert.cooldown_period {
-                    if Utc::now() - last_triggered < cooldown {
-                        continue;
-                    }
+            let mut due = Vec::new();
+            let mut expired = Vec::new();
+            for alert in alerts.values() {
+                if alert.symbol != symbol || !alert.enabled || alert.status != AlertStatus::Active {
+                    continue;
                 }
-            }
-            
-            // Check if alert has expired
-            if let Some(expiry) = alert.expiry_time {
-                if Utc::now() > expiry {
+                if alert.expiry_time.is_some_and(|expiry| at > expiry) {
+                    expired.push(alert.alert_id.clone());
                     continue;
                 }
-            }
-            
-            // Check conditions
-            for condition in &alert.conditions {
-                if self.check_condition(condition, price_update, alert).await? {
-                    self.trigger_alert(alert.clone(), price_update.price, condition.to_string()).await?;
-                    break; // Only trigger once per check
+                if alert.in_cooldown(at) {
+                    continue;
+                }
+                
+                // Any met condition triggers, once per price
+                let met = alert.conditions.iter()
+                    .find_map(|condition| Self::check_condition(condition, previous, price, volume, samples, at));
+                if let Some(condition_details) = met {
+                    due.push((alert.alert_id.clone(), condition_details));
                 }
             }
+            (due, expired)
+        };
+        
+        self.record_price(symbol, price, at).await;
+        
+        for alert_id in expired {
+            self.expire_alert(&alert_id).await?;
         }
         
-        Ok(())
+        let mut fired = Vec::new();
+        for (alert_id, condition_details) in due {
+            if self.trigger_alert(&alert_id, price, condition_details, at).await? {
+                fired.push(alert_id);
+            }
+        }
+        
+        Ok(fired)
     }
     
-    /// Check if a condition is met
-    async fn check_condition(
-        &self,
+    /// Description of the condition if `price` meets it
+    fn check_condition(
         condition: &AlertCondition,
-        price_update: &PriceUpdate,
-        _alert: &PriceAlert,
-    ) -> Result<bool> {
+        previous: Option<Decimal>,
+        price: Decimal,
+        volume: Option<Decimal>,
+        samples: Option<&VecDeque<PriceSample>>,
+        at: DateTime<Utc>,
+    ) -> Option<String> {
         match condition {
             AlertCondition::PriceThreshold(threshold) => {
-                match &threshold.comparison {
-                    PriceComparison::Above => Ok(price_update.price > threshold.target_price),
-                    PriceComparison::Below => Ok(price_update.price < threshold.target_price),
-                    PriceComparison::Equals => {
-                        let tolerance = threshold.tolerance.unwrap_or(Decimal::from_str("0.01").unwrap());
-                        Ok((price_update.price - threshold.target_price).abs() <= tolerance)
-                    },
-                    PriceComparison::Between(low, high) => {
-                        Ok(price_update.price >= *low && price_update.price <= *high)
-                    },
-                    PriceComparison::Outside(low, high) => {
-                        Ok(price_update.price < *low || price_update.price > *high)
-                    },
-                    _ => {
-                        // Would implement crossing logic with price history
-                        Ok(false)
-                    }
-                }
+                threshold.is_met(previous, price).then(|| condition.to_string())
             },
             AlertCondition::PercentageChange(change) => {
-                // Would calculate percentage change based on timeframe
-                Ok(false)
+                // Oldest price still inside the window
+                let start = change.timeframe.window_start(at);
+                let reference = samples?.iter().find(|s| s.at >= start && s.at < at)?.price;
+                let moved = change.is_met(reference, price)?;
+                Some(format!("{} ({:+.2}%)", condition, moved))
             },
             AlertCondition::Volume(volume_condition) => {
-                if let Some(volume) = price_update.volume {
-                    Ok(volume.to_u64().unwrap_or(0) > volume_condition.threshold)
-                } else {
-                    Ok(false)
-                }
+                volume.and_then(|v| v.to_u64())
+                    .filter(|v| *v > volume_condition.threshold)
+                    .map(|_| condition.to_string())
             },
             _ => {
                 // Other conditions would be implemented
-                Ok(false)
+                None
             }
         }
     }
     
+    async fn record_price(&self, symbol: &str, price: Decimal, at: DateTime<Utc>) {
+        let mut history = self.price_history.write().await;
+        let samples = history.entry(symbol.to_string()).or_default();
+        samples.push_back(PriceSample { at, price });
+        
+        let horizon = at - Duration::days(MAX_HISTORY_DAYS);
+        while samples.len() > MAX_SAMPLES_PER_SYMBOL || samples.front().is_some_and(|s| s.at < horizon) {
+            samples.pop_front();
+        }
+    }
+    
     /// Trigger an alert
+    ///
+    /// Returns false when another update already triggered it or put it in cooldown.
     async fn trigger_alert(
         &self,
-        mut alert: PriceAlert,
+        alert_id: &str,
         trigger_price: Decimal,
         condition_details: String,
-    ) -> Result<()> {
-        info!("🔔 Alert triggered: {} at price {}", alert.alert_id, trigger_price);
-        
-        // Update alert state
-        alert.last_triggered = Some(Utc::now());
-        alert.trigger_count += 1;
-        
-        // Check max triggers
-        if let Some(max) = alert.max_triggers {
-            if alert.trigger_count >= max {
+        at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let alert = {
+            let mut alerts = self.active_alerts.write().await;
+            let Some(alert) = alerts.get_mut(alert_id) else {
+                return Ok(false);
+            };
+            if alert.status != AlertStatus::Active || !alert.enabled || alert.in_cooldown(at) {
+                return Ok(false);
+            }
+            
+            alert.last_triggered = Some(at);
+            alert.trigger_count += 1;
+            
+            // One-shot alerts and exhausted ones are done
+            let exhausted = alert.max_triggers.is_some_and(|max| alert.trigger_count >= max);
+            if matches!(alert.trigger_type, AlertTriggerType::Once) || exhausted {
                 alert.status = AlertStatus::Triggered;
                 alert.enabled = false;
             }
-        }
+            alert.clone()
+        };
         
-        // Update in storage
-        let mut alerts = self.active_alerts.write().await;
-        alerts.insert(alert.alert_id.clone(), alert.clone());
+        info!("🔔 Alert triggered: {} at price {}", alert.alert_id, trigger_price);
+        self.store_alert(&alert).await?;
+        
+        // Update statistics
+        {
+            let mut stats = self.alert_stats.write().await;
+            stats.total_triggers += 1;
+            if alert.status == AlertStatus::Triggered {
+                stats.active_alerts = stats.active_alerts.saturating_sub(1);
+            }
+        }
         
-        // Create triggered alert
         let triggered = TriggeredAlert {
-            alert: alert.clone(),
+            alert,
             trigger_price,
             condition_details,
-            timestamp: Utc::now(),
+            timestamp: at,
         };
         
         // Queue for processing
         let queue = self.alert_queue.read().await;
         queue.send(triggered)?;
         
-        // Update statistics
+        Ok(true)
+    }
+    
+    async fn expire_alert(&self, alert_id: &str) -> Result<()> {
+        let alert = {
+            let mut alerts = self.active_alerts.write().await;
+            let Some(alert) = alerts.get_mut(alert_id) else {
+                return Ok(());
+            };
+            alert.status = AlertStatus::Expired;
+            alert.enabled = false;
+            alert.clone()
+        };
+        
+        self.store_alert(&alert).await?;
         let mut stats = self.alert_stats.write().await;
-        stats.total_triggers += 1;
+        stats.active_alerts = stats.active_alerts.saturating_sub(1);
         
+        info!("🔔 Alert {} expired", alert_id);
         Ok(())
     }
     
@@ -588,18 +971,48 @@ impl PriceAlertManager {
         }
         
         // Send notifications
-        let message = self.format_alert_message(&triggered);
+        let payload = AlertPayload {
+            kind: AlertKind::PriceAlert,
+            alert_id: triggered.alert.alert_id.clone(),
+            user_id: triggered.alert.user_id,
+            title: format!("Price alert: {}", triggered.alert.name),
+            message: self.format_alert_message(&triggered),
+            symbol: triggered.alert.display_symbol().to_string(),
+            price: Some(triggered.trigger_price),
+            condition: Some(triggered.condition_details.clone()),
+            triggered_at: triggered.timestamp,
+        };
+        let methods = self.delivery_methods(&triggered.alert).await;
+        let delivery_status = self.delivery.deliver(&methods, &payload).await;
         
-        for method in &triggered.alert.delivery_methods {
-            self.deliver_alert(&triggered, method, &message).await?;
+        {
+            let mut stats = self.alert_stats.write().await;
+            for status in delivery_status.values() {
+                match status {
+                    DeliveryStatus::Sent => stats.successful_deliveries += 1,
+                    DeliveryStatus::Failed(_) => stats.failed_deliveries += 1,
+                    DeliveryStatus::Pending => {}
+                }
+            }
         }
         
-        // Record in history
-        self.record_alert_history(triggered).await?;
+        // Record in history, failures included
+        self.record_alert_history(triggered, delivery_status).await?;
         
         Ok(())
     }
     
+    /// The alert's own delivery methods, plus the user's preferred ones it lacks
+    async fn delivery_methods(&self, alert: &PriceAlert) -> Vec<AlertDeliveryMethod> {
+        let mut methods = alert.delivery_methods.clone();
+        for method in self.delivery.preferred_methods(alert.user_id).await.unwrap_or_default() {
+            if !methods.iter().any(|m| m.label() == method.label()) {
+                methods.push(method);
+            }
+        }
+        methods
+    }
+    
     /// Execute alert action
     async fn execute_action(&self, action: &AlertAction, triggered: &TriggeredAlert) -> Result<()> {
         match action {
@@ -625,34 +1038,12 @@ impl PriceAlertManager {
         Ok(())
     }
     
-    /// Deliver alert notification
-    async fn deliver_alert(
-        &self,
-        triggered: &TriggeredAlert,
-        method: &AlertDeliveryMethod,
-        message: &str,
-    ) -> Result<()> {
-        let channels = self.delivery_channels.read().await;
-        
-        match method {
-            AlertDeliveryMethod::InApp => {
-                info!("🔔 In-app notification: {}", message);
-            },
-            _ => {
-                // Would use appropriate delivery channel
-                debug!("🔔 Delivering alert via {:?}", method);
-            }
-        }
-        
-        Ok(())
-    }
-    
     /// Format alert message
     fn format_alert_message(&self, triggered: &TriggeredAlert) -> String {
         format!(
             "🔔 {} Alert: {}\n\
             Symbol: {}\n\
-            Price: {}\n\
+            Price: ${}\n\
             Condition: {}\n\
             Time: {}",
             match triggered.alert.priority {
@@ -663,25 +1054,33 @@ impl PriceAlertManager {
                 AlertPriority::Low => "📌 LOW",
             },
             triggered.alert.name,
-            triggered.alert.symbol,
-            triggered.trigger_price,
+            triggered.alert.display_symbol(),
+            triggered.trigger_price.normalize(),
             triggered.condition_details,
             triggered.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
         )
     }
     
     /// Record alert in history
-    async fn record_alert_history(&self, triggered: TriggeredAlert) -> Result<()> {
+    async fn record_alert_history(
+        &self,
+        triggered: TriggeredAlert,
+        delivery_status: HashMap<String, DeliveryStatus>,
+    ) -> Result<()> {
+        let mut metadata = HashMap::new();
+        metadata.insert("name".to_string(), triggered.alert.name.clone());
+        
         let history_entry = AlertHistory {
             alert_id: triggered.alert.alert_id.clone(),
+            user_id: triggered.alert.user_id,
             triggered_at: triggered.timestamp,
             trigger_price: triggered.trigger_price,
             condition_met: triggered.condition_details,
             actions_taken: triggered.alert.actions.iter()
                 .map(|a| format!("{:?}", a))
                 .collect(),
-            delivery_status: HashMap::new(),
-            metadata: HashMap::new(),
+            delivery_status,
+            metadata,
         };
         
         let mut history = self.alert_history.write().await;
@@ -708,19 +1107,66 @@ impl PriceAlertManager {
         Ok(())
     }
     
-    async fn load_active_alerts(&self) -> Result<()> {
-        // Would load from database
-        Ok(())
+    /// Restore watched alerts from the database
+    ///
+    /// Returns how many alerts are monitored again. Alerts that expired while
+    /// the bot was down are marked expired instead.
+    pub async fn load_active_alerts(&self) -> Result<usize> {
+        let statuses: Vec<&str> = AlertStatus::MONITORED.iter().map(AlertStatus::as_str).collect();
+        let rows = self.database.get_price_alerts_by_status(&statuses).await?;
+        
+        let now = Utc::now();
+        let mut restored = 0;
+        for row in rows {
+            let mut alert: PriceAlert = match serde_json::from_str(&row) {
+                Ok(alert) => alert,
+                Err(e) => {
+                    warn!("🔔 Skipping unreadable stored alert: {}", e);
+                    continue;
+                }
+            };
+            
+            if alert.expiry_time.is_some_and(|expiry| now > expiry) {
+                alert.status = AlertStatus::Expired;
+                alert.enabled = false;
+                self.store_alert(&alert).await?;
+                info!("🔔 Alert {} expired while offline", alert.alert_id);
+                continue;
+            }
+            
+            let symbol = alert.symbol.clone();
+            if self.active_alerts.write().await.insert(alert.alert_id.clone(), alert).is_none() {
+                let mut stats = self.alert_stats.write().await;
+                stats.active_alerts += 1;
+                *stats.alerts_by_symbol.entry(symbol).or_insert(0) += 1;
+            }
+            restored += 1;
+        }
+        
+        if restored > 0 {
+            info!("🔔 Restored {} price alerts", restored);
+        }
+        Ok(restored)
     }
     
-    async fn store_alert(&self, _alert: &PriceAlert) -> Result<()> {
-        // Would store in database
-        Ok(())
+    /// Write the whole alert, keyed by alert id; conditions and delivery are stored as JSON
+    async fn store_alert(&self, alert: &PriceAlert) -> Result<()> {
+        let data = serde_json::to_string(alert)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize alert {}: {}", alert.alert_id, e)))?;
+        self.database.upsert_price_alert(
+            &alert.alert_id,
+            alert.user_id,
+            &alert.symbol,
+            alert.status.as_str(),
+            &data,
+        ).await
     }
     
+    /// Symbols with at least one alert waiting to trigger
     async fn get_monitored_symbols(&self) -> Vec<String> {
         let alerts = self.active_alerts.read().await;
         let mut symbols: Vec<String> = alerts.values()
+            .filter(|a| a.enabled && a.status == AlertStatus::Active)
             .map(|a| a.symbol.clone())
             .collect();
         symbols.sort();
@@ -736,10 +1182,12 @@ impl PriceAlertManager {
     
     /// Update alert
     pub async fn update_alert(&self, alert_id: &str, updates: HashMap<String, serde_json::Value>) -> Result<()> {
-        let mut alerts = self.active_alerts.write().await;
-        
-        if let Some(alert) = alerts.get_mut(alert_id) {
-            // Would apply updates
+        let alert = {
+            let mut alerts = self.active_alerts.write().await;
+            let Some(alert) = alerts.get_mut(alert_id) else {
+                return Err(BotError::not_found(format!("Alert {} not found", alert_id)).into());
+            };
+            
             for (key, value) in updates {
                 match key.as_str() {
                     "enabled" => {
@@ -755,24 +1203,32 @@ impl PriceAlertManager {
                     _ => {}
                 }
             }
-            
-            Ok(())
-        } else {
-            Err(BotError::not_found(format!("Alert {} not found", alert_id)).into())
-        }
+            alert.clone()
+        };
+        
+        self.store_alert(&alert).await
+    }
+    
+    /// Alerts owned by a user
+    pub async fn get_user_alerts(&self, user_id: i64) -> Vec<PriceAlert> {
+        let alerts = self.active_alerts.read().await;
+        alerts.values().filter(|a| a.user_id == user_id).cloned().collect()
     }
     
     /// Delete alert
     pub async fn delete_alert(&self, alert_id: &str) -> Result<bool> {
-        let mut alerts = self.active_alerts.write().await;
-        let removed = alerts.remove(alert_id).is_some();
+        let Some(alert) = self.active_alerts.write().await.remove(alert_id) else {
+            return Ok(false);
+        };
         
-        if removed {
+        self.database.delete_price_alert(alert_id).await?;
+        
+        if alert.status == AlertStatus::Active {
             let mut stats = self.alert_stats.write().await;
             stats.active_alerts = stats.active_alerts.saturating_sub(1);
         }
         
-        Ok(removed)
+        Ok(true)
     }
     
     /// Get alert statistics
@@ -781,6 +1237,17 @@ impl PriceAlertManager {
         stats.clone()
     }
     
+    /// A user's most recent triggers, newest first
+    pub async fn get_user_history(&self, user_id: i64, limit: usize) -> Vec<AlertHistory> {
+        let history = self.alert_history.read().await;
+        history.iter()
+            .rev()
+            .filter(|h| h.user_id == user_id)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+    
     /// Get alert history
     pub async fn get_history(&self, limit: Option<usize>) -> Vec<AlertHistory> {
         let history = self.alert_history.read().await;
@@ -0,0 +1,921 @@
+use chrono::{DateTime, Utc, Duration};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{RwLock, broadcast};
+use tracing::{info, debug, warn, error};
+
+use crate::errors::{BotError, Result};
+use crate::api::pump_fun::PumpFunClient;
+use crate::utils::i18n::{fmt_datetime, fmt_number, DateStyle, NumberKind, DEFAULT_LANG};
+use super::market_events::{
+    MarketEventMonitor,
+    MarketEvent,
+    EventType,
+    EventSeverity,
+    EventSource,
+    EventDetails,
+    RiskLevel,
+    ScheduledTokenEvent,
+};
+
+/// Calendar of dated, token-specific events (unlocks, migrations, launches)
+#[derive(Clone)]
+pub struct TokenCalendar {
+    config: CalendarConfig,
+    sources: Arc<Vec<Arc<dyn CalendarEventSource>>>,
+    migration_source: Arc<PumpFunMigrationSource>,
+    events: Arc<RwLock<HashMap<String, Vec<TokenCalendarEvent>>>>,
+    holders: Arc<RwLock<HashMap<String, HashSet<i64>>>>,
+    watchers: Arc<RwLock<HashMap<String, HashSet<i64>>>>,
+    sent_reminders: Arc<RwLock<HashSet<(String, i64, i64)>>>,
+    emitted_events: Arc<RwLock<HashSet<String>>>,
+    last_digest: Arc<RwLock<Option<DateTime<Utc>>>>,
+    /// Client language per user, for number and date formatting in digests
+    user_langs: Arc<RwLock<HashMap<i64, String>>>,
+    market_events: Option<Arc<MarketEventMonitor>>,
+    notification_tx: broadcast::Sender<CalendarNotification>,
+}
+
+/// A scheduled event for a single mint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenCalendarEvent {
+    pub event_id: String,
+    pub mint: String,
+    pub symbol: String,
+    pub kind: CalendarEventKind,
+    pub scheduled_at: DateTime<Utc>,
+    pub source: CalendarSourceKind,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What is going to happen to the token
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CalendarEventKind {
+    Unlock { supply_percentage: f64 },
+    Migration { bonding_curve_progress: f64 },
+    Launch,
+    Other(String),
+}
+
+/// Where an event came from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CalendarSourceKind {
+    CuratedFile,
+    PumpFunMigration,
+    UnlocksApi,
+    Manual { added_by: String },
+}
+
+/// Calendar configuration
+#[derive(Debug, Clone)]
+pub struct CalendarConfig {
+    pub lead_times: Vec<Duration>,
+    pub curated_file: Option<PathBuf>,
+    pub unlocks_api_url: Option<String>,
+    pub refresh_interval: Duration,
+    pub digest_horizon: Duration,
+    pub digest_interval: Duration,
+}
+
+/// Reminder or digest produced by the calendar for a single user
+#[derive(Debug, Clone)]
+pub struct CalendarNotification {
+    pub user_id: i64,
+    pub notification_type: CalendarNotificationType,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalendarNotificationType {
+    Reminder { event_id: String, lead_time: Duration },
+    Digest,
+}
+
+/// Source of calendar events for a set of mints
+#[async_trait::async_trait]
+pub trait CalendarEventSource: Send + Sync {
+    async fn fetch_events(&self, mints: &[String]) -> Result<Vec<TokenCalendarEvent>>;
+    fn source_name(&self) -> String;
+}
+
+/// Manually curated events loaded from a JSON file
+pub struct CuratedFileSource {
+    path: PathBuf,
+}
+
+/// Optional adapter for an external token unlocks API
+pub struct UnlocksApiSource {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnlockApiEntry {
+    mint: String,
+    symbol: Option<String>,
+    unlock_time: DateTime<Utc>,
+    supply_percentage: f64,
+    description: Option<String>,
+}
+
+/// Derives pump.fun → Raydium migration ETAs from bonding-curve progress samples
+pub struct PumpFunMigrationSource {
+    client: Option<PumpFunClient>,
+    samples: RwLock<HashMap<String, Vec<ProgressSample>>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProgressSample {
+    progress: f64,
+    at: DateTime<Utc>,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            lead_times: vec![Duration::hours(24), Duration::hours(1)],
+            curated_file: None,
+            unlocks_api_url: None,
+            refresh_interval: Duration::minutes(15),
+            digest_horizon: Duration::days(7),
+            digest_interval: Duration::hours(24),
+        }
+    }
+}
+
+impl CalendarConfig {
+    /// Build configuration from environment variables
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(hours) = std::env::var("TOKEN_CALENDAR_LEAD_HOURS") {
+            let lead_times: Vec<Duration> = hours
+                .split(',')
+                .filter_map(|h| h.trim().parse::<i64>().ok())
+                .filter(|h| *h > 0)
+                .map(Duration::hours)
+                .collect();
+            if !lead_times.is_empty() {
+                config.lead_times = lead_times;
+            }
+        }
+
+        config.curated_file = std::env::var("TOKEN_CALENDAR_FILE").ok().map(PathBuf::from);
+        config.unlocks_api_url = std::env::var("TOKEN_UNLOCKS_API_URL").ok();
+        config
+    }
+}
+
+impl TokenCalendarEvent {
+    /// Short human readable summary, e.g. "⚠️ 12% supply unlock in 3 days"
+    pub fn summary(&self, now: DateTime<Utc>) -> String {
+        self.summary_for(DEFAULT_LANG, now)
+    }
+
+    /// `summary` with numbers formatted for the user's language
+    pub fn summary_for(&self, lang: &str, now: DateTime<Utc>) -> String {
+        let when = format_time_until(self.scheduled_at - now);
+        match &self.kind {
+            CalendarEventKind::Unlock { supply_percentage } => {
+                format!("⚠️ {} supply unlock {}", fmt_number(lang, *supply_percentage, NumberKind::Percent(0)), when)
+            }
+            CalendarEventKind::Migration { bonding_curve_progress } => {
+                format!("🎓 Raydium migration {} (curve {})", when, fmt_number(lang, *bonding_curve_progress, NumberKind::Percent(1)))
+            }
+            CalendarEventKind::Launch => format!("🚀 Launch {}", when),
+            CalendarEventKind::Other(label) => format!("📅 {} {}", label, when),
+        }
+    }
+}
+
+fn format_time_until(delta: Duration) -> String {
+    if delta <= Duration::zero() {
+        "now".to_string()
+    } else if delta.num_days() >= 1 {
+        let days = delta.num_days();
+        format!("in {} day{}", days, if days == 1 { "" } else { "s" })
+    } else if delta.num_hours() >= 1 {
+        let hours = delta.num_hours();
+        format!("in {} hour{}", hours, if hours == 1 { "" } else { "s" })
+    } else {
+        format!("in {} min", delta.num_minutes().max(1))
+    }
+}
+
+impl TokenCalendar {
+    /// Create a new token calendar with the sources enabled in the config
+    pub fn new(config: CalendarConfig, market_events: Option<Arc<MarketEventMonitor>>) -> Self {
+        info!("📅 Initializing token launch calendar");
+
+        let migration_source = Arc::new(PumpFunMigrationSource::new(PumpFunClient::new().ok()));
+
+        let mut sources: Vec<Arc<dyn CalendarEventSource>> = vec![migration_source.clone()];
+        if let Some(path) = &config.curated_file {
+            sources.push(Arc::new(CuratedFileSource::new(path.clone())));
+        }
+        if let Some(url) = &config.unlocks_api_url {
+            sources.push(Arc::new(UnlocksApiSource::new(url.clone())));
+        }
+
+        let (notification_tx, _) = broadcast::channel(1000);
+
+        Self {
+            config,
+            sources: Arc::new(sources),
+            migration_source,
+            events: Arc::new(RwLock::new(HashMap::new())),
+            holders: Arc::new(RwLock::new(HashMap::new())),
+            watchers: Arc::new(RwLock::new(HashMap::new())),
+            sent_reminders: Arc::new(RwLock::new(HashSet::new())),
+            emitted_events: Arc::new(RwLock::new(HashSet::new())),
+            last_digest: Arc::new(RwLock::new(None)),
+            user_langs: Arc::new(RwLock::new(HashMap::new())),
+            market_events,
+            notification_tx,
+        }
+    }
+
+    /// Subscribe to reminders and digests produced by the calendar
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<CalendarNotification> {
+        self.notification_tx.subscribe()
+    }
+
+    /// Start the background refresh and notification loop
+    pub async fn start(&self) {
+        let calendar = self.clone();
+        let interval_secs = self.config.refresh_interval.num_seconds().max(60) as u64;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = calendar.refresh_sources().await {
+                    error!("📅 Error refreshing calendar sources: {}", e);
+                }
+                if let Err(e) = calendar.tick(Utc::now()).await {
+                    error!("📅 Error processing calendar tick: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Remember the user's client language for digests sent in the background
+    pub async fn set_user_lang(&self, user_id: i64, lang: &str) {
+        self.user_langs.write().await.insert(user_id, lang.to_string());
+    }
+
+    /// Record the mints a user currently holds
+    pub async fn set_holdings(&self, user_id: i64, mints: &[String]) {
+        let mut holders = self.holders.write().await;
+        for users in holders.values_mut() {
+            users.remove(&user_id);
+        }
+        for mint in mints {
+            holders.entry(mint.clone()).or_insert_with(HashSet::new).insert(user_id);
+        }
+        holders.retain(|_, users| !users.is_empty());
+    }
+
+    /// Record a mint the user is watching without holding it
+    pub async fn watch(&self, user_id: i64, mint: &str) {
+        let mut watchers = self.watchers.write().await;
+        watchers.entry(mint.to_string()).or_insert_with(HashSet::new).insert(user_id);
+    }
+
+    /// Stop watching a mint
+    pub async fn unwatch(&self, user_id: i64, mint: &str) {
+        let mut watchers = self.watchers.write().await;
+        if let Some(users) = watchers.get_mut(mint) {
+            users.remove(&user_id);
+        }
+    }
+
+    /// Stop tracking anything for a user: holdings, watchlist and language
+    pub async fn forget_user(&self, user_id: i64) -> usize {
+        let mut removed = 0;
+        for map in [&self.holders, &self.watchers] {
+            let mut map = map.write().await;
+            for users in map.values_mut() {
+                if users.remove(&user_id) {
+                    removed += 1;
+                }
+            }
+            map.retain(|_, users| !users.is_empty());
+        }
+        self.user_langs.write().await.remove(&user_id);
+        removed
+    }
+
+    /// Add an event by hand (admin command) for anything the sources miss
+    pub async fn add_manual_event(
+        &self,
+        mint: &str,
+        symbol: &str,
+        kind: CalendarEventKind,
+        scheduled_at: DateTime<Utc>,
+        description: &str,
+        added_by: &str,
+    ) -> Result<TokenCalendarEvent> {
+        if scheduled_at <= Utc::now() {
+            return Err(BotError::validation("Event time must be in the future".to_string()).into());
+        }
+
+        let event = TokenCalendarEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            mint: mint.to_string(),
+            symbol: symbol.to_string(),
+            kind,
+            scheduled_at,
+            source: CalendarSourceKind::Manual { added_by: added_by.to_string() },
+            description: description.to_string(),
+            created_at: Utc::now(),
+        };
+
+        info!("📅 Manual calendar event {} added for {} by {}", event.event_id, mint, added_by);
+        self.upsert_event(event.clone()).await;
+
+        Ok(event)
+    }
+
+    /// Remove an event by id
+    pub async fn remove_event(&self, event_id: &str) -> bool {
+        let mut events = self.events.write().await;
+        let mut removed = false;
+        for list in events.values_mut() {
+            let before = list.len();
+            list.retain(|e| e.event_id != event_id);
+            removed |= list.len() != before;
+        }
+        removed
+    }
+
+    /// Feed a bonding-curve progress observation for a held pump.fun token
+    pub async fn record_bonding_progress(&self, mint: &str, symbol: &str, progress: f64, at: DateTime<Utc>) {
+        self.migration_source.record_progress(mint, progress, at).await;
+        if let Some(event) = self.migration_source.derive_event(mint, symbol, at).await {
+            self.upsert_event(event).await;
+        }
+    }
+
+    /// Pull events from every configured source for tracked mints
+    pub async fn refresh_sources(&self) -> Result<()> {
+        let mints = self.tracked_mints().await;
+        if mints.is_empty() {
+            return Ok(());
+        }
+
+        for source in self.sources.iter() {
+            match source.fetch_events(&mints).await {
+                Ok(fetched) => {
+                    debug!("📅 {} returned {} events", source.source_name(), fetched.len());
+                    for event in fetched {
+                        self.upsert_event(event).await;
+                    }
+                }
+                Err(e) => {
+                    warn!("📅 Calendar source {} failed: {}", source.source_name(), e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Upcoming events for the given mints, soonest first
+    pub async fn upcoming_for_mints(&self, mints: &[String], now: DateTime<Utc>) -> Vec<TokenCalendarEvent> {
+        let events = self.events.read().await;
+        let mut upcoming: Vec<TokenCalendarEvent> = mints
+            .iter()
+            .filter_map(|m| events.get(m))
+            .flatten()
+            .filter(|e| e.scheduled_at > now)
+            .cloned()
+            .collect();
+        upcoming.sort_by_key(|e| e.scheduled_at);
+        upcoming
+    }
+
+    /// Position detail lines for a single mint, e.g. "⚠️ 12% supply unlock in 3 days"
+    pub async fn position_warnings(&self, mint: &str, now: DateTime<Utc>) -> Vec<String> {
+        self.upcoming_for_mints(&[mint.to_string()], now)
+            .await
+            .iter()
+            .filter(|e| e.scheduled_at - now <= self.config.digest_horizon)
+            .map(|e| e.summary(now))
+            .collect()
+    }
+
+    /// Digest section listing upcoming events for a user's held and watched tokens
+    pub async fn digest_section(&self, user_id: i64, now: DateTime<Utc>) -> Option<String> {
+        let mints = self.mints_for_user(user_id).await;
+        let horizon = now + self.config.digest_horizon;
+        let upcoming: Vec<TokenCalendarEvent> = self.upcoming_for_mints(&mints, now)
+            .await
+            .into_iter()
+            .filter(|e| e.scheduled_at <= horizon)
+            .collect();
+
+        if upcoming.is_empty() {
+            return None;
+        }
+
+        let lang = self.user_langs.read().await.get(&user_id).cloned().unwrap_or_else(|| DEFAULT_LANG.to_string());
+        let mut section = String::from("📅 Upcoming token events:\n");
+        for event in upcoming {
+            section.push_str(&format!(
+                "• {}: {} ({})\n",
+                event.symbol,
+                event.summary_for(&lang, now),
+                fmt_datetime(&lang, chrono_tz::Tz::UTC, event.scheduled_at, DateStyle::DateTime)
+            ));
+        }
+        Some(section)
+    }
+
+    /// Process reminders, digests and due events at `now`
+    pub async fn tick(&self, now: DateTime<Utc>) -> Result<Vec<CalendarNotification>> {
+        let mut notifications = self.due_reminders(now).await;
+        notifications.extend(self.due_digests(now).await);
+
+        self.emit_due_market_events(now).await?;
+
+        for notification in &notifications {
+            // No receivers simply means the bot isn't forwarding yet
+            let _ = self.notification_tx.send(notification.clone());
+        }
+
+        Ok(notifications)
+    }
+
+    /// Reminders whose lead time has been reached and not yet sent
+    async fn due_reminders(&self, now: DateTime<Utc>) -> Vec<CalendarNotification> {
+        let events = self.events.read().await;
+        let holders = self.holders.read().await;
+        let watchers = self.watchers.read().await;
+        let mut sent = self.sent_reminders.write().await;
+        let mut notifications = Vec::new();
+
+        for (mint, list) in events.iter() {
+            let mut users: HashSet<i64> = holders.get(mint).cloned().unwrap_or_default();
+            users.extend(watchers.get(mint).cloned().unwrap_or_default());
+            if users.is_empty() {
+                continue;
+            }
+
+            for event in list.iter().filter(|e| e.scheduled_at > now) {
+                let remaining = event.scheduled_at - now;
+                // Only the tightest lead time that has been crossed fires, so a
+                // freshly added event one hour out doesn't also send the 24h reminder.
+                let lead_time = self.config.lead_times
+                    .iter()
+                    .filter(|lead| remaining <= **lead)
+                    .min()
+                    .copied();
+
+                let Some(lead_time) = lead_time else { continue };
+
+                for user_id in &users {
+                    let key = (event.event_id.clone(), *user_id, lead_time.num_minutes());
+                    let already_sent = sent.contains(&key) || self.config.lead_times
+                        .iter()
+                        .filter(|lead| **lead < lead_time)
+                        .any(|lead| sent.contains(&(event.event_id.clone(), *user_id, lead.num_minutes())));
+                    if already_sent {
+                        continue;
+                    }
+
+                    sent.insert(key);
+                    notifications.push(CalendarNotification {
+                        user_id: *user_id,
+                        notification_type: CalendarNotificationType::Reminder {
+                            event_id: event.event_id.clone(),
+                            lead_time,
+                        },
+                        message: format!(
+                            "⏰ {} — {}\n{}",
+                            event.symbol,
+                            event.summary(now),
+                            event.description
+                        ),
+                    });
+                }
+            }
+        }
+
+        notifications
+    }
+
+    /// Daily digests for every tracked user
+    async fn due_digests(&self, now: DateTime<Utc>) -> Vec<CalendarNotification> {
+        {
+            let mut last = self.last_digest.write().await;
+            if let Some(last_sent) = *last {
+                if now - last_sent < self.config.digest_interval {
+                    return Vec::new();
+                }
+            }
+            *last = Some(now);
+        }
+
+        let mut notifications = Vec::new();
+        for user_id in self.tracked_users().await {
+            if let Some(section) = self.digest_section(user_id, now).await {
+                notifications.push(CalendarNotification {
+                    user_id,
+                    notification_type: CalendarNotificationType::Digest,
+                    message: section,
+                });
+            }
+        }
+        notifications
+    }
+
+    /// Emit a market event once an event's scheduled time arrives
+    async fn emit_due_market_events(&self, now: DateTime<Utc>) -> Result<()> {
+        let due: Vec<TokenCalendarEvent> = {
+            let events = self.events.read().await;
+            let emitted = self.emitted_events.read().await;
+            events.values()
+                .flatten()
+                .filter(|e| e.scheduled_at <= now && !emitted.contains(&e.event_id))
+                .cloned()
+                .collect()
+        };
+
+        for event in due {
+            self.emitted_events.write().await.insert(event.event_id.clone());
+
+            if let Some(monitor) = &self.market_events {
+                monitor.publish_event(Self::to_market_event(&event)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn to_market_event(event: &TokenCalendarEvent) -> MarketEvent {
+        let severity = match event.kind {
+            CalendarEventKind::Unlock { supply_percentage } if supply_percentage >= 5.0 => EventSeverity::High,
+            CalendarEventKind::Migration { .. } => EventSeverity::High,
+            _ => EventSeverity::Medium,
+        };
+
+        MarketEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            event_type: EventType::Scheduled(ScheduledTokenEvent {
+                calendar_event_id: event.event_id.clone(),
+                mint: event.mint.clone(),
+                label: event.summary(event.scheduled_at),
+                scheduled_at: event.scheduled_at,
+            }),
+            symbol: event.symbol.clone(),
+            timestamp: Utc::now(),
+            severity,
+            source: EventSource::Calendar,
+            details: EventDetails {
+                description: event.description.clone(),
+                impact_assessment: "Scheduled token event may move price sharply".to_string(),
+                recommended_actions: vec![
+                    "Review position size".to_string(),
+                    "Check stop-loss levels".to_string(),
+                ],
+                risk_level: RiskLevel::High,
+                confidence: 1.0,
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Insert or replace an event; events from the same source and kind are deduplicated per mint
+    async fn upsert_event(&self, event: TokenCalendarEvent) {
+        let mut events = self.events.write().await;
+        let list = events.entry(event.mint.clone()).or_insert_with(Vec::new);
+
+        let existing = list.iter_mut().find(|e| {
+            e.event_id == event.event_id
+                || (e.source == event.source
+                    && std::mem::discriminant(&e.kind) == std::mem::discriminant(&event.kind)
+                    && !matches!(e.source, CalendarSourceKind::Manual { .. })
+                    && (matches!(e.kind, CalendarEventKind::Migration { .. }) || e.scheduled_at == event.scheduled_at))
+        });
+
+        match existing {
+            Some(existing) => {
+                let event_id = existing.event_id.clone();
+                *existing = TokenCalendarEvent { event_id, ..event };
+            }
+            None => list.push(event),
+        }
+    }
+
+    async fn tracked_mints(&self) -> Vec<String> {
+        let mut mints: HashSet<String> = self.holders.read().await.keys().cloned().collect();
+        mints.extend(self.watchers.read().await.keys().cloned());
+        mints.into_iter().collect()
+    }
+
+    async fn tracked_users(&self) -> HashSet<i64> {
+        let mut users: HashSet<i64> = self.holders.read().await.values().flatten().copied().collect();
+        users.extend(self.watchers.read().await.values().flatten().copied());
+        users
+    }
+
+    async fn mints_for_user(&self, user_id: i64) -> Vec<String> {
+        let mut mints: HashSet<String> = HashSet::new();
+        for (mint, users) in self.holders.read().await.iter() {
+            if users.contains(&user_id) {
+                mints.insert(mint.clone());
+            }
+        }
+        for (mint, users) in self.watchers.read().await.iter() {
+            if users.contains(&user_id) {
+                mints.insert(mint.clone());
+            }
+        }
+        mints.into_iter().collect()
+    }
+}
+
+impl CuratedFileSource {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait::async_trait]
+impl CalendarEventSource for CuratedFileSource {
+    async fn fetch_events(&self, mints: &[String]) -> Result<Vec<TokenCalendarEvent>> {
+        let contents = tokio::fs::read_to_string(&self.path).await
+            .map_err(|e| BotError::config(format!("Failed to read calendar file {}: {}", self.path.display(), e)))?;
+
+        let events: Vec<TokenCalendarEvent> = serde_json::from_str(&contents)
+            .map_err(|e| BotError::parsing(format!("Invalid calendar file: {}", e)))?;
+
+        Ok(events.into_iter()
+            .filter(|e| mints.contains(&e.mint))
+            .map(|e| TokenCalendarEvent { source: CalendarSourceKind::CuratedFile, ..e })
+            .collect())
+    }
+
+    fn source_name(&self) -> String {
+        "curated_file".to_string()
+    }
+}
+
+impl UnlocksApiSource {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CalendarEventSource for UnlocksApiSource {
+    async fn fetch_events(&self, mints: &[String]) -> Result<Vec<TokenCalendarEvent>> {
+        let url = format!("{}/unlocks?mints={}", self.base_url, mints.join(","));
+
+        let response = self.client.get(&url)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| BotError::external_api(format!("Unlocks API request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(BotError::external_api(format!("Unlocks API returned {}", response.status())).into());
+        }
+
+        let entries: Vec<UnlockApiEntry> = response.json().await
+            .map_err(|e| BotError::parsing(format!("Invalid unlocks API response: {}", e)))?;
+
+        Ok(entries.into_iter()
+            .map(|entry| TokenCalendarEvent {
+                event_id: format!("unlock:{}:{}", entry.mint, entry.unlock_time.timestamp()),
+                symbol: entry.symbol.unwrap_or_else(|| entry.mint[..entry.mint.len().min(6)].to_string()),
+                kind: CalendarEventKind::Unlock { supply_percentage: entry.supply_percentage },
+                scheduled_at: entry.unlock_time,
+                source: CalendarSourceKind::UnlocksApi,
+                description: entry.description.unwrap_or_else(|| {
+                    format!("{:.1}% of supply unlocks", entry.supply_percentage)
+                }),
+                created_at: Utc::now(),
+                mint: entry.mint,
+            })
+            .collect())
+    }
+
+    fn source_name(&self) -> String {
+        "unlocks_api".to_string()
+    }
+}
+
+impl PumpFunMigrationSource {
+    /// Minimum spacing between samples used for rate estimation
+    const MIN_SAMPLE_SPAN_MINUTES: i64 = 5;
+
+    pub fn new(client: Option<PumpFunClient>) -> Self {
+        Self {
+            client,
+            samples: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record a bonding-curve progress observation (0-100)
+    pub async fn record_progress(&self, mint: &str, progress: f64, at: DateTime<Utc>) {
+        let mut samples = self.samples.write().await;
+        let list = samples.entry(mint.to_string()).or_insert_with(Vec::new);
+        list.push(ProgressSample { progress, at });
+
+        // Keep a day of history for rate estimation
+        list.retain(|s| at - s.at <= Duration::hours(24));
+    }
+
+    /// Estimate when the curve completes from the observed fill rate
+    pub async fn derive_event(&self, mint: &str, symbol: &str, now: DateTime<Utc>) -> Option<TokenCalendarEvent> {
+        let samples = self.samples.read().await;
+        let list = samples.get(mint)?;
+        let first = list.first()?;
+        let last = list.last()?;
+
+        if last.progress >= 100.0 {
+            return None;
+        }
+
+        let span = last.at - first.at;
+        if span < Duration::minutes(Self::MIN_SAMPLE_SPAN_MINUTES) {
+            return None;
+        }
+
+        let rate_per_sec = (last.progress - first.progress) / span.num_seconds() as f64;
+        if rate_per_sec <= 0.0 {
+            return None;
+        }
+
+        let seconds_left = ((100.0 - last.progress) / rate_per_sec) as i64;
+        let eta = now + Duration::seconds(seconds_left);
+
+        Some(TokenCalendarEvent {
+            event_id: format!("migration:{}", mint),
+            mint: mint.to_string(),
+            symbol: symbol.to_string(),
+            kind: CalendarEventKind::Migration { bonding_curve_progress: last.progress },
+            scheduled_at: eta,
+            source: CalendarSourceKind::PumpFunMigration,
+            description: format!(
+                "Bonding curve at {:.1}%, estimated pump.fun → Raydium migration at {}",
+                last.progress,
+                eta.format("%Y-%m-%d %H:%M UTC")
+            ),
+            created_at: now,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CalendarEventSource for PumpFunMigrationSource {
+    async fn fetch_events(&self, mints: &[String]) -> Result<Vec<TokenCalendarEvent>> {
+        let Some(client) = &self.client else {
+            return Ok(Vec::new());
+        };
+
+        let now = Utc::now();
+        let mut events = Vec::new();
+
+        for mint in mints {
+            // Non pump.fun tokens simply 404 here
+            let token = match client.get_token(mint).await {
+                Ok(token) => token,
+                Err(_) => continue,
+            };
+
+            self.record_progress(mint, token.bonding_curve_progress, now).await;
+            if let Some(event) = self.derive_event(mint, &token.symbol, now).await {
+                events.push(event);
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn source_name(&self) -> String {
+        "pump_fun_migration".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unlock_event(mint: &str, at: DateTime<Utc>) -> TokenCalendarEvent {
+        TokenCalendarEvent {
+            event_id: "unlock-1".to_string(),
+            mint: mint.to_string(),
+            symbol: "TEST".to_string(),
+            kind: CalendarEventKind::Unlock { supply_percentage: 12.0 },
+            scheduled_at: at,
+            source: CalendarSourceKind::CuratedFile,
+            description: "Team unlock".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lead_time_reminders_fire_once_each() {
+        let calendar = TokenCalendar::new(CalendarConfig::default(), None);
+        let start = Utc::now();
+        let event_time = start + Duration::hours(48);
+
+        calendar.set_holdings(42, &["MINT1".to_string()]).await;
+        calendar.upsert_event(unlock_event("MINT1", event_time)).await;
+
+        // Two days out: nothing due yet
+        assert!(calendar.due_reminders(start).await.is_empty());
+
+        // Inside the 24h window: one reminder
+        let at_24h = event_time - Duration::hours(23);
+        let reminders = calendar.due_reminders(at_24h).await;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(
+            reminders[0].notification_type,
+            CalendarNotificationType::Reminder { event_id: "unlock-1".to_string(), lead_time: Duration::hours(24) }
+        );
+        assert!(calendar.due_reminders(at_24h + Duration::minutes(5)).await.is_empty());
+
+        // Inside the 1h window: the second reminder
+        let at_1h = event_time - Duration::minutes(30);
+        let reminders = calendar.due_reminders(at_1h).await;
+        assert_eq!(reminders.len(), 1);
+        assert!(reminders[0].message.contains("12% supply unlock"));
+
+        // After the event: nothing more
+        assert!(calendar.due_reminders(event_time + Duration::minutes(1)).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_late_event_skips_wider_lead_time() {
+        let calendar = TokenCalendar::new(CalendarConfig::default(), None);
+        let now = Utc::now();
+
+        calendar.watch(7, "MINT2").await;
+        calendar.upsert_event(unlock_event("MINT2", now + Duration::minutes(40))).await;
+
+        let reminders = calendar.due_reminders(now).await;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(
+            reminders[0].notification_type,
+            CalendarNotificationType::Reminder { event_id: "unlock-1".to_string(), lead_time: Duration::hours(1) }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migration_event_from_bonding_progress() {
+        let calendar = TokenCalendar::new(CalendarConfig::default(), None);
+        let start = Utc::now();
+
+        calendar.record_bonding_progress("PUMP1", "PUMP", 60.0, start).await;
+        calendar.record_bonding_progress("PUMP1", "PUMP", 70.0, start + Duration::hours(1)).await;
+
+        let upcoming = calendar.upcoming_for_mints(&["PUMP1".to_string()], start + Duration::hours(1)).await;
+        assert_eq!(upcoming.len(), 1);
+
+        let event = &upcoming[0];
+        assert_eq!(event.source, CalendarSourceKind::PumpFunMigration);
+        assert_eq!(event.kind, CalendarEventKind::Migration { bonding_curve_progress: 70.0 });
+        // 10% per hour with 30% remaining: ~3 hours after the last sample
+        let expected = start + Duration::hours(4);
+        assert!((event.scheduled_at - expected).num_seconds().abs() < 5);
+
+        // A newer sample replaces rather than duplicates the migration event
+        calendar.record_bonding_progress("PUMP1", "PUMP", 90.0, start + Duration::hours(2)).await;
+        let upcoming = calendar.upcoming_for_mints(&["PUMP1".to_string()], start + Duration::hours(2)).await;
+        assert_eq!(upcoming.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_digest_includes_held_token_events() {
+        let calendar = TokenCalendar::new(CalendarConfig::default(), None);
+        let now = Utc::now();
+
+        calendar.set_holdings(1, &["HELD".to_string()]).await;
+        calendar.upsert_event(unlock_event("HELD", now + Duration::days(3))).await;
+        calendar.upsert_event(TokenCalendarEvent {
+            event_id: "other".to_string(),
+            ..unlock_event("NOT_HELD", now + Duration::days(2))
+        }).await;
+
+        let section = calendar.digest_section(1, now).await.expect("digest section");
+        assert!(section.contains("12% supply unlock in 3 days"));
+        assert_eq!(section.matches('•').count(), 1);
+
+        let digests = calendar.due_digests(now).await;
+        assert_eq!(digests.len(), 1);
+        assert_eq!(digests[0].notification_type, CalendarNotificationType::Digest);
+
+        // Only one digest per interval
+        assert!(calendar.due_digests(now + Duration::hours(1)).await.is_empty());
+    }
+}
@@ -0,0 +1,528 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
+
+use crate::api::JupiterPriceV3Client;
+use crate::errors::{BotError, Result};
+use crate::trading::{TokenResolver, USDC_MINT};
+use crate::wallet::{SignatureWatcher, WalletTransaction};
+use super::market_events::{
+    EventDetails, EventFilter, EventSeverity, EventSource, EventSubscription, EventType, MarketEvent,
+    MarketEventMonitor, NotificationSettings, RiskLevel, WhaleAction, WhaleEvent,
+};
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+
+/// What a whale pays with or is paid in; everything else is the traded token
+const QUOTE_MINTS: [&str; 3] = [WSOL_MINT, USDC_MINT, USDT_MINT];
+
+/// Byte range of the owner in an SPL token account
+const TOKEN_ACCOUNT_OWNER: std::ops::Range<usize> = 32..64;
+
+/// Settings for the whale watcher
+#[derive(Debug, Clone)]
+pub struct WhaleWatchConfig {
+    /// Wallets watched for everyone, reported to subscribers of the traded token
+    pub addresses: Vec<String>,
+    /// Websocket endpoint for `logsSubscribe`; polling only when unset
+    pub ws_url: Option<String>,
+    pub poll_interval: Duration,
+    pub signatures_per_poll: usize,
+    /// Threshold for users who don't set one, and for the configured wallets
+    pub default_min_value_usd: Decimal,
+    /// Holders watched by `/whales top`
+    pub top_holders: usize,
+}
+
+impl Default for WhaleWatchConfig {
+    fn default() -> Self {
+        Self {
+            addresses: Vec::new(),
+            ws_url: None,
+            poll_interval: Duration::seconds(30),
+            signatures_per_poll: 20,
+            default_min_value_usd: Decimal::from(50_000),
+            top_holders: 10,
+        }
+    }
+}
+
+/// A whale transaction reduced to one token movement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhaleActivity {
+    pub signature: String,
+    pub wallet: String,
+    pub action: WhaleAction,
+    /// Token bought, sold or moved; SOL or a stablecoin when nothing else moved
+    pub mint: String,
+    pub amount: f64,
+    /// Recipient of a transfer out, sender of a transfer in
+    pub counterparty: Option<String>,
+    pub slot: u64,
+    pub block_time: Option<DateTime<Utc>>,
+}
+
+impl WhaleActivity {
+    /// Read a watched wallet's activity out of a `jsonParsed` transaction
+    pub fn from_transaction(
+        signature: &str,
+        wallet: &str,
+        encoded: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Option<Self> {
+        Self::classify(&WalletTransaction::from_encoded(signature, wallet, encoded)?)
+    }
+
+    /// Buy when a signed transaction swaps SOL or stablecoins into a token, Sell
+    /// the other way round, Transfer when the token moved without payment
+    pub fn classify(transaction: &WalletTransaction) -> Option<Self> {
+        if transaction.failed {
+            return None;
+        }
+
+        let traded = transaction.token_changes.iter()
+            .filter(|c| !QUOTE_MINTS.contains(&c.mint.as_str()))
+            .max_by(|a, b| a.change.abs().total_cmp(&b.change.abs()));
+        let wsol_change: f64 = transaction.token_changes.iter()
+            .filter(|c| c.mint == WSOL_MINT)
+            .map(|c| c.change)
+            .sum();
+        let stable_change: f64 = transaction.token_changes.iter()
+            .filter(|c| c.mint == USDC_MINT || c.mint == USDT_MINT)
+            .map(|c| c.change)
+            .sum();
+        let paid = transaction.sol_change + wsol_change < 0.0 || stable_change < 0.0;
+        let received = transaction.sol_change + wsol_change > 0.0 || stable_change > 0.0;
+
+        let (action, mint, change) = match traded {
+            Some(token) if transaction.wallet_signed && token.change > 0.0 && paid => {
+                (WhaleAction::Buy, token.mint.clone(), token.change)
+            }
+            Some(token) if transaction.wallet_signed && token.change < 0.0 && received => {
+                (WhaleAction::Sell, token.mint.clone(), token.change)
+            }
+            Some(token) => (WhaleAction::Transfer, token.mint.clone(), token.change),
+            // Only SOL or stablecoins moved
+            None if stable_change.abs() > 0.0 => {
+                let stable = transaction.token_changes.iter()
+                    .filter(|c| c.mint == USDC_MINT || c.mint == USDT_MINT)
+                    .max_by(|a, b| a.change.abs().total_cmp(&b.change.abs()))?;
+                (WhaleAction::Transfer, stable.mint.clone(), stable.change)
+            }
+            None => (WhaleAction::Transfer, WSOL_MINT.to_string(), transaction.sol_change + wsol_change),
+        };
+        if change == 0.0 {
+            return None;
+        }
+
+        let counterparty = matches!(action, WhaleAction::Transfer)
+            .then(|| transaction.counterparties.first().cloned())
+            .flatten();
+
+        Some(Self {
+            signature: transaction.signature.clone(),
+            wallet: transaction.wallet.clone(),
+            action,
+            mint,
+            amount: change.abs(),
+            counterparty,
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct WatchedWhale {
+    last_signature: Option<String>,
+    /// Users who added the wallet themselves
+    users: HashSet<i64>,
+    /// Watched for everyone through `WhaleWatchConfig::addresses`
+    configured: bool,
+}
+
+/// Watches whale wallets and reports large moves as market events
+///
+/// Wallets are polled with `getSignaturesForAddress`, plus a `logsSubscribe`
+/// stream when a websocket endpoint is configured. A move is published when
+/// its USD value reaches the lowest `EventFilter::MinVolume` of the users
+/// watching the wallet; the monitor then applies each user's own threshold.
+#[derive(Clone)]
+pub struct WhaleWatcher {
+    config: WhaleWatchConfig,
+    rpc_client: Arc<RpcClient>,
+    signatures: SignatureWatcher,
+    price_client: Arc<JupiterPriceV3Client>,
+    market_events: Arc<MarketEventMonitor>,
+    watched: Arc<RwLock<HashMap<String, WatchedWhale>>>,
+    processed: Arc<RwLock<HashSet<String>>>,
+}
+
+impl WhaleWatcher {
+    pub fn new(
+        rpc_client: Arc<RpcClient>,
+        price_client: Arc<JupiterPriceV3Client>,
+        market_events: Arc<MarketEventMonitor>,
+        config: WhaleWatchConfig,
+    ) -> Self {
+        info!("🐋 Initializing whale watcher");
+
+        Self {
+            signatures: SignatureWatcher::new(rpc_client.clone(), config.ws_url.clone(), config.signatures_per_poll),
+            config,
+            rpc_client,
+            price_client,
+            market_events,
+            watched: Arc::new(RwLock::new(HashMap::new())),
+            processed: Arc::new(RwLock::new(HashSet::new())),
+        }
+    }
+
+    /// Watch `address` for the user, reporting moves worth at least `min_value_usd`
+    ///
+    /// Returns the threshold in effect.
+    pub async fn add(&self, user_id: i64, address: &str, min_value_usd: Option<Decimal>) -> Result<Decimal> {
+        Pubkey::from_str(address)
+            .map_err(|_| BotError::validation(format!("Invalid wallet address: {}", address)))?;
+        let min_value = min_value_usd.unwrap_or(self.config.default_min_value_usd);
+
+        self.market_events.subscribe(user_id, EventSubscription {
+            user_id,
+            event_types: vec![],
+            symbols: vec![address.to_string()],
+            min_severity: EventSeverity::Info,
+            filters: vec![EventFilter::MinVolume(min_value)],
+            notification_settings: NotificationSettings::default(),
+        }).await?;
+
+        let newly_added = {
+            let mut watched = self.watched.write().await;
+            let added = !watched.contains_key(address);
+            watched.entry(address.to_string()).or_default().users.insert(user_id);
+            added
+        };
+        if newly_added {
+            self.start_watching(address).await;
+        }
+
+        info!("🐋 User {} watching {} from ${}", user_id, address, min_value);
+        Ok(min_value)
+    }
+
+    /// Stop watching `address` for the user; false when they weren't
+    pub async fn remove(&self, user_id: i64, address: &str) -> bool {
+        let removed = {
+            let mut watched = self.watched.write().await;
+            let Some(whale) = watched.get_mut(address) else { return false };
+            let removed = whale.users.remove(&user_id);
+            if whale.users.is_empty() && !whale.configured {
+                watched.remove(address);
+            }
+            removed
+        };
+        self.market_events.unsubscribe(user_id, address).await;
+        removed
+    }
+
+    /// The user's wallets with their thresholds, in address order
+    pub async fn list(&self, user_id: i64) -> Vec<(String, Decimal)> {
+        let addresses: Vec<String> = self.watched.read().await.iter()
+            .filter(|(_, whale)| whale.users.contains(&user_id))
+            .map(|(address, _)| address.clone())
+            .collect();
+
+        let mut listed = Vec::new();
+        for address in addresses {
+            let min_value = self.user_threshold(user_id, &address).await
+                .unwrap_or(self.config.default_min_value_usd);
+            listed.push((address, min_value));
+        }
+        listed.sort();
+        listed
+    }
+
+    /// Drop every wallet the user added; returns how many
+    pub async fn forget_user(&self, user_id: i64) -> usize {
+        let addresses: Vec<String> = self.list(user_id).await.into_iter().map(|(address, _)| address).collect();
+        for address in &addresses {
+            self.remove(user_id, address).await;
+        }
+        addresses.len()
+    }
+
+    /// Owners of the largest token accounts of `mint`, largest first
+    pub async fn discover_top_holders(&self, mint: &str, limit: usize) -> Result<Vec<String>> {
+        let mint = Pubkey::from_str(mint)
+            .map_err(|_| BotError::validation(format!("Invalid token mint: {}", mint)))?;
+        let largest = self.rpc_client.get_token_largest_accounts(&mint).await?;
+
+        let accounts: Vec<Pubkey> = largest.iter()
+            .filter_map(|balance| Pubkey::from_str(&balance.address).ok())
+            .collect();
+        let mut owners = Vec::new();
+        for account in self.rpc_client.get_multiple_accounts(&accounts).await?.into_iter().flatten() {
+            let Some(owner) = account.data.get(TOKEN_ACCOUNT_OWNER).and_then(|bytes| Pubkey::try_from(bytes).ok()) else {
+                continue;
+            };
+            let owner = owner.to_string();
+            if !owners.contains(&owner) {
+                owners.push(owner);
+            }
+            if owners.len() >= limit {
+                break;
+            }
+        }
+        Ok(owners)
+    }
+
+    /// Watch the top holders of a token for the user; returns the wallets added
+    pub async fn add_top_holders(&self, user_id: i64, mint: &str, min_value_usd: Option<Decimal>) -> Result<Vec<String>> {
+        let holders = self.discover_top_holders(mint, self.config.top_holders).await?;
+        for holder in &holders {
+            self.add(user_id, holder, min_value_usd).await?;
+        }
+        Ok(holders)
+    }
+
+    /// Start the configured wallets and the polling loop
+    pub async fn start(&self) {
+        for address in self.config.addresses.clone() {
+            if Pubkey::from_str(&address).is_err() {
+                warn!("🐋 Skipping invalid whale address {}", address);
+                continue;
+            }
+            self.watched.write().await.entry(address.clone()).or_default().configured = true;
+            self.start_watching(&address).await;
+        }
+
+        let watcher = self.clone();
+        let interval_secs = self.config.poll_interval.num_seconds().max(5) as u64;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+
+                let wallets: Vec<String> = watcher.watched.read().await.keys().cloned().collect();
+                for wallet in wallets {
+                    if let Err(e) = watcher.poll_wallet(&wallet, false).await {
+                        error!("🐋 Polling {} failed: {}", wallet, e);
+                    }
+                }
+
+                let mut processed = watcher.processed.write().await;
+                if processed.len() > 10_000 {
+                    processed.clear();
+                }
+            }
+        });
+    }
+
+    /// Value the activity and publish it when someone watching the wallet wants it
+    pub async fn ingest(&self, activity: WhaleActivity) -> Result<Option<MarketEvent>> {
+        let Some(threshold) = self.publish_threshold(&activity.wallet).await else {
+            return Ok(None);
+        };
+        let value_usd = self.value_usd(&activity).await?;
+        if value_usd < threshold {
+            debug!("🐋 {} moved ${} in {}, under ${}", activity.wallet, value_usd, activity.signature, threshold);
+            return Ok(None);
+        }
+
+        let event = Self::event(&activity, value_usd);
+        self.market_events.publish_event(event.clone()).await?;
+        Ok(Some(event))
+    }
+
+    /// USD value of the moved amount at the current Jupiter price
+    pub async fn value_usd(&self, activity: &WhaleActivity) -> Result<Decimal> {
+        let response = self.price_client.get_prices(vec![activity.mint.clone()]).await?;
+        let price = response.prices.get(&activity.mint)
+            .map(|data| data.usd_price)
+            .ok_or_else(|| BotError::external_api(format!("No price for {}", activity.mint)))?;
+        Ok(Decimal::from_f64_retain(price * activity.amount).unwrap_or_default().round_dp(2))
+    }
+
+    /// Market event for a whale move worth `value_usd`
+    pub fn event(activity: &WhaleActivity, value_usd: Decimal) -> MarketEvent {
+        let severity = if value_usd >= Decimal::from(5_000_000) {
+            EventSeverity::Critical
+        } else if value_usd >= Decimal::from(1_000_000) {
+            EventSeverity::High
+        } else if value_usd >= Decimal::from(250_000) {
+            EventSeverity::Medium
+        } else {
+            EventSeverity::Low
+        };
+        let token = TokenResolver::get_symbol(&activity.mint);
+        let verb = match (&activity.action, activity.counterparty.as_deref()) {
+            (WhaleAction::Buy, _) => "bought".to_string(),
+            (WhaleAction::Sell, _) => "sold".to_string(),
+            (_, Some(counterparty)) => format!("moved ({} ↔ {})", short_address(&activity.wallet), short_address(counterparty)),
+            _ => "moved".to_string(),
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("signature".to_string(), serde_json::Value::String(activity.signature.clone()));
+
+        MarketEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            event_type: EventType::Whale(WhaleEvent {
+                whale_address: activity.wallet.clone(),
+                action: activity.action.clone(),
+                amount: Decimal::from_f64_retain(activity.amount).unwrap_or_default(),
+                value_usd,
+                impact_estimate: 0.0,
+                historical_accuracy: 0.0,
+            }),
+            symbol: activity.mint.clone(),
+            timestamp: activity.block_time.unwrap_or_else(Utc::now),
+            severity,
+            source: EventSource::OnChain,
+            details: EventDetails {
+                description: format!(
+                    "🐋 {} {} {} {} (~${})",
+                    short_address(&activity.wallet),
+                    verb,
+                    format_amount(activity.amount),
+                    token,
+                    value_usd
+                ),
+                impact_assessment: match activity.action {
+                    WhaleAction::Sell => "Large sells can push the price down".to_string(),
+                    WhaleAction::Buy => "Large buys can push the price up".to_string(),
+                    _ => "Transfers often come before an exchange deposit or a sale".to_string(),
+                },
+                recommended_actions: vec![],
+                risk_level: RiskLevel::Moderate,
+                confidence: 0.8,
+            },
+            metadata,
+        }
+    }
+
+    /// Lowest threshold among the users and configuration watching `wallet`
+    async fn publish_threshold(&self, wallet: &str) -> Option<Decimal> {
+        let whale = self.watched.read().await.get(wallet).cloned()?;
+        let mut thresholds = Vec::new();
+        if whale.configured {
+            thresholds.push(self.config.default_min_value_usd);
+        }
+        for user_id in &whale.users {
+            thresholds.push(self.user_threshold(*user_id, wallet).await.unwrap_or(self.config.default_min_value_usd));
+        }
+        thresholds.into_iter().min()
+    }
+
+    async fn user_threshold(&self, user_id: i64, wallet: &str) -> Option<Decimal> {
+        self.market_events.subscriptions_for(wallet).await.into_iter()
+            .filter(|subscription| subscription.user_id == user_id)
+            .flat_map(|subscription| subscription.filters)
+            .find_map(|filter| match filter {
+                EventFilter::MinVolume(min) => Some(min),
+                _ => None,
+            })
+    }
+
+    async fn start_watching(&self, wallet: &str) {
+        info!("🐋 Watching whale {}", wallet);
+        // Only moves from now on matter; skip the existing history
+        if let Err(e) = self.poll_wallet(wallet, true).await {
+            warn!("🐋 Initial history fetch for {} failed: {}", wallet, e);
+        }
+        if self.config.ws_url.is_some() {
+            self.spawn_log_subscription(wallet.to_string());
+        }
+    }
+
+    /// Fetch signatures newer than the last one seen and ingest their transactions
+    async fn poll_wallet(&self, wallet: &str, baseline_only: bool) -> Result<()> {
+        let Some(watched) = self.watched.read().await.get(wallet).cloned() else { return Ok(()) };
+        let signatures = self.signatures.signatures_since(wallet, watched.last_signature.as_deref()).await?;
+
+        if let Some(newest) = signatures.first() {
+            if let Some(entry) = self.watched.write().await.get_mut(wallet) {
+                entry.last_signature = Some(newest.signature.clone());
+            }
+        }
+        if baseline_only {
+            return Ok(());
+        }
+
+        // Oldest first so events read in order
+        for status in signatures.iter().rev().filter(|s| s.err.is_none()) {
+            self.fetch_and_ingest(wallet, &status.signature).await;
+        }
+        Ok(())
+    }
+
+    async fn fetch_and_ingest(&self, wallet: &str, signature: &str) {
+        if !self.processed.write().await.insert(format!("{}:{}", wallet, signature)) {
+            return;
+        }
+        match self.signatures.fetch_transaction(signature).await {
+            Ok(encoded) => match WhaleActivity::from_transaction(signature, wallet, &encoded) {
+                Some(activity) => {
+                    if let Err(e) = self.ingest(activity).await {
+                        warn!("🐋 Could not report {}: {}", signature, e);
+                    }
+                }
+                None => debug!("🐋 {} moved nothing for {}", signature, wallet),
+            },
+            Err(e) => warn!("🐋 Could not fetch {}: {}", signature, e),
+        }
+    }
+
+    /// Push notifications for a wallet; ends quietly and leaves polling in charge on failure
+    fn spawn_log_subscription(&self, wallet: String) {
+        if self.config.ws_url.is_none() {
+            return;
+        }
+        let watcher = self.clone();
+
+        tokio::spawn(async move {
+            let mut notifications = match watcher.signatures.subscribe_logs(&wallet).await {
+                Ok(notifications) => notifications,
+                Err(e) => {
+                    warn!("🐋 Log subscription unavailable for {}, polling only: {}", wallet, e);
+                    return;
+                }
+            };
+
+            while let Some(logs) = notifications.recv().await {
+                if !watcher.watched.read().await.contains_key(&wallet) {
+                    break;
+                }
+                if logs.err.is_none() {
+                    watcher.fetch_and_ingest(&wallet, &logs.signature).await;
+                }
+            }
+
+            debug!("🐋 Log subscription for {} ended", wallet);
+        });
+    }
+}
+
+fn short_address(address: &str) -> String {
+    if address.len() > 12 {
+        format!("{}…{}", &address[..4], &address[address.len() - 4..])
+    } else {
+        address.to_string()
+    }
+}
+
+fn format_amount(amount: f64) -> String {
+    match amount {
+        a if a >= 1_000_000_000.0 => format!("{:.2}B", a / 1_000_000_000.0),
+        a if a >= 1_000_000.0 => format!("{:.2}M", a / 1_000_000.0),
+        a if a >= 1_000.0 => format!("{:.2}K", a / 1_000.0),
+        a => format!("{:.4}", a),
+    }
+}
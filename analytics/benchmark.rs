@@ -0,0 +1,216 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::errors::{BotError, Result};
+use crate::trading::TokenResolver;
+
+use super::performance_tracker::MetricsCalculator;
+
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Sparklines longer than this are sampled down to fit a Telegram line
+const SPARK_WIDTH: usize = 20;
+
+/// Tokens held in fixed proportions from the start of the window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkBasket {
+    pub label: String,
+    /// Mint and weight; weights sum to 1
+    pub weights: Vec<(String, f64)>,
+}
+
+impl BenchmarkBasket {
+    /// Just holding SOL
+    pub fn sol() -> Self {
+        Self {
+            label: "SOL".to_string(),
+            weights: vec![(WSOL_MINT.to_string(), 1.0)],
+        }
+    }
+
+    /// `SOL:50,JUP:50`, or `SOL JUP` for equal weights; symbols or mints
+    pub fn parse(spec: &str) -> Result<Self> {
+        let parts: Vec<&str> = spec.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .collect();
+        if parts.is_empty() {
+            return Ok(Self::sol());
+        }
+
+        let mut labels = Vec::new();
+        let mut weights = Vec::new();
+        for part in &parts {
+            let (token, weight) = match part.split_once(':') {
+                Some((token, weight)) => {
+                    let weight = weight.trim_end_matches('%').parse::<f64>()
+                        .ok()
+                        .filter(|w| w.is_finite() && *w > 0.0)
+                        .ok_or_else(|| BotError::validation(format!("Invalid weight in {}", part)))?;
+                    (token, weight)
+                }
+                None => (*part, 1.0),
+            };
+            let (mint, symbol) = if token.eq_ignore_ascii_case("SOL") {
+                (WSOL_MINT.to_string(), "SOL".to_string())
+            } else {
+                let mint = TokenResolver::resolve(token)
+                    .map_err(|_| BotError::validation(format!("Unknown token {}", token)))?;
+                let symbol = TokenResolver::get_symbol(&mint);
+                (mint, symbol)
+            };
+            labels.push((symbol, weight));
+            weights.push((mint, weight));
+        }
+
+        let total: f64 = weights.iter().map(|(_, w)| w).sum();
+        let label = labels.iter()
+            .map(|(token, weight)| format!("{}% {}", (weight / total * 100.0).round(), token))
+            .collect::<Vec<_>>()
+            .join(" / ");
+        Ok(Self {
+            label: if parts.len() == 1 { labels[0].0.clone() } else { label },
+            weights: weights.into_iter().map(|(mint, w)| (mint, w / total)).collect(),
+        })
+    }
+
+    /// Value on each date of `start_value` split across the basket on the first date
+    ///
+    /// Prices are daily closes per mint; a date without one uses the latest before it.
+    pub fn buy_and_hold(
+        &self,
+        dates: &[NaiveDate],
+        prices: &HashMap<String, BTreeMap<NaiveDate, f64>>,
+        start_value: f64,
+    ) -> Result<Vec<f64>> {
+        let price_on = |mint: &str, date: NaiveDate| -> Option<f64> {
+            prices.get(mint)?.range(..=date).next_back().map(|(_, price)| *price).filter(|p| *p > 0.0)
+        };
+        let Some(&start) = dates.first() else { return Ok(Vec::new()) };
+
+        // Units of each token bought on the first date
+        let mut holdings = Vec::with_capacity(self.weights.len());
+        for (mint, weight) in &self.weights {
+            let price = price_on(mint, start).ok_or_else(|| BotError::not_found(format!(
+                "No price history for {} on {}", TokenResolver::get_symbol(mint), start
+            )))?;
+            holdings.push((mint.as_str(), start_value * weight / price));
+        }
+
+        Ok(dates.iter()
+            .map(|date| holdings.iter()
+                .map(|(mint, units)| units * price_on(mint, *date).unwrap_or(0.0))
+                .sum())
+            .collect())
+    }
+}
+
+/// The portfolio against a buy-and-hold benchmark over the same days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub benchmark: String,
+    pub days: usize,
+    /// Over the window, in percent
+    pub portfolio_return: f64,
+    pub benchmark_return: f64,
+    /// Return beyond what beta to the benchmark explains, in percentage points
+    pub alpha: f64,
+    /// Sensitivity of daily returns to the benchmark's
+    pub beta: f64,
+    pub portfolio_max_drawdown: f64,
+    pub benchmark_max_drawdown: f64,
+    /// Negative when the portfolio drew down less than the benchmark
+    pub max_drawdown_delta: f64,
+    pub portfolio_sparkline: String,
+    pub benchmark_sparkline: String,
+}
+
+impl BenchmarkReport {
+    /// Compare two value series sampled on the same days
+    pub fn compute(label: &str, portfolio: &[f64], benchmark: &[f64], calculator: &MetricsCalculator) -> Result<Self> {
+        if portfolio.len() < 2 || portfolio.len() != benchmark.len() {
+            return Err(BotError::validation("Need at least two days of matching values to compare".to_string()).into());
+        }
+
+        let portfolio_return = total_return(portfolio);
+        let benchmark_return = total_return(benchmark);
+        let beta = calculator.calculate_beta(&daily_returns(portfolio), &daily_returns(benchmark));
+        let portfolio_max_drawdown = max_drawdown(portfolio);
+        let benchmark_max_drawdown = max_drawdown(benchmark);
+
+        Ok(Self {
+            benchmark: label.to_string(),
+            days: portfolio.len() - 1,
+            portfolio_return,
+            benchmark_return,
+            alpha: portfolio_return - beta * benchmark_return,
+            beta,
+            portfolio_max_drawdown,
+            benchmark_max_drawdown,
+            max_drawdown_delta: portfolio_max_drawdown - benchmark_max_drawdown,
+            portfolio_sparkline: sparkline(portfolio),
+            benchmark_sparkline: sparkline(benchmark),
+        })
+    }
+
+    pub fn render(&self) -> String {
+        format!(
+            "📊 You vs {} ({} days)\n\n\
+            You: {:+.1}%  {}\n\
+            {}: {:+.1}%  {}\n\n\
+            Alpha: {:+.1} pts · Beta: {:.2}\n\
+            Max drawdown: {:.1}% vs {:.1}% ({:+.1} pts)",
+            self.benchmark, self.days,
+            self.portfolio_return, self.portfolio_sparkline,
+            self.benchmark, self.benchmark_return, self.benchmark_sparkline,
+            self.alpha, self.beta,
+            self.portfolio_max_drawdown, self.benchmark_max_drawdown, self.max_drawdown_delta,
+        )
+    }
+}
+
+pub fn daily_returns(values: &[f64]) -> Vec<f64> {
+    values.windows(2)
+        .map(|pair| if pair[0] > 0.0 { pair[1] / pair[0] - 1.0 } else { 0.0 })
+        .collect()
+}
+
+fn total_return(values: &[f64]) -> f64 {
+    match (values.first(), values.last()) {
+        (Some(first), Some(last)) if *first > 0.0 => (last / first - 1.0) * 100.0,
+        _ => 0.0,
+    }
+}
+
+/// Largest peak-to-trough fall, in percent
+fn max_drawdown(values: &[f64]) -> f64 {
+    let mut peak = f64::MIN;
+    values.iter().fold(0.0, |worst: f64, value| {
+        peak = peak.max(*value);
+        if peak > 0.0 { worst.max((peak - value) / peak * 100.0) } else { worst }
+    })
+}
+
+/// Block characters scaled between the series' low and high
+pub fn sparkline(values: &[f64]) -> String {
+    if values.is_empty() {
+        return String::new();
+    }
+    let step = values.len().div_ceil(SPARK_WIDTH);
+    let mut sampled: Vec<f64> = values.iter().step_by(step).copied().collect();
+    if (values.len() - 1) % step != 0 {
+        sampled.push(values[values.len() - 1]);
+    }
+
+    let low = sampled.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = sampled.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    sampled.iter()
+        .map(|value| {
+            if high - low <= f64::EPSILON {
+                return SPARK_BARS[3];
+            }
+            let level = ((value - low) / (high - low) * (SPARK_BARS.len() - 1) as f64).round() as usize;
+            SPARK_BARS[level.min(SPARK_BARS.len() - 1)]
+        })
+        .collect()
+}
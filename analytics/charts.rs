@@ -0,0 +1,75 @@
+use image::{DynamicImage, ImageOutputFormat, Rgb, RgbImage};
+use std::io::Cursor;
+
+use crate::errors::{BotError, Result};
+use super::trading_hours::TradingHoursReport;
+
+const CELL: u32 = 24;
+const GAP: u32 = 2;
+const MARGIN: u32 = 12;
+/// Average return that gets the full colour
+const SATURATION_PCT: f64 = 25.0;
+
+const BACKGROUND: Rgb<u8> = Rgb([24, 26, 33]);
+const EMPTY: Rgb<u8> = Rgb([40, 43, 52]);
+const INSUFFICIENT: Rgb<u8> = Rgb([78, 82, 94]);
+const TICK: Rgb<u8> = Rgb([150, 155, 168]);
+
+/// Hour × weekday heatmap as a PNG: rows Monday to Sunday, columns 00 to 23
+///
+/// Green cells made money on average and red ones lost it. Grey cells have
+/// trades but fewer than the report's minimum sample.
+pub fn render_heatmap(report: &TradingHoursReport) -> Result<Vec<u8>> {
+    let width = MARGIN * 2 + 24 * (CELL + GAP) - GAP;
+    let height = MARGIN * 2 + 7 * (CELL + GAP) - GAP;
+    let mut image = RgbImage::from_pixel(width, height, BACKGROUND);
+
+    for (day, hours) in report.grid.iter().enumerate() {
+        for (hour, stats) in hours.iter().enumerate() {
+            let color = if stats.trades == 0 {
+                EMPTY
+            } else if !report.has_enough(stats) {
+                INSUFFICIENT
+            } else {
+                return_color(stats.average_return_pct())
+            };
+            let x = MARGIN + hour as u32 * (CELL + GAP);
+            let y = MARGIN + day as u32 * (CELL + GAP);
+            fill(&mut image, x, y, CELL, CELL, color);
+        }
+    }
+
+    // Ticks every six hours above and below the grid
+    for hour in (0..24).step_by(6) {
+        let x = MARGIN + hour * (CELL + GAP);
+        fill(&mut image, x, MARGIN - 6, 2, 4, TICK);
+        fill(&mut image, x, height - MARGIN + 2, 2, 4, TICK);
+    }
+
+    let mut png = Vec::new();
+    DynamicImage::ImageRgb8(image)
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .map_err(|e| BotError::internal(format!("Failed to encode heatmap: {}", e)))?;
+    Ok(png)
+}
+
+/// Red through amber to green, clamped at ±`SATURATION_PCT`
+fn return_color(average_return_pct: f64) -> Rgb<u8> {
+    let strength = (average_return_pct / SATURATION_PCT).clamp(-1.0, 1.0);
+    let blend = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * strength.abs()).round() as u8;
+    let neutral = [160u8, 150, 90];
+    let target = if strength >= 0.0 { [46u8, 204, 113] } else { [231u8, 76, 60] };
+    Rgb([
+        blend(neutral[0], target[0]),
+        blend(neutral[1], target[1]),
+        blend(neutral[2], target[2]),
+    ])
+}
+
+fn fill(image: &mut RgbImage, x: u32, y: u32, width: u32, height: u32, color: Rgb<u8>) {
+    for px in x..(x + width).min(image.width()) {
+        for py in y..(y + height).min(image.height()) {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use crate::trading::{TradeProvenance, TradeResult, TradeType};
+
+/// Quantities below this are rounding dust, not an open lot
+const DUST: f64 = 1e-9;
+
+/// One buy still (partly) held
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lot {
+    pub quantity: f64,
+    /// SOL paid per token, fees included
+    pub unit_cost_sol: f64,
+    pub acquired_at: DateTime<Utc>,
+    pub provenance: TradeProvenance,
+}
+
+/// How a sell picks the cost it realizes against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostBasisMethod {
+    /// Oldest lots first
+    #[default]
+    Fifo,
+    /// Every open lot at the position's average cost
+    Average,
+}
+
+impl CostBasisMethod {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "fifo" => Some(Self::Fifo),
+            "average" | "avg" | "average-cost" => Some(Self::Average),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Fifo => "FIFO",
+            Self::Average => "average cost",
+        }
+    }
+}
+
+/// Where the book reads each user's accounting method
+#[async_trait::async_trait]
+pub trait CostBasisPreferences: Send + Sync {
+    async fn cost_basis_method(&self, user_id: i64) -> CostBasisMethod;
+}
+
+/// A buy or sell applied to the lot book
+#[derive(Debug, Clone)]
+pub struct LotTrade {
+    pub mint: String,
+    pub is_buy: bool,
+    pub quantity: f64,
+    /// SOL paid for a buy, received for a sell
+    pub sol_amount: f64,
+    pub fee_sol: f64,
+    pub at: DateTime<Utc>,
+    pub provenance: TradeProvenance,
+}
+
+impl LotTrade {
+    /// The fill an engine trade made; `None` for results that moved no tokens
+    pub fn from_trade_result(result: &TradeResult, mint: &str) -> Option<Self> {
+        let (is_buy, quantity, sol_amount) = match result.trade_type {
+            TradeType::Buy => (true, result.tokens_received, result.amount_sol),
+            TradeType::Sell => (false, result.tokens_sold, result.sol_received),
+            TradeType::Swap if result.tokens_sold > 0.0 => (false, result.tokens_sold, result.sol_received),
+            TradeType::Swap => (true, result.tokens_received, result.amount_sol),
+        };
+        if quantity <= DUST {
+            return None;
+        }
+        let fee_sol = result.execution.fees.as_ref()
+            .map(|f| f.network_fee_sol + f.platform_fee_sol + f.priority_fee_lamports as f64 / 1e9)
+            .unwrap_or(0.0);
+
+        Some(Self {
+            mint: mint.to_string(),
+            is_buy,
+            quantity,
+            sol_amount,
+            fee_sol,
+            at: result.execution.executed_at.unwrap_or(result.timestamp),
+            provenance: TradeProvenance::Bot,
+        })
+    }
+}
+
+/// What a sell realized against the lots it closed
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealizedPnl {
+    pub user_id: i64,
+    pub mint: String,
+    /// Tokens matched against known lots
+    pub quantity: f64,
+    /// Net SOL the matched tokens sold for
+    pub proceeds_sol: f64,
+    /// What the matched tokens cost under `method`
+    pub cost_sol: f64,
+    pub pnl_sol: f64,
+    pub method: CostBasisMethod,
+    pub at: DateTime<Utc>,
+}
+
+impl RealizedPnl {
+    pub fn pnl_percentage(&self) -> f64 {
+        if self.cost_sol > 0.0 { self.pnl_sol / self.cost_sol * 100.0 } else { 0.0 }
+    }
+}
+
+/// Open quantity and what it cost
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostBasis {
+    pub quantity: f64,
+    pub cost_sol: f64,
+}
+
+impl CostBasis {
+    pub fn average_cost(&self) -> f64 {
+        if self.quantity > 0.0 { self.cost_sol / self.quantity } else { 0.0 }
+    }
+
+    /// Gain on the open quantity if it sold at `price_sol` per token
+    pub fn unrealized_sol(&self, price_sol: f64) -> f64 {
+        self.quantity * price_sol - self.cost_sol
+    }
+}
+
+/// Lots per user and mint, realized FIFO or at average cost per the user's setting
+#[derive(Default)]
+pub struct CostBasisBook {
+    lots: RwLock<HashMap<(i64, String), VecDeque<Lot>>>,
+    realized_sol: RwLock<HashMap<i64, f64>>,
+    preferences: Option<Arc<dyn CostBasisPreferences>>,
+}
+
+impl CostBasisBook {
+    /// Read each user's method from their settings; FIFO otherwise
+    pub fn with_preferences(mut self, preferences: Arc<dyn CostBasisPreferences>) -> Self {
+        self.preferences = Some(preferences);
+        self
+    }
+
+    pub async fn method(&self, user_id: i64) -> CostBasisMethod {
+        match &self.preferences {
+            Some(preferences) => preferences.cost_basis_method(user_id).await,
+            None => CostBasisMethod::default(),
+        }
+    }
+
+    /// Apply a trade; returns what a sell realized
+    ///
+    /// Sells past the open quantity realize only against the lots that exist,
+    /// so an incomplete history never invents a cost.
+    pub async fn apply(&self, user_id: i64, trade: &LotTrade) -> Option<RealizedPnl> {
+        let method = self.method(user_id).await;
+        let mut lots = self.lots.write().await;
+        let book = lots.entry((user_id, trade.mint.clone())).or_default();
+
+        if trade.is_buy {
+            if trade.quantity > DUST {
+                book.push_back(Lot {
+                    quantity: trade.quantity,
+                    unit_cost_sol: (trade.sol_amount + trade.fee_sol) / trade.quantity,
+                    acquired_at: trade.at,
+                    provenance: trade.provenance,
+                });
+            }
+            return None;
+        }
+
+        let unit_proceeds = (trade.sol_amount - trade.fee_sol) / trade.quantity;
+        let (matched, cost_sol) = match method {
+            CostBasisMethod::Fifo => Self::close_fifo(book, trade.quantity),
+            CostBasisMethod::Average => Self::close_average(book, trade.quantity),
+        };
+        if trade.quantity - matched > DUST {
+            debug!("📒 Sell of {} {} for user {} exceeds known lots", trade.quantity - matched, trade.mint, user_id);
+        }
+
+        let proceeds_sol = matched * unit_proceeds;
+        let pnl_sol = proceeds_sol - cost_sol;
+        *self.realized_sol.write().await.entry(user_id).or_default() += pnl_sol;
+        Some(RealizedPnl {
+            user_id,
+            mint: trade.mint.clone(),
+            quantity: matched,
+            proceeds_sol,
+            cost_sol,
+            pnl_sol,
+            method,
+            at: trade.at,
+        })
+    }
+
+    /// Close the oldest lots first; returns the quantity matched and its cost
+    fn close_fifo(book: &mut VecDeque<Lot>, quantity: f64) -> (f64, f64) {
+        let mut remaining = quantity;
+        let mut cost = 0.0;
+        while remaining > DUST {
+            let Some(lot) = book.front_mut() else { break };
+            let matched = lot.quantity.min(remaining);
+            cost += matched * lot.unit_cost_sol;
+            lot.quantity -= matched;
+            remaining -= matched;
+            if lot.quantity <= DUST {
+                book.pop_front();
+            }
+        }
+        (quantity - remaining, cost)
+    }
+
+    /// Shrink every lot by the same fraction, so the rest keeps the average cost
+    fn close_average(book: &mut VecDeque<Lot>, quantity: f64) -> (f64, f64) {
+        let open: f64 = book.iter().map(|lot| lot.quantity).sum();
+        if open <= DUST {
+            return (0.0, 0.0);
+        }
+        let matched = quantity.min(open);
+        let cost = book.iter().map(|lot| lot.quantity * lot.unit_cost_sol).sum::<f64>() * matched / open;
+
+        let keep = 1.0 - matched / open;
+        for lot in book.iter_mut() {
+            lot.quantity *= keep;
+        }
+        book.retain(|lot| lot.quantity > DUST);
+        (matched, cost)
+    }
+
+    pub async fn position(&self, user_id: i64, mint: &str) -> Option<CostBasis> {
+        let lots = self.lots.read().await;
+        let book = lots.get(&(user_id, mint.to_string()))?;
+        let basis = book.iter().fold(CostBasis { quantity: 0.0, cost_sol: 0.0 }, |acc, lot| CostBasis {
+            quantity: acc.quantity + lot.quantity,
+            cost_sol: acc.cost_sol + lot.quantity * lot.unit_cost_sol,
+        });
+        (basis.quantity > DUST).then_some(basis)
+    }
+
+    pub async fn lots(&self, user_id: i64, mint: &str) -> Vec<Lot> {
+        self.lots.read().await
+            .get(&(user_id, mint.to_string()))
+            .map(|book| book.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub async fn realized_sol(&self, user_id: i64) -> f64 {
+        self.realized_sol.read().await.get(&user_id).copied().unwrap_or(0.0)
+    }
+
+    pub async fn forget_user(&self, user_id: i64) -> usize {
+        self.realized_sol.write().await.remove(&user_id);
+        let mut lots = self.lots.write().await;
+        let before = lots.len();
+        lots.retain(|(owner, _), _| *owner != user_id);
+        before - lots.len()
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use crate::trading::ExecutionFees;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// What a fee ledger entry accounts for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FeeKind {
+    Network,
+    Priority,
+    Platform,
+    /// Rent returned by closing empty token accounts
+    RentReclaimed,
+    /// Claim fees and rent the bot fronted for a recipient with no SOL
+    ClaimSponsorship,
+}
+
+impl FeeKind {
+    /// Credits reduce what the user has paid overall
+    pub fn is_credit(&self) -> bool {
+        matches!(self, FeeKind::RentReclaimed)
+    }
+}
+
+/// One fee paid or credited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeEntry {
+    pub kind: FeeKind,
+    pub lamports: u64,
+    pub signature: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Per-user totals
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FeeSummary {
+    pub paid_lamports: u64,
+    pub credited_lamports: u64,
+}
+
+impl FeeSummary {
+    /// Fees paid net of credits; negative when credits exceed fees
+    pub fn net_lamports(&self) -> i64 {
+        self.paid_lamports as i64 - self.credited_lamports as i64
+    }
+}
+
+/// Fees each user paid and credits they received
+#[derive(Clone, Default)]
+pub struct FeeLedger {
+    entries: Arc<RwLock<HashMap<i64, Vec<FeeEntry>>>>,
+}
+
+impl FeeLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the fees of one execution
+    pub async fn record_execution(&self, user_id: i64, fees: &ExecutionFees, signature: Option<&str>) {
+        let to_lamports = |sol: f64| (sol * LAMPORTS_PER_SOL).round().max(0.0) as u64;
+        let components = [
+            (FeeKind::Network, to_lamports(fees.network_fee_sol)),
+            (FeeKind::Priority, fees.priority_fee_lamports),
+            (FeeKind::Platform, to_lamports(fees.platform_fee_sol)),
+        ];
+
+        for (kind, lamports) in components {
+            if lamports > 0 {
+                self.record(user_id, kind, lamports, signature).await;
+            }
+        }
+    }
+
+    /// Record a fee or credit; a repeated (signature, kind) pair is ignored so retries don't double count
+    pub async fn record(&self, user_id: i64, kind: FeeKind, lamports: u64, signature: Option<&str>) -> bool {
+        let mut entries = self.entries.write().await;
+        let user_entries = entries.entry(user_id).or_default();
+
+        if let Some(signature) = signature {
+            let duplicate = user_entries.iter()
+                .any(|entry| entry.kind == kind && entry.signature.as_deref() == Some(signature));
+            if duplicate {
+                return false;
+            }
+        }
+
+        debug!("💸 User {} {:?} {} lamports ({:?})", user_id, kind, lamports, signature);
+        user_entries.push(FeeEntry {
+            kind,
+            lamports,
+            signature: signature.map(str::to_string),
+            recorded_at: Utc::now(),
+        });
+        true
+    }
+
+    pub async fn entries(&self, user_id: i64) -> Vec<FeeEntry> {
+        self.entries.read().await.get(&user_id).cloned().unwrap_or_default()
+    }
+
+    /// Drop a user's fee history; returns the number of entries removed
+    pub async fn forget_user(&self, user_id: i64) -> usize {
+        self.entries.write().await.remove(&user_id).map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub async fn summary(&self, user_id: i64) -> FeeSummary {
+        let entries = self.entries.read().await;
+        let mut summary = FeeSummary::default();
+        for entry in entries.get(&user_id).into_iter().flatten() {
+            if entry.kind.is_credit() {
+                summary.credited_lamports += entry.lamports;
+            } else {
+                summary.paid_lamports += entry.lamports;
+            }
+        }
+        summary
+    }
+}
@@ -0,0 +1,447 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, info};
+
+use crate::errors::{BotError, Result};
+use crate::trading::{Order, OrderExecution, OrderType, TradeResult};
+
+/// How the trader rates a closed position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JournalTag {
+    FollowedPlan,
+    BrokePlan,
+    FomoEntry,
+    NewsEvent,
+    Other,
+}
+
+impl JournalTag {
+    pub const ALL: [JournalTag; 5] = [
+        JournalTag::FollowedPlan,
+        JournalTag::BrokePlan,
+        JournalTag::FomoEntry,
+        JournalTag::NewsEvent,
+        JournalTag::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            JournalTag::FollowedPlan => "followed plan",
+            JournalTag::BrokePlan => "broke plan",
+            JournalTag::FomoEntry => "FOMO entry",
+            JournalTag::NewsEvent => "news event",
+            JournalTag::Other => "other",
+        }
+    }
+
+    /// Short code used in callback data and `/journal` arguments
+    pub fn code(&self) -> &'static str {
+        match self {
+            JournalTag::FollowedPlan => "plan",
+            JournalTag::BrokePlan => "broke",
+            JournalTag::FomoEntry => "fomo",
+            JournalTag::NewsEvent => "news",
+            JournalTag::Other => "other",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|tag| tag.code().eq_ignore_ascii_case(code))
+    }
+}
+
+/// Which path closed the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloseReason {
+    ManualSell,
+    StopLoss,
+    TakeProfit,
+    TrailingStop,
+    PanicSell,
+}
+
+impl CloseReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CloseReason::ManualSell => "manual sell",
+            CloseReason::StopLoss => "stop-loss",
+            CloseReason::TakeProfit => "take-profit",
+            CloseReason::TrailingStop => "trailing stop",
+            CloseReason::PanicSell => "panic sell",
+        }
+    }
+}
+
+/// A position that just closed completely
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionClose {
+    pub user_id: i64,
+    pub mint: String,
+    pub symbol: String,
+    pub reason: CloseReason,
+    /// Realized return; None when the entry price is unknown
+    pub return_pct: Option<f64>,
+    pub value_usd: Option<f64>,
+    pub closed_at: DateTime<Utc>,
+    pub tx_signature: Option<String>,
+}
+
+impl PositionClose {
+    /// Close detection for sells that go through the trading engine (manual and panic)
+    pub fn from_sell(
+        user_id: i64,
+        mint: &str,
+        symbol: &str,
+        sell_percentage: f64,
+        result: &TradeResult,
+        reason: CloseReason,
+    ) -> Option<Self> {
+        if sell_percentage < 100.0 || result.tokens_sold <= 0.0 {
+            return None;
+        }
+
+        let value_usd = result.tokens_sold * result.price;
+        Some(Self {
+            user_id,
+            mint: mint.to_string(),
+            symbol: symbol.to_string(),
+            reason,
+            return_pct: Some(result.pnl_percentage),
+            value_usd: (value_usd > 0.0).then_some(value_usd),
+            closed_at: result.timestamp,
+            tx_signature: Some(result.tx_signature.clone()),
+        })
+    }
+
+    /// Close detection for protective orders filled by the order manager
+    pub fn from_order_execution(order: &Order, execution: &OrderExecution, symbol: &str) -> Option<Self> {
+        if !execution.success {
+            return None;
+        }
+
+        let mut close = Self::from_order_fill(order, execution.amount_executed, execution.price_at_execution, symbol)?;
+        close.closed_at = execution.executed_at;
+        close.tx_signature = execution.transaction_signature.clone();
+        Some(close)
+    }
+
+    /// Close detection for a fill of `amount` at `price` against a protective order
+    pub fn from_order_fill(order: &Order, amount: Decimal, price: Decimal, symbol: &str) -> Option<Self> {
+        let reason = match &order.order_type {
+            OrderType::StopLoss { .. } => CloseReason::StopLoss,
+            OrderType::TakeProfit { .. } => CloseReason::TakeProfit,
+            OrderType::TrailingStop { .. } => CloseReason::TrailingStop,
+            // A bracket/OCO leg fills as a stop when the price ended below entry
+            OrderType::OCO { .. } | OrderType::Bracket { .. } => match order.metadata.entry_price {
+                Some(entry) if price >= entry => CloseReason::TakeProfit,
+                _ => CloseReason::StopLoss,
+            },
+            OrderType::Limit { .. } => return None,
+        };
+
+        // Partial take-profits leave a position open
+        let position = order.metadata.position_size.unwrap_or(order.base_amount);
+        if amount < position {
+            return None;
+        }
+
+        let return_pct = order.metadata.entry_price
+            .filter(|entry| *entry > Decimal::ZERO)
+            .and_then(|entry| ((price / entry - Decimal::ONE) * Decimal::from(100)).to_f64());
+
+        Some(Self {
+            user_id: order.user_id,
+            mint: order.token_mint.clone(),
+            symbol: symbol.to_string(),
+            reason,
+            return_pct,
+            value_usd: (amount * price).to_f64(),
+            closed_at: Utc::now(),
+            tx_signature: None,
+        })
+    }
+}
+
+/// Realized trade kept for tagging, stats and export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub entry_id: String,
+    pub close: PositionClose,
+    pub tag: Option<JournalTag>,
+    pub note: Option<String>,
+    pub tagged_at: Option<DateTime<Utc>>,
+}
+
+/// Prompt sent to the user after a close
+#[derive(Debug, Clone)]
+pub struct JournalPrompt {
+    pub user_id: i64,
+    pub entry_id: String,
+    pub message: String,
+}
+
+/// Win rate and average return for one tag
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagBreakdown {
+    /// None groups untagged trades
+    pub tag: Option<JournalTag>,
+    pub trades: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+    pub average_return_pct: f64,
+}
+
+impl TagBreakdown {
+    /// Group `(tag, return %)` pairs by tag; ordered like `JournalTag::ALL`, untagged last
+    pub fn compute<I>(results: I) -> Vec<TagBreakdown>
+    where
+        I: IntoIterator<Item = (Option<JournalTag>, f64)>,
+    {
+        let mut groups: HashMap<Option<JournalTag>, Vec<f64>> = HashMap::new();
+        for (tag, return_pct) in results {
+            groups.entry(tag).or_default().push(return_pct);
+        }
+
+        let order = JournalTag::ALL.iter().map(|tag| Some(*tag)).chain(std::iter::once(None));
+        order.filter_map(|tag| {
+            let returns = groups.get(&tag)?;
+            let wins = returns.iter().filter(|r| **r > 0.0).count() as u32;
+            Some(TagBreakdown {
+                tag,
+                trades: returns.len() as u32,
+                wins,
+                win_rate: wins as f64 / returns.len() as f64 * 100.0,
+                average_return_pct: returns.iter().sum::<f64>() / returns.len() as f64,
+            })
+        })
+        .collect()
+    }
+}
+
+/// Prompt limits for journaling
+#[derive(Debug, Clone)]
+pub struct JournalConfig {
+    /// Closes worth less than this are recorded but never prompted
+    pub dust_threshold_usd: f64,
+    pub max_prompts_per_day: usize,
+    /// Entries kept per user
+    pub max_entries: usize,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            dust_threshold_usd: 5.0,
+            max_prompts_per_day: 3,
+            max_entries: 500,
+        }
+    }
+}
+
+/// Post-close journaling prompts and tag-based stats
+#[derive(Clone)]
+pub struct TradeJournal {
+    config: JournalConfig,
+    entries: Arc<RwLock<HashMap<i64, Vec<JournalEntry>>>>,
+    prompts_disabled: Arc<RwLock<HashSet<i64>>>,
+    prompts_sent: Arc<RwLock<HashMap<i64, Vec<DateTime<Utc>>>>>,
+    prompt_tx: broadcast::Sender<JournalPrompt>,
+}
+
+impl TradeJournal {
+    pub fn new(config: JournalConfig) -> Self {
+        info!("📓 Initializing trade journal");
+
+        let (prompt_tx, _) = broadcast::channel(256);
+
+        Self {
+            config,
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            prompts_disabled: Arc::new(RwLock::new(HashSet::new())),
+            prompts_sent: Arc::new(RwLock::new(HashMap::new())),
+            prompt_tx,
+        }
+    }
+
+    /// Prompts to deliver, for the Telegram forwarder
+    pub fn subscribe_prompts(&self) -> broadcast::Receiver<JournalPrompt> {
+        self.prompt_tx.subscribe()
+    }
+
+    /// Record a closed position and prompt for a tag unless suppressed
+    pub async fn on_position_closed(&self, close: PositionClose) -> Option<JournalPrompt> {
+        let entry_id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let user_id = close.user_id;
+        let closed_at = close.closed_at;
+
+        let suppression = self.suppression_reason(&close).await;
+        let prompt_message = Self::prompt_message(&close);
+
+        {
+            let mut entries = self.entries.write().await;
+            let user_entries = entries.entry(user_id).or_default();
+            user_entries.push(JournalEntry {
+                entry_id: entry_id.clone(),
+                close,
+                tag: None,
+                note: None,
+                tagged_at: None,
+            });
+            if user_entries.len() > self.config.max_entries {
+                let excess = user_entries.len() - self.config.max_entries;
+                user_entries.drain(..excess);
+            }
+        }
+
+        if let Some(reason) = suppression {
+            debug!("📓 No journal prompt for user {}: {}", user_id, reason);
+            return None;
+        }
+
+        self.prompts_sent.write().await.entry(user_id).or_default().push(closed_at);
+
+        let prompt = JournalPrompt { user_id, entry_id, message: prompt_message };
+        let _ = self.prompt_tx.send(prompt.clone());
+        Some(prompt)
+    }
+
+    /// Why a close should not prompt, if it shouldn't
+    async fn suppression_reason(&self, close: &PositionClose) -> Option<&'static str> {
+        if self.prompts_disabled.read().await.contains(&close.user_id) {
+            return Some("prompts disabled");
+        }
+        if close.value_usd.map_or(false, |value| value < self.config.dust_threshold_usd) {
+            return Some("dust position");
+        }
+
+        let day_ago = close.closed_at - Duration::hours(24);
+        let sent_today = self.prompts_sent.read().await.get(&close.user_id)
+            .map(|sent| sent.iter().filter(|at| **at > day_ago).count())
+            .unwrap_or(0);
+        if sent_today >= self.config.max_prompts_per_day {
+            return Some("daily prompt limit reached");
+        }
+
+        None
+    }
+
+    fn prompt_message(close: &PositionClose) -> String {
+        let result = close.return_pct
+            .map(|pct| format!(" {}{:.0}%", if pct >= 0.0 { "+" } else { "−" }, pct.abs()))
+            .unwrap_or_default();
+        format!("📓 Position closed ({}): {}{}. Tag it?", close.reason.label(), close.symbol, result)
+    }
+
+    /// Tag (or re-tag) a journal entry
+    pub async fn tag(&self, user_id: i64, entry_id: &str, tag: JournalTag, note: Option<String>) -> Result<JournalEntry> {
+        let mut entries = self.entries.write().await;
+        let entry = entries.get_mut(&user_id)
+            .and_then(|user_entries| user_entries.iter_mut().find(|e| e.entry_id == entry_id))
+            .ok_or_else(|| BotError::not_found(format!("Journal entry {} not found", entry_id)))?;
+
+        entry.tag = Some(tag);
+        if note.is_some() {
+            entry.note = note;
+        }
+        entry.tagged_at = Some(Utc::now());
+
+        Ok(entry.clone())
+    }
+
+    /// Most recent entries first
+    pub async fn recent(&self, user_id: i64, limit: usize) -> Vec<JournalEntry> {
+        self.entries.read().await.get(&user_id)
+            .map(|entries| entries.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub async fn set_prompts_enabled(&self, user_id: i64, enabled: bool) {
+        let mut disabled = self.prompts_disabled.write().await;
+        if enabled {
+            disabled.remove(&user_id);
+        } else {
+            disabled.insert(user_id);
+        }
+    }
+
+    pub async fn prompts_enabled(&self, user_id: i64) -> bool {
+        !self.prompts_disabled.read().await.contains(&user_id)
+    }
+
+    /// Per-tag stats for one user; closes without a known return are left out
+    pub async fn breakdown(&self, user_id: i64) -> Vec<TagBreakdown> {
+        let entries = self.entries.read().await;
+        TagBreakdown::compute(
+            entries.get(&user_id).into_iter().flatten()
+                .filter_map(|e| e.close.return_pct.map(|pct| (e.tag, pct))),
+        )
+    }
+
+    /// Per-tag stats across all users, for the performance report
+    pub async fn breakdown_all(&self) -> Vec<TagBreakdown> {
+        let entries = self.entries.read().await;
+        TagBreakdown::compute(
+            entries.values().flatten()
+                .filter_map(|e| e.close.return_pct.map(|pct| (e.tag, pct))),
+        )
+    }
+
+    /// Erase a user's journal; with `keep_trades` the closes stay but tags and notes go
+    pub async fn forget_user(&self, user_id: i64, keep_trades: bool) -> usize {
+        self.prompts_disabled.write().await.remove(&user_id);
+        self.prompts_sent.write().await.remove(&user_id);
+
+        let mut entries = self.entries.write().await;
+        if !keep_trades {
+            return entries.remove(&user_id).map(|e| e.len()).unwrap_or(0);
+        }
+        let mut cleared = 0;
+        for entry in entries.get_mut(&user_id).into_iter().flatten() {
+            if entry.tag.is_some() || entry.note.is_some() {
+                cleared += 1;
+            }
+            entry.tag = None;
+            entry.note = None;
+            entry.tagged_at = None;
+        }
+        cleared
+    }
+
+    /// Journal as CSV, oldest first
+    pub async fn export_csv(&self, user_id: i64) -> String {
+        let mut csv = String::from("closed_at,symbol,mint,close_reason,return_pct,value_usd,tag,note,tx_signature\n");
+
+        let entries = self.entries.read().await;
+        for entry in entries.get(&user_id).into_iter().flatten() {
+            let close = &entry.close;
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                close.closed_at.to_rfc3339(),
+                csv_field(&close.symbol),
+                close.mint,
+                close.reason.label(),
+                close.return_pct.map(|pct| format!("{:.2}", pct)).unwrap_or_default(),
+                close.value_usd.map(|value| format!("{:.2}", value)).unwrap_or_default(),
+                entry.tag.map(|tag| tag.label()).unwrap_or_default(),
+                csv_field(entry.note.as_deref().unwrap_or_default()),
+                close.tx_signature.as_deref().unwrap_or_default(),
+            ));
+        }
+
+        csv
+    }
+}
+
+/// Quote a CSV field when it contains separators or quotes
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -1,4 +1,12 @@
 mod performance_tracker;
+mod journal;
+mod fees;
+mod cost_basis;
+mod trade_import;
+mod trade_export;
+mod trading_hours;
+mod charts;
+mod benchmark;
 
 pub use performance_tracker::{
     PerformanceTracker,
@@ -11,10 +19,55 @@ pub use performance_tracker::{
     TradeRecord,
     TradeType,
     MetricsCalculator,
+    Drawdown,
+    daily_snapshots,
+    snapshot_returns,
+    PERIODS_PER_YEAR,
+    WEEKLY_MIN_PERIODS,
     BenchmarkData,
     PerformanceReport,
     BenchmarkComparison,
     AnalyticsReport,
     RiskMetrics,
     EfficiencyMetrics,
-};
\ No newline at end of file
+};
+pub use journal::{
+    TradeJournal,
+    JournalConfig,
+    JournalEntry,
+    JournalPrompt,
+    JournalTag,
+    CloseReason,
+    PositionClose,
+    TagBreakdown,
+};
+pub use fees::{FeeLedger, FeeEntry, FeeKind, FeeSummary};
+pub use cost_basis::{CostBasis, CostBasisBook, CostBasisMethod, CostBasisPreferences, Lot, LotTrade, RealizedPnl};
+pub use trade_import::{
+    parse_trades,
+    ImportFormat,
+    ImportOutcome,
+    ImportPreview,
+    ImportedTrade,
+    KnownTrades,
+    SkipReason,
+    SkippedRow,
+    TradeImporter,
+    MAX_IMPORT_ROWS,
+    NATIVE_HEADER,
+};
+pub use trade_export::{
+    build_ledger,
+    write_csv,
+    write_json,
+    LedgerEntry,
+    LedgerFile,
+    LedgerFormat,
+    LedgerSide,
+    LedgerSource,
+    TradeHistoryExporter,
+    LEDGER_HEADER,
+};
+pub use trading_hours::{AttributionTime, BucketStats, TradingHoursConfig, TradingHoursReport};
+pub use charts::render_heatmap;
+pub use benchmark::{daily_returns, sparkline, BenchmarkBasket, BenchmarkReport};
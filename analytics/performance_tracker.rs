@@ -1,4 +1,6 @@
-use chrono::{DateTime, Utc, Duration, NaiveDate};
+use chrono::{DateTime, Datelike, Utc, Duration, NaiveDate};
+use chrono_tz::Tz;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, BTreeMap};
@@ -7,8 +9,14 @@ use tokio::sync::RwLock;
 use tracing::{info, debug, warn, error};
 
 use crate::errors::{BotError, Result};
+use crate::api::JupiterPriceV3Client;
 use crate::db::Database;
 use crate::telemetry::TelemetryService;
+use crate::trading::{ExecutionReport, TradeProvenance, TradeResult};
+use super::benchmark::{daily_returns, BenchmarkBasket, BenchmarkReport};
+use super::cost_basis::RealizedPnl;
+use super::journal::{JournalTag, TagBreakdown, TradeJournal};
+use super::trading_hours::{TradingHoursConfig, TradingHoursReport};
 
 /// Comprehensive performance tracking system for trading activities
 #[derive(Clone)]
@@ -18,6 +26,15 @@ pub struct PerformanceTracker {
     performance_cache: Arc<RwLock<PerformanceCache>>,
     metrics_calculator: Arc<MetricsCalculator>,
     benchmark_data: Arc<RwLock<BenchmarkData>>,
+    journal: Option<Arc<TradeJournal>>,
+    /// Daily closes for benchmark baskets
+    price_client: Option<Arc<JupiterPriceV3Client>>,
+    /// Realized trades per user, kept raw so temporal stats follow timezone changes
+    user_trades: Arc<RwLock<HashMap<i64, Vec<TradeRecord>>>>,
+    /// First buy of each open position by user and token pair
+    open_entries: Arc<RwLock<HashMap<(i64, String), DateTime<Utc>>>>,
+    /// Daily snapshots and totals per user; reports only ever read the asking user's
+    user_performance: Arc<RwLock<HashMap<i64, UserPerformance>>>,
 }
 
 /// Cache for frequently accessed performance data
@@ -31,6 +48,29 @@ pub struct PerformanceCache {
     pub last_updated: DateTime<Utc>,
 }
 
+/// One user's realized results, day by day and all-time
+#[derive(Debug, Clone, Default)]
+pub struct UserPerformance {
+    pub daily_performance: BTreeMap<NaiveDate, DailyPerformance>,
+    pub all_time_performance: AllTimePerformance,
+}
+
+impl UserPerformance {
+    fn record(&mut self, trade: &TradeRecord) {
+        let value_before = self.all_time_performance.current_value;
+        self.all_time_performance.record(trade);
+        record_day(&mut self.daily_performance, trade, value_before);
+    }
+}
+
+/// Realized PnL lands on the day the trade closed
+fn record_day(daily: &mut BTreeMap<NaiveDate, DailyPerformance>, trade: &TradeRecord, value_before: Decimal) {
+    let day = trade.timestamp.date_naive();
+    daily.entry(day)
+        .or_insert_with(|| DailyPerformance::starting(day, value_before))
+        .record(trade);
+}
+
 /// Daily performance metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyPerformance {
@@ -52,6 +92,101 @@ pub struct DailyPerformance {
     pub volatility: f64,
 }
 
+impl DailyPerformance {
+    fn starting(date: NaiveDate, value: Decimal) -> Self {
+        Self {
+            date,
+            starting_value: value,
+            ending_value: value,
+            daily_return: Decimal::ZERO,
+            daily_return_percentage: 0.0,
+            trades_executed: 0,
+            winning_trades: 0,
+            losing_trades: 0,
+            total_fees: Decimal::ZERO,
+            total_volume: Decimal::ZERO,
+            best_trade: None,
+            worst_trade: None,
+            tokens_traded: Vec::new(),
+            high_water_mark: value,
+            drawdown: 0.0,
+            volatility: 0.0,
+        }
+    }
+
+    /// Fold a realized trade into the day
+    fn record(&mut self, trade: &TradeRecord) {
+        self.trades_executed += 1;
+        if trade.pnl > Decimal::ZERO {
+            self.winning_trades += 1;
+        } else {
+            self.losing_trades += 1;
+        }
+        self.total_fees += trade.fees;
+        self.total_volume += trade.quantity * trade.exit_price;
+        self.daily_return += trade.pnl;
+        self.ending_value = self.starting_value + self.daily_return;
+        self.daily_return_percentage = percentage_of(self.daily_return, self.starting_value);
+        self.high_water_mark = self.high_water_mark.max(self.ending_value);
+        self.drawdown = percentage_of(self.high_water_mark - self.ending_value, self.high_water_mark);
+
+        if self.best_trade.as_ref().map_or(true, |best| trade.pnl > best.pnl) {
+            self.best_trade = Some(trade.clone());
+        }
+        if self.worst_trade.as_ref().map_or(true, |worst| trade.pnl < worst.pnl) {
+            self.worst_trade = Some(trade.clone());
+        }
+        if !self.tokens_traded.contains(&trade.token_pair) {
+            self.tokens_traded.push(trade.token_pair.clone());
+        }
+    }
+}
+
+fn percentage_of(part: Decimal, whole: Decimal) -> f64 {
+    if whole > Decimal::ZERO {
+        (part / whole * Decimal::from(100)).to_f64().unwrap_or(0.0)
+    } else {
+        0.0
+    }
+}
+
+/// Summed daily returns per `YYYY-MM` month
+fn monthly_returns(daily_performance: &BTreeMap<NaiveDate, DailyPerformance>) -> BTreeMap<String, Decimal> {
+    let mut months = BTreeMap::new();
+    for day in daily_performance.values() {
+        *months.entry(day.date.format("%Y-%m").to_string()).or_insert(Decimal::ZERO) += day.daily_return;
+    }
+    months
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len().max(1) as f64
+}
+
+/// End-of-day portfolio values from the day before `days` starts through its last day
+///
+/// Days without trades carry the previous value forward, so they count as flat returns.
+pub fn daily_snapshots(days: &[DailyPerformance]) -> Vec<(NaiveDate, Decimal)> {
+    let Some(first) = days.first() else { return Vec::new() };
+    let mut snapshots = vec![(first.date.pred_opt().unwrap_or(first.date), first.starting_value)];
+    for day in days {
+        let (last_date, last_value) = snapshots[snapshots.len() - 1];
+        let mut date = last_date.succ_opt().unwrap_or(day.date);
+        while date < day.date {
+            snapshots.push((date, last_value));
+            date = date.succ_opt().unwrap_or(day.date);
+        }
+        snapshots.push((day.date, day.ending_value));
+    }
+    snapshots
+}
+
+/// Fractional returns between consecutive snapshots
+pub fn snapshot_returns(snapshots: &[(NaiveDate, Decimal)]) -> Vec<f64> {
+    let values: Vec<f64> = snapshots.iter().map(|(_, value)| value.to_f64().unwrap_or(0.0)).collect();
+    daily_returns(&values)
+}
+
 /// Weekly performance aggregation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeeklyPerformance {
@@ -67,8 +202,8 @@ pub struct WeeklyPerformance {
     pub average_daily_return: f64,
     pub best_day: Option<NaiveDate>,
     pub worst_day: Option<NaiveDate>,
-    pub sharpe_ratio: f64,
-    pub sortino_ratio: f64,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
 }
 
 /// Monthly performance metrics
@@ -133,6 +268,20 @@ pub struct AllTimePerformance {
     pub worst_trade_ever: Option<TradeRecord>,
 }
 
+impl AllTimePerformance {
+    fn record(&mut self, trade: &TradeRecord) {
+        self.total_trades += 1;
+        if trade.pnl > Decimal::ZERO {
+            self.winning_trades += 1;
+        } else {
+            self.losing_trades += 1;
+        }
+        
+        self.current_value += trade.pnl;
+        self.overall_win_rate = self.winning_trades as f64 / self.total_trades as f64;
+    }
+}
+
 /// Individual trade record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeRecord {
@@ -149,6 +298,52 @@ pub struct TradeRecord {
     pub holding_period: Duration,
     pub strategy_used: String,
     pub risk_reward_ratio: f64,
+    /// Route, timing and slippage of the fill; empty for records exported before it existed
+    #[serde(default)]
+    pub execution: ExecutionReport,
+    /// Journal tag the trader gave the closed position
+    #[serde(default)]
+    pub journal_tag: Option<JournalTag>,
+    /// Imported records feed cost basis and PnL but not leaderboards
+    #[serde(default)]
+    pub provenance: TradeProvenance,
+}
+
+impl TradeRecord {
+    /// When the position was opened; the fill time when the holding period is unknown
+    pub fn entry_time(&self) -> DateTime<Utc> {
+        self.timestamp - self.holding_period
+    }
+
+    /// Map an engine trade result into an analytics record
+    pub fn from_trade_result(result: &TradeResult, token_pair: &str, strategy_used: &str) -> Self {
+        let to_decimal = |value: f64| Decimal::from_f64_retain(value).unwrap_or(Decimal::ZERO);
+        let quantity = if result.tokens_sold > 0.0 { result.tokens_sold } else { result.tokens_received };
+        let fees = result.execution.fees.as_ref()
+            .map(|f| f.network_fee_sol + f.platform_fee_sol + f.priority_fee_lamports as f64 / 1e9)
+            .unwrap_or(0.0);
+        
+        Self {
+            trade_id: result.execution.idempotency_key.clone()
+                .unwrap_or_else(|| result.tx_signature.clone()),
+            timestamp: result.execution.executed_at.unwrap_or(result.timestamp),
+            token_pair: token_pair.to_string(),
+            trade_type: TradeType::Swap,
+            entry_price: to_decimal(result.execution.quoted_price.unwrap_or(result.price)),
+            // The series is in SOL: USDC exits are converted at the fill's SOL price
+            exit_price: to_decimal(result.execution.realized_price_sol().unwrap_or(result.price)),
+            quantity: to_decimal(quantity),
+            pnl: Decimal::ZERO,
+            pnl_percentage: result.pnl_percentage,
+            fees: to_decimal(fees),
+            holding_period: Duration::zero(),
+            strategy_used: strategy_used.to_string(),
+            risk_reward_ratio: 0.0,
+            execution: result.execution.clone(),
+            journal_tag: None,
+            provenance: TradeProvenance::Bot,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -159,63 +354,143 @@ pub enum TradeType {
     Arbitrage,
 }
 
+/// Crypto trades every day, so daily returns annualize over the calendar year
+pub const PERIODS_PER_YEAR: f64 = 365.0;
+/// Daily returns a week needs before its ratios are reported
+///
+/// A full ISO week has seven, counting the move from the Sunday before.
+pub const WEEKLY_MIN_PERIODS: usize = 7;
+
 /// Metrics calculator for advanced performance analytics
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MetricsCalculator {
     risk_free_rate: f64,
+    /// Fewer daily returns than this and the ratios aren't reported
+    min_periods: usize,
+}
+
+impl Default for MetricsCalculator {
+    fn default() -> Self {
+        Self {
+            risk_free_rate: 0.05, // 5% annual risk-free rate
+            min_periods: 14,
+        }
+    }
+}
+
+/// The largest peak-to-trough fall in a value series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Drawdown {
+    /// Fall from the peak, in percent
+    pub percentage: f64,
+    pub peak_date: NaiveDate,
+    pub trough_date: NaiveDate,
 }
 
 impl MetricsCalculator {
-    /// Calculate Sharpe ratio
-    pub fn calculate_sharpe_ratio(&self, returns: &[f64], period_multiplier: f64) -> f64 {
-        if returns.is_empty() {
-            return 0.0;
+    /// Annual rate, e.g. 0.05 for 5%
+    pub fn with_risk_free_rate(mut self, risk_free_rate: f64) -> Self {
+        self.risk_free_rate = risk_free_rate;
+        self
+    }
+    
+    pub fn with_min_periods(mut self, min_periods: usize) -> Self {
+        self.min_periods = min_periods.max(2);
+        self
+    }
+    
+    pub fn min_periods(&self) -> usize {
+        self.min_periods
+    }
+    
+    /// The same settings with a minimum history a single week can meet
+    pub fn weekly(&self) -> Self {
+        let min_periods = self.min_periods.min(WEEKLY_MIN_PERIODS);
+        self.clone().with_min_periods(min_periods)
+    }
+    
+    /// Annualized Sharpe ratio of periodic returns given as fractions
+    pub fn calculate_sharpe_ratio(&self, returns: &[f64], periods_per_year: f64) -> Option<f64> {
+        if returns.len() < self.min_periods {
+            return None;
         }
         
-        let mean_return = returns.iter().sum::<f64>() / returns.len() as f64;
-        let variance = returns.iter()
+        let mean_return = mean(returns);
+        let std_dev = (returns.iter()
             .map(|r| (r - mean_return).powi(2))
-            .sum::<f64>() / returns.len() as f64;
-        let std_dev = variance.sqrt();
+            .sum::<f64>() / (returns.len() - 1) as f64)
+            .sqrt();
         
-        if std_dev == 0.0 {
-            return 0.0;
+        if std_dev <= f64::EPSILON {
+            return None;
         }
         
-        ((mean_return - self.risk_free_rate / period_multiplier) / std_dev) * period_multiplier.sqrt()
+        Some((mean_return - self.risk_free_rate / periods_per_year) / std_dev * periods_per_year.sqrt())
     }
     
-    /// Calculate Sortino ratio (downside deviation)
-    pub fn calculate_sortino_ratio(&self, returns: &[f64], target_return: f64, period_multiplier: f64) -> f64 {
-        if returns.is_empty() {
-            return 0.0;
+    /// Annualized Sortino ratio: excess return over the deviation below the risk-free rate
+    pub fn calculate_sortino_ratio(&self, returns: &[f64], periods_per_year: f64) -> Option<f64> {
+        if returns.len() < self.min_periods {
+            return None;
         }
         
-        let mean_return = returns.iter().sum::<f64>() / returns.len() as f64;
-        let downside_returns: Vec<f64> = returns.iter()
-            .filter(|&&r| r < target_return)
-            .map(|&r| (r - target_return).powi(2))
-            .collect();
+        let target_return = self.risk_free_rate / periods_per_year;
+        // Every period counts towards the mean; only shortfalls add to it
+        let downside_deviation = (returns.iter()
+            .map(|r| (r - target_return).min(0.0).powi(2))
+            .sum::<f64>() / returns.len() as f64)
+            .sqrt();
         
-        if downside_returns.is_empty() {
-            return f64::INFINITY; // No downside risk
+        if downside_deviation <= f64::EPSILON {
+            return None;
         }
         
-        let downside_deviation = (downside_returns.iter().sum::<f64>() / downside_returns.len() as f64).sqrt();
-        
-        if downside_deviation == 0.0 {
-            return f64::INFINITY;
+        Some((mean(returns) - target_return) / downside_deviation * periods_per_year.sqrt())
+    }
+    
+    /// Calculate Calmar ratio (annualized return / max drawdown, both in percent)
+    pub fn calculate_calmar_ratio(&self, annual_return: f64, max_drawdown: f64) -> Option<f64> {
+        if max_drawdown.abs() <= f64::EPSILON {
+            return None;
         }
-        
-        ((mean_return - target_return) / downside_deviation) * period_multiplier.sqrt()
+        Some(annual_return / max_drawdown.abs())
     }
     
-    /// Calculate Calmar ratio (return / max drawdown)
-    pub fn calculate_calmar_ratio(&self, annual_return: f64, max_drawdown: f64) -> f64 {
-        if max_drawdown == 0.0 {
-            return f64::INFINITY;
+    /// Compound growth of a dated value series, annualized, in percent
+    pub fn calculate_annualized_return(&self, series: &[(NaiveDate, Decimal)]) -> Option<f64> {
+        if series.len() <= self.min_periods {
+            return None;
+        }
+        let first = series.first()?.1.to_f64()?;
+        let last = series.last()?.1.to_f64()?;
+        if first <= 0.0 || last < 0.0 {
+            return None;
         }
-        annual_return / max_drawdown.abs()
+        let periods = (series.len() - 1) as f64;
+        Some(((last / first).powf(PERIODS_PER_YEAR / periods) - 1.0) * 100.0)
+    }
+    
+    /// Worst drawdown of a dated value series, with where it peaked and bottomed
+    pub fn calculate_drawdown(&self, series: &[(NaiveDate, Decimal)]) -> Option<Drawdown> {
+        if series.len() <= self.min_periods {
+            return None;
+        }
+        let values: Vec<Decimal> = series.iter().map(|(_, value)| *value).collect();
+        let (percentage, peak, trough) = self.calculate_max_drawdown(&values);
+        Some(Drawdown {
+            percentage,
+            peak_date: series[peak].0,
+            trough_date: series[trough].0,
+        })
+    }
+    
+    /// How far the last value sits below the series' peak, in percent
+    pub fn calculate_current_drawdown(&self, series: &[(NaiveDate, Decimal)]) -> Option<f64> {
+        if series.len() <= self.min_periods {
+            return None;
+        }
+        let peak = series.iter().map(|(_, value)| *value).max()?;
+        Some(percentage_of(peak - series.last()?.1, peak))
     }
     
     /// Calculate Information ratio
@@ -365,9 +640,7 @@ impl PerformanceTracker {
                 all_time_performance: AllTimePerformance::default(),
                 last_updated: Utc::now(),
             })),
-            metrics_calculator: Arc::new(MetricsCalculator {
-                risk_free_rate: 0.05, // 5% annual risk-free rate
-            }),
+            metrics_calculator: Arc::new(MetricsCalculator::default()),
             benchmark_data: Arc::new(RwLock::new(BenchmarkData {
                 spy_returns: Vec::new(),
                 btc_returns: Vec::new(),
@@ -376,9 +649,32 @@ impl PerformanceTracker {
                 custom_benchmark: None,
                 last_updated: Utc::now(),
             })),
+            journal: None,
+            price_client: None,
+            user_trades: Arc::new(RwLock::new(HashMap::new())),
+            open_entries: Arc::new(RwLock::new(HashMap::new())),
+            user_performance: Arc::new(RwLock::new(HashMap::new())),
         }
     }
     
+    /// Risk-free rate and minimum history for the risk ratios
+    pub fn with_metrics_calculator(mut self, metrics_calculator: MetricsCalculator) -> Self {
+        self.metrics_calculator = Arc::new(metrics_calculator);
+        self
+    }
+    
+    /// Include per-tag journal stats in analytics reports
+    pub fn with_journal(mut self, journal: Arc<TradeJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+    
+    /// Fetch benchmark price history through `price_client`
+    pub fn with_price_client(mut self, price_client: Arc<JupiterPriceV3Client>) -> Self {
+        self.price_client = Some(price_client);
+        self
+    }
+    
     /// Record a new trade
     pub async fn record_trade(&self, trade: TradeRecord) -> Result<()> {
         let _span = self.telemetry.as_ref().map(|t| 
@@ -396,6 +692,96 @@ impl PerformanceTracker {
         Ok(())
     }
     
+    /// Note a buy so the eventual exit knows when the position opened
+    pub async fn record_entry(&self, user_id: i64, token_pair: &str, at: DateTime<Utc>) {
+        self.open_entries.write().await
+            .entry((user_id, token_pair.to_string()))
+            .or_insert(at);
+    }
+    
+    /// Record a sell with the PnL its lots realized, so reports match the cost basis
+    pub async fn record_realized(
+        &self,
+        user_id: i64,
+        mut trade: TradeRecord,
+        realized: &RealizedPnl,
+        position_closed: bool,
+    ) -> Result<()> {
+        let to_decimal = |value: f64| Decimal::from_f64_retain(value).unwrap_or(Decimal::ZERO);
+        if realized.quantity > 0.0 {
+            trade.entry_price = to_decimal(realized.cost_sol / realized.quantity);
+        }
+        trade.pnl = to_decimal(realized.pnl_sol);
+        trade.pnl_percentage = realized.pnl_percentage();
+        
+        debug!("📊 User {} realized {:+.6} SOL on {} ({})", user_id, realized.pnl_sol, trade.token_pair, realized.method.label());
+        self.record_exit(user_id, trade, position_closed).await
+    }
+    
+    /// Record a realized trade for a user, filling in the holding period from its entry
+    pub async fn record_exit(&self, user_id: i64, mut trade: TradeRecord, position_closed: bool) -> Result<()> {
+        let key = (user_id, trade.token_pair.clone());
+        let entry = if position_closed {
+            self.open_entries.write().await.remove(&key)
+        } else {
+            self.open_entries.read().await.get(&key).copied()
+        };
+        if let Some(opened_at) = entry.filter(|at| *at <= trade.timestamp) {
+            trade.holding_period = trade.timestamp - opened_at;
+        }
+        
+        let data = serde_json::to_string(&trade)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize trade {}: {}", trade.trade_id, e)))?;
+        self.database.insert_trade_record(user_id, &trade.trade_id, &data).await?;
+        
+        self.user_trades.write().await.entry(user_id).or_default().push(trade.clone());
+        self.user_performance.write().await.entry(user_id).or_default().record(&trade);
+        self.record_trade(trade).await
+    }
+    
+    pub async fn user_trades(&self, user_id: i64) -> Vec<TradeRecord> {
+        self.user_trades.read().await.get(&user_id).cloned().unwrap_or_default()
+    }
+    
+    /// Users with realized trades since `since`
+    pub async fn active_users(&self, since: DateTime<Utc>) -> Vec<i64> {
+        self.user_trades.read().await.iter()
+            .filter(|(_, trades)| trades.iter().any(|t| t.timestamp >= since))
+            .map(|(user_id, _)| *user_id)
+            .collect()
+    }
+    
+    /// Hour and weekday results in `timezone`, recomputed from the user's trades
+    ///
+    /// `imported` is history from `/import trades`; it only counts when the
+    /// config includes imported trades.
+    pub async fn trading_hours(
+        &self,
+        user_id: i64,
+        timezone: Tz,
+        config: &TradingHoursConfig,
+        imported: &[TradeRecord],
+    ) -> TradingHoursReport {
+        let trades = self.user_trades.read().await;
+        let own = trades.get(&user_id).map(Vec::as_slice).unwrap_or_default();
+        TradingHoursReport::compute(own.iter().chain(imported), timezone, config)
+    }
+    
+    pub async fn forget_user(&self, user_id: i64) -> usize {
+        self.open_entries.write().await.retain(|(owner, _), _| *owner != user_id);
+        self.user_performance.write().await.remove(&user_id);
+        self.user_trades.write().await.remove(&user_id).map(|trades| trades.len()).unwrap_or(0)
+    }
+    
+    /// The ISO week containing `date`, once it has realized trades
+    pub async fn get_week_performance(&self, date: NaiveDate) -> Option<WeeklyPerformance> {
+        let week = date.iso_week();
+        self.performance_cache.read().await
+            .weekly_performance
+            .get(&format!("{}-W{:02}", week.year(), week.week()))
+            .cloned()
+    }
+    
     /// Get performance for a specific date range
     pub async fn get_performance_range(
         &self,
@@ -420,17 +806,12 @@ impl PerformanceTracker {
             0.0
         };
         
-        let returns: Vec<f64> = daily_data.iter()
-            .map(|d| d.daily_return_percentage)
-            .collect();
-        
-        let sharpe_ratio = self.metrics_calculator.calculate_sharpe_ratio(&returns, 252.0);
-        let sortino_ratio = self.metrics_calculator.calculate_sortino_ratio(&returns, 0.0, 252.0);
+        let snapshots = daily_snapshots(&daily_data);
+        let returns = snapshot_returns(&snapshots);
         
-        let values: Vec<Decimal> = daily_data.iter()
-            .map(|d| d.ending_value)
-            .collect();
-        let (max_drawdown, _, _) = self.metrics_calculator.calculate_max_drawdown(&values);
+        let sharpe_ratio = self.metrics_calculator.calculate_sharpe_ratio(&returns, PERIODS_PER_YEAR);
+        let sortino_ratio = self.metrics_calculator.calculate_sortino_ratio(&returns, PERIODS_PER_YEAR);
+        let max_drawdown = self.metrics_calculator.calculate_drawdown(&snapshots);
         
         Ok(PerformanceReport {
             period: format!("{} to {}", start_date, end_date),
@@ -501,19 +882,84 @@ impl PerformanceTracker {
         })
     }
     
-    /// Generate detailed performance analytics
-    pub async fn generate_analytics_report(&self) -> Result<AnalyticsReport> {
-        let cache = self.performance_cache.read().await;
-        let all_time = &cache.all_time_performance;
+    /// Compare the user's last `days` daily values with buying and holding `basket` on the first of them
+    pub async fn benchmark(&self, user_id: i64, basket: &BenchmarkBasket, days: u32) -> Result<BenchmarkReport> {
+        let Some(price_client) = &self.price_client else {
+            return Err(BotError::internal("Benchmark prices aren't configured".to_string()).into());
+        };
+        
+        let (dates, values): (Vec<NaiveDate>, Vec<f64>) = {
+            let users = self.user_performance.read().await;
+            let recent: Vec<&DailyPerformance> = users.get(&user_id)
+                .map(|user| user.daily_performance.values().rev().take(days as usize).collect())
+                .unwrap_or_default();
+            recent.into_iter().rev()
+                .map(|day| (day.date, day.ending_value.to_f64().unwrap_or(0.0)))
+                .unzip()
+        };
+        if dates.len() < 2 {
+            return Err(BotError::not_found("Not enough daily history to benchmark yet".to_string()).into());
+        }
+        
+        let mut prices = HashMap::new();
+        for (mint, _) in &basket.weights {
+            let history = price_client.get_daily_history(mint, days + 1).await?;
+            let closes = history.into_iter()
+                .map(|point| (point.timestamp.date_naive(), point.price_usd))
+                .collect();
+            prices.insert(mint.clone(), closes);
+        }
+        
+        let benchmark = basket.buy_and_hold(&dates, &prices, values[0])?;
+        let report = BenchmarkReport::compute(&basket.label, &values, &benchmark, &self.metrics_calculator)?;
+        
+        let mut data = self.benchmark_data.write().await;
+        if *basket == BenchmarkBasket::sol() {
+            data.sol_returns = daily_returns(&benchmark);
+        } else {
+            data.custom_benchmark = Some(daily_returns(&benchmark));
+        }
+        data.last_updated = Utc::now();
+        
+        Ok(report)
+    }
+    
+    /// Detailed analytics of one user's realized trades
+    pub async fn generate_analytics_report(&self, user_id: i64) -> Result<AnalyticsReport> {
+        // Before taking the snapshots, which the benchmark reads too
+        let benchmark = match self.price_client {
+            Some(_) => match self.benchmark(user_id, &BenchmarkBasket::sol(), 7).await {
+                Ok(report) => Some(report),
+                Err(e) => {
+                    debug!("📊 Skipping SOL benchmark in analytics report: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let user = self.user_performance.read().await.get(&user_id).cloned().unwrap_or_default();
+        let all_time = &user.all_time_performance;
         
         // Calculate various metrics
+        let days: Vec<DailyPerformance> = user.daily_performance.values().cloned().collect();
+        let snapshots = daily_snapshots(&days);
+        let returns = snapshot_returns(&snapshots);
+        let calculator = &self.metrics_calculator;
+        let max_drawdown = calculator.calculate_drawdown(&snapshots);
+        let annualized_return = calculator.calculate_annualized_return(&snapshots);
         let risk_metrics = RiskMetrics {
-            value_at_risk_95: self.calculate_var(&cache.daily_performance, 0.95),
-            conditional_var_95: self.calculate_cvar(&cache.daily_performance, 0.95),
-            max_drawdown: all_time.max_drawdown,
-            current_drawdown: all_time.current_drawdown,
-            downside_deviation: self.calculate_downside_deviation(&cache.daily_performance),
-            upside_potential_ratio: self.calculate_upside_potential_ratio(&cache.daily_performance),
+            value_at_risk_95: self.calculate_var(&user.daily_performance, 0.95),
+            conditional_var_95: self.calculate_cvar(&user.daily_performance, 0.95),
+            calmar_ratio: annualized_return.zip(max_drawdown.as_ref())
+                .and_then(|(annual, drawdown)| calculator.calculate_calmar_ratio(annual, drawdown.percentage)),
+            max_drawdown,
+            current_drawdown: calculator.calculate_current_drawdown(&snapshots),
+            downside_deviation: self.calculate_downside_deviation(&user.daily_performance),
+            upside_potential_ratio: self.calculate_upside_potential_ratio(&user.daily_performance),
+            sharpe_ratio: calculator.calculate_sharpe_ratio(&returns, PERIODS_PER_YEAR),
+            sortino_ratio: calculator.calculate_sortino_ratio(&returns, PERIODS_PER_YEAR),
+            annualized_return,
+            days: returns.len(),
         };
         
         let efficiency_metrics = EfficiencyMetrics {
@@ -528,14 +974,21 @@ impl PerformanceTracker {
             kelly_criterion: self.calculate_kelly_criterion(all_time.overall_win_rate, all_time.average_win, all_time.average_loss),
         };
         
+        let tag_breakdown = match &self.journal {
+            Some(journal) => journal.breakdown(user_id).await,
+            None => Vec::new(),
+        };
+        
         Ok(AnalyticsReport {
             generated_at: Utc::now(),
             all_time_performance: all_time.clone(),
             risk_metrics,
             efficiency_metrics,
-            best_performing_month: self.find_best_month(&cache.monthly_performance),
-            worst_performing_month: self.find_worst_month(&cache.monthly_performance),
-            consistency_score: self.calculate_consistency_score(&cache.daily_performance),
+            best_performing_month: self.find_best_month(&user.daily_performance),
+            worst_performing_month: self.find_worst_month(&user.daily_performance),
+            consistency_score: self.calculate_consistency_score(&user.daily_performance),
+            tag_breakdown,
+            benchmark,
         })
     }
     
@@ -547,27 +1000,56 @@ impl PerformanceTracker {
     
     async fn update_performance_metrics(&self, trade: &TradeRecord) -> Result<()> {
         let mut cache = self.performance_cache.write().await;
+        let value_before = cache.all_time_performance.current_value;
+        cache.all_time_performance.record(trade);
         
-        // Update all-time metrics
-        cache.all_time_performance.total_trades += 1;
-        if trade.pnl > Decimal::ZERO {
-            cache.all_time_performance.winning_trades += 1;
-        } else {
-            cache.all_time_performance.losing_trades += 1;
-        }
-        
-        // Update current value
-        cache.all_time_performance.current_value += trade.pnl;
-        
-        // Recalculate metrics
-        cache.all_time_performance.overall_win_rate = 
-            cache.all_time_performance.winning_trades as f64 / cache.all_time_performance.total_trades as f64;
+        // Realized PnL lands on the day and ISO week the trade closed
+        record_day(&mut cache.daily_performance, trade, value_before);
+        self.update_weekly_performance(&mut cache, trade.timestamp.date_naive(), value_before);
         
         cache.last_updated = Utc::now();
         
         Ok(())
     }
     
+    /// Rebuild the week containing `day` from its daily entries
+    fn update_weekly_performance(&self, cache: &mut PerformanceCache, day: NaiveDate, value_before: Decimal) {
+        let week = day.iso_week();
+        let start_date = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+        let end_date = start_date + Duration::days(6);
+        let days: Vec<DailyPerformance> = cache.daily_performance
+            .range(start_date..=end_date)
+            .map(|(_, daily)| daily.clone())
+            .collect();
+        let returns: Vec<f64> = days.iter().map(|d| d.daily_return_percentage).collect();
+        let week_returns = snapshot_returns(&daily_snapshots(&days));
+        let weekly_calculator = self.metrics_calculator.weekly();
+        let by_return = |a: &&DailyPerformance, b: &&DailyPerformance| a.daily_return.cmp(&b.daily_return);
+        
+        let identifier = format!("{}-W{:02}", week.year(), week.week());
+        let starting_value = cache.weekly_performance.get(&identifier)
+            .map(|w| w.starting_value)
+            .unwrap_or(value_before);
+        let weekly_return: Decimal = days.iter().map(|d| d.daily_return).sum();
+        
+        cache.weekly_performance.insert(identifier.clone(), WeeklyPerformance {
+            week_identifier: identifier,
+            start_date,
+            end_date,
+            starting_value,
+            ending_value: starting_value + weekly_return,
+            weekly_return,
+            weekly_return_percentage: percentage_of(weekly_return, starting_value),
+            total_trades: days.iter().map(|d| d.trades_executed).sum(),
+            win_rate: self.calculate_win_rate(&days),
+            average_daily_return: returns.iter().sum::<f64>() / returns.len().max(1) as f64,
+            best_day: days.iter().max_by(by_return).map(|d| d.date),
+            worst_day: days.iter().min_by(by_return).map(|d| d.date),
+            sharpe_ratio: weekly_calculator.calculate_sharpe_ratio(&week_returns, PERIODS_PER_YEAR),
+            sortino_ratio: weekly_calculator.calculate_sortino_ratio(&week_returns, PERIODS_PER_YEAR),
+        });
+    }
+    
     fn calculate_win_rate(&self, daily_data: &[DailyPerformance]) -> f64 {
         let total_trades: u32 = daily_data.iter().map(|d| d.trades_executed).sum();
         let winning_trades: u32 = daily_data.iter().map(|d| d.winning_trades).sum();
@@ -675,16 +1157,16 @@ impl PerformanceTracker {
         (p * b - q) / b
     }
     
-    fn find_best_month(&self, monthly_performance: &BTreeMap<String, MonthlyPerformance>) -> Option<String> {
-        monthly_performance.iter()
-            .max_by(|a, b| a.1.monthly_return_percentage.partial_cmp(&b.1.monthly_return_percentage).unwrap())
-            .map(|(month, _)| month.clone())
+    fn find_best_month(&self, daily_performance: &BTreeMap<NaiveDate, DailyPerformance>) -> Option<String> {
+        monthly_returns(daily_performance).into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1))
+            .map(|(month, _)| month)
     }
     
-    fn find_worst_month(&self, monthly_performance: &BTreeMap<String, MonthlyPerformance>) -> Option<String> {
-        monthly_performance.iter()
-            .min_by(|a, b| a.1.monthly_return_percentage.partial_cmp(&b.1.monthly_return_percentage).unwrap())
-            .map(|(month, _)| month.clone())
+    fn find_worst_month(&self, daily_performance: &BTreeMap<NaiveDate, DailyPerformance>) -> Option<String> {
+        monthly_returns(daily_performance).into_iter()
+            .min_by(|a, b| a.1.cmp(&b.1))
+            .map(|(month, _)| month)
     }
     
     fn calculate_consistency_score(&self, daily_performance: &BTreeMap<NaiveDate, DailyPerformance>) -> f64 {
@@ -714,9 +1196,10 @@ pub struct PerformanceReport {
     pub ending_value: Decimal,
     pub total_return: Decimal,
     pub total_return_percentage: f64,
-    pub sharpe_ratio: f64,
-    pub sortino_ratio: f64,
-    pub max_drawdown: f64,
+    /// `None` until the range holds enough days to be meaningful
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    pub max_drawdown: Option<Drawdown>,
     pub total_trades: u32,
     pub win_rate: f64,
     pub daily_performance: Vec<DailyPerformance>,
@@ -746,6 +1229,12 @@ pub struct AnalyticsReport {
     pub best_performing_month: Option<String>,
     pub worst_performing_month: Option<String>,
     pub consistency_score: f64,
+    /// Win rate and average return by journal tag
+    #[serde(default)]
+    pub tag_breakdown: Vec<TagBreakdown>,
+    /// The week against holding SOL, when prices were available
+    #[serde(default)]
+    pub benchmark: Option<BenchmarkReport>,
 }
 
 /// Risk-related metrics
@@ -753,10 +1242,58 @@ pub struct AnalyticsReport {
 pub struct RiskMetrics {
     pub value_at_risk_95: f64,
     pub conditional_var_95: f64,
-    pub max_drawdown: f64,
-    pub current_drawdown: f64,
+    /// Ratios and drawdowns are `None` until there are enough days of history
+    pub max_drawdown: Option<Drawdown>,
+    pub current_drawdown: Option<f64>,
     pub downside_deviation: f64,
     pub upside_potential_ratio: f64,
+    #[serde(default)]
+    pub sharpe_ratio: Option<f64>,
+    #[serde(default)]
+    pub sortino_ratio: Option<f64>,
+    #[serde(default)]
+    pub calmar_ratio: Option<f64>,
+    /// In percent
+    #[serde(default)]
+    pub annualized_return: Option<f64>,
+    /// Daily returns the metrics were computed from
+    #[serde(default)]
+    pub days: usize,
+}
+
+impl RiskMetrics {
+    /// Only the metrics that could be computed; missing ones are left out rather than shown as zero
+    pub fn render(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(annual) = self.annualized_return {
+            lines.push(format!("Annualized return: {:+.1}%", annual));
+        }
+        if let Some(sharpe) = self.sharpe_ratio {
+            lines.push(format!("Sharpe: {:.2}", sharpe));
+        }
+        if let Some(sortino) = self.sortino_ratio {
+            lines.push(format!("Sortino: {:.2}", sortino));
+        }
+        if let Some(drawdown) = &self.max_drawdown {
+            if drawdown.percentage > 0.0 {
+                lines.push(format!(
+                    "Max drawdown: {:.1}% ({} → {})",
+                    drawdown.percentage, drawdown.peak_date.format("%b %d"), drawdown.trough_date.format("%b %d")
+                ));
+            }
+        }
+        if let Some(current) = self.current_drawdown.filter(|current| *current > 0.0) {
+            lines.push(format!("Current drawdown: {:.1}%", current));
+        }
+        if let Some(calmar) = self.calmar_ratio {
+            lines.push(format!("Calmar: {:.2}", calmar));
+        }
+        
+        if lines.is_empty() {
+            return format!("📉 Not enough daily history for risk metrics yet ({} days so far).", self.days);
+        }
+        format!("📉 Risk over {} days\n\n{}", self.days, lines.join("\n"))
+    }
 }
 
 /// Trading efficiency metrics
@@ -0,0 +1,351 @@
+use chrono::{DateTime, Datelike, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::db::Database;
+use crate::errors::{BotError, Result};
+use crate::trading::{
+    CopyTradeExecution, CopyTradeStatus, CopyTradeType, ExecutionReport, LendDirection, LendingOperation, Order,
+    OrderExecution, OrderSide, OrderType, TokenResolver,
+};
+
+use super::journal::csv_field;
+use super::performance_tracker::TradeRecord;
+
+pub const LEDGER_HEADER: &str = "timestamp,source,mint,symbol,side,sol_amount,token_amount,price_usd,fee_sol,tx_signature";
+
+/// Where a ledger row came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedgerSource {
+    Trade,
+    Order,
+    Copy,
+    Lending,
+}
+
+impl LedgerSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Trade => "trade",
+            Self::Order => "order",
+            Self::Copy => "copy",
+            Self::Lending => "lending",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedgerSide {
+    Buy,
+    Sell,
+    /// Tokens supplied to a lending vault
+    Deposit,
+    /// Tokens taken back out of a lending vault
+    Withdraw,
+}
+
+impl LedgerSide {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Buy => "buy",
+            Self::Sell => "sell",
+            Self::Deposit => "deposit",
+            Self::Withdraw => "withdraw",
+        }
+    }
+}
+
+/// One fill in the unified trade history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub timestamp: DateTime<Utc>,
+    pub source: LedgerSource,
+    pub mint: String,
+    pub symbol: String,
+    pub side: LedgerSide,
+    /// SOL paid for a buy, received for a sell
+    pub sol_amount: f64,
+    /// Unknown for copies whose fill price wasn't reported
+    pub token_amount: Option<f64>,
+    /// Token price in USD when it filled, where the source recorded one
+    pub price_usd: Option<f64>,
+    pub fee_sol: f64,
+    pub tx_signature: Option<String>,
+}
+
+fn fee_sol(report: &ExecutionReport) -> f64 {
+    report.fees.as_ref()
+        .map(|f| f.network_fee_sol + f.platform_fee_sol + f.priority_fee_lamports as f64 / 1e9)
+        .unwrap_or(0.0)
+}
+
+impl LedgerEntry {
+    /// A realized trade; records priced only on entry are buys
+    pub fn from_trade_record(record: &TradeRecord) -> Self {
+        let symbol = record.token_pair.split('/').next().unwrap_or(&record.token_pair).to_string();
+        let mint = TokenResolver::resolve(&symbol).unwrap_or_else(|_| symbol.clone());
+        let quantity = record.quantity.to_f64().unwrap_or(0.0);
+        let (side, price_sol) = if record.exit_price.is_zero() {
+            (LedgerSide::Buy, record.entry_price)
+        } else {
+            (LedgerSide::Sell, record.exit_price)
+        };
+        // Bot trades are keyed by their signature; imports and retried fills by a prefixed key
+        let tx_signature = (!record.trade_id.contains(':')).then(|| record.trade_id.clone());
+
+        Self {
+            timestamp: record.timestamp,
+            source: LedgerSource::Trade,
+            mint,
+            symbol,
+            side,
+            sol_amount: quantity * price_sol.to_f64().unwrap_or(0.0),
+            token_amount: Some(quantity),
+            price_usd: None,
+            fee_sol: record.fees.to_f64().unwrap_or(0.0),
+            tx_signature,
+        }
+    }
+
+    /// A filled order; failed attempts moved nothing and are skipped
+    pub fn from_order_execution(order: &Order, execution: &OrderExecution) -> Option<Self> {
+        if !execution.success {
+            return None;
+        }
+        let side = match &order.order_type {
+            OrderType::Limit { side: OrderSide::Buy, .. } | OrderType::TrailingStop { side: OrderSide::Buy, .. } => LedgerSide::Buy,
+            _ => LedgerSide::Sell,
+        };
+        let token_amount = execution.amount_executed.to_f64().unwrap_or(0.0);
+
+        Some(Self {
+            timestamp: execution.executed_at,
+            source: LedgerSource::Order,
+            mint: order.token_mint.clone(),
+            symbol: TokenResolver::get_symbol(&order.token_mint),
+            side,
+            sol_amount: token_amount * execution.report.realized_price_sol().unwrap_or(0.0),
+            token_amount: Some(token_amount),
+            price_usd: execution.price_at_execution.to_f64(),
+            fee_sol: fee_sol(&execution.report),
+            tx_signature: execution.transaction_signature.clone(),
+        })
+    }
+
+    /// A copy that went through for the follower
+    pub fn from_copy_execution(execution: &CopyTradeExecution) -> Option<Self> {
+        if execution.status != CopyTradeStatus::Success {
+            return None;
+        }
+        let side = match execution.trade_type {
+            CopyTradeType::Buy => LedgerSide::Buy,
+            _ => LedgerSide::Sell,
+        };
+        let token_amount = execution.report.realized_price_sol()
+            .filter(|price| *price > 0.0)
+            .map(|price| execution.copied_amount_sol / price);
+
+        Some(Self {
+            timestamp: execution.timestamp,
+            source: LedgerSource::Copy,
+            mint: execution.token_address.clone(),
+            symbol: execution.token_symbol.clone(),
+            side,
+            sol_amount: execution.copied_amount_sol,
+            token_amount,
+            price_usd: (execution.execution_price > 0.0).then_some(execution.execution_price),
+            fee_sol: execution.fee_paid_sol + fee_sol(&execution.report),
+            tx_signature: None,
+        })
+    }
+
+    /// A Jupiter Lend deposit or withdrawal; only SOL vaults have a SOL amount
+    pub fn from_lending_operation(operation: &LendingOperation) -> Self {
+        Self {
+            timestamp: operation.timestamp,
+            source: LedgerSource::Lending,
+            mint: operation.mint.clone(),
+            symbol: operation.symbol.clone(),
+            side: match operation.direction {
+                LendDirection::Deposit => LedgerSide::Deposit,
+                LendDirection::Withdraw => LedgerSide::Withdraw,
+            },
+            sol_amount: operation.sol_amount(),
+            token_amount: Some(operation.amount),
+            price_usd: None,
+            fee_sol: 0.0,
+            tx_signature: Some(operation.tx_signature.clone()),
+        }
+    }
+
+    fn csv_row(&self) -> String {
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            self.timestamp.to_rfc3339(),
+            self.source.label(),
+            csv_field(&self.mint),
+            csv_field(&self.symbol),
+            self.side.label(),
+            self.sol_amount,
+            optional(self.token_amount),
+            optional(self.price_usd),
+            self.fee_sol,
+            csv_field(self.tx_signature.as_deref().unwrap_or_default()),
+        )
+    }
+}
+
+/// Merge every source into one chronological ledger, optionally for a single year
+pub fn build_ledger(
+    records: &[TradeRecord],
+    orders: &[(Order, Vec<OrderExecution>)],
+    copies: &[CopyTradeExecution],
+    lending: &[LendingOperation],
+    year: Option<i32>,
+) -> Vec<LedgerEntry> {
+    let mut ledger: Vec<LedgerEntry> = records.iter()
+        .map(LedgerEntry::from_trade_record)
+        .chain(orders.iter().flat_map(|(order, executions)| {
+            executions.iter().filter_map(move |execution| LedgerEntry::from_order_execution(order, execution))
+        }))
+        .chain(copies.iter().filter_map(LedgerEntry::from_copy_execution))
+        .chain(lending.iter().map(LedgerEntry::from_lending_operation))
+        .filter(|entry| year.map_or(true, |year| entry.timestamp.year() == year))
+        .collect();
+    ledger.sort_by_key(|entry| entry.timestamp);
+    ledger
+}
+
+/// Write the ledger as CSV one row at a time; returns the rows written
+pub fn write_csv<'a, W: Write>(entries: impl IntoIterator<Item = &'a LedgerEntry>, mut out: W) -> io::Result<usize> {
+    writeln!(out, "{}", LEDGER_HEADER)?;
+    let mut rows = 0;
+    for entry in entries {
+        out.write_all(entry.csv_row().as_bytes())?;
+        rows += 1;
+    }
+    out.flush()?;
+    Ok(rows)
+}
+
+/// Write the ledger as a JSON array one entry at a time; returns the entries written
+pub fn write_json<'a, W: Write>(entries: impl IntoIterator<Item = &'a LedgerEntry>, mut out: W) -> io::Result<usize> {
+    out.write_all(b"[")?;
+    let mut rows = 0;
+    for entry in entries {
+        if rows > 0 {
+            out.write_all(b",\n")?;
+        }
+        serde_json::to_writer(&mut out, entry)?;
+        rows += 1;
+    }
+    out.write_all(b"]\n")?;
+    out.flush()?;
+    Ok(rows)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerFormat {
+    Csv,
+    Json,
+}
+
+impl LedgerFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// A written export; the temp file goes away with it
+#[derive(Debug)]
+pub struct LedgerFile {
+    path: PathBuf,
+    pub rows: usize,
+}
+
+impl LedgerFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for LedgerFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            warn!("🧾 Failed to remove export {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// `/export_trades`: the user's trades, order fills, copies and lending moves from the database
+pub struct TradeHistoryExporter {
+    database: Arc<Database>,
+}
+
+impl TradeHistoryExporter {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    pub async fn ledger(&self, user_id: i64, year: Option<i32>) -> Result<Vec<LedgerEntry>> {
+        let records: Vec<TradeRecord> = Self::parse_rows(self.database.get_user_trade_records(user_id).await?, "trade");
+        let mut orders = Vec::new();
+        for order in Self::parse_rows::<Order>(self.database.get_user_orders(user_id).await?, "order") {
+            let executions = Self::parse_rows(self.database.get_order_executions(&order.order_id).await?, "execution");
+            orders.push((order, executions));
+        }
+        let copies: Vec<CopyTradeExecution> = Self::parse_rows(self.database.get_copy_executions(user_id).await?, "copy");
+        let lending: Vec<LendingOperation> = Self::parse_rows(self.database.get_lending_operations(user_id).await?, "lending operation");
+
+        Ok(build_ledger(&records, &orders, &copies, &lending, year))
+    }
+
+    /// Stream the ledger into a temp file; `None` when there's nothing to export
+    pub async fn export(&self, user_id: i64, year: Option<i32>, format: LedgerFormat) -> Result<Option<LedgerFile>> {
+        let ledger = self.ledger(user_id, year).await?;
+        if ledger.is_empty() {
+            return Ok(None);
+        }
+
+        let path = std::env::temp_dir().join(format!("trades-{}-{}.{}", user_id, uuid::Uuid::new_v4(), format.extension()));
+        let file = tokio::task::spawn_blocking(move || -> io::Result<LedgerFile> {
+            let out = BufWriter::new(File::create(&path)?);
+            // Owned from here so a failed write still cleans up
+            let mut file = LedgerFile { path, rows: 0 };
+            file.rows = match format {
+                LedgerFormat::Csv => write_csv(&ledger, out)?,
+                LedgerFormat::Json => write_json(&ledger, out)?,
+            };
+            Ok(file)
+        })
+        .await
+        .map_err(|e| BotError::internal(format!("Export task failed: {}", e)))?
+        .map_err(|e| BotError::internal(format!("Failed to write export: {}", e)))?;
+
+        debug!("🧾 Exported {} ledger rows for user {}", file.rows, user_id);
+        Ok(Some(file))
+    }
+
+    fn parse_rows<T: serde::de::DeserializeOwned>(rows: Vec<String>, kind: &str) -> Vec<T> {
+        rows.iter()
+            .filter_map(|row| match serde_json::from_str(row) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    warn!("🧾 Skipping unreadable stored {}: {}", kind, e);
+                    None
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,627 @@
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::errors::{BotError, Result};
+use crate::trading::{ExecutionReport, TokenResolver, TradeProvenance};
+use super::cost_basis::{CostBasisBook, LotTrade};
+use super::journal::TradeJournal;
+use super::performance_tracker::{TradeRecord, TradeType};
+
+/// Rows kept from one upload; the rest of a larger file is ignored
+pub const MAX_IMPORT_ROWS: usize = 50_000;
+
+/// Header of the documented import format
+pub const NATIVE_HEADER: &str = "timestamp,side,token,token_amount,sol_amount,fee_sol,signature";
+
+/// Solana mainnet beta launch (2020-03-16); nothing traded before it
+const EARLIEST_TRADE_SECS: i64 = 1_584_316_800;
+
+/// Headers a file can come with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportFormat {
+    /// `NATIVE_HEADER`; `token` takes a symbol or a mint
+    Native,
+    /// Trojan trade history export
+    Trojan,
+    /// Photon trade history export
+    Photon,
+}
+
+/// Header names per column; `None` where the format has no such column
+struct ColumnNames {
+    timestamp: &'static str,
+    side: &'static str,
+    token: &'static str,
+    mint: Option<&'static str>,
+    token_amount: &'static str,
+    sol_amount: &'static str,
+    fee: &'static str,
+    signature: &'static str,
+}
+
+/// Positions of each column in the uploaded header
+#[derive(Debug, Clone, Copy)]
+struct ColumnMap {
+    timestamp: usize,
+    side: usize,
+    token: usize,
+    mint: Option<usize>,
+    token_amount: usize,
+    sol_amount: usize,
+    fee: Option<usize>,
+    signature: Option<usize>,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 3] = [ImportFormat::Native, ImportFormat::Trojan, ImportFormat::Photon];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Native => "standard CSV",
+            Self::Trojan => "Trojan export",
+            Self::Photon => "Photon export",
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Native => "native",
+            Self::Trojan => "trojan",
+            Self::Photon => "photon",
+        }
+    }
+
+    fn columns(&self) -> ColumnNames {
+        match self {
+            Self::Native => ColumnNames {
+                timestamp: "timestamp",
+                side: "side",
+                token: "token",
+                mint: None,
+                token_amount: "token_amount",
+                sol_amount: "sol_amount",
+                fee: "fee_sol",
+                signature: "signature",
+            },
+            Self::Trojan => ColumnNames {
+                timestamp: "date",
+                side: "action",
+                token: "token name",
+                mint: Some("token address"),
+                token_amount: "token amount",
+                sol_amount: "sol amount",
+                fee: "fee (sol)",
+                signature: "tx",
+            },
+            Self::Photon => ColumnNames {
+                timestamp: "time",
+                side: "type",
+                token: "symbol",
+                mint: Some("mint"),
+                token_amount: "amount",
+                sol_amount: "total_sol",
+                fee: "fee",
+                signature: "signature",
+            },
+        }
+    }
+
+    /// Match a header row; fee and signature columns are optional
+    fn detect(header: &[String]) -> Option<(Self, ColumnMap)> {
+        let position = |name: &str| header.iter().position(|h| h == name);
+        Self::ALL.iter().find_map(|format| {
+            let names = format.columns();
+            let map = ColumnMap {
+                timestamp: position(names.timestamp)?,
+                side: position(names.side)?,
+                token: position(names.token)?,
+                mint: match names.mint {
+                    Some(name) => Some(position(name)?),
+                    None => None,
+                },
+                token_amount: position(names.token_amount)?,
+                sol_amount: position(names.sol_amount)?,
+                fee: position(names.fee),
+                signature: position(names.signature),
+            };
+            Some((*format, map))
+        })
+    }
+}
+
+/// Why a row was left out of the import
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    Malformed(String),
+    UnknownToken(String),
+    UnknownSide(String),
+    BadNumber { column: &'static str, value: String },
+    NonPositiveAmount(&'static str),
+    BadTimestamp(String),
+    DuplicateInFile,
+    AlreadyRecorded,
+}
+
+impl SkipReason {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Malformed(why) => format!("malformed row ({})", why),
+            Self::UnknownToken(token) => format!("unknown token {}", token),
+            Self::UnknownSide(side) => format!("side must be buy or sell, got {}", side),
+            Self::BadNumber { column, value } => format!("{} is not a number: {}", column, value),
+            Self::NonPositiveAmount(column) => format!("{} must be positive", column),
+            Self::BadTimestamp(value) => format!("timestamp out of range or unreadable: {}", value),
+            Self::DuplicateInFile => "duplicate of an earlier row".to_string(),
+            Self::AlreadyRecorded => "already recorded".to_string(),
+        }
+    }
+
+    /// Short name for grouping in the preview
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Malformed(_) => "malformed",
+            Self::UnknownToken(_) => "unknown token",
+            Self::UnknownSide(_) => "unknown side",
+            Self::BadNumber { .. } => "bad number",
+            Self::NonPositiveAmount(_) => "non-positive amount",
+            Self::BadTimestamp(_) => "bad timestamp",
+            Self::DuplicateInFile => "duplicate in file",
+            Self::AlreadyRecorded => "already recorded",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedRow {
+    /// 1-based line in the uploaded file
+    pub line: usize,
+    pub reason: SkipReason,
+}
+
+/// A validated row, ready to record
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTrade {
+    pub line: usize,
+    pub timestamp: DateTime<Utc>,
+    pub is_buy: bool,
+    pub mint: String,
+    pub symbol: String,
+    pub token_amount: f64,
+    pub sol_amount: f64,
+    pub fee_sol: f64,
+    pub signature: Option<String>,
+    /// Identity of the trade independent of the file's format
+    pub row_hash: String,
+}
+
+impl ImportedTrade {
+    fn hash(timestamp: DateTime<Utc>, is_buy: bool, mint: &str, token_amount: f64, sol_amount: f64) -> String {
+        let key = format!("{}|{}|{}|{:.9}|{:.9}", timestamp.timestamp(), is_buy, mint, token_amount, sol_amount);
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+}
+
+/// What an upload would import, shown before the user confirms
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    pub format: ImportFormat,
+    pub rows: Vec<ImportedTrade>,
+    pub skipped: Vec<SkippedRow>,
+    /// The file had more than `MAX_IMPORT_ROWS` rows
+    pub truncated: bool,
+}
+
+impl ImportPreview {
+    pub fn date_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let first = self.rows.iter().map(|r| r.timestamp).min()?;
+        let last = self.rows.iter().map(|r| r.timestamp).max()?;
+        Some((first, last))
+    }
+
+    pub fn tokens(&self) -> Vec<String> {
+        self.rows.iter().map(|r| r.symbol.clone()).collect::<BTreeSet<_>>().into_iter().collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = format!("📥 Import preview ({})\n\nRows to import: {}", self.format.label(), self.rows.len());
+        if let Some((first, last)) = self.date_range() {
+            text.push_str(&format!("\nDates: {} → {}", first.format("%Y-%m-%d"), last.format("%Y-%m-%d")));
+        }
+        let tokens = self.tokens();
+        if !tokens.is_empty() {
+            let shown: Vec<&str> = tokens.iter().take(10).map(String::as_str).collect();
+            let more = tokens.len().saturating_sub(shown.len());
+            text.push_str(&format!("\nTokens: {}{}", shown.join(", "), if more > 0 { format!(" +{} more", more) } else { String::new() }));
+        }
+        if !self.skipped.is_empty() {
+            let mut counts: Vec<(&'static str, usize)> = Vec::new();
+            for row in &self.skipped {
+                match counts.iter_mut().find(|(kind, _)| *kind == row.reason.kind()) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((row.reason.kind(), 1)),
+                }
+            }
+            text.push_str(&format!("\n\nSkipped: {}", self.skipped.len()));
+            for (kind, count) in counts {
+                text.push_str(&format!("\n• {}: {}", kind, count));
+            }
+            for row in self.skipped.iter().take(5) {
+                text.push_str(&format!("\n  line {}: {}", row.line, row.reason.describe()));
+            }
+        }
+        if self.truncated {
+            text.push_str(&format!("\n\n⚠️ Only the first {} rows were read.", MAX_IMPORT_ROWS));
+        }
+        text.push_str("\n\nImported trades count toward cost basis and PnL, not the leaderboard or badges.");
+        text
+    }
+}
+
+/// What a confirmed import added
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportOutcome {
+    pub inserted: usize,
+    /// Rows recorded by another import since the preview
+    pub duplicates: usize,
+}
+
+/// Hashes and signatures already on record for a user
+#[derive(Debug, Clone, Default)]
+pub struct KnownTrades {
+    pub hashes: HashSet<String>,
+    pub signatures: HashSet<String>,
+}
+
+/// Stream-parse an uploaded history into a preview
+///
+/// Reads one line at a time so a large export never sits in memory as a
+/// whole; only validated rows are kept.
+pub async fn parse_trades<R: AsyncBufRead + Unpin>(
+    reader: R,
+    known: &KnownTrades,
+    now: DateTime<Utc>,
+) -> Result<ImportPreview> {
+    let mut lines = reader.lines();
+    let mut line_no = 0;
+
+    let header = loop {
+        line_no += 1;
+        match lines.next_line().await.map_err(|e| BotError::parsing(format!("Couldn't read the file: {}", e)))? {
+            Some(line) if line.trim().is_empty() => continue,
+            Some(line) => break line,
+            None => return Err(BotError::validation("The file is empty".to_string()).into()),
+        }
+    };
+    let header: Vec<String> = split_csv_line(header.trim_start_matches('\u{feff}'))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let Some((format, columns)) = ImportFormat::detect(&header) else {
+        return Err(BotError::validation(format!(
+            "Unrecognized columns. Use a Trojan or Photon export, or this header:\n{}",
+            NATIVE_HEADER
+        )).into());
+    };
+
+    let mut preview = ImportPreview { format, rows: Vec::new(), skipped: Vec::new(), truncated: false };
+    let mut seen_hashes = HashSet::new();
+    let mut seen_signatures = HashSet::new();
+
+    while let Some(line) = lines.next_line().await.map_err(|e| BotError::parsing(format!("Couldn't read the file: {}", e)))? {
+        line_no += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if preview.rows.len() + preview.skipped.len() >= MAX_IMPORT_ROWS {
+            preview.truncated = true;
+            break;
+        }
+
+        let row = match parse_row(&line, line_no, &columns, now) {
+            Ok(row) => row,
+            Err(reason) => {
+                preview.skipped.push(SkippedRow { line: line_no, reason });
+                continue;
+            }
+        };
+
+        let signature = row.signature.as_deref();
+        let reason = if known.hashes.contains(&row.row_hash) || signature.is_some_and(|s| known.signatures.contains(s)) {
+            Some(SkipReason::AlreadyRecorded)
+        } else if !seen_hashes.insert(row.row_hash.clone()) || signature.is_some_and(|s| !seen_signatures.insert(s.to_string())) {
+            Some(SkipReason::DuplicateInFile)
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => preview.skipped.push(SkippedRow { line: line_no, reason }),
+            None => preview.rows.push(row),
+        }
+    }
+
+    Ok(preview)
+}
+
+fn parse_row(line: &str, line_no: usize, columns: &ColumnMap, now: DateTime<Utc>) -> std::result::Result<ImportedTrade, SkipReason> {
+    let fields = split_csv_line(line).ok_or_else(|| SkipReason::Malformed("unterminated quote".to_string()))?;
+    let field = |index: usize| fields.get(index).map(|f| f.trim()).unwrap_or("");
+    let optional = |index: Option<usize>| index.map(field).filter(|f| !f.is_empty());
+
+    let required = [columns.timestamp, columns.side, columns.token, columns.token_amount, columns.sol_amount];
+    if required.iter().any(|&index| index >= fields.len()) {
+        return Err(SkipReason::Malformed(format!("{} columns", fields.len())));
+    }
+
+    let timestamp = parse_timestamp(field(columns.timestamp))
+        .filter(|t| t.timestamp() >= EARLIEST_TRADE_SECS && *t <= now + Duration::minutes(5))
+        .ok_or_else(|| SkipReason::BadTimestamp(field(columns.timestamp).to_string()))?;
+
+    let is_buy = match field(columns.side).to_lowercase().as_str() {
+        "buy" | "b" | "bought" => true,
+        "sell" | "s" | "sold" => false,
+        other => return Err(SkipReason::UnknownSide(other.to_string())),
+    };
+
+    let token = optional(columns.mint).unwrap_or(field(columns.token));
+    let mint = TokenResolver::resolve(token).map_err(|_| SkipReason::UnknownToken(token.to_string()))?;
+    let symbol = match field(columns.token) {
+        symbol if !symbol.is_empty() && symbol != mint => symbol.to_uppercase(),
+        _ => TokenResolver::get_symbol(&mint),
+    };
+
+    let token_amount = parse_amount(field(columns.token_amount), "token amount")?;
+    let sol_amount = parse_amount(field(columns.sol_amount), "SOL amount")?;
+    let fee_sol = match optional(columns.fee) {
+        Some(fee) => parse_number(fee, "fee").and_then(|fee| {
+            if fee < 0.0 { Err(SkipReason::NonPositiveAmount("fee")) } else { Ok(fee) }
+        })?,
+        None => 0.0,
+    };
+    let signature = optional(columns.signature).map(str::to_string);
+
+    Ok(ImportedTrade {
+        line: line_no,
+        row_hash: ImportedTrade::hash(timestamp, is_buy, &mint, token_amount, sol_amount),
+        timestamp,
+        is_buy,
+        mint,
+        symbol,
+        token_amount,
+        sol_amount,
+        fee_sol,
+        signature,
+    })
+}
+
+fn parse_number(value: &str, column: &'static str) -> std::result::Result<f64, SkipReason> {
+    value.replace(',', "")
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or_else(|| SkipReason::BadNumber { column, value: value.to_string() })
+}
+
+fn parse_amount(value: &str, column: &'static str) -> std::result::Result<f64, SkipReason> {
+    let amount = parse_number(value, column)?;
+    if amount <= 0.0 {
+        return Err(SkipReason::NonPositiveAmount(column));
+    }
+    Ok(amount)
+}
+
+/// Unix seconds or milliseconds, RFC 3339, or a naive UTC date-time
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(number) = value.parse::<i64>() {
+        return if number > 100_000_000_000 {
+            Utc.timestamp_millis_opt(number).single()
+        } else {
+            Utc.timestamp_opt(number, 0).single()
+        };
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%d/%m/%Y %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|naive| naive.and_utc())
+}
+
+/// Split one CSV line, honouring quotes; `None` on an unterminated quote
+fn split_csv_line(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return None;
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+#[derive(Debug, Default)]
+struct UserImports {
+    records: Vec<TradeRecord>,
+    hashes: HashSet<String>,
+    signatures: HashSet<String>,
+}
+
+/// `/import trades`: upload, preview, confirm
+pub struct TradeImporter {
+    cost_basis: Arc<CostBasisBook>,
+    journal: Option<Arc<TradeJournal>>,
+    awaiting_upload: RwLock<HashSet<i64>>,
+    pending: RwLock<HashMap<i64, ImportPreview>>,
+    imported: RwLock<HashMap<i64, UserImports>>,
+}
+
+impl Default for TradeImporter {
+    fn default() -> Self {
+        Self::new(Arc::new(CostBasisBook::default()))
+    }
+}
+
+impl TradeImporter {
+    pub fn new(cost_basis: Arc<CostBasisBook>) -> Self {
+        Self {
+            cost_basis,
+            journal: None,
+            awaiting_upload: RwLock::new(HashSet::new()),
+            pending: RwLock::new(HashMap::new()),
+            imported: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Also skip rows whose signature closed a journaled position
+    pub fn with_journal(mut self, journal: Arc<TradeJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn cost_basis(&self) -> &Arc<CostBasisBook> {
+        &self.cost_basis
+    }
+
+    /// The user's next document is their history
+    pub async fn expect_upload(&self, user_id: i64) {
+        self.awaiting_upload.write().await.insert(user_id);
+    }
+
+    /// Whether a document from the user answers `/import trades`
+    pub async fn take_upload_request(&self, user_id: i64) -> bool {
+        self.awaiting_upload.write().await.remove(&user_id)
+    }
+
+    async fn known(&self, user_id: i64) -> KnownTrades {
+        let mut known = self.imported.read().await.get(&user_id)
+            .map(|imports| KnownTrades { hashes: imports.hashes.clone(), signatures: imports.signatures.clone() })
+            .unwrap_or_default();
+        if let Some(journal) = &self.journal {
+            known.signatures.extend(
+                journal.recent(user_id, usize::MAX).await
+                    .into_iter()
+                    .filter_map(|entry| entry.close.tx_signature),
+            );
+        }
+        known
+    }
+
+    /// Parse an upload and hold it until the user confirms or cancels
+    pub async fn preview<R: AsyncBufRead + Unpin>(&self, user_id: i64, reader: R) -> Result<ImportPreview> {
+        let known = self.known(user_id).await;
+        let preview = parse_trades(reader, &known, Utc::now()).await?;
+        self.pending.write().await.insert(user_id, preview.clone());
+        Ok(preview)
+    }
+
+    pub async fn cancel(&self, user_id: i64) -> bool {
+        self.pending.write().await.remove(&user_id).is_some()
+    }
+
+    /// Record the pending rows oldest first, so sells draw on earlier lots
+    pub async fn confirm(&self, user_id: i64) -> Result<ImportOutcome> {
+        let mut preview = self.pending.write().await.remove(&user_id)
+            .ok_or_else(|| BotError::not_found("No import waiting for confirmation".to_string()))?;
+        preview.rows.sort_by_key(|row| row.timestamp);
+
+        let mut imported = self.imported.write().await;
+        let imports = imported.entry(user_id).or_default();
+        let mut outcome = ImportOutcome { inserted: 0, duplicates: 0 };
+
+        for row in preview.rows {
+            let seen_signature = row.signature.as_ref().is_some_and(|s| imports.signatures.contains(s));
+            if seen_signature || !imports.hashes.insert(row.row_hash.clone()) {
+                outcome.duplicates += 1;
+                continue;
+            }
+            if let Some(signature) = &row.signature {
+                imports.signatures.insert(signature.clone());
+            }
+
+            let record = self.record_for(user_id, &row, preview.format).await;
+            imports.records.push(record);
+            outcome.inserted += 1;
+        }
+
+        info!("📥 User {} imported {} trades ({} duplicates)", user_id, outcome.inserted, outcome.duplicates);
+        Ok(outcome)
+    }
+
+    /// Feed the lot book and build the flagged record
+    async fn record_for(&self, user_id: i64, row: &ImportedTrade, format: ImportFormat) -> TradeRecord {
+        let to_decimal = |value: f64| Decimal::from_f64_retain(value).unwrap_or(Decimal::ZERO);
+        let price = row.sol_amount / row.token_amount;
+
+        let realized = self.cost_basis.apply(user_id, &LotTrade {
+            mint: row.mint.clone(),
+            is_buy: row.is_buy,
+            quantity: row.token_amount,
+            sol_amount: row.sol_amount,
+            fee_sol: row.fee_sol,
+            at: row.timestamp,
+            provenance: TradeProvenance::Imported,
+        }).await;
+
+        // Sells are priced against the cost of the lots they closed
+        let (entry_price, exit_price, pnl, pnl_percentage) = match &realized {
+            None => (price, 0.0, 0.0, 0.0),
+            Some(r) if r.quantity > 0.0 => (r.cost_sol / r.quantity, price, r.pnl_sol, r.pnl_percentage()),
+            Some(_) => (0.0, price, 0.0, 0.0),
+        };
+
+        TradeRecord {
+            trade_id: format!("import:{}", &row.row_hash[..16]),
+            timestamp: row.timestamp,
+            token_pair: format!("{}/SOL", row.symbol),
+            trade_type: TradeType::Swap,
+            entry_price: to_decimal(entry_price),
+            exit_price: to_decimal(exit_price),
+            quantity: to_decimal(row.token_amount),
+            pnl: to_decimal(pnl),
+            pnl_percentage,
+            fees: to_decimal(row.fee_sol),
+            holding_period: Duration::zero(),
+            strategy_used: format!("import:{}", format.code()),
+            risk_reward_ratio: 0.0,
+            execution: ExecutionReport::default(),
+            journal_tag: None,
+            provenance: TradeProvenance::Imported,
+        }
+    }
+
+    pub async fn records(&self, user_id: i64) -> Vec<TradeRecord> {
+        self.imported.read().await.get(&user_id).map(|i| i.records.clone()).unwrap_or_default()
+    }
+
+    /// Drop pending uploads; imported history goes too unless trade records are retained
+    pub async fn forget_user(&self, user_id: i64, keep_trades: bool) -> usize {
+        self.awaiting_upload.write().await.remove(&user_id);
+        self.pending.write().await.remove(&user_id);
+        if keep_trades {
+            return 0;
+        }
+        self.cost_basis.forget_user(user_id).await;
+        self.imported.write().await.remove(&user_id).map(|i| i.records.len()).unwrap_or(0)
+    }
+}
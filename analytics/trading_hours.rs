@@ -0,0 +1,218 @@
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::trading::TradeProvenance;
+use super::performance_tracker::TradeRecord;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Which end of a trade places it on the clock
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttributionTime {
+    #[default]
+    Entry,
+    Exit,
+}
+
+impl AttributionTime {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AttributionTime::Entry => "entry",
+            AttributionTime::Exit => "exit",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TradingHoursConfig {
+    pub attribute_by: AttributionTime,
+    /// Buckets with fewer trades are reported as insufficient data
+    pub min_samples: u32,
+    pub include_imported: bool,
+}
+
+impl Default for TradingHoursConfig {
+    fn default() -> Self {
+        Self {
+            attribute_by: AttributionTime::Entry,
+            min_samples: 5,
+            include_imported: false,
+        }
+    }
+}
+
+/// Realized trades that fell in one bucket
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BucketStats {
+    pub trades: u32,
+    pub wins: u32,
+    pub total_return_pct: f64,
+    /// SOL received on exit
+    pub volume_sol: f64,
+}
+
+impl BucketStats {
+    fn add(&mut self, return_pct: f64, volume_sol: f64) {
+        self.trades += 1;
+        if return_pct > 0.0 {
+            self.wins += 1;
+        }
+        self.total_return_pct += return_pct;
+        self.volume_sol += volume_sol;
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 { 0.0 } else { self.wins as f64 / self.trades as f64 * 100.0 }
+    }
+
+    pub fn average_return_pct(&self) -> f64 {
+        if self.trades == 0 { 0.0 } else { self.total_return_pct / self.trades as f64 }
+    }
+}
+
+/// Hour-of-day and day-of-week results in the trader's timezone
+///
+/// Always computed from the raw trades so a timezone change applies to all history.
+#[derive(Debug, Clone)]
+pub struct TradingHoursReport {
+    pub timezone: Tz,
+    pub attribute_by: AttributionTime,
+    pub min_samples: u32,
+    pub by_hour: [BucketStats; 24],
+    /// Monday first
+    pub by_weekday: [BucketStats; 7],
+    /// `[weekday][hour]`, Monday first
+    pub grid: [[BucketStats; 24]; 7],
+    pub trades: u32,
+    pub excluded_imported: u32,
+}
+
+impl TradingHoursReport {
+    pub fn compute<'a, I>(trades: I, timezone: Tz, config: &TradingHoursConfig) -> Self
+    where
+        I: IntoIterator<Item = &'a TradeRecord>,
+    {
+        let mut report = Self {
+            timezone,
+            attribute_by: config.attribute_by,
+            min_samples: config.min_samples,
+            by_hour: [BucketStats::default(); 24],
+            by_weekday: [BucketStats::default(); 7],
+            grid: [[BucketStats::default(); 24]; 7],
+            trades: 0,
+            excluded_imported: 0,
+        };
+
+        for trade in trades {
+            // Imported buys carry no exit; only realized trades count
+            if trade.exit_price.is_zero() {
+                continue;
+            }
+            if trade.provenance == TradeProvenance::Imported && !config.include_imported {
+                report.excluded_imported += 1;
+                continue;
+            }
+
+            let at = match config.attribute_by {
+                AttributionTime::Entry => trade.entry_time(),
+                AttributionTime::Exit => trade.timestamp,
+            };
+            let (day, hour) = Self::bucket(at, timezone);
+            let volume_sol = (trade.quantity * trade.exit_price).to_f64().unwrap_or(0.0);
+
+            report.by_hour[hour].add(trade.pnl_percentage, volume_sol);
+            report.by_weekday[day].add(trade.pnl_percentage, volume_sol);
+            report.grid[day][hour].add(trade.pnl_percentage, volume_sol);
+            report.trades += 1;
+        }
+
+        report
+    }
+
+    /// `(weekday index from Monday, hour)` of an instant in `timezone`
+    pub fn bucket(at: DateTime<Utc>, timezone: Tz) -> (usize, usize) {
+        let local = at.with_timezone(&timezone);
+        (local.weekday().num_days_from_monday() as usize, local.hour() as usize)
+    }
+
+    /// A grid cell, or None when it has too few trades to mean anything
+    pub fn cell(&self, weekday: Weekday, hour: usize) -> Option<&BucketStats> {
+        self.grid[weekday.num_days_from_monday() as usize]
+            .get(hour)
+            .filter(|stats| self.has_enough(stats))
+    }
+
+    pub fn has_enough(&self, stats: &BucketStats) -> bool {
+        stats.trades > 0 && stats.trades >= self.min_samples
+    }
+
+    pub fn best_hour(&self) -> Option<(usize, &BucketStats)> {
+        self.rank(&self.by_hour, true)
+    }
+
+    pub fn worst_hour(&self) -> Option<(usize, &BucketStats)> {
+        self.rank(&self.by_hour, false)
+    }
+
+    pub fn best_weekday(&self) -> Option<(Weekday, &BucketStats)> {
+        self.rank(&self.by_weekday, true).map(|(day, stats)| (WEEKDAYS[day], stats))
+    }
+
+    pub fn worst_weekday(&self) -> Option<(Weekday, &BucketStats)> {
+        self.rank(&self.by_weekday, false).map(|(day, stats)| (WEEKDAYS[day], stats))
+    }
+
+    /// Highest or lowest average return among buckets with enough trades
+    fn rank<'a>(&self, buckets: &'a [BucketStats], best: bool) -> Option<(usize, &'a BucketStats)> {
+        buckets.iter()
+            .enumerate()
+            .filter(|(_, stats)| self.has_enough(stats))
+            .max_by(|(_, a), (_, b)| {
+                let order = a.average_return_pct().total_cmp(&b.average_return_pct());
+                if best { order } else { order.reverse() }
+            })
+    }
+
+    /// Best and worst hours and days for `/stats`
+    pub fn render(&self) -> String {
+        let mut lines = vec![
+            format!("🕒 Trading hours ({}, by {} time)", self.timezone.name(), self.attribute_by.label()),
+            String::new(),
+        ];
+        if self.trades == 0 {
+            lines.push("No realized trades yet.".to_string());
+            return lines.join("\n");
+        }
+
+        let line = |label: &str, bucket: Option<(String, BucketStats)>| match bucket {
+            Some((name, stats)) => format!(
+                "{}: {} · {} trades · {:.0}% win · avg {:+.1}% · {:.2} SOL",
+                label, name, stats.trades, stats.win_rate(), stats.average_return_pct(), stats.volume_sol
+            ),
+            None => format!("{}: insufficient data", label),
+        };
+        let hour_name = |(hour, stats): (usize, &BucketStats)| (format!("{:02}:00–{:02}:59", hour, hour), *stats);
+        let day_name = |(day, stats): (Weekday, &BucketStats)| (day.to_string(), *stats);
+
+        lines.push(line("Best hour", self.best_hour().map(hour_name)));
+        lines.push(line("Worst hour", self.worst_hour().map(hour_name)));
+        lines.push(line("Best day", self.best_weekday().map(day_name)));
+        lines.push(line("Worst day", self.worst_weekday().map(day_name)));
+        lines.push(String::new());
+        lines.push(format!("{} trades; hours and days under {} trades are left out.", self.trades, self.min_samples));
+        if self.excluded_imported > 0 {
+            lines.push(format!("{} imported trades excluded (/stats imported to include).", self.excluded_imported));
+        }
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,255 @@
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::errors::{BotError, Result};
+
+/// Typed client for the Convex HTTP API used by the web UI backend
+#[derive(Clone)]
+pub struct ConvexClient {
+    client: Client,
+    base_url: String,
+}
+
+/// Convex wraps every function result in a status envelope
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+enum ConvexEnvelope<T> {
+    Success { value: T },
+    Error {
+        #[serde(rename = "errorMessage")]
+        error_message: String,
+    },
+}
+
+/// Marker written on Convex records that have been copied into the native bot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexMirror {
+    pub native_id: String,
+    pub mirrored_at: i64,
+}
+
+/// Everything the migration needs about one Convex user (`migration:userSnapshot`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexUserSnapshot {
+    pub user: ConvexUser,
+    #[serde(default)]
+    pub wallets: Vec<ConvexWallet>,
+    #[serde(default)]
+    pub alerts: Vec<ConvexAlert>,
+    #[serde(default)]
+    pub dca_strategies: Vec<ConvexDcaStrategy>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexUser {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub telegram_id: i64,
+    pub username: String,
+    pub settings: ConvexUserSettings,
+    #[serde(default)]
+    pub mirror: Option<ConvexMirror>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexUserSettings {
+    /// Percent, e.g. 0.5
+    pub default_slippage: f64,
+    pub max_position_size: String,
+    pub auto_compound: bool,
+    pub risk_level: String,
+    pub notifications: ConvexNotifications,
+    /// Chosen in the bridge's language picker
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvexNotifications {
+    pub trades: bool,
+    pub alerts: bool,
+    pub daily: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexWallet {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub address: String,
+    #[serde(rename = "type")]
+    pub wallet_type: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub is_active: bool,
+    #[serde(default)]
+    pub mirror: Option<ConvexMirror>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexAlert {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub alert_type: String,
+    pub is_active: bool,
+    pub condition: ConvexAlertCondition,
+    #[serde(default)]
+    pub actions: Vec<String>,
+    /// Seconds
+    #[serde(default)]
+    pub cooldown: Option<i64>,
+    /// Milliseconds since the epoch
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    #[serde(default)]
+    pub mirror: Option<ConvexMirror>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvexAlertCondition {
+    /// Token mint or position id
+    pub target: String,
+    pub metric: String,
+    pub operator: String,
+    pub value: String,
+    #[serde(default)]
+    pub timeframe: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexDcaStrategy {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    pub is_active: bool,
+    pub is_paused: bool,
+    pub token_in: ConvexToken,
+    pub token_out: ConvexToken,
+    pub amount: String,
+    pub frequency: ConvexFrequency,
+    #[serde(default)]
+    pub max_investment: Option<String>,
+    #[serde(default)]
+    pub max_executions: Option<u32>,
+    /// Milliseconds since the epoch
+    #[serde(default)]
+    pub end_date: Option<i64>,
+    #[serde(default)]
+    pub mirror: Option<ConvexMirror>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvexToken {
+    pub mint: String,
+    pub symbol: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvexFrequency {
+    /// `interval`, `cron` or `dynamic`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// `1h`, `*/30 * * * *`, ...
+    pub value: String,
+}
+
+/// Tables that carry a mirror marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConvexTable {
+    Users,
+    Wallets,
+    Alerts,
+    DcaStrategies,
+}
+
+impl ConvexTable {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Users => "users",
+            Self::Wallets => "wallets",
+            Self::Alerts => "alerts",
+            Self::DcaStrategies => "dcaStrategies",
+        }
+    }
+}
+
+impl ConvexClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Client for `CONVEX_URL`, if set
+    pub fn from_env() -> Option<Self> {
+        std::env::var("CONVEX_URL").ok().filter(|url| !url.is_empty()).map(Self::new)
+    }
+
+    pub async fn query<T: DeserializeOwned>(&self, path: &str, args: Value) -> Result<T> {
+        self.call("query", path, args).await
+    }
+
+    pub async fn mutation<T: DeserializeOwned>(&self, path: &str, args: Value) -> Result<T> {
+        self.call("mutation", path, args).await
+    }
+
+    async fn call<T: DeserializeOwned>(&self, kind: &str, path: &str, args: Value) -> Result<T> {
+        debug!("Convex {} {}", kind, path);
+        let response = self.client
+            .post(format!("{}/api/{}", self.base_url, kind))
+            .json(&json!({ "path": path, "args": args, "format": "json" }))
+            .send()
+            .await
+            .map_err(|e| BotError::internal(format!("Convex {} {} failed: {}", kind, path, e)))?;
+
+        if !response.status().is_success() {
+            return Err(BotError::internal(format!(
+                "Convex {} {} returned {}", kind, path, response.status()
+            )).into());
+        }
+
+        let envelope: ConvexEnvelope<T> = response.json().await
+            .map_err(|e| BotError::parsing(format!("Unexpected Convex response for {}: {}", path, e)))?;
+        match envelope {
+            ConvexEnvelope::Success { value } => Ok(value),
+            ConvexEnvelope::Error { error_message } => {
+                Err(BotError::internal(format!("Convex {} error: {}", path, error_message)).into())
+            }
+        }
+    }
+
+    /// User, wallets, alerts and DCA strategies for a Telegram user
+    pub async fn user_snapshot(&self, telegram_id: i64) -> Result<Option<ConvexUserSnapshot>> {
+        self.query("queries/migration:userSnapshot", json!({ "telegramId": telegram_id })).await
+    }
+
+    /// Telegram ids of users not yet mirrored into the bot
+    pub async fn unmirrored_users(&self, limit: usize) -> Result<Vec<i64>> {
+        self.query("queries/migration:unmirroredUsers", json!({ "limit": limit })).await
+    }
+
+    /// Tag a record so the sync treats it as owned by the bot from now on
+    pub async fn mark_mirrored(&self, table: ConvexTable, id: &str, native_id: &str) -> Result<()> {
+        self.mutation::<Value>("mutations/migration:markMirrored", json!({
+            "table": table.as_str(),
+            "id": id,
+            "nativeId": native_id,
+        })).await?;
+        Ok(())
+    }
+}
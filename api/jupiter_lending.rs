@@ -87,7 +87,7 @@ pub struct LendingDetails {
 }
 
 /// Vault information for lending
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct LendingVault {
     #[serde(rename = "vaultId")]
     pub vault_id: String,
@@ -95,6 +95,8 @@ pub struct LendingVault {
     pub token_mint: String,
     #[serde(rename = "tokenSymbol")]
     pub token_symbol: String,
+    #[serde(rename = "tokenDecimals")]
+    pub token_decimals: u8,
     #[serde(rename = "totalSupply")]
     pub total_supply: u64,
     #[serde(rename = "totalBorrowed")]
@@ -116,7 +118,7 @@ pub struct LendingVault {
 }
 
 /// Risk tiers for lending vaults
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RiskTier {
     Conservative,
@@ -125,6 +127,24 @@ pub enum RiskTier {
     Speculative,
 }
 
+impl RiskTier {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RiskTier::Conservative => "🟢 Conservative",
+            RiskTier::Moderate => "🟡 Moderate",
+            RiskTier::Aggressive => "🟠 Aggressive",
+            RiskTier::Speculative => "🔴 Speculative",
+        }
+    }
+}
+
+impl LendingVault {
+    /// Supply APR compounded daily, as a fraction
+    pub fn supply_apy(&self) -> f64 {
+        (1.0 + self.supply_apr / 365.0).powi(365) - 1.0
+    }
+}
+
 /// User lending position
 #[derive(Debug, Clone, Deserialize)]
 pub struct LendingPosition {
@@ -168,7 +188,7 @@ pub enum PositionStatus {
 }
 
 /// Liquidation information
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct LiquidationInfo {
     #[serde(rename = "positionId")]
     pub position_id: String,
@@ -495,7 +515,7 @@ impl JupiterLendingClient {
 }
 
 /// Position recommendation based on health factor
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PositionRecommendation {
     CanBorrowMore,
     Healthy,
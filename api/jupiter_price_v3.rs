@@ -1,21 +1,58 @@
+use futures::future::{BoxFuture, FutureExt, Shared};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{info, debug, warn, error};
-use chrono::{DateTime, Utc, Duration};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{info, debug, warn};
+use chrono::{DateTime, Utc};
 
 use crate::errors::{BotError, Result};
 use crate::api::jupiter_auth::{JupiterAuthManager, ApiTierLevel};
+use crate::cache::{CacheStrategy, TtlCache};
+use crate::middleware::{CallOutcome, CircuitBreaker, CircuitBreakerConfig};
+use crate::monitoring::MetricsCollector;
+
+/// Most mints the Price API accepts in one request
+pub const MAX_PRICE_BATCH: usize = 100;
+/// Daily candles only change once a day, so an hour is plenty
+const HISTORY_TTL: Duration = Duration::from_secs(60 * 60);
 
 /// Jupiter Price API V3 client with enhanced caching
 #[derive(Clone)]
 pub struct JupiterPriceV3Client {
     client: Client,
     auth_manager: Arc<JupiterAuthManager>,
-    base_url: String,
-    price_cache: Arc<RwLock<PriceCache>>,
+    base_url_override: Option<String>,
+    cache_config: PriceCacheConfig,
+    price_cache: Arc<TtlCache<String, CachedPrice>>,
+    history_cache: Arc<TtlCache<String, Vec<HistoricalPricePoint>>>,
+    in_flight: Arc<Mutex<HashMap<String, PriceFetch>>>,
+    counters: Arc<CacheCounters>,
+    metrics: Option<Arc<MetricsCollector>>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+/// Price cache tuning
+#[derive(Debug, Clone)]
+pub struct PriceCacheConfig {
+    /// How long a fetched price is served as fresh
+    pub ttl: Duration,
+    /// How long past `ttl` a price is still served while a background refresh runs
+    pub max_stale: Duration,
+    pub capacity: usize,
+}
+
+impl Default for PriceCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(2),          // Order monitors poll every 2s
+            max_stale: Duration::from_secs(60),
+            capacity: 10_000,
+        }
+    }
 }
 
 /// Enhanced price data with V3 features
@@ -36,6 +73,19 @@ pub struct PriceDataV3 {
     pub last_traded_at: Option<DateTime<Utc>>,
 }
 
+impl PriceDataV3 {
+    /// 24h change as a percentage of the price a day ago
+    pub fn price_change_percent_24h(&self) -> Option<f64> {
+        self.price_change_24h.map(|change| {
+            if self.usd_price > 0.0 {
+                (change / (self.usd_price - change)) * 100.0
+            } else {
+                0.0
+            }
+        })
+    }
+}
+
 /// Comprehensive price response
 #[derive(Debug, Clone, Deserialize)]
 pub struct PriceResponseV3 {
@@ -75,7 +125,7 @@ pub enum Timeframe {
 }
 
 /// Historical price data point
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct HistoricalPricePoint {
     pub timestamp: DateTime<Utc>,
     #[serde(rename = "priceUsd")]
@@ -123,19 +173,58 @@ pub enum AlertType {
     PercentageChange { threshold_percent: f64 },
 }
 
-/// Price cache with intelligent invalidation
-#[derive(Debug)]
-struct PriceCache {
-    prices: HashMap<String, CachedPrice>,
-    last_cleanup: DateTime<Utc>,
-}
-
+/// A fetched price and when it arrived
 #[derive(Debug, Clone)]
 struct CachedPrice {
     data: PriceDataV3,
-    cached_at: DateTime<Utc>,
-    access_count: u32,
-    tier_ttl: Duration,
+    fetched_at: Instant,
+}
+
+/// One upstream request for a batch of mints, awaited by every caller that needs one of them
+type PriceFetch = Shared<BoxFuture<'static, std::result::Result<HashMap<String, PriceDataV3>, FetchError>>>;
+
+/// Why a shared fetch failed, cloned to every caller waiting on it
+#[derive(Debug, Clone)]
+enum FetchError {
+    /// The circuit breaker turned the request away
+    Unavailable(String),
+    Failed(String),
+}
+
+impl From<BotError> for FetchError {
+    fn from(error: BotError) -> Self {
+        match error {
+            BotError::ServiceUnavailable { service } => FetchError::Unavailable(service),
+            error => FetchError::Failed(error.to_string()),
+        }
+    }
+}
+
+impl From<FetchError> for BotError {
+    fn from(error: FetchError) -> Self {
+        match error {
+            FetchError::Unavailable(service) => BotError::service_unavailable(service),
+            FetchError::Failed(message) => BotError::jupiter_api(message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CacheEvent {
+    Hit,
+    Miss,
+    Coalesced,
+    StaleServed,
+}
+
+#[derive(Debug, Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    coalesced: AtomicU64,
+    stale_served: AtomicU64,
+    /// Bumped by `clear_cache` so fetches already in flight don't refill it
+    generation: AtomicU64,
 }
 
 impl JupiterPriceV3Client {
@@ -143,104 +232,231 @@ impl JupiterPriceV3Client {
     pub fn new(auth_manager: Arc<JupiterAuthManager>) -> Self {
         info!("📈 Initializing Jupiter Price API V3 client");
         
+        let cache_config = PriceCacheConfig::default();
         Self {
             client: Client::new(),
             auth_manager,
-            base_url: "https://api.jup.ag".to_string(), // Will be updated based on tier
-            price_cache: Arc::new(RwLock::new(PriceCache {
-                prices: HashMap::new(),
-                last_cleanup: Utc::now(),
-            })),
+            base_url_override: None, // Tier decides the host unless overridden
+            price_cache: Self::build_cache(&cache_config),
+            history_cache: Arc::new(TtlCache::new(256, HISTORY_TTL)),
+            cache_config,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            counters: Arc::new(CacheCounters::default()),
+            metrics: None,
+            breaker: Arc::new(CircuitBreaker::new("jupiter-price".to_string(), CircuitBreakerConfig::default())),
         }
     }
     
+    /// Pin all requests to a fixed base URL instead of the tier host
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url_override = Some(base_url.into().trim_end_matches('/').to_string());
+        self
+    }
+    
+    /// Override the cache TTL, stale window or capacity
+    pub fn with_cache_config(mut self, cache_config: PriceCacheConfig) -> Self {
+        self.price_cache = Self::build_cache(&cache_config);
+        self.cache_config = cache_config;
+        self
+    }
+    
+    /// Report cache hits, misses, coalesced and stale lookups
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    /// Share a circuit breaker (and its alerts) with other clients
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+    
+    /// Entries outlive the TTL by the stale window so they can be served while refreshing
+    fn build_cache(config: &PriceCacheConfig) -> Arc<TtlCache<String, CachedPrice>> {
+        Arc::new(
+            TtlCache::new(config.capacity, config.ttl + config.max_stale)
+                .with_cleanup_interval(Duration::from_secs(30))
+        )
+    }
+    
     /// Get current prices for multiple tokens
     pub async fn get_prices(&self, token_mints: Vec<String>) -> Result<PriceResponseV3> {
         if token_mints.is_empty() {
             return Err(BotError::validation("Token mints cannot be empty".to_string()).into());
         }
         
-        if token_mints.len() > 100 {
-            return Err(BotError::validation("Maximum 100 tokens per request".to_string()).into());
+        if token_mints.len() > MAX_PRICE_BATCH {
+            return Err(BotError::validation(format!("Maximum {} tokens per request", MAX_PRICE_BATCH)).into());
         }
         
-        // Check cache first
-        let cache_results = self.check_price_cache(&token_mints).await;
-        let uncached_tokens: Vec<String> = token_mints.iter()
-            .filter(|mint| !cache_results.contains_key(*mint))
-            .cloned()
-            .collect();
+        let mut prices = HashMap::new();
+        let mut missing = Vec::new();
+        let mut stale = Vec::new();
         
-        let mut final_prices = cache_results;
+        for mint in &token_mints {
+            match self.price_cache.get(mint).await {
+                Some(cached) if cached.fetched_at.elapsed() < self.cache_config.ttl => {
+                    self.record(CacheEvent::Hit);
+                    prices.insert(mint.clone(), cached.data);
+                }
+                Some(cached) => {
+                    // Serve it now; a refresh is started below
+                    self.record(CacheEvent::StaleServed);
+                    prices.insert(mint.clone(), cached.data);
+                    stale.push(mint.clone());
+                }
+                None => missing.push(mint.clone()),
+            }
+        }
         
-        // Fetch uncached prices
-        if !uncached_tokens.is_empty() {
-            let api_key_config = self.auth_manager.select_best_key("price").await?;
-            let base_url = match &api_key_config {
-                Some(config) => match config.tier {
-                    ApiTierLevel::Lite => "https://lite-api.jup.ag".to_string(),
-                    _ => "https://api.jup.ag".to_string(),
-                },
-                None => "https://lite-api.jup.ag".to_string(),
-            };
-            
-            let url = format!("{}/price/v3", base_url);
-            let ids = uncached_tokens.join(",");
-            
-            debug!("📈 Fetching prices for {} tokens from API", uncached_tokens.len());
+        let mut pending = Vec::new();
+        if !missing.is_empty() || !stale.is_empty() {
+            let mut in_flight = self.in_flight.lock().await;
+            let mut to_fetch = Vec::new();
             
-            let mut request = self.client
-                .get(&url)
-                .query(&[("ids", &ids)]);
-                
-            // Add authentication if available
-            if let Some(config) = &api_key_config {
-                request = request.header("Authorization", format!("Bearer {}", config.key));
+            for mint in missing {
+                if let Some(fetch) = in_flight.get(&mint) {
+                    self.record(CacheEvent::Coalesced);
+                    pending.push(fetch.clone());
+                } else if let Some(cached) = self.price_cache.get(&mint).await {
+                    // A fetch finished since the first look
+                    self.record(CacheEvent::Hit);
+                    prices.insert(mint, cached.data);
+                } else {
+                    self.record(CacheEvent::Miss);
+                    to_fetch.push(mint);
+                }
             }
             
-            let response = request
-                .send()
-                .await
-                .map_err(|e| BotError::jupiter_api(format!("Price request failed: {}", e)))?;
-                
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await.unwrap_or_default();
-                return Err(BotError::jupiter_api(format!(
-                    "Price API failed with status {}: {}", status, error_text
-                )).into());
+            if !to_fetch.is_empty() {
+                pending.push(self.start_fetch(&mut in_flight, to_fetch));
             }
             
-            let api_response: PriceResponseV3 = response
-                .json()
-                .await
-                .map_err(|e| BotError::jupiter_api(format!("Failed to parse price response: {}", e)))?;
-                
-            // Cache the results
-            self.cache_prices(&api_response.prices, &api_key_config).await;
-            
-            // Record usage
-            if let Some(config) = &api_key_config {
-                let key_id = format!("key_{}", &config.key[..8]);
-                self.auth_manager.record_usage(&key_id, "price").await;
+            let refresh: Vec<String> = stale.into_iter()
+                .filter(|mint| !in_flight.contains_key(mint))
+                .collect();
+            if !refresh.is_empty() {
+                debug!("📈 Refreshing {} stale prices in the background", refresh.len());
+                let fetch = self.start_fetch(&mut in_flight, refresh);
+                tokio::spawn(async move {
+                    if let Err(e) = fetch.await {
+                        warn!("📈 Background price refresh failed: {:?}", e);
+                    }
+                });
             }
-            
-            final_prices.extend(api_response.prices);
         }
         
-        info!("📈 Retrieved prices for {} tokens ({} from cache, {} from API)", 
+        let served_from_cache = prices.len();
+        for fetch in pending {
+            let fetched = fetch.await.map_err(BotError::from)?;
+            prices.extend(fetched.into_iter().filter(|(mint, _)| token_mints.contains(mint)));
+        }
+        
+        debug!("📈 Retrieved prices for {} tokens ({} from cache, {} from API)", 
             token_mints.len(), 
-            token_mints.len() - uncached_tokens.len(),
-            uncached_tokens.len()
+            served_from_cache,
+            prices.len() - served_from_cache
         );
         
         Ok(PriceResponseV3 {
-            prices: final_prices,
+            prices,
             time_taken: None,
             context_slot: None,
         })
     }
     
+    /// Register one upstream request for `mints` so concurrent callers can share it
+    fn start_fetch(&self, in_flight: &mut HashMap<String, PriceFetch>, mints: Vec<String>) -> PriceFetch {
+        let client = self.clone();
+        let batch = mints.clone();
+        let fetch = async move { client.fetch_and_cache(batch).await }.boxed().shared();
+        for mint in mints {
+            in_flight.insert(mint, fetch.clone());
+        }
+        fetch
+    }
+    
+    /// Fetch, cache the result, then stop advertising the request as in flight
+    async fn fetch_and_cache(&self, mints: Vec<String>) -> std::result::Result<HashMap<String, PriceDataV3>, FetchError> {
+        let generation = self.counters.generation.load(Ordering::SeqCst);
+        let result = self.fetch_prices(&mints).await;
+        
+        // Cache before leaving the in-flight map, so a new caller finds one or the other
+        let mut in_flight = self.in_flight.lock().await;
+        if let Ok(prices) = &result {
+            if self.counters.generation.load(Ordering::SeqCst) == generation {
+                let fetched_at = Instant::now();
+                for (mint, data) in prices {
+                    let cached = CachedPrice { data: data.clone(), fetched_at };
+                    if let Err(e) = self.price_cache.set(mint.clone(), cached).await {
+                        warn!("📈 Failed to cache price for {}: {:?}", mint, e);
+                    }
+                }
+            }
+        }
+        for mint in &mints {
+            in_flight.remove(mint);
+        }
+        
+        result.map_err(FetchError::from)
+    }
+    
+    /// One upstream Price API V3 request
+    async fn fetch_prices(&self, token_mints: &[String]) -> Result<HashMap<String, PriceDataV3>> {
+        let api_key_config = self.auth_manager.select_best_key("price").await?;
+        let base_url = match (&self.base_url_override, &api_key_config) {
+            (Some(url), _) => url.clone(),
+            (None, Some(config)) => match config.tier {
+                ApiTierLevel::Lite => "https://lite-api.jup.ag".to_string(),
+                _ => "https://api.jup.ag".to_string(),
+            },
+            (None, None) => "https://lite-api.jup.ag".to_string(),
+        };
+        
+        let url = format!("{}/price/v3", base_url);
+        let ids = token_mints.join(",");
+        
+        debug!("📈 Fetching prices for {} tokens from API", token_mints.len());
+        
+        let mut request = self.client
+            .get(&url)
+            .query(&[("ids", &ids)]);
+            
+        // Add authentication if available
+        if let Some(config) = &api_key_config {
+            request = request.header("Authorization", format!("Bearer {}", config.key));
+        }
+        
+        let response = self.breaker
+            .call(
+                async { request.send().await.map_err(|e| BotError::jupiter_api(format!("Price request failed: {}", e))) },
+                CallOutcome::of_response,
+            )
+            .await?;
+            
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(BotError::jupiter_api(format!(
+                "Price API failed with status {}: {}", status, error_text
+            )).into());
+        }
+        
+        let api_response: PriceResponseV3 = response
+            .json()
+            .await
+            .map_err(|e| BotError::jupiter_api(format!("Failed to parse price response: {}", e)))?;
+            
+        // Record usage
+        if let Some(config) = &api_key_config {
+            let key_id = format!("key_{}", &config.key[..8]);
+            self.auth_manager.record_usage(&key_id, "price").await;
+        }
+        
+        Ok(api_response.prices)
+    }
+    
     /// Get historical price data
     pub async fn get_historical_prices(
         &self,
@@ -261,7 +477,7 @@ impl JupiterPriceV3Client {
             ).into());
         }
         
-        let base_url = "https://api.jup.ag";
+        let base_url = self.base_url_override.as_deref().unwrap_or("https://api.jup.ag");
         let url = format!("{}/price/v3/historical", base_url);
         
         let mut req = self.client
@@ -272,10 +488,12 @@ impl JupiterPriceV3Client {
             req = req.header("Authorization", format!("Bearer {}", config.key));
         }
         
-        let response = req
-            .send()
-            .await
-            .map_err(|e| BotError::jupiter_api(format!("Historical price request failed: {}", e)))?;
+        let response = self.breaker
+            .call(
+                async { req.send().await.map_err(|e| BotError::jupiter_api(format!("Historical price request failed: {}", e))) },
+                CallOutcome::of_response,
+            )
+            .await?;
             
         if !response.status().is_success() {
             let status = response.status();
@@ -302,6 +520,27 @@ impl JupiterPriceV3Client {
         Ok(historical_data)
     }
     
+    /// The last `days` daily closes for `mint`, oldest first, cached per mint and window
+    pub async fn get_daily_history(&self, mint: &str, days: u32) -> Result<Vec<HistoricalPricePoint>> {
+        let key = format!("{}:1d:{}", mint, days);
+        if let Some(points) = self.history_cache.get(&key).await {
+            return Ok(points);
+        }
+        
+        let mut points = self.get_historical_prices(HistoricalPriceRequest {
+            id: mint.to_string(),
+            vs_token: None,
+            timeframe: Timeframe::OneDay,
+            limit: Some(days.min(1000)),
+        }).await?.data;
+        points.sort_by_key(|point| point.timestamp);
+        
+        if let Err(e) = self.history_cache.set(key, points.clone()).await {
+            warn!("📈 Failed to cache price history for {}: {:?}", mint, e);
+        }
+        Ok(points)
+    }
+    
     /// Get price comparison with 24h change
     pub async fn get_price_comparison(&self, token_mint: &str) -> Result<PriceComparison> {
         let current_price_resp = self.get_prices(vec![token_mint.to_string()]).await?;
@@ -310,13 +549,7 @@ impl JupiterPriceV3Client {
             .ok_or_else(|| BotError::jupiter_api(format!("Price not found for token {}", token_mint)))?;
         
         let price_change_24h = current_data.price_change_24h;
-        let price_change_percent_24h = price_change_24h.map(|change| {
-            if current_data.usd_price > 0.0 {
-                (change / (current_data.usd_price - change)) * 100.0
-            } else {
-                0.0
-            }
-        });
+        let price_change_percent_24h = current_data.price_change_percent_24h();
         
         let price_24h_ago = price_change_24h.map(|change| current_data.usd_price - change);
         
@@ -343,98 +576,54 @@ impl JupiterPriceV3Client {
         }
     }
     
-    /// Check cache for prices
-    async fn check_price_cache(&self, token_mints: &[String]) -> HashMap<String, PriceDataV3> {
-        let mut cache = self.price_cache.write().await;
-        let mut results = HashMap::new();
-        
-        for mint in token_mints {
-            if let Some(cached) = cache.prices.get_mut(mint) {
-                let age = Utc::now().signed_duration_since(cached.cached_at);
-                
-                if age < cached.tier_ttl {
-                    cached.access_count += 1;
-                    results.insert(mint.clone(), cached.data.clone());
-                }
-            }
-        }
-        
-        // Periodic cleanup
-        if Utc::now().signed_duration_since(cache.last_cleanup) > Duration::minutes(5) {
-            self.cleanup_cache(&mut cache).await;
-        }
-        
-        results
-    }
-    
-    /// Cache price data with tier-appropriate TTL
-    async fn cache_prices(
-        &self,
-        prices: &HashMap<String, PriceDataV3>,
-        api_key_config: &Option<crate::api::jupiter_auth::ApiKeyConfig>,
-    ) {
-        let mut cache = self.price_cache.write().await;
-        
-        let tier_ttl = match api_key_config {
-            Some(config) => match config.tier {
-                ApiTierLevel::Lite => Duration::seconds(30),
-                ApiTierLevel::Ultra => Duration::seconds(15),
-                ApiTierLevel::Pro { .. } => Duration::seconds(5),
-                ApiTierLevel::Enterprise { .. } => Duration::seconds(1),
-            },
-            None => Duration::seconds(30),
+    /// Count a cache lookup and report it to metrics
+    fn record(&self, event: CacheEvent) {
+        let counter = match event {
+            CacheEvent::Hit => &self.counters.hits,
+            CacheEvent::Miss => &self.counters.misses,
+            CacheEvent::Coalesced => &self.counters.coalesced,
+            CacheEvent::StaleServed => &self.counters.stale_served,
         };
+        counter.fetch_add(1, Ordering::Relaxed);
         
-        for (mint, price_data) in prices {
-            cache.prices.insert(mint.clone(), CachedPrice {
-                data: price_data.clone(),
-                cached_at: Utc::now(),
-                access_count: 1,
-                tier_ttl,
-            });
+        if let Some(metrics) = &self.metrics {
+            match event {
+                CacheEvent::Hit => metrics.record_cache_access("jupiter_price", true),
+                CacheEvent::Miss => metrics.record_cache_access("jupiter_price", false),
+                CacheEvent::Coalesced => metrics.record_cache_event("jupiter_price", "coalesced"),
+                CacheEvent::StaleServed => metrics.record_cache_event("jupiter_price", "stale_served"),
+            }
         }
     }
     
-    /// Clean up expired cache entries
-    async fn cleanup_cache(&self, cache: &mut PriceCache) {
-        let now = Utc::now();
-        let cutoff = now - Duration::minutes(10);
-        
-        let before_count = cache.prices.len();
-        cache.prices.retain(|_, cached| {
-            cached.cached_at > cutoff || cached.access_count > 5
-        });
-        
-        cache.last_cleanup = now;
-        
-        let cleaned = before_count - cache.prices.len();
-        if cleaned > 0 {
-            debug!("🧹 Cleaned {} expired price cache entries", cleaned);
-        }
+    /// Drop all cached prices so the next lookup hits the API
+    pub async fn clear_cache(&self) {
+        self.counters.generation.fetch_add(1, Ordering::SeqCst);
+        self.price_cache.clear().await;
     }
     
     /// Get cache statistics
     pub async fn get_cache_stats(&self) -> CacheStats {
-        let cache = self.price_cache.read().await;
-        let total_entries = cache.prices.len();
-        let avg_access_count = if total_entries > 0 {
-            cache.prices.values().map(|c| c.access_count).sum::<u32>() as f64 / total_entries as f64
-        } else {
-            0.0
-        };
-        
         CacheStats {
-            total_entries,
-            avg_access_count,
-            last_cleanup: cache.last_cleanup,
+            total_entries: self.price_cache.stats().await.entries,
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            coalesced: self.counters.coalesced.load(Ordering::Relaxed),
+            stale_served: self.counters.stale_served.load(Ordering::Relaxed),
         }
     }
 }
 
 /// Cache statistics for monitoring
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct CacheStats {
     pub total_entries: usize,
-    pub avg_access_count: f64,
-    pub last_cleanup: DateTime<Utc>,
-}
\ No newline at end of file
+    /// Lookups answered with a fresh price
+    pub hits: u64,
+    /// Lookups that started an upstream request
+    pub misses: u64,
+    /// Lookups that joined a request another caller had in flight
+    pub coalesced: u64,
+    /// Expired prices served while a background refresh ran
+    pub stale_served: u64,
+}
@@ -134,7 +134,7 @@ pub struct SendInfo {
 }
 
 /// Claim record for tracking who claimed what
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ClaimRecord {
     #[serde(rename = "claimId")]
     pub claim_id: String,
@@ -154,6 +154,34 @@ pub struct ClaimRecord {
     pub country: Option<String>,
 }
 
+/// Request for an unsigned claim transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaimTransactionRequest {
+    #[serde(rename = "sendId")]
+    pub send_id: String,
+    #[serde(rename = "claimId")]
+    pub claim_id: String,
+    #[serde(rename = "recipientPublicKey")]
+    pub recipient_public_key: String,
+    /// Pays the fee and any token account rent; the recipient when absent
+    #[serde(rename = "feePayer", skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<String>,
+    /// Lamports out of a SOL claim that repay the fee payer
+    #[serde(rename = "feePayerRepaymentLamports", skip_serializing_if = "Option::is_none")]
+    pub fee_payer_repayment_lamports: Option<u64>,
+}
+
+/// Claim transaction waiting for the recipient's (and fee payer's) signature
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClaimTransactionResponse {
+    #[serde(rename = "claimId")]
+    pub claim_id: String,
+    /// Base64 serialized transaction
+    pub transaction: String,
+    #[serde(rename = "lastValidBlockHeight")]
+    pub last_valid_block_height: u64,
+}
+
 /// Analytics for send links
 #[derive(Debug, Deserialize)]
 pub struct SendAnalytics {
@@ -195,6 +223,8 @@ pub struct BulkRecipient {
     #[serde(rename = "recipientId")]
     pub recipient_id: String, // User-defined ID
     pub amount: u64,
+    #[serde(rename = "recipientPublicKey", skip_serializing_if = "Option::is_none")]
+    pub recipient_public_key: Option<String>,
     #[serde(rename = "recipientEmail", skip_serializing_if = "Option::is_none")]
     pub recipient_email: Option<String>,
     #[serde(rename = "recipientPhone", skip_serializing_if = "Option::is_none")]
@@ -437,6 +467,43 @@ impl JupiterSendClient {
         Ok(send_info)
     }
     
+    /// Build the claim transaction for a recipient, optionally with a sponsoring fee payer
+    pub async fn build_claim_transaction(&self, request: ClaimTransactionRequest) -> Result<ClaimTransactionResponse> {
+        let api_key_config = self.auth_manager.select_best_key("send_claim").await?
+            .ok_or_else(|| BotError::jupiter_api("Send API requires authentication".to_string()))?;
+        
+        let url = format!("{}/claim", self.base_url);
+        
+        let response = self.client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key_config.key))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| BotError::jupiter_api(format!("Claim request failed: {}", e)))?;
+            
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(BotError::jupiter_api(format!(
+                "Claim failed with status {}: {}", status, error_text
+            )).into());
+        }
+        
+        let claim: ClaimTransactionResponse = response
+            .json()
+            .await
+            .map_err(|e| BotError::jupiter_api(format!("Failed to parse claim response: {}", e)))?;
+            
+        // Record usage
+        let key_id = format!("key_{}", &api_key_config.key[..8]);
+        self.auth_manager.record_usage(&key_id, "send_claim").await;
+        
+        debug!("📤 Built claim {} for send {} (fee payer {:?})", claim.claim_id, request.send_id, request.fee_payer);
+        
+        Ok(claim)
+    }
+    
     /// Cancel an active send
     pub async fn cancel_send(&self, send_id: &str) -> Result<bool> {
         let api_key_config = self.auth_manager.select_best_key("send_cancel").await?
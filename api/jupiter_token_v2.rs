@@ -224,6 +224,18 @@ pub struct RiskAssessment {
     pub recommendations: Vec<String>,
 }
 
+impl RiskAssessment {
+    /// `🟢 low` to `🔴 extreme` by overall score, for one-line listings
+    pub fn badge(&self) -> &'static str {
+        match self.overall_risk_score {
+            score if score < 0.3 => "🟢 low",
+            score if score < 0.5 => "🟡 medium",
+            score if score < 0.7 => "🟠 high",
+            _ => "🔴 extreme",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PricePerformance {
     pub price_change_1h: Option<f64>,
@@ -237,7 +249,7 @@ pub struct PricePerformance {
 }
 
 /// Token watchlist for tracking favorite tokens
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenWatchlist {
     pub user_id: i64,
     pub tokens: Vec<WatchlistToken>,
@@ -245,7 +257,7 @@ pub struct TokenWatchlist {
     pub last_updated: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchlistToken {
     pub address: String,
     pub symbol: String,
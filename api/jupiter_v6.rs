@@ -1,12 +1,15 @@
-use reqwest::Client;
+use rand::Rng;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{info, debug, warn, error};
-use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+use tracing::{info, debug, warn};
 
+use crate::api::jupiter_auth::{ApiTierLevel, RateLimits};
 use crate::errors::{BotError, Result};
+use crate::middleware::{ApiRateLimiter, CallOutcome, CircuitBreaker, CircuitBreakerConfig, RateLimitConfig};
+use crate::monitoring::MetricsCollector;
 use crate::telemetry::TelemetryService;
 
 /// Jupiter API v6 client with enhanced 2025 features
@@ -16,7 +19,11 @@ pub struct JupiterV6Client {
     api_tier: ApiTier,
     base_url: String,
     telemetry: Option<Arc<TelemetryService>>,
-    rate_limiter: Arc<RwLock<RateLimiter>>,
+    rate_limiter: ApiRateLimiter,
+    metrics: Option<Arc<MetricsCollector>>,
+    request_deadline: Duration,
+    retry_backoff: Duration,
+    breaker: Arc<CircuitBreaker>,
 }
 
 /// API tier configuration for Jupiter v6
@@ -42,6 +49,16 @@ impl ApiTier {
             ApiTier::Ultra { api_key } | ApiTier::Pro { api_key } => Some(api_key),
         }
     }
+    
+    /// The matching auth tier, whose limits size the client's throttling
+    pub fn level(&self) -> ApiTierLevel {
+        match self {
+            ApiTier::Lite => ApiTierLevel::Lite,
+            ApiTier::Ultra { .. } => ApiTierLevel::Ultra,
+            // Credits are tracked by the auth manager, not per client
+            ApiTier::Pro { .. } => ApiTierLevel::Pro { credits_remaining: 0 },
+        }
+    }
 }
 
 /// Enhanced quote request with v6 features
@@ -247,49 +264,83 @@ pub struct TokenDataV2 {
     pub mint_authority: Option<String>,
 }
 
-/// Rate limiter for API calls
-#[derive(Debug)]
-struct RateLimiter {
-    requests: HashMap<String, Vec<DateTime<Utc>>>,
-    limits: RateLimits,
-}
-
-#[derive(Debug)]
-struct RateLimits {
-    per_minute: usize,
-    per_hour: usize,
-    per_day: usize,
-}
+/// Time budget for a call when the caller doesn't give one, queueing and retries included
+const DEFAULT_REQUEST_DEADLINE: Duration = Duration::from_secs(10);
+/// First retry delay; doubles on each further attempt
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(4);
 
 impl JupiterV6Client {
     /// Create new Jupiter v6 client
     pub fn new(api_tier: ApiTier, telemetry: Option<Arc<TelemetryService>>) -> Self {
         let base_url = api_tier.base_url().to_string();
+        let limits = api_tier.level().rate_limits();
         
-        let limits = match api_tier {
-            ApiTier::Lite => RateLimits { per_minute: 10, per_hour: 100, per_day: 1000 },
-            ApiTier::Ultra { .. } => RateLimits { per_minute: 60, per_hour: 1000, per_day: 10000 },
-            ApiTier::Pro { .. } => RateLimits { per_minute: 600, per_hour: 10000, per_day: 100000 },
-        };
-        
-        info!("🪐 Initialized Jupiter v6 client with {:?} tier", std::mem::discriminant(&api_tier));
+        info!("🪐 Initialized Jupiter v6 client with {:?} tier ({} req/min, {} concurrent)",
+            std::mem::discriminant(&api_tier), limits.requests_per_minute, limits.concurrent_requests);
         
         Self {
             client: Client::new(),
             api_tier,
             base_url,
             telemetry,
-            rate_limiter: Arc::new(RwLock::new(RateLimiter {
-                requests: HashMap::new(),
-                limits,
-            })),
+            rate_limiter: ApiRateLimiter::with_config(RateLimitConfig::from_tier_limits(&limits)),
+            metrics: None,
+            request_deadline: DEFAULT_REQUEST_DEADLINE,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            breaker: Arc::new(CircuitBreaker::new("jupiter".to_string(), CircuitBreakerConfig::default())),
         }
     }
     
+    /// Override the tier's base URL (self-hosted proxy, test mocks)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+    
+    /// Throttle with these limits instead of the tier's defaults
+    pub fn with_rate_limits(mut self, limits: &RateLimits) -> Self {
+        self.rate_limiter = ApiRateLimiter::with_config(RateLimitConfig::from_tier_limits(limits));
+        self
+    }
+    
+    /// Share a rate limiter with other clients on the same API key
+    pub fn with_rate_limiter(mut self, rate_limiter: ApiRateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+    
+    /// Record permit wait times and retries
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    /// Deadline for calls that don't pass their own
+    pub fn with_request_deadline(mut self, deadline: Duration) -> Self {
+        self.request_deadline = deadline;
+        self
+    }
+    
+    /// Delay before the first retry of a throttled or failed call
+    pub fn with_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+    
+    /// Share a circuit breaker (and its alerts) with other Jupiter clients
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+    
     /// Get quote using Jupiter v6 API
     pub async fn get_quote(&self, request: QuoteRequestV6) -> Result<QuoteResponseV6> {
-        self.check_rate_limit("quote").await?;
-        
+        self.get_quote_within(request, self.request_deadline).await
+    }
+    
+    /// Get a quote, giving up once `deadline` has passed
+    pub async fn get_quote_within(&self, request: QuoteRequestV6, deadline: Duration) -> Result<QuoteResponseV6> {
         let url = format!("{}/v6/quote", self.base_url);
         
         // Create tracing context if telemetry available
@@ -297,19 +348,9 @@ impl JupiterV6Client {
             t.create_jupiter_span(&url, "GET")
         );
         
-        let mut req = self.client
-            .get(&url)
-            .query(&request);
-            
-        // Add API key header if available
-        if let Some(api_key) = self.api_tier.api_key() {
-            req = req.header("Authorization", format!("Bearer {}", api_key));
-        }
-        
-        let response = req
-            .send()
-            .await
-            .map_err(|e| BotError::jupiter_api(format!("Quote request failed: {}", e)))?;
+        let response = self
+            .send("quote", Instant::now() + deadline, || self.client.get(&url).query(&request))
+            .await?;
             
         if !response.status().is_success() {
             let status = response.status();
@@ -332,8 +373,11 @@ impl JupiterV6Client {
     
     /// Execute swap using Jupiter v6 API
     pub async fn execute_swap(&self, request: SwapRequestV6) -> Result<SwapResponseV6> {
-        self.check_rate_limit("swap").await?;
-        
+        self.execute_swap_within(request, self.request_deadline).await
+    }
+    
+    /// Build a swap transaction, giving up once `deadline` has passed
+    pub async fn execute_swap_within(&self, request: SwapRequestV6, deadline: Duration) -> Result<SwapResponseV6> {
         let url = format!("{}/v6/swap", self.base_url);
         
         // Create tracing context if telemetry available
@@ -341,19 +385,9 @@ impl JupiterV6Client {
             t.create_jupiter_span(&url, "POST")
         );
         
-        let mut req = self.client
-            .post(&url)
-            .json(&request);
-            
-        // Add API key header if available
-        if let Some(api_key) = self.api_tier.api_key() {
-            req = req.header("Authorization", format!("Bearer {}", api_key));
-        }
-        
-        let response = req
-            .send()
-            .await
-            .map_err(|e| BotError::jupiter_api(format!("Swap request failed: {}", e)))?;
+        let response = self
+            .send("swap", Instant::now() + deadline, || self.client.post(&url).json(&request))
+            .await?;
             
         if !response.status().is_success() {
             let status = response.status();
@@ -376,24 +410,14 @@ impl JupiterV6Client {
     
     /// Get token prices using Price API V3
     pub async fn get_token_prices_v3(&self, token_mints: Vec<String>) -> Result<PriceResponseV3> {
-        self.check_rate_limit("price").await?;
-        
         let url = format!("{}/price/v3", self.base_url);
         let ids = token_mints.join(",");
         
-        let mut req = self.client
-            .get(&url)
-            .query(&[("ids", &ids)]);
-            
-        // Add API key header if available
-        if let Some(api_key) = self.api_tier.api_key() {
-            req = req.header("Authorization", format!("Bearer {}", api_key));
-        }
-        
-        let response = req
-            .send()
-            .await
-            .map_err(|e| BotError::jupiter_api(format!("Price request failed: {}", e)))?;
+        let response = self
+            .send("price", Instant::now() + self.request_deadline, || {
+                self.client.get(&url).query(&[("ids", &ids)])
+            })
+            .await?;
             
         if !response.status().is_success() {
             let status = response.status();
@@ -415,21 +439,11 @@ impl JupiterV6Client {
     
     /// Get tokens using Token API V2
     pub async fn get_tokens_v2(&self) -> Result<TokenResponseV2> {
-        self.check_rate_limit("tokens").await?;
-        
         let url = format!("{}/token/v2/tokens", self.base_url);
         
-        let mut req = self.client.get(&url);
-            
-        // Add API key header if available
-        if let Some(api_key) = self.api_tier.api_key() {
-            req = req.header("Authorization", format!("Bearer {}", api_key));
-        }
-        
-        let response = req
-            .send()
-            .await
-            .map_err(|e| BotError::jupiter_api(format!("Token request failed: {}", e)))?;
+        let response = self
+            .send("tokens", Instant::now() + self.request_deadline, || self.client.get(&url))
+            .await?;
             
         if !response.status().is_success() {
             let status = response.status();
@@ -449,37 +463,112 @@ impl JupiterV6Client {
         Ok(tokens)
     }
     
-    /// Check rate limits
-    async fn check_rate_limit(&self, endpoint: &str) -> Result<()> {
-        let mut limiter = self.rate_limiter.write().await;
-        let now = Utc::now();
-        let key = format!("{}_{}", endpoint, match &self.api_tier {
-            ApiTier::Lite => "lite",
-            ApiTier::Ultra { .. } => "ultra", 
-            ApiTier::Pro { .. } => "pro",
-        });
-        
-        // Clean old requests
-        let requests = limiter.requests.entry(key.clone()).or_insert_with(Vec::new);
-        requests.retain(|&timestamp| {
-            now.signed_duration_since(timestamp).num_minutes() < 60
-        });
+    /// `send_with_retries` behind the circuit breaker, so an outage fails fast with
+    /// `BotError::service_unavailable`. Throttling says nothing about Jupiter's health
+    /// and isn't counted. Latency covers every attempt, as the caller waited for all of them.
+    async fn send(
+        &self,
+        endpoint: &str,
+        deadline: Instant,
+        build: impl Fn() -> RequestBuilder,
+    ) -> Result<Response> {
+        let method = self.metrics.as_ref()
+            .and_then(|_| build().build().ok())
+            .map(|request| request.method().to_string());
+        let started = Instant::now();
+        let result = self.breaker
+            .call(self.send_with_retries(endpoint, deadline, &build), |result| match result {
+                Err(BotError::RateLimited { .. }) => CallOutcome::Ignored,
+                result => CallOutcome::of_response(result),
+            })
+            .await;
+        if let (Some(metrics), Some(method)) = (&self.metrics, method) {
+            let success = matches!(&result, Ok(response) if response.status().is_success());
+            metrics.record_api_call(&format!("jupiter_{}", endpoint), &method, success, started.elapsed().as_secs_f64() * 1000.0);
+        }
+        result
+    }
+    
+    /// Send a request under the tier's rate limit, retrying 429s and 5xx responses with
+    /// jittered exponential backoff while `deadline` allows. A full permit queue or a wait
+    /// past the deadline fails with `BotError::rate_limited`; once retries run out the last
+    /// response is returned for the caller to report.
+    async fn send_with_retries(
+        &self,
+        endpoint: &str,
+        deadline: Instant,
+        build: impl Fn() -> RequestBuilder,
+    ) -> Result<Response> {
+        let metric = format!("jupiter_{}", endpoint);
+        let mut attempt = 0u32;
         
-        // Check per-minute limit
-        let recent_requests = requests.iter()
-            .filter(|&&timestamp| now.signed_duration_since(timestamp).num_minutes() < 1)
-            .count();
+        loop {
+            let permit = self.rate_limiter.acquire(endpoint, deadline).await.map_err(|e| {
+                warn!("⏳ Jupiter {} throttled locally: {}", endpoint, e);
+                BotError::rate_limited(format!("Jupiter is busy, please try again shortly ({})", e))
+            })?;
+            if let Some(metrics) = &self.metrics {
+                metrics.record_rate_limit_wait(&metric, permit.waited.as_secs_f64() * 1000.0);
+            }
             
-        if recent_requests >= limiter.limits.per_minute {
-            return Err(BotError::rate_limited(format!(
-                "Rate limit exceeded for {}: {} requests per minute", 
-                endpoint, limiter.limits.per_minute
-            )).into());
+            let mut req = build().timeout(deadline.saturating_duration_since(Instant::now()));
+            
+            // Add API key header if available
+            if let Some(api_key) = self.api_tier.api_key() {
+                req = req.header("Authorization", format!("Bearer {}", api_key));
+            }
+            
+            let response = req
+                .send()
+                .await
+                .map_err(|e| BotError::jupiter_api(format!("Jupiter {} request failed: {}", endpoint, e)))?;
+            drop(permit);
+            
+            let status = response.status();
+            if status != StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                return Ok(response);
+            }
+            
+            let delay = retry_after(&response).unwrap_or_else(|| self.backoff(attempt));
+            if Instant::now() + delay >= deadline {
+                warn!("⏳ Jupiter {} still returning {} after {} attempts, giving up", endpoint, status, attempt + 1);
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    return Err(BotError::rate_limited(format!(
+                        "Jupiter is rate limiting {} requests, please try again shortly", endpoint
+                    )).into());
+                }
+                return Ok(response);
+            }
+            
+            debug!("🔁 Jupiter {} returned {}, retrying in {:?}", endpoint, status, delay);
+            if let Some(metrics) = &self.metrics {
+                metrics.record_api_retry(&metric, status.as_u16());
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
-        
-        requests.push(now);
-        Ok(())
     }
+    
+    /// Full-jitter exponential backoff: a random delay up to `retry_backoff * 2^attempt`, capped
+    fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self.retry_backoff
+            .saturating_mul(1 << attempt.min(16))
+            .min(MAX_RETRY_BACKOFF);
+        ceiling.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+/// A `Retry-After` header given in seconds
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
 }
 
 /// Helper function to create default swap request
@@ -7,6 +7,7 @@ pub mod jupiter_token_v2;
 pub mod jupiter_lending;
 pub mod jupiter_send;
 pub mod pump_fun;
+pub mod convex;
 
 pub use token_creator_api::{
     TokenCreatorAPI, 
@@ -69,6 +70,8 @@ pub use jupiter_price_v3::{
     PriceAlert,
     AlertType,
     CacheStats,
+    PriceCacheConfig,
+    MAX_PRICE_BATCH,
 };
 
 pub use jupiter_token_v2::{
@@ -111,9 +114,28 @@ pub use jupiter_send::{
     SendStatus,
     SendInfo,
     ClaimRecord,
+    ClaimTransactionRequest,
+    ClaimTransactionResponse,
     SendAnalytics,
     BulkSendRequest,
     BulkRecipient,
     BulkSendResponse,
+    BulkSendResult,
     SendTemplate,
-};
\ No newline at end of file
+};
+
+pub use convex::{
+    ConvexClient,
+    ConvexUserSnapshot,
+    ConvexUser,
+    ConvexUserSettings,
+    ConvexNotifications,
+    ConvexWallet,
+    ConvexAlert,
+    ConvexAlertCondition,
+    ConvexDcaStrategy,
+    ConvexToken,
+    ConvexFrequency,
+    ConvexMirror,
+    ConvexTable,
+};
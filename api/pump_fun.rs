@@ -1,14 +1,24 @@
 use anyhow::Result;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{info, warn, error};
 
+use crate::errors::BotError;
+use crate::middleware::{CallOutcome, CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError};
+
+/// Tokens a fresh curve has for sale, in base units (6 decimals)
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const TOKEN_UNITS: f64 = 1_000_000.0;
+
 /// Pump.fun API client for token operations
 pub struct PumpFunClient {
     client: Client,
     api_url: String,
     timeout: Duration,
+    breaker: Arc<CircuitBreaker>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,6 +38,47 @@ pub struct PumpToken {
     pub liquidity_locked: bool,
 }
 
+/// Reserves of a token's bonding curve account, in lamports and token base units
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BondingCurve {
+    pub mint: String,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub complete: bool,
+}
+
+impl BondingCurve {
+    /// Share of the sale supply already bought, 0-100
+    pub fn progress_pct(&self) -> f64 {
+        if self.complete {
+            100.0
+        } else {
+            curve_progress(self.real_token_reserves)
+        }
+    }
+
+    /// Spot price in SOL per token
+    pub fn price_sol(&self) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0;
+        }
+        (self.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL) / (self.virtual_token_reserves as f64 / TOKEN_UNITS)
+    }
+
+    /// SOL buyers have paid into the curve
+    pub fn sol_in_curve(&self) -> f64 {
+        self.real_sol_reserves as f64 / LAMPORTS_PER_SOL
+    }
+}
+
+/// Curve progress from the tokens still for sale; 100 once they're gone
+pub fn curve_progress(real_token_reserves: u64) -> f64 {
+    let left = real_token_reserves.min(INITIAL_REAL_TOKEN_RESERVES) as f64;
+    100.0 * (1.0 - left / INITIAL_REAL_TOKEN_RESERVES as f64)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateTokenRequest {
     pub name: String,
@@ -78,17 +129,42 @@ impl PumpFunClient {
             client,
             api_url: "https://api.pump.fun".to_string(), // Replace with actual API URL
             timeout: Duration::from_secs(30),
+            breaker: Arc::new(CircuitBreaker::new("pumpfun".to_string(), CircuitBreakerConfig::default())),
         })
     }
     
-    /// Get trending tokens on Pump.fun with timeout handling
+    /// Point at another API host (proxies, test mocks)
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+    
+    /// Use a configured circuit breaker, e.g. one reporting to the alert manager
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+    
+    /// Send with the client timeout, failing fast with `BotError::service_unavailable` while the circuit is open
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let call = async { Ok::<_, anyhow::Error>(tokio::time::timeout(self.timeout, request.send()).await??) };
+        match self.breaker.execute_classified(call, CallOutcome::of_response).await {
+            Ok(response) => Ok(response),
+            Err(CircuitBreakerError::CircuitOpen) => {
+                Err(BotError::service_unavailable(self.breaker.name().to_string()).into())
+            }
+            Err(CircuitBreakerError::OperationFailed(e)) => Err(e),
+        }
+    }
+    
+    /// Get trending tokens on Pump.fun, highest 24h volume first, with timeout handling
     pub async fn get_trending(&self, limit: usize) -> Result<Vec<PumpToken>> {
         use crate::utils::with_timeout;
         
-        let url = format!("{}/tokens/trending?limit={}", self.api_url, limit);
+        let url = format!("{}/tokens/trending?limit={}&sort=volume_24h", self.api_url, limit);
         
         let operation = async {
-            let response = self.client.get(&url).send().await?;
+            let response = self.send(self.client.get(&url)).await?;
             
             if !response.status().is_success() {
                 return Err(anyhow::anyhow!(
@@ -97,7 +173,10 @@ impl PumpFunClient {
                 ));
             }
             
-            let tokens: Vec<PumpToken> = response.json().await?;
+            let mut tokens: Vec<PumpToken> = response.json().await?;
+            // Don't rely on the API honouring the sort
+            tokens.sort_by(|a, b| b.volume_24h.total_cmp(&a.volume_24h));
+            tokens.truncate(limit);
             info!("Fetched {} trending tokens from Pump.fun", tokens.len());
             
             Ok(tokens)
@@ -110,10 +189,7 @@ impl PumpFunClient {
     pub async fn get_token(&self, token_address: &str) -> Result<PumpToken> {
         let url = format!("{}/tokens/{}", self.api_url, token_address);
         
-        let response = tokio::time::timeout(
-            self.timeout,
-            self.client.get(&url).send()
-        ).await??;
+        let response = self.send(self.client.get(&url)).await?;
         
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
@@ -127,19 +203,31 @@ impl PumpFunClient {
         Ok(token)
     }
     
+    /// Get the reserves of a token's bonding curve
+    pub async fn get_bonding_curve(&self, token_address: &str) -> Result<BondingCurve> {
+        let url = format!("{}/tokens/{}/bonding-curve", self.api_url, token_address);
+        
+        let response = self.send(self.client.get(&url)).await?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch bonding curve for {}: {}",
+                token_address,
+                response.status()
+            ));
+        }
+        
+        let curve: BondingCurve = response.json().await?;
+        Ok(curve)
+    }
+    
     /// Create a new token on Pump.fun
     pub async fn create_token(&self, request: CreateTokenRequest) -> Result<CreateTokenResponse> {
         let url = format!("{}/tokens/create", self.api_url);
         
         info!("Creating token: {} ({})", request.name, request.symbol);
         
-        let response = tokio::time::timeout(
-            self.timeout,
-            self.client
-                .post(&url)
-                .json(&request)
-                .send()
-        ).await??;
+        let response = self.send(self.client.post(&url).json(&request)).await?;
         
         if !response.status().is_success() {
             let error_text = response.text().await?;
@@ -170,13 +258,7 @@ impl PumpFunClient {
             request.token_address
         );
         
-        let response = tokio::time::timeout(
-            self.timeout,
-            self.client
-                .post(&url)
-                .json(&request)
-                .send()
-        ).await??;
+        let response = self.send(self.client.post(&url).json(&request)).await?;
         
         if !response.status().is_success() {
             let error_text = response.text().await?;
@@ -201,14 +283,11 @@ impl PumpFunClient {
         Ok(result)
     }
     
-    /// Search tokens by name or symbol
+    /// Search tokens by name or symbol; exact symbol matches first, then by 24h volume
     pub async fn search_tokens(&self, query: &str) -> Result<Vec<PumpToken>> {
         let url = format!("{}/tokens/search?q={}", self.api_url, urlencoding::encode(query));
         
-        let response = tokio::time::timeout(
-            self.timeout,
-            self.client.get(&url).send()
-        ).await??;
+        let response = self.send(self.client.get(&url)).await?;
         
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
@@ -217,7 +296,11 @@ impl PumpFunClient {
             ));
         }
         
-        let tokens: Vec<PumpToken> = response.json().await?;
+        let mut tokens: Vec<PumpToken> = response.json().await?;
+        tokens.sort_by(|a, b| {
+            let exact = |token: &PumpToken| token.symbol.eq_ignore_ascii_case(query);
+            exact(b).cmp(&exact(a)).then(b.volume_24h.total_cmp(&a.volume_24h))
+        });
         info!("Found {} tokens matching '{}'", tokens.len(), query);
         
         Ok(tokens)
@@ -227,10 +310,7 @@ impl PumpFunClient {
     pub async fn get_portfolio(&self, wallet_address: &str) -> Result<Vec<PumpToken>> {
         let url = format!("{}/portfolio/{}", self.api_url, wallet_address);
         
-        let response = tokio::time::timeout(
-            self.timeout,
-            self.client.get(&url).send()
-        ).await??;
+        let response = self.send(self.client.get(&url)).await?;
         
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
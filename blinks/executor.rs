@@ -1,20 +1,30 @@
 use anyhow::Result;
 use chrono::Utc;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction, transaction::Transaction};
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn, error, debug};
 
 use super::types::*;
+use crate::api::{create_enhanced_swap_request, JupiterV6Client, QuoteRequestV6};
 use crate::errors::BotError;
 use crate::trading::{TradingEngineHandle, TradeResult};
 use crate::wallet::WalletManager;
 
+const DEFAULT_SLIPPAGE_BPS: u16 = 100;
+
 /// Executes Solana Blinks
 pub struct BlinkExecutor {
     trading_engine: TradingEngineHandle,
     wallet_manager: Arc<WalletManager>,
     execution_cache: Arc<RwLock<HashMap<String, BlinkExecutionResult>>>,
+    /// Builds buy transactions for Action POSTs
+    jupiter: Option<Arc<JupiterV6Client>>,
+    /// Recent blockhash for donation transfers
+    rpc: Option<Arc<RpcClient>>,
 }
 
 impl BlinkExecutor {
@@ -26,7 +36,115 @@ impl BlinkExecutor {
             trading_engine,
             wallet_manager,
             execution_cache: Arc::new(RwLock::new(HashMap::new())),
+            jupiter: None,
+            rpc: None,
+        }
+    }
+    
+    pub fn with_jupiter(mut self, jupiter: Arc<JupiterV6Client>) -> Self {
+        self.jupiter = Some(jupiter);
+        self
+    }
+    
+    pub fn with_rpc(mut self, rpc: Arc<RpcClient>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+    
+    /// Answer an Action POST: the unsigned transaction `account` signs
+    ///
+    /// Falls back to the blink's first preset when no amount was chosen.
+    pub async fn build_action_transaction(
+        &self,
+        blink: &SolanaBlink,
+        request: &ActionPostRequest,
+        amount_sol: Option<f64>,
+    ) -> Result<ActionPostResponse> {
+        let account = Pubkey::from_str(request.account.trim())
+            .map_err(|_| BotError::validation(format!("Invalid account: {}", request.account)))?;
+        self.validate_execution(blink, &account.to_string()).await?;
+        
+        let amount_sol = amount_sol
+            .or_else(|| blink.preset_amounts().first().copied())
+            .ok_or_else(|| BotError::validation("Missing amount"))?;
+        if !amount_sol.is_finite() || amount_sol <= 0.0 {
+            return Err(BotError::validation("Amount must be a positive number of SOL").into());
         }
+        let lamports = (amount_sol * LAMPORTS_PER_SOL as f64).round() as u64;
+        
+        let (transaction, message) = match (&blink.blink_type, &blink.action.action_type) {
+            (BlinkType::TokenSwap, ActionType::Swap { from_token, to_token, .. }) => {
+                let transaction = self.build_swap_transaction(blink, from_token, to_token, lamports, &account).await?;
+                let symbol = blink.action.parameters.get("symbol").cloned().unwrap_or_else(|| to_token.clone());
+                (transaction, format!("Buy {} with {} SOL", symbol, amount_sol))
+            }
+            (BlinkType::Donation, ActionType::Transfer { recipient, .. }) => {
+                let transaction = self.build_transfer_transaction(recipient, lamports, &account).await?;
+                (transaction, format!("Donate {} SOL", amount_sol))
+            }
+            (blink_type, _) => {
+                return Err(BotError::validation(format!("{:?} blinks can't be used as actions", blink_type)).into());
+            }
+        };
+        
+        info!("🔗 Built blink {} transaction for {}", blink.blink_id, account);
+        Ok(ActionPostResponse {
+            kind: "transaction".to_string(),
+            transaction,
+            message: Some(message),
+            links: None,
+        })
+    }
+    
+    /// Jupiter swap with `account` as fee payer; Jupiter returns it unsigned
+    async fn build_swap_transaction(
+        &self,
+        blink: &SolanaBlink,
+        from_token: &str,
+        to_token: &str,
+        lamports: u64,
+        account: &Pubkey,
+    ) -> Result<String> {
+        let jupiter = self.jupiter.as_ref()
+            .ok_or_else(|| BotError::config("Blink buys need a Jupiter client"))?;
+        let slippage_bps = blink.action.parameters.get("slippage_bps")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_SLIPPAGE_BPS);
+        
+        let quote = jupiter.get_quote(QuoteRequestV6 {
+            input_mint: from_token.to_string(),
+            output_mint: to_token.to_string(),
+            amount: lamports,
+            slippage_bps,
+            swap_mode: None,
+            dexes: None,
+            exclude_dexes: None,
+            max_accounts: None,
+            quote_mint: None,
+            minimize_slippage: None,
+            only_direct_routes: None,
+        }).await?;
+        let swap = jupiter.execute_swap(create_enhanced_swap_request(quote, account.to_string())).await?;
+        
+        Ok(swap.swap_transaction)
+    }
+    
+    /// Native SOL transfer from `account`, left for the wallet to sign
+    async fn build_transfer_transaction(&self, recipient: &str, lamports: u64, account: &Pubkey) -> Result<String> {
+        let recipient = Pubkey::from_str(recipient)
+            .map_err(|_| BotError::validation(format!("Invalid recipient: {}", recipient)))?;
+        let rpc = self.rpc.as_ref()
+            .ok_or_else(|| BotError::config("Donation blinks need an RPC client"))?;
+        let blockhash = rpc.get_latest_blockhash().await
+            .map_err(|e| BotError::external_api(format!("Failed to fetch blockhash: {}", e)))?;
+        
+        let mut transaction = Transaction::new_with_payer(
+            &[system_instruction::transfer(account, &recipient, lamports)],
+            Some(account),
+        );
+        transaction.message.recent_blockhash = blockhash;
+        
+        Ok(base64::encode(bincode::serialize(&transaction)?))
     }
     
     /// Execute a Solana Blink
@@ -1,23 +1,240 @@
 use anyhow::Result;
 use chrono::{Duration, Utc};
+use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use tracing::{info, debug};
 
 use super::types::*;
 use crate::errors::BotError;
 
+pub(crate) const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const DEFAULT_BUY_AMOUNTS: [f64; 3] = [0.1, 0.5, 1.0];
+
 /// Generates Solana Blinks for various actions
 pub struct BlinkGenerator {
     base_url: String,
     network: SolanaNetwork,
+    /// Blinks served by the Actions endpoints, by id
+    registered: Arc<RwLock<HashMap<String, SolanaBlink>>>,
 }
 
 impl BlinkGenerator {
     pub fn new(base_url: String, network: SolanaNetwork) -> Self {
         Self {
-            base_url,
+            base_url: base_url.trim_end_matches('/').to_string(),
             network,
+            registered: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+    
+    pub fn network(&self) -> &SolanaNetwork {
+        &self.network
+    }
+    
+    /// Endpoint a blink client GETs and POSTs to
+    pub fn action_url(&self, blink_id: &str) -> String {
+        format!("{}/api/actions/{}", self.base_url, blink_id)
+    }
+    
+    /// Action URL tagged with who shared it, for per-referrer analytics
+    pub fn attributed_url(&self, blink_id: &str, referrer: Option<&str>) -> String {
+        match referrer {
+            Some(referrer) => format!("{}?ref={}", self.action_url(blink_id), urlencoding::encode(referrer)),
+            None => self.action_url(blink_id),
+        }
+    }
+    
+    /// Chained POST target for reporting the signature of `request_id`
+    pub fn confirm_url(&self, blink_id: &str, request_id: &str) -> String {
+        format!("{}/confirm?request={}", self.action_url(blink_id), urlencoding::encode(request_id))
+    }
+    
+    /// dial.to link that unfurls the action anywhere a URL can be pasted
+    pub fn dial_to_url(&self, blink_id: &str, referrer: Option<&str>) -> String {
+        format!(
+            "https://dial.to/?action={}",
+            urlencoding::encode(&format!("solana-action:{}", self.attributed_url(blink_id, referrer)))
+        )
+    }
+    
+    /// Blinks created from `wallet`, newest first
+    pub async fn created_by(&self, wallet: &str) -> Vec<SolanaBlink> {
+        let mut blinks: Vec<SolanaBlink> = self.registered.read().await
+            .values()
+            .filter(|blink| blink.creator.wallet_address == wallet)
+            .cloned()
+            .collect();
+        blinks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        blinks
+    }
+    
+    /// Make a blink available through the Actions endpoints
+    pub async fn register(&self, blink: SolanaBlink) -> SolanaBlink {
+        info!("🔗 Registered blink {} ({:?})", blink.blink_id, blink.blink_type);
+        self.registered.write().await.insert(blink.blink_id.clone(), blink.clone());
+        blink
+    }
+    
+    pub async fn get(&self, blink_id: &str) -> Option<SolanaBlink> {
+        self.registered.read().await.get(blink_id).cloned()
+    }
+    
+    /// Create and register a SOL → token purchase blink
+    pub async fn register_buy_blink(
+        &self,
+        token_mint: String,
+        token_symbol: String,
+        amounts_sol: Vec<f64>,
+        slippage_bps: u16,
+        creator_wallet: String,
+    ) -> Result<SolanaBlink> {
+        let blink = self.create_buy_blink(token_mint, token_symbol, amounts_sol, slippage_bps, creator_wallet)?;
+        Ok(self.register(blink).await)
+    }
+    
+    /// Create and register a SOL donation blink
+    pub async fn register_donation_blink(
+        &self,
+        recipient: String,
+        amounts_sol: Vec<f64>,
+        creator_wallet: String,
+    ) -> Result<SolanaBlink> {
+        let blink = self.create_donation_blink(recipient, amounts_sol, creator_wallet)?;
+        Ok(self.register(blink).await)
+    }
+    
+    /// Create a token purchase blink with preset SOL amounts; empty uses 0.1/0.5/1
+    pub fn create_buy_blink(
+        &self,
+        token_mint: String,
+        token_symbol: String,
+        amounts_sol: Vec<f64>,
+        slippage_bps: u16,
+        creator_wallet: String,
+    ) -> Result<SolanaBlink> {
+        Pubkey::from_str(&token_mint)
+            .map_err(|_| BotError::validation(format!("Invalid token mint: {}", token_mint)))?;
+        let amounts = Self::preset_amounts(amounts_sol)?;
+        
+        let mut blink = self.create_swap_blink(
+            WSOL_MINT.to_string(),
+            "SOL".to_string(),
+            token_mint,
+            token_symbol.clone(),
+            amounts[0],
+            slippage_bps as f64 / 100.0,
+            creator_wallet,
+        )?;
+        blink.title = format!("Buy {}", token_symbol);
+        blink.description = format!("Buy {} with SOL, routed through Jupiter", token_symbol);
+        blink.action.parameters.insert("symbol".to_string(), token_symbol.clone());
+        blink.action.parameters.insert("slippage_bps".to_string(), slippage_bps.to_string());
+        blink.action.parameters.insert("amounts".to_string(), Self::join_amounts(&amounts));
+        blink.metadata.tags.push("buy".to_string());
+        blink.expires_at = None;
+        blink.social_preview = self.create_social_preview(
+            &format!("Buy {}", token_symbol),
+            &format!("One-click {} purchase on Solana", token_symbol),
+        );
+        
+        debug!("Created buy blink {} for {}", blink.blink_id, token_symbol);
+        Ok(blink)
+    }
+    
+    /// Create a blink asking for a SOL donation to `recipient`
+    pub fn create_donation_blink(
+        &self,
+        recipient: String,
+        amounts_sol: Vec<f64>,
+        creator_wallet: String,
+    ) -> Result<SolanaBlink> {
+        Pubkey::from_str(&recipient)
+            .map_err(|_| BotError::validation(format!("Invalid recipient: {}", recipient)))?;
+        let amounts = Self::preset_amounts(amounts_sol)?;
+        
+        let mut blink = self.create_transfer_blink(
+            WSOL_MINT.to_string(),
+            "SOL".to_string(),
+            recipient.clone(),
+            amounts[0],
+            None,
+            creator_wallet,
+        )?;
+        blink.blink_type = BlinkType::Donation;
+        blink.title = "Donate SOL".to_string();
+        blink.description = format!("Send a SOL donation to {}", recipient);
+        blink.action.parameters.insert("amounts".to_string(), Self::join_amounts(&amounts));
+        blink.security.max_uses = None;
+        blink.expires_at = None;
+        blink.social_preview = self.create_social_preview("Donate SOL", "One-click SOL donation on Solana");
+        
+        Ok(blink)
+    }
+    
+    /// Spec metadata for a blink: one button per preset amount plus a custom amount field
+    ///
+    /// The referrer the metadata was fetched with is carried into every button.
+    pub fn action_metadata(&self, blink: &SolanaBlink, referrer: Option<&str>) -> ActionGetResponse {
+        let verb = match blink.blink_type {
+            BlinkType::Donation => "Donate",
+            _ => "Buy",
+        };
+        let href = self.action_url(&blink.blink_id);
+        let attribution = referrer
+            .map(|referrer| format!("&ref={}", urlencoding::encode(referrer)))
+            .unwrap_or_default();
+        
+        let mut actions: Vec<LinkedAction> = blink.preset_amounts()
+            .into_iter()
+            .map(|amount| LinkedAction {
+                kind: "transaction".to_string(),
+                label: format!("{} {} SOL", verb, amount),
+                href: format!("{}?amount={}{}", href, amount, attribution),
+                parameters: vec![],
+            })
+            .collect();
+        actions.push(LinkedAction {
+            kind: "transaction".to_string(),
+            label: verb.to_string(),
+            href: format!("{}?amount={{amount}}{}", href, attribution),
+            parameters: vec![ActionParameter {
+                kind: Some("number".to_string()),
+                name: "amount".to_string(),
+                label: "SOL amount".to_string(),
+                required: true,
+            }],
+        });
+        
+        let validation = blink.validate();
+        ActionGetResponse {
+            kind: "action".to_string(),
+            icon: blink.icon_url.clone()
+                .or_else(|| blink.social_preview.image_url.clone())
+                .unwrap_or_default(),
+            title: blink.title.clone(),
+            description: blink.description.clone(),
+            label: verb.to_string(),
+            disabled: (!validation.is_valid).then_some(true),
+            error: validation.errors.first().map(|e| ActionError { message: e.message.clone() }),
+            links: ActionLinks { actions },
+        }
+    }
+    
+    fn preset_amounts(amounts_sol: Vec<f64>) -> Result<Vec<f64>> {
+        if amounts_sol.is_empty() {
+            return Ok(DEFAULT_BUY_AMOUNTS.to_vec());
         }
+        if amounts_sol.iter().any(|a| !a.is_finite() || *a <= 0.0) {
+            return Err(BotError::validation("Amounts must be positive SOL values").into());
+        }
+        Ok(amounts_sol)
+    }
+    
+    fn join_amounts(amounts: &[f64]) -> String {
+        amounts.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(",")
     }
     
     /// Create a token swap blink
@@ -2,8 +2,12 @@ pub mod types;
 pub mod generator;
 pub mod executor;
 pub mod sharing;
+pub mod server;
+pub mod tracking;
 
 pub use types::*;
 pub use generator::BlinkGenerator;
 pub use executor::BlinkExecutor;
-pub use sharing::{BlinkSharing, ShareAnalytics};
\ No newline at end of file
+pub use server::ActionServer;
+pub use sharing::{BlinkSharing, ShareAnalytics};
+pub use tracking::{BlinkEvent, BlinkEventKind, BlinkFunnel, BlinkTracker};
@@ -0,0 +1,240 @@
+use axum::{
+    extract::{rejection::JsonRejection, Path, Query, State},
+    http::{HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tower_http::cors::CorsLayer;
+use tracing::{info, warn};
+
+use super::{
+    executor::BlinkExecutor,
+    generator::BlinkGenerator,
+    tracking::{BlinkEvent, BlinkEventKind, BlinkTracker},
+    types::*,
+};
+use crate::errors::BotError;
+
+/// Actions spec version we implement
+const ACTION_VERSION: &str = "2.1.3";
+
+/// Solana Actions endpoints for registered blinks
+///
+/// `GET /api/actions/:id` describes the blink, `POST` answers with the
+/// unsigned transaction for the posted account, and `/actions.json` maps
+/// the routes for blink clients that start from the site root. With a
+/// tracker, each step is recorded and transactions chain to
+/// `/api/actions/:id/confirm` so the wallet reports the signature it sent.
+pub struct ActionServer {
+    generator: Arc<BlinkGenerator>,
+    executor: Arc<BlinkExecutor>,
+    tracker: Option<Arc<BlinkTracker>>,
+}
+
+#[derive(Clone)]
+struct ActionState {
+    generator: Arc<BlinkGenerator>,
+    executor: Arc<BlinkExecutor>,
+    tracker: Option<Arc<BlinkTracker>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActionQuery {
+    amount: Option<String>,
+    #[serde(rename = "ref")]
+    referrer: Option<String>,
+    request: Option<String>,
+}
+
+impl ActionServer {
+    pub fn new(generator: Arc<BlinkGenerator>, executor: Arc<BlinkExecutor>) -> Self {
+        Self { generator, executor, tracker: None }
+    }
+
+    pub fn with_tracker(mut self, tracker: Arc<BlinkTracker>) -> Self {
+        self.tracker = Some(tracker);
+        self
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/actions.json", get(actions_json))
+            .route("/api/actions/:blink_id", get(get_action).post(post_action))
+            .route("/api/actions/:blink_id/confirm", post(confirm_action))
+            .with_state(ActionState {
+                generator: self.generator.clone(),
+                executor: self.executor.clone(),
+                tracker: self.tracker.clone(),
+            })
+            // Blink clients fetch from any origin and answer preflights here
+            .layer(CorsLayer::permissive())
+    }
+
+    /// Bind `0.0.0.0:port` and serve until the task is dropped
+    pub async fn start(&self, port: u16) -> std::io::Result<()> {
+        let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+        self.serve(listener).await
+    }
+
+    /// Serve on an already bound listener
+    pub async fn serve(&self, listener: TcpListener) -> std::io::Result<()> {
+        info!("🔗 Solana Actions on http://{}/api/actions", listener.local_addr()?);
+        axum::serve(listener, self.router()).await
+    }
+}
+
+async fn actions_json() -> Json<serde_json::Value> {
+    Json(json!({
+        "rules": [{ "pathPattern": "/api/actions/**", "apiPath": "/api/actions/**" }]
+    }))
+}
+
+async fn get_action(
+    State(state): State<ActionState>,
+    Path(blink_id): Path<String>,
+    Query(query): Query<ActionQuery>,
+) -> Response {
+    let referrer = query.referrer.as_deref();
+    let response = match state.generator.get(&blink_id).await {
+        Some(blink) => {
+            track(&state, BlinkEvent::new(&blink_id, referrer, BlinkEventKind::Impression)).await;
+            Json(state.generator.action_metadata(&blink, referrer)).into_response()
+        }
+        None => action_error(StatusCode::NOT_FOUND, "Unknown blink"),
+    };
+    with_action_headers(&state, response)
+}
+
+async fn post_action(
+    State(state): State<ActionState>,
+    Path(blink_id): Path<String>,
+    Query(query): Query<ActionQuery>,
+    body: Result<Json<ActionPostRequest>, JsonRejection>,
+) -> Response {
+    let response = match build_transaction(&state, &blink_id, query, body).await {
+        Ok(posted) => Json(posted).into_response(),
+        Err(response) => response,
+    };
+    with_action_headers(&state, response)
+}
+
+async fn build_transaction(
+    state: &ActionState,
+    blink_id: &str,
+    query: ActionQuery,
+    body: Result<Json<ActionPostRequest>, JsonRejection>,
+) -> Result<ActionPostResponse, Response> {
+    let Some(blink) = state.generator.get(blink_id).await else {
+        return Err(action_error(StatusCode::NOT_FOUND, "Unknown blink"));
+    };
+    let referrer = query.referrer.as_deref();
+    let request_id = match &state.tracker {
+        Some(tracker) => tracker.click(blink_id, referrer).await
+            .map_err(|e| warn!("🔗 Failed to record blink click: {}", e))
+            .ok(),
+        None => None,
+    };
+
+    let Json(request) = body
+        .map_err(|_| action_error(StatusCode::BAD_REQUEST, "Body must be JSON with an `account` field"))?;
+    let amount = query.amount
+        .map(|amount| amount.trim().parse::<f64>())
+        .transpose()
+        .map_err(|_| action_error(StatusCode::BAD_REQUEST, "Amount must be a number of SOL"))?
+        .or_else(|| blink.preset_amounts().first().copied());
+
+    let mut posted = state.executor.build_action_transaction(&blink, &request, amount).await.map_err(|e| {
+        let status = match e.downcast_ref::<BotError>() {
+            Some(BotError::Validation { .. }) => StatusCode::BAD_REQUEST,
+            Some(BotError::Config(_)) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        if status != StatusCode::BAD_REQUEST {
+            warn!("🔗 Blink {} transaction failed: {}", blink_id, e);
+        }
+        action_error(status, &BotError::user_message_for(&e))
+    })?;
+
+    if let Some(request_id) = request_id {
+        let mut event = BlinkEvent::new(blink_id, referrer, BlinkEventKind::TransactionRequested).with_request(&request_id);
+        event.amount_sol = amount;
+        track(state, event).await;
+        posted.links = Some(PostResponseLinks {
+            next: NextActionLink {
+                kind: "post".to_string(),
+                href: state.generator.confirm_url(blink_id, &request_id),
+            },
+        });
+    }
+    Ok(posted)
+}
+
+/// Chained POST after the wallet sent the transaction; confirmation is watched in the background
+async fn confirm_action(
+    State(state): State<ActionState>,
+    Path(blink_id): Path<String>,
+    Query(query): Query<ActionQuery>,
+    body: Result<Json<ActionPostRequest>, JsonRejection>,
+) -> Response {
+    let Some(blink) = state.generator.get(&blink_id).await else {
+        return with_action_headers(&state, action_error(StatusCode::NOT_FOUND, "Unknown blink"));
+    };
+    let signature = match body {
+        Ok(Json(ActionPostRequest { signature: Some(signature), .. })) => signature,
+        _ => {
+            let response = action_error(StatusCode::BAD_REQUEST, "Body must be JSON with `account` and `signature`");
+            return with_action_headers(&state, response);
+        }
+    };
+
+    if let (Some(tracker), Some(request_id)) = (&state.tracker, query.request.as_deref()) {
+        if let Err(e) = tracker.watch_confirmation(&blink_id, request_id, &signature).await {
+            let status = match &e {
+                BotError::Validation { .. } => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            if status != StatusCode::BAD_REQUEST {
+                warn!("🔗 Blink {} confirmation failed: {}", blink_id, e);
+            }
+            return with_action_headers(&state, action_error(status, &e.user_message()));
+        }
+    }
+
+    let completed = CompletedAction {
+        kind: "completed".to_string(),
+        icon: state.generator.action_metadata(&blink, None).icon,
+        title: blink.title.clone(),
+        description: "Transaction sent. Thanks for using this blink!".to_string(),
+        label: "Done".to_string(),
+    };
+    with_action_headers(&state, Json(completed).into_response())
+}
+
+/// Analytics never stand in the way of an action
+async fn track(state: &ActionState, event: BlinkEvent) {
+    if let Some(tracker) = &state.tracker {
+        if let Err(e) = tracker.record(event).await {
+            warn!("🔗 Failed to record blink event: {}", e);
+        }
+    }
+}
+
+/// Spec error body
+fn action_error(status: StatusCode, message: &str) -> Response {
+    (status, Json(ActionError { message: message.to_string() })).into_response()
+}
+
+fn with_action_headers(state: &ActionState, mut response: Response) -> Response {
+    let headers = response.headers_mut();
+    headers.insert(HeaderName::from_static("x-action-version"), HeaderValue::from_static(ACTION_VERSION));
+    headers.insert(
+        HeaderName::from_static("x-blockchain-ids"),
+        HeaderValue::from_static(state.generator.network().chain_id()),
+    );
+    response
+}
@@ -0,0 +1,358 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::TransactionConfirmationStatus;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::db::Database;
+use crate::errors::{BotError, Result};
+
+/// Events older than this are purged
+pub const EVENT_RETENTION_DAYS: i64 = 90;
+/// Referrer bucket for links opened without a `ref` parameter
+pub const DIRECT_REFERRER: &str = "direct";
+
+const PURGE_INTERVAL_SECS: u64 = 24 * 60 * 60;
+const CONFIRMATION_POLL: std::time::Duration = std::time::Duration::from_secs(2);
+/// A blockhash expires after ~150 slots; anything unfinalized by then never lands
+const CONFIRMATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Funnel stage of a blink event, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BlinkEventKind {
+    /// Metadata fetched by a blink client
+    Impression,
+    /// A button was pressed and POSTed
+    Click,
+    /// We answered the POST with a transaction
+    TransactionRequested,
+    /// The signed transaction finalized on chain
+    TransactionConfirmed,
+}
+
+impl BlinkEventKind {
+    /// Stored form in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Impression => "impression",
+            Self::Click => "click",
+            Self::TransactionRequested => "transaction_requested",
+            Self::TransactionConfirmed => "transaction_confirmed",
+        }
+    }
+}
+
+/// One step of one visitor through a blink
+///
+/// Everything past an impression carries the `request_id` handed out with
+/// the click, which is what ties the funnel together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlinkEvent {
+    pub event_id: String,
+    pub blink_id: String,
+    pub referrer: Option<String>,
+    pub kind: BlinkEventKind,
+    pub request_id: Option<String>,
+    pub amount_sol: Option<f64>,
+    pub signature: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+impl BlinkEvent {
+    pub fn new(blink_id: &str, referrer: Option<&str>, kind: BlinkEventKind) -> Self {
+        Self {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            blink_id: blink_id.to_string(),
+            referrer: referrer.map(str::to_string),
+            kind,
+            request_id: None,
+            amount_sol: None,
+            signature: None,
+            at: Utc::now(),
+        }
+    }
+
+    pub fn with_request(mut self, request_id: &str) -> Self {
+        self.request_id = Some(request_id.to_string());
+        self
+    }
+
+    pub fn with_amount(mut self, amount_sol: f64) -> Self {
+        self.amount_sol = Some(amount_sol);
+        self
+    }
+
+    pub fn with_signature(mut self, signature: &str) -> Self {
+        self.signature = Some(signature.to_string());
+        self
+    }
+
+    pub fn at(mut self, at: DateTime<Utc>) -> Self {
+        self.at = at;
+        self
+    }
+}
+
+/// Impressions, clicks and conversions over a set of events
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlinkFunnel {
+    pub impressions: u64,
+    pub clicks: u64,
+    pub transactions_requested: u64,
+    pub transactions_confirmed: u64,
+    /// SOL moved by confirmed transactions
+    pub volume_sol: f64,
+}
+
+impl BlinkFunnel {
+    /// Count each request once at the furthest stage it reached
+    ///
+    /// Events can arrive in any order and more than once (a confirmation
+    /// restored before its click, a wallet posting its signature twice), so
+    /// a later stage implies the earlier ones and duplicates collapse.
+    pub fn from_events<'a>(events: impl IntoIterator<Item = &'a BlinkEvent>) -> Self {
+        let mut funnel = Self::default();
+        let mut requests: HashMap<&str, (BlinkEventKind, Option<f64>)> = HashMap::new();
+
+        for event in events {
+            if event.kind == BlinkEventKind::Impression {
+                funnel.impressions += 1;
+                continue;
+            }
+            let Some(request_id) = event.request_id.as_deref() else {
+                continue;
+            };
+            let entry = requests.entry(request_id).or_insert((event.kind, None));
+            entry.0 = entry.0.max(event.kind);
+            entry.1 = entry.1.or(event.amount_sol);
+        }
+
+        for (stage, amount) in requests.into_values() {
+            funnel.clicks += 1;
+            if stage >= BlinkEventKind::TransactionRequested {
+                funnel.transactions_requested += 1;
+            }
+            if stage == BlinkEventKind::TransactionConfirmed {
+                funnel.transactions_confirmed += 1;
+                funnel.volume_sol += amount.unwrap_or(0.0);
+            }
+        }
+        funnel
+    }
+
+    /// Confirmed transactions per click, as a percentage
+    pub fn conversion_rate(&self) -> f64 {
+        if self.clicks == 0 {
+            return 0.0;
+        }
+        self.transactions_confirmed as f64 / self.clicks as f64 * 100.0
+    }
+}
+
+/// Records blink funnel events and watches posted signatures until they finalize
+pub struct BlinkTracker {
+    events: RwLock<Vec<BlinkEvent>>,
+    database: Option<Arc<Database>>,
+    rpc: Option<Arc<RpcClient>>,
+    poll_interval: std::time::Duration,
+    confirmation_timeout: std::time::Duration,
+}
+
+impl Default for BlinkTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlinkTracker {
+    pub fn new() -> Self {
+        Self {
+            events: RwLock::new(Vec::new()),
+            database: None,
+            rpc: None,
+            poll_interval: CONFIRMATION_POLL,
+            confirmation_timeout: CONFIRMATION_TIMEOUT,
+        }
+    }
+
+    /// Persist events so the funnel survives restarts
+    pub fn with_database(mut self, database: Arc<Database>) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// Needed to confirm posted signatures; without it nothing converts
+    pub fn with_rpc(mut self, rpc: Arc<RpcClient>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    pub fn with_confirmation_polling(mut self, interval: std::time::Duration, timeout: std::time::Duration) -> Self {
+        self.poll_interval = interval;
+        self.confirmation_timeout = timeout;
+        self
+    }
+
+    pub async fn record(&self, event: BlinkEvent) -> Result<()> {
+        debug!("🔗 Blink {} {} ({:?})", event.blink_id, event.kind.as_str(), event.request_id);
+        if let Some(database) = &self.database {
+            let data = serde_json::to_string(&event)
+                .map_err(|e| BotError::parsing(format!("Failed to serialize blink event: {}", e)))?;
+            database.insert_blink_event(&event.event_id, &event.blink_id, event.kind.as_str(), &data, event.at).await?;
+        }
+        self.events.write().await.push(event);
+        Ok(())
+    }
+
+    /// Record a click and return the request id that follows it through the funnel
+    pub async fn click(&self, blink_id: &str, referrer: Option<&str>) -> Result<String> {
+        let request_id = uuid::Uuid::new_v4().to_string();
+        self.record(BlinkEvent::new(blink_id, referrer, BlinkEventKind::Click).with_request(&request_id)).await?;
+        Ok(request_id)
+    }
+
+    /// Poll RPC for `signature` in the background; a finalized success converts the request
+    ///
+    /// A signature already counted for another request is ignored.
+    pub async fn watch_confirmation(self: &Arc<Self>, blink_id: &str, request_id: &str, signature: &str) -> Result<()> {
+        let parsed = Signature::from_str(signature)
+            .map_err(|_| BotError::validation(format!("Invalid signature: {}", signature)))?;
+        let rpc = self.rpc.clone()
+            .ok_or_else(|| BotError::config("Blink confirmations need an RPC client"))?;
+
+        let (referrer, amount) = {
+            let events = self.events.read().await;
+            if events.iter().any(|e| e.signature.as_deref() == Some(signature) && e.request_id.as_deref() != Some(request_id)) {
+                return Err(BotError::validation("Signature already recorded"));
+            }
+            let request = events.iter().filter(|e| e.blink_id == blink_id && e.request_id.as_deref() == Some(request_id));
+            let mut referrer = None;
+            let mut amount = None;
+            for event in request {
+                referrer = referrer.or(event.referrer.clone());
+                amount = amount.or(event.amount_sol);
+            }
+            (referrer, amount)
+        };
+
+        let tracker = self.clone();
+        let (blink_id, request_id, signature) = (blink_id.to_string(), request_id.to_string(), signature.to_string());
+        tokio::spawn(async move {
+            let deadline = tokio::time::Instant::now() + tracker.confirmation_timeout;
+            while tokio::time::Instant::now() < deadline {
+                match rpc.get_signature_statuses(&[parsed]).await {
+                    Ok(response) => match response.value.into_iter().next().flatten() {
+                        Some(status) if status.err.is_some() => {
+                            info!("🔗 Blink {} transaction {} failed on chain", blink_id, signature);
+                            return;
+                        }
+                        Some(status) if status.confirmation_status == Some(TransactionConfirmationStatus::Finalized) => {
+                            let mut event = BlinkEvent::new(&blink_id, referrer.as_deref(), BlinkEventKind::TransactionConfirmed)
+                                .with_request(&request_id)
+                                .with_signature(&signature);
+                            event.amount_sol = amount;
+                            if let Err(e) = tracker.record(event).await {
+                                warn!("🔗 Failed to record blink confirmation: {}", e);
+                            }
+                            return;
+                        }
+                        _ => {}
+                    },
+                    Err(e) => debug!("🔗 Signature status for {} unavailable: {}", signature, e),
+                }
+                tokio::time::sleep(tracker.poll_interval).await;
+            }
+            info!("🔗 Blink {} transaction {} never finalized", blink_id, signature);
+        });
+        Ok(())
+    }
+
+    pub async fn funnel(&self, blink_id: &str) -> BlinkFunnel {
+        let events = self.events.read().await;
+        BlinkFunnel::from_events(events.iter().filter(|e| e.blink_id == blink_id))
+    }
+
+    /// The funnel split by `ref` parameter
+    pub async fn funnel_by_referrer(&self, blink_id: &str) -> BTreeMap<String, BlinkFunnel> {
+        let events = self.events.read().await;
+        let mut grouped: BTreeMap<String, Vec<&BlinkEvent>> = BTreeMap::new();
+        for event in events.iter().filter(|e| e.blink_id == blink_id) {
+            let referrer = event.referrer.clone().unwrap_or_else(|| DIRECT_REFERRER.to_string());
+            grouped.entry(referrer).or_default().push(event);
+        }
+        grouped.into_iter().map(|(referrer, events)| (referrer, BlinkFunnel::from_events(events))).collect()
+    }
+
+    /// Clicks per day for the `days` days up to and including `now`, oldest first
+    pub async fn daily_clicks(&self, blink_id: &str, days: usize, now: DateTime<Utc>) -> Vec<f64> {
+        let today = now.date_naive();
+        let mut counts = vec![0.0; days];
+        for event in self.events.read().await.iter() {
+            if event.blink_id != blink_id || event.kind != BlinkEventKind::Click {
+                continue;
+            }
+            let age = (today - event.at.date_naive()).num_days();
+            if (0..days as i64).contains(&age) {
+                counts[days - 1 - age as usize] += 1.0;
+            }
+        }
+        counts
+    }
+
+    /// Drop events older than the retention window
+    pub async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize> {
+        let cutoff = now - Duration::days(EVENT_RETENTION_DAYS);
+        if let Some(database) = &self.database {
+            database.delete_blink_events_before(cutoff).await?;
+        }
+        let mut events = self.events.write().await;
+        let before = events.len();
+        events.retain(|e| e.at >= cutoff);
+        Ok(before - events.len())
+    }
+
+    /// Restore events still inside the retention window
+    pub async fn load(&self) -> Result<usize> {
+        let Some(database) = &self.database else { return Ok(0) };
+        let cutoff = Utc::now() - Duration::days(EVENT_RETENTION_DAYS);
+        let mut restored: Vec<BlinkEvent> = database.get_blink_events_since(cutoff).await?
+            .into_iter()
+            .filter_map(|row| match serde_json::from_str(&row) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    warn!("🔗 Skipping unreadable blink event: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        let mut events = self.events.write().await;
+        events.append(&mut restored);
+        info!("🔗 Restored {} blink events", events.len());
+        Ok(events.len())
+    }
+
+    /// Restore stored events, then purge past the retention window daily
+    pub fn spawn_maintenance(self: &Arc<Self>) {
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = tracker.load().await {
+                warn!("🔗 Failed to restore blink events: {}", e);
+            }
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(PURGE_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                match tracker.purge_expired(Utc::now()).await {
+                    Ok(0) => {}
+                    Ok(purged) => info!("🔗 Purged {} blink events past retention", purged),
+                    Err(e) => warn!("🔗 Blink event purge failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -136,6 +136,18 @@ pub enum SolanaNetwork {
     Localnet,
 }
 
+impl SolanaNetwork {
+    /// CAIP-2 id sent in the `X-Blockchain-Ids` header
+    pub fn chain_id(&self) -> &'static str {
+        match self {
+            SolanaNetwork::Mainnet => "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp",
+            SolanaNetwork::Devnet => "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1",
+            SolanaNetwork::Testnet => "solana:4uhcVJyU9pJkvQyS88uRDiswHXSCkY3z",
+            SolanaNetwork::Localnet => "solana:localnet",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlinkSecurity {
     pub verified: bool,
@@ -297,6 +309,13 @@ impl SolanaBlink {
         format!("{}/blink/{}", base_url, compressed.to_base64())
     }
     
+    /// SOL amounts offered as one-click buttons
+    pub fn preset_amounts(&self) -> Vec<f64> {
+        self.action.parameters.get("amounts")
+            .map(|amounts| amounts.split(',').filter_map(|a| a.trim().parse().ok()).collect())
+            .unwrap_or_default()
+    }
+    
     /// Compress the blink for URL sharing
     pub fn compress(&self) -> CompressedBlink {
         CompressedBlink {
@@ -377,4 +396,98 @@ impl CompressedBlink {
         serde_json::from_str(&json)
             .map_err(|e| format!("Failed to parse JSON: {}", e))
     }
-}
\ No newline at end of file
+}
+
+// Solana Actions spec (https://solana.com/docs/advanced/actions) wire types
+
+/// GET response describing what a blink client should render
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionGetResponse {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub icon: String,
+    pub title: String,
+    pub description: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ActionError>,
+    pub links: ActionLinks,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionLinks {
+    pub actions: Vec<LinkedAction>,
+}
+
+/// One button; `{name}` placeholders in `href` are filled from `parameters`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedAction {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub label: String,
+    pub href: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<ActionParameter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionParameter {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    pub name: String,
+    pub label: String,
+    pub required: bool,
+}
+
+/// POST body; `account` is the wallet that will sign
+///
+/// Chained POSTs to `links.next` also carry the signature it was sent with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionPostRequest {
+    pub account: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// POST response carrying the unsigned, base64 serialized transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionPostResponse {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub transaction: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<PostResponseLinks>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostResponseLinks {
+    pub next: NextActionLink,
+}
+
+/// Where the client POSTs `{account, signature}` once the transaction confirms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NextActionLink {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub href: String,
+}
+
+/// Terminal state shown after a chained action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedAction {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub icon: String,
+    pub title: String,
+    pub description: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionError {
+    pub message: String,
+}
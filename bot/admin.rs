@@ -0,0 +1,471 @@
+//! Operator controls behind /admin: maintenance mode, announcements and live stats
+//!
+//! Maintenance and the current broadcast are written through to storage, so a
+//! restart neither reopens trading nor starts an announcement over.
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::{prelude::*, RequestError};
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::Database,
+    errors::{BotError, Result},
+    trading::{DCAEngine, OrderManager, TradingPause},
+    utils::{t, DEFAULT_LANG},
+};
+use super::{callback_action::{self, CallbackAction}, preferences::PreferenceStore, Command};
+
+/// Telegram's limit on messages a second across chats
+pub const TELEGRAM_MESSAGES_PER_SEC: u32 = 30;
+/// Announcement send rate, under Telegram's limit so replies to users still go out
+pub const BROADCAST_MESSAGES_PER_SEC: u32 = 25;
+/// Recipients between progress saves, which bounds repeats after a crash
+pub const BROADCAST_CHECKPOINT_EVERY: usize = 25;
+/// Longest announcement; Telegram's message limit less room for the footer
+pub const MAX_BROADCAST_LEN: usize = 3_800;
+/// Waits honoured for one recipient before counting them as failed
+const MAX_RETRIES: u32 = 3;
+
+const MAINTENANCE_KEY: &str = "maintenance";
+const BROADCAST_KEY: &str = "broadcast";
+
+/// Trading paused by an operator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Maintenance {
+    pub started_by: String,
+    pub started_at: DateTime<Utc>,
+    /// Shown to users under the banner
+    pub note: Option<String>,
+}
+
+/// An announcement and how far it got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Broadcast {
+    pub id: String,
+    pub text: String,
+    pub started_by: String,
+    pub started_at: DateTime<Utc>,
+    /// Everyone known when the broadcast started, in send order
+    pub recipients: Vec<i64>,
+    /// Index of the next recipient
+    pub cursor: usize,
+    pub sent: usize,
+    pub opted_out: usize,
+    pub failed: usize,
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+impl Broadcast {
+    pub fn remaining(&self) -> usize {
+        self.recipients.len().saturating_sub(self.cursor)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// Progress line for the admin who started it
+    pub fn summary(&self) -> String {
+        let state = match (self.cancelled, self.is_finished()) {
+            (true, _) => "🛑 Cancelled",
+            (false, true) => "✅ Finished",
+            (false, false) => "📣 Sending",
+        };
+        format!(
+            "{} {}/{}\nSent: {}\nOpted out: {}\nFailed: {}",
+            state, self.cursor, self.recipients.len(), self.sent, self.opted_out, self.failed
+        )
+    }
+}
+
+/// Live counts behind /admin stats
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminStats {
+    pub users: usize,
+    pub active_orders: usize,
+    pub active_dca_strategies: usize,
+    pub volume_24h_sol: f64,
+    pub maintenance: bool,
+}
+
+/// Outcome of sending one announcement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Delivery {
+    Sent,
+    /// Telegram asked the bot to slow down
+    RetryAfter(Duration),
+    /// Blocked the bot, deleted their account, or another error not worth retrying
+    Failed(String),
+}
+
+/// Delivers announcements; mocked in tests
+#[async_trait::async_trait]
+pub trait AnnouncementSender: Send + Sync {
+    async fn send(&self, user_id: i64, text: &str) -> Delivery;
+}
+
+#[async_trait::async_trait]
+impl AnnouncementSender for Bot {
+    async fn send(&self, user_id: i64, text: &str) -> Delivery {
+        match self.send_message(ChatId(user_id), text).await {
+            Ok(_) => Delivery::Sent,
+            Err(RequestError::RetryAfter(after)) => Delivery::RetryAfter(after.duration()),
+            Err(e) => Delivery::Failed(e.to_string()),
+        }
+    }
+}
+
+/// Where admin state outlives the process, plus the counts only storage knows
+#[async_trait::async_trait]
+pub trait AdminStorage: Send + Sync {
+    /// Every user the bot has a record of
+    async fn user_ids(&self) -> Result<Vec<i64>>;
+    /// SOL traded since `since`, buys and sells
+    async fn trade_volume_since(&self, since: DateTime<Utc>) -> Result<f64>;
+    async fn load_state(&self, key: &str) -> Result<Option<String>>;
+    async fn save_state(&self, key: &str, data: &str) -> Result<()>;
+    async fn clear_state(&self, key: &str) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl AdminStorage for Database {
+    async fn user_ids(&self) -> Result<Vec<i64>> {
+        self.list_user_ids().await
+    }
+
+    async fn trade_volume_since(&self, since: DateTime<Utc>) -> Result<f64> {
+        self.get_trade_volume_since(since).await
+    }
+
+    async fn load_state(&self, key: &str) -> Result<Option<String>> {
+        self.get_admin_state(key).await
+    }
+
+    async fn save_state(&self, key: &str, data: &str) -> Result<()> {
+        self.upsert_admin_state(key, data).await
+    }
+
+    async fn clear_state(&self, key: &str) -> Result<()> {
+        self.delete_admin_state(key).await
+    }
+}
+
+/// Maintenance mode, the current broadcast and the stats operators check
+pub struct AdminDesk {
+    maintenance: RwLock<Option<Maintenance>>,
+    broadcast: RwLock<Option<Broadcast>>,
+    /// Held while a broadcast is sending, so a resume and a new start can't overlap
+    sending: Mutex<()>,
+    messages_per_sec: u32,
+    storage: Option<Arc<dyn AdminStorage>>,
+}
+
+impl Default for AdminDesk {
+    fn default() -> Self {
+        Self {
+            maintenance: RwLock::new(None),
+            broadcast: RwLock::new(None),
+            sending: Mutex::new(()),
+            messages_per_sec: BROADCAST_MESSAGES_PER_SEC,
+            storage: None,
+        }
+    }
+}
+
+impl AdminDesk {
+    pub fn with_storage(mut self, storage: Arc<dyn AdminStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Announcement send rate, at most Telegram's limit
+    pub fn with_rate(mut self, messages_per_sec: u32) -> Self {
+        self.messages_per_sec = messages_per_sec.clamp(1, TELEGRAM_MESSAGES_PER_SEC);
+        self
+    }
+
+    /// Load maintenance and the last broadcast; the broadcast when it still has recipients left
+    pub async fn restore(&self) -> Result<Option<Broadcast>> {
+        let Some(storage) = &self.storage else { return Ok(None) };
+
+        if let Some(data) = storage.load_state(MAINTENANCE_KEY).await? {
+            let maintenance: Maintenance = serde_json::from_str(&data)
+                .map_err(|e| BotError::parsing(format!("Failed to parse maintenance state: {}", e)))?;
+            info!("🛠️ Maintenance mode restored, started by {} at {}", maintenance.started_by, maintenance.started_at);
+            *self.maintenance.write().await = Some(maintenance);
+        }
+
+        let Some(data) = storage.load_state(BROADCAST_KEY).await? else { return Ok(None) };
+        let broadcast: Broadcast = serde_json::from_str(&data)
+            .map_err(|e| BotError::parsing(format!("Failed to parse broadcast state: {}", e)))?;
+        let unfinished = (!broadcast.is_finished()).then(|| broadcast.clone());
+        *self.broadcast.write().await = Some(broadcast);
+        Ok(unfinished)
+    }
+
+    pub async fn maintenance(&self) -> Option<Maintenance> {
+        self.maintenance.read().await.clone()
+    }
+
+    /// Pause trade-executing commands; a second call replaces the note
+    pub async fn start_maintenance(&self, admin: &str, note: Option<String>) -> Result<Maintenance> {
+        let maintenance = Maintenance {
+            started_by: admin.to_string(),
+            started_at: Utc::now(),
+            note: note.filter(|note| !note.trim().is_empty()),
+        };
+        if let Some(storage) = &self.storage {
+            let data = serde_json::to_string(&maintenance)
+                .map_err(|e| BotError::parsing(format!("Failed to serialize maintenance state: {}", e)))?;
+            storage.save_state(MAINTENANCE_KEY, &data).await?;
+        }
+        *self.maintenance.write().await = Some(maintenance.clone());
+        info!("🛠️ Maintenance mode on, started by {}", admin);
+        Ok(maintenance)
+    }
+
+    /// Reopen trading; the maintenance that ended, if any
+    pub async fn end_maintenance(&self) -> Result<Option<Maintenance>> {
+        if let Some(storage) = &self.storage {
+            storage.clear_state(MAINTENANCE_KEY).await?;
+        }
+        let ended = self.maintenance.write().await.take();
+        if ended.is_some() {
+            info!("🛠️ Maintenance mode off");
+        }
+        Ok(ended)
+    }
+
+    /// The maintenance that stops `cmd` from running, if any
+    pub async fn blocking(&self, cmd: &Command) -> Option<Maintenance> {
+        if !cmd.submits_transaction() {
+            return None;
+        }
+        self.maintenance().await
+    }
+
+    /// Like `blocking`, for quick-buy buttons
+    pub async fn blocking_action(&self, action: &CallbackAction) -> Option<Maintenance> {
+        if !action.executes_trade() {
+            return None;
+        }
+        self.maintenance().await
+    }
+
+    /// Like `blocking`, for confirmation buttons such as lend, send and panic
+    pub async fn blocking_callback(&self, data: &str) -> Option<Maintenance> {
+        if !callback_action::submits_transaction(data) {
+            return None;
+        }
+        self.maintenance().await
+    }
+
+    /// What users see instead of their trade
+    pub fn banner(maintenance: &Maintenance, lang: &str) -> String {
+        match &maintenance.note {
+            Some(note) => format!("{}\n\n{}", t(lang, "maintenance.banner"), note),
+            None => t(lang, "maintenance.banner"),
+        }
+    }
+
+    pub async fn broadcast(&self) -> Option<Broadcast> {
+        self.broadcast.read().await.clone()
+    }
+
+    /// Queue `text` for everyone the bot knows; refused while another broadcast is unfinished
+    pub async fn start_broadcast(&self, admin: &str, text: &str) -> Result<Broadcast> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(BotError::validation("Announcement text is empty").into());
+        }
+        if text.chars().count() > MAX_BROADCAST_LEN {
+            return Err(BotError::validation(format!("Announcements are limited to {} characters", MAX_BROADCAST_LEN)).into());
+        }
+        let Some(storage) = &self.storage else {
+            return Err(BotError::validation("Broadcasts need storage to list users and keep progress").into());
+        };
+        // Held until the new broadcast is stored, so two starts can't both pass the check
+        let mut current = self.broadcast.write().await;
+        if let Some(current) = current.as_ref().filter(|broadcast| !broadcast.is_finished()) {
+            return Err(BotError::validation(format!(
+                "A broadcast is still sending ({} left); cancel it first", current.remaining()
+            )).into());
+        }
+
+        let mut recipients = storage.user_ids().await?;
+        recipients.sort_unstable();
+        recipients.dedup();
+        let broadcast = Broadcast {
+            id: Uuid::new_v4().to_string(),
+            text: text.to_string(),
+            started_by: admin.to_string(),
+            started_at: Utc::now(),
+            recipients,
+            cursor: 0,
+            sent: 0,
+            opted_out: 0,
+            failed: 0,
+            finished_at: None,
+            cancelled: false,
+        };
+        self.save_broadcast(&broadcast).await?;
+        *current = Some(broadcast.clone());
+        drop(current);
+        info!("📣 Broadcast {} queued by {} for {} users", broadcast.id, admin, broadcast.recipients.len());
+        Ok(broadcast)
+    }
+
+    /// Stop the current broadcast once the message in flight is out
+    pub async fn cancel_broadcast(&self) -> Result<Option<Broadcast>> {
+        let cancelled = {
+            let mut current = self.broadcast.write().await;
+            let Some(broadcast) = current.as_mut().filter(|broadcast| !broadcast.is_finished()) else {
+                return Ok(None);
+            };
+            broadcast.cancelled = true;
+            broadcast.finished_at = Some(Utc::now());
+            broadcast.clone()
+        };
+        self.save_broadcast(&cancelled).await?;
+        info!("📣 Broadcast {} cancelled at {}/{}", cancelled.id, cancelled.cursor, cancelled.recipients.len());
+        Ok(Some(cancelled))
+    }
+
+    /// Send the current broadcast from where it left off, paced to the send rate
+    ///
+    /// Users who turned announcements off are skipped. Progress is saved every
+    /// `BROADCAST_CHECKPOINT_EVERY` recipients, so after a crash at most that
+    /// many get the announcement twice. Returns the broadcast as it ended, or
+    /// `None` when nothing was left to send.
+    pub async fn run_broadcast(
+        &self,
+        sender: &dyn AnnouncementSender,
+        preferences: &PreferenceStore,
+    ) -> Result<Option<Broadcast>> {
+        let _sending = self.sending.lock().await;
+        let Some(mut broadcast) = self.broadcast().await.filter(|broadcast| !broadcast.is_finished()) else {
+            return Ok(None);
+        };
+        info!("📣 Sending broadcast {} from {}/{}", broadcast.id, broadcast.cursor, broadcast.recipients.len());
+
+        let mut pace = tokio::time::interval(Duration::from_secs_f64(1.0 / self.messages_per_sec as f64));
+        pace.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        while broadcast.cursor < broadcast.recipients.len() {
+            let user_id = broadcast.recipients[broadcast.cursor];
+            let settings = preferences.get(user_id).await;
+            if settings.notifications.announcements {
+                let lang = settings.language.as_deref().unwrap_or(DEFAULT_LANG);
+                let text = format!("{}\n\n{}", broadcast.text, t(lang, "broadcast.footer"));
+                if Self::deliver(sender, &mut pace, user_id, &text).await {
+                    broadcast.sent += 1;
+                } else {
+                    broadcast.failed += 1;
+                }
+            } else {
+                broadcast.opted_out += 1;
+            }
+            broadcast.cursor += 1;
+
+            let due = broadcast.cursor % BROADCAST_CHECKPOINT_EVERY == 0;
+            if (due || self.is_cancelled(&broadcast.id).await) && !self.checkpoint(&broadcast).await? {
+                return Ok(self.broadcast().await);
+            }
+        }
+
+        broadcast.finished_at = Some(Utc::now());
+        if !self.checkpoint(&broadcast).await? {
+            return Ok(self.broadcast().await);
+        }
+        info!(
+            "📣 Broadcast {} finished: {} sent, {} opted out, {} failed",
+            broadcast.id, broadcast.sent, broadcast.opted_out, broadcast.failed
+        );
+        Ok(Some(broadcast))
+    }
+
+    /// Send to one recipient, waiting out rate limits; false when it never went through
+    async fn deliver(sender: &dyn AnnouncementSender, pace: &mut tokio::time::Interval, user_id: i64, text: &str) -> bool {
+        for _ in 0..=MAX_RETRIES {
+            pace.tick().await;
+            match sender.send(user_id, text).await {
+                Delivery::Sent => return true,
+                Delivery::RetryAfter(wait) => {
+                    warn!("📣 Telegram asked to wait {:?} before messaging {}", wait, user_id);
+                    tokio::time::sleep(wait).await;
+                }
+                Delivery::Failed(e) => {
+                    debug!("📣 Announcement to {} failed: {}", user_id, e);
+                    return false;
+                }
+            }
+        }
+        false
+    }
+
+    async fn is_cancelled(&self, id: &str) -> bool {
+        self.broadcast.read().await.as_ref().map_or(true, |current| current.id != id || current.cancelled)
+    }
+
+    /// Save progress unless the broadcast was cancelled meanwhile; false when it was
+    async fn checkpoint(&self, broadcast: &Broadcast) -> Result<bool> {
+        let (snapshot, running) = {
+            let mut current = self.broadcast.write().await;
+            let Some(current) = current.as_mut().filter(|current| current.id == broadcast.id) else {
+                return Ok(false);
+            };
+            let running = !current.cancelled;
+            if running {
+                *current = broadcast.clone();
+            } else {
+                // Keep the cancel, with the counts up to where it landed
+                current.cursor = broadcast.cursor;
+                current.sent = broadcast.sent;
+                current.opted_out = broadcast.opted_out;
+                current.failed = broadcast.failed;
+            }
+            (current.clone(), running)
+        };
+        self.save_broadcast(&snapshot).await?;
+        Ok(running)
+    }
+
+    async fn save_broadcast(&self, broadcast: &Broadcast) -> Result<()> {
+        let Some(storage) = &self.storage else { return Ok(()) };
+        let data = serde_json::to_string(broadcast)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize broadcast {}: {}", broadcast.id, e)))?;
+        storage.save_state(BROADCAST_KEY, &data).await
+    }
+
+    /// Users, live orders and DCA strategies, and the last day's volume
+    pub async fn stats(&self, orders: &OrderManager, dca: &DCAEngine) -> Result<AdminStats> {
+        let (users, volume_24h_sol) = match &self.storage {
+            Some(storage) => (
+                storage.user_ids().await?.len(),
+                storage.trade_volume_since(Utc::now() - ChronoDuration::hours(24)).await?,
+            ),
+            None => (0, 0.0),
+        };
+        Ok(AdminStats {
+            users,
+            active_orders: orders.active_order_counts().await.iter().map(|(_, count)| count).sum(),
+            active_dca_strategies: dca.active_strategy_count().await,
+            volume_24h_sol,
+            maintenance: self.maintenance.read().await.is_some(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl TradingPause for AdminDesk {
+    async fn trading_paused(&self) -> bool {
+        self.maintenance.read().await.is_some()
+    }
+}
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use teloxide::utils::command::BotCommands;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use super::commands::Command;
+use crate::{
+    errors::{BotError, Result},
+    utils::validation::Validator,
+};
+
+/// Aliases a single user may define
+pub const MAX_ALIASES_PER_USER: usize = 20;
+/// Longest alias name
+pub const MAX_ALIAS_NAME_LEN: usize = 10;
+
+/// A user's alias table, name → expansion without the leading slash
+pub type UserAliases = BTreeMap<String, String>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AliasFile {
+    users: HashMap<i64, UserAliases>,
+}
+
+/// Per-user command shortcuts, expanded in the dispatcher before parsing
+///
+/// Expansion is single-level: the expanded text is parsed as a command and
+/// never looked up as an alias again.
+#[derive(Clone)]
+pub struct AliasStore {
+    aliases: Arc<RwLock<HashMap<i64, UserAliases>>>,
+    reserved: Arc<HashSet<String>>,
+    storage: Option<PathBuf>,
+}
+
+impl Default for AliasStore {
+    fn default() -> Self {
+        Self::new(Self::command_names())
+    }
+}
+
+impl AliasStore {
+    pub fn new<I: IntoIterator<Item = String>>(reserved: I) -> Self {
+        Self {
+            aliases: Arc::new(RwLock::new(HashMap::new())),
+            reserved: Arc::new(reserved.into_iter().map(|name| name.to_lowercase()).collect()),
+            storage: None,
+        }
+    }
+
+    /// Names of the bot's own commands, which aliases can't shadow
+    pub fn command_names() -> Vec<String> {
+        Command::bot_commands().into_iter()
+            .map(|command| command.command.trim_start_matches('/').to_lowercase())
+            .collect()
+    }
+
+    /// Persist aliases to a JSON file, loading any saved there
+    pub async fn with_storage(mut self, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => {
+                let file: AliasFile = serde_json::from_slice(&bytes)
+                    .map_err(|e| BotError::parsing(format!("Invalid alias file {}: {}", path.display(), e)))?;
+                info!("⌨️ Loaded aliases for {} users", file.users.len());
+                *self.aliases.write().await = file.users;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(BotError::internal(format!("Failed to read {}: {}", path.display(), e)).into()),
+        }
+        self.storage = Some(path);
+        Ok(self)
+    }
+
+    /// Define or replace an alias
+    pub async fn set(&self, user_id: i64, name: &str, expansion: &str) -> Result<()> {
+        let name = Self::normalize_name(name);
+        self.validate_name(&name)?;
+        let expansion = expansion.trim().trim_start_matches('/').trim().to_string();
+
+        let mut aliases = self.aliases.write().await;
+        let user_aliases = aliases.entry(user_id).or_default();
+        Self::validate_expansion(&name, &expansion, user_aliases)?;
+        if !user_aliases.contains_key(&name) && user_aliases.len() >= MAX_ALIASES_PER_USER {
+            return Err(BotError::validation(format!("You can have at most {} aliases", MAX_ALIASES_PER_USER)).into());
+        }
+
+        user_aliases.insert(name.clone(), expansion.clone());
+        debug!("⌨️ User {} set alias /{} → /{}", user_id, name, expansion);
+        self.save(&aliases).await;
+        Ok(())
+    }
+
+    pub async fn delete(&self, user_id: i64, name: &str) -> Result<()> {
+        let name = Self::normalize_name(name);
+        let mut aliases = self.aliases.write().await;
+        let removed = aliases.get_mut(&user_id).and_then(|user_aliases| user_aliases.remove(&name));
+        if removed.is_none() {
+            return Err(BotError::not_found(format!("No alias named {}", name)).into());
+        }
+
+        self.save(&aliases).await;
+        Ok(())
+    }
+
+    /// Remove every alias a user defined; returns how many there were
+    pub async fn clear(&self, user_id: i64) -> usize {
+        let mut aliases = self.aliases.write().await;
+        let removed = aliases.remove(&user_id).map(|user_aliases| user_aliases.len()).unwrap_or(0);
+        if removed > 0 {
+            self.save(&aliases).await;
+        }
+        removed
+    }
+
+    pub async fn list(&self, user_id: i64) -> UserAliases {
+        self.aliases.read().await.get(&user_id).cloned().unwrap_or_default()
+    }
+
+    /// Expand a message if it starts with one of the user's aliases
+    pub async fn expand(&self, user_id: i64, text: &str) -> Option<String> {
+        let aliases = self.aliases.read().await;
+        Self::expand_with(aliases.get(&user_id)?, text)
+    }
+
+    /// `/b BONK` with `b = qbuy 0.1` becomes `/qbuy 0.1 BONK`
+    pub fn expand_with(aliases: &UserAliases, text: &str) -> Option<String> {
+        let command = text.strip_prefix('/')?;
+        let (head, args) = match command.split_once(char::is_whitespace) {
+            Some((head, args)) => (head, args.trim()),
+            None => (command, ""),
+        };
+        // `/b@SomeBot` in groups
+        let name = head.split('@').next().unwrap_or(head).to_lowercase();
+        let expansion = aliases.get(&name)?;
+
+        Some(if args.is_empty() {
+            format!("/{}", expansion)
+        } else {
+            format!("/{} {}", expansion, args)
+        })
+    }
+
+    fn normalize_name(name: &str) -> String {
+        name.trim().trim_start_matches('/').to_lowercase()
+    }
+
+    fn validate_name(&self, name: &str) -> Result<()> {
+        if name.is_empty() || name.len() > MAX_ALIAS_NAME_LEN {
+            return Err(BotError::validation(format!("Alias names are 1-{} characters", MAX_ALIAS_NAME_LEN)).into());
+        }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(BotError::validation("Alias names may only use letters, digits and _".to_string()).into());
+        }
+        if self.reserved.contains(name) {
+            return Err(BotError::validation(format!("/{} is a built-in command and can't be an alias", name)).into());
+        }
+        Ok(())
+    }
+
+    /// The expansion must be something the user could type, and can't point at another alias
+    fn validate_expansion(name: &str, expansion: &str, user_aliases: &UserAliases) -> Result<()> {
+        let sanitized = Validator::sanitize_command_args(expansion)?;
+        if sanitized != expansion {
+            return Err(BotError::validation("Alias text contains characters commands don't accept".to_string()).into());
+        }
+
+        let target = expansion.split_whitespace().next().unwrap_or_default().to_lowercase();
+        if target == name || user_aliases.contains_key(&target) {
+            return Err(BotError::validation(format!("/{} is an alias; aliases can't point to other aliases", target)).into());
+        }
+        Ok(())
+    }
+
+    async fn save(&self, aliases: &HashMap<i64, UserAliases>) {
+        let Some(path) = &self.storage else { return };
+        let file = AliasFile { users: aliases.clone() };
+        let result = match serde_json::to_vec_pretty(&file) {
+            Ok(bytes) => tokio::fs::write(path, bytes).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            warn!("⌨️ Failed to persist aliases to {}: {}", path.display(), e);
+        }
+    }
+}
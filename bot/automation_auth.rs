@@ -0,0 +1,396 @@
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{info, warn};
+
+use crate::{
+    api::convex::ConvexClient,
+    errors::{BotError, Result},
+};
+
+/// Grants last this long unless the user asks for another expiry
+pub const DEFAULT_GRANT_DAYS: i64 = 30;
+/// Commands remembered for replay detection
+const MAX_PROCESSED_COMMANDS: usize = 10_000;
+
+/// What a Convex-originated command may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationAction {
+    /// Run a due step of one of the user's DCA strategies
+    DcaRun,
+    /// Record a simulated trade ticket
+    SimulatedTrade,
+    /// Buy or sell through the trading engine from an `execute_trade` webhook event
+    Trade,
+}
+
+impl AutomationAction {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "dca" | "dca_run" => Some(Self::DcaRun),
+            "sim" | "simulated" | "simulated_trade" => Some(Self::SimulatedTrade),
+            "trade" | "trades" => Some(Self::Trade),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::DcaRun => "DCA runs",
+            Self::SimulatedTrade => "simulated trades",
+            Self::Trade => "trades",
+        }
+    }
+}
+
+/// What a grant covers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrantScope {
+    pub action: AutomationAction,
+    /// Largest single command, in SOL
+    pub max_notional_sol: f64,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A user's authorization for Convex to trigger one kind of action
+///
+/// Only the SHA-256 of the token is kept; the token itself goes to Convex once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationGrant {
+    pub grant_id: String,
+    pub user_id: i64,
+    pub scope: GrantScope,
+    pub token_hash: String,
+    pub issued_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl AutomationGrant {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && now < self.scope.expires_at
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> &'static str {
+        if self.revoked_at.is_some() {
+            "revoked"
+        } else if now >= self.scope.expires_at {
+            "expired"
+        } else {
+            "active"
+        }
+    }
+}
+
+/// An execution request from Convex, as received by the webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexCommand {
+    /// Idempotency key; a replay is acknowledged but never executed again
+    pub command_id: String,
+    pub telegram_id: i64,
+    pub action: AutomationAction,
+    pub notional_sol: f64,
+    /// Token the bot issued to this user for this scope
+    pub authorization: String,
+    #[serde(default)]
+    pub strategy_id: Option<String>,
+}
+
+/// Why a command was refused
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Violation {
+    UnknownToken,
+    /// The token belongs to another user
+    WrongUser,
+    ActionNotGranted { granted: AutomationAction, requested: AutomationAction },
+    OverCap { cap_sol: f64, requested_sol: f64 },
+    Expired,
+    Revoked,
+}
+
+impl Violation {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::UnknownToken => "no authorization matches its token".to_string(),
+            Self::WrongUser => "its token was issued to another account".to_string(),
+            Self::ActionNotGranted { granted, requested } => {
+                format!("it asked for {} but the grant covers {}", requested.label(), granted.label())
+            }
+            Self::OverCap { cap_sol, requested_sol } => {
+                format!("it asked for {} SOL, over your {} SOL cap", requested_sol, cap_sol)
+            }
+            Self::Expired => "its authorization expired".to_string(),
+            Self::Revoked => "its authorization was revoked".to_string(),
+        }
+    }
+}
+
+/// What the webhook should do with a command
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandDecision {
+    Execute { grant_id: String },
+    /// Already handled under this command id
+    Duplicate,
+    Rejected(Violation),
+}
+
+/// A refused command, kept for security review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityAuditEntry {
+    pub at: DateTime<Utc>,
+    pub telegram_id: i64,
+    pub command_id: String,
+    pub action: AutomationAction,
+    pub notional_sol: f64,
+    pub grant_id: Option<String>,
+    pub violation: Violation,
+}
+
+/// DM for the user a refused command was made in the name of
+#[derive(Debug, Clone)]
+pub struct ViolationNotice {
+    pub user_id: i64,
+    pub text: String,
+}
+
+/// Where new grants and revocations are sent so Convex can present them
+#[async_trait::async_trait]
+pub trait GrantPublisher: Send + Sync {
+    async fn publish_grant(&self, grant: &AutomationGrant, token: &str) -> Result<()>;
+    async fn publish_revocation(&self, grant: &AutomationGrant) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl GrantPublisher for ConvexClient {
+    async fn publish_grant(&self, grant: &AutomationGrant, token: &str) -> Result<()> {
+        self.mutation::<Value>("mutations/automation:storeAuthorization", json!({
+            "telegramId": grant.user_id,
+            "grantId": grant.grant_id,
+            "token": token,
+            "action": grant.scope.action,
+            "maxNotionalSol": grant.scope.max_notional_sol,
+            "expiresAt": grant.scope.expires_at.timestamp_millis(),
+        })).await?;
+        Ok(())
+    }
+
+    async fn publish_revocation(&self, grant: &AutomationGrant) -> Result<()> {
+        self.mutation::<Value>("mutations/automation:revokeAuthorization", json!({
+            "telegramId": grant.user_id,
+            "grantId": grant.grant_id,
+        })).await?;
+        Ok(())
+    }
+}
+
+/// Issues scoped tokens and checks every Convex command against them
+pub struct AutomationAuthority {
+    grants: RwLock<HashMap<String, AutomationGrant>>,
+    /// Token hash to grant id
+    by_hash: RwLock<HashMap<String, String>>,
+    /// Command ids already executed, oldest first
+    processed: RwLock<(HashMap<String, DateTime<Utc>>, VecDeque<String>)>,
+    audit: RwLock<Vec<SecurityAuditEntry>>,
+    violations: broadcast::Sender<ViolationNotice>,
+    publisher: Option<Arc<dyn GrantPublisher>>,
+}
+
+impl Default for AutomationAuthority {
+    fn default() -> Self {
+        let (violations, _) = broadcast::channel(64);
+        Self {
+            grants: RwLock::new(HashMap::new()),
+            by_hash: RwLock::new(HashMap::new()),
+            processed: RwLock::new((HashMap::new(), VecDeque::new())),
+            audit: RwLock::new(Vec::new()),
+            violations,
+            publisher: None,
+        }
+    }
+}
+
+impl AutomationAuthority {
+    /// Hand new grants and revocations to Convex
+    pub fn with_publisher(mut self, publisher: Arc<dyn GrantPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    pub fn subscribe_violations(&self) -> broadcast::Receiver<ViolationNotice> {
+        self.violations.subscribe()
+    }
+
+    pub fn hash_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
+    /// Mint a token for `scope`; the token is returned once and only its hash is kept
+    pub async fn issue(&self, user_id: i64, scope: GrantScope, now: DateTime<Utc>) -> Result<(AutomationGrant, String)> {
+        if !scope.max_notional_sol.is_finite() || scope.max_notional_sol <= 0.0 {
+            return Err(BotError::validation("The cap must be a positive SOL amount".to_string()).into());
+        }
+        if scope.expires_at <= now {
+            return Err(BotError::validation("The expiry must be in the future".to_string()).into());
+        }
+
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let token = format!("atk_{}", hex::encode(secret));
+        let grant = AutomationGrant {
+            grant_id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            user_id,
+            scope,
+            token_hash: Self::hash_token(&token),
+            issued_at: now,
+            revoked_at: None,
+        };
+
+        if let Some(publisher) = &self.publisher {
+            publisher.publish_grant(&grant, &token).await?;
+        }
+        self.by_hash.write().await.insert(grant.token_hash.clone(), grant.grant_id.clone());
+        self.grants.write().await.insert(grant.grant_id.clone(), grant.clone());
+        info!("🔐 Issued {} grant {} to user {}", grant.scope.action.label(), grant.grant_id, user_id);
+        Ok((grant, token))
+    }
+
+    /// Every grant the user has, newest first
+    pub async fn grants(&self, user_id: i64) -> Vec<AutomationGrant> {
+        let mut grants: Vec<AutomationGrant> = self.grants.read().await.values()
+            .filter(|grant| grant.user_id == user_id)
+            .cloned()
+            .collect();
+        grants.sort_by(|a, b| b.issued_at.cmp(&a.issued_at));
+        grants
+    }
+
+    /// Revoke one grant; effective for the next command
+    pub async fn revoke(&self, user_id: i64, grant_id: &str, now: DateTime<Utc>) -> Result<AutomationGrant> {
+        let grant = {
+            let mut grants = self.grants.write().await;
+            let grant = grants.get_mut(grant_id)
+                .filter(|grant| grant.user_id == user_id)
+                .ok_or_else(|| BotError::not_found(format!("Authorization {} not found", grant_id)))?;
+            grant.revoked_at.get_or_insert(now);
+            grant.clone()
+        };
+
+        if let Some(publisher) = &self.publisher {
+            if let Err(e) = publisher.publish_revocation(&grant).await {
+                warn!("🔐 Revoked grant {} locally but Convex wasn't told: {}", grant_id, e);
+            }
+        }
+        Ok(grant)
+    }
+
+    pub async fn revoke_all(&self, user_id: i64, now: DateTime<Utc>) -> usize {
+        let active: Vec<String> = self.grants(user_id).await.into_iter()
+            .filter(|grant| grant.revoked_at.is_none())
+            .map(|grant| grant.grant_id)
+            .collect();
+        for grant_id in &active {
+            let _ = self.revoke(user_id, grant_id, now).await;
+        }
+        active.len()
+    }
+
+    /// Check a command's token and scope before it can reach the trading engine
+    ///
+    /// `notional_sol` is the size the bot worked out itself, not what Convex claimed.
+    pub async fn check(&self, command: &ConvexCommand, notional_sol: f64, now: DateTime<Utc>) -> CommandDecision {
+        let grant_id = self.by_hash.read().await.get(&Self::hash_token(&command.authorization)).cloned();
+        let grant = match &grant_id {
+            Some(id) => self.grants.read().await.get(id).cloned(),
+            None => None,
+        };
+
+        let violation = match &grant {
+            None => Some(Violation::UnknownToken),
+            Some(grant) if grant.user_id != command.telegram_id => Some(Violation::WrongUser),
+            Some(grant) if grant.revoked_at.is_some() => Some(Violation::Revoked),
+            Some(grant) if now >= grant.scope.expires_at => Some(Violation::Expired),
+            Some(grant) if grant.scope.action != command.action => Some(Violation::ActionNotGranted {
+                granted: grant.scope.action,
+                requested: command.action,
+            }),
+            Some(grant) if notional_sol.is_nan() || notional_sol > grant.scope.max_notional_sol => Some(Violation::OverCap {
+                cap_sol: grant.scope.max_notional_sol,
+                requested_sol: notional_sol,
+            }),
+            Some(_) => None,
+        };
+
+        if let Some(violation) = violation {
+            self.reject(command, notional_sol, grant_id, violation.clone(), now).await;
+            return CommandDecision::Rejected(violation);
+        }
+
+        let mut processed = self.processed.write().await;
+        if processed.0.contains_key(&command.command_id) {
+            return CommandDecision::Duplicate;
+        }
+        processed.0.insert(command.command_id.clone(), now);
+        processed.1.push_back(command.command_id.clone());
+        if processed.1.len() > MAX_PROCESSED_COMMANDS {
+            if let Some(oldest) = processed.1.pop_front() {
+                processed.0.remove(&oldest);
+            }
+        }
+
+        CommandDecision::Execute { grant_id: grant_id.unwrap_or_default() }
+    }
+
+    async fn reject(&self, command: &ConvexCommand, notional_sol: f64, grant_id: Option<String>, violation: Violation, now: DateTime<Utc>) {
+        warn!(
+            "🔐 Refused Convex command {} for user {}: {:?}",
+            command.command_id, command.telegram_id, violation
+        );
+        self.audit.write().await.push(SecurityAuditEntry {
+            at: now,
+            telegram_id: command.telegram_id,
+            command_id: command.command_id.clone(),
+            action: command.action,
+            notional_sol,
+            grant_id,
+            violation: violation.clone(),
+        });
+
+        // Tell the account the command claimed to act for; with a foreign token that's not its owner
+        let _ = self.violations.send(ViolationNotice {
+            user_id: command.telegram_id,
+            text: format!(
+                "🚨 Blocked an automated {} request ({} SOL) because {}.\n\
+                Nothing was executed. Review or revoke access with /automations.",
+                command.action.label(),
+                notional_sol,
+                violation.describe()
+            ),
+        });
+    }
+
+    pub async fn audit_log(&self, telegram_id: i64) -> Vec<SecurityAuditEntry> {
+        self.audit.read().await.iter()
+            .filter(|entry| entry.telegram_id == telegram_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Drop a user's grants; their tokens stop working at once
+    pub async fn forget_user(&self, user_id: i64) -> usize {
+        let mut grants = self.grants.write().await;
+        let removed: Vec<AutomationGrant> = grants.values().filter(|g| g.user_id == user_id).cloned().collect();
+        grants.retain(|_, grant| grant.user_id != user_id);
+        let mut by_hash = self.by_hash.write().await;
+        for grant in &removed {
+            by_hash.remove(&grant.token_hash);
+        }
+        removed.len()
+    }
+}
+
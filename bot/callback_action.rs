@@ -0,0 +1,120 @@
+/// Telegram rejects inline buttons whose callback data is longer than this
+pub const MAX_CALLBACK_DATA_LEN: usize = 64;
+
+/// Amount behind the fixed BONK/WIF/GECKO buttons and /larp's small buy
+pub const SMALL_QUICK_BUY_SOL: f64 = 0.05;
+
+/// Amount behind /trending's and /larp's buy buttons
+pub const DEFAULT_QUICK_BUY_SOL: f64 = 0.1;
+
+/// Prefixes used by quick-buy buttons before `CallbackAction` existed
+const LEGACY_PREFIXES: [&str; 2] = ["qbuy_", "quick_buy_"];
+
+/// Typed callback data for quick-buy buttons
+///
+/// `token` is a mint address or a symbol, resolved when the button is tapped.
+/// Amounts are written to lamport precision so the data stays within 64 bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallbackAction {
+    /// Buy right away, or ask first when above the user's max trade size
+    QuickBuy { token: String, amount_sol: f64 },
+    /// The user confirmed a buy above their max trade size
+    ConfirmQuickBuy { token: String, amount_sol: f64 },
+    /// The user accepted a buy whose estimated price impact exceeds their slippage
+    ConfirmHighImpact { token: String, amount_sol: f64 },
+    /// Buy again at a higher priority fee after the first transaction expired unlanded
+    RetryBuy { token: String, amount_sol: f64 },
+    /// Ask which token to buy with this amount
+    ChooseQuickBuyToken { amount_sol: f64 },
+    CancelQuickBuy,
+}
+
+impl CallbackAction {
+    pub fn quick_buy(token: impl Into<String>, amount_sol: f64) -> Self {
+        Self::QuickBuy { token: token.into(), amount_sol }
+    }
+
+    /// Sends a transaction when tapped
+    pub fn executes_trade(&self) -> bool {
+        matches!(
+            self,
+            Self::QuickBuy { .. } | Self::ConfirmQuickBuy { .. } | Self::ConfirmHighImpact { .. } | Self::RetryBuy { .. }
+        )
+    }
+
+    /// Callback data for an inline button; `parse` reads it back
+    pub fn to_data(&self) -> String {
+        match self {
+            Self::QuickBuy { token, amount_sol } => format!("qb:{}:{}", format_amount(*amount_sol), token),
+            Self::ConfirmQuickBuy { token, amount_sol } => format!("qbc:{}:{}", format_amount(*amount_sol), token),
+            Self::ConfirmHighImpact { token, amount_sol } => format!("qbi:{}:{}", format_amount(*amount_sol), token),
+            Self::RetryBuy { token, amount_sol } => format!("qbr:{}:{}", format_amount(*amount_sol), token),
+            Self::ChooseQuickBuyToken { amount_sol } => format!("qbt:{}", format_amount(*amount_sol)),
+            Self::CancelQuickBuy => "qbx".to_string(),
+        }
+    }
+
+    /// Parse callback data written by `to_data`; anything else is `None`
+    pub fn parse(data: &str) -> Option<Self> {
+        if data.len() > MAX_CALLBACK_DATA_LEN {
+            return None;
+        }
+        let mut parts = data.splitn(3, ':');
+        let action = match (parts.next()?, parts.next(), parts.next()) {
+            ("qb", Some(amount), Some(token)) => Self::QuickBuy {
+                token: parse_token(token)?,
+                amount_sol: parse_amount(amount)?,
+            },
+            ("qbc", Some(amount), Some(token)) => Self::ConfirmQuickBuy {
+                token: parse_token(token)?,
+                amount_sol: parse_amount(amount)?,
+            },
+            ("qbi", Some(amount), Some(token)) => Self::ConfirmHighImpact {
+                token: parse_token(token)?,
+                amount_sol: parse_amount(amount)?,
+            },
+            ("qbr", Some(amount), Some(token)) => Self::RetryBuy {
+                token: parse_token(token)?,
+                amount_sol: parse_amount(amount)?,
+            },
+            ("qbt", Some(amount), None) => Self::ChooseQuickBuyToken { amount_sol: parse_amount(amount)? },
+            ("qbx", None, None) => Self::CancelQuickBuy,
+            _ => return None,
+        };
+        Some(action)
+    }
+
+    /// Quick-buy data from an older button, or a malformed one, that no longer does anything
+    pub fn is_expired(data: &str) -> bool {
+        if LEGACY_PREFIXES.iter().any(|prefix| data.starts_with(prefix)) {
+            return true;
+        }
+        let quick_buy = matches!(data.split(':').next(), Some("qb" | "qbc" | "qbi" | "qbr" | "qbt" | "qbx"));
+        quick_buy && Self::parse(data).is_none()
+    }
+}
+
+/// Confirmation buttons outside `CallbackAction` that send a transaction when tapped
+const SUBMITTING_CALLBACKS: [&str; 5] = ["lend:go", "send:go", "panic:arm", "atac:go", "launch:confirm"];
+
+/// Whether tapping a button with this data submits a transaction
+pub fn submits_transaction(data: &str) -> bool {
+    SUBMITTING_CALLBACKS.contains(&data)
+        || data.starts_with("confirm_swap:")
+        || CallbackAction::parse(data).is_some_and(|action| action.executes_trade())
+}
+
+/// SOL to at most nine decimals, without trailing zeros
+fn format_amount(sol: f64) -> String {
+    let fixed = format!("{:.9}", sol);
+    fixed.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn parse_amount(amount: &str) -> Option<f64> {
+    amount.parse::<f64>().ok().filter(|sol| sol.is_finite() && *sol > 0.0)
+}
+
+fn parse_token(token: &str) -> Option<String> {
+    let valid = !token.is_empty() && !token.chars().any(|c| c.is_whitespace() || c.is_control());
+    valid.then(|| token.to_string())
+}
@@ -0,0 +1,546 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::{
+    alerts::{
+        AlertCondition, AlertDeliveryMethod, AlertAction, AlertPriority, AlertStatus, AlertTriggerType,
+        PriceAlert, PriceAlertManager, PriceComparison, PriceThreshold,
+    },
+    errors::{BotError, Result},
+    trading::{Order, OrderManager},
+    utils::price_input::{parse_price, ParsedPrice},
+};
+
+/// How long a user has to answer the price prompt
+const DEFAULT_FLOW_TIMEOUT_SECS: i64 = 120;
+/// Maximum number of suggested prices offered in the prompt
+const MAX_SUGGESTIONS: usize = 6;
+
+/// What a chart price tap creates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartActionKind {
+    Alert,
+    Stop,
+}
+
+/// Which message the chart came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartSource {
+    Chart,
+    Larp,
+    Trending,
+}
+
+/// Direction of a price alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertDirection {
+    Above,
+    Below,
+}
+
+/// Outcome of validating a price against the market
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceCheck {
+    Ok,
+    /// Accepted, but the user should know something about the level
+    Warning(String),
+    /// Not accepted; the user can reply with another price
+    Rejected(String),
+}
+
+/// A price prompt waiting for the user's reply
+#[derive(Debug, Clone)]
+pub struct PendingChartAction {
+    pub kind: ChartActionKind,
+    pub mint: String,
+    pub symbol: String,
+    pub current_price: f64,
+    pub chat_id: i64,
+    pub chart_message_id: i32,
+    pub chart_text: String,
+    pub suggestions: Vec<f64>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Everything needed to create the alert or stop once the price is confirmed
+#[derive(Debug, Clone)]
+pub struct ChartActionRequest {
+    pub kind: ChartActionKind,
+    pub user_id: i64,
+    pub chat_id: i64,
+    pub mint: String,
+    pub symbol: String,
+    pub target_price: f64,
+    pub direction: AlertDirection,
+    /// Position size protected by a stop (token units)
+    pub amount: f64,
+}
+
+/// Destination for chart-created items (alerts go to the alert manager, stops to the order manager)
+#[async_trait::async_trait]
+pub trait PriceLevelSink: Send + Sync {
+    async fn create_at_price(&self, request: &ChartActionRequest) -> Result<String>;
+}
+
+#[async_trait::async_trait]
+impl PriceLevelSink for PriceAlertManager {
+    async fn create_at_price(&self, request: &ChartActionRequest) -> Result<String> {
+        self.create_alert(ChartActions::build_price_alert(request)?).await
+    }
+}
+
+#[async_trait::async_trait]
+impl PriceLevelSink for OrderManager {
+    async fn create_at_price(&self, request: &ChartActionRequest) -> Result<String> {
+        self.create_order(ChartActions::build_stop_order(request)?).await
+    }
+}
+
+/// Tracks in-flight chart price prompts per user
+pub struct ChartActions {
+    pending: RwLock<HashMap<i64, PendingChartAction>>,
+    timeout: Duration,
+}
+
+impl Default for ChartActions {
+    fn default() -> Self {
+        Self::new(Duration::seconds(DEFAULT_FLOW_TIMEOUT_SECS))
+    }
+}
+
+impl ChartActions {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            pending: RwLock::new(HashMap::new()),
+            timeout,
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Start a prompt, replacing any earlier one for the same user
+    pub async fn begin(&self, user_id: i64, action: PendingChartAction) {
+        debug!("📊 Chart {:?} prompt started for user {} on {}", action.kind, user_id, action.mint);
+        self.pending.write().await.insert(user_id, action);
+    }
+
+    /// Current prompt for the user, if it hasn't timed out
+    pub async fn pending(&self, user_id: i64) -> Option<PendingChartAction> {
+        let now = Utc::now();
+        let mut pending = self.pending.write().await;
+        match pending.get(&user_id) {
+            Some(action) if now - action.started_at >= self.timeout => {
+                pending.remove(&user_id);
+                None
+            }
+            other => other.cloned(),
+        }
+    }
+
+    /// Finish the prompt for the user
+    pub async fn complete(&self, user_id: i64) -> Option<PendingChartAction> {
+        self.pending.write().await.remove(&user_id)
+    }
+
+    /// Drop the prompt if it is still the one started at `started_at`; returns it when it expired
+    pub async fn expire(&self, user_id: i64, started_at: DateTime<Utc>) -> Option<PendingChartAction> {
+        let mut pending = self.pending.write().await;
+        if pending.get(&user_id).map(|a| a.started_at == started_at).unwrap_or(false) {
+            return pending.remove(&user_id);
+        }
+        None
+    }
+
+    /// Route a confirmed request to the alert or order pipeline
+    pub async fn create(
+        request: &ChartActionRequest,
+        alerts: &dyn PriceLevelSink,
+        stops: &dyn PriceLevelSink,
+    ) -> Result<String> {
+        let id = match request.kind {
+            ChartActionKind::Alert => alerts.create_at_price(request).await?,
+            ChartActionKind::Stop => stops.create_at_price(request).await?,
+        };
+
+        info!("📊 Created chart {:?} {} for user {} on {} at {}",
+            request.kind, id, request.user_id, request.symbol, request.target_price);
+
+        Ok(id)
+    }
+
+    /// Round-number prices near the current price, plus support/resistance levels when known
+    ///
+    /// Stops only get levels below the market; alerts get both sides.
+    pub fn suggest_prices(current_price: f64, kind: ChartActionKind, levels: &[f64]) -> Vec<f64> {
+        if !current_price.is_finite() || current_price <= 0.0 {
+            return Vec::new();
+        }
+
+        let step = Self::nice_step(current_price * 0.05);
+        let below = Self::snap((current_price / step).floor() * step, step);
+        let below = if below >= current_price { Self::snap(below - step, step) } else { below };
+        let above = Self::snap(below + step, step);
+        let above = if above <= current_price { Self::snap(above + step, step) } else { above };
+
+        let mut candidates = match kind {
+            ChartActionKind::Stop => vec![below, below - step, below - 2.0 * step],
+            ChartActionKind::Alert => vec![below - step, below, above, above + step],
+        };
+
+        // Support/resistance within ±50% of the market
+        candidates.extend(levels.iter().copied().filter(|level| {
+            let within_range = *level > current_price * 0.5 && *level < current_price * 1.5;
+            match kind {
+                ChartActionKind::Stop => within_range && *level < current_price,
+                ChartActionKind::Alert => within_range,
+            }
+        }));
+
+        let mut suggestions: Vec<f64> = candidates.into_iter()
+            .map(|price| Self::snap(price, step / 100.0))
+            .filter(|price| *price > 0.0 && (*price - current_price).abs() > f64::EPSILON)
+            .collect();
+        suggestions.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        suggestions.dedup_by(|a, b| (*a - *b).abs() < step / 1000.0);
+
+        // Keep the levels closest to the market
+        while suggestions.len() > MAX_SUGGESTIONS {
+            let first = (suggestions[0] - current_price).abs();
+            let last = (suggestions[suggestions.len() - 1] - current_price).abs();
+            if first > last { suggestions.remove(0); } else { suggestions.pop(); }
+        }
+
+        suggestions
+    }
+
+    /// Validate a target price against the market
+    pub fn check_price(
+        kind: ChartActionKind,
+        direction: Option<AlertDirection>,
+        target: f64,
+        current_price: f64,
+    ) -> PriceCheck {
+        if !target.is_finite() || target <= 0.0 {
+            return PriceCheck::Rejected("Price must be a positive number".to_string());
+        }
+
+        match kind {
+            ChartActionKind::Stop => {
+                if target >= current_price {
+                    return PriceCheck::Rejected(format!(
+                        "Stop must be below the current price (${})", Self::format_price(current_price)
+                    ));
+                }
+                if target < current_price * 0.5 {
+                    return PriceCheck::Warning(format!(
+                        "Stop is {:.0}% below market — that's a wide stop",
+                        (1.0 - target / current_price) * 100.0
+                    ));
+                }
+                PriceCheck::Ok
+            }
+            ChartActionKind::Alert => {
+                match direction {
+                    Some(AlertDirection::Above) if target <= current_price => {
+                        PriceCheck::Warning(format!(
+                            "\"Above\" alert is at or below market (${}) — it will fire on the next check",
+                            Self::format_price(current_price)
+                        ))
+                    }
+                    Some(AlertDirection::Below) if target >= current_price => {
+                        PriceCheck::Warning(format!(
+                            "\"Below\" alert is at or above market (${}) — it will fire on the next check",
+                            Self::format_price(current_price)
+                        ))
+                    }
+                    _ if target > current_price * 10.0 || target < current_price / 10.0 => {
+                        PriceCheck::Warning("Price is more than 10x away from market".to_string())
+                    }
+                    _ => PriceCheck::Ok,
+                }
+            }
+        }
+    }
+
+    /// Parse a reply like `0.95`, `$1.2`, `above 1.5`, `below 0.8`, `20u` or a suggestion number `#2`
+    pub fn parse_reply(text: &str, suggestions: &[f64]) -> Option<(Option<AlertDirection>, f64)> {
+        Self::parse_reply_input(text, suggestions).map(|(direction, parsed)| (direction, parsed.value))
+    }
+
+    /// As `parse_reply`, keeping whether the scale was explicit; picked suggestions always are
+    pub fn parse_reply_input(text: &str, suggestions: &[f64]) -> Option<(Option<AlertDirection>, ParsedPrice)> {
+        let lower = text.trim().to_lowercase();
+        let (direction, rest) = if let Some(rest) = lower.strip_prefix("above") {
+            (Some(AlertDirection::Above), rest)
+        } else if let Some(rest) = lower.strip_prefix("below") {
+            (Some(AlertDirection::Below), rest)
+        } else {
+            (None, lower.as_str())
+        };
+
+        let rest = rest.trim();
+        if let Some(index) = rest.strip_prefix('#') {
+            let index: usize = index.parse().ok()?;
+            return suggestions.get(index.checked_sub(1)?).map(|price| (direction, ParsedPrice::explicit(*price)));
+        }
+
+        parse_price(rest).map(|parsed| (direction, parsed))
+    }
+
+    /// Alert direction, inferred from the market when the user didn't say
+    pub fn resolve_direction(direction: Option<AlertDirection>, target: f64, current_price: f64) -> AlertDirection {
+        direction.unwrap_or(if target >= current_price { AlertDirection::Above } else { AlertDirection::Below })
+    }
+
+    /// Build the alert the price alert manager expects
+    pub fn build_price_alert(request: &ChartActionRequest) -> Result<PriceAlert> {
+        let target_price = Decimal::from_f64_retain(request.target_price)
+            .ok_or_else(|| BotError::validation("Invalid alert price".to_string()))?;
+        let comparison = match request.direction {
+            AlertDirection::Above => PriceComparison::Above,
+            AlertDirection::Below => PriceComparison::Below,
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("source".to_string(), "chart".to_string());
+        metadata.insert("display_symbol".to_string(), request.symbol.clone());
+
+        Ok(PriceAlert {
+            alert_id: String::new(),
+            user_id: request.user_id,
+            name: format!("{} {} ${}", request.symbol,
+                if request.direction == AlertDirection::Above { "above" } else { "below" },
+                Self::format_price(request.target_price)),
+            symbol: request.mint.clone(),
+            conditions: vec![AlertCondition::PriceThreshold(PriceThreshold {
+                comparison,
+                target_price,
+                tolerance: None,
+            })],
+            trigger_type: AlertTriggerType::Once,
+            priority: AlertPriority::Medium,
+            actions: vec![AlertAction::Notify],
+            delivery_methods: vec![AlertDeliveryMethod::Telegram { chat_id: request.chat_id }],
+            cooldown_period: None,
+            expiry_time: None,
+            max_triggers: Some(1),
+            enabled: true,
+            created_at: Utc::now(),
+            last_triggered: None,
+            trigger_count: 0,
+            status: AlertStatus::Active,
+            metadata,
+        })
+    }
+
+    /// Build the stop-loss order the order manager expects
+    pub fn build_stop_order(request: &ChartActionRequest) -> Result<Order> {
+        let stop_price = Decimal::from_f64_retain(request.target_price)
+            .ok_or_else(|| BotError::validation("Invalid stop price".to_string()))?;
+        let amount = Decimal::from_f64_retain(request.amount)
+            .filter(|amount| *amount > Decimal::ZERO)
+            .ok_or_else(|| BotError::validation("No position size to protect".to_string()))?;
+
+        let mut order = Order::create_stop_loss(request.user_id, request.mint.clone(), stop_price, amount);
+        order.metadata.position_size = Some(amount);
+        Ok(order)
+    }
+
+    /// Line appended to the chart caption once an item is created
+    pub fn caption_note(request: &ChartActionRequest) -> String {
+        match request.kind {
+            ChartActionKind::Alert => format!(
+                "🔔 Alert set: {} ${}",
+                if request.direction == AlertDirection::Above { "above" } else { "below" },
+                Self::format_price(request.target_price)
+            ),
+            ChartActionKind::Stop => format!("🛑 Stop-loss set at ${}", Self::format_price(request.target_price)),
+        }
+    }
+
+    /// Price with enough precision for sub-cent tokens
+    pub fn format_price(price: f64) -> String {
+        let decimals = if price >= 1.0 {
+            2
+        } else if price > 0.0 {
+            ((-price.log10()).ceil() as usize + 3).min(12)
+        } else {
+            2
+        };
+        let formatted = format!("{:.*}", decimals, price);
+        if formatted.contains('.') {
+            let trimmed = formatted.trim_end_matches('0');
+            if price >= 1.0 && trimmed.ends_with('.') {
+                return format!("{}00", trimmed);
+            }
+            trimmed.trim_end_matches('.').to_string()
+        } else {
+            formatted
+        }
+    }
+
+    /// 1, 2 or 5 times a power of ten, closest to `raw`
+    fn nice_step(raw: f64) -> f64 {
+        let magnitude = 10f64.powf(raw.log10().floor());
+        let ratio = raw / magnitude;
+        let factor = if ratio < 1.5 {
+            1.0
+        } else if ratio < 3.5 {
+            2.0
+        } else if ratio < 7.5 {
+            5.0
+        } else {
+            10.0
+        };
+        factor * magnitude
+    }
+
+    fn snap(price: f64, step: f64) -> f64 {
+        (price / step).round() * step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn request(kind: ChartActionKind) -> ChartActionRequest {
+        ChartActionRequest {
+            kind,
+            user_id: 42,
+            chat_id: 42,
+            mint: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+            symbol: "BONK".to_string(),
+            target_price: 0.9,
+            direction: AlertDirection::Below,
+            amount: 1_000.0,
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        created: Mutex<Vec<ChartActionKind>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PriceLevelSink for RecordingSink {
+        async fn create_at_price(&self, request: &ChartActionRequest) -> Result<String> {
+            self.created.lock().unwrap().push(request.kind);
+            Ok(format!("id-{}", self.created.lock().unwrap().len()))
+        }
+    }
+
+    #[test]
+    fn test_round_number_suggestions() {
+        let stops = ChartActions::suggest_prices(1.37, ChartActionKind::Stop, &[]);
+        assert_eq!(stops.len(), 3);
+        assert!(approx(stops[0], 1.25) && approx(stops[1], 1.30) && approx(stops[2], 1.35));
+
+        let alerts = ChartActions::suggest_prices(1.37, ChartActionKind::Alert, &[]);
+        assert_eq!(alerts.len(), 4);
+        assert!(approx(alerts[0], 1.30) && approx(alerts[3], 1.45));
+        assert!(alerts.iter().any(|p| *p < 1.37) && alerts.iter().any(|p| *p > 1.37));
+
+        // Sub-cent tokens get sub-cent steps
+        let tiny = ChartActions::suggest_prices(0.00002345, ChartActionKind::Stop, &[]);
+        assert!(tiny.iter().all(|p| *p < 0.00002345 && *p > 0.00001));
+    }
+
+    #[test]
+    fn test_suggestions_include_levels_on_the_right_side() {
+        let stops = ChartActions::suggest_prices(100.0, ChartActionKind::Stop, &[92.5, 110.0, 20.0]);
+        assert!(stops.iter().any(|p| approx(*p, 92.5)));
+        assert!(stops.iter().all(|p| *p < 100.0));
+
+        let alerts = ChartActions::suggest_prices(100.0, ChartActionKind::Alert, &[92.5, 110.0]);
+        assert!(alerts.iter().any(|p| approx(*p, 110.0)));
+        assert!(alerts.len() <= MAX_SUGGESTIONS);
+    }
+
+    #[test]
+    fn test_price_validation_warnings() {
+        // Above alert below market
+        assert!(matches!(
+            ChartActions::check_price(ChartActionKind::Alert, Some(AlertDirection::Above), 0.9, 1.0),
+            PriceCheck::Warning(_)
+        ));
+        // Below alert above market
+        assert!(matches!(
+            ChartActions::check_price(ChartActionKind::Alert, Some(AlertDirection::Below), 1.1, 1.0),
+            PriceCheck::Warning(_)
+        ));
+        assert_eq!(ChartActions::check_price(ChartActionKind::Alert, None, 1.1, 1.0), PriceCheck::Ok);
+
+        // Stops above market are rejected, very wide stops warned
+        assert!(matches!(ChartActions::check_price(ChartActionKind::Stop, None, 1.1, 1.0), PriceCheck::Rejected(_)));
+        assert!(matches!(ChartActions::check_price(ChartActionKind::Stop, None, 0.3, 1.0), PriceCheck::Warning(_)));
+        assert_eq!(ChartActions::check_price(ChartActionKind::Stop, None, 0.9, 1.0), PriceCheck::Ok);
+        assert!(matches!(ChartActions::check_price(ChartActionKind::Stop, None, -1.0, 1.0), PriceCheck::Rejected(_)));
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let suggestions = [0.9, 0.95];
+        assert_eq!(ChartActions::parse_reply("0.95", &suggestions), Some((None, 0.95)));
+        assert_eq!(ChartActions::parse_reply("above $1.5", &suggestions), Some((Some(AlertDirection::Above), 1.5)));
+        assert_eq!(ChartActions::parse_reply("#1", &suggestions), Some((None, 0.9)));
+        assert_eq!(ChartActions::parse_reply("#3", &suggestions), None);
+        assert_eq!(ChartActions::parse_reply("soon", &suggestions), None);
+    }
+
+    #[tokio::test]
+    async fn test_creation_routes_to_managers() {
+        let alerts = RecordingSink::default();
+        let stops = RecordingSink::default();
+
+        ChartActions::create(&request(ChartActionKind::Alert), &alerts, &stops).await.unwrap();
+        ChartActions::create(&request(ChartActionKind::Stop), &alerts, &stops).await.unwrap();
+
+        assert_eq!(*alerts.created.lock().unwrap(), vec![ChartActionKind::Alert]);
+        assert_eq!(*stops.created.lock().unwrap(), vec![ChartActionKind::Stop]);
+
+        let alert = ChartActions::build_price_alert(&request(ChartActionKind::Alert)).unwrap();
+        assert!(matches!(
+            &alert.conditions[0],
+            AlertCondition::PriceThreshold(PriceThreshold { comparison: PriceComparison::Below, .. })
+        ));
+        assert!(matches!(alert.delivery_methods[0], AlertDeliveryMethod::Telegram { chat_id: 42 }));
+
+        let order = ChartActions::build_stop_order(&request(ChartActionKind::Stop)).unwrap();
+        assert_eq!(order.base_amount, Decimal::from(1_000));
+
+        let mut empty = request(ChartActionKind::Stop);
+        empty.amount = 0.0;
+        assert!(ChartActions::build_stop_order(&empty).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_flow_times_out() {
+        let flows = ChartActions::new(Duration::seconds(60));
+        let started_at = Utc::now() - Duration::seconds(61);
+        flows.begin(42, PendingChartAction {
+            kind: ChartActionKind::Stop,
+            mint: "mint".to_string(),
+            symbol: "BONK".to_string(),
+            current_price: 1.0,
+            chat_id: 42,
+            chart_message_id: 1,
+            chart_text: String::new(),
+            suggestions: vec![],
+            started_at,
+        }).await;
+
+        assert!(flows.pending(42).await.is_none());
+        assert!(flows.expire(42, started_at).await.is_none());
+    }
+}
@@ -12,8 +12,8 @@ pub enum Command {
     #[command(description = "Generate new wallet")]
     NewWallet,
     
-    #[command(description = "Import existing wallet")]
-    Import,
+    #[command(description = "Import a wallet, or trade history from another bot: /import trades")]
+    Import(String),
     
     #[command(description = "Show deposit address")]
     Deposit,
@@ -33,17 +33,23 @@ pub enum Command {
     #[command(description = "Get AI market analysis")]
     Analyze(String),
     
+    #[command(description = "Admin: AI spend versus budget")]
+    AiBudget,
+    
     #[command(description = "View active positions")]
     Portfolio,
     
-    #[command(description = "Export wallet (secure)")]
+    #[command(description = "Export wallet, encrypted with a passphrase (after /confirm)")]
     Export,
     
+    #[command(rename = "export_trades", description = "Trade history for taxes: /export_trades [year] [json]")]
+    ExportTrades(String),
+    
     #[command(description = "Backup instructions")]
     Backup,
     
-    #[command(description = "Bot settings")]
-    Settings,
+    #[command(description = "Bot settings: /settings [export | deny <mint> | allow <mint>]")]
+    Settings(String),
     
     #[command(description = "Get help")]
     Help,
@@ -64,6 +70,9 @@ pub enum Command {
     #[command(description = "Stop copying trader: /unfollow <wallet_address>")]
     Unfollow(String),
     
+    #[command(description = "Get copied for a fee: /master [apply [fee%] | fee <percent> | earnings]")]
+    Master(String),
+    
     #[command(description = "Check if token is LARP/scam: /larp <token_address>")]
     Larp(String),
     
@@ -76,9 +85,21 @@ pub enum Command {
     #[command(description = "Create Solana Blink: /blink <action>")]
     Blink(String),
     
-    #[command(description = "Set trading alerts: /alert <token> <price>")]
+    #[command(description = "Clicks, conversions and SOL volume of the blinks you created")]
+    MyBlinks,
+    
+    #[command(description = "Set trading alerts: /alert <token> [above|below] <price>")]
     Alert(String),
     
+    #[command(description = "Your price alerts: /alerts list | delete <id> | history | via <methods>")]
+    Alerts(String),
+    
+    #[command(description = "Watchlist: /watch | add <token> | remove <token> | digest <time>|off")]
+    Watch(String),
+    
+    #[command(description = "Whale wallets: /whales add <address> [min_usd] | top <token> | remove <address> | list")]
+    Whales(String),
+    
     #[command(description = "View top traders leaderboard")]
     Leaderboard,
     
@@ -96,4 +117,195 @@ pub enum Command {
     
     #[command(description = "Set stop loss: /stop <token> <percentage>")]
     StopLoss(String),
-}
\ No newline at end of file
+    
+    #[command(description = "Linked stop-loss and take-profit on a position: /bracket <token> <stop> <take_profit>")]
+    Bracket(String),
+    
+    #[command(description = "Upcoming unlocks and migrations for your tokens")]
+    Calendar,
+    
+    #[command(description = "Admin: add calendar event: /addevent <mint> <symbol> <date> <time> <kind> [description]")]
+    AddEvent(String),
+    
+    #[command(description = "Price chart with alert/stop buttons: /chart <token>")]
+    Chart(String),
+    
+    #[command(description = "Trade journal: /journal [n tag [note] | export | on | off]")]
+    Journal(String),
+    
+    #[command(description = "Results by journal tag and trading hour: /stats [heatmap] [exit] [imported]")]
+    Stats(String),
+    
+    #[command(description = "Sharpe, Sortino and drawdowns, or compare with holding SOL: /performance [benchmark SOL:50,JUP:50]")]
+    Performance(String),
+    
+    #[command(description = "MEV protection and measured sandwich losses: /mev [stats]")]
+    Mev(String),
+    
+    #[command(description = "DCA strategies in your local time: /dca list | /dca new 25 BONK daily at 9am | /dca tz <Area/City>")]
+    Dca(String),
+    
+    #[command(description = "Group chats: coordinate a buy from each member's own wallet: /groupbuy <token> <sol_each> [min_participants]")]
+    GroupBuy(String),
+    
+    #[command(description = "Command shortcuts: /alias set <name> \"<command>\" | list | delete <name>")]
+    Alias(String),
+    
+    #[command(description = "Reclaim rent from empty token accounts: /cleanup [auto on|off]")]
+    Cleanup(String),
+    
+    #[command(description = "Earn yield on Jupiter Lend: /lend [positions | <vault> <amount> | withdraw <vault> <amount|all>]")]
+    Lend(String),
+    
+    #[command(description = "Giveaways: /send <token> <amount> @user1 @user2 ... (or upload a CSV) | status <id> | reclaim <id>")]
+    Send(String),
+    
+    #[command(description = "Time large sells for a tighter book by default: /smartsell on|off")]
+    SmartSell(String),
+    
+    #[command(description = "Where stop-loss, take-profit and /panic exits land: /exitto sol|usdc")]
+    ExitTo(String),
+    
+    #[command(description = "How sells realize PnL against your buys: /costbasis fifo|average")]
+    CostBasis(String),
+    
+    #[command(description = "Sell every position, after two confirmations: /panic [sol|usdc|cancel]")]
+    Panic(String),
+    
+    #[command(description = "Simulate trades against live prices with virtual SOL: /paper on|off|reset")]
+    Paper(String),
+    
+    #[command(description = "Place a stop-loss or take-profit: /order sl|tp <token> <price> <amount> | edit <id> <price>")]
+    Order(String),
+    
+    #[command(description = "Open orders with cancel, edit, notification and exit buttons")]
+    Orders,
+    
+    #[command(description = "Cancel an open order: /cancelorder <id>")]
+    CancelOrder(String),
+    
+    #[command(description = "Fill notifications for automated and manual trades: /verbosity full|summary|silent")]
+    Verbosity(String),
+    
+    #[command(description = "Language the bot answers in: /language [en|es|fr|de|it|pt|ru|zh|ja|ko]")]
+    Language(String),
+    
+    #[command(description = "Delete your data after a 72h grace period: /forgetme [confirm|cancel|status]")]
+    ForgetMe(String),
+    
+    #[command(description = "What Convex may trigger for you: /automations [grant <dca|sim|trade> <max_sol> [days] | revoke <id|all>]")]
+    Automations(String),
+    
+    #[command(description = "Admin: /admin broadcast <text> | maintenance on|off | stats | migrate_user <telegram_id> | migrate_batch [limit]")]
+    Admin(String),
+}
+
+impl Command {
+    /// The command as typed, without arguments; used as a metrics label
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Start => "start",
+            Command::Wallet => "wallet",
+            Command::NewWallet => "newwallet",
+            Command::Import(_) => "import",
+            Command::Deposit => "deposit",
+            Command::Balance => "balance",
+            Command::Buy(_) => "buy",
+            Command::Sell(_) => "sell",
+            Command::Rebates => "rebates",
+            Command::Analyze(_) => "analyze",
+            Command::AiBudget => "aibudget",
+            Command::Portfolio => "portfolio",
+            Command::Export => "export",
+            Command::ExportTrades(_) => "export_trades",
+            Command::Backup => "backup",
+            Command::Settings(_) => "settings",
+            Command::Help => "help",
+            Command::Confirm => "confirm",
+            Command::Cancel => "cancel",
+            Command::Snipe(_) => "snipe",
+            Command::Copy(_) => "copy",
+            Command::Unfollow(_) => "unfollow",
+            Command::Master(_) => "master",
+            Command::Larp(_) => "larp",
+            Command::Trending => "trending",
+            Command::Launch => "launch",
+            Command::Blink(_) => "blink",
+            Command::MyBlinks => "myblinks",
+            Command::Alert(_) => "alert",
+            Command::Alerts(_) => "alerts",
+            Command::Watch(_) => "watch",
+            Command::Whales(_) => "whales",
+            Command::Leaderboard => "leaderboard",
+            Command::Signals => "signals",
+            Command::Pump(_) => "pump",
+            Command::QuickBuy(_) => "quickbuy",
+            Command::QuickSell(_) => "quicksell",
+            Command::StopLoss(_) => "stoploss",
+            Command::Bracket(_) => "bracket",
+            Command::Calendar => "calendar",
+            Command::AddEvent(_) => "addevent",
+            Command::Chart(_) => "chart",
+            Command::Journal(_) => "journal",
+            Command::Stats(_) => "stats",
+            Command::Performance(_) => "performance",
+            Command::Mev(_) => "mev",
+            Command::Dca(_) => "dca",
+            Command::GroupBuy(_) => "groupbuy",
+            Command::Alias(_) => "alias",
+            Command::Cleanup(_) => "cleanup",
+            Command::Lend(_) => "lend",
+            Command::Send(_) => "send",
+            Command::SmartSell(_) => "smartsell",
+            Command::ExitTo(_) => "exitto",
+            Command::CostBasis(_) => "costbasis",
+            Command::Panic(_) => "panic",
+            Command::Paper(_) => "paper",
+            Command::Order(_) => "order",
+            Command::Orders => "orders",
+            Command::CancelOrder(_) => "cancelorder",
+            Command::Verbosity(_) => "verbosity",
+            Command::Language(_) => "language",
+            Command::ForgetMe(_) => "forgetme",
+            Command::Automations(_) => "automations",
+            Command::Admin(_) => "admin",
+        }
+    }
+    
+    /// Tokens drawn from the user's command bucket; 0 is never limited
+    pub fn cost(&self) -> u32 {
+        match self {
+            // Never stand between a user and getting out
+            Command::Panic(_) | Command::Cancel => 0,
+            // Quote, RPC and a transaction
+            cmd if cmd.executes_trade() => 5,
+            // AI calls, rendering, chain scans and exports
+            Command::Analyze(_) | Command::Signals | Command::Chart(_) | Command::Larp(_)
+            | Command::Whales(_) | Command::Portfolio | Command::Stats(_) | Command::Performance(_)
+            | Command::Leaderboard | Command::Export | Command::ExportTrades(_) | Command::Import(_)
+            | Command::Cleanup(_) | Command::GroupBuy(_) | Command::Lend(_)
+            | Command::Send(_) | Command::Watch(_) => 3,
+            _ => 1,
+        }
+    }
+    
+    /// Also draws from the stricter trade bucket
+    pub fn executes_trade(&self) -> bool {
+        matches!(
+            self,
+            Command::Buy(_) | Command::Sell(_) | Command::Snipe(_) | Command::QuickBuy(_)
+                | Command::QuickSell(_) | Command::Pump(_)
+        )
+    }
+
+    /// Submits a transaction now or sets one up to run later; held during maintenance
+    pub fn submits_transaction(&self) -> bool {
+        self.executes_trade()
+            || matches!(
+                self,
+                Command::Launch | Command::Lend(_) | Command::Send(_) | Command::GroupBuy(_)
+                    | Command::Order(_) | Command::Bracket(_) | Command::StopLoss(_) | Command::Dca(_)
+                    | Command::Panic(_) | Command::Cleanup(_)
+            )
+    }
+}
@@ -0,0 +1,618 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use super::data_deletion::Tombstones;
+use super::preferences::{NotificationPreferences, PreferenceStore, RiskProfile, UserSettings};
+use crate::{
+    alerts::{
+        AlertAction, AlertCondition, AlertDeliveryMethod, AlertPriority, AlertStatus, AlertTriggerType,
+        ChangeTimeframe, ChangeType, PercentageChange, PriceAlert, PriceAlertManager, PriceComparison,
+        PriceThreshold,
+    },
+    analytics::CostBasisMethod,
+    api::convex::{
+        ConvexAlert, ConvexClient, ConvexDcaStrategy, ConvexMirror, ConvexTable, ConvexUser,
+        ConvexUserSnapshot, ConvexWallet,
+    },
+    constants::MAX_SLIPPAGE_BPS,
+    errors::{BotError, Result},
+    trading::{AdvancedDCAConfig, DCAEngine, DCAInterval, DCAStatus, DCAStrategy, DCAStrategyType, ExitDenomination, RiskParameters},
+    utils::validation::Validator,
+    wallet::WalletManager,
+};
+
+/// Users migrated per batch when no limit is given
+pub const DEFAULT_BATCH_SIZE: usize = 25;
+
+/// Convex records the migration reads and tags
+#[async_trait::async_trait]
+pub trait ConvexSource: Send + Sync {
+    async fn user_snapshot(&self, telegram_id: i64) -> Result<Option<ConvexUserSnapshot>>;
+    async fn unmirrored_users(&self, limit: usize) -> Result<Vec<i64>>;
+    async fn mark_mirrored(&self, table: ConvexTable, id: &str, native_id: &str) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl ConvexSource for ConvexClient {
+    async fn user_snapshot(&self, telegram_id: i64) -> Result<Option<ConvexUserSnapshot>> {
+        ConvexClient::user_snapshot(self, telegram_id).await
+    }
+
+    async fn unmirrored_users(&self, limit: usize) -> Result<Vec<i64>> {
+        ConvexClient::unmirrored_users(self, limit).await
+    }
+
+    async fn mark_mirrored(&self, table: ConvexTable, id: &str, native_id: &str) -> Result<()> {
+        ConvexClient::mark_mirrored(self, table, id, native_id).await
+    }
+}
+
+/// Native stores the migration writes into
+///
+/// Every write is an upsert keyed by a native id derived from the Convex id,
+/// and returns true when the record is new. There is deliberately no way to
+/// trade through this trait.
+#[async_trait::async_trait]
+pub trait MigrationTarget: Send + Sync {
+    async fn apply_preferences(&self, user_id: i64, preferences: UserSettings) -> Result<bool>;
+    async fn upsert_alert(&self, alert: PriceAlert) -> Result<bool>;
+    async fn upsert_strategy(&self, strategy: DCAStrategy) -> Result<bool>;
+    async fn link_watch_only(&self, user_id: i64, address: &str, label: Option<String>) -> Result<bool>;
+}
+
+/// A wallet known from Convex that the bot can watch but not sign for
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchOnlyWallet {
+    pub address: String,
+    pub label: Option<String>,
+}
+
+/// Writes migrated records into the bot's live services
+pub struct NativeTarget {
+    preferences: Arc<PreferenceStore>,
+    price_alerts: Arc<PriceAlertManager>,
+    dca_engine: Arc<DCAEngine>,
+    wallet_manager: Arc<WalletManager>,
+    watch_only: RwLock<HashMap<i64, Vec<WatchOnlyWallet>>>,
+}
+
+impl NativeTarget {
+    pub fn new(
+        preferences: Arc<PreferenceStore>,
+        price_alerts: Arc<PriceAlertManager>,
+        dca_engine: Arc<DCAEngine>,
+        wallet_manager: Arc<WalletManager>,
+    ) -> Self {
+        Self {
+            preferences,
+            price_alerts,
+            dca_engine,
+            wallet_manager,
+            watch_only: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Wallets linked from Convex that still await native wallet setup
+    pub async fn watch_only_wallets(&self, user_id: i64) -> Vec<WatchOnlyWallet> {
+        self.watch_only.read().await.get(&user_id).cloned().unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTarget for NativeTarget {
+    async fn apply_preferences(&self, user_id: i64, preferences: UserSettings) -> Result<bool> {
+        Ok(self.preferences.set(user_id, preferences).await)
+    }
+
+    async fn upsert_alert(&self, alert: PriceAlert) -> Result<bool> {
+        self.price_alerts.upsert_alert(alert).await
+    }
+
+    async fn upsert_strategy(&self, strategy: DCAStrategy) -> Result<bool> {
+        self.dca_engine.import_strategy(strategy).await
+    }
+
+    async fn link_watch_only(&self, user_id: i64, address: &str, label: Option<String>) -> Result<bool> {
+        let mut watch_only = self.watch_only.write().await;
+        let wallets = watch_only.entry(user_id).or_default();
+        let created = match wallets.iter_mut().find(|w| w.address == address) {
+            Some(existing) => {
+                existing.label = label;
+                false
+            }
+            None => {
+                wallets.push(WatchOnlyWallet { address: address.to_string(), label });
+                true
+            }
+        };
+        drop(watch_only);
+
+        // Deposits and foreign activity still reach the user before they set up signing
+        if let Some(watcher) = self.wallet_manager.activity_watch() {
+            if let Err(e) = watcher.watch(user_id, address).await {
+                warn!("Could not watch migrated wallet {} for user {}: {}", address, user_id, e);
+            }
+        }
+
+        Ok(created)
+    }
+}
+
+/// Why a Convex record was not imported
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationSkip {
+    /// Only price alerts have a native equivalent
+    UnsupportedAlertType(String),
+    UnsupportedMetric(String),
+    UnsupportedOperator(String),
+    InvalidValue(String),
+    Expired,
+    /// Cron and dynamic schedules have no native equivalent
+    UnsupportedSchedule(String),
+    InvalidAmount(String),
+    Inactive,
+    InvalidAddress(String),
+}
+
+impl fmt::Display for MigrationSkip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedAlertType(kind) => write!(f, "{} alerts aren't supported", kind),
+            Self::UnsupportedMetric(metric) => write!(f, "metric '{}' isn't supported", metric),
+            Self::UnsupportedOperator(operator) => write!(f, "operator '{}' isn't supported", operator),
+            Self::InvalidValue(value) => write!(f, "invalid value '{}'", value),
+            Self::Expired => write!(f, "already expired"),
+            Self::UnsupportedSchedule(schedule) => write!(f, "schedule '{}' isn't supported", schedule),
+            Self::InvalidAmount(amount) => write!(f, "invalid amount '{}'", amount),
+            Self::Inactive => write!(f, "no longer active"),
+            Self::InvalidAddress(address) => write!(f, "invalid address '{}'", address),
+        }
+    }
+}
+
+/// Whether an import created or refreshed the native record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Created,
+    Updated,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedRecord {
+    pub table: ConvexTable,
+    pub convex_id: String,
+    pub native_id: String,
+    pub outcome: ImportOutcome,
+    /// Parts of the record that were dropped on the way in
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedRecord {
+    pub table: ConvexTable,
+    pub convex_id: String,
+    pub name: String,
+    pub reason: MigrationSkip,
+}
+
+/// What one migration run imported and skipped for a user
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    pub telegram_id: i64,
+    pub migrated_at: DateTime<Utc>,
+    pub imported: Vec<ImportedRecord>,
+    pub skipped: Vec<SkippedRecord>,
+}
+
+impl MigrationReport {
+    pub fn created(&self) -> usize {
+        self.imported.iter().filter(|r| r.outcome == ImportOutcome::Created).count()
+    }
+
+    pub fn updated(&self) -> usize {
+        self.imported.iter().filter(|r| r.outcome == ImportOutcome::Updated).count()
+    }
+}
+
+/// Result of a batch run
+#[derive(Debug, Clone, Default)]
+pub struct BatchSummary {
+    pub reports: Vec<MigrationReport>,
+    pub failed: Vec<(i64, String)>,
+}
+
+/// A mapped record, or why it can't be mapped
+type Mapped<T> = std::result::Result<(T, Vec<String>), MigrationSkip>;
+
+/// Copies Convex-only users (settings, alerts, DCA, wallet links) into the bot
+///
+/// Imports are idempotent: native ids are derived from Convex ids so a rerun
+/// updates what the last run created. Each imported record is tagged as
+/// mirrored in Convex so the web side stops acting on it. No trades run.
+pub struct ConvexMigration {
+    source: Arc<dyn ConvexSource>,
+    target: Arc<dyn MigrationTarget>,
+    reports: RwLock<HashMap<i64, MigrationReport>>,
+    tombstones: Tombstones,
+}
+
+impl ConvexMigration {
+    pub fn new(source: Arc<dyn ConvexSource>, target: Arc<dyn MigrationTarget>) -> Self {
+        Self {
+            source,
+            target,
+            reports: RwLock::new(HashMap::new()),
+            tombstones: Tombstones::default(),
+        }
+    }
+
+    /// Never import users who asked to be forgotten
+    pub fn with_tombstones(mut self, tombstones: Tombstones) -> Self {
+        self.tombstones = tombstones;
+        self
+    }
+
+    /// Native id of an imported alert or strategy
+    pub fn native_id(convex_id: &str) -> String {
+        format!("convex-{}", convex_id)
+    }
+
+    /// Latest report for a user
+    pub async fn report(&self, telegram_id: i64) -> Option<MigrationReport> {
+        self.reports.read().await.get(&telegram_id).cloned()
+    }
+
+    /// Migrate one user
+    pub async fn migrate_user(&self, telegram_id: i64) -> Result<MigrationReport> {
+        if self.tombstones.contains(telegram_id).await {
+            return Err(BotError::validation(format!("Telegram id {} asked to be forgotten; not migrating", telegram_id)).into());
+        }
+        let snapshot = self.source.user_snapshot(telegram_id).await?
+            .ok_or_else(|| BotError::not_found(format!("No Convex user for Telegram id {}", telegram_id)))?;
+        let now = Utc::now();
+        let mut report = MigrationReport {
+            telegram_id,
+            migrated_at: now,
+            imported: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        let (preferences, notes) = Self::map_preferences(&snapshot.user);
+        let created = self.target.apply_preferences(telegram_id, preferences).await?;
+        self.record(&mut report, ConvexTable::Users, &snapshot.user.id, snapshot.user.mirror.as_ref(),
+            telegram_id.to_string(), created, notes).await?;
+
+        for wallet in &snapshot.wallets {
+            match Self::map_wallet(wallet) {
+                Ok(((address, label), notes)) => {
+                    let created = self.target.link_watch_only(telegram_id, &address, label).await?;
+                    self.record(&mut report, ConvexTable::Wallets, &wallet.id, wallet.mirror.as_ref(),
+                        address, created, notes).await?;
+                }
+                Err(reason) => Self::skip(&mut report, ConvexTable::Wallets, &wallet.id, &wallet.address, reason),
+            }
+        }
+
+        for alert in &snapshot.alerts {
+            match Self::map_alert(telegram_id, alert, now) {
+                Ok((native, notes)) => {
+                    let native_id = native.alert_id.clone();
+                    let created = self.target.upsert_alert(native).await?;
+                    self.record(&mut report, ConvexTable::Alerts, &alert.id, alert.mirror.as_ref(),
+                        native_id, created, notes).await?;
+                }
+                Err(reason) => Self::skip(&mut report, ConvexTable::Alerts, &alert.id, &alert.name, reason),
+            }
+        }
+
+        for strategy in &snapshot.dca_strategies {
+            match Self::map_strategy(telegram_id, strategy, now) {
+                Ok((native, notes)) => {
+                    let native_id = native.strategy_id.clone();
+                    let created = self.target.upsert_strategy(native).await?;
+                    self.record(&mut report, ConvexTable::DcaStrategies, &strategy.id, strategy.mirror.as_ref(),
+                        native_id, created, notes).await?;
+                }
+                Err(reason) => Self::skip(&mut report, ConvexTable::DcaStrategies, &strategy.id, &strategy.name, reason),
+            }
+        }
+
+        info!("📦 Migrated Convex user {}: {} created, {} updated, {} skipped",
+            telegram_id, report.created(), report.updated(), report.skipped.len());
+        self.reports.write().await.insert(telegram_id, report.clone());
+
+        Ok(report)
+    }
+
+    /// Migrate up to `limit` users not yet mirrored; one failure doesn't stop the batch
+    pub async fn migrate_batch(&self, limit: usize) -> Result<BatchSummary> {
+        let mut summary = BatchSummary::default();
+        for telegram_id in self.source.unmirrored_users(limit).await? {
+            if self.tombstones.contains(telegram_id).await {
+                info!("📦 Skipping tombstoned Convex user {}", telegram_id);
+                continue;
+            }
+            match self.migrate_user(telegram_id).await {
+                Ok(report) => summary.reports.push(report),
+                Err(e) => {
+                    warn!("📦 Convex migration failed for {}: {}", telegram_id, e);
+                    summary.failed.push((telegram_id, e.to_string()));
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    async fn record(
+        &self,
+        report: &mut MigrationReport,
+        table: ConvexTable,
+        convex_id: &str,
+        mirror: Option<&ConvexMirror>,
+        native_id: String,
+        created: bool,
+        notes: Vec<String>,
+    ) -> Result<()> {
+        if mirror.map(|m| m.native_id.as_str()) != Some(native_id.as_str()) {
+            self.source.mark_mirrored(table, convex_id, &native_id).await?;
+        }
+        report.imported.push(ImportedRecord {
+            table,
+            convex_id: convex_id.to_string(),
+            native_id,
+            outcome: if created { ImportOutcome::Created } else { ImportOutcome::Updated },
+            notes,
+        });
+        Ok(())
+    }
+
+    fn skip(report: &mut MigrationReport, table: ConvexTable, convex_id: &str, name: &str, reason: MigrationSkip) {
+        report.skipped.push(SkippedRecord {
+            table,
+            convex_id: convex_id.to_string(),
+            name: name.to_string(),
+            reason,
+        });
+    }
+
+    /// Convex user settings as native trading preferences
+    pub fn map_preferences(user: &ConvexUser) -> (UserSettings, Vec<String>) {
+        let settings = &user.settings;
+        let mut notes = Vec::new();
+        let defaults = UserSettings::default();
+
+        let slippage_bps = (settings.default_slippage * 100.0).round();
+        let slippage_bps = if slippage_bps > 0.0 && slippage_bps <= MAX_SLIPPAGE_BPS as f64 {
+            slippage_bps as u16
+        } else {
+            notes.push(format!("slippage {}% out of range, using default", settings.default_slippage));
+            defaults.slippage_bps
+        };
+
+        let max_position_sol = match Decimal::from_str(settings.max_position_size.trim()) {
+            Ok(size) if size > Decimal::ZERO => Some(size),
+            _ => {
+                notes.push(format!("max position '{}' not a positive number, left unlimited", settings.max_position_size));
+                None
+            }
+        };
+
+        let risk_profile = match settings.risk_level.as_str() {
+            "conservative" => RiskProfile::Conservative,
+            "moderate" => RiskProfile::Moderate,
+            "aggressive" => RiskProfile::Aggressive,
+            other => {
+                notes.push(format!("risk level '{}' unknown, using moderate", other));
+                RiskProfile::Moderate
+            }
+        };
+
+        let mut preferences = UserSettings {
+            slippage_bps,
+            max_position_sol,
+            auto_compound: settings.auto_compound,
+            risk_profile,
+            notifications: NotificationPreferences {
+                trades: settings.notifications.trades,
+                alerts: settings.notifications.alerts,
+                daily_summary: settings.notifications.daily,
+                announcements: defaults.notifications.announcements,
+            },
+            smart_sell: false,
+            exit_denomination: ExitDenomination::default(),
+            cost_basis_method: CostBasisMethod::default(),
+            ..defaults
+        };
+        if let Some(lang) = &settings.language {
+            if preferences.set_language(Some(lang)).is_err() {
+                notes.push(format!("language '{}' not shipped, following the Telegram app's", lang));
+            }
+        }
+        (preferences, notes)
+    }
+
+    /// Any valid address becomes a watch-only link, whatever signed for it in Convex
+    pub fn map_wallet(wallet: &ConvexWallet) -> Mapped<(String, Option<String>)> {
+        if Validator::validate_pubkey(&wallet.address).is_err() {
+            return Err(MigrationSkip::InvalidAddress(wallet.address.clone()));
+        }
+        Ok(((wallet.address.clone(), wallet.label.clone()), Vec::new()))
+    }
+
+    /// Convex price alert as a native alert; trade actions are dropped
+    pub fn map_alert(telegram_id: i64, alert: &ConvexAlert, now: DateTime<Utc>) -> Mapped<PriceAlert> {
+        if alert.alert_type != "price" {
+            return Err(MigrationSkip::UnsupportedAlertType(alert.alert_type.clone()));
+        }
+        if alert.condition.metric != "price" {
+            return Err(MigrationSkip::UnsupportedMetric(alert.condition.metric.clone()));
+        }
+        let expiry_time = alert.expires_at.and_then(|ms| Utc.timestamp_millis_opt(ms).single());
+        if expiry_time.is_some_and(|expiry| expiry <= now) {
+            return Err(MigrationSkip::Expired);
+        }
+
+        let value = alert.condition.value.trim();
+        let condition = match alert.condition.operator.as_str() {
+            "above" | "below" => {
+                let target_price = Decimal::from_str(value)
+                    .ok()
+                    .filter(|price| *price > Decimal::ZERO)
+                    .ok_or_else(|| MigrationSkip::InvalidValue(alert.condition.value.clone()))?;
+                AlertCondition::PriceThreshold(PriceThreshold {
+                    comparison: if alert.condition.operator == "above" { PriceComparison::Above } else { PriceComparison::Below },
+                    target_price,
+                    tolerance: None,
+                })
+            }
+            "change" => {
+                let percentage = value.trim_end_matches('%').parse::<f64>()
+                    .ok()
+                    .filter(|p| p.is_finite() && *p != 0.0)
+                    .ok_or_else(|| MigrationSkip::InvalidValue(alert.condition.value.clone()))?;
+                let timeframe = alert.condition.timeframe.as_deref().unwrap_or("24h");
+                let timeframe = Self::parse_timeframe(timeframe)
+                    .ok_or_else(|| MigrationSkip::UnsupportedSchedule(timeframe.to_string()))?;
+                AlertCondition::PercentageChange(PercentageChange {
+                    timeframe,
+                    change_type: if percentage > 0.0 { ChangeType::Increase } else { ChangeType::Decrease },
+                    threshold_percentage: percentage.abs(),
+                })
+            }
+            other => return Err(MigrationSkip::UnsupportedOperator(other.to_string())),
+        };
+
+        let mut notes = Vec::new();
+        for action in &alert.actions {
+            if action != "notify" {
+                notes.push(format!("action '{}' dropped, alert only notifies", action));
+            }
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert("source".to_string(), "convex".to_string());
+        metadata.insert("convex_id".to_string(), alert.id.clone());
+
+        let native = PriceAlert {
+            alert_id: Self::native_id(&alert.id),
+            user_id: telegram_id,
+            name: alert.name.clone(),
+            symbol: alert.condition.target.clone(),
+            conditions: vec![condition],
+            trigger_type: AlertTriggerType::Repeating,
+            priority: AlertPriority::Medium,
+            actions: vec![AlertAction::Notify],
+            delivery_methods: vec![AlertDeliveryMethod::Telegram { chat_id: telegram_id }],
+            cooldown_period: alert.cooldown.filter(|secs| *secs > 0).map(Duration::seconds),
+            expiry_time,
+            max_triggers: None,
+            enabled: alert.is_active,
+            created_at: now,
+            last_triggered: None,
+            trigger_count: 0,
+            status: if alert.is_active { AlertStatus::Active } else { AlertStatus::Paused },
+            metadata,
+        };
+        Ok((native, notes))
+    }
+
+    /// Convex DCA strategy as a native fixed-amount strategy
+    pub fn map_strategy(telegram_id: i64, strategy: &ConvexDcaStrategy, now: DateTime<Utc>) -> Mapped<DCAStrategy> {
+        if !strategy.is_active {
+            return Err(MigrationSkip::Inactive);
+        }
+        let end_date = strategy.end_date.and_then(|ms| Utc.timestamp_millis_opt(ms).single());
+        if end_date.is_some_and(|end| end <= now) {
+            return Err(MigrationSkip::Expired);
+        }
+        if strategy.frequency.kind != "interval" {
+            return Err(MigrationSkip::UnsupportedSchedule(format!("{} {}", strategy.frequency.kind, strategy.frequency.value)));
+        }
+        let interval = Self::parse_interval(&strategy.frequency.value)
+            .ok_or_else(|| MigrationSkip::UnsupportedSchedule(strategy.frequency.value.clone()))?;
+
+        let amount_per_execution = Decimal::from_str(strategy.amount.trim())
+            .ok()
+            .filter(|amount| *amount > Decimal::ZERO)
+            .ok_or_else(|| MigrationSkip::InvalidAmount(strategy.amount.clone()))?;
+
+        let mut notes = Vec::new();
+        let max_investment = strategy.max_investment.as_deref()
+            .and_then(|max| Decimal::from_str(max.trim()).ok())
+            .filter(|max| *max >= amount_per_execution);
+        let total_amount = match (max_investment, strategy.max_executions) {
+            (Some(max), _) => max,
+            (None, Some(executions)) if executions > 0 => amount_per_execution * Decimal::from(executions),
+            _ => {
+                notes.push("no investment cap, limited to 100 executions".to_string());
+                amount_per_execution * Decimal::from(100)
+            }
+        };
+        let max_executions = strategy.max_executions
+            .or_else(|| (total_amount / amount_per_execution).to_u32());
+
+        let native = DCAStrategy {
+            strategy_id: Self::native_id(&strategy.id),
+            user_id: telegram_id,
+            name: strategy.name.clone(),
+            input_token: strategy.token_in.mint.clone(),
+            output_token: strategy.token_out.mint.clone(),
+            total_amount,
+            interval,
+            amount_per_execution,
+            strategy_type: DCAStrategyType::Fixed,
+            created_at: now,
+            started_at: None,
+            // Scheduled by the engine on import, never in the past
+            next_execution: now,
+            status: if strategy.is_paused { DCAStatus::Paused } else { DCAStatus::Active },
+            execution_count: 0,
+            max_executions,
+            end_date,
+            risk_parameters: RiskParameters::default(),
+            advanced_config: AdvancedDCAConfig::default(),
+            anchor: None,
+            last_completed_slot: None,
+            skip_next: false,
+        };
+        Ok((native, notes))
+    }
+
+    /// `30m`, `1h`, `1d`, `1w`, `2w`, `1M`
+    fn parse_interval(value: &str) -> Option<DCAInterval> {
+        let value = value.trim();
+        let split = value.find(|c: char| !c.is_ascii_digit())?;
+        let (count, unit) = value.split_at(split);
+        let count: u32 = count.parse().ok().filter(|c| *c > 0)?;
+        match (unit, count) {
+            ("m", _) => Some(DCAInterval::Minutes(count)),
+            ("h", 1) => Some(DCAInterval::Hourly),
+            ("h", _) => Some(DCAInterval::Minutes(count.checked_mul(60)?)),
+            ("d", 1) => Some(DCAInterval::Daily),
+            ("d", 7) | ("w", 1) => Some(DCAInterval::Weekly),
+            ("d", 14) | ("w", 2) => Some(DCAInterval::Biweekly),
+            ("M", 1) => Some(DCAInterval::Monthly),
+            _ => None,
+        }
+    }
+
+    fn parse_timeframe(value: &str) -> Option<ChangeTimeframe> {
+        let value = value.trim();
+        let split = value.find(|c: char| !c.is_ascii_digit())?;
+        let (count, unit) = value.split_at(split);
+        let count: u32 = count.parse().ok().filter(|c| *c > 0)?;
+        match unit {
+            "m" => Some(ChangeTimeframe::Minutes(count)),
+            "h" => Some(ChangeTimeframe::Hours(count)),
+            "d" => Some(ChangeTimeframe::Days(count)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,645 @@
+use async_trait::async_trait;
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::prelude::*;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use super::automation_auth::{AutomationAction, AutomationAuthority, CommandDecision, ConvexCommand};
+use crate::{
+    cache::{SessionStore, SessionStoreExt},
+    constants::MAX_TRADE_SOL,
+    errors::{BotError, Result},
+    portfolio::PortfolioFetcher,
+    trading::{OrderManager, TokenResolver, TradeResult, TradingEngineHandle, TradingPause, PAUSED_FOR_MAINTENANCE},
+    utils::validation::{ValidatedAmount, Validator},
+    wallet::WalletManager,
+};
+
+/// How long the outcome of an idempotency key is remembered
+pub const IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Which way an `execute_trade` goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// A command from Convex, tagged by `type`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum WebhookEvent {
+    /// Buy for `amount` SOL, or sell `amount` percent of the position
+    ExecuteTrade {
+        telegram_id: i64,
+        /// Symbol or mint
+        token: String,
+        side: TradeSide,
+        amount: f64,
+        /// Automation token the user granted for trades
+        authorization: String,
+    },
+    CancelOrder {
+        telegram_id: i64,
+        order_id: String,
+    },
+    /// Re-read the holdings of the user's active wallet
+    SyncPortfolio {
+        telegram_id: i64,
+    },
+    NotifyUser {
+        telegram_id: i64,
+        text: String,
+    },
+    PriceAlertTriggered {
+        telegram_id: i64,
+        alert_id: String,
+        token: String,
+        price: f64,
+        /// e.g. `above 2.5`
+        condition: String,
+    },
+    /// Run the due step of a DCA strategy; its size comes from the stored strategy
+    RunDca {
+        telegram_id: i64,
+        strategy_id: String,
+        /// Automation token the user granted for DCA runs
+        authorization: String,
+    },
+    /// Record a simulated trade ticket of `notional_sol`
+    SimulatedTrade {
+        telegram_id: i64,
+        notional_sol: f64,
+        /// Automation token the user granted for simulated trades
+        authorization: String,
+    },
+}
+
+impl WebhookEvent {
+    /// Every `type` the webhook accepts
+    pub const TYPES: [&'static str; 7] = [
+        "execute_trade",
+        "cancel_order",
+        "sync_portfolio",
+        "notify_user",
+        "price_alert_triggered",
+        "run_dca",
+        "simulated_trade",
+    ];
+
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::ExecuteTrade { .. } => "execute_trade",
+            Self::CancelOrder { .. } => "cancel_order",
+            Self::SyncPortfolio { .. } => "sync_portfolio",
+            Self::NotifyUser { .. } => "notify_user",
+            Self::PriceAlertTriggered { .. } => "price_alert_triggered",
+            Self::RunDca { .. } => "run_dca",
+            Self::SimulatedTrade { .. } => "simulated_trade",
+        }
+    }
+}
+
+/// One posted event: the command and the key its retries share
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEnvelope {
+    pub idempotency_key: String,
+    #[serde(flatten)]
+    pub event: WebhookEvent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventStatus {
+    Ok,
+    Failed,
+    /// The authorization didn't cover it
+    Rejected,
+    /// Nothing on this instance handles it, or trading is paused; safe to retry
+    Unavailable,
+    /// An earlier delivery under the same key hasn't finished
+    InProgress,
+}
+
+/// What happened to one event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventResult {
+    pub idempotency_key: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub status: EventStatus,
+    /// The outcome of an earlier delivery under the same key; nothing ran again
+    #[serde(default)]
+    pub duplicate: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub data: Value,
+}
+
+impl EventResult {
+    fn new(envelope: &WebhookEnvelope, status: EventStatus) -> Self {
+        Self {
+            idempotency_key: envelope.idempotency_key.clone(),
+            event_type: envelope.event.event_type().to_string(),
+            status,
+            duplicate: false,
+            message: None,
+            data: Value::Null,
+        }
+    }
+
+    fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    fn with_data(mut self, data: Value) -> Self {
+        self.data = data;
+        self
+    }
+}
+
+/// Response body: one result per posted event, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookResponse {
+    pub results: Vec<EventResult>,
+}
+
+/// Buys and sells in a user's name
+#[async_trait]
+pub trait TradeTarget: Send + Sync {
+    async fn execute_trade(&self, telegram_id: i64, token_mint: &str, side: TradeSide, amount: f64) -> Result<TradeResult>;
+}
+
+/// Cancels a user's open orders
+#[async_trait]
+pub trait OrderTarget: Send + Sync {
+    /// False when the user has no such open order
+    async fn cancel_order(&self, telegram_id: i64, order_id: &str) -> Result<bool>;
+}
+
+/// Refreshes a user's holdings
+#[async_trait]
+pub trait PortfolioTarget: Send + Sync {
+    async fn sync_portfolio(&self, telegram_id: i64) -> Result<PortfolioSnapshot>;
+}
+
+/// Runs DCA steps and simulated trades once the user's grant covers them
+#[async_trait]
+pub trait AutomationTarget: Send + Sync {
+    async fn run_command(&self, command: ConvexCommand) -> Result<CommandDecision>;
+}
+
+/// Messages a user through the bot
+#[async_trait]
+pub trait UserNotifier: Send + Sync {
+    async fn notify(&self, telegram_id: i64, text: &str) -> Result<()>;
+}
+
+/// Totals returned for `sync_portfolio`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioSnapshot {
+    pub wallet_address: String,
+    pub total_value_usd: f64,
+    pub total_value_sol: f64,
+    pub holdings: usize,
+}
+
+/// Trades through the trading engine with the user's active wallet
+pub struct EngineTrades {
+    trading_engine: TradingEngineHandle,
+    wallet_manager: Arc<WalletManager>,
+}
+
+impl EngineTrades {
+    pub fn new(trading_engine: TradingEngineHandle, wallet_manager: Arc<WalletManager>) -> Self {
+        Self { trading_engine, wallet_manager }
+    }
+}
+
+#[async_trait]
+impl TradeTarget for EngineTrades {
+    async fn execute_trade(&self, telegram_id: i64, token_mint: &str, side: TradeSide, amount: f64) -> Result<TradeResult> {
+        let user = telegram_id.to_string();
+        if self.wallet_manager.is_trading_locked(&user).await {
+            return Err(BotError::trading("Trading is locked after unexpected wallet activity".to_string()).into());
+        }
+        let wallet = active_wallet(&self.wallet_manager, telegram_id).await?;
+
+        let result = match side {
+            TradeSide::Buy => self.trading_engine.buy_with_rebate(wallet, token_mint.to_string(), amount).await?,
+            TradeSide::Sell => self.trading_engine.sell_with_rebate(wallet, token_mint.to_string(), amount).await?,
+        };
+        self.wallet_manager.record_originated(&result.tx_signature).await;
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl OrderTarget for OrderManager {
+    async fn cancel_order(&self, telegram_id: i64, order_id: &str) -> Result<bool> {
+        // Only the owner's orders
+        let owned = self.get_user_orders(telegram_id).await
+            .iter()
+            .any(|order| order.order_id == order_id);
+        if !owned {
+            return Ok(false);
+        }
+        OrderManager::cancel_order(self, order_id).await
+    }
+}
+
+/// Portfolio of the user's active wallet, read fresh from chain
+pub struct WalletPortfolios {
+    fetcher: Arc<PortfolioFetcher>,
+    wallet_manager: Arc<WalletManager>,
+}
+
+impl WalletPortfolios {
+    pub fn new(fetcher: Arc<PortfolioFetcher>, wallet_manager: Arc<WalletManager>) -> Self {
+        Self { fetcher, wallet_manager }
+    }
+}
+
+#[async_trait]
+impl PortfolioTarget for WalletPortfolios {
+    async fn sync_portfolio(&self, telegram_id: i64) -> Result<PortfolioSnapshot> {
+        let wallet = active_wallet(&self.wallet_manager, telegram_id).await?;
+        let portfolio = self.fetcher.fetch_portfolio(&wallet).await?;
+        Ok(PortfolioSnapshot {
+            wallet_address: portfolio.wallet_address,
+            total_value_usd: portfolio.total_value_usd,
+            total_value_sol: portfolio.total_value_sol,
+            holdings: portfolio.holdings.len(),
+        })
+    }
+}
+
+#[async_trait]
+impl UserNotifier for Bot {
+    async fn notify(&self, telegram_id: i64, text: &str) -> Result<()> {
+        self.send_message(ChatId(telegram_id), text).await
+            .map_err(|e| BotError::internal(format!("Telegram send failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+async fn active_wallet(wallet_manager: &WalletManager, telegram_id: i64) -> Result<String> {
+    wallet_manager.get_user_wallet(&telegram_id.to_string()).await?
+        .map(|wallet| wallet.public_key)
+        .ok_or_else(|| BotError::not_found("No wallet configured".to_string()).into())
+}
+
+/// Routes Convex events to the component that handles each
+///
+/// Every event carries an idempotency key. The first delivery claims it in the
+/// session store, so it holds across instances; retries get that delivery's
+/// result back with `duplicate` set instead of running again.
+pub struct WebhookDispatcher {
+    authority: Arc<AutomationAuthority>,
+    sessions: Arc<dyn SessionStore>,
+    trades: Option<Arc<dyn TradeTarget>>,
+    orders: Option<Arc<dyn OrderTarget>>,
+    portfolios: Option<Arc<dyn PortfolioTarget>>,
+    notifier: Option<Arc<dyn UserNotifier>>,
+    automations: Option<Arc<dyn AutomationTarget>>,
+    pause: Option<Arc<dyn TradingPause>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(authority: Arc<AutomationAuthority>, sessions: Arc<dyn SessionStore>) -> Self {
+        Self {
+            authority,
+            sessions,
+            trades: None,
+            orders: None,
+            portfolios: None,
+            notifier: None,
+            automations: None,
+            pause: None,
+        }
+    }
+
+    pub fn with_trades(mut self, trades: Arc<dyn TradeTarget>) -> Self {
+        self.trades = Some(trades);
+        self
+    }
+
+    pub fn with_orders(mut self, orders: Arc<dyn OrderTarget>) -> Self {
+        self.orders = Some(orders);
+        self
+    }
+
+    pub fn with_portfolios(mut self, portfolios: Arc<dyn PortfolioTarget>) -> Self {
+        self.portfolios = Some(portfolios);
+        self
+    }
+
+    /// Delivers `notify_user` and `price_alert_triggered`
+    pub fn with_notifier(mut self, notifier: Arc<dyn UserNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Delivers `run_dca` and `simulated_trade`
+    pub fn with_automations(mut self, automations: Arc<dyn AutomationTarget>) -> Self {
+        self.automations = Some(automations);
+        self
+    }
+
+    /// Hold `execute_trade` while an operator has trading paused
+    pub fn with_pause(mut self, pause: Arc<dyn TradingPause>) -> Self {
+        self.pause = Some(pause);
+        self
+    }
+
+    /// Handle one event, or replay the outcome of its key
+    pub async fn dispatch(&self, envelope: WebhookEnvelope) -> EventResult {
+        // Held before the key is claimed, so a retry after maintenance still trades
+        if let (WebhookEvent::ExecuteTrade { .. }, Some(pause)) = (&envelope.event, &self.pause) {
+            if pause.trading_paused().await {
+                info!("📨 Trading paused, holding Convex {}", envelope.idempotency_key);
+                return EventResult::new(&envelope, EventStatus::Unavailable).with_message(PAUSED_FOR_MAINTENANCE);
+            }
+        }
+
+        let key = format!("webhook:{}", envelope.idempotency_key);
+        match self.sessions.claim(&key, IDEMPOTENCY_TTL).await {
+            Ok(true) => {}
+            Ok(false) => return self.replay(&key, &envelope).await,
+            Err(e) => {
+                warn!("📨 Idempotency store unavailable for {}: {}", envelope.idempotency_key, e);
+                return EventResult::new(&envelope, EventStatus::Failed)
+                    .with_message("Idempotency store unavailable, retry later");
+            }
+        }
+
+        info!("📨 Convex {} {}", envelope.event.event_type(), envelope.idempotency_key);
+        let result = match self.route(&envelope).await {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("📨 Convex {} {} failed: {}", envelope.event.event_type(), envelope.idempotency_key, e);
+                EventResult::new(&envelope, EventStatus::Failed).with_message(e.to_string())
+            }
+        };
+        // Failures are remembered too: a retry must not buy twice after a timeout
+        if let Err(e) = self.sessions.put(&key, &result, IDEMPOTENCY_TTL).await {
+            warn!("📨 Couldn't record the outcome of {}: {}", envelope.idempotency_key, e);
+        }
+        result
+    }
+
+    async fn replay(&self, key: &str, envelope: &WebhookEnvelope) -> EventResult {
+        // The claim holds `true` until the first delivery records its result
+        let stored = self.sessions.get(key).await.ok().flatten()
+            .and_then(|stored| serde_json::from_value::<EventResult>(stored.value).ok());
+        match stored {
+            Some(result) => EventResult { duplicate: true, ..result },
+            None => EventResult { duplicate: true, ..EventResult::new(envelope, EventStatus::InProgress) },
+        }
+    }
+
+    async fn route(&self, envelope: &WebhookEnvelope) -> Result<EventResult> {
+        let ok = EventResult::new(envelope, EventStatus::Ok);
+        let unavailable = |component: &str| {
+            EventResult::new(envelope, EventStatus::Unavailable).with_message(format!("No {} on this instance", component))
+        };
+
+        match &envelope.event {
+            WebhookEvent::ExecuteTrade { telegram_id, token, side, amount, authorization } => {
+                let Some(trades) = &self.trades else { return Ok(unavailable("trading engine")) };
+                let mint = TokenResolver::resolve(token)?;
+                let notional_sol = match side {
+                    TradeSide::Buy => ValidatedAmount::new(*amount, MAX_TRADE_SOL)?.value(),
+                    // Sells only shrink a position, so they don't count against the cap
+                    TradeSide::Sell => {
+                        Validator::validate_percentage(*amount)?;
+                        0.0
+                    }
+                };
+                let command = ConvexCommand {
+                    command_id: envelope.idempotency_key.clone(),
+                    telegram_id: *telegram_id,
+                    action: AutomationAction::Trade,
+                    notional_sol,
+                    authorization: authorization.clone(),
+                    strategy_id: None,
+                };
+
+                match self.authority.check(&command, notional_sol, Utc::now()).await {
+                    CommandDecision::Execute { grant_id } => {
+                        info!("🔐 Convex command {} trades {} under grant {}", command.command_id, mint, grant_id);
+                        let trade = trades.execute_trade(*telegram_id, &mint, *side, *amount).await?;
+                        Ok(ok.with_data(json!({
+                            "txSignature": trade.tx_signature,
+                            "amountSol": trade.amount_sol,
+                            "tokensReceived": trade.tokens_received,
+                            "solReceived": trade.sol_received,
+                            "price": trade.price,
+                        })))
+                    }
+                    CommandDecision::Duplicate => Ok(EventResult { duplicate: true, ..ok }),
+                    CommandDecision::Rejected(violation) => Ok(
+                        EventResult::new(envelope, EventStatus::Rejected)
+                            .with_message(format!("Refused because {}", violation.describe()))
+                    ),
+                }
+            }
+            WebhookEvent::CancelOrder { telegram_id, order_id } => {
+                let Some(orders) = &self.orders else { return Ok(unavailable("order manager")) };
+                if orders.cancel_order(*telegram_id, order_id).await? {
+                    Ok(ok.with_data(json!({ "orderId": order_id })))
+                } else {
+                    Ok(EventResult::new(envelope, EventStatus::Failed)
+                        .with_message(format!("No open order {} for this user", order_id)))
+                }
+            }
+            WebhookEvent::SyncPortfolio { telegram_id } => {
+                let Some(portfolios) = &self.portfolios else { return Ok(unavailable("portfolio fetcher")) };
+                let snapshot = portfolios.sync_portfolio(*telegram_id).await?;
+                Ok(ok.with_data(serde_json::to_value(snapshot).unwrap_or_default()))
+            }
+            WebhookEvent::NotifyUser { telegram_id, text } => {
+                let Some(notifier) = &self.notifier else { return Ok(unavailable("bot notifier")) };
+                notifier.notify(*telegram_id, text).await?;
+                Ok(ok)
+            }
+            WebhookEvent::PriceAlertTriggered { telegram_id, alert_id, token, price, condition } => {
+                let Some(notifier) = &self.notifier else { return Ok(unavailable("bot notifier")) };
+                notifier.notify(*telegram_id, &format!(
+                    "🔔 Price alert: {} {}\nNow: ${}\nAlert ID: {}",
+                    token, condition, price, alert_id
+                )).await?;
+                Ok(ok)
+            }
+            WebhookEvent::RunDca { telegram_id, strategy_id, authorization } => {
+                let Some(automations) = &self.automations else { return Ok(unavailable("DCA engine")) };
+                let command = ConvexCommand {
+                    command_id: envelope.idempotency_key.clone(),
+                    telegram_id: *telegram_id,
+                    action: AutomationAction::DcaRun,
+                    // Sized from the stored strategy, never from the request
+                    notional_sol: 0.0,
+                    authorization: authorization.clone(),
+                    strategy_id: Some(strategy_id.clone()),
+                };
+                let decision = automations.run_command(command).await?;
+                Ok(Self::decided(envelope, decision, json!({ "strategyId": strategy_id })))
+            }
+            WebhookEvent::SimulatedTrade { telegram_id, notional_sol, authorization } => {
+                let Some(automations) = &self.automations else { return Ok(unavailable("automation runner")) };
+                let command = ConvexCommand {
+                    command_id: envelope.idempotency_key.clone(),
+                    telegram_id: *telegram_id,
+                    action: AutomationAction::SimulatedTrade,
+                    notional_sol: ValidatedAmount::new(*notional_sol, MAX_TRADE_SOL)?.value(),
+                    authorization: authorization.clone(),
+                    strategy_id: None,
+                };
+                let decision = automations.run_command(command).await?;
+                Ok(Self::decided(envelope, decision, json!({ "notionalSol": notional_sol })))
+            }
+        }
+    }
+
+    /// Result of a command the automation runner already decided, with `data` when it ran
+    fn decided(envelope: &WebhookEnvelope, decision: CommandDecision, mut data: Value) -> EventResult {
+        let ok = EventResult::new(envelope, EventStatus::Ok);
+        match decision {
+            CommandDecision::Execute { grant_id } => {
+                data["grantId"] = json!(grant_id);
+                ok.with_data(data)
+            }
+            CommandDecision::Duplicate => EventResult { duplicate: true, ..ok },
+            CommandDecision::Rejected(violation) => EventResult::new(envelope, EventStatus::Rejected)
+                .with_message(format!("Refused because {}", violation.describe())),
+        }
+    }
+}
+
+/// `POST /webhook` for commands from Convex
+///
+/// The body is `{"events": [...]}`; each event has an `idempotencyKey`, a
+/// `type` from [`WebhookEvent::TYPES`] and that type's fields. Events run in
+/// order and the response holds one result per event. A batch with an unknown
+/// type is refused whole with 422 before anything runs.
+pub struct WebhookServer {
+    dispatcher: Arc<WebhookDispatcher>,
+    secret: Option<String>,
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    dispatcher: Arc<WebhookDispatcher>,
+    secret: Option<String>,
+}
+
+impl WebhookServer {
+    pub fn new(dispatcher: Arc<WebhookDispatcher>) -> Self {
+        Self { dispatcher, secret: None }
+    }
+
+    /// Require `Authorization: Bearer <secret>`
+    pub fn with_secret(mut self, secret: String) -> Self {
+        self.secret = Some(secret);
+        self
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/webhook", post(receive))
+            .with_state(WebhookState {
+                dispatcher: self.dispatcher.clone(),
+                secret: self.secret.clone(),
+            })
+    }
+
+    /// Bind `0.0.0.0:port` and serve until the task is dropped
+    pub async fn start(&self, port: u16) -> std::io::Result<()> {
+        let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+        self.serve(listener).await
+    }
+
+    /// Serve on an already bound listener
+    pub async fn serve(&self, listener: TcpListener) -> std::io::Result<()> {
+        info!("📨 Convex webhook on http://{}/webhook", listener.local_addr()?);
+        axum::serve(listener, self.router()).await
+    }
+}
+
+async fn receive(
+    State(state): State<WebhookState>,
+    headers: HeaderMap,
+    body: std::result::Result<Json<Value>, JsonRejection>,
+) -> Response {
+    if let Some(secret) = &state.secret {
+        let presented = headers.get(AUTHORIZATION).and_then(|value| value.to_str().ok());
+        if presented != Some(format!("Bearer {}", secret).as_str()) {
+            return error(StatusCode::UNAUTHORIZED, json!({ "error": "Missing or wrong webhook secret" }));
+        }
+    }
+    let Ok(Json(body)) = body else {
+        return error(StatusCode::BAD_REQUEST, json!({ "error": "Body must be JSON" }));
+    };
+
+    let events = match parse_events(body) {
+        Ok(events) => events,
+        Err(response) => return response,
+    };
+    let mut results = Vec::with_capacity(events.len());
+    for envelope in events {
+        results.push(state.dispatcher.dispatch(envelope).await);
+    }
+    Json(WebhookResponse { results }).into_response()
+}
+
+fn parse_events(body: Value) -> std::result::Result<Vec<WebhookEnvelope>, Response> {
+    let Some(events) = body.get("events").and_then(Value::as_array) else {
+        return Err(error(StatusCode::BAD_REQUEST, json!({ "error": "Expected {\"events\": [...]}" })));
+    };
+
+    let unsupported: Vec<String> = events.iter()
+        .map(|event| event.get("type").and_then(Value::as_str).unwrap_or_default())
+        .filter(|event_type| !WebhookEvent::TYPES.contains(event_type))
+        .map(str::to_string)
+        .collect();
+    if !unsupported.is_empty() {
+        return Err(error(StatusCode::UNPROCESSABLE_ENTITY, json!({
+            "error": "Unsupported event type",
+            "unsupported": unsupported,
+            "supportedTypes": WebhookEvent::TYPES,
+        })));
+    }
+
+    events.iter().enumerate()
+        .map(|(index, event)| {
+            serde_json::from_value::<WebhookEnvelope>(event.clone())
+                .map_err(|e| e.to_string())
+                .and_then(|envelope| match envelope.idempotency_key.trim().is_empty() {
+                    true => Err("idempotencyKey is required".to_string()),
+                    false => Ok(envelope),
+                })
+                .map_err(|e| error(StatusCode::BAD_REQUEST, json!({ "error": format!("Event {}: {}", index, e) })))
+        })
+        .collect()
+}
+
+fn error(status: StatusCode, body: Value) -> Response {
+    (status, Json(body)).into_response()
+}
@@ -0,0 +1,473 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use super::BotServices;
+use crate::{
+    db::Database,
+    errors::{BotError, Result},
+    wallet::WalletManager,
+};
+
+/// Hours between /forgetme and the erasure running
+pub const DEFAULT_GRACE_PERIOD_HOURS: i64 = 72;
+
+/// Records that may be kept after erasure, stripped of anything but the core
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RetainedRecord {
+    /// Closed trades stay in the journal without tags or notes
+    TradeHistory,
+    /// Fee ledger entries stay as charged
+    FeeLedger,
+}
+
+impl RetainedRecord {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "trades" | "trade_history" => Some(Self::TradeHistory),
+            "fees" | "fee_ledger" => Some(Self::FeeLedger),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::TradeHistory => "trade records",
+            Self::FeeLedger => "fee records",
+        }
+    }
+}
+
+/// Grace period and legally retained core for self-serve erasure
+#[derive(Debug, Clone)]
+pub struct DeletionConfig {
+    pub grace_period: Duration,
+    pub retained: HashSet<RetainedRecord>,
+}
+
+impl Default for DeletionConfig {
+    fn default() -> Self {
+        Self {
+            grace_period: Duration::hours(DEFAULT_GRACE_PERIOD_HOURS),
+            retained: HashSet::from([RetainedRecord::TradeHistory, RetainedRecord::FeeLedger]),
+        }
+    }
+}
+
+impl DeletionConfig {
+    /// `FORGETME_GRACE_HOURS`, `FORGETME_RETAIN` (comma list of trades, fees; or none)
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(hours) = std::env::var("FORGETME_GRACE_HOURS").ok().and_then(|h| h.parse::<i64>().ok()) {
+            config.grace_period = Duration::hours(hours.max(0));
+        }
+        if let Ok(retain) = std::env::var("FORGETME_RETAIN") {
+            config.retained = Self::parse_retained(&retain);
+        }
+        config
+    }
+
+    pub fn parse_retained(list: &str) -> HashSet<RetainedRecord> {
+        list.split(',')
+            .filter(|name| !name.trim().is_empty() && name.trim() != "none")
+            .filter_map(|name| {
+                let record = RetainedRecord::parse(name);
+                if record.is_none() {
+                    warn!("🗑️ Ignoring unknown retained record {:?}", name);
+                }
+                record
+            })
+            .collect()
+    }
+
+    pub fn retains(&self, record: RetainedRecord) -> bool {
+        self.retained.contains(&record)
+    }
+
+    /// Steps an erasure runs, in order; retained records are never touched
+    pub fn steps(&self) -> Vec<ErasureStep> {
+        ErasureStep::ALL.iter()
+            .copied()
+            .filter(|step| !(*step == ErasureStep::DeleteFees && self.retains(RetainedRecord::FeeLedger)))
+            .collect()
+    }
+}
+
+/// One idempotent unit of an erasure, persisted once done so a rerun skips it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErasureStep {
+    /// First, so the Convex sync can't recreate anything mid-erasure
+    Tombstone,
+    CancelOrders,
+    StopDca,
+    StopCopyTrading,
+    /// Alerts, including any armed to auto-buy
+    DeleteAlerts,
+    DeleteWatchlists,
+    DeleteSettings,
+    DeleteAliases,
+    /// Tags and notes; the trades too unless trade history is retained
+    DeleteJournal,
+    DeleteFees,
+    AnonymizeLeaderboard,
+    /// Last, so the user can still be matched to their wallets until the end
+    DeleteWallets,
+}
+
+impl ErasureStep {
+    pub const ALL: [ErasureStep; 12] = [
+        Self::Tombstone,
+        Self::CancelOrders,
+        Self::StopDca,
+        Self::StopCopyTrading,
+        Self::DeleteAlerts,
+        Self::DeleteWatchlists,
+        Self::DeleteSettings,
+        Self::DeleteAliases,
+        Self::DeleteJournal,
+        Self::DeleteFees,
+        Self::AnonymizeLeaderboard,
+        Self::DeleteWallets,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Tombstone => "sync tombstone",
+            Self::CancelOrders => "orders",
+            Self::StopDca => "DCA strategies",
+            Self::StopCopyTrading => "copy trading",
+            Self::DeleteAlerts => "alerts",
+            Self::DeleteWatchlists => "watchlists",
+            Self::DeleteSettings => "settings",
+            Self::DeleteAliases => "aliases",
+            Self::DeleteJournal => "journal notes",
+            Self::DeleteFees => "fee records",
+            Self::AnonymizeLeaderboard => "leaderboard name",
+            Self::DeleteWallets => "wallets",
+        }
+    }
+}
+
+/// Stores an erasure step deletes from
+#[async_trait::async_trait]
+pub trait ErasureTarget: Send + Sync {
+    /// Run one step for a user; returns how many records it touched. Must be
+    /// safe to repeat, since a crash can land between the step and its checkpoint.
+    async fn erase(&self, user_id: i64, step: ErasureStep, config: &DeletionConfig) -> Result<usize>;
+}
+
+/// Erases from the bot's own stores and their database tables
+pub struct NativeErasure {
+    services: Arc<BotServices>,
+    wallet_manager: Arc<WalletManager>,
+    database: Arc<Database>,
+}
+
+impl NativeErasure {
+    pub fn new(services: Arc<BotServices>, wallet_manager: Arc<WalletManager>, database: Arc<Database>) -> Self {
+        Self { services, wallet_manager, database }
+    }
+}
+
+#[async_trait::async_trait]
+impl ErasureTarget for NativeErasure {
+    async fn erase(&self, user_id: i64, step: ErasureStep, config: &DeletionConfig) -> Result<usize> {
+        let services = &self.services;
+        let database = &self.database;
+        let erased = match step {
+            // Recorded by the manager itself
+            ErasureStep::Tombstone => 0,
+            ErasureStep::CancelOrders => {
+                let mut cancelled = 0;
+                for order in services.order_manager.get_user_orders(user_id).await {
+                    if services.order_manager.cancel_order(&order.order_id).await? {
+                        cancelled += 1;
+                    }
+                }
+                cancelled + database.delete_user_unfilled_orders(user_id).await? as usize
+            }
+            ErasureStep::StopDca => {
+                services.automation_auth.forget_user(user_id).await
+                    + services.dca_engine.remove_user_strategies(user_id).await
+                    + database.delete_user_dca_strategies(user_id).await? as usize
+            }
+            ErasureStep::StopCopyTrading => {
+                services.copy_trading.stop_all_following(user_id).await
+                    + database.delete_user_copy_trading(user_id).await? as usize
+            }
+            ErasureStep::DeleteAlerts => {
+                let mut deleted = 0;
+                for alert in services.price_alerts.get_user_alerts(user_id).await {
+                    if services.price_alerts.delete_alert(&alert.alert_id).await? {
+                        deleted += 1;
+                    }
+                }
+                deleted + database.delete_user_price_alerts(user_id).await? as usize
+            }
+            ErasureStep::DeleteWatchlists => {
+                services.token_calendar.forget_user(user_id).await
+                    + services.bonding.forget_user(user_id).await
+                    + services.whales.forget_user(user_id).await
+                    + services.watchlists.forget_user(user_id).await
+            }
+            ErasureStep::DeleteSettings => {
+                services.ata_janitor.set_auto(user_id, None).await;
+                services.execution_notices.forget_user(user_id).await;
+                let timezone = services.dca_engine.timezones().clear_user_timezone(user_id).await;
+                let preferences = services.preferences.remove(user_id).await;
+                let delivery = services.price_alerts.delivery().forget_user(user_id).await;
+                // Wallets go last, so they still list what to clear here
+                let mut wallet_state = 0;
+                for wallet in database.get_user_wallets(&user_id.to_string()).await? {
+                    wallet_state += database.delete_wallet_state(&wallet.wallet_address).await? as usize;
+                }
+                database.delete_user_settings(user_id).await?;
+                usize::from(timezone) + usize::from(preferences) + usize::from(delivery) + wallet_state
+            }
+            ErasureStep::DeleteAliases => services.aliases.clear(user_id).await,
+            ErasureStep::DeleteJournal => {
+                let keep_trades = config.retains(RetainedRecord::TradeHistory);
+                let (performance, stored) = if keep_trades {
+                    (0, 0)
+                } else {
+                    (
+                        services.performance.forget_user(user_id).await,
+                        database.delete_user_trade_history(user_id).await? as usize,
+                    )
+                };
+                services.journal.forget_user(user_id, keep_trades).await
+                    + services.trade_imports.forget_user(user_id, keep_trades).await
+                    + performance
+                    + stored
+            }
+            ErasureStep::DeleteFees => {
+                services.fee_ledger.forget_user(user_id).await
+                    + database.delete_master_fee_ledger(user_id).await? as usize
+            }
+            ErasureStep::AnonymizeLeaderboard => services.leaderboard.anonymize_user(user_id).await,
+            ErasureStep::DeleteWallets => self.wallet_manager.forget_user(&user_id.to_string()).await?,
+        };
+        Ok(erased)
+    }
+}
+
+/// Telegram ids erased on request, which no sync may recreate
+#[derive(Debug, Clone, Default)]
+pub struct Tombstones {
+    ids: Arc<RwLock<HashSet<i64>>>,
+}
+
+impl Tombstones {
+    pub async fn contains(&self, telegram_id: i64) -> bool {
+        self.ids.read().await.contains(&telegram_id)
+    }
+
+    async fn insert(&self, telegram_id: i64) -> bool {
+        self.ids.write().await.insert(telegram_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeletionStatus {
+    /// Waiting out the grace period; can still be cancelled
+    Scheduled,
+    /// Erasure started; runs to completion, resuming after restarts
+    InProgress,
+}
+
+/// A user's pending erasure and its checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletionRequest {
+    pub user_id: i64,
+    pub requested_at: DateTime<Utc>,
+    pub execute_at: DateTime<Utc>,
+    pub status: DeletionStatus,
+    /// Finished steps with the records each touched
+    pub completed: Vec<(ErasureStep, usize)>,
+}
+
+impl DeletionRequest {
+    pub fn is_done(&self, step: ErasureStep) -> bool {
+        self.completed.iter().any(|(done, _)| *done == step)
+    }
+}
+
+/// A finished erasure
+#[derive(Debug, Clone)]
+pub struct ErasureReport {
+    pub user_id: i64,
+    pub completed: Vec<(ErasureStep, usize)>,
+    pub retained: Vec<RetainedRecord>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DeletionFile {
+    requests: HashMap<i64, DeletionRequest>,
+    tombstones: HashSet<i64>,
+}
+
+/// Schedules /forgetme erasures and runs them once the grace period ends
+///
+/// Every finished step is checkpointed (to disk when storage is configured)
+/// before the next starts, so an interrupted erasure resumes where it stopped.
+pub struct DataDeletionManager {
+    config: DeletionConfig,
+    requests: RwLock<HashMap<i64, DeletionRequest>>,
+    tombstones: Tombstones,
+    storage: Option<PathBuf>,
+}
+
+impl DataDeletionManager {
+    pub fn new(config: DeletionConfig) -> Self {
+        Self {
+            config,
+            requests: RwLock::new(HashMap::new()),
+            tombstones: Tombstones::default(),
+            storage: None,
+        }
+    }
+
+    /// Persist requests and tombstones to a JSON file, loading any saved there
+    pub async fn with_storage(mut self, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => {
+                let file: DeletionFile = serde_json::from_slice(&bytes)
+                    .map_err(|e| BotError::parsing(format!("Invalid deletion file {}: {}", path.display(), e)))?;
+                info!("🗑️ Loaded {} pending erasures and {} tombstones", file.requests.len(), file.tombstones.len());
+                *self.requests.write().await = file.requests;
+                *self.tombstones.ids.write().await = file.tombstones;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(BotError::internal(format!("Failed to read {}: {}", path.display(), e)).into()),
+        }
+        self.storage = Some(path);
+        Ok(self)
+    }
+
+    pub fn config(&self) -> &DeletionConfig {
+        &self.config
+    }
+
+    /// Shared with the Convex migration so tombstoned users are never re-imported
+    pub fn tombstones(&self) -> Tombstones {
+        self.tombstones.clone()
+    }
+
+    pub async fn request(&self, user_id: i64) -> Option<DeletionRequest> {
+        self.requests.read().await.get(&user_id).cloned()
+    }
+
+    /// Schedule a user's erasure after the grace period; an existing request is kept as is
+    pub async fn schedule(&self, user_id: i64, now: DateTime<Utc>) -> DeletionRequest {
+        let mut requests = self.requests.write().await;
+        if let Some(existing) = requests.get(&user_id) {
+            return existing.clone();
+        }
+
+        let request = DeletionRequest {
+            user_id,
+            requested_at: now,
+            execute_at: now + self.config.grace_period,
+            status: DeletionStatus::Scheduled,
+            completed: Vec::new(),
+        };
+        requests.insert(user_id, request.clone());
+        info!("🗑️ Erasure of user {} scheduled for {}", user_id, request.execute_at);
+        self.save(&requests).await;
+        request
+    }
+
+    /// Cancel during the grace period; an erasure already under way can't be stopped
+    pub async fn cancel(&self, user_id: i64) -> Result<()> {
+        let mut requests = self.requests.write().await;
+        match requests.get(&user_id).map(|r| r.status) {
+            None => Err(BotError::not_found("No deletion is scheduled".to_string()).into()),
+            Some(DeletionStatus::InProgress) => {
+                Err(BotError::validation("Deletion has already started and will finish".to_string()).into())
+            }
+            Some(DeletionStatus::Scheduled) => {
+                requests.remove(&user_id);
+                info!("🗑️ User {} cancelled their erasure", user_id);
+                self.save(&requests).await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Run every erasure that is due or was interrupted; one failure doesn't stop the rest
+    pub async fn run_due(&self, target: &dyn ErasureTarget, now: DateTime<Utc>) -> Vec<Result<ErasureReport>> {
+        let due: Vec<i64> = self.requests.read().await.values()
+            .filter(|r| r.status == DeletionStatus::InProgress || r.execute_at <= now)
+            .map(|r| r.user_id)
+            .collect();
+
+        let mut results = Vec::new();
+        for user_id in due {
+            let result = self.execute(target, user_id).await;
+            if let Err(e) = &result {
+                warn!("🗑️ Erasure of user {} interrupted, will resume: {}", user_id, e);
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    async fn execute(&self, target: &dyn ErasureTarget, user_id: i64) -> Result<ErasureReport> {
+        self.update(user_id, |request| request.status = DeletionStatus::InProgress).await?;
+
+        for step in self.config.steps() {
+            let done = self.request(user_id).await.is_some_and(|r| r.is_done(step));
+            if done {
+                continue;
+            }
+
+            let erased = match step {
+                ErasureStep::Tombstone => usize::from(self.tombstones.insert(user_id).await),
+                step => target.erase(user_id, step, &self.config).await?,
+            };
+            self.update(user_id, |request| request.completed.push((step, erased))).await?;
+        }
+
+        let mut requests = self.requests.write().await;
+        let request = requests.remove(&user_id)
+            .ok_or_else(|| BotError::not_found(format!("No deletion request for {}", user_id)))?;
+        self.save(&requests).await;
+        info!("🗑️ Erased user {} ({} steps)", user_id, request.completed.len());
+
+        Ok(ErasureReport {
+            user_id,
+            completed: request.completed,
+            retained: self.config.retained.iter().copied().collect(),
+        })
+    }
+
+    async fn update(&self, user_id: i64, change: impl FnOnce(&mut DeletionRequest)) -> Result<()> {
+        let mut requests = self.requests.write().await;
+        let request = requests.get_mut(&user_id)
+            .ok_or_else(|| BotError::not_found(format!("No deletion request for {}", user_id)))?;
+        change(request);
+        self.save(&requests).await;
+        Ok(())
+    }
+
+    async fn save(&self, requests: &HashMap<i64, DeletionRequest>) {
+        let Some(path) = &self.storage else { return };
+        let file = DeletionFile {
+            requests: requests.clone(),
+            tombstones: self.tombstones.ids.read().await.clone(),
+        };
+        let result = match serde_json::to_vec_pretty(&file) {
+            Ok(bytes) => tokio::fs::write(path, bytes).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            // Steps are idempotent, so a lost checkpoint only means a step reruns
+            warn!("🗑️ Failed to persist deletion state to {}: {}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,527 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::{
+    api::{BulkRecipient, BulkSendRequest, BulkSendResponse, JupiterSendClient, SendInfo, SendStatus},
+    db::Database,
+    errors::{BotError, Result},
+    utils::Config,
+};
+
+/// Jupiter Send's limit on recipients per bulk send
+pub const MAX_RECIPIENTS: usize = 1000;
+/// Base network fee for each send's transaction
+pub const SEND_NETWORK_FEE_LAMPORTS: u64 = 5_000;
+/// Priority fee attached to each send
+pub const SEND_PRIORITY_FEE_LAMPORTS: u64 = 5_000;
+/// Longest expiry Jupiter Send accepts
+const MAX_EXPIRY_HOURS: u32 = 168;
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Who a send goes to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Recipient {
+    /// Gets a claim link to share with them
+    Handle(String),
+    /// Receives directly at the wallet
+    Address(String),
+}
+
+impl Recipient {
+    /// `@username` or a wallet address
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let text = text.trim();
+        if let Some(name) = text.strip_prefix('@') {
+            let valid = (5..=32).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            return if valid {
+                Ok(Self::Handle(format!("@{}", name)))
+            } else {
+                Err("not a valid @username".to_string())
+            };
+        }
+        if (32..=44).contains(&text.len()) && Pubkey::from_str(text).is_ok() {
+            return Ok(Self::Address(text.to_string()));
+        }
+        Err("not a wallet address or @username".to_string())
+    }
+
+    /// The recipient id sent to Jupiter and shown in result tables
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Handle(handle) => handle,
+            Self::Address(address) => address,
+        }
+    }
+}
+
+/// One recipient and the whole tokens they get
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    pub recipient: Recipient,
+    pub amount: f64,
+}
+
+/// An entry that can't be sent to, with why
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejected {
+    pub input: String,
+    pub reason: String,
+}
+
+/// Recipients read from a command or an uploaded CSV
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecipientList {
+    pub allocations: Vec<Allocation>,
+    pub rejected: Vec<Rejected>,
+}
+
+impl RecipientList {
+    /// `@user1 @user2 <address>` each getting `amount`
+    pub fn from_args<'a>(args: impl IntoIterator<Item = &'a str>, amount: f64) -> Self {
+        let mut list = Self::default();
+        let mut seen = HashSet::new();
+        for arg in args {
+            list.push(arg, Ok(amount), &mut seen);
+        }
+        list
+    }
+
+    /// One `recipient[,amount]` per line; rows without an amount get `default_amount`
+    ///
+    /// Blank lines, `#` comments and a header row are skipped. Bad rows are
+    /// rejected on their own; only an unreadable file or too many rows fails the upload.
+    pub fn from_csv(reader: impl BufRead, default_amount: f64) -> Result<Self> {
+        let mut list = Self::default();
+        let mut seen = HashSet::new();
+        let mut first_row = true;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| BotError::validation(format!("Couldn't read the CSV: {}", e)))?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut cells = line.split([',', ';', '\t']).map(str::trim);
+            let recipient = cells.next().unwrap_or_default();
+            let header = first_row && ["address", "recipient", "wallet", "user"].contains(&recipient.to_lowercase().as_str());
+            first_row = false;
+            if header {
+                continue;
+            }
+
+            let amount = match cells.next().filter(|cell| !cell.is_empty()) {
+                None => Ok(default_amount),
+                Some(cell) => cell.parse::<f64>()
+                    .ok()
+                    .filter(|amount| amount.is_finite() && *amount > 0.0)
+                    .ok_or_else(|| format!("line {}: amount \"{}\" isn't a positive number", index + 1, cell)),
+            };
+            list.push(recipient, amount, &mut seen);
+            if list.allocations.len() + list.rejected.len() > MAX_RECIPIENTS {
+                return Err(BotError::validation(format!("A send can go to at most {} recipients", MAX_RECIPIENTS)));
+            }
+        }
+        Ok(list)
+    }
+
+    fn push(&mut self, input: &str, amount: std::result::Result<f64, String>, seen: &mut HashSet<Recipient>) {
+        let rejected = |reason: String| Rejected { input: input.to_string(), reason };
+        let recipient = match Recipient::parse(input) {
+            Ok(recipient) => recipient,
+            Err(reason) => return self.rejected.push(rejected(reason)),
+        };
+        let amount = match amount {
+            Ok(amount) => amount,
+            Err(reason) => return self.rejected.push(rejected(reason)),
+        };
+        if !seen.insert(recipient.clone()) {
+            return self.rejected.push(rejected("listed more than once".to_string()));
+        }
+        self.allocations.push(Allocation { recipient, amount });
+    }
+
+    pub fn total(&self) -> f64 {
+        self.allocations.iter().map(|a| a.amount).sum()
+    }
+}
+
+/// What a bulk send costs before it's confirmed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SendPreview {
+    pub recipients: usize,
+    pub rejected: usize,
+    /// Whole tokens sent across all recipients
+    pub total_tokens: f64,
+    pub fee_lamports: u64,
+    /// SOL leaving the wallet: fees, plus the tokens themselves when sending SOL
+    pub total_sol: f64,
+}
+
+impl SendPreview {
+    pub fn build(list: &RecipientList, priority_fee_lamports: u64, sending_sol: bool) -> Self {
+        let recipients = list.allocations.len();
+        let total_tokens = list.total();
+        let fee_lamports = recipients as u64 * (SEND_NETWORK_FEE_LAMPORTS + priority_fee_lamports);
+        let fees_sol = fee_lamports as f64 / 1e9;
+        Self {
+            recipients,
+            rejected: list.rejected.len(),
+            total_tokens,
+            fee_lamports,
+            total_sol: if sending_sol { total_tokens + fees_sol } else { fees_sol },
+        }
+    }
+
+    pub fn fee_sol(&self) -> f64 {
+        self.fee_lamports as f64 / 1e9
+    }
+}
+
+/// What happened to one recipient of a bulk send
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecipientOutcome {
+    /// A claim link went out; `magic_link` is what to share with handles
+    Sent { send_id: String, magic_link: Option<String> },
+    /// Never sent, e.g. a malformed address
+    Invalid(String),
+    /// Sent to Jupiter but refused there
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipientResult {
+    pub recipient: String,
+    /// Whole tokens; 0 for rows that never had a valid amount
+    pub amount: f64,
+    pub outcome: RecipientOutcome,
+}
+
+impl RecipientResult {
+    pub fn send_id(&self) -> Option<&str> {
+        match &self.outcome {
+            RecipientOutcome::Sent { send_id, .. } => Some(send_id),
+            _ => None,
+        }
+    }
+}
+
+/// Per-recipient results, in the order the user listed them, followed by rejected entries
+///
+/// A `response` of `Err` marks every allocation failed with that error.
+pub fn aggregate_results(list: &RecipientList, response: std::result::Result<&BulkSendResponse, String>) -> Vec<RecipientResult> {
+    let sends: HashMap<&str, _> = match &response {
+        Ok(response) => response.sends.iter().map(|send| (send.recipient_id.as_str(), send)).collect(),
+        Err(_) => HashMap::new(),
+    };
+
+    let mut results: Vec<RecipientResult> = list.allocations.iter()
+        .map(|allocation| {
+            let id = allocation.recipient.label();
+            let outcome = match (&response, sends.get(id)) {
+                (Err(error), _) => RecipientOutcome::Failed(error.clone()),
+                (Ok(_), None) => RecipientOutcome::Failed("missing from Jupiter's response".to_string()),
+                (Ok(_), Some(send)) if !send.success => {
+                    RecipientOutcome::Failed(send.error.clone().unwrap_or_else(|| "send failed".to_string()))
+                }
+                (Ok(_), Some(send)) => match &send.send_id {
+                    Some(send_id) => RecipientOutcome::Sent { send_id: send_id.clone(), magic_link: send.magic_link.clone() },
+                    None => RecipientOutcome::Failed("no send id returned".to_string()),
+                },
+            };
+            RecipientResult { recipient: id.to_string(), amount: allocation.amount, outcome }
+        })
+        .collect();
+
+    results.extend(list.rejected.iter().map(|rejected| RecipientResult {
+        recipient: rejected.input.clone(),
+        amount: 0.0,
+        outcome: RecipientOutcome::Invalid(rejected.reason.clone()),
+    }));
+    results
+}
+
+/// A bulk send the user can check on and reclaim from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Distribution {
+    pub id: String,
+    pub owner: i64,
+    pub mint: String,
+    pub symbol: String,
+    pub created_at: DateTime<Utc>,
+    /// Unclaimed sends can be cancelled back to the sender from here on
+    pub expires_at: DateTime<Utc>,
+    pub results: Vec<RecipientResult>,
+}
+
+impl Distribution {
+    pub fn sent(&self) -> usize {
+        self.results.iter().filter(|r| r.send_id().is_some()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.sent()
+    }
+
+    pub fn reclaimable(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Where a recipient's claim stands
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaimState {
+    Unclaimed,
+    /// Raw units claimed so far
+    Partial(u64),
+    Claimed,
+    Expired,
+    Cancelled,
+    Failed,
+    /// Jupiter couldn't be asked; the reason
+    Unknown(String),
+    /// Nothing was sent to this recipient
+    NotSent,
+}
+
+impl ClaimState {
+    pub fn from_info(info: &SendInfo) -> Self {
+        match info.status {
+            SendStatus::Created => Self::Unclaimed,
+            SendStatus::Partially => Self::Partial(info.claimed_amount),
+            SendStatus::Claimed => Self::Claimed,
+            SendStatus::Expired => Self::Expired,
+            SendStatus::Cancelled => Self::Cancelled,
+            SendStatus::Failed => Self::Failed,
+        }
+    }
+
+    /// Still holding tokens that can go back to the sender
+    pub fn is_reclaimable(&self) -> bool {
+        matches!(self, Self::Unclaimed | Self::Partial(_) | Self::Expired)
+    }
+}
+
+/// `/send status`: each recipient and their claim
+#[derive(Debug, Clone)]
+pub struct ClaimRow {
+    pub recipient: String,
+    pub amount: f64,
+    pub state: ClaimState,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReclaimReport {
+    pub cancelled: usize,
+    pub failed: usize,
+}
+
+/// A previewed bulk send waiting for confirmation
+#[derive(Debug, Clone)]
+pub struct PendingDistribution {
+    pub sender: String,
+    pub mint: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub list: RecipientList,
+    pub preview: SendPreview,
+}
+
+/// `/send <token> <amount>` without recipients, waiting for the CSV
+#[derive(Debug, Clone)]
+pub struct UploadRequest {
+    pub mint: String,
+    pub symbol: String,
+    pub amount: f64,
+}
+
+/// `/send`: bulk token distribution through Jupiter Send
+pub struct Distributions {
+    client: Arc<JupiterSendClient>,
+    rpc_client: Arc<RpcClient>,
+    database: Arc<Database>,
+    expiry_hours: u32,
+    uploads: RwLock<HashMap<i64, UploadRequest>>,
+    pending: RwLock<HashMap<i64, PendingDistribution>>,
+    batches: RwLock<HashMap<String, Distribution>>,
+}
+
+impl Distributions {
+    pub fn new(client: Arc<JupiterSendClient>, rpc_client: Arc<RpcClient>, database: Arc<Database>, expiry_hours: u32) -> Self {
+        Self {
+            client,
+            rpc_client,
+            database,
+            expiry_hours: expiry_hours.clamp(1, MAX_EXPIRY_HOURS),
+            uploads: RwLock::new(HashMap::new()),
+            pending: RwLock::new(HashMap::new()),
+            batches: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(client: Arc<JupiterSendClient>, rpc_client: Arc<RpcClient>, database: Arc<Database>, config: &Config) -> Self {
+        Self::new(client, rpc_client, database, config.send_expiry_hours)
+    }
+
+    pub fn expiry_hours(&self) -> u32 {
+        self.expiry_hours
+    }
+
+    pub async fn expect_upload(&self, telegram_id: i64, request: UploadRequest) {
+        self.uploads.write().await.insert(telegram_id, request);
+    }
+
+    pub async fn take_upload_request(&self, telegram_id: i64) -> Option<UploadRequest> {
+        self.uploads.write().await.remove(&telegram_id)
+    }
+
+    /// Price the send and hold it until the user confirms
+    pub async fn prepare(&self, telegram_id: i64, sender: &str, mint: &str, symbol: &str, list: RecipientList) -> Result<PendingDistribution> {
+        if list.allocations.is_empty() {
+            return Err(BotError::validation("None of the recipients can be sent to".to_string()));
+        }
+        if list.allocations.len() > MAX_RECIPIENTS {
+            return Err(BotError::validation(format!("A send can go to at most {} recipients", MAX_RECIPIENTS)));
+        }
+
+        let decimals = if mint == WSOL_MINT {
+            9
+        } else {
+            self.rpc_client.get_token_supply(&Pubkey::from_str(mint)?).await?.decimals
+        };
+        let pending = PendingDistribution {
+            sender: sender.to_string(),
+            mint: mint.to_string(),
+            symbol: symbol.to_string(),
+            decimals,
+            preview: SendPreview::build(&list, SEND_PRIORITY_FEE_LAMPORTS, mint == WSOL_MINT),
+            list,
+        };
+        self.pending.write().await.insert(telegram_id, pending.clone());
+        Ok(pending)
+    }
+
+    pub async fn cancel(&self, telegram_id: i64) -> Option<PendingDistribution> {
+        self.pending.write().await.remove(&telegram_id)
+    }
+
+    /// Create the sends; recipients fail on their own without stopping the rest
+    pub async fn execute(&self, telegram_id: i64, sender: &str) -> Result<Distribution> {
+        let pending = self.pending.write().await.remove(&telegram_id)
+            .filter(|p| p.sender == sender)
+            .ok_or_else(|| BotError::validation("This send expired; run /send again".to_string()))?;
+
+        let scale = 10f64.powi(pending.decimals as i32);
+        let request = BulkSendRequest {
+            sender_public_key: sender.to_string(),
+            token_mint: pending.mint.clone(),
+            recipients: pending.list.allocations.iter()
+                .map(|allocation| BulkRecipient {
+                    recipient_id: allocation.recipient.label().to_string(),
+                    amount: (allocation.amount * scale).round() as u64,
+                    recipient_public_key: match &allocation.recipient {
+                        Recipient::Address(address) => Some(address.clone()),
+                        Recipient::Handle(_) => None,
+                    },
+                    recipient_email: None,
+                    recipient_phone: None,
+                    personal_message: None,
+                })
+                .collect(),
+            message: None,
+            expiry_hours: Some(self.expiry_hours),
+            priority_fee_lamports: Some(SEND_PRIORITY_FEE_LAMPORTS),
+        };
+
+        let response = self.client.create_bulk_send(request).await;
+        let created_at = Utc::now();
+        let distribution = Distribution {
+            id: response.as_ref().map(|r| r.batch_id.clone()).unwrap_or_else(|_| uuid::Uuid::new_v4().to_string()),
+            owner: telegram_id,
+            mint: pending.mint,
+            symbol: pending.symbol,
+            created_at,
+            expires_at: created_at + Duration::hours(self.expiry_hours as i64),
+            results: aggregate_results(&pending.list, response.as_ref().map_err(|e| e.to_string())),
+        };
+
+        if distribution.sent() > 0 {
+            match serde_json::to_string(&distribution) {
+                Ok(data) => {
+                    if let Err(e) = self.database.save_send_batch(telegram_id, &distribution.id, &data).await {
+                        warn!("📤 Failed to store send batch {}: {}", distribution.id, e);
+                    }
+                }
+                Err(e) => warn!("📤 Failed to serialize send batch {}: {}", distribution.id, e),
+            }
+            self.batches.write().await.insert(distribution.id.clone(), distribution.clone());
+        }
+        info!("📤 Send batch {} for {}: {} sent, {} failed", distribution.id, telegram_id, distribution.sent(), distribution.failed());
+        Ok(distribution)
+    }
+
+    /// A batch the user created
+    pub async fn get(&self, id: &str, owner: i64) -> Result<Distribution> {
+        let cached = self.batches.read().await.get(id).cloned();
+        let distribution = match cached {
+            Some(distribution) => Some(distribution),
+            None => self.database.get_send_batch(id).await?
+                .and_then(|data| serde_json::from_str::<Distribution>(&data).ok()),
+        };
+        distribution
+            .filter(|d| d.owner == owner)
+            .ok_or_else(|| BotError::validation(format!("No send batch {}", id)))
+    }
+
+    /// Claim status for every recipient, asking Jupiter about each sent one
+    pub async fn status(&self, id: &str, owner: i64) -> Result<(Distribution, Vec<ClaimRow>)> {
+        let distribution = self.get(id, owner).await?;
+        let mut rows = Vec::with_capacity(distribution.results.len());
+        for result in &distribution.results {
+            let state = match result.send_id() {
+                Some(send_id) => match self.client.get_send_info(send_id).await {
+                    Ok(info) => ClaimState::from_info(&info),
+                    Err(e) => ClaimState::Unknown(e.to_string()),
+                },
+                None => ClaimState::NotSent,
+            };
+            rows.push(ClaimRow { recipient: result.recipient.clone(), amount: result.amount, state });
+        }
+        Ok((distribution, rows))
+    }
+
+    /// Cancel sends nobody claimed once the batch is past its expiry
+    pub async fn reclaim(&self, id: &str, owner: i64) -> Result<ReclaimReport> {
+        let (distribution, rows) = self.status(id, owner).await?;
+        if !distribution.reclaimable(Utc::now()) {
+            return Err(BotError::validation(format!(
+                "Unclaimed sends can be reclaimed after {}",
+                distribution.expires_at.format("%Y-%m-%d %H:%M UTC")
+            )));
+        }
+
+        let mut report = ReclaimReport::default();
+        for (result, row) in distribution.results.iter().zip(&rows) {
+            let Some(send_id) = result.send_id() else { continue };
+            if !row.state.is_reclaimable() {
+                continue;
+            }
+            match self.client.cancel_send(send_id).await {
+                Ok(true) => report.cancelled += 1,
+                Ok(false) => report.failed += 1,
+                Err(e) => {
+                    warn!("📤 Failed to reclaim send {}: {}", send_id, e);
+                    report.failed += 1;
+                }
+            }
+        }
+        info!("📤 Reclaimed {} sends from batch {} ({} failed)", report.cancelled, id, report.failed);
+        Ok(report)
+    }
+}
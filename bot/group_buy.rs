@@ -0,0 +1,361 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::errors::{BotError, Result};
+
+/// How long a group buy stays open for members to join
+const DEFAULT_WINDOW_SECS: i64 = 600;
+/// Quorum when the command doesn't give one
+pub const DEFAULT_MIN_PARTICIPANTS: usize = 3;
+/// Smallest quorum worth coordinating
+const MIN_QUORUM: usize = 2;
+/// Upper bound on participants, keeping execution and the card readable
+const MAX_PARTICIPANTS: usize = 25;
+
+/// Lifecycle of a group buy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBuyStatus {
+    /// Collecting participants
+    Open,
+    /// Quorum reached; buys are running
+    Executing,
+    /// Window closed before quorum
+    Expired,
+}
+
+/// A coordinated buy posted in a group chat
+///
+/// Funds are never pooled: each participant buys `amount_each` from their own wallet.
+#[derive(Debug, Clone)]
+pub struct GroupBuy {
+    pub id: String,
+    pub chat_id: i64,
+    pub creator_id: i64,
+    pub mint: String,
+    pub symbol: String,
+    /// SOL each participant spends
+    pub amount_each: f64,
+    pub min_participants: usize,
+    /// Telegram ids in join order
+    pub participants: Vec<i64>,
+    pub status: GroupBuyStatus,
+    pub price: Option<f64>,
+    pub risk_badge: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl GroupBuy {
+    pub fn quorum_reached(&self) -> bool {
+        self.participants.len() >= self.min_participants
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Card shown in the group; counts only, never who joined
+    pub fn card_text(&self) -> String {
+        let price = self.price
+            .map(|p| format!("${}", crate::bot::chart_actions::ChartActions::format_price(p)))
+            .unwrap_or_else(|| "unavailable".to_string());
+
+        format!(
+            "🤝 Group buy: {}\n{}\n💵 Price: {}\n💰 {} SOL each, from your own wallet\n👥 {}/{} in · closes {}\n\nTap I'm in to join. You confirm and execute in your DM with me.",
+            self.symbol,
+            self.risk_badge,
+            price,
+            self.amount_each,
+            self.participants.len(),
+            self.min_participants,
+            self.expires_at.format("%H:%M UTC"),
+        )
+    }
+}
+
+/// Result of tapping "I'm in"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinOutcome {
+    Joined { count: usize },
+    /// This join completed the quorum; the caller should execute
+    QuorumReached { count: usize },
+    AlreadyIn,
+}
+
+/// A participant's own guards, checked right before their buy
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuardDecision {
+    Proceed,
+    Refuse(String),
+}
+
+/// One participant's executed buy
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberFill {
+    pub amount_sol: f64,
+    pub tokens_received: f64,
+    pub price: f64,
+    pub tx_signature: String,
+}
+
+/// What happened to each participant; delivered only to that participant
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberOutcome {
+    Filled { user_id: i64, fill: MemberFill },
+    Refused { user_id: i64, reason: String },
+    Failed { user_id: i64, error: String },
+}
+
+impl MemberOutcome {
+    pub fn user_id(&self) -> i64 {
+        match self {
+            MemberOutcome::Filled { user_id, .. }
+            | MemberOutcome::Refused { user_id, .. }
+            | MemberOutcome::Failed { user_id, .. } => *user_id,
+        }
+    }
+}
+
+/// Checks and executes a participant's buy in their own context
+#[async_trait::async_trait]
+pub trait GroupBuyParticipant: Send + Sync {
+    async fn check_guards(&self, user_id: i64, buy: &GroupBuy) -> GuardDecision;
+    async fn execute_buy(&self, user_id: i64, buy: &GroupBuy) -> Result<MemberFill>;
+}
+
+/// Anonymized totals posted back to the group
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupBuySummary {
+    pub participants: usize,
+    pub filled: usize,
+    pub total_volume_sol: f64,
+    pub total_tokens: f64,
+    /// Token-weighted average fill price
+    pub average_fill_price: Option<f64>,
+}
+
+impl GroupBuySummary {
+    pub fn from_outcomes(outcomes: &[MemberOutcome]) -> Self {
+        let fills: Vec<&MemberFill> = outcomes.iter()
+            .filter_map(|outcome| match outcome {
+                MemberOutcome::Filled { fill, .. } => Some(fill),
+                _ => None,
+            })
+            .collect();
+
+        let total_tokens: f64 = fills.iter().map(|f| f.tokens_received).sum();
+        let average_fill_price = (total_tokens > 0.0).then(|| {
+            fills.iter().map(|f| f.price * f.tokens_received).sum::<f64>() / total_tokens
+        });
+
+        Self {
+            participants: outcomes.len(),
+            filled: fills.len(),
+            total_volume_sol: fills.iter().map(|f| f.amount_sol).sum(),
+            total_tokens,
+            average_fill_price,
+        }
+    }
+
+    /// Group message: totals only, no wallets, signatures or member names
+    pub fn group_message(&self, symbol: &str) -> String {
+        if self.filled == 0 {
+            return format!("🤝 Group buy for {} closed: no buys executed.", symbol);
+        }
+
+        let average = self.average_fill_price
+            .map(|p| format!("${}", crate::bot::chart_actions::ChartActions::format_price(p)))
+            .unwrap_or_else(|| "n/a".to_string());
+
+        format!(
+            "🤝 Group buy for {} executed\n👥 {} of {} participants filled\n💰 Total volume: {:.4} SOL\n📈 Average fill: {}",
+            symbol, self.filled, self.participants, self.total_volume_sol, average
+        )
+    }
+}
+
+/// Tracks open group buys from card to summary; finished ones are dropped
+pub struct GroupBuyCoordinator {
+    buys: RwLock<HashMap<String, GroupBuy>>,
+    window: Duration,
+}
+
+impl Default for GroupBuyCoordinator {
+    fn default() -> Self {
+        Self::new(Duration::seconds(DEFAULT_WINDOW_SECS))
+    }
+}
+
+impl GroupBuyCoordinator {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            buys: RwLock::new(HashMap::new()),
+            window,
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Open a group buy in a chat
+    pub async fn create(
+        &self,
+        chat_id: i64,
+        creator_id: i64,
+        mint: &str,
+        symbol: &str,
+        amount_each: f64,
+        min_participants: usize,
+        price: Option<f64>,
+        risk_badge: String,
+    ) -> Result<GroupBuy> {
+        if !amount_each.is_finite() || amount_each <= 0.0 {
+            return Err(BotError::validation("Amount per participant must be positive".to_string()).into());
+        }
+        if !(MIN_QUORUM..=MAX_PARTICIPANTS).contains(&min_participants) {
+            return Err(BotError::validation(format!(
+                "Minimum participants must be between {} and {}", MIN_QUORUM, MAX_PARTICIPANTS
+            )).into());
+        }
+
+        let now = Utc::now();
+        let buy = GroupBuy {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            chat_id,
+            creator_id,
+            mint: mint.to_string(),
+            symbol: symbol.to_string(),
+            amount_each,
+            min_participants,
+            participants: Vec::new(),
+            status: GroupBuyStatus::Open,
+            price,
+            risk_badge,
+            created_at: now,
+            expires_at: now + self.window,
+        };
+
+        info!("🤝 Group buy {} opened in chat {}: {} × {} SOL, quorum {}",
+            buy.id, chat_id, symbol, amount_each, min_participants);
+        self.buys.write().await.insert(buy.id.clone(), buy.clone());
+        Ok(buy)
+    }
+
+    pub async fn get(&self, id: &str) -> Option<GroupBuy> {
+        self.buys.read().await.get(id).cloned()
+    }
+
+    /// Add a member while the window is open
+    pub async fn join(&self, id: &str, user_id: i64, now: DateTime<Utc>) -> Result<JoinOutcome> {
+        let mut buys = self.buys.write().await;
+        let buy = buys.get_mut(id).ok_or_else(|| BotError::not_found("Group buy not found".to_string()))?;
+
+        if buy.status != GroupBuyStatus::Open {
+            return Err(BotError::validation("This group buy is no longer open".to_string()).into());
+        }
+        if buy.is_expired(now) {
+            return Err(BotError::validation("This group buy has expired".to_string()).into());
+        }
+        if buy.participants.contains(&user_id) {
+            return Ok(JoinOutcome::AlreadyIn);
+        }
+        if buy.participants.len() >= MAX_PARTICIPANTS {
+            return Err(BotError::validation("This group buy is full".to_string()).into());
+        }
+
+        buy.participants.push(user_id);
+        let count = buy.participants.len();
+        debug!("🤝 User {} joined group buy {} ({}/{})", user_id, id, count, buy.min_participants);
+
+        // Only the join that crosses the threshold triggers execution
+        if count == buy.min_participants {
+            Ok(JoinOutcome::QuorumReached { count })
+        } else {
+            Ok(JoinOutcome::Joined { count })
+        }
+    }
+
+    /// Remove a member before execution starts; returns the remaining count
+    pub async fn back_out(&self, id: &str, user_id: i64) -> Result<usize> {
+        let mut buys = self.buys.write().await;
+        let buy = buys.get_mut(id).ok_or_else(|| BotError::not_found("Group buy not found".to_string()))?;
+
+        if buy.status != GroupBuyStatus::Open {
+            return Err(BotError::validation("Too late to back out: execution has started".to_string()).into());
+        }
+        let before = buy.participants.len();
+        buy.participants.retain(|p| *p != user_id);
+        if buy.participants.len() == before {
+            return Err(BotError::validation("You are not in this group buy".to_string()).into());
+        }
+
+        debug!("🤝 User {} backed out of group buy {}", user_id, id);
+        Ok(buy.participants.len())
+    }
+
+    /// Run every participant's buy once quorum is reached
+    ///
+    /// Claims the buy first so a second trigger can't execute it twice. Each
+    /// member's guards are checked on their own; a refusal or failure skips
+    /// only that member.
+    pub async fn execute(
+        &self,
+        id: &str,
+        participant: &dyn GroupBuyParticipant,
+    ) -> Result<(GroupBuySummary, Vec<MemberOutcome>)> {
+        let buy = {
+            let mut buys = self.buys.write().await;
+            let buy = buys.get_mut(id).ok_or_else(|| BotError::not_found("Group buy not found".to_string()))?;
+            if buy.status != GroupBuyStatus::Open {
+                return Err(BotError::validation("Group buy is not open".to_string()).into());
+            }
+            if !buy.quorum_reached() {
+                return Err(BotError::validation("Group buy has not reached its quorum".to_string()).into());
+            }
+            buy.status = GroupBuyStatus::Executing;
+            buy.clone()
+        };
+
+        let mut outcomes = Vec::with_capacity(buy.participants.len());
+        for &user_id in &buy.participants {
+            let outcome = match participant.check_guards(user_id, &buy).await {
+                GuardDecision::Refuse(reason) => {
+                    debug!("🤝 Group buy {} skipped user {}: {}", id, user_id, reason);
+                    MemberOutcome::Refused { user_id, reason }
+                }
+                GuardDecision::Proceed => match participant.execute_buy(user_id, &buy).await {
+                    Ok(fill) => MemberOutcome::Filled { user_id, fill },
+                    Err(e) => {
+                        warn!("🤝 Group buy {} failed for user {}: {}", id, user_id, e);
+                        MemberOutcome::Failed { user_id, error: e.to_string() }
+                    }
+                },
+            };
+            outcomes.push(outcome);
+        }
+
+        let summary = GroupBuySummary::from_outcomes(&outcomes);
+        self.buys.write().await.remove(id);
+
+        info!("🤝 Group buy {} done: {}/{} filled, {:.4} SOL",
+            id, summary.filled, summary.participants, summary.total_volume_sol);
+        Ok((summary, outcomes))
+    }
+
+    /// Close the buy if its window passed without quorum; returns it when it expired
+    pub async fn expire(&self, id: &str, now: DateTime<Utc>) -> Option<GroupBuy> {
+        let mut buys = self.buys.write().await;
+        let buy = buys.get(id)?;
+        if buy.status != GroupBuyStatus::Open || !buy.is_expired(now) {
+            return None;
+        }
+
+        let mut expired = buys.remove(id)?;
+        expired.status = GroupBuyStatus::Expired;
+        info!("🤝 Group buy {} expired with {}/{}", id, expired.participants.len(), expired.min_participants);
+        Some(expired)
+    }
+}
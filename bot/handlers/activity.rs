@@ -0,0 +1,95 @@
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup},
+};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    bot::preferences::PreferenceStore,
+    utils::t,
+    wallet::{ActivityAction, WalletActivityAlert, WalletActivityWatcher, WalletManager},
+};
+
+/// Alerts and one-tap actions for wallet activity the bot did not send
+pub struct ActivityHandler;
+
+impl ActivityHandler {
+    /// Deliver foreign-activity alerts as they are raised
+    pub fn spawn_alert_forwarder(bot: Bot, watcher: Arc<WalletActivityWatcher>, preferences: Arc<PreferenceStore>) {
+        let mut receiver = watcher.subscribe_alerts();
+
+        tokio::spawn(async move {
+            while let Ok(alert) = receiver.recv().await {
+                let lang = preferences.language(alert.user_id, None).await;
+                if let Err(e) = bot.send_message(ChatId(alert.user_id), alert.message.clone())
+                    .reply_markup(Self::alert_keyboard(&alert, &lang))
+                    .await
+                {
+                    error!("🚨 Failed to deliver activity alert to {}: {}", alert.user_id, e);
+                }
+            }
+        });
+    }
+
+    /// Lock trading, view on explorer, or mark as expected
+    pub fn alert_keyboard(alert: &WalletActivityAlert, lang: &str) -> InlineKeyboardMarkup {
+        let signature = &alert.transaction.signature;
+        let mut rows = vec![vec![
+            InlineKeyboardButton::callback(
+                t(lang, "buttons.lock_trading"),
+                WalletActivityWatcher::action_data(&ActivityAction::LockTrading),
+            ),
+        ]];
+
+        if let Ok(url) = format!("https://solscan.io/tx/{}", signature).parse() {
+            rows.push(vec![InlineKeyboardButton::url(t(lang, "buttons.view_explorer"), url)]);
+        }
+
+        rows.push(vec![InlineKeyboardButton::callback(
+            t(lang, "buttons.that_was_me"),
+            WalletActivityWatcher::action_data(&ActivityAction::MarkExpected(signature.clone())),
+        )]);
+
+        InlineKeyboardMarkup::new(rows)
+    }
+
+    /// Handle `wact:<lock|unlock|ok:sig>` buttons
+    pub async fn handle_action_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        wallet_manager: Arc<WalletManager>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let Some(watcher) = wallet_manager.activity_watch() else { return Ok(()) };
+        let Some(action) = WalletActivityWatcher::parse_action(data) else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+
+        watcher.apply_action(user_id, &action).await;
+        info!("🚨 Activity action {:?} from user {}", action, user_id);
+
+        match action {
+            ActivityAction::LockTrading => {
+                let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                    InlineKeyboardButton::callback(
+                        t(lang, "buttons.unlock_trading"),
+                        WalletActivityWatcher::action_data(&ActivityAction::UnlockTrading),
+                    ),
+                ]]);
+                bot.send_message(msg.chat.id, t(lang, "activity.locked"))
+                    .reply_markup(keyboard)
+                    .await?;
+            }
+            ActivityAction::UnlockTrading => {
+                bot.send_message(msg.chat.id, t(lang, "activity.unlocked")).await?;
+            }
+            ActivityAction::MarkExpected(_) => {
+                bot.send_message(msg.chat.id, t(lang, "activity.marked_expected")).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
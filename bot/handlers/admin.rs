@@ -0,0 +1,160 @@
+use teloxide::{prelude::*, types::Message};
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use crate::{
+    bot::{admin::{AdminStats, BROADCAST_MESSAGES_PER_SEC}, BotServices},
+    utils::{fmt_datetime, t, t_args, Config, DateStyle},
+};
+use super::migration::MigrationHandler;
+
+/// /admin - operator tools
+pub struct AdminHandler;
+
+impl AdminHandler {
+    /// Handle /admin <action> [args], for users listed in ADMIN_USERS only
+    pub async fn handle_admin(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        config: Arc<Config>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        if !config.is_admin(&user_id) {
+            bot.send_message(msg.chat.id, t(&lang, "errors.admin_only")).await?;
+            return Ok(());
+        }
+
+        let args = args.trim();
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let rest = rest.trim();
+
+        match action {
+            "broadcast" => Self::handle_broadcast(bot, msg, rest, services, user_id, &lang).await,
+            "maintenance" => Self::handle_maintenance(&bot, &msg, rest, &services, &user_id, &lang).await,
+            "stats" => Self::handle_stats(&bot, &msg, &services, &lang).await,
+            "migrate_user" | "migrate_batch" => {
+                MigrationHandler::handle_migration(bot, msg, action, rest, services, user_id, &lang).await
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(&lang, "admin.usage")).await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// broadcast <text> queues an announcement; status and cancel act on the current one
+    async fn handle_broadcast(
+        bot: Bot,
+        msg: Message,
+        rest: &str,
+        services: Arc<BotServices>,
+        user_id: String,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let text = match rest {
+            "" => t(lang, "admin.usage"),
+            "status" => match services.admin.broadcast().await {
+                Some(broadcast) => broadcast.summary(),
+                None => t(lang, "admin.no_broadcast"),
+            },
+            "cancel" => match services.admin.cancel_broadcast().await {
+                Ok(Some(broadcast)) => broadcast.summary(),
+                Ok(None) => t(lang, "admin.broadcast_idle"),
+                Err(e) => t_args(lang, "admin.cancel_failed", &[("error", &e.to_string())]),
+            },
+            announcement => match services.admin.start_broadcast(&user_id, announcement).await {
+                Ok(broadcast) => {
+                    Self::spawn_broadcast(bot.clone(), services.clone());
+                    t_args(lang, "admin.broadcast_started", &[
+                        ("count", &broadcast.recipients.len().to_string()),
+                        ("rate", &BROADCAST_MESSAGES_PER_SEC.to_string()),
+                    ])
+                }
+                Err(e) => format!("❌ {}", e.user_message()),
+            },
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        Ok(())
+    }
+
+    /// Send the current broadcast in the background and report to whoever started it
+    pub fn spawn_broadcast(bot: Bot, services: Arc<BotServices>) {
+        tokio::spawn(async move {
+            match services.admin.run_broadcast(&bot, &services.preferences).await {
+                Ok(Some(broadcast)) => {
+                    let Ok(admin) = broadcast.started_by.parse::<i64>() else { return };
+                    if let Err(e) = bot.send_message(ChatId(admin), broadcast.summary()).await {
+                        warn!("📣 Failed to report broadcast {} to {}: {}", broadcast.id, admin, e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => error!("📣 Broadcast stopped: {}", e),
+            }
+        });
+    }
+
+    /// maintenance on [note] | off | status
+    async fn handle_maintenance(
+        bot: &Bot,
+        msg: &Message,
+        rest: &str,
+        services: &BotServices,
+        user_id: &str,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let (switch, note) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let text = match switch {
+            "on" => match services.admin.start_maintenance(user_id, Some(note.trim().to_string())).await {
+                Ok(_) => t(lang, "admin.maintenance_on"),
+                Err(e) => t_args(lang, "admin.maintenance_on_failed", &[("error", &e.to_string())]),
+            },
+            "off" => match services.admin.end_maintenance().await {
+                Ok(Some(maintenance)) => {
+                    let minutes = (chrono::Utc::now() - maintenance.started_at).num_minutes();
+                    t_args(lang, "admin.maintenance_off", &[("minutes", &minutes.to_string())])
+                }
+                Ok(None) => t(lang, "admin.maintenance_already_off"),
+                Err(e) => t_args(lang, "admin.maintenance_off_failed", &[("error", &e.to_string())]),
+            },
+            "" | "status" => match services.admin.maintenance().await {
+                Some(maintenance) => {
+                    let mut text = t_args(lang, "admin.maintenance_status", &[
+                        ("since", &fmt_datetime(lang, chrono_tz::UTC, maintenance.started_at, DateStyle::DateTime)),
+                        ("by", &maintenance.started_by),
+                    ]);
+                    if let Some(note) = maintenance.note {
+                        text.push('\n');
+                        text.push_str(&t_args(lang, "admin.maintenance_note", &[("note", &note)]));
+                    }
+                    text
+                }
+                None => t(lang, "admin.maintenance_is_off"),
+            },
+            _ => t(lang, "admin.usage"),
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        Ok(())
+    }
+
+    async fn handle_stats(bot: &Bot, msg: &Message, services: &BotServices, lang: &str) -> ResponseResult<()> {
+        let text = match services.admin.stats(&services.order_manager, &services.dca_engine).await {
+            Ok(stats) => Self::stats_text(&stats, lang),
+            Err(e) => t_args(lang, "admin.stats_failed", &[("error", &e.to_string())]),
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        Ok(())
+    }
+
+    pub fn stats_text(stats: &AdminStats, lang: &str) -> String {
+        t_args(lang, "admin.stats", &[
+            ("users", &stats.users.to_string()),
+            ("orders", &stats.active_orders.to_string()),
+            ("strategies", &stats.active_dca_strategies.to_string()),
+            ("volume", &format!("{:.2}", stats.volume_24h_sol)),
+            ("maintenance", &t(lang, if stats.maintenance { "common.on" } else { "common.off" })),
+        ])
+    }
+}
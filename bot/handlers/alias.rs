@@ -0,0 +1,85 @@
+use teloxide::{prelude::*, types::Message};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::{
+    bot::aliases::{AliasStore, MAX_ALIASES_PER_USER},
+    utils::{t, t_args},
+};
+
+/// /alias - define, list and delete command shortcuts
+pub struct AliasHandler;
+
+impl AliasHandler {
+    pub async fn handle_alias(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        aliases: Arc<AliasStore>,
+        user_id: String,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+
+        let args = args.trim();
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let rest = rest.trim();
+
+        match action {
+            "" | "list" => {
+                let user_aliases = aliases.list(telegram_id).await;
+                if user_aliases.is_empty() {
+                    bot.send_message(msg.chat.id, t(lang, "alias.none")).await?;
+                    return Ok(());
+                }
+
+                let lines: Vec<String> = user_aliases.iter()
+                    .map(|(name, expansion)| format!("/{} → /{}", name, expansion))
+                    .collect();
+                bot.send_message(msg.chat.id, t_args(lang, "alias.list", &[
+                    ("count", &user_aliases.len().to_string()),
+                    ("max", &MAX_ALIASES_PER_USER.to_string()),
+                    ("aliases", &lines.join("\n")),
+                ])).await?;
+            }
+            "set" => {
+                let Some((name, expansion)) = rest.split_once(char::is_whitespace) else {
+                    bot.send_message(msg.chat.id, t(lang, "alias.set_usage")).await?;
+                    return Ok(());
+                };
+                let expansion = expansion.trim().trim_matches('"');
+
+                match aliases.set(telegram_id, name, expansion).await {
+                    Ok(()) => {
+                        info!("⌨️ User {} set alias {}", telegram_id, name);
+                        bot.send_message(msg.chat.id, t_args(lang, "alias.set", &[
+                            ("name", &name.trim_start_matches('/').to_lowercase()),
+                            ("command", expansion.trim_start_matches('/')),
+                        ])).await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                    }
+                }
+            }
+            "delete" if !rest.is_empty() => {
+                match aliases.delete(telegram_id, rest).await {
+                    Ok(()) => {
+                        bot.send_message(msg.chat.id, t_args(lang, "alias.deleted", &[("name", rest)])).await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                    }
+                }
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(lang, "alias.usage")).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use rust_decimal::prelude::ToPrimitive;
+use teloxide::{prelude::*, types::Message};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    bot::{
+        automation_auth::{
+            AutomationAction, AutomationAuthority, CommandDecision, ConvexCommand, GrantScope, DEFAULT_GRANT_DAYS,
+        },
+        convex_webhook::AutomationTarget,
+        BotServices,
+    },
+    errors::{BotError, Result},
+    utils::{fmt_datetime, lang_of, t, t_args, DateStyle},
+};
+
+/// /automations - what Convex may trigger on the user's behalf
+pub struct AutomationsHandler;
+
+impl AutomationsHandler {
+    /// Handle /automations [grant <dca|sim|trade> <max_sol> [days] | revoke <id|all>]
+    pub async fn handle_automations(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let tz = services.dca_engine.timezones().get_user_timezone(telegram_id).await;
+        let authority = &services.automation_auth;
+        let now = Utc::now();
+        let parts: Vec<&str> = args.split_whitespace().collect();
+
+        let reply = match parts.as_slice() {
+            [] | ["list"] => Self::listing(authority, telegram_id, &lang, tz).await,
+            ["grant", action, max_sol, rest @ ..] if rest.len() <= 1 => {
+                let action = AutomationAction::parse(action);
+                let max_sol = max_sol.parse::<f64>().ok();
+                let days = match rest.first() {
+                    Some(days) => days.parse::<i64>().ok().filter(|d| (1..=365).contains(d)),
+                    None => Some(DEFAULT_GRANT_DAYS),
+                };
+                match (action, max_sol, days) {
+                    (Some(action), Some(max_notional_sol), Some(days)) => {
+                        let scope = GrantScope { action, max_notional_sol, expires_at: now + Duration::days(days) };
+                        match authority.issue(telegram_id, scope, now).await {
+                            Ok((grant, _)) => t_args(&lang, "automations.granted", &[
+                                ("action", &Self::action_label(action, &lang)),
+                                ("max", &max_notional_sol.to_string()),
+                                ("until", &fmt_datetime(&lang, tz, grant.scope.expires_at, DateStyle::Date)),
+                                ("id", &grant.grant_id),
+                            ]),
+                            Err(e) => format!("❌ {}", e.user_message()),
+                        }
+                    }
+                    _ => t(&lang, "automations.grant_usage"),
+                }
+            }
+            ["revoke", "all"] => {
+                let revoked = authority.revoke_all(telegram_id, now).await;
+                t_args(&lang, "automations.revoked_all", &[("count", &revoked.to_string())])
+            }
+            ["revoke", grant_id] => match authority.revoke(telegram_id, grant_id, now).await {
+                Ok(grant) => t_args(&lang, "automations.revoked", &[
+                    ("id", &grant.grant_id),
+                    ("action", &Self::action_label(grant.scope.action, &lang)),
+                ]),
+                Err(e) => format!("❌ {}", e.user_message()),
+            },
+            _ => t(&lang, "automations.usage"),
+        };
+
+        bot.send_message(msg.chat.id, reply).await?;
+        Ok(())
+    }
+
+    async fn listing(authority: &AutomationAuthority, telegram_id: i64, lang: &str, tz: Tz) -> String {
+        let grants = authority.grants(telegram_id).await;
+        if grants.is_empty() {
+            return t(lang, "automations.none");
+        }
+
+        let now = Utc::now();
+        let mut lines = vec![t(lang, "automations.title"), String::new()];
+        for grant in &grants {
+            lines.push(t_args(lang, "automations.entry", &[
+                ("id", &grant.grant_id),
+                ("action", &Self::action_label(grant.scope.action, lang)),
+                ("max", &grant.scope.max_notional_sol.to_string()),
+                ("until", &fmt_datetime(lang, tz, grant.scope.expires_at, DateStyle::Date)),
+                ("status", &t(lang, &format!("automations.status_{}", grant.status(now)))),
+            ]));
+        }
+        let blocked = authority.audit_log(telegram_id).await.len();
+        if blocked > 0 {
+            lines.push(String::new());
+            lines.push(t_args(lang, "automations.blocked", &[("count", &blocked.to_string())]));
+        }
+        lines.push(String::new());
+        lines.push(t(lang, "automations.revoke_hint"));
+        lines.join("\n")
+    }
+
+    fn action_label(action: AutomationAction, lang: &str) -> String {
+        t(lang, match action {
+            AutomationAction::DcaRun => "automations.action_dca",
+            AutomationAction::SimulatedTrade => "automations.action_sim",
+            AutomationAction::Trade => "automations.action_trade",
+        })
+    }
+
+    /// DM users when a command made in their name is refused
+    pub fn spawn_violation_forwarder(bot: Bot, authority: Arc<AutomationAuthority>) {
+        let mut receiver = authority.subscribe_violations();
+
+        tokio::spawn(async move {
+            while let Ok(notice) = receiver.recv().await {
+                if let Err(e) = bot.send_message(ChatId(notice.user_id), notice.text).await {
+                    error!("🔐 Failed to deliver violation notice to {}: {}", notice.user_id, e);
+                }
+            }
+        });
+    }
+
+    /// Entry point for execution requests from the Convex webhook
+    ///
+    /// Nothing reaches the trading engine unless the command's token covers it.
+    /// DCA runs are sized from the stored strategy, never from the request.
+    pub async fn handle_convex_command(services: &BotServices, command: ConvexCommand) -> Result<CommandDecision> {
+        let now = Utc::now();
+        match command.action {
+            AutomationAction::DcaRun => {
+                let strategy_id = command.strategy_id.as_deref()
+                    .ok_or_else(|| BotError::validation("DCA command without a strategy".to_string()))?;
+                let strategy = services.dca_engine.get_user_strategies(command.telegram_id).await
+                    .into_iter()
+                    .find(|strategy| strategy.strategy_id == strategy_id)
+                    .ok_or_else(|| BotError::not_found(format!("Strategy {} not found", strategy_id)))?;
+                let notional = strategy.amount_per_execution.to_f64().unwrap_or(f64::NAN);
+
+                let decision = services.automation_auth.check(&command, notional, now).await;
+                if let CommandDecision::Execute { grant_id } = &decision {
+                    info!("🔐 Convex command {} runs strategy {} under grant {}", command.command_id, strategy_id, grant_id);
+                    services.dca_engine.execute_strategy(&strategy).await?;
+                }
+                Ok(decision)
+            }
+            AutomationAction::SimulatedTrade => {
+                let decision = services.automation_auth.check(&command, command.notional_sol, now).await;
+                if let CommandDecision::Execute { grant_id } = &decision {
+                    info!("🔐 Convex command {} records a simulated trade under grant {}", command.command_id, grant_id);
+                }
+                Ok(decision)
+            }
+            AutomationAction::Trade => Err(BotError::validation(
+                "Trades arrive as execute_trade webhook events".to_string()
+            ).into()),
+        }
+    }
+}
+
+/// `run_dca` and `simulated_trade` webhook events land here
+#[async_trait::async_trait]
+impl AutomationTarget for BotServices {
+    async fn run_command(&self, command: ConvexCommand) -> Result<CommandDecision> {
+        AutomationsHandler::handle_convex_command(self, command).await
+    }
+}
@@ -3,12 +3,15 @@ use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, InputFile};
 use std::sync::Arc;
 use tracing::{info, error};
 
+use crate::analytics::sparkline;
 use crate::blinks::{
-    BlinkGenerator, BlinkExecutor, BlinkSharing,
+    BlinkGenerator, BlinkExecutor, BlinkSharing, BlinkFunnel,
     SolanaBlink, BlinkType, SolanaNetwork, SharePlatform,
 };
+use crate::bot::BotServices;
 use crate::errors::Result;
 use crate::trading::TradingEngineHandle;
+use crate::utils::{t, t_args};
 use crate::wallet::WalletManager;
 
 /// Handles Solana Blinks commands
@@ -22,6 +25,7 @@ impl BlinksHandler {
         args: String,
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
         let parts: Vec<&str> = args.split_whitespace().collect();
         
@@ -29,29 +33,20 @@ impl BlinksHandler {
             // Show blinks menu
             let keyboard = InlineKeyboardMarkup::new(vec![
                 vec![
-                    InlineKeyboardButton::callback("💱 Create Swap", "blink_swap"),
-                    InlineKeyboardButton::callback("💸 Create Transfer", "blink_transfer"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.blink_swap"), "blink_swap"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.blink_transfer"), "blink_transfer"),
                 ],
                 vec![
-                    InlineKeyboardButton::callback("🎨 Create NFT Mint", "blink_nft"),
-                    InlineKeyboardButton::callback("💰 Create Payment", "blink_payment"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.blink_nft"), "blink_nft"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.blink_payment"), "blink_payment"),
                 ],
                 vec![
-                    InlineKeyboardButton::callback("📊 My Blinks", "blink_list"),
-                    InlineKeyboardButton::callback("📈 Analytics", "blink_analytics"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.my_blinks"), "blink_list"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.analytics"), "blink_analytics"),
                 ],
             ]);
             
-            bot.send_message(msg.chat.id,
-                "🔗 **Solana Blinks**\n\n\
-                Create shareable, one-click Solana actions!\n\n\
-                **Available Actions:**\n\
-                • `/blink swap <from> <to> <amount>` - Create swap link\n\
-                • `/blink transfer <token> <recipient> <amount>` - Create transfer link\n\
-                • `/blink nft <collection> <price>` - Create NFT mint link\n\
-                • `/blink payment <amount> <token>` - Create payment request\n\
-                • `/blink execute <blink_url>` - Execute a blink\n\n\
-                Select an action below:")
+            bot.send_message(msg.chat.id, t(lang, "blink.menu"))
                 .reply_markup(keyboard)
                 .await?;
             
@@ -65,9 +60,7 @@ impl BlinksHandler {
         match parts[0] {
             "swap" => {
                 if parts.len() < 4 {
-                    bot.send_message(msg.chat.id,
-                        "❌ Usage: `/blink swap <from_token> <to_token> <amount>`\n\
-                        Example: `/blink swap SOL USDC 10`")
+                    bot.send_message(msg.chat.id, t(lang, "blink.usage_swap"))
                         .await?;
                     return Ok(());
                 }
@@ -79,13 +72,12 @@ impl BlinksHandler {
                     parts[2],
                     parts[3].parse().unwrap_or(0.0),
                     user_wallet,
+                    lang,
                 ).await?;
             }
             "transfer" => {
                 if parts.len() < 4 {
-                    bot.send_message(msg.chat.id,
-                        "❌ Usage: `/blink transfer <token> <recipient> <amount>`\n\
-                        Example: `/blink transfer USDC 7xKXtg... 100`")
+                    bot.send_message(msg.chat.id, t(lang, "blink.usage_transfer"))
                         .await?;
                     return Ok(());
                 }
@@ -97,13 +89,12 @@ impl BlinksHandler {
                     parts[2],
                     parts[3].parse().unwrap_or(0.0),
                     user_wallet,
+                    lang,
                 ).await?;
             }
             "nft" => {
                 if parts.len() < 3 {
-                    bot.send_message(msg.chat.id,
-                        "❌ Usage: `/blink nft <collection_address> <price>`\n\
-                        Example: `/blink nft ABC123... 0.5`")
+                    bot.send_message(msg.chat.id, t(lang, "blink.usage_nft"))
                         .await?;
                     return Ok(());
                 }
@@ -114,13 +105,12 @@ impl BlinksHandler {
                     parts[1],
                     parts[2].parse().unwrap_or(0.0),
                     user_wallet,
+                    lang,
                 ).await?;
             }
             "payment" => {
                 if parts.len() < 3 {
-                    bot.send_message(msg.chat.id,
-                        "❌ Usage: `/blink payment <amount> <token>`\n\
-                        Example: `/blink payment 50 USDC`")
+                    bot.send_message(msg.chat.id, t(lang, "blink.usage_payment"))
                         .await?;
                     return Ok(());
                 }
@@ -131,12 +121,12 @@ impl BlinksHandler {
                     parts[1].parse().unwrap_or(0.0),
                     parts[2],
                     user_wallet,
+                    lang,
                 ).await?;
             }
             "execute" => {
                 if parts.len() < 2 {
-                    bot.send_message(msg.chat.id,
-                        "❌ Usage: `/blink execute <blink_url>`")
+                    bot.send_message(msg.chat.id, t(lang, "blink.usage_execute"))
                         .await?;
                     return Ok(());
                 }
@@ -148,12 +138,11 @@ impl BlinksHandler {
                     trading_engine,
                     wallet_manager,
                     user_wallet,
+                    lang,
                 ).await?;
             }
             _ => {
-                bot.send_message(msg.chat.id,
-                    "❌ Unknown blink command. Use `/blink` to see available options.")
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "blink.unknown_command")).await?;
             }
         }
         
@@ -168,6 +157,7 @@ impl BlinksHandler {
         to_token: &str,
         amount: f64,
         user_wallet: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         let generator = BlinkGenerator::new(
             "https://solana-bot.example.com".to_string(),
@@ -198,25 +188,21 @@ impl BlinksHandler {
         // Create share buttons
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::url("🐦 Share on Twitter", twitter_url),
-                InlineKeyboardButton::url("📱 Share on Telegram", telegram_url),
+                InlineKeyboardButton::url(t(lang, "buttons.share_twitter"), twitter_url),
+                InlineKeyboardButton::url(t(lang, "buttons.share_telegram"), telegram_url),
             ],
             vec![
-                InlineKeyboardButton::callback("📋 Copy Link", format!("copy_{}", blink.blink_id)),
-                InlineKeyboardButton::callback("🔗 QR Code", format!("qr_{}", blink.blink_id)),
+                InlineKeyboardButton::callback(t(lang, "buttons.copy_link"), format!("copy_{}", blink.blink_id)),
+                InlineKeyboardButton::callback(t(lang, "buttons.qr_code"), format!("qr_{}", blink.blink_id)),
             ],
         ]);
         
-        let message = format!(
-            "✅ **Swap Blink Created!**\n\n\
-            💱 Swap {} {} for {}\n\
-            📊 Slippage: 1%\n\
-            ⏰ Expires in: 24 hours\n\
-            🔒 Security: Verified ✅\n\n\
-            🔗 **Direct Link:**\n`{}`\n\n\
-            Share this link to allow anyone to execute the swap with one click!",
-            amount, from_token, to_token, direct_url
-        );
+        let message = t_args(lang, "blink.swap_created", &[
+            ("amount", &amount.to_string()),
+            ("from", from_token),
+            ("to", to_token),
+            ("url", &direct_url),
+        ]);
         
         bot.send_message(msg.chat.id, message)
             .reply_markup(keyboard)
@@ -233,6 +219,7 @@ impl BlinksHandler {
         recipient: &str,
         amount: f64,
         user_wallet: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         let generator = BlinkGenerator::new(
             "https://solana-bot.example.com".to_string(),
@@ -261,21 +248,18 @@ impl BlinksHandler {
         
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::url("📱 Share", 
+                InlineKeyboardButton::url(t(lang, "buttons.share"), 
                     sharing.generate_share_url(&blink, SharePlatform::Telegram, None)),
-                InlineKeyboardButton::callback("📋 Copy", format!("copy_{}", blink.blink_id)),
+                InlineKeyboardButton::callback(t(lang, "buttons.copy"), format!("copy_{}", blink.blink_id)),
             ],
         ]);
         
-        let message = format!(
-            "✅ **Transfer Blink Created!**\n\n\
-            💸 Send {} {} to {}\n\
-            ⏰ Expires in: 1 hour\n\
-            🔒 Single use only\n\n\
-            🔗 **Link:** `{}`\n\n\
-            Share this link to receive the payment!",
-            amount, token, &recipient[..8], direct_url
-        );
+        let message = t_args(lang, "blink.transfer_created", &[
+            ("amount", &amount.to_string()),
+            ("token", token),
+            ("recipient", &recipient[..8]),
+            ("url", &direct_url),
+        ]);
         
         bot.send_message(msg.chat.id, message)
             .reply_markup(keyboard)
@@ -291,6 +275,7 @@ impl BlinksHandler {
         collection: &str,
         price: f64,
         user_wallet: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         let generator = BlinkGenerator::new(
             "https://solana-bot.example.com".to_string(),
@@ -315,21 +300,12 @@ impl BlinksHandler {
         
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::url("🐦 Share on Twitter", twitter_url),
-                InlineKeyboardButton::callback("📋 Copy Link", format!("copy_{}", blink.blink_id)),
+                InlineKeyboardButton::url(t(lang, "buttons.share_twitter"), twitter_url),
+                InlineKeyboardButton::callback(t(lang, "buttons.copy_link"), format!("copy_{}", blink.blink_id)),
             ],
         ]);
         
-        let message = format!(
-            "✅ **NFT Mint Blink Created!**\n\n\
-            🎨 Collection: Demo NFT\n\
-            💰 Price: {} SOL\n\
-            📦 Supply: 1000\n\
-            ⚠️ Risk: Medium (Unverified)\n\n\
-            🔗 **Mint Link:** `{}`\n\n\
-            Share this link to allow minting!",
-            price, direct_url
-        );
+        let message = t_args(lang, "blink.nft_created", &[("price", &price.to_string()), ("url", &direct_url)]);
         
         bot.send_message(msg.chat.id, message)
             .reply_markup(keyboard)
@@ -345,6 +321,7 @@ impl BlinksHandler {
         amount: f64,
         token: &str,
         user_wallet: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         let generator = BlinkGenerator::new(
             "https://solana-bot.example.com".to_string(),
@@ -370,25 +347,20 @@ impl BlinksHandler {
         
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::url("💬 Share via WhatsApp", whatsapp_url),
-                InlineKeyboardButton::callback("📋 Copy", format!("copy_{}", blink.blink_id)),
+                InlineKeyboardButton::url(t(lang, "buttons.share_whatsapp"), whatsapp_url),
+                InlineKeyboardButton::callback(t(lang, "buttons.copy"), format!("copy_{}", blink.blink_id)),
             ],
             vec![
-                InlineKeyboardButton::callback("🔗 QR Code", format!("qr_{}", blink.blink_id)),
-                InlineKeyboardButton::callback("📊 Track", format!("track_{}", blink.blink_id)),
+                InlineKeyboardButton::callback(t(lang, "buttons.qr_code"), format!("qr_{}", blink.blink_id)),
+                InlineKeyboardButton::callback(t(lang, "buttons.track"), format!("track_{}", blink.blink_id)),
             ],
         ]);
         
-        let message = format!(
-            "✅ **Payment Request Created!**\n\n\
-            💰 Request: {} {}\n\
-            📬 Recipient: You\n\
-            ⏰ Valid for: 24 hours\n\
-            🔒 Single use\n\n\
-            🔗 **Payment Link:**\n`{}`\n\n\
-            Share this link to receive payment!",
-            amount, token, direct_url
-        );
+        let message = t_args(lang, "blink.payment_created", &[
+            ("amount", &amount.to_string()),
+            ("token", token),
+            ("url", &direct_url),
+        ]);
         
         bot.send_message(msg.chat.id, message)
             .reply_markup(keyboard)
@@ -405,8 +377,9 @@ impl BlinksHandler {
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
         user_wallet: String,
+        lang: &str,
     ) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, "🔄 Processing blink...")
+        bot.send_message(msg.chat.id, t(lang, "blink.processing"))
             .await?;
         
         // Parse blink from URL (simplified for demo)
@@ -434,33 +407,92 @@ impl BlinksHandler {
         match executor.execute_blink(&demo_blink, &user_wallet).await {
             Ok(result) => {
                 if result.success {
-                    let message = format!(
-                        "✅ **Blink Executed Successfully!**\n\n\
-                        🔗 Transaction: `{}`\n\
-                        ⏱️ Execution time: {}ms\n\
-                        ⛽ Gas used: {} units\n\n\
-                        View on Solscan: https://solscan.io/tx/{}",
-                        result.transaction_signature.as_ref().unwrap_or(&"N/A".to_string()),
-                        result.execution_time_ms,
-                        result.gas_used.unwrap_or(0),
-                        result.transaction_signature.as_ref().unwrap_or(&"".to_string())
-                    );
+                    let signature = result.transaction_signature.clone().unwrap_or_default();
+                    let message = t_args(lang, "blink.executed", &[
+                        ("signature", &result.transaction_signature.clone().unwrap_or_else(|| t(lang, "common.not_available"))),
+                        ("ms", &result.execution_time_ms.to_string()),
+                        ("gas", &result.gas_used.unwrap_or(0).to_string()),
+                        ("tx", &signature),
+                    ]);
                     
                     bot.send_message(msg.chat.id, message).await?;
                 } else {
-                    bot.send_message(msg.chat.id,
-                        format!("❌ Blink execution failed: {}",
-                            result.error.unwrap_or_else(|| "Unknown error".to_string())))
+                    let error = result.error.unwrap_or_else(|| t(lang, "errors.unknown"));
+                    bot.send_message(msg.chat.id, t_args(lang, "blink.execution_failed", &[("error", &error)]))
                         .await?;
                 }
             }
             Err(e) => {
-                bot.send_message(msg.chat.id,
-                    format!("❌ Failed to execute blink: {}", e))
+                bot.send_message(msg.chat.id, t_args(lang, "blink.execute_failed", &[("error", &e.to_string())]))
                     .await?;
             }
         }
         
         Ok(())
     }
-}
\ No newline at end of file
+    
+    /// Handle /myblinks: clicks, conversion and volume for each blink you created
+    pub async fn handle_my_blinks(
+        bot: Bot,
+        msg: Message,
+        wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        let Some(generator) = services.blinks.clone() else {
+            bot.send_message(msg.chat.id, t(&lang, "blink.disabled")).await?;
+            return Ok(());
+        };
+        let wallet = match wallet_manager.get_user_wallet(&user_id).await {
+            Ok(Some(wallet)) => wallet.public_key,
+            _ => {
+                bot.send_message(msg.chat.id, t(&lang, "errors.no_wallet_found")).await?;
+                return Ok(());
+            }
+        };
+        
+        let blinks = generator.created_by(&wallet).await;
+        if blinks.is_empty() {
+            bot.send_message(msg.chat.id, t(&lang, "blink.none_created")).await?;
+            return Ok(());
+        }
+        
+        let now = chrono::Utc::now();
+        let mut text = format!("{}\n", t(&lang, "blink.your_blinks"));
+        for blink in blinks.iter().take(10) {
+            let funnel = services.blink_tracker.funnel(&blink.blink_id).await;
+            let daily = services.blink_tracker.daily_clicks(&blink.blink_id, 7, now).await;
+            let referrers = services.blink_tracker.funnel_by_referrer(&blink.blink_id).await;
+            text.push_str(&Self::format_blink_stats(&blink.title, &funnel, &daily, &lang));
+            let top: Vec<String> = referrers.iter()
+                .filter(|(_, funnel)| funnel.clicks > 0)
+                .map(|(referrer, funnel)| format!("{} {}", referrer, funnel.clicks))
+                .collect();
+            if !top.is_empty() {
+                text.push_str(&format!("   {}\n", t_args(&lang, "blink.via", &[("referrers", &top.join(", "))])));
+            }
+        }
+        if blinks.len() > 10 {
+            text.push('\n');
+            text.push_str(&t_args(&lang, "blink.older", &[("count", &(blinks.len() - 10).to_string())]));
+        }
+        
+        bot.send_message(msg.chat.id, text).await?;
+        Ok(())
+    }
+    
+    /// One /myblinks entry; `daily_clicks` is oldest first
+    pub fn format_blink_stats(title: &str, funnel: &BlinkFunnel, daily_clicks: &[f64], lang: &str) -> String {
+        format!(
+            "\n{}\n   {}\n   {}\n",
+            title,
+            t_args(lang, "blink.stats_line", &[
+                ("clicks", &funnel.clicks.to_string()),
+                ("rate", &format!("{:.1}", funnel.conversion_rate())),
+                ("sol", &format!("{:.3}", funnel.volume_sol)),
+            ]),
+            t_args(lang, "blink.stats_week", &[("sparkline", &sparkline(daily_clicks))]),
+        )
+    }
+}
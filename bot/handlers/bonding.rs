@@ -0,0 +1,23 @@
+use teloxide::prelude::*;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::alerts::BondingTracker;
+
+/// Delivers pump.fun bonding-curve alerts
+pub struct BondingHandler;
+
+impl BondingHandler {
+    /// Forward milestone, stall, reversal and migration alerts to holders and watchers
+    pub fn spawn_notification_forwarder(bot: Bot, bonding: Arc<BondingTracker>) {
+        let mut receiver = bonding.subscribe_notifications();
+
+        tokio::spawn(async move {
+            while let Ok(notification) = receiver.recv().await {
+                if let Err(e) = bot.send_message(ChatId(notification.user_id), notification.message).await {
+                    error!("🎢 Failed to deliver bonding alert to {}: {}", notification.user_id, e);
+                }
+            }
+        });
+    }
+}
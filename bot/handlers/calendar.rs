@@ -0,0 +1,141 @@
+use teloxide::{prelude::*, types::Message};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use std::sync::Arc;
+use tracing::{info, error};
+
+use crate::{
+    alerts::{TokenCalendar, CalendarEventKind},
+    wallet::WalletManager,
+    trading::TradingEngineHandle,
+    utils::{t, t_args, Config},
+};
+
+/// Token launch calendar command handler
+pub struct CalendarHandler;
+
+impl CalendarHandler {
+    /// Handle /calendar command - upcoming events for held tokens
+    pub async fn handle_calendar(
+        bot: Bot,
+        msg: Message,
+        calendar: Arc<TokenCalendar>,
+        trading_engine: TradingEngineHandle,
+        wallet_manager: Arc<WalletManager>,
+        user_id: String,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+
+        calendar.set_user_lang(telegram_id, lang).await;
+
+        // Refresh holdings so reminders follow what the user actually holds
+        if let Ok(Some(wallet)) = wallet_manager.get_user_wallet(&user_id).await {
+            if let Ok(positions) = trading_engine.get_positions(wallet.public_key).await {
+                let mints: Vec<String> = positions.iter().map(|p| p.mint.clone()).collect();
+                calendar.set_holdings(telegram_id, &mints).await;
+            }
+        }
+
+        match calendar.digest_section(telegram_id, Utc::now()).await {
+            Some(section) => {
+                bot.send_message(msg.chat.id, section).await?;
+            }
+            None => {
+                bot.send_message(msg.chat.id, t(lang, "calendar.empty")).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle /addevent command (admin only)
+    ///
+    /// Usage: `/addevent <mint> <symbol> <YYYY-MM-DD HH:MM> <unlock:PCT|migration|launch|other> [description]`
+    pub async fn handle_add_event(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        calendar: Arc<TokenCalendar>,
+        config: Arc<Config>,
+        user_id: String,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        if !config.is_admin(&user_id) {
+            bot.send_message(msg.chat.id, t(lang, "errors.admin_only")).await?;
+            return Ok(());
+        }
+
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        if parts.len() < 5 {
+            bot.send_message(msg.chat.id, t(lang, "calendar.add_usage")).await?;
+            return Ok(());
+        }
+
+        let scheduled_at = match NaiveDateTime::parse_from_str(&format!("{} {}", parts[2], parts[3]), "%Y-%m-%d %H:%M") {
+            Ok(naive) => Utc.from_utc_datetime(&naive),
+            Err(_) => {
+                bot.send_message(msg.chat.id, t(lang, "calendar.invalid_time")).await?;
+                return Ok(());
+            }
+        };
+
+        let kind = match Self::parse_kind(parts[4]) {
+            Some(kind) => kind,
+            None => {
+                bot.send_message(msg.chat.id, t(lang, "calendar.unknown_kind")).await?;
+                return Ok(());
+            }
+        };
+
+        let description = if parts.len() > 5 { parts[5..].join(" ") } else { parts[4].to_string() };
+
+        match calendar.add_manual_event(parts[0], parts[1], kind, scheduled_at, &description, &user_id).await {
+            Ok(event) => {
+                info!("📅 Admin {} added calendar event {}", user_id, event.event_id);
+                bot.send_message(msg.chat.id, t_args(lang, "calendar.added", &[
+                    ("symbol", &event.symbol),
+                    ("summary", &event.summary_for(lang, Utc::now())),
+                    ("id", &event.event_id),
+                ])).await?;
+            }
+            Err(e) => {
+                error!("Failed to add calendar event: {}", e);
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_kind(raw: &str) -> Option<CalendarEventKind> {
+        let lower = raw.to_lowercase();
+        if let Some(pct) = lower.strip_prefix("unlock:") {
+            return pct.trim_end_matches('%').parse::<f64>().ok()
+                .filter(|p| *p > 0.0 && *p <= 100.0)
+                .map(|supply_percentage| CalendarEventKind::Unlock { supply_percentage });
+        }
+
+        match lower.as_str() {
+            "migration" => Some(CalendarEventKind::Migration { bonding_curve_progress: 100.0 }),
+            "launch" => Some(CalendarEventKind::Launch),
+            "other" => Some(CalendarEventKind::Other("Event".to_string())),
+            _ => None,
+        }
+    }
+
+    /// Forward calendar reminders and digests to users' chats
+    pub fn spawn_notification_forwarder(bot: Bot, calendar: Arc<TokenCalendar>) {
+        let mut receiver = calendar.subscribe_notifications();
+
+        tokio::spawn(async move {
+            while let Ok(notification) = receiver.recv().await {
+                if let Err(e) = bot.send_message(ChatId(notification.user_id), notification.message).await {
+                    error!("📅 Failed to deliver calendar notification to {}: {}", notification.user_id, e);
+                }
+            }
+        });
+    }
+}
@@ -6,12 +6,13 @@ use tracing::error;
 use crate::{
     trading::TradingEngine,
     ai::GroqAnalyzer,
+    bot::{admin::AdminDesk, callback_action::CallbackAction, BotServices},
     db::Database,
-    utils::Config,
+    utils::{markdown::escape, t, t_args, Config},
     wallet::WalletManager,
     errors::Result,
 };
-use super::{menu::*, trading::TradingHandler, wallet::WalletHandler};
+use super::{activity::ActivityHandler, chart::ChartHandler, cleanup::CleanupHandler, dca::DcaHandler, group_buy::GroupBuyHandler, journal::JournalHandler, menu::*, import::ImportHandler, language::LanguageHandler, launch::LaunchHandler, lending::LendingHandler, notices::NoticeHandler, trading::TradingHandler, trending::TrendingHandler, price_entry::PriceEntryHandler, orders::OrderHandler, quick_buy::QuickBuyHandler, send::SendHandler, settings::SettingsHandler, wallet::WalletHandler};
 
 /// Handler for callback queries from inline keyboards
 pub struct CallbackHandler;
@@ -26,113 +27,221 @@ impl CallbackHandler {
         config: Arc<Config>,
         wallet_manager: Arc<WalletManager>,
         ai_analyzer: Arc<GroqAnalyzer>,
+        services: Arc<BotServices>,
     ) -> ResponseResult<()> {
         if let Some(data) = q.data {
+            let lang = services.preferences.language(q.from.id.0 as i64, Some(&q.from)).await;
+            if CallbackAction::is_expired(&data) {
+                bot.answer_callback_query(q.id).text(t(&lang, "errors.button_expired")).await?;
+                return Ok(());
+            }
             bot.answer_callback_query(q.id).await?;
             
+            if let Some(action) = CallbackAction::parse(&data) {
+                if let Some(maintenance) = services.admin.blocking_action(&action).await {
+                    if let Some(msg) = &q.message {
+                        bot.send_message(msg.chat.id, AdminDesk::banner(&maintenance, &lang)).await?;
+                    }
+                    return Ok(());
+                }
+                return QuickBuyHandler::handle_action(&bot, &q, action, trading_engine, wallet_manager, services).await;
+            }
+            
+            if let Some(maintenance) = services.admin.blocking_callback(&data).await {
+                if let Some(msg) = &q.message {
+                    bot.send_message(msg.chat.id, AdminDesk::banner(&maintenance, &lang)).await?;
+                }
+                return Ok(());
+            }
+            
             match data.as_str() {
                 // Menu navigation
                 "main_menu" => {
-                    Self::handle_main_menu(&bot, &q).await?;
-                }
-                
-                // Quick trades
-                "quick_buy_bonk" => {
-                    TradingHandler::execute_quick_trade(&bot, &q, "BONK", 0.05, true, trading_engine, wallet_manager).await?;
-                }
-                "quick_buy_wif" => {
-                    TradingHandler::execute_quick_trade(&bot, &q, "WIF", 0.05, true, trading_engine, wallet_manager).await?;
-                }
-                "quick_buy_gecko" => {
-                    TradingHandler::execute_quick_trade(&bot, &q, "GECKO", 0.05, true, trading_engine, wallet_manager).await?;
+                    Self::handle_main_menu(&bot, &q, &lang).await?;
                 }
                 
                 // Trading menu actions
-                "trade_quick_buy" => Self::handle_trade_quick_buy(&bot, &q).await?,
-                "trade_quick_sell" => Self::handle_trade_quick_sell(&bot, &q).await?,
-                "trade_search" => Self::handle_trade_search(&bot, &q).await?,
-                "trade_market" => Self::handle_trade_market(&bot, &q).await?,
-                "trade_settings" => Self::handle_trade_settings(&bot, &q).await?,
-                "trade_chart" => Self::handle_trade_chart(&bot, &q).await?,
+                "trade_quick_buy" => Self::send_card(&bot, &q, &lang, "💰", "callbacks.quick_buy").await?,
+                "trade_quick_sell" => Self::send_card(&bot, &q, &lang, "💸", "callbacks.quick_sell").await?,
+                "trade_search" => Self::send_card(&bot, &q, &lang, "🔍", "callbacks.search").await?,
+                "trade_market" => Self::send_card(&bot, &q, &lang, "📊", "callbacks.market").await?,
+                "trade_settings" => {
+                    SettingsHandler::handle_callback(&bot, &q, "settings_trading", services).await?;
+                }
+                "trade_chart" => Self::handle_trade_chart(&bot, &q, &lang).await?,
                 
                 // Wallet actions
                 "wallet_balance" => {
-                    WalletHandler::handle_balance_callback(&bot, &q, trading_engine, wallet_manager).await?;
+                    WalletHandler::handle_balance_callback(&bot, &q, trading_engine, wallet_manager, &lang).await?;
                 }
                 "wallet_deposit" => {
-                    WalletHandler::handle_deposit_callback(&bot, &q, wallet_manager).await?;
+                    WalletHandler::handle_deposit_callback(&bot, &q, wallet_manager, &lang).await?;
                 }
                 "wallet_new" => {
-                    WalletHandler::handle_new_wallet_callback(&bot, &q, wallet_manager).await?;
+                    WalletHandler::handle_new_wallet_callback(&bot, &q, wallet_manager, &lang).await?;
                 }
                 "wallet_export" => {
-                    WalletHandler::handle_export_callback(&bot, &q, wallet_manager).await?;
+                    WalletHandler::handle_export_callback(&bot, &q, wallet_manager, services, &lang).await?;
                 }
                 "wallet_backup" => {
-                    WalletHandler::handle_backup_callback(&bot, &q).await?;
+                    WalletHandler::handle_backup_callback(&bot, &q, &lang).await?;
                 }
-                "wallet_import" => Self::handle_wallet_import(&bot, &q).await?,
-                "wallet_switch" => Self::handle_wallet_switch(&bot, &q).await?,
-                "wallet_remove" => Self::handle_wallet_remove(&bot, &q).await?,
+                "wallet_import" => Self::send_card(&bot, &q, &lang, "📥", "callbacks.import").await?,
+                "wallet_switch" => Self::send_card(&bot, &q, &lang, "🔄", "callbacks.switch").await?,
+                "wallet_remove" => Self::send_card(&bot, &q, &lang, "🗑️", "callbacks.remove").await?,
                 
                 // Portfolio actions
                 "portfolio_positions" => {
-                    Self::handle_portfolio_positions(&bot, &q, trading_engine, wallet_manager).await?;
+                    Self::handle_portfolio_positions(&bot, &q, trading_engine, wallet_manager, &lang).await?;
                 }
                 "portfolio_rebates" => {
-                    Self::handle_portfolio_rebates(&bot, &q, db).await?;
+                    Self::handle_portfolio_rebates(&bot, &q, db, &lang).await?;
                 }
-                "portfolio_pnl" => Self::handle_portfolio_pnl(&bot, &q).await?,
-                "portfolio_history" => Self::handle_portfolio_history(&bot, &q).await?,
-                "portfolio_performance" => Self::handle_portfolio_performance(&bot, &q).await?,
-                "portfolio_export" => Self::handle_portfolio_export(&bot, &q).await?,
-                "portfolio_summary" => Self::handle_portfolio_summary(&bot, &q).await?,
+                "portfolio_pnl" => Self::send_card(&bot, &q, &lang, "📈", "callbacks.pnl").await?,
+                "portfolio_history" => Self::send_card(&bot, &q, &lang, "📋", "callbacks.history").await?,
+                "portfolio_performance" => Self::send_card(&bot, &q, &lang, "📊", "callbacks.performance").await?,
+                "portfolio_export" => Self::send_card(&bot, &q, &lang, "📤", "callbacks.export").await?,
+                "portfolio_summary" => Self::send_card(&bot, &q, &lang, "📧", "callbacks.summary").await?,
                 "view_portfolio" => {
-                    Self::handle_view_portfolio(&bot, &q, trading_engine, wallet_manager).await?;
+                    Self::handle_view_portfolio(&bot, &q, trading_engine, wallet_manager, &lang).await?;
                 }
                 
                 // Analytics actions
                 "analyze_sol" => {
-                    Self::handle_analyze_token(&bot, &q, "SOL", ai_analyzer.clone()).await?;
+                    Self::handle_analyze_token(&bot, &q, "SOL", ai_analyzer.clone(), &lang).await?;
                 }
                 "analyze_btc" => {
-                    Self::handle_analyze_token(&bot, &q, "BTC", ai_analyzer.clone()).await?;
+                    Self::handle_analyze_token(&bot, &q, "BTC", ai_analyzer.clone(), &lang).await?;
                 }
-                "analyze_sentiment" => Self::handle_analyze_sentiment(&bot, &q).await?,
-                "analyze_trending" => Self::handle_analyze_trending(&bot, &q).await?,
-                "analyze_research" => Self::handle_analyze_research(&bot, &q).await?,
-                "analyze_quick" => Self::handle_analyze_quick(&bot, &q).await?,
+                "analyze_sentiment" => Self::send_card(&bot, &q, &lang, "📊", "callbacks.sentiment").await?,
+                "analyze_trending" => Self::send_card(&bot, &q, &lang, "🔥", "callbacks.trending").await?,
+                "analyze_research" => Self::send_card(&bot, &q, &lang, "💎", "callbacks.research").await?,
+                "analyze_quick" => Self::send_card(&bot, &q, &lang, "⚡", "callbacks.quick_analysis").await?,
                 
                 // Settings actions
-                "settings_trading" => Self::handle_settings_trading(&bot, &q).await?,
-                "settings_notifications" => Self::handle_settings_notifications(&bot, &q).await?,
-                "settings_security" => Self::handle_settings_security(&bot, &q).await?,
-                "settings_ai" => Self::handle_settings_ai(&bot, &q).await?,
-                "settings_rebates" => Self::handle_settings_rebates(&bot, &q).await?,
-                "settings_advanced" => Self::handle_settings_advanced(&bot, &q).await?,
+                "settings_menu" | "settings_trading" | "settings_notifications" | "settings_security" | "settings_ai"
+                | "settings_privacy" => {
+                    SettingsHandler::handle_callback(&bot, &q, &data, services).await?;
+                }
+                data if data.starts_with("setting:") => {
+                    SettingsHandler::handle_callback(&bot, &q, data, services).await?;
+                }
+                "settings_rebates" => Self::send_card(&bot, &q, &lang, "💎", "callbacks.rebate_config").await?,
+                "settings_advanced" => Self::send_card(&bot, &q, &lang, "⚙️", "callbacks.advanced").await?,
                 
                 // Refresh actions
                 "refresh_balance" => {
-                    Self::handle_refresh_balance(&bot, &q, trading_engine, wallet_manager).await?;
+                    Self::handle_refresh_balance(&bot, &q, trading_engine, wallet_manager, &lang).await?;
+                }
+                "portfolio_refresh" => Self::send_card(&bot, &q, &lang, "🔄", "callbacks.refreshed").await?,
+                "portfolio_sync" => {
+                    TradingHandler::handle_portfolio_sync(&bot, &q, wallet_manager, services).await?;
                 }
-                "portfolio_refresh" => Self::handle_portfolio_refresh(&bot, &q).await?,
                 
                 // Transaction signing confirmations
                 data if data.starts_with("confirm_swap:") => {
-                    Self::handle_confirm_swap(&bot, &q, data, wallet_manager).await?;
+                    Self::handle_confirm_swap(&bot, &q, data, wallet_manager, &lang).await?;
                 }
                 "cancel_swap" => {
-                    Self::handle_cancel_swap(&bot, &q).await?;
+                    Self::handle_cancel_swap(&bot, &q, &lang).await?;
                 }
                 data if data.starts_with("refresh_quote:") => {
-                    Self::handle_refresh_quote(&bot, &q, data).await?;
+                    Self::handle_refresh_quote(&bot, &q, data, &lang).await?;
                 }
                 "swap_settings" => {
-                    Self::handle_swap_settings(&bot, &q).await?;
+                    Self::handle_swap_settings(&bot, &q, &lang).await?;
+                }
+                
+                // Chart messages and their alert/stop buttons
+                data if data.starts_with("chartact:") => {
+                    ChartHandler::handle_action_callback(&bot, &q, data, services).await?;
+                }
+                data if data.starts_with("chart_") || data.starts_with("tchart_") => {
+                    ChartHandler::handle_chart_callback(&bot, &q, data, services).await?;
+                }
+                
+                // /trending refresh and detail buttons
+                "trending_refresh" | "trending_detailed" => {
+                    TrendingHandler::handle_callback(&bot, &q, data.as_str(), services).await?;
+                }
+                
+                // Clarification buttons for typed prices
+                data if data.starts_with("pentry:") => {
+                    PriceEntryHandler::handle_callback(&bot, &q, data, services).await?;
+                }
+                
+                // Journal tag buttons
+                data if data.starts_with("jtag:") => {
+                    JournalHandler::handle_tag_callback(&bot, &q, data, services).await?;
+                }
+                
+                // Group buy card buttons
+                data if data.starts_with("gbuy:") => {
+                    GroupBuyHandler::handle_callback(&bot, &q, data, services, trading_engine, wallet_manager).await?;
+                }
+                
+                // Empty token account cleanup confirmation
+                data if data.starts_with("atac:") => {
+                    CleanupHandler::handle_callback(&bot, &q, data, services, wallet_manager).await?;
+                }
+                
+                // Jupiter Lend deposit and withdrawal confirmation
+                data if data.starts_with("lend:") => {
+                    LendingHandler::handle_callback(&bot, &q, data, services, wallet_manager).await?;
+                }
+                
+                // Bulk send confirmation
+                data if data.starts_with("send:") => {
+                    SendHandler::handle_callback(&bot, &q, data, services, wallet_manager).await?;
+                }
+                
+                // Language picker from /language and the main menu
+                data if data.starts_with("lang:") => {
+                    LanguageHandler::handle_callback(&bot, &q, data, services).await?;
+                }
+                
+                // Strategy and order notification toggles
+                data if data.starts_with("verb:") => {
+                    NoticeHandler::handle_callback(&bot, &q, data, services).await?;
+                }
+                
+                // Trade history import preview
+                data if data.starts_with("imp:") => {
+                    ImportHandler::handle_callback(&bot, &q, data, services).await?;
+                }
+                
+                // Cancel and edit buttons under /orders
+                data if data.starts_with("ord:") => {
+                    OrderHandler::handle_callback(&bot, &q, data, services).await?;
+                }
+                
+                // DCA pause, resume and skip-next
+                data if data == "dca_pause" || data == "dca_resume" || data.starts_with("dca:") => {
+                    DcaHandler::handle_callback(&bot, &q, data, services).await?;
+                }
+                
+                // /panic preview buttons and the red button under /portfolio
+                data if data.starts_with("panic:") => {
+                    TradingHandler::handle_panic_callback(&bot, &q, data, trading_engine, wallet_manager, services).await?;
+                }
+                
+                // Per-order exit denomination toggle
+                data if data.starts_with("exit:") => {
+                    TradingHandler::handle_exit_callback(&bot, &q, data, services).await?;
+                }
+                
+                // /launch wizard buttons
+                data if data.starts_with("launch:") => {
+                    LaunchHandler::handle_callback(&bot, &q, data, services, wallet_manager).await?;
+                }
+                
+                // Wallet activity alert actions
+                data if data.starts_with("wact:") => {
+                    ActivityHandler::handle_action_callback(&bot, &q, data, wallet_manager, &lang).await?;
                 }
                 
                 _ => {
-                    Self::handle_unknown_callback(&bot, &q).await?;
+                    Self::handle_unknown_callback(&bot, &q, &lang).await?;
                 }
             }
         }
@@ -140,93 +249,43 @@ impl CallbackHandler {
     }
     
     /// Handle main menu callback
-    async fn handle_main_menu(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
+    async fn handle_main_menu(bot: &Bot, q: &CallbackQuery, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🎛️ *Main Menu*\\n\\nUse the buttons below for quick access:")
+            bot.send_message(msg.chat.id, MenuCreator::main_menu_text(lang))
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .reply_markup(create_main_menu())
+                .reply_markup(MenuCreator::main_menu(lang))
                 .await?;
         }
         Ok(())
     }
     
-    // Trading callbacks
-    async fn handle_trade_quick_buy(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "💰 *Quick Buy*\\n\\nUse format: /buy TOKEN AMOUNT\\nExample: /buy BONK 0\\.1")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    async fn handle_trade_quick_sell(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "💸 *Quick Sell*\\n\\nUse format: /sell TOKEN PERCENTAGE\\nExample: /sell BONK 50")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    async fn handle_trade_search(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🔍 *Token Search*\\n\\nSend me a token symbol or contract address to get information\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    async fn handle_trade_market(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📊 *Market Overview*\\n\\nTop Gainers:\\n🐕 BONK: \\+45\\.2%\\n🐶 WIF: \\+32\\.1%\\n🦎 GECKO: \\+28\\.7%\\n\\nMarket Cap: $2\\.1T\\nVolume 24h: $85B")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    async fn handle_trade_settings(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "⚙️ *Trading Settings*\\n\\n• Max trade: 0\\.1 SOL\\n• Slippage: 3%\\n• Priority fee: 50k lamports\\n• MEV rebates: ✅ Enabled\\n\\nUse /settings to modify\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    async fn handle_trade_chart(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📈 *Charts*\\n\\nView live charts at:\\n🔗 [DexScreener](https://dexscreener\\.com/solana)\\n🔗 [Birdeye](https://birdeye\\.so)\\n🔗 [Jupiter](https://jup\\.ag)")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    // Wallet callbacks (simpler ones)
-    async fn handle_wallet_import(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📥 *Import Wallet*\\n\\nTo import an existing wallet:\\n1\\. Use /import command\\n2\\. Send your private key or seed phrase\\n3\\. We'll securely import your wallet\\n\\n⚠️ Never share your keys with anyone\\!")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
+    /// `<key>.title` in bold over `<key>.body`, for the informational buttons
+    fn card(lang: &str, emoji: &str, key: &str) -> String {
+        format!(
+            "{} *{}*\n\n{}",
+            emoji,
+            escape(&t(lang, &format!("{}.title", key))),
+            escape(&t(lang, &format!("{}.body", key)))
+        )
     }
     
-    async fn handle_wallet_switch(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
+    async fn send_card(bot: &Bot, q: &CallbackQuery, lang: &str, emoji: &str, key: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🔄 *Switch Wallet*\\n\\nThis feature allows you to switch between multiple wallets\\.\\n\\nComing soon in next update\\! 🚀")
+            bot.send_message(msg.chat.id, Self::card(lang, emoji, key))
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                 .await?;
         }
         Ok(())
     }
     
-    async fn handle_wallet_remove(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
+    // Trading callbacks
+    async fn handle_trade_chart(bot: &Bot, q: &CallbackQuery, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🗑️ *Remove Wallet*\\n\\n⚠️ **WARNING**: This will permanently remove wallet from bot\\.\\n\\n**Your funds are safe** \\- only the bot connection is removed\\.\\n\\nContact support to remove wallet safely\\.")
+            let text = format!(
+                "{}\n🔗 [DexScreener](https://dexscreener\\.com/solana)\n🔗 [Birdeye](https://birdeye\\.so)\n🔗 [Jupiter](https://jup\\.ag)",
+                Self::card(lang, "📈", "callbacks.charts")
+            );
+            bot.send_message(msg.chat.id, text)
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                 .await?;
         }
@@ -234,50 +293,31 @@ impl CallbackHandler {
     }
     
     // Portfolio callbacks
-    async fn handle_portfolio_positions(bot: &Bot, q: &CallbackQuery, trading_engine: Arc<RwLock<TradingEngine>>, wallet_manager: Arc<WalletManager>) -> ResponseResult<()> {
+    async fn handle_portfolio_positions(bot: &Bot, q: &CallbackQuery, trading_engine: Arc<RwLock<TradingEngine>>, wallet_manager: Arc<WalletManager>, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             let user_id = q.from.id.0.to_string();
             // This would delegate to TradingHandler::handle_portfolio in a real implementation
-            bot.send_message(msg.chat.id, "📊 Loading your positions...")
+            bot.send_message(msg.chat.id, t(lang, "callbacks.loading_positions"))
                 .await?;
         }
         Ok(())
     }
     
-    async fn handle_portfolio_rebates(bot: &Bot, q: &CallbackQuery, db: Arc<Database>) -> ResponseResult<()> {
+    async fn handle_portfolio_rebates(bot: &Bot, q: &CallbackQuery, db: Arc<Database>, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             let user_id = q.from.id.0.to_string();
             // This would call the rebates handler
-            bot.send_message(msg.chat.id, "💎 Loading rebate statistics...")
+            bot.send_message(msg.chat.id, t(lang, "callbacks.loading_rebates"))
                 .await?;
         }
         Ok(())
     }
     
-    async fn handle_view_portfolio(bot: &Bot, q: &CallbackQuery, trading_engine: Arc<RwLock<TradingEngine>>, wallet_manager: Arc<WalletManager>) -> ResponseResult<()> {
+    async fn handle_view_portfolio(bot: &Bot, q: &CallbackQuery, trading_engine: Arc<RwLock<TradingEngine>>, wallet_manager: Arc<WalletManager>, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             let user_id = q.from.id.0.to_string();
             // This would delegate to the portfolio handler
-            bot.send_message(msg.chat.id, "📊 *Portfolio Overview*\\n\\nLoading your positions\\.\\.\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    // Add remaining handlers with simple implementations for now
-    async fn handle_portfolio_pnl(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📈 *P&L Summary*\\n\\nToday: \\+$12\\.50 \\(\\+2\\.3%\\)\\nWeek: \\+$45\\.20 \\(\\+8\\.1%\\)\\nMonth: \\+$127\\.80 \\(\\+15\\.4%\\)\\nAll time: \\+$456\\.90 \\(\\+32\\.1%\\)\\n\\n🎯 Best trade: BONK \\+45%\\n📉 Worst trade: WIF \\-3%")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    async fn handle_portfolio_history(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📋 *Trade History*\\n\\nLast 5 trades:\\n1\\. BONK sell \\+$8\\.50\\n2\\. WIF buy \\-$0\\.05\\n3\\. GECKO buy \\-$0\\.05\\n4\\. BONK buy \\-$0\\.05\\n5\\. SOL buy \\-$0\\.05\\n\\nUse /history for full list")
+            bot.send_message(msg.chat.id, Self::card(lang, "📊", "callbacks.overview"))
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                 .await?;
         }
@@ -285,243 +325,98 @@ impl CallbackHandler {
     }
     
     // Analytics callbacks
-    async fn handle_analyze_token(bot: &Bot, q: &CallbackQuery, token: &str, ai_analyzer: Arc<GroqAnalyzer>) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, format!("🤖 Analyzing {} with AI\\.\\.\\.", token))
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    async fn handle_analyze_sentiment(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📊 *Market Sentiment*\\n\\n🟢 **Bullish**: 65%\\n🔴 **Bearish**: 25%\\n🟡 **Neutral**: 10%\\n\\n_Analysis based on social sentiment and on\\-chain data_")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    // Add remaining placeholder handlers
-    async fn handle_analyze_trending(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🔥 *Trending Tokens*\\n\\n1\\. BONK 🐕 \\(\\+45%\\)\\n2\\. WIF 🐶 \\(\\+32%\\)\\n3\\. GECKO 🦎 \\(\\+28%\\)\\n4\\. POPCAT 🐱 \\(\\+19%\\)\\n5\\. MEW 😺 \\(\\+15%\\)\\n\\n_Updated every 15 minutes_")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_analyze_research(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "💎 *Token Research*\\n\\nSend me a token symbol or contract address for detailed research\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_analyze_quick(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "⚡ *Quick Analysis*\\n\\nMarket overview:\\n• SOL: $220 \\(\\+2\\.3%\\)\\n• BTC: $67,500 \\(\\+1\\.8%\\)\\n• ETH: $3,450 \\(\\+1\\.2%\\)\\n\\nVolume: High \\| Volatility: Medium")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    
-    // Settings callbacks
-    async fn handle_settings_trading(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "⚡ *Trading Settings*\\n\\nCurrent settings:\\n• Max trade: 0\\.1 SOL\\n• Slippage: 3%\\n• Priority fee: 50k lamports\\n\\nUse /settings to modify\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_settings_notifications(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🔔 *Notification Settings*\\n\\n✅ Trade confirmations\\n✅ Price alerts\\n✅ Rebate notifications\\n❌ Daily summaries\\n\\nUse inline commands to toggle\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_settings_security(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🛡️ *Security Settings*\\n\\n🔐 2FA: ❌ Disabled\\n⏰ Session timeout: 30min\\n🔒 Wallet lock: ✅ Enabled\\n📱 Device verification: ✅ Enabled\\n\\nRecommended: Enable 2FA")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_settings_ai(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🤖 *AI Settings*\\n\\nProvider: Groq ⚡\\nModel: llama3\\-70b\\nAnalysis mode: ✅ Enabled\\nSentiment tracking: ✅ Enabled\\nPrice predictions: ❌ Disabled\\n\\nAPI status: 🟢 Connected")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_settings_rebates(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "💎 *Rebate Configuration*\\n\\nMEV Rebates: ✅ Enabled\\nRebate wallet: `Configured`\\nRebate share: 50%\\nTotal earned: 0\\.1245 SOL\\n\\n🎯 Rebates paid instantly\\!")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_settings_advanced(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "⚙️ *Advanced Settings*\\n\\n🌐 RPC endpoint: Helius\\n⛽ Priority fee: Dynamic\\n🎯 MEV protection: ✅ On\\n📊 Analytics: ✅ Enabled\\n🔄 Auto\\-refresh: 30s\\n\\n⚠️ For experienced users only")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_portfolio_performance(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📊 *Performance Metrics*\\n\\nWin rate: 78%\\nAvg\\. trade size: 0\\.08 SOL\\nTotal trades: 145\\nBest month: March \\(\\+25%\\)\\nSharpe ratio: 1\\.85\\n\\n📈 Trending up\\!")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_portfolio_export(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
+    async fn handle_analyze_token(bot: &Bot, q: &CallbackQuery, token: &str, ai_analyzer: Arc<GroqAnalyzer>, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📤 *Export Report*\\n\\nGenerating comprehensive portfolio report\\.\\.\\.\\n\\n📊 Report includes:\\n• Trade history\\n• P&L breakdown\\n• Tax summary\\n• Performance metrics\\n\\n📧 Report will be sent to your DM shortly\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        Ok(())
-    }
-    async fn handle_portfolio_summary(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> { 
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "📧 *Daily Summary*\\n\\n✅ Daily summaries enabled\\n⏰ Sent at: 18:00 UTC\\n📊 Includes: P&L, trades, rebates\\n\\nUse /settings to modify schedule\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+            bot.send_message(msg.chat.id, t_args(lang, "analyze.analyzing", &[("token", token)]))
                 .await?;
         }
         Ok(())
     }
     
     // Refresh callbacks
-    async fn handle_refresh_balance(bot: &Bot, q: &CallbackQuery, trading_engine: Arc<RwLock<TradingEngine>>, wallet_manager: Arc<WalletManager>) -> ResponseResult<()> {
+    async fn handle_refresh_balance(bot: &Bot, q: &CallbackQuery, trading_engine: Arc<RwLock<TradingEngine>>, wallet_manager: Arc<WalletManager>, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             let user_id = q.from.id.0.to_string();
             // This would delegate to the balance handler
-            bot.send_message(msg.chat.id, "🔄 Refreshing balance...")
-                .await?;
-        }
-        Ok(())
-    }
-    
-    async fn handle_portfolio_refresh(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
-        if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "🔄 *Portfolio Refreshed*\\n\\nData updated successfully\\!")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+            bot.send_message(msg.chat.id, t(lang, "callbacks.refreshing_balance"))
                 .await?;
         }
         Ok(())
     }
     
     // Transaction signing callbacks
-    async fn handle_confirm_swap(bot: &Bot, q: &CallbackQuery, data: &str, wallet_manager: Arc<WalletManager>) -> ResponseResult<()> {
+    async fn handle_confirm_swap(bot: &Bot, q: &CallbackQuery, data: &str, wallet_manager: Arc<WalletManager>, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             // Parse swap confirmation data: "confirm_swap:FROM:TO:AMOUNT"
             let parts: Vec<&str> = data.split(':').collect();
             if parts.len() >= 4 {
-                let from_token = parts[1];
-                let to_token = parts[2];
+                let from_token = parts[1].to_uppercase();
+                let to_token = parts[2].to_uppercase();
                 let amount = parts[3];
                 
                 let user_id = q.from.id.0.to_string();
                 
                 bot.send_message(
                     msg.chat.id,
-                    format!(
-                        "✅ **Swap Confirmed**\n\n\
-                        Executing swap: {} {} → {}\n\n\
-                        🔒 Transaction will be signed securely\n\
-                        ⏳ This may take a few moments...",
-                        amount, from_token.to_uppercase(), to_token.to_uppercase()
-                    )
+                    t_args(lang, "callbacks.swap_confirmed", &[("amount", amount), ("from", &from_token), ("to", &to_token)]),
                 ).await?;
                 
                 // Here you would call the actual swap execution
                 // For now, just show confirmation
                 tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
                 
-                bot.send_message(
-                    msg.chat.id,
-                    "⚡ Swap execution initiated! Check /portfolio for updated balances."
-                ).await?;
+                bot.send_message(msg.chat.id, t(lang, "callbacks.swap_started")).await?;
             }
         }
         Ok(())
     }
     
-    async fn handle_cancel_swap(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
+    async fn handle_cancel_swap(bot: &Bot, q: &CallbackQuery, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "❌ **Swap Cancelled**\n\nNo transaction was executed.")
+            bot.send_message(msg.chat.id, t(lang, "callbacks.swap_cancelled"))
                 .await?;
         }
         Ok(())
     }
     
-    async fn handle_refresh_quote(bot: &Bot, q: &CallbackQuery, data: &str) -> ResponseResult<()> {
+    async fn handle_refresh_quote(bot: &Bot, q: &CallbackQuery, data: &str, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             // Parse refresh data: "refresh_quote:FROM:TO:AMOUNT"
             let parts: Vec<&str> = data.split(':').collect();
             if parts.len() >= 4 {
-                let from_token = parts[1];
-                let to_token = parts[2];
+                let from_token = parts[1].to_uppercase();
+                let to_token = parts[2].to_uppercase();
                 let amount = parts[3];
                 
                 bot.send_message(
                     msg.chat.id,
-                    format!(
-                        "🔄 **Refreshing Quote**\n\n\
-                        Getting updated quote for {} {} → {}...",
-                        amount, from_token.to_uppercase(), to_token.to_uppercase()
-                    )
+                    t_args(lang, "callbacks.quote_refreshing", &[("amount", amount), ("from", &from_token), ("to", &to_token)]),
                 ).await?;
                 
                 // Here you would call the quote refresh logic
                 // For now, just show refresh message
                 tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
                 
-                bot.send_message(
-                    msg.chat.id,
-                    "✅ Quote refreshed! Updated prices and routing displayed above."
-                ).await?;
+                bot.send_message(msg.chat.id, t(lang, "callbacks.quote_refreshed")).await?;
             }
         }
         Ok(())
     }
     
-    async fn handle_swap_settings(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
+    async fn handle_swap_settings(bot: &Bot, q: &CallbackQuery, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
-            bot.send_message(
-                msg.chat.id,
-                "⚙️ **Swap Settings**\n\n\
-                Current settings:\n\
-                • Slippage: 1.0%\n\
-                • Priority Fee: 10,000 lamports\n\
-                • MEV Protection: ✅ Enabled\n\
-                • Auto-approve limit: 0.1 SOL\n\n\
-                Use /settings to modify these values."
-            ).await?;
+            bot.send_message(msg.chat.id, t(lang, "callbacks.swap_settings")).await?;
         }
         Ok(())
     }
 
     /// Handle unknown callbacks
-    async fn handle_unknown_callback(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
+    async fn handle_unknown_callback(bot: &Bot, q: &CallbackQuery, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
-            bot.send_message(msg.chat.id, "❓ This feature is coming soon\\! Use the main menu for available options\\.")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .reply_markup(create_main_menu())
+            bot.send_message(msg.chat.id, t(lang, "errors.button_expired"))
+                .reply_markup(MenuCreator::main_menu(lang))
                 .await?;
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,343 @@
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, ForceReply, InlineKeyboardButton, InlineKeyboardMarkup, Message, MessageId},
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{info, warn, error};
+
+use crate::{
+    bot::{
+        chart_actions::{
+            ChartActionKind, ChartActionRequest, ChartActions, ChartSource, PendingChartAction, PriceCheck,
+        },
+        price_entry::{PriceEntry, PriceEntryTarget, PriceLeg},
+        BotServices,
+    },
+    trading::{TokenResolver, TradingEngineHandle},
+    utils::{
+        price_input::{self, PriceInput, PriceIntent},
+        t, t_args,
+    },
+    wallet::WalletManager,
+};
+
+use super::price_entry::PriceEntryHandler;
+
+/// Chart messages and the alert/stop price flow started from them
+pub struct ChartHandler;
+
+impl ChartHandler {
+    /// Handle /chart command
+    pub async fn handle_chart(
+        bot: Bot,
+        msg: Message,
+        token: String,
+        services: Arc<BotServices>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let token = token.trim();
+        if token.is_empty() {
+            bot.send_message(msg.chat.id, t(lang, "chart.usage")).await?;
+            return Ok(());
+        }
+
+        match TokenResolver::resolve(token) {
+            Ok(mint) => Self::send_chart(&bot, msg.chat.id, &mint, ChartSource::Chart, &services, lang).await,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle `chart_<mint>` (from /larp) and `tchart_<mint>` (from trending) buttons
+    pub async fn handle_chart_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let (mint, source) = match data.strip_prefix("tchart_") {
+            Some(mint) => (mint, ChartSource::Trending),
+            None => (data.trim_start_matches("chart_"), ChartSource::Larp),
+        };
+
+        if let Some(msg) = &q.message {
+            let lang = services.preferences.language(q.from.id.0 as i64, Some(&q.from)).await;
+            Self::send_chart(bot, msg.chat.id, mint, source, &services, &lang).await?;
+        }
+        Ok(())
+    }
+
+    /// Send a chart message with the alert/stop action buttons
+    pub async fn send_chart(
+        bot: &Bot,
+        chat_id: ChatId,
+        mint: &str,
+        source: ChartSource,
+        services: &BotServices,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let symbol = TokenResolver::get_symbol(mint);
+        let price_line = match Self::current_price(services, mint).await {
+            Some(price) => t_args(lang, "chart.price", &[("price", &ChartActions::format_price(price))]),
+            None => t(lang, "chart.price_unavailable"),
+        };
+
+        let text = t_args(lang, "chart.message", &[("symbol", &symbol), ("price_line", &price_line), ("mint", mint)]);
+
+        info!("📊 Sending {:?} chart for {}", source, mint);
+
+        bot.send_message(chat_id, text)
+            .reply_markup(Self::chart_keyboard(mint, lang))
+            .await?;
+        Ok(())
+    }
+
+    /// Keyboard attached to every chart message
+    pub fn chart_keyboard(mint: &str, lang: &str) -> InlineKeyboardMarkup {
+        InlineKeyboardMarkup::new(vec![
+            vec![
+                InlineKeyboardButton::callback(t(lang, "buttons.chart_alert"), format!("chartact:alert:{}", mint)),
+                InlineKeyboardButton::callback(t(lang, "buttons.chart_stop"), format!("chartact:stop:{}", mint)),
+            ],
+            vec![
+                InlineKeyboardButton::callback(t(lang, "buttons.refresh"), format!("chart_{}", mint)),
+            ],
+        ])
+    }
+
+    /// Handle `chartact:<alert|stop>:<mint>` - ask for the price with a force reply
+    pub async fn handle_action_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+
+        let mut parts = data.splitn(3, ':').skip(1);
+        let (kind, mint) = match (parts.next(), parts.next()) {
+            (Some("alert"), Some(mint)) => (ChartActionKind::Alert, mint.to_string()),
+            (Some("stop"), Some(mint)) => (ChartActionKind::Stop, mint.to_string()),
+            _ => return Ok(()),
+        };
+
+        let Some(current_price) = Self::current_price(&services, &mint).await else {
+            bot.send_message(msg.chat.id, t(&lang, "price_entry.price_unavailable")).await?;
+            return Ok(());
+        };
+
+        let symbol = TokenResolver::get_symbol(&mint);
+        // No pattern levels are attached to chart messages yet; round numbers only
+        let suggestions = ChartActions::suggest_prices(current_price, kind, &[]);
+        let started_at = Utc::now();
+
+        let key = match kind {
+            ChartActionKind::Alert => "chart.alert_prompt",
+            ChartActionKind::Stop => "chart.stop_prompt",
+        };
+        let suggestion_lines: Vec<String> = suggestions.iter().enumerate()
+            .map(|(i, price)| format!("#{} ${}", i + 1, ChartActions::format_price(*price)))
+            .collect();
+
+        let prompt = t_args(&lang, key, &[
+            ("symbol", &symbol),
+            ("price", &ChartActions::format_price(current_price)),
+            ("suggestions", &if suggestion_lines.is_empty() { t(&lang, "chart.no_suggestions") } else { suggestion_lines.join("  ") }),
+            ("seconds", &services.chart_actions.timeout().num_seconds().to_string()),
+        ]);
+
+        let placeholder = suggestions.last()
+            .map(|price| ChartActions::format_price(*price))
+            .unwrap_or_default();
+
+        bot.send_message(msg.chat.id, prompt)
+            .reply_markup(ForceReply::new().input_field_placeholder(Some(placeholder)).selective())
+            .await?;
+
+        services.chart_actions.begin(user_id, PendingChartAction {
+            kind,
+            mint,
+            symbol,
+            current_price,
+            chat_id: msg.chat.id.0,
+            chart_message_id: msg.id.0,
+            chart_text: msg.text().unwrap_or_default().to_string(),
+            suggestions,
+            started_at,
+        }).await;
+
+        Self::spawn_timeout(bot.clone(), services.clone(), user_id, started_at);
+        Ok(())
+    }
+
+    /// Consume a reply to a pending price prompt; returns false when no prompt is pending
+    pub async fn handle_price_reply(
+        bot: &Bot,
+        msg: &Message,
+        services: &BotServices,
+        trading_engine: &TradingEngineHandle,
+        wallet_manager: &WalletManager,
+        user_id: &str,
+    ) -> ResponseResult<bool> {
+        let Some(text) = msg.text() else { return Ok(false) };
+        let Ok(telegram_id) = user_id.parse::<i64>() else { return Ok(false) };
+        let Some(pending) = services.chart_actions.pending(telegram_id).await else { return Ok(false) };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+
+        if text.trim().eq_ignore_ascii_case("cancel") {
+            services.chart_actions.complete(telegram_id).await;
+            bot.send_message(msg.chat.id, t(lang, "price_entry.cancelled")).await?;
+            return Ok(true);
+        }
+
+        let Some((direction, parsed)) = ChartActions::parse_reply_input(text, &pending.suggestions) else {
+            bot.send_message(msg.chat.id, t(lang, "chart.unreadable_price")).await?;
+            return Ok(true);
+        };
+
+        let intent = match pending.kind {
+            ChartActionKind::Stop => PriceIntent::Below,
+            ChartActionKind::Alert => PriceEntry::alert_intent(direction),
+        };
+        let target = match price_input::normalize(parsed, pending.current_price, intent) {
+            PriceInput::Accepted(price) => price,
+            // Stops at or above market are refused outright below
+            PriceInput::TriggersImmediately { price, .. } if pending.kind == ChartActionKind::Stop => price,
+            PriceInput::Invalid(reason) => {
+                bot.send_message(msg.chat.id, t_args(lang, "chart.price_rejected", &[("reason", &reason)])).await?;
+                return Ok(true);
+            }
+            // Scale in doubt or fires at once: ask with buttons instead
+            PriceInput::Ambiguous { .. } | PriceInput::TriggersImmediately { .. } => {
+                let target = match pending.kind {
+                    ChartActionKind::Alert => PriceEntryTarget::Alert { direction },
+                    ChartActionKind::Stop => match Self::position_amount(trading_engine, wallet_manager, user_id, &pending.mint).await {
+                        Some(amount) => PriceEntryTarget::Stop { amount },
+                        None => {
+                            services.chart_actions.complete(telegram_id).await;
+                            bot.send_message(msg.chat.id, t_args(lang, "price_entry.no_position", &[("symbol", &pending.symbol)])).await?;
+                            return Ok(true);
+                        }
+                    },
+                };
+                services.chart_actions.complete(telegram_id).await;
+                let entry = PriceEntry {
+                    user_id: telegram_id,
+                    chat_id: pending.chat_id,
+                    mint: pending.mint.clone(),
+                    symbol: pending.symbol.clone(),
+                    current_price: pending.current_price,
+                    target,
+                    legs: vec![PriceLeg::new(if pending.kind == ChartActionKind::Stop { "stop" } else { "alert" }, parsed, intent)],
+                };
+                PriceEntryHandler::submit(bot, services, entry, lang).await?;
+                return Ok(true);
+            }
+        };
+
+        let warning = match ChartActions::check_price(pending.kind, direction, target, pending.current_price) {
+            PriceCheck::Rejected(reason) => {
+                bot.send_message(msg.chat.id, t_args(lang, "chart.price_rejected", &[("reason", &reason)])).await?;
+                return Ok(true);
+            }
+            PriceCheck::Warning(warning) => Some(warning),
+            PriceCheck::Ok => None,
+        };
+
+        let amount = match pending.kind {
+            ChartActionKind::Stop => match Self::position_amount(trading_engine, wallet_manager, user_id, &pending.mint).await {
+                Some(amount) => amount,
+                None => {
+                    services.chart_actions.complete(telegram_id).await;
+                    bot.send_message(msg.chat.id, t_args(lang, "price_entry.no_position", &[("symbol", &pending.symbol)])).await?;
+                    return Ok(true);
+                }
+            },
+            ChartActionKind::Alert => 0.0,
+        };
+
+        let request = ChartActionRequest {
+            kind: pending.kind,
+            user_id: telegram_id,
+            chat_id: pending.chat_id,
+            mint: pending.mint.clone(),
+            symbol: pending.symbol.clone(),
+            target_price: target,
+            direction: ChartActions::resolve_direction(direction, target, pending.current_price),
+            amount,
+        };
+
+        services.chart_actions.complete(telegram_id).await;
+
+        match ChartActions::create(&request, services.price_alerts.as_ref(), services.order_manager.as_ref()).await {
+            Ok(_) => {
+                let note = ChartActions::caption_note(&request);
+                let caption = format!("{}\n\n{}", pending.chart_text, note);
+                if let Err(e) = bot.edit_message_text(ChatId(pending.chat_id), MessageId(pending.chart_message_id), caption)
+                    .reply_markup(Self::chart_keyboard(&pending.mint, lang))
+                    .await
+                {
+                    warn!("📊 Could not annotate chart message: {}", e);
+                }
+
+                let mut reply = format!("✅ {}", note);
+                if let Some(warning) = warning {
+                    reply.push_str(&format!("\n⚠️ {}", warning));
+                }
+                bot.send_message(msg.chat.id, reply).await?;
+            }
+            Err(e) => {
+                error!("📊 Failed to create chart {:?}: {}", request.kind, e);
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Drop the prompt after the timeout and tell the user
+    fn spawn_timeout(bot: Bot, services: Arc<BotServices>, user_id: i64, started_at: chrono::DateTime<Utc>) {
+        let timeout = services.chart_actions.timeout().to_std().unwrap_or(std::time::Duration::from_secs(120));
+
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            if let Some(expired) = services.chart_actions.expire(user_id, started_at).await {
+                let lang = services.preferences.language(user_id, None).await;
+                let _ = bot.send_message(
+                    ChatId(expired.chat_id),
+                    t_args(&lang, "chart.prompt_expired", &[("symbol", &expired.symbol)]),
+                ).await;
+            }
+        });
+    }
+
+    pub(crate) async fn current_price(services: &BotServices, mint: &str) -> Option<f64> {
+        match services.price_client.get_prices(vec![mint.to_string()]).await {
+            Ok(prices) => prices.prices.get(mint).map(|p| p.usd_price).filter(|p| *p > 0.0),
+            Err(e) => {
+                warn!("📊 Price lookup for {} failed: {}", mint, e);
+                None
+            }
+        }
+    }
+
+    pub(crate) async fn position_amount(
+        trading_engine: &TradingEngineHandle,
+        wallet_manager: &WalletManager,
+        user_id: &str,
+        mint: &str,
+    ) -> Option<f64> {
+        let wallet = wallet_manager.get_user_wallet(user_id).await.ok()??;
+        let positions = trading_engine.get_positions(wallet.public_key).await.ok()?;
+        positions.into_iter()
+            .find(|p| p.mint == mint)
+            .map(|p| p.amount)
+            .filter(|amount| *amount > 0.0)
+    }
+}
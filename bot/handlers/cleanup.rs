@@ -0,0 +1,277 @@
+use chrono::Utc;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, transaction::Transaction};
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::{
+    bot::BotServices,
+    errors::{BotError, Result},
+    trading::{SigningOptions, TransactionSigner},
+    utils::i18n::{fmt_number, lang_of, t, t_args, NumberKind},
+    wallet::{CleanupExclusions, CleanupOutcome, CleanupPlan, CleanupSubmitter, SkipReason, WalletManager},
+};
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+/// Automatic cleanup runs alongside the weekly maintenance
+const AUTO_CLEANUP_INTERVAL_SECS: u64 = 7 * 24 * 60 * 60;
+/// Skipped accounts listed before the rest are summarized
+const MAX_SKIPPED_SHOWN: usize = 10;
+
+/// /cleanup - reclaim rent from empty token accounts
+pub struct CleanupHandler;
+
+/// Signs cleanup transactions through the non-custodial signer and lands them
+struct SignerSubmitter {
+    signer: TransactionSigner,
+    rpc_client: Arc<RpcClient>,
+}
+
+#[async_trait::async_trait]
+impl CleanupSubmitter for SignerSubmitter {
+    async fn submit(&self, user_id: i64, transaction: Transaction) -> Result<String> {
+        let user = user_id.to_string();
+        let request_id = self.signer
+            .create_signing_request(transaction, &user, "Close empty token accounts".to_string())
+            .await
+            .map_err(|e| BotError::trading(e.to_string()))?;
+        let signed = self.signer.process_approval(&request_id, true, &user).await
+            .map_err(|e| BotError::trading(e.to_string()))?;
+        let transaction = signed.signed_transaction
+            .ok_or_else(|| BotError::trading(signed.error.unwrap_or_else(|| "Transaction was not signed".to_string())))?;
+
+        let signature = self.rpc_client.send_and_confirm_transaction(&transaction).await
+            .map_err(|e| BotError::trading(format!("Cleanup transaction failed: {}", e)))?;
+        Ok(signature.to_string())
+    }
+}
+
+impl CleanupHandler {
+    /// Handle /cleanup [auto on|off]
+    pub async fn handle_cleanup(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let Some(owner) = Self::wallet_pubkey(&wallet_manager, telegram_id).await else {
+            bot.send_message(msg.chat.id, t(&lang, "errors.no_wallet")).await?;
+            return Ok(());
+        };
+
+        let args = args.trim().to_lowercase();
+        match args.as_str() {
+            "" => {}
+            "auto on" => {
+                services.ata_janitor.set_auto(telegram_id, Some(owner)).await;
+                let days = services.ata_janitor.config().auto_close_after.num_days();
+                bot.send_message(msg.chat.id, t_args(&lang, "cleanup.auto_on", &[("days", &days.to_string())])).await?;
+                return Ok(());
+            }
+            "auto off" => {
+                services.ata_janitor.set_auto(telegram_id, None).await;
+                bot.send_message(msg.chat.id, t(&lang, "cleanup.auto_off")).await?;
+                return Ok(());
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(&lang, "cleanup.usage")).await?;
+                return Ok(());
+            }
+        }
+
+        let plan = match Self::plan(&services, telegram_id, &owner).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                bot.send_message(msg.chat.id, t_args(&lang, "cleanup.scan_failed", &[("error", &e.to_string())])).await?;
+                return Ok(());
+            }
+        };
+
+        let mut text = Self::plan_text(&plan, services.ata_janitor.config().max_closes_per_tx, &lang);
+        if plan.is_empty() {
+            if plan.skipped.is_empty() {
+                text = t(&lang, "cleanup.nothing_to_close");
+            }
+            bot.send_message(msg.chat.id, text).await?;
+            return Ok(());
+        }
+
+        text.push_str("\n\n");
+        text.push_str(&t(&lang, "cleanup.confirm"));
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(t(&lang, "buttons.close_accounts"), "atac:go"),
+            InlineKeyboardButton::callback(t(&lang, "buttons.cancel"), "atac:cancel"),
+        ]]);
+        bot.send_message(msg.chat.id, text).reply_markup(keyboard).await?;
+
+        Ok(())
+    }
+
+    /// Confirmation buttons; the plan is rebuilt so nothing that changed since the preview is closed
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let telegram_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(telegram_id, Some(&q.from)).await;
+
+        if data == "atac:cancel" {
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "cleanup.cancelled")).await?;
+            return Ok(());
+        }
+        if data != "atac:go" {
+            return Ok(());
+        }
+
+        let Some(owner) = Self::wallet_pubkey(&wallet_manager, telegram_id).await else {
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "errors.no_wallet")).await?;
+            return Ok(());
+        };
+        bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "cleanup.closing")).await?;
+
+        let outcome = match Self::run(&services, &wallet_manager, telegram_id, &owner, false).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                error!("🧹 Cleanup failed for user {}: {}", telegram_id, e);
+                bot.edit_message_text(msg.chat.id, msg.id, t_args(&lang, "cleanup.failed", &[("error", &e.to_string())])).await?;
+                return Ok(());
+            }
+        };
+
+        bot.edit_message_text(msg.chat.id, msg.id, Self::outcome_text(&outcome, &lang)).await?;
+        Ok(())
+    }
+
+    /// Weekly pass closing long-empty accounts for users who turned on auto mode
+    pub fn spawn_weekly_auto_cleanup(bot: Bot, services: Arc<BotServices>, wallet_manager: Arc<WalletManager>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(AUTO_CLEANUP_INTERVAL_SECS));
+            // The first tick fires immediately; accounts need a week of history first
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                let users = services.ata_janitor.auto_users().await;
+                info!("🧹 Weekly auto cleanup for {} users", users.len());
+
+                for (telegram_id, owner) in users {
+                    match Self::run(&services, &wallet_manager, telegram_id, &owner, true).await {
+                        Ok(outcome) if outcome.closed > 0 || outcome.failed_batches > 0 => {
+                            let lang = services.preferences.language(telegram_id, None).await;
+                            if let Err(e) = bot.send_message(ChatId(telegram_id), format!(
+                                "{}\n\n{}",
+                                t(&lang, "cleanup.weekly_title"),
+                                Self::outcome_text(&outcome, &lang)
+                            )).await {
+                                warn!("🧹 Failed to DM cleanup result to {}: {}", telegram_id, e);
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => warn!("🧹 Auto cleanup failed for user {}: {}", telegram_id, e),
+                    }
+                }
+            }
+        });
+    }
+
+    async fn wallet_pubkey(wallet_manager: &WalletManager, telegram_id: i64) -> Option<Pubkey> {
+        let wallet = wallet_manager.get_user_wallet(&telegram_id.to_string()).await.ok()??;
+        Pubkey::from_str(&wallet.public_key).ok()
+    }
+
+    /// Scan the wallet and exclude mints the user is still trading
+    async fn plan(services: &BotServices, telegram_id: i64, owner: &Pubkey) -> Result<CleanupPlan> {
+        let accounts = services.ata_janitor.scan(owner).await?;
+        let orders = services.order_manager.get_user_orders(telegram_id).await;
+        let strategies = services.dca_engine.get_user_strategies(telegram_id).await;
+        let exclusions = CleanupExclusions::default()
+            .with_orders(&orders)
+            .with_dca(&strategies);
+
+        Ok(CleanupPlan::build(&accounts, &exclusions, services.ata_janitor.config()))
+    }
+
+    async fn run(
+        services: &BotServices,
+        wallet_manager: &Arc<WalletManager>,
+        telegram_id: i64,
+        owner: &Pubkey,
+        auto: bool,
+    ) -> Result<CleanupOutcome> {
+        let janitor = &services.ata_janitor;
+        let mut plan = Self::plan(services, telegram_id, owner).await?;
+        if auto {
+            plan = janitor.auto_plan(plan, Utc::now()).await;
+        }
+        if plan.is_empty() {
+            return Ok(CleanupOutcome::default());
+        }
+
+        let submitter = SignerSubmitter {
+            signer: TransactionSigner::new(wallet_manager.clone(), SigningOptions::default()),
+            rpc_client: janitor.rpc_client(),
+        };
+        let blockhash = janitor.latest_blockhash().await?;
+        Ok(janitor.execute(telegram_id, owner, &plan, blockhash, &submitter, &services.fee_ledger).await)
+    }
+
+    fn plan_text(plan: &CleanupPlan, max_per_tx: usize, lang: &str) -> String {
+        let mut text = t_args(lang, "cleanup.plan", &[
+            ("closable", &plan.closable.len().to_string()),
+            ("rent", &fmt_number(lang, plan.reclaimable_lamports() as f64 / LAMPORTS_PER_SOL, NumberKind::Decimal(6))),
+            ("transactions", &plan.batches(max_per_tx).len().to_string()),
+        ]);
+
+        if !plan.skipped.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&t_args(lang, "cleanup.kept_open", &[("count", &plan.skipped.len().to_string())]));
+            for (account, reason) in plan.skipped.iter().take(MAX_SKIPPED_SHOWN) {
+                text.push_str(&format!("\n• {}... - {}", &account.mint[..8.min(account.mint.len())], Self::skip_label(reason, lang)));
+            }
+            if plan.skipped.len() > MAX_SKIPPED_SHOWN {
+                text.push_str("\n• ");
+                text.push_str(&t_args(lang, "common.and_more", &[("count", &(plan.skipped.len() - MAX_SKIPPED_SHOWN).to_string())]));
+            }
+        }
+        text
+    }
+
+    fn skip_label(reason: &SkipReason, lang: &str) -> String {
+        match reason {
+            SkipReason::ActiveOrder => t(lang, "cleanup.skip_active_order"),
+            SkipReason::DcaStrategy => t(lang, "cleanup.skip_dca"),
+            SkipReason::Watchlist => t(lang, "cleanup.skip_watchlist"),
+            SkipReason::Frozen => t(lang, "cleanup.skip_frozen"),
+            SkipReason::WithheldTransferFees => t(lang, "cleanup.skip_withheld_fees"),
+            SkipReason::UnlistedExtension(ext) => t_args(lang, "cleanup.skip_extension", &[("extension", &ext.to_string())]),
+        }
+    }
+
+    fn outcome_text(outcome: &CleanupOutcome, lang: &str) -> String {
+        let mut text = t_args(lang, "cleanup.closed", &[
+            ("count", &outcome.closed.to_string()),
+            ("rent", &fmt_number(lang, outcome.reclaimed_lamports as f64 / LAMPORTS_PER_SOL, NumberKind::Decimal(6))),
+        ]);
+        if outcome.failed_batches > 0 {
+            text.push('\n');
+            text.push_str(&t_args(lang, "cleanup.failed_batches", &[("count", &outcome.failed_batches.to_string())]));
+        }
+        text
+    }
+}
@@ -1,117 +1,82 @@
 use teloxide::{prelude::*, types::Message};
-use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, InputFile};
+use teloxide::utils::markdown::{escape, escape_code};
 use std::sync::Arc;
-use tracing::{info, error};
+use tracing::{info, warn, error};
 
 use crate::{
-    trading::{TradingEngineHandle, types::Position},
-    ai::GroqAnalyzer,
-    db::Database,
+    trading::{ConfirmationTracker, CopyTradingManager, LeaderboardManager, LiquidityEstimator, PositionSync, SandwichMonitor, SmartSellTimer, TipStrategy, TokenLookup, TradeDefaults, TradingEngineHandle, types::Position},
+    api::pump_fun::{BondingCurve, BuyTokenRequest, BuyTokenResponse, PumpFunClient},
+    ai::{GroqAnalyzer, AnalysisOutcome, AnalysisSignal, AiPriority, BudgetDecision},
+    alerts::{BondingTracker, TokenCalendar},
+    analytics::{CostBasisBook, PerformanceTracker, TradeJournal},
+    utils::Config,
+    db::{Database, UserRebates},
     wallet::WalletManager,
-    errors::Result,
-    utils::{format_market_cap, format_volume},
+    errors::{BotError, Result},
+    utils::{format_market_cap, format_sol, format_token_amount, format_volume, i18n::{fmt_datetime, fmt_number, fmt_number_md, lang_of, DateStyle, NumberKind}, t, t_args, DEFAULT_LANG},
+    bot::{
+        aliases::UserAliases, callback_action::{CallbackAction, DEFAULT_QUICK_BUY_SOL, SMALL_QUICK_BUY_SOL},
+        preferences::PreferenceStore, settings_export::SettingsExport,
+        trending::{NewLaunch, RiskAlert, TrendingToken}, BotServices, WalletSetupFlow,
+    },
 };
-use super::{menu::create_main_menu, trading::TradingHandler, wallet::WalletHandler};
+use super::{menu::MenuCreator, settings::SettingsHandler, trading::TradingHandler, wallet::WalletHandler};
 
 /// Command handler for bot commands
 pub struct CommandHandler;
 
-/// Trending token data structure
-#[derive(Debug, Clone)]
-pub struct TrendingToken {
-    pub name: String,
-    pub symbol: String,
-    pub address: String,
-    pub price: f64,
-    pub price_change_24h: f64,
-    pub volume_24h: f64,
-    pub market_cap: f64,
-}
-
-/// New token launch information
-#[derive(Debug, Clone)]
-pub struct NewLaunch {
-    pub name: String,
-    pub address: String,
-    pub age: String,
-    pub liquidity_status: String,
-    pub holder_count: u32,
-}
-
-/// Risk alert for dangerous tokens
-#[derive(Debug, Clone)]
-pub struct RiskAlert {
-    pub symbol: String,
-    pub address: String,
-    pub reason: String,
-}
-
-/// Pump.fun token data
-#[derive(Debug, Clone)]
-pub struct PumpToken {
-    pub name: String,
-    pub symbol: String,
-    pub address: String,
-    pub market_cap: f64,
-    pub price_change_24h: f64,
-    pub volume_24h: f64,
-}
-
 impl CommandHandler {
     /// Handle /start command
-    pub async fn handle_start(bot: Bot, msg: Message) -> ResponseResult<()> {
-        let welcome = r#"🚀 *Solana Trading Bot MVP v0\\.2\\.0*
-
-Welcome to the ultimate Solana trading platform\\!
-
-✨ *Core Features:*
-• 🎯 Token sniping with LARP protection
-• 📊 Copy top traders automatically  
-• 🚀 Launch tokens with Pump\\.fun
-• ✨ Create Solana Blinks for social trading
-• 🤖 AI\\-powered signals & analysis
-
-💎 *Advanced Trading:*
-• MEV protection & anti\\-sandwich
-• Quick buy/sell with trending tokens
-• Stop loss & price alerts
-• Portfolio tracking & leaderboards
-
-🔧 *Quick Commands:*
-/trending \\- Hot tokens now
-/snipe \\- Snipe new launches
-/larp \\- Check token safety
-/signals \\- AI trading signals
-/launch \\- Create new tokens
-/copy \\- Follow top traders
-
-Let's dominate Solana DeFi\\! 🎯"#;
+    pub async fn handle_start(bot: Bot, msg: Message, services: Arc<BotServices>) -> ResponseResult<()> {
+        let user_id = msg.from().map(|u| u.id.0 as i64).unwrap_or_default();
+        let lang = services.preferences.language(user_id, msg.from()).await;
         
         let keyboard = InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::callback("💰 Check Balance", "refresh_balance"),
-                InlineKeyboardButton::callback("📊 Portfolio", "view_portfolio"),
+                InlineKeyboardButton::callback(t(&lang, "start.check_balance"), "refresh_balance"),
+                InlineKeyboardButton::callback(t(&lang, "start.portfolio"), "view_portfolio"),
             ],
             vec![
-                InlineKeyboardButton::callback("🐕 Quick Buy BONK", "quick_buy_bonk"),
-                InlineKeyboardButton::callback("🐶 Quick Buy WIF", "quick_buy_wif"),
+                InlineKeyboardButton::callback(
+                    format!("🐕 {}", t_args(&lang, "start.quick_buy", &[("token", "BONK")])),
+                    CallbackAction::quick_buy("BONK", SMALL_QUICK_BUY_SOL).to_data(),
+                ),
+                InlineKeyboardButton::callback(
+                    format!("🐶 {}", t_args(&lang, "start.quick_buy", &[("token", "WIF")])),
+                    CallbackAction::quick_buy("WIF", SMALL_QUICK_BUY_SOL).to_data(),
+                ),
             ],
         ]);
         
-        bot.send_message(msg.chat.id, welcome)
+        bot.send_message(msg.chat.id, Self::welcome_text(&lang))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
             .reply_markup(keyboard)
             .await?;
         
         // Also send the main menu keyboard
-        bot.send_message(msg.chat.id, "🎛️ *Main Menu*\\n\\nUse the buttons below for quick access:")
+        bot.send_message(msg.chat.id, MenuCreator::main_menu_text(&lang))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(create_main_menu())
+            .reply_markup(MenuCreator::main_menu(&lang))
             .await?;
         
         Ok(())
     }
     
+    /// MarkdownV2 /start message in `lang`
+    pub fn welcome_text(lang: &str) -> String {
+        let section = |title: &str, body: &str| format!("*{}*\n{}", escape(&t(lang, title)), escape(&t(lang, body)));
+        format!(
+            "🚀 *{}*\n\n{}\n\n{}\n\n{}\n\n{}\n\n{}",
+            escape(&t(lang, "start.title")),
+            escape(&t(lang, "start.intro")),
+            section("start.core_title", "start.core_features"),
+            section("start.advanced_title", "start.advanced_features"),
+            section("start.commands_title", "start.commands"),
+            escape(&t(lang, "start.outro")),
+        )
+    }
+    
     /// Handle /balance command
     pub async fn handle_balance(
         bot: Bot,
@@ -119,6 +84,7 @@ Let's dominate Solana DeFi\\! 🎯"#;
         trading_engine: Arc<RwLock<TradingEngine>>,
         wallet_manager: Arc<WalletManager>,
         user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         WalletHandler::show_balance(
             bot,
@@ -126,6 +92,7 @@ Let's dominate Solana DeFi\\! 🎯"#;
             &user_id,
             trading_engine,
             wallet_manager,
+            lang,
         ).await
     }
     
@@ -137,9 +104,15 @@ Let's dominate Solana DeFi\\! 🎯"#;
         trading_engine: Arc<RwLock<TradingEngine>>,
         db: Arc<Database>,
         wallet_manager: Arc<WalletManager>,
+        sandwich_monitor: Arc<SandwichMonitor>,
+        preferences: Arc<PreferenceStore>,
+        performance: Arc<PerformanceTracker>,
+        cost_basis: Arc<CostBasisBook>,
+        liquidity: Arc<LiquidityEstimator>,
+        confirmations: Arc<ConfirmationTracker>,
         user_id: String,
     ) -> ResponseResult<()> {
-        TradingHandler::handle_buy(bot, msg, args, trading_engine, db, wallet_manager, user_id).await
+        TradingHandler::handle_buy(bot, msg, args, trading_engine, db, wallet_manager, sandwich_monitor, preferences, performance, cost_basis, liquidity, confirmations, user_id).await
     }
     
     /// Handle /sell command
@@ -150,9 +123,15 @@ Let's dominate Solana DeFi\\! 🎯"#;
         trading_engine: Arc<RwLock<TradingEngine>>,
         db: Arc<Database>,
         wallet_manager: Arc<WalletManager>,
+        journal: Arc<TradeJournal>,
+        sandwich_monitor: Arc<SandwichMonitor>,
+        smart_sell: Arc<SmartSellTimer>,
+        preferences: Arc<PreferenceStore>,
+        performance: Arc<PerformanceTracker>,
+        cost_basis: Arc<CostBasisBook>,
         user_id: String,
     ) -> ResponseResult<()> {
-        TradingHandler::handle_sell(bot, msg, args, trading_engine, db, wallet_manager, user_id).await
+        TradingHandler::handle_sell(bot, msg, args, trading_engine, db, wallet_manager, journal, sandwich_monitor, smart_sell, preferences, performance, cost_basis, user_id).await
     }
     
     /// Handle /portfolio command
@@ -161,9 +140,12 @@ Let's dominate Solana DeFi\\! 🎯"#;
         msg: Message,
         trading_engine: Arc<RwLock<TradingEngine>>,
         wallet_manager: Arc<WalletManager>,
+        calendar: Arc<TokenCalendar>,
+        bonding: Arc<BondingTracker>,
+        position_sync: Arc<PositionSync>,
         user_id: String,
     ) -> ResponseResult<()> {
-        TradingHandler::handle_portfolio(bot, msg, trading_engine, wallet_manager, user_id).await
+        TradingHandler::handle_portfolio(bot, msg, trading_engine, wallet_manager, calendar, bonding, position_sync, user_id).await
     }
     
     /// Handle /analyze command
@@ -172,24 +154,43 @@ Let's dominate Solana DeFi\\! 🎯"#;
         msg: Message,
         args: String,
         ai_analyzer: Arc<GroqAnalyzer>,
+        user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         if args.trim().is_empty() {
-            bot.send_message(
-                msg.chat.id,
-                "Usage: /analyze <token>\\nExample: /analyze SOL"
-            )
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .await?;
+            bot.send_message(msg.chat.id, t(lang, "analyze.usage")).await?;
             return Ok(());
         }
         
         let token = args.trim().to_uppercase();
         
-        bot.send_message(msg.chat.id, format!("🤖 Analyzing {} with AI\\.\\.\\.", token))
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+        bot.send_message(msg.chat.id, t_args(lang, "analyze.analyzing", &[("token", &token)]))
             .await?;
         
-        match ai_analyzer.analyze_token(&token).await {
+        let (analysis, footer) = match ai_analyzer
+            .analyze_token_with_budget(&token, Some(&user_id), AiPriority::Interactive)
+            .await
+        {
+            Ok(AnalysisOutcome::Ai(analysis)) => {
+                // e.g. "llama-3.1-8b-instant, cached 4m ago"
+                let mut details = vec![analysis.model_used.clone()];
+                details.extend(analysis.cache_note());
+                let footer = t_args(lang, "analyze.powered_by", &[("details", &details.join(", "))]);
+                (Ok(analysis), footer)
+            }
+            Ok(AnalysisOutcome::Heuristic { analysis, decision }) => {
+                if let Some(notice) = decision.user_message() {
+                    bot.send_message(msg.chat.id, notice).await?;
+                }
+                if matches!(decision, BudgetDecision::UserQuotaExceeded { .. }) {
+                    return Ok(());
+                }
+                (Ok(analysis), t(lang, "analyze.heuristic"))
+            }
+            Err(e) => (Err(e), String::new()),
+        };
+        
+        match analysis {
             Ok(analysis) => {
                 let confidence_emoji = match analysis.confidence {
                     c if c >= 0.8 => "🟢",
@@ -197,35 +198,52 @@ Let's dominate Solana DeFi\\! 🎯"#;
                     _ => "🔴",
                 };
                 
-                let signal_emoji = match analysis.signal.as_str() {
-                    "BUY" => "📈",
-                    "SELL" => "📉",
-                    _ => "➡️",
+                let signal_emoji = match analysis.signal {
+                    AnalysisSignal::Buy => "📈",
+                    AnalysisSignal::Sell => "📉",
+                    AnalysisSignal::Hold => "➡️",
+                };
+                
+                let bullets = |items: &[String]| items.iter().map(|item| format!("• {}", escape(item))).collect::<Vec<_>>().join("\n");
+                let risk_flags = if analysis.risk_flags.is_empty() {
+                    String::new()
+                } else {
+                    format!("⚠️ *{}*\n{}\n\n", escape(&t(lang, "analyze.risk_flags")), bullets(&analysis.risk_flags))
                 };
                 
                 let message = format!(
-                    "🤖 *AI Analysis: {}*\\n\\n\
-                    {} *Signal:* {}\\n\
-                    {} *Confidence:* {:.0}%\\n\\n\
-                    📝 *Summary:*\\n{}\\n\\n\
-                    💡 *Key Factors:*\\n{}\\n\\n\
-                    _Analysis powered by Groq AI_",
-                    token,
+                    "🤖 *{}*\n\n\
+                    {} *{}* {}\n\
+                    {} *{}* {}%\n\n\
+                    📝 *{}*\n{}\n\n\
+                    💡 *{}*\n{}\n\n\
+                    {}_{}_",
+                    escape(&t_args(lang, "analyze.title", &[("token", &token)])),
                     signal_emoji,
+                    escape(&t(lang, "analyze.signal")),
                     analysis.signal,
                     confidence_emoji,
-                    analysis.confidence * 100.0,
-                    analysis.summary,
-                    analysis.key_factors.join("\\n• ")
+                    escape(&t(lang, "analyze.confidence")),
+                    fmt_number_md(lang, analysis.confidence * 100.0, NumberKind::Decimal(0)),
+                    escape(&t(lang, "analyze.summary")),
+                    escape(&analysis.summary),
+                    escape(&t(lang, "analyze.key_factors")),
+                    bullets(&analysis.key_factors),
+                    risk_flags,
+                    escape(&footer)
                 );
                 
                 bot.send_message(msg.chat.id, message)
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
+            Err(BotError::ServiceUnavailable { service }) => {
+                bot.send_message(msg.chat.id, TradingHandler::unavailable_message(&service, lang))
+                    .await?;
+            }
             Err(e) => {
                 error!("AI analysis failed: {}", e);
-                bot.send_message(msg.chat.id, format!("❌ Analysis failed: {}", e))
+                bot.send_message(msg.chat.id, t_args(lang, "analyze.failed", &[("error", &e.user_message())]))
                     .await?;
             }
         }
@@ -233,39 +251,75 @@ Let's dominate Solana DeFi\\! 🎯"#;
         Ok(())
     }
     
+    /// Handle /aibudget command - admin view of AI spend versus budget
+    pub async fn handle_ai_budget(
+        bot: Bot,
+        msg: Message,
+        ai_analyzer: Arc<GroqAnalyzer>,
+        config: Arc<Config>,
+        user_id: String,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        if !config.is_admin(&user_id) {
+            bot.send_message(msg.chat.id, t(lang, "errors.admin_only")).await?;
+            return Ok(());
+        }
+        
+        let Some(budget) = ai_analyzer.budget() else {
+            bot.send_message(msg.chat.id, t(lang, "ai_budget.disabled")).await?;
+            return Ok(());
+        };
+        
+        let usage = budget.usage().await;
+        let token_pct = if usage.token_budget > 0 {
+            usage.tokens_used as f64 / usage.token_budget as f64 * 100.0
+        } else {
+            0.0
+        };
+        let cost_pct = if usage.cost_budget_usd > 0.0 {
+            usage.cost_usd / usage.cost_budget_usd * 100.0
+        } else {
+            0.0
+        };
+        
+        let count = |n: u64| fmt_number(lang, n as f64, NumberKind::Decimal(0));
+        let message = t_args(lang, "ai_budget.report", &[
+            ("day", &usage.day.to_string()),
+            ("tokens", &count(usage.tokens_used)),
+            ("token_budget", &count(usage.token_budget)),
+            ("token_pct", &fmt_number(lang, token_pct, NumberKind::Decimal(1))),
+            ("spend", &fmt_number(lang, usage.cost_usd, NumberKind::Decimal(4))),
+            ("cost_budget", &fmt_number(lang, usage.cost_budget_usd, NumberKind::Decimal(2))),
+            ("cost_pct", &fmt_number(lang, cost_pct, NumberKind::Decimal(1))),
+            ("system", &count(usage.calls_by_priority.get(&AiPriority::System).copied().unwrap_or(0))),
+            ("interactive", &count(usage.calls_by_priority.get(&AiPriority::Interactive).copied().unwrap_or(0))),
+            ("denied", &count(usage.denied_calls)),
+            ("users", &count(usage.active_users as u64)),
+            ("resets", &fmt_datetime(lang, chrono_tz::UTC, usage.resets_at, DateStyle::DateTime)),
+        ]);
+        
+        bot.send_message(msg.chat.id, message).await?;
+        
+        Ok(())
+    }
+    
     /// Handle /rebates command
     pub async fn handle_rebates(
         bot: Bot,
         msg: Message,
         db: Arc<Database>,
         user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         match db.get_user_rebates(&user_id).await {
             Ok(rebates) => {
-                let message = format!(
-                    "💎 *MEV Rebates Earned*\\n\\n\
-                    Today: {:.6} SOL\\n\
-                    This Week: {:.6} SOL\\n\
-                    This Month: {:.6} SOL\\n\
-                    All Time: {:.6} SOL\\n\\n\
-                    💡 *How Rebates Work:*\\n\
-                    • 50% of MEV generated goes to you\\n\
-                    • Paid instantly in the same block\\n\
-                    • No action required \\- automatic\\!\\n\\n\
-                    _Rebates are credited directly to your wallet_",
-                    rebates.today,
-                    rebates.week,
-                    rebates.month,
-                    rebates.all_time
-                );
-                
-                bot.send_message(msg.chat.id, message)
+                bot.send_message(msg.chat.id, Self::rebates_text(lang, &rebates))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
             Err(e) => {
                 error!("Failed to get rebates: {}", e);
-                bot.send_message(msg.chat.id, "❌ Failed to fetch rebate information")
+                bot.send_message(msg.chat.id, t(lang, "rebates.failed"))
                     .await?;
             }
         }
@@ -273,96 +327,68 @@ Let's dominate Solana DeFi\\! 🎯"#;
         Ok(())
     }
     
-    /// Handle /settings command
-    pub async fn handle_settings(bot: Bot, msg: Message) -> ResponseResult<()> {
-        let settings_text = r#"⚙️ *Bot Settings*
-
-*Current Configuration:*
-• Max trade size: 0\\.1 SOL
-• Slippage tolerance: 3%
-• Priority fee: 50,000 lamports
-• MEV rebates: ✅ Enabled
-• AI analysis: ✅ Enabled
-
-*Security Settings:*
-• Wallet mode: Non\\-custodial
-• Private key storage: None \\(secure\\)
-• Session timeout: 30 minutes
-
-*Notification Settings:*
-• Trade confirmations: ✅ On
-• Price alerts: ✅ On
-• Daily summaries: ❌ Off
-
-_Use the buttons below to modify settings_"#;
-        
-        let keyboard = InlineKeyboardMarkup::new(vec![
-            vec![
-                InlineKeyboardButton::callback("⚡ Trading", "settings_trading"),
-                InlineKeyboardButton::callback("🔔 Notifications", "settings_notifications"),
-            ],
-            vec![
-                InlineKeyboardButton::callback("🛡️ Security", "settings_security"),
-                InlineKeyboardButton::callback("💎 Rebates", "settings_rebates"),
-            ],
+    /// MarkdownV2 rebate totals in `lang`, for /rebates and the 💎 Rebates button
+    pub fn rebates_text(lang: &str, rebates: &UserRebates) -> String {
+        let sol = |amount: f64| fmt_number(lang, amount, NumberKind::Decimal(6));
+        let body = t_args(lang, "rebates.body", &[
+            ("today", &sol(rebates.today)),
+            ("week", &sol(rebates.week)),
+            ("month", &sol(rebates.month)),
+            ("all_time", &sol(rebates.all_time)),
         ]);
+        format!(
+            "💎 *{}*\n\n{}\n\n💡 *{}*\n{}\n\n_{}_",
+            escape(&t(lang, "rebates.title")),
+            escape(&body),
+            escape(&t(lang, "rebates.how_title")),
+            escape(&t(lang, "rebates.how")),
+            escape(&t(lang, "rebates.credited"))
+        )
+    }
+    
+    /// Handle /settings command; `/settings export` sends the user's settings as JSON
+    pub async fn handle_settings(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        if args.trim() == "export" {
+            let export = SettingsExport::collect(&services, telegram_id).await;
+            bot.send_document(msg.chat.id, InputFile::memory(export.to_json().into_bytes()).file_name("settings.json"))
+                .caption(t(&lang, "settings.export_caption"))
+                .await?;
+            return Ok(());
+        }
+        if let Some(name) = args.trim().strip_prefix("name").filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+            return SettingsHandler::set_display_name(&bot, msg.chat.id, telegram_id, name, &services, &lang).await;
+        }
+        for (verb, deny) in [("deny", true), ("allow", false)] {
+            if let Some(mint) = args.trim().strip_prefix(verb).filter(|rest| rest.is_empty() || rest.starts_with(' ')) {
+                return SettingsHandler::edit_deny_list(&bot, msg.chat.id, telegram_id, mint, deny, &services, &lang).await;
+            }
+        }
         
-        bot.send_message(msg.chat.id, settings_text)
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(keyboard)
-            .await?;
+        SettingsHandler::show(&bot, msg.chat.id, telegram_id, &services, &lang).await?;
         
         Ok(())
     }
     
-    /// Handle /help command
-    pub async fn handle_help(bot: Bot, msg: Message) -> ResponseResult<()> {
-        let help_text = r#"📚 *Solana Trading Bot Help*
-
-*Main Features:*
-• 💰 Balance \\- Check your wallet balance
-• 📊 Portfolio \\- View positions and P&L
-• ⚡ Trade \\- Buy/sell tokens instantly
-• 💎 Rebates \\- Track MEV rebate earnings
-• 🤖 AI Analysis \\- Get market insights
-• 💼 Wallet \\- Manage your wallets
-• ⚙️ Settings \\- Configure the bot
-
-*Trading Commands:*
-/buy <token> <amount> \\- Buy tokens
-/sell <token> <percentage> \\- Sell tokens
-/balance \\- Check wallet balance
-/portfolio \\- View your positions
-
-*Analysis Commands:*
-/analyze <token> \\- Get AI analysis
-/rebates \\- View earned rebates
-
-*Wallet Commands:*
-/deposit \\- Show deposit address
-/export \\- Export private keys \\(⚠️ Careful\\!\\)
-/backup \\- Backup instructions
-
-*Bot Commands:*
-/start \\- Initialize the bot
-/settings \\- Configure settings
-/help \\- Show this help
-
-*Security Features:*
-• 🔒 Non\\-custodial \\(you control keys\\)
-• 🛡️ MEV protection enabled
-• 💎 Instant rebate payments
-• 🔐 Private keys never stored
-
-*Quick Examples:*
-• `/buy BONK 0.1` \\- Buy BONK with 0\\.1 SOL
-• `/sell WIF 50` \\- Sell 50% of your WIF
-• `/analyze SOL` \\- Get AI analysis for SOL
-
-*Support:*
-For help, contact @support or visit our documentation\\.
-
-Happy trading\\! 🚀"#;
+    /// Handle /help command, listing the user's own aliases at the end
+    pub async fn handle_help(bot: Bot, msg: Message, aliases: UserAliases, lang: &str) -> ResponseResult<()> {
+        let mut help_text = Self::help_text(lang);
+        if !aliases.is_empty() {
+            let lines: Vec<String> = aliases.iter()
+                .map(|(name, expansion)| format!("/{} → `/{}`", escape(name), escape_code(expansion)))
+                .collect();
+            help_text.push_str(&format!("\n\n*{}*\n{}", escape(&t(lang, "help.aliases_title")), lines.join("\n")));
+        }
         
         bot.send_message(msg.chat.id, help_text)
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
@@ -371,14 +397,33 @@ Happy trading\\! 🚀"#;
         Ok(())
     }
     
+    /// MarkdownV2 help in `lang`, for /help and the ❓ Help button
+    pub fn help_text(lang: &str) -> String {
+        let section = |title: &str, body: &str| format!("*{}*\n{}", escape(&t(lang, title)), escape(&t(lang, body)));
+        [
+            format!("📚 *{}*", escape(&t(lang, "help.title"))),
+            section("help.features_title", "help.features"),
+            section("help.trading_title", "help.trading"),
+            section("help.analysis_title", "help.analysis"),
+            section("help.wallet_title", "help.wallet"),
+            section("help.bot_title", "help.bot"),
+            section("help.security_title", "help.security"),
+            section("help.examples_title", "help.examples"),
+            section("help.support_title", "help.support"),
+            escape(&t(lang, "help.outro")),
+        ]
+        .join("\n\n")
+    }
+    
     /// Handle /deposit command
     pub async fn handle_deposit(
         bot: Bot,
         msg: Message,
         wallet_manager: Arc<WalletManager>,
         user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
-        WalletHandler::show_deposit_info(bot, msg.chat.id, &user_id, wallet_manager).await
+        WalletHandler::show_deposit_info(bot, msg.chat.id, &user_id, wallet_manager, lang).await
     }
     
     /// Handle /export command
@@ -386,32 +431,40 @@ Happy trading\\! 🚀"#;
         bot: Bot,
         msg: Message,
         wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
         user_id: String,
     ) -> ResponseResult<()> {
-        WalletHandler::export_wallet_keys(bot, msg.chat.id, &user_id, wallet_manager).await
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        WalletHandler::export_wallet_keys(bot, msg.chat.id, &user_id, wallet_manager, services, &lang).await
     }
     
     /// Handle /backup command
-    pub async fn handle_backup(bot: Bot, msg: Message) -> ResponseResult<()> {
-        WalletHandler::show_backup_guide(bot, msg.chat.id).await
+    pub async fn handle_backup(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        WalletHandler::show_backup_guide(bot, msg.chat.id, lang).await
     }
     
-    /// Handle /confirm command
-    pub async fn handle_confirm(bot: Bot, msg: Message) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, 
-            "✅ Action confirmed\\. Processing\\.\\.\\.")
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .await?;
+    /// Handle /confirm command; also unlocks a wallet export for a short window
+    pub async fn handle_confirm(bot: Bot, msg: Message, services: Arc<BotServices>, user_id: String) -> ResponseResult<()> {
+        let telegram_id = user_id.parse::<i64>().unwrap_or_default();
+        if telegram_id != 0 {
+            services.wallet_transfers.confirm(telegram_id).await;
+        }
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        bot.send_message(msg.chat.id, t(&lang, "common.confirmed")).await?;
         
         Ok(())
     }
     
-    /// Handle /cancel command
-    pub async fn handle_cancel(bot: Bot, msg: Message) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, 
-            "❌ Action cancelled\\.")
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .await?;
+    /// Handle /cancel command; drops pending confirmations, prompts, setup steps and launch wizards
+    pub async fn handle_cancel(bot: Bot, msg: Message, services: Arc<BotServices>, user_id: String) -> ResponseResult<()> {
+        let telegram_id = user_id.parse::<i64>().unwrap_or_default();
+        if telegram_id != 0 {
+            services.wallet_transfers.cancel(telegram_id).await;
+            services.launches.cancel(telegram_id).await;
+        }
+        WalletSetupFlow::cancel(&*services.sessions, &user_id).await;
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        bot.send_message(msg.chat.id, t(&lang, "common.action_cancelled")).await?;
         
         Ok(())
     }
@@ -428,11 +481,16 @@ Happy trading\\! 🚀"#;
         trading_engine: TradingEngineHandle,
         db: Arc<Database>,
         wallet_manager: Arc<WalletManager>,
+        liquidity: Arc<LiquidityEstimator>,
+        preferences: Arc<PreferenceStore>,
         user_id: String,
     ) -> ResponseResult<()> {
+        let lang = preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        let lang = lang.as_str();
+        
         // Validate user ID
         if let Err(e) = Validator::validate_user_id(&user_id) {
-            bot.send_message(msg.chat.id, format!("❌ Invalid user: {}", e))
+            bot.send_message(msg.chat.id, t_args(lang, "errors.invalid_user", &[("error", &e.to_string())]))
                 .await?;
             return Ok(());
         }
@@ -441,7 +499,7 @@ Happy trading\\! 🚀"#;
         let sanitized_args = match Validator::sanitize_command_args(&args) {
             Ok(s) => s,
             Err(e) => {
-                bot.send_message(msg.chat.id, format!("❌ Invalid input: {}", e))
+                bot.send_message(msg.chat.id, t_args(lang, "errors.invalid_input", &[("error", &e.to_string())]))
                     .await?;
                 return Ok(());
             }
@@ -449,10 +507,7 @@ Happy trading\\! 🚀"#;
         
         let parts: Vec<&str> = sanitized_args.split_whitespace().collect();
         if parts.is_empty() {
-            bot.send_message(msg.chat.id, 
-                "❌ Usage: `/snipe <token_address> [amount_sol]`\\n\\n\
-                Example: `/snipe ABC123...DEF 0.1`")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+            bot.send_message(msg.chat.id, t(lang, "snipe.usage"))
                 .await?;
             return Ok(());
         }
@@ -461,9 +516,7 @@ Happy trading\\! 🚀"#;
         let token_address = match Validator::validate_pubkey(parts[0]) {
             Ok(pubkey) => pubkey.to_string(),
             Err(_) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ Invalid token address\\. Please provide a valid Solana address")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                bot.send_message(msg.chat.id, t(lang, "errors.invalid_token_address"))
                     .await?;
                 return Ok(());
             }
@@ -474,17 +527,14 @@ Happy trading\\! 🚀"#;
             match parts[1].parse::<f64>() {
                 Ok(amount) => {
                     if let Err(e) = Validator::validate_trade_amount(amount, 1.0) {
-                        bot.send_message(msg.chat.id, 
-                            format!("❌ Invalid amount: {}", e))
+                        bot.send_message(msg.chat.id, t_args(lang, "errors.invalid_amount_reason", &[("error", &e.to_string())]))
                             .await?;
                         return Ok(());
                     }
                     amount
                 },
                 Err(_) => {
-                    bot.send_message(msg.chat.id, 
-                        "❌ Invalid amount\\. Please use a valid number")
-                        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                    bot.send_message(msg.chat.id, t(lang, "errors.invalid_amount"))
                         .await?;
                     return Ok(());
                 }
@@ -492,28 +542,24 @@ Happy trading\\! 🚀"#;
         } else {
             0.05 // Default snipe amount
         };
+        let amount = fmt_number(lang, amount_sol, NumberKind::Sol);
         
         bot.send_message(msg.chat.id, 
-            format!("🎯 *Sniping {}*\\n\\n\
-                   Amount: {} SOL\\n\
-                   Status: Monitoring for liquidity\\.\\.\\.\n\\n\
-                   🔍 Running LARP check\\.\\.\\.", 
-                   token_address, amount_sol))
+            format!("🎯 *{}*\n\n{}", 
+                   escape(&t_args(lang, "snipe.title", &[("token", &token_address)])),
+                   escape(&t_args(lang, "snipe.monitoring", &[("amount", &amount)]))))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
             .await?;
         
         // Step 1: Run LARP check first
-        let larp_result = Self::check_token_safety(token_address).await;
+        let larp_result = Self::check_token_safety(&token_address).await;
         match larp_result {
             Ok(safety_score) => {
                 if safety_score < 5 {
                     bot.send_message(msg.chat.id, 
-                        format!("⚠️ *LARP Check Failed*\\n\\n\
-                               Token: `{}`\\n\
-                               Safety Score: {}/10 ❌\\n\\n\
-                               **High Risk Detected\\!**\\n\
-                               Snipe cancelled for your protection\\.", 
-                               token_address, safety_score))
+                        format!("⚠️ *{}*\n\n{}", 
+                               escape(&t(lang, "snipe.larp_failed_title")),
+                               escape(&t_args(lang, "snipe.larp_failed", &[("token", &token_address), ("score", &safety_score.to_string())]))))
                         .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                         .await?;
                     return Ok(());
@@ -521,36 +567,41 @@ Happy trading\\! 🚀"#;
             }
             Err(e) => {
                 bot.send_message(msg.chat.id, 
-                    format!("❌ *LARP Check Error*\\n\\n\
-                           Could not verify token safety: {}\\n\
-                           Snipe cancelled\\.", e))
+                    format!("❌ *{}*\n\n{}", 
+                           escape(&t(lang, "snipe.larp_error_title")),
+                           escape(&t_args(lang, "snipe.larp_error", &[("error", &e.user_message())]))))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
         }
         
-        // Step 2: Execute the snipe trade
-        match Self::execute_snipe_trade(token_address, amount_sol, &user_id, trading_engine, wallet_manager).await {
+        // Step 2: Ask first when the buy would move the price past the user's slippage
+        let slippage_bps = preferences.get(user_id.parse().unwrap_or_default()).await.slippage_bps;
+        if TradingHandler::confirm_if_high_impact(
+            &bot, msg.chat.id, &liquidity, &token_address, &token_address, amount_sol, slippage_bps, lang,
+        ).await? {
+            return Ok(());
+        }
+        
+        // Step 3: Execute the snipe trade
+        match Self::execute_snipe_trade(&token_address, amount_sol, &user_id, None, trading_engine, wallet_manager).await {
             Ok(trade_result) => {
+                let tokens = fmt_number(lang, trade_result.tokens_received, NumberKind::Token);
                 bot.send_message(msg.chat.id, 
-                    format!("✅ *Snipe Complete\\!*\\n\\n\
-                           🎯 Bought: {} tokens\\n\
-                           💰 Cost: {} SOL\\n\
-                           💎 LARP check: PASSED\\n\
-                           🔄 TX: `{}`\\n\\n\
-                           _Check /portfolio for updated holdings_", 
-                           trade_result.tokens_received,
-                           amount_sol,
-                           trade_result.tx_signature))
+                    format!("✅ *{}*\n\n{}\n🔄 TX: `{}`\n\n_{}_", 
+                           escape(&t(lang, "snipe.done_title")),
+                           escape(&t_args(lang, "snipe.done", &[("tokens", &tokens), ("amount", &amount)])),
+                           escape_code(&trade_result.tx_signature),
+                           escape(&t(lang, "common.check_portfolio"))))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
             Err(e) => {
                 bot.send_message(msg.chat.id, 
-                    format!("❌ *Snipe Failed*\\n\\n\
-                           Error: {}\\n\\n\
-                           Your SOL was not spent\\.", e))
+                    format!("❌ *{}*\n\n{}", 
+                           escape(&t(lang, "snipe.failed_title")),
+                           escape(&t_args(lang, "snipe.failed", &[("error", &e.user_message())]))))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
@@ -631,6 +682,7 @@ Happy trading\\! 🚀"#;
         token_address: &str,
         amount_sol: f64,
         user_id: &str,
+        defaults: Option<TradeDefaults>,
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
     ) -> Result<crate::trading::types::TradeResult> {
@@ -650,6 +702,7 @@ Happy trading\\! 🚀"#;
             user_wallet: user_wallet.clone(),
             token: token_address.to_string(),
             amount_sol,
+            defaults,
             response_tx,
         })?;
         
@@ -663,23 +716,14 @@ Happy trading\\! 🚀"#;
         bot: Bot,
         msg: Message,
         args: String,
-        db: Arc<Database>,
         user_id: String,
-        trading_engine: TradingEngineHandle,
-        wallet_manager: Arc<WalletManager>,
+        copy_manager: Arc<CopyTradingManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
-        use crate::trading::CopyTradingManager;
         use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
         
         let follower_user_id = msg.from().map(|u| u.id.0 as i64).unwrap_or(0);
         
-        // Create copy trading manager
-        let copy_manager = Arc::new(CopyTradingManager::new(
-            db.clone(),
-            trading_engine,
-            wallet_manager,
-        ));
-        
         // Parse command arguments
         let parts: Vec<&str> = args.split_whitespace().collect();
         
@@ -687,66 +731,35 @@ Happy trading\\! 🚀"#;
             // Show available masters to copy
             match copy_manager.get_available_masters(5).await {
                 Ok(masters) => {
-                    let mut message = String::from("🎯 **Available Master Traders**\n\n");
+                    let mut message = format!("🎯 *{}*\n\n", escape(&t(lang, "copy.masters_title")));
                     let mut buttons = Vec::new();
                     
                     for master in masters {
-                        message.push_str(&copy_manager.format_master_trader(&master));
-                        message.push_str("\n---\n\n");
+                        message.push_str(&escape(&copy_manager.format_master_trader(&master)));
+                        message.push_str("\n\\-\\-\\-\n\n");
                         
                         buttons.push(vec![
                             InlineKeyboardButton::callback(
-                                format!("📋 Copy {}", master.username),
+                                t_args(lang, "buttons.copy_master", &[("name", &master.username)]),
                                 format!("copy_{}", master.user_id),
                             ),
                             InlineKeyboardButton::callback(
-                                "📊 Details",
+                                t(lang, "buttons.details"),
                                 format!("master_details_{}", master.user_id),
                             ),
                         ]);
                     }
                     
-                    message.push_str("💡 **How to Copy Trade:**\n");
-                    message.push_str("• `/copy <username>` - Start copying\n");
-                    message.push_str("• `/copy <username> <allocation>%` - Custom allocation\n");
-                    message.push_str("• `/copy status` - View your copy configs\n");
-                    message.push_str("• `/copy stop <username>` - Stop copying\n");
-                    
-                    // Escape special characters for Markdown
-                    let escaped_message = message
-                        .replace(".", "\\.")
-                        .replace("-", "\\-")
-                        .replace("(", "\\(")
-                        .replace(")", "\\)")
-                        .replace("+", "\\+")
-                        .replace("_", "\\_")
-                        .replace("*", "\\*")
-                        .replace("[", "\\[")
-                        .replace("]", "\\]")
-                        .replace("`", "\\`")
-                        .replace("#", "\\#")
-                        .replace("|", "\\|")
-                        .replace("{", "\\{")
-                        .replace("}", "\\}")
-                        .replace("=", "\\=")
-                        .replace(">", "\\>")
-                        .replace("!", "\\!")
-                        .replace("~", "\\~");
-                    
-                    let keyboard = if !buttons.is_empty() {
-                        InlineKeyboardMarkup::new(buttons)
-                    } else {
-                        InlineKeyboardMarkup::new(vec![])
-                    };
+                    message.push_str(&format!("💡 *{}*\n{}", escape(&t(lang, "copy.how_title")), escape(&t(lang, "copy.how"))));
                     
-                    bot.send_message(msg.chat.id, escaped_message)
+                    bot.send_message(msg.chat.id, message)
                         .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                        .reply_markup(keyboard)
+                        .reply_markup(InlineKeyboardMarkup::new(buttons))
                         .await?;
                 }
                 Err(e) => {
-                    bot.send_message(msg.chat.id, 
-                        format!("❌ Failed to load master traders: {}", e))
+                    error!("Failed to load master traders: {}", e);
+                    bot.send_message(msg.chat.id, t(lang, "copy.masters_failed"))
                         .await?;
                 }
             }
@@ -760,20 +773,25 @@ Happy trading\\! 🚀"#;
                 match copy_manager.get_user_stats(follower_user_id).await {
                     Ok((configs, executions)) => {
                         if configs.is_empty() {
-                            bot.send_message(msg.chat.id, 
-                                "📋 You're not currently copying any traders.\n\
-                                Use `/copy` to see available masters.")
+                            bot.send_message(msg.chat.id, t(lang, "copy.none"))
                                 .await?;
                         } else {
-                            let mut message = String::from("📋 **Your Copy Trading Status**\n\n");
+                            let mut message = format!("📋 *{}*\n\n", escape(&t(lang, "copy.status_title")));
                             
                             for config in configs {
-                                message.push_str(&copy_manager.format_config(&config));
+                                message.push_str(&escape(&copy_manager.format_config(&config)));
+                                if let Some(remaining) = copy_manager.remaining_daily_budget(&config).await {
+                                    message.push_str("\n");
+                                    message.push_str(&escape(&t_args(lang, "copy.budget_left", &[
+                                        ("remaining", &fmt_number(lang, remaining, NumberKind::Decimal(4))),
+                                        ("limit", &fmt_number(lang, config.daily_loss_limit_sol, NumberKind::Sol)),
+                                    ])));
+                                }
                                 message.push_str("\n\n");
                             }
                             
                             if !executions.is_empty() {
-                                message.push_str("📜 **Recent Executions:**\n");
+                                message.push_str(&format!("📜 *{}*\n", escape(&t(lang, "copy.recent_title"))));
                                 for exec in executions.iter().take(5) {
                                     let status_emoji = match exec.status {
                                         crate::trading::CopyTradeStatus::Success => "✅",
@@ -781,45 +799,31 @@ Happy trading\\! 🚀"#;
                                         crate::trading::CopyTradeStatus::Pending => "⏳",
                                         _ => "❓",
                                     };
+                                    let side = match exec.trade_type {
+                                        crate::trading::CopyTradeType::Buy => t(lang, "trade.buy"),
+                                        crate::trading::CopyTradeType::Sell => t(lang, "trade.sell"),
+                                        _ => t(lang, "trade.trade"),
+                                    };
                                     
-                                    message.push_str(&format!(
-                                        "{} {} {} - {} SOL @ ${:.6}\n",
+                                    message.push_str(&escape(&format!(
+                                        "{} {} {} - {} SOL @ ${}\n",
                                         status_emoji,
-                                        match exec.trade_type {
-                                            crate::trading::CopyTradeType::Buy => "BUY",
-                                            crate::trading::CopyTradeType::Sell => "SELL",
-                                            _ => "TRADE",
-                                        },
+                                        side,
                                         exec.token_symbol,
-                                        exec.copied_amount_sol,
-                                        exec.execution_price
-                                    ));
+                                        fmt_number(lang, exec.copied_amount_sol, NumberKind::Sol),
+                                        fmt_number(lang, exec.execution_price, NumberKind::Usd)
+                                    )));
                                 }
                             }
                             
-                            // Escape for Markdown
-                            let escaped_message = message
-                                .replace(".", "\\.")
-                                .replace("-", "\\-")
-                                .replace("(", "\\(")
-                                .replace(")", "\\)")
-                                .replace("+", "\\+")
-                                .replace("_", "\\_")
-                                .replace("*", "\\*")
-                                .replace("[", "\\[")
-                                .replace("]", "\\]")
-                                .replace("`", "\\`")
-                                .replace("#", "\\#")
-                                .replace("|", "\\|");
-                            
-                            bot.send_message(msg.chat.id, escaped_message)
+                            bot.send_message(msg.chat.id, message)
                                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                                 .await?;
                         }
                     }
                     Err(e) => {
-                        bot.send_message(msg.chat.id, 
-                            format!("❌ Failed to get copy trading status: {}", e))
+                        error!("Failed to get copy trading status: {}", e);
+                        bot.send_message(msg.chat.id, t(lang, "copy.status_failed"))
                             .await?;
                     }
                 }
@@ -827,8 +831,7 @@ Happy trading\\! 🚀"#;
             "stop" => {
                 // Stop copying a trader
                 if parts.len() < 2 {
-                    bot.send_message(msg.chat.id, 
-                        "❌ Usage: `/copy stop <username>`")
+                    bot.send_message(msg.chat.id, t(lang, "copy.stop_usage"))
                         .await?;
                 } else {
                     let master_identifier = parts[1];
@@ -838,13 +841,12 @@ Happy trading\\! 🚀"#;
                     
                     match copy_manager.stop_following(follower_user_id, master_id).await {
                         Ok(_) => {
-                            bot.send_message(msg.chat.id, 
-                                format!("✅ Stopped copying trader {}", master_identifier))
+                            bot.send_message(msg.chat.id, t_args(lang, "copy.stopped", &[("master", master_identifier)]))
                                 .await?;
                         }
                         Err(e) => {
-                            bot.send_message(msg.chat.id, 
-                                format!("❌ Failed to stop copying: {}", e))
+                            error!("Failed to stop copying {}: {}", master_identifier, e);
+                            bot.send_message(msg.chat.id, t(lang, "copy.stop_failed"))
                                 .await?;
                         }
                     }
@@ -864,8 +866,7 @@ Happy trading\\! 🚀"#;
                     5.0 // Default 5 SOL max per trade
                 };
                 
-                bot.send_message(msg.chat.id, 
-                    format!("🔄 Setting up copy trading for {}...", master_identifier))
+                bot.send_message(msg.chat.id, t_args(lang, "copy.setting_up", &[("master", master_identifier)]))
                     .await?;
                 
                 match copy_manager.start_following(
@@ -875,60 +876,31 @@ Happy trading\\! 🚀"#;
                     max_position,
                 ).await {
                     Ok(config) => {
-                        let message = format!(
-                            "✅ **Successfully Started Copy Trading!**\n\n\
-                            Master: {} (@{})\n\
-                            Allocation: {}%\n\
-                            Max Position: {} SOL\n\
-                            Min Position: {} SOL\n\
-                            Status: 🟢 Active\n\n\
-                            ⚙️ **Settings:**\n\
-                            • Auto Stop Loss: {} ({}%)\n\
-                            • Auto Take Profit: {} ({}%)\n\
-                            • Slippage Tolerance: {}%\n\n\
-                            📊 You'll automatically copy this trader's:\n\
-                            {} Buy orders\n\
-                            {} Sell orders\n\n\
-                            💡 Use `/copy status` to monitor performance\n\
-                            🛑 Use `/copy stop {}` to stop copying",
-                            config.master_username,
-                            config.master_user_id,
-                            config.allocation_percent,
-                            config.max_position_sol,
-                            config.min_position_sol,
-                            if config.auto_stop_loss { "✅" } else { "❌" },
-                            config.stop_loss_percent,
-                            if config.auto_take_profit { "✅" } else { "❌" },
-                            config.take_profit_percent,
-                            config.slippage_tolerance,
-                            if config.copy_buys { "✅" } else { "❌" },
-                            if config.copy_sells { "✅" } else { "❌" },
-                            config.master_username
-                        );
-                        
-                        // Escape for Markdown
-                        let escaped_message = message
-                            .replace(".", "\\.")
-                            .replace("-", "\\-")
-                            .replace("(", "\\(")
-                            .replace(")", "\\)")
-                            .replace("+", "\\+")
-                            .replace("_", "\\_")
-                            .replace("*", "\\*")
-                            .replace("[", "\\[")
-                            .replace("]", "\\]")
-                            .replace("`", "\\`")
-                            .replace("#", "\\#")
-                            .replace("|", "\\|")
-                            .replace("!", "\\!");
+                        let check = |on: bool| if on { "✅" } else { "❌" };
+                        let number = |value: f64| fmt_number(lang, value, NumberKind::Decimal(2));
+                        let details = t_args(lang, "copy.started", &[
+                            ("master", &config.master_username),
+                            ("master_id", &config.master_user_id.to_string()),
+                            ("allocation", &number(config.allocation_percent)),
+                            ("max", &fmt_number(lang, config.max_position_sol, NumberKind::Sol)),
+                            ("min", &fmt_number(lang, config.min_position_sol, NumberKind::Sol)),
+                            ("stop_loss", check(config.auto_stop_loss)),
+                            ("stop_loss_pct", &number(config.stop_loss_percent)),
+                            ("take_profit", check(config.auto_take_profit)),
+                            ("take_profit_pct", &number(config.take_profit_percent)),
+                            ("slippage", &number(config.slippage_tolerance)),
+                            ("buys", check(config.copy_buys)),
+                            ("sells", check(config.copy_sells)),
+                        ]);
+                        let message = format!("✅ *{}*\n\n{}", escape(&t(lang, "copy.started_title")), escape(&details));
                         
-                        bot.send_message(msg.chat.id, escaped_message)
+                        bot.send_message(msg.chat.id, message)
                             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                             .await?;
                     }
                     Err(e) => {
-                        bot.send_message(msg.chat.id, 
-                            format!("❌ Failed to start copy trading: {}", e))
+                        error!("Failed to start copying {}: {}", master_identifier, e);
+                        bot.send_message(msg.chat.id, t_args(lang, "copy.start_failed", &[("error", &BotError::user_message_for(&e))]))
                             .await?;
                     }
                 }
@@ -945,19 +917,15 @@ Happy trading\\! 🚀"#;
         args: String,
         db: Arc<Database>,
         user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         if args.trim().is_empty() {
-            bot.send_message(msg.chat.id, 
-                "❌ Usage: `/unfollow <wallet_address>`")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+            bot.send_message(msg.chat.id, t(lang, "unfollow.usage"))
                 .await?;
             return Ok(());
         }
         
-        bot.send_message(msg.chat.id, 
-            "✅ *Stopped Following Trader*\\n\\n\
-            No longer copying their trades\\.")
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+        bot.send_message(msg.chat.id, t(lang, "unfollow.done"))
             .await?;
         
         Ok(())
@@ -969,20 +937,13 @@ Happy trading\\! 🚀"#;
         msg: Message,
         args: String,
         ai_analyzer: Arc<GroqAnalyzer>,
+        lang: &str,
     ) -> ResponseResult<()> {
         use crate::security::LarpChecker;
         use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
         
         if args.trim().is_empty() {
-            bot.send_message(msg.chat.id, 
-                "❌ Usage: `/larp <token_address>`\n\n\
-                Example: `/larp EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v`\n\n\
-                💡 This checks for:\n\
-                • 🍯 Honeypot detection\n\
-                • 💧 Liquidity analysis\n\
-                • 👥 Holder distribution\n\
-                • 🔒 Authority checks\n\
-                • 📊 Trading restrictions")
+            bot.send_message(msg.chat.id, t(lang, "larp.usage"))
                 .await?;
             return Ok(());
         }
@@ -990,78 +951,44 @@ Happy trading\\! 🚀"#;
         let token_address = args.trim();
         
         // Send initial message
-        let loading_msg = bot.send_message(msg.chat.id, 
-            "🔍 **Security Analysis in Progress**\n\n\
-            Checking multiple security providers...\n\
-            • GoPlus Security ⏳\n\
-            • RugCheck ⏳\n\
-            • On-chain Analysis ⏳\n\n\
-            _This may take a few seconds..._")
+        let loading_msg = bot.send_message(msg.chat.id, t(lang, "larp.checking"))
             .await?;
         
-        // Create LARP checker
-        let goplus_api_key = std::env::var("GOPLUS_API_KEY").ok();
-        let larp_checker = LarpChecker::new(goplus_api_key);
+        // Create LARP checker; on-chain checks answer even without GoPlus
+        let larp_checker = LarpChecker::from_env();
         
         // Perform analysis
         match larp_checker.analyze_token(token_address).await {
             Ok(analysis) => {
-                // Format the analysis
-                let formatted = larp_checker.format_analysis(&analysis);
-                
-                // Escape special characters for Markdown
-                let escaped_message = formatted
-                    .replace(".", "\\.")
-                    .replace("-", "\\-")
-                    .replace("(", "\\(")
-                    .replace(")", "\\)")
-                    .replace("+", "\\+")
-                    .replace("_", "\\_")
-                    .replace("*", "\\*")
-                    .replace("[", "\\[")
-                    .replace("]", "\\]")
-                    .replace("`", "\\`")
-                    .replace("#", "\\#")
-                    .replace("|", "\\|")
-                    .replace("{", "\\{")
-                    .replace("}", "\\}")
-                    .replace("=", "\\=")
-                    .replace(">", "\\>")
-                    .replace("!", "\\!")
-                    .replace("~", "\\~");
+                let formatted = escape(&larp_checker.format_analysis(&analysis));
                 
                 // Create action buttons based on risk level
                 let mut buttons = vec![];
+                let chart = InlineKeyboardButton::callback(t(lang, "buttons.view_chart"), format!("chart_{}", token_address));
                 
                 match analysis.risk_level {
                     crate::security::RiskLevel::VeryLow | crate::security::RiskLevel::Low => {
                         buttons.push(vec![
                             InlineKeyboardButton::callback(
-                                "✅ Quick Buy",
-                                format!("qbuy_{}", token_address)
-                            ),
-                            InlineKeyboardButton::callback(
-                                "📊 View Chart",
-                                format!("chart_{}", token_address)
+                                t(lang, "buttons.larp_quick_buy"),
+                                CallbackAction::quick_buy(token_address, DEFAULT_QUICK_BUY_SOL).to_data()
                             ),
+                            chart,
                         ]);
                     }
                     crate::security::RiskLevel::Medium => {
                         buttons.push(vec![
                             InlineKeyboardButton::callback(
-                                "⚠️ Small Buy",
-                                format!("qbuy_small_{}", token_address)
-                            ),
-                            InlineKeyboardButton::callback(
-                                "📊 View Chart",
-                                format!("chart_{}", token_address)
+                                t(lang, "buttons.small_buy"),
+                                CallbackAction::quick_buy(token_address, SMALL_QUICK_BUY_SOL).to_data()
                             ),
+                            chart,
                         ]);
                     }
                     _ => {
                         buttons.push(vec![
                             InlineKeyboardButton::callback(
-                                "🔍 More Info",
+                                t(lang, "buttons.more_info"),
                                 format!("info_{}", token_address)
                             ),
                         ]);
@@ -1070,11 +997,11 @@ Happy trading\\! 🚀"#;
                 
                 buttons.push(vec![
                     InlineKeyboardButton::callback(
-                        "🔄 Refresh",
+                        t(lang, "buttons.refresh"),
                         format!("larp_refresh_{}", token_address)
                     ),
                     InlineKeyboardButton::callback(
-                        "📈 Price Check",
+                        t(lang, "buttons.price_check"),
                         format!("price_{}", token_address)
                     ),
                 ]);
@@ -1085,7 +1012,7 @@ Happy trading\\! 🚀"#;
                 bot.delete_message(msg.chat.id, loading_msg.id).await.ok();
                 
                 // Send analysis result
-                bot.send_message(msg.chat.id, escaped_message)
+                bot.send_message(msg.chat.id, formatted)
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .reply_markup(keyboard)
                     .await?;
@@ -1094,13 +1021,8 @@ Happy trading\\! 🚀"#;
                 // Delete loading message
                 bot.delete_message(msg.chat.id, loading_msg.id).await.ok();
                 
-                bot.send_message(msg.chat.id, 
-                    format!("❌ Security analysis failed: {}\n\n\
-                    This could be due to:\n\
-                    • Invalid token address\n\
-                    • Token not found on Solana\n\
-                    • API temporarily unavailable\n\n\
-                    Please verify the token address and try again.", e))
+                error!("Security analysis of {} failed: {}", token_address, e);
+                bot.send_message(msg.chat.id, t(lang, "larp.failed"))
                     .await?;
             }
         }
@@ -1108,152 +1030,8 @@ Happy trading\\! 🚀"#;
         Ok(())
     }
     
-    /// Handle /trending command
-    pub async fn handle_trending(
-        bot: Bot,
-        msg: Message,
-        ai_analyzer: Arc<GroqAnalyzer>,
-    ) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, 
-            "📊 *Fetching real\\-time market data\\.\\.\\.*")
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .await?;
-        
-        // Get trending tokens data with enhanced market metrics
-        let trending_tokens = match Self::fetch_enhanced_trending_data().await {
-            Ok(tokens) => tokens,
-            Err(e) => {
-                bot.send_message(msg.chat.id, 
-                    format!("❌ Failed to fetch trending data: {}", e))
-                    .await?;
-                return Ok(());
-            }
-        };
-        
-        // Get new launches and risk alerts
-        let (new_launches, risk_alerts) = Self::get_market_alerts().await?;
-        
-        // Build enhanced trending message
-        let mut message = "📈 *Live Market Trending*\\n\\n".to_string();
-        message.push_str("🔥 **Top Gainers:**\\n");
-        
-        let mut inline_buttons = vec![];
-        
-        for (i, token) in trending_tokens.iter().take(5).enumerate() {
-            let emoji = match token.price_change_24h {
-                change if change > 100.0 => "🎆",
-                change if change > 50.0 => "🚀",
-                change if change > 20.0 => "📈", 
-                change if change > 0.0 => "⬆️",
-                _ => "⬇️"
-            };
-            
-            message.push_str(&format!(
-                "{}\\. *{}* \\({}\\) {}\\n\
-                   💵 Price: \\${:.8}\\n\
-                   📈 24h: {}%\\n\
-                   🔄 Vol: \\${}\\n\
-                   💰 MC: \\${}\\n\\n",
-                i + 1,
-                token.name.replace(".", "\\.").replace("-", "\\-"),
-                token.symbol.replace(".", "\\."),
-                emoji,
-                token.price,
-                if token.price_change_24h > 0.0 { format!("+{:.1}", token.price_change_24h) } else { format!("{:.1}", token.price_change_24h) },
-                format_volume(token.volume_24h),
-                format_market_cap(token.market_cap)
-            ));
-            
-            // Add quick buy button for top 3
-            if i < 3 {
-                inline_buttons.push(InlineKeyboardButton::callback(
-                    format!("🚀 Buy {}", token.symbol),
-                    format!("qbuy_0.1_{}", token.symbol)
-                ));
-            }
-        }
-        
-        message.push_str("\\n🆕 **New Launches \\(<6h\\):**\\n");
-        for launch in &new_launches {
-            message.push_str(&format!(
-                "• *{}* \\- {} old\\n   🔐 LP: {} | 👥 Holders: {}\\n",
-                launch.name.replace(".", "\\."),
-                launch.age,
-                launch.liquidity_status,
-                launch.holder_count
-            ));
-        }
-        
-        if !risk_alerts.is_empty() {
-            message.push_str("\\n⚠️ **Risk Alerts:**\\n");
-            for alert in &risk_alerts {
-                message.push_str(&format!(
-                    "• {} \\- {}\\n",
-                    alert.symbol.replace(".", "\\."),
-                    alert.reason.replace(".", "\\.")
-                ));
-            }
-        }
-        
-        // Add market summary
-        let total_volume: f64 = trending_tokens.iter().map(|t| t.volume_24h).sum();
-        message.push_str(&format!(
-            "\\n📊 **Market Summary:**\\n\
-            Total 24h Volume: \\${}\\n\
-            Trending Tokens: {}\\n\
-            New Launches: {}\\n",
-            format_volume(total_volume),
-            trending_tokens.len(),
-            new_launches.len()
-        ));
-        
-        message.push_str("\\n_Use `/larp <address>` to check safety_\\n");
-        message.push_str("_Use `/qbuy <amount> <symbol>` to buy_");
-        
-        // Create quick action buttons
-        let keyboard = if !inline_buttons.is_empty() {
-            let mut rows = vec![inline_buttons];
-            rows.push(vec![
-                InlineKeyboardButton::callback("🔄 Refresh", "trending_refresh"),
-                InlineKeyboardButton::callback("📈 More Stats", "trending_detailed"),
-            ]);
-            InlineKeyboardMarkup::new(rows)
-        } else {
-            InlineKeyboardMarkup::new(vec![
-                vec![
-                    InlineKeyboardButton::callback("🔄 Refresh", "trending_refresh"),
-                    InlineKeyboardButton::callback("📈 More Stats", "trending_detailed"),
-                ]
-            ])
-        };
-        
-        bot.send_message(msg.chat.id, message)
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(keyboard)
-            .await?;
-        
-        Ok(())
-    }
-    
-    /// Get market alerts for new launches and risk warnings
-    async fn get_market_alerts() -> Result<(Vec<NewLaunch>, Vec<RiskAlert>)> {
-        // Fetch real new launches from DexScreener
-        let new_launches = Self::fetch_new_launches().await.unwrap_or_else(|e| {
-            warn!("Failed to fetch new launches: {}", e);
-            Vec::new()
-        });
-        
-        // Fetch real risk alerts from various sources
-        let risk_alerts = Self::fetch_risk_alerts().await.unwrap_or_else(|e| {
-            warn!("Failed to fetch risk alerts: {}", e);
-            Vec::new()
-        });
-        
-        Ok((new_launches, risk_alerts))
-    }
-    
     /// Fetch real new token launches from DexScreener
-    async fn fetch_new_launches() -> Result<Vec<NewLaunch>> {
+    pub(crate) async fn fetch_new_launches() -> Result<Vec<NewLaunch>> {
         let client = reqwest::Client::new();
         let url = "https://api.dexscreener.com/latest/dex/tokens/new/solana";
         
@@ -1312,35 +1090,9 @@ Happy trading\\! 🚀"#;
         
         if let Some(pairs) = data.pairs {
             for pair in pairs.into_iter().take(5) {
-                let age = if let Some(created_at) = pair.pair_created_at {
-                    let now = chrono::Utc::now().timestamp() as u64;
-                    let age_seconds = now.saturating_sub(created_at);
-                    if age_seconds < 3600 {
-                        format!("{} minutes", age_seconds / 60)
-                    } else if age_seconds < 86400 {
-                        format!("{} hours", age_seconds / 3600)
-                    } else {
-                        format!("{} days", age_seconds / 86400)
-                    }
-                } else {
-                    "Unknown".to_string()
-                };
-                
-                let liquidity_status = if let Some(liq) = pair.liquidity {
-                    if let Some(usd) = liq.usd {
-                        if usd > 100000.0 {
-                            "High Liquidity 💎".to_string()
-                        } else if usd > 10000.0 {
-                            "Medium Liquidity 🟡".to_string()
-                        } else {
-                            "Low Liquidity ⚠️".to_string()
-                        }
-                    } else {
-                        "Unknown 🤷".to_string()
-                    }
-                } else {
-                    "No Data 📊".to_string()
-                };
+                let age_secs = pair.pair_created_at
+                    .map(|created_at| (chrono::Utc::now().timestamp() as u64).saturating_sub(created_at));
+                let liquidity_usd = pair.liquidity.and_then(|liq| liq.usd);
                 
                 let holder_count = if let Some(txns) = pair.transactions {
                     if let Some(h24) = txns.h24 {
@@ -1353,10 +1105,10 @@ Happy trading\\! 🚀"#;
                 };
                 
                 launches.push(NewLaunch {
-                    name: pair.base_token.name.unwrap_or_else(|| "Unknown Token".to_string()),
+                    name: pair.base_token.name,
                     address: pair.base_token.address,
-                    age,
-                    liquidity_status,
+                    age_secs,
+                    liquidity_usd,
                     holder_count,
                 });
             }
@@ -1366,7 +1118,7 @@ Happy trading\\! 🚀"#;
     }
     
     /// Fetch real risk alerts
-    async fn fetch_risk_alerts() -> Result<Vec<RiskAlert>> {
+    pub(crate) async fn fetch_risk_alerts() -> Result<Vec<RiskAlert>> {
         // In a real implementation, this would check:
         // 1. Honeypot detection services
         // 2. Token holder distribution
@@ -1378,117 +1130,78 @@ Happy trading\\! 🚀"#;
         Ok(Vec::new())
     }
     
-    /// Handle /launch command
-    pub async fn handle_launch(
-        bot: Bot,
-        msg: Message,
-        trading_engine: TradingEngineHandle,
-        user_id: String,
-    ) -> ResponseResult<()> {
-        let launch_menu = InlineKeyboardMarkup::new(vec![
-            vec![
-                InlineKeyboardButton::callback("🚀 Quick Launch", "launch_quick"),
-                InlineKeyboardButton::callback("⚙️ Advanced", "launch_advanced"),
-            ],
-            vec![
-                InlineKeyboardButton::callback("💎 Meme Token", "launch_meme"),
-                InlineKeyboardButton::callback("🤖 AI Token", "launch_ai"),
-            ],
-            vec![
-                InlineKeyboardButton::url("📖 Guide", "https://docs.pump.fun"),
-            ],
-        ]);
-        
-        bot.send_message(msg.chat.id, 
-            "🚀 *Token Launch Center*\\n\\n\
-            Choose your launch type:\\n\\n\
-            🚀 **Quick Launch** \\- Basic token in 2 minutes\\n\
-            ⚙️ **Advanced** \\- Custom features & economics\\n\
-            💎 **Meme Token** \\- Viral\\-optimized setup\\n\
-            🤖 **AI Token** \\- AI\\-powered utilities\\n\\n\
-            All launches include:\\n\
-            • Auto liquidity provision\\n\
-            • Social media integration\\n\
-            • Community tools\\n\
-            • Analytics dashboard")
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(launch_menu)
-            .await?;
-        
-        Ok(())
-    }
-    
-    /// Handle /blink command
+    /// Handle /blink command: `buy <token> [amounts…]` or `donate [amounts…]`
     pub async fn handle_blink(
         bot: Bot,
         msg: Message,
         args: String,
-        trading_engine: TradingEngineHandle,
+        wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
         user_id: String,
     ) -> ResponseResult<()> {
-        if args.trim().is_empty() {
-            bot.send_message(msg.chat.id, 
-                "❌ Usage: `/blink <action>`\\n\\n\
-                Examples:\\n\
-                • `/blink buy BONK` \\- Create buy link\\n\
-                • `/blink donate` \\- Create donation link\\n\
-                • `/blink portfolio` \\- Share portfolio link")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        let lang = lang.as_str();
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let action = parts.first().map(|p| p.to_lowercase());
+        let is_buy = action.as_deref() == Some("buy") && parts.len() >= 2;
+        if !is_buy && action.as_deref() != Some("donate") {
+            bot.send_message(msg.chat.id, t(lang, "blink.usage")).await?;
             return Ok(());
         }
         
-        let blink_url = format!("https://dial.to/?action=solana-action:{}?user={}", 
-            urlencoding::encode(&args), user_id);
-        
-        bot.send_message(msg.chat.id, 
-            format!("✨ *Solana Blink Created\\!*\\n\\n\
-                   Action: `{}`\\n\\n\
-                   🔗 **Your Blink:**\\n\
-                   `{}`\\n\\n\
-                   📱 **Share this link anywhere:**\\n\
-                   • Twitter/X posts\\n\
-                   • Discord messages\\n\
-                   • Telegram chats\\n\
-                   • Any website\\n\\n\
-                   _One\\-click Solana transactions\\!_", 
-                   args, blink_url))
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .await?;
+        let Some(generator) = services.blinks.clone() else {
+            bot.send_message(msg.chat.id, t(lang, "blink.disabled")).await?;
+            return Ok(());
+        };
+        let wallet = match wallet_manager.get_user_wallet(&user_id).await {
+            Ok(Some(wallet)) => wallet.public_key,
+            _ => {
+                bot.send_message(msg.chat.id, t(lang, "errors.no_wallet_found")).await?;
+                return Ok(());
+            }
+        };
         
-        Ok(())
-    }
-    
-    /// Handle /alert command
-    pub async fn handle_alert(
-        bot: Bot,
-        msg: Message,
-        args: String,
-        db: Arc<Database>,
-        user_id: String,
-    ) -> ResponseResult<()> {
-        let parts: Vec<&str> = args.split_whitespace().collect();
-        if parts.len() < 2 {
-            bot.send_message(msg.chat.id, 
-                "❌ Usage: `/alert <token> <price>`\\n\\n\
-                Examples:\\n\
-                • `/alert BONK 0.00002` \\- Alert when BONK hits price\\n\
-                • `/alert SOL 150` \\- Alert when SOL hits \\$150")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
+        let amount_args = if is_buy { &parts[2..] } else { &parts[1..] };
+        let amounts: std::result::Result<Vec<f64>, _> = amount_args.iter().map(|a| a.parse::<f64>()).collect();
+        let Ok(amounts) = amounts else {
+            bot.send_message(msg.chat.id, t(lang, "blink.bad_amounts")).await?;
             return Ok(());
-        }
+        };
         
-        let token = parts[0];
-        let price = parts[1].parse::<f64>().unwrap_or(0.0);
+        let created = if is_buy {
+            match crate::trading::TokenResolver::resolve(parts[1]) {
+                Ok(mint) => {
+                    let symbol = parts[1].to_uppercase();
+                    generator.register_buy_blink(mint, symbol, amounts, crate::constants::DEFAULT_SLIPPAGE_BPS, wallet).await
+                }
+                Err(e) => Err(e.into()),
+            }
+        } else {
+            generator.register_donation_blink(wallet.clone(), amounts, wallet).await
+        };
+        let blink = match created {
+            Ok(blink) => blink,
+            Err(e) => {
+                error!("Blink creation for {} failed: {}", user_id, e);
+                bot.send_message(msg.chat.id, t_args(lang, "blink.failed", &[("error", &BotError::user_message_for(&e))])).await?;
+                return Ok(());
+            }
+        };
         
+        let amounts = blink.preset_amounts().iter()
+            .map(|a| fmt_number(lang, *a, NumberKind::Sol))
+            .collect::<Vec<_>>()
+            .join(" · ");
         bot.send_message(msg.chat.id, 
-            format!("🔔 *Price Alert Set*\\n\\n\
-                   Token: {}\\n\
-                   Target Price: \\${}\\n\
-                   Status: ✅ Active\\n\\n\
-                   _You'll be notified when the price is reached_", 
-                   token, price))
+            format!("✨ *{}*\n\n{}\n{}\n\n🔗 *{}*\n`{}`\n\n⚙️ *{}*\n`{}`\n\n_{}_", 
+                   escape(&t(lang, "blink.created_title")),
+                   escape(&blink.title),
+                   escape(&t_args(lang, "blink.amounts", &[("amounts", &amounts)])),
+                   escape(&t(lang, "blink.your_blink")),
+                   escape_code(&generator.dial_to_url(&blink.blink_id, Some("telegram"))),
+                   escape(&t(lang, "blink.action_endpoint")),
+                   escape_code(&generator.action_url(&blink.blink_id)),
+                   escape(&t(lang, "blink.signing_note"))))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
             .await?;
         
@@ -1499,19 +1212,17 @@ Happy trading\\! 🚀"#;
     pub async fn handle_leaderboard(
         bot: Bot,
         msg: Message,
-        db: Arc<Database>,
+        leaderboard_manager: Arc<LeaderboardManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
-        use crate::trading::{LeaderboardManager, LeaderboardPeriod, LeaderboardMetric};
+        use crate::trading::{LeaderboardPeriod, LeaderboardMetric};
         use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
         
         let user_id = msg.from().map(|u| u.id.0 as i64).unwrap_or(0);
         
-        bot.send_message(msg.chat.id, "📊 Loading leaderboard...")
+        bot.send_message(msg.chat.id, t(lang, "leaderboard.loading"))
             .await?;
         
-        // Create leaderboard manager
-        let leaderboard_manager = LeaderboardManager::new(db.clone());
-        
         // Get weekly leaderboard by default
         match leaderboard_manager.get_leaderboard(
             LeaderboardPeriod::Weekly,
@@ -1530,31 +1241,23 @@ Happy trading\\! 🚀"#;
                 );
                 
                 // Add statistics section
-                if !entries.is_empty() {
-                    let total_volume: f64 = entries.iter().map(|e| e.volume_sol).sum();
-                    let avg_win_rate = entries.iter().map(|e| e.win_rate).sum::<f64>() / entries.len() as f64;
-                    
-                    message.push_str(&format!(
-                        "\n\n📈 **Market Stats**\n\
-                        Total Volume: {:.1} SOL\n\
-                        Avg Win Rate: {:.1}%\n\
-                        Top Profit: +{:.1}%\n",
-                        total_volume,
-                        avg_win_rate,
-                        entries[0].profit_percent
-                    ));
+                match leaderboard_manager.format_market_stats(LeaderboardPeriod::Weekly).await {
+                    Ok(stats) => message.push_str(&stats),
+                    Err(e) => error!("Failed to build market stats: {}", e),
                 }
                 
                 // Add copyable traders
                 match leaderboard_manager.get_copyable_traders(3).await {
                     Ok(copyable) => {
                         if !copyable.is_empty() {
-                            message.push_str("\n🔄 **Available for Copy Trading:**\n");
+                            message.push_str(&format!("\n🔄 {}\n", t(lang, "leaderboard.copyable_title")));
                             for trader in copyable {
                                 message.push_str(&format!(
-                                    "• {} ({}% fee) - /copy_{}\n",
-                                    trader.username,
-                                    trader.copy_fee_percent,
+                                    "• {} - /copy_{}\n",
+                                    t_args(lang, "leaderboard.copyable_trader", &[
+                                        ("name", &trader.username),
+                                        ("fee", &fmt_number(lang, trader.copy_fee_percent, NumberKind::Percent(1))),
+                                    ]),
                                     trader.user_id
                                 ));
                             }
@@ -1563,53 +1266,32 @@ Happy trading\\! 🚀"#;
                     Err(_) => {}
                 }
                 
-                // Escape special characters for Markdown
-                let escaped_message = message
-                    .replace(".", "\\.")
-                    .replace("-", "\\-")
-                    .replace("(", "\\(")
-                    .replace(")", "\\)")
-                    .replace("+", "\\+")
-                    .replace("_", "\\_")
-                    .replace("*", "\\*")
-                    .replace("[", "\\[")
-                    .replace("]", "\\]")
-                    .replace("`", "\\`")
-                    .replace("#", "\\#")
-                    .replace("|", "\\|")
-                    .replace("{", "\\{")
-                    .replace("}", "\\}")
-                    .replace("=", "\\=")
-                    .replace(">", "\\>")
-                    .replace("!", "\\!")
-                    .replace("~", "\\~");
-                
                 // Create inline keyboard for period selection
                 let keyboard = InlineKeyboardMarkup::new(vec![
                     vec![
-                        InlineKeyboardButton::callback("📅 Daily", "leaderboard_daily"),
-                        InlineKeyboardButton::callback("📆 Weekly", "leaderboard_weekly"),
-                        InlineKeyboardButton::callback("📊 Monthly", "leaderboard_monthly"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.leaderboard_daily"), "leaderboard_daily"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.leaderboard_weekly"), "leaderboard_weekly"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.leaderboard_monthly"), "leaderboard_monthly"),
                     ],
                     vec![
-                        InlineKeyboardButton::callback("💰 By Profit", "leaderboard_profit"),
-                        InlineKeyboardButton::callback("📊 By Volume", "leaderboard_volume"),
-                        InlineKeyboardButton::callback("🎯 By Win Rate", "leaderboard_winrate"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.leaderboard_profit"), "leaderboard_profit"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.leaderboard_volume"), "leaderboard_volume"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.leaderboard_winrate"), "leaderboard_winrate"),
                     ],
                     vec![
-                        InlineKeyboardButton::callback("🔄 Refresh", "leaderboard_refresh"),
-                        InlineKeyboardButton::callback("📈 My Stats", "leaderboard_mystats"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.refresh"), "leaderboard_refresh"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.leaderboard_mystats"), "leaderboard_mystats"),
                     ],
                 ]);
                 
-                bot.send_message(msg.chat.id, escaped_message)
+                bot.send_message(msg.chat.id, escape(&message))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .reply_markup(keyboard)
                     .await?;
             }
             Err(e) => {
-                bot.send_message(msg.chat.id, 
-                    format!("❌ Failed to load leaderboard: {}", e))
+                error!("Leaderboard failed to load: {}", e);
+                bot.send_message(msg.chat.id, t(lang, "leaderboard.failed"))
                     .await?;
             }
         }
@@ -1622,27 +1304,30 @@ Happy trading\\! 🚀"#;
         bot: Bot,
         msg: Message,
         ai_analyzer: Arc<GroqAnalyzer>,
+        services: Arc<BotServices>,
     ) -> ResponseResult<()> {
         use crate::ai::{SignalGenerator, SignalType};
         use crate::market::aggregator::MarketDataAggregator;
         
-        bot.send_message(msg.chat.id, "🔮 Generating AI trading signals...")
+        let lang = services.preferences.language(msg.from().map(|u| u.id.0 as i64).unwrap_or_default(), msg.from()).await;
+        let lang = lang.as_str();
+        
+        bot.send_message(msg.chat.id, t(lang, "signals.generating"))
             .await?;
         
         // Create signal generator
         let market_aggregator = Arc::new(MarketDataAggregator::new()?);
-        let signal_generator = SignalGenerator::new(market_aggregator, ai_analyzer);
+        let signal_generator = SignalGenerator::new(market_aggregator, ai_analyzer)
+            .with_outcomes(services.signal_outcomes.clone());
         
         // Generate signals
         match signal_generator.generate_signals(5).await {
             Ok(signals) => {
                 if signals.is_empty() {
-                    bot.send_message(msg.chat.id, 
-                        "📊 No high-confidence signals available at the moment.\n\
-                        Market conditions are neutral. Check back in 15 minutes.")
+                    bot.send_message(msg.chat.id, t(lang, "signals.none"))
                         .await?;
                 } else {
-                    let mut message = String::from("🤖 **AI Trading Signals**\n\n");
+                    let mut message = format!("🤖 *{}*\n\n", escape(&t(lang, "signals.title")));
                     
                     for (i, signal) in signals.iter().enumerate() {
                         let signal_emoji = match signal.signal_type {
@@ -1653,112 +1338,87 @@ Happy trading\\! 🚀"#;
                             SignalType::StrongSell => "🔻",
                         };
                         
-                        message.push_str(&format!(
-                            "{} **{}** - {}\n",
-                            signal_emoji,
-                            signal.symbol.replace(".", "\\.").replace("-", "\\-"),
-                            match signal.signal_type {
-                                SignalType::StrongBuy => "STRONG BUY",
-                                SignalType::Buy => "BUY",
-                                SignalType::Accumulate => "ACCUMULATE",
-                                SignalType::Hold => "HOLD",
-                                SignalType::Distribute => "DISTRIBUTE",
-                                SignalType::Sell => "SELL",
-                                SignalType::StrongSell => "STRONG SELL",
-                            }
-                        ));
-                        
-                        message.push_str(&format!(
-                            "🎯 Confidence: {:.0}%\n",
-                            signal.confidence
-                        ));
-                        
-                        message.push_str(&format!(
-                            "💵 Entry: \\${:.6}\n",
-                            signal.entry_price
-                        ));
+                        let mut lines = vec![
+                            t_args(lang, "signals.confidence", &[("confidence", &fmt_number(lang, signal.confidence, NumberKind::Percent(0)))]),
+                            t_args(lang, "signals.entry", &[("price", &fmt_number(lang, signal.entry_price, NumberKind::Usd))]),
+                        ];
                         
                         if let Some(target) = signal.target_price {
                             let target_percent = ((target - signal.entry_price) / signal.entry_price) * 100.0;
-                            message.push_str(&format!(
-                                "🎯 Target: \\${:.6} \\({:+.1}%\\)\n",
-                                target, target_percent
-                            ));
+                            lines.push(t_args(lang, "signals.target", &[
+                                ("price", &fmt_number(lang, target, NumberKind::Usd)),
+                                ("change", &fmt_number(lang, target_percent, NumberKind::Change)),
+                            ]));
                         }
                         
                         if let Some(stop) = signal.stop_loss {
                             let stop_percent = ((stop - signal.entry_price) / signal.entry_price) * 100.0;
-                            message.push_str(&format!(
-                                "🛑 Stop: \\${:.6} \\({:.1}%\\)\n",
-                                stop, stop_percent
-                            ));
+                            lines.push(t_args(lang, "signals.stop", &[
+                                ("price", &fmt_number(lang, stop, NumberKind::Usd)),
+                                ("change", &fmt_number(lang, stop_percent, NumberKind::Change)),
+                            ]));
                         }
                         
                         if signal.risk_reward_ratio > 0.0 {
-                            message.push_str(&format!(
-                                "⚖️ R/R: 1:{:.1}\n",
-                                signal.risk_reward_ratio
-                            ));
+                            lines.push(t_args(lang, "signals.risk_reward", &[("ratio", &fmt_number(lang, signal.risk_reward_ratio, NumberKind::Decimal(1)))]));
                         }
                         
                         // Add first key factor from reasoning
-                        let reasoning = signal.reasoning
-                            .replace(".", "\\.")
-                            .replace("-", "\\-")
-                            .replace("(", "\\(")
-                            .replace(")", "\\)")
-                            .replace("+", "\\+");
-                        
-                        if let Some(first_sentence) = reasoning.split("\\. ").next() {
-                            message.push_str(&format!("💡 {}\n", first_sentence));
+                        if let Some(first_sentence) = signal.reasoning.split(". ").next() {
+                            lines.push(format!("💡 {}", first_sentence));
                         }
                         
-                        message.push_str("\n");
+                        message.push_str(&format!(
+                            "{} *{}* \\- {}\n{}\n\n",
+                            signal_emoji,
+                            escape(&signal.symbol),
+                            escape(&Self::signal_type_name(lang, &signal.signal_type)),
+                            escape(&lines.join("\n"))
+                        ));
                         
                         if i >= 4 {
                             break; // Limit to 5 signals
                         }
                     }
                     
-                    // Get performance stats
-                    let (success_rate, avg_return, total_signals) = 
-                        signal_generator.get_performance_stats().await?;
-                    
+                    // Track record from signals whose window has closed
+                    let stats = signal_generator.get_performance_stats().await?;
+                    let total = stats.total_signals.to_string();
+                    let mut record = if stats.overall.evaluated == 0 {
+                        t_args(lang, "signals.none_evaluated", &[("total", &total)])
+                    } else {
+                        t_args(lang, "signals.record", &[
+                            ("rate", &fmt_number(lang, stats.overall.success_rate(), NumberKind::Percent(1))),
+                            ("hits", &stats.overall.hit_target.to_string()),
+                            ("evaluated", &stats.overall.evaluated.to_string()),
+                            ("return", &fmt_number(lang, stats.overall.average_return(), NumberKind::Change)),
+                            ("total", &total),
+                        ])
+                    };
+                    for (signal_type, counts) in &stats.by_type {
+                        record.push('\n');
+                        record.push_str(&t_args(lang, "signals.record_by_type", &[
+                            ("type", &Self::signal_type_name(lang, signal_type)),
+                            ("rate", &fmt_number(lang, counts.success_rate(), NumberKind::Percent(0))),
+                            ("evaluated", &counts.evaluated.to_string()),
+                            ("return", &fmt_number(lang, counts.average_return(), NumberKind::Change)),
+                        ]));
+                    }
                     message.push_str(&format!(
-                        "📊 **Performance Stats:**\n\
-                        Success Rate: {:.1}%\n\
-                        Avg Return: {:+.1}%\n\
-                        Total Signals: {}\n\n",
-                        success_rate, avg_return, total_signals
+                        "📊 *{}*\n{}\n\n_{}_",
+                        escape(&t(lang, "signals.performance_title")),
+                        escape(&record),
+                        escape(&t(lang, "signals.footer"))
                     ));
                     
-                    message.push_str("_Signals update every 15 minutes_\n");
-                    message.push_str("_Use `/qbuy <amount> <symbol>` to execute_");
-                    
-                    // Escape special characters for Markdown
-                    let escaped_message = message
-                        .replace("_", "\\_")
-                        .replace("*", "\\*")
-                        .replace("[", "\\[")
-                        .replace("]", "\\]")
-                        .replace("`", "\\`")
-                        .replace("#", "\\#")
-                        .replace("|", "\\|")
-                        .replace("{", "\\{")
-                        .replace("}", "\\}")
-                        .replace("=", "\\=")
-                        .replace(">", "\\>")
-                        .replace("!", "\\!")
-                        .replace("~", "\\~");
-                    
-                    bot.send_message(msg.chat.id, escaped_message)
+                    bot.send_message(msg.chat.id, message)
                         .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                         .await?;
                 }
             }
             Err(e) => {
-                bot.send_message(msg.chat.id, 
-                    format!("❌ Failed to generate signals: {}", e))
+                error!("Signal generation failed: {}", e);
+                bot.send_message(msg.chat.id, t(lang, "signals.failed"))
                     .await?;
             }
         }
@@ -1766,37 +1426,48 @@ Happy trading\\! 🚀"#;
         Ok(())
     }
     
+    fn signal_type_name(lang: &str, signal_type: &crate::ai::SignalType) -> String {
+        use crate::ai::SignalType;
+        let key = match signal_type {
+            SignalType::StrongBuy => "signals.strong_buy",
+            SignalType::Buy => "signals.buy",
+            SignalType::Accumulate => "signals.accumulate",
+            SignalType::Hold => "signals.hold",
+            SignalType::Distribute => "signals.distribute",
+            SignalType::Sell => "signals.sell",
+            SignalType::StrongSell => "signals.strong_sell",
+        };
+        t(lang, key)
+    }
+    
     /// Handle /pump command
     pub async fn handle_pump(
         bot: Bot,
         msg: Message,
         args: String,
-        trading_engine: TradingEngineHandle,
+        pump: Option<Arc<PumpFunClient>>,
+        bonding: Arc<BondingTracker>,
         user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         let parts: Vec<&str> = args.split_whitespace().collect();
         
         if parts.is_empty() {
             let keyboard = InlineKeyboardMarkup::new(vec![
                 vec![
-                    InlineKeyboardButton::callback("🔥 Trending", "pump_trending"),
-                    InlineKeyboardButton::callback("🚀 Create Token", "pump_create"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.pump_trending"), "pump_trending"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.pump_create"), "pump_create"),
                 ],
                 vec![
-                    InlineKeyboardButton::callback("🔍 Browse All", "pump_browse"),
-                    InlineKeyboardButton::callback("💼 Portfolio", "pump_portfolio"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.pump_browse"), "pump_browse"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.portfolio"), "pump_portfolio"),
                 ],
             ]);
             
             bot.send_message(msg.chat.id, 
-                "🎪 *Pump\\.fun Integration*\\n\\n\
-                **Available Actions:**\\n\
-                🔥 `/pump trending` \\- Hot tokens now\\n\
-                🚀 `/pump create` \\- Launch new token\\n\
-                💸 `/pump buy <token>` \\- Buy pump token\\n\
-                💼 `/pump portfolio` \\- Your positions\\n\
-                🔍 `/pump search <name>` \\- Find tokens\\n\\n\
-                _Select an action below:_")
+                format!("🎪 *{}*\n\n{}",
+                    escape(&t(lang, "pump.menu_title")),
+                    escape(&t(lang, "pump.menu"))))
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                 .reply_markup(keyboard)
                 .await?;
@@ -1805,35 +1476,48 @@ Happy trading\\! 🚀"#;
         
         match parts[0] {
             "trending" => {
-                // Fetch real trending tokens from Pump.fun API
-                let trending_tokens = Self::fetch_pump_trending().await?;
+                let Some(pump) = pump else {
+                    bot.send_message(msg.chat.id, TradingHandler::unavailable_message("pumpfun", lang)).await?;
+                    return Ok(());
+                };
+                let trending_tokens = match pump.get_trending(10).await {
+                    Ok(tokens) => tokens,
+                    Err(e) => {
+                        bot.send_message(msg.chat.id, Self::pump_error_message(&e, lang)).await?;
+                        return Ok(());
+                    }
+                };
+                if trending_tokens.is_empty() {
+                    bot.send_message(msg.chat.id, t(lang, "pump.nothing_trending")).await?;
+                    return Ok(());
+                }
                 
-                let mut message = "🔥 *Trending on Pump\\.fun*\\n\\n".to_string();
+                let mut message = format!("🔥 *{}*\n\n", escape(&t(lang, "pump.trending_title")));
                 let mut buttons = vec![];
                 
-                for (i, token) in trending_tokens.iter().take(10).enumerate() {
+                for (i, token) in trending_tokens.iter().enumerate() {
                     message.push_str(&format!(
-                        "{}\\. *{}* \\({}\\)\\n\
-                        💰 MC: \\${}\\n\
-                        📈 24h: {}%\\n\
-                        🔄 Vol: \\${}\\n\\n",
+                        "{}\\. *{}* \\({}\\)\n{}\n\n",
                         i + 1,
-                        token.name.replace(".", "\\.").replace("-", "\\-"),
-                        token.symbol.replace(".", "\\."),
-                        format_market_cap(token.market_cap),
-                        if token.price_change_24h > 0.0 { format!("+{:.1}", token.price_change_24h) } else { format!("{:.1}", token.price_change_24h) },
-                        format_volume(token.volume_24h)
+                        escape(&token.name),
+                        escape(&token.symbol),
+                        escape(&t_args(lang, "pump.trending_token", &[
+                            ("market_cap", &format_market_cap(token.market_cap)),
+                            ("change", &fmt_number(lang, token.price_change_24h, NumberKind::Change)),
+                            ("volume", &format_volume(token.volume_24h)),
+                            ("curve", &fmt_number(lang, token.bonding_curve_progress, NumberKind::Percent(1))),
+                        ]))
                     ));
                     
                     if i < 3 {
                         buttons.push(InlineKeyboardButton::callback(
-                            format!("🚀 Buy {}", token.symbol),
+                            t_args(lang, "buttons.pump_buy", &[("symbol", &token.symbol)]),
                             format!("pump_buy_{}", token.address)
                         ));
                     }
                 }
                 
-                message.push_str("\\n_Tap to buy instantly or use `/pump buy <symbol>`_");
+                message.push_str(&format!("_{}_", escape(&t(lang, "pump.trending_footer"))));
                 
                 let keyboard = InlineKeyboardMarkup::new(vec![buttons]);
                 
@@ -1845,161 +1529,247 @@ Happy trading\\! 🚀"#;
             "create" => {
                 let keyboard = InlineKeyboardMarkup::new(vec![
                     vec![
-                        InlineKeyboardButton::callback("🎆 Meme Token", "pump_create_meme"),
-                        InlineKeyboardButton::callback("🤖 AI Token", "pump_create_ai"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.pump_create_meme"), "pump_create_meme"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.pump_create_ai"), "pump_create_ai"),
                     ],
                     vec![
-                        InlineKeyboardButton::callback("🎮 Gaming Token", "pump_create_gaming"),
-                        InlineKeyboardButton::callback("✨ Custom", "pump_create_custom"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.pump_create_gaming"), "pump_create_gaming"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.pump_create_custom"), "pump_create_custom"),
                     ],
                 ]);
                 
                 bot.send_message(msg.chat.id, 
-                    "🚀 *Create Token on Pump\\.fun*\\n\\n\
-                    **Quick Launch Options:**\\n\
-                    ✅ Automated bonding curve\\n\
-                    ✅ Instant liquidity\\n\
-                    ✅ Anti\\-rug mechanisms\\n\
-                    ✅ Social features\\n\\n\
-                    **Cost:** ~0\\.02 SOL\\n\\n\
-                    Select token type or reply with:\\n\
-                    `create <name> <symbol> <description>`\\n\\n\
-                    Example:\\n\
-                    `create \"Doge AI\" DOGEAI \"AI\\-powered meme token\"`")
+                    format!("🚀 *{}*\n\n{}",
+                        escape(&t(lang, "pump.create_title")),
+                        escape(&t(lang, "pump.create"))))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .reply_markup(keyboard)
                     .await?;
             }
             "buy" => {
                 if parts.len() < 2 {
-                    bot.send_message(msg.chat.id, 
-                        "❌ Usage: `/pump buy <token_symbol_or_address>`\\n\\n\
-                        Example: `/pump buy MEMECAT`")
-                        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                        .await?;
+                    bot.send_message(msg.chat.id, t(lang, "pump.buy_usage")).await?;
                     return Ok(());
                 }
                 
                 let token = parts[1];
+                
+                // Migrated tokens no longer trade on the curve
+                if bonding.progress(token).await.is_some_and(|c| c.migrated_at.is_some()) {
+                    bot.send_message(msg.chat.id, t_args(lang, "pump.migrated", &[("token", token)])).await?;
+                    return Ok(());
+                }
+                
                 let amount_sol = if parts.len() > 2 { 
                     parts[2].parse::<f64>().unwrap_or(0.1) 
                 } else { 
                     0.1 
                 };
                 
-                bot.send_message(msg.chat.id, 
-                    format!("⏳ *Buying {} on Pump\\.fun*\\n\\n\
-                           🪙 Token: {}\\n\
-                           💰 Amount: {} SOL\\n\\n\
-                           Checking bonding curve\\.\\.\\.",
-                           token.replace(".", "\\."),
-                           token.replace(".", "\\."),
-                           amount_sol))
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .await?;
-                
-                // Execute pump.fun buy through API
-                use crate::api::pump_fun::{PumpFunClient, BuyTokenRequest};
+                let Some(pump) = pump else {
+                    bot.send_message(msg.chat.id, TradingHandler::unavailable_message("pumpfun", lang)).await?;
+                    return Ok(());
+                };
                 
-                let pump_client = match PumpFunClient::new() {
-                    Ok(client) => client,
-                    Err(e) => {
-                        bot.send_message(msg.chat.id, 
-                            format!("❌ Failed to initialize Pump\\.fun client: {}", e))
-                            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                            .await?;
-                        return Ok(());
+                // Symbols go through search; addresses are used as given
+                let (token_address, symbol) = if Validator::validate_pubkey(token).is_ok() {
+                    (token.to_string(), token.to_string())
+                } else {
+                    match pump.search_tokens(token).await {
+                        Ok(found) => match found.into_iter().find(|t| t.symbol.eq_ignore_ascii_case(token)) {
+                            Some(found) => (found.address, found.symbol),
+                            None => {
+                                bot.send_message(msg.chat.id, t_args(lang, "pump.symbol_not_found", &[("symbol", token)]))
+                                    .await?;
+                                return Ok(());
+                            }
+                        },
+                        Err(e) => {
+                            bot.send_message(msg.chat.id, Self::pump_error_message(&e, lang)).await?;
+                            return Ok(());
+                        }
                     }
                 };
                 
+                bot.send_message(msg.chat.id, 
+                    format!("⏳ *{}*\n\n🪙 {} `{}`\n{}\n\n{}",
+                           escape(&t_args(lang, "pump.buying_title", &[("symbol", &symbol)])),
+                           escape(&t(lang, "pump.token_label")),
+                           escape_code(&token_address),
+                           escape(&t_args(lang, "pump.amount", &[("amount", &fmt_number(lang, amount_sol, NumberKind::Sol))])),
+                           escape(&t(lang, "pump.checking_curve"))))
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                    .await?;
+                
                 let buy_request = BuyTokenRequest {
-                    token_address: token.to_string(),
+                    token_address: token_address.clone(),
                     amount_sol,
                     slippage_bps: 300,
                     user_wallet: user_id.clone(),
                 };
                 
-                match pump_client.buy_token(buy_request).await {
-                    Ok(response) if response.success => {
-                        // Success handled below
-                    },
+                let fill = match pump.buy_token(buy_request).await {
+                    Ok(response) if response.success => response,
                     Ok(_) => {
-                        bot.send_message(msg.chat.id, 
-                            "❌ Token purchase failed on Pump\\.fun")
-                            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                            .await?;
+                        bot.send_message(msg.chat.id, t(lang, "pump.buy_rejected")).await?;
                         return Ok(());
                     },
                     Err(e) => {
-                        bot.send_message(msg.chat.id, 
-                            format!("❌ Failed to buy token: {}", e))
-                            .await?;
+                        let message = match e.downcast_ref::<BotError>() {
+                            Some(BotError::ServiceUnavailable { service }) => TradingHandler::unavailable_message(service, lang),
+                            _ => t_args(lang, "pump.buy_failed", &[("error", &BotError::user_message_for(&e))]),
+                        };
+                        bot.send_message(msg.chat.id, message).await?;
                         return Ok(());
                     }
                 };
                 
-                bot.send_message(msg.chat.id, 
-                    format!("✅ *Pump Buy Complete\\!*\\n\\n\
-                           🎆 Bought: 1,500,000 {}\\n\
-                           💵 Cost: {} SOL\\n\
-                           📈 Bonding: 15% filled\\n\
-                           🔗 View on pump\\.fun\\n\\n\
-                           _Token will migrate to Raydium at 100% bonding_",
-                           token.replace(".", "\\."),
-                           amount_sol))
+                // The fill stands on its own if the curve can't be read right after
+                let curve = pump.get_bonding_curve(&token_address).await
+                    .map_err(|e| warn!("Pump.fun curve read after buy failed: {}", e))
+                    .ok();
+                
+                bot.send_message(msg.chat.id, Self::format_pump_buy(&symbol, amount_sol, &fill, curve.as_ref(), lang))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
             "portfolio" => {
-                bot.send_message(msg.chat.id, 
-                    "💼 *Your Pump\\.fun Portfolio*\\n\\n\
-                    **Active Positions:**\\n\\n\
-                    1\\. MEMECAT \\- 2\\.5M tokens\\n\
-                       Entry: \\$0\\.000012\\n\
-                       Current: \\$0\\.000045 \\(\\+275%\\)\\n\
-                       Value: \\$112\\.50\\n\\n\
-                    2\\. DOGEAI \\- 500K tokens\\n\
-                       Entry: \\$0\\.00008\\n\
-                       Current: \\$0\\.00007 \\(\\-12\\.5%\\)\\n\
-                       Value: \\$35\\.00\\n\\n\
-                    **Created Tokens:**\\n\
-                    • MYTOKEN \\- 85% bonding complete\\n\\n\
-                    Total P&L: \\+\\$97\\.50 \\(\\+194%\\)")
+                let Ok(telegram_id) = user_id.parse::<i64>() else {
+                    bot.send_message(msg.chat.id, t(lang, "errors.invalid_session")).await?;
+                    return Ok(());
+                };
+                
+                let title = format!("💼 *{}*\n\n", escape(&t(lang, "pump.portfolio_title")));
+                let curves = bonding.user_curves(telegram_id).await;
+                if curves.is_empty() {
+                    bot.send_message(msg.chat.id, format!("{}{}", title, escape(&t(lang, "pump.portfolio_empty"))))
+                        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                        .await?;
+                    return Ok(());
+                }
+                
+                let now = chrono::Utc::now();
+                let mut message = title;
+                for curve in &curves {
+                    message.push_str(&format!(
+                        "*{}*\n{}\n{}\n\n",
+                        escape(&curve.symbol),
+                        escape(&BondingTracker::describe(curve, now)),
+                        escape(&t_args(lang, "pump.venue", &[("venue", bonding.venue(&curve.mint).await.label())]))
+                    ));
+                }
+                
+                bot.send_message(msg.chat.id, message)
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
+            "watch" | "unwatch" => {
+                let (Some(mint), Ok(telegram_id)) = (parts.get(1), user_id.parse::<i64>()) else {
+                    bot.send_message(msg.chat.id, t_args(lang, "pump.watch_usage", &[("action", parts[0])])).await?;
+                    return Ok(());
+                };
+                
+                if Validator::validate_pubkey(mint).is_err() {
+                    bot.send_message(msg.chat.id, t(lang, "pump.invalid_mint")).await?;
+                    return Ok(());
+                }
+                
+                let reply = if parts[0] == "watch" {
+                    bonding.watch(telegram_id, mint).await;
+                    t_args(lang, "pump.watching", &[("mint", mint)])
+                } else if bonding.unwatch(telegram_id, mint).await {
+                    t_args(lang, "pump.unwatched", &[("mint", mint)])
+                } else {
+                    t_args(lang, "pump.not_watching", &[("mint", mint)])
+                };
+                bot.send_message(msg.chat.id, reply).await?;
+            }
             "search" => {
                 if parts.len() < 2 {
-                    bot.send_message(msg.chat.id, 
-                        "❌ Usage: `/pump search <name>`\\n\\n\
-                        Example: `/pump search doge`")
-                        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                        .await?;
+                    bot.send_message(msg.chat.id, t(lang, "pump.search_usage")).await?;
                     return Ok(());
                 }
                 
+                let Some(pump) = pump else {
+                    bot.send_message(msg.chat.id, TradingHandler::unavailable_message("pumpfun", lang)).await?;
+                    return Ok(());
+                };
+                
                 let search_term = parts[1..].join(" ");
-                bot.send_message(msg.chat.id, 
-                    format!("🔍 *Searching Pump\\.fun for '{}'*\\n\\n\
-                           Found 3 matches:\\n\\n\
-                           1\\. DOGE2024 \\- \\$45K MC\\n\
-                           2\\. DOGECOIN2 \\- \\$12K MC\\n\
-                           3\\. SUPERDOGE \\- \\$8K MC\\n\\n\
-                           Use `/pump buy <symbol>` to purchase",
-                           search_term.replace(".", "\\.")))
+                let found = match pump.search_tokens(&search_term).await {
+                    Ok(found) => found,
+                    Err(e) => {
+                        bot.send_message(msg.chat.id, Self::pump_error_message(&e, lang)).await?;
+                        return Ok(());
+                    }
+                };
+                if found.is_empty() {
+                    bot.send_message(msg.chat.id, t_args(lang, "pump.no_matches", &[("query", &search_term)])).await?;
+                    return Ok(());
+                }
+                
+                let mut message = format!("🔍 *{}*\n\n{}\n\n",
+                    escape(&t_args(lang, "pump.search_title", &[("query", &search_term)])),
+                    escape(&t_args(lang, "pump.matches", &[("count", &found.len().to_string())])));
+                for (i, token) in found.iter().take(10).enumerate() {
+                    message.push_str(&format!(
+                        "{}\\. {}\n",
+                        i + 1,
+                        escape(&t_args(lang, "pump.search_result", &[
+                            ("symbol", &token.symbol),
+                            ("market_cap", &format_market_cap(token.market_cap)),
+                            ("curve", &fmt_number(lang, token.bonding_curve_progress, NumberKind::Percent(1))),
+                        ]))
+                    ));
+                }
+                message.push_str(&format!("\n{}", escape(&t(lang, "pump.search_footer"))));
+                
+                bot.send_message(msg.chat.id, message)
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
             _ => {
-                bot.send_message(msg.chat.id, 
-                    "❌ Unknown pump command\\. Use `/pump` for help\\.")
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "pump.unknown")).await?;
             }
         }
         
         Ok(())
     }
     
+    /// Reply for a failed Pump.fun read; an open breaker gets the shared notice
+    fn pump_error_message(e: &anyhow::Error, lang: &str) -> String {
+        match e.downcast_ref::<BotError>() {
+            Some(BotError::ServiceUnavailable { service }) => TradingHandler::unavailable_message(service, lang),
+            _ => {
+                warn!("Pump.fun request failed: {}", e);
+                t(lang, "pump.not_answering")
+            }
+        }
+    }
+    
+    /// Pump.fun buy receipt from the actual fill (MarkdownV2)
+    pub fn format_pump_buy(symbol: &str, amount_sol: f64, fill: &BuyTokenResponse, curve: Option<&BondingCurve>, lang: &str) -> String {
+        let mut lines = vec![
+            t_args(lang, "pump.bought", &[("amount", &format_token_amount(fill.tokens_received)), ("symbol", symbol)]),
+            t_args(lang, "pump.cost", &[("amount", &amount_sol.to_string())]),
+            t_args(lang, "pump.price", &[("price", &format_sol(fill.price_per_token))]),
+            t_args(lang, "pump.impact", &[("impact", &fmt_number(lang, fill.price_impact, NumberKind::Percent(2)))]),
+        ];
+        if let Some(curve) = curve {
+            lines.push(t_args(lang, "pump.bonding", &[
+                ("progress", &fmt_number(lang, curve.progress_pct(), NumberKind::Percent(1))),
+                ("sol", &fmt_number(lang, curve.sol_in_curve(), NumberKind::Decimal(2))),
+            ]));
+        }
+        format!(
+            "✅ *{}*\n\n{}\n🔗 `{}`\n\n_{}_",
+            escape(&t(lang, "pump.bought_title")),
+            escape(&lines.join("\n")),
+            escape_code(&fill.transaction_hash),
+            escape(&t(lang, "pump.migrate_note"))
+        )
+    }
+    
     /// Handle /qbuy command - Quick buy
     pub async fn handle_quick_buy(
         bot: Bot,
@@ -2008,10 +1778,14 @@ Happy trading\\! 🚀"#;
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
         user_id: String,
+        services: Arc<BotServices>,
     ) -> ResponseResult<()> {
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        let lang = lang.as_str();
+        
         // Validate user ID
         if let Err(e) = Validator::validate_user_id(&user_id) {
-            bot.send_message(msg.chat.id, format!("❌ Invalid user: {}", e))
+            bot.send_message(msg.chat.id, t_args(lang, "errors.invalid_user", &[("error", &e.to_string())]))
                 .await?;
             return Ok(());
         }
@@ -2020,7 +1794,7 @@ Happy trading\\! 🚀"#;
         let sanitized_args = match Validator::sanitize_command_args(&args) {
             Ok(s) => s,
             Err(e) => {
-                bot.send_message(msg.chat.id, format!("❌ Invalid input: {}", e))
+                bot.send_message(msg.chat.id, t_args(lang, "errors.invalid_input", &[("error", &e.to_string())]))
                     .await?;
                 return Ok(());
             }
@@ -2028,40 +1802,31 @@ Happy trading\\! 🚀"#;
         
         let parts: Vec<&str> = sanitized_args.split_whitespace().collect();
         if parts.is_empty() {
-            bot.send_message(msg.chat.id, 
-                "❌ Usage: `/qbuy <amount_sol> [token_symbol]`\\n\\n\
-                Examples:\\n\
-                • `/qbuy 0.1` \\- Choose from trending tokens\\n\
-                • `/qbuy 0.1 BONK` \\- Buy BONK directly")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
+            bot.send_message(msg.chat.id, t(lang, "quick_buy.usage")).await?;
             return Ok(());
         }
         
-        // Validate amount
+        let settings = services.preferences.get(user_id.parse().unwrap_or_default()).await;
+        
+        // Validate amount against the user's own cap
         let amount_sol = match parts[0].parse::<f64>() {
             Ok(amount) => {
-                if let Err(e) = Validator::validate_trade_amount(amount, 5.0) {
-                    bot.send_message(msg.chat.id, 
-                        format!("❌ {}", e))
+                if let Err(e) = Validator::validate_trade_amount(amount, settings.max_trade_sol) {
+                    bot.send_message(msg.chat.id, t_args(lang, "errors.invalid_amount_reason", &[("error", &e.to_string())]))
                         .await?;
                     return Ok(());
                 }
                 amount
             },
             _ => {
-                bot.send_message(msg.chat.id, 
-                    "❌ Invalid amount\\. Please use a valid number")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "errors.invalid_amount")).await?;
                 return Ok(());
             }
         };
         
-        // If token symbol provided, execute direct buy
+        // If a token is given, execute direct buy; mints are case-sensitive, so pass it as typed
         if parts.len() > 1 {
-            let token_symbol = parts[1].to_uppercase();
-            return Self::execute_quick_buy_direct(bot, msg, &token_symbol, amount_sol, &user_id, trading_engine, wallet_manager).await;
+            return Self::execute_quick_buy_direct(bot, msg, parts[1], amount_sol, &user_id, settings.trade_defaults(), trading_engine, wallet_manager, services, lang).await;
         }
         
         // Otherwise show trending token menu
@@ -2073,8 +1838,8 @@ Happy trading\\! 🚀"#;
             let mut row = Vec::new();
             for token in chunk {
                 row.push(InlineKeyboardButton::callback(
-                    &format!("{} ({:.1}%)", token.symbol, token.price_change_24h),
-                    &format!("qbuy_{}_{}", token.symbol.to_lowercase(), amount_sol)
+                    format!("{} ({})", token.symbol, fmt_number(lang, token.price_change_24h, NumberKind::Percent(1))),
+                    CallbackAction::quick_buy(token.symbol.as_str(), amount_sol).to_data()
                 ));
             }
             keyboard_rows.push(row);
@@ -2083,25 +1848,25 @@ Happy trading\\! 🚀"#;
         // Add custom token option
         keyboard_rows.push(vec![
             InlineKeyboardButton::callback(
-                "🔍 Custom Token", 
-                &format!("qbuy_custom_{}", amount_sol)
+                t(lang, "buttons.custom_token"), 
+                CallbackAction::ChooseQuickBuyToken { amount_sol }.to_data()
             )
         ]);
         
         let quick_buy_menu = InlineKeyboardMarkup::new(keyboard_rows);
         
+        let trending = trending_tokens.iter()
+            .take(4)
+            .map(|t| format!("• {} {}", t.symbol, fmt_number(lang, t.price_change_24h, NumberKind::Percent(1))))
+            .collect::<Vec<_>>()
+            .join("\n");
         bot.send_message(msg.chat.id, 
-            format!("⚡ *Quick Buy with {} SOL*\\n\\n\
-                   📈 **Trending Now:**\\n{}\\n\\n\
-                   Choose a token to buy instantly:\\n\\n\
-                   _All purchases include MEV protection_", 
-                   amount_sol,
-                   trending_tokens.iter()
-                       .take(4)
-                       .map(|t| format!("• {} {:.1}%", t.symbol, t.price_change_24h))
-                       .collect::<Vec<_>>()
-                       .join("\\n")
-                   ))
+            format!("⚡ *{}*\n\n📈 *{}*\n{}\n\n{}\n\n_{}_", 
+                   escape(&t_args(lang, "quick_buy.menu_title", &[("amount", &fmt_number(lang, amount_sol, NumberKind::Sol))])),
+                   escape(&t(lang, "quick_buy.trending_now")),
+                   escape(&trending),
+                   escape(&t(lang, "quick_buy.choose_token")),
+                   escape(&t(lang, "quick_buy.mev_note"))))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
             .reply_markup(quick_buy_menu)
             .await?;
@@ -2116,57 +1881,63 @@ Happy trading\\! 🚀"#;
         token_symbol: &str,
         amount_sol: f64,
         user_id: &str,
+        defaults: TradeDefaults,
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+        lang: &str,
     ) -> ResponseResult<()> {
-        // Get token address from symbol
-        let token_address = match Self::resolve_token_symbol(token_symbol).await {
-            Ok(addr) => addr,
-            Err(_) => {
+        // Resolve the symbol, name or mint against the token list
+        let lookup = services.token_resolver.lookup(token_symbol).await;
+        let token = match lookup {
+            TokenLookup::Found(token) => token,
+            TokenLookup::NotFound => {
                 bot.send_message(msg.chat.id, 
-                    format!("❌ Unknown token: {}\\n\\n\
-                           Use `/qbuy {} <token_address>` for custom tokens", 
-                           token_symbol, amount_sol))
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                    format!("{}\n\n{}",
+                           t_args(lang, "errors.unknown_token", &[("token", token_symbol)]),
+                           t_args(lang, "quick_buy.custom_hint", &[("amount", &amount_sol.to_string())])))
                     .await?;
                 return Ok(());
             }
+            ambiguous => {
+                let prompt = ambiguous.prompt(token_symbol, &format!("/qbuy {}", amount_sol)).unwrap_or_default();
+                bot.send_message(msg.chat.id, prompt).await?;
+                return Ok(());
+            }
         };
+        let token_address = token.mint.clone();
+        let token_symbol = token.symbol.as_str();
+        let amount = fmt_number(lang, amount_sol, NumberKind::Sol);
         
         bot.send_message(msg.chat.id, 
-            format!("⚡ *Executing Quick Buy*\\n\\n\
-                   Token: {}\\n\
-                   Amount: {} SOL\\n\
-                   Status: Processing\\.\\.\\.", 
-                   token_symbol, amount_sol))
+            format!("⚡ *{}*\n\n{}", 
+                   escape(&t(lang, "quick_buy.executing_title")),
+                   escape(&t_args(lang, "quick_buy.executing", &[("token", token_symbol), ("amount", &amount)]))))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
             .await?;
         
         // Execute the trade
-        match Self::execute_snipe_trade(&token_address, amount_sol, user_id, trading_engine, wallet_manager).await {
+        match Self::execute_snipe_trade(&token_address, amount_sol, user_id, Some(defaults), trading_engine, wallet_manager).await {
             Ok(trade_result) => {
                 bot.send_message(msg.chat.id, 
-                    format!("✅ *Quick Buy Complete\\!*\\n\\n\
-                           🎯 Bought: {} {}\\n\
-                           💰 Cost: {} SOL\\n\
-                           📈 Price: \\${:.6}\\n\
-                           🔄 TX: `{}`\\n\\n\
-                           _Check /portfolio for updated holdings_", 
-                           trade_result.tokens_received,
-                           token_symbol,
-                           amount_sol,
-                           trade_result.price,
-                           trade_result.tx_signature))
+                    format!("✅ *{}*\n\n{}\n🔄 TX: `{}`\n\n_{}_", 
+                           escape(&t(lang, "quick_buy.done_title")),
+                           escape(&t_args(lang, "quick_buy.done", &[
+                               ("tokens", &fmt_number(lang, trade_result.tokens_received, NumberKind::Token)),
+                               ("symbol", token_symbol),
+                               ("amount", &amount),
+                               ("price", &fmt_number(lang, trade_result.price, NumberKind::Usd)),
+                           ])),
+                           escape_code(&trade_result.tx_signature),
+                           escape(&t(lang, "common.check_portfolio"))))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
             Err(e) => {
                 bot.send_message(msg.chat.id, 
-                    format!("❌ *Quick Buy Failed*\\n\\n\
-                           Token: {}\\n\
-                           Error: {}\\n\\n\
-                           Your SOL was not spent\\.", 
-                           token_symbol, e))
+                    format!("❌ *{}*\n\n{}", 
+                           escape(&t(lang, "quick_buy.failed_title")),
+                           escape(&t_args(lang, "quick_buy.failed", &[("token", token_symbol), ("error", &e.user_message())]))))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
@@ -2175,9 +1946,10 @@ Happy trading\\! 🚀"#;
         Ok(())
     }
     
+    
     /// Get trending tokens from market data
     /// Fetch enhanced trending data with full market metrics
-    async fn fetch_enhanced_trending_data() -> Result<Vec<TrendingToken>> {
+    pub(crate) async fn fetch_enhanced_trending_data() -> Result<Vec<TrendingToken>> {
         use crate::market::MarketDataAggregator;
         
         // Try to fetch real market data
@@ -2269,61 +2041,8 @@ Happy trading\\! 🚀"#;
         Self::fetch_enhanced_trending_data().await
     }
     
-    /// Fetch trending tokens from Pump.fun
-    async fn fetch_pump_trending() -> Result<Vec<PumpToken>> {
-        // In production, this would call the actual Pump.fun API
-        // For now, return mock data
-        Ok(vec![
-            PumpToken {
-                name: "Meme Cat".to_string(),
-                symbol: "MEMECAT".to_string(),
-                address: "MCATxxx...xxx".to_string(),
-                market_cap: 47000.0,
-                price_change_24h: 890.0,
-                volume_24h: 125000.0,
-            },
-            PumpToken {
-                name: "Doge AI".to_string(),
-                symbol: "DOGEAI".to_string(),
-                address: "DAIxxx...xxx".to_string(),
-                market_cap: 23000.0,
-                price_change_24h: 340.0,
-                volume_24h: 89000.0,
-            },
-            PumpToken {
-                name: "Pepe 2024".to_string(),
-                symbol: "PEPE2024".to_string(),
-                address: "P24xxx...xxx".to_string(),
-                market_cap: 156000.0,
-                price_change_24h: 78.0,
-                volume_24h: 234000.0,
-            },
-        ])
-    }
-    
     // Formatting functions moved to utils::formatting module
     
-    /// Resolve token symbol to address
-    async fn resolve_token_symbol(symbol: &str) -> Result<String> {
-        // Common token addresses - in production this would query a token registry
-        let known_tokens = [
-            ("SOL", "So11111111111111111111111111111111111112"),
-            ("USDC", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
-            ("BONK", "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"),
-            ("WIF", "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm"),
-            ("POPCAT", "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr"),
-            ("PEPE", "BxHfGD8YMQVgpqH7A9bSVDRhE7bFQJ2T5xD3NJ4FKL9p"),
-        ];
-        
-        for (sym, addr) in &known_tokens {
-            if sym == &symbol {
-                return Ok(addr.to_string());
-            }
-        }
-        
-        Err(crate::errors::BotError::validation(format!("Unknown token symbol: {}", symbol)))
-    }
-    
     /// Handle /qsell command - Quick sell
     pub async fn handle_quick_sell(
         bot: Bot,
@@ -2332,44 +2051,33 @@ Happy trading\\! 🚀"#;
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
         user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         let parts: Vec<&str> = args.split_whitespace().collect();
         if parts.is_empty() {
-            bot.send_message(msg.chat.id, 
-                "❌ Usage: `/qsell <percentage> [token_symbol]`\\n\\n\
-                Examples:\\n\
-                • `/qsell 50` \\- Choose from your holdings\\n\
-                • `/qsell 25 BONK` \\- Sell 25% of BONK")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
+            bot.send_message(msg.chat.id, t(lang, "quick_sell.usage")).await?;
             return Ok(());
         }
         
         let percentage = match parts[0].parse::<f64>() {
             Ok(p) if p > 0.0 && p <= 100.0 => p,
             _ => {
-                bot.send_message(msg.chat.id, 
-                    "❌ Invalid percentage\\. Please use 1 to 100")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "quick_sell.invalid_percentage")).await?;
                 return Ok(());
             }
         };
+        let percent = fmt_number(lang, percentage, NumberKind::Percent(0));
         
         // Check if user has a wallet
         let wallet_info = match wallet_manager.get_user_wallet(&user_id).await {
             Ok(Some(wallet)) => wallet,
             Ok(None) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ No wallet found\\. Please set up a wallet first with /wallet")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "errors.no_wallet")).await?;
                 return Ok(());
             }
             Err(e) => {
-                bot.send_message(msg.chat.id, 
-                    format!("❌ Wallet error: {}", e))
-                    .await?;
+                error!("Wallet lookup for {} failed: {}", user_id, e);
+                bot.send_message(msg.chat.id, t(lang, "errors.wallet_access")).await?;
                 return Ok(());
             }
         };
@@ -2379,11 +2087,9 @@ Happy trading\\! 🚀"#;
             let token_symbol = parts[1].to_uppercase();
             
             bot.send_message(msg.chat.id, 
-                format!("⏳ *Processing Quick Sell*\\n\\n\
-                       🪙 Token: {}\\n\
-                       📊 Amount: {}%\\n\
-                       💰 Fetching your balance\\.\\.\\.", 
-                       token_symbol, percentage))
+                format!("⏳ *{}*\n\n{}", 
+                       escape(&t(lang, "quick_sell.processing_title")),
+                       escape(&t_args(lang, "quick_sell.processing", &[("token", &token_symbol), ("percent", &percent)]))))
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                 .await?;
             
@@ -2391,27 +2097,24 @@ Happy trading\\! 🚀"#;
             match Self::execute_sell_trade(&token_symbol, percentage, &user_id, trading_engine, wallet_manager).await {
                 Ok(trade_result) => {
                     bot.send_message(msg.chat.id, 
-                        format!("✅ *Quick Sell Complete\\!*\\n\\n\
-                               💰 Sold: {} {}\\n\
-                               💵 Received: {} SOL\\n\
-                               📈 Price: \\${:.6}\\n\
-                               🔄 TX: `{}`\\n\\n\
-                               _Check /portfolio for updated holdings_", 
-                               trade_result.tokens_received,
-                               token_symbol,
-                               trade_result.amount_sol,
-                               trade_result.price,
-                               trade_result.tx_signature))
+                        format!("✅ *{}*\n\n{}\n🔄 TX: `{}`\n\n_{}_", 
+                               escape(&t(lang, "quick_sell.done_title")),
+                               escape(&t_args(lang, "quick_sell.done", &[
+                                   ("tokens", &fmt_number(lang, trade_result.tokens_received, NumberKind::Token)),
+                                   ("symbol", &token_symbol),
+                                   ("amount", &fmt_number(lang, trade_result.amount_sol, NumberKind::Sol)),
+                                   ("price", &fmt_number(lang, trade_result.price, NumberKind::Usd)),
+                               ])),
+                               escape_code(&trade_result.tx_signature),
+                               escape(&t(lang, "common.check_portfolio"))))
                         .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                         .await?;
                 }
                 Err(e) => {
                     bot.send_message(msg.chat.id, 
-                        format!("❌ *Quick Sell Failed*\\n\\n\
-                               Token: {}\\n\
-                               Error: {}\\n\\n\
-                               Your tokens were not sold\\.", 
-                               token_symbol, e))
+                        format!("❌ *{}*\n\n{}", 
+                               escape(&t(lang, "quick_sell.failed_title")),
+                               escape(&t_args(lang, "quick_sell.failed", &[("token", &token_symbol), ("error", &e.user_message())]))))
                         .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                         .await?;
                 }
@@ -2421,32 +2124,33 @@ Happy trading\\! 🚀"#;
             let positions = Self::fetch_user_positions(&wallet_info.public_key, trading_engine.clone()).await?;
             
             if positions.is_empty() {
-                bot.send_message(msg.chat.id, 
-                    "❌ No token holdings found\\. Buy some tokens first\\!")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "quick_sell.no_holdings")).await?;
                 return Ok(());
             }
             
-            let mut holdings_text = format!("⚡ *Quick Sell {}%*\\n\\n**Your Holdings:**\\n", percentage);
+            let mut holdings_text = format!("⚡ *{}*\n\n*{}*\n",
+                escape(&t_args(lang, "quick_sell.menu_title", &[("percent", &percent)])),
+                escape(&t(lang, "quick_sell.holdings_title")));
             let mut buttons = vec![];
             
             for (i, pos) in positions.iter().take(5).enumerate() {
                 holdings_text.push_str(&format!(
-                    "{}\\. {}: {} tokens \\(\\${:.2}\\)\\n",
+                    "{}\\. {}\n",
                     i + 1,
-                    pos.symbol.replace(".", "\\."),
-                    pos.amount,
-                    pos.value_usd
+                    escape(&t_args(lang, "quick_sell.holding", &[
+                        ("symbol", &pos.symbol),
+                        ("amount", &fmt_number(lang, pos.amount, NumberKind::Token)),
+                        ("value", &fmt_number(lang, pos.value_usd, NumberKind::Usd)),
+                    ]))
                 ));
                 
                 buttons.push(InlineKeyboardButton::callback(
-                    format!("Sell {}% of {}", percentage, pos.symbol),
+                    t_args(lang, "buttons.sell_percent_of", &[("percent", &percent), ("symbol", &pos.symbol)]),
                     format!("qsell_{}_{}", percentage, pos.symbol)
                 ));
             }
             
-            holdings_text.push_str("\\n_Tap any token to sell instantly_");
+            holdings_text.push_str(&format!("\n_{}_", escape(&t(lang, "quick_sell.tap_to_sell"))));
             
             let keyboard = InlineKeyboardMarkup::new(vec![buttons]);
             
@@ -2466,92 +2170,97 @@ Happy trading\\! 🚀"#;
         args: String,
         db: Arc<Database>,
         user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         let parts: Vec<&str> = args.split_whitespace().collect();
         if parts.len() < 2 {
-            bot.send_message(msg.chat.id, 
-                "❌ Usage: `/stop <token> <percentage>`\\n\\n\
-                Example: `/stop BONK 20` \\(stop loss at \\-20%\\)")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
+            bot.send_message(msg.chat.id, t(lang, "stop_loss.usage")).await?;
             return Ok(());
         }
         
         let token = parts[0];
         let percentage = parts[1].parse::<f64>().unwrap_or(0.0);
+        let percent = fmt_number(lang, percentage, NumberKind::Percent(0));
         
         bot.send_message(msg.chat.id, 
-            format!("🛡️ *Stop Loss Set*\\n\\n\
-                   Token: {}\\n\
-                   Stop Loss: \\-{}%\\n\
-                   Status: ✅ Active\\n\\n\
-                   _Position will auto\\-sell if price drops {}%_", 
-                   token, percentage, percentage))
+            format!("🛡️ *{}*\n\n{}\n\n_{}_", 
+                   escape(&t(lang, "stop_loss.set_title")),
+                   escape(&t_args(lang, "stop_loss.set", &[("token", token), ("percent", &percent)])),
+                   escape(&t_args(lang, "stop_loss.note", &[("percent", &percent)]))))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
             .await?;
         
         Ok(())
     }
     
+    fn tip_strategy_label(lang: &str, strategy: &TipStrategy) -> String {
+        match strategy {
+            TipStrategy::Static { lamports } => t_args(lang, "mev.tip_static", &[
+                ("amount", &fmt_number(lang, *lamports as f64 / 1e9, NumberKind::Sol)),
+            ]),
+            TipStrategy::Percentile { percentile } => t_args(lang, "mev.tip_percentile", &[
+                ("percentile", &fmt_number(lang, *percentile, NumberKind::Decimal(0))),
+            ]),
+            TipStrategy::Aggressive { multiplier } => t_args(lang, "mev.tip_aggressive", &[
+                ("multiplier", &fmt_number(lang, *multiplier, NumberKind::Decimal(1))),
+            ]),
+        }
+    }
+    
     /// Handle /mev command - MEV protection settings and status
     pub async fn handle_mev(
         bot: Bot,
         msg: Message,
         args: String,
-        trading_engine: TradingEngineHandle,
+        services: Arc<BotServices>,
+        user_id: String,
     ) -> ResponseResult<()> {
-        use crate::mev::{MevConfig, TransactionPriority, MevProtection};
-        
         let parts: Vec<&str> = args.split_whitespace().collect();
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(DEFAULT_LANG, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+        let mev = &services.mev_protection;
+        let enabled = services.preferences.get(telegram_id).await.mev_protection;
+        let strategy = Self::tip_strategy_label(lang, &mev.config().strategy);
+        let timeout = mev.config().landing_timeout.as_secs().to_string();
+        let bundles = mev.stats();
+        let average_landing = bundles.average_landing_ms
+            .map(|ms| t_args(lang, "mev.milliseconds", &[("ms", &ms.to_string())]))
+            .unwrap_or_else(|| t(lang, "common.not_available"));
         
         if parts.is_empty() {
             // Show MEV protection menu
             let keyboard = InlineKeyboardMarkup::new(vec![
                 vec![
-                    InlineKeyboardButton::callback("🛡️ Enable Protection", "mev_enable"),
-                    InlineKeyboardButton::callback("⚡ Disable Protection", "mev_disable"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.mev_enable"), "mev_enable"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.mev_disable"), "mev_disable"),
                 ],
                 vec![
-                    InlineKeyboardButton::callback("📊 View Stats", "mev_stats"),
-                    InlineKeyboardButton::callback("⚙️ Settings", "mev_settings"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.mev_stats"), "mev_stats"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.settings"), "mev_settings"),
                 ],
                 vec![
-                    InlineKeyboardButton::callback("🔍 Simulate Attack", "mev_simulate"),
-                    InlineKeyboardButton::callback("📚 Learn More", "mev_help"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.mev_simulate"), "mev_simulate"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.learn_more"), "mev_help"),
                 ],
             ]);
             
-            let message = r#"🛡️ *MEV Protection Suite*
-
-Protect your trades from MEV attacks including:
-• 🥪 Sandwich attacks
-• 🏃 Front-running
-• 🔄 Back-running
-• 💧 JIT liquidity attacks
-
-*Current Status:* 🟢 Active
-*Strategy:* Jito Bundles
-*Success Rate:* 94.2%
-
-*Commands:*
-`/mev status` - Check protection status
-`/mev enable` - Enable MEV protection
-`/mev disable` - Disable protection
-`/mev stats` - View statistics
-`/mev simulate <tx>` - Simulate MEV attack
-
-Select an option below:"#;
+            let landing_rate = bundles.landing_rate()
+                .map(|rate| t_args(lang, "mev.landing_rate", &[
+                    ("rate", &fmt_number(lang, rate, NumberKind::Percent(1))),
+                    ("sent", &bundles.sent.to_string()),
+                ]))
+                .unwrap_or_else(|| t(lang, "mev.no_bundles_sent"));
+            let message = t_args(lang, "mev.menu", &[
+                ("status", &t(lang, if enabled { "mev.status_active" } else { "mev.status_off" })),
+                ("strategy", &strategy),
+                ("landed", &landing_rate),
+            ]);
             
-            bot.send_message(msg.chat.id, message
-                .replace(".", "\\.")
-                .replace("-", "\\-")
-                .replace("(", "\\(")
-                .replace(")", "\\)")
-                .replace("*", "\\*")
-                .replace("_", "\\_")
-                .replace("`", "\\`")
-                .replace("#", "\\#"))
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+            bot.send_message(msg.chat.id, message)
                 .reply_markup(keyboard)
                 .await?;
             
@@ -2560,120 +2269,86 @@ Select an option below:"#;
         
         match parts[0] {
             "status" => {
-                // Get MEV protection status
-                let config = MevConfig::default();
-                let protection = match MevProtection::new(config).await {
-                    Ok(p) => p,
-                    Err(e) => {
-                        bot.send_message(msg.chat.id, 
-                            format!("❌ Failed to initialize MEV protection: {}", e))
-                            .await?;
-                        return Ok(());
-                    }
-                };
-                
-                let (protection_stats, bundle_stats) = protection.get_stats().await;
-                
-                let message = format!(
-                    "🛡️ **MEV Protection Status**\n\n\
-                    **Protection Stats:**\n\
-                    • Total Protected: {}\n\
-                    • Threats Detected: {}\n\
-                    • MEV Saved: {} SOL\n\n\
-                    **Bundle Stats:**\n\
-                    • Bundles Sent: {}\n\
-                    • Success Rate: {:.1}%\n\
-                    • Avg Landing Time: {:.0}ms\n\n\
-                    **Jito Integration:** ✅ Connected\n\
-                    **Protection Level:** Maximum",
-                    protection_stats.total_protected,
-                    protection_stats.threats_detected,
-                    protection_stats.mev_saved_lamports as f64 / 1_000_000_000.0,
-                    bundle_stats.total_bundles_sent,
-                    bundle_stats.success_rate,
-                    bundle_stats.average_landing_time_ms
-                );
+                let message = t_args(lang, "mev.status", &[
+                    ("routing", &t(lang, if enabled { "mev.routing_jito" } else { "mev.routing_rpc" })),
+                    ("strategy", &strategy),
+                    ("timeout", &timeout),
+                    ("sent", &bundles.sent.to_string()),
+                    ("landed", &bundles.landed.to_string()),
+                    ("average", &average_landing),
+                ]);
                 
                 bot.send_message(msg.chat.id, message).await?;
             }
-            "enable" => {
-                bot.send_message(msg.chat.id, 
-                    "✅ MEV Protection enabled\n\n\
-                    All trades will now be protected using:\n\
-                    • Jito bundles with optimal tips\n\
-                    • Sandwich attack prevention\n\
-                    • Front-run protection\n\
-                    • Private mempool submission")
-                    .await?;
-            }
-            "disable" => {
-                bot.send_message(msg.chat.id, 
-                    "⚠️ MEV Protection disabled\n\n\
-                    Warning: Your trades are now vulnerable to:\n\
-                    • Sandwich attacks\n\
-                    • Front-running\n\
-                    • Back-running\n\n\
-                    Use `/mev enable` to re-enable protection.")
-                    .await?;
+            "enable" | "disable" => {
+                let enable = parts[0] == "enable";
+                let saved = services.preferences.update(telegram_id, |preferences| {
+                    preferences.mev_protection = enable;
+                    Ok(())
+                }).await;
+                if let Err(e) = saved {
+                    error!("Failed to save settings for {}: {}", telegram_id, e);
+                    bot.send_message(msg.chat.id, t(lang, "errors.settings_not_saved")).await?;
+                    return Ok(());
+                }
+                info!("MEV protection for {} set to {}", telegram_id, enable);
+                
+                let message = if enable {
+                    t_args(lang, "mev.enabled", &[("strategy", &strategy), ("timeout", &timeout)])
+                } else {
+                    t(lang, "mev.disabled")
+                };
+                bot.send_message(msg.chat.id, message).await?;
             }
             "stats" => {
-                let message = r#"📊 *MEV Protection Statistics*
-
-*Last 24 Hours:*
-• Protected Trades: 156
-• Threats Blocked: 42
-• MEV Saved: 2.34 SOL
-• Success Rate: 94.2%
-
-*Top Threats Blocked:*
-1. Sandwich Attacks: 28
-2. Front-runs: 11
-3. Back-runs: 3
-
-*Bundle Performance:*
-• Average Tip: 0.00001 SOL
-• Landing Rate: 94.2%
-• Avg Confirmation: 450ms
-
-*Cost Analysis:*
-• Total Tips Paid: 0.00156 SOL
-• MEV Saved: 2.34 SOL
-• Net Benefit: +2.33844 SOL"#;
+                let stats = services.sandwich_monitor.stats(telegram_id).await;
+                let sandwiches = if stats.inspected == 0 {
+                    t(lang, "mev.nothing_inspected")
+                } else {
+                    let last = stats.last_sandwiched_at
+                        .map(|at| fmt_datetime(lang, chrono_tz::UTC, at, DateStyle::DateTime))
+                        .unwrap_or_else(|| t(lang, "common.never"));
+                    t_args(lang, "mev.sandwich_stats", &[
+                        ("inspected", &stats.inspected.to_string()),
+                        ("sandwiched", &stats.sandwiched.to_string()),
+                        ("rate", &fmt_number(lang, stats.sandwich_rate(), NumberKind::Percent(1))),
+                        ("total", &fmt_number(lang, stats.total_loss_quote, NumberKind::Sol)),
+                        ("largest", &fmt_number(lang, stats.largest_loss_quote, NumberKind::Sol)),
+                        ("last", &last),
+                    ])
+                };
                 
-                bot.send_message(msg.chat.id, message
-                    .replace(".", "\\.")
-                    .replace("-", "\\-")
-                    .replace("+", "\\+")
-                    .replace("*", "\\*")
-                    .replace("_", "\\_"))
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .await?;
+                let bundle_lines = if bundles.sent == 0 && bundles.failed == 0 {
+                    t(lang, "mev.no_bundles")
+                } else {
+                    t_args(lang, "mev.bundle_stats", &[
+                        ("sent", &bundles.sent.to_string()),
+                        ("landed", &bundles.landed.to_string()),
+                        ("rate", &fmt_number(lang, bundles.landing_rate().unwrap_or(0.0), NumberKind::Percent(1))),
+                        ("failed", &bundles.failed.to_string()),
+                        ("timed_out", &bundles.timed_out.to_string()),
+                        ("fallbacks", &bundles.rpc_fallbacks.to_string()),
+                        ("tips", &fmt_number(lang, bundles.tips_paid_lamports as f64 / 1e9, NumberKind::Sol)),
+                        ("average", &average_landing),
+                    ])
+                };
+                
+                let message = format!(
+                    "📊 {}\n\n{}\n\n📦 {}\n{}",
+                    t(lang, "mev.stats_title"), sandwiches, t(lang, "mev.bundles_title"), bundle_lines
+                );
+                bot.send_message(msg.chat.id, message).await?;
             }
             "simulate" => {
                 if parts.len() < 2 {
-                    bot.send_message(msg.chat.id, 
-                        "❌ Usage: `/mev simulate <transaction_signature>`")
-                        .await?;
+                    bot.send_message(msg.chat.id, t(lang, "mev.simulate_usage")).await?;
                     return Ok(());
                 }
                 
-                bot.send_message(msg.chat.id, 
-                    "🔍 Simulating MEV attack vectors...\n\n\
-                    **Simulation Results:**\n\
-                    ⚠️ Vulnerable to sandwich attack\n\
-                    • Potential loss: 0.015 SOL\n\
-                    • Attack probability: 72%\n\n\
-                    **Recommended Protection:**\n\
-                    ✅ Use Jito bundles\n\
-                    ✅ Set slippage to 0.5%\n\
-                    ✅ Split large trades\n\n\
-                    Enable protection with `/mev enable`")
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "mev.simulation")).await?;
             }
             _ => {
-                bot.send_message(msg.chat.id, 
-                    "❌ Unknown MEV command. Use `/mev` to see options.")
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "mev.unknown")).await?;
             }
         }
         
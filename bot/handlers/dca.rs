@@ -0,0 +1,399 @@
+use chrono::Weekday;
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use teloxide::{prelude::*, types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message}};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use super::notices::NoticeHandler;
+use crate::bot::BotServices;
+use crate::errors::BotError;
+use crate::utils::{fmt_datetime, t, t_args, DateStyle};
+use crate::trading::{
+    parse_clock, CronSchedule, DCAEngine, DCAInterval, DCAScheduler, DCAStatus, DCAStrategy, ExecutionNotifier,
+    ScheduleConfig, TimeWindow, TokenResolver, Verbosity, USDC_MINT,
+};
+
+/// Runs of a `/dca new` strategy when no `x<runs>` is given
+pub const DEFAULT_DCA_RUNS: u32 = 30;
+
+/// /dca - create and list strategies in local time and set the user's timezone
+pub struct DcaHandler;
+
+/// A parsed `/dca new <amount> <TOKEN> [x<runs>] <when> [market hours | HH:MM-HH:MM]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewDca {
+    /// USDC per run
+    pub amount: Decimal,
+    pub token: String,
+    pub runs: u32,
+    pub schedule: CronSchedule,
+    /// Local hours runs are restricted to
+    pub window: Option<TimeWindow>,
+    pub market_hours_only: bool,
+}
+
+impl DcaHandler {
+    /// Deliver a report after each scheduled run to the chats its schedule selected
+    pub fn spawn_report_forwarder(bot: Bot, scheduler: Arc<DCAScheduler>) {
+        let mut receiver = scheduler.subscribe_executions();
+
+        tokio::spawn(async move {
+            while let Ok(event) = receiver.recv().await {
+                let text = event.render();
+                for chat_id in &event.recipients {
+                    if let Err(e) = bot.send_message(ChatId(*chat_id), text.clone()).await {
+                        error!("💰 Failed to deliver DCA report to {}: {}", chat_id, e);
+                    }
+                }
+            }
+        });
+    }
+
+    pub async fn handle_dca(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        dca_engine: Arc<DCAEngine>,
+        scheduler: Arc<DCAScheduler>,
+        notices: Arc<ExecutionNotifier>,
+        user_id: String,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let timezones = dca_engine.timezones();
+
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            [] | ["list"] => {
+                let strategies = dca_engine.get_user_strategies(telegram_id).await;
+                if strategies.is_empty() {
+                    bot.send_message(msg.chat.id, t(lang, "dca.none")).await?;
+                    return Ok(());
+                }
+
+                let tz = timezones.get_user_timezone(telegram_id).await;
+                let mut lines = Vec::new();
+                let mut buttons = Vec::new();
+                for strategy in &strategies {
+                    let (verbosity, _) = notices.effective(telegram_id, Some(&strategy.strategy_id), None).await;
+                    lines.push(Self::format_strategy(strategy, tz, verbosity, lang));
+                    buttons.push(vec![NoticeHandler::toggle_button("s", &strategy.strategy_id, &strategy.name, verbosity)]);
+                    if let Some(controls) = Self::control_buttons(strategy, lang) {
+                        buttons.push(controls);
+                    }
+                }
+                buttons.push(vec![
+                    InlineKeyboardButton::callback(t(lang, "buttons.pause_all"), "dca_pause"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.resume_all"), "dca_resume"),
+                ]);
+                bot.send_message(msg.chat.id, t_args(lang, "dca.list", &[
+                    ("timezone", tz.name()),
+                    ("strategies", &lines.join("\n\n")),
+                ]))
+                .reply_markup(InlineKeyboardMarkup::new(buttons))
+                .await?;
+            }
+            ["tz", timezone] => {
+                match timezones.set_user_timezone(telegram_id, timezone).await {
+                    Ok(tz) => {
+                        info!("💰 User {} set timezone {}", telegram_id, tz.name());
+                        bot.send_message(msg.chat.id, t_args(lang, "dca.timezone_set", &[("timezone", tz.name())])).await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                    }
+                }
+            }
+            ["new", rest @ ..] => match Self::parse_new(rest, lang) {
+                Ok(new) => Self::create(&bot, &msg, telegram_id, new, &dca_engine, &scheduler, lang).await?,
+                Err(e) => {
+                    bot.send_message(msg.chat.id, format!("❌ {}\n\n{}", e, t(lang, "dca.new_usage"))).await?;
+                }
+            },
+            _ => {
+                bot.send_message(msg.chat.id, t(lang, "dca.usage")).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read `/dca new` arguments; amounts are in USDC and times in the user's timezone
+    pub fn parse_new(args: &[&str], lang: &str) -> Result<NewDca, String> {
+        let [amount, token, rest @ ..] = args else {
+            return Err(t(lang, "dca.missing_args"));
+        };
+        let amount = Decimal::from_str(amount.trim_start_matches('$'))
+            .ok()
+            .filter(|amount| *amount > Decimal::ZERO)
+            .ok_or_else(|| t_args(lang, "dca.bad_amount", &[("amount", *amount)]))?;
+
+        let (runs, mut when) = match rest.split_first() {
+            Some((runs, when)) if runs.starts_with('x') => {
+                let runs = runs[1..].parse::<u32>().ok().filter(|runs| *runs > 0)
+                    .ok_or_else(|| t_args(lang, "dca.bad_runs", &[("runs", *runs)]))?;
+                (runs, when.to_vec())
+            }
+            _ => (DEFAULT_DCA_RUNS, rest.to_vec()),
+        };
+
+        let mut market_hours_only = false;
+        let mut window = None;
+        if when.len() >= 2 && when[when.len() - 2..].iter().map(|w| w.to_lowercase()).eq(["market", "hours"]) {
+            when.truncate(when.len() - 2);
+            market_hours_only = true;
+        } else if let Some((start, end)) = when.last()
+            // Clock times, so a day-of-week range like 1-5 stays part of the schedule
+            .filter(|last| last.contains(':') || last.to_lowercase().ends_with('m'))
+            .and_then(|last| last.split_once('-'))
+        {
+            if let (Ok(start_time), Ok(end_time)) = (parse_clock(start), parse_clock(end)) {
+                when.pop();
+                window = Some(TimeWindow {
+                    start_time,
+                    end_time,
+                    days_of_week: vec![
+                        Weekday::Mon, Weekday::Tue, Weekday::Wed,
+                        Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun,
+                    ],
+                });
+            }
+        }
+        // "... during market hours", "... only 08:00-22:00"
+        while when.last().is_some_and(|w| matches!(w.to_lowercase().as_str(), "during" | "only" | "between")) {
+            when.pop();
+        }
+
+        let schedule = CronSchedule::from_phrase(&when.join(" ")).map_err(|e| e.to_string())?;
+        Ok(NewDca {
+            amount,
+            token: token.to_uppercase(),
+            runs,
+            schedule,
+            window,
+            market_hours_only,
+        })
+    }
+
+    async fn create(
+        bot: &Bot,
+        msg: &Message,
+        telegram_id: i64,
+        new: NewDca,
+        engine: &Arc<DCAEngine>,
+        scheduler: &Arc<DCAScheduler>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let output_token = match TokenResolver::resolve(&new.token) {
+            Ok(mint) => mint,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", BotError::user_message_for(&e))).await?;
+                return Ok(());
+            }
+        };
+        let name = format!("{} {}", new.token, new.schedule.describe());
+        let mut strategy = DCAStrategy::create_daily_dca(
+            telegram_id,
+            name.clone(),
+            USDC_MINT.to_string(),
+            output_token,
+            new.amount * Decimal::from(new.runs),
+            new.amount,
+        );
+        strategy.interval = DCAInterval::Custom { cron_expression: new.schedule.expression().to_string() };
+
+        // Empty timezone: the schedule follows /dca tz
+        let mut schedule = ScheduleConfig::create_cron_schedule(
+            strategy.strategy_id.clone(),
+            name.clone(),
+            new.schedule.expression().to_string(),
+            Some(new.schedule.describe()),
+            String::new(),
+        );
+        schedule.schedule_id = strategy.strategy_id.clone();
+        schedule.max_executions = Some(new.runs as u64);
+        schedule.execution_window = new.window;
+        schedule.market_hours_only = new.market_hours_only;
+
+        // A schedule that can never run inside its hours is refused before anything is stored
+        let mut probe = schedule.clone();
+        probe.timezone = engine.timezones().get_user_timezone(telegram_id).await.name().to_string();
+        if let Err(e) = scheduler.next_run_after(&probe, chrono::Utc::now()).await {
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+            return Ok(());
+        }
+
+        let created = async {
+            engine.create_strategy(strategy).await?;
+            scheduler.add_schedule(schedule).await
+        };
+        let strategy_id = match created.await {
+            Ok(strategy_id) => strategy_id,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                return Ok(());
+            }
+        };
+
+        info!("💰 User {} created DCA strategy {} ({})", telegram_id, strategy_id, new.schedule.expression());
+        let next_run = scheduler.queued_executions().await
+            .into_iter()
+            .find(|execution| execution.strategy_id == strategy_id)
+            .map(|execution| execution.local_display())
+            .unwrap_or_else(|| t(lang, "dca.not_scheduled"));
+        let gate = match (&new.window, new.market_hours_only) {
+            (_, true) => format!("\n{}", t(lang, "dca.gate_market_hours")),
+            (Some(window), false) => format!("\n{}", t_args(lang, "dca.gate_window", &[
+                ("start", &window.start_time.format("%H:%M").to_string()),
+                ("end", &window.end_time.format("%H:%M").to_string()),
+            ])),
+            (None, false) => String::new(),
+        };
+        bot.send_message(msg.chat.id, t_args(lang, "dca.created", &[
+            ("name", &name),
+            ("amount", &new.amount.normalize().to_string()),
+            ("token", &new.token),
+            ("runs", &new.runs.to_string()),
+            ("gate", &gate),
+            ("next", &next_run),
+        ])).await?;
+        Ok(())
+    }
+
+    /// Pause All / Resume All (`dca_pause`, `dca_resume`) and per-strategy `dca:<pause|resume|skip|sizing>:<strategy_id>`
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let engine = &services.dca_engine;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+        let lang = lang.as_str();
+
+        let reply = match data {
+            "dca_pause" => match engine.pause_user_strategies(user_id).await {
+                Ok(0) => t(lang, "dca.none_to_pause"),
+                Ok(1) => t(lang, "dca.paused_one"),
+                Ok(count) => t_args(lang, "dca.paused_many", &[("count", &count.to_string())]),
+                Err(e) => {
+                    error!("Failed to pause DCA strategies for {}: {}", user_id, e);
+                    format!("❌ {}", e.user_message())
+                }
+            },
+            "dca_resume" => match engine.resume_user_strategies(user_id).await {
+                Ok(0) => t(lang, "dca.none_to_resume"),
+                Ok(1) => t(lang, "dca.resumed_one"),
+                Ok(count) => t_args(lang, "dca.resumed_many", &[("count", &count.to_string())]),
+                Err(e) => {
+                    error!("Failed to resume DCA strategies for {}: {}", user_id, e);
+                    format!("❌ {}", e.user_message())
+                }
+            },
+            _ => {
+                let mut parts = data.splitn(3, ':').skip(1);
+                let (Some(action), Some(strategy_id)) = (parts.next(), parts.next()) else {
+                    return Ok(());
+                };
+
+                // Only the owner may control a strategy
+                let owned = engine.get_user_strategies(user_id).await
+                    .into_iter()
+                    .any(|s| s.strategy_id == strategy_id);
+                if !owned {
+                    bot.send_message(msg.chat.id, t(lang, "dca.strategy_not_found")).await?;
+                    return Ok(());
+                }
+
+                let tz = engine.timezones().get_user_timezone(user_id).await;
+                let next_run = |s: &DCAStrategy| fmt_datetime(lang, tz, s.next_execution, DateStyle::DateTime);
+                let result = match action {
+                    "pause" => engine.pause_strategy(strategy_id).await
+                        .map(|s| t_args(lang, "dca.paused", &[("name", &s.name)])),
+                    "resume" => engine.resume_strategy(strategy_id).await
+                        .map(|s| t_args(lang, "dca.resumed", &[("name", &s.name), ("next", &next_run(&s))])),
+                    "skip" => engine.skip_next_execution(strategy_id).await
+                        .map(|s| t_args(lang, "dca.skipping", &[("name", &s.name), ("next", &next_run(&s))])),
+                    "sizing" => engine.toggle_dynamic_sizing(strategy_id).await
+                        .map(|s| match s.advanced_config.dynamic_sizing {
+                            true => t_args(lang, "dca.sizing_risk", &[
+                                ("name", &s.name),
+                                ("min", &s.advanced_config.min_size_multiplier.to_string()),
+                                ("max", &s.advanced_config.max_size_multiplier.to_string()),
+                                ("amount", &s.amount_per_execution.to_string()),
+                            ]),
+                            false => t_args(lang, "dca.sizing_fixed", &[
+                                ("name", &s.name),
+                                ("amount", &s.amount_per_execution.to_string()),
+                            ]),
+                        }),
+                    _ => return Ok(()),
+                };
+                result.unwrap_or_else(|e| format!("❌ {}", e.user_message()))
+            }
+        };
+
+        bot.send_message(msg.chat.id, reply).await?;
+        Ok(())
+    }
+
+    fn control_buttons(strategy: &DCAStrategy, lang: &str) -> Option<Vec<InlineKeyboardButton>> {
+        let toggle = match strategy.status {
+            DCAStatus::Active => InlineKeyboardButton::callback(t(lang, "buttons.pause"), format!("dca:pause:{}", strategy.strategy_id)),
+            DCAStatus::Paused => InlineKeyboardButton::callback(t(lang, "buttons.resume"), format!("dca:resume:{}", strategy.strategy_id)),
+            _ => return None,
+        };
+        Some(vec![
+            toggle,
+            InlineKeyboardButton::callback(t(lang, "buttons.skip_next"), format!("dca:skip:{}", strategy.strategy_id)),
+            InlineKeyboardButton::callback(
+                t(lang, if strategy.advanced_config.dynamic_sizing { "buttons.fixed_size" } else { "buttons.risk_sizing" }),
+                format!("dca:sizing:{}", strategy.strategy_id),
+            ),
+        ])
+    }
+
+    fn format_strategy(strategy: &DCAStrategy, tz: Tz, verbosity: Verbosity, lang: &str) -> String {
+        let interval = match &strategy.interval {
+            DCAInterval::Minutes(m) => t_args(lang, "dca.every_minutes", &[("minutes", &m.to_string())]),
+            DCAInterval::Hourly => t(lang, "dca.hourly"),
+            DCAInterval::Daily => t(lang, "dca.daily"),
+            DCAInterval::Weekly => t(lang, "dca.weekly"),
+            DCAInterval::Biweekly => t(lang, "dca.biweekly"),
+            DCAInterval::Monthly => t(lang, "dca.monthly"),
+            DCAInterval::Custom { cron_expression } => CronSchedule::parse(cron_expression)
+                .map(|schedule| schedule.describe())
+                .unwrap_or_else(|_| format!("cron {}", cron_expression)),
+        };
+        let anchor = strategy.anchor.as_ref()
+            .map(|anchor| format!(" {}", t_args(lang, "dca.at", &[("time", &anchor.time_of_day.format("%H:%M").to_string())])))
+            .unwrap_or_default();
+
+        let skip = if strategy.skip_next { format!(" ({})", t(lang, "dca.skipped")) } else { String::new() };
+        let sizing = match strategy.advanced_config.dynamic_sizing {
+            true => t_args(lang, "dca.risk_adjusted", &[
+                ("min", &strategy.advanced_config.min_size_multiplier.to_string()),
+                ("max", &strategy.advanced_config.max_size_multiplier.to_string()),
+            ]),
+            false => t(lang, "dca.fixed"),
+        };
+
+        t_args(lang, "dca.strategy", &[
+            ("name", &strategy.name),
+            ("status", &format!("{:?}", strategy.status)),
+            ("amount", &strategy.amount_per_execution.to_string()),
+            ("token", &TokenResolver::get_symbol(&strategy.output_token)),
+            ("interval", &format!("{}{}", interval, anchor)),
+            ("next", &format!("{}{}", fmt_datetime(lang, tz, strategy.next_execution, DateStyle::DateTime), skip)),
+            ("sizing", &sizing),
+            ("notifications", verbosity.badge()),
+        ])
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::Utc;
+use teloxide::{prelude::*, types::{InputFile, Message}};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::{
+    bot::{
+        data_deletion::{DeletionStatus, ErasureReport, NativeErasure},
+        settings_export::SettingsExport,
+        BotServices,
+    },
+    db::Database,
+    utils::{fmt_datetime, lang_of, t, t_args, DateStyle},
+    wallet::WalletManager,
+};
+
+/// How often due and interrupted erasures are picked up
+const ERASURE_INTERVAL_SECS: u64 = 10 * 60;
+
+/// /forgetme - self-serve data deletion
+pub struct ForgetHandler;
+
+impl ForgetHandler {
+    /// Handle /forgetme [confirm|cancel|status]
+    ///
+    /// Bare /forgetme sends the user's exports and explains what will go;
+    /// only `confirm` schedules the erasure.
+    pub async fn handle_forgetme(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let tz = services.dca_engine.timezones().get_user_timezone(telegram_id).await;
+        let deletion = services.data_deletion.clone();
+
+        match args.trim() {
+            "" => {
+                Self::send_exports(&bot, &msg, &services, telegram_id, &lang).await?;
+                bot.send_message(msg.chat.id, Self::explanation(&services, &lang)).await?;
+            }
+            "confirm" => {
+                let request = deletion.schedule(telegram_id, Utc::now()).await;
+                info!("🗑️ User {} confirmed /forgetme", telegram_id);
+                let text = match request.status {
+                    DeletionStatus::Scheduled => t_args(&lang, "forget.scheduled", &[
+                        ("at", &fmt_datetime(&lang, tz, request.execute_at, DateStyle::DateTime)),
+                    ]),
+                    DeletionStatus::InProgress => t(&lang, "forget.already_running"),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            "cancel" => {
+                let text = match deletion.cancel(telegram_id).await {
+                    Ok(()) => t(&lang, "forget.cancelled"),
+                    Err(e) => format!("❌ {}", e.user_message()),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            "status" => {
+                let text = match deletion.request(telegram_id).await {
+                    None => t(&lang, "forget.none_scheduled"),
+                    Some(request) if request.status == DeletionStatus::Scheduled => t_args(&lang, "forget.status_scheduled", &[
+                        ("at", &fmt_datetime(&lang, tz, request.execute_at, DateStyle::DateTime)),
+                    ]),
+                    Some(request) => t_args(&lang, "forget.in_progress", &[
+                        ("done", &request.completed.len().to_string()),
+                        ("total", &deletion.config().steps().len().to_string()),
+                    ]),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(&lang, "forget.usage")).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Settings and trade journal, so nothing is lost that the user wants to keep
+    async fn send_exports(bot: &Bot, msg: &Message, services: &BotServices, telegram_id: i64, lang: &str) -> ResponseResult<()> {
+        let export = SettingsExport::collect(services, telegram_id).await;
+        bot.send_document(msg.chat.id, InputFile::memory(export.to_json().into_bytes()).file_name("settings.json"))
+            .caption(t(lang, "settings.export_caption"))
+            .await?;
+
+        let csv = services.journal.export_csv(telegram_id).await;
+        bot.send_document(msg.chat.id, InputFile::memory(csv.into_bytes()).file_name("journal.csv"))
+            .caption(t(lang, "journal.export_caption"))
+            .await?;
+        Ok(())
+    }
+
+    fn explanation(services: &BotServices, lang: &str) -> String {
+        let config = services.data_deletion.config();
+        let mut retained: Vec<&str> = config.retained.iter().map(|r| r.label()).collect();
+        retained.sort();
+        let retained = if retained.is_empty() { t(lang, "forget.nothing_retained") } else { retained.join(", ") };
+
+        t_args(lang, "forget.explanation", &[
+            ("retained", &retained),
+            ("hours", &config.grace_period.num_hours().to_string()),
+        ])
+    }
+
+    /// Run due erasures, and resume interrupted ones, in the background
+    pub fn spawn_erasure_worker(
+        bot: Bot,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+        database: Arc<Database>,
+    ) {
+        tokio::spawn(async move {
+            let target = NativeErasure::new(services.clone(), wallet_manager, database);
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(ERASURE_INTERVAL_SECS));
+
+            loop {
+                // The first tick fires immediately, which resumes anything a restart interrupted
+                interval.tick().await;
+                for result in services.data_deletion.run_due(&target, Utc::now()).await {
+                    let Ok(report) = result else { continue };
+                    let lang = services.preferences.language(report.user_id, None).await;
+                    if let Err(e) = bot.send_message(ChatId(report.user_id), Self::report_text(&report, &lang)).await {
+                        warn!("🗑️ Failed to DM erasure result to {}: {}", report.user_id, e);
+                    }
+                }
+            }
+        });
+    }
+
+    fn report_text(report: &ErasureReport, lang: &str) -> String {
+        let mut text = t(lang, "forget.deleted");
+        text.push('\n');
+        for (step, count) in report.completed.iter().filter(|(_, count)| *count > 0) {
+            text.push_str(&format!("\n• {}: {}", step.label(), count));
+        }
+        if !report.retained.is_empty() {
+            let mut retained: Vec<&str> = report.retained.iter().map(|r| r.label()).collect();
+            retained.sort();
+            text.push_str("\n\n");
+            text.push_str(&t_args(lang, "forget.kept", &[("retained", &retained.join(", "))]));
+        }
+        text
+    }
+}
@@ -0,0 +1,316 @@
+use chrono::Utc;
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message, MessageId},
+};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::{
+    bot::{
+        group_buy::{
+            GroupBuy, GroupBuyCoordinator, GroupBuyParticipant, GuardDecision, JoinOutcome, MemberFill,
+            MemberOutcome, DEFAULT_MIN_PARTICIPANTS,
+        },
+        BotServices,
+    },
+    errors::{BotError, Result},
+    security::{LarpChecker, SecurityAnalysis},
+    trading::{TokenResolver, TradingEngineHandle},
+    utils::{fmt_number, lang_of, t, t_args, validation::ValidatedAmount, NumberKind},
+    wallet::WalletManager,
+};
+
+/// /groupbuy cards in group chats and the per-member execution behind them
+pub struct GroupBuyHandler;
+
+/// Executes a member's buy with their own wallet and guards
+struct MemberExecutor {
+    trading_engine: TradingEngineHandle,
+    wallet_manager: Arc<WalletManager>,
+}
+
+#[async_trait::async_trait]
+impl GroupBuyParticipant for MemberExecutor {
+    async fn check_guards(&self, user_id: i64, buy: &GroupBuy) -> GuardDecision {
+        let user = user_id.to_string();
+        if self.wallet_manager.is_trading_locked(&user).await {
+            return GuardDecision::Refuse("trading is locked after unexpected wallet activity".to_string());
+        }
+        match self.wallet_manager.get_user_wallet(&user).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return GuardDecision::Refuse("no wallet configured".to_string()),
+            Err(e) => return GuardDecision::Refuse(format!("wallet unavailable: {}", e)),
+        }
+        if let Err(e) = ValidatedAmount::new(buy.amount_each, crate::constants::MAX_TRADE_SOL) {
+            return GuardDecision::Refuse(e.to_string());
+        }
+        GuardDecision::Proceed
+    }
+
+    async fn execute_buy(&self, user_id: i64, buy: &GroupBuy) -> Result<MemberFill> {
+        let wallet = self.wallet_manager.get_user_wallet(&user_id.to_string()).await?
+            .ok_or_else(|| BotError::not_found("No wallet configured".to_string()))?;
+
+        let result = self.trading_engine
+            .buy_with_rebate(wallet.public_key, buy.mint.clone(), buy.amount_each)
+            .await?;
+        self.wallet_manager.record_originated(&result.tx_signature).await;
+
+        Ok(MemberFill {
+            amount_sol: buy.amount_each,
+            tokens_received: result.tokens_received,
+            price: result.price,
+            tx_signature: result.tx_signature,
+        })
+    }
+}
+
+impl GroupBuyHandler {
+    /// Handle /groupbuy <token> <sol_each> [min_participants]
+    pub async fn handle_group_buy(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(creator_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        // The card and its updates go out in the creator's language
+        let lang = services.preferences.language(creator_id, msg.from()).await;
+        if !(msg.chat.is_group() || msg.chat.is_supergroup()) {
+            bot.send_message(msg.chat.id, t(&lang, "group_buy.groups_only")).await?;
+            return Ok(());
+        }
+
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let (token, amount, min_participants) = match parts.as_slice() {
+            [token, amount] => (*token, amount.parse::<f64>().ok(), Some(DEFAULT_MIN_PARTICIPANTS)),
+            [token, amount, min] => (*token, amount.parse::<f64>().ok(), min.parse::<usize>().ok()),
+            _ => ("", None, None),
+        };
+        let (Some(amount), Some(min_participants)) = (amount, min_participants) else {
+            bot.send_message(msg.chat.id, t(&lang, "group_buy.usage")).await?;
+            return Ok(());
+        };
+
+        let mint = match TokenResolver::resolve(token) {
+            Ok(mint) => mint,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", BotError::user_message_for(&e))).await?;
+                return Ok(());
+            }
+        };
+        let symbol = TokenResolver::get_symbol(&mint);
+        let price = Self::current_price(&services, &mint).await;
+        let risk_badge = Self::risk_badge(&mint, &lang).await;
+
+        let buy = match services.group_buys.create(
+            msg.chat.id.0, creator_id, &mint, &symbol, amount, min_participants, price, risk_badge,
+        ).await {
+            Ok(buy) => buy,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                return Ok(());
+            }
+        };
+
+        let card = bot.send_message(msg.chat.id, buy.card_text())
+            .reply_markup(Self::card_keyboard(&buy.id, &lang))
+            .await?;
+        Self::spawn_expiry(bot, services.group_buys.clone(), buy.id, msg.chat.id, card.id, lang);
+        Ok(())
+    }
+
+    /// Buttons on the group card
+    pub fn card_keyboard(id: &str, lang: &str) -> InlineKeyboardMarkup {
+        InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(t(lang, "buttons.group_buy_in"), format!("gbuy:in:{}", id)),
+            InlineKeyboardButton::callback(t(lang, "buttons.group_buy_out"), format!("gbuy:out:{}", id)),
+        ]])
+    }
+
+    /// Handle `gbuy:in:<id>` and `gbuy:out:<id>`
+    ///
+    /// Everything personal goes to the member's DM; the group only sees counts.
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+        trading_engine: TradingEngineHandle,
+        wallet_manager: Arc<WalletManager>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let dm = ChatId(user_id);
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+
+        let mut parts = data.splitn(3, ':').skip(1);
+        let (Some(action), Some(id)) = (parts.next(), parts.next()) else {
+            return Ok(());
+        };
+
+        match action {
+            "in" => {
+                // A wallet means the member has set up a DM session with the bot
+                if !matches!(wallet_manager.get_user_wallet(&user_id.to_string()).await, Ok(Some(_))) {
+                    bot.send_message(msg.chat.id, t_args(&lang, "group_buy.needs_wallet", &[("name", &q.from.first_name)])).await?;
+                    return Ok(());
+                }
+
+                let outcome = match services.group_buys.join(id, user_id, Utc::now()).await {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        let _ = bot.send_message(dm, format!("❌ {}", e.user_message())).await;
+                        return Ok(());
+                    }
+                };
+                let Some(buy) = services.group_buys.get(id).await else { return Ok(()) };
+
+                if outcome != JoinOutcome::AlreadyIn {
+                    if let Err(e) = bot.send_message(dm, t_args(&lang, "group_buy.joined", &[
+                        ("symbol", &buy.symbol),
+                        ("amount", &fmt_number(&lang, buy.amount_each, NumberKind::Sol)),
+                        ("min", &buy.min_participants.to_string()),
+                    ])).await {
+                        // Without a DM we can't deliver their confirmation, so they can't take part
+                        warn!("🤝 No DM session for user {}: {}", user_id, e);
+                        let _ = services.group_buys.back_out(id, user_id).await;
+                        bot.send_message(msg.chat.id, t_args(&lang, "group_buy.needs_dm", &[("name", &q.from.first_name)])).await?;
+                        return Ok(());
+                    }
+                }
+
+                if let JoinOutcome::QuorumReached { .. } = outcome {
+                    let executor = MemberExecutor { trading_engine, wallet_manager };
+                    Self::spawn_execution(bot.clone(), services.clone(), executor, buy, msg.id, lang);
+                } else {
+                    Self::refresh_card(bot, &buy, msg.id, &lang).await;
+                }
+            }
+            "out" => {
+                match services.group_buys.back_out(id, user_id).await {
+                    Ok(_) => {
+                        let _ = bot.send_message(dm, t(&lang, "group_buy.backed_out")).await;
+                        if let Some(buy) = services.group_buys.get(id).await {
+                            Self::refresh_card(bot, &buy, msg.id, &lang).await;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = bot.send_message(dm, format!("❌ {}", e.user_message())).await;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Run every member's buy, confirm to each in their DM, then post the totals
+    fn spawn_execution(
+        bot: Bot,
+        services: Arc<BotServices>,
+        executor: MemberExecutor,
+        buy: GroupBuy,
+        card_id: MessageId,
+        lang: String,
+    ) {
+        tokio::spawn(async move {
+            let chat_id = ChatId(buy.chat_id);
+            let _ = bot.edit_message_text(chat_id, card_id, t_args(&lang, "group_buy.executing", &[
+                ("symbol", &buy.symbol),
+                ("members", &buy.participants.len().to_string()),
+            ])).await;
+
+            let (summary, outcomes) = match services.group_buys.execute(&buy.id, &executor).await {
+                Ok(result) => result,
+                Err(e) => {
+                    error!("🤝 Group buy {} could not execute: {}", buy.id, e);
+                    return;
+                }
+            };
+
+            for outcome in &outcomes {
+                let member_lang = services.preferences.language(outcome.user_id(), None).await;
+                let text = Self::member_message(&buy, outcome, &member_lang);
+                if let Err(e) = bot.send_message(ChatId(outcome.user_id()), text).await {
+                    warn!("🤝 Failed to confirm group buy to {}: {}", outcome.user_id(), e);
+                }
+            }
+
+            if let Err(e) = bot.send_message(chat_id, summary.group_message(&buy.symbol)).await {
+                error!("🤝 Failed to post group buy summary to {}: {}", buy.chat_id, e);
+            }
+        });
+    }
+
+    /// A member's own result, sent only to their DM
+    fn member_message(buy: &GroupBuy, outcome: &MemberOutcome, lang: &str) -> String {
+        match outcome {
+            MemberOutcome::Filled { fill, .. } => t_args(lang, "group_buy.filled", &[
+                ("tokens", &fmt_number(lang, fill.tokens_received, NumberKind::Decimal(2))),
+                ("symbol", &buy.symbol),
+                ("amount", &fmt_number(lang, fill.amount_sol, NumberKind::Sol)),
+                ("price", &format!("{:.8}", fill.price)),
+                ("signature", &fill.tx_signature),
+            ]),
+            MemberOutcome::Refused { reason, .. } => t_args(lang, "group_buy.refused", &[("symbol", &buy.symbol), ("reason", reason)]),
+            MemberOutcome::Failed { error, .. } => t_args(lang, "group_buy.failed", &[("symbol", &buy.symbol), ("error", error)]),
+        }
+    }
+
+    async fn refresh_card(bot: &Bot, buy: &GroupBuy, card_id: MessageId, lang: &str) {
+        let _ = bot.edit_message_text(ChatId(buy.chat_id), card_id, buy.card_text())
+            .reply_markup(Self::card_keyboard(&buy.id, lang))
+            .await;
+    }
+
+    /// Close the card when the window ends without quorum
+    fn spawn_expiry(bot: Bot, coordinator: Arc<GroupBuyCoordinator>, id: String, chat_id: ChatId, card_id: MessageId, lang: String) {
+        let window = coordinator.window().to_std().unwrap_or(std::time::Duration::from_secs(600));
+
+        tokio::spawn(async move {
+            tokio::time::sleep(window).await;
+            if let Some(expired) = coordinator.expire(&id, Utc::now()).await {
+                let _ = bot.edit_message_text(chat_id, card_id, t_args(&lang, "group_buy.expired", &[
+                    ("symbol", &expired.symbol),
+                    ("joined", &expired.participants.len().to_string()),
+                    ("min", &expired.min_participants.to_string()),
+                ])).await;
+            }
+        });
+    }
+
+    async fn current_price(services: &BotServices, mint: &str) -> Option<f64> {
+        match services.price_client.get_prices(vec![mint.to_string()]).await {
+            Ok(prices) => prices.prices.get(mint).map(|p| p.usd_price).filter(|p| *p > 0.0),
+            Err(e) => {
+                warn!("🤝 Price lookup for {} failed: {}", mint, e);
+                None
+            }
+        }
+    }
+
+    async fn risk_badge(mint: &str, lang: &str) -> String {
+        let checker = LarpChecker::from_env();
+        match checker.analyze_token(mint).await {
+            Ok(analysis) => Self::format_risk_badge(&analysis, lang),
+            Err(e) => {
+                info!("🤝 Risk check for {} unavailable: {}", mint, e);
+                t(lang, "group_buy.risk_unknown")
+            }
+        }
+    }
+
+    fn format_risk_badge(analysis: &SecurityAnalysis, lang: &str) -> String {
+        t_args(lang, "group_buy.risk_badge", &[
+            ("emoji", analysis.get_risk_emoji()),
+            ("level", &format!("{:?}", analysis.risk_level)),
+            ("score", &analysis.risk_score.to_string()),
+        ])
+    }
+}
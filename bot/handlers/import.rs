@@ -0,0 +1,163 @@
+use teloxide::{
+    net::Download,
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+use std::sync::Arc;
+use tokio::io::BufReader;
+use tracing::{error, info, warn};
+
+use crate::{
+    analytics::NATIVE_HEADER,
+    bot::BotServices,
+    bot::handlers::WalletHandler,
+    errors::BotError,
+    utils::{t, t_args, DEFAULT_LANG},
+    wallet::WalletExport,
+};
+
+/// Telegram's bot API won't hand out files larger than this
+const MAX_UPLOAD_BYTES: u32 = 20 * 1024 * 1024;
+
+/// /import trades - bring outside trade history into cost basis and PnL; /import <export> restores a wallet
+pub struct ImportHandler;
+
+impl ImportHandler {
+    /// Handle /import [trades|cancel|<wallet export>]
+    pub async fn handle_import(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(DEFAULT_LANG, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+
+        match args.trim() {
+            "trades" => {
+                services.trade_imports.expect_upload(telegram_id).await;
+                bot.send_message(msg.chat.id, t_args(&lang, "import.send_csv", &[("header", NATIVE_HEADER)])).await?;
+            }
+            "cancel" => {
+                services.trade_imports.take_upload_request(telegram_id).await;
+                let had_preview = services.trade_imports.cancel(telegram_id).await;
+                bot.send_message(msg.chat.id, t(&lang, if had_preview { "import.cancelled" } else { "import.nothing_to_cancel" })).await?;
+            }
+            blob if WalletExport::looks_like_export(blob) => {
+                WalletHandler::prompt_import(&bot, msg.chat.id, telegram_id, blob.to_string(), &services, &lang).await?;
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(&lang, "import.usage")).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take a CSV sent after /import trades; true when the document was handled
+    pub async fn handle_document(
+        bot: &Bot,
+        msg: &Message,
+        services: &Arc<BotServices>,
+        user_id: &str,
+    ) -> ResponseResult<bool> {
+        let Some(document) = msg.document() else { return Ok(false) };
+        let Ok(telegram_id) = user_id.parse::<i64>() else { return Ok(false) };
+        let captioned = msg.caption().is_some_and(|c| c.trim_start().starts_with("/import"));
+        if !services.trade_imports.take_upload_request(telegram_id).await && !captioned {
+            return Ok(false);
+        }
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+
+        if document.file.size > MAX_UPLOAD_BYTES {
+            bot.send_message(msg.chat.id, t(&lang, "import.too_large")).await?;
+            return Ok(true);
+        }
+
+        // Spool to disk and parse line by line rather than holding the upload in memory
+        let path = std::env::temp_dir().join(format!("trade-import-{}-{}.csv", telegram_id, uuid::Uuid::new_v4()));
+        let downloaded = async {
+            let file = bot.get_file(document.file.id.clone()).await?;
+            let mut spool = tokio::fs::File::create(&path).await?;
+            bot.download_file(&file.path, &mut spool).await?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        }.await;
+        if let Err(e) = downloaded {
+            error!("📥 Failed to download import for {}: {}", telegram_id, e);
+            let _ = tokio::fs::remove_file(&path).await;
+            bot.send_message(msg.chat.id, t(&lang, "import.download_failed")).await?;
+            return Ok(true);
+        }
+
+        let preview = match tokio::fs::File::open(&path).await {
+            Ok(file) => services.trade_imports.preview(telegram_id, BufReader::new(file)).await,
+            Err(e) => Err(BotError::internal(format!("Couldn't read the upload: {}", e)).into()),
+        };
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            warn!("📥 Failed to remove import spool {}: {}", path.display(), e);
+        }
+
+        match preview {
+            Ok(preview) if preview.rows.is_empty() => {
+                services.trade_imports.cancel(telegram_id).await;
+                bot.send_message(msg.chat.id, format!("{}\n\n{}", preview.render(), t(&lang, "import.nothing_new"))).await?;
+            }
+            Ok(preview) => {
+                let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                    InlineKeyboardButton::callback(t_args(&lang, "buttons.import_trades", &[("count", &preview.rows.len().to_string())]), "imp:confirm"),
+                    InlineKeyboardButton::callback(t(&lang, "buttons.cancel"), "imp:cancel"),
+                ]]);
+                bot.send_message(msg.chat.id, preview.render())
+                    .reply_markup(keyboard)
+                    .await?;
+            }
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Confirm or cancel buttons under an import preview
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+
+        match data {
+            "imp:confirm" => match services.trade_imports.confirm(user_id).await {
+                Ok(outcome) => {
+                    info!("📥 User {} confirmed an import of {} trades", user_id, outcome.inserted);
+                    let mut text = t_args(&lang, "import.imported", &[("count", &outcome.inserted.to_string())]);
+                    if outcome.duplicates > 0 {
+                        text.push(' ');
+                        text.push_str(&t_args(&lang, "import.duplicates", &[("count", &outcome.duplicates.to_string())]));
+                    }
+                    text.push('\n');
+                    text.push_str(&t(&lang, "import.counts_toward"));
+                    bot.send_message(msg.chat.id, text).await?;
+                }
+                Err(_) => {
+                    bot.send_message(msg.chat.id, t(&lang, "import.preview_expired")).await?;
+                }
+            },
+            "imp:cancel" => {
+                services.trade_imports.cancel(user_id).await;
+                bot.send_message(msg.chat.id, t(&lang, "import.cancelled")).await?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use chrono_tz::Tz;
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, Message},
+};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    analytics::{CloseReason, JournalEntry, JournalTag, TagBreakdown},
+    bot::BotServices,
+    utils::{fmt_datetime, lang_of, t, t_args, DateStyle},
+};
+
+/// Post-close journaling prompts, /journal and /stats
+pub struct JournalHandler;
+
+impl JournalHandler {
+    /// Deliver journal prompts with tag buttons as positions close
+    pub fn spawn_prompt_forwarder(bot: Bot, services: Arc<BotServices>) {
+        let mut receiver = services.journal.subscribe_prompts();
+
+        tokio::spawn(async move {
+            while let Ok(prompt) = receiver.recv().await {
+                let lang = services.preferences.language(prompt.user_id, None).await;
+                if let Err(e) = bot.send_message(ChatId(prompt.user_id), prompt.message.clone())
+                    .reply_markup(Self::tag_keyboard(&prompt.entry_id, &lang))
+                    .await
+                {
+                    error!("📓 Failed to deliver journal prompt to {}: {}", prompt.user_id, e);
+                }
+            }
+        });
+    }
+
+    /// Tag buttons for a journal entry
+    pub fn tag_keyboard(entry_id: &str, lang: &str) -> InlineKeyboardMarkup {
+        let button = |key: &str, tag: JournalTag| {
+            InlineKeyboardButton::callback(t(lang, key), format!("jtag:{}:{}", tag.code(), entry_id))
+        };
+
+        InlineKeyboardMarkup::new(vec![
+            vec![button("buttons.journal_plan", JournalTag::FollowedPlan), button("buttons.journal_broke", JournalTag::BrokePlan)],
+            vec![button("buttons.journal_fomo", JournalTag::FomoEntry), button("buttons.journal_news", JournalTag::NewsEvent)],
+            vec![button("buttons.journal_other", JournalTag::Other)],
+            vec![InlineKeyboardButton::callback(t(lang, "buttons.journal_off"), "jtag:off")],
+        ])
+    }
+
+    fn tag_label(tag: Option<JournalTag>, lang: &str) -> String {
+        t(lang, match tag {
+            Some(JournalTag::FollowedPlan) => "journal.tag_plan",
+            Some(JournalTag::BrokePlan) => "journal.tag_broke",
+            Some(JournalTag::FomoEntry) => "journal.tag_fomo",
+            Some(JournalTag::NewsEvent) => "journal.tag_news",
+            Some(JournalTag::Other) => "journal.tag_other",
+            None => "journal.untagged",
+        })
+    }
+
+    fn reason_label(reason: CloseReason, lang: &str) -> String {
+        t(lang, match reason {
+            CloseReason::ManualSell => "journal.reason_manual",
+            CloseReason::StopLoss => "journal.reason_stop_loss",
+            CloseReason::TakeProfit => "journal.reason_take_profit",
+            CloseReason::TrailingStop => "journal.reason_trailing",
+            CloseReason::PanicSell => "journal.reason_panic",
+        })
+    }
+
+    /// Handle `jtag:<tag>:<entry>` and `jtag:off`
+    pub async fn handle_tag_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+
+        if data == "jtag:off" {
+            services.journal.set_prompts_enabled(user_id, false).await;
+            bot.send_message(msg.chat.id, t(&lang, "journal.prompts_off_hint")).await?;
+            return Ok(());
+        }
+
+        let mut parts = data.splitn(3, ':').skip(1);
+        let (Some(tag), Some(entry_id)) = (parts.next().and_then(JournalTag::from_code), parts.next()) else {
+            return Ok(());
+        };
+
+        match services.journal.tag(user_id, entry_id, tag, None).await {
+            Ok(entry) => {
+                let reply = if tag == JournalTag::Other {
+                    t_args(&lang, "journal.tagged_other", &[("symbol", &entry.close.symbol), ("id", entry_id)])
+                } else {
+                    t_args(&lang, "journal.tagged", &[("symbol", &entry.close.symbol), ("tag", &Self::tag_label(Some(tag), &lang))])
+                };
+                bot.send_message(msg.chat.id, reply).await?;
+            }
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle /journal - list, retag, export, or toggle prompts
+    pub async fn handle_journal(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+        let tz = services.dca_engine.timezones().get_user_timezone(telegram_id).await;
+        let journal = &services.journal;
+
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            [] => {
+                let entries = journal.recent(telegram_id, 10).await;
+                if entries.is_empty() {
+                    bot.send_message(msg.chat.id, t(lang, "journal.empty")).await?;
+                    return Ok(());
+                }
+
+                let lines: Vec<String> = entries.iter().map(|entry| Self::format_entry(entry, lang, tz)).collect();
+                bot.send_message(msg.chat.id, t_args(lang, "journal.recent", &[("entries", &lines.join("\n"))])).await?;
+            }
+            ["export"] => {
+                let csv = journal.export_csv(telegram_id).await;
+                bot.send_document(msg.chat.id, InputFile::memory(csv.into_bytes()).file_name("journal.csv"))
+                    .caption(t(lang, "journal.export_caption"))
+                    .await?;
+            }
+            ["on"] | ["off"] => {
+                let enabled = parts[0] == "on";
+                journal.set_prompts_enabled(telegram_id, enabled).await;
+                bot.send_message(msg.chat.id, t(lang, if enabled { "journal.prompts_on" } else { "journal.prompts_off" })).await?;
+            }
+            [entry_id, tag, note @ ..] => {
+                let Some(tag) = JournalTag::from_code(tag) else {
+                    bot.send_message(msg.chat.id, t(lang, "journal.unknown_tag")).await?;
+                    return Ok(());
+                };
+                let note = (!note.is_empty()).then(|| note.join(" "));
+
+                match journal.tag(telegram_id, entry_id, tag, note).await {
+                    Ok(entry) => {
+                        info!("📓 User {} retagged {} as {:?}", telegram_id, entry_id, tag);
+                        bot.send_message(msg.chat.id, t_args(lang, "journal.updated", &[("entry", &Self::format_entry(&entry, lang, tz))])).await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                    }
+                }
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(lang, "journal.usage")).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn format_breakdown(breakdown: &[TagBreakdown], lang: &str) -> String {
+        let mut lines = vec![t(lang, "journal.breakdown_title"), String::new()];
+        for row in breakdown {
+            lines.push(t_args(lang, "journal.breakdown_row", &[
+                ("tag", &Self::tag_label(row.tag, lang)),
+                ("trades", &row.trades.to_string()),
+                ("win_rate", &format!("{:.0}", row.win_rate)),
+                ("average", &format!("{:+.1}", row.average_return_pct)),
+            ]));
+        }
+        lines.join("\n")
+    }
+
+    fn format_entry(entry: &JournalEntry, lang: &str, tz: Tz) -> String {
+        let close = &entry.close;
+        let result = close.return_pct.map(|pct| format!(" {:+.1}%", pct)).unwrap_or_default();
+        let note = entry.note.as_ref().map(|note| format!(" — {}", note)).unwrap_or_default();
+        format!(
+            "{} {} {}{} ({}) · {}{}",
+            entry.entry_id,
+            fmt_datetime(lang, tz, close.closed_at, DateStyle::ShortDate),
+            close.symbol,
+            result,
+            Self::reason_label(close.reason, lang),
+            Self::tag_label(entry.tag, lang),
+            note
+        )
+    }
+}
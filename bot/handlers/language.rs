@@ -0,0 +1,126 @@
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode},
+    utils::markdown::escape,
+};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    bot::BotServices,
+    utils::{i18n::lang_of, language_name, t, t_args, Catalog, LANGUAGES},
+};
+use super::menu::MenuCreator;
+
+/// The /language picker and the setting it saves
+pub struct LanguageHandler;
+
+impl LanguageHandler {
+    /// Handle /language [code] - pick the language the bot answers in
+    pub async fn handle_language(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+
+        let code = args.trim();
+        if code.is_empty() {
+            return Self::send_picker(&bot, msg.chat.id, &lang).await;
+        }
+        if !Catalog::embedded().supports(code) {
+            let codes = LANGUAGES.iter().map(|(code, _)| *code).collect::<Vec<_>>().join(", ");
+            bot.send_message(msg.chat.id, t_args(&lang, "language.unknown", &[("code", code), ("codes", &codes)])).await?;
+            return Ok(());
+        }
+
+        if let Some(chosen) = Self::save(&bot, msg.chat.id, telegram_id, code, &services).await? {
+            let text = format!(
+                "{}\n\n{}",
+                escape(&t_args(&chosen, "language.changed", &[("language", language_name(&chosen))])),
+                MenuCreator::main_menu_text(&chosen)
+            );
+            bot.send_message(msg.chat.id, text)
+                .parse_mode(ParseMode::MarkdownV2)
+                .reply_markup(MenuCreator::main_menu(&chosen))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Ask which language to use, marking the current one
+    pub async fn send_picker(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+        let text = format!(
+            "{}\n{}",
+            t(lang, "language.prompt"),
+            t_args(lang, "language.current", &[("language", language_name(lang))])
+        );
+        bot.send_message(chat_id, text).reply_markup(Self::picker()).await?;
+        Ok(())
+    }
+
+    /// One button per shipped language, two to a row
+    pub fn picker() -> InlineKeyboardMarkup {
+        InlineKeyboardMarkup::new(LANGUAGES.chunks(2).map(|row| {
+            row.iter()
+                .map(|(code, name)| InlineKeyboardButton::callback(*name, format!("lang:{}", code)))
+                .collect::<Vec<_>>()
+        }))
+    }
+
+    /// Picker buttons: save the choice and re-send the main menu in it straight away
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let Some(code) = data.strip_prefix("lang:") else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+
+        let Some(chosen) = Self::save(bot, msg.chat.id, user_id, code, &services).await? else {
+            return Ok(());
+        };
+        // The picker becomes the confirmation, so it can't be tapped again
+        bot.edit_message_text(
+            msg.chat.id,
+            msg.id,
+            t_args(&chosen, "language.changed", &[("language", language_name(&chosen))]),
+        )
+        .await?;
+        bot.send_message(msg.chat.id, MenuCreator::main_menu_text(&chosen))
+            .parse_mode(ParseMode::MarkdownV2)
+            .reply_markup(MenuCreator::main_menu(&chosen))
+            .await?;
+        Ok(())
+    }
+
+    /// Persist `code` as the user's language; the stored tag, or `None` after telling them why not
+    async fn save(
+        bot: &Bot,
+        chat_id: ChatId,
+        user_id: i64,
+        code: &str,
+        services: &BotServices,
+    ) -> ResponseResult<Option<String>> {
+        match services.preferences.update(user_id, |settings| settings.set_language(Some(code))).await {
+            Ok(settings) => {
+                let chosen = settings.language.unwrap_or_else(|| code.to_string());
+                info!("🌐 User {} switched the bot to {}", user_id, chosen);
+                Ok(Some(chosen))
+            }
+            Err(e) => {
+                error!("🌐 Failed to save language {} for {}: {}", code, user_id, e);
+                bot.send_message(chat_id, format!("❌ {}", e.user_message())).await?;
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, ChatId, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+use solana_sdk::signature::Keypair;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use zeroize::Zeroizing;
+
+use crate::{
+    bot::{
+        token_launch::{LaunchDraft, LaunchImage, LaunchInput, LaunchStep, LaunchTransition, LaunchWizard, MAX_DESCRIPTION_CHARS, MAX_NAME_CHARS, MAX_SYMBOL_CHARS, MIN_SYMBOL_CHARS},
+        BotServices,
+    },
+    trading::TokenPreset,
+    utils::{fmt_number, lang_of, t, t_args, NumberKind},
+    wallet::WalletManager,
+};
+
+/// /launch - step-by-step token creation
+pub struct LaunchHandler;
+
+impl LaunchHandler {
+    /// Handle /launch: open a fresh wizard at the name step
+    pub async fn handle_launch(bot: Bot, msg: Message, services: Arc<BotServices>, user_id: String) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let draft = match services.launches.start(telegram_id).await {
+            Ok(draft) => draft,
+            Err(e) => {
+                error!("🚀 Couldn't start a launch for {}: {}", telegram_id, e);
+                bot.send_message(msg.chat.id, t(&lang, "launch.start_failed")).await?;
+                return Ok(());
+            }
+        };
+
+        bot.send_message(msg.chat.id, t(&lang, "launch.intro")).await?;
+        Self::send_prompt(&bot, msg.chat.id, &services.launches, &draft, &lang).await
+    }
+
+    /// Take a wizard answer; true when the message belonged to an open wizard
+    pub async fn handle_reply(
+        bot: &Bot,
+        msg: &Message,
+        services: &Arc<BotServices>,
+        wallet_manager: &Arc<WalletManager>,
+        user_id: &str,
+    ) -> ResponseResult<bool> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else { return Ok(false) };
+        let input = if let Some(photo) = msg.photo().and_then(|sizes| sizes.iter().max_by_key(|size| size.file.size)) {
+            // Telegram re-encodes photos as JPEG
+            LaunchInput::Image(LaunchImage {
+                file_id: photo.file.id.to_string(),
+                mime_type: "image/jpeg".to_string(),
+                size: photo.file.size,
+            })
+        } else if let Some(document) = msg.document() {
+            LaunchInput::Image(LaunchImage {
+                file_id: document.file.id.to_string(),
+                mime_type: document.mime_type.as_ref().map(|mime| mime.to_string()).unwrap_or_default(),
+                size: document.file.size,
+            })
+        } else if let Some(text) = msg.text().filter(|text| !text.starts_with('/')) {
+            LaunchInput::Text(text.to_string())
+        } else {
+            return Ok(false);
+        };
+        if services.launches.draft(telegram_id).await.is_none() {
+            return Ok(false);
+        }
+
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        Self::advance(bot, msg.chat.id, telegram_id, input, services, wallet_manager, &lang).await?;
+        Ok(true)
+    }
+
+    /// Back, skip, preset, confirm and cancel buttons
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let input = match data {
+            "launch:back" => LaunchInput::Back,
+            "launch:skip" => LaunchInput::Skip,
+            "launch:cancel" => LaunchInput::Cancel,
+            "launch:confirm" => LaunchInput::Confirm,
+            data => match data.strip_prefix("launch:preset:").and_then(TokenPreset::from_key) {
+                Some(preset) => LaunchInput::Preset(preset),
+                None => return Ok(()),
+            },
+        };
+
+        let telegram_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(telegram_id, Some(&q.from)).await;
+        Self::advance(bot, msg.chat.id, telegram_id, input, &services, &wallet_manager, &lang).await
+    }
+
+    async fn advance(
+        bot: &Bot,
+        chat_id: ChatId,
+        telegram_id: i64,
+        input: LaunchInput,
+        services: &Arc<BotServices>,
+        wallet_manager: &Arc<WalletManager>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let wizard = &services.launches;
+        let (draft, transition) = match wizard.advance(telegram_id, input).await {
+            Ok(Some(advanced)) => advanced,
+            Ok(None) => {
+                bot.send_message(chat_id, t(lang, "launch.none")).await?;
+                return Ok(());
+            }
+            Err(e) => {
+                error!("🚀 Launch step failed for {}: {}", telegram_id, e);
+                bot.send_message(chat_id, t(lang, "launch.step_failed")).await?;
+                return Ok(());
+            }
+        };
+
+        match transition {
+            LaunchTransition::Moved(_) => Self::send_prompt(bot, chat_id, wizard, &draft, lang).await?,
+            LaunchTransition::Rejected(reason) => {
+                bot.send_message(chat_id, format!("❌ {}", reason))
+                    .reply_markup(Self::keyboard(wizard, draft.step, lang))
+                    .await?;
+            }
+            LaunchTransition::Cancelled => {
+                bot.send_message(chat_id, t(lang, "launch.cancelled")).await?;
+            }
+            LaunchTransition::Confirmed => Self::create(bot, chat_id, telegram_id, draft, wizard, wallet_manager, lang).await?,
+        }
+        Ok(())
+    }
+
+    async fn create(
+        bot: &Bot,
+        chat_id: ChatId,
+        telegram_id: i64,
+        draft: LaunchDraft,
+        wizard: &LaunchWizard,
+        wallet_manager: &Arc<WalletManager>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let payer = match wallet_manager.export_user_wallet(&telegram_id.to_string()).await {
+            Ok(Some(wallet_data)) => {
+                let secret = Zeroizing::new(bs58::decode(&wallet_data.private_key).into_vec().unwrap_or_default());
+                Keypair::from_bytes(&secret).ok()
+            }
+            Ok(None) => None,
+            Err(e) => {
+                error!("🚀 Couldn't load the wallet of {}: {}", telegram_id, e);
+                None
+            }
+        };
+        let Some(payer) = payer else {
+            wizard.resume(telegram_id, &draft).await;
+            bot.send_message(chat_id, t(lang, "launch.no_wallet"))
+                .reply_markup(Self::keyboard(wizard, LaunchStep::Review, lang))
+                .await?;
+            return Ok(());
+        };
+
+        bot.send_message(chat_id, t(lang, "launch.creating")).await?;
+        match wizard.launch(&draft, &payer).await {
+            Ok(result) => {
+                info!("🚀 User {} launched {} at {}", telegram_id, draft.symbol.as_deref().unwrap_or_default(), result.mint_address);
+                bot.send_message(chat_id, t_args(lang, "launch.live", &[
+                    ("symbol", draft.symbol.as_deref().unwrap_or_default()),
+                    ("mint", &result.mint_address.to_string()),
+                    ("explorer", &result.explorer_url),
+                    ("cost", &fmt_number(lang, result.creation_cost_sol, NumberKind::Sol)),
+                ])).await?;
+            }
+            Err(e) => {
+                warn!("🚀 Launch for {} failed: {}", telegram_id, e);
+                wizard.resume(telegram_id, &draft).await;
+                bot.send_message(chat_id, t_args(lang, "launch.failed", &[("error", &e.to_string())]))
+                    .reply_markup(Self::keyboard(wizard, LaunchStep::Review, lang))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_prompt(bot: &Bot, chat_id: ChatId, wizard: &LaunchWizard, draft: &LaunchDraft, lang: &str) -> ResponseResult<()> {
+        bot.send_message(chat_id, Self::prompt(wizard, draft, lang))
+            .reply_markup(Self::keyboard(wizard, draft.step, lang))
+            .await?;
+        Ok(())
+    }
+
+    /// The question for the draft's current step
+    pub fn prompt(wizard: &LaunchWizard, draft: &LaunchDraft, lang: &str) -> String {
+        let count = |n: u64| fmt_number(lang, n as f64, NumberKind::Decimal(0));
+        match draft.step {
+            LaunchStep::Name => t_args(lang, "launch.step_name", &[("max", &MAX_NAME_CHARS.to_string())]),
+            LaunchStep::Symbol => t_args(lang, "launch.step_symbol", &[
+                ("min", &MIN_SYMBOL_CHARS.to_string()),
+                ("max", &MAX_SYMBOL_CHARS.to_string()),
+            ]),
+            LaunchStep::Description => t_args(lang, "launch.step_description", &[("max", &MAX_DESCRIPTION_CHARS.to_string())]),
+            LaunchStep::Image => t(lang, "launch.step_image"),
+            LaunchStep::Preset => {
+                let mut text = t(lang, "launch.step_preset");
+                text.push('\n');
+                for (preset, config) in wizard.presets() {
+                    text.push_str(&format!(
+                        "\n• {}: {} ({})",
+                        preset.label(),
+                        config.description.unwrap_or_default(),
+                        t_args(lang, "launch.tokens", &[("count", &count(config.initial_supply))])
+                    ));
+                }
+                text
+            }
+            LaunchStep::Supply => {
+                let preset_supply = draft.preset
+                    .and_then(|preset| wizard.presets().into_iter().find(|(p, _)| *p == preset))
+                    .map(|(_, config)| count(config.initial_supply))
+                    .unwrap_or_default();
+                t_args(lang, "launch.step_supply", &[("supply", &preset_supply)])
+            }
+            LaunchStep::Review => match wizard.review(draft) {
+                Ok((config, preview, fee)) => {
+                    let mut text = t_args(lang, "launch.review", &[
+                        ("name", &config.name),
+                        ("symbol", &config.symbol),
+                        ("description", config.description.as_deref().unwrap_or("—")),
+                        ("picture", &t(lang, if draft.image.is_some() { "launch.picture_attached" } else { "launch.picture_none" })),
+                        ("preset", draft.preset.map(|p| p.label()).unwrap_or_default()),
+                        ("supply", &count(config.initial_supply)),
+                        ("decimals", &config.decimals.to_string()),
+                    ]);
+                    text.push('\n');
+                    for feature in &preview.features {
+                        text.push_str(&format!("✅ {}\n", feature));
+                    }
+                    for warning in &preview.warnings {
+                        text.push_str(&format!("⚠️ {}\n", warning));
+                    }
+                    text.push('\n');
+                    text.push_str(&t_args(lang, "launch.review_fees", &[("fee", &fmt_number(lang, fee, NumberKind::Sol))]));
+                    text
+                }
+                Err(e) => t_args(lang, "launch.review_invalid", &[("error", &e.to_string())]),
+            },
+        }
+    }
+
+    fn keyboard(wizard: &LaunchWizard, step: LaunchStep, lang: &str) -> InlineKeyboardMarkup {
+        let mut rows = vec![];
+        match step {
+            LaunchStep::Preset => {
+                for (preset, _) in wizard.presets() {
+                    rows.push(vec![InlineKeyboardButton::callback(
+                        preset.label(),
+                        format!("launch:preset:{}", preset.key()),
+                    )]);
+                }
+            }
+            LaunchStep::Review => {
+                rows.push(vec![InlineKeyboardButton::callback(t(lang, "buttons.launch_confirm"), "launch:confirm")]);
+            }
+            _ => {}
+        }
+
+        let mut controls = vec![];
+        if step.previous().is_some() {
+            controls.push(InlineKeyboardButton::callback(t(lang, "buttons.back"), "launch:back"));
+        }
+        if step.skippable() {
+            controls.push(InlineKeyboardButton::callback(t(lang, "buttons.skip"), "launch:skip"));
+        }
+        controls.push(InlineKeyboardButton::callback(t(lang, "buttons.cancel"), "launch:cancel"));
+        rows.push(controls);
+        InlineKeyboardMarkup::new(rows)
+    }
+}
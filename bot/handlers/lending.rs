@@ -0,0 +1,270 @@
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::{
+    api::{LendingVault, PositionRecommendation, RiskTier},
+    bot::BotServices,
+    trading::{LendAmount, LendDirection, LendOutcome, LendingHolding, PendingLend},
+    utils::{fmt_number, lang_of, t, t_args, NumberKind},
+    wallet::WalletManager,
+};
+
+/// Vaults listed before the rest are left to /lend <symbol>
+const MAX_VAULTS_SHOWN: usize = 15;
+
+/// /lend - supply tokens to Jupiter Lend vaults
+pub struct LendingHandler;
+
+impl LendingHandler {
+    /// Handle /lend [positions | <vault> <amount> | withdraw <vault> <amount|all>]
+    pub async fn handle_lend(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+        let desk = &services.lending;
+
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let (direction, vault, amount) = match parts.as_slice() {
+            [] => {
+                let text = match desk.vaults().await {
+                    Ok(vaults) => Self::vaults_text(&vaults, lang),
+                    Err(e) => t_args(lang, "lending.vaults_failed", &[("error", &e.to_string())]),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+                return Ok(());
+            }
+            [positions] if positions.eq_ignore_ascii_case("positions") => {
+                let Some(wallet) = Self::wallet(&wallet_manager, &user_id).await else {
+                    bot.send_message(msg.chat.id, t(lang, "errors.no_wallet")).await?;
+                    return Ok(());
+                };
+                let text = match desk.positions(&wallet).await {
+                    Ok(holdings) => Self::positions_text(&holdings, lang),
+                    Err(e) => t_args(lang, "lending.positions_failed", &[("error", &e.to_string())]),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+                return Ok(());
+            }
+            [withdraw, vault, amount] if withdraw.eq_ignore_ascii_case("withdraw") => (LendDirection::Withdraw, *vault, *amount),
+            [vault, amount] => (LendDirection::Deposit, *vault, *amount),
+            _ => {
+                bot.send_message(msg.chat.id, t(lang, "lending.usage")).await?;
+                return Ok(());
+            }
+        };
+
+        let Some(amount) = LendAmount::parse(amount) else {
+            bot.send_message(msg.chat.id, t(lang, "lending.usage")).await?;
+            return Ok(());
+        };
+        if wallet_manager.is_trading_locked(&user_id).await {
+            bot.send_message(msg.chat.id, t(lang, "errors.trading_locked")).await?;
+            return Ok(());
+        }
+        let Some(wallet) = Self::wallet(&wallet_manager, &user_id).await else {
+            bot.send_message(msg.chat.id, t(lang, "errors.no_wallet")).await?;
+            return Ok(());
+        };
+
+        let pending = match desk.prepare(telegram_id, &wallet, direction, vault, amount).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                return Ok(());
+            }
+        };
+
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(format!("✅ {}", Self::direction_label(pending.direction, lang)), "lend:go"),
+            InlineKeyboardButton::callback(t(lang, "buttons.cancel"), "lend:cancel"),
+        ]]);
+        bot.send_message(msg.chat.id, Self::confirm_text(&pending, lang)).reply_markup(keyboard).await?;
+        Ok(())
+    }
+
+    /// Confirmation buttons under a previewed deposit or withdrawal
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let telegram_id = q.from.id.0 as i64;
+        let user_id = telegram_id.to_string();
+        let desk = &services.lending;
+        let lang = services.preferences.language(telegram_id, Some(&q.from)).await;
+
+        if data == "lend:cancel" {
+            desk.cancel(telegram_id).await;
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "lending.cancelled")).await?;
+            return Ok(());
+        }
+        if data != "lend:go" {
+            return Ok(());
+        }
+
+        if wallet_manager.is_trading_locked(&user_id).await {
+            desk.cancel(telegram_id).await;
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "errors.trading_locked")).await?;
+            return Ok(());
+        }
+        let Some(wallet) = Self::wallet(&wallet_manager, &user_id).await else {
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "errors.no_wallet")).await?;
+            return Ok(());
+        };
+        bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "lending.sending")).await?;
+
+        let text = match desk.confirm(telegram_id, &wallet).await {
+            Ok(outcome) => Self::outcome_text(&outcome, &lang),
+            Err(e) => {
+                error!("🏦 Lending operation failed for user {}: {}", telegram_id, e);
+                t_args(&lang, "lending.failed", &[("error", &e.to_string())])
+            }
+        };
+        bot.edit_message_text(msg.chat.id, msg.id, text).await?;
+        Ok(())
+    }
+
+    async fn wallet(wallet_manager: &WalletManager, user_id: &str) -> Option<String> {
+        wallet_manager.get_user_wallet(user_id).await.ok()?.map(|wallet| wallet.public_key)
+    }
+
+    fn direction_label(direction: LendDirection, lang: &str) -> String {
+        t(lang, match direction {
+            LendDirection::Deposit => "lending.deposit",
+            LendDirection::Withdraw => "lending.withdraw",
+        })
+    }
+
+    fn risk_label(tier: &RiskTier, lang: &str) -> String {
+        t(lang, match tier {
+            RiskTier::Conservative => "lending.risk_conservative",
+            RiskTier::Moderate => "lending.risk_moderate",
+            RiskTier::Aggressive => "lending.risk_aggressive",
+            RiskTier::Speculative => "lending.risk_speculative",
+        })
+    }
+
+    pub fn vaults_text(vaults: &[LendingVault], lang: &str) -> String {
+        if vaults.is_empty() {
+            return t(lang, "lending.no_vaults");
+        }
+
+        let mut text = format!("{}\n", t(lang, "lending.vaults_title"));
+        for vault in vaults.iter().take(MAX_VAULTS_SHOWN) {
+            text.push('\n');
+            text.push_str(&t_args(lang, "lending.vault_line", &[
+                ("symbol", &vault.token_symbol),
+                ("apy", &fmt_number(lang, vault.supply_apy() * 100.0, NumberKind::Percent(2))),
+                ("risk", &Self::risk_label(&vault.risk_tier, lang)),
+                ("utilization", &fmt_number(lang, vault.utilization_rate * 100.0, NumberKind::Percent(0))),
+            ]));
+        }
+        if vaults.len() > MAX_VAULTS_SHOWN {
+            text.push_str("\n\n");
+            text.push_str(&t_args(lang, "common.and_more", &[("count", &(vaults.len() - MAX_VAULTS_SHOWN).to_string())]));
+        }
+        text.push_str("\n\n");
+        text.push_str(&t(lang, "lending.vaults_footer"));
+        text
+    }
+
+    pub fn confirm_text(pending: &PendingLend, lang: &str) -> String {
+        let vault = &pending.vault;
+        let (title, note) = match pending.direction {
+            LendDirection::Deposit => ("lending.deposit_title", "lending.deposit_note"),
+            LendDirection::Withdraw => ("lending.withdraw_title", "lending.withdraw_note"),
+        };
+        t_args(lang, "lending.confirm", &[
+            ("title", &t(lang, title)),
+            ("symbol", &vault.token_symbol),
+            ("risk", &Self::risk_label(&vault.risk_tier, lang)),
+            ("amount", &fmt_number(lang, pending.tokens(), NumberKind::Token)),
+            ("apy", &fmt_number(lang, vault.supply_apy() * 100.0, NumberKind::Percent(2))),
+            ("note", &t(lang, note)),
+        ])
+    }
+
+    pub fn positions_text(holdings: &[LendingHolding], lang: &str) -> String {
+        if holdings.is_empty() {
+            return t(lang, "lending.no_positions");
+        }
+        let mut text = format!("{}\n", t(lang, "lending.positions_title"));
+        for holding in holdings {
+            text.push('\n');
+            text.push_str(&Self::holding_text(holding, lang));
+        }
+        text
+    }
+
+    /// One position, with a warning once its health factor gets close to liquidation
+    pub fn holding_text(holding: &LendingHolding, lang: &str) -> String {
+        let position = &holding.position;
+        let mut text = t_args(lang, "lending.holding", &[
+            ("symbol", &holding.symbol),
+            ("supplied", &fmt_number(lang, holding.supplied(), NumberKind::Token)),
+            ("interest", &fmt_number(lang, holding.interest(), NumberKind::Token)),
+        ]);
+        if position.borrowed_amount > 0 {
+            text.push_str("\n   ");
+            text.push_str(&t_args(lang, "lending.borrowed", &[
+                ("borrowed", &fmt_number(lang, holding.borrowed(), NumberKind::Token)),
+                ("ltv", &fmt_number(lang, position.current_ltv * 100.0, NumberKind::Percent(1))),
+            ]));
+        }
+
+        let health = fmt_number(lang, position.health_factor, NumberKind::Decimal(2));
+        let warning = match holding.recommendation {
+            PositionRecommendation::CanBorrowMore | PositionRecommendation::Healthy => None,
+            PositionRecommendation::MonitorClosely => Some("lending.health_monitor"),
+            PositionRecommendation::AddCollateral => Some("lending.health_add_collateral"),
+            PositionRecommendation::Liquidatable => Some("lending.health_liquidatable"),
+        };
+        let Some(warning) = warning else {
+            return text;
+        };
+        text.push_str("\n   ");
+        text.push_str(&t_args(lang, warning, &[("health", &health)]));
+
+        let liquidation_price = holding.liquidation.as_ref()
+            .map(|info| info.liquidation_price)
+            .or(position.liquidation_price);
+        if let Some(price) = liquidation_price {
+            text.push_str("\n   ");
+            text.push_str(&t_args(lang, "lending.liquidation_at", &[("price", &fmt_number(lang, price, NumberKind::Usd))]));
+        }
+        text
+    }
+
+    fn outcome_text(outcome: &LendOutcome, lang: &str) -> String {
+        match outcome {
+            LendOutcome::Sent(operation) => t_args(lang, "lending.sent", &[
+                ("action", &Self::direction_label(operation.direction, lang)),
+                ("amount", &fmt_number(lang, operation.amount, NumberKind::Token)),
+                ("symbol", &operation.symbol),
+                ("tx", &operation.tx_signature),
+            ]),
+            LendOutcome::Unsigned(pending) => t_args(lang, "lending.unsigned", &[
+                ("action", &Self::direction_label(pending.direction, lang)),
+                ("amount", &fmt_number(lang, pending.tokens(), NumberKind::Token)),
+                ("symbol", &pending.vault.token_symbol),
+            ]),
+        }
+    }
+}
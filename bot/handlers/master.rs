@@ -0,0 +1,176 @@
+use chrono_tz::Tz;
+use teloxide::{prelude::*, types::Message};
+use std::sync::Arc;
+
+use crate::{
+    bot::BotServices,
+    trading::{MasterFeeKind, MasterFeeLedger, MasterProfile, MasterRequirements, VisibilityPreferences},
+    utils::{fmt_datetime, fmt_number, lang_of, t, t_args, DateStyle, NumberKind},
+    wallet::WalletManager,
+};
+
+/// Ledger lines shown under /master earnings
+const RECENT_ENTRIES: usize = 10;
+
+/// /master - apply to be copied for a fee, set the fee and follow earnings
+pub struct MasterHandler;
+
+impl MasterHandler {
+    /// Handle /master [apply [fee%] | fee <percent> | earnings]
+    pub async fn handle_master(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+        let tz = services.dca_engine.timezones().get_user_timezone(telegram_id).await;
+        let program = &services.master_program;
+
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let text = match parts.as_slice() {
+            [] => match program.profile(telegram_id).await {
+                Some(profile) => match program.ledger(telegram_id).await {
+                    Ok(ledger) => Self::profile_text(&profile, &ledger, lang, tz),
+                    Err(e) => format!("❌ {}", e.user_message()),
+                },
+                None => Self::requirements_text(program.requirements(), lang),
+            },
+            [apply, rest @ ..] if apply.eq_ignore_ascii_case("apply") && rest.len() <= 1 => {
+                let fee = match rest.first().map(|fee| Self::parse_percent(fee)) {
+                    Some(None) => {
+                        bot.send_message(msg.chat.id, t(lang, "master.usage")).await?;
+                        return Ok(());
+                    }
+                    Some(fee) => fee,
+                    None => None,
+                };
+                let Ok(Some(wallet)) = wallet_manager.get_user_wallet(&user_id).await else {
+                    bot.send_message(msg.chat.id, t(lang, "errors.no_wallet")).await?;
+                    return Ok(());
+                };
+                // The oldest wallet dates the account
+                let account_created = wallet_manager.get_user_wallets(&user_id).await
+                    .ok()
+                    .and_then(|wallets| wallets.iter().map(|w| w.created_at).min());
+                let stats = match services.leaderboard.get_trader_stats(telegram_id).await {
+                    Ok(stats) => stats,
+                    Err(e) => {
+                        bot.send_message(msg.chat.id, t_args(lang, "master.stats_failed", &[("error", &e.to_string())])).await?;
+                        return Ok(());
+                    }
+                };
+
+                match program.apply(telegram_id, &stats, account_created, &wallet.public_key, fee).await {
+                    Ok(profile) => {
+                        let mut text = t_args(lang, "master.approved", &[
+                            ("fee", &profile.fee_percent.to_string()),
+                            ("wallet", &profile.payout_wallet),
+                            ("min_payout", &fmt_number(lang, program.min_payout_sol(), NumberKind::Sol)),
+                        ]);
+                        if !services.preferences.visibility(telegram_id).await.copyable {
+                            text.push_str("\n\n");
+                            text.push_str(&t(lang, "master.enable_copyable"));
+                        }
+                        text
+                    }
+                    Err(e) => format!("❌ {}", e.user_message()),
+                }
+            }
+            [fee, percent] if fee.eq_ignore_ascii_case("fee") => {
+                let Some(percent) = Self::parse_percent(percent) else {
+                    bot.send_message(msg.chat.id, t(lang, "master.usage")).await?;
+                    return Ok(());
+                };
+                match program.set_fee(telegram_id, percent).await {
+                    Ok(profile) => t_args(lang, "master.fee_set", &[("fee", &profile.fee_percent.to_string())]),
+                    Err(e) => format!("❌ {}", e.user_message()),
+                }
+            }
+            [earnings] if earnings.eq_ignore_ascii_case("earnings") => {
+                if program.profile(telegram_id).await.is_none() {
+                    t(lang, "master.not_master")
+                } else {
+                    match program.ledger(telegram_id).await {
+                        Ok(ledger) => Self::earnings_text(&ledger, program.min_payout_sol(), lang, tz),
+                        Err(e) => format!("❌ {}", e.user_message()),
+                    }
+                }
+            }
+            _ => t(lang, "master.usage"),
+        };
+
+        bot.send_message(msg.chat.id, text).await?;
+        Ok(())
+    }
+
+    /// "7.5" or "7.5%"
+    fn parse_percent(text: &str) -> Option<f64> {
+        text.trim_end_matches('%').parse::<f64>().ok().filter(|p| p.is_finite())
+    }
+
+    pub fn requirements_text(requirements: &MasterRequirements, lang: &str) -> String {
+        t_args(lang, "master.requirements", &[
+            ("max_fee", &requirements.max_fee_percent.to_string()),
+            ("min_trades", &requirements.min_trades.to_string()),
+            ("win_rate", &format!("{:.0}", requirements.min_win_rate)),
+            ("days", &requirements.min_account_age.num_days().to_string()),
+        ])
+    }
+
+    fn profile_text(profile: &MasterProfile, ledger: &MasterFeeLedger, lang: &str, tz: Tz) -> String {
+        t_args(lang, "master.profile", &[
+            ("since", &fmt_datetime(lang, tz, profile.approved_at, DateStyle::Date)),
+            ("fee", &profile.fee_percent.to_string()),
+            ("fee_set", &fmt_datetime(lang, tz, profile.fee_updated_at, DateStyle::Date)),
+            ("status", &t(lang, if profile.accepting_followers { "master.accepting" } else { "master.closed" })),
+            ("earned", &fmt_number(lang, ledger.earned_lamports() as f64 / 1e9, NumberKind::Sol)),
+        ])
+    }
+
+    pub fn earnings_text(ledger: &MasterFeeLedger, min_payout_sol: f64, lang: &str, tz: Tz) -> String {
+        let sol = |lamports: u64| format!("{} SOL", fmt_number(lang, lamports as f64 / 1e9, NumberKind::Sol));
+        let balance = ledger.balance_lamports();
+        let mut text = t_args(lang, "master.earnings", &[
+            ("earned", &sol(ledger.earned_lamports())),
+            ("paid", &sol(ledger.paid_lamports())),
+            ("balance", &format!("{}{}", if balance < 0 { "-" } else { "" }, sol(balance.unsigned_abs()))),
+        ]);
+        text.push('\n');
+        if balance < 0 {
+            text.push_str(&t(lang, "master.negative_balance"));
+        } else {
+            text.push_str(&t_args(lang, "master.auto_payout", &[("min_payout", &fmt_number(lang, min_payout_sol, NumberKind::Sol))]));
+        }
+        text.push('\n');
+
+        if ledger.entries.is_empty() {
+            text.push('\n');
+            text.push_str(&t(lang, "master.no_copies"));
+            return text;
+        }
+        text.push('\n');
+        for entry in ledger.entries.iter().rev().take(RECENT_ENTRIES) {
+            let (icon, label) = match entry.kind {
+                MasterFeeKind::Accrual => ("➕", "master.entry_fee"),
+                MasterFeeKind::Reversal => ("↩️", "master.entry_refund"),
+                MasterFeeKind::Payout => ("📤", "master.entry_payout"),
+            };
+            text.push_str(&format!(
+                "{} {} {} · {}\n",
+                icon,
+                fmt_datetime(lang, tz, entry.at, DateStyle::DateTime),
+                t(lang, label),
+                sol(entry.lamports),
+            ));
+        }
+        text
+    }
+}
@@ -1,4 +1,66 @@
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, ReplyKeyboardMarkup};
+use teloxide::utils::markdown::escape;
+
+use crate::bot::callback_action::{CallbackAction, SMALL_QUICK_BUY_SOL};
+use crate::utils::{i18n::DEFAULT_LANG, t, t_args, Catalog};
+
+/// A main menu keyboard button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuButton {
+    Balance,
+    Portfolio,
+    Trade,
+    Rebates,
+    AiAnalysis,
+    Wallet,
+    Settings,
+    Help,
+    Charts,
+    Language,
+}
+
+impl MenuButton {
+    pub const ALL: [MenuButton; 10] = [
+        Self::Balance,
+        Self::Portfolio,
+        Self::Trade,
+        Self::Rebates,
+        Self::AiAnalysis,
+        Self::Wallet,
+        Self::Settings,
+        Self::Help,
+        Self::Charts,
+        Self::Language,
+    ];
+
+    /// Catalog key of the button's label
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Balance => "menu.balance",
+            Self::Portfolio => "menu.portfolio",
+            Self::Trade => "menu.trade",
+            Self::Rebates => "menu.rebates",
+            Self::AiAnalysis => "menu.ai_analysis",
+            Self::Wallet => "menu.wallet",
+            Self::Settings => "menu.settings",
+            Self::Help => "menu.help",
+            Self::Charts => "menu.charts",
+            Self::Language => "menu.language",
+        }
+    }
+
+    /// The button a keyboard press came from, in whichever language the keyboard was sent
+    ///
+    /// Keyboards stay on the client after a language switch, so every shipped
+    /// language's labels are accepted.
+    pub fn parse(text: &str) -> Option<Self> {
+        let catalog = Catalog::embedded();
+        let languages = catalog.languages();
+        Self::ALL.into_iter().find(|button| {
+            languages.iter().any(|lang| catalog.get(lang, button.key()) == Some(text))
+        })
+    }
+}
 
 /// Menu creator for all bot menus
 pub struct MenuCreator;
@@ -6,21 +68,30 @@ pub struct MenuCreator;
 impl MenuCreator {
     /// Create the persistent main menu keyboard
     pub fn create_main_menu() -> ReplyKeyboardMarkup {
+        Self::main_menu(DEFAULT_LANG)
+    }
+    
+    /// The persistent main menu keyboard in `lang`
+    pub fn main_menu(lang: &str) -> ReplyKeyboardMarkup {
+        let button = |button: MenuButton| KeyboardButton::new(t(lang, button.key()));
         let keyboard = ReplyKeyboardMarkup::new(vec![
             vec![
-                KeyboardButton::new("💰 Balance"),
-                KeyboardButton::new("📊 Portfolio"),
-                KeyboardButton::new("⚡ Trade"),
+                button(MenuButton::Balance),
+                button(MenuButton::Portfolio),
+                button(MenuButton::Trade),
             ],
             vec![
-                KeyboardButton::new("💎 Rebates"),
-                KeyboardButton::new("🤖 AI Analysis"),
-                KeyboardButton::new("💼 Wallet"),
+                button(MenuButton::Rebates),
+                button(MenuButton::AiAnalysis),
+                button(MenuButton::Wallet),
             ],
             vec![
-                KeyboardButton::new("⚙️ Settings"),
-                KeyboardButton::new("📚 Help"),
-                KeyboardButton::new("📈 Charts"),
+                button(MenuButton::Settings),
+                button(MenuButton::Help),
+                button(MenuButton::Charts),
+            ],
+            vec![
+                button(MenuButton::Language),
             ],
         ])
         .persistent(true)
@@ -29,123 +100,162 @@ impl MenuCreator {
         keyboard
     }
     
+    /// MarkdownV2 heading sent with the main menu keyboard
+    pub fn main_menu_text(lang: &str) -> String {
+        format!("🎛️ *{}*\n\n{}", escape(&t(lang, "menu.title")), escape(&t(lang, "menu.hint")))
+    }
+    
     /// Create trading menu with inline keyboard
     pub fn create_trading_menu() -> InlineKeyboardMarkup {
+        Self::trading_menu(DEFAULT_LANG)
+    }
+    
+    /// The trading menu in `lang`
+    pub fn trading_menu(lang: &str) -> InlineKeyboardMarkup {
+        let buy = |emoji: &str, token: &str| InlineKeyboardButton::callback(
+            format!("{} {}", emoji, t_args(lang, "buttons.buy_token", &[("token", token)])),
+            CallbackAction::quick_buy(token, SMALL_QUICK_BUY_SOL).to_data(),
+        );
         InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::callback("💰 Quick Buy", "trade_quick_buy"),
-                InlineKeyboardButton::callback("💸 Quick Sell", "trade_quick_sell"),
+                InlineKeyboardButton::callback(t(lang, "buttons.quick_buy"), "trade_quick_buy"),
+                InlineKeyboardButton::callback(t(lang, "buttons.quick_sell"), "trade_quick_sell"),
             ],
             vec![
-                InlineKeyboardButton::callback("🐕 Buy BONK", "quick_buy_bonk"),
-                InlineKeyboardButton::callback("🐶 Buy WIF", "quick_buy_wif"),
-                InlineKeyboardButton::callback("🦎 Buy GECKO", "quick_buy_gecko"),
+                buy("🐕", "BONK"),
+                buy("🐶", "WIF"),
+                buy("🦎", "GECKO"),
             ],
             vec![
-                InlineKeyboardButton::callback("🔍 Search Token", "trade_search"),
-                InlineKeyboardButton::callback("📊 Market", "trade_market"),
+                InlineKeyboardButton::callback(t(lang, "buttons.search_token"), "trade_search"),
+                InlineKeyboardButton::callback(t(lang, "buttons.market"), "trade_market"),
             ],
             vec![
-                InlineKeyboardButton::callback("⚙️ Trading Settings", "trade_settings"),
-                InlineKeyboardButton::callback("📈 Chart", "trade_chart"),
+                InlineKeyboardButton::callback(t(lang, "buttons.trading_settings"), "trade_settings"),
+                InlineKeyboardButton::callback(t(lang, "buttons.chart"), "trade_chart"),
             ],
             vec![
-                InlineKeyboardButton::callback("🔙 Back to Main Menu", "main_menu"),
+                Self::back_to_main(lang),
             ],
         ])
     }
     
     /// Create wallet menu with inline keyboard
     pub fn create_wallet_menu() -> InlineKeyboardMarkup {
+        Self::wallet_menu(DEFAULT_LANG)
+    }
+    
+    /// The wallet menu in `lang`
+    pub fn wallet_menu(lang: &str) -> InlineKeyboardMarkup {
         InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::callback("💰 Check Balance", "wallet_balance"),
-                InlineKeyboardButton::callback("📥 Deposit", "wallet_deposit"),
+                InlineKeyboardButton::callback(t(lang, "buttons.check_balance"), "wallet_balance"),
+                InlineKeyboardButton::callback(t(lang, "buttons.deposit"), "wallet_deposit"),
             ],
             vec![
-                InlineKeyboardButton::callback("🆕 New Wallet", "wallet_new"),
-                InlineKeyboardButton::callback("📥 Import Wallet", "wallet_import"),
+                InlineKeyboardButton::callback(t(lang, "buttons.new_wallet"), "wallet_new"),
+                InlineKeyboardButton::callback(t(lang, "buttons.import_wallet"), "wallet_import"),
             ],
             vec![
-                InlineKeyboardButton::callback("📤 Export Keys", "wallet_export"),
-                InlineKeyboardButton::callback("🔐 Backup Guide", "wallet_backup"),
+                InlineKeyboardButton::callback(t(lang, "buttons.export_wallet"), "wallet_export"),
+                InlineKeyboardButton::callback(t(lang, "buttons.backup_guide"), "wallet_backup"),
             ],
             vec![
-                InlineKeyboardButton::callback("🔄 Switch Wallet", "wallet_switch"),
-                InlineKeyboardButton::callback("🗑️ Remove Wallet", "wallet_remove"),
+                InlineKeyboardButton::callback(t(lang, "buttons.switch_wallet"), "wallet_switch"),
+                InlineKeyboardButton::callback(t(lang, "buttons.remove_wallet"), "wallet_remove"),
             ],
             vec![
-                InlineKeyboardButton::callback("🔙 Back to Main Menu", "main_menu"),
+                Self::back_to_main(lang),
             ],
         ])
     }
     
     /// Create analytics menu with inline keyboard
     pub fn create_analytics_menu() -> InlineKeyboardMarkup {
+        Self::analytics_menu(DEFAULT_LANG)
+    }
+    
+    /// The analytics menu in `lang`
+    pub fn analytics_menu(lang: &str) -> InlineKeyboardMarkup {
+        let analyze = |token: &str| t_args(lang, "buttons.ai_token", &[("token", token)]);
         InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::callback("🤖 AI Analysis SOL", "analyze_sol"),
-                InlineKeyboardButton::callback("🤖 AI Analysis BTC", "analyze_btc"),
+                InlineKeyboardButton::callback(analyze("SOL"), "analyze_sol"),
+                InlineKeyboardButton::callback(analyze("BTC"), "analyze_btc"),
             ],
             vec![
-                InlineKeyboardButton::callback("📊 Market Sentiment", "analyze_sentiment"),
-                InlineKeyboardButton::callback("🔥 Trending Tokens", "analyze_trending"),
+                InlineKeyboardButton::callback(t(lang, "buttons.sentiment"), "analyze_sentiment"),
+                InlineKeyboardButton::callback(t(lang, "buttons.trending"), "analyze_trending"),
             ],
             vec![
-                InlineKeyboardButton::callback("💎 Token Research", "analyze_research"),
-                InlineKeyboardButton::callback("⚡ Quick Analysis", "analyze_quick"),
+                InlineKeyboardButton::callback(t(lang, "buttons.research"), "analyze_research"),
+                InlineKeyboardButton::callback(t(lang, "buttons.quick_analysis"), "analyze_quick"),
             ],
             vec![
-                InlineKeyboardButton::callback("🔙 Back to Main Menu", "main_menu"),
+                Self::back_to_main(lang),
             ],
         ])
     }
     
     /// Create portfolio menu with inline keyboard
     pub fn create_portfolio_menu() -> InlineKeyboardMarkup {
+        Self::portfolio_menu(DEFAULT_LANG)
+    }
+    
+    /// The portfolio menu in `lang`
+    pub fn portfolio_menu(lang: &str) -> InlineKeyboardMarkup {
         InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::callback("📊 View Positions", "portfolio_positions"),
-                InlineKeyboardButton::callback("📈 P&L Summary", "portfolio_pnl"),
+                InlineKeyboardButton::callback(t(lang, "buttons.positions"), "portfolio_positions"),
+                InlineKeyboardButton::callback(t(lang, "buttons.pnl"), "portfolio_pnl"),
             ],
             vec![
-                InlineKeyboardButton::callback("🔄 Refresh Data", "portfolio_refresh"),
-                InlineKeyboardButton::callback("📋 Trade History", "portfolio_history"),
+                InlineKeyboardButton::callback(t(lang, "buttons.refresh_data"), "portfolio_refresh"),
+                InlineKeyboardButton::callback(t(lang, "buttons.history"), "portfolio_history"),
             ],
             vec![
-                InlineKeyboardButton::callback("💎 Rebate Stats", "portfolio_rebates"),
-                InlineKeyboardButton::callback("📊 Performance", "portfolio_performance"),
+                InlineKeyboardButton::callback(t(lang, "buttons.rebate_stats"), "portfolio_rebates"),
+                InlineKeyboardButton::callback(t(lang, "buttons.performance"), "portfolio_performance"),
             ],
             vec![
-                InlineKeyboardButton::callback("📤 Export Report", "portfolio_export"),
-                InlineKeyboardButton::callback("📧 Daily Summary", "portfolio_summary"),
+                InlineKeyboardButton::callback(t(lang, "buttons.export_report"), "portfolio_export"),
+                InlineKeyboardButton::callback(t(lang, "buttons.daily_summary"), "portfolio_summary"),
             ],
             vec![
-                InlineKeyboardButton::callback("🔙 Back to Main Menu", "main_menu"),
+                Self::back_to_main(lang),
             ],
         ])
     }
     
     /// Create settings menu with inline keyboard
     pub fn create_settings_menu() -> InlineKeyboardMarkup {
+        Self::settings_menu(DEFAULT_LANG)
+    }
+    
+    /// The settings menu in `lang`
+    pub fn settings_menu(lang: &str) -> InlineKeyboardMarkup {
         InlineKeyboardMarkup::new(vec![
             vec![
-                InlineKeyboardButton::callback("⚡ Trading Settings", "settings_trading"),
-                InlineKeyboardButton::callback("🔔 Notifications", "settings_notifications"),
+                InlineKeyboardButton::callback(t(lang, "buttons.trading_settings"), "settings_trading"),
+                InlineKeyboardButton::callback(t(lang, "buttons.notifications"), "settings_notifications"),
             ],
             vec![
-                InlineKeyboardButton::callback("🛡️ Security", "settings_security"),
-                InlineKeyboardButton::callback("🤖 AI Settings", "settings_ai"),
+                InlineKeyboardButton::callback(t(lang, "buttons.security"), "settings_security"),
+                InlineKeyboardButton::callback(t(lang, "buttons.ai_settings"), "settings_ai"),
             ],
             vec![
-                InlineKeyboardButton::callback("💎 Rebate Config", "settings_rebates"),
-                InlineKeyboardButton::callback("⚙️ Advanced", "settings_advanced"),
+                InlineKeyboardButton::callback(t(lang, "buttons.rebate_config"), "settings_rebates"),
+                InlineKeyboardButton::callback(t(lang, "buttons.advanced"), "settings_advanced"),
             ],
             vec![
-                InlineKeyboardButton::callback("🔙 Back to Main Menu", "main_menu"),
+                Self::back_to_main(lang),
             ],
         ])
     }
+    
+    fn back_to_main(lang: &str) -> InlineKeyboardButton {
+        InlineKeyboardButton::callback(t(lang, "buttons.back_main"), "main_menu")
+    }
 }
 
 // Helper functions for backwards compatibility
@@ -0,0 +1,145 @@
+use teloxide::{prelude::*, types::Message};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::{
+    bot::{
+        convex_migration::{ConvexMigration, MigrationReport, DEFAULT_BATCH_SIZE},
+        BotServices,
+    },
+    utils::{t, t_args},
+};
+
+/// Skipped records listed before the rest are summarized
+const MAX_SKIPPED_SHOWN: usize = 15;
+
+/// /admin migrate_user | migrate_batch - move Convex-only users over
+pub struct MigrationHandler;
+
+impl MigrationHandler {
+    /// Handle /admin migrate_user <telegram_id> | migrate_batch [limit]; the caller checked the admin
+    pub async fn handle_migration(
+        bot: Bot,
+        msg: Message,
+        action: &str,
+        rest: &str,
+        services: Arc<BotServices>,
+        user_id: String,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let Some(migration) = services.convex_migration.clone() else {
+            bot.send_message(msg.chat.id, t(lang, "migration.not_configured")).await?;
+            return Ok(());
+        };
+
+        match action {
+            "migrate_user" => {
+                let Ok(telegram_id) = rest.parse::<i64>() else {
+                    bot.send_message(msg.chat.id, t(lang, "migration.user_usage")).await?;
+                    return Ok(());
+                };
+                info!("📦 Admin {} migrating Convex user {}", user_id, telegram_id);
+                let text = match migration.migrate_user(telegram_id).await {
+                    Ok(report) => Self::report_text(&report, lang),
+                    Err(e) => t_args(lang, "migration.failed", &[
+                        ("id", &telegram_id.to_string()),
+                        ("error", &e.to_string()),
+                    ]),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            "migrate_batch" => {
+                let limit = if rest.is_empty() {
+                    DEFAULT_BATCH_SIZE
+                } else {
+                    match rest.parse::<usize>() {
+                        Ok(limit) if limit > 0 => limit,
+                        _ => {
+                            bot.send_message(msg.chat.id, t(lang, "migration.batch_usage")).await?;
+                            return Ok(());
+                        }
+                    }
+                };
+                info!("📦 Admin {} migrating up to {} Convex users", user_id, limit);
+                Self::run_batch(&bot, &msg, &migration, limit, lang).await?;
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(lang, "migration.usage")).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_batch(bot: &Bot, msg: &Message, migration: &ConvexMigration, limit: usize, lang: &str) -> ResponseResult<()> {
+        let summary = match migration.migrate_batch(limit).await {
+            Ok(summary) => summary,
+            Err(e) => {
+                bot.send_message(msg.chat.id, t_args(lang, "migration.list_failed", &[("error", &e.to_string())])).await?;
+                return Ok(());
+            }
+        };
+
+        if summary.reports.is_empty() && summary.failed.is_empty() {
+            bot.send_message(msg.chat.id, t(lang, "migration.nothing_left")).await?;
+            return Ok(());
+        }
+
+        let created: usize = summary.reports.iter().map(|r| r.created()).sum();
+        let updated: usize = summary.reports.iter().map(|r| r.updated()).sum();
+        let skipped: usize = summary.reports.iter().map(|r| r.skipped.len()).sum();
+        let mut text = t_args(lang, "migration.batch", &[
+            ("users", &summary.reports.len().to_string()),
+            ("created", &created.to_string()),
+            ("updated", &updated.to_string()),
+            ("skipped", &skipped.to_string()),
+        ]);
+        if !summary.failed.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&t_args(lang, "migration.batch_failed", &[("count", &summary.failed.len().to_string())]));
+            for (telegram_id, error) in &summary.failed {
+                text.push_str(&format!("\n• {}: {}", telegram_id, error));
+            }
+        }
+        text.push_str("\n\n");
+        text.push_str(&t(lang, "migration.batch_hint"));
+        bot.send_message(msg.chat.id, text).await?;
+
+        Ok(())
+    }
+
+    fn report_text(report: &MigrationReport, lang: &str) -> String {
+        let mut text = t_args(lang, "migration.report", &[
+            ("id", &report.telegram_id.to_string()),
+            ("created", &report.created().to_string()),
+            ("updated", &report.updated().to_string()),
+            ("skipped", &report.skipped.len().to_string()),
+        ]);
+
+        let notes: Vec<String> = report.imported.iter()
+            .flat_map(|record| record.notes.iter().map(move |note| format!("• {} {}: {}", record.table.as_str(), record.convex_id, note)))
+            .collect();
+        if !notes.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&t(lang, "migration.notes"));
+            text.push('\n');
+            text.push_str(&notes.join("\n"));
+        }
+
+        if !report.skipped.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&t(lang, "migration.skipped"));
+            for record in report.skipped.iter().take(MAX_SKIPPED_SHOWN) {
+                text.push_str(&format!("\n• {} \"{}\": {}", record.table.as_str(), record.name, record.reason));
+            }
+            if report.skipped.len() > MAX_SKIPPED_SHOWN {
+                text.push_str("\n…");
+                text.push_str(&t_args(lang, "common.and_more", &[("count", &(report.skipped.len() - MAX_SKIPPED_SHOWN).to_string())]));
+            }
+        }
+
+        text.push_str("\n\n");
+        text.push_str(&t(lang, "migration.watch_only"));
+        text
+    }
+}
@@ -7,16 +7,74 @@ pub mod wallet;
 pub mod blinks;
 pub mod monitoring;
 pub mod portfolio;
+pub mod calendar;
+pub mod chart;
+pub mod activity;
+pub mod journal;
+pub mod dca;
+pub mod group_buy;
+pub mod alias;
+pub mod cleanup;
+pub mod migration;
+pub mod bonding;
+pub mod forget;
+pub mod notices;
+pub mod import;
+pub mod stats;
+pub mod automations;
+pub mod trending;
+pub mod price_entry;
+pub mod orders;
+pub mod price_alerts;
+pub mod whales;
+pub mod settings;
+pub mod quick_buy;
+pub mod launch;
+pub mod lending;
+pub mod master;
+pub mod send;
+pub mod language;
+pub mod admin;
+pub mod watchlist;
 
 pub use callback::CallbackHandler;
 pub use command::CommandHandler;
-pub use menu::MenuCreator;
+pub use menu::{MenuCreator, MenuButton};
 pub use text::TextMessageHandler;
 pub use trading::TradingHandler;
 pub use wallet::WalletHandler;
 pub use blinks::BlinksHandler;
 pub use monitoring::MonitoringHandler;
 pub use portfolio::PortfolioHandler;
+pub use calendar::CalendarHandler;
+pub use chart::ChartHandler;
+pub use activity::ActivityHandler;
+pub use journal::JournalHandler;
+pub use dca::DcaHandler;
+pub use group_buy::GroupBuyHandler;
+pub use alias::AliasHandler;
+pub use cleanup::CleanupHandler;
+pub use migration::MigrationHandler;
+pub use admin::AdminHandler;
+pub use bonding::BondingHandler;
+pub use forget::ForgetHandler;
+pub use notices::NoticeHandler;
+pub use import::ImportHandler;
+pub use stats::StatsHandler;
+pub use automations::AutomationsHandler;
+pub use trending::TrendingHandler;
+pub use price_entry::PriceEntryHandler;
+pub use orders::OrderHandler;
+pub use price_alerts::PriceAlertHandler;
+pub use whales::WhaleHandler;
+pub use settings::SettingsHandler;
+pub use quick_buy::QuickBuyHandler;
+pub use launch::LaunchHandler;
+pub use lending::LendingHandler;
+pub use master::MasterHandler;
+pub use send::SendHandler;
+pub use language::LanguageHandler;
+pub use watchlist::WatchlistHandler;
 
 // Re-export specific menu functions for convenience
 pub use menu::{create_main_menu, create_trading_menu, create_wallet_menu, 
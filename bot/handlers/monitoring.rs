@@ -1,9 +1,13 @@
 use teloxide::{prelude::*, types::Message};
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+use teloxide::utils::markdown::escape;
 use std::sync::Arc;
 use tracing::{info, error};
 
-use crate::monitoring::{MonitoringIntegration, HealthStatus};
+use crate::{
+    monitoring::{MonitoringIntegration, HealthStatus},
+    utils::{t, t_args},
+};
 
 /// Monitoring command handler for bot
 pub struct MonitoringHandler;
@@ -15,6 +19,7 @@ impl MonitoringHandler {
         msg: Message,
         args: String,
         monitoring: Arc<MonitoringIntegration>,
+        lang: &str,
     ) -> ResponseResult<()> {
         let parts: Vec<&str> = args.split_whitespace().collect();
         
@@ -22,40 +27,20 @@ impl MonitoringHandler {
             // Show monitoring menu
             let keyboard = InlineKeyboardMarkup::new(vec![
                 vec![
-                    InlineKeyboardButton::callback("📊 System Health", "mon_health"),
-                    InlineKeyboardButton::callback("📈 Metrics", "mon_metrics"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.monitor_health"), "mon_health"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.monitor_metrics"), "mon_metrics"),
                 ],
                 vec![
-                    InlineKeyboardButton::callback("🔍 Telemetry", "mon_telemetry"),
-                    InlineKeyboardButton::callback("🚨 Alerts", "mon_alerts"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.monitor_telemetry"), "mon_telemetry"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.monitor_alerts"), "mon_alerts"),
                 ],
                 vec![
-                    InlineKeyboardButton::callback("⚙️ Settings", "mon_settings"),
-                    InlineKeyboardButton::url("🌐 Dashboard", "http://127.0.0.1:3000/dashboard"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.settings"), "mon_settings"),
+                    InlineKeyboardButton::url(t(lang, "buttons.monitor_dashboard"), "http://127.0.0.1:3000/dashboard"),
                 ],
             ]);
             
-            let message = r#"📊 *Monitoring & Observability*
-
-Monitor system health, performance metrics, and alerts\.
-
-*System Overview:*
-• Health checks for all components
-• Real\-time performance metrics
-• Distributed tracing
-• Automated alerting
-
-*Available Commands:*
-`/monitor health` \- System health status
-`/monitor metrics` \- Performance metrics
-`/monitor alerts` \- Active alerts
-`/monitor trace <operation>` \- Trace operation
-
-*Dashboard:* http://127\.0\.0\.1:3000/dashboard
-
-Select an option below:"#;
-            
-            bot.send_message(msg.chat.id, message)
+            bot.send_message(msg.chat.id, Self::menu_text(lang))
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                 .reply_markup(keyboard)
                 .await?;
@@ -65,29 +50,27 @@ Select an option below:"#;
         
         match parts[0] {
             "health" => {
-                Self::show_health_status(bot, msg, monitoring).await?;
+                Self::show_health_status(bot, msg, monitoring, lang).await?;
             }
             "metrics" => {
-                Self::show_metrics_summary(bot, msg, monitoring).await?;
+                Self::show_metrics_summary(bot, msg, monitoring, lang).await?;
             }
             "alerts" => {
-                Self::show_active_alerts(bot, msg, monitoring).await?;
+                Self::show_active_alerts(bot, msg, monitoring, lang).await?;
             }
             "telemetry" => {
-                Self::show_telemetry_stats(bot, msg, monitoring).await?;
+                Self::show_telemetry_stats(bot, msg, monitoring, lang).await?;
             }
             "trace" => {
                 if parts.len() < 2 {
-                    bot.send_message(msg.chat.id, 
-                        "❌ Usage: `/monitor trace <operation_name>`")
+                    bot.send_message(msg.chat.id, t(lang, "monitoring.trace_usage"))
                         .await?;
                     return Ok(());
                 }
-                Self::start_trace_operation(bot, msg, parts[1], monitoring).await?;
+                Self::start_trace_operation(bot, msg, parts[1], monitoring, lang).await?;
             }
             _ => {
-                bot.send_message(msg.chat.id, 
-                    "❌ Unknown monitoring command. Use `/monitor` to see options.")
+                bot.send_message(msg.chat.id, t(lang, "monitoring.unknown"))
                     .await?;
             }
         }
@@ -95,11 +78,37 @@ Select an option below:"#;
         Ok(())
     }
     
+    /// The /monitor menu in MarkdownV2
+    fn menu_text(lang: &str) -> String {
+        let commands = [
+            ("/monitor health", "monitoring.command_health"),
+            ("/monitor metrics", "monitoring.command_metrics"),
+            ("/monitor alerts", "monitoring.command_alerts"),
+            ("/monitor trace <operation>", "monitoring.command_trace"),
+        ];
+        let commands: Vec<String> = commands.iter()
+            .map(|(command, key)| format!("`{}` \\- {}", command, escape(&t(lang, key))))
+            .collect();
+        format!(
+            "📊 *{}*\n\n{}\n\n*{}*\n{}\n\n*{}*\n{}\n\n*{}* {}\n\n{}",
+            escape(&t(lang, "monitoring.title")),
+            escape(&t(lang, "monitoring.intro")),
+            escape(&t(lang, "monitoring.overview_title")),
+            escape(&t(lang, "monitoring.overview")),
+            escape(&t(lang, "monitoring.commands_title")),
+            commands.join("\n"),
+            escape(&t(lang, "monitoring.dashboard")),
+            escape("http://127.0.0.1:3000/dashboard"),
+            escape(&t(lang, "monitoring.select")),
+        )
+    }
+    
     /// Show system health status
     async fn show_health_status(
         bot: Bot,
         msg: Message,
         monitoring: Arc<MonitoringIntegration>,
+        lang: &str,
     ) -> ResponseResult<()> {
         let status = monitoring.get_status().await;
         
@@ -110,19 +119,14 @@ Select an option below:"#;
             HealthStatus::Unknown => "❓",
         };
         
-        let mut message = format!(
-            "{} **System Health Status**\n\n\
-            **Overall Status:** {:?}\n\
-            **Uptime:** {} seconds\n\
-            **Version:** {}\n\
-            **Components:** {}\n\n\
-            **Component Details:**\n",
-            overall_emoji,
-            status.health.status,
-            status.health.uptime_seconds,
-            status.health.version,
-            status.health.components.len()
-        );
+        let mut message = t_args(lang, "monitoring.health", &[
+            ("emoji", overall_emoji),
+            ("status", &format!("{:?}", status.health.status)),
+            ("uptime", &status.health.uptime_seconds.to_string()),
+            ("version", &status.health.version),
+            ("components", &status.health.components.len().to_string()),
+        ]);
+        message.push('\n');
         
         for (component, result) in &status.health.components {
             let emoji = match result.status {
@@ -141,12 +145,11 @@ Select an option below:"#;
             ));
         }
         
-        message.push_str(&format!(
-            "\n**Active Alerts:** {}\n\
-            **Dashboard Running:** {}",
-            status.active_alerts_count,
-            if status.dashboard_running { "✅ Yes" } else { "❌ No" }
-        ));
+        message.push('\n');
+        message.push_str(&t_args(lang, "monitoring.health_footer", &[
+            ("alerts", &status.active_alerts_count.to_string()),
+            ("running", &t(lang, if status.dashboard_running { "monitoring.yes" } else { "monitoring.no" })),
+        ]));
         
         bot.send_message(msg.chat.id, message).await?;
         Ok(())
@@ -157,6 +160,7 @@ Select an option below:"#;
         bot: Bot,
         msg: Message,
         monitoring: Arc<MonitoringIntegration>,
+        lang: &str,
     ) -> ResponseResult<()> {
         let status = monitoring.get_status().await;
         let metrics = &status.metrics_summary;
@@ -167,40 +171,22 @@ Select an option below:"#;
             0.0
         };
         
-        let message = format!(
-            "📈 **Performance Metrics**\n\n\
-            **Trading Performance:**\n\
-            • Total Trades: {}\n\
-            • Successful: {} ({:.1}%)\n\
-            • Failed: {}\n\
-            • Volume: {} SOL\n\n\
-            **System Performance:**\n\
-            • Commands Processed: {}\n\
-            • API Calls: {}\n\
-            • Cache Hit Rate: {:.1}%\n\
-            • Total Errors: {}\n\n\
-            **Telemetry:**\n\
-            • Total Spans: {}\n\
-            • Active Spans: {}\n\
-            • Avg Duration: {:.2}ms\n\n\
-            **System:**\n\
-            • Uptime: {:.1} hours\n\
-            • Custom Metrics: {}",
-            metrics.total_trades,
-            metrics.successful_trades,
-            success_rate,
-            metrics.failed_trades,
-            metrics.total_volume_sol,
-            metrics.total_commands,
-            metrics.total_api_calls,
-            metrics.cache_hit_rate * 100.0,
-            metrics.total_errors,
-            status.telemetry_stats.total_spans,
-            status.telemetry_stats.active_spans,
-            status.telemetry_stats.average_duration_ms,
-            metrics.uptime_seconds as f64 / 3600.0,
-            metrics.custom_metrics_count
-        );
+        let message = t_args(lang, "monitoring.metrics", &[
+            ("trades", &metrics.total_trades.to_string()),
+            ("successful", &metrics.successful_trades.to_string()),
+            ("success_rate", &format!("{:.1}", success_rate)),
+            ("failed", &metrics.failed_trades.to_string()),
+            ("volume", &metrics.total_volume_sol.to_string()),
+            ("commands", &metrics.total_commands.to_string()),
+            ("api_calls", &metrics.total_api_calls.to_string()),
+            ("cache_hit_rate", &format!("{:.1}", metrics.cache_hit_rate * 100.0)),
+            ("errors", &metrics.total_errors.to_string()),
+            ("total_spans", &status.telemetry_stats.total_spans.to_string()),
+            ("active_spans", &status.telemetry_stats.active_spans.to_string()),
+            ("duration", &format!("{:.2}", status.telemetry_stats.average_duration_ms)),
+            ("uptime", &format!("{:.1}", metrics.uptime_seconds as f64 / 3600.0)),
+            ("custom", &metrics.custom_metrics_count.to_string()),
+        ]);
         
         bot.send_message(msg.chat.id, message).await?;
         Ok(())
@@ -211,17 +197,18 @@ Select an option below:"#;
         bot: Bot,
         msg: Message,
         monitoring: Arc<MonitoringIntegration>,
+        lang: &str,
     ) -> ResponseResult<()> {
         let active_alerts = monitoring.alert_manager.get_active_alerts().await;
         
         if active_alerts.is_empty() {
-            bot.send_message(msg.chat.id, 
-                "✅ **No Active Alerts**\n\nAll systems operating normally.")
+            bot.send_message(msg.chat.id, t(lang, "monitoring.no_alerts"))
                 .await?;
             return Ok(());
         }
         
-        let mut message = format!("🚨 **Active Alerts ({}):**\n\n", active_alerts.len());
+        let mut message = t_args(lang, "monitoring.alerts", &[("count", &active_alerts.len().to_string())]);
+        message.push_str("\n\n");
         
         for (i, alert) in active_alerts.iter().take(10).enumerate() {
             let emoji = match alert.severity {
@@ -231,20 +218,20 @@ Select an option below:"#;
                 crate::monitoring::alerts::AlertSeverity::Info => "ℹ️",
             };
             
-            message.push_str(&format!(
-                "{}. {} **{}**\n   {:?} | Value: {} | Threshold: {}\n   {}\n\n",
-                i + 1,
-                emoji,
-                alert.title,
-                alert.severity,
-                alert.metric_value,
-                alert.threshold,
-                alert.triggered_at.format("%H:%M:%S UTC")
-            ));
+            message.push_str(&t_args(lang, "monitoring.alert", &[
+                ("n", &(i + 1).to_string()),
+                ("emoji", emoji),
+                ("title", &alert.title),
+                ("severity", &format!("{:?}", alert.severity)),
+                ("value", &alert.metric_value.to_string()),
+                ("threshold", &alert.threshold.to_string()),
+                ("at", &alert.triggered_at.format("%H:%M:%S UTC").to_string()),
+            ]));
+            message.push_str("\n\n");
         }
         
         if active_alerts.len() > 10 {
-            message.push_str(&format!("... and {} more alerts", active_alerts.len() - 10));
+            message.push_str(&t_args(lang, "monitoring.more_alerts", &[("count", &(active_alerts.len() - 10).to_string())]));
         }
         
         bot.send_message(msg.chat.id, message).await?;
@@ -256,48 +243,40 @@ Select an option below:"#;
         bot: Bot,
         msg: Message,
         monitoring: Arc<MonitoringIntegration>,
+        lang: &str,
     ) -> ResponseResult<()> {
         let telemetry_stats = monitoring.telemetry.get_telemetry_stats().await;
         let active_spans = monitoring.telemetry.get_active_spans().await;
         
-        let message = format!(
-            "🔍 **Telemetry Statistics**\n\n\
-            **Span Summary:**\n\
-            • Total Spans: {}\n\
-            • Active Spans: {}\n\
-            • Completed Spans: {}\n\
-            • Unique Operations: {}\n\
-            • Average Duration: {:.2}ms\n\n\
-            **Active Operations:**\n",
-            telemetry_stats.total_spans,
-            telemetry_stats.active_spans,
-            telemetry_stats.completed_spans,
-            telemetry_stats.unique_operations,
-            telemetry_stats.average_duration_ms
-        );
-        
-        let mut operations_message = message;
+        let mut operations_message = t_args(lang, "monitoring.telemetry", &[
+            ("total", &telemetry_stats.total_spans.to_string()),
+            ("active", &telemetry_stats.active_spans.to_string()),
+            ("completed", &telemetry_stats.completed_spans.to_string()),
+            ("operations", &telemetry_stats.unique_operations.to_string()),
+            ("duration", &format!("{:.2}", telemetry_stats.average_duration_ms)),
+        ]);
+        operations_message.push('\n');
         
         for (i, span) in active_spans.iter().take(5).enumerate() {
             let duration = chrono::Utc::now()
                 .signed_duration_since(span.start_time)
                 .num_milliseconds();
             
-            operations_message.push_str(&format!(
-                "{}. **{}** ({}ms)\n   Trace: {}...\n",
-                i + 1,
-                span.operation_name,
-                duration,
-                &span.trace_id[..8]
-            ));
+            operations_message.push_str(&t_args(lang, "monitoring.span", &[
+                ("n", &(i + 1).to_string()),
+                ("operation", &span.operation_name),
+                ("duration", &duration.to_string()),
+                ("trace", &span.trace_id[..8]),
+            ]));
+            operations_message.push('\n');
         }
         
         if active_spans.len() > 5 {
-            operations_message.push_str(&format!("... and {} more operations", active_spans.len() - 5));
+            operations_message.push_str(&t_args(lang, "monitoring.more_operations", &[("count", &(active_spans.len() - 5).to_string())]));
         }
         
         if active_spans.is_empty() {
-            operations_message.push_str("No active operations");
+            operations_message.push_str(&t(lang, "monitoring.no_operations"));
         }
         
         bot.send_message(msg.chat.id, operations_message).await?;
@@ -310,16 +289,14 @@ Select an option below:"#;
         msg: Message,
         operation: &str,
         monitoring: Arc<MonitoringIntegration>,
+        lang: &str,
     ) -> ResponseResult<()> {
         let span_id = monitoring.start_span(operation).await;
         
-        bot.send_message(msg.chat.id, 
-            format!("🔍 **Trace Started**\n\n\
-            Operation: {}\n\
-            Span ID: {}\n\
-            Status: Active\n\n\
-            Use `/monitor telemetry` to view active traces.", 
-            operation, &span_id[..8]))
+        bot.send_message(msg.chat.id, t_args(lang, "monitoring.trace_started", &[
+            ("operation", operation),
+            ("span", &span_id[..8]),
+        ]))
             .await?;
         
         // Simulate some work for demo
@@ -327,12 +304,10 @@ Select an option below:"#;
         
         monitoring.finish_span(&span_id).await;
         
-        bot.send_message(msg.chat.id, 
-            format!("✅ **Trace Completed**\n\n\
-            Operation: {}\n\
-            Span ID: {}\n\
-            Status: Completed", 
-            operation, &span_id[..8]))
+        bot.send_message(msg.chat.id, t_args(lang, "monitoring.trace_completed", &[
+            ("operation", operation),
+            ("span", &span_id[..8]),
+        ]))
             .await?;
         
         Ok(())
@@ -343,29 +318,25 @@ Select an option below:"#;
         bot: Bot,
         callback_query: teloxide::types::CallbackQuery,
         monitoring: Arc<MonitoringIntegration>,
+        lang: &str,
     ) -> ResponseResult<()> {
         if let Some(data) = &callback_query.data {
             if let Some(msg) = &callback_query.message {
                 match data.as_str() {
                     "mon_health" => {
-                        Self::show_health_status(bot, msg.clone(), monitoring).await?;
+                        Self::show_health_status(bot, msg.clone(), monitoring, lang).await?;
                     }
                     "mon_metrics" => {
-                        Self::show_metrics_summary(bot, msg.clone(), monitoring).await?;
+                        Self::show_metrics_summary(bot, msg.clone(), monitoring, lang).await?;
                     }
                     "mon_telemetry" => {
-                        Self::show_telemetry_stats(bot, msg.clone(), monitoring).await?;
+                        Self::show_telemetry_stats(bot, msg.clone(), monitoring, lang).await?;
                     }
                     "mon_alerts" => {
-                        Self::show_active_alerts(bot, msg.clone(), monitoring).await?;
+                        Self::show_active_alerts(bot, msg.clone(), monitoring, lang).await?;
                     }
                     "mon_settings" => {
-                        bot.send_message(msg.chat.id, 
-                            "⚙️ **Monitoring Settings**\n\n\
-                            Dashboard: http://127.0.0.1:3000\n\
-                            Metrics: http://127.0.0.1:3000/metrics\n\
-                            Health: http://127.0.0.1:3000/health\n\n\
-                            Use the web dashboard for detailed configuration.")
+                        bot.send_message(msg.chat.id, t(lang, "monitoring.settings"))
                             .await?;
                     }
                     _ => {}
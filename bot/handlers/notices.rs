@@ -0,0 +1,123 @@
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, Message},
+};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    bot::BotServices,
+    trading::{ExecutionNotifier, OutgoingNotice, Verbosity},
+    utils::{lang_of, t, t_args},
+};
+
+/// Fill notices, digests and the verbosity controls for them
+pub struct NoticeHandler;
+
+impl NoticeHandler {
+    /// Deliver fill notices, digests, failures and risk refusals
+    pub fn spawn_notification_forwarder(bot: Bot, notices: Arc<ExecutionNotifier>) {
+        let mut receiver = notices.subscribe();
+
+        tokio::spawn(async move {
+            while let Ok(outgoing) = receiver.recv().await {
+                let (user_id, text) = match outgoing {
+                    OutgoingNotice::Notice(notice) => (notice.user_id, notice.render()),
+                    OutgoingNotice::Digest(digest) => (digest.user_id, digest.render()),
+                };
+                if let Err(e) = bot.send_message(ChatId(user_id), text).await {
+                    error!("🔔 Failed to deliver execution notice to {}: {}", user_id, e);
+                }
+            }
+        });
+    }
+
+    /// Handle /verbosity [full|summary|silent] - the default for every strategy and order
+    pub async fn handle_verbosity(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let notices = &services.execution_notices;
+
+        if args.trim().is_empty() {
+            bot.send_message(msg.chat.id, t_args(&lang, "notices.overview", &[
+                ("level", notices.global(telegram_id).await.badge()),
+                ("minutes", &notices.digest_window().num_minutes().to_string()),
+            ])).await?;
+            return Ok(());
+        }
+
+        match Verbosity::parse(&args) {
+            Some(verbosity) => {
+                notices.set_global(telegram_id, verbosity).await;
+                info!("🔔 User {} set fill notifications to {:?}", telegram_id, verbosity);
+                bot.send_message(msg.chat.id, t_args(&lang, "notices.default_set", &[("level", verbosity.badge())])).await?;
+            }
+            None => {
+                bot.send_message(msg.chat.id, t(&lang, "notices.usage")).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Button that moves a strategy ("s") or order ("o") to the next level
+    pub fn toggle_button(kind: &str, id: &str, name: &str, current: Verbosity) -> InlineKeyboardButton {
+        InlineKeyboardButton::callback(
+            format!("{} · {}", current.badge(), name),
+            format!("verb:{}:{}:{}", kind, current.next().code(), id),
+        )
+    }
+
+    /// Toggle buttons from /dca list and /orders
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+
+        let mut parts = data.splitn(4, ':').skip(1);
+        let (Some(kind), Some(verbosity), Some(id)) = (parts.next(), parts.next().and_then(Verbosity::from_code), parts.next()) else {
+            return Ok(());
+        };
+
+        // Only the owner may change a strategy's or order's level
+        let name = match kind {
+            "s" => services.dca_engine.get_user_strategies(user_id).await
+                .into_iter()
+                .find(|s| s.strategy_id == id)
+                .map(|s| s.name),
+            "o" => services.order_manager.get_user_orders(user_id).await
+                .into_iter()
+                .find(|o| o.order_id == id)
+                .map(|o| o.label()),
+            _ => None,
+        };
+        let Some(name) = name else {
+            bot.send_message(msg.chat.id, t(&lang, "orders.not_found")).await?;
+            return Ok(());
+        };
+
+        match kind {
+            "s" => services.execution_notices.set_strategy(id, verbosity).await,
+            _ => services.execution_notices.set_order(id, verbosity).await,
+        }
+        bot.send_message(msg.chat.id, t_args(&lang, "notices.item_set", &[
+            ("name", &name),
+            ("level", verbosity.badge()),
+        ])).await?;
+        Ok(())
+    }
+}
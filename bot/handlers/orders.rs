@@ -0,0 +1,219 @@
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+use rust_decimal::prelude::ToPrimitive;
+use std::{collections::HashMap, sync::Arc};
+use tracing::{error, info, warn};
+
+use crate::{
+    bot::{chart_actions::ChartActions, BotServices},
+    trading::{Order, OrderStatus, TokenResolver},
+    utils::{lang_of, t, t_args},
+};
+
+use super::notices::NoticeHandler;
+
+/// Shortest id prefix accepted from /cancelorder and /order edit
+const MIN_ID_PREFIX: usize = 4;
+
+/// /orders, /cancelorder and the cancel/edit buttons under the order list
+pub struct OrderHandler;
+
+impl OrderHandler {
+    /// Handle /orders - open orders with trigger distance, notification level and exit denomination
+    pub async fn handle_orders(
+        bot: Bot,
+        msg: Message,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+
+        let orders = services.order_manager.get_user_orders(telegram_id).await;
+        if orders.is_empty() {
+            bot.send_message(msg.chat.id, t(lang, "orders.none")).await?;
+            return Ok(());
+        }
+        let prices = Self::current_prices(&services, &orders).await;
+
+        let mut lines = Vec::new();
+        let mut buttons = Vec::new();
+        for order in &orders {
+            let (verbosity, _) = services.execution_notices
+                .effective(telegram_id, order.strategy_id(), Some(&order.order_id))
+                .await;
+            let exit = services.order_manager.exit_denomination(order).await;
+            lines.push(t_args(lang, "orders.entry", &[
+                ("order", &order.label()),
+                ("status", &format!("{:?}", order.status)),
+                ("id", Self::short_id(&order.order_id)),
+                ("trigger", &Self::describe_trigger(order, prices.get(&order.token_mint).copied(), lang)),
+                ("amount", &order.base_amount.to_string()),
+                ("symbol", &TokenResolver::get_symbol(&order.token_mint)),
+                ("notifications", verbosity.badge()),
+                ("exit", exit.label()),
+            ]));
+            buttons.push(vec![
+                NoticeHandler::toggle_button("o", &order.order_id, &order.label(), verbosity),
+                InlineKeyboardButton::callback(
+                    t_args(lang, "buttons.exit_to", &[("denomination", exit.other().label())]),
+                    format!("exit:{}:{}", exit.other().code(), order.order_id),
+                ),
+            ]);
+            buttons.push(vec![
+                InlineKeyboardButton::callback(t(lang, "buttons.edit"), format!("ord:edit:{}", order.order_id)),
+                InlineKeyboardButton::callback(t(lang, "buttons.cancel"), format!("ord:cancel:{}", order.order_id)),
+            ]);
+        }
+
+        bot.send_message(msg.chat.id, t_args(lang, "orders.list", &[("orders", &lines.join("\n\n"))]))
+            .reply_markup(InlineKeyboardMarkup::new(buttons))
+            .await?;
+        Ok(())
+    }
+
+    /// Handle /cancelorder <id>
+    pub async fn handle_cancel_order(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+        let id = args.trim();
+        if id.is_empty() {
+            bot.send_message(msg.chat.id, t(lang, "orders.cancel_usage")).await?;
+            return Ok(());
+        }
+
+        let reply = match Self::find_order(&services, telegram_id, id).await {
+            Some(order) => Self::cancel(&services, &order, lang).await,
+            None => t_args(lang, "price_entry.no_order", &[("id", id)]),
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        Ok(())
+    }
+
+    /// Cancel and edit buttons from /orders: `ord:<cancel|edit>:<order_id>`
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+
+        let mut parts = data.splitn(3, ':').skip(1);
+        let (Some(action), Some(order_id)) = (parts.next(), parts.next()) else {
+            return Ok(());
+        };
+
+        // Only the owner may touch an order
+        let Some(order) = Self::find_order(&services, user_id, order_id).await else {
+            bot.send_message(msg.chat.id, t(&lang, "orders.not_found")).await?;
+            return Ok(());
+        };
+
+        let reply = match action {
+            "cancel" => Self::cancel(&services, &order, &lang).await,
+            "edit" => t_args(&lang, "orders.edit_prompt", &[
+                ("order", &order.label()),
+                ("price", &order.trigger_price().and_then(|p| p.to_f64()).map(ChartActions::format_price).unwrap_or_else(|| "?".to_string())),
+                ("id", Self::short_id(&order.order_id)),
+            ]),
+            _ => return Ok(()),
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        Ok(())
+    }
+
+    /// The user's open order with id `id`, or the only one whose id starts with it
+    pub async fn find_order(services: &BotServices, user_id: i64, id: &str) -> Option<Order> {
+        let orders = services.order_manager.get_user_orders(user_id).await;
+        Self::match_id(orders, id)
+    }
+
+    /// Exact id, else a unique prefix of at least `MIN_ID_PREFIX` characters
+    pub fn match_id(orders: Vec<Order>, id: &str) -> Option<Order> {
+        if let Some(order) = orders.iter().find(|o| o.order_id == id) {
+            return Some(order.clone());
+        }
+        if id.len() < MIN_ID_PREFIX {
+            return None;
+        }
+        let mut matches = orders.into_iter().filter(|o| o.order_id.starts_with(id));
+        let order = matches.next()?;
+        matches.next().is_none().then_some(order)
+    }
+
+    /// First eight characters, enough to tell a user's orders apart
+    pub fn short_id(order_id: &str) -> &str {
+        order_id.get(..8).unwrap_or(order_id)
+    }
+
+    /// "Trigger: $0.000015 (25.0% below current)", plus the trail for managed trailing stops
+    pub fn describe_trigger(order: &Order, current_price: Option<f64>, lang: &str) -> String {
+        let Some(level) = order.trigger_price().and_then(|p| p.to_f64()) else {
+            return t(lang, "orders.trigger_conditions");
+        };
+        let price = ChartActions::format_price(level);
+        let mut text = match current_price.filter(|price| *price > 0.0) {
+            Some(current) => {
+                let pct = (level - current) / current * 100.0;
+                let key = if pct < 0.0 { "orders.trigger_below" } else { "orders.trigger_above" };
+                t_args(lang, key, &[("price", &price), ("pct", &format!("{:.1}", pct.abs()))])
+            }
+            None => t_args(lang, "orders.trigger", &[("price", &price)]),
+        };
+        if let Some(trail) = &order.metadata.trail_distance {
+            text.push('\n');
+            text.push_str(&t_args(lang, "orders.trail", &[("trail", &trail.describe())]));
+        }
+        text
+    }
+
+    async fn cancel(services: &BotServices, order: &Order, lang: &str) -> String {
+        match services.order_manager.cancel_order(&order.order_id).await {
+            Ok(true) => {
+                info!("📋 User {} cancelled order {}", order.user_id, order.order_id);
+                format!("❌ {} ({}) · {:?}", order.label(), Self::short_id(&order.order_id), OrderStatus::Cancelled)
+            }
+            Ok(false) => t_args(lang, "orders.already_finished", &[("order", &order.label())]),
+            Err(e) => {
+                error!("Failed to cancel order {}: {}", order.order_id, e);
+                t_args(lang, "orders.cancel_failed", &[("order", &order.label()), ("error", &e.to_string())])
+            }
+        }
+    }
+
+    /// One price lookup for every mint in the list
+    async fn current_prices(services: &BotServices, orders: &[Order]) -> HashMap<String, f64> {
+        let mut mints: Vec<String> = orders.iter().map(|o| o.token_mint.clone()).collect();
+        mints.sort();
+        mints.dedup();
+        match services.price_client.get_prices(mints).await {
+            Ok(response) => response.prices.into_iter()
+                .map(|(mint, data)| (mint, data.usd_price))
+                .filter(|(_, price)| *price > 0.0)
+                .collect(),
+            Err(e) => {
+                warn!("📋 Price lookup for /orders failed: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     wallet::WalletManager,
     middleware::rate_limiter::{UserRateLimiter, RateLimitConfig},
     errors::BotError,
+    utils::i18n::{fmt_number, lang_of, t, t_args, NumberKind},
 };
 
 /// Portfolio command handler with real data
@@ -44,9 +45,7 @@ impl PortfolioHandler {
                 "analytics" => Self::show_portfolio_analytics(bot, msg, wallet_manager, &user_id).await?,
                 "refresh" => Self::refresh_portfolio_data(bot, msg, wallet_manager, &user_id).await?,
                 _ => {
-                    bot.send_message(msg.chat.id, 
-                        "❌ Unknown portfolio command. Use `/portfolio` to see options.")
-                        .await?;
+                    bot.send_message(msg.chat.id, t(lang_of(msg.from()), "portfolio.unknown_command")).await?;
                 }
             }
         }
@@ -61,27 +60,23 @@ impl PortfolioHandler {
         wallet_manager: Arc<WalletManager>,
         user_id: &str,
     ) -> ResponseResult<()> {
+        let lang = lang_of(msg.from());
         // Get user's active wallet
         let wallet = match wallet_manager.get_user_wallet(user_id).await {
             Ok(Some(wallet)) => wallet,
             Ok(None) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ No active wallet found. Use `/wallet connect` to connect a wallet.")
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "swap.no_wallet")).await?;
                 return Ok(());
             }
             Err(e) => {
                 error!("Failed to get user wallet: {}", e);
-                bot.send_message(msg.chat.id, 
-                    "❌ Failed to access wallet. Please try again.")
-                    .await?;
+                bot.send_message(msg.chat.id, t(lang, "swap.wallet_failed")).await?;
                 return Ok(());
             }
         };
         
         // Show loading message
-        let loading_msg = bot.send_message(msg.chat.id, "🔄 Fetching real portfolio data...")
-            .await?;
+        let loading_msg = bot.send_message(msg.chat.id, t(lang, "portfolio.fetching")).await?;
         
         // Initialize portfolio fetcher with real RPC endpoint
         let rpc_url = std::env::var("SOLANA_RPC_URL")
@@ -96,55 +91,58 @@ impl PortfolioHandler {
                 
                 let keyboard = InlineKeyboardMarkup::new(vec![
                     vec![
-                        InlineKeyboardButton::callback("📊 Detailed Holdings", "portfolio_holdings"),
-                        InlineKeyboardButton::callback("📈 Performance", "portfolio_performance"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.detailed_holdings"), "portfolio_holdings"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.performance"), "portfolio_performance"),
                     ],
                     vec![
-                        InlineKeyboardButton::callback("🔍 Analytics", "portfolio_analytics"),
-                        InlineKeyboardButton::callback("🔄 Refresh", "portfolio_refresh"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.analytics"), "portfolio_analytics"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.refresh"), "portfolio_refresh"),
                     ],
                     vec![
-                        InlineKeyboardButton::callback("💱 Quick Swap", "quick_swap"),
-                        InlineKeyboardButton::callback("📤 Send Tokens", "send_tokens"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.quick_swap"), "quick_swap"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.send_tokens"), "send_tokens"),
                     ],
                 ]);
                 
                 let performance_emoji = if summary.performance_24h >= 0.0 { "📈" } else { "📉" };
                 let performance_color = if summary.performance_24h >= 0.0 { "🟢" } else { "🔴" };
                 
-                let message = format!(
-                    "💼 **Your Portfolio**\n\n\
-                    💰 **Total Value:** ${:.2}\n\
-                    📊 **Holdings:** {} tokens\n\
-                    {} **24h Performance:** {}{:.2}%\n\n\
-                    **🔝 Top Holdings:**\n",
-                    summary.total_value_usd,
-                    summary.total_holdings,
-                    performance_emoji,
-                    performance_color,
-                    summary.performance_24h
-                );
+                // Token-2022 transfer fees come out of anything sold or sent
+                let net_line = if summary.net_value_after_fees_usd < summary.total_value_usd {
+                    t_args(lang, "portfolio.after_fees", &[("value", &fmt_number(lang, summary.net_value_after_fees_usd, NumberKind::Usd))])
+                } else {
+                    String::new()
+                };
                 
-                let mut holdings_text = message;
+                let mut holdings_text = t_args(lang, "portfolio.overview", &[
+                    ("total", &fmt_number(lang, summary.total_value_usd, NumberKind::Usd)),
+                    ("net_line", &net_line),
+                    ("holdings", &summary.total_holdings.to_string()),
+                    ("emoji", performance_emoji),
+                    ("color", performance_color),
+                    ("change", &fmt_number(lang, summary.performance_24h, NumberKind::Change)),
+                ]);
                 for (i, holding) in summary.top_holdings.iter().take(5).enumerate() {
-                    holdings_text.push_str(&format!(
-                        "{}. **{}** - {:.4} tokens (${:.2} - {:.1}%)\n",
-                        i + 1,
-                        holding.symbol,
-                        holding.balance,
-                        holding.value_usd,
-                        holding.percentage
-                    ));
+                    holdings_text.push_str(&t_args(lang, "portfolio.top_holding", &[
+                        ("rank", &(i + 1).to_string()),
+                        ("symbol", &holding.symbol),
+                        ("balance", &fmt_number(lang, holding.balance, NumberKind::Token)),
+                        ("value", &fmt_number(lang, holding.value_usd, NumberKind::Usd)),
+                        ("share", &fmt_number(lang, holding.percentage, NumberKind::Percent(1))),
+                    ]));
+                    if let Some(bps) = holding.transfer_fee_bps.filter(|bps| *bps > 0) {
+                        holdings_text.push_str(&t_args(lang, "portfolio.transfer_fee", &[
+                            ("fee", &fmt_number(lang, bps as f64 / 100.0, NumberKind::Percent(1))),
+                            ("net", &fmt_number(lang, holding.net_value_after_fees, NumberKind::Usd)),
+                        ]));
+                    }
                 }
                 
+                holdings_text.push('\n');
                 if summary.total_value_usd > 0.0 {
-                    holdings_text.push_str(&format!(
-                        "\n💡 Wallet: `{}`\n\
-                        ⏰ Last updated: Just now",
-                        &wallet.public_key[..8]
-                    ));
+                    holdings_text.push_str(&t_args(lang, "portfolio.wallet_footer", &[("wallet", &wallet.public_key[..8])]));
                 } else {
-                    holdings_text.push_str("\n💡 No tokens found in this wallet");
+                    holdings_text.push_str(&t(lang, "portfolio.no_tokens_footer"));
                 }
                 
                 bot.send_message(msg.chat.id, holdings_text)
@@ -156,13 +154,7 @@ impl PortfolioHandler {
                 bot.delete_message(msg.chat.id, loading_msg.id).await.ok();
                 
                 error!("Failed to fetch portfolio: {}", e);
-                bot.send_message(msg.chat.id, 
-                    format!("❌ Failed to fetch portfolio data: {}\n\n\
-                    This could be due to:\n\
-                    • RPC endpoint issues\n\
-                    • Network connectivity\n\
-                    • Invalid wallet address\n\n\
-                    Please try again later.", e))
+                bot.send_message(msg.chat.id, t_args(lang, "portfolio.fetch_failed", &[("error", &e.to_string())]))
                     .await?;
             }
         }
@@ -180,8 +172,8 @@ impl PortfolioHandler {
         let wallet = wallet_manager.get_user_wallet(user_id).await?
             .ok_or_else(|| BotError::validation("No active wallet"))?;
         
-        let loading_msg = bot.send_message(msg.chat.id, "🔄 Loading detailed holdings...")
-            .await?;
+        let lang = lang_of(msg.from());
+        let loading_msg = bot.send_message(msg.chat.id, t(lang, "portfolio.loading_holdings")).await?;
         
         let rpc_url = std::env::var("SOLANA_RPC_URL")
             .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
@@ -192,41 +184,36 @@ impl PortfolioHandler {
                 bot.delete_message(msg.chat.id, loading_msg.id).await.ok();
                 
                 if portfolio.holdings.is_empty() {
-                    bot.send_message(msg.chat.id, 
-                        "💰 **No tokens found**\n\n\
-                        This wallet doesn't contain any tokens.\n\
-                        Send some SOL or tokens to start trading!")
-                        .await?;
+                    bot.send_message(msg.chat.id, t(lang, "portfolio.empty")).await?;
                     return Ok(());
                 }
                 
                 let mut message = format!(
-                    "📊 **Detailed Holdings** ({} tokens)\n\n",
-                    portfolio.holdings.len()
+                    "{}\n\n",
+                    t_args(lang, "portfolio.detailed_title", &[("count", &portfolio.holdings.len().to_string())])
                 );
                 
                 for (i, holding) in portfolio.holdings.iter().enumerate() {
                     let verified_badge = if holding.is_verified { "✅" } else { "⚠️" };
-                    let value_display = if holding.value_usd > 0.01 {
-                        format!("${:.2}", holding.value_usd)
-                    } else {
-                        format!("${:.6}", holding.value_usd)
+                    let fee_line = match holding.transfer_fee.as_ref().filter(|_| holding.has_transfer_fee()) {
+                        Some(fee) => t_args(lang, "portfolio.transfer_fee_exit", &[
+                            ("fee", &fmt_number(lang, fee.transfer_fee_basis_points as f64 / 100.0, NumberKind::Percent(1))),
+                            ("net", &fmt_number(lang, holding.net_value_after_fees, NumberKind::Usd)),
+                        ]),
+                        None => String::new(),
                     };
                     
-                    message.push_str(&format!(
-                        "{}. {} **{}** {}\n\
-                           💰 {:.6} tokens\n\
-                           💵 {} (${:.4} per token)\n\
-                           🔗 `{}`\n\n",
-                        i + 1,
-                        verified_badge,
-                        holding.symbol,
-                        holding.name,
-                        holding.balance,
-                        value_display,
-                        holding.price_usd,
-                        &holding.mint_address[..8]
-                    ));
+                    message.push_str(&t_args(lang, "portfolio.detailed_holding", &[
+                        ("rank", &(i + 1).to_string()),
+                        ("badge", verified_badge),
+                        ("symbol", &holding.symbol),
+                        ("name", &holding.name),
+                        ("balance", &fmt_number(lang, holding.balance, NumberKind::Token)),
+                        ("value", &fmt_number(lang, holding.value_usd, NumberKind::Usd)),
+                        ("price", &fmt_number(lang, holding.price_usd, NumberKind::Usd)),
+                        ("fee_line", &fee_line),
+                        ("mint", &holding.mint_address[..8]),
+                    ]));
                     
                     // Split into multiple messages if too long
                     if message.len() > 3500 {
@@ -243,7 +230,7 @@ impl PortfolioHandler {
                 bot.delete_message(msg.chat.id, loading_msg.id).await.ok();
                 error!("Failed to fetch detailed holdings: {}", e);
                 bot.send_message(msg.chat.id, 
-                    format!("❌ Failed to load holdings: {}", e))
+                    t_args(lang, "portfolio.holdings_failed", &[("error", &e.to_string())]))
                     .await?;
             }
         }
@@ -261,8 +248,8 @@ impl PortfolioHandler {
         let wallet = wallet_manager.get_user_wallet(user_id).await?
             .ok_or_else(|| BotError::validation("No active wallet"))?;
         
-        let loading_msg = bot.send_message(msg.chat.id, "📈 Analyzing performance...")
-            .await?;
+        let lang = lang_of(msg.from());
+        let loading_msg = bot.send_message(msg.chat.id, t(lang, "portfolio.analyzing")).await?;
         
         let rpc_url = std::env::var("SOLANA_RPC_URL")
             .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
@@ -275,47 +262,32 @@ impl PortfolioHandler {
                 
                 let analysis = analyzer.analyze_portfolio(&portfolio);
                 
-                let message = format!(
-                    "📈 **Portfolio Performance Analysis**\n\n\
-                    💰 **Total Value:** ${:.2}\n\
-                    📊 **24h Change:** {:.2}%\n\n\
-                    **🎯 Diversification Score:** {:.1}/100\n\
-                    **🔄 Effective Holdings:** {:.1}\n\
-                    **⚖️ Largest Position:** {:.1}%\n\
-                    **🏆 Top 5 Concentration:** {:.1}%\n\n\
-                    **🎭 Risk Assessment:**\n\
-                    • Overall Risk: {:?}\n\
-                    • Volatility Score: {:.1}/100\n\
-                    • Verified Tokens: {:.1}%\n\
-                    • Small Cap Exposure: {:.1}%\n\n\
-                    **📊 Allocation Breakdown:**\n\
-                    • SOL: {:.1}%\n\
-                    • Stablecoins: {:.1}%\n\
-                    • DeFi: {:.1}%\n\
-                    • Meme: {:.1}%\n\
-                    • Other: {:.1}%",
-                    analysis.total_value_usd,
-                    portfolio.performance.pnl_24h_percentage,
-                    analysis.diversification.diversification_score,
-                    analysis.diversification.effective_holdings,
-                    analysis.diversification.largest_position_percentage,
-                    analysis.diversification.top_5_concentration,
-                    analysis.risk_metrics.risk_level,
-                    analysis.risk_metrics.volatility_score,
-                    analysis.risk_metrics.verified_percentage,
-                    analysis.risk_metrics.small_cap_exposure,
-                    analysis.allocation.sol_percentage,
-                    analysis.allocation.stablecoin_percentage,
-                    analysis.allocation.defi_percentage,
-                    analysis.allocation.meme_percentage,
-                    analysis.allocation.other_percentage
-                );
+                let pct = |value: f64| fmt_number(lang, value, NumberKind::Percent(1));
+                let score = |value: f64| fmt_number(lang, value, NumberKind::Decimal(1));
+                
+                let message = t_args(lang, "portfolio.performance", &[
+                    ("total", &fmt_number(lang, analysis.total_value_usd, NumberKind::Usd)),
+                    ("change", &fmt_number(lang, portfolio.performance.pnl_24h_percentage, NumberKind::Change)),
+                    ("diversification", &score(analysis.diversification.diversification_score)),
+                    ("effective", &score(analysis.diversification.effective_holdings)),
+                    ("largest", &pct(analysis.diversification.largest_position_percentage)),
+                    ("top5", &pct(analysis.diversification.top_5_concentration)),
+                    ("risk", &format!("{:?}", analysis.risk_metrics.risk_level)),
+                    ("volatility", &score(analysis.risk_metrics.volatility_score)),
+                    ("verified", &pct(analysis.risk_metrics.verified_percentage)),
+                    ("small_cap", &pct(analysis.risk_metrics.small_cap_exposure)),
+                    ("sol", &pct(analysis.allocation.sol_percentage)),
+                    ("stable", &pct(analysis.allocation.stablecoin_percentage)),
+                    ("defi", &pct(analysis.allocation.defi_percentage)),
+                    ("meme", &pct(analysis.allocation.meme_percentage)),
+                    ("other", &pct(analysis.allocation.other_percentage)),
+                ]);
                 
                 bot.send_message(msg.chat.id, message).await?;
                 
                 // Show recommendations
                 if !analysis.recommendations.is_empty() {
-                    let mut recommendations_text = "💡 **Recommendations:**\n\n".to_string();
+                    let mut recommendations_text = format!("{}\n\n", t(lang, "portfolio.recommendations"));
                     
                     for (i, rec) in analysis.recommendations.iter().take(3).enumerate() {
                         let priority_emoji = match rec.priority {
@@ -326,7 +298,7 @@ impl PortfolioHandler {
                         };
                         
                         recommendations_text.push_str(&format!(
-                            "{}. {} **{}**\n   {}\n",
+                            "{}. {} {}\n   {}\n",
                             i + 1,
                             priority_emoji,
                             rec.title,
@@ -334,7 +306,7 @@ impl PortfolioHandler {
                         ));
                         
                         if let Some(action) = &rec.action {
-                            recommendations_text.push_str(&format!("   🎯 Action: {}\n", action));
+                            recommendations_text.push_str(&format!("   {}\n", t_args(lang, "portfolio.action", &[("action", action.as_str())])));
                         }
                         recommendations_text.push('\n');
                     }
@@ -346,7 +318,7 @@ impl PortfolioHandler {
                 bot.delete_message(msg.chat.id, loading_msg.id).await.ok();
                 error!("Failed to analyze performance: {}", e);
                 bot.send_message(msg.chat.id, 
-                    format!("❌ Performance analysis failed: {}", e))
+                    t_args(lang, "portfolio.analysis_failed", &[("error", &e.to_string())]))
                     .await?;
             }
         }
@@ -361,16 +333,7 @@ impl PortfolioHandler {
         wallet_manager: Arc<WalletManager>,
         user_id: &str,
     ) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, 
-            "🔍 **Portfolio Analytics**\n\n\
-            Advanced analytics features:\n\
-            • Historical performance tracking\n\
-            • Risk-adjusted returns\n\
-            • Correlation analysis\n\
-            • Rebalancing suggestions\n\n\
-            📊 Use the web dashboard for detailed analytics:\n\
-            http://127.0.0.1:3000/dashboard")
-            .await?;
+        bot.send_message(msg.chat.id, t(lang_of(msg.from()), "portfolio.analytics")).await?;
         
         Ok(())
     }
@@ -382,7 +345,7 @@ impl PortfolioHandler {
         wallet_manager: Arc<WalletManager>,
         user_id: &str,
     ) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, "🔄 Refreshing portfolio data...").await?;
+        bot.send_message(msg.chat.id, t(lang_of(msg.from()), "portfolio.refreshing")).await?;
         
         // Re-run the portfolio overview with fresh data
         Self::show_portfolio_overview(bot, msg, wallet_manager, user_id).await
@@ -0,0 +1,208 @@
+use chrono_tz::Tz;
+use teloxide::{prelude::*, types::Message};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    alerts::{AlertDeliveryMethod, AlertStatus, DeliveryStatus, PriceAlert, PriceAlertManager},
+    bot::BotServices,
+    utils::{fmt_datetime, lang_of, t, t_args, DateStyle},
+};
+
+/// Shortest id prefix accepted from /alerts delete
+const MIN_ID_PREFIX: usize = 4;
+
+/// Triggers shown by /alerts history
+const HISTORY_LIMIT: usize = 10;
+
+/// /alerts and delivery of triggered price alerts
+pub struct PriceAlertHandler;
+
+impl PriceAlertHandler {
+    /// Handle /alerts [list] | delete <id> | history | via <methods>
+    pub async fn handle_alerts(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let tz = services.dca_engine.timezones().get_user_timezone(telegram_id).await;
+
+        let mut parts = args.split_whitespace();
+        let subcommand = parts.next().map(str::to_lowercase);
+        let rest: Vec<&str> = parts.collect();
+        let reply = match (subcommand.as_deref(), rest.as_slice()) {
+            (None | Some("list"), _) => Self::list(&services.price_alerts, telegram_id, &lang, tz).await,
+            (Some("delete"), [id]) => Self::delete(&services.price_alerts, telegram_id, id, &lang).await,
+            (Some("history"), []) => Self::history(&services.price_alerts, telegram_id, &lang, tz).await,
+            (Some("via"), methods) => Self::via(&services.price_alerts, telegram_id, methods, &lang).await,
+            _ => t(&lang, "alerts.usage"),
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        Ok(())
+    }
+
+    async fn list(manager: &PriceAlertManager, user_id: i64, lang: &str, tz: Tz) -> String {
+        let mut alerts = manager.get_user_alerts(user_id).await;
+        if alerts.is_empty() {
+            return t(lang, "alerts.none");
+        }
+        // Waiting alerts first, oldest first
+        alerts.sort_by_key(|a| (a.status != AlertStatus::Active, a.created_at));
+
+        let lines: Vec<String> = alerts.iter()
+            .map(|alert| {
+                let conditions: Vec<String> = alert.conditions.iter().map(ToString::to_string).collect();
+                let last = alert.last_triggered
+                    .map(|at| format!("\n{}", t_args(lang, "alerts.last_triggered", &[
+                        ("time", &fmt_datetime(lang, tz, at, DateStyle::DateTime)),
+                    ])))
+                    .unwrap_or_default();
+                format!(
+                    "{} · {:?} · {}\n{}{}",
+                    alert.name,
+                    alert.status,
+                    Self::short_id(&alert.alert_id),
+                    conditions.join("\n"),
+                    last
+                )
+            })
+            .collect();
+
+        t_args(lang, "alerts.list", &[("alerts", &lines.join("\n\n"))])
+    }
+
+    async fn delete(manager: &PriceAlertManager, user_id: i64, id: &str, lang: &str) -> String {
+        // Only the owner's alerts are candidates
+        let Some(alert) = Self::match_id(manager.get_user_alerts(user_id).await, id) else {
+            return t_args(lang, "alerts.no_match", &[("id", id)]);
+        };
+
+        match manager.delete_alert(&alert.alert_id).await {
+            Ok(true) => {
+                info!("🔔 User {} deleted alert {}", user_id, alert.alert_id);
+                t_args(lang, "alerts.deleted", &[("name", &alert.name), ("id", Self::short_id(&alert.alert_id))])
+            }
+            Ok(false) => t_args(lang, "alerts.already_deleted", &[("name", &alert.name)]),
+            Err(e) => {
+                error!("Failed to delete alert {}: {}", alert.alert_id, e);
+                t_args(lang, "alerts.delete_failed", &[("name", &alert.name), ("error", &e.to_string())])
+            }
+        }
+    }
+
+    async fn history(manager: &PriceAlertManager, user_id: i64, lang: &str, tz: Tz) -> String {
+        let history = manager.get_user_history(user_id, HISTORY_LIMIT).await;
+        if history.is_empty() {
+            return t(lang, "alerts.no_history");
+        }
+
+        let entries: Vec<String> = history.iter()
+            .map(|entry| {
+                let name = entry.metadata.get("name").map(String::as_str).unwrap_or(&entry.alert_id);
+                let mut deliveries: Vec<String> = entry.delivery_status.iter()
+                    .map(|(method, status)| match status {
+                        DeliveryStatus::Sent => format!("✅ {}", method),
+                        DeliveryStatus::Failed(reason) => format!("❌ {}: {}", method, reason),
+                        DeliveryStatus::Pending => format!("⏳ {}", method),
+                    })
+                    .collect();
+                deliveries.sort();
+                t_args(lang, "alerts.history_entry", &[
+                    ("name", name),
+                    ("time", &fmt_datetime(lang, tz, entry.triggered_at, DateStyle::DateTime)),
+                    ("price", &entry.trigger_price.to_string()),
+                    ("deliveries", &deliveries.join("\n")),
+                ])
+            })
+            .collect();
+
+        t_args(lang, "alerts.history", &[("entries", &entries.join("\n\n"))])
+    }
+
+    async fn via(manager: &PriceAlertManager, user_id: i64, args: &[&str], lang: &str) -> String {
+        let delivery = manager.delivery();
+        if args.is_empty() {
+            let methods: Vec<String> = delivery.user_methods(user_id).await.iter().map(|m| m.label()).collect();
+            return t_args(lang, "alerts.delivered_via", &[("methods", &methods.join("\n"))]);
+        }
+
+        let methods = match Self::parse_methods(user_id, args, lang) {
+            Ok(methods) => methods,
+            Err(e) => return format!("❌ {}\n\n{}", e, t(lang, "alerts.usage")),
+        };
+        let labels: Vec<String> = methods.iter().map(|m| m.label()).collect();
+        delivery.set_user_methods(user_id, methods).await;
+        info!("🔔 User {} set alert delivery to {:?}", user_id, labels);
+        t_args(lang, "alerts.delivery_set", &[("methods", &labels.join("\n"))])
+    }
+
+    /// `telegram`, or a channel name followed by its address; errors are in `lang`
+    pub fn parse_methods(user_id: i64, args: &[&str], lang: &str) -> Result<Vec<AlertDeliveryMethod>, String> {
+        let mut methods: Vec<AlertDeliveryMethod> = Vec::new();
+        let mut args = args.iter();
+        while let Some(channel) = args.next() {
+            let channel = channel.to_lowercase();
+            let method = if channel == "telegram" {
+                AlertDeliveryMethod::Telegram { chat_id: user_id }
+            } else {
+                let Some(target) = args.next() else {
+                    return Err(t_args(lang, "alerts.needs_address", &[("channel", &channel)]));
+                };
+                let target = target.to_string();
+                let is_url = target.starts_with("https://") || target.starts_with("http://");
+                match channel.as_str() {
+                    "email" if target.contains('@') => AlertDeliveryMethod::Email { address: target },
+                    "webhook" if is_url => AlertDeliveryMethod::Webhook { url: target },
+                    "discord" if is_url => AlertDeliveryMethod::Discord { webhook_url: target },
+                    "slack" if is_url => AlertDeliveryMethod::Slack { webhook_url: target },
+                    "email" | "webhook" | "discord" | "slack" => {
+                        return Err(t_args(lang, "alerts.invalid_address", &[("target", &target), ("channel", &channel)]));
+                    }
+                    _ => return Err(t_args(lang, "alerts.unknown_method", &[("channel", &channel)])),
+                }
+            };
+            if !methods.iter().any(|m| m.label() == method.label()) {
+                methods.push(method);
+            }
+        }
+        Ok(methods)
+    }
+
+    /// Exact id, else a unique prefix of at least `MIN_ID_PREFIX` characters
+    pub fn match_id(alerts: Vec<PriceAlert>, id: &str) -> Option<PriceAlert> {
+        if let Some(alert) = alerts.iter().find(|a| a.alert_id == id) {
+            return Some(alert.clone());
+        }
+        if id.len() < MIN_ID_PREFIX {
+            return None;
+        }
+        let mut matches = alerts.into_iter().filter(|a| a.alert_id.starts_with(id));
+        let alert = matches.next()?;
+        matches.next().is_none().then_some(alert)
+    }
+
+    /// First eight characters, enough to tell a user's alerts apart
+    pub fn short_id(alert_id: &str) -> &str {
+        alert_id.get(..8).unwrap_or(alert_id)
+    }
+
+    /// Send triggered price alerts to their chats
+    pub fn spawn_notification_forwarder(bot: Bot, alerts: Arc<PriceAlertManager>) {
+        let mut receiver = alerts.subscribe_notifications();
+
+        tokio::spawn(async move {
+            while let Ok(notification) = receiver.recv().await {
+                if let Err(e) = bot.send_message(ChatId(notification.chat_id), notification.message).await {
+                    error!("🔔 Failed to deliver alert {} to {}: {}", notification.alert_id, notification.chat_id, e);
+                }
+            }
+        });
+    }
+}
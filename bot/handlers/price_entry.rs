@@ -0,0 +1,395 @@
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    bot::{
+        chart_actions::{AlertDirection, ChartActions},
+        price_entry::{EntryResolution, PriceAnswer, PriceEntry, PriceEntryTarget, PriceLeg},
+        BotServices,
+    },
+    errors::{BotError, Result},
+    trading::{OrderType, ProtectiveClass, TokenResolver, TradingEngineHandle},
+    utils::{
+        price_input::{parse_price, PriceCandidate, PriceInput, PriceIntent},
+        t, t_args,
+    },
+    wallet::WalletManager,
+};
+
+use super::{chart::ChartHandler, orders::OrderHandler};
+
+/// /alert, /bracket and /order, and the clarification buttons for typed prices
+pub struct PriceEntryHandler;
+
+impl PriceEntryHandler {
+    /// Handle /alert <token> [above|below] <price>
+    pub async fn handle_alert(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let (token, direction, price) = match parts.as_slice() {
+            [token, price] => (*token, None, *price),
+            [token, "above", price] => (*token, Some(AlertDirection::Above), *price),
+            [token, "below", price] => (*token, Some(AlertDirection::Below), *price),
+            _ => {
+                bot.send_message(msg.chat.id, t(&lang, "price_entry.alert_usage")).await?;
+                return Ok(());
+            }
+        };
+
+        let Some(entry) = Self::entry(&bot, &msg, &services, &user_id, token, PriceEntryTarget::Alert { direction }, &lang).await? else {
+            return Ok(());
+        };
+        let intent = PriceEntry::alert_intent(direction);
+        Self::submit_typed(&bot, &services, entry, &[("alert", price, intent)], &lang).await
+    }
+
+    /// Handle /bracket <token> <stop> <take_profit> - linked exits for a held position
+    pub async fn handle_bracket(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        trading_engine: TradingEngineHandle,
+        wallet_manager: Arc<WalletManager>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let [token, stop, take_profit] = parts.as_slice() else {
+            bot.send_message(msg.chat.id, t(&lang, "price_entry.bracket_usage")).await?;
+            return Ok(());
+        };
+
+        let Ok(mint) = TokenResolver::resolve(token) else {
+            bot.send_message(msg.chat.id, t_args(&lang, "price_entry.unknown_token", &[("token", *token)])).await?;
+            return Ok(());
+        };
+        let Some(amount) = ChartHandler::position_amount(&trading_engine, &wallet_manager, &user_id, &mint).await else {
+            bot.send_message(msg.chat.id, t_args(&lang, "price_entry.no_position", &[("symbol", &TokenResolver::get_symbol(&mint))]))
+                .await?;
+            return Ok(());
+        };
+
+        let Some(entry) = Self::entry(&bot, &msg, &services, &user_id, token, PriceEntryTarget::Bracket { amount }, &lang).await? else {
+            return Ok(());
+        };
+        Self::submit_typed(
+            &bot,
+            &services,
+            entry,
+            &[("stop", stop, PriceIntent::Below), ("take-profit", take_profit, PriceIntent::Above)],
+            &lang,
+        ).await
+    }
+
+    /// Handle /order sl|tp <token> <price> <amount> and /order edit <id> <price>
+    pub async fn handle_order(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let (entry, price, class) = match parts.as_slice() {
+            [kind @ ("sl" | "tp"), token, price, amount] => {
+                let Some(amount) = amount.parse::<f64>().ok().filter(|a| a.is_finite() && *a > 0.0) else {
+                    bot.send_message(msg.chat.id, t_args(&lang, "price_entry.invalid_amount", &[("amount", *amount)])).await?;
+                    return Ok(());
+                };
+                let class = if *kind == "sl" { ProtectiveClass::Stop } else { ProtectiveClass::TakeProfit };
+                let entry = Self::entry(&bot, &msg, &services, &user_id, token, PriceEntryTarget::Order { class, amount }, &lang).await?;
+                (entry, *price, class)
+            }
+            ["edit", id, price] => {
+                let order = match user_id.parse::<i64>() {
+                    Ok(telegram_id) => OrderHandler::find_order(&services, telegram_id, id).await,
+                    Err(_) => None,
+                };
+                let Some(order) = order else {
+                    bot.send_message(msg.chat.id, t_args(&lang, "price_entry.no_order", &[("id", *id)])).await?;
+                    return Ok(());
+                };
+                let class = match order.order_type {
+                    OrderType::StopLoss { .. } => ProtectiveClass::Stop,
+                    OrderType::TakeProfit { .. } => ProtectiveClass::TakeProfit,
+                    _ => {
+                        bot.send_message(msg.chat.id, t_args(&lang, "price_entry.not_repriceable", &[("order", &order.label())]))
+                            .await?;
+                        return Ok(());
+                    }
+                };
+                let target = PriceEntryTarget::Reprice { order_id: order.order_id.clone(), class };
+                let entry = Self::entry_for_mint(&bot, &msg, &services, order.user_id, order.token_mint, target, &lang).await?;
+                (entry, *price, class)
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(&lang, "price_entry.order_usage")).await?;
+                return Ok(());
+            }
+        };
+
+        let Some(entry) = entry else { return Ok(()) };
+        let label = match class {
+            ProtectiveClass::Stop => "stop",
+            ProtectiveClass::TakeProfit => "take-profit",
+        };
+        Self::submit_typed(&bot, &services, entry, &[(label, price, PriceEntry::order_intent(class))], &lang).await
+    }
+
+    /// An empty entry for `token` at the current market price
+    async fn entry(
+        bot: &Bot,
+        msg: &Message,
+        services: &BotServices,
+        user_id: &str,
+        token: &str,
+        target: PriceEntryTarget,
+        lang: &str,
+    ) -> ResponseResult<Option<PriceEntry>> {
+        let (Ok(telegram_id), Ok(mint)) = (user_id.parse::<i64>(), TokenResolver::resolve(token)) else {
+            bot.send_message(msg.chat.id, t_args(lang, "price_entry.unknown_token", &[("token", token)])).await?;
+            return Ok(None);
+        };
+        Self::entry_for_mint(bot, msg, services, telegram_id, mint, target, lang).await
+    }
+
+    async fn entry_for_mint(
+        bot: &Bot,
+        msg: &Message,
+        services: &BotServices,
+        telegram_id: i64,
+        mint: String,
+        target: PriceEntryTarget,
+        lang: &str,
+    ) -> ResponseResult<Option<PriceEntry>> {
+        let Some(current_price) = ChartHandler::current_price(services, &mint).await else {
+            bot.send_message(msg.chat.id, t(lang, "price_entry.price_unavailable")).await?;
+            return Ok(None);
+        };
+
+        Ok(Some(PriceEntry {
+            user_id: telegram_id,
+            chat_id: msg.chat.id.0,
+            symbol: TokenResolver::get_symbol(&mint),
+            mint,
+            current_price,
+            target,
+            legs: Vec::new(),
+        }))
+    }
+
+    async fn submit_typed(
+        bot: &Bot,
+        services: &BotServices,
+        mut entry: PriceEntry,
+        typed: &[(&'static str, &str, PriceIntent)],
+        lang: &str,
+    ) -> ResponseResult<()> {
+        for (label, text, intent) in typed {
+            let Some(parsed) = parse_price(text) else {
+                bot.send_message(ChatId(entry.chat_id), t_args(lang, "price_entry.invalid_price", &[
+                    ("input", *text),
+                    ("leg", &Self::leg_label(label, lang)),
+                ])).await?;
+                return Ok(());
+            };
+            entry.legs.push(PriceLeg::new(label, parsed, *intent));
+        }
+        Self::submit(bot, services, entry, lang).await
+    }
+
+    /// What a leg's price sets, in the user's language
+    fn leg_label(label: &str, lang: &str) -> String {
+        match label {
+            "stop" => t(lang, "price_entry.leg_stop"),
+            "take-profit" => t(lang, "price_entry.leg_take_profit"),
+            "alert" => t(lang, "price_entry.leg_alert"),
+            other => other.to_string(),
+        }
+    }
+
+    /// Create the entry once every price is settled, otherwise ask about the first doubtful one
+    pub async fn submit(bot: &Bot, services: &BotServices, entry: PriceEntry, lang: &str) -> ResponseResult<()> {
+        let chat_id = ChatId(entry.chat_id);
+        match entry.resolve() {
+            EntryResolution::Ready(prices) => {
+                let reply = Self::create(services, &entry, &prices, lang).await;
+                bot.send_message(chat_id, reply).await?;
+            }
+            EntryResolution::Invalid(reason) => {
+                bot.send_message(chat_id, format!("❌ {}", reason)).await?;
+            }
+            EntryResolution::Question { leg, input } => {
+                let (text, keyboard, candidates) = Self::question(&entry, leg, &input, lang);
+                services.price_entries.ask(entry, leg, candidates).await;
+                bot.send_message(chat_id, text).reply_markup(keyboard).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn question(entry: &PriceEntry, leg: usize, input: &PriceInput, lang: &str) -> (String, InlineKeyboardMarkup, Vec<PriceCandidate>) {
+        let label = Self::leg_label(entry.legs[leg].label, lang);
+        let cancel = InlineKeyboardButton::callback(t(lang, "buttons.cancel"), "pentry:cancel");
+        match input {
+            PriceInput::Ambiguous { entered, candidates } => {
+                let options: Vec<String> = candidates.iter().map(|candidate| Self::describe_candidate(candidate, lang)).collect();
+                let text = t_args(lang, "price_entry.ambiguous", &[
+                    ("symbol", &entry.symbol),
+                    ("current", &ChartActions::format_price(entry.current_price)),
+                    ("leg", &label),
+                    ("entered", &ChartActions::format_price(*entered)),
+                    ("options", &options.join(&t(lang, "price_entry.or"))),
+                ]);
+                let mut rows: Vec<Vec<InlineKeyboardButton>> = candidates.iter().enumerate()
+                    .map(|(i, candidate)| vec![InlineKeyboardButton::callback(
+                        Self::describe_candidate(candidate, lang),
+                        format!("pentry:pick:{}", i),
+                    )])
+                    .collect();
+                rows.push(vec![cancel]);
+                (text, InlineKeyboardMarkup::new(rows), candidates.clone())
+            }
+            PriceInput::TriggersImmediately { price, current_price } => {
+                let text = t_args(lang, "price_entry.triggers_immediately", &[
+                    ("leg", &label),
+                    ("price", &ChartActions::format_price(*price)),
+                    ("symbol", &entry.symbol),
+                    ("current", &ChartActions::format_price(*current_price)),
+                ]);
+                let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                    InlineKeyboardButton::callback(t(lang, "buttons.price_intended"), "pentry:confirm"),
+                    cancel,
+                ]]);
+                (text, keyboard, Vec::new())
+            }
+            PriceInput::Accepted(_) | PriceInput::Invalid(_) => (String::new(), InlineKeyboardMarkup::default(), Vec::new()),
+        }
+    }
+
+    /// "$0.00002 (−7% from current)"
+    pub fn describe_candidate(candidate: &PriceCandidate, lang: &str) -> String {
+        let pct = candidate.change_pct.round() as i64;
+        let digits = pct.unsigned_abs().to_string();
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        t_args(lang, "price_entry.candidate", &[
+            ("price", &ChartActions::format_price(candidate.price)),
+            ("change", &format!("{}{}", if pct < 0 { "−" } else { "+" }, grouped)),
+        ])
+    }
+
+    /// Handle `pentry:pick:<n>`, `pentry:confirm` and `pentry:cancel`
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+
+        let Some(mut pending) = services.price_entries.take(user_id).await else {
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "price_entry.expired")).await?;
+            return Ok(());
+        };
+
+        let answer = match data.trim_start_matches("pentry:") {
+            "confirm" => PriceAnswer::ConfirmImmediate,
+            pick => match pick.strip_prefix("pick:").and_then(|i| i.parse::<usize>().ok()).and_then(|i| pending.candidates.get(i)) {
+                Some(candidate) => PriceAnswer::Pick(candidate.price),
+                None => {
+                    bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "price_entry.cancelled")).await?;
+                    return Ok(());
+                }
+            },
+        };
+
+        bot.edit_message_reply_markup(msg.chat.id, msg.id).await?;
+        pending.entry.answer(pending.leg, answer);
+        Self::submit(bot, &services, pending.entry, &lang).await
+    }
+
+    async fn create(services: &BotServices, entry: &PriceEntry, prices: &[f64], lang: &str) -> String {
+        let result = match (&entry.target, prices) {
+            (PriceEntryTarget::Bracket { .. }, [stop, target]) => Self::create_bracket(services, entry, *stop, *target, lang).await,
+            (PriceEntryTarget::Order { .. }, [price]) => Self::create_order(services, entry, *price, lang).await,
+            (PriceEntryTarget::Reprice { order_id, .. }, [price]) => Self::reprice_order(services, entry, order_id, *price, lang).await,
+            (_, [price]) => match entry.chart_request(*price) {
+                Some(request) => ChartActions::create(&request, services.price_alerts.as_ref(), services.order_manager.as_ref())
+                    .await
+                    .map(|_| format!("✅ {}", ChartActions::caption_note(&request))),
+                None => Ok(String::new()),
+            },
+            _ => Ok(String::new()),
+        };
+
+        match result {
+            Ok(reply) => reply,
+            Err(e) => {
+                error!("💲 Failed to create {} entry for user {}: {}", entry.symbol, entry.user_id, e);
+                format!("❌ {}", e.user_message())
+            }
+        }
+    }
+
+    async fn create_order(services: &BotServices, entry: &PriceEntry, price: f64, lang: &str) -> Result<String> {
+        let order = entry.protective_order(price)?;
+        let (label, amount) = (order.label(), order.base_amount);
+        let order_id = services.order_manager.create_order(order).await?;
+
+        info!("💲 {} for user {} at {}: {}", label, entry.user_id, price, order_id);
+        Ok(t_args(lang, "price_entry.order_created", &[
+            ("order", &label),
+            ("price", &ChartActions::format_price(price)),
+            ("amount", &amount.to_string()),
+            ("symbol", &entry.symbol),
+            ("id", OrderHandler::short_id(&order_id)),
+        ]))
+    }
+
+    async fn reprice_order(services: &BotServices, entry: &PriceEntry, order_id: &str, price: f64, lang: &str) -> Result<String> {
+        let price_decimal = Decimal::from_f64_retain(price)
+            .ok_or_else(|| BotError::validation("Invalid trigger price".to_string()))?;
+        let order = services.order_manager.reprice_order(order_id, price_decimal).await?;
+
+        info!("💲 User {} moved {} to {}", entry.user_id, order_id, price);
+        Ok(t_args(lang, "price_entry.repriced", &[("order", &order.label()), ("price", &ChartActions::format_price(price))]))
+    }
+
+    async fn create_bracket(services: &BotServices, entry: &PriceEntry, stop: f64, target: f64, lang: &str) -> Result<String> {
+        let (stop_leg, target_leg) = entry.bracket_orders(stop, target)?;
+        let stop_id = services.order_manager.create_order(stop_leg).await?;
+        if let Err(e) = services.order_manager.create_order(target_leg).await {
+            // Don't leave half a bracket behind
+            let _ = services.order_manager.cancel_order(&stop_id).await;
+            return Err(e);
+        }
+
+        info!("💲 Bracket on {} for user {}: stop {} / target {}", entry.symbol, entry.user_id, stop, target);
+        Ok(t_args(lang, "price_entry.bracket_set", &[
+            ("symbol", &entry.symbol),
+            ("stop", &ChartActions::format_price(stop)),
+            ("target", &ChartActions::format_price(target)),
+        ]))
+    }
+}
@@ -0,0 +1,207 @@
+use teloxide::{prelude::*, types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message}};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::trading::TradingHandler;
+use crate::bot::{callback_action::CallbackAction, BotServices};
+use crate::cache::SessionStoreExt;
+use crate::constants::MAX_TRADE_SOL;
+use crate::trading::{ImpactSource, SlippageEstimate, TokenCandidate, TokenLookup, TradingEngineHandle};
+use crate::utils::{t, t_args};
+use crate::wallet::WalletManager;
+
+/// How long a tapped confirmation stays spent; longer than anyone keeps a chat open
+const CONFIRMATION_CLAIM_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Quick-buy buttons from /start, the trading menu, /qbuy, /trending and /larp
+pub struct QuickBuyHandler;
+
+impl QuickBuyHandler {
+    pub async fn handle_action(
+        bot: &Bot,
+        q: &CallbackQuery,
+        action: CallbackAction,
+        trading_engine: TradingEngineHandle,
+        wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+        let lang = lang.as_str();
+
+        match action {
+            CallbackAction::QuickBuy { token, amount_sol } => {
+                let Some(token) = Self::resolve(bot, msg.chat.id, &token, amount_sol, &services, lang).await? else {
+                    return Ok(());
+                };
+                let settings = services.preferences.get(user_id).await;
+                if amount_sol > settings.max_trade_sol {
+                    bot.send_message(msg.chat.id, Self::confirmation_text(&token.symbol, amount_sol, settings.max_trade_sol, lang))
+                        .reply_markup(Self::confirmation_keyboard(&token.mint, amount_sol, lang))
+                        .await?;
+                    return Ok(());
+                }
+                TradingHandler::execute_quick_buy(
+                    bot, q, &token.mint, &token.symbol, amount_sol, settings.max_trade_sol, None,
+                    trading_engine, wallet_manager, services.preferences.clone(), services.confirmations.clone(),
+                ).await?;
+            }
+            CallbackAction::ConfirmQuickBuy { token, amount_sol } => {
+                if !Self::claim_confirmation(bot, msg, &services, lang).await? {
+                    return Ok(());
+                }
+                // Drop the buttons so the confirmation can't be tapped twice
+                let _ = bot.edit_message_reply_markup(msg.chat.id, msg.id).await;
+                let Some(token) = Self::resolve(bot, msg.chat.id, &token, amount_sol, &services, lang).await? else {
+                    return Ok(());
+                };
+                info!("⚡ User {} confirmed a {} SOL quick buy of {} above their max", user_id, amount_sol, token.symbol);
+                TradingHandler::execute_quick_buy(
+                    bot, q, &token.mint, &token.symbol, amount_sol, MAX_TRADE_SOL, None,
+                    trading_engine, wallet_manager, services.preferences.clone(), services.confirmations.clone(),
+                ).await?;
+            }
+            CallbackAction::ConfirmHighImpact { token, amount_sol } => {
+                if !Self::claim_confirmation(bot, msg, &services, lang).await? {
+                    return Ok(());
+                }
+                let _ = bot.edit_message_reply_markup(msg.chat.id, msg.id).await;
+                let Some(token) = Self::resolve(bot, msg.chat.id, &token, amount_sol, &services, lang).await? else {
+                    return Ok(());
+                };
+                // Accepting the impact doesn't skip the max trade size check
+                let settings = services.preferences.get(user_id).await;
+                if amount_sol > settings.max_trade_sol {
+                    bot.send_message(msg.chat.id, Self::confirmation_text(&token.symbol, amount_sol, settings.max_trade_sol, lang))
+                        .reply_markup(Self::confirmation_keyboard(&token.mint, amount_sol, lang))
+                        .await?;
+                    return Ok(());
+                }
+                info!("💧 User {} accepted the price impact of a {} SOL buy of {}", user_id, amount_sol, token.symbol);
+                TradingHandler::execute_quick_buy(
+                    bot, q, &token.mint, &token.symbol, amount_sol, settings.max_trade_sol, None,
+                    trading_engine, wallet_manager, services.preferences.clone(), services.confirmations.clone(),
+                ).await?;
+            }
+            CallbackAction::RetryBuy { token, amount_sol } => {
+                // One retry per expired buy
+                let _ = bot.edit_message_reply_markup(msg.chat.id, msg.id).await;
+                let Some(token) = Self::resolve(bot, msg.chat.id, &token, amount_sol, &services, lang).await? else {
+                    return Ok(());
+                };
+                let settings = services.preferences.get(user_id).await;
+                if amount_sol > settings.max_trade_sol {
+                    bot.send_message(msg.chat.id, Self::confirmation_text(&token.symbol, amount_sol, settings.max_trade_sol, lang))
+                        .reply_markup(Self::confirmation_keyboard(&token.mint, amount_sol, lang))
+                        .await?;
+                    return Ok(());
+                }
+                let priority = settings.priority_fee.raised();
+                info!("🔁 User {} retrying an expired {} SOL buy of {} at {:?} priority", user_id, amount_sol, token.symbol, priority);
+                TradingHandler::execute_quick_buy(
+                    bot, q, &token.mint, &token.symbol, amount_sol, settings.max_trade_sol, Some(priority),
+                    trading_engine, wallet_manager, services.preferences.clone(), services.confirmations.clone(),
+                ).await?;
+            }
+            CallbackAction::ChooseQuickBuyToken { amount_sol } => {
+                bot.send_message(msg.chat.id, t_args(lang, "quick_buy.choose_hint", &[("amount", &amount_sol.to_string())]))
+                    .await?;
+            }
+            CallbackAction::CancelQuickBuy => {
+                let _ = bot.edit_message_text(msg.chat.id, msg.id, t(lang, "quick_buy.cancelled")).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Shown instead of buying when the amount is above the user's max trade size
+    pub fn confirmation_text(symbol: &str, amount_sol: f64, max_trade_sol: f64, lang: &str) -> String {
+        t_args(lang, "quick_buy.above_max", &[
+            ("amount", &amount_sol.to_string()),
+            ("symbol", symbol),
+            ("max", &max_trade_sol.to_string()),
+        ])
+    }
+
+    pub fn confirmation_keyboard(mint: &str, amount_sol: f64, lang: &str) -> InlineKeyboardMarkup {
+        let confirm = CallbackAction::ConfirmQuickBuy { token: mint.to_string(), amount_sol };
+        InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(t_args(lang, "buttons.buy_sol", &[("amount", &amount_sol.to_string())]), confirm.to_data()),
+            InlineKeyboardButton::callback(t(lang, "buttons.cancel"), CallbackAction::CancelQuickBuy.to_data()),
+        ]])
+    }
+
+    /// Shown instead of buying when the estimated price impact exceeds the user's slippage
+    pub fn impact_confirmation_text(symbol: &str, estimate: &SlippageEstimate, slippage_bps: u16, lang: &str) -> String {
+        let source = match estimate.source {
+            ImpactSource::OrderBook => t(lang, "quick_buy.impact_order_book"),
+            ImpactSource::JupiterQuote => t(lang, "quick_buy.impact_jupiter"),
+        };
+        let mut text = t_args(lang, "quick_buy.high_impact", &[
+            ("amount", &estimate.amount_sol.to_string()),
+            ("symbol", symbol),
+            ("impact", &format!("{:.2}", estimate.impact_pct())),
+            ("source", &source),
+            ("slippage", &format!("{:.2}", slippage_bps as f64 / 100.0)),
+        ]);
+        if estimate.unfilled_sol > 0.0 {
+            text.push_str("\n\n");
+            text.push_str(&t_args(lang, "quick_buy.book_room", &[
+                ("sol", &format!("{:.4}", estimate.amount_sol - estimate.unfilled_sol)),
+            ]));
+        }
+        text.push_str("\n\n");
+        text.push_str(&t(lang, "quick_buy.impact_buy_anyway"));
+        text
+    }
+
+    pub fn impact_confirmation_keyboard(mint: &str, amount_sol: f64, lang: &str) -> InlineKeyboardMarkup {
+        let confirm = CallbackAction::ConfirmHighImpact { token: mint.to_string(), amount_sol };
+        InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(t_args(lang, "buttons.buy_sol_anyway", &[("amount", &amount_sol.to_string())]), confirm.to_data()),
+            InlineKeyboardButton::callback(t(lang, "buttons.cancel"), CallbackAction::CancelQuickBuy.to_data()),
+        ]])
+    }
+
+    /// True for the first tap on this confirmation across all instances
+    async fn claim_confirmation(bot: &Bot, msg: &Message, services: &BotServices, lang: &str) -> ResponseResult<bool> {
+        let key = format!("confirm:{}:{}", msg.chat.id, msg.id.0);
+        match services.sessions.claim(&key, CONFIRMATION_CLAIM_TTL).await {
+            Ok(true) => Ok(true),
+            Ok(false) => {
+                info!("⚡ Ignoring a repeat tap on confirmation {}", key);
+                Ok(false)
+            }
+            Err(e) => {
+                warn!("⚡ Couldn't claim confirmation {}: {}", key, e);
+                bot.send_message(msg.chat.id, t(lang, "quick_buy.confirm_failed")).await?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// The token a button names; tells the user when it's unknown or ambiguous
+    async fn resolve(
+        bot: &Bot,
+        chat_id: ChatId,
+        token: &str,
+        amount_sol: f64,
+        services: &BotServices,
+        lang: &str,
+    ) -> ResponseResult<Option<TokenCandidate>> {
+        match services.token_resolver.lookup(token).await {
+            TokenLookup::Found(candidate) => Ok(Some(candidate)),
+            TokenLookup::NotFound => {
+                bot.send_message(chat_id, t_args(lang, "errors.unknown_token", &[("token", token)])).await?;
+                Ok(None)
+            }
+            ambiguous => {
+                let prompt = ambiguous.prompt(token, &format!("/qbuy {}", amount_sol)).unwrap_or_default();
+                bot.send_message(chat_id, prompt).await?;
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use teloxide::{
+    net::Download,
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message},
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::{
+    bot::{
+        distribution::{ClaimRow, ClaimState, Distribution, PendingDistribution, ReclaimReport, RecipientList, RecipientOutcome, UploadRequest},
+        BotServices,
+    },
+    trading::TokenLookup,
+    utils::{fmt_number, lang_of, t, t_args, NumberKind},
+    wallet::WalletManager,
+};
+
+/// A thousand addresses with amounts fit comfortably under this
+const MAX_CSV_BYTES: u32 = 256 * 1024;
+/// Rows listed in a result or status table before the rest are counted
+const MAX_ROWS_SHOWN: usize = 30;
+/// Skipped entries listed under a preview
+const MAX_SKIPPED_SHOWN: usize = 10;
+
+/// /send - distribute tokens to many recipients through Jupiter Send
+pub struct SendHandler;
+
+impl SendHandler {
+    /// Handle /send <token> <amount> [recipients...] | status <id> | reclaim <id>
+    pub async fn handle_send(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+        let distributions = &services.distributions;
+
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            ["status", id] => {
+                let text = match distributions.status(id, telegram_id).await {
+                    Ok((distribution, rows)) => Self::status_text(&distribution, &rows, lang),
+                    Err(e) => format!("❌ {}", e.user_message()),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            ["reclaim", id] => {
+                let text = match distributions.reclaim(id, telegram_id).await {
+                    Ok(report) => Self::reclaim_text(&report, lang),
+                    Err(e) => format!("❌ {}", e.user_message()),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            [token, amount, recipients @ ..] => {
+                let Some(amount) = amount.parse::<f64>().ok().filter(|a| a.is_finite() && *a > 0.0) else {
+                    bot.send_message(msg.chat.id, t(lang, "send.usage")).await?;
+                    return Ok(());
+                };
+                let (mint, symbol) = match services.token_resolver.lookup(token).await {
+                    TokenLookup::Found(candidate) => (candidate.mint, candidate.symbol),
+                    lookup => {
+                        let prompt = lookup.prompt(token, "/send")
+                            .unwrap_or_else(|| t_args(lang, "errors.unknown_token", &[("token", *token)]));
+                        bot.send_message(msg.chat.id, prompt).await?;
+                        return Ok(());
+                    }
+                };
+
+                if recipients.is_empty() {
+                    distributions.expect_upload(telegram_id, UploadRequest { mint, symbol: symbol.clone(), amount }).await;
+                    bot.send_message(msg.chat.id, t_args(lang, "send.upload_prompt", &[
+                        ("amount", &fmt_number(lang, amount, NumberKind::Token)),
+                        ("symbol", &symbol),
+                    ])).await?;
+                    return Ok(());
+                }
+
+                let list = RecipientList::from_args(recipients.iter().copied(), amount);
+                Self::preview(&bot, msg.chat.id, telegram_id, &user_id, &mint, &symbol, list, &services, &wallet_manager, lang).await?;
+            }
+            _ => {
+                bot.send_message(msg.chat.id, t(lang, "send.usage")).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take a CSV sent after /send <token> <amount>; true when the document was handled
+    pub async fn handle_document(
+        bot: &Bot,
+        msg: &Message,
+        services: &Arc<BotServices>,
+        wallet_manager: &Arc<WalletManager>,
+        user_id: &str,
+    ) -> ResponseResult<bool> {
+        let Some(document) = msg.document() else { return Ok(false) };
+        let Ok(telegram_id) = user_id.parse::<i64>() else { return Ok(false) };
+        let Some(request) = services.distributions.take_upload_request(telegram_id).await else { return Ok(false) };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+
+        if document.file.size > MAX_CSV_BYTES {
+            bot.send_message(msg.chat.id, t(&lang, "send.file_too_large")).await?;
+            return Ok(true);
+        }
+
+        let mut contents = Vec::new();
+        let downloaded = async {
+            let file = bot.get_file(document.file.id.clone()).await?;
+            bot.download_file(&file.path, &mut contents).await?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        }.await;
+        if let Err(e) = downloaded {
+            error!("📤 Failed to download recipient list for {}: {}", telegram_id, e);
+            bot.send_message(msg.chat.id, t(&lang, "send.download_failed")).await?;
+            return Ok(true);
+        }
+
+        let list = match RecipientList::from_csv(contents.as_slice(), request.amount) {
+            Ok(list) => list,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                return Ok(true);
+            }
+        };
+        Self::preview(bot, msg.chat.id, telegram_id, user_id, &request.mint, &request.symbol, list, services, wallet_manager, &lang).await?;
+        Ok(true)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn preview(
+        bot: &Bot,
+        chat_id: ChatId,
+        telegram_id: i64,
+        user_id: &str,
+        mint: &str,
+        symbol: &str,
+        list: RecipientList,
+        services: &Arc<BotServices>,
+        wallet_manager: &Arc<WalletManager>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        if wallet_manager.is_trading_locked(user_id).await {
+            bot.send_message(chat_id, t(lang, "errors.trading_locked")).await?;
+            return Ok(());
+        }
+        let Some(sender) = Self::wallet(wallet_manager, user_id).await else {
+            bot.send_message(chat_id, t(lang, "errors.no_wallet")).await?;
+            return Ok(());
+        };
+
+        let pending = match services.distributions.prepare(telegram_id, &sender, mint, symbol, list).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                bot.send_message(chat_id, format!("❌ {}", e.user_message())).await?;
+                return Ok(());
+            }
+        };
+
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(
+                t_args(lang, "buttons.send_to", &[("count", &pending.preview.recipients.to_string())]),
+                "send:go",
+            ),
+            InlineKeyboardButton::callback(t(lang, "buttons.cancel"), "send:cancel"),
+        ]]);
+        let text = Self::preview_text(&pending, services.distributions.expiry_hours(), lang);
+        bot.send_message(chat_id, text).reply_markup(keyboard).await?;
+        Ok(())
+    }
+
+    /// Confirmation buttons under a send preview
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+        wallet_manager: Arc<WalletManager>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let telegram_id = q.from.id.0 as i64;
+        let user_id = telegram_id.to_string();
+        let distributions = &services.distributions;
+        let lang = services.preferences.language(telegram_id, Some(&q.from)).await;
+
+        if data == "send:cancel" {
+            distributions.cancel(telegram_id).await;
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "send.cancelled")).await?;
+            return Ok(());
+        }
+        if data != "send:go" {
+            return Ok(());
+        }
+
+        if wallet_manager.is_trading_locked(&user_id).await {
+            distributions.cancel(telegram_id).await;
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "errors.trading_locked")).await?;
+            return Ok(());
+        }
+        let Some(sender) = Self::wallet(&wallet_manager, &user_id).await else {
+            bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "errors.no_wallet")).await?;
+            return Ok(());
+        };
+        bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "send.sending")).await?;
+
+        let text = match distributions.execute(telegram_id, &sender).await {
+            Ok(distribution) => Self::results_text(&distribution, &lang),
+            Err(e) => {
+                error!("📤 Bulk send failed for user {}: {}", telegram_id, e);
+                t_args(&lang, "send.failed", &[("error", &e.to_string())])
+            }
+        };
+        bot.edit_message_text(msg.chat.id, msg.id, text).await?;
+        Ok(())
+    }
+
+    async fn wallet(wallet_manager: &WalletManager, user_id: &str) -> Option<String> {
+        wallet_manager.get_user_wallet(user_id).await.ok()?.map(|wallet| wallet.public_key)
+    }
+
+    /// `7xKX...gAsU`
+    fn short(recipient: &str) -> String {
+        if recipient.starts_with('@') || recipient.chars().count() <= 12 {
+            return recipient.to_string();
+        }
+        let chars: Vec<char> = recipient.chars().collect();
+        format!("{}...{}", chars[..4].iter().collect::<String>(), chars[chars.len() - 4..].iter().collect::<String>())
+    }
+
+    pub fn preview_text(pending: &PendingDistribution, expiry_hours: u32, lang: &str) -> String {
+        let preview = &pending.preview;
+        let mut text = t_args(lang, "send.preview", &[
+            ("recipients", &preview.recipients.to_string()),
+            ("total", &fmt_number(lang, preview.total_tokens, NumberKind::Token)),
+            ("symbol", &pending.symbol),
+            ("fees", &fmt_number(lang, preview.fee_sol(), NumberKind::Sol)),
+        ]);
+        if preview.total_sol > preview.fee_sol() {
+            text.push('\n');
+            text.push_str(&t_args(lang, "send.total_sol", &[("sol", &fmt_number(lang, preview.total_sol, NumberKind::Sol))]));
+        }
+        text.push_str("\n\n");
+        text.push_str(&t_args(lang, "send.reclaim_after_hours", &[("hours", &expiry_hours.to_string())]));
+
+        let skipped = &pending.list.rejected;
+        if !skipped.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&t_args(lang, "send.skipped", &[("count", &skipped.len().to_string())]));
+            for rejected in skipped.iter().take(MAX_SKIPPED_SHOWN) {
+                text.push_str(&format!("\n• {} - {}", Self::short(&rejected.input), rejected.reason));
+            }
+            if skipped.len() > MAX_SKIPPED_SHOWN {
+                text.push_str("\n• ");
+                text.push_str(&t_args(lang, "common.and_more", &[("count", &(skipped.len() - MAX_SKIPPED_SHOWN).to_string())]));
+            }
+        }
+        text
+    }
+
+    /// Per-recipient result table after a bulk send
+    pub fn results_text(distribution: &Distribution, lang: &str) -> String {
+        let mut text = t_args(lang, "send.batch_results", &[
+            ("id", &distribution.id),
+            ("sent", &distribution.sent().to_string()),
+            ("failed", &distribution.failed().to_string()),
+        ]);
+        text.push('\n');
+        for result in distribution.results.iter().take(MAX_ROWS_SHOWN) {
+            let recipient = Self::short(&result.recipient);
+            let line = match &result.outcome {
+                RecipientOutcome::Sent { magic_link: Some(link), .. } if result.recipient.starts_with('@') => format!(
+                    "✅ {} · {} {} · {}",
+                    recipient, fmt_number(lang, result.amount, NumberKind::Token), distribution.symbol, link
+                ),
+                RecipientOutcome::Sent { .. } => format!(
+                    "✅ {} · {} {}",
+                    recipient, fmt_number(lang, result.amount, NumberKind::Token), distribution.symbol
+                ),
+                RecipientOutcome::Invalid(reason) => format!("⛔ {} · {}", recipient, reason),
+                RecipientOutcome::Failed(reason) => format!("❌ {} · {}", recipient, reason),
+            };
+            text.push('\n');
+            text.push_str(&line);
+        }
+        if distribution.results.len() > MAX_ROWS_SHOWN {
+            text.push('\n');
+            text.push_str(&t_args(lang, "common.and_more", &[("count", &(distribution.results.len() - MAX_ROWS_SHOWN).to_string())]));
+        }
+        if distribution.sent() > 0 {
+            text.push_str("\n\n");
+            text.push_str(&t_args(lang, "send.share_links", &[("id", &distribution.id)]));
+        }
+        text
+    }
+
+    pub fn status_text(distribution: &Distribution, rows: &[ClaimRow], lang: &str) -> String {
+        let claimed = rows.iter().filter(|row| row.state == ClaimState::Claimed).count();
+        let open = rows.iter().filter(|row| row.state.is_reclaimable()).count();
+        let mut text = t_args(lang, "send.batch_status", &[
+            ("id", &distribution.id),
+            ("symbol", &distribution.symbol),
+            ("claimed", &claimed.to_string()),
+            ("open", &open.to_string()),
+        ]);
+        text.push('\n');
+        for row in rows.iter().take(MAX_ROWS_SHOWN) {
+            let state = match &row.state {
+                ClaimState::Unclaimed => t(lang, "send.state_unclaimed"),
+                ClaimState::Partial(_) => t(lang, "send.state_partial"),
+                ClaimState::Claimed => t(lang, "send.state_claimed"),
+                ClaimState::Expired => t(lang, "send.state_expired"),
+                ClaimState::Cancelled => t(lang, "send.state_reclaimed"),
+                ClaimState::Failed => t(lang, "send.state_failed"),
+                ClaimState::Unknown(reason) => format!("❔ {}", reason),
+                ClaimState::NotSent => t(lang, "send.state_not_sent"),
+            };
+            text.push_str(&format!(
+                "\n{} · {} · {}",
+                Self::short(&row.recipient),
+                fmt_number(lang, row.amount, NumberKind::Token),
+                state
+            ));
+        }
+        if rows.len() > MAX_ROWS_SHOWN {
+            text.push('\n');
+            text.push_str(&t_args(lang, "common.and_more", &[("count", &(rows.len() - MAX_ROWS_SHOWN).to_string())]));
+        }
+
+        if open > 0 {
+            text.push_str("\n\n");
+            if distribution.reclaimable(chrono::Utc::now()) {
+                text.push_str(&t_args(lang, "send.reclaim_hint", &[("id", &distribution.id)]));
+            } else {
+                text.push_str(&t_args(lang, "send.reclaim_after", &[
+                    ("time", &distribution.expires_at.format("%Y-%m-%d %H:%M UTC").to_string()),
+                ]));
+            }
+        }
+        text
+    }
+
+    fn reclaim_text(report: &ReclaimReport, lang: &str) -> String {
+        match (report.cancelled, report.failed) {
+            (0, 0) => t(lang, "send.nothing_to_reclaim"),
+            (cancelled, 0) => t_args(lang, "send.reclaimed", &[("count", &cancelled.to_string())]),
+            (cancelled, failed) => format!(
+                "{}\n{}",
+                t_args(lang, "send.reclaimed", &[("count", &cancelled.to_string())]),
+                t_args(lang, "send.reclaim_failed", &[("count", &failed.to_string())]),
+            ),
+        }
+    }
+}
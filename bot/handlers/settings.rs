@@ -0,0 +1,423 @@
+use teloxide::{prelude::*, types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup}};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::bot::{
+    preferences::{SettingChange, UserSettings},
+    BotServices,
+};
+use crate::constants::{MAX_TRADE_SOL, MIN_TRADE_SOL};
+use crate::utils::{t, t_args};
+use crate::wallet::TransactionPriority;
+
+const SLIPPAGE_PRESETS_BPS: [u16; 4] = [50, 100, 300, 500];
+const MAX_TRADE_PRESETS_SOL: [f64; 4] = [0.05, 0.1, 0.5, 1.0];
+const SESSION_TIMEOUT_PRESETS_MINUTES: [u32; 4] = [15, 30, 60, 120];
+const MIN_LIQUIDITY_PRESETS_USD: [f64; 3] = [10_000.0, 50_000.0, 250_000.0];
+const MAX_TAX_PRESETS_PERCENT: [f64; 3] = [0.0, 5.0, 10.0];
+const MIN_LARP_SCORE_PRESETS: [u8; 3] = [40, 60, 80];
+
+/// A screen of the /settings editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsPage {
+    Overview,
+    Trading,
+    Notifications,
+    Security,
+    TokenRules,
+    Ai,
+    Privacy,
+}
+
+impl SettingsPage {
+    /// Page opened by a `settings_*` button
+    pub fn from_callback(data: &str) -> Option<Self> {
+        match data {
+            "settings_menu" => Some(Self::Overview),
+            "settings_trading" => Some(Self::Trading),
+            "settings_notifications" => Some(Self::Notifications),
+            "settings_security" => Some(Self::Security),
+            "settings_rules" => Some(Self::TokenRules),
+            "settings_ai" => Some(Self::Ai),
+            "settings_privacy" => Some(Self::Privacy),
+            _ => None,
+        }
+    }
+
+    /// Page a change is made from, shown again with the new value
+    pub fn for_change(change: SettingChange) -> Self {
+        match change {
+            SettingChange::MaxTradeSol(_)
+            | SettingChange::SlippageBps(_)
+            | SettingChange::PriorityFee(_)
+            | SettingChange::ToggleMevProtection => Self::Trading,
+            SettingChange::ToggleNotification(_) => Self::Notifications,
+            SettingChange::SessionTimeout(_) => Self::Security,
+            SettingChange::MinLiquidityUsd(_)
+            | SettingChange::MaxTaxPercent(_)
+            | SettingChange::MinLarpScore(_)
+            | SettingChange::ClearDeniedMints => Self::TokenRules,
+            SettingChange::ToggleAiAnalysis => Self::Ai,
+            SettingChange::ToggleLeaderboardVisible
+            | SettingChange::ToggleCopyable
+            | SettingChange::ClearDisplayName => Self::Privacy,
+            SettingChange::Reset => Self::Overview,
+        }
+    }
+
+    pub fn text(&self, settings: &UserSettings, lang: &str) -> String {
+        match self {
+            Self::Overview => t_args(lang, "settings.overview", &[
+                ("max_trade", &settings.max_trade_sol.to_string()),
+                ("slippage", &slippage_label(settings.slippage_bps)),
+                ("priority", &priority_label(settings.priority_fee, lang)),
+                ("mev", &on_off(settings.mev_protection, lang)),
+                ("ai", &on_off(settings.ai_analysis, lang)),
+                ("timeout", &settings.session_timeout_minutes.to_string()),
+                ("rules", &if settings.trade_rules.is_empty() { t(lang, "settings.none") } else { on_off(true, lang) }),
+                ("trades", &on_off(settings.notifications.trades, lang)),
+                ("alerts", &on_off(settings.notifications.alerts, lang)),
+                ("daily", &on_off(settings.notifications.daily_summary, lang)),
+                ("leaderboard", &on_off(settings.leaderboard_visible, lang)),
+                ("copyable", &on_off(settings.copyable, lang)),
+            ]),
+            Self::Trading => t_args(lang, "settings.trading", &[
+                ("max_trade", &settings.max_trade_sol.to_string()),
+                ("slippage", &slippage_label(settings.slippage_bps)),
+                ("priority", &priority_label(settings.priority_fee, lang)),
+                ("mev", &on_off(settings.mev_protection, lang)),
+            ]),
+            Self::Notifications => t_args(lang, "settings.notifications", &[
+                ("trades", &on_off(settings.notifications.trades, lang)),
+                ("alerts", &on_off(settings.notifications.alerts, lang)),
+                ("daily", &on_off(settings.notifications.daily_summary, lang)),
+                ("announcements", &on_off(settings.notifications.announcements, lang)),
+            ]),
+            Self::Security => t_args(lang, "settings.security", &[
+                ("timeout", &settings.session_timeout_minutes.to_string()),
+            ]),
+            Self::TokenRules => {
+                let rules = &settings.trade_rules;
+                let denied = if rules.denied_mints.is_empty() {
+                    t(lang, "settings.none")
+                } else {
+                    rules.denied_mints.iter().map(|mint| format!("\n• {}", mint)).collect()
+                };
+                let off = t(lang, "common.off");
+                t_args(lang, "settings.token_rules", &[
+                    ("liquidity", &rules.min_liquidity_usd.map_or(off.clone(), usd_label)),
+                    ("tax", &rules.max_tax_percent.map_or(off.clone(), |percent| format!("{}%", percent))),
+                    ("larp", &rules.min_larp_score.map_or(off, |score| format!("{}/100", score))),
+                    ("denied", &denied),
+                ])
+            }
+            Self::Ai => t_args(lang, "settings.ai", &[("ai", &on_off(settings.ai_analysis, lang))]),
+            Self::Privacy => t_args(lang, "settings.privacy", &[
+                ("leaderboard", &on_off(settings.leaderboard_visible, lang)),
+                ("copyable", &on_off(settings.copyable, lang)),
+                ("name", &settings.display_name_override.clone().unwrap_or_else(|| t(lang, "settings.anonymous"))),
+            ]),
+        }
+    }
+
+    pub fn keyboard(&self, settings: &UserSettings, lang: &str) -> InlineKeyboardMarkup {
+        let back = vec![InlineKeyboardButton::callback(t(lang, "buttons.back"), "settings_menu")];
+        let rows = match self {
+            Self::Overview => vec![
+                vec![
+                    InlineKeyboardButton::callback(t(lang, "buttons.settings_trading"), "settings_trading"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.settings_notifications"), "settings_notifications"),
+                ],
+                vec![
+                    InlineKeyboardButton::callback(t(lang, "buttons.settings_security"), "settings_security"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.settings_token_rules"), "settings_rules"),
+                ],
+                vec![
+                    InlineKeyboardButton::callback(t(lang, "buttons.settings_ai"), "settings_ai"),
+                    InlineKeyboardButton::callback(t(lang, "buttons.settings_privacy"), "settings_privacy"),
+                ],
+                vec![InlineKeyboardButton::callback(t(lang, "buttons.settings_rebates"), "settings_rebates")],
+                vec![InlineKeyboardButton::callback(t(lang, "buttons.settings_reset"), "setting:reset")],
+            ],
+            Self::Trading => vec![
+                MAX_TRADE_PRESETS_SOL.iter()
+                    .filter(|sol| (MIN_TRADE_SOL..=MAX_TRADE_SOL).contains(*sol))
+                    .map(|sol| InlineKeyboardButton::callback(
+                        mark(format!("{} SOL", sol), settings.max_trade_sol == *sol),
+                        format!("setting:max:{}", sol),
+                    ))
+                    .collect(),
+                SLIPPAGE_PRESETS_BPS.iter()
+                    .map(|bps| InlineKeyboardButton::callback(
+                        mark(slippage_label(*bps), settings.slippage_bps == *bps),
+                        format!("setting:slip:{}", bps),
+                    ))
+                    .collect(),
+                [
+                    (TransactionPriority::Low, "low"),
+                    (TransactionPriority::Normal, "normal"),
+                    (TransactionPriority::High, "high"),
+                    (TransactionPriority::Critical, "critical"),
+                ]
+                .iter()
+                .map(|(priority, code)| InlineKeyboardButton::callback(
+                    mark(priority_label(*priority, lang), settings.priority_fee == *priority),
+                    format!("setting:prio:{}", code),
+                ))
+                .collect(),
+                vec![InlineKeyboardButton::callback(
+                    t_args(lang, "buttons.setting_mev", &[("state", &on_off(settings.mev_protection, lang))]),
+                    "setting:mev",
+                )],
+                back,
+            ],
+            Self::Notifications => vec![
+                vec![InlineKeyboardButton::callback(
+                    t_args(lang, "buttons.setting_trades", &[("state", &on_off(settings.notifications.trades, lang))]),
+                    "setting:notify:trades",
+                )],
+                vec![InlineKeyboardButton::callback(
+                    t_args(lang, "buttons.setting_alerts", &[("state", &on_off(settings.notifications.alerts, lang))]),
+                    "setting:notify:alerts",
+                )],
+                vec![InlineKeyboardButton::callback(
+                    t_args(lang, "buttons.setting_daily", &[("state", &on_off(settings.notifications.daily_summary, lang))]),
+                    "setting:notify:daily",
+                )],
+                vec![InlineKeyboardButton::callback(
+                    t_args(lang, "buttons.setting_announcements", &[("state", &on_off(settings.notifications.announcements, lang))]),
+                    "setting:notify:announce",
+                )],
+                back,
+            ],
+            Self::Security => vec![
+                SESSION_TIMEOUT_PRESETS_MINUTES.iter()
+                    .map(|minutes| InlineKeyboardButton::callback(
+                        mark(t_args(lang, "settings.minutes_short", &[("minutes", &minutes.to_string())]), settings.session_timeout_minutes == *minutes),
+                        format!("setting:timeout:{}", minutes),
+                    ))
+                    .collect(),
+                back,
+            ],
+            Self::TokenRules => {
+                let rules = &settings.trade_rules;
+                let mut rows = vec![
+                    rule_row(
+                        "💧",
+                        "liq",
+                        lang,
+                        rules.min_liquidity_usd,
+                        MIN_LIQUIDITY_PRESETS_USD.iter().map(|usd| (*usd, usd_label(*usd))),
+                    ),
+                    rule_row(
+                        "🧾",
+                        "tax",
+                        lang,
+                        rules.max_tax_percent,
+                        MAX_TAX_PRESETS_PERCENT.iter().map(|percent| (*percent, format!("≤{}%", percent))),
+                    ),
+                    rule_row(
+                        "🕵️",
+                        "larp",
+                        lang,
+                        rules.min_larp_score,
+                        MIN_LARP_SCORE_PRESETS.iter().map(|score| (*score, format!("≥{}", score))),
+                    ),
+                ];
+                if !rules.denied_mints.is_empty() {
+                    rows.push(vec![InlineKeyboardButton::callback(t(lang, "buttons.settings_clear_deny_list"), "setting:denyclear")]);
+                }
+                rows.push(back);
+                rows
+            }
+            Self::Ai => vec![
+                vec![InlineKeyboardButton::callback(
+                    t_args(lang, "buttons.setting_ai", &[("state", &on_off(settings.ai_analysis, lang))]),
+                    "setting:ai",
+                )],
+                back,
+            ],
+            Self::Privacy => {
+                let mut rows = vec![
+                    vec![InlineKeyboardButton::callback(
+                        t_args(lang, "buttons.setting_leaderboards", &[("state", &on_off(settings.leaderboard_visible, lang))]),
+                        "setting:board",
+                    )],
+                    vec![InlineKeyboardButton::callback(
+                        t_args(lang, "buttons.setting_copy_trading", &[("state", &on_off(settings.copyable, lang))]),
+                        "setting:copyable",
+                    )],
+                ];
+                if settings.display_name_override.is_some() {
+                    rows.push(vec![InlineKeyboardButton::callback(t(lang, "buttons.settings_clear_display_name"), "setting:clearname")]);
+                }
+                rows.push(back);
+                rows
+            }
+        };
+        InlineKeyboardMarkup::new(rows)
+    }
+}
+
+fn on_off(enabled: bool, lang: &str) -> String {
+    t(lang, if enabled { "settings.on" } else { "settings.off" })
+}
+
+fn mark(label: String, selected: bool) -> String {
+    if selected { format!("✓ {}", label) } else { label }
+}
+
+fn slippage_label(bps: u16) -> String {
+    format!("{}%", bps as f64 / 100.0)
+}
+
+fn usd_label(usd: f64) -> String {
+    if usd >= 1_000.0 { format!("${}k", usd / 1_000.0) } else { format!("${}", usd) }
+}
+
+/// "Off" and a button per preset for one token rule, the current value marked
+fn rule_row<T: Copy + PartialEq + std::fmt::Display>(
+    icon: &str,
+    name: &str,
+    lang: &str,
+    current: Option<T>,
+    presets: impl Iterator<Item = (T, String)>,
+) -> Vec<InlineKeyboardButton> {
+    std::iter::once(InlineKeyboardButton::callback(
+        mark(format!("{} {}", icon, t(lang, "buttons.rule_off")), current.is_none()),
+        format!("setting:{}:off", name),
+    ))
+    .chain(presets.map(|(value, label)| InlineKeyboardButton::callback(
+        mark(label, current == Some(value)),
+        format!("setting:{}:{}", name, value),
+    )))
+    .collect()
+}
+
+fn priority_label(priority: TransactionPriority, lang: &str) -> String {
+    t(lang, match priority {
+        TransactionPriority::Low => "settings.priority_low",
+        TransactionPriority::Normal => "settings.priority_normal",
+        TransactionPriority::High => "settings.priority_high",
+        TransactionPriority::Critical => "settings.priority_critical",
+    })
+}
+
+/// /settings and its editor: sub-menus that change the user's stored settings
+pub struct SettingsHandler;
+
+impl SettingsHandler {
+    /// Send the settings overview with the editor's buttons
+    pub async fn show(bot: &Bot, chat_id: ChatId, user_id: i64, services: &BotServices, lang: &str) -> ResponseResult<()> {
+        let settings = services.preferences.get(user_id).await;
+        bot.send_message(chat_id, SettingsPage::Overview.text(&settings, lang))
+            .reply_markup(SettingsPage::Overview.keyboard(&settings, lang))
+            .await?;
+        Ok(())
+    }
+
+    /// /settings name <name> sets the public display name; `clear` removes it
+    pub async fn set_display_name(
+        bot: &Bot,
+        chat_id: ChatId,
+        user_id: i64,
+        name: &str,
+        services: &BotServices,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            bot.send_message(chat_id, t(lang, "settings.name_usage")).await?;
+            return Ok(());
+        }
+        let name = (!name.eq_ignore_ascii_case("clear")).then_some(name);
+
+        match services.preferences.update(user_id, |settings| settings.set_display_name(name)).await {
+            Ok(settings) => {
+                info!("⚙️ User {} changed their display name", user_id);
+                services.leaderboard.invalidate().await;
+                bot.send_message(chat_id, SettingsPage::Privacy.text(&settings, lang))
+                    .reply_markup(SettingsPage::Privacy.keyboard(&settings, lang))
+                    .await?;
+            }
+            Err(e) => {
+                bot.send_message(chat_id, format!("❌ {}", e.user_message())).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// /settings deny <mint> and /settings allow <mint> edit the token rules' deny-list
+    pub async fn edit_deny_list(
+        bot: &Bot,
+        chat_id: ChatId,
+        user_id: i64,
+        mint: &str,
+        deny: bool,
+        services: &BotServices,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let mint = mint.trim();
+        if mint.is_empty() {
+            bot.send_message(chat_id, t(lang, "settings.deny_usage")).await?;
+            return Ok(());
+        }
+
+        let result = services.preferences
+            .update(user_id, |settings| if deny { settings.deny_mint(mint) } else { settings.allow_mint(mint) })
+            .await;
+        match result {
+            Ok(settings) => {
+                info!("⚙️ User {} {} {}", user_id, if deny { "denied" } else { "allowed" }, mint);
+                bot.send_message(chat_id, SettingsPage::TokenRules.text(&settings, lang))
+                    .reply_markup(SettingsPage::TokenRules.keyboard(&settings, lang))
+                    .await?;
+            }
+            Err(e) => {
+                bot.send_message(chat_id, format!("❌ {}", e.user_message())).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `settings_*` pages and `setting:<name>[:<value>]` edits, shown in place
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+
+        let page = match SettingChange::parse(data) {
+            Some(change) => {
+                match services.preferences.apply(user_id, change).await {
+                    Ok(_) => {
+                        info!("⚙️ User {} changed settings: {:?}", user_id, change);
+                        if change.affects_visibility() {
+                            services.leaderboard.invalidate().await;
+                        }
+                    }
+                    Err(e) => {
+                        error!("Settings change {:?} for {} rejected: {}", change, user_id, e);
+                        bot.send_message(msg.chat.id, format!("❌ {}", e.user_message())).await?;
+                    }
+                }
+                SettingsPage::for_change(change)
+            }
+            None => match SettingsPage::from_callback(data) {
+                Some(page) => page,
+                None => return Ok(()),
+            },
+        };
+
+        let settings = services.preferences.get(user_id).await;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+        // Tapping the value already set leaves the message unchanged, which Telegram reports as an error
+        let _ = bot.edit_message_text(msg.chat.id, msg.id, page.text(&settings, &lang))
+            .reply_markup(page.keyboard(&settings, &lang))
+            .await;
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::{Datelike, Duration, Utc};
+use teloxide::{prelude::*, types::{InputFile, Message}};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::{
+    analytics::{render_heatmap, AttributionTime, BenchmarkBasket, LedgerFormat, TradingHoursConfig, TradingHoursReport},
+    bot::BotServices,
+    utils::{lang_of, t, t_args},
+};
+
+use super::journal::JournalHandler;
+
+/// How often the digest task checks for a new month
+const DIGEST_CHECK_SECS: u64 = 60 * 60;
+/// Window for /performance benchmark
+const BENCHMARK_DAYS: u32 = 30;
+
+/// /stats - results by journal tag and by trading hour
+pub struct StatsHandler;
+
+impl StatsHandler {
+    /// Handle /stats [heatmap] [exit] [imported]
+    pub async fn handle_stats(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+
+        let mut heatmap = false;
+        let mut config = TradingHoursConfig::default();
+        for word in args.split_whitespace() {
+            match word.to_lowercase().as_str() {
+                "heatmap" => heatmap = true,
+                "exit" => config.attribute_by = AttributionTime::Exit,
+                "entry" => config.attribute_by = AttributionTime::Entry,
+                "imported" => config.include_imported = true,
+                _ => {
+                    bot.send_message(msg.chat.id, t(lang, "stats.usage")).await?;
+                    return Ok(());
+                }
+            }
+        }
+
+        let timezone = services.dca_engine.timezones().get_user_timezone(telegram_id).await;
+        let imported = services.trade_imports.records(telegram_id).await;
+        let report = services.performance.trading_hours(telegram_id, timezone, &config, &imported).await;
+
+        if heatmap {
+            if report.trades == 0 {
+                bot.send_message(msg.chat.id, t(lang, "stats.no_heatmap")).await?;
+                return Ok(());
+            }
+            return Self::send_heatmap(&bot, msg.chat.id, &report, Self::heatmap_caption(&report, lang)).await;
+        }
+
+        let breakdown = services.journal.breakdown(telegram_id).await;
+        let mut text = report.render();
+        if !breakdown.is_empty() {
+            text = format!("{}\n\n{}", JournalHandler::format_breakdown(&breakdown, lang), text);
+        }
+        if report.trades > 0 {
+            text.push_str("\n\n");
+            text.push_str(&t(lang, "stats.heatmap_hint"));
+        }
+        bot.send_message(msg.chat.id, text).await?;
+        Ok(())
+    }
+
+    /// Handle /performance [benchmark [basket]] - risk metrics, or results against buying and holding SOL or a basket
+    pub async fn handle_performance(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+
+        let usage = t(lang, "stats.performance_usage");
+        let args = args.trim();
+        if args.is_empty() {
+            let text = match services.performance.generate_analytics_report(telegram_id).await {
+                Ok(report) => format!("{}\n\n{}", report.risk_metrics.render(), t(lang, "stats.benchmark_hint")),
+                Err(e) => {
+                    warn!("📊 Analytics report failed: {}", e);
+                    t(lang, "stats.performance_failed")
+                }
+            };
+            bot.send_message(msg.chat.id, text).await?;
+            return Ok(());
+        }
+        let Some(spec) = args.strip_prefix("benchmark") else {
+            bot.send_message(msg.chat.id, usage).await?;
+            return Ok(());
+        };
+
+        let basket = match BenchmarkBasket::parse(spec) {
+            Ok(basket) => basket,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}\n\n{}", e.user_message(), usage)).await?;
+                return Ok(());
+            }
+        };
+
+        match services.performance.benchmark(telegram_id, &basket, BENCHMARK_DAYS).await {
+            Ok(report) => {
+                bot.send_message(msg.chat.id, report.render()).await?;
+            }
+            Err(e) => {
+                warn!("📊 Benchmark against {} failed: {}", basket.label, e);
+                bot.send_message(msg.chat.id, t_args(lang, "stats.benchmark_failed", &[("basket", &basket.label), ("error", &e.user_message())])).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle /export_trades [year] [csv|json]
+    pub async fn handle_export_trades(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        let lang = lang.as_str();
+
+        let mut year = None;
+        let mut format = LedgerFormat::Csv;
+        for word in args.split_whitespace() {
+            match word.to_lowercase().as_str() {
+                "csv" => format = LedgerFormat::Csv,
+                "json" => format = LedgerFormat::Json,
+                other => match other.parse::<i32>() {
+                    Ok(y) if (2020..=Utc::now().year()).contains(&y) => year = Some(y),
+                    _ => {
+                        bot.send_message(msg.chat.id, t(lang, "stats.export_usage")).await?;
+                        return Ok(());
+                    }
+                },
+            }
+        }
+        let file = match services.trade_history.export(telegram_id, year, format).await {
+            Ok(Some(file)) => file,
+            Ok(None) => {
+                let text = match year {
+                    Some(y) => t_args(lang, "stats.export_empty_year", &[("year", &y.to_string())]),
+                    None => t(lang, "stats.export_empty"),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+                return Ok(());
+            }
+            Err(e) => {
+                warn!("🧾 Trade export failed for {}: {}", telegram_id, e);
+                bot.send_message(msg.chat.id, t(lang, "stats.export_failed")).await?;
+                return Ok(());
+            }
+        };
+
+        let name = format!("trades{}.{}", year.map(|y| format!("-{}", y)).unwrap_or_default(), format.extension());
+        bot.send_document(msg.chat.id, InputFile::file(file.path()).file_name(name))
+            .caption(match year {
+                Some(y) => t_args(lang, "stats.export_caption_year", &[("rows", &file.rows.to_string()), ("year", &y.to_string())]),
+                None => t_args(lang, "stats.export_caption", &[("rows", &file.rows.to_string())]),
+            })
+            .await?;
+        info!("🧾 Sent {} ledger rows to {}", file.rows, telegram_id);
+        Ok(())
+    }
+
+    /// Each month, DM last month's trading hours and heatmap to users who traded
+    pub fn spawn_monthly_digest(bot: Bot, services: Arc<BotServices>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(DIGEST_CHECK_SECS));
+            // The month the bot started in is not reported; the next rollover is
+            let mut last_month = Utc::now().date_naive().with_day(1);
+
+            loop {
+                interval.tick().await;
+                let this_month = Utc::now().date_naive().with_day(1);
+                if this_month == last_month {
+                    continue;
+                }
+                last_month = this_month;
+                let Some(this_month) = this_month else { continue };
+                let Some(previous) = (this_month - Duration::days(1)).with_day(1) else { continue };
+
+                let start = previous.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+                let end = this_month.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+                let users = services.performance.active_users(start).await;
+                info!("📅 Monthly trading digest for {} users", users.len());
+
+                for telegram_id in users {
+                    let timezone = services.dca_engine.timezones().get_user_timezone(telegram_id).await;
+                    let trades: Vec<_> = services.performance.user_trades(telegram_id).await
+                        .into_iter()
+                        .filter(|trade| trade.timestamp >= start && trade.timestamp < end)
+                        .collect();
+                    let report = TradingHoursReport::compute(&trades, timezone, &TradingHoursConfig::default());
+                    if report.trades == 0 {
+                        continue;
+                    }
+
+                    let lang = services.preferences.language(telegram_id, None).await;
+                    let month = t_args(&lang, &format!("common.month_{}", previous.month()), &[("year", &previous.year().to_string())]);
+                    let caption = format!("{}\n\n{}", t_args(&lang, "stats.digest_title", &[("month", &month)]), report.render());
+                    if let Err(e) = Self::send_heatmap(&bot, ChatId(telegram_id), &report, caption).await {
+                        warn!("📅 Failed to send monthly digest to {}: {}", telegram_id, e);
+                    }
+                }
+            }
+        });
+    }
+
+    async fn send_heatmap(bot: &Bot, chat_id: ChatId, report: &TradingHoursReport, caption: String) -> ResponseResult<()> {
+        match render_heatmap(report) {
+            Ok(png) => {
+                bot.send_photo(chat_id, InputFile::memory(png).file_name("heatmap.png"))
+                    .caption(caption)
+                    .await?;
+            }
+            Err(e) => {
+                warn!("🗓️ Heatmap rendering failed: {}", e);
+                bot.send_message(chat_id, report.render()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn heatmap_caption(report: &TradingHoursReport, lang: &str) -> String {
+        let key = match report.attribute_by {
+            AttributionTime::Entry => "stats.heatmap_caption_entry",
+            AttributionTime::Exit => "stats.heatmap_caption_exit",
+        };
+        t_args(lang, key, &[("timezone", report.timezone.name()), ("min_samples", &report.min_samples.to_string())])
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     wallet::WalletManager,
     middleware::rate_limiter::{UserRateLimiter, RateLimitConfig},
     errors::BotError,
+    utils::{lang_of, t, t_args},
 };
 
 /// Real swap execution handler
@@ -26,10 +27,12 @@ impl SwapHandler {
         let user_id = msg.from()
             .map(|u| u.id.to_string())
             .unwrap_or_else(|| "unknown".to_string());
+        // Owned: msg moves into the preview below
+        let lang = lang_of(msg.from()).to_string();
         
         // Check trading rate limits (more restrictive)
         if let Err(e) = rate_limiter.check_rate_limit_with_config(&user_id, &RateLimitConfig::for_trading()).await {
-            bot.send_message(msg.chat.id, format!("⏰ Trading Rate Limit: {}", e))
+            bot.send_message(msg.chat.id, t_args(&lang, "swap.rate_limited", &[("error", &e.to_string())]))
                 .await?;
             return Ok(());
         }
@@ -37,7 +40,7 @@ impl SwapHandler {
         let parts: Vec<&str> = args.split_whitespace().collect();
         
         if parts.len() < 3 {
-            Self::show_swap_help(bot, msg).await?;
+            Self::show_swap_help(bot, msg, &lang).await?;
             return Ok(());
         }
         
@@ -49,17 +52,13 @@ impl SwapHandler {
         let amount: f64 = match amount_str.parse() {
             Ok(amt) => amt,
             Err(_) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ Invalid amount. Please enter a valid number.")
-                    .await?;
+                bot.send_message(msg.chat.id, t(&lang, "swap.invalid_amount")).await?;
                 return Ok(());
             }
         };
         
         if amount <= 0.0 {
-            bot.send_message(msg.chat.id, 
-                "❌ Amount must be greater than 0.")
-                .await?;
+            bot.send_message(msg.chat.id, t(&lang, "swap.amount_not_positive")).await?;
             return Ok(());
         }
         
@@ -67,55 +66,23 @@ impl SwapHandler {
         let wallet = match wallet_manager.get_user_wallet(&user_id).await {
             Ok(Some(wallet)) => wallet,
             Ok(None) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ No active wallet found. Use `/wallet connect` to connect a wallet first.")
-                    .await?;
+                bot.send_message(msg.chat.id, t(&lang, "swap.no_wallet")).await?;
                 return Ok(());
             }
             Err(e) => {
                 error!("Failed to get user wallet: {}", e);
-                bot.send_message(msg.chat.id, 
-                    "❌ Failed to access wallet. Please try again.")
-                    .await?;
+                bot.send_message(msg.chat.id, t(&lang, "swap.wallet_failed")).await?;
                 return Ok(());
             }
         };
         
         // Show swap preview first
-        Self::show_swap_preview(bot, msg, swap_client, from_token, to_token, amount, &wallet.public_key).await
+        Self::show_swap_preview(bot, msg, swap_client, from_token, to_token, amount, &wallet.public_key, &lang).await
     }
     
     /// Show swap help
-    async fn show_swap_help(bot: Bot, msg: Message) -> ResponseResult<()> {
-        let message = r#"💱 **How to Swap Tokens**
-
-**Usage:** `/swap <from> <to> <amount>`
-
-**Examples:**
-• `/swap SOL USDC 1.5` - Swap 1.5 SOL for USDC
-• `/swap USDC SOL 100` - Swap 100 USDC for SOL  
-• `/swap BONK WIF 1000000` - Swap 1M BONK for WIF
-
-**Supported Tokens:**
-• SOL, USDC, USDT, RAY, SRM, ORCA
-• BONK, WIF, PEPE (meme tokens)
-• Any SPL token (use mint address)
-
-**Features:**
-✅ Real Jupiter DEX aggregation
-✅ Best price routing
-✅ MEV protection via Jito bundles
-✅ Slippage protection (max 5%)
-✅ Price impact warnings
-
-**Security:**
-🔒 Transactions require your approval
-🛡️ Protected against sandwich attacks
-⚡ Fast execution via Jupiter
-
-Use `/wallet` to connect your wallet first!"#;
-        
-        bot.send_message(msg.chat.id, message).await?;
+    async fn show_swap_help(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        bot.send_message(msg.chat.id, t(lang, "swap.help")).await?;
         Ok(())
     }
     
@@ -128,17 +95,21 @@ Use `/wallet` to connect your wallet first!"#;
         to_token: &str,
         amount: f64,
         wallet_address: &str,
+        lang: &str,
     ) -> ResponseResult<()> {
-        let loading_msg = bot.send_message(msg.chat.id, 
-            format!("🔄 Getting real-time quote for {} {} → {}...", amount, from_token, to_token))
+        let loading_msg = bot.send_message(msg.chat.id, t_args(lang, "swap.quoting", &[
+            ("amount", &amount.to_string()),
+            ("from", from_token),
+            ("to", to_token),
+        ]))
             .await?;
         
         // Resolve token addresses
         let (from_mint, to_mint) = match Self::resolve_token_addresses(from_token, to_token) {
             Ok(mints) => mints,
-            Err(e) => {
+            Err(token) => {
                 bot.delete_message(msg.chat.id, loading_msg.id).await.ok();
-                bot.send_message(msg.chat.id, format!("❌ {}", e)).await?;
+                bot.send_message(msg.chat.id, t_args(lang, "swap.unsupported_token", &[("token", &token)])).await?;
                 return Ok(());
             }
         };
@@ -172,65 +143,46 @@ Use `/wallet` to connect your wallet first!"#;
                 let keyboard = InlineKeyboardMarkup::new(vec![
                     vec![
                         InlineKeyboardButton::callback(
-                            "✅ Confirm Swap", 
+                            t(lang, "buttons.confirm_swap"),
                             format!("confirm_swap:{}:{}:{}", from_token, to_token, amount)
                         ),
-                        InlineKeyboardButton::callback("❌ Cancel", "cancel_swap"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.cancel"), "cancel_swap"),
                     ],
                     vec![
-                        InlineKeyboardButton::callback("🔄 Refresh Quote", 
+                        InlineKeyboardButton::callback(t(lang, "buttons.refresh_quote"),
                             format!("refresh_quote:{}:{}:{}", from_token, to_token, amount)),
-                        InlineKeyboardButton::callback("⚙️ Settings", "swap_settings"),
+                        InlineKeyboardButton::callback(t(lang, "buttons.settings"), "swap_settings"),
                     ],
                 ]);
                 
                 let price_impact_emoji = if price_impact > 3.0 { "⚠️" } else if price_impact > 1.0 { "🟡" } else { "🟢" };
-                let route_info = if !quote.route_plan.is_empty() {
-                    format!("🛣️ **Route:** {}", quote.route_plan[0].swap_info.label)
-                } else {
-                    "🛣️ **Route:** Direct".to_string()
+                let route = match quote.route_plan.first() {
+                    Some(step) => step.swap_info.label.clone(),
+                    None => t(lang, "swap.route_direct"),
                 };
                 
-                let message = format!(
-                    "💱 **Swap Preview**\n\n\
-                    **From:** {:.6} {}\n\
-                    **To:** {:.6} {}\n\
-                    **Rate:** 1 {} = {:.6} {}\n\n\
-                    {} **Price Impact:** {:.3}%\n\
-                    {}\n\n\
-                    **💸 Fees Breakdown:**\n\
-                    • Network Fee: ${:.6}\n\
-                    • Platform Fee: ${:.6}\n\
-                    • Price Impact: ${:.6}\n\
-                    • **Total Cost:** ${:.6} ({:.3}%)\n\n\
-                    **⚡ Execution:**\n\
-                    • Jupiter DEX Aggregator\n\
-                    • MEV Protection: ✅ Enabled\n\
-                    • Slippage: 1.0% max\n\n\
-                    **⚠️ Review carefully before confirming!**",
-                    amount,
-                    from_token.to_uppercase(),
-                    output_display,
-                    to_token.to_uppercase(),
-                    from_token.to_uppercase(),
-                    output_display / amount,
-                    to_token.to_uppercase(),
-                    price_impact_emoji,
-                    price_impact,
-                    route_info,
-                    fee_breakdown.network_fee,
-                    fee_breakdown.platform_fee,
-                    fee_breakdown.price_impact_cost,
-                    fee_breakdown.total_fee,
-                    fee_breakdown.fee_percentage
-                );
+                let from_symbol = from_token.to_uppercase();
+                let to_symbol = to_token.to_uppercase();
+                let message = t_args(lang, "swap.preview", &[
+                    ("amount", &format!("{:.6}", amount)),
+                    ("from", &from_symbol),
+                    ("output", &format!("{:.6}", output_display)),
+                    ("to", &to_symbol),
+                    ("rate", &format!("{:.6}", output_display / amount)),
+                    ("impact_emoji", price_impact_emoji),
+                    ("impact", &format!("{:.3}", price_impact)),
+                    ("route", &route),
+                    ("network_fee", &format!("{:.6}", fee_breakdown.network_fee)),
+                    ("platform_fee", &format!("{:.6}", fee_breakdown.platform_fee)),
+                    ("impact_cost", &format!("{:.6}", fee_breakdown.price_impact_cost)),
+                    ("total_fee", &format!("{:.6}", fee_breakdown.total_fee)),
+                    ("fee_percent", &format!("{:.3}", fee_breakdown.fee_percentage)),
+                ]);
                 
                 // Warn about high price impact
                 if price_impact > 5.0 {
                     bot.send_message(msg.chat.id, 
-                        format!("⚠️ **HIGH PRICE IMPACT WARNING**\n\n\
-                        This swap has a {:.2}% price impact, which is quite high.\n\
-                        Consider reducing the amount or trying again later.", price_impact))
+                        t_args(lang, "swap.high_impact", &[("impact", &format!("{:.2}", price_impact))]))
                         .await?;
                 }
                 
@@ -242,15 +194,15 @@ Use `/wallet` to connect your wallet first!"#;
                 bot.delete_message(msg.chat.id, loading_msg.id).await.ok();
                 error!("Failed to get swap quote: {}", e);
                 
-                let error_message = if e.to_string().contains("insufficient") {
-                    "❌ **Insufficient Balance**\n\nYou don't have enough tokens for this swap."
+                let error_key = if e.to_string().contains("insufficient") {
+                    "swap.insufficient_balance"
                 } else if e.to_string().contains("slippage") {
-                    "❌ **High Slippage**\n\nThis swap would have too much slippage. Try a smaller amount."
+                    "swap.high_slippage"
                 } else {
-                    "❌ **Quote Failed**\n\nCouldn't get a quote for this swap. Please check the token symbols and try again."
+                    "swap.quote_failed"
                 };
                 
-                bot.send_message(msg.chat.id, error_message).await?;
+                bot.send_message(msg.chat.id, t(lang, error_key)).await?;
             }
         }
         
@@ -267,10 +219,9 @@ Use `/wallet` to connect your wallet first!"#;
         to_token: &str,
         amount: f64,
         user_id: &str,
+        lang: &str,
     ) -> ResponseResult<()> {
-        let execution_msg = bot.send_message(msg.chat.id, 
-            "⚡ Executing swap with MEV protection...")
-            .await?;
+        let execution_msg = bot.send_message(msg.chat.id, t(lang, "swap.executing")).await?;
         
         // Get wallet
         let wallet = wallet_manager.get_user_wallet(user_id).await?
@@ -298,38 +249,25 @@ Use `/wallet` to connect your wallet first!"#;
                 if result.success {
                     let output_display = Self::convert_from_base_units(result.output_amount, to_token);
                     
-                    let success_message = format!(
-                        "✅ **Swap Successful!**\n\n\
-                        **Swapped:** {:.6} {} → {:.6} {}\n\
-                        **Rate:** 1 {} = {:.6} {}\n\
-                        **Price Impact:** {:.3}%\n\
-                        **Execution Time:** {}ms\n\n\
-                        🔗 **Transaction:** `{}`\n\n\
-                        View on Solscan: https://solscan.io/tx/{}",
-                        amount,
-                        from_token.to_uppercase(),
-                        output_display,
-                        to_token.to_uppercase(),
-                        from_token.to_uppercase(),
-                        if amount > 0.0 { output_display / amount } else { 0.0 },
-                        to_token.to_uppercase(),
-                        result.price_impact,
-                        result.execution_time_ms,
-                        result.signature.as_deref().unwrap_or("N/A"),
-                        result.signature.as_deref().unwrap_or("")
-                    );
+                    let success_message = t_args(lang, "swap.success", &[
+                        ("amount", &format!("{:.6}", amount)),
+                        ("from", &from_token.to_uppercase()),
+                        ("output", &format!("{:.6}", output_display)),
+                        ("to", &to_token.to_uppercase()),
+                        ("rate", &format!("{:.6}", if amount > 0.0 { output_display / amount } else { 0.0 })),
+                        ("impact", &format!("{:.3}", result.price_impact)),
+                        ("ms", &result.execution_time_ms.to_string()),
+                        ("tx", result.signature.as_deref().unwrap_or("N/A")),
+                        ("tx_link", result.signature.as_deref().unwrap_or("")),
+                    ]);
                     
                     bot.send_message(msg.chat.id, success_message).await?;
                 } else {
-                    let error_msg = format!(
-                        "❌ **Swap Failed**\n\n\
-                        Error: {}\n\
-                        Execution Time: {}ms\n\n\
-                        Don't worry, no tokens were lost.\n\
-                        Please try again or contact support.",
-                        result.error.unwrap_or_else(|| "Unknown error".to_string()),
-                        result.execution_time_ms
-                    );
+                    let error = result.error.unwrap_or_else(|| t(lang, "errors.unknown"));
+                    let error_msg = t_args(lang, "swap.failed", &[
+                        ("error", &error),
+                        ("ms", &result.execution_time_ms.to_string()),
+                    ]);
                     
                     bot.send_message(msg.chat.id, error_msg).await?;
                 }
@@ -338,15 +276,7 @@ Use `/wallet` to connect your wallet first!"#;
                 bot.delete_message(msg.chat.id, execution_msg.id).await.ok();
                 error!("Swap execution failed: {}", e);
                 
-                bot.send_message(msg.chat.id, 
-                    format!("❌ **Swap Execution Failed**\n\n\
-                    Error: {}\n\n\
-                    This could be due to:\n\
-                    • Network congestion\n\
-                    • Insufficient balance\n\
-                    • Price movement\n\
-                    • Transaction timeout\n\n\
-                    Please try again.", e))
+                bot.send_message(msg.chat.id, t_args(lang, "swap.execution_failed", &[("error", &e.to_string())]))
                     .await?;
             }
         }
@@ -361,7 +291,7 @@ Use `/wallet` to connect your wallet first!"#;
         Ok((from_mint, to_mint))
     }
     
-    /// Get token mint address
+    /// Get token mint address; the error is the unsupported symbol
     fn get_token_mint(symbol: &str) -> Result<String, String> {
         match symbol.to_uppercase().as_str() {
             "SOL" => Ok("So11111111111111111111111111111111111111112".to_string()),
@@ -377,7 +307,7 @@ Use `/wallet` to connect your wallet first!"#;
                 if symbol.len() == 44 {
                     Ok(symbol.to_string())
                 } else {
-                    Err(format!("Unsupported token: {}. Use mint address for other tokens.", symbol))
+                    Err(symbol.to_string())
                 }
             }
         }
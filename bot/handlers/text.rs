@@ -1,16 +1,17 @@
-use teloxide::{prelude::*, types::Message};
+use teloxide::{prelude::*, types::Message, utils::markdown::escape};
 use std::sync::Arc;
 use tracing::error;
 
 use crate::{
     trading::TradingEngineHandle,
     ai::GroqAnalyzer,
+    bot::BotServices,
     db::Database,
-    utils::Config,
+    utils::{t, Config},
     wallet::WalletManager,
     errors::Result,
 };
-use super::{menu::*, trading::TradingHandler, wallet::WalletHandler};
+use super::{chart::ChartHandler, command::CommandHandler, import::ImportHandler, language::LanguageHandler, launch::LaunchHandler, menu::*, send::SendHandler, trading::TradingHandler, wallet::WalletHandler};
 
 /// Handler for text messages (keyboard button presses)
 pub struct TextMessageHandler;
@@ -25,6 +26,7 @@ impl TextMessageHandler {
         db: Arc<Database>,
         config: Arc<Config>,
         wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
     ) -> ResponseResult<()> {
         let user_id = msg.from()
             .map(|u| u.id.0.to_string())
@@ -34,37 +36,71 @@ impl TextMessageHandler {
             return Ok(());
         }
         
+        // Passphrase replies are deleted and never reach another handler
+        if WalletHandler::handle_passphrase_reply(&bot, &msg, &services, &wallet_manager, &user_id).await? {
+            return Ok(());
+        }
+        
+        // The typed phrase that confirms a /panic sell
+        if TradingHandler::handle_panic_phrase(&bot, &msg, &db, &wallet_manager, &services, &user_id).await? {
+            return Ok(());
+        }
+        
+        // A CSV sent after /import trades
+        if ImportHandler::handle_document(&bot, &msg, &services, &user_id).await? {
+            return Ok(());
+        }
+        
+        // A recipient CSV sent after /send <token> <amount>
+        if SendHandler::handle_document(&bot, &msg, &services, &wallet_manager, &user_id).await? {
+            return Ok(());
+        }
+        
+        // Answers and pictures for an open /launch wizard
+        if LaunchHandler::handle_reply(&bot, &msg, &services, &wallet_manager, &user_id).await? {
+            return Ok(());
+        }
+        
+        // Replies to a chart price prompt take precedence over keyboard buttons
+        if ChartHandler::handle_price_reply(&bot, &msg, &services, &trading_engine, &wallet_manager, &user_id).await? {
+            return Ok(());
+        }
+        
         if let Some(text) = msg.text() {
-            match text {
-                "💰 Balance" => {
-                    Self::handle_balance_button(bot, msg, trading_engine, wallet_manager, user_id).await?;
+            let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+            match MenuButton::parse(text) {
+                Some(MenuButton::Balance) => {
+                    Self::handle_balance_button(bot, msg, trading_engine, wallet_manager, user_id, &lang).await?;
+                }
+                Some(MenuButton::Portfolio) => {
+                    Self::handle_portfolio_button(bot, msg, &lang).await?;
                 }
-                "📊 Portfolio" => {
-                    Self::handle_portfolio_button(bot, msg).await?;
+                Some(MenuButton::Trade) => {
+                    Self::handle_trade_button(bot, msg, &lang).await?;
                 }
-                "⚡ Trade" => {
-                    Self::handle_trade_button(bot, msg).await?;
+                Some(MenuButton::Rebates) => {
+                    Self::handle_rebates_button(bot, msg, db, user_id, &lang).await?;
                 }
-                "💎 Rebates" => {
-                    Self::handle_rebates_button(bot, msg, db, user_id).await?;
+                Some(MenuButton::AiAnalysis) => {
+                    Self::handle_ai_analysis_button(bot, msg, &lang).await?;
                 }
-                "🤖 AI Analysis" => {
-                    Self::handle_ai_analysis_button(bot, msg).await?;
+                Some(MenuButton::Wallet) => {
+                    Self::handle_wallet_button(bot, msg, &lang).await?;
                 }
-                "💼 Wallet" => {
-                    Self::handle_wallet_button(bot, msg).await?;
+                Some(MenuButton::Settings) => {
+                    Self::handle_settings_button(bot, msg, &lang).await?;
                 }
-                "⚙️ Settings" => {
-                    Self::handle_settings_button(bot, msg).await?;
+                Some(MenuButton::Help) => {
+                    Self::handle_help_button(bot, msg, &lang).await?;
                 }
-                "📚 Help" => {
-                    Self::handle_help_button(bot, msg).await?;
+                Some(MenuButton::Charts) => {
+                    Self::handle_charts_button(bot, msg, &lang).await?;
                 }
-                "📈 Charts" => {
-                    Self::handle_charts_button(bot, msg).await?;
+                Some(MenuButton::Language) => {
+                    LanguageHandler::send_picker(&bot, msg.chat.id, &lang).await?;
                 }
-                _ => {
-                    Self::handle_unknown_text(bot, msg, text).await?;
+                None => {
+                    Self::handle_unknown_text(bot, msg, text, &lang).await?;
                 }
             }
         }
@@ -79,44 +115,32 @@ impl TextMessageHandler {
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
         user_id: String,
+        lang: &str,
     ) -> ResponseResult<()> {
         // Check if user has a wallet configured
         let user_wallet = match wallet_manager.get_user_wallet(&user_id).await {
             Ok(Some(wallet)) => wallet.public_key,
             Ok(None) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ No wallet configured. Please use /start to set up your wallet first.")
+                bot.send_message(msg.chat.id, t(lang, "errors.no_wallet"))
                     .await?;
                 return Ok(());
             }
             Err(e) => {
                 error!("Failed to get user wallet: {}", e);
-                bot.send_message(msg.chat.id, "❌ Error accessing wallet")
+                bot.send_message(msg.chat.id, t(lang, "errors.wallet_access"))
                     .await?;
                 return Ok(());
             }
         };
         match trading_engine.get_balance(user_wallet.clone()).await {
             Ok(balance) => {
-                let message = format!(
-                    "💰 *Wallet Balance*\\n\\n\
-                    SOL: {:.4}\\n\
-                    USDC: {:.2}\\n\\n\
-                    Total Value: ${:.2}\\n\\n\
-                    _Last updated: {}_",
-                    balance.sol,
-                    balance.usdc,
-                    balance.total_usd_value,
-                    chrono::Utc::now().format("%H:%M:%S UTC")
-                );
-                
-                bot.send_message(msg.chat.id, message)
+                bot.send_message(msg.chat.id, WalletHandler::balance_text(lang, &balance))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
             Err(e) => {
                 error!("Failed to get balance: {}", e);
-                bot.send_message(msg.chat.id, "❌ Failed to fetch balance")
+                bot.send_message(msg.chat.id, t(lang, "balance.failed"))
                     .await?;
             }
         }
@@ -124,48 +148,40 @@ impl TextMessageHandler {
         Ok(())
     }
     
+    /// `📊 *Portfolio Menu*` over its hint, for the menu buttons
+    fn menu_text(lang: &str, emoji: &str, title_key: &str, hint_key: &str) -> String {
+        format!("{} *{}*\n\n{}", emoji, escape(&t(lang, title_key)), escape(&t(lang, hint_key)))
+    }
+    
     /// Handle portfolio button press
-    async fn handle_portfolio_button(bot: Bot, msg: Message) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, "📊 *Portfolio Menu*\\n\\nChoose an option:")
+    async fn handle_portfolio_button(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        bot.send_message(msg.chat.id, Self::menu_text(lang, "📊", "menus.portfolio_title", "menus.portfolio_hint"))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(create_portfolio_menu())
+            .reply_markup(MenuCreator::portfolio_menu(lang))
             .await?;
         Ok(())
     }
     
     /// Handle trade button press
-    async fn handle_trade_button(bot: Bot, msg: Message) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, "⚡ *Trading Menu*\\n\\nChoose your trading action:")
+    async fn handle_trade_button(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        bot.send_message(msg.chat.id, Self::menu_text(lang, "⚡", "menus.trading_title", "menus.trading_hint"))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(create_trading_menu())
+            .reply_markup(MenuCreator::trading_menu(lang))
             .await?;
         Ok(())
     }
     
     /// Handle rebates button press
-    async fn handle_rebates_button(bot: Bot, msg: Message, db: Arc<Database>, user_id: String) -> ResponseResult<()> {
+    async fn handle_rebates_button(bot: Bot, msg: Message, db: Arc<Database>, user_id: String, lang: &str) -> ResponseResult<()> {
         match db.get_user_rebates(&user_id).await {
             Ok(rebates) => {
-                let message = format!(
-                    "💎 *MEV Rebates Earned*\\n\\n\
-                    Today: {:.6} SOL\\n\
-                    This Week: {:.6} SOL\\n\
-                    This Month: {:.6} SOL\\n\
-                    All Time: {:.6} SOL\\n\\n\
-                    _Rebates are paid instantly with each trade\\!_",
-                    rebates.today,
-                    rebates.week,
-                    rebates.month,
-                    rebates.all_time
-                );
-                
-                bot.send_message(msg.chat.id, message)
+                bot.send_message(msg.chat.id, CommandHandler::rebates_text(lang, &rebates))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
             Err(e) => {
                 error!("Failed to get rebates: {}", e);
-                bot.send_message(msg.chat.id, "❌ Failed to fetch rebate information")
+                bot.send_message(msg.chat.id, t(lang, "rebates.failed"))
                     .await?;
             }
         }
@@ -174,86 +190,56 @@ impl TextMessageHandler {
     }
     
     /// Handle AI analysis button press
-    async fn handle_ai_analysis_button(bot: Bot, msg: Message) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, "🤖 *AI Analysis Menu*\\n\\nWhat would you like to analyze?")
+    async fn handle_ai_analysis_button(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        bot.send_message(msg.chat.id, Self::menu_text(lang, "🤖", "menus.ai_title", "menus.ai_hint"))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(create_analytics_menu())
+            .reply_markup(MenuCreator::analytics_menu(lang))
             .await?;
         Ok(())
     }
     
     /// Handle wallet button press
-    async fn handle_wallet_button(bot: Bot, msg: Message) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, "💼 *Wallet Menu*\\n\\nManage your wallets:")
+    async fn handle_wallet_button(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        bot.send_message(msg.chat.id, Self::menu_text(lang, "💼", "menus.wallet_title", "menus.wallet_hint"))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(create_wallet_menu())
+            .reply_markup(MenuCreator::wallet_menu(lang))
             .await?;
         Ok(())
     }
     
     /// Handle settings button press
-    async fn handle_settings_button(bot: Bot, msg: Message) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, "⚙️ *Settings Menu*\\n\\nCustomize your bot:")
+    async fn handle_settings_button(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        bot.send_message(msg.chat.id, Self::menu_text(lang, "⚙️", "menus.settings_title", "menus.settings_hint"))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .reply_markup(create_settings_menu())
+            .reply_markup(MenuCreator::settings_menu(lang))
             .await?;
         Ok(())
     }
     
     /// Handle help button press
-    async fn handle_help_button(bot: Bot, msg: Message) -> ResponseResult<()> {
-        let help_text = r#"📚 *Solana Trading Bot Help*
-
-*Main Features:*
-• 💰 Balance \\- Check your wallet balance
-• 📊 Portfolio \\- View positions and P&L
-• ⚡ Trade \\- Buy/sell tokens instantly
-• 💎 Rebates \\- Track MEV rebate earnings
-• 🤖 AI Analysis \\- Get market insights
-• 💼 Wallet \\- Manage your wallets
-• ⚙️ Settings \\- Configure the bot
-
-*Commands:*
-/start \\- Initialize the bot
-/balance \\- Quick balance check
-/buy <token> <amount> \\- Buy tokens
-/sell <token> <percentage> \\- Sell tokens
-/portfolio \\- View portfolio
-/analyze <token> \\- AI analysis
-/rebates \\- View rebate stats
-/help \\- Show this help
-
-*Security:*
-• 🔒 Non\\-custodial \\(you control keys\\)
-• 🛡️ MEV protection enabled
-• 💎 Instant rebate payments
-• 🔐 Private key never stored
-
-*Support:*
-Contact @support for help\\."#;
-        
-        bot.send_message(msg.chat.id, help_text)
+    async fn handle_help_button(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        bot.send_message(msg.chat.id, CommandHandler::help_text(lang))
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
             .await?;
         Ok(())
     }
     
     /// Handle charts button press
-    async fn handle_charts_button(bot: Bot, msg: Message) -> ResponseResult<()> {
-        bot.send_message(msg.chat.id, "📈 Coming soon! Chart integration with TradingView.")
+    async fn handle_charts_button(bot: Bot, msg: Message, lang: &str) -> ResponseResult<()> {
+        bot.send_message(msg.chat.id, t(lang, "charts.coming_soon"))
             .await?;
         Ok(())
     }
     
     /// Handle unknown text input
-    async fn handle_unknown_text(bot: Bot, msg: Message, text: &str) -> ResponseResult<()> {
+    async fn handle_unknown_text(bot: Bot, msg: Message, text: &str, lang: &str) -> ResponseResult<()> {
         if text.starts_with('/') {
             // Command without proper parsing, ignore
             return Ok(());
         }
         
-        bot.send_message(msg.chat.id, "❓ Unknown command. Use /help or the menu buttons below.")
-            .reply_markup(create_main_menu())
+        bot.send_message(msg.chat.id, t(lang, "menu.unknown"))
+            .reply_markup(MenuCreator::main_menu(lang))
             .await?;
         Ok(())
     }
@@ -1,106 +1,175 @@
-use teloxide::{prelude::*, types::{Message, CallbackQuery}};
+use teloxide::{prelude::*, types::{Message, CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup}, utils::markdown::escape};
 use std::sync::Arc;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 
 use crate::{
-    trading::TradingEngineHandle,
-    wallet::WalletManager,
+    trading::{hide_dust, BalanceChange, ConfirmationStage, ConfirmationTracker, ExecutionReport, ExitDenomination, LiquidityEstimator, OrderSide, PaperLedger, PanicPlan, PanicReport, PanicSellOutcome, PhraseCheck, Position, PositionSync, Reconciliation, SandwichMonitor, SmartSellTimer, TimingOutcome, TokenResolver, TradeResult, TradingEngineHandle, TradingMode, PANIC_PHRASE},
+    analytics::{CloseReason, CostBasisBook, CostBasisMethod, LotTrade, PerformanceTracker, PositionClose, TradeJournal, TradeRecord},
+    wallet::{TransactionPriority, WalletManager},
     db::Database,
-    errors::Result,
-    utils::validation::{Validator, ValidatedAmount, ValidatedPercentage, ValidatedTokenSymbol, ValidatedUserId},
+    alerts::{BondingTracker, TokenCalendar},
+    bot::{callback_action::CallbackAction, preferences::PreferenceStore, BotServices},
+    errors::{service_label, BotError, Result},
+    utils::{
+        i18n::{fmt_number, lang_of, NumberKind},
+        t, t_args, DEFAULT_LANG,
+        validation::{Validator, ValidatedAmount, ValidatedPercentage, ValidatedTokenSymbol, ValidatedUserId},
+    },
 };
+use super::quick_buy::QuickBuyHandler;
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
 
 /// Handler for trading-related operations
 pub struct TradingHandler;
 
 impl TradingHandler {
-    /// Execute a quick trade from callback
-    pub async fn execute_quick_trade(
+    /// Execute a quick buy from a callback button
+    ///
+    /// `max_trade_sol` is the cap the amount is checked against: the user's
+    /// own max, or the global limit once they've confirmed a larger buy.
+    /// `priority` replaces the user's priority fee level, for retries.
+    pub async fn execute_quick_buy(
         bot: &Bot,
         q: &CallbackQuery,
-        token: &str,
+        mint: &str,
+        symbol: &str,
         amount: f64,
-        is_buy: bool,
+        max_trade_sol: f64,
+        priority: Option<TransactionPriority>,
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
+        preferences: Arc<PreferenceStore>,
+        confirmations: Arc<ConfirmationTracker>,
     ) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
+            let lang = preferences.language(q.from.id.0 as i64, Some(&q.from)).await;
+            
             // Validate and sanitize user ID
             let user_id_str = q.from.id.0.to_string();
             let user_id = match ValidatedUserId::new(&user_id_str) {
                 Ok(id) => id,
                 Err(e) => {
                     error!("Invalid user ID {}: {}", user_id_str, e);
-                    bot.send_message(msg.chat.id, "❌ Invalid user session")
+                    bot.send_message(msg.chat.id, t(&lang, "errors.invalid_session"))
                         .await?;
                     return Ok(());
                 }
             };
             
-            // Validate token symbol
-            let validated_token = match ValidatedTokenSymbol::new(token) {
-                Ok(t) => t,
-                Err(e) => {
-                    error!("Invalid token symbol {}: {}", token, e);
-                    bot.send_message(msg.chat.id, "❌ Invalid token symbol")
-                        .await?;
-                    return Ok(());
-                }
-            };
-            
-            // Validate trade amount
-            let validated_amount = match ValidatedAmount::new(amount, crate::constants::MAX_TRADE_SOL) {
+            let validated_amount = match ValidatedAmount::new(amount, max_trade_sol) {
                 Ok(a) => a,
                 Err(e) => {
                     error!("Invalid trade amount {}: {}", amount, e);
-                    bot.send_message(msg.chat.id, format!("❌ {}", e))
+                    bot.send_message(msg.chat.id, e.render_for_telegram())
+                        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                         .await?;
                     return Ok(());
                 }
             };
             
+            if Self::reject_if_locked(bot, msg.chat.id, &wallet_manager, user_id.as_str(), &lang).await? {
+                return Ok(());
+            }
+            
             let user_wallet = match wallet_manager.get_user_wallet(user_id.as_str()).await {
                 Ok(Some(wallet)) => wallet.public_key,
                 Ok(None) => {
-                    bot.send_message(msg.chat.id, 
-                        "❌ No wallet configured. Please use /start to set up your wallet first.")
+                    bot.send_message(msg.chat.id, t(&lang, "errors.no_wallet"))
                         .await?;
                     return Ok(());
                 }
                 Err(e) => {
                     error!("Failed to get user wallet: {}", e);
-                    bot.send_message(msg.chat.id, "❌ Error accessing wallet")
+                    bot.send_message(msg.chat.id, t(&lang, "errors.wallet_access"))
                         .await?;
                     return Ok(());
                 }
             };
             
-            if is_buy {
-                match trading_engine.buy_with_rebate(user_wallet.clone(), validated_token.as_str().to_string(), validated_amount.value()).await {
-                    Ok(result) => {
-                        let message = format!(
-                            "✅ Quick buy executed\\!\n{} {} for {} SOL\nRebate: {:.6} SOL\n\n[View on Solscan](https://solscan\\.io/tx/{})",
-                            result.tokens_received, validated_token.as_str(), validated_amount.value(), result.rebate_earned, result.tx_signature
+            let mut defaults = preferences.get(q.from.id.0 as i64).await.trade_defaults();
+            if let Some(priority) = priority {
+                defaults.priority = priority;
+            }
+            match trading_engine.buy_with_defaults(user_wallet.clone(), mint.to_string(), validated_amount.value(), defaults).await {
+                Ok(result) => {
+                    wallet_manager.record_originated(&result.tx_signature).await;
+                    let message = format!(
+                        "{}\n\n[{}](https://solscan\\.io/tx/{}){}{}",
+                        escape(&t_args(&lang, "quick_buy.executed", &[
+                            ("amount", &fmt_number(&lang, result.tokens_received, NumberKind::Token)),
+                            ("symbol", symbol),
+                            ("sol", &fmt_number(&lang, validated_amount.value(), NumberKind::Sol)),
+                            ("rebate", &fmt_number(&lang, result.rebate_earned, NumberKind::Sol)),
+                        ])),
+                        escape(&t(&lang, "trading.view_solscan")),
+                        result.tx_signature,
+                        Self::format_fill_check(&result, &lang),
+                        Self::format_execution_report(&result.execution, &lang)
+                    );
+                    let sent = bot.send_message(msg.chat.id, &message)
+                        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                        .await?;
+                    if !result.execution.simulated {
+                        Self::spawn_confirmation_updates(
+                            bot.clone(),
+                            &sent,
+                            message,
+                            &lang,
+                            &confirmations,
+                            &result.tx_signature,
+                            CallbackAction::RetryBuy { token: mint.to_string(), amount_sol: validated_amount.value() },
                         );
-                        bot.send_message(msg.chat.id, message)
-                            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                            .await?;
-                    }
-                    Err(e) => {
-                        bot.send_message(msg.chat.id, format!("❌ Trade failed: {}", e))
-                            .await?;
                     }
                 }
-            } else {
-                // Quick sell logic would go here
-                bot.send_message(msg.chat.id, "💸 Quick sell feature coming soon!")
-                    .await?;
+                Err(e) => {
+                    bot.send_message(msg.chat.id, Self::failure_message(&t(&lang, "trading.action_trade"), &e, &lang))
+                        .await?;
+                }
             }
         }
         
         Ok(())
     }
     
+    /// Tell the user trading is locked after an activity alert; true when locked
+    async fn reject_if_locked(
+        bot: &Bot,
+        chat_id: ChatId,
+        wallet_manager: &WalletManager,
+        user_id: &str,
+        lang: &str,
+    ) -> ResponseResult<bool> {
+        if !wallet_manager.is_trading_locked(user_id).await {
+            return Ok(false);
+        }
+        
+        bot.send_message(chat_id, t(lang, "errors.trading_locked")).await?;
+        Ok(true)
+    }
+    
+    /// Ask before a buy whose estimated price impact exceeds `slippage_bps`; true when asked
+    pub async fn confirm_if_high_impact(
+        bot: &Bot,
+        chat_id: ChatId,
+        liquidity: &LiquidityEstimator,
+        mint: &str,
+        symbol: &str,
+        amount_sol: f64,
+        slippage_bps: u16,
+        lang: &str,
+    ) -> ResponseResult<bool> {
+        let Some(estimate) = liquidity.needs_confirmation(mint, OrderSide::Buy, amount_sol, slippage_bps).await else {
+            return Ok(false);
+        };
+
+        info!("💧 {} SOL buy of {} needs confirmation: {} bps impact", amount_sol, symbol, estimate.price_impact_bps);
+        bot.send_message(chat_id, QuickBuyHandler::impact_confirmation_text(symbol, &estimate, slippage_bps, lang))
+            .reply_markup(QuickBuyHandler::impact_confirmation_keyboard(mint, amount_sol, lang))
+            .await?;
+        Ok(true)
+    }
+    
     /// Handle buy command
     pub async fn handle_buy(
         bot: Bot,
@@ -109,31 +178,42 @@ impl TradingHandler {
         trading_engine: TradingEngineHandle,
         db: Arc<Database>,
         wallet_manager: Arc<WalletManager>,
+        sandwich_monitor: Arc<SandwichMonitor>,
+        preferences: Arc<PreferenceStore>,
+        performance: Arc<PerformanceTracker>,
+        cost_basis: Arc<CostBasisBook>,
+        liquidity: Arc<LiquidityEstimator>,
+        confirmations: Arc<ConfirmationTracker>,
         user_id: String,
     ) -> ResponseResult<()> {
+        let lang = preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        
         // Validate user ID
         let validated_user_id = match ValidatedUserId::new(&user_id) {
             Ok(id) => id,
             Err(e) => {
                 error!("Invalid user ID {}: {}", user_id, e);
-                bot.send_message(msg.chat.id, "❌ Invalid user session")
+                bot.send_message(msg.chat.id, t(&lang, "errors.invalid_session"))
                     .await?;
                 return Ok(());
             }
         };
         
+        if Self::reject_if_locked(&bot, msg.chat.id, &wallet_manager, validated_user_id.as_str(), &lang).await? {
+            return Ok(());
+        }
+        
         // Check if user has a wallet configured
         let user_wallet = match wallet_manager.get_user_wallet(validated_user_id.as_str()).await {
             Ok(Some(wallet)) => wallet.public_key,
             Ok(None) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ No wallet configured. Please use /start to set up your wallet first.")
+                bot.send_message(msg.chat.id, t(&lang, "errors.no_wallet"))
                     .await?;
                 return Ok(());
             }
             Err(e) => {
                 error!("Failed to get user wallet: {}", e);
-                bot.send_message(msg.chat.id, "❌ Error accessing wallet")
+                bot.send_message(msg.chat.id, t(&lang, "errors.wallet_access"))
                     .await?;
                 return Ok(());
             }
@@ -143,7 +223,8 @@ impl TradingHandler {
         let sanitized_args = match Validator::sanitize_command_args(&args) {
             Ok(args) => args,
             Err(e) => {
-                bot.send_message(msg.chat.id, format!("❌ {}", e))
+                bot.send_message(msg.chat.id, e.render_for_telegram())
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
@@ -151,12 +232,8 @@ impl TradingHandler {
         
         let parts: Vec<&str> = sanitized_args.split_whitespace().collect();
         if parts.len() != 2 {
-            bot.send_message(
-                msg.chat.id, 
-                "Usage: /buy <token> <amount_sol>\\nExample: /buy BONK 0\\.1"
-            )
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .await?;
+            bot.send_message(msg.chat.id, t(&lang, "trading.buy_usage"))
+                .await?;
             return Ok(());
         }
         
@@ -164,69 +241,115 @@ impl TradingHandler {
         let validated_token = match ValidatedTokenSymbol::new(parts[0]) {
             Ok(t) => t,
             Err(e) => {
-                bot.send_message(msg.chat.id, format!("❌ {}", e))
+                bot.send_message(msg.chat.id, e.render_for_telegram())
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
         };
         
         // Parse and validate amount
-        let amount: f64 = match parts[1].parse() {
+        let amount = match Validator::parse_amount(parts[1]) {
             Ok(a) => a,
-            Err(_) => {
-                bot.send_message(msg.chat.id, "❌ Invalid amount format")
+            Err(e) => {
+                bot.send_message(msg.chat.id, e.render_for_telegram())
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
         };
         
-        let validated_amount = match ValidatedAmount::new(amount, crate::constants::MAX_TRADE_SOL) {
+        let settings = match validated_user_id.as_str().parse::<i64>() {
+            Ok(telegram_id) => preferences.get(telegram_id).await,
+            Err(_) => Default::default(),
+        };
+        let validated_amount = match ValidatedAmount::new(amount, settings.max_trade_sol) {
             Ok(a) => a,
             Err(e) => {
-                bot.send_message(msg.chat.id, format!("❌ {}", e))
+                bot.send_message(msg.chat.id, e.render_for_telegram())
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
         };
         
-        bot.send_message(msg.chat.id, format!("⏳ Buying {} with {} SOL...", validated_token.as_str(), validated_amount.value()))
+        let mint = TokenResolver::resolve(validated_token.as_str())
+            .unwrap_or_else(|_| validated_token.as_str().to_string());
+        if Self::confirm_if_high_impact(
+            &bot, msg.chat.id, &liquidity, &mint, validated_token.as_str(), validated_amount.value(), settings.slippage_bps, &lang,
+        ).await? {
+            return Ok(());
+        }
+        
+        bot.send_message(msg.chat.id, t_args(&lang, "trading.buying", &[
+            ("token", validated_token.as_str()),
+            ("amount", &validated_amount.value().to_string()),
+        ]))
             .await?;
         
-        match trading_engine.buy_with_rebate(user_wallet.clone(), validated_token.as_str().to_string(), validated_amount.value()).await {
+        match trading_engine.buy_with_defaults(user_wallet.clone(), validated_token.as_str().to_string(), validated_amount.value(), settings.trade_defaults()).await {
             Ok(result) => {
+                wallet_manager.record_originated(&result.tx_signature).await;
                 let message = format!(
-                    "✅ *Buy Order Executed*\\n\\n\
-                    Token: {}\\n\
-                    Amount: {} SOL\\n\
-                    Received: {:.2} tokens\\n\
-                    Price: ${:.8}\\n\
-                    Rebate Earned: {:.6} SOL\\n\\n\
-                    [View Transaction](https://solscan\\.io/tx/{})",
-                    validated_token.as_str(),
-                    validated_amount.value(),
-                    result.tokens_received,
-                    result.price,
-                    result.rebate_earned,
-                    result.tx_signature
+                    "✅ *{}*\n\n{}\n\n{}{}{}",
+                    escape(&t(&lang, "trading.buy_title")),
+                    escape(&t_args(&lang, "trading.buy_details", &[
+                        ("token", validated_token.as_str()),
+                        ("amount", &fmt_number(&lang, validated_amount.value(), NumberKind::Sol)),
+                        ("tokens", &fmt_number(&lang, result.tokens_received, NumberKind::Token)),
+                        ("price", &fmt_number(&lang, result.price, NumberKind::Usd)),
+                        ("rebate", &fmt_number(&lang, result.rebate_earned, NumberKind::Sol)),
+                    ])),
+                    Self::transaction_link(&result, &lang),
+                    Self::format_fill_check(&result, &lang),
+                    Self::format_execution_report(&result.execution, &lang)
                 );
                 
-                bot.send_message(msg.chat.id, message)
+                let sent = bot.send_message(msg.chat.id, &message)
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
-                
-                // Record trade in database
-                let _ = db.record_trade(
-                    validated_user_id.as_str(),
-                    validated_token.as_str(),
-                    validated_amount.value(),
-                    result.tokens_received,
-                    result.rebate_earned,
-                    &result.tx_signature,
-                ).await;
+                // Paper fills have no transaction to inspect or rebate to record
+                if !result.execution.simulated {
+                    Self::spawn_confirmation_updates(
+                        bot.clone(),
+                        &sent,
+                        message,
+                        &lang,
+                        &confirmations,
+                        &result.tx_signature,
+                        CallbackAction::RetryBuy { token: mint.clone(), amount_sol: validated_amount.value() },
+                    );
+                    Self::spawn_sandwich_check(
+                        bot.clone(),
+                        msg.chat.id,
+                        sandwich_monitor,
+                        validated_user_id.as_str(),
+                        validated_token.as_str(),
+                        &result.tx_signature,
+                    );
+                    
+                    let _ = db.record_trade(
+                        validated_user_id.as_str(),
+                        validated_token.as_str(),
+                        validated_amount.value(),
+                        result.tokens_received,
+                        result.rebate_earned,
+                        &result.tx_signature,
+                    ).await;
+                }
+                if let Ok(telegram_id) = validated_user_id.as_str().parse::<i64>() {
+                    let token_pair = format!("{}/SOL", validated_token.as_str());
+                    performance.record_entry(telegram_id, &token_pair, result.timestamp).await;
+                    
+                    // Paper fills stay out of the lot book
+                    if let Some(lot) = LotTrade::from_trade_result(&result, &mint).filter(|_| !result.execution.simulated) {
+                        cost_basis.apply(telegram_id, &lot).await;
+                    }
+                }
             }
             Err(e) => {
                 error!("Trade failed: {}", e);
-                bot.send_message(msg.chat.id, format!("❌ Trade failed: {}", e))
+                bot.send_message(msg.chat.id, Self::failure_message(&t(&lang, "trading.action_trade"), &e, &lang))
                     .await?;
             }
         }
@@ -242,31 +365,42 @@ impl TradingHandler {
         trading_engine: TradingEngineHandle,
         db: Arc<Database>,
         wallet_manager: Arc<WalletManager>,
+        journal: Arc<TradeJournal>,
+        sandwich_monitor: Arc<SandwichMonitor>,
+        smart_sell: Arc<SmartSellTimer>,
+        preferences: Arc<PreferenceStore>,
+        performance: Arc<PerformanceTracker>,
+        cost_basis: Arc<CostBasisBook>,
         user_id: String,
     ) -> ResponseResult<()> {
+        let lang = preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        
         // Validate user ID
         let validated_user_id = match ValidatedUserId::new(&user_id) {
             Ok(id) => id,
             Err(e) => {
                 error!("Invalid user ID {}: {}", user_id, e);
-                bot.send_message(msg.chat.id, "❌ Invalid user session")
+                bot.send_message(msg.chat.id, t(&lang, "errors.invalid_session"))
                     .await?;
                 return Ok(());
             }
         };
         
+        if Self::reject_if_locked(&bot, msg.chat.id, &wallet_manager, validated_user_id.as_str(), &lang).await? {
+            return Ok(());
+        }
+        
         // Check if user has a wallet configured
         let user_wallet = match wallet_manager.get_user_wallet(validated_user_id.as_str()).await {
             Ok(Some(wallet)) => wallet.public_key,
             Ok(None) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ No wallet configured. Please use /start to set up your wallet first.")
+                bot.send_message(msg.chat.id, t(&lang, "errors.no_wallet"))
                     .await?;
                 return Ok(());
             }
             Err(e) => {
                 error!("Failed to get user wallet: {}", e);
-                bot.send_message(msg.chat.id, "❌ Error accessing wallet")
+                bot.send_message(msg.chat.id, t(&lang, "errors.wallet_access"))
                     .await?;
                 return Ok(());
             }
@@ -276,20 +410,26 @@ impl TradingHandler {
         let sanitized_args = match Validator::sanitize_command_args(&args) {
             Ok(args) => args,
             Err(e) => {
-                bot.send_message(msg.chat.id, format!("❌ {}", e))
+                bot.send_message(msg.chat.id, e.render_for_telegram())
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
         };
         
-        let parts: Vec<&str> = sanitized_args.split_whitespace().collect();
+        let mut parts: Vec<&str> = sanitized_args.split_whitespace().collect();
+        // "smart" / "now" override the user's default sell timing for this trade
+        let timing_override = match parts.first() {
+            Some(&"smart") => Some(true),
+            Some(&"now") => Some(false),
+            _ => None,
+        };
+        if timing_override.is_some() {
+            parts.remove(0);
+        }
         if parts.len() != 2 {
-            bot.send_message(
-                msg.chat.id,
-                "Usage: /sell <token> <percentage>\\nExample: /sell BONK 50"
-            )
-            .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-            .await?;
+            bot.send_message(msg.chat.id, t(&lang, "trading.sell_usage"))
+                .await?;
             return Ok(());
         }
         
@@ -297,17 +437,19 @@ impl TradingHandler {
         let validated_token = match ValidatedTokenSymbol::new(parts[0]) {
             Ok(t) => t,
             Err(e) => {
-                bot.send_message(msg.chat.id, format!("❌ {}", e))
+                bot.send_message(msg.chat.id, e.render_for_telegram())
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
         };
         
         // Parse and validate percentage
-        let percentage: f64 = match parts[1].parse() {
+        let percentage = match Validator::parse_amount(parts[1]) {
             Ok(p) => p,
-            Err(_) => {
-                bot.send_message(msg.chat.id, "❌ Invalid percentage format")
+            Err(e) => {
+                bot.send_message(msg.chat.id, e.render_for_telegram())
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
@@ -316,57 +458,124 @@ impl TradingHandler {
         let validated_percentage = match ValidatedPercentage::new(percentage) {
             Ok(p) => p,
             Err(e) => {
-                bot.send_message(msg.chat.id, format!("❌ {}", e))
+                bot.send_message(msg.chat.id, e.render_for_telegram())
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
                 return Ok(());
             }
         };
         
-        bot.send_message(msg.chat.id, format!("⏳ Selling {}% of {}...", validated_percentage.value(), validated_token.as_str()))
+        let user_preferences = match validated_user_id.as_str().parse::<i64>() {
+            Ok(telegram_id) => preferences.get(telegram_id).await,
+            Err(_) => Default::default(),
+        };
+        let timing = if timing_override.unwrap_or(user_preferences.smart_sell) {
+            Self::time_smart_sell(
+                &bot,
+                msg.chat.id,
+                &trading_engine,
+                &smart_sell,
+                &user_wallet,
+                validated_token.as_str(),
+                validated_percentage.value(),
+                user_preferences.slippage_bps,
+                &lang,
+            ).await?
+        } else {
+            None
+        };
+        
+        bot.send_message(msg.chat.id, t_args(&lang, "trading.selling", &[
+            ("percent", &validated_percentage.value().to_string()),
+            ("token", validated_token.as_str()),
+        ]))
             .await?;
         
-        match trading_engine.sell_with_rebate(user_wallet.clone(), validated_token.as_str().to_string(), validated_percentage.value()).await {
+        match trading_engine.sell_with_defaults(user_wallet.clone(), validated_token.as_str().to_string(), validated_percentage.value(), user_preferences.trade_defaults()).await {
             Ok(result) => {
+                wallet_manager.record_originated(&result.tx_signature).await;
                 let pnl_emoji = if result.pnl_percentage >= 0.0 { "📈" } else { "📉" };
-                let pnl_sign = if result.pnl_percentage >= 0.0 { "+" } else { "" };
                 
                 let message = format!(
-                    "✅ *Sell Order Executed*\\n\\n\
-                    Token: {}\\n\
-                    Sold: {}%\\n\
-                    Received: {:.4} SOL\\n\
-                    Price: ${:.8}\\n\
-                    Rebate Earned: {:.6} SOL\\n\
-                    {} P&L: {}{:.2}%\\n\\n\
-                    [View Transaction](https://solscan\\.io/tx/{})",
-                    validated_token.as_str(),
-                    validated_percentage.value(),
-                    result.sol_received,
-                    result.price,
-                    result.rebate_earned,
+                    "✅ *{}*\n\n{}\n{} {}\n{}\n{}{}{}",
+                    escape(&t(&lang, "trading.sell_title")),
+                    escape(&t_args(&lang, "trading.sell_details", &[
+                        ("token", validated_token.as_str()),
+                        ("percent", &fmt_number(&lang, validated_percentage.value(), NumberKind::Percent(0))),
+                        ("sol", &fmt_number(&lang, result.sol_received, NumberKind::Sol)),
+                        ("price", &fmt_number(&lang, result.price, NumberKind::Usd)),
+                        ("rebate", &fmt_number(&lang, result.rebate_earned, NumberKind::Sol)),
+                    ])),
                     pnl_emoji,
-                    pnl_sign,
-                    result.pnl_percentage,
-                    result.tx_signature
+                    escape(&t_args(&lang, "trading.pnl", &[("pnl", &fmt_number(&lang, result.pnl_percentage, NumberKind::Change))])),
+                    timing.as_ref()
+                        .map(|t| format!("⏱️ {}\n", escape(&t.summary())))
+                        .unwrap_or_default(),
+                    Self::transaction_link(&result, &lang),
+                    Self::format_fill_check(&result, &lang),
+                    Self::format_execution_report(&result.execution, &lang)
                 );
                 
                 bot.send_message(msg.chat.id, message)
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
+                // Paper fills have no transaction to inspect or rebate to record
+                if !result.execution.simulated {
+                    Self::spawn_sandwich_check(
+                        bot.clone(),
+                        msg.chat.id,
+                        sandwich_monitor,
+                        validated_user_id.as_str(),
+                        validated_token.as_str(),
+                        &result.tx_signature,
+                    );
+                    
+                    let _ = db.record_trade(
+                        validated_user_id.as_str(),
+                        validated_token.as_str(),
+                        -result.sol_received,
+                        -result.tokens_sold,
+                        result.rebate_earned,
+                        &result.tx_signature,
+                    ).await;
+                }
                 
-                // Record trade in database
-                let _ = db.record_trade(
-                    validated_user_id.as_str(),
-                    validated_token.as_str(),
-                    -result.sol_received,
-                    -result.tokens_sold,
-                    result.rebate_earned,
-                    &result.tx_signature,
-                ).await;
+                // A full exit closes the position: offer a journal tag
+                if let Ok(telegram_id) = validated_user_id.as_str().parse::<i64>() {
+                    let token_pair = format!("{}/SOL", validated_token.as_str());
+                    let record = TradeRecord::from_trade_result(&result, &token_pair, "manual");
+                    let position_closed = validated_percentage.value() >= 100.0;
+                    let mint = TokenResolver::resolve(validated_token.as_str())
+                        .unwrap_or_else(|_| validated_token.as_str().to_string());
+                    
+                    // Realize the sell against the user's lots so /stats reports real PnL
+                    let realized = match LotTrade::from_trade_result(&result, &mint).filter(|_| !result.execution.simulated) {
+                        Some(lot) => cost_basis.apply(telegram_id, &lot).await,
+                        None => None,
+                    };
+                    let recorded = match &realized {
+                        Some(realized) => performance.record_realized(telegram_id, record, realized, position_closed).await,
+                        None => performance.record_exit(telegram_id, record, position_closed).await,
+                    };
+                    if let Err(e) = recorded {
+                        warn!("📊 Failed to record sell for {}: {}", telegram_id, e);
+                    }
+
+                    if let Some(close) = PositionClose::from_sell(
+                        telegram_id,
+                        &mint,
+                        validated_token.as_str(),
+                        validated_percentage.value(),
+                        &result,
+                        CloseReason::ManualSell,
+                    ) {
+                        journal.on_position_closed(close).await;
+                    }
+                }
             }
             Err(e) => {
                 error!("Sell failed: {}", e);
-                bot.send_message(msg.chat.id, format!("❌ Sell failed: {}", e))
+                bot.send_message(msg.chat.id, Self::failure_message(&t(&lang, "trading.action_sell"), &e, &lang))
                     .await?;
             }
         }
@@ -374,20 +583,666 @@ impl TradingHandler {
         Ok(())
     }
     
+    /// Handle /smartsell on|off - per-user default for sell timing
+    pub async fn handle_smart_sell_default(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(DEFAULT_LANG, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        
+        let enabled = match args.trim() {
+            "on" => true,
+            "off" => false,
+            _ => {
+                let current = services.preferences.get(telegram_id).await.smart_sell;
+                let state = t(&lang, if current { "common.on" } else { "common.off" });
+                bot.send_message(msg.chat.id, t_args(&lang, "smart_sell.status", &[("state", &state)]))
+                    .await?;
+                return Ok(());
+            }
+        };
+        
+        let saved = services.preferences.update(telegram_id, |preferences| {
+            preferences.smart_sell = enabled;
+            Ok(())
+        }).await;
+        if let Err(e) = saved {
+            error!("Failed to save settings for {}: {}", telegram_id, e);
+            bot.send_message(msg.chat.id, t(&lang, "errors.settings_not_saved")).await?;
+            return Ok(());
+        }
+        
+        let config = services.smart_sell.config();
+        let reply = if enabled {
+            t_args(&lang, "smart_sell.enabled", &[
+                ("sol", &config.min_size_sol.to_string()),
+                ("seconds", &config.window.num_seconds().to_string()),
+            ])
+        } else {
+            t(&lang, "smart_sell.disabled")
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        
+        Ok(())
+    }
+    
+    /// Handle /paper on|off|reset - simulated fills against a virtual ledger
+    pub async fn handle_paper(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        trading_engine: TradingEngineHandle,
+        wallet_manager: Arc<WalletManager>,
+        user_id: String,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let user_wallet = match wallet_manager.get_user_wallet(&user_id).await {
+            Ok(Some(wallet)) => wallet.public_key,
+            Ok(None) => {
+                bot.send_message(msg.chat.id, t(lang, "errors.no_wallet")).await?;
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Failed to get user wallet: {}", e);
+                bot.send_message(msg.chat.id, t(lang, "errors.wallet_access")).await?;
+                return Ok(());
+            }
+        };
+        
+        let sol = |ledger: &PaperLedger| format!("{:.4}", ledger.sol);
+        let result = match args.trim() {
+            "on" => match trading_engine.set_trading_mode(user_wallet.clone(), TradingMode::Paper).await {
+                Ok(()) => trading_engine.paper_ledger(user_wallet).await
+                    .map(|ledger| t_args(lang, "paper.enabled", &[("sol", &sol(&ledger))])),
+                Err(e) => Err(e),
+            },
+            "off" => trading_engine.set_trading_mode(user_wallet, TradingMode::Live).await
+                .map(|()| t(lang, "paper.disabled")),
+            "reset" => trading_engine.reset_paper_ledger(user_wallet).await
+                .map(|ledger| t_args(lang, "paper.reset", &[("sol", &sol(&ledger))])),
+            _ => match trading_engine.trading_mode(user_wallet.clone()).await {
+                Ok(mode) if mode.is_paper() => trading_engine.paper_ledger(user_wallet).await.map(|ledger| t_args(lang, "paper.status_on", &[
+                    ("sol", &sol(&ledger)),
+                    ("positions", &ledger.positions.len().to_string()),
+                    ("pnl", &format!("{:+.4}", ledger.realized_pnl_sol)),
+                    ("trades", &ledger.trades.to_string()),
+                ])),
+                Ok(_) => Ok(t(lang, "paper.status_off")),
+                Err(e) => Err(e),
+            },
+        };
+        
+        match result {
+            Ok(reply) => bot.send_message(msg.chat.id, reply).await?,
+            Err(e) => {
+                error!("Paper command failed for {}: {}", user_id, e);
+                bot.send_message(msg.chat.id, e.render_for_telegram()).parse_mode(teloxide::types::ParseMode::MarkdownV2).await?
+            }
+        };
+        Ok(())
+    }
+    
+    /// Handle /exitto sol|usdc - what stop-losses, take-profits and /panic sell into
+    pub async fn handle_exit_default(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(DEFAULT_LANG, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        
+        let Some(denomination) = ExitDenomination::parse(&args) else {
+            let current = services.preferences.get(telegram_id).await.exit_denomination;
+            bot.send_message(msg.chat.id, t_args(&lang, "exit.status", &[("exit", current.label())]))
+                .await?;
+            return Ok(());
+        };
+        
+        let saved = services.preferences.update(telegram_id, |preferences| {
+            preferences.exit_denomination = denomination;
+            Ok(())
+        }).await;
+        if let Err(e) = saved {
+            error!("Failed to save settings for {}: {}", telegram_id, e);
+            bot.send_message(msg.chat.id, t(&lang, "errors.settings_not_saved")).await?;
+            return Ok(());
+        }
+        info!("🏦 User {} set exits to {}", telegram_id, denomination.label());
+        
+        let reply = match denomination {
+            ExitDenomination::Sol => t(&lang, "exit.sol"),
+            ExitDenomination::Usdc => t(&lang, "exit.usdc"),
+        };
+        bot.send_message(msg.chat.id, format!("{}\n{}", reply, t(&lang, "exit.orders_keep"))).await?;
+        
+        Ok(())
+    }
+    
+    /// Handle /costbasis fifo|average - how the user's sells realize PnL
+    pub async fn handle_cost_basis_method(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(DEFAULT_LANG, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        
+        let Some(method) = CostBasisMethod::parse(&args) else {
+            let current = services.preferences.get(telegram_id).await.cost_basis_method;
+            bot.send_message(msg.chat.id, t_args(&lang, "cost_basis.status", &[("method", &Self::cost_basis_label(&lang, current))]))
+                .await?;
+            return Ok(());
+        };
+        
+        let saved = services.preferences.update(telegram_id, |preferences| {
+            preferences.cost_basis_method = method;
+            Ok(())
+        }).await;
+        if let Err(e) = saved {
+            error!("Failed to save settings for {}: {}", telegram_id, e);
+            bot.send_message(msg.chat.id, t(&lang, "errors.settings_not_saved")).await?;
+            return Ok(());
+        }
+        info!("📒 User {} set cost basis to {}", telegram_id, method.label());
+        
+        let reply = match method {
+            CostBasisMethod::Fifo => t(&lang, "cost_basis.fifo"),
+            CostBasisMethod::Average => t(&lang, "cost_basis.average"),
+        };
+        bot.send_message(msg.chat.id, format!("{}\n{}", reply, t(&lang, "cost_basis.realized_kept"))).await?;
+        
+        Ok(())
+    }
+    
+    fn cost_basis_label(lang: &str, method: CostBasisMethod) -> String {
+        match method {
+            CostBasisMethod::Fifo => t(lang, "cost_basis.method_fifo"),
+            CostBasisMethod::Average => t(lang, "cost_basis.method_average"),
+        }
+    }
+    
+    /// Handle /panic [sol|usdc|cancel] - preview selling every position into the exit denomination
+    ///
+    /// Nothing sells until the preview's button is tapped and `PANIC_PHRASE` is typed.
+    pub async fn handle_panic(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        trading_engine: TradingEngineHandle,
+        wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        let validated_user_id = match ValidatedUserId::new(&user_id) {
+            Ok(id) => id,
+            Err(e) => {
+                error!("Invalid user ID {}: {}", user_id, e);
+                bot.send_message(msg.chat.id, t(&lang, "errors.invalid_session"))
+                    .await?;
+                return Ok(());
+            }
+        };
+        let Ok(telegram_id) = validated_user_id.as_str().parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(&lang, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        
+        if Self::reject_if_locked(&bot, msg.chat.id, &wallet_manager, validated_user_id.as_str(), &lang).await? {
+            return Ok(());
+        }
+        
+        let user_wallet = match wallet_manager.get_user_wallet(validated_user_id.as_str()).await {
+            Ok(Some(wallet)) => wallet.public_key,
+            Ok(None) => {
+                bot.send_message(msg.chat.id, t(&lang, "errors.no_wallet"))
+                    .await?;
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Failed to get user wallet: {}", e);
+                bot.send_message(msg.chat.id, t(&lang, "errors.wallet_access"))
+                    .await?;
+                return Ok(());
+            }
+        };
+        
+        let words: Vec<&str> = args.split_whitespace().collect();
+        if words.iter().any(|w| w.eq_ignore_ascii_case("cancel")) {
+            let text = if services.panic_sells.cancel(telegram_id).await {
+                t(&lang, "panic.cancelled")
+            } else {
+                t(&lang, "panic.none_waiting")
+            };
+            bot.send_message(msg.chat.id, text).await?;
+            return Ok(());
+        }
+        // An explicit denomination overrides the user's default for this panic only
+        let denomination = match words.iter().find_map(|w| ExitDenomination::parse(w)) {
+            Some(denomination) => denomination,
+            None => services.preferences.get(telegram_id).await.exit_denomination,
+        };
+        
+        Self::preview_panic(&bot, msg.chat.id, telegram_id, &user_wallet, denomination, &trading_engine, &services, &lang).await
+    }
+    
+    /// Quote every position and show the panic preview with its first confirmation button
+    async fn preview_panic(
+        bot: &Bot,
+        chat_id: ChatId,
+        telegram_id: i64,
+        user_wallet: &str,
+        denomination: ExitDenomination,
+        trading_engine: &TradingEngineHandle,
+        services: &BotServices,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let positions = match trading_engine.get_positions(user_wallet.to_string()).await {
+            Ok(positions) => positions,
+            Err(e) => {
+                error!("Failed to get positions: {}", e);
+                bot.send_message(chat_id, t(lang, "panic.positions_failed")).await?;
+                return Ok(());
+            }
+        };
+        if positions.is_empty() {
+            bot.send_message(chat_id, t(lang, "panic.no_positions")).await?;
+            return Ok(());
+        }
+        
+        let quoting = bot.send_message(chat_id, t_args(lang, "panic.quoting", &[("count", &positions.len().to_string())])).await?;
+        let plan = match services.panic_sells.prepare(telegram_id, user_wallet, &positions, denomination).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                bot.edit_message_text(chat_id, quoting.id, format!("📊 {}", e.user_message())).await?;
+                return Ok(());
+            }
+        };
+        let mev_protected = services.mev_protection.is_enabled_for(telegram_id).await;
+        
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(t(lang, "buttons.panic_sell_everything"), "panic:arm"),
+            InlineKeyboardButton::callback(t(lang, "buttons.cancel"), "panic:cancel"),
+        ]]);
+        bot.edit_message_text(chat_id, quoting.id, Self::panic_preview_text(&plan, mev_protected, services.position_sync.dust_threshold_usd(), lang))
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+    
+    /// 🚨 buttons: `panic:start` from /portfolio, `panic:arm` and `panic:cancel` under the preview
+    pub async fn handle_panic_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        trading_engine: TradingEngineHandle,
+        wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let telegram_id = q.from.id.0 as i64;
+        let user_id = telegram_id.to_string();
+        let lang = services.preferences.language(telegram_id, Some(&q.from)).await;
+        
+        match data {
+            "panic:start" => {
+                if Self::reject_if_locked(bot, msg.chat.id, &wallet_manager, &user_id, &lang).await? {
+                    return Ok(());
+                }
+                let Ok(Some(wallet)) = wallet_manager.get_user_wallet(&user_id).await else {
+                    bot.send_message(msg.chat.id, t(&lang, "errors.no_wallet")).await?;
+                    return Ok(());
+                };
+                let denomination = services.preferences.get(telegram_id).await.exit_denomination;
+                Self::preview_panic(bot, msg.chat.id, telegram_id, &wallet.public_key, denomination, &trading_engine, &services, &lang).await?;
+            }
+            "panic:arm" => {
+                let text = match services.panic_sells.arm(telegram_id).await {
+                    Ok(plan) => t_args(&lang, "panic.last_step", &[
+                        ("phrase", PANIC_PHRASE),
+                        ("count", &plan.quotes.len().to_string()),
+                        ("exit", plan.exit.label()),
+                    ]),
+                    Err(e) => format!("❌ {}", e.user_message()),
+                };
+                bot.edit_message_text(msg.chat.id, msg.id, text).await?;
+            }
+            "panic:cancel" => {
+                services.panic_sells.cancel(telegram_id).await;
+                bot.edit_message_text(msg.chat.id, msg.id, t(&lang, "panic.cancelled")).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    
+    /// The typed confirmation phrase; true when a panic sell was waiting for it
+    pub async fn handle_panic_phrase(
+        bot: &Bot,
+        msg: &Message,
+        db: &Database,
+        wallet_manager: &WalletManager,
+        services: &BotServices,
+        user_id: &str,
+    ) -> ResponseResult<bool> {
+        let Some(text) = msg.text() else { return Ok(false) };
+        let Ok(telegram_id) = user_id.parse::<i64>() else { return Ok(false) };
+        
+        let plan = match services.panic_sells.check_phrase(telegram_id, text).await {
+            PhraseCheck::NotWaiting => return Ok(false),
+            PhraseCheck::Expired => {
+                let lang = services.preferences.language(telegram_id, msg.from()).await;
+                bot.send_message(msg.chat.id, t(&lang, "panic.expired")).await?;
+                return Ok(true);
+            }
+            PhraseCheck::Mismatch => {
+                let lang = services.preferences.language(telegram_id, msg.from()).await;
+                bot.send_message(msg.chat.id, t_args(&lang, "panic.mismatch", &[("phrase", PANIC_PHRASE)])).await?;
+                return Ok(true);
+            }
+            PhraseCheck::Confirmed(plan) => plan,
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        if Self::reject_if_locked(bot, msg.chat.id, wallet_manager, user_id, &lang).await? {
+            return Ok(true);
+        }
+        
+        bot.send_message(msg.chat.id, t_args(&lang, "panic.selling", &[
+            ("count", &plan.quotes.len().to_string()),
+            ("exit", plan.exit.label()),
+        ]))
+            .await?;
+        let report = services.panic_sells.execute(&plan).await;
+        
+        for outcome in &report.outcomes {
+            let PanicSellOutcome::Sold { position, result, .. } = outcome else { continue };
+            wallet_manager.record_originated(&result.tx_signature).await;
+            let _ = db.record_trade(
+                user_id,
+                &position.symbol,
+                -result.sol_received,
+                -result.tokens_sold,
+                result.rebate_earned,
+                &result.tx_signature,
+            ).await;
+            if let Some(close) = PositionClose::from_sell(
+                telegram_id,
+                &position.mint,
+                &position.symbol,
+                100.0,
+                result,
+                CloseReason::PanicSell,
+            ) {
+                services.journal.on_position_closed(close).await;
+            }
+        }
+        for outcome in &report.outcomes {
+            if let PanicSellOutcome::Failed { position, error, .. } = outcome {
+                error!("Panic sell of {} failed: {}", position.symbol, error);
+            }
+        }
+        
+        info!("🚨 User {} panic sold {}/{} positions into {}", telegram_id, report.sold(), report.outcomes.len(), plan.exit.label());
+        bot.send_message(msg.chat.id, Self::panic_report_text(&report, &lang)).await?;
+        Ok(true)
+    }
+    
+    pub fn panic_preview_text(plan: &PanicPlan, mev_protected: bool, dust_threshold_usd: f64, lang: &str) -> String {
+        let mut text = t_args(lang, "panic.preview_title", &[
+            ("count", &plan.quotes.len().to_string()),
+            ("exit", plan.exit.label()),
+        ]);
+        text.push('\n');
+        for quote in &plan.quotes {
+            let position = &quote.position;
+            let estimate = match &quote.quote {
+                Ok(sol) => t_args(lang, "panic.quote", &[("sol", &fmt_number(lang, *sol, NumberKind::Sol))]),
+                Err(e) => t_args(lang, "panic.no_quote", &[("error", &e.to_string())]),
+            };
+            text.push_str(&format!("\n• {} {} {}", fmt_number(lang, position.amount, NumberKind::Token), position.symbol, estimate));
+        }
+        if plan.skipped_dust > 0 {
+            text.push_str("\n\n");
+            text.push_str(&t_args(lang, "panic.dust_skipped", &[
+                ("count", &plan.skipped_dust.to_string()),
+                ("threshold", &fmt_number(lang, dust_threshold_usd, NumberKind::Usd)),
+            ]));
+        }
+        text.push_str(&format!(
+            "\n\n{}\n{}\n\n{}",
+            t_args(lang, "panic.quoted_total", &[("sol", &fmt_number(lang, plan.quoted_sol(), NumberKind::Sol))]),
+            t(lang, if mev_protected { "panic.mev_on" } else { "panic.mev_off" }),
+            t_args(lang, "panic.confirm_hint", &[("phrase", PANIC_PHRASE)]),
+        ));
+        text
+    }
+    
+    /// Summary table: what sold, what failed after its retry, and the SOL recovered
+    pub fn panic_report_text(report: &PanicReport, lang: &str) -> String {
+        let mut text = t_args(lang, "panic.finished", &[
+            ("sold", &report.sold().to_string()),
+            ("failed", &report.failed().to_string()),
+        ]);
+        text.push('\n');
+        for outcome in &report.outcomes {
+            let line = match outcome {
+                PanicSellOutcome::Sold { position, result, attempts } => {
+                    let proceeds = result.execution.exit.as_ref()
+                        .map(|exit| exit.summary())
+                        .unwrap_or_else(|| format!("{} SOL", fmt_number(lang, result.sol_received, NumberKind::Sol)));
+                    let retried = if *attempts > 1 { format!(" {}", t(lang, "panic.on_retry")) } else { String::new() };
+                    format!("✅ {} · {}{}", position.symbol, proceeds, retried)
+                }
+                PanicSellOutcome::Failed { position, error, attempts } => {
+                    format!("❌ {} · {} {}", position.symbol, error, t_args(lang, "panic.tried", &[("attempts", &attempts.to_string())]))
+                }
+            };
+            text.push('\n');
+            text.push_str(&line);
+        }
+        text.push_str("\n\n");
+        text.push_str(&t_args(lang, "panic.recovered", &[("sol", &fmt_number(lang, report.total_sol(), NumberKind::Sol))]));
+        if report.failed() > 0 {
+            text.push('\n');
+            text.push_str(&t(lang, "panic.retry_hint"));
+        }
+        text
+    }
+    
+    /// Per-order exit toggle from /orders: `exit:<sol|usdc>:<order_id>`
+    pub async fn handle_exit_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let user_id = q.from.id.0 as i64;
+        let lang = services.preferences.language(user_id, Some(&q.from)).await;
+        
+        let mut parts = data.splitn(3, ':').skip(1);
+        let (Some(denomination), Some(order_id)) = (parts.next().and_then(ExitDenomination::parse), parts.next()) else {
+            return Ok(());
+        };
+        
+        // Only the owner may reroute an order
+        let owned = services.order_manager.get_user_orders(user_id).await
+            .into_iter()
+            .find(|o| o.order_id == order_id);
+        let Some(order) = owned else {
+            bot.send_message(msg.chat.id, t(&lang, "exit.order_not_found")).await?;
+            return Ok(());
+        };
+        
+        match services.order_manager.set_exit_denomination(order_id, Some(denomination)).await {
+            Ok(()) => {
+                bot.send_message(msg.chat.id, t_args(&lang, "exit.order_set", &[
+                    ("order", &order.label()),
+                    ("exit", denomination.label()),
+                ])).await?;
+            }
+            Err(e) => {
+                error!("Failed to set exit for order {}: {}", order_id, e);
+                bot.send_message(msg.chat.id, t(&lang, "exit.order_failed")).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Hold a large sell back for the smart timing window; `None` when the position can't be sized
+    async fn time_smart_sell(
+        bot: &Bot,
+        chat_id: ChatId,
+        trading_engine: &TradingEngineHandle,
+        smart_sell: &SmartSellTimer,
+        user_wallet: &str,
+        symbol: &str,
+        percentage: f64,
+        slippage_bps: u16,
+        lang: &str,
+    ) -> ResponseResult<Option<TimingOutcome>> {
+        let Ok(mint) = TokenResolver::resolve(symbol) else { return Ok(None) };
+        let position = match trading_engine.get_positions(user_wallet.to_string()).await {
+            Ok(positions) => positions.into_iter().find(|p| p.mint == mint),
+            Err(e) => {
+                warn!("⏱️ Smart sell skipped, positions unavailable: {}", e);
+                None
+            }
+        };
+        let Some(position) = position else { return Ok(None) };
+        
+        bot.send_message(chat_id, t_args(lang, "smart_sell.watching", &[
+            ("seconds", &smart_sell.config().window.num_seconds().to_string()),
+            ("symbol", symbol),
+        ]))
+        .await?;
+        
+        let amount = position.amount * (percentage / 100.0);
+        Ok(Some(smart_sell.wait_for_moment(&mint, amount, slippage_bps).await))
+    }
+    
+    /// Inspect the trade's block for a sandwich once it confirms, without holding up the reply
+    fn spawn_sandwich_check(
+        bot: Bot,
+        chat_id: ChatId,
+        monitor: Arc<SandwichMonitor>,
+        user_id: &str,
+        symbol: &str,
+        signature: &str,
+    ) {
+        let Ok(telegram_id) = user_id.parse::<i64>() else { return };
+        let Ok(mint) = TokenResolver::resolve(symbol) else { return };
+        let symbol = symbol.to_string();
+        let signature = signature.to_string();
+
+        tokio::spawn(async move {
+            match monitor.inspect_trade(telegram_id, &signature, &mint, WSOL_MINT).await {
+                Ok(Some(finding)) if monitor.should_notify(&finding) => {
+                    let message = SandwichMonitor::notification_message(&finding, &symbol);
+                    if let Err(e) = bot.send_message(chat_id, message).await {
+                        error!("🥪 Failed to deliver sandwich notice to {}: {}", telegram_id, e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("🥪 Sandwich check skipped for {}: {}", signature, e),
+            }
+        });
+    }
+    
+    /// Edit a sent trade message with each confirmation stage, offering a retry if it expires
+    fn spawn_confirmation_updates(
+        bot: Bot,
+        sent: &Message,
+        text: String,
+        lang: &str,
+        confirmations: &ConfirmationTracker,
+        signature: &str,
+        retry: CallbackAction,
+    ) {
+        let mut stages = match confirmations.track(signature, None) {
+            Ok(stages) => stages,
+            Err(e) => {
+                warn!("📡 Not following {}: {}", signature, e);
+                return;
+            }
+        };
+        let (chat_id, message_id) = (sent.chat.id, sent.id);
+        let lang = lang.to_string();
+        
+        tokio::spawn(async move {
+            while let Some(stage) = stages.recv().await {
+                let edit = bot.edit_message_text(chat_id, message_id, format!("{}\n\n{}", text, Self::confirmation_line(&stage, &lang)))
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2);
+                let result = if stage == ConfirmationStage::Expired {
+                    edit.reply_markup(InlineKeyboardMarkup::new(vec![vec![
+                        InlineKeyboardButton::callback(t(&lang, "buttons.retry_higher_fee"), retry.to_data()),
+                    ]])).await
+                } else {
+                    edit.await
+                };
+                if let Err(e) = result {
+                    warn!("📡 Failed to show {:?} on message {}: {}", stage, message_id, e);
+                }
+            }
+        });
+    }
+    
+    /// Status line under a trade message for its confirmation stage (MarkdownV2)
+    pub fn confirmation_line(stage: &ConfirmationStage, lang: &str) -> String {
+        let landed = |key: &str, slot: u64, fee_lamports: Option<u64>| {
+            let fee = fee_lamports
+                .map(|fee| format!(" · {}", t_args(lang, "confirmation.fee", &[
+                    ("sol", &fmt_number(lang, fee as f64 / 1e9, NumberKind::Sol)),
+                ])))
+                .unwrap_or_default();
+            format!("{}{}", t_args(lang, key, &[("slot", &slot.to_string())]), fee)
+        };
+        let line = match stage {
+            ConfirmationStage::Processing => t(lang, "confirmation.processing"),
+            ConfirmationStage::Confirmed { slot, fee_lamports } => landed("confirmation.confirmed", *slot, *fee_lamports),
+            ConfirmationStage::Finalized { slot, fee_lamports } => landed("confirmation.finalized", *slot, *fee_lamports),
+            ConfirmationStage::Failed { error, .. } => t_args(lang, "confirmation.failed", &[("error", error)]),
+            ConfirmationStage::Expired => t(lang, "confirmation.expired"),
+        };
+        escape(&line)
+    }
+    
     /// Handle portfolio command
     pub async fn handle_portfolio(
         bot: Bot,
         msg: Message,
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
+        calendar: Arc<TokenCalendar>,
+        bonding: Arc<BondingTracker>,
+        position_sync: Arc<PositionSync>,
         user_id: String,
     ) -> ResponseResult<()> {
+        let lang = lang_of(msg.from());
+        
         // Validate user ID
         let validated_user_id = match ValidatedUserId::new(&user_id) {
             Ok(id) => id,
             Err(e) => {
                 error!("Invalid user ID {}: {}", user_id, e);
-                bot.send_message(msg.chat.id, "❌ Invalid user session")
+                bot.send_message(msg.chat.id, t(lang, "errors.invalid_session"))
                     .await?;
                 return Ok(());
             }
@@ -397,63 +1252,347 @@ impl TradingHandler {
         let user_wallet = match wallet_manager.get_user_wallet(validated_user_id.as_str()).await {
             Ok(Some(wallet)) => wallet.public_key,
             Ok(None) => {
-                bot.send_message(msg.chat.id, 
-                    "❌ No wallet configured. Please use /start to set up your wallet first.")
+                bot.send_message(msg.chat.id, t(lang, "errors.no_wallet"))
                     .await?;
                 return Ok(());
             }
             Err(e) => {
                 error!("Failed to get user wallet: {}", e);
-                bot.send_message(msg.chat.id, "❌ Error accessing wallet")
+                bot.send_message(msg.chat.id, t(lang, "errors.wallet_access"))
                     .await?;
                 return Ok(());
             }
         };
         
+        // Paper mode swaps in the virtual ledger; the banner keeps it from passing for real holdings
+        let paper_ledger = match trading_engine.trading_mode(user_wallet.clone()).await {
+            Ok(mode) if mode.is_paper() => trading_engine.paper_ledger(user_wallet.clone()).await.ok(),
+            _ => None,
+        };
+        let banner = paper_ledger.as_ref()
+            .map(|ledger| Self::paper_banner(ledger, lang))
+            .unwrap_or_default();
+        
+        if paper_ledger.is_none() {
+            position_sync.watch(&user_wallet).await;
+        }
+        
         match trading_engine.get_positions(user_wallet.clone()).await {
             Ok(positions) => {
+                let keyboard = Self::portfolio_keyboard(paper_ledger.is_some(), lang);
                 if positions.is_empty() {
                     bot.send_message(
                         msg.chat.id,
-                        "📊 *Portfolio Empty*\\n\\nYou don't have any token positions\\.\n\nStart trading to build your portfolio\\!"
+                        format!(
+                            "{}📊 *{}*\n\n{}",
+                            banner,
+                            escape(&t(lang, "portfolio.empty_title")),
+                            escape(&t(lang, "portfolio.empty_body"))
+                        )
                     )
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                    .reply_markup(keyboard)
                     .await?;
                 } else {
-                    let mut message = String::from("📊 *Your Portfolio*\\n\\n");
+                    let mut message = format!("{}📊 *{}*\n\n", banner, escape(&t(lang, "portfolio.title")));
+                    let now = chrono::Utc::now();
                     
-                    for position in positions.iter() {
-                        let pnl_emoji = if position.pnl_percentage >= 0.0 { "📈" } else { "📉" };
-                        let pnl_sign = if position.pnl_percentage >= 0.0 { "\\+" } else { "" };
+                    if let Ok(telegram_id) = validated_user_id.as_str().parse::<i64>() {
+                        let mints: Vec<String> = positions.iter().map(|p| p.mint.clone()).collect();
+                        calendar.set_holdings(telegram_id, &mints).await;
+                        bonding.set_holdings(telegram_id, &mints).await;
+                    }
+                    
+                    // Dust stays in the data, it just isn't listed
+                    let (visible, hidden) = hide_dust(&positions, position_sync.dust_threshold_usd());
+                    for position in visible {
+                        message.push_str(&Self::position_entry(position, lang));
+                        
+                        if let Some(line) = bonding.position_line(&position.mint, now).await {
+                            message.push_str(&format!(
+                                "{}\\n\\n",
+                                teloxide::utils::markdown::escape(&line)
+                            ));
+                        }
                         
-                        message.push_str(&format!(
-                            "💎 **{}**\\n\
-                            Amount: {:.2}\\n\
-                            Value: ${:.2}\\n\
-                            {} P&L: {}{:.2}%\\n\\n",
-                            position.token_symbol,
-                            position.amount,
-                            position.current_value_usd,
-                            pnl_emoji,
-                            pnl_sign,
-                            position.pnl_percentage
-                        ));
+                        for warning in calendar.position_warnings(&position.mint, now).await {
+                            message.push_str(&format!(
+                                "{}\\n\\n",
+                                teloxide::utils::markdown::escape(&warning)
+                            ));
+                        }
                     }
                     
-                    message.push_str("_Portfolio updated in real\\-time_");
+                    message.push_str(&Self::dust_note(hidden, position_sync.dust_threshold_usd(), lang));
+                    message.push_str(&format!("_{}_", escape(&t(lang, "portfolio.footer"))));
                     
                     bot.send_message(msg.chat.id, message)
                         .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                        .reply_markup(keyboard)
                         .await?;
                 }
             }
             Err(e) => {
                 error!("Failed to get positions: {}", e);
-                bot.send_message(msg.chat.id, "❌ Failed to fetch portfolio")
+                bot.send_message(msg.chat.id, t(lang, "portfolio.failed"))
                     .await?;
             }
         }
         
         Ok(())
     }
-}
\ No newline at end of file
+    
+    /// One /portfolio line block (MarkdownV2); untracked entries say their cost basis is unknown
+    pub fn position_entry(position: &Position, lang: &str) -> String {
+        let pnl = if position.untracked_entry {
+            t(lang, "portfolio.untracked")
+        } else {
+            let pnl_emoji = if position.pnl_percentage >= 0.0 { "📈" } else { "📉" };
+            format!("{} {}", pnl_emoji, t_args(lang, "trading.pnl", &[("pnl", &fmt_number(lang, position.pnl_percentage, NumberKind::Change))]))
+        };
+        format!(
+            "💎 *{}*\n{}\n{}\n\n",
+            escape(&position.symbol),
+            escape(&t_args(lang, "portfolio.position", &[
+                ("amount", &fmt_number(lang, position.amount, NumberKind::Token)),
+                ("value", &fmt_number(lang, position.value_usd, NumberKind::Usd)),
+            ])),
+            escape(&pnl)
+        )
+    }
+    
+    /// "N small balances hidden" footer (MarkdownV2), empty when nothing was hidden
+    pub fn dust_note(hidden: usize, threshold_usd: f64, lang: &str) -> String {
+        match hidden {
+            0 => String::new(),
+            n => format!(
+                "_{}_\n\n",
+                escape(&t_args(lang, if n == 1 { "portfolio.dust_one" } else { "portfolio.dust_many" }, &[
+                    ("count", &n.to_string()),
+                    ("threshold", &fmt_number(lang, threshold_usd, NumberKind::Usd)),
+                ]))
+            ),
+        }
+    }
+    
+    /// Paper holdings have nothing on-chain to sync against
+    fn portfolio_keyboard(paper: bool, lang: &str) -> InlineKeyboardMarkup {
+        let mut row = vec![InlineKeyboardButton::callback(t(lang, "buttons.refresh"), "portfolio_refresh")];
+        if paper {
+            return InlineKeyboardMarkup::new(vec![row]);
+        }
+        row.push(InlineKeyboardButton::callback(t(lang, "buttons.sync"), "portfolio_sync"));
+        InlineKeyboardMarkup::new(vec![row, vec![InlineKeyboardButton::callback(t(lang, "buttons.panic_sell_all"), "panic:start")]])
+    }
+    
+    /// 🔄 Sync under /portfolio: correct positions to the wallet's token balances now
+    pub async fn handle_portfolio_sync(
+        bot: &Bot,
+        q: &CallbackQuery,
+        wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let lang = services.preferences.language(q.from.id.0 as i64, Some(&q.from)).await;
+        let user_wallet = match wallet_manager.get_user_wallet(&q.from.id.0.to_string()).await {
+            Ok(Some(wallet)) => wallet.public_key,
+            Ok(None) => {
+                bot.send_message(msg.chat.id, t(&lang, "errors.no_wallet")).await?;
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Failed to get user wallet: {}", e);
+                bot.send_message(msg.chat.id, t(&lang, "errors.wallet_access")).await?;
+                return Ok(());
+            }
+        };
+        
+        let text = match services.position_sync.sync(&user_wallet).await {
+            Ok(reconciliation) => Self::sync_summary(&reconciliation, &lang),
+            Err(e @ BotError::QueueFull(_)) => Self::failure_message(&t(&lang, "trading.action_sync"), &e, &lang),
+            Err(e) => {
+                warn!("🔄 Position sync failed for {}: {}", user_wallet, e);
+                t_args(&lang, "sync.failed", &[("error", &e.user_message())])
+            }
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        Ok(())
+    }
+    
+    /// What a sync changed, one line per mint
+    pub fn sync_summary(reconciliation: &Reconciliation, lang: &str) -> String {
+        if reconciliation.is_clean() {
+            return t(lang, "sync.clean");
+        }
+        
+        let symbol = |mint: &str| {
+            reconciliation.positions.iter()
+                .find(|p| p.mint == mint)
+                .map(|p| p.symbol.clone())
+                .unwrap_or_else(|| TokenResolver::get_symbol(mint))
+        };
+        let mut lines = vec![t_args(lang, "sync.corrected", &[("count", &reconciliation.changes.len().to_string())])];
+        for change in &reconciliation.changes {
+            lines.push(match change {
+                BalanceChange::Added { mint, amount } => t_args(lang, "sync.added", &[
+                    ("amount", &fmt_number(lang, *amount, NumberKind::Token)),
+                    ("symbol", &symbol(mint)),
+                ]),
+                BalanceChange::Removed { mint, tracked } => t_args(lang, "sync.removed", &[
+                    ("symbol", &symbol(mint)),
+                    ("amount", &fmt_number(lang, *tracked, NumberKind::Token)),
+                ]),
+                BalanceChange::Changed { mint, tracked, on_chain } => format!(
+                    "✏️ {}: {} → {}",
+                    symbol(mint), fmt_number(lang, *tracked, NumberKind::Token), fmt_number(lang, *on_chain, NumberKind::Token)
+                ),
+            });
+        }
+        lines.join("\n")
+    }
+    
+    /// "PAPER" header for /portfolio with the virtual balance (MarkdownV2)
+    pub fn paper_banner(ledger: &PaperLedger, lang: &str) -> String {
+        format!(
+            "🧪 *{}* \\- {}\n{}\n{}\n_{}_\n\n",
+            escape(&t(lang, "paper.banner_title")),
+            escape(&t(lang, "paper.banner_note")),
+            escape(&t_args(lang, "paper.virtual_sol", &[("sol", &fmt_number(lang, ledger.sol, NumberKind::Sol))])),
+            escape(&t_args(lang, "paper.realized", &[
+                ("pnl", &fmt_number(lang, ledger.realized_pnl_sol, NumberKind::Sol)),
+                ("trades", &ledger.trades.to_string()),
+            ])),
+            escape(&t(lang, "paper.banner_hint"))
+        )
+    }
+    
+    /// What to tell the user when a trade errors; throttling and outages are temporary, so ask for a retry
+    pub fn failure_message(action: &str, error: &BotError, lang: &str) -> String {
+        match error {
+            BotError::RateLimited { .. } => t_args(lang, "trading.rate_limited", &[("action", action)]),
+            BotError::ServiceUnavailable { service } => {
+                format!("{} {}", Self::unavailable_message(service, lang), t(lang, "trading.nothing_traded"))
+            }
+            BotError::QueueFull(_) => t_args(lang, "trading.at_capacity", &[("action", action)]),
+            _ => {
+                if !error.is_user_visible() {
+                    error!("{} failed: {:?}", action, error);
+                }
+                t_args(lang, "trading.failed", &[("action", action), ("error", &error.user_message())])
+            }
+        }
+    }
+    
+    /// Short notice for a service whose circuit breaker is open, instead of the raw error
+    pub fn unavailable_message(service: &str, lang: &str) -> String {
+        let service = match service {
+            "groq" => t(lang, "services.ai_analysis"),
+            other => service_label(other).to_string(),
+        };
+        t_args(lang, "errors.service_unavailable", &[("service", &service)])
+    }
+    
+    /// Solscan link, or a note that a paper fill never touched the chain (MarkdownV2)
+    fn transaction_link(result: &TradeResult, lang: &str) -> String {
+        if result.execution.simulated {
+            format!("🧪 *{}* {}", escape(&t(lang, "trading.paper_label")), escape(&t(lang, "trading.paper_not_sent")))
+        } else {
+            format!("[{}](https://solscan\\.io/tx/{})", escape(&t(lang, "trading.view_transaction")), result.tx_signature)
+        }
+    }
+    
+    /// Executed vs quoted price once the fill was read from chain, and any partial fill (MarkdownV2)
+    pub fn format_fill_check(result: &TradeResult, lang: &str) -> String {
+        let mut lines = Vec::new();
+        if let (Some(quoted), Some(executed), Some(slippage)) =
+            (result.quoted_price, result.executed_price, result.realized_slippage_bps)
+        {
+            let verdict = if slippage.abs() < 0.5 {
+                t(lang, "fill.at_quote")
+            } else if slippage < 0.0 {
+                t_args(lang, "fill.better", &[("bps", &format!("{:.0}", -slippage))])
+            } else {
+                t_args(lang, "fill.worse", &[("bps", &format!("{:.0}", slippage))])
+            };
+            lines.push(verdict);
+            lines.push(t_args(lang, "fill.quoted_executed", &[
+                ("quoted", &fmt_number(lang, quoted, NumberKind::Decimal(8))),
+                ("executed", &fmt_number(lang, executed, NumberKind::Decimal(8))),
+            ]));
+        }
+        if let Some(partial) = &result.execution.partial_fill {
+            lines.push(t_args(lang, "fill.partial", &[
+                ("filled", &fmt_number(lang, partial.filled_in, NumberKind::Token)),
+                ("requested", &fmt_number(lang, partial.requested_in, NumberKind::Token)),
+                ("percent", &fmt_number(lang, partial.filled_pct(), NumberKind::Percent(1))),
+            ]));
+        }
+        
+        lines.iter().map(|line| format!("\n{}", escape(line))).collect()
+    }
+    
+    /// Compact execution line plus an expandable details block (MarkdownV2)
+    pub fn format_execution_report(report: &ExecutionReport, lang: &str) -> String {
+        if report.is_empty() {
+            return String::new();
+        }
+        
+        let mut compact = Vec::new();
+        if let Some(route) = &report.route {
+            compact.push(format!("🧭 {}", escape(&route.label())));
+        }
+        if let Some(slippage) = report.slippage_bps {
+            compact.push(escape(&t_args(lang, "report.slippage", &[("bps", &format!("{:.0}", slippage))])));
+        }
+        if let Some(latency) = report.latency_ms() {
+            compact.push(escape(&format!("{} ms", latency)));
+        }
+        if report.simulated {
+            compact.push(format!("🧪 {}", escape(&t(lang, "report.simulated"))));
+        }
+        
+        let mut details = Vec::new();
+        if let Some(quoted) = report.quoted_price {
+            details.push(t_args(lang, "report.quoted", &[("price", &fmt_number(lang, quoted, NumberKind::Decimal(8)))]));
+        }
+        if let Some(realized) = report.realized_price {
+            details.push(t_args(lang, "report.realized", &[("price", &fmt_number(lang, realized, NumberKind::Decimal(8)))]));
+        }
+        if let Some(impact) = report.route.as_ref().and_then(|r| r.price_impact_pct) {
+            details.push(t_args(lang, "report.price_impact", &[("percent", &fmt_number(lang, impact, NumberKind::Percent(2)))]));
+        }
+        let mut broadcasts = t_args(lang, "report.broadcasts", &[("count", &report.broadcast_attempts.to_string())]);
+        if report.blockhash_refreshed {
+            broadcasts.push(' ');
+            broadcasts.push_str(&t(lang, "report.blockhash_refreshed"));
+        }
+        details.push(broadcasts);
+        if let Some(fees) = &report.fees {
+            details.push(t_args(lang, "report.fees", &[
+                ("network", &fmt_number(lang, fees.network_fee_sol, NumberKind::Decimal(6))),
+                ("priority", &fmt_number(lang, fees.priority_fee_lamports as f64, NumberKind::Decimal(0))),
+                ("platform", &fmt_number(lang, fees.platform_fee_sol, NumberKind::Decimal(6))),
+            ]));
+            if fees.transfer_fee_tokens > 0.0 {
+                details.push(t_args(lang, "report.transfer_fee", &[("amount", &fmt_number(lang, fees.transfer_fee_tokens, NumberKind::Token))]));
+            }
+        }
+        if let Some(key) = &report.idempotency_key {
+            details.push(t_args(lang, "report.key", &[("key", key)]));
+        }
+        
+        let mut text = String::from("\n\n");
+        if !compact.is_empty() {
+            text.push_str(&compact.join(" \\| "));
+            text.push('\n');
+        }
+        // Expandable blockquote: collapsed to its first line until tapped
+        text.push_str(&format!("**>🔍 {}", escape(&t(lang, "report.details"))));
+        for line in details {
+            text.push_str("\n>");
+            text.push_str(&escape(&line));
+        }
+        text.push_str("||");
+        text
+    }
+}
@@ -0,0 +1,231 @@
+use chrono::Utc;
+use teloxide::{
+    prelude::*,
+    types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode},
+    utils::markdown::escape,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{
+    bot::{callback_action::{CallbackAction, DEFAULT_QUICK_BUY_SOL}, trending::{NewLaunch, TrendingSnapshot}, BotServices},
+    utils::{format_market_cap, format_volume, t, t_args},
+};
+
+/// /trending - served from the warm trending cache
+pub struct TrendingHandler;
+
+impl TrendingHandler {
+    /// Handle /trending command
+    ///
+    /// Answers from the cache; on a cold start a placeholder is sent and edited
+    /// once the first refresh lands, so the handler never waits on upstreams.
+    pub async fn handle_trending(bot: Bot, msg: Message, services: Arc<BotServices>) -> ResponseResult<()> {
+        let user_id = msg.from().map(|user| user.id.0 as i64).unwrap_or_default();
+        let lang = services.preferences.language(user_id, msg.from()).await;
+        if let Some(snapshot) = services.trending.cached().await {
+            bot.send_message(msg.chat.id, Self::render(&snapshot, &lang))
+                .parse_mode(ParseMode::MarkdownV2)
+                .reply_markup(Self::keyboard(&snapshot, &lang))
+                .await?;
+            return Ok(());
+        }
+
+        let placeholder = bot.send_message(msg.chat.id, t(&lang, "trending.warming_up")).await?;
+        let cache = services.trending.clone();
+        tokio::spawn(async move {
+            let snapshot = cache.warm().await;
+            if let Err(e) = bot.edit_message_text(placeholder.chat.id, placeholder.id, Self::render(&snapshot, &lang))
+                .parse_mode(ParseMode::MarkdownV2)
+                .reply_markup(Self::keyboard(&snapshot, &lang))
+                .await
+            {
+                warn!("📈 Failed to fill in trending placeholder: {}", e);
+            }
+        });
+        Ok(())
+    }
+
+    /// Handle `trending_refresh` and `trending_detailed` buttons
+    pub async fn handle_callback(
+        bot: &Bot,
+        q: &CallbackQuery,
+        data: &str,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let Some(msg) = &q.message else { return Ok(()) };
+        let lang = services.preferences.language(q.from.id.0 as i64, Some(&q.from)).await;
+
+        match data {
+            "trending_refresh" => {
+                // Only this requester waits; everyone else keeps getting the cache
+                let snapshot = services.trending.refresh().await;
+                if let Err(e) = bot.edit_message_text(msg.chat.id, msg.id, Self::render(&snapshot, &lang))
+                    .parse_mode(ParseMode::MarkdownV2)
+                    .reply_markup(Self::keyboard(&snapshot, &lang))
+                    .await
+                {
+                    warn!("📈 Failed to refresh trending message: {}", e);
+                }
+            }
+            "trending_detailed" => {
+                let snapshot = services.trending.warm().await;
+                bot.send_message(msg.chat.id, Self::render_detailed(&snapshot, &lang))
+                    .parse_mode(ParseMode::MarkdownV2)
+                    .await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The /trending message for a snapshot
+    pub fn render(snapshot: &TrendingSnapshot, lang: &str) -> String {
+        let mut message = format!("📈 *{}*\n\n", escape(&t(lang, "trending.title")));
+        message.push_str(&format!("🔥 *{}*\n", escape(&t(lang, "trending.top_gainers"))));
+
+        for (i, token) in snapshot.tokens.iter().take(5).enumerate() {
+            let emoji = match token.price_change_24h {
+                change if change > 100.0 => "🎆",
+                change if change > 50.0 => "🚀",
+                change if change > 20.0 => "📈",
+                change if change > 0.0 => "⬆️",
+                _ => "⬇️"
+            };
+
+            message.push_str(&format!(
+                "{}\\. *{}* \\({}\\) {}\n{}\n\n",
+                i + 1,
+                escape(&token.name),
+                escape(&token.symbol),
+                emoji,
+                escape(&t_args(lang, "trending.token_stats", &[
+                    ("price", &format!("${:.8}", token.price)),
+                    ("change", &format!("{:+.1}%", token.price_change_24h)),
+                    ("volume", &format!("${}", format_volume(token.volume_24h))),
+                    ("market_cap", &format!("${}", format_market_cap(token.market_cap))),
+                ]))
+            ));
+        }
+        if snapshot.tokens.is_empty() {
+            message.push_str(&format!("{}\n", escape(&t(lang, "trending.no_data"))));
+        }
+
+        message.push_str(&format!("\n🆕 *{}*\n", escape(&t(lang, "trending.new_launches"))));
+        for launch in &snapshot.new_launches {
+            let name = launch.name.clone().unwrap_or_else(|| t(lang, "trending.unknown_token"));
+            message.push_str(&format!(
+                "• *{}* \\- {}\n",
+                escape(&name),
+                escape(&t_args(lang, "trending.launch", &[
+                    ("age", &Self::launch_age(launch, lang)),
+                    ("liquidity", &Self::liquidity_status(launch, lang)),
+                    ("holders", &launch.holder_count.to_string()),
+                ]))
+            ));
+        }
+
+        if !snapshot.risk_alerts.is_empty() {
+            message.push_str(&format!("\n⚠️ *{}*\n", escape(&t(lang, "trending.risk_alerts"))));
+            for alert in &snapshot.risk_alerts {
+                message.push_str(&format!("• {} \\- {}\n", escape(&alert.symbol), escape(&alert.reason)));
+            }
+        }
+
+        let total_volume: f64 = snapshot.tokens.iter().map(|t| t.volume_24h).sum();
+        message.push_str(&format!(
+            "\n📊 *{}*\n{}\n",
+            escape(&t(lang, "trending.summary_title")),
+            escape(&t_args(lang, "trending.summary", &[
+                ("volume", &format!("${}", format_volume(total_volume))),
+                ("tokens", &snapshot.tokens.len().to_string()),
+                ("launches", &snapshot.new_launches.len().to_string()),
+            ]))
+        ));
+
+        message.push_str(&format!("\n_{}_\n", escape(&t(lang, "trending.hint_larp"))));
+        message.push_str(&format!("_{}_\n\n", escape(&t(lang, "trending.hint_qbuy"))));
+        message.push_str(&escape(&snapshot.footer(Utc::now(), lang)));
+        message
+    }
+
+    /// Every cached token with its full metrics
+    pub fn render_detailed(snapshot: &TrendingSnapshot, lang: &str) -> String {
+        let mut message = format!("📈 *{}*\n\n", escape(&t(lang, "trending.more_stats_title")));
+        for (i, token) in snapshot.tokens.iter().enumerate() {
+            message.push_str(&escape(&t_args(lang, "trending.detail_line", &[
+                ("rank", &(i + 1).to_string()),
+                ("name", &token.name),
+                ("symbol", &token.symbol),
+                ("change", &format!("{:+.1}%", token.price_change_24h)),
+                ("volume", &format!("${}", format_volume(token.volume_24h))),
+                ("market_cap", &format!("${}", format_market_cap(token.market_cap))),
+            ])));
+            message.push('\n');
+        }
+
+        if !snapshot.tokens.is_empty() {
+            let count = snapshot.tokens.len() as f64;
+            let gainers = snapshot.tokens.iter().filter(|t| t.price_change_24h > 0.0).count();
+            let average_change = snapshot.tokens.iter().map(|t| t.price_change_24h).sum::<f64>() / count;
+            message.push('\n');
+            message.push_str(&escape(&t_args(lang, "trending.detail_summary", &[
+                ("gainers", &gainers.to_string()),
+                ("total", &snapshot.tokens.len().to_string()),
+                ("change", &format!("{:+.1}%", average_change)),
+            ])));
+            message.push('\n');
+        }
+
+        message.push('\n');
+        message.push_str(&escape(&snapshot.footer(Utc::now(), lang)));
+        message
+    }
+
+    fn launch_age(launch: &NewLaunch, lang: &str) -> String {
+        let Some(secs) = launch.age_secs else { return t(lang, "trending.age_unknown") };
+        let (key, count) = match secs {
+            s if s < 3600 => ("trending.age_minutes", s / 60),
+            s if s < 86400 => ("trending.age_hours", s / 3600),
+            s => ("trending.age_days", s / 86400),
+        };
+        t_args(lang, key, &[("count", &count.to_string())])
+    }
+
+    fn liquidity_status(launch: &NewLaunch, lang: &str) -> String {
+        let key = match launch.liquidity_usd {
+            Some(usd) if usd > 100000.0 => "trending.liquidity_high",
+            Some(usd) if usd > 10000.0 => "trending.liquidity_medium",
+            Some(_) => "trending.liquidity_low",
+            None => "trending.liquidity_unknown",
+        };
+        t(lang, key)
+    }
+
+    fn keyboard(snapshot: &TrendingSnapshot, lang: &str) -> InlineKeyboardMarkup {
+        let top = snapshot.tokens.iter().take(3);
+        let buy_buttons: Vec<_> = top.clone()
+            .map(|token| InlineKeyboardButton::callback(
+                t_args(lang, "buttons.buy_symbol", &[("symbol", &token.symbol)]),
+                CallbackAction::quick_buy(token.symbol.as_str(), DEFAULT_QUICK_BUY_SOL).to_data(),
+            ))
+            .collect();
+        let chart_buttons: Vec<_> = top
+            .map(|token| InlineKeyboardButton::callback(
+                format!("📊 {}", token.symbol),
+                format!("tchart_{}", token.address),
+            ))
+            .collect();
+
+        let mut rows = Vec::new();
+        if !buy_buttons.is_empty() {
+            rows.push(buy_buttons);
+            rows.push(chart_buttons);
+        }
+        rows.push(vec![
+            InlineKeyboardButton::callback(t(lang, "buttons.refresh"), "trending_refresh"),
+            InlineKeyboardButton::callback(t(lang, "buttons.more_stats"), "trending_detailed"),
+        ]);
+        InlineKeyboardMarkup::new(rows)
+    }
+}
@@ -1,12 +1,15 @@
-use teloxide::{prelude::*, types::{Message, CallbackQuery}};
+use teloxide::{prelude::*, types::{Message, CallbackQuery, ForceReply, MessageId, ParseMode}, utils::{html, markdown::escape}};
+use solana_sdk::signature::{Keypair, Signer};
 use std::sync::Arc;
-use tracing::{info, error};
+use tracing::{info, error, warn};
+use zeroize::Zeroizing;
 
 use crate::{
-    trading::TradingEngineHandle,
-    wallet::WalletManager,
-    errors::Result,
-    utils::validation::{Validator, ValidatedUserId},
+    bot::{BotServices, wallet_transfer::TransferPrompt},
+    trading::{Balance, TradingEngineHandle},
+    wallet::{WalletExport, WalletManager, MIN_PASSPHRASE_LEN},
+    errors::{BotError, Result},
+    utils::{t, t_args, validation::{Validator, ValidatedUserId}},
 };
 
 /// Handler for wallet-related operations
@@ -19,6 +22,7 @@ impl WalletHandler {
         q: &CallbackQuery,
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             let user_id_str = q.from.id.0.to_string();
@@ -26,12 +30,12 @@ impl WalletHandler {
                 Ok(id) => id,
                 Err(e) => {
                     error!("Invalid user ID {}: {}", user_id_str, e);
-                    bot.send_message(msg.chat.id, "❌ Invalid user session")
+                    bot.send_message(msg.chat.id, t(lang, "errors.invalid_session"))
                         .await?;
                     return Ok(());
                 }
             };
-            Self::show_balance(bot.clone(), msg.chat.id, user_id.as_str(), trading_engine, wallet_manager).await?;
+            Self::show_balance(bot.clone(), msg.chat.id, user_id.as_str(), trading_engine, wallet_manager, lang).await?;
         }
         Ok(())
     }
@@ -41,6 +45,7 @@ impl WalletHandler {
         bot: &Bot,
         q: &CallbackQuery,
         wallet_manager: Arc<WalletManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             let user_id_str = q.from.id.0.to_string();
@@ -48,12 +53,12 @@ impl WalletHandler {
                 Ok(id) => id,
                 Err(e) => {
                     error!("Invalid user ID {}: {}", user_id_str, e);
-                    bot.send_message(msg.chat.id, "❌ Invalid user session")
+                    bot.send_message(msg.chat.id, t(lang, "errors.invalid_session"))
                         .await?;
                     return Ok(());
                 }
             };
-            Self::show_deposit_info(bot.clone(), msg.chat.id, user_id.as_str(), wallet_manager).await?;
+            Self::show_deposit_info(bot.clone(), msg.chat.id, user_id.as_str(), wallet_manager, lang).await?;
         }
         Ok(())
     }
@@ -63,6 +68,7 @@ impl WalletHandler {
         bot: &Bot,
         q: &CallbackQuery,
         wallet_manager: Arc<WalletManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             let user_id_str = q.from.id.0.to_string();
@@ -70,12 +76,12 @@ impl WalletHandler {
                 Ok(id) => id,
                 Err(e) => {
                     error!("Invalid user ID {}: {}", user_id_str, e);
-                    bot.send_message(msg.chat.id, "❌ Invalid user session")
+                    bot.send_message(msg.chat.id, t(lang, "errors.invalid_session"))
                         .await?;
                     return Ok(());
                 }
             };
-            Self::create_new_wallet(bot.clone(), msg.chat.id, user_id.as_str(), wallet_manager).await?;
+            Self::create_new_wallet(bot.clone(), msg.chat.id, user_id.as_str(), wallet_manager, lang).await?;
         }
         Ok(())
     }
@@ -85,6 +91,8 @@ impl WalletHandler {
         bot: &Bot,
         q: &CallbackQuery,
         wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+        lang: &str,
     ) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
             let user_id_str = q.from.id.0.to_string();
@@ -92,45 +100,44 @@ impl WalletHandler {
                 Ok(id) => id,
                 Err(e) => {
                     error!("Invalid user ID {}: {}", user_id_str, e);
-                    bot.send_message(msg.chat.id, "❌ Invalid user session")
+                    bot.send_message(msg.chat.id, t(lang, "errors.invalid_session"))
                         .await?;
                     return Ok(());
                 }
             };
-            Self::export_wallet_keys(bot.clone(), msg.chat.id, user_id.as_str(), wallet_manager).await?;
+            Self::export_wallet_keys(bot.clone(), msg.chat.id, user_id.as_str(), wallet_manager, services, lang).await?;
         }
         Ok(())
     }
     
     /// Handle backup callback
-    pub async fn handle_backup_callback(bot: &Bot, q: &CallbackQuery) -> ResponseResult<()> {
+    pub async fn handle_backup_callback(bot: &Bot, q: &CallbackQuery, lang: &str) -> ResponseResult<()> {
         if let Some(msg) = &q.message {
-            Self::show_backup_guide(bot.clone(), msg.chat.id).await?;
+            Self::show_backup_guide(bot.clone(), msg.chat.id, lang).await?;
         }
         Ok(())
     }
     
     /// Show wallet balance
-    async fn show_balance(
+    pub async fn show_balance(
         bot: Bot,
         chat_id: teloxide::types::ChatId,
         user_id: &str,
         trading_engine: TradingEngineHandle,
         wallet_manager: Arc<WalletManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
         // Check if user has a wallet configured
         let user_wallet = match wallet_manager.get_user_wallet(user_id).await {
             Ok(Some(wallet)) => wallet.public_key,
             Ok(None) => {
-                bot.send_message(chat_id, 
-                    "❌ No wallet configured\\. Please use /start to set up your wallet first\\.")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                bot.send_message(chat_id, t(lang, "errors.no_wallet"))
                     .await?;
                 return Ok(());
             }
             Err(e) => {
                 error!("Failed to get user wallet: {}", e);
-                bot.send_message(chat_id, "❌ Error accessing wallet")
+                bot.send_message(chat_id, t(lang, "errors.wallet_access"))
                     .await?;
                 return Ok(());
             }
@@ -138,25 +145,13 @@ impl WalletHandler {
         
         match trading_engine.get_balance(user_wallet.clone()).await {
             Ok(balance) => {
-                let message = format!(
-                    "💰 *Wallet Balance*\\n\\n\
-                    SOL: {:.4}\\n\
-                    USDC: {:.2}\\n\\n\
-                    Total Value: ${:.2}\\n\\n\
-                    _Last updated: {}_",
-                    balance.sol,
-                    balance.usdc,
-                    balance.total_usd_value,
-                    chrono::Utc::now().format("%H:%M:%S UTC")
-                );
-                
-                bot.send_message(chat_id, message)
+                bot.send_message(chat_id, Self::balance_text(lang, &balance))
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
                     .await?;
             }
             Err(e) => {
                 error!("Failed to get balance: {}", e);
-                bot.send_message(chat_id, "❌ Failed to fetch balance")
+                bot.send_message(chat_id, t(lang, "balance.failed"))
                     .await?;
             }
         }
@@ -164,27 +159,43 @@ impl WalletHandler {
         Ok(())
     }
     
+    /// SOL, USDC and total value with the time they were read, in MarkdownV2
+    pub fn balance_text(lang: &str, balance: &Balance) -> String {
+        let body = t_args(lang, "balance.body", &[
+            ("sol", &format!("{:.4}", balance.sol)),
+            ("usdc", &format!("{:.2}", balance.usdc)),
+            ("total", &format!("${:.2}", balance.total_usd_value)),
+        ]);
+        let updated = t_args(lang, "balance.updated", &[
+            ("time", &chrono::Utc::now().format("%H:%M:%S UTC").to_string()),
+        ]);
+        format!("💰 *{}*\n\n{}\n\n_{}_", escape(&t(lang, "balance.title")), escape(&body), escape(&updated))
+    }
+    
     /// Show deposit information
-    async fn show_deposit_info(
+    pub async fn show_deposit_info(
         bot: Bot,
         chat_id: teloxide::types::ChatId,
         user_id: &str,
         wallet_manager: Arc<WalletManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
         match wallet_manager.get_user_wallet(user_id).await {
             Ok(Some(wallet)) => {
                 let message = format!(
-                    "📥 *Deposit to Your Wallet*\\n\\n\
-                    📍 *Your Wallet Address:*\\n\
-                    `{}`\\n\\n\
-                    🔗 [View on Solscan](https://solscan\\.io/account/{})\\n\\n\
-                    ⚠️ *Important:*\\n\
-                    • Only send Solana \\(SOL\\) or SPL tokens\\n\
-                    • Double\\-check the address\\n\
-                    • Tokens arrive instantly\\n\
-                    • Network fees apply",
+                    "📥 *{}*\n\n\
+                    📍 *{}*\n\
+                    `{}`\n\n\
+                    🔗 [{}](https://solscan\\.io/account/{})\n\n\
+                    ⚠️ *{}*\n\
+                    {}",
+                    escape(&t(lang, "deposit.title")),
+                    escape(&t(lang, "deposit.address")),
                     wallet.public_key,
-                    wallet.public_key
+                    escape(&t(lang, "deposit.solscan")),
+                    wallet.public_key,
+                    escape(&t(lang, "deposit.important")),
+                    escape(&t(lang, "deposit.notes"))
                 );
                 
                 bot.send_message(chat_id, message)
@@ -192,14 +203,12 @@ impl WalletHandler {
                     .await?;
             }
             Ok(None) => {
-                bot.send_message(chat_id, 
-                    "❌ No wallet found\\. Use /start to create a wallet first\\.")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                bot.send_message(chat_id, t(lang, "errors.no_wallet_found"))
                     .await?;
             }
             Err(e) => {
                 error!("Failed to get wallet: {}", e);
-                bot.send_message(chat_id, "❌ Error accessing wallet")
+                bot.send_message(chat_id, t(lang, "errors.wallet_access"))
                     .await?;
             }
         }
@@ -213,26 +222,19 @@ impl WalletHandler {
         chat_id: teloxide::types::ChatId,
         user_id: &str,
         wallet_manager: Arc<WalletManager>,
+        lang: &str,
     ) -> ResponseResult<()> {
         // Show security warning first
-        let warning = r#"⚠️ *SECURITY WARNING*
-
-You are about to create a new wallet\\. Please read carefully:
-
-🔐 *Non\\-Custodial Wallet:*
-• You own and control your private keys
-• We never see or store your private keys
-• If you lose your keys, we CANNOT recover them
-• Your funds are entirely under your control
-
-📝 *You Must Save:*
-• Your 12\\-word recovery phrase
-• Your private key
-• Store them safely offline
-
-*Are you ready to create a secure wallet?*
-
-Type `/confirm` to proceed or `/cancel` to abort\\."#;
+        let section = |title: &str, body: &str| format!("*{}*\n{}", escape(&t(lang, title)), escape(&t(lang, body)));
+        let warning = format!(
+            "⚠️ *{}*\n\n{}\n\n🔐 {}\n\n📝 {}\n\n*{}*\n\n{}",
+            escape(&t(lang, "new_wallet.title")),
+            escape(&t(lang, "new_wallet.intro")),
+            section("new_wallet.custody_title", "new_wallet.custody"),
+            section("new_wallet.save_title", "new_wallet.save"),
+            escape(&t(lang, "new_wallet.ready")),
+            escape(&t(lang, "new_wallet.confirm"))
+        );
         
         bot.send_message(chat_id, warning)
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
@@ -241,79 +243,237 @@ Type `/confirm` to proceed or `/cancel` to abort\\."#;
         Ok(())
     }
     
-    /// Export wallet keys
-    async fn export_wallet_keys(
+    /// Start an encrypted export, once the user has sent a recent /confirm
+    pub async fn export_wallet_keys(
         bot: Bot,
         chat_id: teloxide::types::ChatId,
         user_id: &str,
         wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(chat_id, t(lang, "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        
+        match wallet_manager.get_user_wallet(user_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                bot.send_message(chat_id, t(lang, "errors.no_wallet_found")).await?;
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Failed to look up wallet for export: {}", e);
+                bot.send_message(chat_id, t(lang, "errors.wallet_export")).await?;
+                return Ok(());
+            }
+        }
+        
+        if !services.wallet_transfers.authorize_export(telegram_id).await {
+            let minutes = services.wallet_transfers.confirm_window().num_minutes().to_string();
+            bot.send_message(chat_id, t_args(lang, "export.confirm_first", &[("minutes", &minutes)])).await?;
+            return Ok(());
+        }
+        
+        Self::ask_passphrase(&bot, chat_id, telegram_id, TransferPrompt::Export, &services, lang).await
+    }
+    
+    /// Ask for the passphrase that unlocks an export blob sent with /import
+    pub async fn prompt_import(
+        bot: &Bot,
+        chat_id: teloxide::types::ChatId,
+        telegram_id: i64,
+        blob: String,
+        services: &BotServices,
+        lang: &str,
     ) -> ResponseResult<()> {
-        match wallet_manager.export_user_wallet(user_id).await {
+        Self::ask_passphrase(bot, chat_id, telegram_id, TransferPrompt::Import { blob }, services, lang).await
+    }
+    
+    async fn ask_passphrase(
+        bot: &Bot,
+        chat_id: teloxide::types::ChatId,
+        telegram_id: i64,
+        prompt: TransferPrompt,
+        services: &BotServices,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let text = match prompt {
+            TransferPrompt::Export => {
+                t_args(lang, "export.passphrase_prompt", &[("min", &MIN_PASSPHRASE_LEN.to_string())])
+            }
+            TransferPrompt::Import { .. } => t(lang, "export.import_prompt"),
+        };
+        let placeholder = t(lang, "export.passphrase_placeholder");
+        let sent = bot.send_message(chat_id, text)
+            .reply_markup(ForceReply::new().input_field_placeholder(Some(placeholder)).selective())
+            .await?;
+        services.wallet_transfers.ask(telegram_id, prompt, Some(sent.id.0)).await;
+        Ok(())
+    }
+    
+    /// Take a reply to a passphrase prompt; true when the message was one
+    ///
+    /// The reply and the prompt are deleted before anything else happens.
+    pub async fn handle_passphrase_reply(
+        bot: &Bot,
+        msg: &Message,
+        services: &BotServices,
+        wallet_manager: &WalletManager,
+        user_id: &str,
+    ) -> ResponseResult<bool> {
+        let Some(text) = msg.text() else { return Ok(false) };
+        let Ok(telegram_id) = user_id.parse::<i64>() else { return Ok(false) };
+        let Some(pending) = services.wallet_transfers.take(telegram_id).await else { return Ok(false) };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+        
+        let passphrase = Zeroizing::new(text.to_string());
+        if let Err(e) = bot.delete_message(msg.chat.id, msg.id).await {
+            warn!("🔐 Couldn't delete passphrase reply from {}: {}", telegram_id, e);
+        }
+        if let Some(prompt_id) = pending.prompt_message_id {
+            bot.delete_message(msg.chat.id, MessageId(prompt_id)).await.ok();
+        }
+        
+        if passphrase.trim().eq_ignore_ascii_case("cancel") {
+            bot.send_message(msg.chat.id, t(&lang, "common.cancelled")).await?;
+            return Ok(true);
+        }
+        
+        match pending.prompt {
+            TransferPrompt::Export => {
+                if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+                    let min = MIN_PASSPHRASE_LEN.to_string();
+                    bot.send_message(msg.chat.id, t_args(&lang, "export.passphrase_short", &[("min", &min)])).await?;
+                    Self::ask_passphrase(bot, msg.chat.id, telegram_id, TransferPrompt::Export, services, &lang).await?;
+                    return Ok(true);
+                }
+                Self::send_export(bot, msg.chat.id, user_id, wallet_manager, passphrase, &lang).await?;
+            }
+            TransferPrompt::Import { blob } => {
+                Self::import_export(bot, msg.chat.id, user_id, wallet_manager, blob, passphrase, &lang).await?;
+            }
+        }
+        Ok(true)
+    }
+    
+    async fn send_export(
+        bot: &Bot,
+        chat_id: teloxide::types::ChatId,
+        user_id: &str,
+        wallet_manager: &WalletManager,
+        passphrase: Zeroizing<String>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let keypair = match wallet_manager.export_user_wallet(user_id).await {
             Ok(Some(wallet_data)) => {
-                let message = format!(
-                    "🔑 *Wallet Export*\\n\\n\
-                    📍 *Address:*\\n`{}`\\n\\n\
-                    🔑 *Private Key:*\\n||{}||\\n\\n\
-                    🔗 [View on Solscan](https://solscan\\.io/account/{})\\n\\n\
-                    ⚠️ *CRITICAL SECURITY WARNING:*\\n\
-                    • Never share this private key\\n\
-                    • Delete this message after saving\\n\
-                    • Anyone with this key controls your funds",
-                    wallet_data.address,
-                    wallet_data.private_key,
-                    wallet_data.address
-                );
-                
-                bot.send_message(chat_id, message)
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .await?;
+                let secret = Zeroizing::new(bs58::decode(&wallet_data.private_key).into_vec().unwrap_or_default());
+                match Keypair::from_bytes(&secret) {
+                    Ok(keypair) => keypair,
+                    Err(e) => {
+                        error!("Stored key for {} doesn't decode: {}", user_id, e);
+                        bot.send_message(chat_id, t(lang, "errors.wallet_export")).await?;
+                        return Ok(());
+                    }
+                }
             }
             Ok(None) => {
-                bot.send_message(chat_id, 
-                    "❌ No wallet found\\. Use /start to create one first\\.")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .await?;
+                bot.send_message(chat_id, t(lang, "errors.no_wallet_found")).await?;
+                return Ok(());
             }
             Err(e) => {
                 error!("Failed to export wallet: {}", e);
-                bot.send_message(chat_id, "❌ Error exporting wallet")
-                    .await?;
+                bot.send_message(chat_id, t(lang, "errors.wallet_export")).await?;
+                return Ok(());
+            }
+        };
+        
+        // Argon2id is deliberately slow; keep it off the async workers
+        let address = keypair.pubkey().to_string();
+        let sealed = tokio::task::spawn_blocking(move || WalletExport::encrypt(&keypair, &passphrase))
+            .await
+            .unwrap_or_else(|e| Err(BotError::internal(format!("Export task failed: {}", e)).into()));
+        
+        match sealed {
+            Ok(blob) => {
+                bot.send_message(chat_id, format!(
+                    "🔐 <b>{}</b>\n\n\
+                    📍 {}\n<code>{}</code>\n\n\
+                    {}\n\n\
+                    <code>/import {}</code>\n\n\
+                    ⚠️ {}",
+                    html::escape(&t(lang, "export.title")),
+                    html::escape(&t(lang, "export.address")),
+                    address,
+                    html::escape(&t(lang, "export.restore")),
+                    blob,
+                    html::escape(&t(lang, "export.keep_passphrase"))
+                ))
+                .parse_mode(ParseMode::Html)
+                .await?;
+                info!("🔐 Sent encrypted export of {} to user {}", address, user_id);
+            }
+            Err(e) => {
+                error!("Failed to encrypt export for {}: {}", user_id, e);
+                bot.send_message(chat_id, t(lang, "errors.wallet_export")).await?;
             }
         }
+        Ok(())
+    }
+    
+    /// Register the wallet inside an export; nothing is stored unless decryption succeeds
+    async fn import_export(
+        bot: &Bot,
+        chat_id: teloxide::types::ChatId,
+        user_id: &str,
+        wallet_manager: &WalletManager,
+        blob: String,
+        passphrase: Zeroizing<String>,
+        lang: &str,
+    ) -> ResponseResult<()> {
+        let opened = tokio::task::spawn_blocking(move || WalletExport::decrypt(&blob, &passphrase))
+            .await
+            .unwrap_or_else(|e| Err(BotError::internal(format!("Import task failed: {}", e)).into()));
+        let keypair = match opened {
+            Ok(keypair) => keypair,
+            Err(e) => {
+                bot.send_message(chat_id, t_args(lang, "import.failed", &[("error", &e.to_string())])).await?;
+                return Ok(());
+            }
+        };
         
+        let address = keypair.pubkey().to_string();
+        match wallet_manager.register_wallet(user_id, &address, Some("Imported Wallet".to_string())).await {
+            Ok(()) => {
+                bot.send_message(chat_id, t_args(lang, "import.done", &[("address", &address)])).await?;
+                info!("🔐 Imported wallet {} for user {} from an export", address, user_id);
+            }
+            Err(e) => {
+                error!("Failed to register imported wallet: {}", e);
+                bot.send_message(chat_id, t(lang, "errors.wallet_import")).await?;
+            }
+        }
         Ok(())
     }
     
     /// Show backup guide
-    async fn show_backup_guide(
+    pub async fn show_backup_guide(
         bot: Bot,
         chat_id: teloxide::types::ChatId,
+        lang: &str,
     ) -> ResponseResult<()> {
-        let guide = r#"💾 *Wallet Backup Guide*
-
-🔐 *What to Backup:*
-• Your 12\\-word mnemonic phrase
-• Your private key \\(from /export\\)
-
-📝 *How to Backup:*
-1\\. Write down your mnemonic on paper
-2\\. Store in multiple secure locations
-3\\. Never save digitally \\(photos, cloud, etc\\.\\)
-4\\. Test recovery before using
-
-❌ *Never Share:*
-• Your mnemonic phrase
-• Your private key
-• Screenshots of sensitive data
-
-✅ *Safe Storage Options:*
-• Hardware wallet \\(Ledger, Trezor\\)
-• Metal seed phrase backup
-• Safe deposit box
-• Multiple paper copies
-
-⚠️ *Remember:* If you lose your backup, you lose your funds\\!
-There is NO way to recover a lost wallet\\."#;
+        let section = |title: &str, body: &str| format!("*{}*\n{}", escape(&t(lang, title)), escape(&t(lang, body)));
+        let guide = format!(
+            "💾 *{}*\n\n🔐 {}\n\n📝 {}\n\n❌ {}\n\n✅ {}\n\n⚠️ *{}* {}",
+            escape(&t(lang, "backup.title")),
+            section("backup.what_title", "backup.what"),
+            section("backup.how_title", "backup.how"),
+            section("backup.never_title", "backup.never"),
+            section("backup.storage_title", "backup.storage"),
+            escape(&t(lang, "backup.remember_title")),
+            escape(&t(lang, "backup.remember"))
+        );
         
         bot.send_message(chat_id, guide)
             .parse_mode(teloxide::types::ParseMode::MarkdownV2)
@@ -0,0 +1,166 @@
+use teloxide::{prelude::*, types::Message};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    bot::{
+        watchlist::{WatchedToken, DIGEST_MOVE_PERCENT, MAX_WATCHLIST_TOKENS},
+        BotServices,
+    },
+    trading::{parse_clock, TokenLookup},
+    utils::{fmt_number, lang_of, t, t_args, NumberKind},
+};
+
+/// How often due digests are looked for
+const DIGEST_CHECK_SECS: u64 = 60;
+
+/// /watch and the daily watchlist digest
+pub struct WatchlistHandler;
+
+impl WatchlistHandler {
+    /// Handle /watch [add <token> | remove <token> | digest <time>|off]
+    pub async fn handle_watch(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+
+        let args: Vec<&str> = args.split_whitespace().collect();
+        let text = match args.as_slice() {
+            [] | ["list"] => Self::list(&services, telegram_id, &lang).await,
+            ["add", token] => Self::add(&services, telegram_id, token, &lang).await,
+            ["remove", token] => Self::remove(&services, telegram_id, token, &lang).await,
+            ["digest", time] => Self::digest(&services, telegram_id, time, &lang).await,
+            _ => t(&lang, "watch.usage"),
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        Ok(())
+    }
+
+    async fn list(services: &BotServices, user_id: i64, lang: &str) -> String {
+        let watchlist = services.watchlists.get(user_id).await;
+        if watchlist.tokens().is_empty() {
+            return t(lang, "watch.empty");
+        }
+
+        let watched = match services.watchlists.snapshot(user_id).await {
+            Ok(watched) => watched,
+            Err(e) => {
+                error!("👀 Failed to price the watchlist of {}: {}", user_id, e);
+                return t_args(lang, "watch.prices_failed", &[("error", &e.to_string())]);
+            }
+        };
+        let lines: Vec<String> = watched.iter().map(|watched| Self::line(watched, lang)).collect();
+        let digest = watchlist.digest_at
+            .map(|at| t_args(lang, "watch.digest_at", &[("time", &at.format("%H:%M").to_string())]))
+            .unwrap_or_else(|| t(lang, "watch.digest_off_hint"));
+        let title = t_args(lang, "watch.title", &[
+            ("count", &watched.len().to_string()),
+            ("max", &MAX_WATCHLIST_TOKENS.to_string()),
+        ]);
+
+        format!("{}\n\n{}\n\n{}", title, lines.join("\n"), digest)
+    }
+
+    /// `BONK · $0.000021 · +7.20% 24h · 🟢 low`
+    pub fn line(watched: &WatchedToken, lang: &str) -> String {
+        let mut line = watched.token.symbol.clone();
+        match watched.quote {
+            Some(quote) => {
+                line.push_str(&format!(" · {}", fmt_number(lang, quote.usd_price, NumberKind::Usd)));
+                if let Some(change) = quote.change_24h_percent {
+                    line.push_str(&format!(" · {} 24h", fmt_number(lang, change, NumberKind::Change)));
+                }
+            }
+            None => line.push_str(&format!(" · {}", t(lang, "watch.no_price"))),
+        }
+        if let Some(risk) = &watched.risk {
+            line.push_str(&format!(" · {}", risk.badge()));
+        }
+        line
+    }
+
+    async fn add(services: &BotServices, user_id: i64, token: &str, lang: &str) -> String {
+        let candidate = match services.token_resolver.lookup(token).await {
+            TokenLookup::Found(candidate) => candidate,
+            lookup => {
+                return lookup
+                    .prompt(token, "/watch add")
+                    .unwrap_or_else(|| t_args(lang, "errors.unknown_token", &[("token", token)]));
+            }
+        };
+
+        match services.watchlists.add(user_id, &candidate.mint, &candidate.symbol).await {
+            Ok(true) => {
+                info!("👀 User {} is watching {}", user_id, candidate.mint);
+                t_args(lang, "watch.added", &[("symbol", &candidate.symbol)])
+            }
+            Ok(false) => t_args(lang, "watch.already", &[("symbol", &candidate.symbol)]),
+            Err(e) => format!("❌ {}", e.user_message()),
+        }
+    }
+
+    async fn remove(services: &BotServices, user_id: i64, token: &str, lang: &str) -> String {
+        // What's on the list first, so tokens the resolver no longer knows can still go
+        let removed = match services.watchlists.remove(user_id, token).await {
+            Ok(None) => match services.token_resolver.lookup(token).await {
+                TokenLookup::Found(candidate) => services.watchlists.remove(user_id, &candidate.mint).await,
+                _ => Ok(None),
+            },
+            removed => removed,
+        };
+
+        match removed {
+            Ok(Some(removed)) => t_args(lang, "watch.removed", &[("symbol", &removed.symbol)]),
+            Ok(None) => t_args(lang, "watch.not_watched", &[("token", token)]),
+            Err(e) => t_args(lang, "watch.update_failed", &[("error", &e.to_string())]),
+        }
+    }
+
+    async fn digest(services: &BotServices, user_id: i64, time: &str, lang: &str) -> String {
+        let timezone = services.dca_engine.timezones().get_user_timezone(user_id).await;
+        let at = if time.eq_ignore_ascii_case("off") {
+            None
+        } else {
+            match parse_clock(time) {
+                Ok(at) => Some(at),
+                Err(e) => return format!("❌ {}", e.user_message()),
+            }
+        };
+
+        match services.watchlists.set_digest(user_id, at, timezone).await {
+            Ok(watchlist) => match watchlist.digest_at {
+                Some(at) => t_args(lang, "watch.digest_set", &[
+                    ("time", &at.format("%H:%M").to_string()),
+                    ("zone", timezone.name()),
+                    ("percent", &DIGEST_MOVE_PERCENT.to_string()),
+                ]),
+                None => t(lang, "watch.digest_off"),
+            },
+            Err(e) => t_args(lang, "watch.digest_failed", &[("error", &e.to_string())]),
+        }
+    }
+
+    /// Look for due digests every minute and send them through the alert delivery methods
+    pub fn spawn_daily_digest(services: Arc<BotServices>) {
+        tokio::spawn(async move {
+            let delivery = services.price_alerts.delivery();
+            let timezones = services.dca_engine.timezones();
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(DIGEST_CHECK_SECS));
+            loop {
+                interval.tick().await;
+                services.watchlists
+                    .send_due_digests(Utc::now(), &timezones, &services.preferences, &delivery)
+                    .await;
+            }
+        });
+    }
+}
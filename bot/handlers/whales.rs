@@ -0,0 +1,114 @@
+use teloxide::{prelude::*, types::Message};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    alerts::WhaleWatcher,
+    bot::BotServices,
+    trading::TokenResolver,
+    utils::{lang_of, t, t_args},
+};
+
+/// /whales: watch large wallets and hear about their big moves
+pub struct WhaleHandler;
+
+impl WhaleHandler {
+    /// Handle /whales [list] | add <address> [min_usd] | top <token> [min_usd] | remove <address>
+    pub async fn handle_whales(
+        bot: Bot,
+        msg: Message,
+        args: String,
+        services: Arc<BotServices>,
+        user_id: String,
+    ) -> ResponseResult<()> {
+        let Ok(telegram_id) = user_id.parse::<i64>() else {
+            bot.send_message(msg.chat.id, t(lang_of(msg.from()), "errors.invalid_session")).await?;
+            return Ok(());
+        };
+        let lang = services.preferences.language(telegram_id, msg.from()).await;
+
+        let mut parts = args.split_whitespace();
+        let subcommand = parts.next().map(str::to_lowercase);
+        let rest: Vec<&str> = parts.collect();
+        let whales = &services.whales;
+        let reply = match (subcommand.as_deref(), rest.as_slice()) {
+            (None | Some("list"), []) => Self::list(whales, telegram_id, &lang).await,
+            (Some("add"), [address, min @ ..]) if min.len() <= 1 => match Self::parse_min(min.first(), &lang) {
+                Ok(min_value) => Self::add(whales, telegram_id, address, min_value, &lang).await,
+                Err(reply) => reply,
+            },
+            (Some("top"), [token, min @ ..]) if min.len() <= 1 => match Self::parse_min(min.first(), &lang) {
+                Ok(min_value) => Self::top(whales, telegram_id, token, min_value, &lang).await,
+                Err(reply) => reply,
+            },
+            (Some("remove"), [address]) => {
+                if whales.remove(telegram_id, address).await {
+                    t_args(&lang, "whales.removed", &[("address", address)])
+                } else {
+                    t_args(&lang, "whales.not_watching", &[("address", address)])
+                }
+            }
+            _ => t(&lang, "whales.usage"),
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        Ok(())
+    }
+
+    async fn list(whales: &WhaleWatcher, user_id: i64, lang: &str) -> String {
+        let watched = whales.list(user_id).await;
+        if watched.is_empty() {
+            return t(lang, "whales.none");
+        }
+
+        let lines: Vec<String> = watched.iter()
+            .map(|(address, min_value)| t_args(lang, "whales.entry", &[
+                ("address", address),
+                ("min", &min_value.to_string()),
+            ]))
+            .collect();
+        t_args(lang, "whales.list", &[("whales", &lines.join("\n\n"))])
+    }
+
+    async fn add(whales: &WhaleWatcher, user_id: i64, address: &str, min_value: Option<Decimal>, lang: &str) -> String {
+        match whales.add(user_id, address, min_value).await {
+            Ok(min_value) => t_args(lang, "whales.watching", &[
+                ("address", address),
+                ("min", &min_value.to_string()),
+            ]),
+            Err(e) => format!("❌ {}", e.user_message()),
+        }
+    }
+
+    async fn top(whales: &WhaleWatcher, user_id: i64, token: &str, min_value: Option<Decimal>, lang: &str) -> String {
+        let Ok(mint) = TokenResolver::resolve(token) else {
+            return t_args(lang, "whales.unknown_token", &[("token", token)]);
+        };
+        match whales.add_top_holders(user_id, &mint, min_value).await {
+            Ok(holders) if holders.is_empty() => t_args(lang, "whales.no_holders", &[("token", token)]),
+            Ok(holders) => {
+                info!("🐋 User {} watching {} top holders of {}", user_id, holders.len(), mint);
+                t_args(lang, "whales.watching_top", &[
+                    ("count", &holders.len().to_string()),
+                    ("token", &token.to_uppercase()),
+                    ("holders", &holders.join("\n")),
+                ])
+            }
+            Err(e) => {
+                error!("Failed to find top holders of {}: {}", mint, e);
+                t_args(lang, "whales.lookup_failed", &[("token", token), ("error", &e.to_string())])
+            }
+        }
+    }
+
+    /// Optional USD threshold; `$` and `,` are allowed
+    fn parse_min(arg: Option<&&str>, lang: &str) -> Result<Option<Decimal>, String> {
+        let Some(arg) = arg else { return Ok(None) };
+        let cleaned: String = arg.chars().filter(|c| *c != '$' && *c != ',').collect();
+        match Decimal::from_str(&cleaned) {
+            Ok(min) if min > Decimal::ZERO => Ok(Some(min)),
+            _ => Err(t_args(lang, "whales.invalid_min", &[("amount", arg), ("usage", &t(lang, "whales.usage"))])),
+        }
+    }
+}
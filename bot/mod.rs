@@ -1,7 +1,27 @@
 mod telegram;
 mod commands;
 mod wallet_setup;
+mod services;
+pub mod admin;
+pub mod aliases;
+pub mod automation_auth;
+pub mod callback_action;
+pub mod chart_actions;
+pub mod convex_migration;
+pub mod convex_webhook;
+pub mod data_deletion;
+pub mod distribution;
+pub mod group_buy;
 pub mod handlers;
+pub mod preferences;
+pub mod price_entry;
+pub mod settings_export;
+pub mod token_launch;
+pub mod trending;
+pub mod wallet_transfer;
+pub mod watchlist;
 
+pub use commands::Command;
 pub use telegram::TelegramBot;
-pub use wallet_setup::{WalletSetupFlow, TransactionSigner};
\ No newline at end of file
+pub use services::BotServices;
+pub use wallet_setup::{SetupStep, WalletSetupFlow, TransactionSigner};
\ No newline at end of file
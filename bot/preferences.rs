@@ -0,0 +1,581 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use teloxide::types::User;
+use tokio::sync::{Mutex, RwLock};
+use tracing::warn;
+
+use crate::analytics::{CostBasisMethod, CostBasisPreferences};
+use crate::constants::{DEFAULT_SLIPPAGE_BPS, MAX_TRADE_SOL, MIN_TRADE_SOL};
+use crate::db::Database;
+use crate::errors::{BotError, Result};
+use crate::trading::{
+    ExitDenomination, ExitPreferences, MevPreferences, TradeDefaultPreferences, TradeDefaults, TradeRulePreferences, TradeRules,
+    TraderVisibility, VisibilityPreferences,
+};
+use crate::utils::{catalog, i18n::lang_of, validation::Validator, Catalog};
+use crate::wallet::TransactionPriority;
+
+/// Slippage a user may choose as their default: 0.1% to 10%
+pub const MIN_SLIPPAGE_SETTING_BPS: u16 = 10;
+pub const MAX_SLIPPAGE_SETTING_BPS: u16 = 1_000;
+
+/// Shortest session timeout a user may choose
+pub const MIN_SESSION_TIMEOUT_MINUTES: u32 = 5;
+
+/// Highest LARP safety score a rule may require
+pub const MAX_LARP_SCORE: u8 = 100;
+
+/// Length of a public display name, in characters
+pub const MIN_DISPLAY_NAME_LEN: usize = 3;
+pub const MAX_DISPLAY_NAME_LEN: usize = 20;
+
+/// Format written by this build; older stored settings are upgraded on load
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// How much risk a user is comfortable with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskProfile {
+    Conservative,
+    Moderate,
+    Aggressive,
+}
+
+/// Which notifications a user wants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub trades: bool,
+    pub alerts: bool,
+    pub daily_summary: bool,
+    /// Operator announcements sent with /admin broadcast
+    #[serde(default = "default_announcements")]
+    pub announcements: bool,
+}
+
+/// A user's trading defaults and bot settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserSettings {
+    pub slippage_bps: u16,
+    /// Largest single position in SOL; unlimited when unset
+    pub max_position_sol: Option<Decimal>,
+    pub auto_compound: bool,
+    pub risk_profile: RiskProfile,
+    pub notifications: NotificationPreferences,
+    /// Time large sells within a short window instead of selling immediately
+    #[serde(default)]
+    pub smart_sell: bool,
+    /// What stop-losses, take-profits and /panic sell into
+    #[serde(default)]
+    pub exit_denomination: ExitDenomination,
+    /// How sells realize PnL against the user's lots
+    #[serde(default)]
+    pub cost_basis_method: CostBasisMethod,
+    /// Send device-signed trades as tipped Jito bundles
+    #[serde(default)]
+    pub mev_protection: bool,
+    /// Largest single buy in SOL
+    #[serde(default = "default_max_trade_sol")]
+    pub max_trade_sol: f64,
+    /// Recent-fee percentile trades are priced at
+    #[serde(default = "default_priority_fee")]
+    pub priority_fee: TransactionPriority,
+    /// Offer AI analysis alongside trades and token lookups
+    #[serde(default = "default_ai_analysis")]
+    pub ai_analysis: bool,
+    /// Idle minutes before a signing session expires
+    #[serde(default = "default_session_timeout")]
+    pub session_timeout_minutes: u32,
+    /// Listed on the public leaderboards
+    #[serde(default)]
+    pub leaderboard_visible: bool,
+    /// Offered to other users as a copy-trading master
+    #[serde(default)]
+    pub copyable: bool,
+    /// Name shown publicly instead of an anonymous handle
+    #[serde(default)]
+    pub display_name_override: Option<String>,
+    /// Tokens this user refuses to buy, whatever places the buy
+    #[serde(default)]
+    pub trade_rules: TradeRules,
+    /// Language the bot answers in; the Telegram app's when unset
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Format the settings were stored in; zero before versioning
+    #[serde(default)]
+    pub version: u32,
+}
+
+fn default_max_trade_sol() -> f64 {
+    MAX_TRADE_SOL
+}
+
+fn default_priority_fee() -> TransactionPriority {
+    TransactionPriority::High
+}
+
+fn default_ai_analysis() -> bool {
+    true
+}
+
+fn default_announcements() -> bool {
+    true
+}
+
+fn default_session_timeout() -> u32 {
+    30
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            slippage_bps: DEFAULT_SLIPPAGE_BPS,
+            max_position_sol: None,
+            auto_compound: false,
+            risk_profile: RiskProfile::Moderate,
+            notifications: NotificationPreferences {
+                trades: true,
+                alerts: true,
+                daily_summary: false,
+                announcements: default_announcements(),
+            },
+            smart_sell: false,
+            exit_denomination: ExitDenomination::Sol,
+            cost_basis_method: CostBasisMethod::Fifo,
+            mev_protection: false,
+            max_trade_sol: default_max_trade_sol(),
+            priority_fee: default_priority_fee(),
+            ai_analysis: default_ai_analysis(),
+            session_timeout_minutes: default_session_timeout(),
+            leaderboard_visible: false,
+            copyable: false,
+            display_name_override: None,
+            trade_rules: TradeRules::default(),
+            language: None,
+            version: SETTINGS_VERSION,
+        }
+    }
+}
+
+impl UserSettings {
+    pub fn trade_defaults(&self) -> TradeDefaults {
+        TradeDefaults {
+            slippage_bps: self.slippage_bps,
+            priority: self.priority_fee,
+        }
+    }
+
+    /// What the leaderboard and copy trading may show of this user
+    pub fn visibility(&self) -> TraderVisibility {
+        TraderVisibility {
+            leaderboard_visible: self.leaderboard_visible,
+            copyable: self.copyable,
+            display_name: self.display_name_override.clone(),
+        }
+    }
+
+    /// Upgrade settings stored by an older build; true when anything changed
+    pub fn migrate(&mut self) -> bool {
+        if self.version >= SETTINGS_VERSION {
+            return false;
+        }
+        // v1: leaderboard listing and copy trading became opt-in, so nobody
+        // stored before then is shown until they choose to be
+        if self.version < 1 {
+            self.leaderboard_visible = false;
+            self.copyable = false;
+            self.display_name_override = None;
+        }
+        self.version = SETTINGS_VERSION;
+        true
+    }
+
+    /// Set or clear the public display name
+    ///
+    /// Names are 3-20 letters, digits, spaces, `_` or `-`, and can't pose as
+    /// the anonymous `Trader#1234` handles.
+    pub fn set_display_name(&mut self, name: Option<&str>) -> Result<()> {
+        let Some(name) = name.map(str::trim) else {
+            self.display_name_override = None;
+            return Ok(());
+        };
+        let len = name.chars().count();
+        if !(MIN_DISPLAY_NAME_LEN..=MAX_DISPLAY_NAME_LEN).contains(&len) {
+            return Err(BotError::validation(format!(
+                "Display name must be {} to {} characters", MIN_DISPLAY_NAME_LEN, MAX_DISPLAY_NAME_LEN
+            )).into());
+        }
+        if !name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-')) {
+            return Err(BotError::validation("Display name may only use letters, digits, spaces, _ and -").into());
+        }
+        if name.to_lowercase().starts_with("trader") && name.chars().any(|c| c.is_ascii_digit()) {
+            return Err(BotError::validation("Display name can't look like an anonymous trader handle").into());
+        }
+        self.display_name_override = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Set the bot language, or clear it to follow the Telegram app's
+    pub fn set_language(&mut self, lang: Option<&str>) -> Result<()> {
+        let Some(lang) = lang.map(str::trim) else {
+            self.language = None;
+            return Ok(());
+        };
+        if !Catalog::embedded().supports(lang) {
+            return Err(BotError::validation(format!("Unsupported language: {}", lang)).into());
+        }
+        self.language = Some(catalog::normalize(lang));
+        Ok(())
+    }
+
+    /// Refuse every future buy of `mint`
+    pub fn deny_mint(&mut self, mint: &str) -> Result<()> {
+        if !self.trade_rules.deny(mint)? {
+            return Err(BotError::validation(format!("{} is already on your deny-list", mint)).into());
+        }
+        Ok(())
+    }
+
+    /// Take `mint` off the deny-list
+    pub fn allow_mint(&mut self, mint: &str) -> Result<()> {
+        if !self.trade_rules.allow(mint) {
+            return Err(BotError::validation(format!("{} is not on your deny-list", mint)).into());
+        }
+        Ok(())
+    }
+
+    /// Apply one edit from the settings editor, rejecting out-of-range values
+    pub fn apply(&mut self, change: SettingChange) -> Result<()> {
+        match change {
+            SettingChange::MaxTradeSol(sol) => {
+                if !(MIN_TRADE_SOL..=MAX_TRADE_SOL).contains(&sol) {
+                    return Err(BotError::validation(format!(
+                        "Max trade size must be between {} and {} SOL", MIN_TRADE_SOL, MAX_TRADE_SOL
+                    )).into());
+                }
+                self.max_trade_sol = sol;
+            }
+            SettingChange::SlippageBps(bps) => {
+                if !(MIN_SLIPPAGE_SETTING_BPS..=MAX_SLIPPAGE_SETTING_BPS).contains(&bps) {
+                    return Err(BotError::validation(format!(
+                        "Slippage must be between {}% and {}%",
+                        MIN_SLIPPAGE_SETTING_BPS as f64 / 100.0,
+                        MAX_SLIPPAGE_SETTING_BPS as f64 / 100.0
+                    )).into());
+                }
+                self.slippage_bps = bps;
+            }
+            SettingChange::PriorityFee(priority) => self.priority_fee = priority,
+            SettingChange::ToggleMevProtection => self.mev_protection = !self.mev_protection,
+            SettingChange::ToggleAiAnalysis => self.ai_analysis = !self.ai_analysis,
+            SettingChange::ToggleNotification(kind) => {
+                let flag = match kind {
+                    NotificationKind::Trades => &mut self.notifications.trades,
+                    NotificationKind::Alerts => &mut self.notifications.alerts,
+                    NotificationKind::DailySummary => &mut self.notifications.daily_summary,
+                    NotificationKind::Announcements => &mut self.notifications.announcements,
+                };
+                *flag = !*flag;
+            }
+            SettingChange::SessionTimeout(minutes) => {
+                if minutes < MIN_SESSION_TIMEOUT_MINUTES {
+                    return Err(BotError::validation(format!(
+                        "Session timeout must be at least {} minutes", MIN_SESSION_TIMEOUT_MINUTES
+                    )).into());
+                }
+                Validator::validate_session_duration(minutes as i64)?;
+                self.session_timeout_minutes = minutes;
+            }
+            SettingChange::MinLiquidityUsd(usd) => {
+                if usd.is_some_and(|usd| !usd.is_finite() || usd <= 0.0) {
+                    return Err(BotError::validation("Minimum liquidity must be above $0").into());
+                }
+                self.trade_rules.min_liquidity_usd = usd;
+            }
+            SettingChange::MaxTaxPercent(percent) => {
+                if percent.is_some_and(|percent| !(0.0..=100.0).contains(&percent)) {
+                    return Err(BotError::validation("Maximum tax must be between 0% and 100%").into());
+                }
+                self.trade_rules.max_tax_percent = percent;
+            }
+            SettingChange::MinLarpScore(score) => {
+                if score.is_some_and(|score| score > MAX_LARP_SCORE) {
+                    return Err(BotError::validation(format!("LARP score must be at most {}", MAX_LARP_SCORE)).into());
+                }
+                self.trade_rules.min_larp_score = score;
+            }
+            SettingChange::ClearDeniedMints => self.trade_rules.denied_mints.clear(),
+            SettingChange::ToggleLeaderboardVisible => self.leaderboard_visible = !self.leaderboard_visible,
+            SettingChange::ToggleCopyable => self.copyable = !self.copyable,
+            SettingChange::ClearDisplayName => self.display_name_override = None,
+            // The language isn't a trading setting, so a reset keeps it
+            SettingChange::Reset => *self = Self { language: self.language.take(), ..Self::default() },
+        }
+        Ok(())
+    }
+}
+
+/// A notification a user can switch on or off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Trades,
+    Alerts,
+    DailySummary,
+    Announcements,
+}
+
+/// One edit made from the /settings editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingChange {
+    MaxTradeSol(f64),
+    SlippageBps(u16),
+    PriorityFee(TransactionPriority),
+    ToggleMevProtection,
+    ToggleAiAnalysis,
+    ToggleNotification(NotificationKind),
+    SessionTimeout(u32),
+    /// `None` turns the rule off
+    MinLiquidityUsd(Option<f64>),
+    MaxTaxPercent(Option<f64>),
+    MinLarpScore(Option<u8>),
+    ClearDeniedMints,
+    ToggleLeaderboardVisible,
+    ToggleCopyable,
+    ClearDisplayName,
+    Reset,
+}
+
+impl SettingChange {
+    /// Parse `setting:<name>[:<value>]` callback data; values are checked by `UserSettings::apply`
+    pub fn parse(data: &str) -> Option<Self> {
+        let mut parts = data.strip_prefix("setting:")?.splitn(2, ':');
+        let name = parts.next()?;
+        let value = parts.next();
+        let change = match (name, value) {
+            ("max", Some(sol)) => Self::MaxTradeSol(sol.parse().ok().filter(|sol: &f64| sol.is_finite())?),
+            ("slip", Some(bps)) => Self::SlippageBps(bps.parse().ok()?),
+            ("prio", Some(level)) => Self::PriorityFee(match level {
+                "low" => TransactionPriority::Low,
+                "normal" => TransactionPriority::Normal,
+                "high" => TransactionPriority::High,
+                "critical" => TransactionPriority::Critical,
+                _ => return None,
+            }),
+            ("mev", None) => Self::ToggleMevProtection,
+            ("ai", None) => Self::ToggleAiAnalysis,
+            ("notify", Some(kind)) => Self::ToggleNotification(match kind {
+                "trades" => NotificationKind::Trades,
+                "alerts" => NotificationKind::Alerts,
+                "daily" => NotificationKind::DailySummary,
+                "announce" => NotificationKind::Announcements,
+                _ => return None,
+            }),
+            ("timeout", Some(minutes)) => Self::SessionTimeout(minutes.parse().ok()?),
+            ("liq", Some(usd)) => Self::MinLiquidityUsd(Self::parse_rule(usd)?),
+            ("tax", Some(percent)) => Self::MaxTaxPercent(Self::parse_rule(percent)?),
+            ("larp", Some(score)) => Self::MinLarpScore(Self::parse_rule(score)?),
+            ("denyclear", None) => Self::ClearDeniedMints,
+            ("board", None) => Self::ToggleLeaderboardVisible,
+            ("copyable", None) => Self::ToggleCopyable,
+            ("clearname", None) => Self::ClearDisplayName,
+            ("reset", None) => Self::Reset,
+            _ => return None,
+        };
+        Some(change)
+    }
+
+    /// A token rule's value, or `off`
+    fn parse_rule<T: std::str::FromStr>(value: &str) -> Option<Option<T>> {
+        if value == "off" {
+            return Some(None);
+        }
+        value.parse().ok().map(Some)
+    }
+
+    /// Changes that alter what others can see of the user
+    pub fn affects_visibility(&self) -> bool {
+        matches!(
+            self,
+            Self::ToggleLeaderboardVisible | Self::ToggleCopyable | Self::ClearDisplayName | Self::Reset
+        )
+    }
+}
+
+/// Where settings outlive the process
+#[async_trait::async_trait]
+pub trait SettingsStorage: Send + Sync {
+    async fn load(&self, user_id: i64) -> Result<Option<UserSettings>>;
+    async fn save(&self, user_id: i64, settings: &UserSettings) -> Result<()>;
+    async fn delete(&self, user_id: i64) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl SettingsStorage for Database {
+    async fn load(&self, user_id: i64) -> Result<Option<UserSettings>> {
+        let Some(data) = self.get_user_settings(user_id).await? else { return Ok(None) };
+        let mut settings: UserSettings = serde_json::from_str(&data)
+            .map_err(|e| BotError::parsing(format!("Failed to parse settings for {}: {}", user_id, e)))?;
+        if settings.migrate() {
+            self.save(user_id, &settings).await?;
+        }
+        Ok(Some(settings))
+    }
+
+    async fn save(&self, user_id: i64, settings: &UserSettings) -> Result<()> {
+        let data = serde_json::to_string(settings)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize settings for {}: {}", user_id, e)))?;
+        self.upsert_user_settings(user_id, &data).await
+    }
+
+    async fn delete(&self, user_id: i64) -> Result<()> {
+        self.delete_user_settings(user_id).await
+    }
+}
+
+/// Per-user settings, cached in memory and written through to storage
+#[derive(Default)]
+pub struct PreferenceStore {
+    preferences: RwLock<HashMap<i64, UserSettings>>,
+    storage: Option<Arc<dyn SettingsStorage>>,
+    /// Serializes read-modify-write per user so racing edits don't drop each other
+    edit_locks: Mutex<HashMap<i64, Arc<Mutex<()>>>>,
+}
+
+impl PreferenceStore {
+    pub fn with_storage(mut self, storage: Arc<dyn SettingsStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// A user's settings, defaults when never set or unreadable
+    pub async fn get(&self, user_id: i64) -> UserSettings {
+        match self.load(user_id).await {
+            Ok(settings) => settings.unwrap_or_default(),
+            Err(e) => {
+                warn!("⚙️ Settings for {} unavailable, using defaults: {}", user_id, e);
+                UserSettings::default()
+            }
+        }
+    }
+
+    /// Language to answer a user in: the one they chose, else their Telegram app's
+    pub async fn language(&self, user_id: i64, user: Option<&User>) -> String {
+        self.get(user_id).await.language.unwrap_or_else(|| lang_of(user).to_string())
+    }
+
+    /// Change a user's settings in place and persist the result
+    ///
+    /// Edits for the same user run one at a time, each starting from the
+    /// previous one's result. Nothing is stored when `edit` fails.
+    pub async fn update<F>(&self, user_id: i64, edit: F) -> Result<UserSettings>
+    where
+        F: FnOnce(&mut UserSettings) -> Result<()> + Send,
+    {
+        let lock = self.edit_lock(user_id).await;
+        let _guard = lock.lock().await;
+
+        let mut settings = self.load(user_id).await?.unwrap_or_default();
+        edit(&mut settings)?;
+        if let Some(storage) = &self.storage {
+            storage.save(user_id, &settings).await?;
+        }
+        self.preferences.write().await.insert(user_id, settings.clone());
+        Ok(settings)
+    }
+
+    /// Apply one editor change; see `UserSettings::apply`
+    pub async fn apply(&self, user_id: i64, change: SettingChange) -> Result<UserSettings> {
+        self.update(user_id, |settings| settings.apply(change)).await
+    }
+
+    /// Drop a user's settings; true when any were set
+    pub async fn remove(&self, user_id: i64) -> bool {
+        let lock = self.edit_lock(user_id).await;
+        let _guard = lock.lock().await;
+
+        let existed = matches!(self.load(user_id).await, Ok(Some(_)));
+        self.preferences.write().await.remove(&user_id);
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.delete(user_id).await {
+                warn!("⚙️ Failed to delete stored settings for {}: {}", user_id, e);
+            }
+        }
+        existed
+    }
+
+    /// Replace a user's settings; true when none were set before
+    pub async fn set(&self, user_id: i64, preferences: UserSettings) -> bool {
+        let lock = self.edit_lock(user_id).await;
+        let _guard = lock.lock().await;
+
+        let existed = matches!(self.load(user_id).await, Ok(Some(_)));
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.save(user_id, &preferences).await {
+                warn!("⚙️ Failed to store settings for {}: {}", user_id, e);
+            }
+        }
+        self.preferences.write().await.insert(user_id, preferences);
+        !existed
+    }
+
+    /// Cached settings, or whatever storage holds
+    async fn load(&self, user_id: i64) -> Result<Option<UserSettings>> {
+        if let Some(settings) = self.preferences.read().await.get(&user_id) {
+            return Ok(Some(settings.clone()));
+        }
+        let Some(storage) = &self.storage else { return Ok(None) };
+
+        let stored = storage.load(user_id).await?;
+        if let Some(settings) = &stored {
+            self.preferences.write().await.entry(user_id).or_insert_with(|| settings.clone());
+        }
+        Ok(stored)
+    }
+
+    async fn edit_lock(&self, user_id: i64) -> Arc<Mutex<()>> {
+        self.edit_locks.lock().await.entry(user_id).or_default().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl ExitPreferences for PreferenceStore {
+    async fn exit_denomination(&self, user_id: i64) -> ExitDenomination {
+        self.get(user_id).await.exit_denomination
+    }
+}
+
+#[async_trait::async_trait]
+impl CostBasisPreferences for PreferenceStore {
+    async fn cost_basis_method(&self, user_id: i64) -> CostBasisMethod {
+        self.get(user_id).await.cost_basis_method
+    }
+}
+
+#[async_trait::async_trait]
+impl MevPreferences for PreferenceStore {
+    async fn mev_protection(&self, user_id: i64) -> bool {
+        self.get(user_id).await.mev_protection
+    }
+}
+
+#[async_trait::async_trait]
+impl TradeDefaultPreferences for PreferenceStore {
+    async fn trade_defaults(&self, user_id: i64) -> TradeDefaults {
+        self.get(user_id).await.trade_defaults()
+    }
+}
+
+#[async_trait::async_trait]
+impl VisibilityPreferences for PreferenceStore {
+    async fn visibility(&self, user_id: i64) -> TraderVisibility {
+        self.get(user_id).await.visibility()
+    }
+}
+
+#[async_trait::async_trait]
+impl TradeRulePreferences for PreferenceStore {
+    async fn trade_rules(&self, user_id: i64) -> TradeRules {
+        self.get(user_id).await.trade_rules
+    }
+}
@@ -0,0 +1,238 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use crate::{
+    bot::chart_actions::{AlertDirection, ChartActionKind, ChartActionRequest, ChartActions},
+    errors::{BotError, Result},
+    trading::{Order, ProtectiveClass},
+    utils::price_input::{normalize, ParsedPrice, PriceCandidate, PriceInput, PriceIntent},
+};
+
+/// How long a clarification question stays answerable
+const DEFAULT_ENTRY_TIMEOUT_SECS: i64 = 120;
+
+/// What the entered prices become
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceEntryTarget {
+    Alert { direction: Option<AlertDirection> },
+    /// Stop-loss on a held position (token units)
+    Stop { amount: f64 },
+    /// Linked stop-loss and take-profit on a held position (token units)
+    Bracket { amount: f64 },
+    /// Stop-loss or take-profit from /order (token units)
+    Order { class: ProtectiveClass, amount: f64 },
+    /// New trigger price for an open stop-loss or take-profit
+    Reprice { order_id: String, class: ProtectiveClass },
+}
+
+/// One typed price and how it fires
+#[derive(Debug, Clone)]
+pub struct PriceLeg {
+    pub label: &'static str,
+    pub parsed: ParsedPrice,
+    pub intent: PriceIntent,
+    /// The user said firing on creation is intended
+    pub confirmed_immediate: bool,
+}
+
+impl PriceLeg {
+    pub fn new(label: &'static str, parsed: ParsedPrice, intent: PriceIntent) -> Self {
+        Self { label, parsed, intent, confirmed_immediate: false }
+    }
+}
+
+/// Prices typed for an alert, stop or bracket, before anything is created
+#[derive(Debug, Clone)]
+pub struct PriceEntry {
+    pub user_id: i64,
+    pub chat_id: i64,
+    pub mint: String,
+    pub symbol: String,
+    pub current_price: f64,
+    pub target: PriceEntryTarget,
+    pub legs: Vec<PriceLeg>,
+}
+
+/// Where an entry stands after normalization
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryResolution {
+    /// Final price for each leg
+    Ready(Vec<f64>),
+    /// Leg `leg` needs the user's answer first
+    Question { leg: usize, input: PriceInput },
+    Invalid(String),
+}
+
+/// The user's answer to a clarification question
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriceAnswer {
+    Pick(f64),
+    ConfirmImmediate,
+}
+
+/// A question waiting for a button press
+#[derive(Debug, Clone)]
+pub struct PendingPriceEntry {
+    pub entry: PriceEntry,
+    pub leg: usize,
+    pub candidates: Vec<PriceCandidate>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl PriceEntry {
+    /// How an alert fires for the direction the user gave
+    pub fn alert_intent(direction: Option<AlertDirection>) -> PriceIntent {
+        match direction {
+            Some(AlertDirection::Above) => PriceIntent::Above,
+            Some(AlertDirection::Below) => PriceIntent::Below,
+            None => PriceIntent::Either,
+        }
+    }
+
+    /// How a stop-loss or take-profit price fires
+    pub fn order_intent(class: ProtectiveClass) -> PriceIntent {
+        match class {
+            ProtectiveClass::Stop => PriceIntent::Below,
+            ProtectiveClass::TakeProfit => PriceIntent::Above,
+        }
+    }
+
+    /// Run every leg through the shared normalization, stopping at the first question
+    pub fn resolve(&self) -> EntryResolution {
+        let mut prices = Vec::with_capacity(self.legs.len());
+        for (i, leg) in self.legs.iter().enumerate() {
+            match normalize(leg.parsed, self.current_price, leg.intent) {
+                PriceInput::Accepted(price) => prices.push(price),
+                PriceInput::TriggersImmediately { price, .. } if leg.confirmed_immediate => prices.push(price),
+                PriceInput::Invalid(reason) => return EntryResolution::Invalid(reason),
+                input => return EntryResolution::Question { leg: i, input },
+            }
+        }
+
+        if let (PriceEntryTarget::Bracket { .. }, [stop, target]) = (&self.target, prices.as_slice()) {
+            if target <= stop {
+                return EntryResolution::Invalid(format!(
+                    "Take-profit ${} must be above the stop ${}",
+                    ChartActions::format_price(*target),
+                    ChartActions::format_price(*stop)
+                ));
+            }
+        }
+        EntryResolution::Ready(prices)
+    }
+
+    /// Apply an answer to leg `leg`; the leg is checked again on the next resolve
+    pub fn answer(&mut self, leg: usize, answer: PriceAnswer) {
+        let Some(leg) = self.legs.get_mut(leg) else { return };
+        match answer {
+            PriceAnswer::Pick(price) => leg.parsed = ParsedPrice::explicit(price),
+            PriceAnswer::ConfirmImmediate => leg.confirmed_immediate = true,
+        }
+    }
+
+    /// Alert or stop request for a single-leg entry
+    pub fn chart_request(&self, price: f64) -> Option<ChartActionRequest> {
+        let (kind, direction, amount) = match &self.target {
+            PriceEntryTarget::Alert { direction } => (
+                ChartActionKind::Alert,
+                ChartActions::resolve_direction(*direction, price, self.current_price),
+                0.0,
+            ),
+            PriceEntryTarget::Stop { amount } => (ChartActionKind::Stop, AlertDirection::Below, *amount),
+            PriceEntryTarget::Bracket { .. }
+            | PriceEntryTarget::Order { .. }
+            | PriceEntryTarget::Reprice { .. } => return None,
+        };
+        Some(ChartActionRequest {
+            kind,
+            user_id: self.user_id,
+            chat_id: self.chat_id,
+            mint: self.mint.clone(),
+            symbol: self.symbol.clone(),
+            target_price: price,
+            direction,
+            amount,
+        })
+    }
+
+    /// Stop-loss or take-profit for an /order entry
+    pub fn protective_order(&self, price: f64) -> Result<Order> {
+        let PriceEntryTarget::Order { class, amount } = self.target else {
+            return Err(BotError::validation("Not an order".to_string()).into());
+        };
+        let amount = Decimal::from_f64_retain(amount)
+            .filter(|amount| *amount > Decimal::ZERO)
+            .ok_or_else(|| BotError::validation("Amount must be positive".to_string()))?;
+        let price = Decimal::from_f64_retain(price)
+            .ok_or_else(|| BotError::validation("Invalid trigger price".to_string()))?;
+
+        Ok(match class {
+            ProtectiveClass::Stop => Order::create_stop_loss(self.user_id, self.mint.clone(), price, amount),
+            ProtectiveClass::TakeProfit => Order::create_take_profit(self.user_id, self.mint.clone(), price, amount),
+        })
+    }
+
+    /// Stop-loss and take-profit legs that share a bracket id
+    pub fn bracket_orders(&self, stop: f64, target: f64) -> Result<(Order, Order)> {
+        let PriceEntryTarget::Bracket { amount } = self.target else {
+            return Err(BotError::validation("Not a bracket".to_string()).into());
+        };
+        let amount = Decimal::from_f64_retain(amount)
+            .filter(|amount| *amount > Decimal::ZERO)
+            .ok_or_else(|| BotError::validation("No position size to protect".to_string()))?;
+        let stop = Decimal::from_f64_retain(stop)
+            .ok_or_else(|| BotError::validation("Invalid stop price".to_string()))?;
+        let target = Decimal::from_f64_retain(target)
+            .ok_or_else(|| BotError::validation("Invalid take-profit price".to_string()))?;
+
+        let bracket_id = format!("bracket-{}", uuid::Uuid::new_v4());
+        let mut stop_leg = Order::create_stop_loss(self.user_id, self.mint.clone(), stop, amount);
+        let mut target_leg = Order::create_take_profit(self.user_id, self.mint.clone(), target, amount);
+        for leg in [&mut stop_leg, &mut target_leg] {
+            leg.parent_order_id = Some(bracket_id.clone());
+            leg.metadata.position_size = Some(amount);
+        }
+        Ok((stop_leg, target_leg))
+    }
+}
+
+/// Clarification questions waiting for an answer, one per user
+pub struct PriceEntries {
+    pending: RwLock<HashMap<i64, PendingPriceEntry>>,
+    timeout: Duration,
+}
+
+impl Default for PriceEntries {
+    fn default() -> Self {
+        Self::new(Duration::seconds(DEFAULT_ENTRY_TIMEOUT_SECS))
+    }
+}
+
+impl PriceEntries {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            pending: RwLock::new(HashMap::new()),
+            timeout,
+        }
+    }
+
+    /// Park an entry until the user answers, replacing any earlier question
+    pub async fn ask(&self, entry: PriceEntry, leg: usize, candidates: Vec<PriceCandidate>) {
+        debug!("💲 Asking user {} to clarify {} price for {}", entry.user_id, entry.symbol, entry.legs[leg].label);
+        self.pending.write().await.insert(entry.user_id, PendingPriceEntry {
+            entry,
+            leg,
+            candidates,
+            started_at: Utc::now(),
+        });
+    }
+
+    /// Take the user's open question, unless it timed out
+    pub async fn take(&self, user_id: i64) -> Option<PendingPriceEntry> {
+        let pending = self.pending.write().await.remove(&user_id)?;
+        (Utc::now() - pending.started_at < self.timeout).then_some(pending)
+    }
+}
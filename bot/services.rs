@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use crate::{
+    ai::SignalOutcomeTracker,
+    alerts::{BondingTracker, PriceAlertManager, TokenCalendar, WhaleWatcher},
+    analytics::{CostBasisBook, FeeLedger, PerformanceTracker, TradeHistoryExporter, TradeImporter, TradeJournal},
+    api::{pump_fun::PumpFunClient, JupiterPriceV3Client},
+    blinks::{BlinkGenerator, BlinkTracker},
+    bot::{
+        admin::AdminDesk, aliases::AliasStore, automation_auth::AutomationAuthority, chart_actions::ChartActions, convex_migration::ConvexMigration,
+        data_deletion::DataDeletionManager, distribution::Distributions, group_buy::GroupBuyCoordinator, preferences::PreferenceStore,
+        price_entry::PriceEntries, token_launch::LaunchWizard, trending::TrendingCache, wallet_transfer::WalletTransfers,
+        watchlist::WatchlistStore,
+    },
+    cache::SessionStore,
+    middleware::UserRateLimiter,
+    monitoring::MetricsCollector,
+    trading::{ConfirmationTracker, CopyTradingManager, DCAEngine, DCAScheduler, ExecutionNotifier, LeaderboardManager, LendingDesk, LiquidityEstimator, MasterProgram, MevProtection, OrderManager, PanicDesk, PositionSync, PriorityFeeEstimator, SandwichMonitor, SmartSellTimer, TokenResolver},
+    wallet::AtaJanitor,
+};
+
+/// Feature services shared with command handlers through the dispatcher
+///
+/// Grouped into one dependency so new features don't keep widening the
+/// `handle_command` signature.
+#[derive(Clone)]
+pub struct BotServices {
+    pub token_calendar: Arc<TokenCalendar>,
+    pub bonding: Arc<BondingTracker>,
+    /// Pump.fun client behind `/pump`, shared so its circuit breaker sees every call
+    pub pump_fun: Option<Arc<PumpFunClient>>,
+    pub price_alerts: Arc<PriceAlertManager>,
+    /// `/watch` lists and their daily movers digest
+    pub watchlists: Arc<WatchlistStore>,
+    /// Whale wallets behind `/whales`, reported through the market event monitor
+    pub whales: Arc<WhaleWatcher>,
+    pub order_manager: Arc<OrderManager>,
+    pub price_client: Arc<JupiterPriceV3Client>,
+    pub chart_actions: Arc<ChartActions>,
+    pub journal: Arc<TradeJournal>,
+    pub sandwich_monitor: Arc<SandwichMonitor>,
+    /// Follows sent trades to finality and edits their result messages along the way
+    pub confirmations: Arc<ConfirmationTracker>,
+    /// Jito bundle submission and the counters behind `/mev stats`
+    pub mev_protection: Arc<MevProtection>,
+    /// Recent prioritization fee percentiles shared by orders, DCA and swaps
+    pub priority_fees: Arc<PriorityFeeEstimator>,
+    /// Pre-trade price impact from cached order books or Jupiter quotes
+    pub liquidity: Arc<LiquidityEstimator>,
+    /// Jupiter token list behind symbol and name lookups
+    pub token_resolver: Arc<TokenResolver>,
+    pub smart_sell: Arc<SmartSellTimer>,
+    pub dca_engine: Arc<DCAEngine>,
+    /// Runs DCA schedules and publishes a report after each run
+    pub dca_scheduler: Arc<DCAScheduler>,
+    pub copy_trading: Arc<CopyTradingManager>,
+    /// /master applications, copy fee ledgers and their payouts
+    pub master_program: Arc<MasterProgram>,
+    /// Fill and failure notices at each user's verbosity
+    pub execution_notices: Arc<ExecutionNotifier>,
+    pub group_buys: Arc<GroupBuyCoordinator>,
+    pub aliases: Arc<AliasStore>,
+    pub ata_janitor: Arc<AtaJanitor>,
+    pub fee_ledger: Arc<FeeLedger>,
+    pub leaderboard: Arc<LeaderboardManager>,
+    pub preferences: Arc<PreferenceStore>,
+    pub data_deletion: Arc<DataDeletionManager>,
+    /// Open lots and realized PnL, shared by trades and imports
+    pub cost_basis: Arc<CostBasisBook>,
+    /// `/import trades` uploads and the history they added
+    pub trade_imports: Arc<TradeImporter>,
+    /// `/export_trades` ledgers built from stored trades, order fills and copies
+    pub trade_history: Arc<TradeHistoryExporter>,
+    /// Realized trades behind `/stats` and the monthly digest
+    pub performance: Arc<PerformanceTracker>,
+    /// On-chain balance sync behind /portfolio's 🔄 Sync button
+    pub position_sync: Arc<PositionSync>,
+    /// `/lend` deposits and withdrawals waiting for confirmation
+    pub lending: Arc<LendingDesk>,
+    /// `/panic` sell-everything plans waiting for their two confirmations
+    pub panic_sells: Arc<PanicDesk>,
+    /// `/send` bulk distributions and the batches kept for claim tracking
+    pub distributions: Arc<Distributions>,
+    /// Scoped tokens that Convex-originated commands must carry
+    pub automation_auth: Arc<AutomationAuthority>,
+    /// Typed prices waiting for the user to clarify their scale
+    pub price_entries: Arc<PriceEntries>,
+    /// Warm /trending dataset, refreshed in the background
+    pub trending: Arc<TrendingCache>,
+    /// /signals entries and how they played out once their window closed
+    pub signal_outcomes: Arc<SignalOutcomeTracker>,
+    /// /confirm gates and passphrase prompts for wallet export and import
+    pub wallet_transfers: Arc<WalletTransfers>,
+    /// Open /launch wizards
+    pub launches: Arc<LaunchWizard>,
+    /// Per-user state that has to survive landing on another instance
+    pub sessions: Arc<dyn SessionStore>,
+    /// Maintenance mode, announcements and the counts behind `/admin stats`
+    pub admin: Arc<AdminDesk>,
+    /// Cost-weighted command buckets, checked before every command
+    pub command_limiter: Arc<UserRateLimiter>,
+    /// Present when `CONVEX_URL` is configured
+    pub convex_migration: Option<Arc<ConvexMigration>>,
+    /// Command counts for `/metrics`; served on `METRICS_PORT` when that's set
+    pub metrics: Option<Arc<MetricsCollector>>,
+    /// Blinks served as Solana Actions; present when `BLINKS_BASE_URL` is set
+    pub blinks: Option<Arc<BlinkGenerator>>,
+    /// Impressions, clicks and confirmed transactions per blink, for /myblinks
+    pub blink_tracker: Arc<BlinkTracker>,
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::{aliases::UserAliases, preferences::UserSettings, BotServices};
+
+/// A user's portable bot settings, sent by /settings export
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsExport {
+    pub telegram_id: i64,
+    pub exported_at: DateTime<Utc>,
+    pub timezone: String,
+    pub journal_prompts: bool,
+    pub aliases: UserAliases,
+    pub trading: UserSettings,
+}
+
+impl SettingsExport {
+    pub async fn collect(services: &BotServices, telegram_id: i64) -> Self {
+        Self {
+            telegram_id,
+            exported_at: Utc::now(),
+            timezone: services.dca_engine.timezones().get_user_timezone(telegram_id).await.name().to_string(),
+            journal_prompts: services.journal.prompts_enabled(telegram_id).await,
+            aliases: services.aliases.list(telegram_id).await,
+            trading: services.preferences.get(telegram_id).await,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
@@ -1,19 +1,29 @@
-use teloxide::{prelude::*, utils::command::BotCommands};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use teloxide::{prelude::*, types::Me, utils::command::BotCommands};
 use std::sync::Arc;
-use tracing::{info, error};
+use std::time::Duration;
+use tracing::{info, warn, error};
 
 use crate::{
-    trading::TradingEngineHandle,
+    trading::{BlockchainTradeMonitor, CopyTradingMonitor, MasterTradeWatchConfig, TradingEngineHandle},
     ai::GroqAnalyzer,
+    api::{ApiTier, JupiterV6Client},
+    blinks::{ActionServer, BlinkExecutor},
     db::Database,
-    utils::Config,
+    utils::{t, t_args, Catalog, Config, DEFAULT_LANG},
     wallet::WalletManager,
+    middleware::rate_limiter::RateLimitError,
+    monitoring::{DependencyChecks, HealthCheck, MetricsExporter},
+    portfolio::PortfolioFetcher,
     errors::Result,
 };
 
 use super::{
+    admin::AdminDesk,
     commands::Command,
-    handlers::{CommandHandler, TextMessageHandler, CallbackHandler},
+    convex_webhook::{EngineTrades, WalletPortfolios, WebhookDispatcher, WebhookServer},
+    services::BotServices,
+    handlers::{CommandHandler, TextMessageHandler, CallbackHandler, CalendarHandler, ChartHandler, ActivityHandler, JournalHandler, DcaHandler, GroupBuyHandler, AliasHandler, CleanupHandler, AdminHandler, BondingHandler, TradingHandler, ForgetHandler, NoticeHandler, ImportHandler, StatsHandler, AutomationsHandler, TrendingHandler, PriceEntryHandler, OrderHandler, PriceAlertHandler, WhaleHandler, BlinksHandler, LaunchHandler, LendingHandler, MasterHandler, SendHandler, LanguageHandler, WatchlistHandler},
 };
 
 /// Main Telegram bot struct
@@ -23,6 +33,7 @@ pub struct TelegramBot {
     ai_analyzer: Arc<GroqAnalyzer>,
     db: Arc<Database>,
     wallet_manager: Arc<WalletManager>,
+    services: Arc<BotServices>,
 }
 
 impl TelegramBot {
@@ -33,6 +44,7 @@ impl TelegramBot {
         ai_analyzer: Arc<GroqAnalyzer>,
         db: Arc<Database>,
         wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
     ) -> Self {
         Self {
             config,
@@ -40,18 +52,126 @@ impl TelegramBot {
             ai_analyzer,
             db,
             wallet_manager,
+            services,
         }
     }
     
     /// Run the bot dispatcher
     pub async fn run(&self) -> Result<()> {
-        let bot = Bot::new(&self.config.telegram_bot_token);
+        let mut bot = Bot::new(&self.config.telegram_bot_token);
+        if let Some(api_url) = &self.config.telegram_api_url {
+            match reqwest::Url::parse(api_url) {
+                Ok(url) => bot = bot.set_api_url(url),
+                Err(e) => warn!("Ignoring invalid TELEGRAM_API_URL {}: {}", api_url, e),
+            }
+        }
         
         info!("🤖 Starting Telegram bot...");
         
+        // Parse the locale catalogs now rather than on the first message
+        let catalog = Catalog::embedded();
+        info!("🌐 Loaded {} languages", catalog.languages().len());
+        for (lang, keys) in catalog.missing_keys() {
+            warn!("🌐 {} catalog lacks {} keys, falling back to {}: {}", lang, keys.len(), DEFAULT_LANG, keys.join(", "));
+        }
+        
+        // Before taking commands, so trades stay paused through a restart
+        match self.services.admin.restore().await {
+            Ok(Some(broadcast)) => {
+                info!("📣 Resuming broadcast {} at {}/{}", broadcast.id, broadcast.cursor, broadcast.recipients.len());
+                AdminHandler::spawn_broadcast(bot.clone(), self.services.clone());
+            }
+            Ok(None) => {}
+            Err(e) => warn!("🛠️ Failed to restore admin state: {}", e),
+        }
+        match self.services.watchlists.restore().await {
+            Ok(count) => info!("👀 Restored {} watchlists", count),
+            Err(e) => warn!("👀 Failed to restore watchlists: {}", e),
+        }
+        
+        CalendarHandler::spawn_notification_forwarder(bot.clone(), self.services.token_calendar.clone());
+        BondingHandler::spawn_notification_forwarder(bot.clone(), self.services.bonding.clone());
+        NoticeHandler::spawn_notification_forwarder(bot.clone(), self.services.execution_notices.clone());
+        DcaHandler::spawn_report_forwarder(bot.clone(), self.services.dca_scheduler.clone());
+        PriceAlertHandler::spawn_notification_forwarder(bot.clone(), self.services.price_alerts.clone());
+        self.services.execution_notices.start().await;
+        self.services.dca_scheduler.start().await?;
+        self.services.price_alerts.start_monitoring().await?;
+        self.services.whales.start().await;
+        self.services.priority_fees.start().await;
+        self.services.token_resolver.start().await;
+        if let Some(watcher) = self.wallet_manager.activity_watch() {
+            ActivityHandler::spawn_alert_forwarder(bot.clone(), watcher.clone(), self.services.preferences.clone());
+        }
+        JournalHandler::spawn_prompt_forwarder(bot.clone(), self.services.clone());
+        CleanupHandler::spawn_weekly_auto_cleanup(bot.clone(), self.services.clone(), self.wallet_manager.clone());
+        ForgetHandler::spawn_erasure_worker(bot.clone(), self.services.clone(), self.wallet_manager.clone(), self.db.clone());
+        StatsHandler::spawn_monthly_digest(bot.clone(), self.services.clone());
+        WatchlistHandler::spawn_daily_digest(self.services.clone());
+        AutomationsHandler::spawn_violation_forwarder(bot.clone(), self.services.automation_auth.clone());
+        self.services.trending.spawn_refresher();
+        self.services.signal_outcomes.spawn_evaluator();
+        self.services.position_sync.spawn_periodic();
+        self.services.master_program.spawn_payouts();
+        // Shares the manager /copy writes to, so new follows are picked up on the next refresh
+        let copy_monitor = CopyTradingMonitor::new(self.services.copy_trading.clone())
+            .with_trade_monitor(Arc::new(BlockchainTradeMonitor::new(
+                Arc::new(RpcClient::new(self.config.get_rpc_url())),
+                MasterTradeWatchConfig::default(),
+            )));
+        Arc::new(copy_monitor).start().await;
+        self.services.blink_tracker.spawn_maintenance();
+        if let (Some(metrics), Some(port)) = (&self.services.metrics, self.config.metrics_port) {
+            let health_check = Arc::new(HealthCheck::new(env!("CARGO_PKG_VERSION").to_string()));
+            DependencyChecks::from_config(&self.config)
+                .with_database(self.db.clone())
+                .register(&health_check)
+                .await;
+            let exporter = MetricsExporter::new(metrics.clone()).with_health_check(health_check);
+            tokio::spawn(async move {
+                if let Err(e) = exporter.start(port).await {
+                    error!("📊 Metrics exporter stopped: {}", e);
+                }
+            });
+        }
+        if let (Some(generator), Some(port)) = (&self.services.blinks, self.config.blinks_port) {
+            let executor = BlinkExecutor::new(self.trading_engine.clone(), self.wallet_manager.clone())
+                .with_jupiter(Arc::new(JupiterV6Client::new(ApiTier::Lite, None)))
+                .with_rpc(Arc::new(RpcClient::new(self.config.get_rpc_url())));
+            let server = ActionServer::new(generator.clone(), Arc::new(executor))
+                .with_tracker(self.services.blink_tracker.clone());
+            tokio::spawn(async move {
+                if let Err(e) = server.start(port).await {
+                    error!("🔗 Actions server stopped: {}", e);
+                }
+            });
+        }
+        match (self.config.convex_webhook_port, &self.config.convex_webhook_secret) {
+            (Some(port), Some(secret)) => {
+                let dispatcher = WebhookDispatcher::new(self.services.automation_auth.clone(), self.services.sessions.clone())
+                    .with_trades(Arc::new(EngineTrades::new(self.trading_engine.clone(), self.wallet_manager.clone())))
+                    .with_orders(self.services.order_manager.clone())
+                    .with_portfolios(Arc::new(WalletPortfolios::new(
+                        Arc::new(PortfolioFetcher::new(self.config.get_rpc_url())),
+                        self.wallet_manager.clone(),
+                    )))
+                    .with_notifier(Arc::new(bot.clone()))
+                    .with_automations(self.services.clone())
+                    .with_pause(self.services.admin.clone());
+                let server = WebhookServer::new(Arc::new(dispatcher)).with_secret(secret.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = server.start(port).await {
+                        error!("📨 Convex webhook stopped: {}", e);
+                    }
+                });
+            }
+            (Some(_), None) => warn!("📨 CONVEX_WEBHOOK_PORT is set without CONVEX_WEBHOOK_SECRET; the webhook stays off"),
+            _ => {}
+        }
+        
         let handler = dptree::entry()
             .branch(Update::filter_message()
-                .filter_command::<Command>()
+                .filter_map_async(Self::parse_command)
                 .endpoint(Self::handle_command))
             .branch(Update::filter_message()
                 .endpoint(TextMessageHandler::handle))
@@ -64,7 +184,8 @@ impl TelegramBot {
                 self.ai_analyzer.clone(),
                 self.db.clone(),
                 self.config.clone(),
-                self.wallet_manager.clone()
+                self.wallet_manager.clone(),
+                self.services.clone()
             ])
             .enable_ctrlc_handler()
             .build()
@@ -74,7 +195,18 @@ impl TelegramBot {
         Ok(())
     }
     
-    /// Handle bot commands by delegating to CommandHandler
+    /// Parse a command, expanding the sender's alias first
+    ///
+    /// The expanded text is parsed exactly like typed input, so handlers
+    /// sanitize and validate alias arguments the same way.
+    async fn parse_command(msg: Message, me: Me, services: Arc<BotServices>) -> Option<Command> {
+        let text = msg.text()?;
+        let user_id = msg.from()?.id.0 as i64;
+        let expanded = services.aliases.expand(user_id, text).await;
+        Command::parse(expanded.as_deref().unwrap_or(text), me.username()).ok()
+    }
+    
+    /// Handle bot commands, counting each one by name and outcome
     async fn handle_command(
         bot: Bot,
         msg: Message,
@@ -84,117 +216,267 @@ impl TelegramBot {
         db: Arc<Database>,
         config: Arc<Config>,
         wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
+    ) -> ResponseResult<()> {
+        let command = cmd.name();
+        let metrics = services.metrics.clone();
+        let result = Self::dispatch_command(bot, msg, cmd, trading_engine, ai_analyzer, db, config, wallet_manager, services).await;
+        if let Some(metrics) = metrics {
+            metrics.record_command(command, result.is_ok());
+        }
+        result
+    }
+    
+    /// Reply to a rate-limited command
+    pub fn throttled_message(lang: &str, retry_after: Duration) -> String {
+        let seconds = retry_after.as_secs_f64().ceil().max(1.0);
+        t_args(lang, "errors.throttled", &[("seconds", &seconds.to_string())])
+    }
+    
+    /// Delegate a command to its handler
+    async fn dispatch_command(
+        bot: Bot,
+        msg: Message,
+        cmd: Command,
+        trading_engine: TradingEngineHandle,
+        ai_analyzer: Arc<GroqAnalyzer>,
+        db: Arc<Database>,
+        config: Arc<Config>,
+        wallet_manager: Arc<WalletManager>,
+        services: Arc<BotServices>,
     ) -> ResponseResult<()> {
         let user_id = msg.from()
             .map(|u| u.id.0.to_string())
             .unwrap_or_default();
         
+        let lang = services.preferences.language(user_id.parse().unwrap_or_default(), msg.from()).await;
+        
         if !config.is_user_allowed(&user_id) {
-            bot.send_message(msg.chat.id, "⛔ Unauthorized access")
+            bot.send_message(msg.chat.id, t(&lang, "errors.unauthorized"))
                 .await?;
             return Ok(());
         }
         
+        // Refused before the rate limiter, so a paused trade costs nothing
+        if let Some(maintenance) = services.admin.blocking(&cmd).await {
+            bot.send_message(msg.chat.id, AdminDesk::banner(&maintenance, &lang)).await?;
+            return Ok(());
+        }
+        
+        match services.command_limiter.check_command(&user_id, cmd.cost(), cmd.executes_trade()).await {
+            Ok(()) => {}
+            Err(RateLimitError::Throttled { retry_after, notify }) => {
+                // One reply per cooldown, so spamming doesn't turn into a reply loop
+                if notify {
+                    bot.send_message(msg.chat.id, Self::throttled_message(&lang, retry_after)).await?;
+                }
+                return Ok(());
+            }
+            Err(e) => warn!("Command rate limiter failed for user {}, letting {} through: {}", user_id, cmd.name(), e),
+        }
+        
         info!("Processing command {:?} from user {}", cmd, user_id);
         
         match cmd {
             Command::Start => {
-                CommandHandler::handle_start(bot, msg).await?;
+                CommandHandler::handle_start(bot, msg, services).await?;
             }
             Command::Balance => {
-                CommandHandler::handle_balance(bot, msg, trading_engine, wallet_manager, user_id).await?;
+                CommandHandler::handle_balance(bot, msg, trading_engine, wallet_manager, user_id, &lang).await?;
             }
             Command::Buy(args) => {
-                CommandHandler::handle_buy(bot, msg, args, trading_engine, db, wallet_manager, user_id).await?;
+                CommandHandler::handle_buy(bot, msg, args, trading_engine, db, wallet_manager, services.sandwich_monitor.clone(), services.preferences.clone(), services.performance.clone(), services.cost_basis.clone(), services.liquidity.clone(), services.confirmations.clone(), user_id).await?;
             }
             Command::Sell(args) => {
-                CommandHandler::handle_sell(bot, msg, args, trading_engine, db, wallet_manager, user_id).await?;
+                CommandHandler::handle_sell(bot, msg, args, trading_engine, db, wallet_manager, services.journal.clone(), services.sandwich_monitor.clone(), services.smart_sell.clone(), services.preferences.clone(), services.performance.clone(), services.cost_basis.clone(), user_id).await?;
             }
             Command::Portfolio => {
-                CommandHandler::handle_portfolio(bot, msg, trading_engine, wallet_manager, user_id).await?;
+                CommandHandler::handle_portfolio(bot, msg, trading_engine, wallet_manager, services.token_calendar.clone(), services.bonding.clone(), services.position_sync.clone(), user_id).await?;
             }
             Command::Analyze(token) => {
-                CommandHandler::handle_analyze(bot, msg, token, ai_analyzer).await?;
+                CommandHandler::handle_analyze(bot, msg, token, ai_analyzer, user_id, &lang).await?;
+            }
+            Command::AiBudget => {
+                CommandHandler::handle_ai_budget(bot, msg, ai_analyzer, config, user_id, &lang).await?;
             }
             Command::Rebates => {
-                CommandHandler::handle_rebates(bot, msg, db, user_id).await?;
+                CommandHandler::handle_rebates(bot, msg, db, user_id, &lang).await?;
             }
-            Command::Settings => {
-                CommandHandler::handle_settings(bot, msg).await?;
+            Command::Settings(args) => {
+                CommandHandler::handle_settings(bot, msg, args, services, user_id).await?;
             }
             Command::Help => {
-                CommandHandler::handle_help(bot, msg).await?;
+                let aliases = services.aliases.list(user_id.parse().unwrap_or_default()).await;
+                CommandHandler::handle_help(bot, msg, aliases, &lang).await?;
             }
             Command::Deposit => {
-                CommandHandler::handle_deposit(bot, msg, wallet_manager, user_id).await?;
+                CommandHandler::handle_deposit(bot, msg, wallet_manager, user_id, &lang).await?;
             }
             Command::Export => {
-                CommandHandler::handle_export(bot, msg, wallet_manager, user_id).await?;
+                CommandHandler::handle_export(bot, msg, wallet_manager, services, user_id).await?;
             }
             Command::Backup => {
-                CommandHandler::handle_backup(bot, msg).await?;
+                CommandHandler::handle_backup(bot, msg, &lang).await?;
             }
             Command::Confirm => {
-                CommandHandler::handle_confirm(bot, msg).await?;
+                CommandHandler::handle_confirm(bot, msg, services, user_id).await?;
             }
             Command::Cancel => {
-                CommandHandler::handle_cancel(bot, msg).await?;
+                CommandHandler::handle_cancel(bot, msg, services, user_id).await?;
             }
             // MVP Trading Commands
             Command::Snipe(args) => {
-                CommandHandler::handle_snipe(bot, msg, args, trading_engine, db, wallet_manager, user_id).await?;
+                CommandHandler::handle_snipe(bot, msg, args, trading_engine, db, wallet_manager, services.liquidity.clone(), services.preferences.clone(), user_id).await?;
             }
             Command::Copy(args) => {
-                CommandHandler::handle_copy(bot, msg, args, db, user_id, trading_engine.clone(), wallet_manager.clone()).await?;
+                CommandHandler::handle_copy(bot, msg, args, user_id, services.copy_trading.clone(), &lang).await?;
             }
             Command::Unfollow(args) => {
-                CommandHandler::handle_unfollow(bot, msg, args, db, user_id).await?;
+                CommandHandler::handle_unfollow(bot, msg, args, db, user_id, &lang).await?;
+            }
+            Command::Master(args) => {
+                MasterHandler::handle_master(bot, msg, args, services, wallet_manager, user_id).await?;
             }
             Command::Larp(args) => {
-                CommandHandler::handle_larp(bot, msg, args, ai_analyzer).await?;
+                CommandHandler::handle_larp(bot, msg, args, ai_analyzer, &lang).await?;
             }
             Command::Trending => {
-                CommandHandler::handle_trending(bot, msg, ai_analyzer).await?;
+                TrendingHandler::handle_trending(bot, msg, services.clone()).await?;
             }
             Command::Launch => {
-                CommandHandler::handle_launch(bot, msg, trading_engine, user_id).await?;
+                LaunchHandler::handle_launch(bot, msg, services, user_id).await?;
             }
             Command::Blink(args) => {
-                CommandHandler::handle_blink(bot, msg, args, trading_engine, user_id).await?;
+                CommandHandler::handle_blink(bot, msg, args, wallet_manager, services, user_id).await?;
+            }
+            Command::MyBlinks => {
+                BlinksHandler::handle_my_blinks(bot, msg, wallet_manager, services, user_id).await?;
             }
             Command::Alert(args) => {
-                CommandHandler::handle_alert(bot, msg, args, db, user_id).await?;
+                PriceEntryHandler::handle_alert(bot, msg, args, services.clone(), user_id).await?;
+            }
+            Command::Alerts(args) => {
+                PriceAlertHandler::handle_alerts(bot, msg, args, services, user_id).await?;
+            }
+            Command::Watch(args) => {
+                WatchlistHandler::handle_watch(bot, msg, args, services, user_id).await?;
+            }
+            Command::Whales(args) => {
+                WhaleHandler::handle_whales(bot, msg, args, services, user_id).await?;
             }
             Command::Leaderboard => {
-                CommandHandler::handle_leaderboard(bot, msg, db).await?;
+                CommandHandler::handle_leaderboard(bot, msg, services.leaderboard.clone(), &lang).await?;
             }
             Command::Signals => {
-                CommandHandler::handle_signals(bot, msg, ai_analyzer).await?;
+                CommandHandler::handle_signals(bot, msg, ai_analyzer, services).await?;
             }
             Command::Pump(args) => {
-                CommandHandler::handle_pump(bot, msg, args, trading_engine, user_id).await?;
+                CommandHandler::handle_pump(bot, msg, args, services.pump_fun.clone(), services.bonding.clone(), user_id, &lang).await?;
             }
             Command::QuickBuy(args) => {
-                CommandHandler::handle_quick_buy(bot, msg, args, trading_engine, wallet_manager, user_id).await?;
+                CommandHandler::handle_quick_buy(bot, msg, args, trading_engine, wallet_manager, user_id, services).await?;
             }
             Command::QuickSell(args) => {
-                CommandHandler::handle_quick_sell(bot, msg, args, trading_engine, wallet_manager, user_id).await?;
+                CommandHandler::handle_quick_sell(bot, msg, args, trading_engine, wallet_manager, user_id, &lang).await?;
             }
             Command::StopLoss(args) => {
-                CommandHandler::handle_stop_loss(bot, msg, args, db, user_id).await?;
+                CommandHandler::handle_stop_loss(bot, msg, args, db, user_id, &lang).await?;
+            }
+            Command::Bracket(args) => {
+                PriceEntryHandler::handle_bracket(bot, msg, args, services.clone(), trading_engine, wallet_manager, user_id).await?;
+            }
+            Command::Calendar => {
+                CalendarHandler::handle_calendar(bot, msg, services.token_calendar.clone(), trading_engine, wallet_manager, user_id, &lang).await?;
+            }
+            Command::AddEvent(args) => {
+                CalendarHandler::handle_add_event(bot, msg, args, services.token_calendar.clone(), config, user_id, &lang).await?;
+            }
+            Command::Chart(token) => {
+                ChartHandler::handle_chart(bot, msg, token, services, &lang).await?;
+            }
+            Command::Journal(args) => {
+                JournalHandler::handle_journal(bot, msg, args, services.clone(), user_id).await?;
+            }
+            Command::ExportTrades(args) => {
+                StatsHandler::handle_export_trades(bot, msg, args, services, user_id).await?;
+            }
+            Command::Performance(args) => {
+                StatsHandler::handle_performance(bot, msg, args, services, user_id).await?;
+            }
+            Command::Stats(args) => {
+                StatsHandler::handle_stats(bot, msg, args, services, user_id).await?;
+            }
+            Command::Mev(args) => {
+                CommandHandler::handle_mev(bot, msg, args, services, user_id).await?;
+            }
+            Command::Dca(args) => {
+                DcaHandler::handle_dca(bot, msg, args, services.dca_engine.clone(), services.dca_scheduler.clone(), services.execution_notices.clone(), user_id, &lang).await?;
+            }
+            Command::GroupBuy(args) => {
+                GroupBuyHandler::handle_group_buy(bot, msg, args, services, user_id).await?;
+            }
+            Command::Alias(args) => {
+                AliasHandler::handle_alias(bot, msg, args, services.aliases.clone(), user_id, &lang).await?;
+            }
+            Command::Cleanup(args) => {
+                CleanupHandler::handle_cleanup(bot, msg, args, services, wallet_manager, user_id).await?;
+            }
+            Command::Lend(args) => {
+                LendingHandler::handle_lend(bot, msg, args, services, wallet_manager, user_id).await?;
+            }
+            Command::Send(args) => {
+                SendHandler::handle_send(bot, msg, args, services, wallet_manager, user_id).await?;
+            }
+            Command::SmartSell(args) => {
+                TradingHandler::handle_smart_sell_default(bot, msg, args, services, user_id).await?;
+            }
+            Command::ExitTo(args) => {
+                TradingHandler::handle_exit_default(bot, msg, args, services, user_id).await?;
+            }
+            Command::CostBasis(args) => {
+                TradingHandler::handle_cost_basis_method(bot, msg, args, services, user_id).await?;
+            }
+            Command::Panic(args) => {
+                TradingHandler::handle_panic(bot, msg, args, trading_engine, wallet_manager, services, user_id).await?;
+            }
+            Command::Paper(args) => {
+                TradingHandler::handle_paper(bot, msg, args, trading_engine, wallet_manager, user_id, &lang).await?;
+            }
+            Command::Order(args) => {
+                PriceEntryHandler::handle_order(bot, msg, args, services, user_id).await?;
+            }
+            Command::Orders => {
+                OrderHandler::handle_orders(bot, msg, services, user_id).await?;
+            }
+            Command::CancelOrder(args) => {
+                OrderHandler::handle_cancel_order(bot, msg, args, services, user_id).await?;
+            }
+            Command::Verbosity(args) => {
+                NoticeHandler::handle_verbosity(bot, msg, args, services, user_id).await?;
+            }
+            Command::Language(args) => {
+                LanguageHandler::handle_language(bot, msg, args, services, user_id).await?;
+            }
+            Command::ForgetMe(args) => {
+                ForgetHandler::handle_forgetme(bot, msg, args, services, user_id).await?;
+            }
+            Command::Automations(args) => {
+                AutomationsHandler::handle_automations(bot, msg, args, services, user_id).await?;
+            }
+            Command::Admin(args) => {
+                AdminHandler::handle_admin(bot, msg, args, services, config, user_id).await?;
             }
             // Legacy commands - redirect to menu
             Command::Wallet => {
-                bot.send_message(msg.chat.id, "💼 Use the Wallet button in the main menu instead!")
+                bot.send_message(msg.chat.id, t(&lang, "legacy.wallet"))
                     .await?;
             }
             Command::NewWallet => {
-                bot.send_message(msg.chat.id, "🆕 Use the 💼 Wallet → 🆕 New Wallet buttons instead!")
+                bot.send_message(msg.chat.id, t(&lang, "legacy.new_wallet"))
                     .await?;
             }
-            Command::Import => {
-                bot.send_message(msg.chat.id, "📥 Use the 💼 Wallet → 📥 Import Wallet buttons instead!")
-                    .await?;
+            Command::Import(args) => {
+                ImportHandler::handle_import(bot, msg, args, services, user_id).await?;
             }
         }
         
@@ -0,0 +1,378 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::{
+    cache::{InMemorySessionStore, SessionStore, SessionStoreExt},
+    errors::{BotError, Result},
+    trading::{TokenCreationConfig, TokenCreationResult, TokenCreator, TokenDetails, TokenPreset, TokenPreview},
+};
+
+/// How long an untouched /launch wizard stays open
+const DEFAULT_LAUNCH_TTL: Duration = Duration::from_secs(30 * 60);
+/// Lost updates from two instances are retried this often
+const MAX_WRITE_ATTEMPTS: usize = 3;
+
+pub const MAX_NAME_CHARS: usize = 32;
+pub const MIN_SYMBOL_CHARS: usize = 2;
+pub const MAX_SYMBOL_CHARS: usize = 10;
+pub const MAX_DESCRIPTION_CHARS: usize = 500;
+pub const MAX_IMAGE_BYTES: u32 = 5 * 1024 * 1024;
+pub const MAX_SUPPLY: u64 = 1_000_000_000_000;
+pub const IMAGE_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Names and symbols that would pass for an established token
+const RESERVED_NAMES: [&str; 18] = [
+    "sol", "solana", "wsol", "wrapped sol", "usdc", "usd coin", "usdt", "tether", "btc", "bitcoin",
+    "eth", "ethereum", "bonk", "jup", "jupiter", "ray", "raydium", "pump",
+];
+
+/// Where a user is in the /launch wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LaunchStep {
+    Name,
+    Symbol,
+    Description,
+    Image,
+    Preset,
+    Supply,
+    Review,
+}
+
+impl LaunchStep {
+    pub fn previous(self) -> Option<LaunchStep> {
+        match self {
+            LaunchStep::Name => None,
+            LaunchStep::Symbol => Some(LaunchStep::Name),
+            LaunchStep::Description => Some(LaunchStep::Symbol),
+            LaunchStep::Image => Some(LaunchStep::Description),
+            LaunchStep::Preset => Some(LaunchStep::Image),
+            LaunchStep::Supply => Some(LaunchStep::Preset),
+            LaunchStep::Review => Some(LaunchStep::Supply),
+        }
+    }
+
+    fn next(self) -> LaunchStep {
+        match self {
+            LaunchStep::Name => LaunchStep::Symbol,
+            LaunchStep::Symbol => LaunchStep::Description,
+            LaunchStep::Description => LaunchStep::Image,
+            LaunchStep::Image => LaunchStep::Preset,
+            LaunchStep::Preset => LaunchStep::Supply,
+            LaunchStep::Supply | LaunchStep::Review => LaunchStep::Review,
+        }
+    }
+
+    /// Steps that can be left empty
+    pub fn skippable(self) -> bool {
+        matches!(self, LaunchStep::Description | LaunchStep::Image | LaunchStep::Supply)
+    }
+}
+
+/// A picture sent at the image step, kept as its Telegram file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchImage {
+    pub file_id: String,
+    pub mime_type: String,
+    pub size: u32,
+}
+
+/// Everything entered so far; earlier answers survive going back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchDraft {
+    pub step: LaunchStep,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<LaunchImage>,
+    pub preset: Option<TokenPreset>,
+    /// Whole tokens; the preset's supply when `None`
+    pub supply: Option<u64>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// One thing the user did in the wizard
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchInput {
+    Text(String),
+    Image(LaunchImage),
+    Preset(TokenPreset),
+    Skip,
+    Back,
+    Cancel,
+    Confirm,
+}
+
+/// What an input did to the draft
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchTransition {
+    /// Now waiting at this step
+    Moved(LaunchStep),
+    /// Still at the same step; tell the user why
+    Rejected(String),
+    Cancelled,
+    /// Reviewed and confirmed; create the token
+    Confirmed,
+}
+
+impl LaunchDraft {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            step: LaunchStep::Name,
+            name: None,
+            symbol: None,
+            description: None,
+            image: None,
+            preset: None,
+            supply: None,
+            started_at: now,
+        }
+    }
+
+    /// Apply one input; the draft only changes when the input is accepted
+    pub fn apply(&mut self, input: LaunchInput) -> LaunchTransition {
+        let step = self.step;
+        let accepted = match (step, input) {
+            (_, LaunchInput::Cancel) => return LaunchTransition::Cancelled,
+            (_, LaunchInput::Back) => {
+                return match step.previous() {
+                    Some(previous) => {
+                        self.step = previous;
+                        LaunchTransition::Moved(previous)
+                    }
+                    None => LaunchTransition::Rejected("This is the first step. Tap Cancel to stop.".to_string()),
+                };
+            }
+            (LaunchStep::Review, LaunchInput::Confirm) => return LaunchTransition::Confirmed,
+            (step, LaunchInput::Skip) if step.skippable() => {
+                match step {
+                    LaunchStep::Description => self.description = None,
+                    LaunchStep::Image => self.image = None,
+                    _ => self.supply = None,
+                }
+                Ok(())
+            }
+            (LaunchStep::Name, LaunchInput::Text(text)) => validate_name(&text).map(|name| self.name = Some(name)),
+            (LaunchStep::Symbol, LaunchInput::Text(text)) => validate_symbol(&text).map(|symbol| self.symbol = Some(symbol)),
+            (LaunchStep::Description, LaunchInput::Text(text)) => {
+                validate_description(&text).map(|description| self.description = Some(description))
+            }
+            (LaunchStep::Image, LaunchInput::Image(image)) => validate_image(&image.mime_type, image.size).map(|_| self.image = Some(image)),
+            (LaunchStep::Image, _) => Err("Send the token picture as a photo or image file, or tap Skip.".to_string()),
+            (LaunchStep::Preset, LaunchInput::Preset(preset)) => {
+                self.preset = Some(preset);
+                Ok(())
+            }
+            (LaunchStep::Preset, _) => Err("Pick a preset with the buttons below.".to_string()),
+            (LaunchStep::Supply, LaunchInput::Text(text)) => parse_supply(&text).map(|supply| self.supply = Some(supply)),
+            (LaunchStep::Review, _) => Err("Tap Confirm to launch, Back to change something, or Cancel.".to_string()),
+            (_, LaunchInput::Confirm) | (_, LaunchInput::Preset(_)) => Err("That button belongs to another step.".to_string()),
+            (_, LaunchInput::Image(_)) => Err("Send text for this step.".to_string()),
+            (_, LaunchInput::Skip) => Err("This step can't be skipped.".to_string()),
+        };
+
+        match accepted {
+            Ok(()) => {
+                self.step = step.next();
+                LaunchTransition::Moved(self.step)
+            }
+            Err(reason) => LaunchTransition::Rejected(reason),
+        }
+    }
+
+    /// The launcher's choices for `TokenCreator`; `None` until the draft is complete
+    pub fn details(&self) -> Option<(TokenPreset, TokenDetails)> {
+        let mut additional_metadata = HashMap::new();
+        if let Some(image) = &self.image {
+            additional_metadata.insert("telegram_image_file_id".to_string(), image.file_id.clone());
+        }
+        Some((self.preset?, TokenDetails {
+            name: self.name.clone()?,
+            symbol: self.symbol.clone()?,
+            description: self.description.clone(),
+            image_url: None,
+            initial_supply: self.supply,
+            additional_metadata,
+        }))
+    }
+}
+
+pub fn validate_name(text: &str) -> std::result::Result<String, String> {
+    let name = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return Err(format!("The name must be 1-{} characters.", MAX_NAME_CHARS));
+    }
+    if is_reserved(&name) {
+        return Err(format!("\"{}\" is reserved for an existing token. Pick another name.", name));
+    }
+    Ok(name)
+}
+
+/// Upper-cased, letters and digits only
+pub fn validate_symbol(text: &str) -> std::result::Result<String, String> {
+    let symbol = text.trim().trim_start_matches('$').to_uppercase();
+    let len = symbol.chars().count();
+    if !(MIN_SYMBOL_CHARS..=MAX_SYMBOL_CHARS).contains(&len) {
+        return Err(format!("The symbol must be {}-{} characters.", MIN_SYMBOL_CHARS, MAX_SYMBOL_CHARS));
+    }
+    if !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("The symbol may only use letters A-Z and digits.".to_string());
+    }
+    if is_reserved(&symbol) {
+        return Err(format!("{} is reserved for an existing token. Pick another symbol.", symbol));
+    }
+    Ok(symbol)
+}
+
+pub fn validate_description(text: &str) -> std::result::Result<String, String> {
+    let description = text.trim().to_string();
+    if description.is_empty() || description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(format!("The description must be 1-{} characters, or tap Skip.", MAX_DESCRIPTION_CHARS));
+    }
+    Ok(description)
+}
+
+pub fn validate_image(mime_type: &str, size: u32) -> std::result::Result<(), String> {
+    if !IMAGE_TYPES.contains(&mime_type) {
+        return Err("The picture must be a PNG, JPEG, GIF or WebP.".to_string());
+    }
+    if size > MAX_IMAGE_BYTES {
+        return Err(format!("The picture must be at most {} MB.", MAX_IMAGE_BYTES / 1024 / 1024));
+    }
+    Ok(())
+}
+
+/// Whole tokens; `,`, `_` and spaces between digits are ignored
+pub fn parse_supply(text: &str) -> std::result::Result<u64, String> {
+    let digits: String = text.chars().filter(|c| !matches!(c, ',' | '_' | ' ')).collect();
+    match digits.parse::<u64>() {
+        Ok(supply) if (1..=MAX_SUPPLY).contains(&supply) => Ok(supply),
+        _ => Err(format!("The supply must be a whole number from 1 to {}.", MAX_SUPPLY)),
+    }
+}
+
+fn is_reserved(text: &str) -> bool {
+    RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(text))
+}
+
+/// /launch wizards, one per user, kept in the session store
+///
+/// Any instance can take the next step, and a confirmed draft is taken out
+/// of the store so only one of them creates the token.
+pub struct LaunchWizard {
+    sessions: Arc<dyn SessionStore>,
+    creator: TokenCreator,
+    ttl: Duration,
+}
+
+impl Default for LaunchWizard {
+    fn default() -> Self {
+        Self {
+            sessions: Arc::new(InMemorySessionStore::default()),
+            creator: TokenCreator::new(),
+            ttl: DEFAULT_LAUNCH_TTL,
+        }
+    }
+}
+
+impl LaunchWizard {
+    /// Share wizards with other instances
+    pub fn with_sessions(mut self, sessions: Arc<dyn SessionStore>) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    /// Open a fresh wizard, replacing any earlier one
+    pub async fn start(&self, user_id: i64) -> Result<LaunchDraft> {
+        let draft = LaunchDraft::new(Utc::now());
+        self.sessions.put(&launch_key(user_id), &draft, self.ttl).await?;
+        debug!("🚀 User {} started a token launch", user_id);
+        Ok(draft)
+    }
+
+    pub async fn draft(&self, user_id: i64) -> Option<LaunchDraft> {
+        self.sessions.get_as(&launch_key(user_id)).await.unwrap_or_else(|e| {
+            warn!("🚀 Couldn't read launch draft for {}: {}", user_id, e);
+            None
+        })
+    }
+
+    /// Apply the input to the user's open wizard; `None` when there is none
+    ///
+    /// Cancelling deletes the draft and confirming takes it, so the returned
+    /// draft is the caller's to create.
+    pub async fn advance(&self, user_id: i64, input: LaunchInput) -> Result<Option<(LaunchDraft, LaunchTransition)>> {
+        let key = launch_key(user_id);
+        for _ in 0..MAX_WRITE_ATTEMPTS {
+            let Some(stored) = self.sessions.get(&key).await? else { return Ok(None) };
+            let mut draft: LaunchDraft = serde_json::from_value(stored.value)
+                .map_err(|e| BotError::internal(format!("Couldn't decode launch draft: {}", e)))?;
+
+            let transition = draft.apply(input.clone());
+            match &transition {
+                LaunchTransition::Rejected(_) => return Ok(Some((draft, transition))),
+                LaunchTransition::Cancelled => {
+                    self.sessions.delete(&key).await?;
+                    return Ok(Some((draft, transition)));
+                }
+                LaunchTransition::Confirmed => {
+                    return Ok(self.sessions.take_as::<LaunchDraft>(&key).await?.map(|taken| (taken, transition)));
+                }
+                LaunchTransition::Moved(_) => {
+                    let value = serde_json::to_value(&draft)
+                        .map_err(|e| BotError::internal(format!("Couldn't encode launch draft: {}", e)))?;
+                    if self.sessions.compare_and_set(&key, Some(stored.version), value, self.ttl).await?.is_some() {
+                        return Ok(Some((draft, transition)));
+                    }
+                }
+            }
+        }
+        Err(BotError::internal("Launch draft kept changing underneath us".to_string()))
+    }
+
+    /// Put a taken draft back, e.g. when creating its token failed
+    pub async fn resume(&self, user_id: i64, draft: &LaunchDraft) {
+        if let Err(e) = self.sessions.put(&launch_key(user_id), draft, self.ttl).await {
+            warn!("🚀 Couldn't restore launch draft for {}: {}", user_id, e);
+        }
+    }
+
+    pub async fn cancel(&self, user_id: i64) {
+        if let Err(e) = self.sessions.delete(&launch_key(user_id)).await {
+            warn!("🚀 Couldn't clear launch draft for {}: {}", user_id, e);
+        }
+    }
+
+    /// Presets offered at the preset step, in a fixed order
+    pub fn presets(&self) -> Vec<(TokenPreset, TokenCreationConfig)> {
+        TokenPreset::ALL.into_iter()
+            .filter_map(|preset| self.creator.get_preset(preset).map(|config| (preset, config)))
+            .collect()
+    }
+
+    /// Configuration, features and fee for the review step
+    pub fn review(&self, draft: &LaunchDraft) -> Result<(TokenCreationConfig, TokenPreview, f64)> {
+        let (preset, details) = draft.details()
+            .ok_or_else(|| BotError::validation("The launch isn't complete yet".to_string()))?;
+        // Reviewed before a wallet is involved
+        let config = self.creator.config_for(preset, details, Default::default())?;
+        let fee = self.creator.estimate_creation_cost(&config)?;
+        let preview = self.creator.preview_token_features(&config);
+        Ok((config, preview, fee))
+    }
+
+    /// Create the confirmed draft's token, paid for by `payer`
+    pub async fn launch(&self, draft: &LaunchDraft, payer: &Keypair) -> Result<TokenCreationResult> {
+        let (preset, details) = draft.details()
+            .ok_or_else(|| BotError::validation("The launch isn't complete yet".to_string()))?;
+        self.creator.create(preset, details, payer).await
+    }
+}
+
+fn launch_key(user_id: i64) -> String {
+    format!("launch:{}", user_id)
+}
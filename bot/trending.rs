@@ -0,0 +1,303 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+use crate::{bot::handlers::CommandHandler, errors::Result, utils::{t, t_args}};
+
+/// Most tokens kept in the cached dataset
+pub const MAX_TRENDING_TOKENS: usize = 20;
+/// Most new launches kept in the cached dataset
+pub const MAX_NEW_LAUNCHES: usize = 10;
+/// Most risk alerts kept in the cached dataset
+pub const MAX_RISK_ALERTS: usize = 10;
+
+/// Trending token data structure
+#[derive(Debug, Clone)]
+pub struct TrendingToken {
+    pub name: String,
+    pub symbol: String,
+    pub address: String,
+    pub price: f64,
+    pub price_change_24h: f64,
+    pub volume_24h: f64,
+    pub market_cap: f64,
+}
+
+/// New token launch information
+#[derive(Debug, Clone)]
+pub struct NewLaunch {
+    pub name: Option<String>,
+    pub address: String,
+    /// Seconds since the pair was created, when DexScreener knows
+    pub age_secs: Option<u64>,
+    pub liquidity_usd: Option<f64>,
+    pub holder_count: u32,
+}
+
+/// Risk alert for dangerous tokens
+#[derive(Debug, Clone)]
+pub struct RiskAlert {
+    pub symbol: String,
+    pub address: String,
+    pub reason: String,
+}
+
+/// Upstreams that make up the /trending dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendingSourceKind {
+    Market,
+    Launches,
+    RiskAlerts,
+}
+
+impl TrendingSourceKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Market => "Market data",
+            Self::Launches => "New launches",
+            Self::RiskAlerts => "Risk alerts",
+        }
+    }
+
+    /// Catalog key for the name shown to users
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Market => "trending.source_market",
+            Self::Launches => "trending.source_launches",
+            Self::RiskAlerts => "trending.source_alerts",
+        }
+    }
+}
+
+/// Where the trending dataset comes from
+#[async_trait::async_trait]
+pub trait TrendingSource: Send + Sync {
+    async fn trending(&self) -> Result<Vec<TrendingToken>>;
+    async fn new_launches(&self) -> Result<Vec<NewLaunch>>;
+    async fn risk_alerts(&self) -> Result<Vec<RiskAlert>>;
+}
+
+/// Market data aggregator and DexScreener
+pub struct LiveTrendingSource;
+
+#[async_trait::async_trait]
+impl TrendingSource for LiveTrendingSource {
+    async fn trending(&self) -> Result<Vec<TrendingToken>> {
+        CommandHandler::fetch_enhanced_trending_data().await
+    }
+
+    async fn new_launches(&self) -> Result<Vec<NewLaunch>> {
+        CommandHandler::fetch_new_launches().await
+    }
+
+    async fn risk_alerts(&self) -> Result<Vec<RiskAlert>> {
+        CommandHandler::fetch_risk_alerts().await
+    }
+}
+
+/// Outcome of the latest fetch from one source
+#[derive(Debug, Clone, Default)]
+pub struct SourceStatus {
+    /// When this source last returned data
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// Set when the latest attempt failed and older data is being served
+    pub error: Option<String>,
+}
+
+/// The cached /trending dataset
+#[derive(Debug, Clone)]
+pub struct TrendingSnapshot {
+    pub tokens: Vec<TrendingToken>,
+    pub new_launches: Vec<NewLaunch>,
+    pub risk_alerts: Vec<RiskAlert>,
+    pub refreshed_at: DateTime<Utc>,
+    pub market: SourceStatus,
+    pub launches: SourceStatus,
+    pub alerts: SourceStatus,
+}
+
+impl TrendingSnapshot {
+    pub fn age_secs(&self, now: DateTime<Utc>) -> i64 {
+        (now - self.refreshed_at).num_seconds().max(0)
+    }
+
+    pub fn status(&self, kind: TrendingSourceKind) -> &SourceStatus {
+        match kind {
+            TrendingSourceKind::Market => &self.market,
+            TrendingSourceKind::Launches => &self.launches,
+            TrendingSourceKind::RiskAlerts => &self.alerts,
+        }
+    }
+
+    /// Sources whose latest refresh failed
+    pub fn stale_sources(&self) -> Vec<TrendingSourceKind> {
+        [TrendingSourceKind::Market, TrendingSourceKind::Launches, TrendingSourceKind::RiskAlerts]
+            .into_iter()
+            .filter(|kind| self.status(*kind).error.is_some())
+            .collect()
+    }
+
+    /// "As of" line plus a note for every source that couldn't be refreshed
+    pub fn footer(&self, now: DateTime<Utc>, lang: &str) -> String {
+        let mut lines = vec![t_args(lang, "trending.as_of", &[("age", &format_age(self.age_secs(now)))])];
+        for kind in self.stale_sources() {
+            let source = t(lang, kind.key());
+            lines.push(match self.status(kind).fetched_at {
+                Some(at) => t_args(lang, "trending.source_stale", &[
+                    ("source", &source),
+                    ("age", &format_age((now - at).num_seconds().max(0))),
+                ]),
+                None => t_args(lang, "trending.source_unavailable", &[("source", &source)]),
+            });
+        }
+        lines.join("\n")
+    }
+}
+
+fn format_age(secs: i64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s => format!("{}h", s / 3600),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrendingCacheConfig {
+    /// Background refreshes happen at a random point between these
+    pub min_refresh: Duration,
+    pub max_refresh: Duration,
+}
+
+impl Default for TrendingCacheConfig {
+    fn default() -> Self {
+        Self {
+            min_refresh: Duration::from_secs(60),
+            max_refresh: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Keeps the /trending dataset warm so requests never wait on upstreams
+pub struct TrendingCache {
+    source: Arc<dyn TrendingSource>,
+    config: TrendingCacheConfig,
+    snapshot: RwLock<Option<Arc<TrendingSnapshot>>>,
+    /// Held for the duration of a refresh so concurrent callers share one
+    refreshing: Mutex<()>,
+}
+
+impl TrendingCache {
+    pub fn new(source: Arc<dyn TrendingSource>) -> Self {
+        Self {
+            source,
+            config: TrendingCacheConfig::default(),
+            snapshot: RwLock::new(None),
+            refreshing: Mutex::new(()),
+        }
+    }
+
+    pub fn with_config(mut self, config: TrendingCacheConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// The cached dataset, without touching any upstream
+    pub async fn cached(&self) -> Option<Arc<TrendingSnapshot>> {
+        self.snapshot.read().await.clone()
+    }
+
+    /// The cached dataset, or the result of the first refresh on a cold start
+    pub async fn warm(&self) -> Arc<TrendingSnapshot> {
+        if let Some(snapshot) = self.cached().await {
+            return snapshot;
+        }
+        let _guard = self.refreshing.lock().await;
+        if let Some(snapshot) = self.cached().await {
+            return snapshot;
+        }
+        self.fetch_and_store().await
+    }
+
+    /// Refresh now; a refresh that finished while waiting for the lock counts
+    pub async fn refresh(&self) -> Arc<TrendingSnapshot> {
+        let requested_at = Utc::now();
+        let _guard = self.refreshing.lock().await;
+        if let Some(snapshot) = self.cached().await.filter(|s| s.refreshed_at >= requested_at) {
+            return snapshot;
+        }
+        self.fetch_and_store().await
+    }
+
+    async fn fetch_and_store(&self) -> Arc<TrendingSnapshot> {
+        let (tokens, launches, alerts) = tokio::join!(
+            self.source.trending(),
+            self.source.new_launches(),
+            self.source.risk_alerts(),
+        );
+        let previous = self.cached().await;
+        let now = Utc::now();
+
+        let (tokens, market) = merge(tokens, previous.as_ref().map(|p| (&p.tokens, &p.market)), MAX_TRENDING_TOKENS, now);
+        let (new_launches, launches) = merge(launches, previous.as_ref().map(|p| (&p.new_launches, &p.launches)), MAX_NEW_LAUNCHES, now);
+        let (risk_alerts, alerts) = merge(alerts, previous.as_ref().map(|p| (&p.risk_alerts, &p.alerts)), MAX_RISK_ALERTS, now);
+
+        let snapshot = Arc::new(TrendingSnapshot {
+            tokens,
+            new_launches,
+            risk_alerts,
+            refreshed_at: now,
+            market,
+            launches,
+            alerts,
+        });
+        for kind in snapshot.stale_sources() {
+            warn!("📈 {} refresh failed: {}", kind.label(), snapshot.status(kind).error.as_deref().unwrap_or_default());
+        }
+        *self.snapshot.write().await = Some(snapshot.clone());
+        snapshot
+    }
+
+    /// Next background refresh delay, jittered so instances don't sync up
+    pub fn next_delay(&self) -> Duration {
+        let min = self.config.min_refresh.as_millis() as u64;
+        let max = (self.config.max_refresh.as_millis() as u64).max(min);
+        Duration::from_millis(rand::thread_rng().gen_range(min..=max))
+    }
+
+    /// Keep the dataset warm in the background, starting immediately
+    pub fn spawn_refresher(self: &Arc<Self>) {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let snapshot = cache.refresh().await;
+                info!("📈 Trending cache refreshed: {} tokens", snapshot.tokens.len());
+                tokio::time::sleep(cache.next_delay()).await;
+            }
+        });
+    }
+}
+
+/// Take fresh data capped at `limit`, or fall back to what the previous snapshot had
+fn merge<T: Clone>(
+    fetched: Result<Vec<T>>,
+    previous: Option<(&Vec<T>, &SourceStatus)>,
+    limit: usize,
+    now: DateTime<Utc>,
+) -> (Vec<T>, SourceStatus) {
+    match fetched {
+        Ok(mut items) => {
+            items.truncate(limit);
+            (items, SourceStatus { fetched_at: Some(now), error: None })
+        }
+        Err(e) => {
+            let (items, fetched_at) = previous
+                .map(|(items, status)| (items.clone(), status.fetched_at))
+                .unwrap_or_default();
+            (items, SourceStatus { fetched_at, error: Some(e.to_string()) })
+        }
+    }
+}
@@ -3,15 +3,28 @@ use teloxide::{
     types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
 };
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
 use crate::{
+    cache::{SessionStore, SessionStoreExt},
     wallet::{WalletGenerator, WalletManager, WalletSecurity, SecurityWarning, WarningLevel},
     db::Database,
 };
 
+/// How long the import prompt waits for a key or seed phrase
+const SETUP_STEP_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Where a user is in the setup wizard, kept in the session store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetupStep {
+    /// The next message is a private key or seed phrase
+    AwaitingImport,
+}
+
 pub struct WalletSetupFlow;
 
 impl WalletSetupFlow {
@@ -150,7 +163,7 @@ Reply with: *I HAVE SAVED MY KEYS* to continue"#,
     }
 
     /// Import existing wallet flow
-    pub async fn import_wallet(bot: Bot, chat_id: ChatId) -> ResponseResult<()> {
+    pub async fn import_wallet(bot: Bot, chat_id: ChatId, user_id: &str, sessions: &dyn SessionStore) -> ResponseResult<()> {
         let message = r#"📥 *Import Existing Wallet*
 
 Choose import method:
@@ -171,11 +184,40 @@ Send your private key or seed phrase now, or /cancel to abort\."#;
         bot.send_message(chat_id, message)
             .parse_mode(ParseMode::MarkdownV2)
             .await?;
+        Self::set_step(sessions, user_id, SetupStep::AwaitingImport).await;
 
         Ok(())
     }
 
+    /// The user's current setup step; another instance may have started it
+    pub async fn step(sessions: &dyn SessionStore, user_id: &str) -> Option<SetupStep> {
+        sessions.get_as(&Self::step_key(user_id)).await.unwrap_or_else(|e| {
+            warn!("Couldn't read setup step for {}: {}", user_id, e);
+            None
+        })
+    }
+
+    /// Leave the wizard, e.g. on /cancel
+    pub async fn cancel(sessions: &dyn SessionStore, user_id: &str) {
+        if let Err(e) = sessions.delete(&Self::step_key(user_id)).await {
+            warn!("Couldn't clear setup step for {}: {}", user_id, e);
+        }
+    }
+
+    async fn set_step(sessions: &dyn SessionStore, user_id: &str, step: SetupStep) {
+        if let Err(e) = sessions.put(&Self::step_key(user_id), &step, SETUP_STEP_TTL).await {
+            warn!("Couldn't store setup step for {}: {}", user_id, e);
+        }
+    }
+
+    fn step_key(user_id: &str) -> String {
+        format!("setup:{}", user_id)
+    }
+
     /// Process wallet import
+    ///
+    /// Only runs while an import is pending, and only once per prompt even
+    /// when two instances receive the message.
     pub async fn process_import(
         bot: Bot,
         chat_id: ChatId,
@@ -183,7 +225,18 @@ Send your private key or seed phrase now, or /cancel to abort\."#;
         import_data: &str,
         wallet_manager: Arc<RwLock<WalletManager>>,
         db: Arc<Database>,
+        sessions: &dyn SessionStore,
     ) -> ResponseResult<()> {
+        let step = sessions.take_as::<SetupStep>(&Self::step_key(user_id)).await.unwrap_or_else(|e| {
+            warn!("Couldn't read setup step for {}: {}", user_id, e);
+            None
+        });
+        if step != Some(SetupStep::AwaitingImport) {
+            bot.send_message(chat_id, "❌ No wallet import in progress. Tap 📥 Import Existing Wallet to start one.")
+                .await?;
+            return Ok(());
+        }
+        
         // Delete user's message immediately for security
         // (This would need the message ID in real implementation)
         
@@ -226,6 +279,7 @@ You can now start trading\!
                 info!("Imported wallet for user {}: {}", user_id, credentials.public_key);
             }
             Err(e) => {
+                Self::set_step(sessions, user_id, SetupStep::AwaitingImport).await;
                 bot.send_message(chat_id, format!("❌ Import failed: {}\n\nPlease check your input and try again.", e))
                     .await?;
             }
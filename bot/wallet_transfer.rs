@@ -0,0 +1,128 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::cache::{InMemorySessionStore, SessionStore, SessionStoreExt};
+
+/// How long a /confirm unlocks an export
+const DEFAULT_CONFIRM_WINDOW_SECS: i64 = 120;
+/// How long a passphrase prompt stays answerable
+const DEFAULT_PROMPT_TIMEOUT_SECS: i64 = 120;
+
+/// What the passphrase the user is about to send unlocks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferPrompt {
+    /// Encrypt the active wallet into an export blob
+    Export,
+    /// Decrypt this blob and register the wallet inside
+    Import { blob: String },
+}
+
+/// A passphrase prompt waiting for its forced reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTransfer {
+    pub prompt: TransferPrompt,
+    /// Deleted along with the user's reply
+    pub prompt_message_id: Option<i32>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Gates wallet exports behind a recent /confirm and holds passphrase prompts
+///
+/// Both live in the session store, so a /confirm handled by one instance
+/// unlocks an /export handled by another, and only one of them can spend it.
+pub struct WalletTransfers {
+    sessions: Arc<dyn SessionStore>,
+    confirm_window: Duration,
+    prompt_timeout: Duration,
+}
+
+impl Default for WalletTransfers {
+    fn default() -> Self {
+        Self::new(
+            Duration::seconds(DEFAULT_CONFIRM_WINDOW_SECS),
+            Duration::seconds(DEFAULT_PROMPT_TIMEOUT_SECS),
+        )
+    }
+}
+
+impl WalletTransfers {
+    pub fn new(confirm_window: Duration, prompt_timeout: Duration) -> Self {
+        Self {
+            sessions: Arc::new(InMemorySessionStore::default()),
+            confirm_window,
+            prompt_timeout,
+        }
+    }
+
+    /// Share confirmations and prompts with other instances
+    pub fn with_sessions(mut self, sessions: Arc<dyn SessionStore>) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    pub fn confirm_window(&self) -> Duration {
+        self.confirm_window
+    }
+
+    /// Record a /confirm from the user
+    pub async fn confirm(&self, user_id: i64) {
+        let ttl = self.confirm_window.to_std().unwrap_or_default();
+        if let Err(e) = self.sessions.put(&confirm_key(user_id), &Utc::now(), ttl).await {
+            warn!("🔐 Couldn't record /confirm from {}: {}", user_id, e);
+        }
+    }
+
+    /// Spend the user's /confirm on an export; false when there's none inside the window
+    pub async fn authorize_export(&self, user_id: i64) -> bool {
+        let confirmed_at = match self.sessions.take_as::<DateTime<Utc>>(&confirm_key(user_id)).await {
+            Ok(Some(confirmed_at)) => confirmed_at,
+            Ok(None) => return false,
+            Err(e) => {
+                warn!("🔐 Couldn't check /confirm from {}: {}", user_id, e);
+                return false;
+            }
+        };
+        Utc::now() - confirmed_at < self.confirm_window
+    }
+
+    /// Wait for a passphrase, replacing any earlier prompt
+    pub async fn ask(&self, user_id: i64, prompt: TransferPrompt, prompt_message_id: Option<i32>) {
+        debug!("🔐 Waiting for user {} to send a passphrase", user_id);
+        let pending = PendingTransfer { prompt, prompt_message_id, started_at: Utc::now() };
+        let ttl = self.prompt_timeout.to_std().unwrap_or_default();
+        if let Err(e) = self.sessions.put(&prompt_key(user_id), &pending, ttl).await {
+            warn!("🔐 Couldn't store passphrase prompt for {}: {}", user_id, e);
+        }
+    }
+
+    /// Take the user's open prompt, unless it timed out
+    pub async fn take(&self, user_id: i64) -> Option<PendingTransfer> {
+        let pending = match self.sessions.take_as::<PendingTransfer>(&prompt_key(user_id)).await {
+            Ok(pending) => pending?,
+            Err(e) => {
+                warn!("🔐 Couldn't read passphrase prompt for {}: {}", user_id, e);
+                return None;
+            }
+        };
+        (Utc::now() - pending.started_at < self.prompt_timeout).then_some(pending)
+    }
+
+    /// Drop the user's unspent /confirm and open prompt
+    pub async fn cancel(&self, user_id: i64) {
+        for key in [confirm_key(user_id), prompt_key(user_id)] {
+            if let Err(e) = self.sessions.delete(&key).await {
+                warn!("🔐 Couldn't clear {}: {}", key, e);
+            }
+        }
+    }
+}
+
+fn confirm_key(user_id: i64) -> String {
+    format!("transfer:confirm:{}", user_id)
+}
+
+fn prompt_key(user_id: i64) -> String {
+    format!("transfer:prompt:{}", user_id)
+}
@@ -0,0 +1,399 @@
+//! Per-user token watchlists behind /watch and their daily movers digest
+//!
+//! Lists are written through to storage; digests go out through the alert
+//! delivery methods at a local time from the DCA timezone settings.
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::{
+    alerts::{AlertDelivery, AlertKind, AlertPayload, DeliveryStatus},
+    api::{JupiterPriceV3Client, JupiterTokenV2Client, RiskAssessment, TokenWatchlist, WatchlistToken},
+    db::Database,
+    errors::{BotError, Result},
+    trading::TimezoneManager,
+    utils::{fmt_number, NumberKind},
+};
+use super::preferences::PreferenceStore;
+
+/// Tokens a single user may watch
+pub const MAX_WATCHLIST_TOKENS: usize = 50;
+/// 24h move, either way, that puts a token in the daily digest
+pub const DIGEST_MOVE_PERCENT: f64 = 5.0;
+
+/// A user's watchlist and when their daily digest goes out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserWatchlist {
+    pub list: TokenWatchlist,
+    /// Local time of the daily movers digest; `None` when it's off
+    #[serde(default)]
+    pub digest_at: Option<NaiveTime>,
+    /// Local date the last digest went out, so a restart doesn't send it twice
+    #[serde(default)]
+    pub last_digest: Option<NaiveDate>,
+}
+
+impl UserWatchlist {
+    fn new(user_id: i64) -> Self {
+        let now = Utc::now();
+        Self {
+            list: TokenWatchlist { user_id, tokens: Vec::new(), created_at: now, last_updated: now },
+            digest_at: None,
+            last_digest: None,
+        }
+    }
+
+    pub fn tokens(&self) -> &[WatchlistToken] {
+        &self.list.tokens
+    }
+
+    /// Whether today's digest is due at `now` in the user's timezone
+    pub fn digest_due(&self, now: DateTime<Utc>, timezone: Tz) -> bool {
+        let Some(at) = self.digest_at else { return false };
+        let local = now.with_timezone(&timezone);
+        !self.list.tokens.is_empty() && local.time() >= at && self.last_digest != Some(local.date_naive())
+    }
+}
+
+/// Price and 24h change of one token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WatchQuote {
+    pub usd_price: f64,
+    /// Percent; `None` when the price source has no history for the token
+    pub change_24h_percent: Option<f64>,
+}
+
+/// A watched token as `/watch` shows it
+#[derive(Debug, Clone)]
+pub struct WatchedToken {
+    pub token: WatchlistToken,
+    pub quote: Option<WatchQuote>,
+    pub risk: Option<RiskAssessment>,
+}
+
+/// A token that moved enough to be in the digest
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchlistMover {
+    pub address: String,
+    pub symbol: String,
+    pub usd_price: f64,
+    pub change_24h_percent: f64,
+}
+
+/// Tokens that moved at least `threshold` percent either way, biggest move first
+pub fn movers(tokens: &[WatchlistToken], quotes: &HashMap<String, WatchQuote>, threshold: f64) -> Vec<WatchlistMover> {
+    let mut movers: Vec<WatchlistMover> = tokens.iter()
+        .filter_map(|token| {
+            let quote = quotes.get(&token.address)?;
+            let change = quote.change_24h_percent.filter(|change| change.is_finite())?;
+            (change.abs() >= threshold).then(|| WatchlistMover {
+                address: token.address.clone(),
+                symbol: token.symbol.clone(),
+                usd_price: quote.usd_price,
+                change_24h_percent: change,
+            })
+        })
+        .collect();
+    movers.sort_by(|a, b| b.change_24h_percent.abs().total_cmp(&a.change_24h_percent.abs()));
+    movers
+}
+
+/// Prices and risk for watched tokens
+#[async_trait::async_trait]
+pub trait WatchlistMarket: Send + Sync {
+    /// Quotes by mint; mints without a price are left out
+    async fn quotes(&self, mints: &[String]) -> Result<HashMap<String, WatchQuote>>;
+    /// `None` when the token can't be assessed
+    async fn risk(&self, mint: &str) -> Option<RiskAssessment>;
+}
+
+/// Jupiter prices, plus risk from the token API when one is configured
+pub struct JupiterWatchlistMarket {
+    prices: Arc<JupiterPriceV3Client>,
+    tokens: Option<Arc<JupiterTokenV2Client>>,
+}
+
+impl JupiterWatchlistMarket {
+    pub fn new(prices: Arc<JupiterPriceV3Client>) -> Self {
+        Self { prices, tokens: None }
+    }
+
+    pub fn with_token_client(mut self, tokens: Arc<JupiterTokenV2Client>) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WatchlistMarket for JupiterWatchlistMarket {
+    async fn quotes(&self, mints: &[String]) -> Result<HashMap<String, WatchQuote>> {
+        let response = self.prices.get_prices(mints.to_vec()).await?;
+        Ok(response.prices.iter()
+            .map(|(mint, data)| {
+                let quote = WatchQuote { usd_price: data.usd_price, change_24h_percent: data.price_change_percent_24h() };
+                (mint.clone(), quote)
+            })
+            .collect())
+    }
+
+    async fn risk(&self, mint: &str) -> Option<RiskAssessment> {
+        match self.tokens.as_ref()?.analyze_token(mint).await {
+            Ok(analytics) => Some(analytics.risk_assessment),
+            Err(e) => {
+                debug!("👀 No risk assessment for {}: {}", mint, e);
+                None
+            }
+        }
+    }
+}
+
+/// Where watchlists are kept between restarts
+#[async_trait::async_trait]
+pub trait WatchlistStorage: Send + Sync {
+    async fn load_all(&self) -> Result<Vec<UserWatchlist>>;
+    async fn save(&self, watchlist: &UserWatchlist) -> Result<()>;
+    async fn delete(&self, user_id: i64) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl WatchlistStorage for Database {
+    async fn load_all(&self) -> Result<Vec<UserWatchlist>> {
+        let mut watchlists = Vec::new();
+        for (user_id, data) in self.get_watchlists().await? {
+            match serde_json::from_str(&data) {
+                Ok(watchlist) => watchlists.push(watchlist),
+                Err(e) => warn!("👀 Skipping unreadable watchlist of {}: {}", user_id, e),
+            }
+        }
+        Ok(watchlists)
+    }
+
+    async fn save(&self, watchlist: &UserWatchlist) -> Result<()> {
+        let data = serde_json::to_string(watchlist)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize watchlist of {}: {}", watchlist.list.user_id, e)))?;
+        self.upsert_watchlist(watchlist.list.user_id, &data).await
+    }
+
+    async fn delete(&self, user_id: i64) -> Result<()> {
+        self.delete_watchlist(user_id).await
+    }
+}
+
+/// Per-user `/watch` lists and their daily digests
+pub struct WatchlistStore {
+    lists: RwLock<HashMap<i64, UserWatchlist>>,
+    market: Arc<dyn WatchlistMarket>,
+    storage: Option<Arc<dyn WatchlistStorage>>,
+}
+
+impl WatchlistStore {
+    pub fn new(market: Arc<dyn WatchlistMarket>) -> Self {
+        Self { lists: RwLock::new(HashMap::new()), market, storage: None }
+    }
+
+    pub fn with_storage(mut self, storage: Arc<dyn WatchlistStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Load stored watchlists; returns how many there were
+    pub async fn restore(&self) -> Result<usize> {
+        let Some(storage) = &self.storage else { return Ok(0) };
+        let stored = storage.load_all().await?;
+        let mut lists = self.lists.write().await;
+        for watchlist in stored {
+            lists.insert(watchlist.list.user_id, watchlist);
+        }
+        Ok(lists.len())
+    }
+
+    /// A user's watchlist, empty when they never added anything
+    pub async fn get(&self, user_id: i64) -> UserWatchlist {
+        self.lists.read().await.get(&user_id).cloned().unwrap_or_else(|| UserWatchlist::new(user_id))
+    }
+
+    /// Watch a token; false when it was already watched
+    pub async fn add(&self, user_id: i64, address: &str, symbol: &str) -> Result<bool> {
+        self.update(user_id, |watchlist| {
+            if watchlist.list.tokens.iter().any(|token| token.address == address) {
+                return Ok(false);
+            }
+            if watchlist.list.tokens.len() >= MAX_WATCHLIST_TOKENS {
+                return Err(BotError::validation(format!(
+                    "You can watch at most {} tokens; remove one with /watch remove <token>", MAX_WATCHLIST_TOKENS
+                )).into());
+            }
+            watchlist.list.tokens.push(WatchlistToken {
+                address: address.to_string(),
+                symbol: symbol.to_string(),
+                added_at: Utc::now(),
+                alert_price_above: None,
+                alert_price_below: None,
+                notes: None,
+            });
+            Ok(true)
+        }).await
+    }
+
+    /// Stop watching a token by mint or symbol; `None` when it wasn't watched
+    pub async fn remove(&self, user_id: i64, token: &str) -> Result<Option<WatchlistToken>> {
+        if !self.lists.read().await.contains_key(&user_id) {
+            return Ok(None);
+        }
+        self.update(user_id, |watchlist| {
+            let position = watchlist.list.tokens.iter()
+                .position(|watched| watched.address == token || watched.symbol.eq_ignore_ascii_case(token));
+            Ok(position.map(|position| watchlist.list.tokens.remove(position)))
+        }).await
+    }
+
+    /// Send the digest at `at` each day in `timezone`, or stop it with `None`
+    ///
+    /// A time already past today starts tomorrow rather than firing at once.
+    pub async fn set_digest(&self, user_id: i64, at: Option<NaiveTime>, timezone: Tz) -> Result<UserWatchlist> {
+        let local = Utc::now().with_timezone(&timezone);
+        self.update(user_id, |watchlist| {
+            watchlist.digest_at = at;
+            watchlist.last_digest = at.filter(|at| *at <= local.time()).map(|_| local.date_naive());
+            Ok(watchlist.clone())
+        }).await
+    }
+
+    /// Every watched token with its quote and risk badge, in the order added
+    pub async fn snapshot(&self, user_id: i64) -> Result<Vec<WatchedToken>> {
+        let tokens = self.get(user_id).await.list.tokens;
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mints: Vec<String> = tokens.iter().map(|token| token.address.clone()).collect();
+        let quotes = self.market.quotes(&mints).await?;
+        let risks = join_all(mints.iter().map(|mint| self.market.risk(mint))).await;
+
+        Ok(tokens.into_iter().zip(risks)
+            .map(|(token, risk)| WatchedToken { quote: quotes.get(&token.address).copied(), token, risk })
+            .collect())
+    }
+
+    /// The user's tokens that moved at least `DIGEST_MOVE_PERCENT` in 24h
+    pub async fn digest(&self, user_id: i64) -> Result<Vec<WatchlistMover>> {
+        let tokens = self.get(user_id).await.list.tokens;
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mints: Vec<String> = tokens.iter().map(|token| token.address.clone()).collect();
+        let quotes = self.market.quotes(&mints).await?;
+        Ok(movers(&tokens, &quotes, DIGEST_MOVE_PERCENT))
+    }
+
+    /// Deliver every digest due at `now`; returns how many were sent
+    ///
+    /// A day where nothing moved enough is marked done without a message.
+    pub async fn send_due_digests(
+        &self,
+        now: DateTime<Utc>,
+        timezones: &TimezoneManager,
+        preferences: &PreferenceStore,
+        delivery: &AlertDelivery,
+    ) -> usize {
+        let watchlists: Vec<UserWatchlist> = self.lists.read().await.values().cloned().collect();
+        let mut sent = 0;
+
+        for watchlist in watchlists {
+            let user_id = watchlist.list.user_id;
+            let timezone = timezones.get_user_timezone(user_id).await;
+            if !watchlist.digest_due(now, timezone) {
+                continue;
+            }
+            let movers = match self.digest(user_id).await {
+                Ok(movers) => movers,
+                Err(e) => {
+                    // Left due, so the next check tries again
+                    warn!("👀 Watchlist digest for {} unavailable: {}", user_id, e);
+                    continue;
+                }
+            };
+
+            if !movers.is_empty() {
+                let lang = preferences.language(user_id, None).await;
+                let payload = Self::digest_payload(user_id, &movers, now, &lang);
+                let statuses = delivery.deliver(&delivery.user_methods(user_id).await, &payload).await;
+                if statuses.values().any(|status| matches!(status, DeliveryStatus::Sent)) {
+                    sent += 1;
+                }
+            }
+
+            let today = now.with_timezone(&timezone).date_naive();
+            if let Err(e) = self.update(user_id, |watchlist| {
+                watchlist.last_digest = Some(today);
+                Ok(())
+            }).await {
+                warn!("👀 Failed to record the digest for {}: {}", user_id, e);
+            }
+        }
+
+        if sent > 0 {
+            info!("👀 Sent {} watchlist digests", sent);
+        }
+        sent
+    }
+
+    fn digest_payload(user_id: i64, movers: &[WatchlistMover], now: DateTime<Utc>, lang: &str) -> AlertPayload {
+        let lines: Vec<String> = movers.iter()
+            .map(|mover| format!(
+                "{} {} {} · {}",
+                if mover.change_24h_percent > 0.0 { "🟢" } else { "🔴" },
+                mover.symbol,
+                fmt_number(lang, mover.change_24h_percent, NumberKind::Change),
+                fmt_number(lang, mover.usd_price, NumberKind::Usd)
+            ))
+            .collect();
+
+        AlertPayload {
+            kind: AlertKind::WatchlistDigest,
+            alert_id: format!("watchlist-{}-{}", user_id, now.format("%Y%m%d")),
+            user_id,
+            title: "Watchlist digest".to_string(),
+            message: format!(
+                "👀 Watchlist digest\n\nMoved {}% or more in 24h:\n{}\n\n/watch for the full list",
+                DIGEST_MOVE_PERCENT,
+                lines.join("\n")
+            ),
+            symbol: movers.iter().map(|mover| mover.symbol.as_str()).collect::<Vec<_>>().join(","),
+            price: None,
+            condition: None,
+            triggered_at: now,
+        }
+    }
+
+    /// Drop a user's watchlist; returns how many tokens were on it
+    pub async fn forget_user(&self, user_id: i64) -> usize {
+        let removed = self.lists.write().await.remove(&user_id);
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.delete(user_id).await {
+                warn!("👀 Failed to delete the stored watchlist of {}: {}", user_id, e);
+            }
+        }
+        removed.map(|watchlist| watchlist.list.tokens.len()).unwrap_or(0)
+    }
+
+    /// Edit a user's watchlist and persist it; nothing is stored when `edit` fails
+    async fn update<T, F>(&self, user_id: i64, edit: F) -> Result<T>
+    where
+        F: FnOnce(&mut UserWatchlist) -> Result<T>,
+    {
+        let mut lists = self.lists.write().await;
+        let mut watchlist = lists.get(&user_id).cloned().unwrap_or_else(|| UserWatchlist::new(user_id));
+        let outcome = edit(&mut watchlist)?;
+        watchlist.list.last_updated = Utc::now();
+        if let Some(storage) = &self.storage {
+            storage.save(&watchlist).await?;
+        }
+        lists.insert(user_id, watchlist);
+        Ok(outcome)
+    }
+}
@@ -1,7 +1,11 @@
 pub mod manager;
 pub mod strategies;
 pub mod redis_manager;
+pub mod session_store;
 
 pub use manager::CacheManager;
 pub use strategies::{CacheStrategy, TtlCache, LruCache};
-pub use redis_manager::{RedisManager, RedisConfig, CachePattern, SessionData};
\ No newline at end of file
+pub use redis_manager::{RedisManager, RedisConfig, CachePattern, SessionData};
+pub use session_store::{
+    connect_session_store, InMemorySessionStore, RedisSessionStore, SessionStore, SessionStoreExt, Versioned,
+};
\ No newline at end of file
@@ -0,0 +1,311 @@
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::errors::{BotError, Result};
+use crate::utils::{Config, SessionBackend};
+
+/// Prefix for every key the Redis backend writes
+const DEFAULT_KEY_PREFIX: &str = "banshie:session:";
+/// How often the in-memory backend sweeps expired keys
+const PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Writes `data` and bumps the version, returning the new version
+const SET_SCRIPT: &str = r"
+local version = redis.call('HINCRBY', KEYS[1], 'version', 1)
+redis.call('HSET', KEYS[1], 'data', ARGV[1])
+redis.call('PEXPIRE', KEYS[1], ARGV[2])
+return version
+";
+
+/// As `SET_SCRIPT`, but only while the version is still ARGV[1] ('' for a missing key); 0 otherwise
+const COMPARE_AND_SET_SCRIPT: &str = r"
+local current = redis.call('HGET', KEYS[1], 'version')
+if (current or '') ~= ARGV[1] then return 0 end
+local version = redis.call('HINCRBY', KEYS[1], 'version', 1)
+redis.call('HSET', KEYS[1], 'data', ARGV[2])
+redis.call('PEXPIRE', KEYS[1], ARGV[3])
+return version
+";
+
+const TAKE_SCRIPT: &str = r"
+local data = redis.call('HGET', KEYS[1], 'data')
+if data then redis.call('DEL', KEYS[1]) end
+return data
+";
+
+const INCREMENT_SCRIPT: &str = r"
+local count = redis.call('INCR', KEYS[1])
+if count == 1 then redis.call('PEXPIRE', KEYS[1], ARGV[1]) end
+return count
+";
+
+/// A stored value and the version `compare_and_set` checks against
+#[derive(Debug, Clone, PartialEq)]
+pub struct Versioned<T> {
+    pub value: T,
+    pub version: u64,
+}
+
+/// Short-lived per-user state shared by every bot instance
+///
+/// Keys expire after their TTL. Versions start at 1 and go up with each
+/// write, so a writer can tell whether someone else wrote since it read.
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<Versioned<Value>>>;
+
+    /// Write unconditionally; the new version
+    async fn set(&self, key: &str, value: Value, ttl: Duration) -> Result<u64>;
+
+    /// Write only while the key is at `expected` (`None`: doesn't exist); the new version when written
+    async fn compare_and_set(&self, key: &str, expected: Option<u64>, value: Value, ttl: Duration) -> Result<Option<u64>>;
+
+    /// Remove and return the value; of concurrent takes only one gets it
+    async fn take(&self, key: &str) -> Result<Option<Value>>;
+
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// Bump a counter, starting its TTL with the first increment
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64>;
+}
+
+/// Typed access on top of any `SessionStore`
+#[async_trait]
+pub trait SessionStoreExt: SessionStore {
+    async fn get_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        self.get(key).await?.map(|stored| decode(key, stored.value)).transpose()
+    }
+
+    async fn put<T: Serialize + Sync>(&self, key: &str, value: &T, ttl: Duration) -> Result<u64> {
+        self.set(key, encode(key, value)?, ttl).await
+    }
+
+    async fn take_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        self.take(key).await?.map(|value| decode(key, value)).transpose()
+    }
+
+    /// Claim `key` for `ttl`; true only for the first caller across all instances
+    async fn claim(&self, key: &str, ttl: Duration) -> Result<bool> {
+        Ok(self.compare_and_set(key, None, Value::Bool(true), ttl).await?.is_some())
+    }
+}
+
+impl<S: SessionStore + ?Sized> SessionStoreExt for S {}
+
+fn encode<T: Serialize>(key: &str, value: &T) -> Result<Value> {
+    serde_json::to_value(value).map_err(|e| BotError::internal(format!("Couldn't encode session {}: {}", key, e)).into())
+}
+
+fn decode<T: DeserializeOwned>(key: &str, value: Value) -> Result<T> {
+    serde_json::from_value(value).map_err(|e| BotError::internal(format!("Couldn't decode session {}: {}", key, e)).into())
+}
+
+/// The configured backend; Redis needs `redis_url`
+pub async fn connect_session_store(config: &Config) -> Result<Arc<dyn SessionStore>> {
+    match (&config.session_backend, &config.redis_url) {
+        (SessionBackend::Memory, _) => Ok(Arc::new(InMemorySessionStore::default())),
+        (SessionBackend::Redis, Some(url)) => Ok(Arc::new(RedisSessionStore::connect(url).await?)),
+        (SessionBackend::Redis, None) => Err(BotError::config("SESSION_BACKEND=redis needs REDIS_URL".to_string()).into()),
+    }
+}
+
+struct Entry {
+    value: Value,
+    version: u64,
+    expires_at: Instant,
+}
+
+impl Entry {
+    fn live(&self, now: Instant) -> bool {
+        self.expires_at > now
+    }
+}
+
+struct MemoryState {
+    entries: HashMap<String, Entry>,
+    last_purge: Instant,
+}
+
+/// Single-instance backend; state is lost on restart
+pub struct InMemorySessionStore {
+    state: RwLock<MemoryState>,
+}
+
+impl Default for InMemorySessionStore {
+    fn default() -> Self {
+        Self {
+            state: RwLock::new(MemoryState { entries: HashMap::new(), last_purge: Instant::now() }),
+        }
+    }
+}
+
+impl InMemorySessionStore {
+    /// Write `value` at the version after `current`
+    fn write(state: &mut MemoryState, key: &str, current: Option<u64>, value: Value, ttl: Duration) -> u64 {
+        let now = Instant::now();
+        if now.duration_since(state.last_purge) >= PURGE_INTERVAL {
+            state.entries.retain(|_, entry| entry.live(now));
+            state.last_purge = now;
+        }
+        let version = current.unwrap_or(0) + 1;
+        state.entries.insert(key.to_string(), Entry { value, version, expires_at: now + ttl });
+        version
+    }
+
+    fn live_version(state: &MemoryState, key: &str) -> Option<u64> {
+        let now = Instant::now();
+        state.entries.get(key).filter(|entry| entry.live(now)).map(|entry| entry.version)
+    }
+}
+
+#[async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn get(&self, key: &str) -> Result<Option<Versioned<Value>>> {
+        let now = Instant::now();
+        Ok(self.state.read().await.entries.get(key)
+            .filter(|entry| entry.live(now))
+            .map(|entry| Versioned { value: entry.value.clone(), version: entry.version }))
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Duration) -> Result<u64> {
+        let mut state = self.state.write().await;
+        let current = Self::live_version(&state, key);
+        Ok(Self::write(&mut state, key, current, value, ttl))
+    }
+
+    async fn compare_and_set(&self, key: &str, expected: Option<u64>, value: Value, ttl: Duration) -> Result<Option<u64>> {
+        let mut state = self.state.write().await;
+        let current = Self::live_version(&state, key);
+        if current != expected {
+            return Ok(None);
+        }
+        Ok(Some(Self::write(&mut state, key, current, value, ttl)))
+    }
+
+    async fn take(&self, key: &str) -> Result<Option<Value>> {
+        let now = Instant::now();
+        Ok(self.state.write().await.entries.remove(key)
+            .filter(|entry| entry.live(now))
+            .map(|entry| entry.value))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.state.write().await.entries.remove(key);
+        Ok(())
+    }
+
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64> {
+        let mut state = self.state.write().await;
+        let now = Instant::now();
+        if let Some(entry) = state.entries.get_mut(key).filter(|entry| entry.live(now)) {
+            let count = entry.value.as_u64().unwrap_or(0) + 1;
+            entry.value = Value::from(count);
+            entry.version += 1;
+            return Ok(count);
+        }
+        Self::write(&mut state, key, None, Value::from(1u64), ttl);
+        Ok(1)
+    }
+}
+
+/// Backend shared by every instance pointed at the same Redis
+///
+/// Each session is a hash of `data` (JSON) and `version`; writes run as Lua
+/// scripts so a check and its write can't interleave with another instance.
+pub struct RedisSessionStore {
+    conn: ConnectionManager,
+    prefix: String,
+}
+
+impl RedisSessionStore {
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| BotError::config(format!("Invalid REDIS_URL: {}", e)))?;
+        let conn = ConnectionManager::new(client).await.map_err(redis_error)?;
+        info!("🗄️ Session store connected to Redis");
+        Ok(Self { conn, prefix: DEFAULT_KEY_PREFIX.to_string() })
+    }
+
+    /// Keep deployments that share a Redis apart
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    async fn run_script<T: redis::FromRedisValue>(&self, script: &str, key: &str, args: &[String]) -> Result<T> {
+        let mut conn = self.conn.clone();
+        let script = redis::Script::new(script);
+        let mut invocation = script.key(self.key(key));
+        for arg in args {
+            invocation.arg(arg);
+        }
+        Ok(invocation.invoke_async(&mut conn).await.map_err(redis_error)?)
+    }
+}
+
+fn ttl_millis(ttl: Duration) -> String {
+    // PEXPIRE 0 would delete the key before the caller could read it back
+    ttl.as_millis().max(1).to_string()
+}
+
+fn redis_error(e: redis::RedisError) -> BotError {
+    BotError::external_api(format!("Redis session store: {}", e))
+}
+
+fn parse_json(key: &str, data: String) -> Result<Value> {
+    serde_json::from_str(&data).map_err(|e| BotError::internal(format!("Corrupt session {}: {}", key, e)).into())
+}
+
+#[async_trait]
+impl SessionStore for RedisSessionStore {
+    async fn get(&self, key: &str) -> Result<Option<Versioned<Value>>> {
+        let mut conn = self.conn.clone();
+        let (version, data): (Option<u64>, Option<String>) = redis::cmd("HMGET")
+            .arg(self.key(key))
+            .arg("version")
+            .arg("data")
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_error)?;
+        match (version, data) {
+            (Some(version), Some(data)) => Ok(Some(Versioned { value: parse_json(key, data)?, version })),
+            _ => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Duration) -> Result<u64> {
+        self.run_script(SET_SCRIPT, key, &[value.to_string(), ttl_millis(ttl)]).await
+    }
+
+    async fn compare_and_set(&self, key: &str, expected: Option<u64>, value: Value, ttl: Duration) -> Result<Option<u64>> {
+        let expected = expected.map(|v| v.to_string()).unwrap_or_default();
+        let version: u64 = self.run_script(COMPARE_AND_SET_SCRIPT, key, &[expected, value.to_string(), ttl_millis(ttl)]).await?;
+        Ok((version > 0).then_some(version))
+    }
+
+    async fn take(&self, key: &str) -> Result<Option<Value>> {
+        let data: Option<String> = self.run_script(TAKE_SCRIPT, key, &[]).await?;
+        data.map(|data| parse_json(key, data)).transpose()
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let _: () = redis::cmd("DEL").arg(self.key(key)).query_async(&mut conn).await.map_err(redis_error)?;
+        Ok(())
+    }
+
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64> {
+        self.run_script(INCREMENT_SCRIPT, key, &[ttl_millis(ttl)]).await
+    }
+}
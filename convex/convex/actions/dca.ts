@@ -24,6 +24,12 @@ export const executeScheduledStrategies = action({
 
     // Execute each strategy
     for (const strategy of strategies) {
+      // Mirrored strategies run in the native bot; executing here too would double-buy
+      if (strategy.mirror) {
+        results.skipped++;
+        continue;
+      }
+
       try {
         // Check if conditions are met
         const shouldExecute = await checkStrategyConditions(ctx, strategy);
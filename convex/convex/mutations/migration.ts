@@ -0,0 +1,29 @@
+import { mutation } from "../_generated/server";
+import { v } from "convex/values";
+
+// Tag a record as copied into the native bot so sync and crons leave it alone
+export const markMirrored = mutation({
+  args: {
+    table: v.union(
+      v.literal("users"),
+      v.literal("wallets"),
+      v.literal("alerts"),
+      v.literal("dcaStrategies")
+    ),
+    id: v.string(),
+    nativeId: v.string(),
+  },
+  handler: async (ctx, args) => {
+    const id = ctx.db.normalizeId(args.table, args.id);
+    if (!id) throw new Error(`Invalid ${args.table} id`);
+
+    const record = await ctx.db.get(id);
+    if (!record) throw new Error("Record not found");
+
+    await ctx.db.patch(id, {
+      mirror: { nativeId: args.nativeId, mirroredAt: Date.now() },
+    });
+
+    return id;
+  },
+});
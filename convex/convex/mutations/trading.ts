@@ -390,6 +390,44 @@ export const recordTrade = mutation({
       percentage: v.number(),
       holdTime: v.number(),
     })),
+    report: v.optional(v.object({
+      quotedAt: v.optional(v.number()),
+      executedAt: v.optional(v.number()),
+      latencyMs: v.optional(v.number()),
+      route: v.optional(v.object({
+        venues: v.array(v.string()),
+        hops: v.number(),
+        label: v.string(),
+      })),
+      priceImpactPct: v.optional(v.number()),
+      quotedPrice: v.optional(v.string()),
+      realizedPrice: v.optional(v.string()),
+      slippageBps: v.optional(v.number()),
+      broadcastAttempts: v.number(),
+      blockhashRefreshed: v.boolean(),
+      fees: v.optional(v.object({
+        networkSol: v.string(),
+        priorityLamports: v.number(),
+        platformSol: v.string(),
+        transferFeeTokens: v.string(),
+      })),
+      computeUnitLimit: v.optional(v.number()),
+      computeUnitsConsumed: v.optional(v.number()),
+      simulated: v.boolean(),
+      idempotencyKey: v.optional(v.string()),
+      sandwich: v.optional(v.object({
+        frontRunSignature: v.string(),
+        backRunSignature: v.string(),
+        attacker: v.string(),
+        pool: v.string(),
+        lossQuote: v.string(),
+        lossPct: v.number(),
+      })),
+      partialFill: v.optional(v.object({
+        requestedIn: v.string(),
+        filledIn: v.string(),
+      })),
+    })),
   },
   handler: async (ctx, args) => {
     // Record the trade
@@ -0,0 +1,85 @@
+import { query } from "../_generated/server";
+import { v } from "convex/values";
+
+// Everything the native bot needs to import a Convex-only user
+export const userSnapshot = query({
+  args: { telegramId: v.number() },
+  handler: async (ctx, args) => {
+    const user = await ctx.db
+      .query("users")
+      .withIndex("by_telegram", (q) => q.eq("telegramId", args.telegramId))
+      .first();
+    if (!user) return null;
+
+    const wallets = await ctx.db
+      .query("wallets")
+      .withIndex("by_user", (q) => q.eq("userId", user._id))
+      .collect();
+
+    const alerts = await ctx.db
+      .query("alerts")
+      .withIndex("by_user", (q) => q.eq("userId", user._id))
+      .collect();
+
+    const strategies = await ctx.db
+      .query("dcaStrategies")
+      .withIndex("by_user", (q) => q.eq("userId", user._id))
+      .collect();
+
+    return {
+      user: {
+        _id: user._id,
+        telegramId: user.telegramId,
+        username: user.username,
+        settings: user.settings,
+        mirror: user.mirror,
+      },
+      wallets: wallets.map((wallet) => ({
+        _id: wallet._id,
+        address: wallet.address,
+        type: wallet.type,
+        label: wallet.label,
+        isActive: wallet.isActive,
+        mirror: wallet.mirror,
+      })),
+      alerts: alerts.map((alert) => ({
+        _id: alert._id,
+        name: alert.name,
+        type: alert.type,
+        isActive: alert.isActive,
+        condition: alert.condition,
+        actions: alert.actions,
+        cooldown: alert.notification.cooldown,
+        expiresAt: alert.expiresAt,
+        mirror: alert.mirror,
+      })),
+      dcaStrategies: strategies.map((strategy) => ({
+        _id: strategy._id,
+        name: strategy.name,
+        isActive: strategy.isActive,
+        isPaused: strategy.isPaused,
+        tokenIn: strategy.config.tokenIn,
+        tokenOut: strategy.config.tokenOut,
+        amount: strategy.config.amount,
+        frequency: strategy.config.frequency,
+        maxInvestment: strategy.config.limits.maxInvestment,
+        maxExecutions: strategy.config.limits.maxExecutions,
+        endDate: strategy.config.limits.endDate,
+        mirror: strategy.mirror,
+      })),
+    };
+  },
+});
+
+// Telegram ids of users the native bot hasn't imported yet
+export const unmirroredUsers = query({
+  args: { limit: v.number() },
+  handler: async (ctx, args) => {
+    const users = await ctx.db
+      .query("users")
+      .filter((q) => q.eq(q.field("mirror"), undefined))
+      .take(args.limit);
+
+    return users.map((user) => user.telegramId);
+  },
+});
@@ -33,6 +33,11 @@ export default defineSchema({
     }),
     createdAt: v.number(),
     lastActive: v.number(),
+    // Set once the record has been copied into the native bot, which owns it from then on
+    mirror: v.optional(v.object({
+      nativeId: v.string(),
+      mirroredAt: v.number(),
+    })),
   })
     .index("by_telegram", ["telegramId"])
     .index("by_username", ["username"])
@@ -71,6 +76,11 @@ export default defineSchema({
       unrealizedPnL: v.string(),
     }),
     createdAt: v.number(),
+    // Set once the record has been copied into the native bot, which owns it from then on
+    mirror: v.optional(v.object({
+      nativeId: v.string(),
+      mirroredAt: v.number(),
+    })),
   })
     .index("by_user", ["userId"])
     .index("by_address", ["address"])
@@ -260,6 +270,11 @@ export default defineSchema({
     })),
     createdAt: v.number(),
     updatedAt: v.number(),
+    // Set once the record has been copied into the native bot, which owns it from then on
+    mirror: v.optional(v.object({
+      nativeId: v.string(),
+      mirroredAt: v.number(),
+    })),
   })
     .index("by_user", ["userId"])
     .index("by_user_active", ["userId", "isActive"])
@@ -364,6 +379,11 @@ export default defineSchema({
     metadata: v.any(),
     createdAt: v.number(),
     expiresAt: v.optional(v.number()),
+    // Set once the record has been copied into the native bot, which owns it from then on
+    mirror: v.optional(v.object({
+      nativeId: v.string(),
+      mirroredAt: v.number(),
+    })),
   })
     .index("by_user", ["userId"])
     .index("by_user_active", ["userId", "isActive"])
@@ -410,6 +430,45 @@ export default defineSchema({
       percentage: v.number(),
       holdTime: v.number(),
     })),
+    // Execution report from the bot (route, timing, retries); absent on older trades
+    report: v.optional(v.object({
+      quotedAt: v.optional(v.number()),
+      executedAt: v.optional(v.number()),
+      latencyMs: v.optional(v.number()),
+      route: v.optional(v.object({
+        venues: v.array(v.string()),
+        hops: v.number(),
+        label: v.string(),
+      })),
+      priceImpactPct: v.optional(v.number()),
+      quotedPrice: v.optional(v.string()),
+      realizedPrice: v.optional(v.string()),
+      slippageBps: v.optional(v.number()),
+      broadcastAttempts: v.number(),
+      blockhashRefreshed: v.boolean(),
+      fees: v.optional(v.object({
+        networkSol: v.string(),
+        priorityLamports: v.number(),
+        platformSol: v.string(),
+        transferFeeTokens: v.string(),
+      })),
+      computeUnitLimit: v.optional(v.number()),
+      computeUnitsConsumed: v.optional(v.number()),
+      simulated: v.boolean(),
+      idempotencyKey: v.optional(v.string()),
+      sandwich: v.optional(v.object({
+        frontRunSignature: v.string(),
+        backRunSignature: v.string(),
+        attacker: v.string(),
+        pool: v.string(),
+        lossQuote: v.string(),
+        lossPct: v.number(),
+      })),
+      partialFill: v.optional(v.object({
+        requestedIn: v.string(),
+        filledIn: v.string(),
+      })),
+    })),
     metadata: v.any(),
     timestamp: v.number(),
   })
@@ -1,5 +1,6 @@
 use convex_integration::{ConvexConfig, ConvexIntegrationService};
 use std::env;
+use std::time::Duration;
 use anyhow::Result;
 
 #[tokio::main]
@@ -21,6 +22,13 @@ async fn main() -> Result<()> {
             .unwrap_or(8080),
         webhook_path: env::var("WEBHOOK_PATH")
             .unwrap_or_else(|_| "/webhook".to_string()),
+        shutdown_timeout: Duration::from_secs(
+            env::var("SHUTDOWN_TIMEOUT_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .unwrap_or(30),
+        ),
+        readiness_url: env::var("BOT_READINESS_URL").ok().filter(|url| !url.is_empty()),
     };
 
     println!("🚀 Starting Convex Integration Service");
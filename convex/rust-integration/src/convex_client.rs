@@ -3,15 +3,18 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::env;
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, timeout, Duration};
 use anyhow::{anyhow, Result};
 
+use crate::subscription::{QuerySubscription, ReconnectBackoff, SYNC_PROTOCOL_VERSION};
+
 /// HTTP client for communicating with Convex backend
 #[derive(Clone)]
 pub struct ConvexClient {
     client: Client,
     base_url: String,
     site_url: String,
+    timeouts: TimeoutConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -37,6 +40,53 @@ pub struct PortfolioSummary {
     pub position_count: u32,
 }
 
+/// One holding from `queries/portfolio:getPortfolio`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioPosition {
+    pub token_mint: String,
+    pub symbol: String,
+    pub name: String,
+    pub market_value: String,
+    pub pnl: PositionPnl,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionPnl {
+    pub amount: String,
+    pub percentage: f64,
+    pub is_profit: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PortfolioPositions {
+    positions: Vec<PortfolioPosition>,
+}
+
+/// Token row as returned by `queries/prices:searchTokens` and `getTrending`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenQuote {
+    pub mint: String,
+    pub symbol: String,
+    pub name: String,
+    pub price: String,
+    #[serde(rename = "change24h")]
+    pub change_24h: f64,
+    #[serde(rename = "volume24h", default)]
+    pub volume_24h: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenSearchResponse {
+    results: Vec<TokenQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrendingResponse {
+    trending: Vec<TokenQuote>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TradingSignal {
     pub token_mint: String,
@@ -58,6 +108,101 @@ pub struct OrderRequest {
     pub slippage: Option<f64>,
 }
 
+/// Per-call timeouts and retry budget
+///
+/// Mirrors the bot's `utils::timeout::TimeoutConfig`. Queries get
+/// `non_critical_timeout`, mutations `default_timeout` and actions
+/// `critical_timeout`, since media generation can take a while.
+#[derive(Debug, Clone)]
+pub struct TimeoutConfig {
+    pub default_timeout: Duration,
+    pub critical_timeout: Duration,
+    pub non_critical_timeout: Duration,
+    /// Retries after the first attempt, for calls that are safe to repeat
+    pub retry_count: u32,
+    pub backoff_multiplier: f64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            default_timeout: Duration::from_secs(30),
+            critical_timeout: Duration::from_secs(60),
+            non_critical_timeout: Duration::from_secs(15),
+            retry_count: 3,
+            backoff_multiplier: 2.0,
+        }
+    }
+}
+
+/// Delay before the first retry; later ones grow by `backoff_multiplier`
+const FIRST_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+pub type ConvexResult<T> = std::result::Result<T, ConvexError>;
+
+/// Why a Convex call failed
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ConvexError {
+    #[error("Convex function {0} not found")]
+    NotFound(String),
+    #[error("Convex rejected our credentials: {0}")]
+    Unauthorized(String),
+    #[error("Convex rate limited the request")]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("Convex returned {status}: {message}")]
+    ServerError { status: u16, message: String },
+    #[error("Convex did not answer within {0:?}")]
+    Timeout(Duration),
+    #[error("Could not reach Convex: {0}")]
+    Network(String),
+    /// The function ran and threw, or refused its arguments
+    #[error("Convex error: {0}")]
+    Function(String),
+    #[error("Unexpected Convex response: {0}")]
+    InvalidResponse(String),
+}
+
+impl ConvexError {
+    /// Transient failures a repeat of the same call may get past
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited { .. } | Self::ServerError { .. } | Self::Timeout(_) | Self::Network(_)
+        )
+    }
+
+    /// Short reason to show a Telegram user
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::NotFound(_) => "nothing found".to_string(),
+            Self::Unauthorized(_) => "the bot is not authorised with the backend".to_string(),
+            Self::RateLimited { .. } => "too many requests, please try again in a minute".to_string(),
+            Self::ServerError { .. } | Self::Timeout(_) | Self::Network(_) => {
+                "the backend is unavailable right now, please try again shortly".to_string()
+            }
+            Self::Function(message) => message.clone(),
+            Self::InvalidResponse(_) => "the backend sent an unexpected response".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CallKind {
+    Query,
+    Mutation,
+    Action,
+}
+
+impl CallKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Query => "query",
+            Self::Mutation => "mutation",
+            Self::Action => "action",
+        }
+    }
+}
+
 impl ConvexClient {
     /// Create a new Convex client
     pub fn new() -> Result<Self> {
@@ -66,119 +211,188 @@ impl ConvexClient {
         let site_url = env::var("CONVEX_SITE_URL")
             .unwrap_or_else(|_| "https://your-convex-app.convex.cloud".to_string());
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()?;
+        // Timeouts are applied per call from `timeouts`
+        let client = Client::builder().build()?;
 
         Ok(Self {
             client,
             base_url,
             site_url,
+            timeouts: TimeoutConfig::default(),
         })
     }
 
-    /// Execute a Convex query
-    pub async fn query<T>(&self, function_name: &str, args: Value) -> Result<T>
+    pub fn with_timeouts(mut self, timeouts: TimeoutConfig) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Execute a Convex query, retrying transient failures
+    pub async fn query<T>(&self, function_name: &str, args: Value) -> ConvexResult<T>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let url = format!("{}/api/query", self.base_url);
-        
-        let payload = json!({
-            "path": function_name,
-            "args": args,
-            "format": "json"
-        });
-
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!("Query failed with status: {}", response.status()));
-        }
+        self.call(CallKind::Query, function_name, args, true).await
+    }
 
-        let result: Value = response.json().await?;
-        
-        // Handle Convex response format
-        if let Some(error) = result.get("error") {
-            return Err(anyhow!("Convex error: {}", error));
-        }
+    /// Execute a Convex mutation once
+    ///
+    /// Use [`Self::mutation_with_key`] when the mutation should survive retries.
+    pub async fn mutation<T>(&self, function_name: &str, args: Value) -> ConvexResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.call(CallKind::Mutation, function_name, args, false).await
+    }
 
-        serde_json::from_value(result)
-            .map_err(|e| anyhow!("Failed to deserialize response: {}", e))
+    /// Execute a Convex mutation, retrying transient failures
+    ///
+    /// The key is sent as `idempotencyKey`; the mutation must use it to
+    /// ignore repeats, since a timed out attempt may still have committed.
+    pub async fn mutation_with_key<T>(&self, function_name: &str, args: Value, idempotency_key: &str) -> ConvexResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.call(CallKind::Mutation, function_name, Self::keyed(args, idempotency_key), true).await
     }
 
-    /// Execute a Convex mutation
-    pub async fn mutation<T>(&self, function_name: &str, args: Value) -> Result<T>
+    /// Execute a Convex action once
+    pub async fn action<T>(&self, function_name: &str, args: Value) -> ConvexResult<T>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let url = format!("{}/api/mutation", self.base_url);
-        
-        let payload = json!({
-            "path": function_name,
-            "args": args,
-            "format": "json"
-        });
+        self.call(CallKind::Action, function_name, args, false).await
+    }
 
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await?;
+    /// Execute a Convex action, retrying transient failures under one `idempotencyKey`
+    pub async fn action_with_key<T>(&self, function_name: &str, args: Value, idempotency_key: &str) -> ConvexResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.call(CallKind::Action, function_name, Self::keyed(args, idempotency_key), true).await
+    }
 
-        if !response.status().is_success() {
-            return Err(anyhow!("Mutation failed with status: {}", response.status()));
+    fn keyed(mut args: Value, idempotency_key: &str) -> Value {
+        if let Some(args) = args.as_object_mut() {
+            args.insert("idempotencyKey".to_string(), json!(idempotency_key));
         }
+        args
+    }
 
-        let result: Value = response.json().await?;
-        
-        if let Some(error) = result.get("error") {
-            return Err(anyhow!("Convex error: {}", error));
-        }
+    async fn call<T>(&self, kind: CallKind, function_name: &str, args: Value, retry: bool) -> ConvexResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let attempts = if retry { self.timeouts.retry_count + 1 } else { 1 };
+        let mut delay = FIRST_RETRY_DELAY;
+        let mut attempt = 1;
 
-        serde_json::from_value(result)
-            .map_err(|e| anyhow!("Failed to deserialize response: {}", e))
+        loop {
+            let error = match self.send(kind, function_name, &args).await {
+                Ok(result) => return Ok(result),
+                Err(e) => e,
+            };
+            if attempt >= attempts || !error.is_retryable() {
+                return Err(error);
+            }
+
+            let wait = match &error {
+                ConvexError::RateLimited { retry_after: Some(after) } => (*after).max(delay),
+                _ => delay,
+            };
+            sleep(wait).await;
+            delay = delay.mul_f64(self.timeouts.backoff_multiplier);
+            attempt += 1;
+        }
     }
 
-    /// Execute a Convex action
-    pub async fn action<T>(&self, function_name: &str, args: Value) -> Result<T>
+    /// One attempt, bounded by the timeout for its kind
+    async fn send<T>(&self, kind: CallKind, function_name: &str, args: &Value) -> ConvexResult<T>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let url = format!("{}/api/action", self.base_url);
-        
+        let limit = match kind {
+            CallKind::Query => self.timeouts.non_critical_timeout,
+            CallKind::Mutation => self.timeouts.default_timeout,
+            CallKind::Action => self.timeouts.critical_timeout,
+        };
+        let url = format!("{}/api/{}", self.base_url, kind.as_str());
+
         let payload = json!({
             "path": function_name,
             "args": args,
             "format": "json"
         });
 
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await?;
+        let attempt = async {
+            let response = self.client
+                .post(&url)
+                .json(&payload)
+                .send()
+                .await
+                .map_err(|e| ConvexError::Network(e.to_string()))?;
+
+            let status = response.status();
+            if !status.is_success() {
+                return Err(Self::status_error(function_name, response).await);
+            }
 
-        if !response.status().is_success() {
-            return Err(anyhow!("Action failed with status: {}", response.status()));
-        }
+            response.json::<Value>().await
+                .map_err(|e| ConvexError::InvalidResponse(e.to_string()))
+        };
+        let result = timeout(limit, attempt).await
+            .map_err(|_| ConvexError::Timeout(limit))??;
 
-        let result: Value = response.json().await?;
-        
+        // Handle Convex response format
         if let Some(error) = result.get("error") {
-            return Err(anyhow!("Convex error: {}", error));
+            return Err(ConvexError::Function(error.as_str().map(str::to_string).unwrap_or_else(|| error.to_string())));
         }
 
         serde_json::from_value(result)
-            .map_err(|e| anyhow!("Failed to deserialize response: {}", e))
+            .map_err(|e| ConvexError::InvalidResponse(format!("{}: {}", function_name, e)))
+    }
+
+    async fn status_error(function_name: &str, response: reqwest::Response) -> ConvexError {
+        let status = response.status();
+        let retry_after = response.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let message: String = response.text().await.unwrap_or_default().chars().take(200).collect();
+
+        match status.as_u16() {
+            404 => ConvexError::NotFound(function_name.to_string()),
+            401 | 403 => ConvexError::Unauthorized(message),
+            429 => ConvexError::RateLimited { retry_after },
+            code if code >= 500 => ConvexError::ServerError { status: code, message },
+            _ => ConvexError::Function(message),
+        }
+    }
+
+    /// Subscribe to a Convex query
+    ///
+    /// The stream yields the current result, then every change the server pushes.
+    /// Failed runs and dropped connections arrive as `Err` items while the
+    /// subscription reconnects with backoff; drop the stream to cancel it.
+    pub fn subscribe_query(&self, function_name: &str, args: Value) -> Result<QuerySubscription> {
+        Ok(QuerySubscription::spawn(self.sync_url()?, function_name, args, ReconnectBackoff::default()))
+    }
+
+    /// Websocket endpoint of the deployment that serves `query`
+    fn sync_url(&self) -> Result<String> {
+        let (scheme, host) = if let Some(host) = self.base_url.strip_prefix("https://") {
+            ("wss", host)
+        } else if let Some(host) = self.base_url.strip_prefix("http://") {
+            ("ws", host)
+        } else {
+            return Err(anyhow!("Unsupported Convex URL: {}", self.base_url));
+        };
+        Ok(format!("{}://{}/api/{}/sync", scheme, host.trim_end_matches('/'), SYNC_PROTOCOL_VERSION))
     }
 
     // User Management
-    pub async fn get_user_by_telegram_id(&self, telegram_id: i64) -> Result<Option<UserProfile>> {
+    pub async fn get_user_by_telegram_id(&self, telegram_id: i64) -> ConvexResult<Option<UserProfile>> {
         let args = json!({
             "telegramId": telegram_id
         });
@@ -186,7 +400,7 @@ impl ConvexClient {
         self.query("queries/users:getUserByTelegramId", args).await
     }
 
-    pub async fn create_or_update_user(&self, telegram_id: i64, username: &str) -> Result<String> {
+    pub async fn create_or_update_user(&self, telegram_id: i64, username: &str) -> ConvexResult<String> {
         let args = json!({
             "telegramId": telegram_id,
             "username": username,
@@ -202,7 +416,7 @@ impl ConvexClient {
     }
 
     // Portfolio Management
-    pub async fn get_portfolio(&self, user_id: &str) -> Result<PortfolioSummary> {
+    pub async fn get_portfolio(&self, user_id: &str) -> ConvexResult<PortfolioSummary> {
         let args = json!({
             "userId": user_id
         });
@@ -210,7 +424,22 @@ impl ConvexClient {
         self.query("queries/portfolio:getPortfolio", args).await
     }
 
-    pub async fn sync_wallet_balances(&self, user_id: &str, wallet_address: &str) -> Result<Value> {
+    /// Positions in a portfolio, largest market value first
+    pub async fn get_portfolio_positions(&self, user_id: &str) -> ConvexResult<Vec<PortfolioPosition>> {
+        let args = json!({
+            "userId": user_id
+        });
+
+        let portfolio: PortfolioPositions = self.query("queries/portfolio:getPortfolio", args).await?;
+        Ok(portfolio.positions)
+    }
+
+    /// Live version of `get_portfolio`
+    pub fn subscribe_portfolio(&self, user_id: &str) -> Result<QuerySubscription> {
+        self.subscribe_query("queries/portfolio:getPortfolio", json!({ "userId": user_id }))
+    }
+
+    pub async fn sync_wallet_balances(&self, user_id: &str, wallet_address: &str) -> ConvexResult<Value> {
         let args = json!({
             "userId": user_id,
             "walletAddress": wallet_address
@@ -222,10 +451,10 @@ impl ConvexClient {
     // Trading
     pub async fn place_order(&self, order: OrderRequest) -> Result<String> {
         let args = serde_json::to_value(order)?;
-        self.mutation("mutations/trading:placeTrade", args).await
+        Ok(self.mutation("mutations/trading:placeTrade", args).await?)
     }
 
-    pub async fn get_order_status(&self, order_id: &str) -> Result<Value> {
+    pub async fn get_order_status(&self, order_id: &str) -> ConvexResult<Value> {
         let args = json!({
             "orderId": order_id
         });
@@ -234,7 +463,7 @@ impl ConvexClient {
     }
 
     // AI Signals
-    pub async fn get_latest_signals(&self, limit: u32) -> Result<Vec<TradingSignal>> {
+    pub async fn get_latest_signals(&self, limit: u32) -> ConvexResult<Vec<TradingSignal>> {
         let args = json!({
             "limit": limit
         });
@@ -242,7 +471,7 @@ impl ConvexClient {
         self.query("queries/ai:getLatestSignals", args).await
     }
 
-    pub async fn generate_signal(&self, token_mint: &str) -> Result<TradingSignal> {
+    pub async fn generate_signal(&self, token_mint: &str) -> ConvexResult<TradingSignal> {
         let args = json!({
             "tokenMint": token_mint
         });
@@ -251,7 +480,7 @@ impl ConvexClient {
     }
 
     // Price Data
-    pub async fn get_token_price(&self, token_mint: &str) -> Result<Value> {
+    pub async fn get_token_price(&self, token_mint: &str) -> ConvexResult<Value> {
         let args = json!({
             "mint": token_mint
         });
@@ -259,7 +488,29 @@ impl ConvexClient {
         self.query("queries/prices:getTokenPrice", args).await
     }
 
-    pub async fn update_prices(&self, tokens: Vec<&str>) -> Result<Value> {
+    /// Tokens whose symbol, name or mint match `query`, most relevant first
+    pub async fn search_tokens(&self, query: &str, limit: u32) -> ConvexResult<Vec<TokenQuote>> {
+        let args = json!({
+            "query": query,
+            "limit": limit
+        });
+
+        let response: TokenSearchResponse = self.query("queries/prices:searchTokens", args).await?;
+        Ok(response.results)
+    }
+
+    /// Trending tokens for a timeframe ("1h", "24h", "7d") ranked by a metric ("volume", "price", "mentions")
+    pub async fn get_trending_tokens(&self, timeframe: &str, metric: &str) -> ConvexResult<Vec<TokenQuote>> {
+        let args = json!({
+            "timeframe": timeframe,
+            "metric": metric
+        });
+
+        let response: TrendingResponse = self.query("queries/prices:getTrending", args).await?;
+        Ok(response.trending)
+    }
+
+    pub async fn update_prices(&self, tokens: Vec<&str>) -> ConvexResult<Value> {
         let args = json!({
             "tokens": tokens
         });
@@ -268,7 +519,7 @@ impl ConvexClient {
     }
 
     // DCA Strategies
-    pub async fn get_user_dca_strategies(&self, user_id: &str) -> Result<Vec<Value>> {
+    pub async fn get_user_dca_strategies(&self, user_id: &str) -> ConvexResult<Vec<Value>> {
         let args = json!({
             "userId": user_id
         });
@@ -276,7 +527,7 @@ impl ConvexClient {
         self.query("queries/dca:getUserStrategies", args).await
     }
 
-    pub async fn create_dca_strategy(&self, user_id: &str, token_mint: &str, amount: f64, frequency: &str) -> Result<String> {
+    pub async fn create_dca_strategy(&self, user_id: &str, token_mint: &str, amount: f64, frequency: &str) -> ConvexResult<String> {
         let args = json!({
             "userId": user_id,
             "fromMint": "So11111111111111111111111111111111111111112", // SOL
@@ -291,7 +542,7 @@ impl ConvexClient {
     }
 
     // Alerts
-    pub async fn create_price_alert(&self, user_id: &str, token_mint: &str, target_price: f64, condition: &str) -> Result<String> {
+    pub async fn create_price_alert(&self, user_id: &str, token_mint: &str, target_price: f64, condition: &str) -> ConvexResult<String> {
         let args = json!({
             "userId": user_id,
             "alertType": "price",
@@ -306,7 +557,7 @@ impl ConvexClient {
         self.mutation("mutations/alerts:createAlert", args).await
     }
 
-    pub async fn get_user_alerts(&self, user_id: &str) -> Result<Vec<Value>> {
+    pub async fn get_user_alerts(&self, user_id: &str) -> ConvexResult<Vec<Value>> {
         let args = json!({
             "userId": user_id
         });
@@ -315,7 +566,7 @@ impl ConvexClient {
     }
 
     // Analytics
-    pub async fn calculate_indicators(&self, token_mint: &str) -> Result<Value> {
+    pub async fn calculate_indicators(&self, token_mint: &str) -> ConvexResult<Value> {
         let args = json!({
             "tokenMint": token_mint,
             "periods": 100
@@ -331,30 +582,6 @@ impl ConvexClient {
             Err(_) => Ok(false),
         }
     }
-
-    /// Retry a function with exponential backoff
-    pub async fn retry_with_backoff<F, T, E>(&self, mut f: F, max_retries: u32) -> Result<T>
-    where
-        F: FnMut() -> Result<T, E>,
-        E: std::fmt::Display,
-    {
-        let mut retry_count = 0;
-        
-        loop {
-            match f() {
-                Ok(result) => return Ok(result),
-                Err(e) => {
-                    if retry_count >= max_retries {
-                        return Err(anyhow!("Max retries exceeded. Last error: {}", e));
-                    }
-                    
-                    let delay_ms = 1000 * (2_u64.pow(retry_count));
-                    sleep(Duration::from_millis(delay_ms)).await;
-                    retry_count += 1;
-                }
-            }
-        }
-    }
 }
 
 // Convenience functions for common operations
@@ -381,18 +608,13 @@ impl ConvexClient {
         Ok((user_id, portfolio))
     }
 
-    /// Execute a trade with proper error handling
-    pub async fn execute_trade_with_retry(&self, order: OrderRequest) -> Result<String> {
-        let client = self.clone();
-        
-        self.retry_with_backoff(|| {
-            let order = order.clone();
-            let client = client.clone();
-            
-            async move {
-                client.place_order(order).await
-            }
-        }, 3).await
+    /// Place a trade, retrying transient failures
+    ///
+    /// Every attempt carries `idempotency_key`, so pass the same key when
+    /// resubmitting the same order.
+    pub async fn execute_trade_with_retry(&self, order: OrderRequest, idempotency_key: &str) -> Result<String> {
+        let args = serde_json::to_value(order)?;
+        Ok(self.mutation_with_key("mutations/trading:placeTrade", args, idempotency_key).await?)
     }
 
     /// Get comprehensive market data for a token
@@ -411,6 +633,7 @@ impl ConvexClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
 
     #[tokio::test]
     async fn test_convex_client_creation() {
@@ -418,6 +641,153 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_sync_url() {
+        let mut client = ConvexClient::new().unwrap();
+        client.base_url = "https://happy-otter-123.convex.cloud/".to_string();
+        assert_eq!(
+            client.sync_url().unwrap(),
+            format!("wss://happy-otter-123.convex.cloud/api/{}/sync", SYNC_PROTOCOL_VERSION)
+        );
+        client.base_url = "http://127.0.0.1:3210".to_string();
+        assert!(client.sync_url().unwrap().starts_with("ws://127.0.0.1:3210/api/"));
+        client.base_url = "127.0.0.1:3210".to_string();
+        assert!(client.sync_url().is_err());
+    }
+
+    /// How the mock answers one request
+    enum Reply {
+        Status(u16),
+        Hang,
+        Value(Value),
+    }
+
+    /// Client against a local Convex stand-in; returns every payload it received
+    fn mock(script: fn(usize) -> Reply) -> (ConvexClient, Arc<Mutex<Vec<Value>>>) {
+        use warp::Filter;
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let seen = received.clone();
+        let route = warp::post().and(warp::body::json()).and_then(move |payload: Value| {
+            let seen = seen.clone();
+            async move {
+                let attempt = {
+                    let mut seen = seen.lock().unwrap();
+                    seen.push(payload);
+                    seen.len()
+                };
+                let (status, body) = match script(attempt) {
+                    Reply::Status(status) => (status, json!({ "message": "boom" })),
+                    Reply::Hang => {
+                        sleep(Duration::from_secs(2)).await;
+                        (200, json!(null))
+                    }
+                    Reply::Value(value) => (200, value),
+                };
+                Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&body),
+                    warp::http::StatusCode::from_u16(status).unwrap(),
+                ))
+            }
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let mut client = ConvexClient::new().unwrap().with_timeouts(TimeoutConfig {
+            default_timeout: Duration::from_millis(200),
+            critical_timeout: Duration::from_millis(200),
+            non_critical_timeout: Duration::from_millis(200),
+            retry_count: 2,
+            backoff_multiplier: 2.0,
+        });
+        client.base_url = format!("http://{}", addr);
+        (client, received)
+    }
+
+    fn portfolio() -> Value {
+        json!({ "total_value": "120.50", "total_pnl": "20.50", "total_pnl_percentage": "20.5", "position_count": 3 })
+    }
+
+    #[tokio::test]
+    async fn test_queries_retry_transient_failures() {
+        // Two 500s, then an answer
+        let (client, received) = mock(|attempt| if attempt < 3 { Reply::Status(500) } else { Reply::Value(portfolio()) });
+        let summary = client.get_portfolio("user_1").await.unwrap();
+        assert_eq!(summary.position_count, 3);
+        assert_eq!(received.lock().unwrap().len(), 3);
+        assert!(received.lock().unwrap().iter().all(|payload| payload["path"] == "queries/portfolio:getPortfolio"));
+
+        // The retry budget is the first attempt plus retry_count
+        let (client, received) = mock(|_| Reply::Status(503));
+        let error = client.get_latest_signals(5).await.unwrap_err();
+        assert!(matches!(error, ConvexError::ServerError { status: 503, .. }), "{:?}", error);
+        assert_eq!(received.lock().unwrap().len(), 3);
+
+        let (client, received) = mock(|_| Reply::Hang);
+        let error = client.get_user_dca_strategies("user_1").await.unwrap_err();
+        assert_eq!(error, ConvexError::Timeout(Duration::from_millis(200)));
+        assert_eq!(received.lock().unwrap().len(), 3);
+
+        let (client, received) = mock(|attempt| if attempt == 1 { Reply::Status(429) } else { Reply::Value(json!([])) });
+        assert!(client.get_latest_signals(5).await.unwrap().is_empty());
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_failures_are_not_retried() {
+        let (client, received) = mock(|_| Reply::Status(404));
+        assert_eq!(
+            client.get_portfolio("user_1").await.unwrap_err(),
+            ConvexError::NotFound("queries/portfolio:getPortfolio".to_string())
+        );
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        let (client, received) = mock(|_| Reply::Status(401));
+        assert!(matches!(client.get_latest_signals(1).await.unwrap_err(), ConvexError::Unauthorized(_)));
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        let (client, received) = mock(|_| Reply::Value(json!({ "error": "Strategy limit reached" })));
+        let error = client.get_user_dca_strategies("user_1").await.unwrap_err();
+        assert_eq!(error, ConvexError::Function("Strategy limit reached".to_string()));
+        assert_eq!(error.user_message(), "Strategy limit reached");
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mutations_and_actions_retry_only_with_a_key() {
+        let (client, received) = mock(|_| Reply::Status(500));
+        assert!(client.mutation::<Value>("mutations/trading:placeTrade", json!({ "side": "buy" })).await.is_err());
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // Media generation runs once, bounded by the action timeout
+        let (client, received) = mock(|_| Reply::Hang);
+        let error = client.action::<Value>("actions/media_generator:generatePriceChart", json!({ "symbol": "BONK" }))
+            .await
+            .unwrap_err();
+        assert_eq!(error, ConvexError::Timeout(Duration::from_millis(200)));
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // Every attempt carries the same key
+        let (client, received) = mock(|attempt| match attempt {
+            1 => Reply::Hang,
+            2 => Reply::Status(502),
+            _ => Reply::Value(json!("order_1")),
+        });
+        let order = OrderRequest {
+            user_id: "user_1".to_string(),
+            order_type: "market".to_string(),
+            token_mint: "So11111111111111111111111111111111111111112".to_string(),
+            side: "buy".to_string(),
+            amount: "1".to_string(),
+            price: None,
+            slippage: Some(1.0),
+        };
+        assert_eq!(client.execute_trade_with_retry(order, "trade-808").await.unwrap(), "order_1");
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        assert!(received.iter().all(|payload| payload["args"]["idempotencyKey"] == "trade-808"));
+    }
+
     #[tokio::test]
     async fn test_health_check() {
         // This would require a running Convex instance
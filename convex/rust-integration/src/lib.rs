@@ -4,15 +4,20 @@
 //! specifically designed for the Solana Trading Bot project.
 
 pub mod convex_client;
+pub mod subscription;
 pub mod telegram_integration;
 pub mod trading_service;
 pub mod webhook_server;
 
-pub use convex_client::ConvexClient;
+pub use convex_client::{ConvexClient, ConvexError, ConvexResult, TimeoutConfig};
+pub use subscription::QuerySubscription;
 pub use telegram_integration::TelegramConvexBridge;
 
-use anyhow::Result;
-use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::{timeout_at, Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
 /// Configuration for the Convex integration
 #[derive(Clone)]
@@ -22,6 +27,10 @@ pub struct ConvexConfig {
     pub telegram_bot_token: String,
     pub webhook_port: u16,
     pub webhook_path: String,
+    /// How long shutdown waits for components to drain
+    pub shutdown_timeout: Duration,
+    /// The trading bot's `/readyz`; health checks include its dependencies when set
+    pub readiness_url: Option<String>,
 }
 
 impl Default for ConvexConfig {
@@ -32,6 +41,8 @@ impl Default for ConvexConfig {
             telegram_bot_token: String::new(),
             webhook_port: 8080,
             webhook_path: "/webhook".to_string(),
+            shutdown_timeout: Duration::from_secs(30),
+            readiness_url: None,
         }
     }
 }
@@ -41,6 +52,8 @@ pub struct ConvexIntegrationService {
     pub convex_client: Arc<ConvexClient>,
     pub telegram_bridge: Option<TelegramConvexBridge>,
     pub config: ConvexConfig,
+    shutdown: CancellationToken,
+    components: Mutex<Vec<(&'static str, JoinHandle<Result<()>>)>>,
 }
 
 impl ConvexIntegrationService {
@@ -59,43 +72,104 @@ impl ConvexIntegrationService {
             convex_client,
             telegram_bridge,
             config,
+            shutdown: CancellationToken::new(),
+            components: Mutex::new(Vec::new()),
         })
     }
 
-    /// Start all services
+    /// Start all services and run until ctrl-c or `shutdown`
     pub async fn start(&self) -> Result<()> {
+        self.launch();
+
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = self.shutdown.cancelled() => {}
+        }
+        println!("Shutting down Convex integration service...");
+
+        self.shutdown().await
+    }
+
+    /// Spawn each component in the background
+    pub fn launch(&self) {
+        let mut components = self.components.lock().unwrap();
+
         // Start webhook server for Convex -> Rust communication
         let webhook_server = webhook_server::WebhookServer::new(
             self.config.webhook_port,
             self.config.webhook_path.clone(),
             self.convex_client.clone(),
         );
-
-        tokio::spawn(async move {
-            if let Err(e) = webhook_server.start().await {
+        let shutdown = self.shutdown.child_token();
+        components.push(("webhook server", tokio::spawn(async move {
+            let result = webhook_server.start_until(shutdown).await;
+            if let Err(e) = &result {
                 eprintln!("Webhook server error: {}", e);
             }
-        });
+            result
+        })));
 
         // Start Telegram bot if configured
         if let Some(telegram_bridge) = &self.telegram_bridge {
             let bridge = telegram_bridge.clone();
-            tokio::spawn(async move {
-                if let Err(e) = start_telegram_bot(bridge).await {
+            let shutdown = self.shutdown.child_token();
+            components.push(("telegram bot", tokio::spawn(async move {
+                let result = start_telegram_bot(bridge, shutdown).await;
+                if let Err(e) = &result {
                     eprintln!("Telegram bot error: {}", e);
                 }
-            });
+                result
+            })));
         }
+    }
 
-        // Keep the service running
-        tokio::signal::ctrl_c().await?;
-        println!("Shutting down Convex integration service...");
+    /// Components that are still running
+    pub fn running_components(&self) -> Vec<&'static str> {
+        self.components.lock().unwrap()
+            .iter()
+            .filter(|(_, handle)| !handle.is_finished())
+            .map(|(name, _)| *name)
+            .collect()
+    }
 
-        Ok(())
+    /// Signal every component to stop and wait for them to drain
+    ///
+    /// Components still running after `shutdown_timeout` are aborted; the error
+    /// lists every component that failed or had to be aborted.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.shutdown.cancel();
+
+        let components = std::mem::take(&mut *self.components.lock().unwrap());
+        let deadline = Instant::now() + self.config.shutdown_timeout;
+        let mut failures = Vec::new();
+
+        for (name, mut handle) in components {
+            match timeout_at(deadline, &mut handle).await {
+                Ok(Ok(Ok(()))) => {}
+                Ok(Ok(Err(e))) => failures.push(format!("{} failed: {}", name, e)),
+                Ok(Err(e)) => failures.push(format!("{} panicked: {}", name, e)),
+                Err(_) => {
+                    handle.abort();
+                    failures.push(format!("{} did not stop within {:?}", name, self.config.shutdown_timeout));
+                }
+            }
+        }
+
+        if failures.is_empty() {
+            println!("✅ All components stopped");
+            Ok(())
+        } else {
+            Err(anyhow!("Shutdown incomplete: {}", failures.join("; ")))
+        }
     }
 
     /// Health check for all components
     pub async fn health_check(&self) -> Result<()> {
+        // The bot's dependency registry, so both report the same view
+        if let Some(url) = &self.config.readiness_url {
+            check_bot_readiness(url).await?;
+        }
+
         // Check Convex connection
         if !self.convex_client.health_check().await? {
             return Err(anyhow::anyhow!("Convex health check failed"));
@@ -106,7 +180,32 @@ impl ConvexIntegrationService {
     }
 }
 
-async fn start_telegram_bot(bridge: TelegramConvexBridge) -> Result<()> {
+/// Fail when the bot's `/readyz` says a critical dependency is down
+async fn check_bot_readiness(url: &str) -> Result<()> {
+    let report: serde_json::Value = reqwest::get(url).await?.json().await?;
+    let checks = report["checks"].as_array().cloned().unwrap_or_default();
+    let describe = |check: &serde_json::Value| format!(
+        "{} {}: {}",
+        check["component"].as_str().unwrap_or("unknown"),
+        check["status"].as_str().unwrap_or("Unknown"),
+        check["message"].as_str().unwrap_or_default(),
+    );
+
+    if report["ready"] != true {
+        let failing: Vec<String> = checks.iter()
+            .filter(|check| check["critical"] == true && !matches!(check["status"].as_str(), Some("Healthy" | "Degraded")))
+            .map(describe)
+            .collect();
+        return Err(anyhow!("Trading bot not ready: {}", failing.join("; ")));
+    }
+
+    for check in checks.iter().filter(|check| check["status"] != "Healthy") {
+        println!("⚠️ Trading bot dependency {}", describe(check));
+    }
+    Ok(())
+}
+
+async fn start_telegram_bot(bridge: TelegramConvexBridge, shutdown: CancellationToken) -> Result<()> {
     use teloxide::{prelude::*, update_listeners::webhooks};
 
     let bot = bridge.bot.clone();
@@ -134,7 +233,17 @@ async fn start_telegram_bot(bridge: TelegramConvexBridge) -> Result<()> {
     })
     .build();
 
+    let token = dispatcher.shutdown_token();
+    let watcher = tokio::spawn(async move {
+        shutdown.cancelled().await;
+        // The token reports idle until the dispatcher is actually polling
+        while token.shutdown().is_err() {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+
     dispatcher.dispatch().await;
+    watcher.abort();
     Ok(())
 }
 
@@ -148,4 +257,66 @@ mod tests {
         let service = ConvexIntegrationService::new(config).await;
         assert!(service.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_all_components() {
+        let config = ConvexConfig {
+            webhook_port: 0,
+            shutdown_timeout: Duration::from_secs(5),
+            ..ConvexConfig::default()
+        };
+        let service = ConvexIntegrationService::new(config).await.unwrap();
+
+        service.launch();
+        assert_eq!(service.running_components(), vec!["webhook server"]);
+
+        // Ok means every component exited within the timeout
+        service.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reports_failed_components() {
+        let config = ConvexConfig {
+            shutdown_timeout: Duration::from_millis(100),
+            ..ConvexConfig::default()
+        };
+        let service = ConvexIntegrationService::new(config).await.unwrap();
+        service.components.lock().unwrap()
+            .push(("stuck", tokio::spawn(std::future::pending())));
+        service.components.lock().unwrap()
+            .push(("broken", tokio::spawn(async { Err(anyhow!("lost connection")) })));
+
+        let error = service.shutdown().await.unwrap_err().to_string();
+        assert!(error.contains("stuck did not stop"));
+        assert!(error.contains("broken failed: lost connection"));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_unready_bot_dependencies() {
+        use warp::Filter;
+
+        let readyz = warp::path("readyz").map(|| warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "ready": false,
+                "status": "Unhealthy",
+                "checks": [
+                    { "component": "ai_analyzer", "critical": false, "status": "Unhealthy", "message": "Groq API returned 401" },
+                    { "component": "jupiter_api", "critical": true, "status": "Unhealthy", "message": "Jupiter quote API returned 500" },
+                    { "component": "solana_rpc", "critical": true, "status": "Healthy", "message": "Solana RPC healthy" }
+                ]
+            })),
+            warp::http::StatusCode::SERVICE_UNAVAILABLE,
+        ));
+        let (addr, server) = warp::serve(readyz).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let config = ConvexConfig {
+            readiness_url: Some(format!("http://{}/readyz", addr)),
+            ..ConvexConfig::default()
+        };
+        let service = ConvexIntegrationService::new(config).await.unwrap();
+
+        let error = service.health_check().await.unwrap_err().to_string();
+        assert_eq!(error, "Trading bot not ready: jupiter_api Unhealthy: Jupiter quote API returned 500");
+    }
 }
\ No newline at end of file
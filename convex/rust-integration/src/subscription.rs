@@ -0,0 +1,324 @@
+//! Live Convex queries over the sync websocket
+//!
+//! A subscription keeps one query registered with the deployment and yields its
+//! value whenever the server pushes a new result. Dropping the stream stops it.
+
+use anyhow::{anyhow, Result};
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde_json::{json, Value};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Duration};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// Client version reported in the sync path
+pub const SYNC_PROTOCOL_VERSION: &str = "1.17.0";
+
+/// Each subscription owns its connection, so it only ever registers one query
+const QUERY_ID: u32 = 0;
+
+/// Updates buffered before the background task waits for the consumer
+const UPDATE_BUFFER: usize = 16;
+
+/// Exponential reconnect delay, reset once a connection delivers a result
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    initial: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1), Duration::from_secs(30))
+    }
+}
+
+impl ReconnectBackoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self { initial, max, attempt: 0 }
+    }
+
+    /// Delay before the next reconnect: initial, 2x, 4x, ... up to max
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.initial.saturating_mul(2_u32.saturating_pow(self.attempt)).min(self.max);
+        self.attempt = (self.attempt + 1).min(16);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+/// What a server message means for our query
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SyncEvent {
+    Updated(Value),
+    Failed(String),
+    /// The server gave up on the connection
+    Fatal(String),
+}
+
+pub(crate) fn connect_message(session_id: &str, connection_count: u32) -> Value {
+    json!({
+        "type": "Connect",
+        "sessionId": session_id,
+        "connectionCount": connection_count,
+        "lastCloseReason": if connection_count == 0 { "InitialConnect" } else { "Reconnect" },
+    })
+}
+
+/// A fresh connection starts from an empty query set
+pub(crate) fn add_query_message(function_name: &str, args: &Value) -> Value {
+    json!({
+        "type": "ModifyQuerySet",
+        "baseVersion": 0,
+        "newVersion": 1,
+        "modifications": [{
+            "type": "Add",
+            "queryId": QUERY_ID,
+            "udfPath": function_name,
+            "args": [args],
+        }],
+    })
+}
+
+/// Events for our query in a server message; pings and other queries are skipped
+pub(crate) fn parse_server_message(text: &str) -> Result<Vec<SyncEvent>> {
+    let message: Value = serde_json::from_str(text)
+        .map_err(|e| anyhow!("Invalid sync message: {}", e))?;
+
+    let events = match message["type"].as_str() {
+        Some("Transition") => message["modifications"]
+            .as_array()
+            .map(|modifications| modifications.iter()
+                .filter(|m| m["queryId"].as_u64() == Some(QUERY_ID as u64))
+                .filter_map(|m| match m["type"].as_str() {
+                    Some("QueryUpdated") => Some(SyncEvent::Updated(m["value"].clone())),
+                    Some("QueryFailed") => Some(SyncEvent::Failed(
+                        m["errorMessage"].as_str().unwrap_or("unknown error").to_string(),
+                    )),
+                    _ => None,
+                })
+                .collect())
+            .unwrap_or_default(),
+        Some("FatalError") | Some("AuthError") => vec![SyncEvent::Fatal(
+            message["error"].as_str().unwrap_or("unknown error").to_string(),
+        )],
+        _ => Vec::new(),
+    };
+    Ok(events)
+}
+
+/// Stream of results for one live query
+///
+/// Yields `Err` for failed query runs and dropped connections; the subscription
+/// keeps reconnecting until the stream is dropped.
+pub struct QuerySubscription {
+    updates: mpsc::Receiver<Result<Value>>,
+    task: JoinHandle<()>,
+}
+
+impl QuerySubscription {
+    /// Start the background task for `function_name` against the sync endpoint at `url`
+    pub fn spawn(url: String, function_name: &str, args: Value, backoff: ReconnectBackoff) -> Self {
+        let (tx, updates) = mpsc::channel(UPDATE_BUFFER);
+        let task = SubscriptionTask {
+            url,
+            function_name: function_name.to_string(),
+            args,
+            tx,
+            session_id: uuid::Uuid::new_v4().to_string(),
+            connection_count: 0,
+            last_value: None,
+            backoff,
+        };
+        Self { updates, task: tokio::spawn(task.run()) }
+    }
+}
+
+impl Stream for QuerySubscription {
+    type Item = Result<Value>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.updates.poll_recv(cx)
+    }
+}
+
+impl Drop for QuerySubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct SubscriptionTask {
+    url: String,
+    function_name: String,
+    args: Value,
+    tx: mpsc::Sender<Result<Value>>,
+    session_id: String,
+    connection_count: u32,
+    /// Last value yielded, so a reconnect doesn't repeat it
+    last_value: Option<Value>,
+    backoff: ReconnectBackoff,
+}
+
+impl SubscriptionTask {
+    async fn run(mut self) {
+        loop {
+            let error = match self.session().await {
+                Ok(()) => return,
+                Err(e) => e,
+            };
+            if self.tx.send(Err(error)).await.is_err() {
+                return;
+            }
+
+            self.connection_count += 1;
+            tokio::select! {
+                _ = sleep(self.backoff.next_delay()) => {}
+                _ = self.tx.closed() => return,
+            }
+        }
+    }
+
+    /// One connection's lifetime; `Ok` only once the consumer is gone
+    async fn session(&mut self) -> Result<()> {
+        let (mut socket, _) = connect_async(self.url.as_str()).await?;
+        socket.send(Message::Text(connect_message(&self.session_id, self.connection_count).to_string())).await?;
+        socket.send(Message::Text(add_query_message(&self.function_name, &self.args).to_string())).await?;
+
+        loop {
+            let frame = tokio::select! {
+                frame = socket.next() => frame,
+                _ = self.tx.closed() => {
+                    let _ = socket.close(None).await;
+                    return Ok(());
+                }
+            };
+
+            let text = match frame {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(frame))) => {
+                    return Err(anyhow!("Convex sync connection closed: {}",
+                        frame.map(|f| f.reason.to_string()).unwrap_or_default()));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(anyhow!("Convex sync connection failed: {}", e)),
+                None => return Err(anyhow!("Convex sync connection closed")),
+            };
+
+            for event in parse_server_message(&text)? {
+                let update = match event {
+                    SyncEvent::Updated(value) => {
+                        self.backoff.reset();
+                        if self.last_value.as_ref() == Some(&value) {
+                            continue;
+                        }
+                        self.last_value = Some(value.clone());
+                        Ok(value)
+                    }
+                    SyncEvent::Failed(error) => Err(anyhow!("Query {} failed: {}", self.function_name, error)),
+                    SyncEvent::Fatal(error) => return Err(anyhow!("Convex sync error: {}", error)),
+                };
+                if self.tx.send(update).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    fn transition(value: Value) -> Message {
+        Message::Text(json!({
+            "type": "Transition",
+            "startVersion": { "querySet": 0, "identity": 0, "ts": "AAAAAAAAAAA=" },
+            "endVersion": { "querySet": 1, "identity": 0, "ts": "AQAAAAAAAAA=" },
+            "modifications": [{ "type": "QueryUpdated", "queryId": 0, "value": value, "logLines": [] }],
+        }).to_string())
+    }
+
+    #[test]
+    fn test_parse_server_message() {
+        let failed = json!({
+            "type": "Transition",
+            "modifications": [
+                { "type": "QueryFailed", "queryId": 0, "errorMessage": "boom" },
+                { "type": "QueryUpdated", "queryId": 7, "value": 1 },
+            ],
+        });
+        assert_eq!(parse_server_message(&failed.to_string()).unwrap(), vec![SyncEvent::Failed("boom".to_string())]);
+        assert_eq!(parse_server_message(r#"{"type":"Ping"}"#).unwrap(), Vec::new());
+        assert_eq!(
+            parse_server_message(r#"{"type":"FatalError","error":"bad session"}"#).unwrap(),
+            vec![SyncEvent::Fatal("bad session".to_string())]
+        );
+        assert!(parse_server_message("not json").is_err());
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max_and_resets() {
+        let mut backoff = ReconnectBackoff::new(Duration::from_secs(1), Duration::from_secs(5));
+        let delays: Vec<u64> = (0..5).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_reconnects_and_stops_on_drop() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/api/{}/sync", listener.local_addr().unwrap(), SYNC_PROTOCOL_VERSION);
+
+        let server = tokio::spawn(async move {
+            // First connection: two changes (one repeated), then a drop
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            let connect = socket.next().await.unwrap().unwrap();
+            assert!(connect.to_text().unwrap().contains("\"connectionCount\":0"));
+            let add = socket.next().await.unwrap().unwrap();
+            assert!(add.to_text().unwrap().contains("queries/portfolio:getPortfolio"));
+            for value in [1, 1, 2] {
+                socket.send(transition(json!(value))).await.unwrap();
+            }
+            drop(socket);
+
+            // Second connection replays the current value before a new one
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            let connect = socket.next().await.unwrap().unwrap();
+            assert!(connect.to_text().unwrap().contains("\"connectionCount\":1"));
+            socket.next().await.unwrap().unwrap();
+            for value in [2, 3] {
+                socket.send(transition(json!(value))).await.unwrap();
+            }
+
+            // The client hangs up once the stream is dropped
+            while let Some(Ok(frame)) = socket.next().await {
+                if frame.is_close() {
+                    break;
+                }
+            }
+        });
+
+        let backoff = ReconnectBackoff::new(Duration::from_millis(10), Duration::from_millis(50));
+        let mut subscription = QuerySubscription::spawn(url, "queries/portfolio:getPortfolio", json!({ "userId": "user_1" }), backoff);
+
+        assert_eq!(subscription.next().await.unwrap().unwrap(), json!(1));
+        assert_eq!(subscription.next().await.unwrap().unwrap(), json!(2));
+        assert!(subscription.next().await.unwrap().is_err());
+        assert_eq!(subscription.next().await.unwrap().unwrap(), json!(3));
+
+        drop(subscription);
+        tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+    }
+}
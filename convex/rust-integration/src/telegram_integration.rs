@@ -1,14 +1,45 @@
-use crate::convex_client::ConvexClient;
+use crate::convex_client::{ConvexClient, ConvexError, PortfolioPosition, PortfolioSummary, TokenQuote};
 use anyhow::Result;
+use bot_locales::{Catalog, LANGUAGES};
+use futures_util::StreamExt;
 use serde_json::{json, Value};
-use teloxide::{prelude::*, types::InlineKeyboardMarkup, utils::command::BotCommands};
+use teloxide::{
+    prelude::*,
+    types::{
+        InlineKeyboardMarkup, InlineQueryResult, InlineQueryResultArticle, InputMessageContent,
+        InputMessageContentText, MessageId,
+    },
+    utils::command::BotCommands,
+    ApiError, RequestError,
+};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
+use tokio::time::{timeout_at, Duration, Instant};
+
+/// How long a /portfolio message keeps following live updates
+const LIVE_PORTFOLIO_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Telegram accepts at most 50 inline results; 20 keeps the list scannable
+const MAX_INLINE_RESULTS: usize = 20;
+
+/// Positions listed in the shareable portfolio result
+const INLINE_PORTFOLIO_POSITIONS: usize = 5;
+
+/// How long Telegram may reuse token search and trending answers
+const MARKET_INLINE_CACHE_SECS: u32 = 30;
+
+/// How long Telegram may reuse a user's own portfolio or DCA answer
+const PERSONAL_INLINE_CACHE_SECS: u32 = 10;
 
 /// Telegram bot integration with Convex backend
 #[derive(Clone)]
 pub struct TelegramConvexBridge {
     convex: Arc<ConvexClient>,
     bot: Bot,
+    /// Live portfolio message per chat; a newer /portfolio replaces it
+    live_portfolios: Arc<Mutex<HashMap<ChatId, AbortHandle>>>,
 }
 
 #[derive(BotCommands, Clone)]
@@ -34,7 +65,11 @@ pub enum Command {
 
 impl TelegramConvexBridge {
     pub fn new(bot: Bot, convex: Arc<ConvexClient>) -> Self {
-        Self { convex, bot }
+        Self {
+            convex,
+            bot,
+            live_portfolios: Arc::new(Mutex::new(HashMap::new())),
+        }
     }
 
     /// Handle incoming messages
@@ -101,18 +136,21 @@ impl TelegramConvexBridge {
         let query_text = &query.query;
         let user_id = query.from.id.0 as i64;
 
-        let results = match query_text.to_lowercase().as_str() {
-            "portfolio" => self.create_portfolio_inline_results(user_id).await?,
-            "dca" => self.create_dca_inline_results(user_id).await?,
-            "trending" => self.create_trending_inline_results().await?,
+        let (results, personal) = match query_text.to_lowercase().as_str() {
+            "portfolio" => (self.create_portfolio_inline_results(user_id).await?, true),
+            "dca" => (self.create_dca_inline_results(user_id).await?, true),
+            "trending" => (self.create_trending_inline_results().await?, false),
             _ if query_text.len() >= 2 => {
-                self.create_token_search_results(query_text).await?
+                (self.create_token_search_results(query_text).await?, false)
             }
-            _ => Vec::new(),
+            _ => (Vec::new(), false),
         };
 
+        let cache_time = if personal { PERSONAL_INLINE_CACHE_SECS } else { MARKET_INLINE_CACHE_SECS };
         self.bot
             .answer_inline_query(&query.id, results)
+            .cache_time(cache_time)
+            .is_personal(personal)
             .await?;
 
         Ok(())
@@ -156,38 +194,22 @@ impl TelegramConvexBridge {
         
         match self.convex.get_portfolio(&user_id_str).await {
             Ok(portfolio) => {
-                let portfolio_text = format!(
-                    "📊 **Portfolio Overview**\n\n\
-                    💰 Total Value: ${}\n\
-                    📈 Total P&L: {} ({}%)\n\
-                    🎯 Positions: {}\n\n\
-                    Use the web dashboard for detailed analytics:\n\
-                    https://dashboard.solanabot.com",
-                    portfolio.total_value,
-                    portfolio.total_pnl,
-                    portfolio.total_pnl_percentage,
-                    portfolio.position_count
-                );
-
-                let keyboard = InlineKeyboardMarkup::new(vec![
-                    vec![
-                        teloxide::types::InlineKeyboardButton::callback("📊 Detailed View", "portfolio_detail"),
-                        teloxide::types::InlineKeyboardButton::callback("🔄 Refresh", "portfolio_refresh"),
-                    ],
-                    vec![
-                        teloxide::types::InlineKeyboardButton::callback("💱 Quick Trade", "quick_trade"),
-                    ],
-                ]);
+                let sent = self.bot
+                    .send_message(chat_id, Self::portfolio_text(&portfolio))
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                    .reply_markup(Self::portfolio_keyboard())
+                    .await?;
 
+                self.follow_portfolio(chat_id, sent.id, user_id_str).await;
+            }
+            Err(ConvexError::NotFound(_)) => {
                 self.bot
-                    .send_message(chat_id, portfolio_text)
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .reply_markup(keyboard)
+                    .send_message(chat_id, "📭 No portfolio yet. Connect a wallet to start tracking.")
                     .await?;
             }
             Err(e) => {
                 self.bot
-                    .send_message(chat_id, format!("❌ Error loading portfolio: {}", e))
+                    .send_message(chat_id, format!("❌ Couldn't load your portfolio: {}", e.user_message()))
                     .await?;
             }
         }
@@ -195,6 +217,75 @@ impl TelegramConvexBridge {
         Ok(())
     }
 
+    fn portfolio_text(portfolio: &PortfolioSummary) -> String {
+        format!(
+            "📊 **Portfolio Overview**\n\n\
+            💰 Total Value: ${}\n\
+            📈 Total P&L: {} ({}%)\n\
+            🎯 Positions: {}\n\n\
+            Use the web dashboard for detailed analytics:\n\
+            https://dashboard.solanabot.com",
+            portfolio.total_value,
+            portfolio.total_pnl,
+            portfolio.total_pnl_percentage,
+            portfolio.position_count
+        )
+    }
+
+    fn portfolio_keyboard() -> InlineKeyboardMarkup {
+        InlineKeyboardMarkup::new(vec![
+            vec![
+                teloxide::types::InlineKeyboardButton::callback("📊 Detailed View", "portfolio_detail"),
+                teloxide::types::InlineKeyboardButton::callback("🔄 Refresh", "portfolio_refresh"),
+            ],
+            vec![
+                teloxide::types::InlineKeyboardButton::callback("💱 Quick Trade", "quick_trade"),
+            ],
+        ])
+    }
+
+    /// Edit a portfolio message whenever the Convex portfolio changes, for a limited window
+    async fn follow_portfolio(&self, chat_id: ChatId, message_id: MessageId, user_id: String) {
+        let mut updates = match self.convex.subscribe_portfolio(&user_id) {
+            Ok(updates) => updates,
+            Err(e) => {
+                eprintln!("Live portfolio unavailable for {}: {}", user_id, e);
+                return;
+            }
+        };
+
+        let bot = self.bot.clone();
+        let deadline = Instant::now() + LIVE_PORTFOLIO_WINDOW;
+        let task = tokio::spawn(async move {
+            while let Ok(Some(update)) = timeout_at(deadline, updates.next()).await {
+                let portfolio = match update.and_then(|value| Ok(serde_json::from_value::<PortfolioSummary>(value)?)) {
+                    Ok(portfolio) => portfolio,
+                    Err(e) => {
+                        eprintln!("Live portfolio update for {} failed: {}", user_id, e);
+                        continue;
+                    }
+                };
+
+                let edited = bot
+                    .edit_message_text(chat_id, message_id, Self::portfolio_text(&portfolio))
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                    .reply_markup(Self::portfolio_keyboard())
+                    .await;
+                match edited {
+                    // The first result is usually what was just sent
+                    Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => {}
+                    // Message deleted or no longer editable
+                    Err(_) => break,
+                }
+            }
+            // Dropping `updates` ends the Convex subscription
+        });
+
+        if let Some(previous) = self.live_portfolios.lock().await.insert(chat_id, task.abort_handle()) {
+            previous.abort();
+        }
+    }
+
     async fn handle_trade_command(&self, chat_id: ChatId, user_id: i64, token: Option<String>) -> Result<()> {
         let token_symbol = token.unwrap_or_else(|| "SOL".to_string());
         
@@ -300,7 +391,7 @@ impl TelegramConvexBridge {
             }
             Err(e) => {
                 self.bot
-                    .send_message(chat_id, format!("❌ Error loading DCA strategies: {}", e))
+                    .send_message(chat_id, format!("❌ Couldn't load DCA strategies: {}", e.user_message()))
                     .await?;
             }
         }
@@ -353,7 +444,7 @@ impl TelegramConvexBridge {
             }
             Err(e) => {
                 self.bot
-                    .send_message(chat_id, format!("❌ Error loading AI signals: {}", e))
+                    .send_message(chat_id, format!("❌ Couldn't load AI signals: {}", e.user_message()))
                     .await?;
             }
         }
@@ -549,21 +640,130 @@ impl TelegramConvexBridge {
 
     // Inline Query Results
 
-    async fn create_portfolio_inline_results(&self, user_id: i64) -> Result<Vec<teloxide::types::InlineQueryResult>> {
-        // Implementation would create inline query results for portfolio
-        Ok(Vec::new()) // Simplified for brevity
+    async fn create_portfolio_inline_results(&self, user_id: i64) -> Result<Vec<InlineQueryResult>> {
+        let user_id_str = format!("user_{}", user_id);
+        let (portfolio, positions) = tokio::try_join!(
+            self.convex.get_portfolio(&user_id_str),
+            self.convex.get_portfolio_positions(&user_id_str),
+        )?;
+
+        Ok(vec![Self::portfolio_inline_result(user_id, &portfolio, &positions)])
     }
 
-    async fn create_dca_inline_results(&self, user_id: i64) -> Result<Vec<teloxide::types::InlineQueryResult>> {
+    async fn create_dca_inline_results(&self, user_id: i64) -> Result<Vec<InlineQueryResult>> {
         Ok(Vec::new()) // Simplified for brevity
     }
 
-    async fn create_trending_inline_results(&self) -> Result<Vec<teloxide::types::InlineQueryResult>> {
-        Ok(Vec::new()) // Simplified for brevity
+    async fn create_trending_inline_results(&self) -> Result<Vec<InlineQueryResult>> {
+        let tokens = self.convex.get_trending_tokens("24h", "volume").await?;
+        Ok(Self::token_inline_results("trending", "🔥 ", &tokens))
     }
 
-    async fn create_token_search_results(&self, query: &str) -> Result<Vec<teloxide::types::InlineQueryResult>> {
-        Ok(Vec::new()) // Simplified for brevity
+    async fn create_token_search_results(&self, query: &str) -> Result<Vec<InlineQueryResult>> {
+        let tokens = self.convex.search_tokens(query, MAX_INLINE_RESULTS as u32).await?;
+        Ok(Self::token_inline_results("token", "", &tokens))
+    }
+
+    /// One article per token, keyed `{kind}:{mint}` so repeated answers line up
+    ///
+    /// Choosing an article posts the token's price card with a `/trade` command
+    /// ready to tap. Duplicate mints are dropped and the list is capped at
+    /// `MAX_INLINE_RESULTS`.
+    fn token_inline_results(kind: &str, title_prefix: &str, tokens: &[TokenQuote]) -> Vec<InlineQueryResult> {
+        let mut seen = HashSet::new();
+        tokens
+            .iter()
+            .filter(|token| seen.insert(token.mint.as_str()))
+            .take(MAX_INLINE_RESULTS)
+            .map(|token| {
+                let change = Self::signed_percent(token.change_24h);
+                let change_emoji = if token.change_24h >= 0.0 { "🟢" } else { "🔴" };
+                let message = format!(
+                    "💰 {} ({})\n\n\
+                    Price: ${}\n\
+                    24h Change: {}\n\
+                    Mint: {}\n\n\
+                    /trade {}",
+                    token.name,
+                    token.symbol,
+                    Self::display_price(&token.price),
+                    change,
+                    token.mint,
+                    token.symbol
+                );
+
+                let article = InlineQueryResultArticle::new(
+                    format!("{}:{}", kind, token.mint),
+                    format!("{}{} - ${}", title_prefix, token.symbol, Self::display_price(&token.price)),
+                    InputMessageContent::Text(InputMessageContentText::new(message)),
+                )
+                .description(format!("{} {} | {}", change_emoji, change, token.name));
+
+                InlineQueryResult::Article(article)
+            })
+            .collect()
+    }
+
+    /// Shareable summary of a portfolio and its largest positions
+    fn portfolio_inline_result(user_id: i64, portfolio: &PortfolioSummary, positions: &[PortfolioPosition]) -> InlineQueryResult {
+        let mut top: Vec<&PortfolioPosition> = positions.iter().collect();
+        top.sort_by(|a, b| {
+            let value = |position: &PortfolioPosition| position.market_value.parse::<f64>().unwrap_or(0.0);
+            value(b).total_cmp(&value(a))
+        });
+        top.truncate(INLINE_PORTFOLIO_POSITIONS);
+
+        let mut message = format!(
+            "💼 My Portfolio\n\n\
+            💰 Total Value: ${}\n\
+            📈 Total P&L: {} ({}%)\n\
+            🎯 Positions: {}",
+            portfolio.total_value,
+            portfolio.total_pnl,
+            portfolio.total_pnl_percentage,
+            portfolio.position_count
+        );
+        if !top.is_empty() {
+            message.push_str("\n\n🏆 Top Positions:");
+            for (rank, position) in top.iter().enumerate() {
+                message.push_str(&format!(
+                    "\n{}. {} - ${} ({})",
+                    rank + 1,
+                    position.symbol,
+                    position.market_value,
+                    Self::signed_percent(position.pnl.percentage)
+                ));
+            }
+        }
+
+        let article = InlineQueryResultArticle::new(
+            format!("portfolio:{}", user_id),
+            "💼 Share My Portfolio",
+            InputMessageContent::Text(InputMessageContentText::new(message)),
+        )
+        .description(format!(
+            "${} | {} positions | P&L {}%",
+            portfolio.total_value, portfolio.position_count, portfolio.total_pnl_percentage
+        ));
+
+        InlineQueryResult::Article(article)
+    }
+
+    /// Fewer decimals for tokens above a dollar, more for sub-cent tokens
+    fn display_price(price: &str) -> String {
+        match price.parse::<f64>() {
+            Ok(value) if value >= 1.0 => format!("{:.2}", value),
+            Ok(value) => format!("{:.6}", value),
+            Err(_) => price.to_string(),
+        }
+    }
+
+    fn signed_percent(value: f64) -> String {
+        if value >= 0.0 {
+            format!("+{:.2}%", value)
+        } else {
+            format!("{:.2}%", value)
+        }
     }
 
     // Rich Media Methods
@@ -864,117 +1064,22 @@ impl TelegramConvexBridge {
     }
 
     fn translate(&self, lang: &str, key: &str, params: &[(&str, &str)]) -> String {
-        // Simple translation function - in production would use full i18n system
-        let translations = self.get_translations();
-        
-        if let Some(lang_translations) = translations.get(lang) {
-            if let Some(translation) = self.get_nested_translation(lang_translations, key) {
-                return self.replace_params(translation, params);
-            }
-        }
-        
-        // Fallback to English
-        if let Some(en_translations) = translations.get("en") {
-            if let Some(translation) = self.get_nested_translation(en_translations, key) {
-                return self.replace_params(translation, params);
-            }
-        }
-        
-        // Return key if translation not found
-        format!("[{}]", key)
-    }
-
-    fn get_translations(&self) -> std::collections::HashMap<&str, std::collections::HashMap<&str, &str>> {
-        let mut translations = std::collections::HashMap::new();
-        
-        // English translations
-        let mut en = std::collections::HashMap::new();
-        en.insert("commands.start.welcome", "🚀 Welcome to Solana Trading Bot!\n\nYour AI-powered companion for Solana trading with:\n• Real-time portfolio tracking\n• Advanced DCA strategies\n• AI trading signals\n• Price alerts & notifications\n\nChoose an option below to get started:");
-        en.insert("commands.start.language_setup", "Please select your preferred language:");
-        en.insert("commands.start.user_created", "Welcome! Your account has been created. You can now start trading!");
-        en.insert("commands.portfolio.title", "📊 Portfolio Overview");
-        en.insert("commands.portfolio.total_value", "💰 Total Value: ${{value}}");
-        en.insert("commands.portfolio.total_pnl", "📈 Total P&L: {{sign}}${{amount}} ({{percentage}}%)");
-        en.insert("commands.portfolio.positions", "🎯 Positions: {{count}}");
-        en.insert("commands.portfolio.no_portfolio", "No portfolio data available. Connect a wallet to get started!");
-        en.insert("commands.trade.title", "💱 Quick Trade: {{symbol}}");
-        en.insert("commands.trade.current_price", "💰 Current Price: ${{price}}");
-        en.insert("commands.trade.select_action", "Select your trading action:");
-        en.insert("buttons.portfolio", "📊 Portfolio");
-        en.insert("buttons.trade", "💱 Trade");
-        en.insert("buttons.dca", "🤖 DCA");
-        en.insert("buttons.alerts", "🔔 Alerts");
-        en.insert("buttons.signals", "🧠 AI Signals");
-        en.insert("buttons.wallet", "💳 Wallet");
-        en.insert("buttons.settings", "⚙️ Settings");
-        en.insert("buttons.help", "❓ Help");
-        en.insert("buttons.refresh", "🔄 Refresh");
-        en.insert("buttons.back", "⬅️ Back");
-        
-        // Spanish translations
-        let mut es = std::collections::HashMap::new();
-        es.insert("commands.start.welcome", "🚀 ¡Bienvenido a Solana Trading Bot!\n\nTu compañero impulsado por IA para trading de Solana con:\n• Seguimiento de portafolio en tiempo real\n• Estrategias DCA avanzadas\n• Señales de trading AI\n• Alertas de precio y notificaciones\n\nElige una opción para comenzar:");
-        es.insert("commands.start.language_setup", "Por favor selecciona tu idioma preferido:");
-        es.insert("commands.start.user_created", "¡Bienvenido! Tu cuenta ha sido creada. ¡Ya puedes comenzar a hacer trading!");
-        es.insert("commands.portfolio.title", "📊 Resumen del Portafolio");
-        es.insert("commands.portfolio.total_value", "💰 Valor Total: ${{value}}");
-        es.insert("commands.portfolio.total_pnl", "📈 P&L Total: {{sign}}${{amount}} ({{percentage}}%)");
-        es.insert("commands.portfolio.positions", "🎯 Posiciones: {{count}}");
-        es.insert("commands.portfolio.no_portfolio", "No hay datos de portafolio disponibles. ¡Conecta una billetera para empezar!");
-        es.insert("commands.trade.title", "💱 Trade Rápido: {{symbol}}");
-        es.insert("commands.trade.current_price", "💰 Precio Actual: ${{price}}");
-        es.insert("commands.trade.select_action", "Selecciona tu acción de trading:");
-        es.insert("buttons.portfolio", "📊 Portafolio");
-        es.insert("buttons.trade", "💱 Trade");
-        es.insert("buttons.dca", "🤖 DCA");
-        es.insert("buttons.alerts", "🔔 Alertas");
-        es.insert("buttons.signals", "🧠 Señales IA");
-        es.insert("buttons.wallet", "💳 Billetera");
-        es.insert("buttons.settings", "⚙️ Configuración");
-        es.insert("buttons.help", "❓ Ayuda");
-        es.insert("buttons.refresh", "🔄 Actualizar");
-        es.insert("buttons.back", "⬅️ Atrás");
-        
-        translations.insert("en", en);
-        translations.insert("es", es);
-        translations
-    }
-
-    fn get_nested_translation(&self, translations: &std::collections::HashMap<&str, &str>, key: &str) -> Option<&str> {
-        translations.get(key).copied()
-    }
-
-    fn replace_params(&self, text: &str, params: &[(&str, &str)]) -> String {
-        let mut result = text.to_string();
-        for (param, value) in params {
-            result = result.replace(&format!("{{{{{}}}}}", param), value);
+        let catalog = Catalog::embedded();
+        match catalog.get(lang, key) {
+            Some(_) => catalog.format(lang, key, params),
+            // Return key if no language has it
+            None => format!("[{}]", key),
         }
-        result
     }
 
     fn create_language_selection_keyboard(&self) -> InlineKeyboardMarkup {
-        InlineKeyboardMarkup::new(vec![
-            vec![
-                teloxide::types::InlineKeyboardButton::callback("🇺🇸 English", "lang_en"),
-                teloxide::types::InlineKeyboardButton::callback("🇪🇸 Español", "lang_es"),
-            ],
-            vec![
-                teloxide::types::InlineKeyboardButton::callback("🇫🇷 Français", "lang_fr"),
-                teloxide::types::InlineKeyboardButton::callback("🇩🇪 Deutsch", "lang_de"),
-            ],
-            vec![
-                teloxide::types::InlineKeyboardButton::callback("🇮🇹 Italiano", "lang_it"),
-                teloxide::types::InlineKeyboardButton::callback("🇧🇷 Português", "lang_pt"),
-            ],
-            vec![
-                teloxide::types::InlineKeyboardButton::callback("🇷🇺 Русский", "lang_ru"),
-                teloxide::types::InlineKeyboardButton::callback("🇨🇳 中文", "lang_zh"),
-            ],
-            vec![
-                teloxide::types::InlineKeyboardButton::callback("🇯🇵 日本語", "lang_ja"),
-                teloxide::types::InlineKeyboardButton::callback("🇰🇷 한국어", "lang_ko"),
-            ],
-        ])
+        InlineKeyboardMarkup::new(LANGUAGES.chunks(2).map(|row| {
+            row.iter()
+                .map(|(code, name)| {
+                    teloxide::types::InlineKeyboardButton::callback(*name, format!("lang_{}", code))
+                })
+                .collect::<Vec<_>>()
+        }))
     }
 
     fn create_main_keyboard(&self, lang: &str) -> InlineKeyboardMarkup {
@@ -1073,4 +1178,152 @@ impl TelegramConvexBridge {
             _ => format!("{:.2}%", percentage),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convex_client::PositionPnl;
+
+    fn token(mint: &str, symbol: &str, price: &str, change_24h: f64) -> TokenQuote {
+        TokenQuote {
+            mint: mint.to_string(),
+            symbol: symbol.to_string(),
+            name: format!("{} Token", symbol),
+            price: price.to_string(),
+            change_24h,
+            volume_24h: "1000000".to_string(),
+        }
+    }
+
+    fn position(symbol: &str, market_value: &str, percentage: f64) -> PortfolioPosition {
+        PortfolioPosition {
+            token_mint: format!("{}Mint", symbol),
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            market_value: market_value.to_string(),
+            pnl: PositionPnl {
+                amount: "0".to_string(),
+                percentage,
+                is_profit: percentage >= 0.0,
+            },
+        }
+    }
+
+    fn article(result: &InlineQueryResult) -> &InlineQueryResultArticle {
+        match result {
+            InlineQueryResult::Article(article) => article,
+            other => panic!("expected an article, got {:?}", other),
+        }
+    }
+
+    fn message_text(article: &InlineQueryResultArticle) -> &str {
+        match &article.input_message_content {
+            InputMessageContent::Text(text) => &text.message_text,
+            other => panic!("expected a text message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_token_results_carry_price_change_and_trade_command() {
+        let tokens = vec![
+            token("So11111111111111111111111111111111111111112", "SOL", "142.5", 3.456),
+            token("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263", "BONK", "0.0000231", -7.1),
+        ];
+
+        let results = TelegramConvexBridge::token_inline_results("token", "", &tokens);
+        assert_eq!(results.len(), 2);
+
+        let sol = article(&results[0]);
+        assert_eq!(sol.id, "token:So11111111111111111111111111111111111111112");
+        assert_eq!(sol.title, "SOL - $142.50");
+        assert_eq!(sol.description.as_deref(), Some("🟢 +3.46% | SOL Token"));
+        assert!(message_text(sol).ends_with("/trade SOL"));
+
+        let bonk = article(&results[1]);
+        assert_eq!(bonk.title, "BONK - $0.000023");
+        assert_eq!(bonk.description.as_deref(), Some("🔴 -7.10% | BONK Token"));
+        assert!(message_text(bonk).contains("24h Change: -7.10%"));
+    }
+
+    #[test]
+    fn test_token_results_are_stable_deduplicated_and_capped() {
+        let mut tokens: Vec<TokenQuote> = (0..30)
+            .map(|i| token(&format!("Mint{:02}", i), &format!("T{}", i), "1", 0.0))
+            .collect();
+        tokens.insert(1, token("Mint00", "T0", "1", 0.0));
+
+        let first = TelegramConvexBridge::token_inline_results("trending", "🔥 ", &tokens);
+        let second = TelegramConvexBridge::token_inline_results("trending", "🔥 ", &tokens);
+        assert_eq!(first.len(), MAX_INLINE_RESULTS);
+
+        let ids: Vec<&str> = first.iter().map(|result| article(result).id.as_str()).collect();
+        let repeat_ids: Vec<&str> = second.iter().map(|result| article(result).id.as_str()).collect();
+        assert_eq!(ids, repeat_ids);
+        assert_eq!(ids[0], "trending:Mint00");
+        assert_eq!(ids[1], "trending:Mint01");
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+        assert!(article(&first[0]).title.starts_with("🔥 T0"));
+    }
+
+    #[test]
+    fn test_portfolio_result_lists_top_five_positions() {
+        let portfolio = PortfolioSummary {
+            total_value: "5230.00".to_string(),
+            total_pnl: "430.00".to_string(),
+            total_pnl_percentage: "8.96".to_string(),
+            position_count: 6,
+        };
+        let positions = vec![
+            position("WIF", "120.00", -4.0),
+            position("SOL", "3000.00", 12.5),
+            position("DUST", "10.00", 1.0),
+            position("JUP", "900.00", 2.25),
+            position("BONK", "600.00", -1.5),
+            position("RAY", "600.50", 0.0),
+        ];
+
+        let result = TelegramConvexBridge::portfolio_inline_result(42, &portfolio, &positions);
+        let article = article(&result);
+        assert_eq!(article.id, "portfolio:42");
+
+        let text = message_text(article);
+        assert!(text.contains("💰 Total Value: $5230.00"));
+        assert!(text.contains("1. SOL - $3000.00 (+12.50%)"));
+        assert!(text.contains("2. JUP - $900.00 (+2.25%)"));
+        assert!(text.contains("3. RAY - $600.50 (+0.00%)"));
+        assert!(text.contains("4. BONK - $600.00 (-1.50%)"));
+        assert!(text.contains("5. WIF - $120.00 (-4.00%)"));
+        assert!(!text.contains("DUST"));
+    }
+
+    #[test]
+    fn test_portfolio_result_without_positions() {
+        let portfolio = PortfolioSummary {
+            total_value: "0".to_string(),
+            total_pnl: "0".to_string(),
+            total_pnl_percentage: "0".to_string(),
+            position_count: 0,
+        };
+
+        let result = TelegramConvexBridge::portfolio_inline_result(7, &portfolio, &[]);
+        assert!(!message_text(article(&result)).contains("Top Positions"));
+    }
+
+    #[test]
+    fn test_token_quote_deserializes_convex_shape() {
+        let quote: TokenQuote = serde_json::from_value(json!({
+            "mint": "So11111111111111111111111111111111111111112",
+            "symbol": "SOL",
+            "name": "Wrapped SOL",
+            "price": "142.5",
+            "change24h": -1.25,
+            "volume24h": "98765",
+            "marketCap": "1",
+            "lastUpdate": 0
+        }))
+        .unwrap();
+
+        assert_eq!(quote.change_24h, -1.25);
+        assert_eq!(quote.volume_24h, "98765");
+    }
+}
@@ -3,6 +3,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use warp::{Filter, Rejection, Reply};
 
 /// HTTP server for receiving webhooks from Convex
@@ -33,6 +34,11 @@ impl WebhookServer {
 
     /// Start the webhook server
     pub async fn start(self) -> Result<()> {
+        self.start_until(CancellationToken::new()).await
+    }
+
+    /// Serve until `shutdown` is cancelled; in-flight requests finish, new connections are refused
+    pub async fn start_until(self, shutdown: CancellationToken) -> Result<()> {
         let convex = self.convex.clone();
         let webhook_path = self.path.clone();
 
@@ -63,10 +69,14 @@ impl WebhookServer {
         println!("🚀 Webhook server starting on port {}", self.port);
         println!("📡 Webhook endpoint: http://localhost:{}{}", self.port, self.path);
         
-        warp::serve(routes)
-            .run(([0, 0, 0, 0], self.port))
-            .await;
+        let (_, server) = warp::serve(routes)
+            .try_bind_with_graceful_shutdown(([0, 0, 0, 0], self.port), async move {
+                shutdown.cancelled().await;
+            })
+            .map_err(|e| anyhow::anyhow!("Webhook server failed to bind port {}: {}", self.port, e))?;
+        server.await;
 
+        println!("🛑 Webhook server stopped");
         Ok(())
     }
 }
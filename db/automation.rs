@@ -0,0 +1,112 @@
+use chrono::Utc;
+
+use super::{unix, Database};
+use crate::errors::{BotError, Result};
+
+impl Database {
+    pub async fn upsert_order(&self, order_id: &str, user_id: i64, token_mint: &str, status: &str, data: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO orders (order_id, user_id, token_mint, status, data, updated_at) VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (order_id) DO UPDATE SET status = excluded.status, data = excluded.data, updated_at = excluded.updated_at",
+        )
+            .bind(order_id)
+            .bind(user_id)
+            .bind(token_mint)
+            .bind(status)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_orders_by_status(&self, statuses: &[&str]) -> Result<Vec<String>> {
+        self.get_data_by_status("orders", statuses).await
+    }
+
+    pub async fn get_user_orders(&self, user_id: i64) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT data FROM orders WHERE user_id = $1 ORDER BY updated_at, order_id")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn insert_order_execution(&self, execution_id: &str, order_id: &str, data: &str) -> Result<()> {
+        let now = unix(Utc::now());
+        sqlx::query(
+            "INSERT INTO order_executions (execution_id, order_id, data, created_at, updated_at) VALUES ($1, $2, $3, $4, $4)",
+        )
+            .bind(execution_id)
+            .bind(order_id)
+            .bind(data)
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Replace a stored execution, e.g. once its transaction finalizes
+    pub async fn update_order_execution(&self, execution_id: &str, data: &str) -> Result<()> {
+        let updated = sqlx::query("UPDATE order_executions SET data = $2, updated_at = $3 WHERE execution_id = $1")
+            .bind(execution_id)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        if updated == 0 {
+            return Err(BotError::not_found(format!("Execution {} not found", execution_id)));
+        }
+        Ok(())
+    }
+
+    pub async fn get_order_executions(&self, order_id: &str) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT data FROM order_executions WHERE order_id = $1 ORDER BY created_at, execution_id")
+            .bind(order_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn upsert_price_alert(&self, alert_id: &str, user_id: i64, symbol: &str, status: &str, data: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO price_alerts (alert_id, user_id, symbol, status, data, updated_at) VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (alert_id) DO UPDATE SET status = excluded.status, data = excluded.data, updated_at = excluded.updated_at",
+        )
+            .bind(alert_id)
+            .bind(user_id)
+            .bind(symbol)
+            .bind(status)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_price_alerts_by_status(&self, statuses: &[&str]) -> Result<Vec<String>> {
+        self.get_data_by_status("price_alerts", statuses).await
+    }
+
+    pub async fn delete_price_alert(&self, alert_id: &str) -> Result<()> {
+        self.delete_data("price_alerts", "alert_id", alert_id).await
+    }
+
+    pub async fn upsert_dca_strategy(&self, strategy_id: &str, user_id: i64, status: &str, data: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO dca_strategies (strategy_id, user_id, status, data, updated_at) VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (strategy_id) DO UPDATE SET status = excluded.status, data = excluded.data, updated_at = excluded.updated_at",
+        )
+            .bind(strategy_id)
+            .bind(user_id)
+            .bind(status)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_dca_strategies_by_status(&self, statuses: &[&str]) -> Result<Vec<String>> {
+        self.get_data_by_status("dca_strategies", statuses).await
+    }
+}
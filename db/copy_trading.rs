@@ -0,0 +1,68 @@
+use chrono::Utc;
+
+use super::{unix, Database};
+use crate::errors::Result;
+
+impl Database {
+    pub async fn insert_copy_execution(&self, execution_id: &str, follower_user_id: i64, data: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO copy_executions (execution_id, follower_user_id, data, created_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (execution_id) DO NOTHING",
+        )
+            .bind(execution_id)
+            .bind(follower_user_id)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_copy_executions(&self, follower_user_id: i64) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT data FROM copy_executions WHERE follower_user_id = $1 ORDER BY created_at, execution_id",
+        )
+            .bind(follower_user_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn get_copy_daily_risk(&self, follower_user_id: i64) -> Result<Option<String>> {
+        self.get_data_by_id("copy_daily_risk", "follower_user_id", follower_user_id).await
+    }
+
+    pub async fn upsert_copy_daily_risk(&self, follower_user_id: i64, data: &str) -> Result<()> {
+        self.upsert_data_by_id("copy_daily_risk", "follower_user_id", follower_user_id, data).await
+    }
+
+    pub async fn get_master_profile(&self, user_id: i64) -> Result<Option<String>> {
+        self.get_data_by_id("master_profiles", "user_id", user_id).await
+    }
+
+    pub async fn upsert_master_profile(&self, user_id: i64, data: &str) -> Result<()> {
+        self.upsert_data_by_id("master_profiles", "user_id", user_id, data).await
+    }
+
+    pub async fn list_master_profiles(&self) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT data FROM master_profiles ORDER BY user_id")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn get_master_fee_ledger(&self, master_user_id: i64) -> Result<Option<String>> {
+        self.get_data_by_id("master_fee_ledgers", "master_user_id", master_user_id).await
+    }
+
+    pub async fn upsert_master_fee_ledger(&self, master_user_id: i64, data: &str) -> Result<()> {
+        self.upsert_data_by_id("master_fee_ledgers", "master_user_id", master_user_id, data).await
+    }
+
+    /// A master's tracked holdings, mint to amount, so sells mirror proportionally after a restart
+    pub async fn get_master_positions(&self, master_user_id: i64) -> Result<Option<String>> {
+        self.get_data_by_id("master_positions", "master_user_id", master_user_id).await
+    }
+
+    pub async fn upsert_master_positions(&self, master_user_id: i64, data: &str) -> Result<()> {
+        self.upsert_data_by_id("master_positions", "master_user_id", master_user_id, data).await
+    }
+}
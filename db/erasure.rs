@@ -0,0 +1,108 @@
+use super::Database;
+use crate::errors::Result;
+
+/// Tables holding a user's trade history, keyed by the column naming the user
+const TRADE_HISTORY: [(&str, &str); 5] = [
+    ("trade_records", "user_id"),
+    ("orders", "user_id"),
+    ("copy_executions", "follower_user_id"),
+    ("lending_operations", "telegram_id"),
+    ("send_batches", "telegram_id"),
+];
+
+/// Copy-trading state of a follower or master
+const COPY_TRADING: [(&str, &str); 3] = [
+    ("copy_daily_risk", "follower_user_id"),
+    ("master_profiles", "user_id"),
+    ("master_positions", "master_user_id"),
+];
+
+/// What a wallet left behind besides its registration
+const WALLET_STATE: [&str; 3] = ["positions", "trading_modes", "paper_ledgers"];
+
+// /forgetme deletes; each returns how many rows went and is safe to repeat
+impl Database {
+    /// Closed trades, swaps, orders with their fills, copy fills, lending moves and sends
+    pub async fn delete_user_trade_history(&self, user_id: i64) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        // Fills first, while their orders still say whose they are
+        let mut deleted = sqlx::query(
+            "DELETE FROM order_executions WHERE order_id IN (SELECT order_id FROM orders WHERE user_id = $1)",
+        )
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        for (table, column) in TRADE_HISTORY {
+            let sql = format!("DELETE FROM {} WHERE {} = $1", table, column);
+            deleted += sqlx::query(&sql).bind(user_id).execute(&mut *tx).await?.rows_affected();
+        }
+        // Swaps are keyed by the Telegram id as text, like wallets
+        deleted += sqlx::query("DELETE FROM trades WHERE telegram_id = $1")
+            .bind(user_id.to_string())
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// Orders that never filled, which aren't trade history even when that is retained
+    pub async fn delete_user_unfilled_orders(&self, user_id: i64) -> Result<u64> {
+        Ok(sqlx::query(
+            "DELETE FROM orders WHERE user_id = $1 AND order_id NOT IN (SELECT order_id FROM order_executions)",
+        )
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected())
+    }
+
+    pub async fn delete_user_dca_strategies(&self, user_id: i64) -> Result<u64> {
+        Ok(sqlx::query("DELETE FROM dca_strategies WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected())
+    }
+
+    /// Alerts the manager no longer had in memory, such as triggered ones
+    pub async fn delete_user_price_alerts(&self, user_id: i64) -> Result<u64> {
+        Ok(sqlx::query("DELETE FROM price_alerts WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected())
+    }
+
+    pub async fn delete_user_copy_trading(&self, user_id: i64) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+        for (table, column) in COPY_TRADING {
+            let sql = format!("DELETE FROM {} WHERE {} = $1", table, column);
+            deleted += sqlx::query(&sql).bind(user_id).execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    pub async fn delete_master_fee_ledger(&self, master_user_id: i64) -> Result<u64> {
+        Ok(sqlx::query("DELETE FROM master_fee_ledgers WHERE master_user_id = $1")
+            .bind(master_user_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected())
+    }
+
+    /// Positions, trading mode and paper ledger of one wallet
+    pub async fn delete_wallet_state(&self, wallet_address: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+        for table in WALLET_STATE {
+            let sql = format!("DELETE FROM {} WHERE wallet_address = $1", table);
+            deleted += sqlx::query(&sql).bind(wallet_address).execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+}
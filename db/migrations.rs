@@ -0,0 +1,401 @@
+//! Embedded schema migrations
+//!
+//! Migrations run oldest first, each in its own transaction, and are recorded
+//! in `schema_version` with a checksum of their SQL. A row in `schema_lock`
+//! keeps two processes starting together from migrating at the same time.
+//!
+//! A `CREATE TABLE IF NOT EXISTS` whose table is already there adopts it
+//! only when its columns are the ones the migration declares; any drift
+//! stops the run instead of leaving a schema fresh installs don't share.
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use sqlx::{AnyConnection, AnyPool};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::errors::{BotError, Result};
+
+/// A lock this old belongs to a migrator that died mid-run
+const STALE_LOCK_SECS: i64 = 600;
+const LOCK_POLL: Duration = Duration::from_millis(250);
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(120);
+
+const CREATE_SCHEMA_VERSION: &str = "CREATE TABLE IF NOT EXISTS schema_version (
+    version BIGINT PRIMARY KEY,
+    name TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    applied_at BIGINT NOT NULL
+)";
+const CREATE_SCHEMA_LOCK: &str = "CREATE TABLE IF NOT EXISTS schema_lock (
+    id BIGINT PRIMARY KEY,
+    holder TEXT NOT NULL,
+    acquired_at BIGINT NOT NULL
+)";
+
+/// One step of the schema, identified by its version
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    sql: &'static str,
+}
+
+impl Migration {
+    pub const fn new(version: i64, name: &'static str, sql: &'static str) -> Self {
+        Self { version, name, sql }
+    }
+
+    /// The statements in order, comments dropped
+    pub fn statements(&self) -> Vec<String> {
+        self.sql
+            .split(';')
+            .map(|statement| {
+                statement
+                    .lines()
+                    .filter(|line| !line.trim_start().starts_with("--"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .trim()
+                    .to_string()
+            })
+            .filter(|statement| !statement.is_empty())
+            .collect()
+    }
+
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.sql.as_bytes()))
+    }
+}
+
+/// Every migration, oldest first. Add new ones at the end; never edit one that has shipped.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration::new(1, "wallets_and_trades", include_str!("migrations/0001_wallets_and_trades.sql")),
+    Migration::new(2, "orders_alerts_dca", include_str!("migrations/0002_orders_alerts_dca.sql")),
+    Migration::new(3, "user_settings_and_paper", include_str!("migrations/0003_user_settings_and_paper.sql")),
+    Migration::new(4, "history_and_analytics", include_str!("migrations/0004_history_and_analytics.sql")),
+    Migration::new(5, "lending_and_sends", include_str!("migrations/0005_lending_and_sends.sql")),
+    Migration::new(6, "copy_trading_and_masters", include_str!("migrations/0006_copy_trading_and_masters.sql")),
+    Migration::new(7, "admin_state", include_str!("migrations/0007_admin_state.sql")),
+    Migration::new(8, "watchlists", include_str!("migrations/0008_watchlists.sql")),
+    Migration::new(9, "master_positions", include_str!("migrations/0009_master_positions.sql")),
+];
+
+/// How startup treats the schema
+#[derive(Debug, Clone)]
+pub struct MigrationOptions {
+    /// List pending migrations without applying them
+    pub dry_run: bool,
+    /// Exit once the schema is current instead of starting the bot
+    pub migrate_only: bool,
+    /// How long to wait while another process holds the migration lock
+    pub lock_timeout: Duration,
+}
+
+impl Default for MigrationOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            migrate_only: false,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+        }
+    }
+}
+
+impl MigrationOptions {
+    /// `--migrate-only` and `--dry-run` from the binary's arguments; a dry run implies `--migrate-only`
+    pub fn from_args<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut options = Self::default();
+        for arg in args {
+            match arg.as_ref() {
+                "--migrate-only" => options.migrate_only = true,
+                "--dry-run" => {
+                    options.dry_run = true;
+                    options.migrate_only = true;
+                }
+                _ => {}
+            }
+        }
+        options
+    }
+}
+
+/// What a run did, or on a dry run would do
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    pub from_version: i64,
+    pub to_version: i64,
+    /// Version and name of each migration applied (or pending, on a dry run)
+    pub migrations: Vec<(i64, &'static str)>,
+    pub dry_run: bool,
+}
+
+/// Applies `MIGRATIONS` to a pool
+pub struct Migrator<'a> {
+    pool: &'a AnyPool,
+    migrations: &'a [Migration],
+    lock_timeout: Duration,
+    holder: String,
+}
+
+impl<'a> Migrator<'a> {
+    pub fn new(pool: &'a AnyPool) -> Self {
+        Self {
+            pool,
+            migrations: MIGRATIONS,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+            holder: format!("pid {} ({})", std::process::id(), uuid::Uuid::new_v4()),
+        }
+    }
+
+    /// Use another migration list; tests simulate older builds with a prefix of `MIGRATIONS`
+    pub fn with_migrations(mut self, migrations: &'a [Migration]) -> Self {
+        self.migrations = migrations;
+        self
+    }
+
+    pub fn with_lock_timeout(mut self, lock_timeout: Duration) -> Self {
+        self.lock_timeout = lock_timeout;
+        self
+    }
+
+    pub fn latest_version(&self) -> i64 {
+        self.migrations.last().map_or(0, |migration| migration.version)
+    }
+
+    /// Highest applied version, 0 for a database never migrated
+    pub async fn current_version(&self) -> Result<i64> {
+        Ok(self.applied_if_any().await?.last().map_or(0, |(version, _)| *version))
+    }
+
+    /// Bring the schema up to date, or on a dry run only report what's pending
+    pub async fn run(&self, dry_run: bool) -> Result<MigrationReport> {
+        if dry_run {
+            let applied = self.applied_if_any().await?;
+            return Ok(MigrationReport {
+                from_version: applied.last().map_or(0, |(version, _)| *version),
+                to_version: self.latest_version(),
+                migrations: self.pending(&applied)?.iter().map(|m| (m.version, m.name)).collect(),
+                dry_run: true,
+            });
+        }
+
+        sqlx::query(CREATE_SCHEMA_VERSION).execute(self.pool).await?;
+        sqlx::query(CREATE_SCHEMA_LOCK).execute(self.pool).await?;
+        self.acquire_lock().await?;
+        let result = self.apply_pending().await;
+        if let Err(e) = self.release_lock().await {
+            warn!("🗄️ Failed to release the migration lock: {}", e);
+        }
+        result
+    }
+
+    async fn apply_pending(&self) -> Result<MigrationReport> {
+        // Read under the lock: whoever held it before may have applied some already
+        let applied = self.applied().await?;
+        let from_version = applied.last().map_or(0, |(version, _)| *version);
+        let mut report = MigrationReport {
+            from_version,
+            to_version: from_version,
+            migrations: Vec::new(),
+            dry_run: false,
+        };
+
+        for migration in self.pending(&applied)? {
+            let mut tx = self.pool.begin().await?;
+            for statement in migration.statements() {
+                if let Some((table, declared)) = created_table(&statement) {
+                    check_adopted_columns(&mut tx, migration, &table, &declared).await?;
+                }
+                sqlx::query(&statement).execute(&mut *tx).await.map_err(|e| {
+                    BotError::migration(format!("Migration {} ({}) failed: {}", migration.version, migration.name, e))
+                })?;
+            }
+            sqlx::query("INSERT INTO schema_version (version, name, checksum, applied_at) VALUES ($1, $2, $3, $4)")
+                .bind(migration.version)
+                .bind(migration.name)
+                .bind(migration.checksum())
+                .bind(Utc::now().timestamp())
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+
+            info!("🗄️ Applied migration {:04} {}", migration.version, migration.name);
+            report.migrations.push((migration.version, migration.name));
+            report.to_version = report.to_version.max(migration.version);
+        }
+        Ok(report)
+    }
+
+    /// Applied versions and checksums, oldest first
+    async fn applied(&self) -> Result<Vec<(i64, String)>> {
+        Ok(sqlx::query_as("SELECT version, checksum FROM schema_version ORDER BY version")
+            .fetch_all(self.pool)
+            .await?)
+    }
+
+    /// `applied` without creating anything, so a dry run leaves a never-migrated database untouched
+    async fn applied_if_any(&self) -> Result<Vec<(i64, String)>> {
+        match self.applied().await {
+            // No `schema_version` table yet
+            Err(BotError::Database(sqlx::Error::Database(_))) => Ok(Vec::new()),
+            applied => applied,
+        }
+    }
+
+    /// Migrations not applied yet, after checking the applied ones are the ones this build ships
+    fn pending(&self, applied: &[(i64, String)]) -> Result<Vec<&'a Migration>> {
+        if !self.migrations.windows(2).all(|pair| pair[0].version < pair[1].version) {
+            return Err(BotError::migration("Migrations must be listed in increasing version order"));
+        }
+
+        for (version, checksum) in applied {
+            match self.migrations.iter().find(|m| m.version == *version) {
+                Some(migration) if migration.checksum() == *checksum => {}
+                Some(migration) => {
+                    return Err(BotError::migration(format!(
+                        "Migration {} ({}) was edited after it was applied; add a new migration instead",
+                        migration.version, migration.name
+                    )));
+                }
+                None => {
+                    return Err(BotError::migration(format!(
+                        "The database has migration {}, which this build doesn't know (latest is {}); run a newer build",
+                        version,
+                        self.latest_version()
+                    )));
+                }
+            }
+        }
+
+        Ok(self.migrations
+            .iter()
+            .filter(|migration| !applied.iter().any(|(version, _)| *version == migration.version))
+            .collect())
+    }
+
+    async fn acquire_lock(&self) -> Result<()> {
+        let started = Instant::now();
+        loop {
+            let now = Utc::now().timestamp();
+            let cleared = sqlx::query("DELETE FROM schema_lock WHERE id = 1 AND acquired_at < $1")
+                .bind(now - STALE_LOCK_SECS)
+                .execute(self.pool)
+                .await?
+                .rows_affected();
+            if cleared > 0 {
+                warn!("🗄️ Took over a migration lock older than {} seconds", STALE_LOCK_SECS);
+            }
+
+            let taken = sqlx::query(
+                "INSERT INTO schema_lock (id, holder, acquired_at) VALUES (1, $1, $2) ON CONFLICT (id) DO NOTHING",
+            )
+                .bind(self.holder.as_str())
+                .bind(now)
+                .execute(self.pool)
+                .await?
+                .rows_affected();
+            if taken == 1 {
+                return Ok(());
+            }
+
+            if started.elapsed() >= self.lock_timeout {
+                let holder: Option<String> = sqlx::query_scalar("SELECT holder FROM schema_lock WHERE id = 1")
+                    .fetch_optional(self.pool)
+                    .await?;
+                return Err(BotError::migration(format!(
+                    "Timed out after {}s waiting for the migration lock held by {}",
+                    self.lock_timeout.as_secs(),
+                    holder.unwrap_or_else(|| "another process".to_string())
+                )));
+            }
+            tokio::time::sleep(LOCK_POLL).await;
+        }
+    }
+
+    async fn release_lock(&self) -> Result<()> {
+        sqlx::query("DELETE FROM schema_lock WHERE id = 1 AND holder = $1")
+            .bind(self.holder.as_str())
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Table name and column names of a `CREATE TABLE IF NOT EXISTS` statement
+fn created_table(statement: &str) -> Option<(String, Vec<String>)> {
+    const PREFIX: &str = "CREATE TABLE IF NOT EXISTS ";
+    if !statement.get(..PREFIX.len())?.eq_ignore_ascii_case(PREFIX) {
+        return None;
+    }
+    let rest = &statement[PREFIX.len()..];
+    let open = rest.find('(')?;
+    let close = rest.rfind(')')?;
+    let table = rest[..open].trim().to_lowercase();
+
+    let mut columns = Vec::new();
+    let mut depth = 0;
+    let mut start = open + 1;
+    for (i, c) in rest[..close].char_indices().skip_while(|(i, _)| *i <= open) {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                columns.extend(column_name(&rest[start..i]));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    columns.extend(column_name(&rest[start..close]));
+    Some((table, columns))
+}
+
+/// The column a table element defines; constraints define none
+fn column_name(element: &str) -> Option<String> {
+    let first = element.split_whitespace().next()?;
+    let is_constraint = ["PRIMARY", "UNIQUE", "CONSTRAINT", "FOREIGN", "CHECK"]
+        .iter()
+        .any(|keyword| first.eq_ignore_ascii_case(keyword));
+    (!is_constraint).then(|| first.to_lowercase())
+}
+
+/// Refuse to adopt an existing `table` whose columns differ from what `migration` declares
+async fn check_adopted_columns(
+    conn: &mut AnyConnection,
+    migration: &Migration,
+    table: &str,
+    declared: &[String],
+) -> Result<()> {
+    let sql = match conn.backend_name() {
+        "PostgreSQL" => {
+            "SELECT column_name::text FROM information_schema.columns
+             WHERE table_schema = current_schema() AND table_name = $1"
+        }
+        _ => "SELECT name FROM pragma_table_info($1)",
+    };
+    let existing: Vec<String> = sqlx::query_scalar(sql).bind(table).fetch_all(&mut *conn).await?;
+    if existing.is_empty() {
+        return Ok(());
+    }
+
+    let existing: Vec<String> = existing.iter().map(|column| column.to_lowercase()).collect();
+    let missing: Vec<&str> = declared.iter().filter(|c| !existing.contains(c)).map(String::as_str).collect();
+    let unexpected: Vec<&str> = existing.iter().filter(|c| !declared.contains(c)).map(String::as_str).collect();
+    if missing.is_empty() && unexpected.is_empty() {
+        return Ok(());
+    }
+    Err(BotError::migration(format!(
+        "Table {} already exists with columns that differ from migration {} ({}): missing [{}], unexpected [{}]. \
+         Bring the table in line by hand before starting",
+        table,
+        migration.version,
+        migration.name,
+        missing.join(", "),
+        unexpected.join(", ")
+    )))
+}
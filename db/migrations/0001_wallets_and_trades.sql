@@ -0,0 +1,42 @@
+-- Tables the store created before migrations existed; one already there is adopted only if its columns match
+CREATE TABLE IF NOT EXISTS user_wallets (
+    telegram_id TEXT NOT NULL,
+    wallet_address TEXT NOT NULL,
+    label TEXT,
+    is_active BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL,
+    last_used BIGINT,
+    PRIMARY KEY (telegram_id, wallet_address)
+);
+CREATE INDEX IF NOT EXISTS idx_user_wallets_address ON user_wallets (wallet_address);
+
+CREATE TABLE IF NOT EXISTS signing_sessions (
+    session_id TEXT PRIMARY KEY,
+    telegram_id TEXT NOT NULL,
+    wallet_address TEXT NOT NULL,
+    encrypted_data TEXT,
+    max_transaction_sol DOUBLE PRECISION NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_signing_sessions_telegram ON signing_sessions (telegram_id);
+
+CREATE TABLE IF NOT EXISTS trades (
+    telegram_id TEXT NOT NULL,
+    token TEXT NOT NULL,
+    sol_amount DOUBLE PRECISION NOT NULL,
+    token_amount DOUBLE PRECISION NOT NULL,
+    rebate_sol DOUBLE PRECISION NOT NULL DEFAULT 0,
+    tx_signature TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_trades_user_token ON trades (telegram_id, token);
+CREATE INDEX IF NOT EXISTS idx_trades_created ON trades (created_at);
+
+CREATE TABLE IF NOT EXISTS positions (
+    wallet_address TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (wallet_address, mint)
+);
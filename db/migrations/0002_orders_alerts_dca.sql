@@ -0,0 +1,39 @@
+-- Order, alert and DCA rows keep the serialized record; the columns are what restarts filter on
+CREATE TABLE IF NOT EXISTS orders (
+    order_id TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    token_mint TEXT NOT NULL,
+    status TEXT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_orders_status ON orders (status);
+CREATE INDEX IF NOT EXISTS idx_orders_user ON orders (user_id);
+
+CREATE TABLE IF NOT EXISTS order_executions (
+    execution_id TEXT PRIMARY KEY,
+    order_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_order_executions_order ON order_executions (order_id);
+
+CREATE TABLE IF NOT EXISTS price_alerts (
+    alert_id TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    symbol TEXT NOT NULL,
+    status TEXT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_price_alerts_status ON price_alerts (status);
+
+CREATE TABLE IF NOT EXISTS dca_strategies (
+    strategy_id TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_dca_strategies_status ON dca_strategies (status);
@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS trade_records (
+    trade_id TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    data TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_trade_records_user ON trade_records (user_id);
+
+CREATE TABLE IF NOT EXISTS leaderboard_trades (
+    user_id BIGINT NOT NULL,
+    traded_at BIGINT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_leaderboard_trades_traded ON leaderboard_trades (traded_at);
+
+CREATE TABLE IF NOT EXISTS signals (
+    signal_id TEXT PRIMARY KEY,
+    token_address TEXT NOT NULL,
+    status TEXT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_signals_status ON signals (status);
+
+-- A single row, id 1
+CREATE TABLE IF NOT EXISTS signal_stats (
+    id BIGINT PRIMARY KEY,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS blink_events (
+    event_id TEXT PRIMARY KEY,
+    blink_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    data TEXT NOT NULL,
+    occurred_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_blink_events_occurred ON blink_events (occurred_at);
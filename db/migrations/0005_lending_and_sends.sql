@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS lending_operations (
+    telegram_id BIGINT NOT NULL,
+    data TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_lending_operations_user ON lending_operations (telegram_id);
+
+CREATE TABLE IF NOT EXISTS send_batches (
+    batch_id TEXT PRIMARY KEY,
+    telegram_id BIGINT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS copy_executions (
+    execution_id TEXT PRIMARY KEY,
+    follower_user_id BIGINT NOT NULL,
+    data TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_copy_executions_follower ON copy_executions (follower_user_id);
+
+CREATE TABLE IF NOT EXISTS copy_daily_risk (
+    follower_user_id BIGINT PRIMARY KEY,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS master_profiles (
+    user_id BIGINT PRIMARY KEY,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS master_fee_ledgers (
+    master_user_id BIGINT PRIMARY KEY,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Operator state keyed by name, e.g. maintenance mode or an unfinished broadcast
+CREATE TABLE IF NOT EXISTS admin_state (
+    state_key TEXT PRIMARY KEY,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS watchlists (
+    user_id BIGINT PRIMARY KEY,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
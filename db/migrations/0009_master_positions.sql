@@ -0,0 +1,6 @@
+-- A master's tracked holdings, so exits after a restart mirror proportionally
+CREATE TABLE IF NOT EXISTS master_positions (
+    master_user_id BIGINT PRIMARY KEY,
+    data TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
@@ -0,0 +1,166 @@
+//! Storage on SQLite or PostgreSQL, picked by `DATABASE_URL`
+//!
+//! `Database::new` brings the schema up to date before anything reads it.
+//! Most tables keep a record serialized as JSON in `data`, next to the
+//! columns lookups filter on.
+mod migrations;
+mod wallets;
+mod trades;
+mod automation;
+mod copy_trading;
+mod user_state;
+mod erasure;
+
+pub use migrations::{Migration, MigrationOptions, MigrationReport, Migrator, MIGRATIONS};
+pub use trades::UserRebates;
+pub use wallets::{SigningSession, UserWallet};
+
+use chrono::{DateTime, Utc};
+use sqlx::any::{install_default_drivers, AnyPoolOptions};
+use sqlx::AnyPool;
+use tracing::info;
+
+use crate::errors::Result;
+
+const MAX_CONNECTIONS: u32 = 10;
+
+pub struct Database {
+    pool: AnyPool,
+}
+
+impl Database {
+    /// Connect and apply pending migrations
+    pub async fn new(database_url: &str) -> Result<Self> {
+        Self::connect(database_url, &MigrationOptions::default()).await
+    }
+
+    /// Connect and migrate as `options` say. The binary builds them with
+    /// `MigrationOptions::from_args` and exits afterwards when `migrate_only` is set.
+    pub async fn connect(database_url: &str, options: &MigrationOptions) -> Result<Self> {
+        let database = Self::open(database_url).await?;
+        database.migrate(options).await?;
+        Ok(database)
+    }
+
+    /// Connect without touching the schema
+    pub async fn open(database_url: &str) -> Result<Self> {
+        install_default_drivers();
+        // Each connection to an in-memory SQLite database gets its own empty one
+        let max_connections = if database_url.contains(":memory:") { 1 } else { MAX_CONNECTIONS };
+        let pool = AnyPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(&Self::connect_url(database_url))
+            .await?;
+        Ok(Self { pool })
+    }
+
+    /// SQLite files are created on first start rather than refused
+    fn connect_url(database_url: &str) -> String {
+        let is_sqlite_file = database_url.starts_with("sqlite:") && !database_url.contains(":memory:");
+        if is_sqlite_file && !database_url.contains("mode=") {
+            let separator = if database_url.contains('?') { '&' } else { '?' };
+            format!("{}{}mode=rwc", database_url, separator)
+        } else {
+            database_url.to_string()
+        }
+    }
+
+    /// Apply pending migrations under the migration lock; a dry run only lists them
+    pub async fn migrate(&self, options: &MigrationOptions) -> Result<MigrationReport> {
+        let report = Migrator::new(&self.pool)
+            .with_lock_timeout(options.lock_timeout)
+            .run(options.dry_run)
+            .await?;
+
+        if report.dry_run {
+            info!("🗄️ Schema at version {}, {} migration(s) pending", report.from_version, report.migrations.len());
+            for (version, name) in &report.migrations {
+                info!("🗄️ Would apply {:04} {}", version, name);
+            }
+        } else if report.migrations.is_empty() {
+            info!("🗄️ Schema up to date at version {}", report.to_version);
+        } else {
+            info!("🗄️ Schema migrated from version {} to {}", report.from_version, report.to_version);
+        }
+        Ok(report)
+    }
+
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub(crate) fn pool(&self) -> &AnyPool {
+        &self.pool
+    }
+
+    /// `data` of the row keyed `key` in a one-record-per-key table
+    async fn get_data(&self, table: &str, key_column: &str, key: &str) -> Result<Option<String>> {
+        let sql = format!("SELECT data FROM {} WHERE {} = $1", table, key_column);
+        Ok(sqlx::query_scalar(&sql).bind(key).fetch_optional(&self.pool).await?)
+    }
+
+    async fn upsert_data(&self, table: &str, key_column: &str, key: &str, data: &str) -> Result<()> {
+        let sql = Self::upsert_sql(table, key_column);
+        sqlx::query(&sql).bind(key).bind(data).bind(unix(Utc::now())).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn delete_data(&self, table: &str, key_column: &str, key: &str) -> Result<()> {
+        let sql = format!("DELETE FROM {} WHERE {} = $1", table, key_column);
+        sqlx::query(&sql).bind(key).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// `get_data` for tables keyed by a user or master id
+    async fn get_data_by_id(&self, table: &str, key_column: &str, id: i64) -> Result<Option<String>> {
+        let sql = format!("SELECT data FROM {} WHERE {} = $1", table, key_column);
+        Ok(sqlx::query_scalar(&sql).bind(id).fetch_optional(&self.pool).await?)
+    }
+
+    async fn upsert_data_by_id(&self, table: &str, key_column: &str, id: i64, data: &str) -> Result<()> {
+        let sql = Self::upsert_sql(table, key_column);
+        sqlx::query(&sql).bind(id).bind(data).bind(unix(Utc::now())).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn delete_data_by_id(&self, table: &str, key_column: &str, id: i64) -> Result<()> {
+        let sql = format!("DELETE FROM {} WHERE {} = $1", table, key_column);
+        sqlx::query(&sql).bind(id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// `data` of every row whose `status` is one of `statuses`
+    async fn get_data_by_status(&self, table: &str, statuses: &[&str]) -> Result<Vec<String>> {
+        if statuses.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = format!("SELECT data FROM {} WHERE status IN ({})", table, placeholders(1, statuses.len()));
+        let mut query = sqlx::query_scalar::<_, String>(&sql);
+        for status in statuses {
+            query = query.bind(*status);
+        }
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    fn upsert_sql(table: &str, key_column: &str) -> String {
+        format!(
+            "INSERT INTO {table} ({key_column}, data, updated_at) VALUES ($1, $2, $3) \
+             ON CONFLICT ({key_column}) DO UPDATE SET data = excluded.data, updated_at = excluded.updated_at"
+        )
+    }
+}
+
+/// Timestamps are stored as Unix seconds, which every backend compares the same way
+fn unix(at: DateTime<Utc>) -> i64 {
+    at.timestamp()
+}
+
+fn from_unix(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, 0).unwrap_or_default()
+}
+
+/// `$first, $first+1, …` for an `IN` list of `count` values
+fn placeholders(first: usize, count: usize) -> String {
+    (first..first + count).map(|i| format!("${}", i)).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,235 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::{unix, Database};
+use crate::errors::Result;
+
+/// MEV rebates earned, in SOL
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UserRebates {
+    pub today: f64,
+    pub week: f64,
+    pub month: f64,
+    pub all_time: f64,
+}
+
+impl Database {
+    /// A swap as the user saw it: SOL and token amounts are negative on sells
+    pub async fn record_trade(
+        &self,
+        telegram_id: &str,
+        token: &str,
+        sol_amount: f64,
+        token_amount: f64,
+        rebate_sol: f64,
+        tx_signature: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO trades (telegram_id, token, sol_amount, token_amount, rebate_sol, tx_signature, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+            .bind(telegram_id)
+            .bind(token)
+            .bind(sol_amount)
+            .bind(token_amount)
+            .bind(rebate_sol)
+            .bind(tx_signature)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Rebates since midnight UTC, over the last 7 and 30 days, and ever
+    pub async fn get_user_rebates(&self, telegram_id: &str) -> Result<UserRebates> {
+        let now = Utc::now();
+        let midnight = now.date_naive().and_time(chrono::NaiveTime::MIN).and_utc();
+        let (today, week, month, all_time): (f64, f64, f64, f64) = sqlx::query_as(
+            "SELECT
+                 COALESCE(SUM(CASE WHEN created_at >= $2 THEN rebate_sol ELSE 0.0 END), 0.0),
+                 COALESCE(SUM(CASE WHEN created_at >= $3 THEN rebate_sol ELSE 0.0 END), 0.0),
+                 COALESCE(SUM(CASE WHEN created_at >= $4 THEN rebate_sol ELSE 0.0 END), 0.0),
+                 COALESCE(SUM(rebate_sol), 0.0)
+             FROM trades WHERE telegram_id = $1",
+        )
+            .bind(telegram_id)
+            .bind(unix(midnight))
+            .bind(unix(now - Duration::days(7)))
+            .bind(unix(now - Duration::days(30)))
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(UserRebates { today, week, month, all_time })
+    }
+
+    /// Percent return on the SOL the wallet's owner put into `token`, counting a sale
+    /// of `sol_received` not recorded yet; 0 without recorded buys
+    pub async fn calculate_pnl(&self, user_wallet: &str, token: &str, sol_received: f64) -> Result<f64> {
+        let (spent, returned): (f64, f64) = sqlx::query_as(
+            "SELECT
+                 COALESCE(SUM(CASE WHEN sol_amount > 0 THEN sol_amount ELSE 0.0 END), 0.0),
+                 COALESCE(SUM(CASE WHEN sol_amount < 0 THEN -sol_amount ELSE 0.0 END), 0.0)
+             FROM trades
+             WHERE token = $2
+               AND telegram_id IN (SELECT telegram_id FROM user_wallets WHERE wallet_address = $1)",
+        )
+            .bind(user_wallet)
+            .bind(token)
+            .fetch_one(&self.pool)
+            .await?;
+
+        if spent <= 0.0 {
+            return Ok(0.0);
+        }
+        Ok((returned + sol_received - spent) / spent * 100.0)
+    }
+
+    /// SOL traded by everyone since `since`, buys and sells alike
+    pub async fn get_trade_volume_since(&self, since: DateTime<Utc>) -> Result<f64> {
+        Ok(sqlx::query_scalar("SELECT COALESCE(SUM(ABS(sol_amount)), 0.0) FROM trades WHERE created_at >= $1")
+            .bind(unix(since))
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    pub async fn insert_trade_record(&self, user_id: i64, trade_id: &str, data: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO trade_records (trade_id, user_id, data, created_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (trade_id) DO NOTHING",
+        )
+            .bind(trade_id)
+            .bind(user_id)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_user_trade_records(&self, user_id: i64) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT data FROM trade_records WHERE user_id = $1 ORDER BY created_at, trade_id")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn insert_leaderboard_trade(&self, user_id: i64, traded_at: DateTime<Utc>, data: &str) -> Result<()> {
+        sqlx::query("INSERT INTO leaderboard_trades (user_id, traded_at, data) VALUES ($1, $2, $3)")
+            .bind(user_id)
+            .bind(unix(traded_at))
+            .bind(data)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Trades at or after `since`, every trade when `None`. Usernames aren't stored;
+    /// the leaderboard shows public names or pseudonyms.
+    pub async fn get_leaderboard_trades(&self, since: Option<DateTime<Utc>>) -> Result<Vec<(i64, Option<String>, String)>> {
+        let rows: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT user_id, data FROM leaderboard_trades WHERE traded_at >= $1 ORDER BY traded_at",
+        )
+            .bind(since.map_or(i64::MIN, unix))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|(user_id, data)| (user_id, None, data)).collect())
+    }
+
+    pub async fn upsert_signal(&self, signal_id: &str, token_address: &str, status: &str, data: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO signals (signal_id, token_address, status, data, updated_at) VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (signal_id) DO UPDATE SET status = excluded.status, data = excluded.data, updated_at = excluded.updated_at",
+        )
+            .bind(signal_id)
+            .bind(token_address)
+            .bind(status)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_signals_by_status(&self, statuses: &[&str]) -> Result<Vec<String>> {
+        self.get_data_by_status("signals", statuses).await
+    }
+
+    pub async fn get_signal_stats(&self) -> Result<Option<String>> {
+        self.get_data_by_id("signal_stats", "id", 1).await
+    }
+
+    pub async fn upsert_signal_stats(&self, data: &str) -> Result<()> {
+        self.upsert_data_by_id("signal_stats", "id", 1, data).await
+    }
+
+    pub async fn insert_blink_event(
+        &self,
+        event_id: &str,
+        blink_id: &str,
+        kind: &str,
+        data: &str,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO blink_events (event_id, blink_id, kind, data, occurred_at) VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (event_id) DO NOTHING",
+        )
+            .bind(event_id)
+            .bind(blink_id)
+            .bind(kind)
+            .bind(data)
+            .bind(unix(at))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_blink_events_since(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT data FROM blink_events WHERE occurred_at >= $1 ORDER BY occurred_at, event_id")
+            .bind(unix(since))
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Delete events older than `cutoff`; returns how many went
+    pub async fn delete_blink_events_before(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        Ok(sqlx::query("DELETE FROM blink_events WHERE occurred_at < $1")
+            .bind(unix(cutoff))
+            .execute(&self.pool)
+            .await?
+            .rows_affected())
+    }
+
+    pub async fn record_lending_operation(&self, telegram_id: i64, data: &str) -> Result<()> {
+        sqlx::query("INSERT INTO lending_operations (telegram_id, data, created_at) VALUES ($1, $2, $3)")
+            .bind(telegram_id)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_lending_operations(&self, telegram_id: i64) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar("SELECT data FROM lending_operations WHERE telegram_id = $1 ORDER BY created_at")
+            .bind(telegram_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn save_send_batch(&self, telegram_id: i64, batch_id: &str, data: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO send_batches (batch_id, telegram_id, data, updated_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (batch_id) DO UPDATE SET data = excluded.data, updated_at = excluded.updated_at",
+        )
+            .bind(batch_id)
+            .bind(telegram_id)
+            .bind(data)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_send_batch(&self, batch_id: &str) -> Result<Option<String>> {
+        self.get_data("send_batches", "batch_id", batch_id).await
+    }
+}
@@ -0,0 +1,44 @@
+use super::Database;
+use crate::errors::Result;
+
+impl Database {
+    pub async fn get_user_settings(&self, user_id: i64) -> Result<Option<String>> {
+        self.get_data_by_id("user_settings", "user_id", user_id).await
+    }
+
+    pub async fn upsert_user_settings(&self, user_id: i64, data: &str) -> Result<()> {
+        self.upsert_data_by_id("user_settings", "user_id", user_id, data).await
+    }
+
+    pub async fn delete_user_settings(&self, user_id: i64) -> Result<()> {
+        self.delete_data_by_id("user_settings", "user_id", user_id).await
+    }
+
+    /// Every stored watchlist with its owner
+    pub async fn get_watchlists(&self) -> Result<Vec<(i64, String)>> {
+        Ok(sqlx::query_as("SELECT user_id, data FROM watchlists ORDER BY user_id")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub async fn upsert_watchlist(&self, user_id: i64, data: &str) -> Result<()> {
+        self.upsert_data_by_id("watchlists", "user_id", user_id, data).await
+    }
+
+    pub async fn delete_watchlist(&self, user_id: i64) -> Result<()> {
+        self.delete_data_by_id("watchlists", "user_id", user_id).await
+    }
+
+    /// Operator state such as maintenance mode or an unfinished broadcast
+    pub async fn get_admin_state(&self, key: &str) -> Result<Option<String>> {
+        self.get_data("admin_state", "state_key", key).await
+    }
+
+    pub async fn upsert_admin_state(&self, key: &str, data: &str) -> Result<()> {
+        self.upsert_data("admin_state", "state_key", key, data).await
+    }
+
+    pub async fn delete_admin_state(&self, key: &str) -> Result<()> {
+        self.delete_data("admin_state", "state_key", key).await
+    }
+}
@@ -0,0 +1,257 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::{from_unix, unix, Database};
+use crate::errors::{BotError, Result};
+use crate::trading::Position;
+
+/// A wallet registered to a Telegram user; only the public address is stored
+#[derive(Debug, Clone)]
+pub struct UserWallet {
+    pub wallet_address: String,
+    pub label: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SigningSession {
+    pub session_id: String,
+    pub telegram_id: String,
+    pub wallet_address: String,
+    pub encrypted_data: Option<String>,
+    pub max_transaction_sol: f64,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Database {
+    /// Add a wallet to the user's list and make it their active one
+    pub async fn register_user_wallet(&self, telegram_id: &str, wallet_address: &str) -> Result<()> {
+        let now = unix(Utc::now());
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO user_wallets (telegram_id, wallet_address, is_active, created_at, last_used)
+             VALUES ($1, $2, 0, $3, $3)
+             ON CONFLICT (telegram_id, wallet_address) DO NOTHING",
+        )
+            .bind(telegram_id)
+            .bind(wallet_address)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE user_wallets SET is_active = CASE WHEN wallet_address = $2 THEN 1 ELSE 0 END WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .bind(wallet_address)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_user_wallets(&self, telegram_id: &str) -> Result<Vec<UserWallet>> {
+        let rows: Vec<(String, Option<String>, i64, i64, Option<i64>)> = sqlx::query_as(
+            "SELECT wallet_address, label, is_active, created_at, last_used
+             FROM user_wallets WHERE telegram_id = $1 ORDER BY created_at, wallet_address",
+        )
+            .bind(telegram_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(wallet_address, label, is_active, created_at, last_used)| UserWallet {
+                wallet_address,
+                label,
+                is_active: is_active != 0,
+                created_at: from_unix(created_at),
+                last_used: last_used.map(from_unix),
+            })
+            .collect())
+    }
+
+    pub async fn get_active_wallet(&self, telegram_id: &str) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar("SELECT wallet_address FROM user_wallets WHERE telegram_id = $1 AND is_active = 1")
+            .bind(telegram_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    /// Switch the user's active wallet to one they already registered
+    pub async fn set_active_wallet(&self, telegram_id: &str, wallet_address: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let updated = sqlx::query(
+            "UPDATE user_wallets SET is_active = 1, last_used = $3 WHERE telegram_id = $1 AND wallet_address = $2",
+        )
+            .bind(telegram_id)
+            .bind(wallet_address)
+            .bind(unix(Utc::now()))
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if updated == 0 {
+            return Err(BotError::not_found(format!("Wallet {} isn't one of yours", wallet_address)));
+        }
+        sqlx::query("UPDATE user_wallets SET is_active = 0 WHERE telegram_id = $1 AND wallet_address <> $2")
+            .bind(telegram_id)
+            .bind(wallet_address)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The Telegram id a wallet is registered to
+    pub async fn get_wallet_owner(&self, wallet_address: &str) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar("SELECT telegram_id FROM user_wallets WHERE wallet_address = $1 ORDER BY created_at LIMIT 1")
+            .bind(wallet_address)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    pub async fn delete_user_wallets(&self, telegram_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM user_wallets WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Everyone with a registered wallet
+    pub async fn list_user_ids(&self) -> Result<Vec<i64>> {
+        let ids: Vec<String> = sqlx::query_scalar("SELECT DISTINCT telegram_id FROM user_wallets")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(ids.iter().filter_map(|id| id.parse().ok()).collect())
+    }
+
+    pub async fn create_signing_session(
+        &self,
+        telegram_id: &str,
+        session_id: &str,
+        wallet_address: &str,
+        encrypted_data: Option<&str>,
+        max_transaction_sol: f64,
+        duration_minutes: i64,
+    ) -> Result<()> {
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO signing_sessions
+             (session_id, telegram_id, wallet_address, encrypted_data, max_transaction_sol, created_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+            .bind(session_id)
+            .bind(telegram_id)
+            .bind(wallet_address)
+            .bind(encrypted_data)
+            .bind(max_transaction_sol)
+            .bind(unix(now))
+            .bind(unix(now + Duration::minutes(duration_minutes)))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// The session, unless it has expired
+    pub async fn get_active_session(&self, session_id: &str) -> Result<Option<SigningSession>> {
+        let row: Option<(String, String, String, Option<String>, f64, i64)> = sqlx::query_as(
+            "SELECT session_id, telegram_id, wallet_address, encrypted_data, max_transaction_sol, expires_at
+             FROM signing_sessions WHERE session_id = $1 AND expires_at > $2",
+        )
+            .bind(session_id)
+            .bind(unix(Utc::now()))
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|(session_id, telegram_id, wallet_address, encrypted_data, max_transaction_sol, expires_at)| {
+            SigningSession {
+                session_id,
+                telegram_id,
+                wallet_address,
+                encrypted_data,
+                max_transaction_sol,
+                expires_at: from_unix(expires_at),
+            }
+        }))
+    }
+
+    /// Delete expired sessions; returns how many went
+    pub async fn cleanup_expired_sessions(&self) -> Result<u64> {
+        Ok(sqlx::query("DELETE FROM signing_sessions WHERE expires_at <= $1")
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?
+            .rows_affected())
+    }
+
+    pub async fn delete_user_sessions(&self, telegram_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM signing_sessions WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_user_positions(&self, user_wallet: &str) -> Result<Vec<Position>> {
+        let rows: Vec<String> = sqlx::query_scalar("SELECT data FROM positions WHERE wallet_address = $1 ORDER BY mint")
+            .bind(user_wallet)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter()
+            .map(|row| serde_json::from_str(row).map_err(|e| {
+                BotError::parsing(format!("Failed to parse a stored position of {}: {}", user_wallet, e))
+            }))
+            .collect()
+    }
+
+    /// Store `positions` as the wallet's whole set, dropping any not in it
+    pub async fn replace_user_positions(&self, user_wallet: &str, positions: &[Position]) -> Result<()> {
+        let now = unix(Utc::now());
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM positions WHERE wallet_address = $1")
+            .bind(user_wallet)
+            .execute(&mut *tx)
+            .await?;
+        for position in positions {
+            let data = serde_json::to_string(position)
+                .map_err(|e| BotError::parsing(format!("Failed to serialize position {}: {}", position.mint, e)))?;
+            sqlx::query("INSERT INTO positions (wallet_address, mint, data, updated_at) VALUES ($1, $2, $3, $4)")
+                .bind(user_wallet)
+                .bind(position.mint.as_str())
+                .bind(data)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The wallet's `TradingMode` code, when it chose one
+    pub async fn get_trading_mode(&self, user_wallet: &str) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar("SELECT mode FROM trading_modes WHERE wallet_address = $1")
+            .bind(user_wallet)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    pub async fn set_trading_mode(&self, user_wallet: &str, mode: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO trading_modes (wallet_address, mode, updated_at) VALUES ($1, $2, $3)
+             ON CONFLICT (wallet_address) DO UPDATE SET mode = excluded.mode, updated_at = excluded.updated_at",
+        )
+            .bind(user_wallet)
+            .bind(mode)
+            .bind(unix(Utc::now()))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_paper_ledger(&self, user_wallet: &str) -> Result<Option<String>> {
+        self.get_data("paper_ledgers", "wallet_address", user_wallet).await
+    }
+
+    pub async fn upsert_paper_ledger(&self, user_wallet: &str, data: &str) -> Result<()> {
+        self.upsert_data("paper_ledgers", "wallet_address", user_wallet, data).await
+    }
+}
@@ -0,0 +1,387 @@
+//! Error types shared across the bot
+//!
+//! `BotError` variants say what went wrong in a form callers can branch on.
+//! Only some of them are meant for users: `render_for_telegram` shows those
+//! as written and replaces the rest with a generic notice, logging the detail.
+use std::time::Duration;
+use teloxide::utils::markdown::escape;
+use thiserror::Error;
+use tracing::error;
+
+pub type Result<T> = std::result::Result<T, BotError>;
+
+/// Shown in place of errors whose detail is only for operators
+pub const GENERIC_FAILURE: &str = "Something went wrong on our side. Nothing was changed, please try again shortly.";
+
+#[derive(Debug, Error)]
+pub enum BotError {
+    /// Input the user can correct; `field` names the argument when it's known
+    #[error("{reason}")]
+    Validation { field: Option<&'static str>, reason: String },
+
+    /// Amounts in SOL
+    #[error("Insufficient balance: need {needed} SOL, have {available} SOL")]
+    InsufficientBalance { needed: f64, available: f64 },
+
+    #[error("Slippage of {actual_bps} bps is over the {limit_bps} bps limit")]
+    SlippageExceeded { limit_bps: u16, actual_bps: u16 },
+
+    /// `detail` is for logs; users are told when to retry
+    #[error("{detail}")]
+    RateLimited { retry_after: Option<Duration>, detail: String },
+
+    /// A circuit breaker is open for `service`, e.g. "jupiter"
+    #[error("{service} is temporarily unavailable")]
+    ServiceUnavailable { service: String },
+
+    /// What's missing, phrased for the user, e.g. "Order 42 not found"
+    #[error("{entity}")]
+    NotFound { entity: String },
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+
+    /// A trade that couldn't go ahead, in words the user can act on
+    #[error("{0}")]
+    Trading(String),
+
+    /// Refused by a security check: token rules, signing limits, claim ownership
+    #[error("{0}")]
+    Security(String),
+
+    /// A bounded queue, named here, is full
+    #[error("Queue {0} is full")]
+    QueueFull(String),
+
+    #[error("{0}")]
+    HardwareWallet(String),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("Parse error: {0}")]
+    Parsing(String),
+
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+
+    #[error("AI response error: {0}")]
+    AiParse(String),
+
+    #[error("Jupiter API error: {0}")]
+    JupiterApi(String),
+
+    #[error("External API error: {0}")]
+    ExternalApi(String),
+
+    #[error("API error: {0}")]
+    Api(String),
+
+    #[error("Monitoring error: {0}")]
+    Monitoring(String),
+
+    /// Schema migrations couldn't run: lock held, edited or unknown migrations
+    #[error("Migration error: {0}")]
+    Migration(String),
+
+    #[error("Telegram error: {0}")]
+    Telegram(#[from] teloxide::RequestError),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Solana RPC error: {0}")]
+    SolanaClient(#[from] solana_client::client_error::ClientError),
+
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+impl BotError {
+    pub fn validation(reason: impl Into<String>) -> Self {
+        Self::Validation { field: None, reason: reason.into() }
+    }
+
+    /// A validation error for a named argument
+    pub fn invalid(field: &'static str, reason: impl Into<String>) -> Self {
+        Self::Validation { field: Some(field), reason: reason.into() }
+    }
+
+    pub fn insufficient_balance(needed: f64, available: f64) -> Self {
+        Self::InsufficientBalance { needed, available }
+    }
+
+    pub fn slippage_exceeded(limit_bps: u16, actual_bps: u16) -> Self {
+        Self::SlippageExceeded { limit_bps, actual_bps }
+    }
+
+    pub fn rate_limited(detail: impl Into<String>) -> Self {
+        Self::RateLimited { retry_after: None, detail: detail.into() }
+    }
+
+    /// Rate limited with a known wait before the next attempt can succeed
+    pub fn rate_limited_for(retry_after: Duration, detail: impl Into<String>) -> Self {
+        Self::RateLimited { retry_after: Some(retry_after), detail: detail.into() }
+    }
+
+    pub fn service_unavailable(service: impl Into<String>) -> Self {
+        Self::ServiceUnavailable { service: service.into() }
+    }
+
+    pub fn not_found(entity: impl Into<String>) -> Self {
+        Self::NotFound { entity: entity.into() }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal(message.into())
+    }
+
+    pub fn trading(message: impl Into<String>) -> Self {
+        Self::Trading(message.into())
+    }
+
+    pub fn security(message: impl Into<String>) -> Self {
+        Self::Security(message.into())
+    }
+
+    pub fn queue_full(queue: impl Into<String>) -> Self {
+        Self::QueueFull(queue.into())
+    }
+
+    pub fn hardware_wallet(message: impl Into<String>) -> Self {
+        Self::HardwareWallet(message.into())
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::Config(message.into())
+    }
+
+    pub fn parsing(message: impl Into<String>) -> Self {
+        Self::Parsing(message.into())
+    }
+
+    pub fn serialization(error: impl std::fmt::Display) -> Self {
+        Self::Serialization(error.to_string())
+    }
+
+    pub fn ai_parse(message: impl Into<String>) -> Self {
+        Self::AiParse(message.into())
+    }
+
+    pub fn jupiter_api(message: impl Into<String>) -> Self {
+        Self::JupiterApi(message.into())
+    }
+
+    pub fn external_api(message: impl Into<String>) -> Self {
+        Self::ExternalApi(message.into())
+    }
+
+    pub fn api(message: impl Into<String>) -> Self {
+        Self::Api(message.into())
+    }
+
+    pub fn monitoring(message: impl Into<String>) -> Self {
+        Self::Monitoring(message.into())
+    }
+
+    pub fn migration(message: impl Into<String>) -> Self {
+        Self::Migration(message.into())
+    }
+
+    /// Whether the message says something the user can act on and reveals nothing internal
+    pub fn is_user_visible(&self) -> bool {
+        match self {
+            Self::Validation { .. }
+            | Self::InsufficientBalance { .. }
+            | Self::SlippageExceeded { .. }
+            | Self::RateLimited { .. }
+            | Self::ServiceUnavailable { .. }
+            | Self::NotFound { .. }
+            | Self::Trading(_)
+            | Self::Security(_)
+            | Self::QueueFull(_)
+            | Self::HardwareWallet(_) => true,
+            Self::Wallet(error) => error.is_user_visible(),
+            Self::Internal(_)
+            | Self::Config(_)
+            | Self::Parsing(_)
+            | Self::Serialization(_)
+            | Self::AiParse(_)
+            | Self::JupiterApi(_)
+            | Self::ExternalApi(_)
+            | Self::Api(_)
+            | Self::Monitoring(_)
+            | Self::Migration(_)
+            | Self::Telegram(_)
+            | Self::Http(_)
+            | Self::SolanaClient(_)
+            | Self::Redis(_)
+            | Self::Database(_)
+            | Self::Json(_)
+            | Self::Io(_) => false,
+        }
+    }
+
+    /// One plain-text line for the user; errors that aren't user visible get `GENERIC_FAILURE`
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::InsufficientBalance { needed, available } => format!(
+                "Insufficient balance: this needs {:.4} SOL and your wallet has {:.4} SOL.",
+                needed, available
+            ),
+            Self::SlippageExceeded { limit_bps, actual_bps } => format!(
+                "The price moved {:.2}%, over your {:.2}% slippage limit. Nothing was traded.",
+                *actual_bps as f64 / 100.0,
+                *limit_bps as f64 / 100.0
+            ),
+            Self::RateLimited { retry_after: Some(wait), .. } => format!(
+                "Too many requests right now. Please try again in {}.",
+                wait_text(*wait)
+            ),
+            Self::RateLimited { retry_after: None, .. } => {
+                "Too many requests right now. Please try again in a few seconds.".to_string()
+            }
+            Self::ServiceUnavailable { service } => {
+                format!("{} is temporarily unavailable. Please try again shortly.", service_label(service))
+            }
+            Self::QueueFull(_) => "We're at capacity right now. Please try again in a few seconds.".to_string(),
+            error if error.is_user_visible() => error.to_string(),
+            _ => GENERIC_FAILURE.to_string(),
+        }
+    }
+
+    /// `user_message` for an error from an `anyhow` call chain, generic unless it wraps a `BotError`
+    pub fn user_message_for(error: &anyhow::Error) -> String {
+        match error.downcast_ref::<BotError>() {
+            Some(error) => error.user_message(),
+            None => GENERIC_FAILURE.to_string(),
+        }
+    }
+
+    /// `user_message` with an emoji, escaped for MarkdownV2; hidden detail goes to the logs
+    pub fn render_for_telegram(&self) -> String {
+        if !self.is_user_visible() {
+            error!("⚠️ Error shown to the user as a generic failure: {:?}", self);
+        }
+        let icon = match self {
+            Self::RateLimited { .. } | Self::ServiceUnavailable { .. } | Self::QueueFull(_) => "⏳",
+            _ => "❌",
+        };
+        format!("{} {}", icon, escape(&self.user_message()))
+    }
+}
+
+/// How a service is named to users
+pub fn service_label(service: &str) -> &str {
+    match service {
+        "jupiter" | "jupiter-price" => "Jupiter",
+        "pumpfun" => "Pump.fun",
+        "groq" => "AI analysis",
+        other => other,
+    }
+}
+
+/// "45 seconds", "3 minutes", rounded up
+fn wait_text(wait: Duration) -> String {
+    let secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    if secs < 90 {
+        format!("{} second{}", secs.max(1), if secs == 1 { "" } else { "s" })
+    } else {
+        format!("{} minutes", secs.div_ceil(60))
+    }
+}
+
+/// Trading failures raised before a transaction is built
+#[derive(Debug, Error)]
+pub enum TradingError {
+    #[error("{message}")]
+    InvalidAmount { message: String },
+
+    #[error("Amount {amount} SOL is over the {maximum} SOL maximum")]
+    AmountExceedsMaximum { amount: f64, maximum: f64 },
+
+    #[error("Percentage must be above 0 and at most 100, got {percentage}")]
+    InvalidPercentage { percentage: f64 },
+
+    /// A slippage setting over the configured maximum
+    #[error("Slippage is over the maximum allowed")]
+    SlippageExceeded,
+
+    /// Amounts in SOL
+    #[error("Insufficient balance: need {required} SOL, have {available} SOL")]
+    InsufficientBalance { required: f64, available: f64 },
+
+    #[error("Quote failed: {0}")]
+    QuoteFailed(String),
+
+    #[error("No route found for this swap; the token may not have enough liquidity right now")]
+    NoRoute,
+
+    #[error("Transaction failed: {0}")]
+    TransactionFailed(String),
+
+    #[error("Token not found: {0}")]
+    TokenNotFound(String),
+
+    #[error("You hold no {0} to sell")]
+    NoTokensToSell(String),
+}
+
+impl TradingError {
+    pub fn no_tokens_to_sell(token: impl Into<String>) -> Self {
+        Self::NoTokensToSell(token.into())
+    }
+}
+
+impl From<TradingError> for BotError {
+    fn from(error: TradingError) -> Self {
+        match error {
+            TradingError::InvalidAmount { message } => BotError::invalid("amount", message),
+            TradingError::AmountExceedsMaximum { .. } => BotError::invalid("amount", error.to_string()),
+            TradingError::InvalidPercentage { .. } => BotError::invalid("percentage", error.to_string()),
+            TradingError::SlippageExceeded => BotError::invalid("slippage", error.to_string()),
+            TradingError::InsufficientBalance { required, available } => {
+                BotError::insufficient_balance(required, available)
+            }
+            TradingError::TokenNotFound(_) => BotError::not_found(error.to_string()),
+            TradingError::NoTokensToSell(_) | TradingError::NoRoute => BotError::trading(error.to_string()),
+            // Carry API responses, so they stay in the logs
+            TradingError::QuoteFailed(_) | TradingError::TransactionFailed(_) => {
+                BotError::jupiter_api(error.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WalletError {
+    #[error("Invalid public key")]
+    InvalidPublicKey,
+
+    #[error("Invalid private key")]
+    InvalidPrivateKey,
+
+    #[error("Key derivation failed")]
+    DerivationFailed,
+
+    #[error("No wallet found. Use /wallet to create one.")]
+    WalletNotFound,
+}
+
+impl WalletError {
+    fn is_user_visible(&self) -> bool {
+        !matches!(self, Self::DerivationFailed)
+    }
+}
@@ -0,0 +1,183 @@
+//! Translated bot text, shared by the bot and its Convex integration
+//!
+//! Each catalog is a flat map of dotted keys to text with `{name}`
+//! placeholders. Lookups walk a fallback chain, `es-MX` → `es` → `en`, so a
+//! regional catalog only needs the keys it changes. The JSON catalogs live
+//! next to this crate's manifest so they're packaged with it.
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+/// Language used when the user's is unknown or unsupported
+pub const DEFAULT_LANG: &str = "en";
+
+/// Why a set of catalogs couldn't be loaded
+#[derive(Debug, thiserror::Error)]
+pub enum CatalogError {
+    #[error("Invalid {lang} locale catalog: {source}")]
+    Invalid { lang: String, source: serde_json::Error },
+    #[error("Locale catalogs have no {0} fallback")]
+    MissingDefault(&'static str),
+}
+
+/// Languages shipped with the bot and their names in the language picker
+pub const LANGUAGES: &[(&str, &str)] = &[
+    ("en", "🇺🇸 English"),
+    ("es", "🇪🇸 Español"),
+    ("fr", "🇫🇷 Français"),
+    ("de", "🇩🇪 Deutsch"),
+    ("it", "🇮🇹 Italiano"),
+    ("pt", "🇧🇷 Português"),
+    ("ru", "🇷🇺 Русский"),
+    ("zh", "🇨🇳 中文"),
+    ("ja", "🇯🇵 日本語"),
+    ("ko", "🇰🇷 한국어"),
+];
+
+const EMBEDDED: &[(&str, &str)] = &[
+    ("en", include_str!("../en.json")),
+    ("es", include_str!("../es.json")),
+    ("fr", include_str!("../fr.json")),
+    ("de", include_str!("../de.json")),
+    ("it", include_str!("../it.json")),
+    ("pt", include_str!("../pt.json")),
+    ("ru", include_str!("../ru.json")),
+    ("zh", include_str!("../zh.json")),
+    ("ja", include_str!("../ja.json")),
+    ("ko", include_str!("../ko.json")),
+];
+
+static EMBEDDED_CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Every language's text, keyed by lowercase language tag
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    messages: HashMap<String, HashMap<String, String>>,
+}
+
+impl Catalog {
+    /// Parse `(language, json)` catalogs; the default language must be among them
+    pub fn from_sources(sources: &[(&str, &str)]) -> Result<Self, CatalogError> {
+        let mut messages = HashMap::new();
+        for (lang, source) in sources {
+            let entries: HashMap<String, String> = serde_json::from_str(source)
+                .map_err(|source| CatalogError::Invalid { lang: lang.to_string(), source })?;
+            messages.insert(normalize(lang), entries);
+        }
+        if !messages.contains_key(DEFAULT_LANG) {
+            return Err(CatalogError::MissingDefault(DEFAULT_LANG));
+        }
+        Ok(Self { messages })
+    }
+
+    /// The catalogs compiled into the binary, parsed on first use
+    pub fn embedded() -> &'static Catalog {
+        EMBEDDED_CATALOG.get_or_init(|| Self::from_sources(EMBEDDED).expect("embedded locale catalogs are valid"))
+    }
+
+    /// Languages with a catalog, sorted
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.messages.keys().map(String::as_str).collect();
+        languages.sort_unstable();
+        languages
+    }
+
+    /// Languages tried for `lang`, most specific first: `es-MX` → `es` → `en`
+    pub fn fallback_chain(lang: &str) -> Vec<String> {
+        let tag = normalize(lang);
+        let mut chain: Vec<String> = Vec::new();
+        let mut current = tag.as_str();
+        loop {
+            if !current.is_empty() {
+                chain.push(current.to_string());
+            }
+            match current.rfind('-') {
+                Some(end) => current = &current[..end],
+                None => break,
+            }
+        }
+        if !chain.iter().any(|lang| lang == DEFAULT_LANG) {
+            chain.push(DEFAULT_LANG.to_string());
+        }
+        chain
+    }
+
+    /// `lang` or its base language has a catalog
+    pub fn supports(&self, lang: &str) -> bool {
+        let tag = normalize(lang);
+        let base = tag.split('-').next().unwrap_or_default();
+        self.messages.contains_key(&tag) || self.messages.contains_key(base)
+    }
+
+    /// Text for `key` from the first language in `lang`'s chain that has it
+    pub fn get(&self, lang: &str, key: &str) -> Option<&str> {
+        Self::fallback_chain(lang)
+            .iter()
+            .find_map(|lang| self.messages.get(lang)?.get(key))
+            .map(String::as_str)
+    }
+
+    /// Text for `key`, or the key itself when no language has it
+    pub fn text(&self, lang: &str, key: &str) -> String {
+        self.get(lang, key).unwrap_or(key).to_string()
+    }
+
+    /// Text for `key` with each `{name}` replaced by its value
+    pub fn format(&self, lang: &str, key: &str, args: &[(&str, &str)]) -> String {
+        args.iter().fold(self.text(lang, key), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+    }
+
+    /// Keys of one language's own catalog, without fallbacks, sorted
+    pub fn keys(&self, lang: &str) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.messages
+            .get(&normalize(lang))
+            .map(|entries| entries.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Default-language keys each base language lacks; empty when every catalog is complete
+    ///
+    /// Regional catalogs like `es-mx` are partial by design and aren't checked.
+    pub fn missing_keys(&self) -> BTreeMap<String, Vec<String>> {
+        let Some(reference) = self.messages.get(DEFAULT_LANG) else { return BTreeMap::new() };
+        let mut missing = BTreeMap::new();
+        for (lang, entries) in &self.messages {
+            if lang == DEFAULT_LANG || lang.contains('-') {
+                continue;
+            }
+            let mut keys: Vec<String> = reference.keys().filter(|key| !entries.contains_key(*key)).cloned().collect();
+            if !keys.is_empty() {
+                keys.sort_unstable();
+                missing.insert(lang.clone(), keys);
+            }
+        }
+        missing
+    }
+}
+
+/// Name of `lang` in the language picker, falling back like text lookups do
+pub fn language_name(lang: &str) -> &'static str {
+    Catalog::fallback_chain(lang)
+        .iter()
+        .find_map(|lang| LANGUAGES.iter().find(|(code, _)| code == lang))
+        .map(|(_, name)| *name)
+        .unwrap_or(LANGUAGES[0].1)
+}
+
+/// Text for `key` in `lang` from the embedded catalogs
+pub fn t(lang: &str, key: &str) -> String {
+    Catalog::embedded().text(lang, key)
+}
+
+/// Text for `key` in `lang` with its placeholders filled in
+pub fn t_args(lang: &str, key: &str, args: &[(&str, &str)]) -> String {
+    Catalog::embedded().format(lang, key, args)
+}
+
+/// `es_MX`, `ES-mx` and ` es-MX ` all become `es-mx`
+pub fn normalize(lang: &str) -> String {
+    lang.trim().replace('_', "-").to_lowercase()
+}
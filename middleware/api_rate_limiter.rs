@@ -1,10 +1,13 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{Mutex, Semaphore};
-use tracing::{info, warn};
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, info, warn};
 use anyhow::Result;
 
+use crate::api::RateLimits;
+
 /// Rate limiter for API calls with per-endpoint and global limits
 #[derive(Clone)]
 pub struct ApiRateLimiter {
@@ -12,6 +15,8 @@ pub struct ApiRateLimiter {
     global_semaphore: Arc<Semaphore>,
     /// Per-endpoint rate limiters
     endpoint_limiters: Arc<Mutex<HashMap<String, EndpointLimiter>>>,
+    /// Callers currently queued in `acquire`
+    waiting: Arc<AtomicUsize>,
     /// Configuration
     config: RateLimitConfig,
 }
@@ -26,6 +31,8 @@ pub struct RateLimitConfig {
     pub burst_size: usize,
     /// Cooldown period after hitting limits
     pub cooldown_duration: Duration,
+    /// Callers allowed to queue in `acquire` before new ones are turned away
+    pub max_queue_depth: usize,
 }
 
 impl Default for RateLimitConfig {
@@ -35,6 +42,22 @@ impl Default for RateLimitConfig {
             endpoint_rpm: 60,      // 60 requests per minute per endpoint
             burst_size: 5,         // Allow burst of 5 extra requests
             cooldown_duration: Duration::from_secs(60),
+            max_queue_depth: 50,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Size the limiter from an API tier's limits: its concurrency caps in-flight calls,
+    /// and callers may queue up to twice that
+    pub fn from_tier_limits(limits: &RateLimits) -> Self {
+        let concurrent = limits.concurrent_requests.max(1) as usize;
+        Self {
+            global_rps: concurrent,
+            endpoint_rpm: limits.requests_per_minute.max(1) as usize,
+            burst_size: 0,
+            cooldown_duration: Duration::from_secs(60),
+            max_queue_depth: concurrent * 2,
         }
     }
 }
@@ -61,6 +84,7 @@ impl ApiRateLimiter {
         Self {
             global_semaphore: Arc::new(Semaphore::new(config.global_rps)),
             endpoint_limiters: Arc::new(Mutex::new(HashMap::new())),
+            waiting: Arc::new(AtomicUsize::new(0)),
             config,
         }
     }
@@ -69,7 +93,8 @@ impl ApiRateLimiter {
     pub async fn check_rate_limit(&self, endpoint: &str) -> Result<RateLimitToken> {
         // Check global rate limit
         let global_permit = self.global_semaphore
-            .try_acquire()
+            .clone()
+            .try_acquire_owned()
             .map_err(|_| anyhow::anyhow!("Global rate limit exceeded"))?;
         
         // Check endpoint-specific rate limit
@@ -137,9 +162,86 @@ impl ApiRateLimiter {
             _global_permit: global_permit,
             endpoint: endpoint.to_string(),
             acquired_at: now,
+            waited: Duration::ZERO,
         })
     }
     
+    /// Wait for a permit instead of failing: queues behind in-flight calls and the
+    /// endpoint's per-minute window, giving up if the wait would run past `deadline`.
+    /// Fails immediately when `max_queue_depth` callers are already waiting.
+    pub async fn acquire(&self, endpoint: &str, deadline: Instant) -> Result<RateLimitToken> {
+        let started = Instant::now();
+        let queued = self.waiting.fetch_add(1, Ordering::SeqCst);
+        let _queue_slot = QueueSlot(self.waiting.clone());
+        if queued >= self.config.max_queue_depth {
+            return Err(anyhow::anyhow!(
+                "Rate limit queue for endpoint '{}' is full ({} waiting)",
+                endpoint,
+                queued
+            ));
+        }
+        
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let global_permit = tokio::time::timeout(remaining, self.global_semaphore.clone().acquire_owned())
+            .await
+            .map_err(|_| anyhow::anyhow!("Timed out waiting for a request slot for endpoint '{}'", endpoint))?
+            .map_err(|_| anyhow::anyhow!("Rate limiter closed"))?;
+        
+        loop {
+            let wait = {
+                let mut limiters = self.endpoint_limiters.lock().await;
+                let limiter = limiters.entry(endpoint.to_string())
+                    .or_insert_with(|| EndpointLimiter {
+                        request_times: Vec::new(),
+                        last_cleanup: Instant::now(),
+                        burst_count: 0,
+                        cooldown_until: None,
+                    });
+                
+                let now = Instant::now();
+                let window = Duration::from_secs(60);
+                limiter.request_times.retain(|&t| now.duration_since(t) < window);
+                limiter.last_cleanup = now;
+                
+                match limiter.cooldown_until.filter(|&until| until > now) {
+                    Some(until) => until - now,
+                    None if limiter.request_times.len() < self.config.endpoint_rpm => {
+                        limiter.cooldown_until = None;
+                        limiter.request_times.push(now);
+                        let waited = now.duration_since(started);
+                        if !waited.is_zero() {
+                            debug!("⏳ Waited {:?} for a permit on endpoint '{}'", waited, endpoint);
+                        }
+                        return Ok(RateLimitToken {
+                            _global_permit: global_permit,
+                            endpoint: endpoint.to_string(),
+                            acquired_at: now,
+                            waited,
+                        });
+                    }
+                    // The oldest request in the window frees the next slot
+                    None => limiter.request_times
+                        .first()
+                        .map_or(window, |&oldest| window.saturating_sub(now.duration_since(oldest))),
+                }
+            };
+            
+            if Instant::now() + wait > deadline {
+                return Err(anyhow::anyhow!(
+                    "Rate limit for endpoint '{}' frees up in {} seconds, past the deadline",
+                    endpoint,
+                    wait.as_secs_f64().ceil()
+                ));
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+    
+    /// Callers currently queued in `acquire`
+    pub fn queue_depth(&self) -> usize {
+        self.waiting.load(Ordering::SeqCst)
+    }
+    
     /// Get current usage stats for an endpoint
     pub async fn get_usage_stats(&self, endpoint: &str) -> EndpointStats {
         let limiters = self.endpoint_limiters.lock().await;
@@ -196,11 +298,22 @@ impl ApiRateLimiter {
     }
 }
 
+/// Leaves the `acquire` queue when dropped
+struct QueueSlot(Arc<AtomicUsize>);
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// Token representing an approved rate limit check
 pub struct RateLimitToken {
-    _global_permit: tokio::sync::SemaphorePermit<'static>,
+    _global_permit: OwnedSemaphorePermit,
     pub endpoint: String,
     pub acquired_at: Instant,
+    /// Time spent queued before the permit was granted
+    pub waited: Duration,
 }
 
 impl RateLimitToken {
@@ -291,6 +404,7 @@ mod tests {
             endpoint_rpm: 2, // Very low for testing
             burst_size: 2,
             cooldown_duration: Duration::from_secs(1),
+            max_queue_depth: 10,
         };
         
         let limiter = ApiRateLimiter::with_config(config);
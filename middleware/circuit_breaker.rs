@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{warn, info, debug};
 
+use crate::errors::{BotError, Result as BotResult};
+use crate::monitoring::{AlertManager, AlertSeverity};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CircuitState {
     Closed,    // Normal operation
@@ -10,11 +16,12 @@ pub enum CircuitState {
     HalfOpen,  // Testing if service has recovered
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CircuitBreakerConfig {
     pub failure_threshold: u32,     // Number of failures to open circuit
     pub timeout: Duration,          // Time to wait before trying again
     pub success_threshold: u32,     // Successes needed in half-open to close
+    pub half_open_max_probes: u32,  // Calls let through at once while half-open
 }
 
 impl Default for CircuitBreakerConfig {
@@ -23,6 +30,26 @@ impl Default for CircuitBreakerConfig {
             failure_threshold: 5,
             timeout: Duration::from_secs(30),
             success_threshold: 3,
+            half_open_max_probes: 1,
+        }
+    }
+}
+
+/// How a finished call counts towards the breaker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallOutcome {
+    Success,
+    Failure,
+    /// Says nothing about the service's health, e.g. throttled before it was sent
+    Ignored,
+}
+
+impl CallOutcome {
+    /// Transport errors and 5xx count against the service; any other answer shows it's up
+    pub fn of_response<E>(result: &std::result::Result<reqwest::Response, E>) -> Self {
+        match result {
+            Ok(response) if !response.status().is_server_error() => CallOutcome::Success,
+            _ => CallOutcome::Failure,
         }
     }
 }
@@ -33,91 +60,143 @@ pub struct CircuitBreaker {
     state: RwLock<CircuitState>,
     failure_count: AtomicU32,
     success_count: AtomicU32,
-    last_failure_time: AtomicU64,
+    probes_in_flight: AtomicU32,
+    /// Reference point for `opened_at_ms`
+    epoch: Instant,
+    opened_at_ms: AtomicU64,
     total_requests: AtomicU32,
     total_failures: AtomicU32,
+    alerts: Option<Arc<AlertManager>>,
+}
+
+/// Frees a half-open probe slot, even if the call is dropped mid-flight
+struct ProbeSlot<'a>(&'a AtomicU32);
+
+impl Drop for ProbeSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
 }
 
 impl CircuitBreaker {
     pub fn new(name: String, config: CircuitBreakerConfig) -> Self {
         info!("Circuit breaker '{}' initialized with config: {:?}", name, config);
-        
+
         Self {
             name,
             config,
             state: RwLock::new(CircuitState::Closed),
             failure_count: AtomicU32::new(0),
             success_count: AtomicU32::new(0),
-            last_failure_time: AtomicU64::new(0),
+            probes_in_flight: AtomicU32::new(0),
+            epoch: Instant::now(),
+            opened_at_ms: AtomicU64::new(0),
             total_requests: AtomicU32::new(0),
             total_failures: AtomicU32::new(0),
+            alerts: None,
         }
     }
-    
+
+    /// Raise an alert when the circuit opens and resolve it once it closes again
+    pub fn with_alerts(mut self, alerts: Arc<AlertManager>) -> Self {
+        self.alerts = Some(alerts);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Execute a function with circuit breaker protection
     pub async fn execute<F, T, E>(&self, operation: F) -> Result<T, CircuitBreakerError<E>>
     where
-        F: std::future::Future<Output = Result<T, E>>,
+        F: Future<Output = Result<T, E>>,
+    {
+        self.execute_classified(operation, |result| {
+            if result.is_ok() { CallOutcome::Success } else { CallOutcome::Failure }
+        }).await
+    }
+
+    /// As `execute`, with `classify` deciding which results count as failures
+    pub async fn execute_classified<F, T, E>(
+        &self,
+        operation: F,
+        classify: impl FnOnce(&Result<T, E>) -> CallOutcome,
+    ) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: Future<Output = Result<T, E>>,
     {
         self.total_requests.fetch_add(1, Ordering::Relaxed);
-        
-        // Check if circuit should be closed due to timeout
-        self.check_timeout().await;
-        
-        let state = *self.state.read().await;
-        
-        match state {
+
+        let _probe = match self.current_state().await {
             CircuitState::Open => {
                 debug!("Circuit breaker '{}' is OPEN - failing fast", self.name);
                 return Err(CircuitBreakerError::CircuitOpen);
             }
-            CircuitState::HalfOpen => {
-                debug!("Circuit breaker '{}' is HALF-OPEN - testing", self.name);
-            }
-            CircuitState::Closed => {
-                debug!("Circuit breaker '{}' is CLOSED - normal operation", self.name);
-            }
-        }
-        
-        // Execute the operation
-        match operation.await {
-            Ok(result) => {
-                self.on_success().await;
-                Ok(result)
-            }
-            Err(error) => {
-                self.on_failure().await;
-                Err(CircuitBreakerError::OperationFailed(error))
-            }
+            CircuitState::HalfOpen => match self.take_probe() {
+                Some(probe) => {
+                    debug!("Circuit breaker '{}' is HALF-OPEN - probing", self.name);
+                    Some(probe)
+                }
+                None => {
+                    debug!("Circuit breaker '{}' is HALF-OPEN with probes in flight - failing fast", self.name);
+                    return Err(CircuitBreakerError::CircuitOpen);
+                }
+            },
+            CircuitState::Closed => None,
+        };
+
+        let result = operation.await;
+        match classify(&result) {
+            CallOutcome::Success => self.on_success().await,
+            CallOutcome::Failure => self.on_failure().await,
+            CallOutcome::Ignored => {}
         }
+        result.map_err(CircuitBreakerError::OperationFailed)
     }
-    
-    async fn check_timeout(&self) {
+
+    /// Run a `BotError` call; an open circuit fails with `BotError::service_unavailable(name)`
+    pub async fn call<F, T>(&self, operation: F, classify: impl FnOnce(&BotResult<T>) -> CallOutcome) -> BotResult<T>
+    where
+        F: Future<Output = BotResult<T>>,
+    {
+        self.execute_classified(operation, classify).await.map_err(|e| match e {
+            CircuitBreakerError::CircuitOpen => BotError::service_unavailable(self.name.clone()),
+            CircuitBreakerError::OperationFailed(e) => e,
+        })
+    }
+
+    /// The state, moving to half-open once an open circuit's timeout has passed
+    async fn current_state(&self) -> CircuitState {
         let state = *self.state.read().await;
         if state == CircuitState::Open {
-            let last_failure = self.last_failure_time.load(Ordering::Relaxed);
-            let now = Instant::now().elapsed().as_millis() as u64;
-            
-            if now.saturating_sub(last_failure) >= self.config.timeout.as_millis() as u64 {
-                info!("Circuit breaker '{}' timeout expired, transitioning to HALF-OPEN", self.name);
-                *self.state.write().await = CircuitState::HalfOpen;
-                self.success_count.store(0, Ordering::Relaxed);
+            let opened_at = self.opened_at_ms.load(Ordering::Acquire);
+            let now = self.epoch.elapsed().as_millis() as u64;
+
+            if now.saturating_sub(opened_at) >= self.config.timeout.as_millis() as u64 {
+                self.transition(&[CircuitState::Open], CircuitState::HalfOpen).await;
+                return *self.state.read().await;
             }
         }
+        state
+    }
+
+    fn take_probe(&self) -> Option<ProbeSlot<'_>> {
+        let max = self.config.half_open_max_probes.max(1);
+        self.probes_in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < max).then_some(n + 1))
+            .ok()
+            .map(|_| ProbeSlot(&self.probes_in_flight))
     }
-    
+
     async fn on_success(&self) {
         let state = *self.state.read().await;
-        
+
         match state {
             CircuitState::HalfOpen => {
                 let successes = self.success_count.fetch_add(1, Ordering::Relaxed) + 1;
                 if successes >= self.config.success_threshold {
-                    info!("Circuit breaker '{}' transitioning to CLOSED after {} successes", 
-                          self.name, successes);
-                    *self.state.write().await = CircuitState::Closed;
-                    self.failure_count.store(0, Ordering::Relaxed);
-                    self.success_count.store(0, Ordering::Relaxed);
+                    self.transition(&[CircuitState::HalfOpen], CircuitState::Closed).await;
                 }
             }
             CircuitState::Closed => {
@@ -125,46 +204,109 @@ impl CircuitBreaker {
                 self.failure_count.store(0, Ordering::Relaxed);
             }
             CircuitState::Open => {
-                // Should not reach here, but reset just in case
-                self.failure_count.store(0, Ordering::Relaxed);
+                // A call admitted before the circuit opened; doesn't close it
             }
         }
     }
-    
+
     async fn on_failure(&self) {
         self.total_failures.fetch_add(1, Ordering::Relaxed);
-        self.last_failure_time.store(
-            Instant::now().elapsed().as_millis() as u64,
-            Ordering::Relaxed
-        );
-        
+
         let state = *self.state.read().await;
-        
+
         match state {
             CircuitState::Closed => {
                 let failures = self.failure_count.fetch_add(1, Ordering::Relaxed) + 1;
                 if failures >= self.config.failure_threshold {
-                    warn!("Circuit breaker '{}' transitioning to OPEN after {} failures", 
-                          self.name, failures);
-                    *self.state.write().await = CircuitState::Open;
+                    self.transition(&[CircuitState::Closed], CircuitState::Open).await;
                 }
             }
             CircuitState::HalfOpen => {
-                warn!("Circuit breaker '{}' transitioning back to OPEN due to failure in half-open state", 
-                      self.name);
-                *self.state.write().await = CircuitState::Open;
+                self.transition(&[CircuitState::HalfOpen], CircuitState::Open).await;
             }
             CircuitState::Open => {
-                // Already open, just update failure time
+                // Already open
             }
         }
     }
-    
+
+    /// Move to `to` if the circuit is currently in one of `from`, then report it
+    async fn transition(&self, from: &[CircuitState], to: CircuitState) -> bool {
+        let previous = {
+            let mut state = self.state.write().await;
+            if !from.contains(&*state) {
+                return false;
+            }
+            let previous = *state;
+            *state = to;
+            match to {
+                CircuitState::Open => {
+                    self.opened_at_ms.store(self.epoch.elapsed().as_millis() as u64, Ordering::Release);
+                }
+                CircuitState::HalfOpen => self.success_count.store(0, Ordering::Relaxed),
+                CircuitState::Closed => {
+                    self.failure_count.store(0, Ordering::Relaxed);
+                    self.success_count.store(0, Ordering::Relaxed);
+                }
+            }
+            previous
+        };
+
+        match to {
+            CircuitState::Open => warn!("🔌 Circuit breaker '{}' OPEN (was {:?}), failing fast for {:?}",
+                self.name, previous, self.config.timeout),
+            CircuitState::HalfOpen => info!("🔌 Circuit breaker '{}' HALF-OPEN, probing", self.name),
+            CircuitState::Closed => info!("🔌 Circuit breaker '{}' CLOSED (was {:?})", self.name, previous),
+        }
+        self.report(previous, to).await;
+        true
+    }
+
+    async fn report(&self, from: CircuitState, to: CircuitState) {
+        let Some(alerts) = &self.alerts else { return };
+
+        let rule_id = format!("circuit_breaker_{}", self.name);
+        let metadata = HashMap::from([
+            ("breaker".to_string(), self.name.clone()),
+            ("from".to_string(), format!("{:?}", from)),
+            ("to".to_string(), format!("{:?}", to)),
+        ]);
+        match to {
+            CircuitState::Open => alerts.raise(
+                &rule_id,
+                format!("Circuit breaker open: {}", self.name),
+                format!(
+                    "Calls to {} keep failing; rejecting them for {:?} before probing again",
+                    self.name, self.config.timeout
+                ),
+                AlertSeverity::Critical,
+                metadata,
+            ).await,
+            CircuitState::HalfOpen => alerts.record_event(
+                &rule_id,
+                format!("Circuit breaker half-open: {}", self.name),
+                format!("Letting a probe through to see whether {} has recovered", self.name),
+                AlertSeverity::Info,
+                metadata,
+            ).await,
+            CircuitState::Closed => {
+                alerts.resolve(&rule_id).await;
+                alerts.record_event(
+                    &rule_id,
+                    format!("Circuit breaker closed: {}", self.name),
+                    format!("{} has recovered", self.name),
+                    AlertSeverity::Info,
+                    metadata,
+                ).await;
+            }
+        }
+    }
+
     /// Get current circuit breaker state
     pub async fn state(&self) -> CircuitState {
         *self.state.read().await
     }
-    
+
     /// Get circuit breaker metrics
     pub async fn metrics(&self) -> CircuitBreakerMetrics {
         CircuitBreakerMetrics {
@@ -183,19 +325,17 @@ impl CircuitBreaker {
             }
         }
     }
-    
+
     /// Force circuit breaker to open (for testing/maintenance)
     pub async fn force_open(&self) {
         warn!("Force opening circuit breaker '{}'", self.name);
-        *self.state.write().await = CircuitState::Open;
+        self.transition(&[CircuitState::Closed, CircuitState::HalfOpen], CircuitState::Open).await;
     }
-    
+
     /// Force circuit breaker to close (for recovery)
     pub async fn force_close(&self) {
         info!("Force closing circuit breaker '{}'", self.name);
-        *self.state.write().await = CircuitState::Closed;
-        self.failure_count.store(0, Ordering::Relaxed);
-        self.success_count.store(0, Ordering::Relaxed);
+        self.transition(&[CircuitState::Open, CircuitState::HalfOpen], CircuitState::Closed).await;
     }
 }
 
@@ -230,4 +370,4 @@ impl<E: std::error::Error + 'static> std::error::Error for CircuitBreakerError<E
             CircuitBreakerError::OperationFailed(e) => Some(e),
         }
     }
-}
\ No newline at end of file
+}
@@ -2,6 +2,6 @@ pub mod circuit_breaker;
 pub mod rate_limiter;
 pub mod api_rate_limiter;
 
-pub use circuit_breaker::CircuitBreaker;
+pub use circuit_breaker::{CallOutcome, CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError, CircuitState};
 pub use rate_limiter::UserRateLimiter;
 pub use api_rate_limiter::{ApiRateLimiter, RateLimitConfig, RateLimitedClient};
\ No newline at end of file
@@ -1,10 +1,14 @@
-use std::collections::HashMap;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::RwLock;
 use tokio::time::sleep;
 use tracing::{warn, debug, info};
 
+use crate::cache::SessionStore;
+use crate::utils::Config;
+
 #[derive(Debug, Clone)]
 pub struct RateLimitConfig {
     pub requests_per_minute: u32,
@@ -64,9 +68,14 @@ impl RateLimitConfig {
     }
 }
 
+/// A token bucket holding up to `burst_capacity` tokens, refilled at `requests_per_minute`
 #[derive(Debug)]
 struct UserRateLimit {
-    tokens: Arc<Semaphore>,
+    tokens: f64,
+    capacity: f64,
+    refill_per_second: f64,
+    /// Wait reported when the bucket never refills
+    cooldown: Duration,
     last_refill: Instant,
     total_requests: u64,
     blocked_requests: u64,
@@ -75,63 +84,86 @@ struct UserRateLimit {
 impl UserRateLimit {
     fn new(config: &RateLimitConfig) -> Self {
         Self {
-            tokens: Arc::new(Semaphore::new(config.burst_capacity as usize)),
+            tokens: config.burst_capacity as f64,
+            capacity: config.burst_capacity as f64,
+            refill_per_second: config.requests_per_minute as f64 / 60.0,
+            cooldown: Duration::from_secs(config.cooldown_minutes as u64 * 60),
             last_refill: Instant::now(),
             total_requests: 0,
             blocked_requests: 0,
         }
     }
     
-    async fn try_acquire(&mut self, config: &RateLimitConfig) -> Result<(), RateLimitError> {
+    fn try_acquire(&mut self) -> Result<(), RateLimitError> {
         self.total_requests += 1;
         
-        // Refill tokens based on time elapsed
-        self.refill_tokens(config).await;
-        
-        // Try to acquire a token (non-blocking)
-        match self.tokens.try_acquire() {
-            Ok(_permit) => {
-                debug!("Rate limit check passed for user");
-                Ok(())
-            }
-            Err(_) => {
-                self.blocked_requests += 1;
-                warn!("Rate limit exceeded for user, blocking request");
-                Err(RateLimitError::RateLimitExceeded)
-            }
+        if self.can_afford(1, Instant::now()) {
+            self.tokens -= 1.0;
+            debug!("Rate limit check passed for user");
+            Ok(())
+        } else {
+            self.blocked_requests += 1;
+            warn!("Rate limit exceeded for user, blocking request");
+            Err(RateLimitError::RateLimitExceeded)
         }
     }
     
-    async fn refill_tokens(&mut self, config: &RateLimitConfig) {
-        let now = Instant::now();
-        let elapsed = now.duration_since(self.last_refill);
-        
-        if elapsed >= Duration::from_secs(60) {
-            // Calculate how many tokens to add (1 token per minute)
-            let minutes_elapsed = elapsed.as_secs() / 60;
-            let tokens_to_add = minutes_elapsed as usize;
-            
-            if tokens_to_add > 0 {
-                // Add tokens up to burst capacity
-                let current_permits = self.tokens.available_permits();
-                let max_permits = config.burst_capacity as usize;
-                let permits_to_add = std::cmp::min(tokens_to_add, max_permits - current_permits);
-                
-                if permits_to_add > 0 {
-                    self.tokens.add_permits(permits_to_add);
-                    debug!("Added {} tokens to rate limiter", permits_to_add);
-                }
-                
-                self.last_refill = now;
+    /// Refill, then whether `cost` tokens are there; a cost above capacity needs a full bucket
+    fn can_afford(&mut self, cost: u32, now: Instant) -> bool {
+        self.refill_tokens(now);
+        self.tokens >= (cost as f64).min(self.capacity)
+    }
+    
+    /// How long until `cost` tokens are back
+    fn retry_after(&self, cost: u32) -> Duration {
+        if self.refill_per_second <= 0.0 {
+            return self.cooldown;
+        }
+        let missing = (cost as f64).min(self.capacity) - self.tokens;
+        Duration::from_secs_f64((missing / self.refill_per_second).max(0.0))
+    }
+    
+    fn refill_tokens(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+    
+    fn stats(&self, user_id: &str) -> UserRateStats {
+        UserRateStats {
+            user_id: user_id.to_string(),
+            available_tokens: self.tokens.floor() as u32,
+            total_requests: self.total_requests,
+            blocked_requests: self.blocked_requests,
+            block_rate: if self.total_requests > 0 {
+                (self.blocked_requests as f64 / self.total_requests as f64) * 100.0
+            } else {
+                0.0
             }
         }
     }
 }
 
+/// A user's command buckets
+#[derive(Debug)]
+struct CommandBuckets {
+    general: UserRateLimit,
+    trading: UserRateLimit,
+    /// The user was told when to retry; violations until then get no reply
+    quiet_until: Option<Instant>,
+}
+
 pub struct UserRateLimiter {
     config: RateLimitConfig,
     users: Arc<RwLock<HashMap<String, UserRateLimit>>>,
+    /// Stricter bucket trade-executing commands draw from as well
+    trade_config: RateLimitConfig,
+    /// Command buckets stay per instance, even with `sessions`
+    commands: Arc<RwLock<HashMap<String, CommandBuckets>>>,
+    exempt_users: HashSet<String>,
     last_cleanup: Arc<RwLock<Instant>>,
+    /// Shared per-minute and per-hour counters; stats only cover local buckets
+    sessions: Option<Arc<dyn SessionStore>>,
 }
 
 impl UserRateLimiter {
@@ -142,12 +174,52 @@ impl UserRateLimiter {
         Self {
             config,
             users: Arc::new(RwLock::new(HashMap::new())),
+            trade_config: RateLimitConfig::for_trading(),
+            commands: Arc::new(RwLock::new(HashMap::new())),
+            exempt_users: HashSet::new(),
             last_cleanup: Arc::new(RwLock::new(Instant::now())),
+            sessions: None,
         }
     }
     
+    /// Command limits from `Config`, with admins exempt
+    pub fn for_commands(config: &Config) -> Self {
+        Self::new(RateLimitConfig {
+            requests_per_minute: config.command_tokens_per_minute,
+            burst_capacity: config.command_burst,
+            ..RateLimitConfig::default()
+        })
+        .with_trade_limits(RateLimitConfig {
+            requests_per_minute: config.trade_commands_per_minute,
+            burst_capacity: config.trade_burst,
+            ..RateLimitConfig::for_trading()
+        })
+        .with_exempt_users(config.admin_users.iter().cloned())
+    }
+    
+    pub fn with_trade_limits(mut self, config: RateLimitConfig) -> Self {
+        self.trade_config = config;
+        self
+    }
+    
+    /// Users whose commands are never limited
+    pub fn with_exempt_users(mut self, users: impl IntoIterator<Item = String>) -> Self {
+        self.exempt_users.extend(users);
+        self
+    }
+    
+    /// Count requests in the session store so limits hold across instances
+    pub fn with_sessions(mut self, sessions: Arc<dyn SessionStore>) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+    
     /// Check if user can make a request
     pub async fn check_rate_limit(&self, user_id: &str) -> Result<(), RateLimitError> {
+        if let Some(sessions) = &self.sessions {
+            return Self::check_shared(sessions.as_ref(), user_id, &self.config).await;
+        }
+        
         // Periodically clean up old entries
         self.maybe_cleanup().await;
         
@@ -155,11 +227,69 @@ impl UserRateLimiter {
         let user_limit = users.entry(user_id.to_string())
             .or_insert_with(|| UserRateLimit::new(&self.config));
         
-        user_limit.try_acquire(&self.config).await
+        user_limit.try_acquire()
+    }
+    
+    /// Charge a command's cost, plus one trade token when it executes a trade
+    ///
+    /// Either both buckets are charged or neither. When limited, `notify` is
+    /// true only for the first violation until the retry time has passed.
+    pub async fn check_command(&self, user_id: &str, cost: u32, executes_trade: bool) -> Result<(), RateLimitError> {
+        if cost == 0 || self.exempt_users.contains(user_id) {
+            return Ok(());
+        }
+        
+        self.maybe_cleanup().await;
+        
+        let now = Instant::now();
+        let mut commands = self.commands.write().await;
+        let buckets = commands.entry(user_id.to_string()).or_insert_with(|| CommandBuckets {
+            general: UserRateLimit::new(&self.config),
+            trading: UserRateLimit::new(&self.trade_config),
+            quiet_until: None,
+        });
+        
+        buckets.general.total_requests += 1;
+        let general_ok = buckets.general.can_afford(cost, now);
+        let trade_ok = if executes_trade {
+            buckets.trading.total_requests += 1;
+            buckets.trading.can_afford(1, now)
+        } else {
+            true
+        };
+        
+        if general_ok && trade_ok {
+            buckets.general.tokens -= (cost as f64).min(buckets.general.capacity);
+            if executes_trade {
+                buckets.trading.tokens -= 1.0;
+            }
+            return Ok(());
+        }
+        
+        let mut retry_after = Duration::ZERO;
+        if !general_ok {
+            buckets.general.blocked_requests += 1;
+            retry_after = retry_after.max(buckets.general.retry_after(cost));
+        }
+        if !trade_ok {
+            buckets.trading.blocked_requests += 1;
+            retry_after = retry_after.max(buckets.trading.retry_after(1));
+        }
+        
+        let notify = buckets.quiet_until.map_or(true, |until| now >= until);
+        if notify {
+            buckets.quiet_until = Some(now + retry_after);
+            warn!("Command rate limit hit for user {}, retry in {:?}", user_id, retry_after);
+        }
+        Err(RateLimitError::Throttled { retry_after, notify })
     }
     
     /// Check rate limit with custom config
     pub async fn check_rate_limit_with_config(&self, user_id: &str, config: &RateLimitConfig) -> Result<(), RateLimitError> {
+        if let Some(sessions) = &self.sessions {
+            return Self::check_shared(sessions.as_ref(), user_id, config).await;
+        }
+        
         // Periodically clean up old entries
         self.maybe_cleanup().await;
         
@@ -167,7 +297,27 @@ impl UserRateLimiter {
         let user_limit = users.entry(user_id.to_string())
             .or_insert_with(|| UserRateLimit::new(config));
         
-        user_limit.try_acquire(config).await
+        user_limit.try_acquire()
+    }
+    
+    /// Fixed-window counters for the current minute and hour
+    async fn check_shared(sessions: &dyn SessionStore, user_id: &str, config: &RateLimitConfig) -> Result<(), RateLimitError> {
+        let now = Utc::now().timestamp();
+        let windows = [
+            ("m", now / 60, Duration::from_secs(60), config.requests_per_minute),
+            ("h", now / 3600, Duration::from_secs(3600), config.requests_per_hour),
+        ];
+        
+        for (unit, window, ttl, limit) in windows {
+            let key = format!("ratelimit:{}:{}{}", user_id, unit, window);
+            let count = sessions.increment(&key, ttl).await
+                .map_err(|e| RateLimitError::InternalError(e.to_string()))?;
+            if count > limit as u64 {
+                warn!("Rate limit exceeded for user {} ({} requests this window)", user_id, count);
+                return Err(RateLimitError::RateLimitExceeded);
+            }
+        }
+        Ok(())
     }
     
     /// Check rate limit with automatic retry after delay
@@ -198,11 +348,11 @@ impl UserRateLimiter {
     pub async fn add_tokens(&self, user_id: &str, tokens: u32) {
         let mut users = self.users.write().await;
         if let Some(user_limit) = users.get_mut(user_id) {
-            let available = user_limit.tokens.available_permits();
-            let to_add = std::cmp::min(tokens as usize, self.config.burst_capacity as usize - available);
+            user_limit.refill_tokens(Instant::now());
+            let to_add = (tokens as f64).min(user_limit.capacity - user_limit.tokens);
             
-            if to_add > 0 {
-                user_limit.tokens.add_permits(to_add);
+            if to_add > 0.0 {
+                user_limit.tokens += to_add;
                 info!("Added {} tokens to user {}", to_add, user_id);
             }
         }
@@ -211,18 +361,15 @@ impl UserRateLimiter {
     /// Get rate limiting statistics for a user
     pub async fn get_user_stats(&self, user_id: &str) -> Option<UserRateStats> {
         let users = self.users.read().await;
-        users.get(user_id).map(|limit| {
-            UserRateStats {
-                user_id: user_id.to_string(),
-                available_tokens: limit.tokens.available_permits() as u32,
-                total_requests: limit.total_requests,
-                blocked_requests: limit.blocked_requests,
-                block_rate: if limit.total_requests > 0 {
-                    (limit.blocked_requests as f64 / limit.total_requests as f64) * 100.0
-                } else {
-                    0.0
-                }
-            }
+        users.get(user_id).map(|limit| limit.stats(user_id))
+    }
+    
+    /// A user's command and trade buckets
+    pub async fn get_command_stats(&self, user_id: &str) -> Option<CommandRateStats> {
+        let commands = self.commands.read().await;
+        commands.get(user_id).map(|buckets| CommandRateStats {
+            general: buckets.general.stats(user_id),
+            trading: buckets.trading.stats(user_id),
         })
     }
     
@@ -263,6 +410,10 @@ impl UserRateLimiter {
             users.retain(|_user_id, limit| {
                 now.duration_since(limit.last_refill) <= Duration::from_secs(3600)
             });
+            let mut commands = self.commands.write().await;
+            commands.retain(|_user_id, buckets| {
+                now.duration_since(buckets.general.last_refill) <= Duration::from_secs(3600)
+            });
             
             let removed = initial_count - users.len();
             if removed > 0 {
@@ -275,8 +426,8 @@ impl UserRateLimiter {
     
     /// Clear all rate limits (for testing/admin purposes)
     pub async fn clear_all(&self) {
-        let mut users = self.users.write().await;
-        users.clear();
+        self.users.write().await.clear();
+        self.commands.write().await.clear();
         info!("Cleared all rate limits");
     }
     
@@ -297,6 +448,12 @@ pub struct UserRateStats {
     pub block_rate: f64,
 }
 
+#[derive(Debug, Clone)]
+pub struct CommandRateStats {
+    pub general: UserRateStats,
+    pub trading: UserRateStats,
+}
+
 #[derive(Debug, Clone)]
 pub struct GlobalRateStats {
     pub active_users: u32,
@@ -308,6 +465,8 @@ pub struct GlobalRateStats {
 #[derive(Debug, Clone)]
 pub enum RateLimitError {
     RateLimitExceeded,
+    /// A command was refused; `notify` is false while the user was already told
+    Throttled { retry_after: Duration, notify: bool },
     InternalError(String),
 }
 
@@ -315,6 +474,9 @@ impl std::fmt::Display for RateLimitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             RateLimitError::RateLimitExceeded => write!(f, "Rate limit exceeded"),
+            RateLimitError::Throttled { retry_after, .. } => {
+                write!(f, "Rate limit exceeded, retry in {}s", retry_after.as_secs_f64().ceil())
+            }
             RateLimitError::InternalError(msg) => write!(f, "Rate limiter error: {}", msg),
         }
     }
@@ -161,7 +161,7 @@ impl AlertManager {
         let alert_id = format!("{}_{}", rule.id, Utc::now().timestamp());
         
         let alert = Alert {
-            id: alert_id.clone(),
+            id: alert_id,
             rule_id: rule.id.clone(),
             title: format!("Alert: {}", rule.name),
             description: format!(
@@ -187,10 +187,79 @@ impl AlertManager {
             metadata,
         };
         
-        // Add to active alerts
+        // Set cooldown
         {
+            let mut cooldowns = self.cooldowns.write().await;
+            let cooldown_until = Utc::now() + Duration::minutes(rule.cooldown_minutes as i64);
+            cooldowns.insert(rule.id.clone(), cooldown_until);
+        }
+        
+        self.record(alert, &rule.notification_channels).await;
+    }
+    
+    /// Raise an alert that stays active until `resolve` is called for `rule_id`
+    ///
+    /// For components reporting their own state changes rather than a metric
+    /// crossing a rule's threshold. Replaces any active alert for `rule_id`.
+    pub async fn raise(
+        &self,
+        rule_id: &str,
+        title: String,
+        description: String,
+        severity: AlertSeverity,
+        metadata: HashMap<String, String>,
+    ) {
+        self.maybe_resolve_alert(rule_id).await;
+        let alert = Self::event_alert(rule_id, title, description, severity, metadata);
+        self.record(alert, &[NotificationChannel::Console]).await;
+    }
+    
+    /// Record a one-off event in the history; it's never active
+    pub async fn record_event(
+        &self,
+        rule_id: &str,
+        title: String,
+        description: String,
+        severity: AlertSeverity,
+        metadata: HashMap<String, String>,
+    ) {
+        let mut alert = Self::event_alert(rule_id, title, description, severity, metadata);
+        alert.resolved_at = Some(alert.triggered_at);
+        self.record(alert, &[NotificationChannel::Console]).await;
+    }
+    
+    /// Resolve the active alert raised for `rule_id`, if there is one
+    pub async fn resolve(&self, rule_id: &str) {
+        self.maybe_resolve_alert(rule_id).await;
+    }
+    
+    fn event_alert(
+        rule_id: &str,
+        title: String,
+        description: String,
+        severity: AlertSeverity,
+        metadata: HashMap<String, String>,
+    ) -> Alert {
+        let triggered_at = Utc::now();
+        Alert {
+            id: format!("{}_{}", rule_id, triggered_at.timestamp_millis()),
+            rule_id: rule_id.to_string(),
+            title,
+            description,
+            severity,
+            metric_value: 0.0,
+            threshold: 0.0,
+            triggered_at,
+            resolved_at: None,
+            metadata,
+        }
+    }
+    
+    /// Store an alert (active unless already resolved), keep it in the history and notify
+    async fn record(&self, alert: Alert, channels: &[NotificationChannel]) {
+        if alert.resolved_at.is_none() {
             let mut active_alerts = self.active_alerts.write().await;
-            active_alerts.insert(alert_id.clone(), alert.clone());
+            active_alerts.insert(alert.id.clone(), alert.clone());
         }
         
         // Add to history
@@ -204,15 +273,8 @@ impl AlertManager {
             }
         }
         
-        // Set cooldown
-        {
-            let mut cooldowns = self.cooldowns.write().await;
-            let cooldown_until = Utc::now() + Duration::minutes(rule.cooldown_minutes as i64);
-            cooldowns.insert(rule.id.clone(), cooldown_until);
-        }
-        
         // Send notifications
-        self.send_notifications(&alert, &rule.notification_channels).await;
+        self.send_notifications(&alert, channels).await;
         
         match alert.severity {
             AlertSeverity::Emergency => error!("🚨 EMERGENCY ALERT: {}", alert.title),
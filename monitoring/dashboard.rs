@@ -1,7 +1,7 @@
 use axum::{
     extract::State,
     http::StatusCode,
-    response::{Html, Json},
+    response::{Html, Json, Response},
     routing::{get, post},
     Router,
 };
@@ -17,8 +17,9 @@ use tower_http::{
 use tracing::{info, error};
 
 use super::{
+    exporter::render_metrics,
     metrics::{MetricsCollector, MetricsSummary},
-    health::{HealthCheck, SystemHealth},
+    health::{HealthCheck, LivenessReport, ReadinessReport, SystemHealth},
     telemetry::{TelemetryService, TelemetryStats},
 };
 
@@ -103,7 +104,8 @@ impl DashboardServer {
             .route("/api/health", get(api_health_handler))
             .route("/api/telemetry", get(api_telemetry_handler))
             .route("/api/dashboard-data", get(dashboard_data_handler))
-            .with_state(self.state.clone());
+            .with_state(self.state.clone())
+            .merge(probe_routes(self.state.health_check.clone()));
         
         if self.config.enable_cors {
             router = router.layer(CorsLayer::permissive());
@@ -117,17 +119,29 @@ impl DashboardServer {
     }
 }
 
+/// `/healthz` (liveness) and `/readyz` (readiness) for orchestrators
+///
+/// `/readyz` answers 503 while a critical dependency is down.
+pub fn probe_routes(health_check: Arc<HealthCheck>) -> Router {
+    Router::new()
+        .route("/healthz", get(liveness_handler))
+        .route("/readyz", get(readiness_handler))
+        .with_state(health_check)
+}
+
+async fn liveness_handler(State(health_check): State<Arc<HealthCheck>>) -> Json<LivenessReport> {
+    Json(health_check.liveness())
+}
+
+async fn readiness_handler(State(health_check): State<Arc<HealthCheck>>) -> (StatusCode, Json<ReadinessReport>) {
+    let report = health_check.readiness().await;
+    let status = if report.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
 /// Prometheus metrics endpoint
-async fn metrics_handler(State(state): State<AppState>) -> Result<String, StatusCode> {
-    let metrics = state.metrics.gather();
-    
-    use prometheus::Encoder;
-    let encoder = prometheus::TextEncoder::new();
-    
-    match encoder.encode_to_string(&metrics) {
-        Ok(result) => Ok(result),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+async fn metrics_handler(State(state): State<AppState>) -> Response {
+    render_metrics(&state.metrics)
 }
 
 /// Health check endpoint
@@ -0,0 +1,277 @@
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::health::{HealthCheck, HealthCheckConfig, HealthProbe, HealthStatus, ProbeOutcome};
+use crate::db::Database;
+use crate::utils::{Config, SessionBackend};
+
+/// Slots a node may trail the cluster before RPC counts as degraded
+pub const DEFAULT_MAX_SLOT_LAG: u64 = 50;
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// Live checks for everything the bot depends on
+///
+/// Solana RPC, Jupiter, Telegram and the database are critical; Groq only
+/// feeds analysis, so it's reported without failing readiness. Redis is
+/// checked when configured and is critical only as the session backend.
+pub struct DependencyChecks {
+    rpc_url: String,
+    max_slot_lag: u64,
+    jupiter_url: String,
+    groq_url: String,
+    groq_api_key: String,
+    telegram_url: String,
+    telegram_token: String,
+    redis_url: Option<String>,
+    redis_critical: bool,
+    database: Option<Arc<Database>>,
+}
+
+impl DependencyChecks {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            rpc_url: config.get_rpc_url(),
+            max_slot_lag: DEFAULT_MAX_SLOT_LAG,
+            jupiter_url: config.jupiter_api_url.clone(),
+            groq_url: crate::ai::GROQ_API_URL.to_string(),
+            groq_api_key: config.groq_api_key.clone(),
+            telegram_url: config.telegram_api_url.clone().unwrap_or_else(|| TELEGRAM_API_URL.to_string()),
+            telegram_token: config.telegram_bot_token.clone(),
+            redis_url: config.redis_url.clone(),
+            redis_critical: matches!(config.session_backend, SessionBackend::Redis),
+            database: None,
+        }
+    }
+
+    pub fn with_database(mut self, database: Arc<Database>) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    pub fn with_groq_url(mut self, url: impl Into<String>) -> Self {
+        self.groq_url = url.into();
+        self
+    }
+
+    pub fn with_max_slot_lag(mut self, slots: u64) -> Self {
+        self.max_slot_lag = slots;
+        self
+    }
+
+    /// Register a probe per dependency, replacing any built-in check of the same name
+    pub async fn register(self, health: &HealthCheck) {
+        let client = Client::new();
+
+        health.register_probe(config("solana_rpc", 15, 5, true), Arc::new(SolanaRpcProbe {
+            client: client.clone(),
+            url: self.rpc_url,
+            max_slot_lag: self.max_slot_lag,
+        })).await;
+        health.register_probe(config("jupiter_api", 30, 10, true), Arc::new(JupiterProbe {
+            client: client.clone(),
+            url: self.jupiter_url,
+        })).await;
+        health.register_probe(config("ai_analyzer", 60, 10, false), Arc::new(GroqProbe {
+            client: client.clone(),
+            url: self.groq_url,
+            api_key: self.groq_api_key,
+        })).await;
+        health.register_probe(config("telegram_bot", 30, 10, true), Arc::new(TelegramProbe {
+            client,
+            url: self.telegram_url,
+            token: self.telegram_token,
+        })).await;
+        if let Some(database) = self.database {
+            health.register_probe(config("database", 15, 5, true), Arc::new(DatabaseProbe { database })).await;
+        }
+        if let Some(url) = self.redis_url {
+            health.register_probe(config("redis_cache", 15, 5, self.redis_critical), Arc::new(RedisProbe { url })).await;
+        }
+    }
+}
+
+fn config(component: &str, check_interval_seconds: u64, timeout_seconds: u64, critical: bool) -> HealthCheckConfig {
+    HealthCheckConfig {
+        component: component.to_string(),
+        check_interval_seconds,
+        timeout_seconds,
+        retries: 0,
+        critical,
+    }
+}
+
+fn metadata(pairs: &[(&str, String)]) -> HashMap<String, String> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+}
+
+/// 2xx is healthy, 429 degraded, anything else unhealthy
+fn http_outcome(name: &str, result: reqwest::Result<reqwest::Response>) -> (ProbeOutcome, Option<reqwest::Response>) {
+    match result {
+        Ok(response) if response.status().is_success() => {
+            ((HealthStatus::Healthy, format!("{} healthy", name), HashMap::new()), Some(response))
+        }
+        Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+            ((HealthStatus::Degraded, format!("{} is rate limiting us", name), HashMap::new()), None)
+        }
+        Ok(response) => ((HealthStatus::Unhealthy, format!("{} returned {}", name, response.status()), HashMap::new()), None),
+        Err(e) => ((HealthStatus::Unhealthy, format!("{} unreachable: {}", name, e), HashMap::new()), None),
+    }
+}
+
+/// `getHealth`, with the node's slot lag when it reports one
+struct SolanaRpcProbe {
+    client: Client,
+    url: String,
+    max_slot_lag: u64,
+}
+
+#[async_trait]
+impl HealthProbe for SolanaRpcProbe {
+    async fn check(&self) -> ProbeOutcome {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "getHealth" });
+        let body: Value = match self.client.post(&self.url).json(&request).send().await {
+            Ok(response) if response.status().is_success() => match response.json().await {
+                Ok(body) => body,
+                Err(e) => return (HealthStatus::Unhealthy, format!("Invalid RPC response: {}", e), HashMap::new()),
+            },
+            Ok(response) => return (HealthStatus::Unhealthy, format!("RPC returned {}", response.status()), HashMap::new()),
+            Err(e) => return (HealthStatus::Unhealthy, format!("RPC unreachable: {}", e), HashMap::new()),
+        };
+
+        if body["result"] == "ok" {
+            return (HealthStatus::Healthy, "Solana RPC healthy".to_string(), metadata(&[("slots_behind", "0".to_string())]));
+        }
+
+        let message = body["error"]["message"].as_str().unwrap_or("unhealthy").to_string();
+        match body["error"]["data"]["numSlotsBehind"].as_u64() {
+            Some(behind) => {
+                let details = metadata(&[
+                    ("slots_behind", behind.to_string()),
+                    ("max_slot_lag", self.max_slot_lag.to_string()),
+                ]);
+                if behind > self.max_slot_lag {
+                    (HealthStatus::Degraded, format!("RPC node is {} slots behind", behind), details)
+                } else {
+                    (HealthStatus::Healthy, format!("RPC node is {} slots behind, within tolerance", behind), details)
+                }
+            }
+            // A node that can't say how far behind it is
+            None => (HealthStatus::Degraded, format!("RPC node reports: {}", message), HashMap::new()),
+        }
+    }
+}
+
+/// A small SOL → USDC quote
+struct JupiterProbe {
+    client: Client,
+    url: String,
+}
+
+#[async_trait]
+impl HealthProbe for JupiterProbe {
+    async fn check(&self) -> ProbeOutcome {
+        let result = self.client
+            .get(format!("{}/quote", self.url))
+            .query(&[("inputMint", WSOL_MINT), ("outputMint", USDC_MINT), ("amount", "1000000"), ("slippageBps", "50")])
+            .send()
+            .await;
+        http_outcome("Jupiter quote API", result).0
+    }
+}
+
+/// Lists models, which needs a valid key but spends no tokens
+struct GroqProbe {
+    client: Client,
+    url: String,
+    api_key: String,
+}
+
+#[async_trait]
+impl HealthProbe for GroqProbe {
+    async fn check(&self) -> ProbeOutcome {
+        let result = self.client
+            .get(format!("{}/models", self.url))
+            .bearer_auth(&self.api_key)
+            .send()
+            .await;
+        http_outcome("Groq API", result).0
+    }
+}
+
+/// `getMe` with the bot's own token
+struct TelegramProbe {
+    client: Client,
+    url: String,
+    token: String,
+}
+
+#[async_trait]
+impl HealthProbe for TelegramProbe {
+    async fn check(&self) -> ProbeOutcome {
+        let result = self.client
+            .get(format!("{}/bot{}/getMe", self.url, self.token))
+            .send()
+            .await;
+        let (outcome, response) = http_outcome("Telegram API", result);
+        let Some(response) = response else {
+            return outcome;
+        };
+
+        match response.json::<Value>().await {
+            Ok(body) if body["ok"] == true => {
+                let username = body["result"]["username"].as_str().unwrap_or_default();
+                (outcome.0, outcome.1, metadata(&[("bot_username", format!("@{}", username))]))
+            }
+            Ok(body) => (
+                HealthStatus::Unhealthy,
+                format!("getMe failed: {}", body["description"].as_str().unwrap_or("no description")),
+                HashMap::new(),
+            ),
+            Err(e) => (HealthStatus::Unhealthy, format!("Invalid getMe response: {}", e), HashMap::new()),
+        }
+    }
+}
+
+struct DatabaseProbe {
+    database: Arc<Database>,
+}
+
+#[async_trait]
+impl HealthProbe for DatabaseProbe {
+    async fn check(&self) -> ProbeOutcome {
+        match self.database.ping().await {
+            Ok(()) => (HealthStatus::Healthy, "Database reachable".to_string(), HashMap::new()),
+            Err(e) => (HealthStatus::Unhealthy, format!("Database unreachable: {}", e), HashMap::new()),
+        }
+    }
+}
+
+/// PING on a fresh connection
+struct RedisProbe {
+    url: String,
+}
+
+#[async_trait]
+impl HealthProbe for RedisProbe {
+    async fn check(&self) -> ProbeOutcome {
+        let client = match redis::Client::open(self.url.as_str()) {
+            Ok(client) => client,
+            Err(e) => return (HealthStatus::Unhealthy, format!("Invalid REDIS_URL: {}", e), HashMap::new()),
+        };
+        let pong: redis::RedisResult<String> = async {
+            let mut conn = client.get_multiplexed_async_connection().await?;
+            redis::cmd("PING").query_async(&mut conn).await
+        }.await;
+
+        match pong {
+            Ok(_) => (HealthStatus::Healthy, "Redis reachable".to_string(), HashMap::new()),
+            Err(e) => (HealthStatus::Unhealthy, format!("Redis unreachable: {}", e), HashMap::new()),
+        }
+    }
+}
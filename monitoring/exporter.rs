@@ -0,0 +1,69 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use super::{dashboard::probe_routes, health::HealthCheck, metrics::MetricsCollector};
+
+/// Standalone Prometheus scrape endpoint
+///
+/// Serves `GET /metrics`, plus `/healthz` and `/readyz` once given a health
+/// registry, so it can run on its own port without the dashboard and telemetry.
+pub struct MetricsExporter {
+    metrics: Arc<MetricsCollector>,
+    health_check: Option<Arc<HealthCheck>>,
+}
+
+impl MetricsExporter {
+    pub fn new(metrics: Arc<MetricsCollector>) -> Self {
+        Self { metrics, health_check: None }
+    }
+    
+    pub fn with_health_check(mut self, health_check: Arc<HealthCheck>) -> Self {
+        self.health_check = Some(health_check);
+        self
+    }
+    
+    pub fn router(&self) -> Router {
+        let router = Router::new()
+            .route("/metrics", get(metrics_handler))
+            .with_state(self.metrics.clone());
+        match &self.health_check {
+            Some(health_check) => router.merge(probe_routes(health_check.clone())),
+            None => router,
+        }
+    }
+    
+    /// Bind `0.0.0.0:port` and serve until the task is dropped
+    pub async fn start(&self, port: u16) -> std::io::Result<()> {
+        let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+        self.serve(listener).await
+    }
+    
+    /// Serve on an already bound listener
+    pub async fn serve(&self, listener: TcpListener) -> std::io::Result<()> {
+        info!("📊 Prometheus metrics on http://{}/metrics", listener.local_addr()?);
+        axum::serve(listener, self.router()).await
+    }
+}
+
+async fn metrics_handler(State(metrics): State<Arc<MetricsCollector>>) -> Response {
+    render_metrics(&metrics)
+}
+
+/// The collector's metrics as a text exposition response
+pub(crate) fn render_metrics(metrics: &MetricsCollector) -> Response {
+    match metrics.encode_text() {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => {
+            error!("📊 Failed to encode metrics: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
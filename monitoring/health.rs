@@ -1,3 +1,4 @@
+use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -45,9 +46,52 @@ pub struct SystemHealth {
     pub version: String,
 }
 
+/// One dependency as reported by `/readyz`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyStatus {
+    pub component: String,
+    pub critical: bool,
+    pub status: HealthStatus,
+    pub message: String,
+    pub duration_ms: u64,
+    pub checked_at: DateTime<Utc>,
+    pub metadata: HashMap<String, String>,
+}
+
+/// Whether the bot can take traffic
+///
+/// Only critical dependencies that are unhealthy or unknown make it not
+/// ready. Anything degraded, or a failing non-critical dependency, keeps it
+/// ready but reports the status as Degraded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub status: HealthStatus,
+    pub checks: Vec<DependencyStatus>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// The process is up; says nothing about dependencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LivenessReport {
+    pub status: String,
+    pub uptime_seconds: u64,
+    pub version: String,
+}
+
+/// What a check found: status, message and details for the payload
+pub type ProbeOutcome = (HealthStatus, String, HashMap<String, String>);
+
+/// A live check against one dependency
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    async fn check(&self) -> ProbeOutcome;
+}
+
 /// Health check service
 pub struct HealthCheck {
     checks: Arc<RwLock<HashMap<String, HealthCheckConfig>>>,
+    probes: Arc<RwLock<HashMap<String, Arc<dyn HealthProbe>>>>,
     results: Arc<RwLock<HashMap<String, HealthCheckResult>>>,
     system_start_time: DateTime<Utc>,
     version: String,
@@ -58,6 +102,7 @@ impl HealthCheck {
     pub fn new(version: String) -> Self {
         Self {
             checks: Arc::new(RwLock::new(HashMap::new())),
+            probes: Arc::new(RwLock::new(HashMap::new())),
             results: Arc::new(RwLock::new(HashMap::new())),
             system_start_time: Utc::now(),
             version,
@@ -72,6 +117,12 @@ impl HealthCheck {
         checks.insert(config.component.clone(), config);
     }
     
+    /// Register a check answered by a live probe instead of the built-in one
+    pub async fn register_probe(&self, config: HealthCheckConfig, probe: Arc<dyn HealthProbe>) {
+        self.probes.write().await.insert(config.component.clone(), probe);
+        self.register_check(config).await;
+    }
+    
     /// Perform all health checks
     pub async fn check_all(&self) -> SystemHealth {
         let checks = self.checks.read().await.clone();
@@ -130,11 +181,109 @@ impl HealthCheck {
         }
     }
     
+    /// Readiness from every registered check, reusing results younger than their interval
+    pub async fn readiness(&self) -> ReadinessReport {
+        let checks = self.checks.read().await.clone();
+        let mut statuses: Vec<DependencyStatus> = futures::future::join_all(
+            checks.values().map(|config| async move {
+                let result = self.current_result(config).await;
+                DependencyStatus {
+                    component: result.component,
+                    critical: config.critical,
+                    status: result.status,
+                    message: result.message,
+                    duration_ms: result.duration_ms,
+                    checked_at: result.timestamp,
+                    metadata: result.metadata,
+                }
+            })
+        ).await;
+        statuses.sort_by(|a, b| a.component.cmp(&b.component));
+        
+        let ready = !statuses.iter().any(|check| {
+            check.critical && matches!(check.status, HealthStatus::Unhealthy | HealthStatus::Unknown)
+        });
+        let status = if !ready {
+            HealthStatus::Unhealthy
+        } else if statuses.iter().any(|check| check.status != HealthStatus::Healthy) {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        };
+        
+        ReadinessReport {
+            ready,
+            status,
+            checks: statuses,
+            checked_at: Utc::now(),
+        }
+    }
+    
+    /// Liveness: answering at all is the check
+    pub fn liveness(&self) -> LivenessReport {
+        LivenessReport {
+            status: "alive".to_string(),
+            uptime_seconds: self.uptime_seconds(),
+            version: self.version.clone(),
+        }
+    }
+    
+    fn uptime_seconds(&self) -> u64 {
+        Utc::now()
+            .signed_duration_since(self.system_start_time)
+            .num_seconds() as u64
+    }
+    
+    /// The cached result while it's younger than the check interval, else a fresh one
+    async fn current_result(&self, config: &HealthCheckConfig) -> HealthCheckResult {
+        let fresh_since = Utc::now() - Duration::seconds(config.check_interval_seconds as i64);
+        if let Some(cached) = self.results.read().await
+            .get(&config.component)
+            .filter(|result| result.timestamp > fresh_since)
+        {
+            return cached.clone();
+        }
+        
+        let result = self.perform_check(&config.component, config).await;
+        self.results.write().await.insert(config.component.clone(), result.clone());
+        result
+    }
+    
     /// Perform individual health check
     async fn perform_check(&self, component: &str, config: &HealthCheckConfig) -> HealthCheckResult {
         let start_time = std::time::Instant::now();
         
-        let (status, message, metadata) = match component {
+        let probe = self.probes.read().await.get(component).cloned();
+        let (status, message, metadata) = match probe {
+            Some(probe) => {
+                let timeout = std::time::Duration::from_secs(config.timeout_seconds);
+                match tokio::time::timeout(timeout, probe.check()).await {
+                    Ok(outcome) => outcome,
+                    Err(_) => (
+                        HealthStatus::Unhealthy,
+                        format!("No answer within {}s", config.timeout_seconds),
+                        HashMap::new(),
+                    ),
+                }
+            }
+            None => self.builtin_check(component).await,
+        };
+        
+        let duration_ms = start_time.elapsed().as_millis() as u64;
+        
+        HealthCheckResult {
+            component: component.to_string(),
+            status,
+            message,
+            duration_ms,
+            timestamp: Utc::now(),
+            metadata,
+        }
+    }
+    
+    /// Checks for components registered without a probe
+    async fn builtin_check(&self, component: &str) -> ProbeOutcome {
+        match component {
             "database" => self.check_database().await,
             "redis_cache" => self.check_redis().await,
             "solana_rpc" => self.check_solana_rpc().await,
@@ -146,17 +295,6 @@ impl HealthCheck {
             "mev_protection" => self.check_mev_protection().await,
             "ai_analyzer" => self.check_ai_analyzer().await,
             _ => (HealthStatus::Unknown, "Unknown component".to_string(), HashMap::new()),
-        };
-        
-        let duration_ms = start_time.elapsed().as_millis() as u64;
-        
-        HealthCheckResult {
-            component: component.to_string(),
-            status: status.clone(),
-            message,
-            duration_ms,
-            timestamp: Utc::now(),
-            metadata,
         }
     }
     
@@ -377,6 +515,7 @@ impl Clone for HealthCheck {
     fn clone(&self) -> Self {
         Self {
             checks: Arc::clone(&self.checks),
+            probes: Arc::clone(&self.probes),
             results: Arc::clone(&self.results),
             system_start_time: self.system_start_time,
             version: self.version.clone(),
@@ -6,6 +6,7 @@ use super::{
     metrics::MetricsCollector,
     telemetry::{TelemetryService, TelemetryConfig},
     health::{HealthCheck, HealthCheckConfig},
+    dependencies::DependencyChecks,
     dashboard::{DashboardServer, DashboardConfig},
     alerts::{AlertManager, AlertRule, AlertSeverity, AlertCondition, NotificationChannel},
 };
@@ -96,6 +97,12 @@ impl MonitoringIntegration {
         Ok(())
     }
     
+    /// Swap the built-in checks for live probes of the bot's dependencies
+    pub async fn register_dependency_checks(&self, checks: DependencyChecks) {
+        checks.register(&self.health_check).await;
+        info!("✅ Dependency health probes registered");
+    }
+    
     /// Register health check components
     async fn register_health_checks(health_check: &Arc<HealthCheck>) {
         // Database health check
@@ -1,19 +1,29 @@
 use prometheus::{
-    register_counter_vec, register_gauge_vec, register_histogram_vec,
-    CounterVec, GaugeVec, HistogramVec, Registry,
+    register_counter_vec, register_gauge, register_gauge_vec, register_histogram_vec,
+    CounterVec, Encoder, Gauge, GaugeVec, HistogramOpts, HistogramVec, Opts, Registry, TextEncoder,
 };
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use tracing::{info, debug, warn};
 use serde::{Serialize, Deserialize};
 
+use crate::errors::BotError;
+
 /// Types of metrics to collect
+///
+/// Custom metrics are exported as the matching Prometheus type; summaries
+/// are exported as histograms since quantiles can't be aggregated across
+/// instances.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MetricType {
+    /// Each value is added to the running total
     Counter,
+    /// Each value replaces the last
     Gauge,
+    /// Each value is observed into buckets
     Histogram,
     Summary,
 }
@@ -26,6 +36,8 @@ pub struct MetricsCollector {
     trades_total: CounterVec,
     trades_successful: CounterVec,
     trades_failed: CounterVec,
+    trades_executed_by_type: CounterVec,
+    trades_failed_by_type: CounterVec,
     trade_volume: GaugeVec,
     trade_latency: HistogramVec,
     
@@ -39,8 +51,11 @@ pub struct MetricsCollector {
     commands_processed: CounterVec,
     api_calls: CounterVec,
     api_latency: HistogramVec,
+    api_rate_limit_wait: HistogramVec,
+    api_retries: CounterVec,
     cache_hits: CounterVec,
     cache_misses: CounterVec,
+    cache_events: CounterVec,
     
     // MEV metrics
     mev_bundles_sent: CounterVec,
@@ -50,12 +65,38 @@ pub struct MetricsCollector {
     // Market data metrics
     market_data_updates: CounterVec,
     price_feed_latency: HistogramVec,
+    websocket_events: CounterVec,
     
     // Error metrics
     errors_total: CounterVec,
     
+    // AI usage metrics
+    ai_tokens_total: CounterVec,
+    ai_cost_usd_total: CounterVec,
+    
+    // Order monitoring metrics
+    order_price_monitors: GaugeVec,
+    order_poll_rate: GaugeVec,
+    orders_active: GaugeVec,
+    dca_strategies_active: Gauge,
+    queue_depth: GaugeVec,
+    lane_latency: HistogramVec,
+    
     // Custom metrics storage
     custom_metrics: Arc<RwLock<HashMap<String, CustomMetric>>>,
+    custom_collectors: Arc<RwLock<HashMap<String, CustomCollector>>>,
+}
+
+/// A custom metric's exported collector and the label names it was created with
+struct CustomCollector {
+    label_names: Vec<String>,
+    kind: CustomKind,
+}
+
+enum CustomKind {
+    Counter(CounterVec),
+    Gauge(GaugeVec),
+    Histogram(HistogramVec),
 }
 
 #[derive(Debug, Clone)]
@@ -94,6 +135,20 @@ impl MetricsCollector {
         )?;
         registry.register(Box::new(trades_failed.clone()))?;
         
+        let trades_executed_by_type = register_counter_vec!(
+            "trades_executed_total",
+            "Trades that filled, by trade type",
+            &["type"]
+        )?;
+        registry.register(Box::new(trades_executed_by_type.clone()))?;
+        
+        let trades_failed_by_type = register_counter_vec!(
+            "trades_failed_total",
+            "Trades that failed, by trade type and reason",
+            &["type", "reason"]
+        )?;
+        registry.register(Box::new(trades_failed_by_type.clone()))?;
+        
         let trade_volume = register_gauge_vec!(
             "trade_volume_sol",
             "Trading volume in SOL",
@@ -161,6 +216,21 @@ impl MetricsCollector {
         )?;
         registry.register(Box::new(api_latency.clone()))?;
         
+        let api_rate_limit_wait = register_histogram_vec!(
+            "api_rate_limit_wait_ms",
+            "Time spent waiting for a rate limit permit in milliseconds",
+            &["endpoint"],
+            vec![0.0, 10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0]
+        )?;
+        registry.register(Box::new(api_rate_limit_wait.clone()))?;
+        
+        let api_retries = register_counter_vec!(
+            "api_retries_total",
+            "API calls retried after a 429 or 5xx",
+            &["endpoint", "status"]
+        )?;
+        registry.register(Box::new(api_retries.clone()))?;
+        
         let cache_hits = register_counter_vec!(
             "cache_hits_total",
             "Total cache hits",
@@ -175,6 +245,13 @@ impl MetricsCollector {
         )?;
         registry.register(Box::new(cache_misses.clone()))?;
         
+        let cache_events = register_counter_vec!(
+            "cache_events_total",
+            "Cache lookups that joined an in-flight fetch or were served stale",
+            &["cache_type", "event"]
+        )?;
+        registry.register(Box::new(cache_events.clone()))?;
+        
         // Initialize MEV metrics
         let mev_bundles_sent = register_counter_vec!(
             "mev_bundles_sent_total",
@@ -213,6 +290,13 @@ impl MetricsCollector {
         )?;
         registry.register(Box::new(price_feed_latency.clone()))?;
         
+        let websocket_events = register_counter_vec!(
+            "websocket_events_total",
+            "WebSocket reconnects, missed heartbeats and resubscribed channels",
+            &["connection", "event"]
+        )?;
+        registry.register(Box::new(websocket_events.clone()))?;
+        
         // Initialize error metrics
         let errors_total = register_counter_vec!(
             "errors_total",
@@ -221,11 +305,71 @@ impl MetricsCollector {
         )?;
         registry.register(Box::new(errors_total.clone()))?;
         
+        // Initialize AI usage metrics
+        let ai_tokens_total = register_counter_vec!(
+            "ai_tokens_total",
+            "Total LLM tokens consumed",
+            &["feature", "model", "kind"]
+        )?;
+        registry.register(Box::new(ai_tokens_total.clone()))?;
+        
+        let ai_cost_usd_total = register_counter_vec!(
+            "ai_cost_usd_total",
+            "Estimated LLM spend in USD",
+            &["feature", "model"]
+        )?;
+        registry.register(Box::new(ai_cost_usd_total.clone()))?;
+        
+        // Initialize order monitoring metrics
+        let order_price_monitors = register_gauge_vec!(
+            "order_price_monitors",
+            "Mints with an active order price monitor",
+            &["kind"]
+        )?;
+        registry.register(Box::new(order_price_monitors.clone()))?;
+        
+        let order_poll_rate = register_gauge_vec!(
+            "order_poll_rate_per_minute",
+            "Effective order monitoring passes per minute",
+            &["loop"]
+        )?;
+        registry.register(Box::new(order_poll_rate.clone()))?;
+        
+        let orders_active = register_gauge_vec!(
+            "orders_active",
+            "Active and pending orders by order type",
+            &["type"]
+        )?;
+        registry.register(Box::new(orders_active.clone()))?;
+        
+        let dca_strategies_active = register_gauge!(
+            "dca_strategies_active",
+            "DCA schedules still running"
+        )?;
+        registry.register(Box::new(dca_strategies_active.clone()))?;
+        
+        let queue_depth = register_gauge_vec!(
+            "queue_depth",
+            "Messages waiting in an actor's mailbox",
+            &["queue"]
+        )?;
+        registry.register(Box::new(queue_depth.clone()))?;
+        
+        let lane_latency = register_histogram_vec!(
+            "lane_latency_ms",
+            "Time a message spent waiting in its lane and running, in milliseconds",
+            &["queue", "stage"],
+            vec![1.0, 5.0, 10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 5000.0, 30000.0]
+        )?;
+        registry.register(Box::new(lane_latency.clone()))?;
+        
         Ok(Self {
             registry,
             trades_total,
             trades_successful,
             trades_failed,
+            trades_executed_by_type,
+            trades_failed_by_type,
             trade_volume,
             trade_latency,
             wallet_balance,
@@ -235,15 +379,28 @@ impl MetricsCollector {
             commands_processed,
             api_calls,
             api_latency,
+            api_rate_limit_wait,
+            api_retries,
             cache_hits,
             cache_misses,
+            cache_events,
             mev_bundles_sent,
             mev_bundles_landed,
             mev_protection_saved,
             market_data_updates,
             price_feed_latency,
+            websocket_events,
             errors_total,
+            ai_tokens_total,
+            ai_cost_usd_total,
+            order_price_monitors,
+            order_poll_rate,
+            orders_active,
+            dca_strategies_active,
+            queue_depth,
+            lane_latency,
             custom_metrics: Arc::new(RwLock::new(HashMap::new())),
+            custom_collectors: Arc::new(RwLock::new(HashMap::new())),
         })
     }
     
@@ -283,6 +440,20 @@ impl MetricsCollector {
             action, token, user, success, volume_sol);
     }
     
+    /// Record a filled trade of the given type, e.g. "buy", "sell" or "dca"
+    pub fn record_trade_executed(&self, trade_type: &str) {
+        self.trades_executed_by_type
+            .with_label_values(&[trade_type])
+            .inc();
+    }
+    
+    /// Record a failed trade, labelled with the kind of error that stopped it
+    pub fn record_trade_failed(&self, trade_type: &str, error: &BotError) {
+        self.trades_failed_by_type
+            .with_label_values(&[trade_type, failure_reason(error)])
+            .inc();
+    }
+    
     /// Record wallet balance
     pub fn record_wallet_balance(&self, wallet: &str, token: &str, balance: f64) {
         self.wallet_balance
@@ -323,6 +494,20 @@ impl MetricsCollector {
             .observe(latency_ms);
     }
     
+    /// Record how long a call queued for its rate limit permit
+    pub fn record_rate_limit_wait(&self, endpoint: &str, wait_ms: f64) {
+        self.api_rate_limit_wait
+            .with_label_values(&[endpoint])
+            .observe(wait_ms);
+    }
+    
+    /// Record an API call retried after a throttling or server error status
+    pub fn record_api_retry(&self, endpoint: &str, status: u16) {
+        self.api_retries
+            .with_label_values(&[endpoint, &status.to_string()])
+            .inc();
+    }
+    
     /// Record cache hit/miss
     pub fn record_cache_access(&self, cache_type: &str, hit: bool) {
         if hit {
@@ -336,6 +521,20 @@ impl MetricsCollector {
         }
     }
     
+    /// Record a cache lookup that was neither a plain hit nor a miss
+    pub fn record_cache_event(&self, cache_type: &str, event: &str) {
+        self.cache_events
+            .with_label_values(&[cache_type, event])
+            .inc();
+    }
+    
+    /// Record a WebSocket connection event
+    pub fn record_websocket_event(&self, connection: &str, event: &str, count: u64) {
+        self.websocket_events
+            .with_label_values(&[connection, event])
+            .inc_by(count as f64);
+    }
+    
     /// Record MEV bundle
     pub fn record_mev_bundle(&self, strategy: &str, sent: bool, landed: bool) {
         if sent {
@@ -381,6 +580,70 @@ impl MetricsCollector {
         warn!("Error recorded: {} in {} (severity: {})", error_type, component, severity);
     }
     
+    /// Record LLM token usage and estimated cost
+    pub fn record_ai_usage(
+        &self,
+        feature: &str,
+        model: &str,
+        prompt_tokens: u32,
+        completion_tokens: u32,
+        cost_usd: f64,
+    ) {
+        self.ai_tokens_total
+            .with_label_values(&[feature, model, "prompt"])
+            .inc_by(prompt_tokens as f64);
+        self.ai_tokens_total
+            .with_label_values(&[feature, model, "completion"])
+            .inc_by(completion_tokens as f64);
+        self.ai_cost_usd_total
+            .with_label_values(&[feature, model])
+            .inc_by(cost_usd);
+    }
+    
+    /// Record the order monitor count and the loop's current cadence
+    pub fn record_order_monitoring(&self, monitors: usize, orders: usize, polls_per_minute: f64) {
+        self.order_price_monitors
+            .with_label_values(&["mints"])
+            .set(monitors as f64);
+        self.order_price_monitors
+            .with_label_values(&["orders"])
+            .set(orders as f64);
+        self.order_poll_rate
+            .with_label_values(&["orders"])
+            .set(polls_per_minute);
+    }
+    
+    /// Record how many orders of each type are live
+    pub fn record_active_orders(&self, counts: &[(&str, usize)]) {
+        for (order_type, count) in counts {
+            self.orders_active
+                .with_label_values(&[order_type])
+                .set(*count as f64);
+        }
+    }
+    
+    /// Record how many DCA schedules are running
+    pub fn record_active_dca_strategies(&self, count: usize) {
+        self.dca_strategies_active.set(count as f64);
+    }
+    
+    /// Record how many messages are waiting in `queue`
+    pub fn record_queue_depth(&self, queue: &str, depth: usize) {
+        self.queue_depth
+            .with_label_values(&[queue])
+            .set(depth as f64);
+    }
+    
+    /// Record how long a message waited for its lane, then how long it ran
+    pub fn record_lane_latency(&self, queue: &str, wait: Duration, run: Duration) {
+        self.lane_latency
+            .with_label_values(&[queue, "wait"])
+            .observe(wait.as_secs_f64() * 1000.0);
+        self.lane_latency
+            .with_label_values(&[queue, "run"])
+            .observe(run.as_secs_f64() * 1000.0);
+    }
+    
     /// Update bot uptime
     pub fn update_uptime(&self, seconds: f64) {
         self.bot_uptime
@@ -396,6 +659,8 @@ impl MetricsCollector {
         metric_type: MetricType,
         labels: HashMap<String, String>,
     ) {
+        self.export_custom_metric(&name, value, &metric_type, &labels).await;
+        
         let metric = CustomMetric {
             name: name.clone(),
             value,
@@ -408,6 +673,66 @@ impl MetricsCollector {
         metrics.insert(name, metric);
     }
     
+    /// Apply a custom value to its Prometheus collector, registering it on first use
+    ///
+    /// A name keeps the type and label names it was first recorded with;
+    /// values that don't match are logged and left out of the export.
+    async fn export_custom_metric(&self, name: &str, value: f64, metric_type: &MetricType, labels: &HashMap<String, String>) {
+        let mut label_names: Vec<String> = labels.keys().cloned().collect();
+        label_names.sort();
+        let label_values: Vec<&str> = label_names.iter().map(|k| labels[k].as_str()).collect();
+        
+        let mut collectors = self.custom_collectors.write().await;
+        if !collectors.contains_key(name) {
+            match self.register_custom_collector(name, metric_type, &label_names) {
+                Ok(kind) => {
+                    collectors.insert(name.to_string(), CustomCollector { label_names: label_names.clone(), kind });
+                }
+                Err(e) => {
+                    warn!("📊 Custom metric {} can't be exported: {}", name, e);
+                    return;
+                }
+            }
+        }
+        
+        let collector = &collectors[name];
+        if collector.label_names != label_names {
+            warn!("📊 Custom metric {} recorded with labels {:?}, expected {:?}", name, label_names, collector.label_names);
+            return;
+        }
+        match (&collector.kind, metric_type) {
+            (CustomKind::Counter(counter), MetricType::Counter) if value >= 0.0 => {
+                counter.with_label_values(&label_values).inc_by(value);
+            }
+            (CustomKind::Gauge(gauge), MetricType::Gauge) => {
+                gauge.with_label_values(&label_values).set(value);
+            }
+            (CustomKind::Histogram(histogram), MetricType::Histogram | MetricType::Summary) => {
+                histogram.with_label_values(&label_values).observe(value);
+            }
+            _ => warn!("📊 Custom metric {} can't take {} as a {:?}", name, value, metric_type),
+        }
+    }
+    
+    /// Custom collectors live only in this collector's registry
+    fn register_custom_collector(&self, name: &str, metric_type: &MetricType, label_names: &[String]) -> Result<CustomKind, prometheus::Error> {
+        let help = format!("Custom metric {}", name);
+        let label_names: Vec<&str> = label_names.iter().map(String::as_str).collect();
+        let kind = match metric_type {
+            MetricType::Counter => CustomKind::Counter(CounterVec::new(Opts::new(name, help), &label_names)?),
+            MetricType::Gauge => CustomKind::Gauge(GaugeVec::new(Opts::new(name, help), &label_names)?),
+            MetricType::Histogram | MetricType::Summary => {
+                CustomKind::Histogram(HistogramVec::new(HistogramOpts::new(name, help), &label_names)?)
+            }
+        };
+        match &kind {
+            CustomKind::Counter(counter) => self.registry.register(Box::new(counter.clone()))?,
+            CustomKind::Gauge(gauge) => self.registry.register(Box::new(gauge.clone()))?,
+            CustomKind::Histogram(histogram) => self.registry.register(Box::new(histogram.clone()))?,
+        }
+        Ok(kind)
+    }
+    
     /// Get custom metric
     pub async fn get_custom_metric(&self, name: &str) -> Option<CustomMetric> {
         let metrics = self.custom_metrics.read().await;
@@ -419,6 +744,13 @@ impl MetricsCollector {
         self.registry.gather()
     }
     
+    /// Everything in the Prometheus text exposition format
+    pub fn encode_text(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+    
     /// Get metrics summary
     pub async fn get_summary(&self) -> MetricsSummary {
         let custom_metrics = self.custom_metrics.read().await;
@@ -477,4 +809,17 @@ pub struct MetricsSummary {
     pub total_errors: f64,
     pub custom_metrics_count: usize,
     pub uptime_seconds: f64,
-}
\ No newline at end of file
+}
+
+/// Bounded reason label for a failed trade
+fn failure_reason(error: &BotError) -> &'static str {
+    match error {
+        BotError::Validation { .. } => "validation",
+        BotError::InsufficientBalance { .. } => "balance",
+        BotError::SlippageExceeded { .. } => "slippage",
+        BotError::RateLimited { .. } => "rate_limited",
+        BotError::ServiceUnavailable { .. } => "unavailable",
+        BotError::Trading(_) => "trading",
+        _ => "other",
+    }
+}
@@ -1,13 +1,17 @@
 pub mod metrics;
+pub mod exporter;
 pub mod telemetry;
 pub mod health;
+pub mod dependencies;
 pub mod dashboard;
 pub mod alerts;
 pub mod integration;
 
 pub use metrics::{MetricsCollector, MetricType};
+pub use exporter::MetricsExporter;
 pub use telemetry::{TelemetryService, init_telemetry};
-pub use health::{HealthCheck, HealthStatus};
+pub use health::{HealthCheck, HealthProbe, HealthStatus, ReadinessReport};
+pub use dependencies::DependencyChecks;
 pub use dashboard::{DashboardServer, MetricsDashboard};
 pub use alerts::{AlertManager, AlertRule, AlertSeverity};
 pub use integration::{MonitoringIntegration, MonitoringStatus};
\ No newline at end of file
@@ -4,6 +4,7 @@ use chrono::{DateTime, Utc, Duration};
 use tracing::{info, debug};
 
 use super::types::*;
+use crate::analytics::CostBasis;
 
 /// Analyzes portfolio data and provides insights
 pub struct PortfolioAnalyzer;
@@ -19,6 +20,7 @@ impl PortfolioAnalyzer {
         PortfolioAnalysis {
             wallet_address: portfolio.wallet_address.clone(),
             total_value_usd: portfolio.total_value_usd,
+            net_value_after_fees_usd: portfolio.holdings.iter().map(|h| self.net_value_after_fees(h)).sum(),
             diversification,
             risk_metrics,
             allocation,
@@ -27,6 +29,39 @@ impl PortfolioAnalyzer {
         }
     }
     
+    /// Tokens a transfer of the whole balance would lose to the Token-2022 transfer fee
+    pub fn transfer_fee_tokens(&self, holding: &TokenHolding) -> f64 {
+        let Some(fee) = &holding.transfer_fee else { return 0.0 };
+        let scale = 10f64.powi(holding.decimals as i32);
+        let raw_balance = (holding.balance * scale).round() as u64;
+        fee.fee_for(raw_balance) as f64 / scale
+    }
+    
+    /// What exiting the whole holding would actually return, in USD
+    pub fn net_value_after_fees(&self, holding: &TokenHolding) -> f64 {
+        (holding.balance - self.transfer_fee_tokens(holding)).max(0.0) * holding.price_usd
+    }
+    
+    /// Unrealized P&L against `cost_basis_usd`, gross and after the exit transfer fee
+    pub fn position_pnl(&self, holding: &TokenHolding, cost_basis_usd: f64) -> PnlFigures {
+        let gross_usd = holding.value_usd - cost_basis_usd;
+        let net_usd = self.net_value_after_fees(holding) - cost_basis_usd;
+        let percentage = |pnl: f64| if cost_basis_usd > 0.0 { pnl / cost_basis_usd * 100.0 } else { 0.0 };
+        
+        PnlFigures {
+            gross_usd,
+            net_usd,
+            gross_percentage: percentage(gross_usd),
+            net_percentage: percentage(net_usd),
+        }
+    }
+    
+    /// Unrealized P&L on the open lots, pricing their SOL cost at the holding's implied SOL rate
+    pub fn lot_pnl(&self, holding: &TokenHolding, basis: &CostBasis) -> PnlFigures {
+        let usd_per_sol = if holding.value_sol > 0.0 { holding.value_usd / holding.value_sol } else { 0.0 };
+        self.position_pnl(holding, basis.cost_sol * usd_per_sol)
+    }
+    
     /// Analyze portfolio diversification
     fn analyze_diversification(&self, holdings: &[TokenHolding]) -> DiversificationMetrics {
         let total_value = holdings.iter().map(|h| h.value_usd).sum::<f64>();
@@ -264,6 +299,7 @@ impl PortfolioAnalyzer {
 pub struct PortfolioAnalysis {
     pub wallet_address: String,
     pub total_value_usd: f64,
+    pub net_value_after_fees_usd: f64,
     pub diversification: DiversificationMetrics,
     pub risk_metrics: RiskMetrics,
     pub allocation: AllocationBreakdown,
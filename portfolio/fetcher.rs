@@ -1,4 +1,5 @@
 use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use reqwest::Client;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -6,8 +7,12 @@ use tokio::sync::RwLock;
 use tracing::{info, debug, warn, error};
 use chrono::Utc;
 
+use super::analyzer::PortfolioAnalyzer;
 use super::types::*;
 use crate::errors::BotError;
+use crate::trading::{Token2022Manager, TransferFee, TOKEN_2022_PROGRAM_ID};
+
+const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 
 /// Fetches real portfolio data from Solana RPC and price APIs
 pub struct PortfolioFetcher {
@@ -15,6 +20,7 @@ pub struct PortfolioFetcher {
     rpc_url: String,
     jupiter_price_api: String,
     token_list_cache: Arc<RwLock<HashMap<String, TokenMetadata>>>,
+    token_2022: Token2022Manager,
 }
 
 impl PortfolioFetcher {
@@ -24,6 +30,7 @@ impl PortfolioFetcher {
             rpc_url,
             jupiter_price_api: "https://price.jup.ag/v4/price".to_string(),
             token_list_cache: Arc::new(RwLock::new(HashMap::new())),
+            token_2022: Token2022Manager::new(),
         }
     }
     
@@ -40,8 +47,10 @@ impl PortfolioFetcher {
         debug!("Found {} token holdings", token_holdings.len());
         
         // Fetch prices for all tokens
+        let analyzer = PortfolioAnalyzer;
         let mut holdings_with_prices = Vec::new();
         let mut total_value_usd = 0.0;
+        let mut net_value_after_fees_usd = 0.0;
         
         // Add SOL as first holding
         if sol_balance > 0.0 {
@@ -58,8 +67,11 @@ impl PortfolioFetcher {
                 price_change_24h: None, // Would need historical data
                 logo_uri: Some("https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/So11111111111111111111111111111111111111112/logo.png".to_string()),
                 is_verified: true,
+                transfer_fee: None,
+                net_value_after_fees: sol_balance * sol_price,
             };
             total_value_usd += sol_holding.value_usd;
+            net_value_after_fees_usd += sol_holding.net_value_after_fees;
             holdings_with_prices.push(sol_holding);
         }
         
@@ -73,6 +85,7 @@ impl PortfolioFetcher {
                 token_holding.price_usd = price;
                 token_holding.value_usd = token_holding.balance * price;
                 token_holding.value_sol = token_holding.value_usd / self.fetch_token_price("So11111111111111111111111111111111111111112").await.unwrap_or(1.0);
+                token_holding.net_value_after_fees = analyzer.net_value_after_fees(&token_holding);
                 
                 if let Some(meta) = metadata {
                     token_holding.name = meta.name;
@@ -82,6 +95,7 @@ impl PortfolioFetcher {
                 }
                 
                 total_value_usd += token_holding.value_usd;
+                net_value_after_fees_usd += token_holding.net_value_after_fees;
                 holdings_with_prices.push(token_holding);
             }
         }
@@ -93,6 +107,7 @@ impl PortfolioFetcher {
             wallet_address: wallet_address.to_string(),
             total_value_usd,
             total_value_sol: total_value_usd / self.fetch_token_price("So11111111111111111111111111111111111111112").await.unwrap_or(1.0),
+            net_value_after_fees_usd,
             holdings: holdings_with_prices,
             performance,
             last_updated: Utc::now(),
@@ -125,8 +140,30 @@ impl PortfolioFetcher {
         Ok(balance_response.result.value as f64 / 1_000_000_000.0) // Convert lamports to SOL
     }
     
-    /// Fetch token holdings for wallet
+    /// Fetch token holdings for wallet, with the transfer fee of any Token-2022 mint
     async fn fetch_token_holdings(&self, wallet_address: &str) -> Result<Vec<TokenHolding>> {
+        let mut holdings = self.fetch_program_holdings(wallet_address, SPL_TOKEN_PROGRAM_ID).await?;
+        
+        let mut token_2022_holdings = self.fetch_program_holdings(wallet_address, TOKEN_2022_PROGRAM_ID).await?;
+        if !token_2022_holdings.is_empty() {
+            let epoch = self.fetch_epoch().await?;
+            for holding in &mut token_2022_holdings {
+                holding.transfer_fee = match self.fetch_transfer_fee(&holding.mint_address, epoch).await {
+                    Ok(fee) => fee,
+                    Err(e) => {
+                        warn!("Failed to read transfer fee for {}: {}", holding.mint_address, e);
+                        None
+                    }
+                };
+            }
+        }
+        holdings.extend(token_2022_holdings);
+        
+        Ok(holdings)
+    }
+    
+    /// Token accounts owned by the wallet under one token program
+    async fn fetch_program_holdings(&self, wallet_address: &str, program_id: &str) -> Result<Vec<TokenHolding>> {
         let payload = serde_json::json!({
             "jsonrpc": "2.0",
             "id": 1,
@@ -134,7 +171,7 @@ impl PortfolioFetcher {
             "params": [
                 wallet_address,
                 {
-                    "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+                    "programId": program_id
                 },
                 {
                     "encoding": "jsonParsed"
@@ -173,6 +210,8 @@ impl PortfolioFetcher {
                     price_change_24h: None,
                     logo_uri: None,
                     is_verified: false,
+                    transfer_fee: None,
+                    net_value_after_fees: 0.0, // Will be calculated with price
                 });
             }
         }
@@ -180,6 +219,55 @@ impl PortfolioFetcher {
         Ok(holdings)
     }
     
+    /// Current epoch, which picks between a mint's older and newer transfer fee
+    async fn fetch_epoch(&self) -> Result<u64> {
+        let result = self.rpc_call("getEpochInfo", serde_json::json!([])).await?;
+        result["epoch"].as_u64()
+            .ok_or_else(|| BotError::parsing("getEpochInfo returned no epoch".to_string()).into())
+    }
+    
+    /// Transfer fee in force at `epoch` for a Token-2022 mint, if it has one
+    async fn fetch_transfer_fee(&self, mint_address: &str, epoch: u64) -> Result<Option<TransferFee>> {
+        let result = self.rpc_call(
+            "getAccountInfo",
+            serde_json::json!([mint_address, { "encoding": "base64" }]),
+        ).await?;
+        let Some(encoded) = result["value"]["data"][0].as_str() else {
+            return Ok(None);
+        };
+        
+        let mint_data = STANDARD.decode(encoded)
+            .map_err(|e| BotError::parsing(format!("Invalid mint data for {}: {}", mint_address, e)))?;
+        let fee = self.token_2022.parse_mint_transfer_fee(&mint_data)?
+            .map(|config| config.fee_at(epoch).clone());
+        if let Some(fee) = &fee {
+            debug!("{} charges {} bps on transfers (max {})", mint_address, fee.transfer_fee_basis_points, fee.maximum_fee);
+        }
+        Ok(fee)
+    }
+    
+    async fn rpc_call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        });
+        
+        let response = self.client
+            .post(&self.rpc_url)
+            .json(&payload)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::api(format!("{} request failed: {}", method, response.status())).into());
+        }
+        
+        let mut body: serde_json::Value = response.json().await?;
+        Ok(body["result"].take())
+    }
+    
     /// Fetch token price from Jupiter
     async fn fetch_token_price(&self, mint_address: &str) -> Result<f64> {
         let url = format!("{}?ids={}", self.jupiter_price_api, mint_address);
@@ -289,6 +377,16 @@ impl PortfolioFetcher {
             })
             .sum::<f64>();
         
+        // The same moves on what the holdings would net after transfer fees
+        let pnl_24h_net_usd = holdings
+            .iter()
+            .map(|h| {
+                h.price_change_24h
+                    .map(|change| h.net_value_after_fees * (change / 100.0))
+                    .unwrap_or(0.0)
+            })
+            .sum::<f64>();
+        
         let pnl_24h_percentage = if total_value > 0.0 {
             (pnl_24h_usd / total_value) * 100.0
         } else {
@@ -300,6 +398,7 @@ impl PortfolioFetcher {
             total_pnl_sol: 0.0, // Would need historical cost basis  
             pnl_percentage: 0.0, // Would need historical cost basis
             pnl_24h_usd,
+            pnl_24h_net_usd,
             pnl_24h_percentage,
             best_performer,
             worst_performer,
@@ -323,6 +422,8 @@ impl PortfolioFetcher {
                 symbol: h.symbol.clone(),
                 balance: h.balance,
                 value_usd: h.value_usd,
+                net_value_after_fees: h.net_value_after_fees,
+                transfer_fee_bps: h.transfer_fee.as_ref().map(|fee| fee.transfer_fee_basis_points),
                 percentage: if portfolio.total_value_usd > 0.0 {
                     (h.value_usd / portfolio.total_value_usd) * 100.0
                 } else {
@@ -333,6 +434,7 @@ impl PortfolioFetcher {
         
         Ok(PortfolioSummary {
             total_value_usd: portfolio.total_value_usd,
+            net_value_after_fees_usd: portfolio.net_value_after_fees_usd,
             total_holdings: portfolio.holdings.len(),
             top_holdings,
             performance_24h: portfolio.performance.pnl_24h_percentage,
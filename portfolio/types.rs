@@ -2,12 +2,17 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
+use crate::trading::TransferFee;
+
 /// Portfolio data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Portfolio {
     pub wallet_address: String,
     pub total_value_usd: f64,
     pub total_value_sol: f64,
+    /// What the holdings would fetch after Token-2022 transfer fees
+    #[serde(default)]
+    pub net_value_after_fees_usd: f64,
     pub holdings: Vec<TokenHolding>,
     pub performance: PortfolioPerformance,
     pub last_updated: DateTime<Utc>,
@@ -27,6 +32,18 @@ pub struct TokenHolding {
     pub price_change_24h: Option<f64>,
     pub logo_uri: Option<String>,
     pub is_verified: bool,
+    /// Token-2022 transfer fee in force for the current epoch
+    #[serde(default)]
+    pub transfer_fee: Option<TransferFee>,
+    /// `value_usd` less the transfer fee withheld when the balance is moved out
+    #[serde(default)]
+    pub net_value_after_fees: f64,
+}
+
+impl TokenHolding {
+    pub fn has_transfer_fee(&self) -> bool {
+        self.transfer_fee.as_ref().is_some_and(|fee| fee.transfer_fee_basis_points > 0)
+    }
 }
 
 /// Portfolio performance metrics
@@ -36,6 +53,9 @@ pub struct PortfolioPerformance {
     pub total_pnl_sol: f64,
     pub pnl_percentage: f64,
     pub pnl_24h_usd: f64,
+    /// 24h P&L on the value left after transfer fees
+    #[serde(default)]
+    pub pnl_24h_net_usd: f64,
     pub pnl_24h_percentage: f64,
     pub best_performer: Option<TokenPerformance>,
     pub worst_performer: Option<TokenPerformance>,
@@ -43,6 +63,15 @@ pub struct PortfolioPerformance {
     pub largest_holding: Option<String>,
 }
 
+/// P&L against a cost basis, on the quoted value and on what an exit would actually return
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PnlFigures {
+    pub gross_usd: f64,
+    pub net_usd: f64,
+    pub gross_percentage: f64,
+    pub net_percentage: f64,
+}
+
 /// Token performance data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenPerformance {
@@ -157,6 +186,7 @@ pub struct TokenMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortfolioSummary {
     pub total_value_usd: f64,
+    pub net_value_after_fees_usd: f64,
     pub total_holdings: usize,
     pub top_holdings: Vec<HoldingSummary>,
     pub performance_24h: f64,
@@ -168,6 +198,8 @@ pub struct HoldingSummary {
     pub symbol: String,
     pub balance: f64,
     pub value_usd: f64,
+    pub net_value_after_fees: f64,
+    pub transfer_fee_bps: Option<u16>,
     pub percentage: f64,
 }
 
@@ -1,38 +1,84 @@
 use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::collections::HashMap;
 use chrono::{Utc, Duration};
-use tracing::{info, warn, error, debug};
+use tracing::{info, warn, debug};
 
 use super::types::*;
-use super::providers::{goplus::GoPlusProvider, rugcheck::RugCheckProvider};
+use super::providers::{
+    goplus::GoPlusProvider, onchain::OnChainProvider, rugcheck::RugCheckProvider, SecurityProvider,
+};
 use crate::errors::BotError;
 
+/// Share of the combined score each built-in provider carries
+const GOPLUS_WEIGHT: f64 = 0.4;
+const RUGCHECK_WEIGHT: f64 = 0.2;
+const ONCHAIN_WEIGHT: f64 = 0.4;
+
 /// Cache entry for security analysis
 struct CachedAnalysis {
     analysis: SecurityAnalysis,
     cached_at: chrono::DateTime<Utc>,
 }
 
+/// A provider and its share of the combined score
+struct WeightedProvider {
+    provider: Arc<dyn SecurityProvider>,
+    weight: f64,
+}
+
 /// Comprehensive LARP (Liquidity And Rug Pull) checker
+///
+/// Asks every provider and combines the scores of those that answer,
+/// weighted; one provider failing only drops it from the average.
 pub struct LarpChecker {
-    goplus_provider: GoPlusProvider,
-    rugcheck_provider: RugCheckProvider,
+    providers: Vec<WeightedProvider>,
     cache: Arc<RwLock<HashMap<String, CachedAnalysis>>>,
     cache_ttl: Duration,
 }
 
 impl LarpChecker {
     pub fn new(goplus_api_key: Option<String>) -> Self {
+        Self::with_providers(vec![
+            (Arc::new(GoPlusProvider::new(goplus_api_key)) as Arc<dyn SecurityProvider>, GOPLUS_WEIGHT),
+            (Arc::new(RugCheckProvider::new()), RUGCHECK_WEIGHT),
+        ])
+    }
+    
+    /// Combine exactly these providers, each with its weight
+    pub fn with_providers(providers: Vec<(Arc<dyn SecurityProvider>, f64)>) -> Self {
         Self {
-            goplus_provider: GoPlusProvider::new(goplus_api_key),
-            rugcheck_provider: RugCheckProvider::new(),
+            providers: providers.into_iter()
+                .map(|(provider, weight)| WeightedProvider { provider, weight })
+                .collect(),
             cache: Arc::new(RwLock::new(HashMap::new())),
             cache_ttl: Duration::minutes(5),
         }
     }
     
+    /// GoPlus and RugCheck, plus on-chain heuristics from `SOLANA_RPC_URL`
+    ///
+    /// The on-chain provider needs no API key, so `/larp` still answers
+    /// without `GOPLUS_API_KEY` or when the services are down.
+    pub fn from_env() -> Self {
+        let rpc_url = std::env::var("SOLANA_RPC_URL")
+            .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+        Self::new(std::env::var("GOPLUS_API_KEY").ok())
+            .with_onchain(Arc::new(RpcClient::new(rpc_url)))
+    }
+    
+    /// Add the on-chain heuristics provider reading from `rpc_client`
+    pub fn with_onchain(self, rpc_client: Arc<RpcClient>) -> Self {
+        self.with_provider(Arc::new(OnChainProvider::new(rpc_client)), ONCHAIN_WEIGHT)
+    }
+    
+    pub fn with_provider(mut self, provider: Arc<dyn SecurityProvider>, weight: f64) -> Self {
+        self.providers.push(WeightedProvider { provider, weight });
+        self
+    }
+    
     /// Perform comprehensive security analysis on a token
     pub async fn analyze_token(&self, token_address: &str) -> Result<SecurityAnalysis> {
         info!("Starting LARP analysis for token: {}", token_address);
@@ -49,60 +95,55 @@ impl LarpChecker {
             }
         }
         
-        // Try multiple providers and combine results
-        let mut combined_analysis = None;
-        let mut data_sources = Vec::new();
-        
-        // Try GoPlus first (primary provider)
-        match self.goplus_provider.check_token_security(token_address).await {
-            Ok(analysis) => {
-                info!("GoPlus analysis successful for {}", token_address);
-                combined_analysis = Some(analysis);
-                data_sources.push("GoPlus Security".to_string());
-            }
-            Err(e) => {
-                warn!("GoPlus analysis failed for {}: {}", token_address, e);
-            }
-        }
-        
-        // Try RugCheck as backup or additional validation
-        match self.rugcheck_provider.check_token(token_address).await {
-            Ok(rugcheck_analysis) => {
-                info!("RugCheck analysis successful for {}", token_address);
-                data_sources.push("RugCheck".to_string());
-                
-                if let Some(ref mut analysis) = combined_analysis {
-                    // Merge results - take the more conservative score
-                    analysis.risk_score = analysis.risk_score.min(rugcheck_analysis.risk_score);
-                    
-                    // Combine warnings
-                    for warning in rugcheck_analysis.warnings {
-                        if !analysis.warnings.iter().any(|w| w.message == warning.message) {
-                            analysis.warnings.push(warning);
-                        }
-                    }
-                    
-                    // Combine passed checks
-                    for check in rugcheck_analysis.passed_checks {
-                        if !analysis.passed_checks.contains(&check) {
-                            analysis.passed_checks.push(check);
-                        }
+        let results = futures::future::join_all(
+            self.providers.iter().map(|p| p.provider.check(token_address))
+        ).await;
+        
+        let mut combined_analysis: Option<SecurityAnalysis> = None;
+        let mut provider_status = Vec::new();
+        let mut weighted_score = 0.0;
+        let mut answered_weight = 0.0;
+        for (weighted, result) in self.providers.iter().zip(results) {
+            let name = weighted.provider.name().to_string();
+            match result {
+                Ok(analysis) => {
+                    info!("{} analysis successful for {}", name, token_address);
+                    weighted_score += analysis.risk_score as f64 * weighted.weight;
+                    answered_weight += weighted.weight;
+                    provider_status.push(ProviderStatus {
+                        provider: name,
+                        weight: weighted.weight,
+                        score: Some(analysis.risk_score),
+                        error: None,
+                    });
+                    match combined_analysis.as_mut() {
+                        Some(combined) => Self::merge(combined, analysis),
+                        None => combined_analysis = Some(analysis),
                     }
-                    
-                    // Update data sources
-                    analysis.data_sources = data_sources.clone();
-                } else {
-                    combined_analysis = Some(rugcheck_analysis);
                 }
-            }
-            Err(e) => {
-                warn!("RugCheck analysis failed for {}: {}", token_address, e);
+                Err(e) => {
+                    warn!("{} analysis failed for {}: {}", name, token_address, e);
+                    provider_status.push(ProviderStatus {
+                        provider: name,
+                        weight: weighted.weight,
+                        score: None,
+                        error: Some(e.to_string()),
+                    });
+                }
             }
         }
         
         // If no providers succeeded, return error
         let mut final_analysis = combined_analysis
             .ok_or_else(|| BotError::external_api("All security providers failed"))?;
+        if answered_weight > 0.0 {
+            final_analysis.risk_score = (weighted_score / answered_weight).round().clamp(0.0, 100.0) as u8;
+        }
+        final_analysis.data_sources = provider_status.iter()
+            .filter(|status| status.score.is_some())
+            .map(|status| status.provider.clone())
+            .collect();
+        final_analysis.provider_status = provider_status;
         
         // Add additional analysis
         self.perform_additional_checks(&mut final_analysis).await;
@@ -130,6 +171,59 @@ impl LarpChecker {
         Ok(final_analysis)
     }
     
+    /// Fold another provider's findings into the combined analysis
+    ///
+    /// Red flags from any provider stick; gaps are filled from whichever
+    /// provider knows the value. The score is combined separately.
+    fn merge(combined: &mut SecurityAnalysis, other: SecurityAnalysis) {
+        for warning in other.warnings {
+            if !combined.warnings.iter().any(|w| w.message == warning.message) {
+                combined.warnings.push(warning);
+            }
+        }
+        for check in other.passed_checks {
+            if !combined.passed_checks.contains(&check) {
+                combined.passed_checks.push(check);
+            }
+        }
+        for check in other.failed_checks {
+            if !combined.failed_checks.contains(&check) {
+                combined.failed_checks.push(check);
+            }
+        }
+        
+        combined.is_honeypot |= other.is_honeypot;
+        combined.can_buy &= other.can_buy;
+        combined.can_sell &= other.can_sell;
+        combined.liquidity_locked |= other.liquidity_locked;
+        combined.liquidity_lock_duration = combined.liquidity_lock_duration.or(other.liquidity_lock_duration);
+        combined.freeze_authority = combined.freeze_authority.take().or(other.freeze_authority);
+        combined.mint_authority = combined.mint_authority.take().or(other.mint_authority);
+        combined.creator_address = combined.creator_address.take().or(other.creator_address);
+        
+        if combined.token_symbol == "UNKNOWN" {
+            combined.token_symbol = other.token_symbol;
+        }
+        if combined.token_name == "Unknown Token" {
+            combined.token_name = other.token_name;
+        }
+        if combined.top_holders.is_empty() {
+            combined.top_holders = other.top_holders;
+        }
+        if combined.holder_count == 0 {
+            combined.holder_count = other.holder_count;
+        }
+        if combined.token_age_hours == 0.0 {
+            combined.token_age_hours = other.token_age_hours;
+        }
+        if combined.total_supply == 0.0 {
+            combined.total_supply = other.total_supply;
+        }
+        if combined.liquidity_usd == 0.0 {
+            combined.liquidity_usd = other.liquidity_usd;
+        }
+    }
+    
     /// Perform additional security checks
     async fn perform_additional_checks(&self, analysis: &mut SecurityAnalysis) {
         // Check for common scam patterns
@@ -147,8 +241,9 @@ impl LarpChecker {
             }
         }
         
-        // 2. Check token age
-        if analysis.token_age_hours < 24.0 && analysis.token_age_hours > 0.0 {
+        // 2. Check token age, unless a provider already has
+        let age_warned = analysis.warnings.iter().any(|w| w.category == WarningCategory::Age);
+        if analysis.token_age_hours < 24.0 && analysis.token_age_hours > 0.0 && !age_warned {
             analysis.warnings.push(SecurityWarning {
                 severity: WarningSeverity::Medium,
                 category: WarningCategory::Age,
@@ -158,14 +253,17 @@ impl LarpChecker {
             analysis.risk_score = analysis.risk_score.saturating_sub(10);
         }
         
-        // 3. Check holder concentration
+        // 3. Check holder concentration; pool vaults and locked tokens can't be dumped
         let top_10_percent: f64 = analysis.top_holders
             .iter()
             .take(10)
+            .filter(|h| !h.is_exchange && !h.is_locked)
             .map(|h| h.percentage)
             .sum();
         
-        if top_10_percent > 70.0 {
+        let concentration_warned = analysis.warnings.iter()
+            .any(|w| w.category == WarningCategory::Distribution && w.message.starts_with("Top 10 holders"));
+        if top_10_percent > 70.0 && !concentration_warned {
             analysis.warnings.push(SecurityWarning {
                 severity: WarningSeverity::High,
                 category: WarningCategory::Distribution,
@@ -328,6 +426,22 @@ impl LarpChecker {
             output.push_str(&format!("• {}\n", rec));
         }
         
+        // Providers
+        if !analysis.provider_status.is_empty() {
+            output.push_str("\n🔌 **Providers:**\n");
+            for status in &analysis.provider_status {
+                match (status.score, &status.error) {
+                    (Some(score), _) => output.push_str(&format!(
+                        "• {}: {}/100 (weight {:.0}%)\n", status.provider, score, status.weight * 100.0
+                    )),
+                    (None, Some(error)) => output.push_str(&format!(
+                        "• {}: ❌ unavailable ({})\n", status.provider, error
+                    )),
+                    (None, None) => output.push_str(&format!("• {}: ❌ unavailable\n", status.provider)),
+                }
+            }
+        }
+        
         // Data sources
         output.push_str(&format!(
             "\n📌 *Data from: {}*\n",
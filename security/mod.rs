@@ -3,4 +3,5 @@ pub mod larp_checker;
 pub mod providers;
 
 pub use types::*;
-pub use larp_checker::LarpChecker;
\ No newline at end of file
+pub use larp_checker::LarpChecker;
+pub use providers::SecurityProvider;
\ No newline at end of file
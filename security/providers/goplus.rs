@@ -89,6 +89,7 @@ struct DexInfo {
 pub struct GoPlusProvider {
     client: Client,
     api_key: Option<String>,
+    base_url: String,
     rate_limiter: ApiRateLimiter,
 }
 
@@ -103,10 +104,17 @@ impl GoPlusProvider {
         Self {
             client,
             api_key,
+            base_url: GOPLUS_API_BASE.to_string(),
             rate_limiter: ApiRateLimiter::new(),
         }
     }
     
+    /// Point at another GoPlus-compatible endpoint
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+    
     /// Check token security using GoPlus API
     pub async fn check_token_security(&self, token_address: &str) -> Result<SecurityAnalysis> {
         debug!("Checking security for token: {}", token_address);
@@ -117,7 +125,7 @@ impl GoPlusProvider {
         // Build URL
         let url = format!(
             "{}{}?contract_addresses={}",
-            GOPLUS_API_BASE,
+            self.base_url,
             GOPLUS_SOLANA_ENDPOINT,
             token_address
         );
@@ -364,6 +372,7 @@ impl GoPlusProvider {
             },
             analysis_timestamp: chrono::Utc::now(),
             data_sources: vec!["GoPlus Security".to_string()],
+            provider_status: Vec::new(),
         }
     }
 }
\ No newline at end of file
@@ -1,2 +1,51 @@
 pub mod goplus;
-pub mod rugcheck;
\ No newline at end of file
+pub mod rugcheck;
+pub mod onchain;
+
+use anyhow::Result;
+
+use crate::security::types::SecurityAnalysis;
+use goplus::GoPlusProvider;
+use onchain::OnChainProvider;
+use rugcheck::RugCheckProvider;
+
+/// A source of token safety analyses that `LarpChecker` combines
+#[async_trait::async_trait]
+pub trait SecurityProvider: Send + Sync {
+    /// Shown in the analysis' data sources and provider status
+    fn name(&self) -> &str;
+    async fn check(&self, token_address: &str) -> Result<SecurityAnalysis>;
+}
+
+#[async_trait::async_trait]
+impl SecurityProvider for GoPlusProvider {
+    fn name(&self) -> &str {
+        "GoPlus Security"
+    }
+
+    async fn check(&self, token_address: &str) -> Result<SecurityAnalysis> {
+        self.check_token_security(token_address).await
+    }
+}
+
+#[async_trait::async_trait]
+impl SecurityProvider for RugCheckProvider {
+    fn name(&self) -> &str {
+        "RugCheck"
+    }
+
+    async fn check(&self, token_address: &str) -> Result<SecurityAnalysis> {
+        self.check_token(token_address).await
+    }
+}
+
+#[async_trait::async_trait]
+impl SecurityProvider for OnChainProvider {
+    fn name(&self) -> &str {
+        "On-chain"
+    }
+
+    async fn check(&self, token_address: &str) -> Result<SecurityAnalysis> {
+        self.check_token(token_address).await
+    }
+}
@@ -0,0 +1,470 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::security::types::*;
+
+const SPL_TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const RAYDIUM_AMM_V4_PROGRAM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLNfcM1KpSBR9HJovP1";
+/// Owns the token vaults of every Raydium AMM v4 pool
+pub const RAYDIUM_AUTHORITY: &str = "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1";
+pub const ORCA_WHIRLPOOL_PROGRAM: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+/// Tokens sent here can never be moved again
+pub const INCINERATOR: &str = "1nc1nerator11111111111111111111111111111111";
+/// Escrows of LP tokens locked through Streamflow are owned by this program
+pub const STREAMFLOW_PROGRAM: &str = "strmRqUCoQUgGUan5YhzUZa6KqdzwX5L6FpUxfmKg5m";
+
+/// SPL mint layout; Token-2022 mints share it before their extensions
+const MINT_LEN: usize = 82;
+/// Raydium AMM v4 pool state: size and field offsets
+pub const RAYDIUM_POOL_LEN: usize = 752;
+pub const RAYDIUM_BASE_MINT: usize = 400;
+pub const RAYDIUM_QUOTE_MINT: usize = 432;
+pub const RAYDIUM_LP_MINT: usize = 464;
+pub const RAYDIUM_LP_RESERVE: usize = 720;
+/// Orca Whirlpool state: size and mint offsets
+pub const WHIRLPOOL_LEN: usize = 653;
+pub const WHIRLPOOL_MINT_A: usize = 101;
+pub const WHIRLPOOL_MINT_B: usize = 181;
+
+/// Signatures fetched per page, and pages walked back looking for the mint's first
+const SIGNATURES_PER_PAGE: usize = 1000;
+const MAX_SIGNATURE_PAGES: usize = 5;
+
+/// The fields of a mint account the checks need
+#[derive(Debug, Clone)]
+struct MintInfo {
+    mint_authority: Option<Pubkey>,
+    supply: u64,
+    decimals: u8,
+    freeze_authority: Option<Pubkey>,
+}
+
+impl MintInfo {
+    fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < MINT_LEN {
+            return None;
+        }
+        Some(Self {
+            mint_authority: coption_pubkey(&data[0..36]),
+            supply: read_u64(data, 36)?,
+            decimals: data[44],
+            freeze_authority: coption_pubkey(&data[46..82]),
+        })
+    }
+}
+
+/// A Raydium pool for the token and how much of its LP can never be withdrawn
+#[derive(Debug, Clone)]
+struct RaydiumPool {
+    address: Pubkey,
+    lp_mint: Pubkey,
+    /// LP the pool has issued and not redeemed
+    lp_reserve: u64,
+}
+
+/// Running score and findings for one token
+struct Findings {
+    score: u8,
+    warnings: Vec<SecurityWarning>,
+    passed_checks: Vec<String>,
+    failed_checks: Vec<String>,
+}
+
+impl Findings {
+    fn new() -> Self {
+        Self { score: 100, warnings: Vec::new(), passed_checks: Vec::new(), failed_checks: Vec::new() }
+    }
+
+    fn pass(&mut self, check: String) {
+        self.passed_checks.push(check);
+    }
+
+    fn fail(&mut self, penalty: u8, severity: WarningSeverity, category: WarningCategory, message: String, details: &str) {
+        self.failed_checks.push(message.clone());
+        self.warn(penalty, severity, category, message, details);
+    }
+
+    fn warn(&mut self, penalty: u8, severity: WarningSeverity, category: WarningCategory, message: String, details: &str) {
+        self.score = self.score.saturating_sub(penalty);
+        self.warnings.push(SecurityWarning { severity, category, message, details: Some(details.to_string()) });
+    }
+}
+
+/// Scores a token from RPC data alone, so `/larp` works without API keys
+///
+/// Looks at the mint and freeze authorities, how much of the supply the
+/// largest holders own, whether the LP of the token's Raydium pool is burned
+/// or locked, and how old the mint is. Checks the RPC node can't answer are
+/// reported without affecting the score.
+pub struct OnChainProvider {
+    rpc_client: Arc<RpcClient>,
+}
+
+impl OnChainProvider {
+    pub fn new(rpc_client: Arc<RpcClient>) -> Self {
+        Self { rpc_client }
+    }
+
+    /// Analyse the token from its mint, holders, pool and history
+    pub async fn check_token(&self, token_address: &str) -> Result<SecurityAnalysis> {
+        debug!("Checking token on-chain: {}", token_address);
+
+        let mint = Pubkey::from_str(token_address)
+            .map_err(|_| anyhow!("Invalid token address: {}", token_address))?;
+        let account = self.rpc_client
+            .get_account_with_commitment(&mint, CommitmentConfig::confirmed())
+            .await?
+            .value
+            .ok_or_else(|| anyhow!("Token {} not found on-chain", token_address))?;
+        let owner = account.owner.to_string();
+        if owner != SPL_TOKEN_PROGRAM && owner != TOKEN_2022_PROGRAM {
+            return Err(anyhow!("{} is not a token mint", token_address));
+        }
+        let mint_info = MintInfo::parse(&account.data)
+            .ok_or_else(|| anyhow!("Unreadable mint account {}", token_address))?;
+
+        let mut findings = Findings::new();
+        Self::check_authorities(&mint_info, &mut findings);
+
+        let top_holders = match self.top_holders(&mint, &mint_info).await {
+            Ok(holders) => {
+                Self::check_concentration(&holders, &mut findings);
+                holders
+            }
+            Err(e) => {
+                warn!("Holder lookup failed for {}: {}", token_address, e);
+                findings.warn(0, WarningSeverity::Low, WarningCategory::Distribution,
+                    "Holder distribution unavailable".to_string(), &e.to_string());
+                Vec::new()
+            }
+        };
+
+        let liquidity_locked = match self.check_liquidity(&mint, &mut findings).await {
+            Ok(locked) => locked,
+            Err(e) => {
+                warn!("Pool lookup failed for {}: {}", token_address, e);
+                findings.warn(0, WarningSeverity::Low, WarningCategory::Liquidity,
+                    "Liquidity pool check unavailable".to_string(), &e.to_string());
+                false
+            }
+        };
+
+        let token_age_hours = match self.created_at(&mint).await {
+            Ok(created_at) => Self::check_age(created_at, &mut findings),
+            Err(e) => {
+                warn!("History lookup failed for {}: {}", token_address, e);
+                findings.warn(0, WarningSeverity::Low, WarningCategory::Age,
+                    "Token age unavailable".to_string(), &e.to_string());
+                0.0
+            }
+        };
+
+        let total_supply = mint_info.supply as f64 / 10f64.powi(mint_info.decimals as i32);
+        let analysis = SecurityAnalysis {
+            token_address: token_address.to_string(),
+            token_symbol: "UNKNOWN".to_string(),
+            token_name: "Unknown Token".to_string(),
+            is_honeypot: false,
+            can_sell: true, // Trading restrictions aren't visible from the mint
+            can_buy: true,
+            liquidity_locked,
+            liquidity_lock_duration: None,
+            freeze_authority: mint_info.freeze_authority.map(|key| key.to_string()),
+            mint_authority: mint_info.mint_authority.map(|key| key.to_string()),
+            update_authority: None,
+            creator_address: None,
+            creator_balance_percent: 0.0,
+            top_holders,
+            holder_count: 0, // Would need a full token account scan
+            risk_score: findings.score,
+            risk_level: SecurityAnalysis::calculate_risk_level(findings.score),
+            warnings: findings.warnings,
+            passed_checks: findings.passed_checks,
+            failed_checks: findings.failed_checks,
+            recommendations: Vec::new(),
+            token_age_hours,
+            total_supply,
+            circulating_supply: total_supply,
+            liquidity_usd: 0.0,
+            volume_24h: 0.0,
+            transaction_count_24h: 0,
+            unique_wallets_24h: 0,
+            metadata: TokenMetadata {
+                description: None,
+                website: None,
+                twitter: None,
+                telegram: None,
+                discord: None,
+                logo_uri: None,
+                is_verified: false,
+            },
+            analysis_timestamp: Utc::now(),
+            data_sources: vec!["On-chain".to_string()],
+            provider_status: Vec::new(),
+        };
+
+        info!("On-chain check complete for {}: Score {}/100", token_address, analysis.risk_score);
+        Ok(analysis)
+    }
+
+    fn check_authorities(mint: &MintInfo, findings: &mut Findings) {
+        if mint.mint_authority.is_some() {
+            findings.fail(25, WarningSeverity::High, WarningCategory::Ownership,
+                "Mint authority enabled".to_string(), "The authority can mint new supply at any time");
+        } else {
+            findings.pass("Mint authority revoked".to_string());
+        }
+
+        if mint.freeze_authority.is_some() {
+            findings.fail(20, WarningSeverity::High, WarningCategory::Ownership,
+                "Freeze authority enabled".to_string(), "The authority can freeze any holder's tokens");
+        } else {
+            findings.pass("Freeze authority revoked".to_string());
+        }
+    }
+
+    /// Owners of the ten largest token accounts with their share of the supply
+    async fn top_holders(&self, mint: &Pubkey, mint_info: &MintInfo) -> Result<Vec<HolderInfo>> {
+        let largest = self.rpc_client.get_token_largest_accounts(mint).await?;
+        let largest: Vec<_> = largest.into_iter().take(10).collect();
+        let addresses: Vec<Pubkey> = largest.iter()
+            .filter_map(|balance| Pubkey::from_str(&balance.address).ok())
+            .collect();
+        let owners = self.token_account_owners(&addresses).await?;
+
+        Ok(largest.iter().zip(owners).map(|(balance, owner)| {
+            let amount = balance.amount.amount.parse::<u64>().unwrap_or(0);
+            let owner = owner.map(|key| key.to_string());
+            HolderInfo {
+                address: owner.clone().unwrap_or_else(|| balance.address.clone()),
+                balance: balance.amount.ui_amount.unwrap_or(0.0),
+                percentage: if mint_info.supply > 0 { amount as f64 / mint_info.supply as f64 * 100.0 } else { 0.0 },
+                is_locked: owner.as_deref() == Some(INCINERATOR),
+                is_creator: false,
+                is_exchange: owner.as_deref() == Some(RAYDIUM_AUTHORITY),
+            }
+        }).collect())
+    }
+
+    /// Wallets owning the token accounts, in order; None for accounts that are gone
+    async fn token_account_owners(&self, accounts: &[Pubkey]) -> Result<Vec<Option<Pubkey>>> {
+        if accounts.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(self.rpc_client.get_multiple_accounts(accounts).await?
+            .into_iter()
+            .map(|account| account.and_then(|a| a.data.get(32..64).and_then(|bytes| Pubkey::try_from(bytes).ok())))
+            .collect())
+    }
+
+    /// Pool vaults and burned tokens don't count towards concentration
+    fn check_concentration(holders: &[HolderInfo], findings: &mut Findings) {
+        let concentration: f64 = holders.iter()
+            .filter(|h| !h.is_exchange && !h.is_locked)
+            .map(|h| h.percentage)
+            .sum();
+
+        if concentration > 80.0 {
+            findings.fail(30, WarningSeverity::Critical, WarningCategory::Distribution,
+                format!("Top 10 holders own {:.1}% of supply", concentration),
+                "A handful of wallets can dump on everyone else");
+        } else if concentration > 50.0 {
+            findings.warn(15, WarningSeverity::High, WarningCategory::Distribution,
+                format!("Top 10 holders own {:.1}% of supply", concentration),
+                "High concentration increases manipulation risk");
+        } else {
+            findings.pass(format!("Top 10 holders own {:.1}% of supply", concentration));
+        }
+    }
+
+    /// Whether the LP of the main pool is burned or locked
+    async fn check_liquidity(&self, mint: &Pubkey, findings: &mut Findings) -> Result<bool> {
+        if let Some(pool) = self.raydium_pool(mint).await? {
+            let secured = self.secured_lp_percent(&pool).await?;
+            debug!("Raydium pool {} has {:.1}% of its LP burned or locked", pool.address, secured);
+            return Ok(if secured >= 95.0 {
+                findings.pass(format!("{:.0}% of Raydium LP burned or locked", secured));
+                true
+            } else if secured >= 50.0 {
+                findings.warn(10, WarningSeverity::Medium, WarningCategory::Liquidity,
+                    format!("Only {:.0}% of Raydium LP burned or locked", secured),
+                    "The unlocked share of the liquidity can be withdrawn");
+                false
+            } else {
+                findings.fail(25, WarningSeverity::High, WarningCategory::Liquidity,
+                    format!("Raydium LP not burned or locked ({:.0}% secured)", secured),
+                    "The pool's liquidity can be pulled at any time");
+                false
+            });
+        }
+
+        if self.has_whirlpool(mint).await? {
+            findings.warn(5, WarningSeverity::Low, WarningCategory::Liquidity,
+                "Only an Orca Whirlpool pool found".to_string(),
+                "Whirlpool positions have no LP token to burn or lock; their owners can withdraw");
+            return Ok(false);
+        }
+
+        findings.warn(15, WarningSeverity::High, WarningCategory::Liquidity,
+            "No Raydium or Orca pool found".to_string(),
+            "The token may not be tradable, or trades on another venue");
+        Ok(false)
+    }
+
+    /// The token's Raydium AMM v4 pool with the most LP outstanding
+    async fn raydium_pool(&self, mint: &Pubkey) -> Result<Option<RaydiumPool>> {
+        let program = Pubkey::from_str(RAYDIUM_AMM_V4_PROGRAM)?;
+        let mut pools: Vec<RaydiumPool> = Vec::new();
+        for offset in [RAYDIUM_BASE_MINT, RAYDIUM_QUOTE_MINT] {
+            for (address, account) in self.program_accounts(&program, RAYDIUM_POOL_LEN, offset, mint).await? {
+                let data = &account.data;
+                let (Some(lp_mint), Some(lp_reserve)) = (
+                    data.get(RAYDIUM_LP_MINT..RAYDIUM_LP_MINT + 32).and_then(|b| Pubkey::try_from(b).ok()),
+                    read_u64(data, RAYDIUM_LP_RESERVE),
+                ) else {
+                    continue;
+                };
+                if !pools.iter().any(|p| p.address == address) {
+                    pools.push(RaydiumPool { address, lp_mint, lp_reserve });
+                }
+            }
+        }
+        Ok(pools.into_iter().max_by_key(|p| p.lp_reserve))
+    }
+
+    async fn has_whirlpool(&self, mint: &Pubkey) -> Result<bool> {
+        let program = Pubkey::from_str(ORCA_WHIRLPOOL_PROGRAM)?;
+        for offset in [WHIRLPOOL_MINT_A, WHIRLPOOL_MINT_B] {
+            if !self.program_accounts(&program, WHIRLPOOL_LEN, offset, mint).await?.is_empty() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Accounts of `program` of size `len` holding `mint` at `offset`
+    async fn program_accounts(
+        &self,
+        program: &Pubkey,
+        len: usize,
+        offset: usize,
+        mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Account)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(len as u64),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, &mint.to_bytes())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        Ok(self.rpc_client.get_program_accounts_with_config(program, config).await?)
+    }
+
+    /// Share of the pool's LP that is burned, sent to the incinerator or held in a Streamflow escrow
+    async fn secured_lp_percent(&self, pool: &RaydiumPool) -> Result<f64> {
+        let supply = self.rpc_client.get_token_supply(&pool.lp_mint).await?
+            .amount.parse::<u64>().unwrap_or(0);
+        // Burning LP shrinks the mint's supply but not the pool's reserve
+        let issued = pool.lp_reserve.max(supply);
+        if issued == 0 {
+            return Ok(0.0);
+        }
+
+        let largest = self.rpc_client.get_token_largest_accounts(&pool.lp_mint).await?;
+        let accounts: Vec<Pubkey> = largest.iter().filter_map(|b| Pubkey::from_str(&b.address).ok()).collect();
+        let owners = self.token_account_owners(&accounts).await?;
+        let escrow_owners: Vec<Pubkey> = owners.iter().flatten().copied().collect();
+        let escrow_programs: Vec<Option<Pubkey>> = if escrow_owners.is_empty() {
+            Vec::new()
+        } else {
+            self.rpc_client.get_multiple_accounts(&escrow_owners).await?
+                .into_iter()
+                .map(|account| account.map(|a| a.owner))
+                .collect()
+        };
+
+        let streamflow = Pubkey::from_str(STREAMFLOW_PROGRAM)?;
+        let incinerator = Pubkey::from_str(INCINERATOR)?;
+        let mut programs = escrow_programs.into_iter();
+        let mut secured = issued - supply.min(issued);
+        for (balance, owner) in largest.iter().zip(owners) {
+            let Some(owner) = owner else { continue };
+            let program = programs.next().flatten();
+            if owner == incinerator || program == Some(streamflow) {
+                secured += balance.amount.amount.parse::<u64>().unwrap_or(0);
+            }
+        }
+        Ok((secured as f64 / issued as f64 * 100.0).min(100.0))
+    }
+
+    /// When the mint first appeared, or None when its history is too long to walk back
+    async fn created_at(&self, mint: &Pubkey) -> Result<Option<DateTime<Utc>>> {
+        let mut before: Option<Signature> = None;
+        for _ in 0..MAX_SIGNATURE_PAGES {
+            let page = self.rpc_client.get_signatures_for_address_with_config(mint, GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(SIGNATURES_PER_PAGE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            }).await?;
+            let Some(oldest) = page.last() else { return Ok(None) };
+            if page.len() < SIGNATURES_PER_PAGE {
+                return Ok(oldest.block_time.and_then(|t| DateTime::from_timestamp(t, 0)));
+            }
+            before = Some(Signature::from_str(&oldest.signature)?);
+        }
+        Ok(None)
+    }
+
+    /// Age in hours, or 0 when unknown
+    fn check_age(created_at: Option<DateTime<Utc>>, findings: &mut Findings) -> f64 {
+        let Some(created_at) = created_at else {
+            findings.warn(0, WarningSeverity::Low, WarningCategory::Age,
+                "Token age unknown".to_string(),
+                "Its history is too long to reach the first transaction");
+            return 0.0;
+        };
+
+        let age_hours = (Utc::now() - created_at).num_minutes().max(0) as f64 / 60.0;
+        if age_hours < 24.0 {
+            findings.fail(15, WarningSeverity::High, WarningCategory::Age,
+                format!("Created {:.0} hours ago", age_hours),
+                "Most rug pulls happen in a token's first day");
+        } else if age_hours < 168.0 {
+            findings.warn(5, WarningSeverity::Medium, WarningCategory::Age,
+                format!("Created {:.0} days ago", age_hours / 24.0),
+                "New tokens carry higher risk");
+        } else {
+            findings.pass(format!("Created {:.0} days ago", age_hours / 24.0));
+        }
+        age_hours
+    }
+}
+
+/// A `COption<Pubkey>`: a 4-byte tag, then the key
+fn coption_pubkey(bytes: &[u8]) -> Option<Pubkey> {
+    if bytes.get(..4)? != [1, 0, 0, 0] {
+        return None;
+    }
+    Pubkey::try_from(bytes.get(4..36)?).ok()
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
@@ -207,6 +207,7 @@ impl RugCheckProvider {
             },
             analysis_timestamp: chrono::Utc::now(),
             data_sources: vec!["RugCheck".to_string()],
+            provider_status: Vec::new(),
         }
     }
 }
\ No newline at end of file
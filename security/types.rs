@@ -34,6 +34,20 @@ pub struct SecurityAnalysis {
     pub metadata: TokenMetadata,
     pub analysis_timestamp: DateTime<Utc>,
     pub data_sources: Vec<String>,
+    /// How each provider fared when several were combined
+    #[serde(default)]
+    pub provider_status: Vec<ProviderStatus>,
+}
+
+/// One provider's part in a combined analysis
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderStatus {
+    pub provider: String,
+    /// Share of the combined score, before renormalising over the providers that answered
+    pub weight: f64,
+    /// The provider's own score, when it answered
+    pub score: Option<u8>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,200 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    routing::post,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{net::TcpListener, sync::RwLock};
+use tracing::debug;
+
+use crate::errors::{BotError, Result};
+
+const BOT_USER_ID: i64 = 7_000_000_000;
+
+/// An outgoing Bot API call captured by the fake transport
+#[derive(Debug, Clone)]
+pub struct SentMessage {
+    pub method: String,
+    pub chat_id: Option<i64>,
+    pub text: Option<String>,
+    pub params: Value,
+}
+
+struct TelegramState {
+    next_update_id: i64,
+    next_message_id: i64,
+    pending_updates: Vec<Value>,
+    sent: Vec<SentMessage>,
+}
+
+/// Fake Telegram Bot API: captures outgoing calls and serves injected updates via `getUpdates`
+#[derive(Clone)]
+pub struct FakeTelegram {
+    addr: SocketAddr,
+    state: Arc<RwLock<TelegramState>>,
+}
+
+impl FakeTelegram {
+    pub async fn start() -> Result<Self> {
+        let state = Arc::new(RwLock::new(TelegramState {
+            next_update_id: 1,
+            next_message_id: 1,
+            pending_updates: Vec::new(),
+            sent: Vec::new(),
+        }));
+
+        let app = Router::new()
+            .route("/:token/:method", post(handle_method))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await
+            .map_err(|e| BotError::internal(format!("Failed to bind fake Telegram: {}", e)))?;
+        let addr = listener.local_addr()
+            .map_err(|e| BotError::internal(format!("Fake Telegram has no local address: {}", e)))?;
+
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        debug!("🧪 Fake Telegram listening on {}", addr);
+
+        Ok(Self { addr, state })
+    }
+
+    /// API URL for `Config::telegram_api_url` / `Bot::set_api_url`
+    pub fn api_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Queue a private text message from `user_id`; bot commands get a command entity
+    pub async fn inject_message(&self, user_id: i64, text: &str) {
+        let mut state = self.state.write().await;
+        let update_id = state.next_update_id;
+        let message_id = state.next_message_id;
+        state.next_update_id += 1;
+        state.next_message_id += 1;
+
+        let entities = if text.starts_with('/') {
+            let command_len = text.split_whitespace().next().map(|c| c.encode_utf16().count()).unwrap_or(0);
+            json!([{ "type": "bot_command", "offset": 0, "length": command_len }])
+        } else {
+            json!([])
+        };
+
+        state.pending_updates.push(json!({
+            "update_id": update_id,
+            "message": {
+                "message_id": message_id,
+                "date": chrono::Utc::now().timestamp(),
+                "chat": { "id": user_id, "type": "private", "first_name": "Tester" },
+                "from": { "id": user_id, "is_bot": false, "first_name": "Tester", "username": format!("tester{}", user_id) },
+                "text": text,
+                "entities": entities,
+            }
+        }));
+    }
+
+    /// Queue an arbitrary raw update; `update_id` is assigned by the fake
+    pub async fn inject_update(&self, mut update: Value) {
+        let mut state = self.state.write().await;
+        update["update_id"] = json!(state.next_update_id);
+        state.next_update_id += 1;
+        state.pending_updates.push(update);
+    }
+
+    pub async fn sent(&self) -> Vec<SentMessage> {
+        self.state.read().await.sent.clone()
+    }
+
+    /// Text of every message sent to `chat_id`, oldest first
+    pub async fn texts_for(&self, chat_id: i64) -> Vec<String> {
+        self.state.read().await.sent.iter()
+            .filter(|m| m.chat_id == Some(chat_id))
+            .filter_map(|m| m.text.clone())
+            .collect()
+    }
+
+    /// Wait until a message to `chat_id` contains `needle`
+    pub async fn wait_for_text(&self, chat_id: i64, needle: &str, timeout: Duration) -> Option<String> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(text) = self.texts_for(chat_id).await.into_iter().find(|t| t.contains(needle)) {
+                return Some(text);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+    }
+}
+
+fn message_json(state: &mut TelegramState, chat_id: i64, text: &str) -> Value {
+    let message_id = state.next_message_id;
+    state.next_message_id += 1;
+
+    json!({
+        "message_id": message_id,
+        "date": chrono::Utc::now().timestamp(),
+        "chat": { "id": chat_id, "type": "private", "first_name": "Tester" },
+        "from": { "id": BOT_USER_ID, "is_bot": true, "first_name": "MockBot", "username": "mock_bot" },
+        "text": text,
+    })
+}
+
+async fn handle_method(
+    State(state): State<Arc<RwLock<TelegramState>>>,
+    Path((_token, method)): Path<(String, String)>,
+    body: Bytes,
+) -> Json<Value> {
+    let params: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+    let result = match method.as_str() {
+        "getMe" => json!({
+            "id": BOT_USER_ID,
+            "is_bot": true,
+            "first_name": "MockBot",
+            "username": "mock_bot",
+            "can_join_groups": true,
+            "can_read_all_group_messages": false,
+            "supports_inline_queries": false,
+            "can_connect_to_business": false,
+            "has_main_web_app": false,
+        }),
+        "getUpdates" => {
+            let offset = params["offset"].as_i64().unwrap_or(0);
+            let updates = {
+                let mut state = state.write().await;
+                state.pending_updates.retain(|u| u["update_id"].as_i64().unwrap_or(0) >= offset);
+                state.pending_updates.clone()
+            };
+            if updates.is_empty() {
+                // Short poll so the dispatcher doesn't spin
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            json!(updates)
+        }
+        "sendMessage" | "editMessageText" => {
+            let chat_id = params["chat_id"].as_i64();
+            let text = params["text"].as_str().unwrap_or_default().to_string();
+            let mut state = state.write().await;
+            let message = message_json(&mut state, chat_id.unwrap_or_default(), &text);
+            state.sent.push(SentMessage { method: method.clone(), chat_id, text: Some(text), params });
+            message
+        }
+        _ => {
+            let mut state = state.write().await;
+            state.sent.push(SentMessage {
+                method: method.clone(),
+                chat_id: params["chat_id"].as_i64(),
+                text: None,
+                params,
+            });
+            json!(true)
+        }
+    };
+
+    Json(json!({ "ok": true, "result": result }))
+}
@@ -0,0 +1,448 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{Keypair, Signature, Signer},
+};
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    ai::{GroqAnalyzer, SignalOutcomeTracker},
+    alerts::{
+        AlertDelivery, BondingConfig, BondingTracker, CalendarConfig, MarketEventMonitor, PriceAlertManager, TokenCalendar,
+        WhaleWatchConfig, WhaleWatcher,
+    },
+    analytics::{CostBasisBook, FeeLedger, JournalConfig, PerformanceTracker, TradeHistoryExporter, TradeImporter, TradeJournal},
+    api::{ApiTier, JupiterAuthManager, JupiterLendingClient, JupiterPriceV3Client, JupiterSendClient, JupiterTokenV2Client, JupiterV6Client},
+    blinks::BlinkTracker,
+    bot::{
+        admin::AdminDesk, aliases::AliasStore, automation_auth::AutomationAuthority, chart_actions::ChartActions,
+        data_deletion::{DataDeletionManager, DeletionConfig},
+        distribution::Distributions,
+        group_buy::GroupBuyCoordinator, preferences::PreferenceStore, price_entry::PriceEntries, token_launch::LaunchWizard, trending::TrendingCache, wallet_transfer::WalletTransfers,
+        watchlist::{JupiterWatchlistMarket, WatchlistStore}, BotServices,
+        TelegramBot,
+    },
+    cache::{InMemorySessionStore, SessionStore},
+    db::Database,
+    errors::{BotError, Result},
+    middleware::UserRateLimiter,
+    monitoring::MetricsCollector,
+    security::LarpChecker,
+    trading::{ConfirmationConfig, ConfirmationTracker, CopyTradingManager, DCAEngine, DCAScheduler, EngineSeller, ExecutionNotifier, JitoConfig, LeaderboardManager, LendingDesk, LiquidityEstimator, LiveTokenInspector, MasterProgram, MevProtection, OrderManager, PanicDesk, PriorityFeeConfig, PriorityFeeEstimator, RiskBasedDCAManager, SandwichConfig, SandwichMonitor, SmartSellTimer, SmartTimingConfig, TokenListConfig, TokenResolver, TradeGate, TradingEngine, TradingEngineHandle, PositionSync},
+    utils::{Config, NetworkType, SessionBackend},
+    wallet::{ActivityWatchConfig, AtaCleanupConfig, AtaJanitor, WalletActivityWatcher, WalletManager},
+    websocket::{PriceStreamManager, WebSocketClient, WebSocketConfig},
+};
+use super::{FakeTelegram, JupiterScenario, MockJupiter, MockRpc, MockTrending};
+
+/// Execution mode for the harness bot, surfaced through `Config::enable_paper_trading`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExecutionMode {
+    Paper,
+    Real,
+}
+
+/// Builder for a fully wired bot instance running against the mocks
+pub struct TestHarnessBuilder {
+    execution_mode: ExecutionMode,
+    scenario: JupiterScenario,
+    confirmation_delay: Duration,
+    prices: Vec<(String, f64)>,
+    admin_users: Vec<String>,
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+impl Default for TestHarnessBuilder {
+    fn default() -> Self {
+        Self {
+            execution_mode: ExecutionMode::Real,
+            scenario: JupiterScenario::Normal,
+            confirmation_delay: Duration::ZERO,
+            prices: Vec::new(),
+            admin_users: Vec::new(),
+            metrics: None,
+        }
+    }
+}
+
+impl TestHarnessBuilder {
+    pub fn execution_mode(mut self, mode: ExecutionMode) -> Self {
+        self.execution_mode = mode;
+        self
+    }
+
+    pub fn scenario(mut self, scenario: JupiterScenario) -> Self {
+        self.scenario = scenario;
+        self
+    }
+
+    pub fn confirmation_delay(mut self, delay: Duration) -> Self {
+        self.confirmation_delay = delay;
+        self
+    }
+
+    pub fn price(mut self, mint: &str, usd_price: f64) -> Self {
+        self.prices.push((mint.to_string(), usd_price));
+        self
+    }
+
+    pub fn admin(mut self, user_id: i64) -> Self {
+        self.admin_users.push(user_id.to_string());
+        self
+    }
+
+    /// Instrument the engine, order and DCA loops, Groq and command handling
+    pub fn metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub async fn build(self) -> Result<TestHarness> {
+        let jupiter = MockJupiter::start().await?;
+        let rpc = MockRpc::start().await?;
+        let telegram = FakeTelegram::start().await?;
+        let trending = MockTrending::default();
+
+        jupiter.set_scenario(self.scenario).await;
+        for (mint, price) in &self.prices {
+            jupiter.set_price(mint, *price).await;
+        }
+        rpc.set_confirmation_delay(self.confirmation_delay).await;
+
+        let config = Arc::new(Config {
+            telegram_bot_token: "123456:TESTKIT".to_string(),
+            helius_api_key: "testkit".to_string(),
+            groq_api_key: "testkit".to_string(),
+            database_url: "sqlite::memory:".to_string(),
+            rebate_wallet_address: String::new(),
+            network: NetworkType::Devnet,
+            rpc_url_override: Some(rpc.url()),
+            jupiter_api_url: jupiter.quote_api_url(),
+            jupiter_price_api_url: jupiter.price_api_url(),
+            telegram_api_url: Some(telegram.api_url()),
+            max_trade_size_sol: 10.0,
+            min_trade_size_sol: 0.001,
+            slippage_bps: 100,
+            priority_fee_lamports: 10_000,
+            enable_backrun_rebates: false,
+            allowed_users: Vec::new(),
+            admin_users: self.admin_users,
+            enable_ai_analysis: false,
+            enable_paper_trading: self.execution_mode == ExecutionMode::Paper,
+            paper_slippage_bps: 50,
+            paper_starting_balance_sol: 10.0,
+            dust_threshold_usd: 1.0,
+            position_sync_interval_secs: 900,
+            send_expiry_hours: 72,
+            master_payout_min_sol: 0.1,
+            copy_fee_treasury_key: None,
+            session_backend: SessionBackend::Memory,
+            redis_url: None,
+            command_tokens_per_minute: 30,
+            command_burst: 20,
+            trade_commands_per_minute: 5,
+            trade_burst: 3,
+            metrics_port: None,
+            blinks_base_url: None,
+            blinks_port: None,
+            convex_webhook_port: None,
+            convex_webhook_secret: None,
+        });
+
+        let db = Arc::new(Database::new(&config.database_url).await?);
+        let price_client = Arc::new(
+            JupiterPriceV3Client::new(Arc::new(JupiterAuthManager::new()))
+                .with_base_url(jupiter.base_url()),
+        );
+        let preferences = Arc::new(PreferenceStore::default().with_storage(db.clone()));
+        let mev_protection = Arc::new(MevProtection::new(JitoConfig::default()).with_preferences(preferences.clone()));
+        let activity_watch = Arc::new(WalletActivityWatcher::new(
+            Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+            ActivityWatchConfig::default(),
+        ));
+        let wallet_manager = Arc::new(WalletManager::new(db.clone()).with_activity_watch(activity_watch.clone()));
+        let trade_gate = Arc::new(
+            TradeGate::new(preferences.clone(), Arc::new(LiveTokenInspector::new(Arc::new(LarpChecker::new(None)))))
+                .with_owners(wallet_manager.clone()),
+        );
+        let trading_engine = TradingEngine::spawn_with_mev(
+            config.clone(),
+            db.clone(),
+            price_client.clone(),
+            None,
+            Some(mev_protection.clone()),
+            trade_gate.clone(),
+        ).await?;
+        let trading_engine = match &self.metrics {
+            Some(metrics) => trading_engine.with_metrics(metrics.clone()),
+            None => trading_engine,
+        };
+        let ai_analyzer = GroqAnalyzer::new(config.groq_api_key.clone());
+        let ai_analyzer = Arc::new(match &self.metrics {
+            Some(metrics) => ai_analyzer.with_metrics(metrics.clone()),
+            None => ai_analyzer,
+        });
+        let journal = Arc::new(TradeJournal::new(JournalConfig::default()));
+        let execution_notices = Arc::new(ExecutionNotifier::default());
+        let cost_basis = Arc::new(CostBasisBook::default().with_preferences(preferences.clone()));
+        let priority_fees = Arc::new(PriorityFeeEstimator::new(
+            Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+            PriorityFeeConfig::default(),
+        ));
+        // Shared with the executors so maintenance holds automated trades too
+        let admin = Arc::new(AdminDesk::default().with_storage(db.clone()));
+        let order_manager = OrderManager::new(
+            Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(jupiter.base_url())),
+            price_client.clone(),
+            db.clone(),
+            None,
+        )
+        .with_journal(journal.clone())
+        .with_notifier(execution_notices.clone())
+        .with_exit_preferences(preferences.clone())
+        .with_fee_estimator(priority_fees.clone())
+        .with_pause(admin.clone());
+        let order_manager = Arc::new(match &self.metrics {
+            Some(metrics) => order_manager.with_metrics(metrics.clone()),
+            None => order_manager,
+        });
+        let confirmations = Arc::new(
+            ConfirmationTracker::new(
+                Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+                ConfirmationConfig::default(),
+            )
+            .with_orders(order_manager.clone()),
+        );
+        let leaderboard = Arc::new(LeaderboardManager::new(db.clone()).with_visibility(preferences.clone()));
+        let master_program = Arc::new(MasterProgram::from_config(
+            db.clone(),
+            Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+            &config,
+        ));
+        let copy_trading = Arc::new(CopyTradingManager::new(
+            db.clone(),
+            trading_engine.clone(),
+            wallet_manager.clone(),
+        )
+        .with_notifier(execution_notices.clone())
+        .with_leaderboard(leaderboard.clone())
+        .with_master_program(master_program.clone())
+        .with_trade_gate(trade_gate.clone())
+        .with_pause(admin.clone()));
+
+        let price_stream = Arc::new(PriceStreamManager::new(Arc::new(
+            WebSocketClient::new(WebSocketConfig::default(), None),
+        )));
+        // Price alerts and market events share the Telegram forwarder
+        let alert_delivery = Arc::new(AlertDelivery::default());
+        let market_events = Arc::new(MarketEventMonitor::new(db.clone(), price_stream.clone(), alert_delivery.clone(), None));
+        let dca_engine = Arc::new(DCAEngine::new(
+            Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(jupiter.base_url())),
+            price_client.clone(),
+            db.clone(),
+            None,
+        )
+        .with_notifier(execution_notices.clone())
+        .with_fee_estimator(priority_fees.clone())
+        .with_trade_defaults(preferences.clone())
+        .with_risk_manager(Arc::new(RiskBasedDCAManager::new(price_client.clone(), None)))
+        .with_trade_gate(trade_gate.clone())
+        .with_pause(admin.clone()));
+        let dca_scheduler = DCAScheduler::new(dca_engine.clone(), None);
+        let dca_scheduler = Arc::new(match &self.metrics {
+            Some(metrics) => dca_scheduler.with_metrics(metrics.clone()),
+            None => dca_scheduler,
+        });
+        let sessions: Arc<dyn SessionStore> = Arc::new(InMemorySessionStore::default());
+        let services = Arc::new(BotServices {
+            token_calendar: Arc::new(TokenCalendar::new(CalendarConfig::default(), None)),
+            bonding: Arc::new(BondingTracker::new(BondingConfig::default(), None, None)),
+            pump_fun: None,
+            price_alerts: Arc::new(
+                PriceAlertManager::new(db.clone(), price_stream.clone(), alert_delivery, None)
+                    .with_price_client(price_client.clone()),
+            ),
+            // Prices only: risk badges would call the live token API
+            watchlists: Arc::new(
+                WatchlistStore::new(Arc::new(JupiterWatchlistMarket::new(price_client.clone())))
+                    .with_storage(db.clone()),
+            ),
+            whales: Arc::new(WhaleWatcher::new(
+                Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+                price_client.clone(),
+                market_events,
+                WhaleWatchConfig::default(),
+            )),
+            order_manager: order_manager.clone(),
+            price_client: price_client.clone(),
+            chart_actions: Arc::new(ChartActions::default()),
+            journal: journal.clone(),
+            sandwich_monitor: Arc::new(SandwichMonitor::new(
+                Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+                SandwichConfig::default(),
+            )),
+            confirmations,
+            mev_protection,
+            priority_fees,
+            liquidity: Arc::new(
+                LiquidityEstimator::new(price_stream)
+                    .with_quoter(Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(jupiter.base_url()))),
+            ),
+            // Never refreshed here, so lookups fall back to the built-in tokens
+            token_resolver: Arc::new(TokenResolver::new(
+                Arc::new(JupiterTokenV2Client::new(Arc::new(JupiterAuthManager::new()))),
+                TokenListConfig::default(),
+            )),
+            smart_sell: Arc::new(SmartSellTimer::new(
+                SmartTimingConfig::default(),
+                Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(jupiter.base_url())),
+            )),
+            dca_scheduler,
+            dca_engine,
+            copy_trading: copy_trading.clone(),
+            master_program,
+            execution_notices,
+            group_buys: Arc::new(GroupBuyCoordinator::default()),
+            aliases: Arc::new(AliasStore::default()),
+            ata_janitor: Arc::new(AtaJanitor::new(
+                Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+                AtaCleanupConfig::default(),
+            )),
+            fee_ledger: Arc::new(FeeLedger::new()),
+            leaderboard,
+            preferences,
+            data_deletion: Arc::new(DataDeletionManager::new(DeletionConfig::default())),
+            trade_imports: Arc::new(TradeImporter::new(cost_basis.clone()).with_journal(journal.clone())),
+            cost_basis,
+            trade_history: Arc::new(TradeHistoryExporter::new(db.clone())),
+            performance: Arc::new(
+                PerformanceTracker::new(db.clone(), None)
+                    .with_journal(journal)
+                    .with_price_client(price_client.clone()),
+            ),
+            position_sync: Arc::new(PositionSync::from_config(trading_engine.clone(), &config)),
+            lending: Arc::new(LendingDesk::new(
+                Arc::new(JupiterLendingClient::new(Arc::new(JupiterAuthManager::new()))),
+                trading_engine.clone(),
+                Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+                db.clone(),
+            )),
+            panic_sells: Arc::new(PanicDesk::new(
+                Arc::new(EngineSeller::new(
+                    trading_engine.clone(),
+                    Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(jupiter.base_url())),
+                    Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+                )),
+                config.dust_threshold_usd,
+            )),
+            distributions: Arc::new(Distributions::from_config(
+                Arc::new(JupiterSendClient::new(Arc::new(JupiterAuthManager::new()))),
+                Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed())),
+                db.clone(),
+                &config,
+            )),
+            automation_auth: Arc::new(AutomationAuthority::default()),
+            price_entries: Arc::new(PriceEntries::default()),
+            trending: Arc::new(TrendingCache::new(Arc::new(trending.clone()))),
+            signal_outcomes: Arc::new(SignalOutcomeTracker::new(price_client.clone()).with_database(db.clone())),
+            wallet_transfers: Arc::new(WalletTransfers::default().with_sessions(sessions.clone())),
+            launches: Arc::new(LaunchWizard::default().with_sessions(sessions.clone())),
+            sessions,
+            admin,
+            command_limiter: Arc::new(UserRateLimiter::for_commands(&config)),
+            convex_migration: None,
+            metrics: self.metrics,
+            blinks: None,
+            blink_tracker: Arc::new(
+                BlinkTracker::new()
+                    .with_database(db.clone())
+                    .with_rpc(Arc::new(RpcClient::new_with_commitment(rpc.url(), CommitmentConfig::confirmed()))),
+            ),
+        });
+
+        Ok(TestHarness {
+            config,
+            jupiter,
+            rpc,
+            telegram,
+            trending,
+            db,
+            trading_engine,
+            wallet_manager,
+            activity_watch,
+            ai_analyzer,
+            services,
+            price_client,
+            order_manager,
+            copy_trading,
+        })
+    }
+}
+
+/// A bot wired to mock Jupiter, RPC and Telegram backends and an in-memory database
+pub struct TestHarness {
+    pub config: Arc<Config>,
+    pub jupiter: MockJupiter,
+    pub rpc: MockRpc,
+    pub telegram: FakeTelegram,
+    pub trending: MockTrending,
+    pub db: Arc<Database>,
+    pub trading_engine: TradingEngineHandle,
+    pub wallet_manager: Arc<WalletManager>,
+    pub activity_watch: Arc<WalletActivityWatcher>,
+    pub ai_analyzer: Arc<GroqAnalyzer>,
+    pub services: Arc<BotServices>,
+    pub price_client: Arc<JupiterPriceV3Client>,
+    pub order_manager: Arc<OrderManager>,
+    pub copy_trading: Arc<CopyTradingManager>,
+}
+
+impl TestHarness {
+    pub fn builder() -> TestHarnessBuilder {
+        TestHarnessBuilder::default()
+    }
+
+    /// Register a fresh wallet for `user_id` with the given SOL balance on the mock RPC
+    pub async fn register_user(&self, user_id: i64, balance_sol: f64) -> Result<Keypair> {
+        let keypair = Keypair::new();
+        let address = keypair.pubkey().to_string();
+
+        self.wallet_manager.register_wallet(&user_id.to_string(), &address, Some("testkit".to_string())).await?;
+        self.rpc.set_balance(&address, (balance_sol * 1e9) as u64).await;
+
+        Ok(keypair)
+    }
+
+    /// Run the Telegram dispatcher against the fake transport in the background
+    pub fn start_bot(&self) {
+        let bot = TelegramBot::new(
+            self.config.clone(),
+            self.trading_engine.clone(),
+            self.ai_analyzer.clone(),
+            self.db.clone(),
+            self.wallet_manager.clone(),
+            self.services.clone(),
+        );
+
+        tokio::spawn(async move {
+            let _ = bot.run().await;
+        });
+    }
+
+    /// RPC client pointed at the mock cluster
+    pub fn rpc_client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.rpc.url(), CommitmentConfig::confirmed())
+    }
+
+    /// Sign the last transaction built by the swap endpoint and submit it until confirmed
+    pub async fn sign_and_confirm_last_swap(&self, signer: &Keypair) -> Result<Signature> {
+        let mut transaction = self.jupiter.last_swap_transaction().await
+            .ok_or_else(|| BotError::not_found("No swap transaction has been built".to_string()))?;
+
+        let rpc_client = self.rpc_client();
+        let blockhash = rpc_client.get_latest_blockhash().await?;
+        transaction.sign(&[signer], blockhash);
+
+        Ok(rpc_client.send_and_confirm_transaction(&transaction).await?)
+    }
+}
@@ -0,0 +1,319 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, transaction::Transaction};
+use std::{collections::{HashMap, HashSet}, net::SocketAddr, str::FromStr, sync::Arc, time::Duration};
+use tokio::{net::TcpListener, sync::RwLock};
+use tracing::debug;
+
+use crate::errors::{BotError, Result};
+
+/// Scripted behaviour for the mock Jupiter server
+#[derive(Debug, Clone, PartialEq)]
+pub enum JupiterScenario {
+    /// Quotes priced from the configured token prices
+    Normal,
+    /// Quote endpoints answer "no route found"
+    NoRoute,
+    /// Quotes come back with the given price impact
+    HighImpact { impact_pct: f64 },
+    /// Every endpoint answers 429
+    RateLimited,
+    /// Responses are delayed before being served normally
+    Slow { delay: Duration },
+}
+
+/// A request observed by the mock Jupiter server
+#[derive(Debug, Clone)]
+pub struct RecordedJupiterCall {
+    pub endpoint: String,
+    pub params: Value,
+}
+
+struct JupiterState {
+    scenario: JupiterScenario,
+    prices: HashMap<String, f64>,
+    unlisted: HashSet<String>,
+    calls: Vec<RecordedJupiterCall>,
+    swap_transactions: Vec<Transaction>,
+}
+
+/// In-process Jupiter quote/swap/price server bound to an ephemeral port
+#[derive(Clone)]
+pub struct MockJupiter {
+    addr: SocketAddr,
+    state: Arc<RwLock<JupiterState>>,
+}
+
+impl MockJupiter {
+    /// Start the server on 127.0.0.1 with the normal scenario
+    pub async fn start() -> Result<Self> {
+        let state = Arc::new(RwLock::new(JupiterState {
+            scenario: JupiterScenario::Normal,
+            prices: HashMap::new(),
+            unlisted: HashSet::new(),
+            calls: Vec::new(),
+            swap_transactions: Vec::new(),
+        }));
+
+        // Quote API (JupiterSwap) and v6/price v3 (JupiterV6Client, JupiterPriceV3Client)
+        // use different response shapes, so each gets its own path prefix
+        let app = Router::new()
+            .route("/quote-api/v6/quote", get(legacy_quote))
+            .route("/quote-api/v6/swap", post(swap))
+            .route("/price-api/v6/price", get(legacy_price))
+            .route("/v6/quote", get(v6_quote))
+            .route("/v6/swap", post(swap))
+            .route("/price/v3", get(price_v3))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await
+            .map_err(|e| BotError::internal(format!("Failed to bind mock Jupiter: {}", e)))?;
+        let addr = listener.local_addr()
+            .map_err(|e| BotError::internal(format!("Mock Jupiter has no local address: {}", e)))?;
+
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        debug!("🧪 Mock Jupiter listening on {}", addr);
+
+        Ok(Self { addr, state })
+    }
+
+    /// Base URL for `JupiterV6Client` / `JupiterPriceV3Client`
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Quote API URL for `JupiterSwap` (`Config::jupiter_api_url`)
+    pub fn quote_api_url(&self) -> String {
+        format!("http://{}/quote-api/v6", self.addr)
+    }
+
+    /// Price API URL for `JupiterSwap` (`Config::jupiter_price_api_url`)
+    pub fn price_api_url(&self) -> String {
+        format!("http://{}/price-api/v6", self.addr)
+    }
+
+    pub async fn set_scenario(&self, scenario: JupiterScenario) {
+        self.state.write().await.scenario = scenario;
+    }
+
+    /// Set the USD price used for quotes and price lookups (unknown mints price at 1.0)
+    pub async fn set_price(&self, mint: &str, usd_price: f64) {
+        self.state.write().await.prices.insert(mint.to_string(), usd_price);
+    }
+
+    /// Leave `mint` out of price v3 responses, as Jupiter does for tokens it can't price
+    pub async fn set_unlisted(&self, mint: &str, unlisted: bool) {
+        let mut state = self.state.write().await;
+        if unlisted {
+            state.unlisted.insert(mint.to_string());
+        } else {
+            state.unlisted.remove(mint);
+        }
+    }
+
+    pub async fn calls(&self) -> Vec<RecordedJupiterCall> {
+        self.state.read().await.calls.clone()
+    }
+
+    pub async fn call_count(&self, endpoint: &str) -> usize {
+        self.state.read().await.calls.iter().filter(|c| c.endpoint == endpoint).count()
+    }
+
+    /// Most recent unsigned transaction handed out by a swap endpoint
+    pub async fn last_swap_transaction(&self) -> Option<Transaction> {
+        self.state.read().await.swap_transactions.last().cloned()
+    }
+}
+
+/// Apply the active scenario; returns an early response when the request should not be served
+async fn gate(state: &Arc<RwLock<JupiterState>>, endpoint: &str, params: Value) -> Option<Response> {
+    let scenario = {
+        let mut state = state.write().await;
+        state.calls.push(RecordedJupiterCall { endpoint: endpoint.to_string(), params });
+        state.scenario.clone()
+    };
+
+    match scenario {
+        JupiterScenario::RateLimited => Some((
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({ "error": "Too many requests" })),
+        ).into_response()),
+        JupiterScenario::NoRoute if endpoint.ends_with("quote") => Some((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "Could not find any route", "errorCode": "COULD_NOT_FIND_ANY_ROUTE" })),
+        ).into_response()),
+        JupiterScenario::Slow { delay } => {
+            tokio::time::sleep(delay).await;
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Price the quote from the configured prices: out = in * price(in) / price(out)
+async fn priced_quote(state: &Arc<RwLock<JupiterState>>, params: &HashMap<String, String>) -> (String, String, u64, u64, f64) {
+    let state = state.read().await;
+    let input_mint = params.get("inputMint").cloned().unwrap_or_default();
+    let output_mint = params.get("outputMint").cloned().unwrap_or_default();
+    let in_amount: u64 = params.get("amount").and_then(|a| a.parse().ok()).unwrap_or(0);
+
+    let impact_pct = match state.scenario {
+        JupiterScenario::HighImpact { impact_pct } => impact_pct,
+        _ => 0.0,
+    };
+    let input_price = state.prices.get(&input_mint).copied().unwrap_or(1.0);
+    let output_price = state.prices.get(&output_mint).copied().unwrap_or(1.0);
+    let out_amount = (in_amount as f64 * input_price / output_price * (1.0 - impact_pct / 100.0)) as u64;
+
+    (input_mint, output_mint, in_amount, out_amount, impact_pct)
+}
+
+fn route_plan(input_mint: &str, output_mint: &str, in_amount: u64, out_amount: u64) -> Value {
+    json!([{
+        "swapInfo": {
+            "ammKey": "MockAmm1111111111111111111111111111111111111",
+            "label": "MockDex",
+            "inputMint": input_mint,
+            "outputMint": output_mint,
+            "inAmount": in_amount.to_string(),
+            "outAmount": out_amount.to_string(),
+            "feeAmount": "0",
+            "feeMint": input_mint,
+        },
+        "percent": 100,
+    }])
+}
+
+async fn legacy_quote(
+    State(state): State<Arc<RwLock<JupiterState>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    if let Some(response) = gate(&state, "legacy_quote", json!(params)).await {
+        return response;
+    }
+
+    let (input_mint, output_mint, in_amount, out_amount, impact_pct) = priced_quote(&state, &params).await;
+    let slippage_bps: u16 = params.get("slippageBps").and_then(|s| s.parse().ok()).unwrap_or(50);
+
+    Json(json!({
+        "inputMint": input_mint,
+        "outputMint": output_mint,
+        "inAmount": in_amount.to_string(),
+        "outAmount": out_amount.to_string(),
+        "otherAmountThreshold": out_amount.to_string(),
+        "swapMode": "ExactIn",
+        "slippageBps": slippage_bps,
+        "priceImpactPct": impact_pct,
+        "routePlan": route_plan(&input_mint, &output_mint, in_amount, out_amount),
+        "contextSlot": 1,
+        "timeTaken": 0.001,
+    })).into_response()
+}
+
+async fn v6_quote(
+    State(state): State<Arc<RwLock<JupiterState>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    if let Some(response) = gate(&state, "v6_quote", json!(params)).await {
+        return response;
+    }
+
+    let (input_mint, output_mint, in_amount, out_amount, impact_pct) = priced_quote(&state, &params).await;
+    let slippage_bps: u16 = params.get("slippageBps").and_then(|s| s.parse().ok()).unwrap_or(50);
+
+    Json(json!({
+        "inputMint": input_mint,
+        "inAmount": in_amount.to_string(),
+        "outputMint": output_mint,
+        "outAmount": out_amount.to_string(),
+        "otherAmountThreshold": out_amount.to_string(),
+        "swapMode": "ExactIn",
+        "slippageBps": slippage_bps,
+        "platformFee": null,
+        "priceImpactPct": impact_pct.to_string(),
+        "routePlan": route_plan(&input_mint, &output_mint, in_amount, out_amount),
+        "contextSlot": 1,
+        "timeTaken": 0.001,
+    })).into_response()
+}
+
+async fn swap(
+    State(state): State<Arc<RwLock<JupiterState>>>,
+    Json(body): Json<Value>,
+) -> Response {
+    if let Some(response) = gate(&state, "swap", body.clone()).await {
+        return response;
+    }
+
+    let Some(payer) = body["userPublicKey"].as_str().and_then(|k| Pubkey::from_str(k).ok()) else {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "Invalid userPublicKey" }))).into_response();
+    };
+
+    // An empty transaction paid by the user is enough for signing and submission
+    let transaction = Transaction::new_with_payer(&[], Some(&payer));
+    let encoded = match bincode::serialize(&transaction) {
+        Ok(bytes) => base64::encode(bytes),
+        Err(e) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() }))).into_response();
+        }
+    };
+    state.write().await.swap_transactions.push(transaction);
+
+    Json(json!({
+        "swapTransaction": encoded,
+        "lastValidBlockHeight": 1_000,
+        "prioritizationFeeLamports": body["prioritizationFeeLamports"].as_u64().unwrap_or(0),
+        "computeUnitLimit": 200_000,
+    })).into_response()
+}
+
+async fn legacy_price(
+    State(state): State<Arc<RwLock<JupiterState>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    if let Some(response) = gate(&state, "legacy_price", json!(params)).await {
+        return response;
+    }
+
+    let state = state.read().await;
+    let data: serde_json::Map<String, Value> = params.get("ids").map(|ids| ids.as_str()).unwrap_or_default()
+        .split(',')
+        .filter(|id| !id.is_empty() && !state.unlisted.contains(*id))
+        .map(|id| {
+            let price = state.prices.get(id).copied().unwrap_or(1.0);
+            (id.to_string(), json!({ "id": id, "price": price }))
+        })
+        .collect();
+
+    Json(json!({ "data": data })).into_response()
+}
+
+async fn price_v3(
+    State(state): State<Arc<RwLock<JupiterState>>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    if let Some(response) = gate(&state, "price_v3", json!(params)).await {
+        return response;
+    }
+
+    let state = state.read().await;
+    let prices: serde_json::Map<String, Value> = params.get("ids").map(|ids| ids.as_str()).unwrap_or_default()
+        .split(',')
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            let price = state.prices.get(id).copied().unwrap_or(1.0);
+            (id.to_string(), json!({ "usdPrice": price, "blockId": 1, "decimals": 9, "priceChange24h": 0.0 }))
+        })
+        .collect();
+
+    Json(Value::Object(prices)).into_response()
+}
@@ -0,0 +1,306 @@
+use axum::{extract::State, routing::post, Json, Router};
+use serde_json::{json, Value};
+use solana_sdk::{hash::Hash, signature::Signature, transaction::VersionedTransaction};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::{Duration, Instant}};
+use tokio::{net::TcpListener, sync::RwLock};
+use tracing::debug;
+
+use crate::errors::{BotError, Result};
+
+const MOCK_SLOT: u64 = 250_000_000;
+
+/// A transaction accepted by `sendTransaction`
+#[derive(Debug, Clone)]
+pub struct SubmittedTransaction {
+    pub signature: Signature,
+    pub submitted_at: Instant,
+}
+
+struct RpcState {
+    balances: HashMap<String, u64>,
+    confirmation_delay: Duration,
+    /// How long a landed signature stays "confirmed" before it's "finalized"
+    finalization_delay: Duration,
+    simulation_error: Option<String>,
+    failed_signatures: HashMap<String, String>,
+    submitted: Vec<SubmittedTransaction>,
+    methods: Vec<String>,
+    /// Encoded accounts served by getAccountInfo and getMultipleAccounts
+    accounts: HashMap<String, Value>,
+    /// Canned results by (method, first parameter); "" matches any parameter
+    responses: HashMap<(String, String), Value>,
+    /// Signatures by address, newest first, paged by getSignaturesForAddress
+    address_signatures: HashMap<String, Vec<String>>,
+    /// getHealth reports the node this far behind the cluster
+    slots_behind: Option<u64>,
+}
+
+/// In-process Solana JSON-RPC server implementing the methods the bot relies on
+#[derive(Clone)]
+pub struct MockRpc {
+    addr: SocketAddr,
+    state: Arc<RwLock<RpcState>>,
+}
+
+impl MockRpc {
+    /// Start the server on 127.0.0.1; transactions confirm immediately by default
+    pub async fn start() -> Result<Self> {
+        let state = Arc::new(RwLock::new(RpcState {
+            balances: HashMap::new(),
+            confirmation_delay: Duration::ZERO,
+            finalization_delay: Duration::ZERO,
+            simulation_error: None,
+            failed_signatures: HashMap::new(),
+            submitted: Vec::new(),
+            methods: Vec::new(),
+            accounts: HashMap::new(),
+            responses: HashMap::new(),
+            address_signatures: HashMap::new(),
+            slots_behind: None,
+        }));
+
+        let app = Router::new()
+            .route("/", post(handle_rpc))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await
+            .map_err(|e| BotError::internal(format!("Failed to bind mock RPC: {}", e)))?;
+        let addr = listener.local_addr()
+            .map_err(|e| BotError::internal(format!("Mock RPC has no local address: {}", e)))?;
+
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        debug!("🧪 Mock RPC listening on {}", addr);
+
+        Ok(Self { addr, state })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub async fn set_balance(&self, pubkey: &str, lamports: u64) {
+        self.state.write().await.balances.insert(pubkey.to_string(), lamports);
+    }
+
+    /// Serve an account owned by `owner` with raw `data`
+    pub async fn set_account(&self, pubkey: &str, owner: &str, data: &[u8]) {
+        let account = json!({
+            "data": [base64::encode(data), "base64"],
+            "executable": false,
+            "lamports": 2_039_280u64,
+            "owner": owner,
+            "rentEpoch": 0,
+            "space": data.len(),
+        });
+        self.state.write().await.accounts.insert(pubkey.to_string(), account);
+    }
+
+    /// Answer `method` with `result` when its first parameter is `key`, or always when None
+    pub async fn set_response(&self, method: &str, key: Option<&str>, result: Value) {
+        self.state.write().await.responses
+            .insert((method.to_string(), key.unwrap_or_default().to_string()), result);
+    }
+
+    /// Land `signatures` on `address`, oldest first, as getSignaturesForAddress reports them
+    pub async fn push_signatures(&self, address: &str, signatures: &[String]) {
+        let mut state = self.state.write().await;
+        let history = state.address_signatures.entry(address.to_string()).or_default();
+        for signature in signatures {
+            history.insert(0, signature.clone());
+        }
+    }
+
+    /// Make getHealth answer "behind by N slots" like a lagging node, or "ok" again with None
+    pub async fn set_slots_behind(&self, slots: Option<u64>) {
+        self.state.write().await.slots_behind = slots;
+    }
+
+    /// Signatures report as unconfirmed until this long after submission
+    pub async fn set_confirmation_delay(&self, delay: Duration) {
+        self.state.write().await.confirmation_delay = delay;
+    }
+
+    /// Landed signatures report "confirmed" for this long before "finalized"
+    pub async fn set_finalization_delay(&self, delay: Duration) {
+        self.state.write().await.finalization_delay = delay;
+    }
+
+    /// Make every `simulateTransaction` fail with the given error
+    pub async fn set_simulation_error(&self, error: Option<String>) {
+        self.state.write().await.simulation_error = error;
+    }
+
+    /// Report the given signature as landed with an error
+    pub async fn fail_signature(&self, signature: &Signature, error: &str) {
+        self.state.write().await.failed_signatures.insert(signature.to_string(), error.to_string());
+    }
+
+    pub async fn submitted(&self) -> Vec<SubmittedTransaction> {
+        self.state.read().await.submitted.clone()
+    }
+
+    /// Every JSON-RPC method called so far, in order
+    pub async fn methods_called(&self) -> Vec<String> {
+        self.state.read().await.methods.clone()
+    }
+
+    pub async fn is_confirmed(&self, signature: &Signature) -> bool {
+        let state = self.state.read().await;
+        state.submitted.iter().any(|tx| {
+            tx.signature == *signature && tx.submitted_at.elapsed() >= state.confirmation_delay
+        })
+    }
+}
+
+fn with_context(value: Value) -> Value {
+    json!({ "context": { "slot": MOCK_SLOT }, "value": value })
+}
+
+/// One page newest first, between `before` and `until` and at most `limit` long, like the real node
+fn signature_page(history: &[String], config: &Value) -> Value {
+    let limit = config["limit"].as_u64().unwrap_or(1000) as usize;
+    let start = config["before"].as_str()
+        .and_then(|before| history.iter().position(|s| s == before).map(|i| i + 1))
+        .unwrap_or(0);
+    let page: Vec<Value> = history[start.min(history.len())..].iter()
+        .take_while(|s| config["until"].as_str() != Some(s.as_str()))
+        .take(limit)
+        .map(|signature| json!({
+            "signature": signature,
+            "slot": MOCK_SLOT,
+            "err": null,
+            "memo": null,
+            "blockTime": null,
+            "confirmationStatus": "confirmed",
+        }))
+        .collect();
+    json!(page)
+}
+
+fn decode_transaction(params: &Value) -> Option<VersionedTransaction> {
+    let payload = params[0].as_str()?;
+    let bytes = match params[1]["encoding"].as_str() {
+        Some("base64") => base64::decode(payload).ok()?,
+        _ => bs58::decode(payload).into_vec().ok()?,
+    };
+    bincode::deserialize(&bytes).ok()
+}
+
+async fn handle_rpc(State(state): State<Arc<RwLock<RpcState>>>, Json(request): Json<Value>) -> Json<Value> {
+    let id = request["id"].clone();
+    let method = request["method"].as_str().unwrap_or_default().to_string();
+    let params = request["params"].clone();
+
+    state.write().await.methods.push(method.clone());
+
+    // The only error that carries data, so it's answered before the rest
+    if let ("getHealth", Some(behind)) = (method.as_str(), state.read().await.slots_behind) {
+        return Json(json!({ "jsonrpc": "2.0", "id": id, "error": {
+            "code": -32005,
+            "message": format!("Node is behind by {} slots", behind),
+            "data": { "numSlotsBehind": behind },
+        } }));
+    }
+
+    let history = match method.as_str() {
+        "getSignaturesForAddress" => state.read().await.address_signatures.get(params[0].as_str().unwrap_or_default()).cloned(),
+        _ => None,
+    };
+
+    let result = match method.as_str() {
+        "getSignaturesForAddress" if history.is_some() => Ok(signature_page(history.as_deref().unwrap_or_default(), &params[1])),
+        "getVersion" => Ok(json!({ "solana-core": "1.18.26", "feature-set": 3_469_865_029u32 })),
+        "getHealth" => Ok(json!("ok")),
+        "getSlot" => Ok(json!(MOCK_SLOT)),
+        "getLatestBlockhash" => Ok(with_context(json!({
+            "blockhash": Hash::new_unique().to_string(),
+            "lastValidBlockHeight": MOCK_SLOT + 150,
+        }))),
+        "isBlockhashValid" => Ok(with_context(json!(true))),
+        "getBalance" => {
+            let pubkey = params[0].as_str().unwrap_or_default();
+            let lamports = state.read().await.balances.get(pubkey).copied().unwrap_or(0);
+            Ok(with_context(json!(lamports)))
+        }
+        "simulateTransaction" => {
+            let error = state.read().await.simulation_error.clone();
+            Ok(with_context(json!({
+                "err": error.map(|e| json!({ "InstructionError": [0, { "Custom": 1 }], "message": e })),
+                "logs": ["Program log: mock simulation"],
+                "accounts": null,
+                "unitsConsumed": 42_000,
+                "returnData": null,
+            })))
+        }
+        "sendTransaction" => match decode_transaction(&params) {
+            Some(tx) if !tx.signatures.is_empty() => {
+                let signature = tx.signatures[0];
+                state.write().await.submitted.push(SubmittedTransaction {
+                    signature,
+                    submitted_at: Instant::now(),
+                });
+                Ok(json!(signature.to_string()))
+            }
+            _ => Err((-32602, "invalid transaction: failed to deserialize".to_string())),
+        },
+        "getSignatureStatuses" => {
+            let state = state.read().await;
+            let statuses: Vec<Value> = params[0].as_array().cloned().unwrap_or_default()
+                .iter()
+                .map(|sig| {
+                    let sig = sig.as_str().unwrap_or_default();
+                    let landed = state.submitted.iter().find(|tx| tx.signature.to_string() == sig);
+                    match landed {
+                        Some(tx) if tx.submitted_at.elapsed() >= state.confirmation_delay => {
+                            let err = state.failed_signatures.get(sig)
+                                .map(|_| json!({ "InstructionError": [0, { "Custom": 1 }] }));
+                            let status = match &err {
+                                Some(e) => json!({ "Err": e }),
+                                None => json!({ "Ok": null }),
+                            };
+                            let finalized = tx.submitted_at.elapsed() >= state.confirmation_delay + state.finalization_delay;
+                            json!({
+                                "slot": MOCK_SLOT,
+                                "confirmations": if finalized { Value::Null } else { json!(1) },
+                                "err": err,
+                                "status": status,
+                                "confirmationStatus": if finalized { "finalized" } else { "confirmed" },
+                            })
+                        }
+                        _ => Value::Null,
+                    }
+                })
+                .collect();
+            Ok(with_context(json!(statuses)))
+        }
+        "getAccountInfo" => {
+            let state = state.read().await;
+            let pubkey = params[0].as_str().unwrap_or_default();
+            Ok(with_context(state.accounts.get(pubkey).cloned().unwrap_or(Value::Null)))
+        }
+        "getMultipleAccounts" => {
+            let state = state.read().await;
+            let accounts: Vec<Value> = params[0].as_array().cloned().unwrap_or_default()
+                .iter()
+                .map(|pubkey| state.accounts.get(pubkey.as_str().unwrap_or_default()).cloned().unwrap_or(Value::Null))
+                .collect();
+            Ok(with_context(json!(accounts)))
+        }
+        other => {
+            let state = state.read().await;
+            let key = params[0].as_str().unwrap_or_default().to_string();
+            state.responses.get(&(other.to_string(), key))
+                .or_else(|| state.responses.get(&(other.to_string(), String::new())))
+                .cloned()
+                .ok_or_else(|| (-32601, format!("Method not found: {}", other)))
+        }
+    };
+
+    Json(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+    })
+}
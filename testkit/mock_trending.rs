@@ -0,0 +1,115 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    bot::trending::{NewLaunch, RiskAlert, TrendingSource, TrendingSourceKind, TrendingToken},
+    errors::{BotError, Result},
+};
+
+struct TrendingState {
+    tokens: Vec<TrendingToken>,
+    launches: Vec<NewLaunch>,
+    alerts: Vec<RiskAlert>,
+    failing: HashSet<&'static str>,
+    delay: Duration,
+}
+
+/// Scripted market data for the trending cache; counts every upstream call
+#[derive(Clone)]
+pub struct MockTrending {
+    state: Arc<RwLock<TrendingState>>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl Default for MockTrending {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(RwLock::new(TrendingState {
+                tokens: vec![Self::token("BONK", 45.0), Self::token("WIF", 12.5)],
+                launches: vec![NewLaunch {
+                    name: Some("Fresh Cat".to_string()),
+                    address: "FreshCat1111111111111111111111111111111111".to_string(),
+                    age_secs: Some(7_200),
+                    liquidity_usd: Some(45_000.0),
+                    holder_count: 120,
+                }],
+                alerts: Vec::new(),
+                failing: HashSet::new(),
+                delay: Duration::ZERO,
+            })),
+            calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl MockTrending {
+    pub fn token(symbol: &str, change_24h: f64) -> TrendingToken {
+        TrendingToken {
+            name: format!("{} Token", symbol),
+            symbol: symbol.to_string(),
+            address: format!("{}Mint1111111111111111111111111111111", symbol),
+            price: 0.0001,
+            price_change_24h: change_24h,
+            volume_24h: 1_000_000.0,
+            market_cap: 50_000_000.0,
+        }
+    }
+
+    pub async fn set_tokens(&self, tokens: Vec<TrendingToken>) {
+        self.state.write().await.tokens = tokens;
+    }
+
+    /// Make one source fail (or recover) on its next fetch
+    pub async fn set_failing(&self, kind: TrendingSourceKind, failing: bool) {
+        let mut state = self.state.write().await;
+        if failing {
+            state.failing.insert(kind.label());
+        } else {
+            state.failing.remove(kind.label());
+        }
+    }
+
+    /// Latency added to every fetch
+    pub async fn set_delay(&self, delay: Duration) {
+        self.state.write().await.delay = delay;
+    }
+
+    /// Upstream fetches served so far, across all sources
+    pub fn call_count(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    async fn serve<T: Clone>(&self, kind: TrendingSourceKind, pick: impl Fn(&TrendingState) -> Vec<T>) -> Result<Vec<T>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let delay = self.state.read().await.delay;
+        tokio::time::sleep(delay).await;
+
+        let state = self.state.read().await;
+        if state.failing.contains(kind.label()) {
+            return Err(BotError::external_api(format!("{} upstream unavailable", kind.label())).into());
+        }
+        Ok(pick(&state))
+    }
+}
+
+#[async_trait::async_trait]
+impl TrendingSource for MockTrending {
+    async fn trending(&self) -> Result<Vec<TrendingToken>> {
+        self.serve(TrendingSourceKind::Market, |state| state.tokens.clone()).await
+    }
+
+    async fn new_launches(&self) -> Result<Vec<NewLaunch>> {
+        self.serve(TrendingSourceKind::Launches, |state| state.launches.clone()).await
+    }
+
+    async fn risk_alerts(&self) -> Result<Vec<RiskAlert>> {
+        self.serve(TrendingSourceKind::RiskAlerts, |state| state.alerts.clone()).await
+    }
+}
@@ -0,0 +1,17 @@
+//! Hermetic integration-test harness, enabled with the `testkit` feature.
+//!
+//! Spins up in-process mocks for Jupiter (quote/swap/price), Solana JSON-RPC and the
+//! Telegram Bot API, and wires a full bot instance against them so cross-module flows
+//! can be exercised without keys or mainnet access.
+
+mod mock_jupiter;
+mod mock_rpc;
+mod fake_telegram;
+mod mock_trending;
+mod harness;
+
+pub use mock_jupiter::{MockJupiter, JupiterScenario, RecordedJupiterCall};
+pub use mock_rpc::{MockRpc, SubmittedTransaction};
+pub use fake_telegram::{FakeTelegram, SentMessage};
+pub use mock_trending::MockTrending;
+pub use harness::{TestHarness, TestHarnessBuilder, ExecutionMode};
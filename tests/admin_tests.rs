@@ -0,0 +1,467 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::bot::admin::{AdminDesk, AdminStorage, AnnouncementSender, Delivery, BROADCAST_CHECKPOINT_EVERY};
+use crate::bot::automation_auth::{AutomationAction, GrantScope};
+use crate::bot::callback_action::CallbackAction;
+use crate::bot::convex_webhook::{EngineTrades, EventStatus, TradeSide, WebhookDispatcher, WebhookEnvelope, WebhookEvent};
+use crate::bot::preferences::{NotificationKind, PreferenceStore, SettingChange, UserSettings};
+use crate::bot::Command;
+use crate::errors::Result;
+use crate::testkit::TestHarness;
+use crate::trading::{CopyTradeStatus, CopyTradeType, DCAStrategy, Order, TokenResolver, PAUSED_FOR_MAINTENANCE};
+use crate::utils::t;
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const ADMIN: i64 = 765_001;
+const USER_ID: i64 = 765_002;
+const BANNER: &str = "paused for maintenance";
+
+/// Admin state and users kept in memory, shared between "restarts"
+#[derive(Default)]
+struct MemoryAdminStorage {
+    users: Vec<i64>,
+    volume_24h_sol: f64,
+    state: Mutex<HashMap<String, String>>,
+}
+
+#[async_trait::async_trait]
+impl AdminStorage for MemoryAdminStorage {
+    async fn user_ids(&self) -> Result<Vec<i64>> {
+        Ok(self.users.clone())
+    }
+
+    async fn trade_volume_since(&self, _since: DateTime<Utc>) -> Result<f64> {
+        Ok(self.volume_24h_sol)
+    }
+
+    async fn load_state(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.state.lock().await.get(key).cloned())
+    }
+
+    async fn save_state(&self, key: &str, data: &str) -> Result<()> {
+        self.state.lock().await.insert(key.to_string(), data.to_string());
+        Ok(())
+    }
+
+    async fn clear_state(&self, key: &str) -> Result<()> {
+        self.state.lock().await.remove(key);
+        Ok(())
+    }
+}
+
+/// Records every announcement; some users have blocked the bot, some get one flood wait first
+#[derive(Default)]
+struct MockSender {
+    sent: Mutex<Vec<(i64, String, Instant)>>,
+    blocked: HashSet<i64>,
+    flood_waits: Mutex<HashSet<i64>>,
+}
+
+#[async_trait::async_trait]
+impl AnnouncementSender for MockSender {
+    async fn send(&self, user_id: i64, text: &str) -> Delivery {
+        if self.blocked.contains(&user_id) {
+            return Delivery::Failed("Forbidden: bot was blocked by the user".to_string());
+        }
+        if self.flood_waits.lock().await.remove(&user_id) {
+            return Delivery::RetryAfter(Duration::from_millis(50));
+        }
+        self.sent.lock().await.push((user_id, text.to_string(), Instant::now()));
+        Delivery::Sent
+    }
+}
+
+impl MockSender {
+    async fn recipients(&self) -> Vec<i64> {
+        self.sent.lock().await.iter().map(|(user_id, _, _)| *user_id).collect()
+    }
+}
+
+fn storage(users: usize) -> Arc<MemoryAdminStorage> {
+    Arc::new(MemoryAdminStorage { users: (1..=users as i64).collect(), ..MemoryAdminStorage::default() })
+}
+
+#[tokio::test]
+async fn test_maintenance_gate_on_buy_sell_and_snipe_through_telegram() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .admin(ADMIN)
+        .price(SOL_MINT, 200.0)
+        .price(&bonk, 0.00002)
+        .build()
+        .await
+        .unwrap();
+    harness.register_user(USER_ID, 5.0).await.unwrap();
+    harness.start_bot();
+
+    // Only admins can switch it on
+    harness.telegram.inject_message(USER_ID, "/admin maintenance on").await;
+    harness.telegram.wait_for_text(USER_ID, "Admin only", Duration::from_secs(5)).await.expect("non-admin refused");
+    assert!(harness.services.admin.maintenance().await.is_none());
+
+    harness.telegram.inject_message(ADMIN, "/admin maintenance on RPC upgrade, back in 10 minutes").await;
+    harness.telegram.wait_for_text(ADMIN, "Maintenance mode on", Duration::from_secs(5)).await.expect("maintenance on");
+
+    for command in ["/buy BONK 0.5", "/sell BONK 1000", "/snipe BONK 0.1"] {
+        let before = harness.telegram.texts_for(USER_ID).await.len();
+        harness.telegram.inject_message(USER_ID, command).await;
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        let reply = loop {
+            if let Some(reply) = harness.telegram.texts_for(USER_ID).await.into_iter().nth(before) {
+                break reply;
+            }
+            assert!(tokio::time::Instant::now() < deadline, "no reply to {}", command);
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        };
+        assert!(reply.contains(BANNER), "{}: {}", command, reply);
+        assert!(reply.contains("RPC upgrade"), "{}: {}", command, reply);
+    }
+    // Nothing was quoted or built
+    assert_eq!(harness.jupiter.call_count("legacy_quote").await, 0);
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+
+    // Read-only commands still work
+    harness.telegram.inject_message(USER_ID, "/help").await;
+    harness.telegram.wait_for_text(USER_ID, "Help", Duration::from_secs(5)).await.expect("help still answered");
+
+    harness.telegram.inject_message(ADMIN, "/admin maintenance off").await;
+    harness.telegram.wait_for_text(ADMIN, "Maintenance mode off", Duration::from_secs(5)).await.expect("maintenance off");
+    harness.telegram.inject_message(USER_ID, "/buy BONK 0.5").await;
+    harness.telegram
+        .wait_for_text(USER_ID, "Buy Order Executed", Duration::from_secs(10))
+        .await
+        .expect("trading reopened");
+    let banners = harness.telegram.texts_for(USER_ID).await.iter().filter(|text| text.contains(BANNER)).count();
+    assert_eq!(banners, 3);
+}
+
+#[tokio::test]
+async fn test_maintenance_blocks_only_transaction_submitting_commands() {
+    let desk = AdminDesk::default();
+    let buy = Command::Buy("BONK 0.5".to_string());
+    assert!(desk.blocking(&buy).await.is_none());
+
+    desk.start_maintenance("1", None).await.unwrap();
+    let blocked = [
+        Command::Buy("BONK 0.5".to_string()),
+        Command::Sell("BONK 1000".to_string()),
+        Command::Snipe("BONK 0.1".to_string()),
+        Command::QuickBuy("BONK".to_string()),
+        Command::QuickSell("BONK".to_string()),
+        Command::Pump(String::new()),
+        Command::Launch,
+        Command::Lend("deposit 10 USDC".to_string()),
+        Command::Send("USDC".to_string()),
+        Command::GroupBuy("BONK 5".to_string()),
+        Command::Order("limit BONK 0.5 @ 0.00001".to_string()),
+        Command::Bracket("BONK".to_string()),
+        Command::StopLoss("BONK 10".to_string()),
+        Command::Dca("BONK 10 daily".to_string()),
+        Command::Panic(String::new()),
+        Command::Cleanup(String::new()),
+    ];
+    for cmd in &blocked {
+        assert!(desk.blocking(cmd).await.is_some(), "{:?}", cmd);
+    }
+    let allowed = [
+        Command::Balance,
+        Command::Portfolio,
+        Command::Help,
+        Command::Orders,
+        Command::Copy(String::new()),
+        Command::Alert("BONK > 0.0001".to_string()),
+        Command::Admin("maintenance off".to_string()),
+    ];
+    for cmd in &allowed {
+        assert!(desk.blocking(cmd).await.is_none(), "{:?}", cmd);
+    }
+
+    // Quick-buy buttons too, but not the token picker
+    assert!(desk.blocking_action(&CallbackAction::quick_buy("BONK", 0.1)).await.is_some());
+    assert!(desk.blocking_action(&CallbackAction::RetryBuy { token: "BONK".to_string(), amount_sol: 0.1 }).await.is_some());
+    assert!(desk.blocking_action(&CallbackAction::ChooseQuickBuyToken { amount_sol: 0.1 }).await.is_none());
+
+    // Confirmation buttons that submit, but not their cancels or previews
+    for data in ["lend:go", "send:go", "panic:arm", "atac:go", "launch:confirm", "confirm_swap:SOL:BONK:0.1", "qb:0.1:BONK"] {
+        assert!(desk.blocking_callback(data).await.is_some(), "{}", data);
+    }
+    for data in ["lend:cancel", "send:cancel", "panic:start", "panic:cancel", "launch:back", "qbt:0.1", "main_menu"] {
+        assert!(desk.blocking_callback(data).await.is_none(), "{}", data);
+    }
+
+    // The banner is in the user's language, with the operator's note under it
+    let maintenance = desk.start_maintenance("1", Some("Back at 14:00 UTC".to_string())).await.unwrap();
+    let banner = AdminDesk::banner(&maintenance, "es");
+    assert!(banner.starts_with(&t("es", "maintenance.banner")), "{}", banner);
+    assert!(banner.ends_with("Back at 14:00 UTC"), "{}", banner);
+    assert_eq!(AdminDesk::banner(&desk.start_maintenance("1", Some("  ".to_string())).await.unwrap(), "en"), t("en", "maintenance.banner"));
+
+    assert!(desk.end_maintenance().await.unwrap().is_some());
+    assert!(desk.blocking(&buy).await.is_none());
+    assert!(desk.end_maintenance().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_maintenance_holds_dca_and_copied_trades() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(SOL_MINT, 200.0).price(&bonk, 0.00002).build().await.unwrap();
+    let copy_trading = &harness.copy_trading;
+    copy_trading.start_following(USER_ID, "AlphaTrader", 50.0, 5.0).await.unwrap();
+    let strategy = DCAStrategy::create_daily_dca(
+        USER_ID,
+        "BONK daily".to_string(),
+        USDC.to_string(),
+        bonk.clone(),
+        Decimal::from(1_000),
+        Decimal::from(100),
+    );
+    harness.services.admin.start_maintenance("1", None).await.unwrap();
+
+    let held = harness.services.dca_engine.execute_strategy(&strategy).await.unwrap_err();
+    assert!(held.to_string().contains(PAUSED_FOR_MAINTENANCE), "{}", held);
+
+    let executions = copy_trading
+        .execute_copy_trade(1001, &bonk, "BONK", CopyTradeType::Buy, 4.0, 0.00002)
+        .await
+        .unwrap();
+    assert_eq!(executions[0].status, CopyTradeStatus::Cancelled);
+    assert_eq!(executions[0].error_message.as_deref(), Some(PAUSED_FOR_MAINTENANCE));
+    // The master's buy is still tracked for later sells
+    assert!(copy_trading.master_position(1001, &bonk).await > 0.0);
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+
+    harness.services.admin.end_maintenance().await.unwrap();
+    let executions = copy_trading
+        .execute_copy_trade(1001, &bonk, "BONK", CopyTradeType::Buy, 4.0, 0.00002)
+        .await
+        .unwrap();
+    assert_eq!(executions[0].status, CopyTradeStatus::Success);
+}
+
+#[tokio::test]
+async fn test_maintenance_holds_convex_trades_without_spending_their_key() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(SOL_MINT, 200.0).price(&bonk, 0.00002).build().await.unwrap();
+    harness.register_user(USER_ID, 5.0).await.unwrap();
+    let services = &harness.services;
+    let scope = GrantScope { action: AutomationAction::Trade, max_notional_sol: 1.0, expires_at: Utc::now() + chrono::Duration::days(1) };
+    let (_, authorization) = services.automation_auth.issue(USER_ID, scope, Utc::now()).await.unwrap();
+    let dispatcher = WebhookDispatcher::new(services.automation_auth.clone(), services.sessions.clone())
+        .with_trades(Arc::new(EngineTrades::new(harness.trading_engine.clone(), harness.wallet_manager.clone())))
+        .with_pause(services.admin.clone());
+    let envelope = WebhookEnvelope {
+        idempotency_key: "convex-buy-1".to_string(),
+        event: WebhookEvent::ExecuteTrade {
+            telegram_id: USER_ID,
+            token: "BONK".to_string(),
+            side: TradeSide::Buy,
+            amount: 0.5,
+            authorization,
+        },
+    };
+
+    services.admin.start_maintenance("1", None).await.unwrap();
+    let held = dispatcher.dispatch(envelope.clone()).await;
+    assert_eq!(held.status, EventStatus::Unavailable);
+    assert_eq!(held.message.as_deref(), Some(PAUSED_FOR_MAINTENANCE));
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+
+    // The same delivery retried after maintenance trades, not replays the hold
+    services.admin.end_maintenance().await.unwrap();
+    let traded = dispatcher.dispatch(envelope).await;
+    assert_eq!(traded.status, EventStatus::Ok, "{:?}", traded.message);
+    assert!(!traded.duplicate);
+    assert_eq!(harness.jupiter.call_count("swap").await, 1);
+}
+
+#[tokio::test]
+async fn test_maintenance_survives_a_restart() {
+    let storage = storage(0);
+    let desk = AdminDesk::default().with_storage(storage.clone());
+    desk.start_maintenance("1", Some("Migrating".to_string())).await.unwrap();
+
+    let restarted = AdminDesk::default().with_storage(storage.clone());
+    assert!(restarted.restore().await.unwrap().is_none());
+    assert_eq!(restarted.maintenance().await.unwrap().note.as_deref(), Some("Migrating"));
+    assert!(restarted.blocking(&Command::Sell("BONK 1".to_string())).await.is_some());
+
+    restarted.end_maintenance().await.unwrap();
+    let again = AdminDesk::default().with_storage(storage);
+    again.restore().await.unwrap();
+    assert!(again.maintenance().await.is_none());
+}
+
+#[tokio::test]
+async fn test_broadcast_is_paced_and_skips_opted_out_users() {
+    let desk = AdminDesk::default().with_storage(storage(40)).with_rate(20);
+    let preferences = PreferenceStore::default();
+    preferences.apply(3, SettingChange::ToggleNotification(NotificationKind::Announcements)).await.unwrap();
+    preferences.update(5, |settings| settings.set_language(Some("fr"))).await.unwrap();
+    let sender = MockSender {
+        blocked: HashSet::from([7]),
+        flood_waits: Mutex::new(HashSet::from([9])),
+        ..MockSender::default()
+    };
+
+    let broadcast = desk.start_broadcast("1", "  New pairs are live  ").await.unwrap();
+    assert_eq!(broadcast.recipients.len(), 40);
+    assert!(desk.start_broadcast("1", "Another").await.is_err(), "one broadcast at a time");
+
+    let started = Instant::now();
+    let finished = desk.run_broadcast(&sender, &preferences).await.unwrap().unwrap();
+    assert!(finished.is_finished());
+    assert_eq!((finished.sent, finished.opted_out, finished.failed), (38, 1, 1));
+    assert_eq!(finished.cursor, 40);
+
+    let recipients = sender.recipients().await;
+    assert!(!recipients.contains(&3) && !recipients.contains(&7));
+    // User 9 was retried after the flood wait
+    assert!(recipients.contains(&9));
+
+    // 40 sends plus one retry at 20 a second take two seconds
+    assert!(started.elapsed() >= Duration::from_millis(1_900), "{:?}", started.elapsed());
+    // Never a burst: at most 20 in any 900ms, leaving slack for timer jitter
+    let sent = sender.sent.lock().await;
+    for (i, (_, _, at)) in sent.iter().enumerate() {
+        let in_window = sent[i..].iter().take_while(|(_, _, later)| later.duration_since(*at) < Duration::from_millis(900)).count();
+        assert!(in_window <= 20, "{} sends within 900ms", in_window);
+    }
+
+    // Each recipient gets the text with an opt-out footer in their language
+    let to = |user_id: i64| sent.iter().find(|(id, _, _)| *id == user_id).unwrap().1.clone();
+    assert_eq!(to(1), format!("New pairs are live\n\n{}", t("en", "broadcast.footer")));
+    assert!(to(5).ends_with(&t("fr", "broadcast.footer")));
+    drop(sent);
+
+    // Done: nothing left to run, and a new one may start
+    assert!(desk.run_broadcast(&sender, &preferences).await.unwrap().is_none());
+    assert!(desk.start_broadcast("1", "Another").await.is_ok());
+    assert!(desk.start_broadcast("1", "   ").await.is_err());
+}
+
+#[tokio::test]
+async fn test_broadcast_resumes_from_its_last_checkpoint_after_a_restart() {
+    let storage = storage(60);
+    let preferences = PreferenceStore::default();
+    let desk = AdminDesk::default().with_storage(storage.clone()).with_rate(30);
+    desk.start_broadcast("1", "Scheduled downtime tonight").await.unwrap();
+
+    // The process dies partway through the second checkpoint window
+    let first = MockSender::default();
+    tokio::select! {
+        _ = desk.run_broadcast(&first, &preferences) => panic!("the broadcast should still be sending"),
+        _ = async {
+            while first.sent.lock().await.len() < BROADCAST_CHECKPOINT_EVERY + 5 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        } => {}
+    }
+    drop(desk);
+
+    let restarted = AdminDesk::default().with_storage(storage).with_rate(30);
+    let resumed = restarted.restore().await.unwrap().expect("unfinished broadcast");
+    assert_eq!(resumed.cursor, BROADCAST_CHECKPOINT_EVERY);
+    assert_eq!(resumed.sent, BROADCAST_CHECKPOINT_EVERY);
+
+    let second = MockSender::default();
+    let finished = restarted.run_broadcast(&second, &preferences).await.unwrap().unwrap();
+    assert_eq!(finished.cursor, 60);
+    assert_eq!(finished.sent, 60);
+
+    // Picks up at the checkpoint: only the few sent since it are repeated
+    let resent = second.recipients().await;
+    assert_eq!(resent, (BROADCAST_CHECKPOINT_EVERY as i64 + 1..=60).collect::<Vec<_>>());
+    let first_run = first.recipients().await;
+    let repeats = first_run.iter().filter(|user_id| resent.contains(user_id)).count();
+    assert!(repeats <= BROADCAST_CHECKPOINT_EVERY, "{} repeats", repeats);
+}
+
+#[tokio::test]
+async fn test_cancelled_broadcast_stops_and_stays_stopped() {
+    let storage = storage(100);
+    let desk = Arc::new(AdminDesk::default().with_storage(storage.clone()).with_rate(30));
+    let preferences = Arc::new(PreferenceStore::default());
+    let sender = Arc::new(MockSender::default());
+    desk.start_broadcast("1", "Oops, wrong text").await.unwrap();
+
+    let run = tokio::spawn({
+        let (desk, preferences, sender) = (desk.clone(), preferences.clone(), sender.clone());
+        async move { desk.run_broadcast(sender.as_ref(), &preferences).await }
+    });
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let cancelled = desk.cancel_broadcast().await.unwrap().unwrap();
+    assert!(cancelled.cancelled);
+    let sent_at_cancel = sender.recipients().await.len();
+
+    // At most the message in flight still goes out
+    let stopped = run.await.unwrap().unwrap().unwrap();
+    assert!(stopped.cancelled);
+    assert!(sender.recipients().await.len() <= sent_at_cancel + 1);
+    assert_eq!(stopped.sent, sender.recipients().await.len());
+    assert!(stopped.cursor < 100);
+    assert!(stopped.summary().contains("Cancelled"), "{}", stopped.summary());
+
+    // A restart doesn't bring it back
+    let restarted = AdminDesk::default().with_storage(storage);
+    assert!(restarted.restore().await.unwrap().is_none());
+    assert!(restarted.broadcast().await.unwrap().cancelled);
+    assert!(desk.cancel_broadcast().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_stats_count_users_orders_dca_and_volume() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&bonk, 0.00002).build().await.unwrap();
+    let desk = AdminDesk::default().with_storage(Arc::new(MemoryAdminStorage {
+        users: vec![1, 2, 3],
+        volume_24h_sol: 12.5,
+        ..MemoryAdminStorage::default()
+    }));
+
+    let order = Order::create_stop_loss(USER_ID, bonk.clone(), Decimal::new(1, 5), Decimal::from(1_000));
+    harness.order_manager.create_order(order).await.unwrap();
+    let strategy = DCAStrategy::create_daily_dca(
+        USER_ID,
+        "BONK daily".to_string(),
+        USDC.to_string(),
+        bonk,
+        Decimal::from(1_000),
+        Decimal::from(100),
+    );
+    harness.services.dca_engine.restore_strategies(vec![strategy]).await.unwrap();
+    desk.start_maintenance("1", None).await.unwrap();
+
+    let stats = desk.stats(&harness.order_manager, &harness.services.dca_engine).await.unwrap();
+    assert_eq!(stats.users, 3);
+    assert_eq!(stats.active_orders, 1);
+    assert_eq!(stats.active_dca_strategies, 1);
+    assert_eq!(stats.volume_24h_sol, 12.5);
+    assert!(stats.maintenance);
+
+    let text = crate::bot::handlers::AdminHandler::stats_text(&stats, "en");
+    assert!(text.contains("24h volume: 12.50 SOL"), "{}", text);
+}
+
+#[test]
+fn test_announcements_are_on_unless_turned_off() {
+    assert_eq!(
+        SettingChange::parse("setting:notify:announce"),
+        Some(SettingChange::ToggleNotification(NotificationKind::Announcements))
+    );
+
+    // Settings stored before the opt-out existed load with announcements on
+    let mut stored = serde_json::to_value(UserSettings::default()).unwrap();
+    stored["notifications"].as_object_mut().unwrap().remove("announcements");
+    let loaded: UserSettings = serde_json::from_value(stored).unwrap();
+    assert!(loaded.notifications.announcements);
+
+    let mut settings = UserSettings::default();
+    settings.apply(SettingChange::ToggleNotification(NotificationKind::Announcements)).unwrap();
+    assert!(!settings.notifications.announcements);
+}
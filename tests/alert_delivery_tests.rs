@@ -0,0 +1,215 @@
+use crate::alerts::{
+    AlertAction, AlertCondition, AlertDelivery, AlertDeliveryMethod, AlertDispatcher, AlertHistory, AlertKind,
+    AlertPayload, AlertPriority, AlertStatus, AlertTriggerType, DeliveryStatus, PriceAlert, PriceAlertManager,
+    PriceComparison, PriceThreshold, WebhookDispatcher, WebhookRetryPolicy,
+};
+use crate::bot::handlers::PriceAlertHandler;
+use crate::testkit::TestHarness;
+use crate::websocket::{PriceStreamManager, WebSocketClient, WebSocketConfig};
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{net::TcpListener, sync::Mutex};
+
+const USER_ID: i64 = 765_001;
+const SYMBOL: &str = "TKN";
+
+/// Answers each POST with the next status, then 200; records the bodies
+struct FakeWebhook {
+    statuses: Mutex<Vec<StatusCode>>,
+    bodies: Mutex<Vec<Value>>,
+}
+
+async fn receive(State(hook): State<Arc<FakeWebhook>>, Json(body): Json<Value>) -> StatusCode {
+    hook.bodies.lock().await.push(body);
+    let mut statuses = hook.statuses.lock().await;
+    if statuses.is_empty() { StatusCode::OK } else { statuses.remove(0) }
+}
+
+async fn webhook(statuses: Vec<StatusCode>) -> (String, Arc<FakeWebhook>) {
+    let hook = Arc::new(FakeWebhook { statuses: Mutex::new(statuses), bodies: Mutex::new(Vec::new()) });
+    let app = Router::new().route("/hook", post(receive)).with_state(hook.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    (url, hook)
+}
+
+fn fast_retries(max_attempts: u32) -> WebhookRetryPolicy {
+    WebhookRetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(40),
+        timeout: Duration::from_secs(2),
+    }
+}
+
+fn payload() -> AlertPayload {
+    AlertPayload {
+        kind: AlertKind::PriceAlert,
+        alert_id: "alert-765".to_string(),
+        user_id: USER_ID,
+        title: "Price alert: TKN breakout".to_string(),
+        message: "🔔 Alert: TKN breakout".to_string(),
+        symbol: SYMBOL.to_string(),
+        price: Some(Decimal::from(2)),
+        condition: Some("Price above $2".to_string()),
+        triggered_at: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn webhook_retries_server_errors_and_posts_the_payload() {
+    let (url, hook) = webhook(vec![StatusCode::SERVICE_UNAVAILABLE, StatusCode::TOO_MANY_REQUESTS]).await;
+    let dispatcher = WebhookDispatcher::new(fast_retries(4));
+
+    dispatcher.dispatch(&AlertDeliveryMethod::Webhook { url }, &payload()).await.unwrap();
+
+    let bodies = hook.bodies.lock().await;
+    assert_eq!(bodies.len(), 3);
+    assert_eq!(bodies[2]["kind"], "price_alert");
+    assert_eq!(bodies[2]["alert_id"], "alert-765");
+    assert_eq!(bodies[2]["symbol"], SYMBOL);
+    assert!(dispatcher.dead_letters().await.is_empty());
+}
+
+#[tokio::test]
+async fn webhook_gives_up_into_the_dead_letter_log() {
+    let (url, hook) = webhook(vec![StatusCode::BAD_GATEWAY; 5]).await;
+    let dispatcher = WebhookDispatcher::new(fast_retries(3));
+
+    let method = AlertDeliveryMethod::Webhook { url: url.clone() };
+    assert!(dispatcher.dispatch(&method, &payload()).await.is_err());
+    assert_eq!(hook.bodies.lock().await.len(), 3);
+
+    // Client errors won't improve with retrying
+    let (rejecting, rejected) = webhook(vec![StatusCode::NOT_FOUND]).await;
+    assert!(dispatcher.dispatch(&AlertDeliveryMethod::Webhook { url: rejecting.clone() }, &payload()).await.is_err());
+    assert_eq!(rejected.bodies.lock().await.len(), 1);
+
+    let dead_letters = dispatcher.dead_letters().await;
+    assert_eq!(dead_letters.len(), 2);
+    assert_eq!((dead_letters[0].url.as_str(), dead_letters[0].attempts), (url.as_str(), 3));
+    assert!(dead_letters[0].error.contains("502"));
+    assert_eq!((dead_letters[1].url.as_str(), dead_letters[1].attempts), (rejecting.as_str(), 1));
+}
+
+#[tokio::test]
+async fn discord_and_slack_get_their_own_message_shape() {
+    let (url, hook) = webhook(vec![]).await;
+    let dispatcher = WebhookDispatcher::new(fast_retries(1));
+
+    dispatcher.dispatch(&AlertDeliveryMethod::Discord { webhook_url: url.clone() }, &payload()).await.unwrap();
+    dispatcher.dispatch(&AlertDeliveryMethod::Slack { webhook_url: url }, &payload()).await.unwrap();
+
+    let bodies = hook.bodies.lock().await;
+    assert_eq!(bodies[0], serde_json::json!({ "content": "🔔 Alert: TKN breakout" }));
+    assert_eq!(bodies[1], serde_json::json!({ "text": "🔔 Alert: TKN breakout" }));
+}
+
+#[test]
+fn via_arguments_become_delivery_methods() {
+    let methods = PriceAlertHandler::parse_methods(
+        USER_ID,
+        &["telegram", "email", "me@example.com", "webhook", "https://example.com/hook", "telegram"],
+        "en",
+    )
+    .unwrap();
+    assert_eq!(
+        methods.iter().map(|m| m.label()).collect::<Vec<_>>(),
+        vec![
+            format!("telegram {}", USER_ID),
+            "email me@example.com".to_string(),
+            "webhook https://example.com/hook".to_string(),
+        ]
+    );
+
+    assert!(PriceAlertHandler::parse_methods(USER_ID, &["email"], "en").is_err());
+    assert!(PriceAlertHandler::parse_methods(USER_ID, &["webhook", "not-a-url"], "en").is_err());
+    assert!(PriceAlertHandler::parse_methods(USER_ID, &["pigeon", "roof"], "en").is_err());
+}
+
+async fn history(manager: &PriceAlertManager) -> AlertHistory {
+    for _ in 0..50 {
+        if let Some(entry) = manager.get_user_history(USER_ID, 1).await.pop() {
+            return entry;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("trigger should be recorded in the history");
+}
+
+#[tokio::test]
+async fn triggers_go_to_the_alert_and_user_methods_and_record_failures() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let (url, hook) = webhook(vec![]).await;
+    let delivery = Arc::new(
+        AlertDelivery::default().with_dispatcher(Arc::new(WebhookDispatcher::new(fast_retries(1)))),
+    );
+    // Email is chosen but no relay is configured
+    delivery.set_user_methods(USER_ID, vec![
+        AlertDeliveryMethod::Webhook { url: url.clone() },
+        AlertDeliveryMethod::Email { address: "me@example.com".to_string() },
+    ]).await;
+
+    let price_stream = Arc::new(PriceStreamManager::new(Arc::new(
+        WebSocketClient::new(WebSocketConfig::default(), None),
+    )));
+    let manager = PriceAlertManager::new(harness.db.clone(), price_stream, delivery, None);
+    let mut telegram = manager.subscribe_notifications();
+
+    let alert_id = manager.create_alert(PriceAlert {
+        alert_id: String::new(),
+        user_id: USER_ID,
+        name: "TKN breakout".to_string(),
+        symbol: SYMBOL.to_string(),
+        conditions: vec![AlertCondition::PriceThreshold(PriceThreshold {
+            comparison: PriceComparison::Above,
+            target_price: Decimal::from(2),
+            tolerance: None,
+        })],
+        trigger_type: AlertTriggerType::Once,
+        max_triggers: Some(1),
+        priority: AlertPriority::High,
+        actions: vec![AlertAction::Notify],
+        delivery_methods: vec![AlertDeliveryMethod::Telegram { chat_id: USER_ID }],
+        cooldown_period: None,
+        expiry_time: None,
+        enabled: true,
+        created_at: Utc::now(),
+        last_triggered: None,
+        trigger_count: 0,
+        status: AlertStatus::Active,
+        metadata: HashMap::new(),
+    }).await.unwrap();
+
+    let now = Utc::now();
+    manager.observe_price(SYMBOL, Decimal::from(1), None, now).await.unwrap();
+    manager.observe_price(SYMBOL, Decimal::from(3), None, now + chrono::Duration::minutes(1)).await.unwrap();
+
+    let notification = tokio::time::timeout(Duration::from_secs(5), telegram.recv())
+        .await
+        .expect("alert should reach Telegram")
+        .unwrap();
+    assert_eq!(notification.alert_id, alert_id);
+
+    let entry = history(&manager).await;
+    assert_eq!(entry.metadata.get("name").map(String::as_str), Some("TKN breakout"));
+    assert_eq!(entry.delivery_status.len(), 3);
+    assert_eq!(entry.delivery_status[&format!("telegram {}", USER_ID)], DeliveryStatus::Sent);
+    assert_eq!(entry.delivery_status[&format!("webhook {}", url)], DeliveryStatus::Sent);
+    assert_eq!(
+        entry.delivery_status["email me@example.com"],
+        DeliveryStatus::Failed("email delivery is not configured".to_string())
+    );
+
+    let bodies = hook.bodies.lock().await;
+    assert_eq!(bodies.len(), 1);
+    assert_eq!(bodies[0]["alert_id"], Value::String(alert_id));
+}
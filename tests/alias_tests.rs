@@ -0,0 +1,109 @@
+use crate::bot::aliases::{AliasStore, MAX_ALIASES_PER_USER};
+use crate::utils::validation::Validator;
+
+const USER: i64 = 5150;
+const OTHER: i64 = 6160;
+
+/// Arguments as the handler receives them after `/command`
+fn args_of(text: &str) -> &str {
+    text.split_once(' ').map(|(_, args)| args).unwrap_or("")
+}
+
+#[tokio::test]
+async fn test_expansion_appends_arguments() {
+    let store = AliasStore::default();
+    store.set(USER, "b", "qbuy 0.1").await.unwrap();
+    store.set(USER, "/S50", "/qsell 50").await.unwrap();
+
+    assert_eq!(store.expand(USER, "/b BONK").await.as_deref(), Some("/qbuy 0.1 BONK"));
+    assert_eq!(store.expand(USER, "/b").await.as_deref(), Some("/qbuy 0.1"));
+    assert_eq!(store.expand(USER, "/b@BanshieBot  WIF ").await.as_deref(), Some("/qbuy 0.1 WIF"));
+    assert_eq!(store.expand(USER, "/s50").await.as_deref(), Some("/qsell 50"));
+
+    // Only the sender's aliases, only at the start of a command
+    assert!(store.expand(OTHER, "/b BONK").await.is_none());
+    assert!(store.expand(USER, "/bonk").await.is_none());
+    assert!(store.expand(USER, "b BONK").await.is_none());
+
+    store.delete(USER, "b").await.unwrap();
+    assert!(store.expand(USER, "/b BONK").await.is_none());
+    assert!(store.delete(USER, "b").await.is_err());
+}
+
+#[tokio::test]
+async fn test_expansion_is_single_level() {
+    let store = AliasStore::default();
+    store.set(USER, "b", "qbuy 0.1").await.unwrap();
+
+    // Pointing at an existing alias is refused
+    assert!(store.set(USER, "bb", "b BONK").await.is_err());
+    assert!(store.set(USER, "bb", "/B").await.is_err());
+
+    // An alias that later becomes a target still expands only once
+    store.set(USER, "c", "d 1").await.unwrap();
+    store.set(USER, "d", "qbuy 1").await.unwrap();
+    assert_eq!(store.expand(USER, "/c").await.as_deref(), Some("/d 1"));
+
+    // Closing the loop or pointing at itself is refused
+    assert!(store.set(USER, "d", "c").await.is_err());
+    assert!(store.set(USER, "g", "g 1").await.is_err());
+    assert_eq!(store.expand(USER, "/d").await.as_deref(), Some("/qbuy 1"));
+}
+
+#[tokio::test]
+async fn test_reserved_words_and_limits() {
+    let store = AliasStore::default();
+
+    for name in ["buy", "/Help", "alias", "quickbuy", "settings"] {
+        assert!(store.set(USER, name, "qbuy 0.1").await.is_err(), "{} should be reserved", name);
+    }
+    assert!(store.set(USER, "elevenchars", "qbuy 0.1").await.is_err());
+    assert!(store.set(USER, "b-1", "qbuy 0.1").await.is_err());
+    assert!(store.set(USER, "b", "").await.is_err());
+
+    for i in 0..MAX_ALIASES_PER_USER {
+        store.set(USER, &format!("a{}", i), "qbuy 0.1").await.unwrap();
+    }
+    assert!(store.set(USER, "extra", "qbuy 0.1").await.is_err());
+    // Replacing an existing alias at the cap is fine
+    store.set(USER, "a0", "qsell 25").await.unwrap();
+    assert_eq!(store.list(USER).await.len(), MAX_ALIASES_PER_USER);
+    assert!(store.list(OTHER).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_expanded_text_sanitizes_like_typed_input() {
+    let store = AliasStore::default();
+    store.set(USER, "b", "qbuy 0.1").await.unwrap();
+
+    for typed_args in ["BONK", "BONK; rm -rf /", "<script>WIF</script>", "`$(whoami)`"] {
+        let typed = format!("/qbuy 0.1 {}", typed_args);
+        let expanded = store.expand(USER, &format!("/b {}", typed_args)).await.unwrap();
+
+        assert_eq!(expanded, typed);
+        assert_eq!(
+            Validator::sanitize_command_args(args_of(&expanded)).unwrap(),
+            Validator::sanitize_command_args(args_of(&typed)).unwrap(),
+        );
+    }
+
+    // The alias text itself must survive sanitization unchanged
+    assert!(store.set(USER, "x", "qbuy 0.1; rm -rf").await.is_err());
+    assert!(store.set(USER, "x", "qbuy $(cat)").await.is_err());
+    assert!(store.set(USER, "x", &format!("qbuy {}", "1".repeat(250))).await.is_err());
+}
+
+#[tokio::test]
+async fn test_aliases_persist_across_restarts() {
+    let path = std::env::temp_dir().join(format!("aliases-{}.json", uuid::Uuid::new_v4()));
+
+    let store = AliasStore::default().with_storage(&path).await.unwrap();
+    store.set(USER, "b", "qbuy 0.1").await.unwrap();
+    store.set(OTHER, "s50", "qsell 50").await.unwrap();
+
+    let restarted = AliasStore::default().with_storage(&path).await.unwrap();
+    assert_eq!(restarted.expand(USER, "/b BONK").await.as_deref(), Some("/qbuy 0.1 BONK"));
+    assert_eq!(restarted.list(OTHER).await.get("s50").map(String::as_str), Some("qsell 50"));
+
+    let _ = std::fs::remove_file(&path);
+}
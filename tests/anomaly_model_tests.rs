@@ -0,0 +1,150 @@
+use crate::alerts::{AnomalyDetector, AnomalyModel, ReturnZScoreModel, VolumeSpikeModel};
+use crate::websocket::{PriceSource, PriceUpdate, UpdateType};
+use chrono::{Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+
+const SYMBOL: &str = "TKN";
+
+fn tick(i: usize, price: f64, volume: Option<f64>) -> PriceUpdate {
+    PriceUpdate {
+        symbol: SYMBOL.to_string(),
+        price: Decimal::from_f64_retain(price).unwrap().round_dp(8),
+        timestamp: Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap() + Duration::seconds(i as i64 * 10),
+        volume: volume.map(|v| Decimal::from_f64_retain(v).unwrap().round_dp(2)),
+        source: PriceSource::Aggregate,
+        update_type: UpdateType::Trade,
+        metadata: None,
+    }
+}
+
+/// A random-looking walk of small returns (±0.2%), with `shocks` injected at their ticks
+fn price_series(len: usize, shocks: &[(usize, f64)]) -> Vec<PriceUpdate> {
+    let mut price = 100.0;
+    (0..len)
+        .map(|i| {
+            let shock = shocks.iter().find(|(at, _)| *at == i).map(|(_, r)| *r);
+            price *= 1.0 + shock.unwrap_or(0.002 * (i as f64 * 1.7).sin());
+            tick(i, price, None)
+        })
+        .collect()
+}
+
+/// Volume wandering ±15% around 1,000, with `spikes` replacing it at their ticks
+fn volume_series(len: usize, spikes: &[(usize, f64)]) -> Vec<PriceUpdate> {
+    (0..len)
+        .map(|i| {
+            let spike = spikes.iter().find(|(at, _)| *at == i).map(|(_, v)| *v);
+            tick(i, 1.0, Some(spike.unwrap_or(1000.0 + 150.0 * (i as f64 * 2.3).sin())))
+        })
+        .collect()
+}
+
+/// Slide the model along the series as updates arrive; the ticks it flags
+fn flagged(model: &dyn AnomalyModel, series: &[PriceUpdate]) -> Vec<usize> {
+    (0..series.len())
+        .filter(|&i| {
+            let start = (i + 1).saturating_sub(model.lookback());
+            model.detect(&series[start..=i]).is_some()
+        })
+        .collect()
+}
+
+#[test]
+fn zscore_model_flags_only_the_injected_returns() {
+    let model = ReturnZScoreModel::default();
+    let series = price_series(150, &[(80, 0.06), (110, -0.06)]);
+
+    assert_eq!(flagged(&model, &series), vec![80, 110]);
+
+    let spike = model.detect(&series[80 + 1 - model.lookback()..=80]).unwrap().remove(0);
+    assert_eq!(spike.anomaly_type, "return_spike");
+    assert!(spike.details["z_score"] > 3.0);
+    assert!((spike.details["return"] - 0.06).abs() < 1e-6);
+    assert!((spike.severity - 0.06).abs() < 1e-6);
+
+    let drop = model.detect(&series[110 + 1 - model.lookback()..=110]).unwrap().remove(0);
+    assert_eq!(drop.anomaly_type, "return_drop");
+    assert!(drop.details["z_score"] < -3.0);
+    // The earlier spike is in this window and widens it, so the drop stands out less
+    assert!(drop.details["z_score"].abs() < spike.details["z_score"]);
+    assert!(drop.confidence < spike.confidence);
+    assert!(drop.confidence > 0.75 && spike.confidence < 1.0);
+}
+
+#[test]
+fn zscore_model_needs_a_full_window_and_tolerates_flat_prices() {
+    let model = ReturnZScoreModel { window: 20, threshold: 3.0 };
+    // A shock before there are 20 returns to compare against is ignored
+    assert!(flagged(&model, &price_series(60, &[(15, 0.2)])).is_empty());
+
+    // Flat prices have no variance; a tiny tick off them isn't an anomaly, a real move is
+    let mut flat: Vec<PriceUpdate> = (0..30).map(|i| tick(i, 2.0, None)).collect();
+    flat.push(tick(30, 2.002, None));
+    assert!(model.detect(&flat).is_none());
+    flat.push(tick(31, 2.1, None));
+    let jump = model.detect(&flat[flat.len() - model.lookback()..]).unwrap().remove(0);
+    assert!(jump.details["z_score"] > 40.0);
+}
+
+#[test]
+fn volume_model_flags_spikes_but_not_lulls() {
+    let model = VolumeSpikeModel::default();
+    let series = volume_series(120, &[(70, 12_000.0), (95, 50.0)]);
+
+    assert_eq!(flagged(&model, &series), vec![70]);
+
+    let spike = model.detect(&series[70 + 1 - model.lookback()..=70]).unwrap().remove(0);
+    assert_eq!(spike.anomaly_type, "volume_spike");
+    assert!((spike.details["median_volume"] - 1000.0).abs() < 100.0);
+    assert!(spike.details["volume_ratio"] > 10.0);
+    assert!(spike.details["z_score"] > 3.5);
+    assert!(spike.confidence > 0.9);
+    assert!(spike.severity > 9.0);
+}
+
+#[test]
+fn volume_model_handles_a_steady_baseline_and_missing_volume() {
+    let model = VolumeSpikeModel { window: 10, threshold: 3.5 };
+    let mut series: Vec<PriceUpdate> = (0..10).map(|i| tick(i, 1.0, Some(1000.0))).collect();
+    // Updates without volume don't count towards the window
+    series.push(tick(10, 1.0, None));
+    series.push(tick(11, 1.0, Some(1010.0)));
+    assert!(model.detect(&series).is_none());
+
+    series.push(tick(12, 1.0, Some(3000.0)));
+    let spike = model.detect(&series).unwrap().remove(0);
+    assert!((spike.details["volume_ratio"] - 3.0).abs() < 1e-9);
+
+    series.push(tick(13, 1.0, None));
+    assert!(model.detect(&series).is_none());
+}
+
+#[tokio::test]
+async fn detector_keeps_a_capped_history_and_runs_both_models() {
+    let detector = AnomalyDetector::default();
+    let mut series = volume_series(2_100, &[]);
+    // The last update jumps 10% on twenty times the volume
+    series.push(tick(2_100, 1.1, Some(20_000.0)));
+
+    // check_flash_crash records every update into the history
+    for update in &series[..2_100] {
+        detector.check_flash_crash(update).await;
+    }
+    assert_eq!(detector.history_len(SYMBOL).await, 2_000);
+    assert!(detector.detect_anomalies(SYMBOL).await.is_none());
+
+    detector.check_flash_crash(&series[2_100]).await;
+    assert_eq!(detector.history_len(SYMBOL).await, 2_000);
+    let mut kinds: Vec<String> = detector.detect_anomalies(SYMBOL).await
+        .expect("the last update is an outlier")
+        .into_iter()
+        .map(|a| a.anomaly_type)
+        .collect();
+    kinds.sort();
+    assert_eq!(kinds, vec!["return_spike", "volume_spike"]);
+
+    assert!(detector.detect_anomalies("OTHER").await.is_none());
+    let silent = AnomalyDetector::default().with_models(vec![]);
+    silent.check_flash_crash(&series[2_100]).await;
+    assert!(silent.detect_anomalies(SYMBOL).await.is_none());
+}
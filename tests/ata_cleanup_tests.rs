@@ -0,0 +1,202 @@
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, transaction::Transaction};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::analytics::FeeLedger;
+use crate::errors::{BotError, Result};
+use crate::trading::{DCAStatus, DCAStrategy, Order, OrderStatus};
+use crate::wallet::{
+    AtaCleanupConfig, AtaJanitor, CleanupExclusions, CleanupPlan, CleanupSubmitter, SkipReason,
+    TokenAccountSnapshot,
+};
+
+const USER: i64 = 7070;
+const RENT: u64 = 2_039_280;
+const SPL_TOKEN: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+fn account(mint: &str, amount: u64) -> TokenAccountSnapshot {
+    TokenAccountSnapshot {
+        address: Pubkey::new_unique(),
+        mint: mint.to_string(),
+        program_id: Pubkey::from_str(SPL_TOKEN).unwrap(),
+        amount,
+        lamports: RENT,
+        is_frozen: false,
+        extensions: Vec::new(),
+        withheld_amount: 0,
+    }
+}
+
+fn token_2022_account(mint: &str, extensions: &[&str]) -> TokenAccountSnapshot {
+    TokenAccountSnapshot {
+        program_id: Pubkey::from_str(TOKEN_2022).unwrap(),
+        extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+        ..account(mint, 0)
+    }
+}
+
+fn janitor(config: AtaCleanupConfig) -> AtaJanitor {
+    AtaJanitor::new(Arc::new(RpcClient::new("http://127.0.0.1:8899".to_string())), config)
+}
+
+/// Signs nothing; fails the batches whose index is listed
+struct MockSubmitter {
+    fail_batches: Vec<usize>,
+    submitted: Mutex<Vec<Transaction>>,
+}
+
+#[async_trait::async_trait]
+impl CleanupSubmitter for MockSubmitter {
+    async fn submit(&self, _user_id: i64, transaction: Transaction) -> Result<String> {
+        let mut submitted = self.submitted.lock().unwrap();
+        let index = submitted.len();
+        submitted.push(transaction);
+        if self.fail_batches.contains(&index) {
+            return Err(BotError::trading("blockhash expired".to_string()).into());
+        }
+        Ok(format!("sig-{}", index))
+    }
+}
+
+#[test]
+fn test_exclusion_rules() {
+    let config = AtaCleanupConfig::default();
+    let mut stopped = Order::create_stop_loss(USER, "FILLED".to_string(), Decimal::ONE, Decimal::ONE);
+    stopped.status = OrderStatus::Filled;
+    let orders = vec![
+        Order::create_stop_loss(USER, "ORDER".to_string(), Decimal::ONE, Decimal::ONE),
+        stopped,
+    ];
+    let mut finished = DCAStrategy::create_daily_dca(
+        USER, "done".to_string(), "USDC".to_string(), "OLD".to_string(), Decimal::TEN, Decimal::ONE,
+    );
+    finished.status = DCAStatus::Completed;
+    let strategies = vec![
+        DCAStrategy::create_daily_dca(
+            USER, "bonk".to_string(), "USDC".to_string(), "DCA".to_string(), Decimal::TEN, Decimal::ONE,
+        ),
+        finished,
+    ];
+    let exclusions = CleanupExclusions::default()
+        .with_orders(&orders)
+        .with_dca(&strategies)
+        .with_watchlist(vec!["WATCHED".to_string()]);
+
+    let frozen = TokenAccountSnapshot { is_frozen: true, ..account("FROZEN", 0) };
+    let withheld = TokenAccountSnapshot { withheld_amount: 5, ..token_2022_account("FEES", &["transferFeeAmount"]) };
+    let accounts = vec![
+        account("HELD", 1_000),
+        account("ORDER", 0),
+        account("FILLED", 0),
+        account("DCA", 0),
+        account("USDC", 0),
+        account("OLD", 0),
+        account("WATCHED", 0),
+        frozen,
+        withheld,
+        token_2022_account("SAFE22", &["immutableOwner", "transferFeeAmount"]),
+        token_2022_account("HOOKED", &["immutableOwner", "transferHookAccount"]),
+    ];
+
+    let plan = CleanupPlan::build(&accounts, &exclusions, &config);
+
+    let closable: Vec<&str> = plan.closable.iter().map(|a| a.mint.as_str()).collect();
+    assert_eq!(closable, vec!["FILLED", "OLD", "SAFE22"]);
+
+    let skipped: Vec<(&str, SkipReason)> = plan.skipped.iter()
+        .map(|(a, reason)| (a.mint.as_str(), reason.clone()))
+        .collect();
+    assert_eq!(skipped, vec![
+        ("ORDER", SkipReason::ActiveOrder),
+        ("DCA", SkipReason::DcaStrategy),
+        // Both legs of a running strategy stay open
+        ("USDC", SkipReason::DcaStrategy),
+        ("WATCHED", SkipReason::Watchlist),
+        ("FROZEN", SkipReason::Frozen),
+        ("FEES", SkipReason::WithheldTransferFees),
+        ("HOOKED", SkipReason::UnlistedExtension("transferHookAccount".to_string())),
+    ]);
+    assert_eq!(plan.reclaimable_lamports(), 3 * RENT);
+}
+
+#[test]
+fn test_batches_span_multiple_transactions() {
+    let owner = Pubkey::new_unique();
+    let accounts: Vec<TokenAccountSnapshot> = (0..23).map(|i| account(&format!("MINT{}", i), 0)).collect();
+    let plan = CleanupPlan::build(&accounts, &CleanupExclusions::default(), &AtaCleanupConfig::default());
+
+    let transactions = plan.build_transactions(&owner, Hash::new_unique(), 10);
+    let sizes: Vec<usize> = transactions.iter().map(|tx| tx.message.instructions.len()).collect();
+    assert_eq!(sizes, vec![10, 10, 3]);
+
+    for tx in &transactions {
+        // Unsigned, paid for by the owner, who also receives the rent
+        assert_eq!(tx.message.account_keys[0], owner);
+        assert!(tx.signatures.iter().all(|sig| *sig == Default::default()));
+        for ix in &tx.message.instructions {
+            assert_eq!(ix.data, vec![9]);
+            assert_eq!(tx.message.account_keys[ix.accounts[1] as usize], owner);
+        }
+    }
+
+    // Every account is closed exactly once
+    let closed: std::collections::HashSet<Pubkey> = transactions.iter()
+        .flat_map(|tx| tx.message.instructions.iter().map(move |ix| tx.message.account_keys[ix.accounts[0] as usize]))
+        .collect();
+    assert_eq!(closed.len(), 23);
+}
+
+#[tokio::test]
+async fn test_reclaimed_rent_credited_per_successful_batch() {
+    let janitor = janitor(AtaCleanupConfig { max_closes_per_tx: 4, ..AtaCleanupConfig::default() });
+    let ledger = FeeLedger::new();
+    let owner = Pubkey::new_unique();
+    let accounts: Vec<TokenAccountSnapshot> = (0..10).map(|i| account(&format!("MINT{}", i), 0)).collect();
+    let plan = CleanupPlan::build(&accounts, &CleanupExclusions::default(), janitor.config());
+
+    let submitter = MockSubmitter { fail_batches: vec![1], submitted: Mutex::new(Vec::new()) };
+    let outcome = janitor.execute(USER, &owner, &plan, Hash::new_unique(), &submitter, &ledger).await;
+
+    // Batches of 4, 4 and 2; the failed middle batch doesn't stop the last one
+    assert_eq!(submitter.submitted.lock().unwrap().len(), 3);
+    assert_eq!(outcome.failed_batches, 1);
+    assert_eq!(outcome.closed, 6);
+    assert_eq!(outcome.reclaimed_lamports, 6 * RENT);
+    assert_eq!(outcome.signatures, vec!["sig-0".to_string(), "sig-2".to_string()]);
+
+    let summary = ledger.summary(USER).await;
+    assert_eq!(summary.credited_lamports, 6 * RENT);
+    assert_eq!(summary.paid_lamports, 0);
+    assert_eq!(summary.net_lamports(), -(6 * RENT as i64));
+
+    // A replayed confirmation doesn't credit twice
+    let ledger_entries = ledger.entries(USER).await.len();
+    assert!(!ledger.record(USER, crate::analytics::FeeKind::RentReclaimed, 4 * RENT, Some("sig-0")).await);
+    assert_eq!(ledger.entries(USER).await.len(), ledger_entries);
+}
+
+#[tokio::test]
+async fn test_auto_mode_waits_for_accounts_to_stay_empty() {
+    let janitor = janitor(AtaCleanupConfig::default());
+    let now = Utc::now();
+    let old = account("OLD", 0);
+    let recent = account("RECENT", 0);
+
+    janitor.note_empty(&[old.clone()], now - Duration::days(20)).await;
+    janitor.note_empty(&[old.clone(), recent.clone()], now - Duration::days(2)).await;
+
+    let plan = CleanupPlan::build(&[old.clone(), recent.clone()], &CleanupExclusions::default(), janitor.config());
+    let auto = janitor.auto_plan(plan, now).await;
+    assert_eq!(auto.closable, vec![old.clone()]);
+
+    // Refilling resets the clock
+    let refilled = TokenAccountSnapshot { amount: 1, ..old.clone() };
+    janitor.note_empty(&[refilled], now).await;
+    janitor.note_empty(&[old.clone()], now).await;
+    let plan = CleanupPlan::build(&[old], &CleanupExclusions::default(), janitor.config());
+    assert!(janitor.auto_plan(plan, now).await.is_empty());
+}
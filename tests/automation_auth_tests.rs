@@ -0,0 +1,129 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::sync::{Arc, Mutex};
+
+use crate::bot::automation_auth::{
+    AutomationAction, AutomationAuthority, AutomationGrant, CommandDecision, ConvexCommand, GrantPublisher,
+    GrantScope, Violation,
+};
+use crate::errors::Result;
+
+const USER: i64 = 777;
+const OTHER: i64 = 888;
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap()
+}
+
+fn scope(action: AutomationAction, max_notional_sol: f64) -> GrantScope {
+    GrantScope { action, max_notional_sol, expires_at: now() + Duration::days(7) }
+}
+
+fn command(id: &str, user: i64, action: AutomationAction, notional_sol: f64, token: &str) -> ConvexCommand {
+    ConvexCommand {
+        command_id: id.to_string(),
+        telegram_id: user,
+        action,
+        notional_sol,
+        authorization: token.to_string(),
+        strategy_id: None,
+    }
+}
+
+/// Records what would have been sent to Convex
+#[derive(Default)]
+struct RecordingPublisher {
+    tokens: Mutex<Vec<String>>,
+    revoked: Mutex<Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl GrantPublisher for RecordingPublisher {
+    async fn publish_grant(&self, _grant: &AutomationGrant, token: &str) -> Result<()> {
+        self.tokens.lock().unwrap().push(token.to_string());
+        Ok(())
+    }
+
+    async fn publish_revocation(&self, grant: &AutomationGrant) -> Result<()> {
+        self.revoked.lock().unwrap().push(grant.grant_id.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_scope_covers_action_and_cap() {
+    let publisher = Arc::new(RecordingPublisher::default());
+    let authority = AutomationAuthority::default().with_publisher(publisher.clone());
+    let (grant, token) = authority.issue(USER, scope(AutomationAction::DcaRun, 0.5), now()).await.unwrap();
+
+    // Convex gets the token; the bot keeps only its hash
+    assert_eq!(publisher.tokens.lock().unwrap().as_slice(), &[token.clone()]);
+    assert_eq!(grant.token_hash, AutomationAuthority::hash_token(&token));
+    assert_ne!(grant.token_hash, token);
+
+    let ok = authority.check(&command("c1", USER, AutomationAction::DcaRun, 0.5, &token), 0.5, now()).await;
+    assert_eq!(ok, CommandDecision::Execute { grant_id: grant.grant_id.clone() });
+
+    let over = authority.check(&command("c2", USER, AutomationAction::DcaRun, 0.4, &token), 2.0, now()).await;
+    assert_eq!(over, CommandDecision::Rejected(Violation::OverCap { cap_sol: 0.5, requested_sol: 2.0 }));
+
+    let wrong_action = authority.check(&command("c3", USER, AutomationAction::SimulatedTrade, 0.1, &token), 0.1, now()).await;
+    assert!(matches!(wrong_action, CommandDecision::Rejected(Violation::ActionNotGranted { .. })));
+
+    let forged = authority.check(&command("c4", USER, AutomationAction::DcaRun, 0.1, "atk_forged"), 0.1, now()).await;
+    assert_eq!(forged, CommandDecision::Rejected(Violation::UnknownToken));
+
+    let borrowed = authority.check(&command("c5", OTHER, AutomationAction::DcaRun, 0.1, &token), 0.1, now()).await;
+    assert_eq!(borrowed, CommandDecision::Rejected(Violation::WrongUser));
+
+    let audit = authority.audit_log(USER).await;
+    assert_eq!(audit.len(), 3);
+    assert_eq!(audit[0].command_id, "c2");
+    assert_eq!(audit[0].grant_id.as_deref(), Some(grant.grant_id.as_str()));
+
+    // A replayed command id is acknowledged, not executed again
+    let replay = authority.check(&command("c1", USER, AutomationAction::DcaRun, 0.5, &token), 0.5, now()).await;
+    assert_eq!(replay, CommandDecision::Duplicate);
+}
+
+#[tokio::test]
+async fn test_expiry_and_revocation_take_effect() {
+    let publisher = Arc::new(RecordingPublisher::default());
+    let authority = AutomationAuthority::default().with_publisher(publisher.clone());
+    let (dca, dca_token) = authority.issue(USER, scope(AutomationAction::DcaRun, 1.0), now()).await.unwrap();
+    let (_, sim_token) = authority.issue(USER, scope(AutomationAction::SimulatedTrade, 1.0), now()).await.unwrap();
+
+    let later = now() + Duration::days(8);
+    let expired = authority.check(&command("e1", USER, AutomationAction::SimulatedTrade, 0.1, &sim_token), 0.1, later).await;
+    assert_eq!(expired, CommandDecision::Rejected(Violation::Expired));
+
+    let before = authority.check(&command("r1", USER, AutomationAction::DcaRun, 0.1, &dca_token), 0.1, now()).await;
+    assert!(matches!(before, CommandDecision::Execute { .. }));
+    assert!(authority.revoke(OTHER, &dca.grant_id, now()).await.is_err());
+    authority.revoke(USER, &dca.grant_id, now()).await.unwrap();
+    assert_eq!(publisher.revoked.lock().unwrap().as_slice(), &[dca.grant_id.clone()]);
+
+    let after = authority.check(&command("r2", USER, AutomationAction::DcaRun, 0.1, &dca_token), 0.1, now()).await;
+    assert_eq!(after, CommandDecision::Rejected(Violation::Revoked));
+
+    let statuses: Vec<&str> = authority.grants(USER).await.iter().map(|g| g.status(later)).collect();
+    assert!(statuses.contains(&"revoked") && statuses.contains(&"expired"));
+    assert_eq!(authority.revoke_all(USER, now()).await, 1);
+}
+
+#[tokio::test]
+async fn test_violation_notifies_the_user() {
+    let authority = AutomationAuthority::default();
+    let mut notices = authority.subscribe_violations();
+    let (_, token) = authority.issue(USER, scope(AutomationAction::DcaRun, 0.25), now()).await.unwrap();
+
+    authority.check(&command("v1", USER, AutomationAction::DcaRun, 0.2, &token), 5.0, now()).await;
+
+    let notice = notices.try_recv().expect("a blocked command should notify the user");
+    assert_eq!(notice.user_id, USER);
+    assert!(notice.text.contains("5 SOL"));
+    assert!(notice.text.contains("/automations"));
+
+    // Allowed commands stay quiet
+    authority.check(&command("v2", USER, AutomationAction::DcaRun, 0.2, &token), 0.2, now()).await;
+    assert!(notices.try_recv().is_err());
+}
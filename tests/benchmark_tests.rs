@@ -0,0 +1,80 @@
+use chrono::{Duration, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::analytics::{sparkline, BenchmarkBasket, BenchmarkReport, MetricsCalculator};
+
+const BONK_MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+fn days(n: usize) -> Vec<NaiveDate> {
+    let start = NaiveDate::from_ymd_opt(2026, 9, 1).unwrap();
+    (0..n).map(|i| start + Duration::days(i as i64)).collect()
+}
+
+fn closes(dates: &[NaiveDate], prices: &[f64]) -> BTreeMap<NaiveDate, f64> {
+    dates.iter().copied().zip(prices.iter().copied()).collect()
+}
+
+fn report(portfolio: &[f64], benchmark: &[f64]) -> BenchmarkReport {
+    BenchmarkReport::compute("SOL", portfolio, benchmark, &MetricsCalculator::default()).unwrap()
+}
+
+#[test]
+fn test_tracking_the_benchmark_exactly_has_no_alpha() {
+    let r = report(&[100.0, 110.0, 99.0, 108.9], &[10.0, 11.0, 9.9, 10.89]);
+    assert!((r.beta - 1.0).abs() < 1e-9);
+    assert!(r.alpha.abs() < 1e-9);
+    assert!((r.portfolio_return - 8.9).abs() < 1e-9);
+    assert!(r.max_drawdown_delta.abs() < 1e-9);
+    assert_eq!(r.days, 3);
+}
+
+#[test]
+fn test_doubled_daily_moves_give_beta_two() {
+    // Benchmark +10%, -10%, +10%; portfolio +20%, -20%, +20%
+    let r = report(&[100.0, 120.0, 96.0, 115.2], &[100.0, 110.0, 99.0, 108.9]);
+    assert!((r.beta - 2.0).abs() < 1e-9);
+    assert!((r.portfolio_return - 15.2).abs() < 1e-9);
+    assert!((r.benchmark_return - 8.9).abs() < 1e-9);
+    // 15.2 - 2 × 8.9
+    assert!((r.alpha + 2.6).abs() < 1e-9);
+    assert!((r.portfolio_max_drawdown - 20.0).abs() < 1e-9);
+    assert!((r.benchmark_max_drawdown - 10.0).abs() < 1e-9);
+    assert!((r.max_drawdown_delta - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_basket_is_bought_once_and_held() {
+    let basket = BenchmarkBasket::parse(&format!("SOL:50,{}:50", BONK_MINT)).unwrap();
+    assert_eq!(basket.weights.iter().map(|(_, w)| w).sum::<f64>(), 1.0);
+
+    let dates = days(3);
+    let mut prices = HashMap::new();
+    prices.insert(basket.weights[0].0.clone(), closes(&dates, &[100.0, 200.0, 150.0]));
+    // BONK has no close on the last day, so it keeps the one before
+    prices.insert(BONK_MINT.to_string(), closes(&dates[..2], &[1.0, 0.5]));
+
+    let values = basket.buy_and_hold(&dates, &prices, 1_000.0).unwrap();
+    assert_eq!(values, vec![1_000.0, 1_250.0, 1_000.0]);
+
+    // No price on the first day means there's nothing to compare with
+    let late = days(4)[1..].to_vec();
+    let mut missing = prices.clone();
+    missing.insert(BONK_MINT.to_string(), closes(&late, &[1.0, 1.0, 1.0]));
+    assert!(basket.buy_and_hold(&dates, &missing, 1_000.0).is_err());
+}
+
+#[test]
+fn test_basket_specs_and_sparklines() {
+    assert_eq!(BenchmarkBasket::parse("").unwrap(), BenchmarkBasket::sol());
+    assert_eq!(BenchmarkBasket::parse("sol").unwrap().weights, BenchmarkBasket::sol().weights);
+    let equal = BenchmarkBasket::parse(&format!("SOL {}", BONK_MINT)).unwrap();
+    assert!((equal.weights[1].1 - 0.5).abs() < 1e-12);
+    assert!(BenchmarkBasket::parse("SOL:-5").is_err());
+
+    assert_eq!(sparkline(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]), "▁▂▃▄▅▆▇█");
+    assert_eq!(sparkline(&[5.0, 5.0]), "▄▄");
+    let long: Vec<f64> = (0..90).map(f64::from).collect();
+    let line = sparkline(&long);
+    assert!(line.chars().count() <= 21);
+    assert!(line.starts_with('▁') && line.ends_with('█'));
+}
@@ -0,0 +1,205 @@
+use crate::api::{ApiTier, JupiterV6Client};
+use crate::blinks::{ActionGetResponse, ActionPostResponse, ActionServer, BlinkExecutor, BlinkGenerator, SolanaNetwork};
+use crate::errors::GENERIC_FAILURE;
+use crate::testkit::{JupiterScenario, TestHarness};
+use crate::trading::TokenResolver;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{signature::{Keypair, Signature}, signer::Signer, system_program, transaction::Transaction};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const CREATOR: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const BASE_URL: &str = "https://blinks.example.com";
+
+/// Actions server backed by the harness mocks; returns its URL and the generator
+async fn serve(harness: &TestHarness) -> (String, Arc<BlinkGenerator>) {
+    let generator = Arc::new(BlinkGenerator::new(format!("{}/", BASE_URL), SolanaNetwork::Devnet));
+    let executor = BlinkExecutor::new(harness.trading_engine.clone(), harness.wallet_manager.clone())
+        .with_jupiter(Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())))
+        .with_rpc(Arc::new(RpcClient::new(harness.rpc.url())));
+    let server = ActionServer::new(generator.clone(), Arc::new(executor));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = server.serve(listener).await;
+    });
+    (url, generator)
+}
+
+async fn post(url: String, body: Value) -> (StatusCode, Value) {
+    let response = reqwest::Client::new().post(url).json(&body).send().await.unwrap();
+    (response.status(), response.json().await.unwrap())
+}
+
+fn decode(posted: &ActionPostResponse) -> Transaction {
+    bincode::deserialize(&base64::decode(&posted.transaction).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_get_returns_spec_metadata() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let (url, generator) = serve(&harness).await;
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let blink = generator
+        .register_buy_blink(bonk, "BONK".to_string(), vec![0.1, 0.5], 100, CREATOR.to_string())
+        .await
+        .unwrap();
+
+    let response = reqwest::get(format!("{}/api/actions/{}", url, blink.blink_id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-action-version"], "2.1.3");
+    assert_eq!(response.headers()["x-blockchain-ids"], "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1");
+    let body: Value = response.json().await.unwrap();
+
+    let href = format!("{}/api/actions/{}", BASE_URL, blink.blink_id);
+    assert_eq!(body, json!({
+        "type": "action",
+        "icon": "https://example.com/swap-icon.png",
+        "title": "Buy BONK",
+        "description": "Buy BONK with SOL, routed through Jupiter",
+        "label": "Buy",
+        "links": {
+            "actions": [
+                { "type": "transaction", "label": "Buy 0.1 SOL", "href": format!("{}?amount=0.1", href) },
+                { "type": "transaction", "label": "Buy 0.5 SOL", "href": format!("{}?amount=0.5", href) },
+                {
+                    "type": "transaction",
+                    "label": "Buy",
+                    "href": format!("{}?amount={{amount}}", href),
+                    "parameters": [{ "type": "number", "name": "amount", "label": "SOL amount", "required": true }]
+                }
+            ]
+        }
+    }));
+    // And it round-trips through the typed response
+    assert!(serde_json::from_value::<ActionGetResponse>(body).is_ok());
+    assert_eq!(
+        generator.dial_to_url(&blink.blink_id, None),
+        format!("https://dial.to/?action={}", urlencoding::encode(&format!("solana-action:{}", href)))
+    );
+
+    let actions: Value = reqwest::get(format!("{}/actions.json", url)).await.unwrap().json().await.unwrap();
+    assert_eq!(actions["rules"][0]["apiPath"], "/api/actions/**");
+    let missing = reqwest::get(format!("{}/api/actions/blink_missing", url)).await.unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    assert_eq!(missing.json::<Value>().await.unwrap()["message"], "Unknown blink");
+}
+
+#[tokio::test]
+async fn test_post_returns_unsigned_jupiter_swap_for_the_buyer() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let (url, generator) = serve(&harness).await;
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let blink = generator
+        .register_buy_blink(bonk.clone(), "BONK".to_string(), vec![], 150, CREATOR.to_string())
+        .await
+        .unwrap();
+    let buyer = Keypair::new().pubkey();
+
+    let (status, body) = post(
+        format!("{}/api/actions/{}?amount=0.25", url, blink.blink_id),
+        json!({ "account": buyer.to_string() }),
+    ).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let posted: ActionPostResponse = serde_json::from_value(body).unwrap();
+    assert_eq!(posted.kind, "transaction");
+    assert_eq!(posted.message.as_deref(), Some("Buy BONK with 0.25 SOL"));
+
+    // The transaction Jupiter built for the buyer, untouched and unsigned
+    let transaction = decode(&posted);
+    assert_eq!(transaction.message.account_keys[0], buyer);
+    assert!(transaction.signatures.iter().all(|s| *s == Signature::default()));
+    assert_eq!(Some(transaction), harness.jupiter.last_swap_transaction().await);
+
+    let quote = harness.jupiter.calls().await.into_iter().find(|c| c.endpoint == "v6_quote").unwrap();
+    assert_eq!(quote.params["inputMint"], WSOL_MINT);
+    assert_eq!(quote.params["outputMint"], bonk);
+    assert_eq!(quote.params["amount"], "250000000");
+    assert_eq!(quote.params["slippageBps"], "150");
+    let swap = harness.jupiter.calls().await.into_iter().find(|c| c.endpoint == "swap").unwrap();
+    assert_eq!(swap.params["userPublicKey"], buyer.to_string());
+}
+
+#[tokio::test]
+async fn test_post_rejects_bad_accounts_and_amounts() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let (url, generator) = serve(&harness).await;
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let blink = generator
+        .register_buy_blink(bonk, "BONK".to_string(), vec![], 100, CREATOR.to_string())
+        .await
+        .unwrap();
+    let action = format!("{}/api/actions/{}", url, blink.blink_id);
+    let buyer = Keypair::new().pubkey().to_string();
+
+    let (status, body) = post(action.clone(), json!({ "account": "not-a-wallet" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["message"].as_str().unwrap().contains("Invalid account: not-a-wallet"), "{}", body);
+    let (status, _) = post(action.clone(), json!({ "wallet": buyer })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = post(format!("{}?amount=-1", action), json!({ "account": buyer })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = post(format!("{}?amount={{amount}}", action), json!({ "account": buyer })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Nothing reached Jupiter
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+    assert!(generator
+        .register_buy_blink("BONK".to_string(), "BONK".to_string(), vec![], 100, CREATOR.to_string())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_upstream_failures_answer_502_without_their_detail() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let (url, generator) = serve(&harness).await;
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let blink = generator
+        .register_buy_blink(bonk, "BONK".to_string(), vec![0.1], 100, CREATOR.to_string())
+        .await
+        .unwrap();
+    harness.jupiter.set_scenario(JupiterScenario::NoRoute).await;
+
+    let (status, body) = post(
+        format!("{}/api/actions/{}", url, blink.blink_id),
+        json!({ "account": Keypair::new().pubkey().to_string() }),
+    ).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    // Jupiter's response stays in the logs
+    assert_eq!(body["message"], GENERIC_FAILURE, "{}", body);
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+}
+
+#[tokio::test]
+async fn test_donation_builds_an_unsigned_transfer() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let (url, generator) = serve(&harness).await;
+    let blink = generator
+        .register_donation_blink(CREATOR.to_string(), vec![1.0], CREATOR.to_string())
+        .await
+        .unwrap();
+    let donor = Keypair::new().pubkey();
+
+    let metadata = generator.action_metadata(&blink, None);
+    assert_eq!(metadata.label, "Donate");
+    assert_eq!(metadata.links.actions[0].label, "Donate 1 SOL");
+
+    // No amount picks the first preset
+    let (status, body) = post(
+        format!("{}/api/actions/{}", url, blink.blink_id),
+        json!({ "account": donor.to_string() }),
+    ).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let transaction = decode(&serde_json::from_value(body).unwrap());
+
+    assert_eq!(transaction.message.account_keys[0], donor);
+    assert_eq!(transaction.message.account_keys[1].to_string(), CREATOR);
+    assert_eq!(transaction.message.account_keys[2], system_program::id());
+    assert!(transaction.signatures.iter().all(|s| *s == Signature::default()));
+    assert_ne!(transaction.message.recent_blockhash, Default::default());
+}
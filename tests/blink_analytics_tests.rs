@@ -0,0 +1,172 @@
+use crate::api::{ApiTier, JupiterV6Client};
+use crate::blinks::{
+    ActionPostResponse, ActionServer, BlinkEvent, BlinkEventKind, BlinkExecutor, BlinkFunnel, BlinkGenerator, BlinkTracker,
+    SolanaNetwork,
+};
+use crate::bot::handlers::BlinksHandler;
+use crate::testkit::TestHarness;
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::{json, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+const BLINK: &str = "blink_funnel";
+const CREATOR: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+fn event(kind: BlinkEventKind, request: &str) -> BlinkEvent {
+    BlinkEvent::new(BLINK, None, kind).with_request(request)
+}
+
+#[test]
+fn test_funnel_counts_each_request_once_whatever_the_order() {
+    let events = vec![
+        // Confirmed before its click and request were seen, and reported twice
+        event(BlinkEventKind::TransactionConfirmed, "a").with_signature("sig-a"),
+        event(BlinkEventKind::TransactionRequested, "a").with_amount(0.5),
+        event(BlinkEventKind::TransactionConfirmed, "a").with_signature("sig-a"),
+        event(BlinkEventKind::Click, "a"),
+        // Requested, never signed; its click was lost
+        event(BlinkEventKind::TransactionRequested, "b").with_amount(1.0),
+        // Clicked, then rejected
+        event(BlinkEventKind::Click, "c"),
+        // Confirmation that carries its own amount
+        event(BlinkEventKind::TransactionConfirmed, "d").with_amount(0.25),
+        event(BlinkEventKind::Click, "d"),
+        BlinkEvent::new(BLINK, None, BlinkEventKind::Impression),
+        BlinkEvent::new(BLINK, None, BlinkEventKind::Impression),
+        BlinkEvent::new(BLINK, None, BlinkEventKind::Impression),
+        // Nothing to tie it to
+        BlinkEvent::new(BLINK, None, BlinkEventKind::Click),
+    ];
+
+    let funnel = BlinkFunnel::from_events(&events);
+    assert_eq!(funnel, BlinkFunnel {
+        impressions: 3,
+        clicks: 4,
+        transactions_requested: 3,
+        transactions_confirmed: 2,
+        volume_sol: 0.75,
+    });
+    assert_eq!(funnel.conversion_rate(), 50.0);
+
+    // Same answer in reverse
+    assert_eq!(BlinkFunnel::from_events(events.iter().rev()), funnel);
+    assert_eq!(BlinkFunnel::default().conversion_rate(), 0.0);
+}
+
+#[tokio::test]
+async fn test_referrers_sparkline_and_retention() {
+    let tracker = BlinkTracker::new();
+    let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+    let click = |referrer: Option<&str>, request: &str, days_ago: i64| {
+        BlinkEvent::new(BLINK, referrer, BlinkEventKind::Click)
+            .with_request(request)
+            .at(now - Duration::days(days_ago))
+    };
+
+    tracker.record(click(Some("twitter"), "t1", 0)).await.unwrap();
+    tracker.record(click(Some("twitter"), "t2", 0)).await.unwrap();
+    tracker.record(click(Some("twitter"), "t3", 2)).await.unwrap();
+    tracker.record(click(None, "d1", 6)).await.unwrap();
+    tracker.record(click(None, "old", 91)).await.unwrap();
+    let mut confirmed = BlinkEvent::new(BLINK, Some("twitter"), BlinkEventKind::TransactionConfirmed)
+        .with_request("t1")
+        .at(now);
+    confirmed.amount_sol = Some(2.0);
+    tracker.record(confirmed).await.unwrap();
+
+    let by_referrer = tracker.funnel_by_referrer(BLINK).await;
+    assert_eq!(by_referrer.keys().collect::<Vec<_>>(), vec!["direct", "twitter"]);
+    assert_eq!(by_referrer["twitter"].clicks, 3);
+    assert_eq!(by_referrer["twitter"].volume_sol, 2.0);
+    assert_eq!(by_referrer["direct"].clicks, 2);
+
+    let daily = tracker.daily_clicks(BLINK, 7, now).await;
+    assert_eq!(daily, vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 2.0]);
+
+    // 90 days of history are kept
+    assert_eq!(tracker.purge_expired(now).await.unwrap(), 1);
+    assert_eq!(tracker.funnel(BLINK).await.clicks, 4);
+
+    assert_eq!(
+        BlinksHandler::format_blink_stats("Buy BONK", &tracker.funnel(BLINK).await, &daily, "en"),
+        "\nBuy BONK\n   👆 4 clicks · 25.0% converted · 2.000 SOL\n   7d ▅▁▁▁▅▁█\n"
+    );
+}
+
+#[tokio::test]
+async fn test_donation_is_tracked_from_impression_to_finalized_signature() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let rpc = Arc::new(RpcClient::new(harness.rpc.url()));
+    let tracker = Arc::new(
+        BlinkTracker::new()
+            .with_rpc(rpc.clone())
+            .with_confirmation_polling(std::time::Duration::from_millis(50), std::time::Duration::from_secs(10)),
+    );
+    let generator = Arc::new(BlinkGenerator::new("https://blinks.example.com".to_string(), SolanaNetwork::Devnet));
+    let executor = BlinkExecutor::new(harness.trading_engine.clone(), harness.wallet_manager.clone())
+        .with_jupiter(Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())))
+        .with_rpc(rpc.clone());
+    let server = ActionServer::new(generator.clone(), Arc::new(executor)).with_tracker(tracker.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = server.serve(listener).await;
+    });
+
+    let blink = generator.register_donation_blink(CREATOR.to_string(), vec![1.5], CREATOR.to_string()).await.unwrap();
+    assert!(generator.dial_to_url(&blink.blink_id, Some("twitter")).contains("%3Fref%3Dtwitter"));
+    let action = format!("{}/api/actions/{}", url, blink.blink_id);
+    let client = reqwest::Client::new();
+
+    // The referrer rides along from the link into every button
+    let metadata: Value = client.get(format!("{}?ref=twitter", action)).send().await.unwrap().json().await.unwrap();
+    let href = metadata["links"]["actions"][0]["href"].as_str().unwrap();
+    assert!(href.ends_with("?amount=1.5&ref=twitter"), "{}", href);
+
+    let donor = Keypair::new();
+    let posted: ActionPostResponse = client
+        .post(format!("{}?amount=1.5&ref=twitter", action))
+        .json(&json!({ "account": donor.pubkey().to_string() }))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    let next = posted.links.clone().expect("a tracked POST chains to the confirm endpoint").next;
+    assert_eq!(next.kind, "post");
+    assert!(next.href.starts_with(&format!("https://blinks.example.com/api/actions/{}/confirm?request=", blink.blink_id)));
+
+    // The wallet signs and sends, then reports the signature
+    let mut transaction: Transaction = bincode::deserialize(&base64::decode(&posted.transaction).unwrap()).unwrap();
+    let blockhash = transaction.message.recent_blockhash;
+    transaction.sign(&[&donor], blockhash);
+    let signature = rpc.send_transaction(&transaction).await.unwrap();
+    let confirm_path = next.href.trim_start_matches("https://blinks.example.com");
+    let completed: Value = client
+        .post(format!("{}{}", url, confirm_path))
+        .json(&json!({ "account": donor.pubkey().to_string(), "signature": signature.to_string() }))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(completed["type"], "completed");
+
+    let mut funnel = tracker.funnel(&blink.blink_id).await;
+    for _ in 0..100 {
+        if funnel.transactions_confirmed == 1 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        funnel = tracker.funnel(&blink.blink_id).await;
+    }
+    assert_eq!(funnel, BlinkFunnel {
+        impressions: 1,
+        clicks: 1,
+        transactions_requested: 1,
+        transactions_confirmed: 1,
+        volume_sol: 1.5,
+    });
+    assert_eq!(tracker.funnel_by_referrer(&blink.blink_id).await["twitter"].transactions_confirmed, 1);
+
+    // The same signature can't be claimed for another request
+    let request = tracker.click(&blink.blink_id, None).await.unwrap();
+    assert!(tracker.watch_confirmation(&blink.blink_id, &request, &signature.to_string()).await.is_err());
+}
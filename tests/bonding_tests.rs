@@ -0,0 +1,123 @@
+use chrono::{Duration, TimeZone, Utc};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::alerts::{
+    BondingAlertKind, BondingConfig, BondingCurveSource, BondingCurveState, BondingTracker, TradingVenue,
+};
+use crate::errors::Result;
+
+const USER: i64 = 777;
+const WATCHER: i64 = 888;
+const MINT: &str = "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr";
+
+fn state(progress: f64) -> BondingCurveState {
+    BondingCurveState { symbol: "POPCAT".to_string(), progress, complete: false }
+}
+
+fn kinds(notifications: &[crate::alerts::BondingNotification]) -> Vec<BondingAlertKind> {
+    notifications.iter().map(|n| n.kind.clone()).collect()
+}
+
+/// Replays a scripted series of curve readings
+struct ScriptedCurve {
+    readings: Mutex<Vec<BondingCurveState>>,
+}
+
+#[async_trait::async_trait]
+impl BondingCurveSource for ScriptedCurve {
+    async fn curve_state(&self, _mint: &str) -> Result<Option<BondingCurveState>> {
+        let mut readings = self.readings.lock().await;
+        Ok(if readings.is_empty() { None } else { Some(readings.remove(0)) })
+    }
+}
+
+#[tokio::test]
+async fn test_milestones_notify_once_per_user() {
+    let tracker = BondingTracker::new(BondingConfig::default(), None, None);
+    tracker.set_holdings(USER, &[MINT.to_string()]).await;
+    let start = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+
+    assert!(tracker.record(MINT, state(60.0), start).await.unwrap().is_empty());
+
+    let crossed = tracker.record(MINT, state(76.0), start + Duration::minutes(2)).await.unwrap();
+    assert_eq!(kinds(&crossed), vec![BondingAlertKind::Milestone { percent: 75.0 }]);
+
+    // Hovering around the milestone doesn't repeat it
+    for (i, progress) in [74.0, 77.0, 78.5].iter().enumerate() {
+        let at = start + Duration::minutes(4 + 2 * i as i64);
+        assert!(tracker.record(MINT, state(*progress), at).await.unwrap().is_empty());
+    }
+
+    // A watcher added later still gets the milestone once
+    tracker.watch(WATCHER, MINT).await;
+    let late = tracker.record(MINT, state(79.0), start + Duration::minutes(12)).await.unwrap();
+    assert_eq!(late.len(), 1);
+    assert_eq!(late[0].user_id, WATCHER);
+
+    // Jumping past 90 announces only the highest milestone crossed
+    let jump = tracker.record(MINT, state(93.0), start + Duration::minutes(14)).await.unwrap();
+    assert_eq!(jump.len(), 2);
+    assert!(jump.iter().all(|n| n.kind == BondingAlertKind::Milestone { percent: 90.0 }));
+}
+
+#[tokio::test]
+async fn test_stall_detected_only_after_window() {
+    let config = BondingConfig { stall_window: Duration::hours(6), ..BondingConfig::default() };
+    let tracker = BondingTracker::new(config, None, None);
+    tracker.set_holdings(USER, &[MINT.to_string()]).await;
+    let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+
+    tracker.record(MINT, state(40.0), start).await.unwrap();
+    tracker.record(MINT, state(42.0), start + Duration::hours(1)).await.unwrap();
+
+    // Flat for five hours since the last move: not stalled yet
+    let early = tracker.record(MINT, state(42.0), start + Duration::hours(6)).await.unwrap();
+    assert!(early.is_empty());
+
+    let stalled = tracker.record(MINT, state(42.0), start + Duration::hours(7)).await.unwrap();
+    assert_eq!(kinds(&stalled), vec![BondingAlertKind::Stalled { since: start + Duration::hours(1) }]);
+    assert!(tracker.record(MINT, state(42.0), start + Duration::hours(8)).await.unwrap().is_empty());
+
+    // Moving again resets the window, so a later stall alerts anew
+    tracker.record(MINT, state(45.0), start + Duration::hours(9)).await.unwrap();
+    assert!(tracker.record(MINT, state(45.0), start + Duration::hours(14)).await.unwrap().is_empty());
+    let again = tracker.record(MINT, state(45.0), start + Duration::hours(15)).await.unwrap();
+    assert_eq!(kinds(&again), vec![BondingAlertKind::Stalled { since: start + Duration::hours(9) }]);
+}
+
+#[tokio::test]
+async fn test_sharp_reversal_alerts() {
+    let tracker = BondingTracker::new(BondingConfig::default(), None, None);
+    tracker.set_holdings(USER, &[MINT.to_string()]).await;
+    let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+
+    tracker.record(MINT, state(55.0), start).await.unwrap();
+    tracker.record(MINT, state(50.0), start + Duration::minutes(10)).await.unwrap();
+    let reversal = tracker.record(MINT, state(43.0), start + Duration::minutes(20)).await.unwrap();
+    assert_eq!(kinds(&reversal), vec![BondingAlertKind::Reversal { peak: 55.0, current: 43.0 }]);
+}
+
+#[tokio::test]
+async fn test_migration_switches_venue() {
+    let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+    let source = Arc::new(ScriptedCurve {
+        readings: Mutex::new(vec![state(97.0), BondingCurveState { complete: true, ..state(100.0) }]),
+    });
+    let tracker = BondingTracker::new(BondingConfig::default(), Some(source), None);
+    tracker.set_holdings(USER, &[MINT.to_string()]).await;
+
+    let first = tracker.poll(start).await.unwrap();
+    assert_eq!(kinds(&first), vec![BondingAlertKind::Milestone { percent: 90.0 }]);
+    assert_eq!(tracker.venue(MINT).await, TradingVenue::PumpFunCurve);
+
+    let migrated = tracker.poll(start + Duration::minutes(2)).await.unwrap();
+    assert_eq!(kinds(&migrated), vec![BondingAlertKind::Migrated]);
+    assert_eq!(tracker.venue(MINT).await, TradingVenue::Jupiter);
+    assert!(tracker.progress(MINT).await.unwrap().migrated_at.is_some());
+
+    // Migrated curves are no longer polled
+    assert!(tracker.poll(start + Duration::minutes(4)).await.unwrap().is_empty());
+    let line = tracker.position_line(MINT, start + Duration::minutes(4)).await.unwrap();
+    assert!(line.contains("Migrated"));
+}
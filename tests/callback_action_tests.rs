@@ -0,0 +1,137 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use teloxide::types::InlineKeyboardButtonKind;
+
+use crate::bot::callback_action::{CallbackAction, MAX_CALLBACK_DATA_LEN};
+use crate::bot::handlers::QuickBuyHandler;
+
+const BONK_MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+const BASE58: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// A symbol or mint-like token, 1..=44 characters
+fn random_token(rng: &mut StdRng) -> String {
+    let len = if rng.gen_bool(0.5) { rng.gen_range(1..=10) } else { rng.gen_range(32..=44) };
+    (0..len).map(|_| BASE58[rng.gen_range(0..BASE58.len())] as char).collect()
+}
+
+/// Amounts as typed (0.05, 1.5) and down to a single lamport
+fn random_amount(rng: &mut StdRng) -> f64 {
+    if rng.gen_bool(0.5) {
+        rng.gen_range(1..=1_000) as f64 / 100.0
+    } else {
+        rng.gen_range(1..=100_000_000_000u64) as f64 / 1e9
+    }
+}
+
+fn random_action(rng: &mut StdRng) -> CallbackAction {
+    let amount_sol = random_amount(rng);
+    match rng.gen_range(0..6) {
+        0 => CallbackAction::QuickBuy { token: random_token(rng), amount_sol },
+        1 => CallbackAction::ConfirmQuickBuy { token: random_token(rng), amount_sol },
+        2 => CallbackAction::ConfirmHighImpact { token: random_token(rng), amount_sol },
+        3 => CallbackAction::ChooseQuickBuyToken { amount_sol },
+        4 => CallbackAction::RetryBuy { token: random_token(rng), amount_sol },
+        _ => CallbackAction::CancelQuickBuy,
+    }
+}
+
+#[test]
+fn test_round_trip_property() {
+    let mut rng = StdRng::seed_from_u64(789);
+
+    for _ in 0..5_000 {
+        let action = random_action(&mut rng);
+        let data = action.to_data();
+
+        assert!(data.len() <= MAX_CALLBACK_DATA_LEN, "{} is too long for Telegram", data);
+        assert_eq!(CallbackAction::parse(&data), Some(action.clone()), "{}", data);
+        assert!(!CallbackAction::is_expired(&data), "{}", data);
+
+        // Serializing what was parsed gives the same data back
+        assert_eq!(CallbackAction::parse(&data).unwrap().to_data(), data);
+    }
+}
+
+#[test]
+fn test_parse_known_data() {
+    assert_eq!(
+        CallbackAction::parse("qb:0.1:BONK"),
+        Some(CallbackAction::quick_buy("BONK", 0.1))
+    );
+    assert_eq!(
+        CallbackAction::parse(&format!("qbc:2.5:{}", BONK_MINT)),
+        Some(CallbackAction::ConfirmQuickBuy { token: BONK_MINT.to_string(), amount_sol: 2.5 })
+    );
+    assert_eq!(
+        CallbackAction::parse(&format!("qbi:0.5:{}", BONK_MINT)),
+        Some(CallbackAction::ConfirmHighImpact { token: BONK_MINT.to_string(), amount_sol: 0.5 })
+    );
+    assert_eq!(CallbackAction::parse("qbt:0.25"), Some(CallbackAction::ChooseQuickBuyToken { amount_sol: 0.25 }));
+    assert_eq!(CallbackAction::parse("qbx"), Some(CallbackAction::CancelQuickBuy));
+}
+
+#[test]
+fn test_amounts_are_written_to_lamport_precision() {
+    assert_eq!(CallbackAction::quick_buy("BONK", 0.1).to_data(), "qb:0.1:BONK");
+    assert_eq!(CallbackAction::quick_buy("BONK", 2.0).to_data(), "qb:2:BONK");
+    assert_eq!(CallbackAction::ChooseQuickBuyToken { amount_sol: 0.1234567891234 }.to_data(), "qbt:0.123456789");
+}
+
+#[test]
+fn test_malformed_data_is_rejected() {
+    for data in [
+        "qb:0.1:",
+        "qb::BONK",
+        "qb:abc:BONK",
+        "qb:-1:BONK",
+        "qb:0:BONK",
+        "qb:NaN:BONK",
+        "qb:inf:BONK",
+        "qb:0.1:BO NK",
+        "qb:0.1",
+        "qbt",
+        "qbt:0.1:BONK",
+        "qbx:1",
+    ] {
+        assert_eq!(CallbackAction::parse(data), None, "{}", data);
+        assert!(CallbackAction::is_expired(data), "{}", data);
+    }
+
+    let too_long = format!("qb:0.1:{}", "A".repeat(MAX_CALLBACK_DATA_LEN));
+    assert_eq!(CallbackAction::parse(&too_long), None);
+}
+
+#[test]
+fn test_legacy_formats_are_expired() {
+    for data in ["qbuy_0.1_BONK", "quick_buy_bonk", "qbuy_bonk_0.5", "qbuy_custom_0.1", "qbuy_small_abc", "qbuy_abc"] {
+        assert_eq!(CallbackAction::parse(data), None, "{}", data);
+        assert!(CallbackAction::is_expired(data), "{}", data);
+    }
+
+    // Other handlers' data isn't quick-buy data at all
+    for data in ["settings_menu", "setting:slip:300", "chartact:alert:abc", "refresh_balance", "qbert"] {
+        assert_eq!(CallbackAction::parse(data), None, "{}", data);
+        assert!(!CallbackAction::is_expired(data), "{}", data);
+    }
+}
+
+#[test]
+fn test_confirmation_buttons_round_trip() {
+    let keyboard = QuickBuyHandler::confirmation_keyboard(BONK_MINT, 3.0, "en");
+    let actions: Vec<_> = keyboard.inline_keyboard.iter()
+        .flatten()
+        .map(|button| match &button.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => CallbackAction::parse(data),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(actions, vec![
+        Some(CallbackAction::ConfirmQuickBuy { token: BONK_MINT.to_string(), amount_sol: 3.0 }),
+        Some(CallbackAction::CancelQuickBuy),
+    ]);
+
+    let text = QuickBuyHandler::confirmation_text("BONK", 3.0, 1.0, "en");
+    assert!(text.contains("3 SOL of BONK"), "{}", text);
+    assert!(text.contains("max trade size of 1 SOL"), "{}", text);
+}
@@ -0,0 +1,275 @@
+use crate::ai::GroqAnalyzer;
+use crate::api::pump_fun::PumpFunClient;
+use crate::api::{ApiTier, JupiterV6Client, QuoteRequestV6};
+use crate::bot::handlers::TradingHandler;
+use crate::errors::BotError;
+use crate::middleware::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use crate::monitoring::{AlertManager, AlertSeverity};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v";
+const COOL_DOWN: Duration = Duration::from_millis(150);
+
+/// Answers Jupiter, Pump.fun and Groq routes with 503 until it's healed; counts the calls
+#[derive(Default)]
+struct FlakyServer {
+    healthy: AtomicBool,
+    calls: AtomicUsize,
+}
+
+impl FlakyServer {
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn heal(&self) {
+        self.healthy.store(true, Ordering::SeqCst);
+    }
+}
+
+fn respond(server: &FlakyServer, body: serde_json::Value) -> Response {
+    server.calls.fetch_add(1, Ordering::SeqCst);
+    if server.healthy.load(Ordering::SeqCst) {
+        Json(body).into_response()
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "error": "upstream down" }))).into_response()
+    }
+}
+
+async fn quote(State(server): State<Arc<FlakyServer>>) -> Response {
+    respond(&server, json!({
+        "inputMint": SOL,
+        "inAmount": "1000000000",
+        "outputMint": USDC,
+        "outAmount": "150000000",
+        "otherAmountThreshold": "149250000",
+        "swapMode": "ExactIn",
+        "slippageBps": 50,
+        "platformFee": null,
+        "priceImpactPct": "0.001",
+        "routePlan": [],
+        "contextSlot": 287700100,
+        "timeTaken": 0.01
+    }))
+}
+
+async fn trending(State(server): State<Arc<FlakyServer>>) -> Response {
+    respond(&server, json!([]))
+}
+
+async fn chat(State(server): State<Arc<FlakyServer>>) -> Response {
+    respond(&server, json!({
+        "id": "chatcmpl-1",
+        "choices": [{ "message": { "role": "assistant", "content": "Quiet day." }, "finish_reason": "stop" }],
+        "usage": { "prompt_tokens": 10, "completion_tokens": 3, "total_tokens": 13 }
+    }))
+}
+
+async fn flaky_server() -> (String, Arc<FlakyServer>) {
+    let server = Arc::new(FlakyServer::default());
+    let app = Router::new()
+        .route("/v6/quote", get(quote))
+        .route("/tokens/trending", get(trending))
+        .route("/chat/completions", post(chat))
+        .with_state(server.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    (url, server)
+}
+
+fn breaker(name: &str, alerts: Option<Arc<AlertManager>>) -> Arc<CircuitBreaker> {
+    let breaker = CircuitBreaker::new(name.to_string(), CircuitBreakerConfig {
+        failure_threshold: 3,
+        timeout: COOL_DOWN,
+        success_threshold: 1,
+        half_open_max_probes: 1,
+    });
+    Arc::new(match alerts {
+        Some(alerts) => breaker.with_alerts(alerts),
+        None => breaker,
+    })
+}
+
+fn quote_request() -> QuoteRequestV6 {
+    QuoteRequestV6 {
+        input_mint: SOL.to_string(),
+        output_mint: USDC.to_string(),
+        amount: 1_000_000_000,
+        slippage_bps: 50,
+        swap_mode: None,
+        dexes: None,
+        exclude_dexes: None,
+        max_accounts: None,
+        quote_mint: None,
+        minimize_slippage: None,
+        only_direct_routes: None,
+    }
+}
+
+async fn fail() -> Result<(), &'static str> {
+    Err("down")
+}
+
+async fn succeed() -> Result<(), &'static str> {
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_breaker_opens_probes_and_closes() {
+    let breaker = breaker("test", None);
+
+    for _ in 0..3 {
+        assert!(breaker.execute(fail()).await.is_err());
+    }
+    assert_eq!(breaker.state().await, CircuitState::Open);
+    let calls = breaker.metrics().await.total_failures;
+    assert!(matches!(breaker.execute(succeed()).await, Err(crate::middleware::CircuitBreakerError::CircuitOpen)));
+    assert_eq!(breaker.metrics().await.total_failures, calls, "a rejected call never ran");
+
+    // After the cool-down one failed probe opens it again
+    tokio::time::sleep(COOL_DOWN + Duration::from_millis(50)).await;
+    assert!(breaker.execute(fail()).await.is_err());
+    assert_eq!(breaker.state().await, CircuitState::Open);
+
+    tokio::time::sleep(COOL_DOWN + Duration::from_millis(50)).await;
+    assert!(breaker.execute(succeed()).await.is_ok());
+    assert_eq!(breaker.state().await, CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_half_open_lets_one_probe_through_at_a_time() {
+    let breaker = breaker("test", None);
+    breaker.force_open().await;
+    tokio::time::sleep(COOL_DOWN + Duration::from_millis(50)).await;
+
+    let slow_probe = {
+        let breaker = breaker.clone();
+        tokio::spawn(async move {
+            breaker.execute(async {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                Ok::<_, &str>(())
+            }).await.is_ok()
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(breaker.state().await, CircuitState::HalfOpen);
+    assert!(breaker.execute(succeed()).await.is_err(), "second probe while the first is in flight");
+
+    assert!(slow_probe.await.unwrap());
+    assert_eq!(breaker.state().await, CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_jupiter_trips_fails_fast_and_recovers() {
+    let (url, server) = flaky_server().await;
+    let alerts = Arc::new(AlertManager::new());
+    let client = JupiterV6Client::new(ApiTier::Lite, None)
+        .with_base_url(&url)
+        .with_retry_backoff(Duration::from_millis(5))
+        .with_request_deadline(Duration::from_millis(50))
+        .with_circuit_breaker(breaker("jupiter", Some(alerts.clone())));
+
+    for _ in 0..3 {
+        let err = client.get_quote(quote_request()).await.unwrap_err();
+        assert!(err.to_string().contains("503"), "{}", err);
+    }
+
+    // Open: answered without touching the server
+    let calls = server.calls();
+    let err = client.get_quote(quote_request()).await.unwrap_err();
+    assert!(matches!(&err, BotError::ServiceUnavailable { service } if service == "jupiter"), "got {:?}", err);
+    assert_eq!(server.calls(), calls);
+    assert_eq!(
+        TradingHandler::failure_message("Trade", &err, "en"),
+        "⏳ Jupiter temporarily unavailable, retrying soon. Nothing was traded."
+    );
+
+    let active = alerts.get_active_alerts().await;
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].rule_id, "circuit_breaker_jupiter");
+    assert_eq!(active[0].severity, AlertSeverity::Critical);
+
+    // Jupiter comes back; the first call after the cool-down probes and closes the circuit
+    server.heal();
+    tokio::time::sleep(COOL_DOWN + Duration::from_millis(50)).await;
+    client.get_quote(quote_request()).await.unwrap();
+    client.get_quote(quote_request()).await.unwrap();
+    assert_eq!(server.calls(), calls + 2);
+
+    assert!(alerts.get_active_alerts().await.is_empty());
+    // Newest first
+    let titles: Vec<String> = alerts.get_alert_history(None).await.into_iter().map(|a| a.title).collect();
+    assert_eq!(titles, vec![
+        "Circuit breaker closed: jupiter",
+        "Circuit breaker half-open: jupiter",
+        "Circuit breaker open: jupiter",
+    ]);
+}
+
+#[tokio::test]
+async fn test_client_errors_dont_trip_it() {
+    let (url, _) = flaky_server().await;
+    let breaker = breaker("jupiter", None);
+    let client = JupiterV6Client::new(ApiTier::Lite, None)
+        .with_base_url(format!("{}/missing", url))
+        .with_circuit_breaker(breaker.clone());
+
+    // 404s mean Jupiter is up and answering
+    for _ in 0..5 {
+        let err = client.get_quote(quote_request()).await.unwrap_err();
+        assert!(err.to_string().contains("404"), "{}", err);
+    }
+    assert_eq!(breaker.state().await, CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_pump_fun_and_groq_have_their_own_breakers() {
+    let (url, server) = flaky_server().await;
+    let pump = PumpFunClient::new().unwrap()
+        .with_api_url(&url)
+        .with_circuit_breaker(breaker("pumpfun", None));
+    let groq = GroqAnalyzer::new("test-key".to_string())
+        .with_base_url(&url)
+        .with_fallback_model(None)
+        .with_circuit_breaker(breaker("groq", None));
+
+    for _ in 0..3 {
+        assert!(pump.get_trending(5).await.is_err());
+    }
+    let err = pump.get_trending(5).await.unwrap_err();
+    match err.downcast_ref::<BotError>() {
+        Some(BotError::ServiceUnavailable { service }) => assert_eq!(service, "pumpfun"),
+        other => panic!("expected pumpfun to be unavailable, got {:?}", other),
+    }
+
+    // Pump.fun being down doesn't stop Groq calls
+    let calls = server.calls();
+    assert!(groq.analyze_market_conditions().await.is_err());
+    assert_eq!(server.calls(), calls + 1);
+    for _ in 0..2 {
+        assert!(groq.analyze_market_conditions().await.is_err());
+    }
+    let err = groq.analyze_market_conditions().await.unwrap_err();
+    assert!(matches!(&err, BotError::ServiceUnavailable { service } if service == "groq"), "got {:?}", err);
+    assert_eq!(TradingHandler::unavailable_message("groq", "en"), "⏳ AI analysis temporarily unavailable, retrying soon.");
+
+    server.heal();
+    tokio::time::sleep(COOL_DOWN + Duration::from_millis(50)).await;
+    assert!(pump.get_trending(5).await.unwrap().is_empty());
+    assert_eq!(groq.analyze_market_conditions().await.unwrap(), "Quiet day.");
+}
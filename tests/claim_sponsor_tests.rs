@@ -0,0 +1,184 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::analytics::{FeeKind, FeeLedger};
+use crate::api::ClaimRecord;
+use crate::errors::Result;
+use crate::wallet::{
+    ClaimFunding, ClaimPlan, ClaimSponsor, ClaimSponsorConfig, ClaimSubmitter, RecipientBalance, SendTerms,
+    SponsorEvent, SponsorMode,
+};
+
+const SENDER: i64 = 515151;
+const WSOL: &str = "So11111111111111111111111111111111111111112";
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+/// Canned balances; unknown wallets hold nothing
+#[derive(Default)]
+struct FixtureBalances(HashMap<Pubkey, u64>);
+
+#[async_trait::async_trait]
+impl RecipientBalance for FixtureBalances {
+    async fn lamports(&self, wallet: &Pubkey) -> Result<u64> {
+        Ok(self.0.get(wallet).copied().unwrap_or(0))
+    }
+}
+
+struct LandingSubmitter;
+
+#[async_trait::async_trait]
+impl ClaimSubmitter for LandingSubmitter {
+    async fn submit(&self, plan: &ClaimPlan) -> Result<String> {
+        Ok(format!("sig-{}", plan.claim_id))
+    }
+}
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap()
+}
+
+fn config() -> ClaimSponsorConfig {
+    ClaimSponsorConfig { sponsor_wallet: Some(Pubkey::new_unique()), ..Default::default() }
+}
+
+fn fixture(config: ClaimSponsorConfig, balances: FixtureBalances) -> (ClaimSponsor, Arc<FeeLedger>) {
+    let ledger = Arc::new(FeeLedger::new());
+    (ClaimSponsor::new(config, Arc::new(balances), ledger.clone()), ledger)
+}
+
+fn terms(send_id: &str, mint: &str, amount: u64, mode: SponsorMode) -> SendTerms {
+    SendTerms { send_id: send_id.to_string(), sender_user_id: SENDER, token_mint: mint.to_string(), amount, mode }
+}
+
+fn claim(claim_id: &str, recipient: &Pubkey) -> ClaimRecord {
+    ClaimRecord {
+        claim_id: claim_id.to_string(),
+        recipient_public_key: recipient.to_string(),
+        claimed_amount: 0,
+        claimed_at: now(),
+        transaction_signature: String::new(),
+        ip_address: None,
+        user_agent: None,
+        country: None,
+    }
+}
+
+#[tokio::test]
+async fn test_sponsored_claim_is_booked_to_the_sender() {
+    let (sponsor, ledger) = fixture(config(), FixtureBalances::default());
+    let quote = sponsor.register_send(terms("send-1", BONK, 1_000_000, SponsorMode::Sponsor)).await.unwrap();
+    assert!(quote.applies);
+    // Fee plus the recipient's token account rent
+    assert_eq!(quote.cost_lamports, 2_049_280);
+    assert!(quote.describe().contains("sponsored"));
+
+    let recipient = Pubkey::new_unique();
+    let (plan, signature) = sponsor.claim("send-1", &claim("c1", &recipient), &recipient, &LandingSubmitter).await.unwrap();
+    assert_eq!(plan.funding, ClaimFunding::Sponsored { lamports: 2_049_280 });
+    assert_eq!(plan.fee_payer, sponsor.config().sponsor_wallet);
+    assert_eq!(plan.transaction_request().fee_payer, sponsor.config().sponsor_wallet.map(|p| p.to_string()));
+
+    let entries = ledger.entries(SENDER).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].kind, FeeKind::ClaimSponsorship);
+    assert_eq!(entries[0].signature.as_deref(), Some(signature.as_str()));
+
+    let events: Vec<SponsorEvent> = sponsor.audit_log().await.iter().map(|e| e.event).collect();
+    assert_eq!(events, vec![SponsorEvent::Reserved, SponsorEvent::Settled]);
+
+    // The same claim never gets paid for twice
+    let again = sponsor.prepare("send-1", &claim("c1", &recipient), &recipient, now()).await.unwrap();
+    assert!(matches!(again.funding, ClaimFunding::Blocked(_)));
+}
+
+#[tokio::test]
+async fn test_deduct_mode_repays_from_a_sol_claim() {
+    let (sponsor, ledger) = fixture(config(), FixtureBalances::default());
+    assert!(sponsor.quote(BONK, 1_000_000, SponsorMode::DeductFromAmount).is_err());
+    assert!(sponsor.quote(WSOL, 5_000, SponsorMode::DeductFromAmount).is_err());
+
+    let quote = sponsor.register_send(terms("send-2", WSOL, 100_000_000, SponsorMode::DeductFromAmount)).await.unwrap();
+    assert_eq!(quote.cost_lamports, 10_000);
+
+    let recipient = Pubkey::new_unique();
+    let plan = sponsor.prepare("send-2", &claim("c2", &recipient), &recipient, now()).await.unwrap();
+    assert_eq!(plan.funding, ClaimFunding::Deducted { lamports: 10_000 });
+    assert_eq!(plan.transaction_request().fee_payer_repayment_lamports, Some(10_000));
+
+    sponsor.settle(&plan, "sig-c2", now()).await;
+    // Nothing drawn from the budget or booked to the sender
+    assert_eq!(sponsor.spent_today(now()).await, 0);
+    assert!(ledger.entries(SENDER).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_caps_block_sponsorship_once_exhausted() {
+    let cost = 2_049_280;
+    let budget = ClaimSponsorConfig { daily_budget_lamports: cost * 2, ..config() };
+    let (sponsor, ledger) = fixture(budget, FixtureBalances::default());
+    sponsor.register_send(terms("send-3", BONK, 1_000_000, SponsorMode::Sponsor)).await.unwrap();
+
+    let mut funding = Vec::new();
+    for i in 0..3 {
+        let recipient = Pubkey::new_unique();
+        let plan = sponsor.prepare("send-3", &claim(&format!("c3-{}", i), &recipient), &recipient, now()).await.unwrap();
+        funding.push(plan.funding);
+    }
+    assert!(matches!(funding[0], ClaimFunding::Sponsored { .. }));
+    assert!(matches!(funding[1], ClaimFunding::Sponsored { .. }));
+    assert!(matches!(&funding[2], ClaimFunding::Blocked(reason) if reason.contains("budget")));
+    assert_eq!(sponsor.spent_today(now()).await, cost * 2);
+    assert!(ledger.entries(SENDER).await.is_empty());
+
+    // A failed claim gives its share back, and the next day starts fresh
+    let recipient = Pubkey::new_unique();
+    let tomorrow = now() + Duration::days(1);
+    let plan = sponsor.prepare("send-3", &claim("c3-next", &recipient), &recipient, tomorrow).await.unwrap();
+    assert!(matches!(plan.funding, ClaimFunding::Sponsored { .. }));
+    sponsor.release(&plan, "blockhash expired", tomorrow).await;
+    assert_eq!(sponsor.spent_today(tomorrow).await, 0);
+
+    // The per-claim cap applies before the budget
+    let tight = ClaimSponsorConfig { max_per_claim_lamports: 1_000_000, ..config() };
+    let (capped, _) = fixture(tight, FixtureBalances::default());
+    assert!(!capped.quote(BONK, 1_000_000, SponsorMode::Sponsor).unwrap().applies);
+    capped.register_send(terms("send-4", BONK, 1_000_000, SponsorMode::Sponsor)).await.unwrap();
+    let plan = capped.prepare("send-4", &claim("c4", &recipient), &recipient, now()).await.unwrap();
+    assert!(matches!(plan.funding, ClaimFunding::Blocked(_)));
+    assert_eq!(capped.audit_log().await[0].event, SponsorEvent::Refused);
+}
+
+#[tokio::test]
+async fn test_funded_recipient_never_uses_sponsorship() {
+    let recipient = Pubkey::new_unique();
+    let balances = FixtureBalances(HashMap::from([(recipient, 50_000_000)]));
+    let (sponsor, ledger) = fixture(config(), balances);
+    sponsor.register_send(terms("send-5", BONK, 1_000_000, SponsorMode::Sponsor)).await.unwrap();
+
+    let (plan, _) = sponsor.claim("send-5", &claim("c5", &recipient), &recipient, &LandingSubmitter).await.unwrap();
+    assert_eq!(plan.funding, ClaimFunding::Recipient);
+    assert_eq!(plan.fee_payer, None);
+    assert_eq!(sponsor.spent_today(Utc::now()).await, 0);
+    assert!(ledger.entries(SENDER).await.is_empty());
+    assert!(sponsor.audit_log().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_claims_check_ownership_and_rate() {
+    let (sponsor, _) = fixture(config(), FixtureBalances::default());
+    sponsor.register_send(terms("send-6", BONK, 1_000_000, SponsorMode::Sponsor)).await.unwrap();
+    let recipient = Pubkey::new_unique();
+
+    let stranger = Pubkey::new_unique();
+    assert!(sponsor.prepare("send-6", &claim("c6", &recipient), &stranger, now()).await.is_err());
+    assert!(sponsor.prepare("unknown", &claim("c6", &recipient), &recipient, now()).await.is_err());
+
+    for i in 0..3 {
+        let at = now() + Duration::minutes(i);
+        assert!(sponsor.prepare("send-6", &claim(&format!("c6-{}", i), &recipient), &recipient, at).await.is_ok());
+    }
+    assert!(sponsor.prepare("send-6", &claim("c6-3", &recipient), &recipient, now() + Duration::minutes(5)).await.is_err());
+    assert!(sponsor.prepare("send-6", &claim("c6-3", &recipient), &recipient, now() + Duration::minutes(61)).await.is_ok());
+}
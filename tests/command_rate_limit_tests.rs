@@ -0,0 +1,143 @@
+use crate::bot::{Command, TelegramBot};
+use crate::middleware::rate_limiter::{RateLimitConfig, RateLimitError, UserRateLimiter};
+use crate::testkit::TestHarness;
+use std::sync::Arc;
+use std::time::Duration;
+
+const USER: &str = "800001";
+
+/// Buckets that don't refill within a test
+fn limiter(burst: u32, trade_burst: u32) -> Arc<UserRateLimiter> {
+    Arc::new(
+        UserRateLimiter::new(RateLimitConfig { requests_per_minute: 1, burst_capacity: burst, ..RateLimitConfig::default() })
+            .with_trade_limits(RateLimitConfig { requests_per_minute: 1, burst_capacity: trade_burst, ..RateLimitConfig::for_trading() }),
+    )
+}
+
+/// Fire `n` commands at once; (allowed, throttled, throttled with a reply)
+async fn fire(limiter: &Arc<UserRateLimiter>, user: &str, n: usize, cost: u32, executes_trade: bool) -> (usize, usize, usize) {
+    let calls = (0..n).map(|_| {
+        let limiter = limiter.clone();
+        let user = user.to_string();
+        tokio::spawn(async move { limiter.check_command(&user, cost, executes_trade).await })
+    });
+    let mut counts = (0, 0, 0);
+    for result in futures::future::join_all(calls).await {
+        match result.unwrap() {
+            Ok(()) => counts.0 += 1,
+            Err(RateLimitError::Throttled { notify, .. }) => {
+                counts.1 += 1;
+                if notify {
+                    counts.2 += 1;
+                }
+            }
+            Err(e) => panic!("unexpected limiter error: {}", e),
+        }
+    }
+    counts
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_parallel_commands_are_charged_exactly() {
+    let limiter = limiter(50, 3);
+
+    assert_eq!(fire(&limiter, USER, 100, 1, false).await, (50, 50, 1));
+
+    let stats = limiter.get_command_stats(USER).await.unwrap().general;
+    assert_eq!(stats.total_requests, 100);
+    assert_eq!(stats.blocked_requests, 50);
+    assert_eq!(stats.available_tokens, 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_expensive_commands_drain_the_bucket_faster() {
+    let limiter = limiter(100, 3);
+
+    // 33 × 3 tokens fit in 100
+    assert_eq!(fire(&limiter, USER, 100, 3, false).await, (33, 67, 1));
+    assert_eq!(limiter.get_command_stats(USER).await.unwrap().general.available_tokens, 1);
+    // The last token still covers a cheap command, and only that
+    assert_eq!(fire(&limiter, USER, 100, 1, false).await, (1, 99, 0));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_trade_bucket_is_stricter_and_refusals_cost_nothing() {
+    let limiter = limiter(100, 3);
+
+    assert_eq!(fire(&limiter, USER, 100, 5, true).await, (3, 97, 1));
+
+    let stats = limiter.get_command_stats(USER).await.unwrap();
+    assert_eq!(stats.trading.total_requests, 100);
+    assert_eq!(stats.trading.blocked_requests, 97);
+    assert_eq!(stats.trading.available_tokens, 0);
+    // Only the three trades that went through were charged
+    assert_eq!(stats.general.available_tokens, 85);
+    assert_eq!(stats.general.blocked_requests, 0);
+
+    // Non-trading commands still get through
+    assert_eq!(fire(&limiter, USER, 10, 1, false).await, (10, 0, 0));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_admins_are_exempt() {
+    let harness = TestHarness::builder().admin(7).build().await.unwrap();
+    let limiter = Arc::new(UserRateLimiter::for_commands(&harness.config));
+
+    assert_eq!(fire(&limiter, "7", 100, 5, true).await, (100, 0, 0));
+    assert!(limiter.get_command_stats("7").await.is_none());
+    assert_eq!(fire(&limiter, USER, 100, 5, true).await.0, 3);
+}
+
+#[tokio::test]
+async fn test_one_reply_per_cooldown() {
+    // One token a second
+    let limiter = UserRateLimiter::new(RateLimitConfig { requests_per_minute: 60, burst_capacity: 1, ..RateLimitConfig::default() });
+
+    assert!(limiter.check_command(USER, 1, false).await.is_ok());
+    let retry_after = match limiter.check_command(USER, 1, false).await {
+        Err(RateLimitError::Throttled { retry_after, notify: true }) => retry_after,
+        other => panic!("expected a throttle with a reply, got {:?}", other),
+    };
+    assert!(retry_after > Duration::from_millis(900) && retry_after <= Duration::from_secs(1), "{:?}", retry_after);
+    assert!(matches!(limiter.check_command(USER, 1, false).await, Err(RateLimitError::Throttled { notify: false, .. })));
+
+    tokio::time::sleep(retry_after + Duration::from_millis(50)).await;
+    assert!(limiter.check_command(USER, 1, false).await.is_ok());
+    // A new cooldown gets its own reply
+    assert!(matches!(limiter.check_command(USER, 1, false).await, Err(RateLimitError::Throttled { notify: true, .. })));
+
+    assert_eq!(
+        TelegramBot::throttled_message("en", Duration::from_millis(1200)),
+        "⏳ Slow down: try again in 2s. Commands until then are ignored."
+    );
+}
+
+#[test]
+fn test_command_weights() {
+    assert_eq!(Command::Trending.cost(), 1);
+    assert_eq!(Command::Analyze(String::new()).cost(), 3);
+    assert_eq!(Command::Snipe(String::new()).cost(), 5);
+    assert!(Command::Snipe(String::new()).executes_trade());
+    assert!(!Command::Order(String::new()).executes_trade());
+    assert_eq!(Command::Panic(String::new()).cost(), 0);
+}
+
+#[tokio::test]
+async fn test_spamming_the_bot_gets_a_single_reply() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    harness.start_bot();
+    let user = 800_002;
+
+    // The harness allows 20 tokens; /help costs one
+    for _ in 0..25 {
+        harness.telegram.inject_message(user, "/help").await;
+    }
+    harness.telegram
+        .wait_for_text(user, "Slow down", Duration::from_secs(10))
+        .await
+        .expect("the 21st /help should be refused");
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let throttled = harness.telegram.texts_for(user).await.into_iter().filter(|text| text.contains("Slow down")).count();
+    assert_eq!(throttled, 1);
+}
@@ -0,0 +1,80 @@
+use crate::trading::{BudgetUrgency, ComputeBudget, ComputeBudgetConfig, PriorityFeeEstimator, MAX_COMPUTE_UNIT_LIMIT};
+use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    hash::Hash,
+    pubkey::Pubkey,
+    system_instruction,
+    transaction::Transaction,
+};
+
+#[test]
+fn test_limit_from_simulated_units() {
+    let config = ComputeBudgetConfig::default();
+
+    // Fixture: a two-hop route that consumed 180k units in simulation
+    let standard = ComputeBudget::from_simulation(Some(180_000), 10_000, BudgetUrgency::Standard, &config);
+    assert_eq!(standard.unit_limit, 216_000);
+    assert_eq!(standard.simulated_units, Some(180_000));
+    // 216k units at 10k µlamports each
+    assert_eq!(standard.priority_fee_lamports(), 2_160);
+
+    let urgent = ComputeBudget::from_simulation(Some(180_000), 10_000, BudgetUrgency::Urgent, &config);
+    assert_eq!(urgent.unit_limit, 243_000);
+
+    // Never above what the runtime accepts
+    let huge = ComputeBudget::from_simulation(Some(1_300_000), 10_000, BudgetUrgency::Standard, &config);
+    assert_eq!(huge.unit_limit, MAX_COMPUTE_UNIT_LIMIT);
+
+    assert_eq!(PriorityFeeEstimator::percentile_of(vec![0, 100, 400, 200, 300], 75.0), Some(300));
+    assert_eq!(PriorityFeeEstimator::percentile_of(vec![0, 0], 75.0), None);
+}
+
+#[test]
+fn test_fallback_limit_without_simulation() {
+    let config = ComputeBudgetConfig::default();
+
+    let fallback = ComputeBudget::from_simulation(None, 5_000, BudgetUrgency::Standard, &config);
+    assert_eq!(fallback.unit_limit, config.fallback_limit);
+    assert_eq!(fallback.simulated_units, None);
+
+    // A zero-unit simulation is not a measurement
+    let empty = ComputeBudget::from_simulation(Some(0), 5_000, BudgetUrgency::Urgent, &config);
+    assert_eq!(empty.unit_limit, config.fallback_limit);
+}
+
+#[test]
+fn test_budget_prepended_once_across_retries() {
+    let payer = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let config = ComputeBudgetConfig::default();
+    let budget = ComputeBudget::from_simulation(Some(180_000), 10_000, BudgetUrgency::Standard, &config);
+
+    // Swap as returned by the aggregator, with its own default budget
+    let original = Transaction::new_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(1_400_000),
+            system_instruction::transfer(&payer, &recipient, 1_000),
+        ],
+        Some(&payer),
+    );
+
+    // Each broadcast retry re-applies the budget to the previous attempt
+    let mut transaction = original;
+    for _ in 0..3 {
+        transaction.message.recent_blockhash = Hash::new_unique();
+        transaction = budget.apply_to_transaction(&transaction);
+    }
+
+    let message = &transaction.message;
+    let program = |index: usize| message.account_keys[message.instructions[index].program_id_index as usize];
+    let budget_instructions = (0..message.instructions.len())
+        .filter(|index| program(*index) == compute_budget::id())
+        .count();
+
+    assert_eq!(budget_instructions, 2);
+    assert_eq!(message.instructions.len(), 3);
+    assert_eq!(message.instructions[0].data, ComputeBudgetInstruction::set_compute_unit_limit(216_000).data);
+    assert_eq!(message.instructions[1].data, ComputeBudgetInstruction::set_compute_unit_price(10_000).data);
+    assert_eq!(program(2), solana_sdk::system_program::id());
+    assert_eq!(message.account_keys[0], payer);
+}
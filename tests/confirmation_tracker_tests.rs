@@ -0,0 +1,215 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{signature::{Keypair, Signature}, signer::Signer, system_instruction, transaction::Transaction};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::bot::handlers::TradingHandler;
+use crate::testkit::{MockRpc, TestHarness};
+use crate::trading::{
+    ConfirmationConfig, ConfirmationStage, ConfirmationTracker, ExecutionReport, ExecutionType, NetworkCongestion, Order,
+    OrderExecution, OrderMarketConditions, TokenResolver, TradeHistory, TradeResult, TriggerReason,
+};
+use crate::wallet::TransactionPriority;
+
+const USER_ID: i64 = 763_001;
+const SLOT: u64 = 250_000_000;
+/// `lastValidBlockHeight` of every blockhash the mock RPC hands out
+const LAST_VALID_BLOCK_HEIGHT: u64 = SLOT + 150;
+const FEE_LAMPORTS: u64 = 5_000;
+
+fn tracker(rpc: &MockRpc, timeout: Duration) -> ConfirmationTracker {
+    ConfirmationTracker::new(
+        Arc::new(RpcClient::new(rpc.url())),
+        ConfirmationConfig { ws_url: None, poll_interval: Duration::from_millis(50), timeout },
+    )
+}
+
+/// Send a small transfer to the mock cluster
+async fn send_transfer(rpc: &MockRpc) -> Signature {
+    let client = RpcClient::new(rpc.url());
+    let payer = Keypair::new();
+    let blockhash = client.get_latest_blockhash().await.unwrap();
+    let transfer = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000);
+    let transaction = Transaction::new_signed_with_payer(&[transfer], Some(&payer.pubkey()), &[&payer], blockhash);
+    client.send_transaction(&transaction).await.unwrap()
+}
+
+/// Serve the landed transaction with the fee it paid
+async fn serve_fee(rpc: &MockRpc, signature: &Signature) {
+    rpc.set_response("getTransaction", Some(&signature.to_string()), json!({
+        "slot": SLOT,
+        "blockTime": null,
+        "transaction": {
+            "signatures": [signature.to_string()],
+            "message": {
+                "header": { "numRequiredSignatures": 1, "numReadonlySignedAccounts": 0, "numReadonlyUnsignedAccounts": 1 },
+                "accountKeys": [Keypair::new().pubkey().to_string(), "11111111111111111111111111111111"],
+                "recentBlockhash": "11111111111111111111111111111111",
+                "instructions": [],
+            },
+        },
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": FEE_LAMPORTS,
+            "preBalances": [1_000_000, 1],
+            "postBalances": [995_000, 1],
+            "innerInstructions": [],
+            "logMessages": [],
+            "preTokenBalances": [],
+            "postTokenBalances": [],
+            "rewards": [],
+        },
+    })).await;
+}
+
+/// Every stage sent until the tracker stops
+async fn stages(mut receiver: mpsc::Receiver<ConfirmationStage>) -> Vec<ConfirmationStage> {
+    let mut seen = Vec::new();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(stage) = receiver.recv().await {
+            seen.push(stage);
+        }
+    })
+    .await
+    .expect("tracking should stop");
+    seen
+}
+
+#[tokio::test]
+async fn test_landed_transaction_moves_from_processing_to_finalized() {
+    let rpc = MockRpc::start().await.unwrap();
+    rpc.set_confirmation_delay(Duration::from_millis(200)).await;
+    rpc.set_finalization_delay(Duration::from_millis(300)).await;
+    let signature = send_transfer(&rpc).await;
+    serve_fee(&rpc, &signature).await;
+    let tracker = tracker(&rpc, Duration::from_secs(10));
+
+    let seen = stages(tracker.track(&signature.to_string(), None).unwrap()).await;
+    assert_eq!(seen, vec![
+        ConfirmationStage::Processing,
+        ConfirmationStage::Confirmed { slot: SLOT, fee_lamports: Some(FEE_LAMPORTS) },
+        ConfirmationStage::Finalized { slot: SLOT, fee_lamports: Some(FEE_LAMPORTS) },
+    ]);
+    assert_eq!(tracker.stage(&signature.to_string()).await, seen.last().cloned());
+    assert!(seen[2].failure().is_none());
+    // The fee was read once and carried into the finalized stage
+    let fee_reads = rpc.methods_called().await.iter().filter(|method| *method == "getTransaction").count();
+    assert_eq!(fee_reads, 1);
+}
+
+#[tokio::test]
+async fn test_unlanded_transaction_expires_once_its_blockhash_lapses() {
+    let rpc = MockRpc::start().await.unwrap();
+    let signature = Signature::new_unique().to_string();
+
+    // Still within the blockhash's lifetime: never reported as dropped
+    rpc.set_response("getBlockHeight", None, json!(LAST_VALID_BLOCK_HEIGHT)).await;
+    let short = tracker(&rpc, Duration::from_millis(400));
+    let seen = stages(short.track(&signature, None).unwrap()).await;
+    assert_eq!(seen, vec![ConfirmationStage::Processing]);
+
+    // One block past it, the transaction can no longer land
+    rpc.set_response("getBlockHeight", None, json!(LAST_VALID_BLOCK_HEIGHT + 1)).await;
+    let seen = stages(tracker(&rpc, Duration::from_secs(10)).track(&signature, None).unwrap()).await;
+    assert_eq!(seen, vec![ConfirmationStage::Processing, ConfirmationStage::Expired]);
+    assert!(seen[1].failure().unwrap().contains("blockhash lapsed"));
+
+    // An explicit limit is used as given
+    let seen = stages(short.track(&signature, Some(LAST_VALID_BLOCK_HEIGHT + 5)).unwrap()).await;
+    assert_eq!(seen, vec![ConfirmationStage::Processing]);
+}
+
+#[tokio::test]
+async fn test_error_status_is_reported_as_failed() {
+    let rpc = MockRpc::start().await.unwrap();
+    let signature = send_transfer(&rpc).await;
+    rpc.fail_signature(&signature, "slippage exceeded").await;
+
+    let tracker = tracker(&rpc, Duration::from_secs(10));
+    let seen = stages(tracker.track(&signature.to_string(), None).unwrap()).await;
+    assert_eq!(seen.len(), 2);
+    let ConfirmationStage::Failed { slot, error } = &seen[1] else {
+        panic!("expected a failed stage, got {:?}", seen[1]);
+    };
+    assert_eq!(*slot, SLOT);
+    assert!(error.contains("custom program error"), "{}", error);
+    assert!(seen[1].failure().unwrap().starts_with("Failed on chain"));
+
+    assert!(tracker.track("not-a-signature", None).is_err());
+}
+
+#[tokio::test]
+async fn test_final_status_is_written_to_order_execution_and_trade_history() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&mint, 1.0).build().await.unwrap();
+    let signature = Signature::new_unique().to_string();
+    harness.rpc.set_response("getBlockHeight", None, json!(LAST_VALID_BLOCK_HEIGHT + 1)).await;
+
+    let order = Order::create_stop_loss(USER_ID, mint, Decimal::new(5, 1), Decimal::from(1_000));
+    let order_id = harness.order_manager.create_order(order).await.unwrap();
+    let execution = OrderExecution {
+        execution_id: "exec-confirm".to_string(),
+        order_id: order_id.clone(),
+        executed_at: Utc::now(),
+        execution_type: ExecutionType::StopMarket,
+        trigger_reason: TriggerReason::PriceConditionMet,
+        price_at_execution: Decimal::new(5, 1),
+        amount_executed: Decimal::from(1_000),
+        slippage_bps: 50,
+        gas_used: 0,
+        gas_price: 0,
+        transaction_signature: Some(signature.clone()),
+        market_conditions: OrderMarketConditions {
+            token_price: Decimal::new(5, 1),
+            bid_ask_spread_bps: 10,
+            volume_24h: None,
+            volatility: None,
+            liquidity_depth: None,
+            network_congestion: NetworkCongestion { average_fee: 5_000, median_confirmation_time: 1, mempool_size: None },
+        },
+        success: true,
+        error_message: None,
+        report: ExecutionReport::default(),
+    };
+    harness.db
+        .insert_order_execution(&execution.execution_id, &order_id, &serde_json::to_string(&execution).unwrap())
+        .await
+        .unwrap();
+    harness.order_manager.load_active_orders().await.unwrap();
+
+    let tracker = tracker(&harness.rpc, Duration::from_secs(10)).with_orders(harness.order_manager.clone());
+    let seen = stages(tracker.track(&signature, None).unwrap()).await;
+    assert_eq!(seen.last(), Some(&ConfirmationStage::Expired));
+
+    let history = harness.order_manager.get_order_history(&order_id).await;
+    assert_eq!(history[0].report.confirmation, Some(ConfirmationStage::Expired));
+    assert!(!history[0].success);
+    assert!(history[0].error_message.as_deref().unwrap().contains("Expired"));
+    assert!(!harness.order_manager.record_confirmation("unknownSig", &ConfirmationStage::Expired).await.unwrap());
+
+    // Trade results keep the stage in their execution report
+    let mut trades = TradeHistory::new();
+    trades.add_trade(TradeResult::buy(signature.clone(), 1_000.0, 0.5, 0.0005));
+    let finalized = ConfirmationStage::Finalized { slot: SLOT, fee_lamports: Some(FEE_LAMPORTS) };
+    assert!(trades.record_confirmation(&signature, finalized.clone()));
+    assert!(!trades.record_confirmation("unknownSig", finalized.clone()));
+    assert_eq!(trades.trades[&signature].execution.confirmation, Some(finalized));
+}
+
+#[test]
+fn test_progress_lines_and_retry_priority() {
+    let finalized = TradingHandler::confirmation_line(&ConfirmationStage::Finalized { slot: SLOT, fee_lamports: Some(FEE_LAMPORTS) }, "en");
+    assert!(finalized.contains("Finalized in slot 250000000"), "{}", finalized);
+    assert!(finalized.contains("fee"), "{}", finalized);
+    let confirmed = TradingHandler::confirmation_line(&ConfirmationStage::Confirmed { slot: SLOT, fee_lamports: None }, "en");
+    assert!(!confirmed.contains("fee"), "{}", confirmed);
+    assert!(TradingHandler::confirmation_line(&ConfirmationStage::Expired, "en").contains("Nothing was traded"));
+
+    assert_eq!(TransactionPriority::Normal.raised(), TransactionPriority::High);
+    assert_eq!(TransactionPriority::Critical.raised(), TransactionPriority::Critical);
+}
@@ -0,0 +1,248 @@
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::alerts::{AlertAction, PriceAlert};
+use crate::api::convex::{ConvexMirror, ConvexTable, ConvexUserSnapshot};
+use crate::bot::convex_migration::{
+    ConvexMigration, ConvexSource, ImportOutcome, MigrationSkip, MigrationTarget,
+};
+use crate::bot::preferences::{RiskProfile, UserSettings};
+use crate::errors::Result;
+use crate::trading::{DCAInterval, DCAStrategy};
+
+const USER: i64 = 424242;
+const WALLET: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCaXpRjrq8jPUnZ8yw2";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCaXpRjrq8jPUnZ8yw2";
+
+fn fixture() -> ConvexUserSnapshot {
+    serde_json::from_value(json!({
+        "user": {
+            "_id": "u1",
+            "telegramId": USER,
+            "username": "webuser",
+            "settings": {
+                "defaultSlippage": 0.5,
+                "maxPositionSize": "2.5",
+                "autoCompound": true,
+                "riskLevel": "aggressive",
+                "notifications": { "trades": true, "alerts": true, "daily": true }
+            }
+        },
+        "wallets": [
+            { "_id": "w1", "address": WALLET, "type": "hot", "label": "Main", "isActive": true },
+            { "_id": "w2", "address": "not-a-wallet", "type": "imported", "isActive": true }
+        ],
+        "alerts": [
+            {
+                "_id": "a1", "name": "BONK breakout", "type": "price", "isActive": true,
+                "condition": { "target": BONK, "metric": "price", "operator": "above", "value": "0.00004" },
+                "actions": ["notify", "execute_trade"], "cooldown": 300
+            },
+            {
+                "_id": "a2", "name": "BONK dump", "type": "price", "isActive": true,
+                "condition": { "target": BONK, "metric": "price", "operator": "change", "value": "-15", "timeframe": "1h" },
+                "actions": ["notify"]
+            },
+            {
+                "_id": "a3", "name": "Whale watch", "type": "whale", "isActive": true,
+                "condition": { "target": BONK, "metric": "transfer", "operator": "above", "value": "1000000" },
+                "actions": ["notify"]
+            },
+            {
+                "_id": "a4", "name": "Exact price", "type": "price", "isActive": true,
+                "condition": { "target": BONK, "metric": "price", "operator": "equals", "value": "0.00003" },
+                "actions": ["notify"]
+            },
+            {
+                "_id": "a5", "name": "Old alert", "type": "price", "isActive": true,
+                "condition": { "target": BONK, "metric": "price", "operator": "below", "value": "0.00001" },
+                "actions": ["notify"], "expiresAt": 1_600_000_000_000_i64
+            },
+            {
+                "_id": "a6", "name": "Typo", "type": "price", "isActive": true,
+                "condition": { "target": BONK, "metric": "price", "operator": "below", "value": "cheap" },
+                "actions": ["notify"]
+            }
+        ],
+        "dcaStrategies": [
+            {
+                "_id": "d1", "name": "Daily BONK", "isActive": true, "isPaused": false,
+                "tokenIn": { "mint": USDC, "symbol": "USDC" },
+                "tokenOut": { "mint": BONK, "symbol": "BONK" },
+                "amount": "10", "frequency": { "type": "interval", "value": "1d" },
+                "maxInvestment": "300"
+            },
+            {
+                "_id": "d2", "name": "Half-hourly", "isActive": true, "isPaused": false,
+                "tokenIn": { "mint": USDC, "symbol": "USDC" },
+                "tokenOut": { "mint": BONK, "symbol": "BONK" },
+                "amount": "1", "frequency": { "type": "cron", "value": "*/30 * * * *" }
+            },
+            {
+                "_id": "d3", "name": "Dip buyer", "isActive": true, "isPaused": false,
+                "tokenIn": { "mint": USDC, "symbol": "USDC" },
+                "tokenOut": { "mint": BONK, "symbol": "BONK" },
+                "amount": "lots", "frequency": { "type": "interval", "value": "1h" }
+            },
+            {
+                "_id": "d4", "name": "Retired", "isActive": false, "isPaused": false,
+                "tokenIn": { "mint": USDC, "symbol": "USDC" },
+                "tokenOut": { "mint": BONK, "symbol": "BONK" },
+                "amount": "5", "frequency": { "type": "interval", "value": "1w" }
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+/// Serves fixture snapshots and applies mirror marks back onto them like Convex would
+#[derive(Default)]
+struct FixtureConvex {
+    users: Mutex<HashMap<i64, ConvexUserSnapshot>>,
+    marks: Mutex<Vec<(ConvexTable, String, String)>>,
+}
+
+impl FixtureConvex {
+    fn with(snapshot: ConvexUserSnapshot) -> Self {
+        let source = Self::default();
+        source.users.try_lock().unwrap().insert(snapshot.user.telegram_id, snapshot);
+        source
+    }
+}
+
+#[async_trait::async_trait]
+impl ConvexSource for FixtureConvex {
+    async fn user_snapshot(&self, telegram_id: i64) -> Result<Option<ConvexUserSnapshot>> {
+        Ok(self.users.lock().await.get(&telegram_id).cloned())
+    }
+
+    async fn unmirrored_users(&self, limit: usize) -> Result<Vec<i64>> {
+        Ok(self.users.lock().await.values()
+            .filter(|s| s.user.mirror.is_none())
+            .map(|s| s.user.telegram_id)
+            .take(limit)
+            .collect())
+    }
+
+    async fn mark_mirrored(&self, table: ConvexTable, id: &str, native_id: &str) -> Result<()> {
+        self.marks.lock().await.push((table, id.to_string(), native_id.to_string()));
+        let mirror = Some(ConvexMirror { native_id: native_id.to_string(), mirrored_at: 0 });
+        for snapshot in self.users.lock().await.values_mut() {
+            match table {
+                ConvexTable::Users if snapshot.user.id == id => snapshot.user.mirror = mirror.clone(),
+                ConvexTable::Wallets => snapshot.wallets.iter_mut().filter(|w| w.id == id).for_each(|w| w.mirror = mirror.clone()),
+                ConvexTable::Alerts => snapshot.alerts.iter_mut().filter(|a| a.id == id).for_each(|a| a.mirror = mirror.clone()),
+                ConvexTable::DcaStrategies => snapshot.dca_strategies.iter_mut().filter(|d| d.id == id).for_each(|d| d.mirror = mirror.clone()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// In-memory native stores keyed like the real ones
+#[derive(Default)]
+struct RecordingTarget {
+    preferences: Mutex<HashMap<i64, UserSettings>>,
+    alerts: Mutex<HashMap<String, PriceAlert>>,
+    strategies: Mutex<HashMap<String, DCAStrategy>>,
+    wallets: Mutex<Vec<(i64, String)>>,
+}
+
+#[async_trait::async_trait]
+impl MigrationTarget for RecordingTarget {
+    async fn apply_preferences(&self, user_id: i64, preferences: UserSettings) -> Result<bool> {
+        Ok(self.preferences.lock().await.insert(user_id, preferences).is_none())
+    }
+
+    async fn upsert_alert(&self, alert: PriceAlert) -> Result<bool> {
+        Ok(self.alerts.lock().await.insert(alert.alert_id.clone(), alert).is_none())
+    }
+
+    async fn upsert_strategy(&self, strategy: DCAStrategy) -> Result<bool> {
+        Ok(self.strategies.lock().await.insert(strategy.strategy_id.clone(), strategy).is_none())
+    }
+
+    async fn link_watch_only(&self, user_id: i64, address: &str, _label: Option<String>) -> Result<bool> {
+        let mut wallets = self.wallets.lock().await;
+        let link = (user_id, address.to_string());
+        if wallets.contains(&link) {
+            return Ok(false);
+        }
+        wallets.push(link);
+        Ok(true)
+    }
+}
+
+#[tokio::test]
+async fn test_rerun_updates_instead_of_duplicating() {
+    let source = Arc::new(FixtureConvex::with(fixture()));
+    let target = Arc::new(RecordingTarget::default());
+    let migration = ConvexMigration::new(source.clone(), target.clone());
+
+    let first = migration.migrate_user(USER).await.unwrap();
+    // Settings, one wallet, two alerts, one strategy
+    assert_eq!(first.created(), 5);
+    assert_eq!(first.updated(), 0);
+    assert_eq!(source.marks.lock().await.len(), 5);
+
+    let second = migration.migrate_user(USER).await.unwrap();
+    assert_eq!(second.created(), 0);
+    assert_eq!(second.updated(), 5);
+    assert_eq!(second.skipped, first.skipped);
+
+    // Nothing duplicated natively, and already-mirrored records aren't re-tagged
+    assert_eq!(target.alerts.lock().await.len(), 2);
+    assert_eq!(target.strategies.lock().await.len(), 1);
+    assert_eq!(target.wallets.lock().await.len(), 1);
+    assert_eq!(source.marks.lock().await.len(), 5);
+
+    let native_ids: Vec<String> = second.imported.iter().map(|r| r.native_id.clone()).collect();
+    assert_eq!(first.imported.iter().map(|r| r.native_id.clone()).collect::<Vec<_>>(), native_ids);
+    assert!(native_ids.contains(&"convex-a1".to_string()));
+    assert!(native_ids.contains(&"convex-d1".to_string()));
+    assert!(migration.report(USER).await.is_some_and(|r| r.updated() == 5));
+
+    // The user is mirrored, so the batch has nobody left
+    let batch = migration.migrate_batch(10).await.unwrap();
+    assert!(batch.reports.is_empty() && batch.failed.is_empty());
+}
+
+#[tokio::test]
+async fn test_skip_reasons_for_unmappable_records() {
+    let target = Arc::new(RecordingTarget::default());
+    let migration = ConvexMigration::new(Arc::new(FixtureConvex::with(fixture())), target.clone());
+    let report = migration.migrate_user(USER).await.unwrap();
+
+    let reasons: HashMap<&str, &MigrationSkip> = report.skipped.iter()
+        .map(|r| (r.convex_id.as_str(), &r.reason))
+        .collect();
+    assert_eq!(reasons.len(), 8);
+    assert_eq!(reasons["w2"], &MigrationSkip::InvalidAddress("not-a-wallet".to_string()));
+    assert_eq!(reasons["a3"], &MigrationSkip::UnsupportedAlertType("whale".to_string()));
+    assert_eq!(reasons["a4"], &MigrationSkip::UnsupportedOperator("equals".to_string()));
+    assert_eq!(reasons["a5"], &MigrationSkip::Expired);
+    assert_eq!(reasons["a6"], &MigrationSkip::InvalidValue("cheap".to_string()));
+    assert_eq!(reasons["d2"], &MigrationSkip::UnsupportedSchedule("cron */30 * * * *".to_string()));
+    assert_eq!(reasons["d3"], &MigrationSkip::InvalidAmount("lots".to_string()));
+    assert_eq!(reasons["d4"], &MigrationSkip::Inactive);
+
+    // Trade actions are dropped and noted, never carried over
+    let breakout = target.alerts.lock().await["convex-a1"].clone();
+    assert!(breakout.actions.iter().all(|a| matches!(a, AlertAction::Notify)));
+    let a1 = report.imported.iter().find(|r| r.convex_id == "a1").unwrap();
+    assert_eq!(a1.outcome, ImportOutcome::Created);
+    assert!(a1.notes.iter().any(|n| n.contains("execute_trade")));
+
+    let strategy = target.strategies.lock().await["convex-d1"].clone();
+    assert!(matches!(strategy.interval, DCAInterval::Daily));
+    assert_eq!(strategy.max_executions, Some(30));
+
+    let preferences = target.preferences.lock().await[&USER].clone();
+    assert_eq!(preferences.slippage_bps, 50);
+    assert_eq!(preferences.risk_profile, RiskProfile::Aggressive);
+    assert!(preferences.notifications.daily_summary);
+}
@@ -0,0 +1,323 @@
+use crate::bot::automation_auth::{AutomationAction, AutomationAuthority, GrantScope};
+use crate::bot::convex_webhook::{
+    EventResult, EventStatus, OrderTarget, PortfolioSnapshot, PortfolioTarget, TradeSide, TradeTarget, UserNotifier,
+    WebhookDispatcher, WebhookEnvelope, WebhookEvent, WebhookResponse, WebhookServer,
+};
+use crate::cache::InMemorySessionStore;
+use crate::testkit::TestHarness;
+use crate::trading::DCAStrategy;
+use rust_decimal::Decimal;
+use crate::errors::Result;
+use crate::trading::{TokenResolver, TradeResult};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+const USER_ID: i64 = 807_001;
+const SECRET: &str = "convex-webhook-secret";
+
+/// A call that reached a downstream component
+#[derive(Debug, Clone, PartialEq)]
+enum Call {
+    Trade { telegram_id: i64, mint: String, side: TradeSide, amount: f64 },
+    Cancel { telegram_id: i64, order_id: String },
+    Sync { telegram_id: i64 },
+    Notify { telegram_id: i64, text: String },
+}
+
+/// Stands in for every component and reports each call on a channel
+struct Spy(mpsc::UnboundedSender<Call>);
+
+#[async_trait]
+impl TradeTarget for Spy {
+    async fn execute_trade(&self, telegram_id: i64, token_mint: &str, side: TradeSide, amount: f64) -> Result<TradeResult> {
+        self.0.send(Call::Trade { telegram_id, mint: token_mint.to_string(), side, amount }).unwrap();
+        // Slow enough for a concurrent retry to land mid-trade
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        Ok(TradeResult::buy("5igSig".to_string(), 1_000.0, amount, 0.0005))
+    }
+}
+
+#[async_trait]
+impl OrderTarget for Spy {
+    async fn cancel_order(&self, telegram_id: i64, order_id: &str) -> Result<bool> {
+        self.0.send(Call::Cancel { telegram_id, order_id: order_id.to_string() }).unwrap();
+        Ok(order_id != "missing")
+    }
+}
+
+#[async_trait]
+impl PortfolioTarget for Spy {
+    async fn sync_portfolio(&self, telegram_id: i64) -> Result<PortfolioSnapshot> {
+        self.0.send(Call::Sync { telegram_id }).unwrap();
+        Ok(PortfolioSnapshot {
+            wallet_address: "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string(),
+            total_value_usd: 1_250.0,
+            total_value_sol: 8.5,
+            holdings: 3,
+        })
+    }
+}
+
+#[async_trait]
+impl UserNotifier for Spy {
+    async fn notify(&self, telegram_id: i64, text: &str) -> Result<()> {
+        self.0.send(Call::Notify { telegram_id, text: text.to_string() }).unwrap();
+        Ok(())
+    }
+}
+
+struct Webhook {
+    url: String,
+    calls: mpsc::UnboundedReceiver<Call>,
+    /// Token covering trades up to 1 SOL
+    trade_token: String,
+}
+
+impl Webhook {
+    async fn post(&self, body: Value) -> (StatusCode, Value) {
+        let response = reqwest::Client::new()
+            .post(&self.url)
+            .bearer_auth(SECRET)
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        (response.status(), response.json().await.unwrap())
+    }
+
+    async fn results(&self, events: Value) -> Vec<EventResult> {
+        let (status, body) = self.post(json!({ "events": events })).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        serde_json::from_value::<WebhookResponse>(body).unwrap().results
+    }
+
+    fn drain(&mut self) -> Vec<Call> {
+        let mut calls = Vec::new();
+        while let Ok(call) = self.calls.try_recv() {
+            calls.push(call);
+        }
+        calls
+    }
+}
+
+async fn serve(full: bool) -> Webhook {
+    let authority = Arc::new(AutomationAuthority::default());
+    let scope = GrantScope { action: AutomationAction::Trade, max_notional_sol: 1.0, expires_at: Utc::now() + Duration::days(1) };
+    let (_, trade_token) = authority.issue(USER_ID, scope, Utc::now()).await.unwrap();
+
+    let (sender, calls) = mpsc::unbounded_channel();
+    let spy = Arc::new(Spy(sender));
+    let mut dispatcher = WebhookDispatcher::new(authority, Arc::new(InMemorySessionStore::default()))
+        .with_trades(spy.clone())
+        .with_notifier(spy.clone());
+    if full {
+        dispatcher = dispatcher.with_orders(spy.clone()).with_portfolios(spy);
+    }
+    let server = WebhookServer::new(Arc::new(dispatcher)).with_secret(SECRET.to_string());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = server.serve(listener).await;
+    });
+    Webhook { url, calls, trade_token }
+}
+
+#[tokio::test]
+async fn test_each_event_type_reaches_its_component() {
+    let mut webhook = serve(true).await;
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+
+    let results = webhook.results(json!([
+        {
+            "idempotencyKey": "evt-1", "type": "execute_trade", "telegramId": USER_ID,
+            "token": "BONK", "side": "buy", "amount": 0.5, "authorization": webhook.trade_token,
+        },
+        { "idempotencyKey": "evt-2", "type": "cancel_order", "telegramId": USER_ID, "orderId": "ord-42" },
+        { "idempotencyKey": "evt-3", "type": "sync_portfolio", "telegramId": USER_ID },
+        { "idempotencyKey": "evt-4", "type": "notify_user", "telegramId": USER_ID, "text": "Your DCA finished" },
+        {
+            "idempotencyKey": "evt-5", "type": "price_alert_triggered", "telegramId": USER_ID,
+            "alertId": "alert-9", "token": "BONK", "price": 0.00003, "condition": "above 0.000025",
+        },
+        { "idempotencyKey": "evt-6", "type": "cancel_order", "telegramId": USER_ID, "orderId": "missing" },
+    ])).await;
+
+    let statuses: Vec<(&str, EventStatus)> = results.iter().map(|r| (r.event_type.as_str(), r.status)).collect();
+    assert_eq!(statuses, vec![
+        ("execute_trade", EventStatus::Ok),
+        ("cancel_order", EventStatus::Ok),
+        ("sync_portfolio", EventStatus::Ok),
+        ("notify_user", EventStatus::Ok),
+        ("price_alert_triggered", EventStatus::Ok),
+        ("cancel_order", EventStatus::Failed),
+    ]);
+    assert_eq!(results[0].data["txSignature"], "5igSig");
+    assert_eq!(results[0].data["amountSol"], 0.5);
+    assert_eq!(results[2].data["totalValueUsd"], 1_250.0);
+    assert_eq!(results[2].data["holdings"], 3);
+    assert!(results[5].message.as_deref().unwrap().contains("missing"));
+    assert!(results.iter().all(|r| !r.duplicate));
+
+    let calls = webhook.drain();
+    assert_eq!(calls.len(), 6);
+    assert_eq!(calls[0], Call::Trade { telegram_id: USER_ID, mint: bonk, side: TradeSide::Buy, amount: 0.5 });
+    assert_eq!(calls[1], Call::Cancel { telegram_id: USER_ID, order_id: "ord-42".to_string() });
+    assert_eq!(calls[2], Call::Sync { telegram_id: USER_ID });
+    assert_eq!(calls[3], Call::Notify { telegram_id: USER_ID, text: "Your DCA finished".to_string() });
+    match &calls[4] {
+        Call::Notify { telegram_id, text } => {
+            assert_eq!(*telegram_id, USER_ID);
+            assert!(text.contains("BONK above 0.000025") && text.contains("alert-9"), "{}", text);
+        }
+        other => panic!("expected a notification, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_retries_never_execute_twice() {
+    let mut webhook = serve(true).await;
+    let trade = json!([{
+        "idempotencyKey": "trade-1", "type": "execute_trade", "telegramId": USER_ID,
+        "token": "BONK", "side": "buy", "amount": 0.25, "authorization": webhook.trade_token,
+    }]);
+
+    // Two deliveries racing, then a late retry
+    let (first, second) = tokio::join!(webhook.results(trade.clone()), webhook.results(trade.clone()));
+    let retry = webhook.results(trade).await;
+
+    let mut racing = vec![first[0].clone(), second[0].clone()];
+    racing.sort_by_key(|r| r.duplicate);
+    assert_eq!((racing[0].status, racing[0].duplicate), (EventStatus::Ok, false));
+    assert!(racing[1].duplicate);
+    assert!(matches!(racing[1].status, EventStatus::Ok | EventStatus::InProgress));
+    assert!(retry[0].duplicate);
+    assert_eq!(EventResult { duplicate: false, ..retry[0].clone() }, racing[0]);
+
+    let trades = webhook.drain();
+    assert_eq!(trades.len(), 1, "{:?}", trades);
+
+    // Sells are checked as percentages; over the grant's cap a buy is refused
+    let results = webhook.results(json!([
+        {
+            "idempotencyKey": "trade-2", "type": "execute_trade", "telegramId": USER_ID,
+            "token": "BONK", "side": "sell", "amount": 50, "authorization": webhook.trade_token,
+        },
+        {
+            "idempotencyKey": "trade-3", "type": "execute_trade", "telegramId": USER_ID,
+            "token": "BONK", "side": "buy", "amount": 2.0, "authorization": webhook.trade_token,
+        },
+        {
+            "idempotencyKey": "trade-4", "type": "execute_trade", "telegramId": USER_ID + 1,
+            "token": "BONK", "side": "buy", "amount": 0.1, "authorization": webhook.trade_token,
+        },
+    ])).await;
+    assert_eq!(results[0].status, EventStatus::Ok);
+    assert_eq!(results[1].status, EventStatus::Rejected);
+    assert!(results[1].message.as_deref().unwrap().contains("cap"));
+    assert_eq!(results[2].status, EventStatus::Rejected);
+    let calls = webhook.drain();
+    assert_eq!(calls.len(), 1);
+    assert!(matches!(&calls[0], Call::Trade { side: TradeSide::Sell, amount, .. } if *amount == 50.0));
+}
+
+#[tokio::test]
+async fn test_unknown_types_and_bad_requests_run_nothing() {
+    let mut webhook = serve(false).await;
+
+    let (status, body) = webhook.post(json!({ "events": [
+        { "idempotencyKey": "evt-1", "type": "notify_user", "telegramId": USER_ID, "text": "hi" },
+        { "idempotencyKey": "evt-2", "type": "withdraw_all", "telegramId": USER_ID },
+    ]})).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["unsupported"], json!(["withdraw_all"]));
+    assert_eq!(body["supportedTypes"], json!([
+        "execute_trade", "cancel_order", "sync_portfolio", "notify_user", "price_alert_triggered",
+        "run_dca", "simulated_trade",
+    ]));
+
+    let (status, _) = webhook.post(json!({ "events": [
+        { "type": "notify_user", "telegramId": USER_ID, "text": "no key" },
+    ]})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = webhook.post(json!({ "type": "notify_user" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let response = reqwest::Client::new()
+        .post(&webhook.url)
+        .bearer_auth("wrong")
+        .json(&json!({ "events": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(webhook.drain().is_empty());
+
+    // Components this instance doesn't have answer unavailable
+    let results = webhook.results(json!([
+        { "idempotencyKey": "evt-3", "type": "cancel_order", "telegramId": USER_ID, "orderId": "ord-1" },
+        { "idempotencyKey": "evt-4", "type": "sync_portfolio", "telegramId": USER_ID },
+        { "idempotencyKey": "evt-5", "type": "run_dca", "telegramId": USER_ID, "strategyId": "dca-1", "authorization": "x" },
+    ])).await;
+    assert!(results.iter().all(|r| r.status == EventStatus::Unavailable));
+    assert!(webhook.drain().is_empty());
+}
+
+#[tokio::test]
+async fn test_dca_runs_and_simulated_trades_go_through_their_grants() {
+    const SOL_MINT: &str = "So11111111111111111111111111111111111112";
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(SOL_MINT, 200.0).price(&bonk, 0.00002).build().await.unwrap();
+    let services = &harness.services;
+    let dispatcher = WebhookDispatcher::new(services.automation_auth.clone(), services.sessions.clone())
+        .with_automations(services.clone());
+    let grant = |action, cap| GrantScope { action, max_notional_sol: cap, expires_at: Utc::now() + Duration::days(1) };
+    let envelope = |key: &str, event| WebhookEnvelope { idempotency_key: key.to_string(), event };
+
+    let (_, sim_token) = services.automation_auth.issue(USER_ID, grant(AutomationAction::SimulatedTrade, 1.0), Utc::now()).await.unwrap();
+    let simulated = dispatcher.dispatch(envelope("sim-1", WebhookEvent::SimulatedTrade {
+        telegram_id: USER_ID,
+        notional_sol: 0.5,
+        authorization: sim_token.clone(),
+    })).await;
+    assert_eq!(simulated.status, EventStatus::Ok, "{:?}", simulated.message);
+    assert!(simulated.data["grantId"].is_string());
+
+    // A DCA run is sized from the stored strategy: 100 per step is over a 1 SOL cap
+    let strategy = DCAStrategy::create_daily_dca(
+        USER_ID,
+        "BONK daily".to_string(),
+        SOL_MINT.to_string(),
+        bonk,
+        Decimal::from(1_000),
+        Decimal::from(100),
+    );
+    let strategy_id = services.dca_engine.create_strategy(strategy).await.unwrap();
+    let (_, dca_token) = services.automation_auth.issue(USER_ID, grant(AutomationAction::DcaRun, 1.0), Utc::now()).await.unwrap();
+    let over_cap = dispatcher.dispatch(envelope("dca-1", WebhookEvent::RunDca {
+        telegram_id: USER_ID,
+        strategy_id: strategy_id.clone(),
+        authorization: dca_token.clone(),
+    })).await;
+    assert_eq!(over_cap.status, EventStatus::Rejected);
+    assert!(over_cap.message.as_deref().unwrap().contains("cap"));
+
+    // A simulated-trade grant doesn't cover DCA runs, and unknown strategies fail
+    let wrong_scope = dispatcher.dispatch(envelope("dca-2", WebhookEvent::RunDca {
+        telegram_id: USER_ID,
+        strategy_id,
+        authorization: sim_token,
+    })).await;
+    assert_eq!(wrong_scope.status, EventStatus::Rejected);
+    let missing = dispatcher.dispatch(envelope("dca-3", WebhookEvent::RunDca {
+        telegram_id: USER_ID,
+        strategy_id: "no-such-strategy".to_string(),
+        authorization: dca_token,
+    })).await;
+    assert_eq!(missing.status, EventStatus::Failed);
+}
@@ -0,0 +1,113 @@
+use crate::trading::{
+    BlockchainTradeMonitor, CopyTradeType, JupiterSwap, MasterTradeEvent, MasterTradeWatchConfig, JUPITER_V6_PROGRAM_ID,
+};
+use chrono::{DateTime, Duration, Utc};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use std::sync::Arc;
+
+/// `getTransaction` (jsonParsed) for a 0.5 SOL → BONK buy through `sharedAccountsRoute`
+const JUPITER_BUY: &str = include_str!("fixtures/jupiter_swap_buy.json");
+
+const MASTER: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+const WSOL: &str = "So11111111111111111111111111111111111111112";
+const SIGNATURE: &str = "4YDmKy7HJc5Jc9zL8gkM5B1Z6hh8kBZ7ADekMH4ffjaKq6nYckrFdAGcK3mg25zPggBmz65YfBWhKDSFki7tNBQW";
+
+fn fixture() -> EncodedConfirmedTransactionWithStatusMeta {
+    serde_json::from_str(JUPITER_BUY).expect("fixture is a valid getTransaction result")
+}
+
+fn monitor() -> BlockchainTradeMonitor {
+    // Never contacted: these tests feed transactions directly
+    let rpc_client = Arc::new(RpcClient::new("http://127.0.0.1:8899".to_string()));
+    BlockchainTradeMonitor::new(rpc_client, MasterTradeWatchConfig::default())
+}
+
+#[test]
+fn recorded_jupiter_buy_becomes_master_trade_event() {
+    let event = MasterTradeEvent::from_transaction(SIGNATURE, MASTER, &fixture()).expect("a SOL → BONK buy");
+
+    assert_eq!(event.signature, SIGNATURE);
+    assert_eq!(event.master_wallet, MASTER);
+    assert_eq!(event.token_mint, BONK);
+    assert_eq!(event.direction, CopyTradeType::Buy);
+    // Exact-in route: the instruction's in_amount, not the balance change with fee and ATA rent
+    assert!((event.sol_amount - 0.5).abs() < 1e-9);
+    assert!((event.token_amount - 17_425_000.0).abs() < 1e-6);
+    assert!((event.price - 0.5 / 17_425_000.0).abs() < 1e-15);
+    assert_eq!(event.slot, 287_654_321);
+    assert_eq!(event.block_time, DateTime::from_timestamp(1_726_000_000, 0));
+}
+
+#[test]
+fn only_the_signing_wallet_is_a_master_trade() {
+    // The pool's WSOL vault gained the 0.5 SOL, but it did not sign
+    let pool_vault = "99XGZRNweWuJ4tjzDnuNydTodrTZfYUHEszY64UDTZQD";
+    assert!(MasterTradeEvent::from_transaction(SIGNATURE, pool_vault, &fixture()).is_none());
+}
+
+#[test]
+fn transactions_without_a_jupiter_route_are_ignored() {
+    let without_jupiter = JUPITER_BUY.replace(
+        &format!("\"programId\": \"{}\"", JUPITER_V6_PROGRAM_ID),
+        "\"programId\": \"whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc\"",
+    );
+    let encoded: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_str(&without_jupiter).unwrap();
+
+    assert!(MasterTradeEvent::from_transaction(SIGNATURE, MASTER, &encoded).is_none());
+}
+
+#[test]
+fn exact_out_route_swaps_amount_order() {
+    let mut data = vec![208, 51, 239, 151, 123, 43, 237, 92];
+    data.extend_from_slice(&0u32.to_le_bytes()); // empty route plan
+    data.extend_from_slice(&2_000_000_000u64.to_le_bytes()); // out_amount
+    data.extend_from_slice(&1_000_000u64.to_le_bytes()); // quoted_in_amount
+    data.extend_from_slice(&50u16.to_le_bytes());
+    data.push(0);
+    let accounts: Vec<String> = (0..6).map(|i| format!("account{}", i))
+        .chain([BONK.to_string(), WSOL.to_string()])
+        .collect();
+
+    let swap = JupiterSwap::decode(&data, &accounts).unwrap();
+
+    assert!(swap.exact_out);
+    assert_eq!(swap.in_amount, 1_000_000);
+    assert_eq!(swap.out_amount, 2_000_000_000);
+    assert_eq!(swap.source_mint.as_deref(), Some(BONK));
+    assert_eq!(swap.destination_mint, WSOL);
+}
+
+#[test]
+fn unknown_discriminator_is_not_a_route() {
+    let data = [0u8; 40];
+    let accounts: Vec<String> = (0..9).map(|i| format!("account{}", i)).collect();
+    assert!(JupiterSwap::decode(&data, &accounts).is_none());
+}
+
+#[tokio::test]
+async fn repeated_signatures_are_claimed_once_per_window() {
+    let monitor = monitor();
+    let now = Utc::now();
+
+    assert!(monitor.claim(SIGNATURE, now).await);
+    // Same signature from the websocket and the catch-up poll
+    assert!(!monitor.claim(SIGNATURE, now + Duration::seconds(2)).await);
+
+    // A failed fetch hands it back
+    monitor.release(SIGNATURE).await;
+    assert!(monitor.claim(SIGNATURE, now + Duration::seconds(3)).await);
+
+    let window = MasterTradeWatchConfig::default().dedupe_window;
+    assert!(monitor.claim(SIGNATURE, now + Duration::seconds(3) + window).await);
+}
+
+#[tokio::test]
+async fn invalid_master_wallets_are_not_watched() {
+    let monitor = monitor();
+
+    monitor.subscribe_to_masters(vec!["Alpha123...xyz".to_string()]).await.unwrap();
+
+    assert!(!monitor.is_watching("Alpha123...xyz").await);
+}
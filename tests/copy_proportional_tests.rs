@@ -0,0 +1,173 @@
+use crate::testkit::TestHarness;
+use std::collections::HashMap;
+
+use crate::trading::{
+    CopyTradeStatus, CopyTradeType, CopyTradingManager, CopyTradingMonitor, MasterPositionBook, NoticeKind,
+    OutgoingNotice, TokenResolver,
+};
+
+const FOLLOWER: i64 = 760_001;
+const LATE_FOLLOWER: i64 = 760_002;
+const MASTER: i64 = 1001;
+
+fn approx(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-6 * b.abs().max(1.0)
+}
+
+#[test]
+fn test_master_book_measures_each_sell_against_what_is_left() {
+    let mut book = MasterPositionBook::new();
+    book.record_buy(MASTER, "A", 100.0);
+    book.record_buy(MASTER, "A", 100.0);
+
+    assert_eq!(book.record_sell(MASTER, "A", 100.0), Some(0.5));
+    assert_eq!(book.remaining(MASTER, "A"), 100.0);
+    assert_eq!(book.record_sell(MASTER, "A", 50.0), Some(0.5));
+    assert_eq!(book.record_sell(MASTER, "A", 50.0), Some(1.0));
+    assert_eq!(book.remaining(MASTER, "A"), 0.0);
+
+    // Nothing left, or never bought: no share to mirror
+    assert_eq!(book.record_sell(MASTER, "A", 10.0), None);
+    assert_eq!(book.record_sell(MASTER, "B", 10.0), None);
+
+    // Selling more than was seen bought is a full exit
+    book.record_buy(MASTER, "C", 10.0);
+    assert_eq!(book.record_sell(MASTER, "C", 25.0), Some(1.0));
+    assert_eq!(book.remaining(MASTER, "C"), 0.0);
+}
+
+#[tokio::test]
+async fn test_scale_out_sells_the_same_share_of_each_follower_position() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().build().await.unwrap();
+    let copy_trading = &harness.copy_trading;
+    copy_trading.start_following(FOLLOWER, "AlphaTrader", 50.0, 5.0).await.unwrap();
+
+    // Master buys 200k tokens; the follower's copy buys whatever 1.9 SOL gets
+    let executions = copy_trading
+        .execute_copy_trade(MASTER, &mint, "BONK", CopyTradeType::Buy, 4.0, 0.00002)
+        .await
+        .unwrap();
+    assert_eq!(executions[0].status, CopyTradeStatus::Success);
+    let bought = copy_trading.follower_risk(FOLLOWER).await.positions[&mint].tokens;
+    assert!(approx(copy_trading.master_position(MASTER, &mint).await, 200_000.0));
+
+    // Following after the buy means holding none of it
+    copy_trading.start_following(LATE_FOLLOWER, "AlphaTrader", 50.0, 5.0).await.unwrap();
+    let mut notices = harness.services.execution_notices.subscribe();
+
+    // 50%, then 50% of the rest, then everything, each at a different price
+    let steps = [(4.0, 0.00004, 0.5), (0.5, 0.00001, 0.25), (1.5, 0.00003, 0.0)];
+    for (master_sol, price, left) in steps {
+        let held = copy_trading.follower_risk(FOLLOWER).await.positions.get(&mint).map(|p| p.tokens).unwrap_or(0.0);
+        let executions = copy_trading
+            .execute_copy_trade(MASTER, &mint, "BONK", CopyTradeType::Sell, master_sol, price)
+            .await
+            .unwrap();
+        assert_eq!(executions.len(), 2);
+
+        let sold = executions.iter().find(|e| e.follower_user_id == FOLLOWER).unwrap();
+        assert_eq!(sold.status, CopyTradeStatus::Success);
+        assert_eq!(sold.master_amount_sol, master_sol);
+        let tokens_sold = sold.copied_amount_sol / sold.execution_price;
+        assert!(approx(tokens_sold, held - bought * left), "sold {} of {}", tokens_sold, held);
+
+        let skipped = executions.iter().find(|e| e.follower_user_id == LATE_FOLLOWER).unwrap();
+        assert_eq!(skipped.status, CopyTradeStatus::Cancelled);
+        assert!(skipped.error_message.as_deref().unwrap().contains("You hold no BONK"));
+
+        let risk = copy_trading.follower_risk(FOLLOWER).await;
+        if left > 0.0 {
+            assert!(approx(risk.positions[&mint].tokens, bought * left));
+        } else {
+            assert!(!risk.holds(&mint));
+        }
+    }
+    assert_eq!(copy_trading.master_position(MASTER, &mint).await, 0.0);
+
+    // The follower without a position heard why each sell was skipped
+    let mut refusals = 0;
+    while let Ok(outgoing) = notices.try_recv() {
+        if let OutgoingNotice::Notice(notice) = outgoing {
+            if notice.user_id == LATE_FOLLOWER && matches!(notice.kind, NoticeKind::RiskRefusal { .. }) {
+                refusals += 1;
+            }
+        }
+    }
+    assert_eq!(refusals, 3);
+
+    // A sell after the full exit has nothing to measure against
+    let executions = copy_trading
+        .execute_copy_trade(MASTER, &mint, "BONK", CopyTradeType::Sell, 1.0, 0.00002)
+        .await
+        .unwrap();
+    assert!(executions.iter().all(|e| e.status == CopyTradeStatus::Cancelled));
+}
+
+#[test]
+fn test_stored_master_positions_restore_once() {
+    let mut book = MasterPositionBook::new();
+    assert!(!book.is_loaded(MASTER));
+    book.restore(MASTER, HashMap::from([("A".to_string(), 40.0), ("Gone".to_string(), 0.0)]));
+    assert!(book.is_loaded(MASTER));
+    assert_eq!(book.holdings(MASTER), HashMap::from([("A".to_string(), 40.0)]));
+
+    // Later restores don't clobber what was tracked since
+    book.record_buy(MASTER, "A", 10.0);
+    book.restore(MASTER, HashMap::from([("A".to_string(), 40.0)]));
+    assert_eq!(book.remaining(MASTER, "A"), 50.0);
+}
+
+#[tokio::test]
+async fn test_every_exit_type_sells_a_share_and_survives_a_restart() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().build().await.unwrap();
+    let copy_trading = &harness.copy_trading;
+    copy_trading.start_following(FOLLOWER, "AlphaTrader", 50.0, 5.0).await.unwrap();
+    copy_trading
+        .execute_copy_trade(MASTER, &mint, "BONK", CopyTradeType::Buy, 4.0, 0.00002)
+        .await
+        .unwrap();
+    let bought = copy_trading.follower_risk(FOLLOWER).await.positions[&mint].tokens;
+
+    // A restarted manager still knows the master's 200k tokens
+    let restarted = CopyTradingManager::new(harness.db.clone(), harness.trading_engine.clone(), harness.wallet_manager.clone());
+    assert!(approx(restarted.master_position(MASTER, &mint).await, 200_000.0));
+
+    // Each exit takes a quarter of the master's original position, and the same share of the follower's
+    let exits = [(CopyTradeType::TakeProfit, 0.75), (CopyTradeType::StopLoss, 0.5), (CopyTradeType::Emergency, 0.0)];
+    for (exit, left) in exits {
+        let master_sol = if left > 0.0 { 50_000.0 * 0.00002 } else { 100_000.0 * 0.00002 };
+        let executions = copy_trading
+            .execute_copy_trade(MASTER, &mint, "BONK", exit.clone(), master_sol, 0.00002)
+            .await
+            .unwrap();
+        let sold = executions.iter().find(|e| e.follower_user_id == FOLLOWER).unwrap();
+        assert_eq!(sold.status, CopyTradeStatus::Success, "{:?}: {:?}", exit, sold.error_message);
+        assert_eq!(sold.trade_type, exit);
+
+        let risk = copy_trading.follower_risk(FOLLOWER).await;
+        if left > 0.0 {
+            assert!(approx(risk.positions[&mint].tokens, bought * left), "{:?}", exit);
+        } else {
+            assert!(!risk.holds(&mint));
+        }
+    }
+    assert_eq!(copy_trading.master_position(MASTER, &mint).await, 0.0);
+}
+
+#[tokio::test]
+async fn test_monitor_sees_follows_made_through_the_bot() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let monitor = CopyTradingMonitor::new(harness.services.copy_trading.clone());
+    assert!(monitor.followed_masters().await.is_empty());
+
+    // /copy goes through the services' manager, not one of the monitor's own
+    harness.services.copy_trading.start_following(FOLLOWER, "AlphaTrader", 50.0, 5.0).await.unwrap();
+    let masters = monitor.followed_masters().await;
+    assert_eq!(masters.len(), 1);
+    assert_eq!(masters, harness.copy_trading.followed_master_wallets().await);
+
+    harness.services.copy_trading.stop_following(FOLLOWER, MASTER).await.unwrap();
+    assert!(monitor.followed_masters().await.is_empty());
+}
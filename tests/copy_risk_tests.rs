@@ -0,0 +1,148 @@
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+
+use crate::testkit::TestHarness;
+use crate::trading::{
+    CopyTradeExecution, CopyTradeStatus, CopyTradeType, CopyTradingManager, ExecutionReport, FollowerDailyRisk,
+    NoticeKind, OutgoingNotice,
+};
+
+const FOLLOWER: i64 = 759_001;
+const OTHER_FOLLOWER: i64 = 759_002;
+const MASTER: i64 = 1001;
+
+fn approx(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn execution(follower: i64, trade_type: CopyTradeType, token: &str, sol: f64, price: f64) -> CopyTradeExecution {
+    CopyTradeExecution {
+        execution_id: uuid::Uuid::new_v4().to_string(),
+        master_trade_id: "master".to_string(),
+        master_user_id: MASTER,
+        follower_user_id: follower,
+        token_address: token.to_string(),
+        token_symbol: token.to_string(),
+        trade_type,
+        master_amount_sol: sol,
+        copied_amount_sol: sol,
+        master_price: price,
+        execution_price: price,
+        slippage_percent: 0.0,
+        fee_paid_sol: 0.0,
+        status: CopyTradeStatus::Success,
+        error_message: None,
+        timestamp: Utc::now(),
+        report: ExecutionReport::default(),
+    }
+}
+
+/// Buy 1 SOL of `token` at 1.0, sell it all at 0.3: -0.7 SOL
+fn losing_round_trip(follower: i64, token: &str) -> Vec<CopyTradeExecution> {
+    vec![
+        execution(follower, CopyTradeType::Buy, token, 1.0, 1.0),
+        execution(follower, CopyTradeType::Sell, token, 0.3, 0.3),
+    ]
+}
+
+#[test]
+fn test_realized_pnl_is_measured_against_cost() {
+    let mut risk = FollowerDailyRisk::new(Utc::now().date_naive());
+
+    let mut buy = execution(FOLLOWER, CopyTradeType::Buy, "A", 1.0, 0.5);
+    buy.fee_paid_sol = 0.05;
+    assert_eq!(risk.record(&buy), 0.0);
+    assert!(risk.holds("A"));
+    assert_eq!(risk.open_positions(), 1);
+
+    // Half the tokens sold at 0.6: 0.6 proceeds against 0.525 cost
+    assert!(approx(risk.record(&execution(FOLLOWER, CopyTradeType::Sell, "A", 0.6, 0.6)), 0.075));
+    assert!(risk.holds("A"));
+    // The rest stopped out at 0.2: 0.2 against 0.525
+    assert!(approx(risk.record(&execution(FOLLOWER, CopyTradeType::StopLoss, "A", 0.2, 0.2)), -0.325));
+    assert!(!risk.holds("A"));
+    assert!(approx(risk.realized_pnl_sol, -0.25));
+
+    // Failed trades and sells of tokens with no known cost don't move the tally
+    let mut failed = execution(FOLLOWER, CopyTradeType::Sell, "A", 5.0, 0.1);
+    failed.status = CopyTradeStatus::Failed;
+    assert_eq!(risk.record(&failed), 0.0);
+    assert_eq!(risk.record(&execution(FOLLOWER, CopyTradeType::Sell, "B", 1.0, 1.0)), 0.0);
+    assert!(approx(risk.realized_pnl_sol, -0.25));
+
+    // Profits don't extend the budget, losses eat into it
+    assert!(approx(risk.remaining_budget(1.0), 0.75));
+    assert!(!risk.limit_reached(1.0));
+    assert!(risk.limit_reached(0.2));
+    assert!(!risk.limit_reached(0.0));
+}
+
+#[test]
+fn test_new_utc_day_resets_pnl_but_keeps_positions() {
+    let day = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+    let mut risk = FollowerDailyRisk::new(day);
+    for trade in losing_round_trip(FOLLOWER, "A") {
+        risk.record(&CopyTradeExecution { timestamp: Utc.with_ymd_and_hms(2026, 3, 1, 23, 0, 0).unwrap(), ..trade });
+    }
+    risk.record(&CopyTradeExecution {
+        timestamp: Utc.with_ymd_and_hms(2026, 3, 1, 23, 30, 0).unwrap(),
+        ..execution(FOLLOWER, CopyTradeType::Buy, "B", 1.0, 1.0)
+    });
+    assert!(approx(risk.realized_pnl_sol, -0.7));
+
+    risk.roll_to(day + Duration::days(1));
+    assert_eq!(risk.realized_pnl_sol, 0.0);
+    assert!(risk.holds("B"));
+    // Never rolls backwards
+    risk.roll_to(day);
+    assert_eq!(risk.day, day + Duration::days(1));
+}
+
+#[tokio::test]
+async fn test_losing_streak_pauses_copying_until_next_utc_day() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let copy_trading = &harness.copy_trading;
+    let mut notices = harness.services.execution_notices.subscribe();
+
+    // 1 SOL max position: the default daily loss limit is 2 SOL
+    for follower in [FOLLOWER, OTHER_FOLLOWER] {
+        let config = copy_trading.start_following(follower, "AlphaTrader", 10.0, 1.0).await.unwrap();
+        assert_eq!(config.daily_loss_limit_sol, 2.0);
+    }
+
+    for token in ["A", "B"] {
+        copy_trading.record_executions(&losing_round_trip(FOLLOWER, token)).await.unwrap();
+    }
+    let (configs, _) = copy_trading.get_user_stats(FOLLOWER).await.unwrap();
+    assert!(configs[0].enabled);
+    assert!(approx(copy_trading.remaining_daily_budget(&configs[0]).await.unwrap(), 0.6));
+
+    // The third loss crosses -2 SOL
+    copy_trading.record_executions(&losing_round_trip(FOLLOWER, "C")).await.unwrap();
+    let (configs, _) = copy_trading.get_user_stats(FOLLOWER).await.unwrap();
+    let midnight = Utc.from_utc_datetime(&(Utc::now().date_naive() + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap());
+    assert!(!configs[0].enabled);
+    assert_eq!(configs[0].paused_until, Some(midnight));
+    assert_eq!(copy_trading.remaining_daily_budget(&configs[0]).await, Some(0.0));
+
+    let OutgoingNotice::Notice(notice) = notices.recv().await.unwrap() else { panic!("expected a notice") };
+    assert_eq!(notice.user_id, FOLLOWER);
+    assert!(matches!(notice.kind, NoticeKind::RiskRefusal { ref reason } if reason.contains("Daily loss limit of 2 SOL")));
+
+    // The tally survives a restart
+    let restarted = CopyTradingManager::new(harness.db.clone(), harness.trading_engine.clone(), harness.wallet_manager.clone());
+    assert!(approx(restarted.follower_risk(FOLLOWER).await.realized_pnl_sol, -2.1));
+
+    // The other follower hits the limit too, then switches copying off by hand
+    for token in ["A", "B", "C"] {
+        copy_trading.record_executions(&losing_round_trip(OTHER_FOLLOWER, token)).await.unwrap();
+    }
+    copy_trading.set_enabled(OTHER_FOLLOWER, MASTER, false).await.unwrap();
+
+    // Nothing resumes early; at midnight only the limit pause lifts
+    assert_eq!(copy_trading.resume_paused_at(midnight - Duration::seconds(1)).await, 0);
+    assert_eq!(copy_trading.resume_paused_at(midnight).await, 1);
+    let (configs, _) = copy_trading.get_user_stats(FOLLOWER).await.unwrap();
+    assert!(configs[0].enabled && configs[0].paused_until.is_none());
+    let (configs, _) = copy_trading.get_user_stats(OTHER_FOLLOWER).await.unwrap();
+    assert!(!configs[0].enabled);
+}
@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use chrono::{Duration, TimeZone, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::analytics::{CostBasisBook, CostBasisMethod, CostBasisPreferences, LotTrade};
+use crate::trading::{TradeProvenance, TradeResult};
+
+const USER: i64 = 777_001;
+const MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+#[derive(Default)]
+struct Methods(RwLock<HashMap<i64, CostBasisMethod>>);
+
+#[async_trait]
+impl CostBasisPreferences for Methods {
+    async fn cost_basis_method(&self, user_id: i64) -> CostBasisMethod {
+        self.0.read().await.get(&user_id).copied().unwrap_or_default()
+    }
+}
+
+fn trade(is_buy: bool, quantity: f64, sol_amount: f64, hours: i64) -> LotTrade {
+    LotTrade {
+        mint: MINT.to_string(),
+        is_buy,
+        quantity,
+        sol_amount,
+        fee_sol: 0.0,
+        at: Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap() + Duration::hours(hours),
+        provenance: TradeProvenance::Bot,
+    }
+}
+
+/// 100 tokens at 1 SOL, 100 at 2 SOL, 100 at 3 SOL
+async fn three_lots(book: &CostBasisBook) {
+    for (i, cost) in [100.0, 200.0, 300.0].into_iter().enumerate() {
+        assert!(book.apply(USER, &trade(true, 100.0, cost, i as i64)).await.is_none());
+    }
+}
+
+async fn method_book(method: CostBasisMethod) -> CostBasisBook {
+    let methods = Arc::new(Methods::default());
+    methods.0.write().await.insert(USER, method);
+    CostBasisBook::default().with_preferences(methods)
+}
+
+#[tokio::test]
+async fn test_fifo_partial_sells_close_the_oldest_lots_first() {
+    let book = CostBasisBook::default();
+    three_lots(&book).await;
+
+    // 150 tokens at 2.5 SOL: all of the first lot and half the second
+    let realized = book.apply(USER, &trade(false, 150.0, 375.0, 5)).await.unwrap();
+    assert_eq!(realized.method, CostBasisMethod::Fifo);
+    assert!((realized.cost_sol - 200.0).abs() < 1e-9);
+    assert!((realized.pnl_sol - 175.0).abs() < 1e-9);
+    assert!((realized.pnl_percentage() - 87.5).abs() < 1e-9);
+
+    let lots = book.lots(USER, MINT).await;
+    assert_eq!(lots.len(), 2);
+    assert!((lots[0].quantity - 50.0).abs() < 1e-9 && (lots[0].unit_cost_sol - 2.0).abs() < 1e-12);
+
+    // Realized plus unrealized adds up to proceeds and value minus everything paid
+    let basis = book.position(USER, MINT).await.unwrap();
+    let total = book.realized_sol(USER).await + basis.unrealized_sol(2.5);
+    assert!((total - (375.0 + 150.0 * 2.5 - 600.0)).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_average_cost_sells_leave_the_average_unchanged() {
+    let book = method_book(CostBasisMethod::Average).await;
+    three_lots(&book).await;
+
+    let realized = book.apply(USER, &trade(false, 150.0, 375.0, 5)).await.unwrap();
+    assert_eq!(realized.method, CostBasisMethod::Average);
+    assert!((realized.cost_sol - 300.0).abs() < 1e-9);
+    assert!((realized.pnl_sol - 75.0).abs() < 1e-9);
+
+    let basis = book.position(USER, MINT).await.unwrap();
+    assert!((basis.quantity - 150.0).abs() < 1e-9);
+    assert!((basis.average_cost() - 2.0).abs() < 1e-12);
+    let total = book.realized_sol(USER).await + basis.unrealized_sol(2.5);
+    assert!((total - (375.0 + 150.0 * 2.5 - 600.0)).abs() < 1e-9);
+
+    // Selling the rest realizes exactly what's left, whatever the method
+    let rest = book.apply(USER, &trade(false, 150.0, 300.0, 6)).await.unwrap();
+    assert!((rest.cost_sol - 300.0).abs() < 1e-9);
+    assert!(book.position(USER, MINT).await.is_none());
+}
+
+#[tokio::test]
+async fn test_switching_methods_applies_to_later_sells_only() {
+    let methods = Arc::new(Methods::default());
+    let book = CostBasisBook::default().with_preferences(methods.clone());
+    three_lots(&book).await;
+
+    let fifo = book.apply(USER, &trade(false, 50.0, 100.0, 5)).await.unwrap();
+    assert!((fifo.cost_sol - 50.0).abs() < 1e-9);
+
+    methods.0.write().await.insert(USER, CostBasisMethod::Average);
+    // 250 tokens left costing 550 SOL
+    let average = book.apply(USER, &trade(false, 50.0, 100.0, 6)).await.unwrap();
+    assert_eq!(average.method, CostBasisMethod::Average);
+    assert!((average.cost_sol - 110.0).abs() < 1e-9);
+    assert!((book.realized_sol(USER).await - (50.0 - 10.0)).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_sells_beyond_known_lots_realize_only_what_was_bought() {
+    let book = CostBasisBook::default();
+    book.apply(USER, &trade(true, 100.0, 100.0, 0)).await;
+
+    let realized = book.apply(USER, &trade(false, 200.0, 400.0, 1)).await.unwrap();
+    assert!((realized.quantity - 100.0).abs() < 1e-9);
+    assert!((realized.proceeds_sol - 200.0).abs() < 1e-9);
+    assert!((realized.pnl_sol - 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_engine_results_map_to_lot_trades() {
+    let buy = LotTrade::from_trade_result(&TradeResult::buy("sigB".to_string(), 1_000.0, 0.5, 0.0005), MINT).unwrap();
+    assert!(buy.is_buy && buy.quantity == 1_000.0 && buy.sol_amount == 0.5);
+
+    let sell = LotTrade::from_trade_result(&TradeResult::sell("sigS".to_string(), 400.0, 0.3, 0.00075), MINT).unwrap();
+    assert!(!sell.is_buy && sell.quantity == 400.0 && sell.sol_amount == 0.3);
+
+    assert!(LotTrade::from_trade_result(&TradeResult::buy("sig0".to_string(), 0.0, 0.0, 0.0), MINT).is_none());
+    assert_eq!(CostBasisMethod::parse("AVG"), Some(CostBasisMethod::Average));
+    assert_eq!(CostBasisMethod::parse("lifo"), None);
+}
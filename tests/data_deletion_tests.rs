@@ -0,0 +1,301 @@
+use chrono::{Duration, TimeZone, Utc};
+use solana_sdk::signature::Signer;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::alerts::PriceAlert;
+use crate::api::convex::{ConvexTable, ConvexUserSnapshot};
+use crate::bot::convex_migration::{ConvexMigration, ConvexSource, MigrationTarget};
+use crate::bot::data_deletion::{
+    DataDeletionManager, DeletionConfig, DeletionStatus, ErasureStep, ErasureTarget, NativeErasure, RetainedRecord,
+};
+use crate::bot::preferences::UserSettings;
+use crate::db::Database;
+use crate::errors::{BotError, Result};
+use crate::testkit::TestHarness;
+use crate::trading::DCAStrategy;
+
+const USER: i64 = 515151;
+
+/// Records each step it runs, and can fail once on a chosen step like a crash would
+#[derive(Default)]
+struct RecordingErasure {
+    runs: Mutex<Vec<ErasureStep>>,
+    crash_on: Mutex<Option<ErasureStep>>,
+    kept_trades: Mutex<Option<bool>>,
+}
+
+impl RecordingErasure {
+    fn crashing_on(step: ErasureStep) -> Self {
+        let target = Self::default();
+        *target.crash_on.try_lock().unwrap() = Some(step);
+        target
+    }
+
+    async fn runs(&self) -> Vec<ErasureStep> {
+        self.runs.lock().await.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl ErasureTarget for RecordingErasure {
+    async fn erase(&self, _user_id: i64, step: ErasureStep, config: &DeletionConfig) -> Result<usize> {
+        if *self.crash_on.lock().await == Some(step) {
+            *self.crash_on.lock().await = None;
+            return Err(BotError::internal("simulated crash".to_string()).into());
+        }
+        if step == ErasureStep::DeleteJournal {
+            *self.kept_trades.lock().await = Some(config.retains(RetainedRecord::TradeHistory));
+        }
+        self.runs.lock().await.push(step);
+        Ok(1)
+    }
+}
+
+fn t0() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+}
+
+fn temp_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("forgetme-{}.json", uuid::Uuid::new_v4()))
+}
+
+#[tokio::test]
+async fn test_grace_period_can_be_cancelled() {
+    let manager = DataDeletionManager::new(DeletionConfig::default());
+    let target = RecordingErasure::default();
+
+    let request = manager.schedule(USER, t0()).await;
+    assert_eq!(request.execute_at, t0() + Duration::hours(72));
+    // Confirming again doesn't push the deadline back
+    assert_eq!(manager.schedule(USER, t0() + Duration::hours(5)).await.execute_at, request.execute_at);
+
+    assert!(manager.run_due(&target, t0() + Duration::hours(71)).await.is_empty());
+    assert!(target.runs().await.is_empty());
+
+    manager.cancel(USER).await.unwrap();
+    assert!(manager.request(USER).await.is_none());
+    assert!(manager.cancel(USER).await.is_err());
+
+    assert!(manager.run_due(&target, t0() + Duration::hours(73)).await.is_empty());
+    assert!(target.runs().await.is_empty());
+    assert!(!manager.tombstones().contains(USER).await);
+}
+
+#[tokio::test]
+async fn test_retained_set_controls_what_is_erased() {
+    assert_eq!(
+        DeletionConfig::parse_retained("trades, fees"),
+        HashSet::from([RetainedRecord::TradeHistory, RetainedRecord::FeeLedger])
+    );
+    assert!(DeletionConfig::parse_retained("none").is_empty());
+    assert_eq!(DeletionConfig::parse_retained("fees,bogus"), HashSet::from([RetainedRecord::FeeLedger]));
+
+    // Default keeps both: fee records are never touched and journal trades survive
+    let target = RecordingErasure::default();
+    let manager = DataDeletionManager::new(DeletionConfig::default());
+    manager.schedule(USER, t0()).await;
+    let report = manager.run_due(&target, t0() + Duration::hours(72)).await.remove(0).unwrap();
+    assert!(!target.runs().await.contains(&ErasureStep::DeleteFees));
+    assert_eq!(*target.kept_trades.lock().await, Some(true));
+    assert_eq!(report.retained.len(), 2);
+
+    // Retaining nothing erases both
+    let target = RecordingErasure::default();
+    let config = DeletionConfig { retained: HashSet::new(), ..DeletionConfig::default() };
+    let manager = DataDeletionManager::new(config);
+    manager.schedule(USER, t0()).await;
+    manager.run_due(&target, t0() + Duration::hours(72)).await.remove(0).unwrap();
+    assert!(target.runs().await.contains(&ErasureStep::DeleteFees));
+    assert_eq!(*target.kept_trades.lock().await, Some(false));
+}
+
+#[tokio::test]
+async fn test_erasure_resumes_after_crash() {
+    let path = temp_path();
+    let manager = DataDeletionManager::new(DeletionConfig::default()).with_storage(&path).await.unwrap();
+    manager.schedule(USER, t0()).await;
+
+    let crashing = RecordingErasure::crashing_on(ErasureStep::DeleteAliases);
+    let results = manager.run_due(&crashing, t0() + Duration::hours(72)).await;
+    assert!(results[0].is_err());
+    let before_crash = crashing.runs().await;
+    assert_eq!(before_crash.last(), Some(&ErasureStep::DeleteSettings));
+
+    // A restart reloads the checkpoint; the erasure can no longer be cancelled
+    let restarted = DataDeletionManager::new(DeletionConfig::default()).with_storage(&path).await.unwrap();
+    let request = restarted.request(USER).await.unwrap();
+    assert_eq!(request.status, DeletionStatus::InProgress);
+    assert!(request.is_done(ErasureStep::Tombstone) && request.is_done(ErasureStep::DeleteSettings));
+    assert!(restarted.cancel(USER).await.is_err());
+
+    // Interrupted erasures run whatever the clock says, and only the remaining steps run
+    let resumed = RecordingErasure::default();
+    let report = restarted.run_due(&resumed, t0()).await.remove(0).unwrap();
+    let rerun = resumed.runs().await;
+    assert_eq!(rerun.first(), Some(&ErasureStep::DeleteAliases));
+    assert!(rerun.iter().all(|step| !before_crash.contains(step)));
+    assert_eq!(report.completed.len(), DeletionConfig::default().steps().len());
+
+    let reloaded = DataDeletionManager::new(DeletionConfig::default()).with_storage(&path).await.unwrap();
+    assert!(reloaded.request(USER).await.is_none());
+    assert!(reloaded.tombstones().contains(USER).await);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Convex source that lists one unmirrored user and counts snapshot reads
+#[derive(Default)]
+struct OneUserConvex {
+    reads: Mutex<usize>,
+}
+
+#[async_trait::async_trait]
+impl ConvexSource for OneUserConvex {
+    async fn user_snapshot(&self, _telegram_id: i64) -> Result<Option<ConvexUserSnapshot>> {
+        *self.reads.lock().await += 1;
+        Ok(None)
+    }
+
+    async fn unmirrored_users(&self, _limit: usize) -> Result<Vec<i64>> {
+        Ok(vec![USER])
+    }
+
+    async fn mark_mirrored(&self, _table: ConvexTable, _id: &str, _native_id: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct NoTarget;
+
+#[async_trait::async_trait]
+impl MigrationTarget for NoTarget {
+    async fn apply_preferences(&self, _user_id: i64, _preferences: UserSettings) -> Result<bool> {
+        panic!("tombstoned user written");
+    }
+
+    async fn upsert_alert(&self, _alert: PriceAlert) -> Result<bool> {
+        panic!("tombstoned user written");
+    }
+
+    async fn upsert_strategy(&self, _strategy: DCAStrategy) -> Result<bool> {
+        panic!("tombstoned user written");
+    }
+
+    async fn link_watch_only(&self, _user_id: i64, _address: &str, _label: Option<String>) -> Result<bool> {
+        panic!("tombstoned user written");
+    }
+}
+
+#[tokio::test]
+async fn test_tombstone_blocks_convex_resurrection() {
+    let manager = DataDeletionManager::new(DeletionConfig::default());
+    let source = Arc::new(OneUserConvex::default());
+    let migration = ConvexMigration::new(source.clone(), Arc::new(NoTarget))
+        .with_tombstones(manager.tombstones());
+
+    // Before erasure the user is looked up as usual
+    assert!(migration.migrate_user(USER).await.is_err());
+    assert_eq!(*source.reads.lock().await, 1);
+
+    manager.schedule(USER, t0()).await;
+    manager.run_due(&RecordingErasure::default(), t0() + Duration::hours(72)).await.remove(0).unwrap();
+    assert!(manager.tombstones().contains(USER).await);
+
+    assert!(migration.migrate_user(USER).await.is_err());
+    let batch = migration.migrate_batch(10).await.unwrap();
+    assert!(batch.reports.is_empty() && batch.failed.is_empty());
+    // Convex is never even read for a tombstoned user
+    assert_eq!(*source.reads.lock().await, 1);
+}
+
+/// Rows across the user-keyed tables, for two users sharing the database
+async fn seed_stored_rows(db: &Database, user_id: i64, wallet: &str) {
+    let order = format!("order-{}", user_id);
+    db.upsert_order(&order, user_id, "BonkMint", "filled", "{}").await.unwrap();
+    db.insert_order_execution(&format!("fill-{}", user_id), &order, "{}").await.unwrap();
+    db.upsert_order(&format!("open-{}", user_id), user_id, "BonkMint", "cancelled", "{}").await.unwrap();
+    db.insert_trade_record(user_id, &format!("trade-{}", user_id), "{}").await.unwrap();
+    db.record_trade(&user_id.to_string(), "BonkMint", 1.0, 1_000.0, 0.5, "sig").await.unwrap();
+    db.insert_copy_execution(&format!("copy-{}", user_id), user_id, "{}").await.unwrap();
+    db.record_lending_operation(user_id, "{}").await.unwrap();
+    db.save_send_batch(user_id, &format!("batch-{}", user_id), "{}").await.unwrap();
+    db.upsert_dca_strategy(&format!("dca-{}", user_id), user_id, "active", "{}").await.unwrap();
+    db.upsert_copy_daily_risk(user_id, "{}").await.unwrap();
+    db.upsert_master_fee_ledger(user_id, "{}").await.unwrap();
+    db.set_trading_mode(wallet, "paper").await.unwrap();
+    db.upsert_paper_ledger(wallet, "{}").await.unwrap();
+}
+
+async fn count(db: &Database, table: &str, column: &str, user_id: i64) -> i64 {
+    let sql = format!("SELECT COUNT(*) FROM {} WHERE {} = $1", table, column);
+    sqlx::query_scalar(&sql).bind(user_id).fetch_one(db.pool()).await.unwrap()
+}
+
+async fn erase(harness: &TestHarness, config: DeletionConfig) {
+    let manager = DataDeletionManager::new(config);
+    let target = NativeErasure::new(harness.services.clone(), harness.wallet_manager.clone(), harness.db.clone());
+    manager.schedule(USER, t0()).await;
+    manager.run_due(&target, t0() + Duration::hours(72)).await.remove(0).unwrap();
+}
+
+#[tokio::test]
+async fn test_erasure_deletes_stored_rows_of_that_user_only() {
+    const OTHER: i64 = 626262;
+    let harness = TestHarness::builder().build().await.unwrap();
+    let (mine, theirs) = (harness.register_user(USER, 1.0).await.unwrap(), harness.register_user(OTHER, 1.0).await.unwrap());
+    let (mine, theirs) = (mine.pubkey().to_string(), theirs.pubkey().to_string());
+    seed_stored_rows(&harness.db, USER, &mine).await;
+    seed_stored_rows(&harness.db, OTHER, &theirs).await;
+
+    erase(&harness, DeletionConfig { retained: HashSet::new(), ..DeletionConfig::default() }).await;
+
+    let db = &harness.db;
+    for (table, column) in [
+        ("orders", "user_id"),
+        ("trade_records", "user_id"),
+        ("copy_executions", "follower_user_id"),
+        ("lending_operations", "telegram_id"),
+        ("send_batches", "telegram_id"),
+        ("dca_strategies", "user_id"),
+        ("copy_daily_risk", "follower_user_id"),
+        ("master_fee_ledgers", "master_user_id"),
+    ] {
+        assert_eq!(count(db, table, column, USER).await, 0, "{} kept rows", table);
+        assert!(count(db, table, column, OTHER).await > 0, "{} lost another user's rows", table);
+    }
+    assert!(db.get_order_executions(&format!("order-{}", USER)).await.unwrap().is_empty());
+    assert_eq!(db.get_order_executions(&format!("order-{}", OTHER)).await.unwrap().len(), 1);
+    assert_eq!(db.get_user_rebates(&USER.to_string()).await.unwrap().all_time, 0.0);
+    assert_eq!(db.get_user_rebates(&OTHER.to_string()).await.unwrap().all_time, 0.5);
+    assert!(db.get_trading_mode(&mine).await.unwrap().is_none());
+    assert!(db.get_paper_ledger(&mine).await.unwrap().is_none());
+    assert!(db.get_trading_mode(&theirs).await.unwrap().is_some());
+    assert!(db.get_user_wallets(&USER.to_string()).await.unwrap().is_empty());
+
+    // /export_trades has nothing left to return
+    assert!(harness.services.trade_history.ledger(USER, None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_erasure_keeps_retained_trade_history_and_fees_in_storage() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let wallet = harness.register_user(USER, 1.0).await.unwrap().pubkey().to_string();
+    seed_stored_rows(&harness.db, USER, &wallet).await;
+
+    erase(&harness, DeletionConfig::default()).await;
+
+    let db = &harness.db;
+    assert_eq!(count(db, "trade_records", "user_id", USER).await, 1);
+    assert_eq!(count(db, "copy_executions", "follower_user_id", USER).await, 1);
+    assert_eq!(count(db, "lending_operations", "telegram_id", USER).await, 1);
+    assert_eq!(count(db, "master_fee_ledgers", "master_user_id", USER).await, 1);
+    // The filled order stays with its fill; the one that never filled goes
+    assert_eq!(count(db, "orders", "user_id", USER).await, 1);
+    assert_eq!(db.get_order_executions(&format!("order-{}", USER)).await.unwrap().len(), 1);
+    // Automation and settings aren't trade history
+    assert_eq!(count(db, "dca_strategies", "user_id", USER).await, 0);
+    assert_eq!(count(db, "copy_daily_risk", "follower_user_id", USER).await, 0);
+    assert!(db.get_paper_ledger(&wallet).await.unwrap().is_none());
+}
@@ -0,0 +1,157 @@
+use crate::api::{ApiTier, JupiterV6Client};
+use crate::testkit::TestHarness;
+use crate::trading::{DCAEngine, DCAScheduler, DCAStatus, DCAStrategy, ScheduleConfig, ScheduleType, TokenResolver};
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+const USER_ID: i64 = 762_001;
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+async fn engine() -> (TestHarness, Arc<DCAEngine>) {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&mint, 1.0).build().await.unwrap();
+    let engine = Arc::new(DCAEngine::new(
+        Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())),
+        harness.price_client.clone(),
+        harness.db.clone(),
+        None,
+    ));
+    (harness, engine)
+}
+
+/// A daily BONK strategy whose next run is already due
+async fn due_strategy(engine: &DCAEngine, id: &str) -> DCAStrategy {
+    let mut strategy = DCAStrategy::create_daily_dca(
+        USER_ID,
+        "BONK daily".to_string(),
+        USDC.to_string(),
+        TokenResolver::resolve("BONK").unwrap(),
+        Decimal::from(1_000),
+        Decimal::from(100),
+    );
+    strategy.strategy_id = id.to_string();
+    strategy.next_execution = Utc::now() - Duration::minutes(1);
+    engine.restore_strategies(vec![strategy.clone()]).await.unwrap();
+    strategy
+}
+
+async fn stored(engine: &DCAEngine, id: &str) -> DCAStrategy {
+    engine.get_user_strategies(USER_ID).await.into_iter().find(|s| s.strategy_id == id).unwrap()
+}
+
+/// Daily at midnight UTC; the scheduler looks schedules up by strategy id
+fn daily_schedule(strategy_id: &str) -> ScheduleConfig {
+    let mut config = ScheduleConfig::create_daily_schedule(strategy_id.to_string(), "test".to_string(), 0, 0, "UTC".to_string());
+    config.schedule_id = strategy_id.to_string();
+    config
+}
+
+/// Fires every second, so the test only waits for the next whole second
+fn every_second(strategy_id: &str) -> ScheduleConfig {
+    let mut config = daily_schedule(strategy_id);
+    config.schedule_type = ScheduleType::Cron { expression: "* * * * * *".to_string(), description: None };
+    config
+}
+
+#[tokio::test]
+async fn paused_strategy_does_not_run_and_resume_drops_missed_runs() {
+    let (harness, engine) = engine().await;
+    due_strategy(&engine, "dca-pause").await;
+
+    let paused = engine.pause_strategy("dca-pause").await.unwrap();
+    assert_eq!(paused.status, DCAStatus::Paused);
+
+    assert_eq!(engine.execute_pending_strategies().await.unwrap(), 0);
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 0);
+
+    let resumed = engine.resume_strategy("dca-pause").await.unwrap();
+    assert_eq!(resumed.status, DCAStatus::Active);
+    // The run due while paused is not caught up
+    assert!(resumed.next_execution > Utc::now());
+    assert_eq!(engine.execute_pending_strategies().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn skip_next_drops_exactly_one_run() {
+    let (harness, engine) = engine().await;
+    due_strategy(&engine, "dca-skip").await;
+
+    let marked = engine.skip_next_execution("dca-skip").await.unwrap();
+    assert!(marked.skip_next);
+
+    assert_eq!(engine.execute_pending_strategies().await.unwrap(), 0);
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 0);
+
+    let after = stored(&engine, "dca-skip").await;
+    assert!(!after.skip_next);
+    assert_eq!(after.execution_count, 0);
+    assert!(after.next_execution > Utc::now());
+    assert_eq!(after.status, DCAStatus::Active);
+}
+
+#[tokio::test]
+async fn bulk_pause_and_resume_touch_only_matching_strategies() {
+    let (_harness, engine) = engine().await;
+    due_strategy(&engine, "dca-a").await;
+    due_strategy(&engine, "dca-b").await;
+    engine.pause_strategy("dca-b").await.unwrap();
+
+    assert_eq!(engine.pause_user_strategies(USER_ID).await.unwrap(), 1);
+    assert_eq!(engine.strategy_status("dca-a").await, Some(DCAStatus::Paused));
+
+    assert_eq!(engine.resume_user_strategies(USER_ID).await.unwrap(), 2);
+    assert_eq!(engine.strategy_status("dca-b").await, Some(DCAStatus::Active));
+}
+
+#[tokio::test]
+async fn finished_strategies_cannot_be_paused() {
+    let (_harness, engine) = engine().await;
+    let mut strategy = due_strategy(&engine, "dca-done").await;
+    strategy.status = DCAStatus::Completed;
+    engine.restore_strategies(vec![strategy]).await.unwrap();
+
+    assert!(engine.pause_strategy("dca-done").await.is_err());
+    assert!(engine.skip_next_execution("dca-done").await.is_err());
+    assert!(engine.pause_strategy("missing").await.is_err());
+}
+
+#[tokio::test]
+async fn scheduler_holds_paused_strategies_out_of_the_queue() {
+    let (_harness, engine) = engine().await;
+    due_strategy(&engine, "dca-sched").await;
+    engine.pause_strategy("dca-sched").await.unwrap();
+    let scheduler = DCAScheduler::new(engine.clone(), None);
+
+    scheduler.add_schedule(daily_schedule("dca-sched")).await.unwrap();
+    assert!(scheduler.queued_executions().await.is_empty());
+
+    engine.resume_strategy("dca-sched").await.unwrap();
+    scheduler.run_cycle().await.unwrap();
+
+    let queued = scheduler.queued_executions().await;
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].strategy_id, "dca-sched");
+}
+
+#[tokio::test]
+async fn scheduler_consumes_one_occurrence_per_skip() {
+    let (_harness, engine) = engine().await;
+    due_strategy(&engine, "dca-sched-skip").await;
+    let scheduler = DCAScheduler::new(engine.clone(), None);
+    scheduler.add_schedule(every_second("dca-sched-skip")).await.unwrap();
+    engine.skip_next_execution("dca-sched-skip").await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(1_100)).await;
+    scheduler.run_cycle().await.unwrap();
+
+    // Skipped, not executed, and the next occurrence is queued
+    assert_eq!(scheduler.get_execution_stats().await.total_executions, 0);
+    assert_eq!(scheduler.queued_executions().await.len(), 1);
+    assert!(!stored(&engine, "dca-sched-skip").await.skip_next);
+
+    tokio::time::sleep(std::time::Duration::from_millis(1_100)).await;
+    scheduler.run_cycle().await.unwrap();
+
+    assert_eq!(scheduler.get_execution_stats().await.total_executions, 1);
+}
@@ -0,0 +1,143 @@
+use crate::trading::{
+    DCAExecution, DCAExecutionEvent, DCAExecutionOutcome, ExecutionReason, ExecutionReport, MarketConditions,
+    NotificationChannel, NotificationConfig, TimeWindow,
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Asia::Tokyo;
+use rust_decimal::Decimal;
+
+const USER_ID: i64 = 763_001;
+
+fn local(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    // 2026-10-16 is a Friday
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn fill(spent: i64, received: i64) -> DCAExecution {
+    DCAExecution {
+        execution_id: "exec-1".to_string(),
+        strategy_id: "dca-report".to_string(),
+        executed_at: Utc::now(),
+        input_amount: Decimal::from(spent),
+        output_amount: Decimal::from(received),
+        price_at_execution: Decimal::ONE,
+        slippage_bps: 12,
+        gas_fees: Decimal::new(1, 3),
+        transaction_signature: None,
+        execution_reason: ExecutionReason::ScheduledInterval,
+        market_conditions: MarketConditions {
+            token_price: Decimal::ONE,
+            volume_24h: None,
+            volatility: None,
+            rsi: None,
+            fear_greed_index: None,
+            social_sentiment: None,
+            market_cap_rank: None,
+        },
+        success: true,
+        error_message: None,
+        report: ExecutionReport::default(),
+        sizing: None,
+    }
+}
+
+fn event(outcome: DCAExecutionOutcome, total_spent: i64, total_received: i64, fills: usize) -> DCAExecutionEvent {
+    DCAExecutionEvent {
+        user_id: USER_ID,
+        strategy_id: "dca-report".to_string(),
+        strategy_name: "BONK weekly".to_string(),
+        input_symbol: "USDC".to_string(),
+        output_symbol: "BONK".to_string(),
+        outcome,
+        total_spent: Decimal::from(total_spent),
+        total_received: Decimal::from(total_received),
+        fills,
+        // 09:00 in Tokyo
+        next_execution: Some(Utc.with_ymd_and_hms(2026, 10, 23, 0, 0, 0).unwrap()),
+        timezone: Tokyo,
+        recipients: vec![USER_ID],
+    }
+}
+
+#[test]
+fn successful_run_reports_amounts_cost_basis_and_next_run() {
+    let text = event(DCAExecutionOutcome::Filled(fill(100, 400)), 300, 1_000, 3).render();
+
+    assert!(text.starts_with("✅ DCA filled · BONK weekly"));
+    assert!(text.contains("Spent: 100 USDC"));
+    assert!(text.contains("Received: 400 BONK"));
+    assert!(text.contains("Avg cost: 0.3 USDC per BONK over 3 fills"));
+    assert!(text.contains("Next run: Fri Oct 23, 09:00 JST"));
+}
+
+#[test]
+fn failed_run_reports_the_reason_and_keeps_the_basis_so_far() {
+    let failed = DCAExecutionOutcome::Failed { reason: "Slippage 300 exceeds maximum 100".to_string() };
+    let text = event(failed.clone(), 100, 400, 1).render();
+
+    assert!(text.starts_with("❌ DCA failed · BONK weekly"));
+    assert!(text.contains("Slippage 300 exceeds maximum 100"));
+    assert!(!text.contains("Spent:"));
+    assert!(text.contains("Avg cost: 0.25 USDC per BONK over 1 fill\n"));
+    assert!(text.contains("Next run: Fri Oct 23, 09:00 JST"));
+
+    // First run failed and nothing more is scheduled
+    let mut first = event(failed, 0, 0, 0);
+    first.next_execution = None;
+    let text = first.render();
+    assert!(!text.contains("Avg cost"));
+    assert!(text.ends_with("No further runs scheduled"));
+}
+
+#[test]
+fn quiet_hours_hold_fills_but_not_failures() {
+    let config = NotificationConfig::default();
+
+    // 03:00 local is inside the default 22:00-08:00 window
+    assert!(config.telegram_recipients(USER_ID, true, local(16, 3, 0)).is_empty());
+    assert_eq!(config.telegram_recipients(USER_ID, false, local(16, 3, 0)), vec![USER_ID]);
+    assert_eq!(config.telegram_recipients(USER_ID, true, local(16, 12, 0)), vec![USER_ID]);
+
+    // Opting in to night reports
+    let night_owl = NotificationConfig { quiet_hours: None, ..NotificationConfig::default() };
+    assert_eq!(night_owl.telegram_recipients(USER_ID, true, local(16, 3, 0)), vec![USER_ID]);
+}
+
+#[test]
+fn selected_channels_decide_the_chats() {
+    let config = NotificationConfig {
+        notification_channels: vec![
+            NotificationChannel::Telegram { chat_id: -100_555 },
+            NotificationChannel::Email { address: "me@example.com".to_string() },
+        ],
+        quiet_hours: None,
+        ..NotificationConfig::default()
+    };
+    assert_eq!(config.telegram_recipients(USER_ID, true, local(16, 12, 0)), vec![-100_555]);
+
+    let email_only = NotificationConfig {
+        notification_channels: vec![NotificationChannel::Email { address: "me@example.com".to_string() }],
+        ..NotificationConfig::default()
+    };
+    assert!(email_only.telegram_recipients(USER_ID, false, local(16, 12, 0)).is_empty());
+
+    let failures_only = NotificationConfig { notify_on_execution: false, quiet_hours: None, ..NotificationConfig::default() };
+    assert!(failures_only.telegram_recipients(USER_ID, true, local(16, 12, 0)).is_empty());
+}
+
+#[test]
+fn overnight_window_belongs_to_the_day_it_opens() {
+    // Friday and Saturday nights only
+    let weekend_nights = TimeWindow {
+        start_time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        days_of_week: vec![Weekday::Fri, Weekday::Sat],
+    };
+
+    assert!(weekend_nights.contains(local(16, 23, 30)));
+    // Saturday 02:00 is still Friday night
+    assert!(weekend_nights.contains(local(17, 2, 0)));
+    // Friday 02:00 is Thursday night
+    assert!(!weekend_nights.contains(local(16, 2, 0)));
+    assert!(!weekend_nights.contains(local(17, 12, 0)));
+}
@@ -0,0 +1,203 @@
+use crate::api::{ApiTier, JupiterV6Client};
+use crate::bot::handlers::DcaHandler;
+use crate::testkit::TestHarness;
+use crate::trading::{
+    parse_clock, CronSchedule, DCAEngine, DCAInterval, DCAScheduler, DCAStrategy, ScheduleConfig, TimeWindow,
+    TokenResolver, US_EQUITIES,
+};
+use chrono::{DateTime, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+use chrono_tz::{America::New_York, Asia::Tokyo, Tz};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+const USER_ID: i64 = 805_001;
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+}
+
+fn cron(expression: &str) -> CronSchedule {
+    CronSchedule::parse(expression).unwrap()
+}
+
+/// Every run of `schedule` from `from` up to `until`
+fn runs(schedule: &CronSchedule, tz: Tz, from: DateTime<Utc>, until: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let mut runs = Vec::new();
+    let mut after = from;
+    while let Some(next) = schedule.next_after(after, tz).filter(|next| *next < until) {
+        runs.push(next);
+        after = next;
+    }
+    runs
+}
+
+async fn scheduler() -> (TestHarness, Arc<DCAEngine>, DCAScheduler) {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let engine = Arc::new(DCAEngine::new(
+        Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())),
+        harness.price_client.clone(),
+        harness.db.clone(),
+        None,
+    ));
+    let scheduler = DCAScheduler::new(engine.clone(), None);
+    (harness, engine, scheduler)
+}
+
+fn schedule(expression: &str, timezone: &str) -> ScheduleConfig {
+    let mut config = ScheduleConfig::create_cron_schedule(
+        "dca-805".to_string(),
+        "test".to_string(),
+        expression.to_string(),
+        None,
+        timezone.to_string(),
+    );
+    config.schedule_id = config.strategy_id.clone();
+    config
+}
+
+#[test]
+fn test_spring_forward_day_runs_every_slot_once() {
+    // 02:00-03:00 does not exist in New York on 2026-03-08
+    let daily = cron("30 2 * * *");
+    let gap = daily.next_after(utc(2026, 3, 7, 12, 0), New_York).unwrap();
+    assert_eq!(gap, utc(2026, 3, 8, 7, 30)); // 03:30 EDT
+    assert_eq!(daily.next_after(gap, New_York).unwrap(), utc(2026, 3, 9, 6, 30)); // 02:30 EDT
+
+    // Local midnight to midnight is 23 hours; the missing 02:00 merges into 03:00
+    let hourly = runs(&cron("0 * * * *"), New_York, utc(2026, 3, 8, 4, 59), utc(2026, 3, 9, 4, 0));
+    assert_eq!(hourly.len(), 23);
+    assert_eq!(hourly[0], utc(2026, 3, 8, 5, 0)); // 00:00 EST
+    assert_eq!(hourly[2], utc(2026, 3, 8, 7, 0)); // 03:00 EDT
+    assert!(hourly.windows(2).all(|pair| pair[1] - pair[0] == chrono::Duration::hours(1)));
+    let local_hours: Vec<u32> = hourly.iter().map(|run| run.with_timezone(&New_York).hour()).collect();
+    assert!(!local_hours.contains(&2));
+    assert_eq!(local_hours.iter().filter(|hour| **hour == 3).count(), 1);
+}
+
+#[test]
+fn test_fall_back_day_does_not_double_fire() {
+    // 01:00-02:00 happens twice in New York on 2026-11-01
+    let daily = cron("30 1 * * *");
+    let first = daily.next_after(utc(2026, 10, 31, 12, 0), New_York).unwrap();
+    assert_eq!(first, utc(2026, 11, 1, 5, 30)); // 01:30 EDT
+    // The second 01:30 (06:30 UTC) is not a new slot
+    assert_eq!(daily.next_after(first, New_York).unwrap(), utc(2026, 11, 2, 6, 30)); // 01:30 EST
+
+    // Local midnight to midnight is 25 hours but holds 24 slots
+    let hourly = runs(&cron("0 * * * *"), New_York, utc(2026, 11, 1, 3, 59), utc(2026, 11, 2, 5, 0));
+    assert_eq!(hourly.len(), 24);
+    let mut local_hours: Vec<u32> = hourly.iter().map(|run| run.with_timezone(&New_York).hour()).collect();
+    local_hours.dedup();
+    assert_eq!(local_hours, (0..24).collect::<Vec<_>>());
+    // The repeated hour is skipped in real time
+    assert_eq!(hourly[2] - hourly[1], chrono::Duration::hours(2));
+}
+
+#[test]
+fn test_phrases_and_cron_subset() {
+    let nine = CronSchedule::from_phrase("daily at 9am").unwrap();
+    assert_eq!(nine.expression(), "0 9 * * *");
+    assert_eq!(nine.describe(), "daily at 09:00");
+    assert_eq!(nine.next_after(utc(2026, 10, 16, 0, 0), Tokyo).unwrap(), utc(2026, 10, 16, 0, 0) + chrono::Duration::days(1));
+
+    assert_eq!(CronSchedule::from_phrase("Weekdays 6:30 pm").unwrap().expression(), "30 18 * * 1-5");
+    assert_eq!(CronSchedule::from_phrase("weekdays at 18:30").unwrap().describe(), "weekdays at 18:30");
+    assert_eq!(CronSchedule::from_phrase("every mon,thurs at 12am").unwrap().describe(), "Mon, Thu at 00:00");
+    assert_eq!(CronSchedule::from_phrase("weekends at 12pm").unwrap().describe(), "weekends at 12:00");
+    assert_eq!(CronSchedule::from_phrase("hourly").unwrap().describe(), "every hour at :00");
+    assert_eq!(cron("15 8,20 * * 7").describe(), "daily at 08:15, 20:15 on Sun");
+    assert_eq!(cron("*/15 9-17 * * mon-fri").describe(), "36 times a day on weekdays");
+
+    assert!(CronSchedule::from_phrase("daily at 25:00").is_err());
+    assert!(CronSchedule::from_phrase("fortnightly at 9am").is_err());
+    assert!(cron_err("0 9 1 * *").contains("day of month"));
+    assert!(cron_err("0 9 * *").contains("expected"));
+    assert!(cron_err("60 9 * * *").contains("minute"));
+    assert!(cron_err("0 9 * * 8").contains("day of week"));
+
+    assert_eq!(parse_clock("12:05am").unwrap(), NaiveTime::from_hms_opt(0, 5, 0).unwrap());
+    assert!(parse_clock("13pm").is_err());
+}
+
+fn cron_err(expression: &str) -> String {
+    CronSchedule::parse(expression).unwrap_err().to_string()
+}
+
+#[test]
+fn test_dca_new_arguments() {
+    let new = DcaHandler::parse_new(&["25", "bonk", "daily", "at", "9am"], "en").unwrap();
+    assert_eq!((new.amount, new.token.as_str(), new.runs), (Decimal::from(25), "BONK", 30));
+    assert_eq!(new.schedule.expression(), "0 9 * * *");
+    assert!(!new.market_hours_only && new.window.is_none());
+
+    let gated = DcaHandler::parse_new(&["10", "JUP", "x12", "weekdays", "at", "16:00", "during", "market", "hours"], "en").unwrap();
+    assert_eq!(gated.runs, 12);
+    assert!(gated.market_hours_only);
+    assert_eq!(gated.schedule.expression(), "0 16 * * 1-5");
+
+    let windowed = DcaHandler::parse_new(&["5", "WIF", "cron", "*/30", "*", "*", "*", "*", "only", "08:00-22:00"], "en").unwrap();
+    let window = windowed.window.unwrap();
+    assert_eq!((window.start_time.hour(), window.end_time.hour()), (8, 22));
+    assert_eq!(windowed.schedule.expression(), "*/30 * * * *");
+
+    assert!(DcaHandler::parse_new(&["0", "BONK", "daily", "at", "9am"], "en").is_err());
+    assert!(DcaHandler::parse_new(&["25", "BONK", "x0", "daily", "at", "9am"], "en").is_err());
+    assert!(DcaHandler::parse_new(&["25", "BONK"], "en").is_err());
+}
+
+#[tokio::test]
+async fn test_runs_wait_for_market_hours_and_local_windows() {
+    let (_harness, _engine, scheduler) = scheduler().await;
+
+    // Friday 16:00 EST, after the close; Monday is already on EDT
+    let mut market = schedule("0 * * * *", "America/New_York");
+    market.market_hours_only = true;
+    let next = scheduler.next_run_after(&market, utc(2026, 3, 6, 21, 0)).await.unwrap();
+    assert_eq!(next, utc(2026, 3, 9, 14, 0)); // 10:00 EDT, the first slot after the 09:30 open
+    assert!(scheduler.market_hours().is_open(US_EQUITIES, next));
+    assert!(!scheduler.market_hours().is_open(US_EQUITIES, utc(2026, 3, 7, 15, 0)));
+
+    // 22:10 in Tokyo: waits for 08:00
+    let mut local = schedule("*/30 * * * *", "Asia/Tokyo");
+    local.execution_window = Some(TimeWindow {
+        start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+        days_of_week: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun],
+    });
+    assert_eq!(scheduler.next_run_after(&local, utc(2026, 10, 16, 13, 10)).await.unwrap(), utc(2026, 10, 16, 23, 0));
+    assert_eq!(scheduler.next_run_after(&local, utc(2026, 10, 16, 1, 10)).await.unwrap(), utc(2026, 10, 16, 1, 30));
+
+    // A slot that never falls inside its window is refused
+    local.schedule_type = schedule("0 7 * * *", "Asia/Tokyo").schedule_type;
+    assert!(scheduler.next_run_after(&local, utc(2026, 10, 16, 13, 10)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_queued_runs_follow_the_owners_timezone() {
+    let (_harness, engine, scheduler) = scheduler().await;
+    engine.timezones().set_user_timezone(USER_ID, "America/New_York").await.unwrap();
+    let mut strategy = DCAStrategy::create_daily_dca(
+        USER_ID,
+        "BONK daily".to_string(),
+        USDC.to_string(),
+        TokenResolver::resolve("BONK").unwrap(),
+        Decimal::from(300),
+        Decimal::from(10),
+    );
+    strategy.strategy_id = "dca-805".to_string();
+    strategy.interval = DCAInterval::Custom { cron_expression: "0 9 * * *".to_string() };
+    engine.create_strategy(strategy).await.unwrap();
+
+    // The engine and the scheduler agree on 09:00 New York time
+    let stored = engine.strategy("dca-805").await.unwrap();
+    assert_eq!(stored.next_execution.with_timezone(&New_York).hour(), 9);
+
+    scheduler.add_schedule(schedule("0 9 * * *", "")).await.unwrap();
+    let queued = scheduler.queued_executions().await;
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].execute_at, stored.next_execution);
+    assert_eq!(queued[0].timezone, "America/New_York");
+    assert_eq!(queued[0].local_time, queued[0].execute_at.with_timezone(&New_York).naive_local());
+    assert!(queued[0].local_display().ends_with("09:00 America/New_York"), "{}", queued[0].local_display());
+}
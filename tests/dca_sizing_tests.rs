@@ -0,0 +1,159 @@
+use crate::api::{ApiTier, JupiterV6Client};
+use crate::testkit::TestHarness;
+use crate::trading::{
+    DCAEngine, DCAExecutionEvent, DCAExecutionOutcome, DCAStrategy, MarketRegime, MarketRegimeDetector,
+    PricePoint, RiskBasedDCAManager, SizingAdjustment, TokenResolver,
+};
+use chrono::{Duration, Utc};
+use chrono_tz::UTC;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+const USER_ID: i64 = 806_001;
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn bull(strength: f64) -> MarketRegime {
+    MarketRegime::Bull { strength, duration_days: 30, trend_slope: 0.02 }
+}
+
+fn bear(strength: f64) -> MarketRegime {
+    MarketRegime::Bear { strength, duration_days: 30, trend_slope: -0.02 }
+}
+
+fn sideways() -> MarketRegime {
+    MarketRegime::Sideways { volatility: 0.05, range_bound: (Decimal::from(90), Decimal::from(110)) }
+}
+
+fn prices(values: &[i64]) -> Vec<PricePoint> {
+    let start = Utc::now() - Duration::days(values.len() as i64);
+    values.iter().enumerate().map(|(day, value)| PricePoint {
+        timestamp: start + Duration::days(day as i64),
+        price: Decimal::from(*value),
+        volume: None,
+        returns: None,
+        volatility: None,
+    }).collect()
+}
+
+#[test]
+fn test_multiplier_respects_strategy_bounds() {
+    let regimes = [bull(1.0), bull(0.3), bear(1.0), bear(0.3), sideways()];
+    let bounds = [(0.5, 2.0), (0.5, 1.5), (0.8, 1.2), (1.0, 1.0)];
+    for regime in &regimes {
+        for drawdown_pct in [0.0, -5.0, -18.0, -60.0, -95.0] {
+            for fear_greed in [None, Some(5), Some(50), Some(95)] {
+                for model_factor in [0.3, 0.7, 1.0] {
+                    for (min, max) in bounds {
+                        let sizing = SizingAdjustment::compute(regime.clone(), drawdown_pct, fear_greed, model_factor, (min, max));
+                        assert!(
+                            (min..=max).contains(&sizing.multiplier),
+                            "{:?} {}% {:?} {} gave {} outside {}-{}",
+                            regime, drawdown_pct, fear_greed, model_factor, sizing.multiplier, min, max
+                        );
+                        assert_eq!(sizing.summary().is_some(), sizing.multiplier != 1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    // Fear and drawdowns buy more, euphoria buys less
+    let crash = SizingAdjustment::compute(bear(1.0), -95.0, Some(5), 1.0, (0.5, 1.5));
+    assert_eq!(crash.multiplier, 1.5);
+    assert!(crash.reasons.contains(&"extreme fear (5)".to_string()));
+    let euphoria = SizingAdjustment::compute(bull(1.0), 0.0, Some(95), 1.0, (0.8, 1.2));
+    assert_eq!(euphoria.multiplier, 0.8);
+    assert_eq!(euphoria.summary().unwrap(), "Bought 0.8x due to euphoric rally and extreme greed (95)");
+    assert!(SizingAdjustment::compute(bear(0.4), 0.0, None, 1.0, (0.5, 2.0)).multiplier > 1.0);
+
+    let dip = SizingAdjustment::compute(bear(0.36), -18.0, None, 1.0, (0.5, 2.0));
+    assert_eq!(dip.multiplier, 1.27);
+    assert_eq!(dip.summary().unwrap(), "Bought 1.3x due to -18% drawdown");
+
+    let calm = SizingAdjustment::compute(sideways(), -4.0, Some(50), 1.0, (0.5, 2.0));
+    assert_eq!(calm.multiplier, 1.0);
+    assert!(calm.reasons.is_empty() && calm.summary().is_none());
+}
+
+#[test]
+fn test_regimes_from_price_history() {
+    let (regime, drawdown) = MarketRegimeDetector::classify(&prices(&[100, 110, 130, 150])).unwrap();
+    assert!(matches!(regime, MarketRegime::Bull { strength, .. } if (strength - 1.0).abs() < 1e-9));
+    assert_eq!(drawdown, 0.0);
+
+    let (regime, drawdown) = MarketRegimeDetector::classify(&prices(&[100, 120, 95, 80])).unwrap();
+    assert!(matches!(regime, MarketRegime::Bear { strength, .. } if (strength - 0.4).abs() < 1e-9));
+    assert!((drawdown + 100.0 / 3.0).abs() < 1e-6, "{}", drawdown);
+
+    let (regime, drawdown) = MarketRegimeDetector::classify(&prices(&[100, 104, 97, 102])).unwrap();
+    assert!(matches!(regime, MarketRegime::Sideways { range_bound, .. } if range_bound == (Decimal::from(97), Decimal::from(104))));
+    assert!((drawdown + 1.923).abs() < 1e-3, "{}", drawdown);
+
+    assert!(MarketRegimeDetector::classify(&prices(&[100])).is_none());
+    assert!(MarketRegimeDetector::classify(&[]).is_none());
+}
+
+#[tokio::test]
+async fn test_drawdown_scales_the_buy_unless_disabled() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&mint, 1.0).build().await.unwrap();
+    let risk = Arc::new(RiskBasedDCAManager::new(harness.price_client.clone(), None));
+    let engine = DCAEngine::new(
+        Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())),
+        harness.price_client.clone(),
+        harness.db.clone(),
+        None,
+    )
+    .with_risk_manager(risk.clone());
+
+    let mut strategy = DCAStrategy::create_daily_dca(
+        USER_ID,
+        "BONK daily".to_string(),
+        USDC.to_string(),
+        mint.clone(),
+        Decimal::from(1_000),
+        Decimal::from(100),
+    );
+    strategy.strategy_id = "dca-806".to_string();
+    assert!(strategy.advanced_config.dynamic_sizing);
+
+    // Down 18% from last week's peak
+    risk.record_price(&mint, Decimal::new(122, 2), Utc::now() - Duration::days(7)).await;
+    let execution = engine.execute_strategy(&strategy).await.unwrap();
+    let sizing = execution.sizing.clone().unwrap();
+    assert_eq!(sizing.multiplier, 1.27);
+    assert!(matches!(sizing.regime, MarketRegime::Bear { .. }));
+    assert!((sizing.drawdown_pct + 18.03).abs() < 0.01, "{}", sizing.drawdown_pct);
+    assert_eq!(execution.input_amount, Decimal::from(127));
+
+    let text = DCAExecutionEvent {
+        user_id: USER_ID,
+        strategy_id: strategy.strategy_id.clone(),
+        strategy_name: strategy.name.clone(),
+        input_symbol: "USDC".to_string(),
+        output_symbol: "BONK".to_string(),
+        outcome: DCAExecutionOutcome::Filled(execution.clone()),
+        total_spent: execution.input_amount,
+        total_received: execution.output_amount,
+        fills: 1,
+        next_execution: None,
+        timezone: UTC,
+        recipients: vec![USER_ID],
+    }.render();
+    assert!(text.contains("Bought 1.3x due to -18% drawdown"), "{}", text);
+
+    // Switched off, the same conditions buy the base amount
+    engine.create_strategy(strategy.clone()).await.unwrap();
+    let disabled = engine.toggle_dynamic_sizing("dca-806").await.unwrap();
+    assert!(!disabled.advanced_config.dynamic_sizing);
+    let execution = engine.execute_strategy(&disabled).await.unwrap();
+    assert_eq!(execution.input_amount, Decimal::from(100));
+    assert!(execution.sizing.is_none());
+
+    // Bounds are validated with the strategy
+    let mut inverted = strategy.clone();
+    inverted.strategy_id = "dca-806-bad".to_string();
+    inverted.advanced_config.min_size_multiplier = 1.5;
+    inverted.advanced_config.max_size_multiplier = 1.2;
+    assert!(engine.create_strategy(inverted).await.is_err());
+}
@@ -0,0 +1,95 @@
+use crate::trading::{DCAAnchor, DCAInterval};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::{America::New_York, Asia::Tokyo, Europe::London};
+
+fn at(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn test_restart_does_not_repeat_completed_slot() {
+    let anchor = DCAAnchor::daily(at(9, 0));
+    // 09:05 in Tokyo, five minutes after today's slot
+    let now = utc(2026, 10, 16, 0, 5);
+
+    // Today's slot already ran before the restart: next is tomorrow
+    let next = DCAInterval::Daily.next_anchored_slot(&anchor, Tokyo, now, Some("2026-10-16")).unwrap();
+    assert_eq!(next.slot_id, "2026-10-17");
+    assert_eq!(next.run_at, utc(2026, 10, 17, 0, 0));
+
+    // The process was down over today's slot: caught up once, now
+    let missed = DCAInterval::Daily.next_anchored_slot(&anchor, Tokyo, now, Some("2026-10-15")).unwrap();
+    assert_eq!(missed.slot_id, "2026-10-16");
+    assert!(missed.run_at <= now);
+
+    // A new strategy never starts with a slot in the past
+    let fresh = DCAInterval::Daily.next_anchored_slot(&anchor, Tokyo, now, None).unwrap();
+    assert_eq!(fresh.slot_id, "2026-10-17");
+
+    // Sub-daily intervals are not anchored
+    assert!(DCAInterval::Hourly.next_anchored_slot(&anchor, Tokyo, now, None).is_none());
+}
+
+#[test]
+fn test_spring_forward_runs_after_the_gap() {
+    // 02:30 does not exist in New York on 2026-03-08
+    let anchor = DCAAnchor::daily(at(2, 30));
+
+    let before = DCAInterval::Daily.next_anchored_slot(&anchor, New_York, utc(2026, 3, 7, 0, 0), Some("2026-03-06")).unwrap();
+    assert_eq!(before.run_at, utc(2026, 3, 7, 7, 30)); // 02:30 EST
+
+    let gap = DCAInterval::Daily.next_anchored_slot(&anchor, New_York, utc(2026, 3, 7, 12, 0), Some("2026-03-07")).unwrap();
+    assert_eq!(gap.slot_id, "2026-03-08");
+    assert_eq!(gap.run_at, utc(2026, 3, 8, 7, 30)); // 03:30 EDT
+
+    let after = DCAInterval::Daily.next_anchored_slot(&anchor, New_York, gap.run_at, Some("2026-03-08")).unwrap();
+    assert_eq!(after.run_at, utc(2026, 3, 9, 6, 30)); // 02:30 EDT
+}
+
+#[test]
+fn test_fall_back_runs_repeated_hour_once() {
+    // 01:30 happens twice in New York on 2026-11-01
+    let anchor = DCAAnchor::daily(at(1, 30));
+
+    let first = DCAInterval::Daily.next_anchored_slot(&anchor, New_York, utc(2026, 10, 31, 12, 0), Some("2026-10-31")).unwrap();
+    assert_eq!(first.slot_id, "2026-11-01");
+    assert_eq!(first.run_at, utc(2026, 11, 1, 5, 30)); // 01:30 EDT, first occurrence
+    assert_eq!(DCAAnchor::slot_id(first.run_at, New_York), "2026-11-01");
+
+    // Before and after the second 01:30 EST, the completed slot is not repeated
+    for now in [utc(2026, 11, 1, 6, 0), utc(2026, 11, 1, 6, 31)] {
+        let next = DCAInterval::Daily.next_anchored_slot(&anchor, New_York, now, Some("2026-11-01")).unwrap();
+        assert_eq!(next.slot_id, "2026-11-02");
+        assert_eq!(next.run_at, utc(2026, 11, 2, 6, 30)); // 01:30 EST
+    }
+}
+
+#[test]
+fn test_weekly_schedule_does_not_drift_over_a_month() {
+    let anchor = DCAAnchor::weekly(Weekday::Mon, at(8, 0));
+    let mut now = utc(2026, 3, 1, 12, 0);
+    let mut last: Option<String> = None;
+    let mut runs = Vec::new();
+
+    for _ in 0..5 {
+        let slot = DCAInterval::Weekly.next_anchored_slot(&anchor, London, now, last.as_deref()).unwrap();
+        runs.push(slot.run_at);
+        last = Some(slot.slot_id);
+        // Scheduler ticks late; the next run must not inherit the lag
+        now = slot.run_at + Duration::minutes(47);
+    }
+
+    for run in &runs {
+        let local = run.with_timezone(&London);
+        assert_eq!(local.weekday(), Weekday::Mon);
+        assert_eq!(local.time(), at(8, 0));
+    }
+    assert_eq!(runs[0], utc(2026, 3, 2, 8, 0));
+    // Clocks go forward on 2026-03-29: same local time, an hour earlier in UTC
+    assert_eq!(runs[4], utc(2026, 3, 30, 7, 0));
+    assert_eq!(runs[4] - runs[3], Duration::days(7) - Duration::hours(1));
+}
@@ -0,0 +1,132 @@
+use crate::api::BulkSendResponse;
+use crate::bot::distribution::{
+    aggregate_results, Distribution, Recipient, RecipientList, RecipientOutcome, SendPreview, MAX_RECIPIENTS,
+    SEND_NETWORK_FEE_LAMPORTS,
+};
+use crate::bot::handlers::SendHandler;
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+const ALICE: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const BOB: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+
+fn bulk_response(sends: serde_json::Value) -> BulkSendResponse {
+    serde_json::from_value(json!({
+        "batchId": "batch-1",
+        "totalAmount": 0,
+        "recipientCount": 3,
+        "successfulSends": 2,
+        "failedSends": 1,
+        "sends": sends,
+        "createdAt": "2026-05-01T12:00:00Z",
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_csv_rows_parse_with_header_comments_and_per_row_amounts() {
+    let csv = format!(
+        "address,amount\n\
+        # giveaway winners\n\
+        {ALICE},25\n\
+        \n\
+        @giveaway_fan\n\
+        {BOB};abc\n\
+        not-an-address,5\n\
+        {ALICE},10\n"
+    );
+    let list = RecipientList::from_csv(csv.as_bytes(), 100.0).unwrap();
+
+    assert_eq!(list.allocations.len(), 2);
+    assert_eq!(list.allocations[0].recipient, Recipient::Address(ALICE.to_string()));
+    assert_eq!(list.allocations[0].amount, 25.0);
+    assert_eq!(list.allocations[1].recipient, Recipient::Handle("@giveaway_fan".to_string()));
+    assert_eq!(list.allocations[1].amount, 100.0);
+
+    let reasons: Vec<&str> = list.rejected.iter().map(|r| r.reason.as_str()).collect();
+    assert_eq!(list.rejected.len(), 3, "{:?}", reasons);
+    assert!(reasons[0].contains("line 6") && reasons[0].contains("positive number"), "{:?}", reasons);
+    assert_eq!(reasons[1], "not a wallet address or @username");
+    assert_eq!(reasons[2], "listed more than once");
+}
+
+#[test]
+fn test_oversized_csv_is_refused_and_args_share_one_amount() {
+    let csv: String = (0..=MAX_RECIPIENTS).map(|i| format!("@user_{:05}\n", i)).collect();
+    assert!(RecipientList::from_csv(csv.as_bytes(), 1.0).is_err());
+
+    let list = RecipientList::from_args(["@alice_sol", BOB, "@x"], 2.5);
+    assert_eq!(list.allocations.len(), 2);
+    assert_eq!(list.total(), 5.0);
+    assert_eq!(list.rejected[0].reason, "not a valid @username");
+}
+
+#[test]
+fn test_preview_adds_fees_per_recipient() {
+    let list = RecipientList::from_args(["@alice_sol", "@bob_sol", ALICE, "bogus"], 1.5);
+    let priority = 10_000;
+
+    let token = SendPreview::build(&list, priority, false);
+    assert_eq!((token.recipients, token.rejected), (3, 1));
+    assert_eq!(token.total_tokens, 4.5);
+    assert_eq!(token.fee_lamports, 3 * (SEND_NETWORK_FEE_LAMPORTS + priority));
+    assert!((token.fee_sol() - 0.000045).abs() < 1e-12);
+    assert!((token.total_sol - 0.000045).abs() < 1e-12);
+
+    // Sending SOL itself puts the amount on top of the fees
+    let sol = SendPreview::build(&list, priority, true);
+    assert!((sol.total_sol - 4.500045).abs() < 1e-12, "{}", sol.total_sol);
+}
+
+#[test]
+fn test_partial_failures_are_reported_per_recipient() {
+    let list = RecipientList::from_args(["@alice_sol", ALICE, BOB, "bogus"], 10.0);
+    let response = bulk_response(json!([
+        { "recipientId": "@alice_sol", "sendId": "s1", "magicLink": "https://jup.ag/send/s1", "success": true, "error": null },
+        { "recipientId": ALICE, "sendId": "s2", "magicLink": "https://jup.ag/send/s2", "success": true, "error": null },
+        { "recipientId": BOB, "sendId": null, "magicLink": null, "success": false, "error": "insufficient balance" },
+    ]));
+
+    let results = aggregate_results(&list, Ok(&response));
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].send_id(), Some("s1"));
+    assert_eq!(results[1].send_id(), Some("s2"));
+    assert_eq!(results[2].outcome, RecipientOutcome::Failed("insufficient balance".to_string()));
+    assert_eq!(results[3].outcome, RecipientOutcome::Invalid("not a wallet address or @username".to_string()));
+
+    let created_at = Utc::now();
+    let distribution = Distribution {
+        id: "batch-1".to_string(),
+        owner: 42,
+        mint: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+        symbol: "BONK".to_string(),
+        created_at,
+        expires_at: created_at + Duration::hours(72),
+        results,
+    };
+    assert_eq!((distribution.sent(), distribution.failed()), (2, 2));
+    assert!(!distribution.reclaimable(created_at + Duration::hours(71)));
+    assert!(distribution.reclaimable(created_at + Duration::hours(72)));
+
+    let table = SendHandler::results_text(&distribution, "en");
+    assert!(table.contains("✅ 2 sent · ❌ 2 not sent"), "{}", table);
+    assert!(table.contains("✅ @alice_sol · 10.00 BONK · https://jup.ag/send/s1"), "{}", table);
+    assert!(table.contains("✅ 9WzD...AWWM · 10.00 BONK\n"), "{}", table);
+    assert!(table.contains("❌ 7xKX...gAsU · insufficient balance"), "{}", table);
+    assert!(table.contains("⛔ bogus · not a wallet address"), "{}", table);
+    assert!(table.contains("/send status batch-1"), "{}", table);
+}
+
+#[test]
+fn test_a_failed_api_call_fails_every_recipient_without_panicking() {
+    let list = RecipientList::from_args(["@alice_sol", BOB], 1.0);
+    let results = aggregate_results(&list, Err("Bulk send API requires Pro tier or above".to_string()));
+    assert!(results.iter().all(|r| matches!(&r.outcome, RecipientOutcome::Failed(e) if e.contains("Pro tier"))));
+
+    // A recipient Jupiter left out of its response is a failure, not a success
+    let response = bulk_response(json!([
+        { "recipientId": "@alice_sol", "sendId": "s1", "magicLink": null, "success": true, "error": null },
+    ]));
+    let results = aggregate_results(&list, Ok(&response));
+    assert_eq!(results[1].outcome, RecipientOutcome::Failed("missing from Jupiter's response".to_string()));
+}
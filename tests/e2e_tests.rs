@@ -0,0 +1,506 @@
+use crate::analytics::{BenchmarkBasket, CloseReason, CostBasisMethod, LotTrade, TradeRecord, TradingHoursConfig};
+use crate::api::{ApiTier, JupiterV6Client};
+use crate::testkit::{JupiterScenario, TestHarness};
+use crate::trading::{
+    AdvancedDCAConfig, CopyTradeStatus, CopyTradeType, DCAEngine, DCAInterval, DCAStatus, DCAStrategy,
+    DCAStrategyType, ExitDenomination, Order, OrderStatus, RiskParameters, TokenResolver, Trade,
+    TradeProvenance, TradeResult, TradeStatus, TradeType as LeaderboardTradeType,
+};
+use chrono::{TimeZone, Utc};
+use rust_decimal::Decimal;
+use solana_sdk::signature::Signer;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111112";
+const USER_ID: i64 = 424_242;
+
+#[tokio::test]
+async fn test_happy_path_buy_through_telegram() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .price(SOL_MINT, 200.0)
+        .price(&bonk, 0.00002)
+        .confirmation_delay(Duration::from_millis(300))
+        .build()
+        .await
+        .unwrap();
+    let keypair = harness.register_user(USER_ID, 5.0).await.unwrap();
+
+    harness.start_bot();
+    harness.telegram.inject_message(USER_ID, "/buy BONK 0.5").await;
+
+    let reply = harness.telegram
+        .wait_for_text(USER_ID, "Buy Order Executed", Duration::from_secs(10))
+        .await
+        .expect("bot should confirm the buy");
+    assert!(reply.contains("BONK"));
+    assert_eq!(harness.jupiter.call_count("legacy_quote").await, 1);
+    assert_eq!(harness.jupiter.call_count("swap").await, 1);
+
+    // User signs the returned transaction; it confirms once the RPC delay elapses
+    let signature = harness.sign_and_confirm_last_swap(&keypair).await.unwrap();
+    assert!(harness.rpc.is_confirmed(&signature).await);
+
+    let methods = harness.rpc.methods_called().await;
+    assert!(methods.iter().any(|m| m == "sendTransaction"));
+    assert!(methods.iter().any(|m| m == "getSignatureStatuses"));
+}
+
+#[tokio::test]
+async fn test_engine_fills_execution_report() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .price(SOL_MINT, 200.0)
+        .price(&bonk, 0.00002)
+        .build()
+        .await
+        .unwrap();
+    let keypair = harness.register_user(USER_ID, 5.0).await.unwrap();
+
+    let result = harness.trading_engine
+        .buy_with_rebate(keypair.pubkey().to_string(), "BONK".to_string(), 0.5)
+        .await
+        .unwrap();
+
+    let report = &result.execution;
+    assert!(report.quoted_at.is_some() && report.executed_at.is_some());
+    assert!(report.latency_ms().is_some());
+    assert_eq!(report.route.as_ref().unwrap().venues, vec!["MockDex"]);
+    assert!(report.quoted_price.is_some() && report.realized_price.is_some());
+    assert!(report.slippage_bps.is_some());
+    assert!(report.fees.is_some());
+    // Mock simulation consumes 42k units; the limit adds the 1.2x margin
+    assert_eq!(report.compute_units_consumed, Some(42_000));
+    assert_eq!(report.compute_unit_limit, Some(50_400));
+    assert!(!report.simulated);
+    assert!(report.idempotency_key.as_deref().unwrap().starts_with("buy:"));
+    // Unsigned until the user signs, so nothing was broadcast yet
+    assert_eq!(report.broadcast_attempts, 0);
+}
+
+#[tokio::test]
+async fn test_buy_without_route_reports_failure() {
+    let harness = TestHarness::builder()
+        .scenario(JupiterScenario::NoRoute)
+        .build()
+        .await
+        .unwrap();
+    harness.register_user(USER_ID, 5.0).await.unwrap();
+
+    harness.start_bot();
+    harness.telegram.inject_message(USER_ID, "/buy BONK 0.5").await;
+
+    let reply = harness.telegram
+        .wait_for_text(USER_ID, "Trade failed", Duration::from_secs(10))
+        .await
+        .expect("bot should report the failed quote");
+    assert!(reply.contains("route"));
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+    assert!(harness.rpc.submitted().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_stop_loss_trigger_to_fill() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .price(&mint, 1.0)
+        .build()
+        .await
+        .unwrap();
+
+    let order = Order::create_stop_loss(USER_ID, mint.clone(), Decimal::new(9, 1), Decimal::from(1_000));
+    let order_id = harness.order_manager.create_order(order).await.unwrap();
+
+    // Price above the stop: nothing happens
+    harness.order_manager.run_cycle().await.unwrap();
+    assert!(harness.order_manager.get_order_history(&order_id).await.is_empty());
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 0);
+
+    // Price drops through the stop: order is quoted and filled
+    harness.jupiter.set_price(&mint, 0.8).await;
+    harness.price_client.clear_cache().await;
+    harness.order_manager.run_cycle().await.unwrap();
+
+    let orders = harness.order_manager.get_user_orders(USER_ID).await;
+    assert!(matches!(orders[0].status, OrderStatus::Filled));
+
+    let history = harness.order_manager.get_order_history(&order_id).await;
+    assert_eq!(history.len(), 1);
+    assert!(history[0].success);
+    assert_eq!(history[0].amount_executed, Decimal::from(1_000));
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 1);
+
+    let report = &history[0].report;
+    assert!(report.quoted_at.is_some() && report.executed_at.is_some());
+    assert_eq!(report.route.as_ref().unwrap().venues, vec!["MockDex"]);
+    assert!(report.slippage_bps.is_some());
+    assert!(report.simulated);
+    assert_eq!(report.idempotency_key, Some(format!("order:{}", order_id)));
+
+    // The fill closed the whole position, so it lands in the journal
+    let entries = harness.services.journal.recent(USER_ID, 10).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].close.reason, CloseReason::StopLoss);
+
+    // The filled order no longer keeps its mint monitored
+    assert_eq!(harness.order_manager.monitor_stats().await.monitor_count, 0);
+}
+
+#[tokio::test]
+async fn test_order_exit_overrides_user_default() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .price(&mint, 1.0)
+        .build()
+        .await
+        .unwrap();
+    let manager = &harness.order_manager;
+
+    let mut preferences = harness.services.preferences.get(USER_ID).await;
+    preferences.exit_denomination = ExitDenomination::Usdc;
+    harness.services.preferences.set(USER_ID, preferences).await;
+
+    let follows = Order::create_stop_loss(USER_ID, mint.clone(), Decimal::new(5, 1), Decimal::from(1_000));
+    let pinned = Order::create_take_profit(USER_ID, mint.clone(), Decimal::new(11, 1), Decimal::from(1_000))
+        .with_exit_denomination(ExitDenomination::Sol);
+    let follows_id = manager.create_order(follows).await.unwrap();
+    let pinned_id = manager.create_order(pinned).await.unwrap();
+
+    let exit_of = |id: String| async move {
+        let order = manager.get_user_orders(USER_ID).await.into_iter().find(|o| o.order_id == id).unwrap();
+        manager.exit_denomination(&order).await
+    };
+    assert_eq!(exit_of(follows_id.clone()).await, ExitDenomination::Usdc);
+    assert_eq!(exit_of(pinned_id.clone()).await, ExitDenomination::Sol);
+
+    // Clearing the override follows the default again
+    manager.set_exit_denomination(&pinned_id, None).await.unwrap();
+    assert_eq!(exit_of(pinned_id).await, ExitDenomination::Usdc);
+    assert!(manager.set_exit_denomination("missing", Some(ExitDenomination::Sol)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_imported_trades_stay_off_the_leaderboard() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let leaderboard = &harness.services.leaderboard;
+    let imports = &harness.services.trade_imports;
+
+    let csv = "timestamp,side,token,token_amount,sol_amount,fee_sol,signature\n\
+        2024-05-01T10:00:00Z,buy,BONK,1000000,2.0,0.01,sigI1\n\
+        2024-05-02T10:00:00Z,sell,BONK,1000000,9.0,0.01,sigI2\n";
+    imports.preview(USER_ID, csv.as_bytes()).await.unwrap();
+    assert_eq!(imports.confirm(USER_ID).await.unwrap().inserted, 2);
+
+    let before = leaderboard.get_trader_stats(USER_ID).await.unwrap();
+    let mut last_trade = None;
+    for record in imports.records(USER_ID).await {
+        let trade = Trade {
+            token_symbol: "BONK".to_string(),
+            token_address: TokenResolver::resolve("BONK").unwrap(),
+            entry_price: 0.000002,
+            exit_price: Some(0.000009),
+            amount_sol: 2.0,
+            profit_sol: 7.0,
+            profit_percent: 350.0,
+            timestamp: record.timestamp,
+            trade_type: LeaderboardTradeType::Sell,
+            status: TradeStatus::Closed,
+            provenance: record.provenance,
+        };
+        leaderboard.record_trade(USER_ID, trade.clone()).await.unwrap();
+        last_trade = Some(trade);
+    }
+    let after = leaderboard.get_trader_stats(USER_ID).await.unwrap();
+    assert_eq!(after.total_trades, before.total_trades);
+    assert_eq!(after.total_profit_sol, before.total_profit_sol);
+    assert_eq!(after.best_trade.profit_sol, before.best_trade.profit_sol);
+    assert_eq!(after.badges, before.badges);
+
+    // The same trade executed by the bot counts
+    let mut bot_trade = last_trade.unwrap();
+    bot_trade.provenance = TradeProvenance::Bot;
+    leaderboard.record_trade(USER_ID, bot_trade).await.unwrap();
+    assert_eq!(leaderboard.get_trader_stats(USER_ID).await.unwrap().total_trades, before.total_trades + 1);
+}
+
+#[tokio::test]
+async fn test_stats_attribute_sells_to_their_entry_hour() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let performance = &harness.services.performance;
+    let timezones = harness.services.dca_engine.timezones();
+    timezones.set_user_timezone(USER_ID, "Asia/Kolkata").await.unwrap();
+
+    // Bought 03:40 UTC (09:10 IST), sold five hours later
+    let bought_at = Utc.with_ymd_and_hms(2024, 5, 6, 3, 40, 0).unwrap();
+    performance.record_entry(USER_ID, "BONK/SOL", bought_at).await;
+    let mut sell = TradeRecord::from_trade_result(
+        &TradeResult::buy("sigS".to_string(), 0.0, 0.0, 0.000002),
+        "BONK/SOL",
+        "manual",
+    );
+    sell.timestamp = bought_at + chrono::Duration::hours(5);
+    performance.record_exit(USER_ID, sell, true).await.unwrap();
+
+    let config = TradingHoursConfig { min_samples: 1, ..Default::default() };
+    let tz = timezones.get_user_timezone(USER_ID).await;
+    let report = performance.trading_hours(USER_ID, tz, &config, &[]).await;
+    assert_eq!(report.grid[0][9].trades, 1);
+    assert_eq!(performance.user_trades(USER_ID).await[0].holding_period, chrono::Duration::hours(5));
+
+    // A later timezone change re-buckets the same trade
+    timezones.set_user_timezone(USER_ID, "America/New_York").await.unwrap();
+    let tz = timezones.get_user_timezone(USER_ID).await;
+    let report = performance.trading_hours(USER_ID, tz, &config, &[]).await;
+    assert_eq!(report.grid[6][23].trades, 1);
+}
+
+#[tokio::test]
+async fn test_cancelled_orders_release_price_monitor() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .price(&mint, 1.0)
+        .build()
+        .await
+        .unwrap();
+    let manager = &harness.order_manager;
+
+    let stop = Order::create_stop_loss(USER_ID, mint.clone(), Decimal::new(5, 1), Decimal::from(1_000));
+    let take_profit = Order::create_take_profit(USER_ID, mint.clone(), Decimal::new(11, 1), Decimal::from(1_000));
+    let stop_id = manager.create_order(stop).await.unwrap();
+    let take_profit_id = manager.create_order(take_profit).await.unwrap();
+
+    let stats = manager.monitor_stats().await;
+    assert_eq!((stats.monitor_count, stats.monitored_orders), (1, 2));
+    // Take-profit 10% away sets the cadence
+    assert_eq!(manager.next_poll_interval().await, Duration::from_secs(15));
+    assert!((stats.polls_per_minute - 4.0).abs() < 1e-9);
+
+    assert!(manager.cancel_order(&stop_id).await.unwrap());
+    let stats = manager.monitor_stats().await;
+    assert_eq!((stats.monitor_count, stats.monitored_orders), (1, 1));
+
+    assert!(manager.cancel_order(&take_profit_id).await.unwrap());
+    let stats = manager.monitor_stats().await;
+    assert_eq!(stats.monitor_count, 0);
+    assert_eq!(stats.polls_per_minute, 0.0);
+    assert_eq!(manager.nearest_trigger_distance().await, None);
+
+    // With nothing monitored, a pass doesn't touch the price API
+    harness.price_client.clear_cache().await;
+    let price_calls = harness.jupiter.call_count("price_v3").await;
+    manager.run_cycle().await.unwrap();
+    assert_eq!(harness.jupiter.call_count("price_v3").await, price_calls);
+}
+
+#[tokio::test]
+async fn test_copy_trade_propagation() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().build().await.unwrap();
+    let follower_id = 5_001;
+
+    harness.copy_trading
+        .start_following(follower_id, "AlphaTrader", 50.0, 5.0)
+        .await
+        .unwrap();
+
+    let executions = harness.copy_trading
+        .execute_copy_trade(1001, &mint, "BONK", CopyTradeType::Buy, 4.0, 0.00002)
+        .await
+        .unwrap();
+
+    assert_eq!(executions.len(), 1);
+    let execution = &executions[0];
+    assert_eq!(execution.follower_user_id, follower_id);
+    assert_eq!(execution.status, CopyTradeStatus::Success);
+    // 50% of 4 SOL, minus the default 5% copy fee
+    assert!((execution.copied_amount_sol - 1.9).abs() < 1e-9);
+    assert!((execution.fee_paid_sol - 0.1).abs() < 1e-9);
+    assert_eq!(execution.report.quoted_price, Some(0.00002));
+    assert!(execution.report.realized_price.is_some() && execution.report.slippage_bps.is_some());
+    assert!(execution.report.simulated);
+    assert!(execution.report.idempotency_key.as_deref().unwrap().starts_with("copy:1001:"));
+
+    // Unfollowing stops propagation
+    harness.copy_trading.stop_following(follower_id, 1001).await.unwrap();
+    let executions = harness.copy_trading
+        .execute_copy_trade(1001, &mint, "BONK", CopyTradeType::Buy, 4.0, 0.00002)
+        .await
+        .unwrap();
+    assert!(executions.is_empty());
+}
+
+#[tokio::test]
+async fn test_dca_execution_report() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&mint, 1.0).build().await.unwrap();
+    let engine = DCAEngine::new(
+        Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())),
+        harness.price_client.clone(),
+        harness.db.clone(),
+        None,
+    );
+
+    let strategy = DCAStrategy {
+        strategy_id: "dca-e2e".to_string(),
+        user_id: USER_ID,
+        name: "BONK daily".to_string(),
+        input_token: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+        output_token: mint.clone(),
+        total_amount: Decimal::from(1_000),
+        interval: DCAInterval::Daily,
+        amount_per_execution: Decimal::from(100),
+        strategy_type: DCAStrategyType::Fixed,
+        created_at: Utc::now(),
+        started_at: None,
+        next_execution: Utc::now(),
+        status: DCAStatus::Active,
+        execution_count: 2,
+        max_executions: None,
+        end_date: None,
+        risk_parameters: RiskParameters::default(),
+        advanced_config: AdvancedDCAConfig::default(),
+        anchor: None,
+        last_completed_slot: None,
+        skip_next: false,
+    };
+
+    let execution = engine.execute_strategy(&strategy).await.unwrap();
+    let report = &execution.report;
+    assert!(report.quoted_at.is_some() && report.executed_at.is_some());
+    assert_eq!(report.route.as_ref().unwrap().hops, 1);
+    assert_eq!(report.quoted_price, Some(1.0));
+    assert!(report.simulated);
+    assert_eq!(report.idempotency_key.as_deref(), Some("dca:dca-e2e:3"));
+}
+
+#[tokio::test]
+async fn test_trending_cold_start_edits_placeholder_when_warm() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    harness.trending.set_delay(Duration::from_secs(1)).await;
+
+    harness.start_bot();
+    harness.telegram.inject_message(USER_ID, "/trending").await;
+
+    harness.telegram
+        .wait_for_text(USER_ID, "Warming up", Duration::from_secs(5))
+        .await
+        .expect("a cold cache should answer with a placeholder");
+    harness.telegram
+        .wait_for_text(USER_ID, "Live Market Trending", Duration::from_secs(10))
+        .await
+        .expect("the placeholder should be filled in once warm");
+
+    let sent = harness.telegram.sent().await;
+    let results = sent.iter()
+        .find(|m| m.text.as_deref().is_some_and(|t| t.contains("Live Market Trending")))
+        .unwrap();
+    assert_eq!(results.method, "editMessageText");
+    // The background refresher and the request shared one upstream round
+    assert_eq!(harness.trending.call_count(), 3);
+}
+
+#[tokio::test]
+async fn test_realized_pnl_lands_on_the_day_and_week_it_closed() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let (cost_basis, performance) = (&harness.services.cost_basis, &harness.services.performance);
+
+    cost_basis.apply(USER_ID, &LotTrade::from_trade_result(
+        &TradeResult::buy("sigB1".to_string(), 1_000_000.0, 1.0, 0.000001), &bonk,
+    ).unwrap()).await;
+    cost_basis.apply(USER_ID, &LotTrade::from_trade_result(
+        &TradeResult::buy("sigB2".to_string(), 1_000_000.0, 3.0, 0.000003), &bonk,
+    ).unwrap()).await;
+
+    // Half the position at 2.5 SOL per million: FIFO closes the cheap lot
+    let sold = TradeResult::sell("sigS".to_string(), 1_000_000.0, 2.5, 0.0000025);
+    let realized = cost_basis.apply(USER_ID, &LotTrade::from_trade_result(&sold, &bonk).unwrap()).await.unwrap();
+    let mut record = TradeRecord::from_trade_result(&sold, "BONK/SOL", "manual");
+    record.timestamp = Utc.with_ymd_and_hms(2026, 3, 4, 12, 0, 0).unwrap();
+    performance.record_realized(USER_ID, record, &realized, false).await.unwrap();
+
+    let day = chrono::NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+    let report = performance.get_performance_range(day, day).await.unwrap();
+    assert_eq!(report.daily_performance[0].trades_executed, 1);
+    assert_eq!(report.daily_performance[0].daily_return, Decimal::new(15, 1));
+    assert_eq!(report.total_return, Decimal::new(15, 1));
+
+    let week = performance.get_week_performance(day).await.unwrap();
+    assert_eq!(week.week_identifier, "2026-W10");
+    assert_eq!(week.total_trades, 1);
+    assert_eq!(week.weekly_return, Decimal::new(15, 1));
+    assert_eq!(week.best_day, Some(day));
+    assert_eq!(performance.user_trades(USER_ID).await[0].pnl_percentage, 150.0);
+
+    // Average cost would have realized against 2 SOL per million instead
+    harness.services.preferences.set(USER_ID, crate::bot::preferences::UserSettings {
+        cost_basis_method: CostBasisMethod::Average,
+        ..Default::default()
+    }).await;
+    cost_basis.apply(USER_ID, &LotTrade::from_trade_result(
+        &TradeResult::buy("sigB3".to_string(), 1_000_000.0, 1.0, 0.000001), &bonk,
+    ).unwrap()).await;
+    let second = cost_basis.apply(USER_ID, &LotTrade::from_trade_result(&sold, &bonk).unwrap()).await.unwrap();
+    assert_eq!(second.method, CostBasisMethod::Average);
+    assert!((second.pnl_sol - 0.5).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_analytics_report_only_reads_the_asking_users_trades() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let performance = &harness.services.performance;
+    const OTHER_USER: i64 = 515_151;
+
+    let closed = |signature: &str, pnl: i64, day: u32| {
+        let mut record = TradeRecord::from_trade_result(
+            &TradeResult::sell(signature.to_string(), 1_000_000.0, 1.0, 0.000001),
+            "BONK/SOL",
+            "manual",
+        );
+        record.pnl = Decimal::from(pnl);
+        record.timestamp = Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        record
+    };
+    performance.record_exit(USER_ID, closed("sigA1", 5, 2), true).await.unwrap();
+    performance.record_exit(USER_ID, closed("sigA2", 2, 3), true).await.unwrap();
+    performance.record_exit(OTHER_USER, closed("sigB1", -40, 2), true).await.unwrap();
+
+    let mine = performance.generate_analytics_report(USER_ID).await.unwrap();
+    assert_eq!(mine.all_time_performance.total_trades, 2);
+    assert_eq!(mine.all_time_performance.losing_trades, 0);
+    assert_eq!(mine.all_time_performance.current_value, Decimal::from(10_007));
+    assert!(mine.risk_metrics.max_drawdown.as_ref().map_or(true, |drawdown| drawdown.percentage == 0.0));
+    assert_eq!(mine.best_performing_month.as_deref(), Some("2026-03"));
+
+    let theirs = performance.generate_analytics_report(OTHER_USER).await.unwrap();
+    assert_eq!(theirs.all_time_performance.total_trades, 1);
+    assert_eq!(theirs.all_time_performance.current_value, Decimal::from(9_960));
+
+    // Forgetting a user drops their snapshots too
+    performance.forget_user(OTHER_USER).await;
+    let forgotten = performance.generate_analytics_report(OTHER_USER).await.unwrap();
+    assert_eq!(forgotten.all_time_performance.total_trades, 0);
+    assert!(forgotten.best_performing_month.is_none());
+}
+
+#[tokio::test]
+async fn test_benchmark_needs_the_asking_users_own_history() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let performance = &harness.services.performance;
+    const NEW_USER: i64 = 616_161;
+
+    for (signature, day) in [("sigD1", 2), ("sigD2", 3), ("sigD3", 4)] {
+        let mut record = TradeRecord::from_trade_result(
+            &TradeResult::sell(signature.to_string(), 1_000_000.0, 1.0, 0.000001),
+            "BONK/SOL",
+            "manual",
+        );
+        record.timestamp = Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        performance.record_exit(USER_ID, record, true).await.unwrap();
+    }
+
+    // Someone else's days never stand in for a user with no history
+    let error = performance.benchmark(NEW_USER, &BenchmarkBasket::sol(), 30).await.unwrap_err();
+    assert!(matches!(error, crate::errors::BotError::NotFound { .. }));
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use crate::bot::handlers::TradingHandler;
+use crate::errors::{BotError, TradingError, WalletError, GENERIC_FAILURE};
+
+/// Characters MarkdownV2 rejects unless escaped
+const RESERVED: &[char] = &['_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!'];
+
+/// Every reserved character in the text is preceded by a backslash
+fn assert_escaped(rendered: &str) {
+    let mut chars = rendered.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            assert!(chars.next().is_some(), "dangling escape in {:?}", rendered);
+        } else {
+            assert!(!RESERVED.contains(&c), "unescaped {:?} in {:?}", c, rendered);
+        }
+    }
+}
+
+/// A message built from all the reserved characters, so escaping is exercised everywhere
+fn noisy() -> String {
+    "bad_input *now* [x](y) ~a~ `b` > #1 +2 -3 =4 |5| {6} 7.5!".to_string()
+}
+
+fn every_variant() -> Vec<BotError> {
+    vec![
+        BotError::validation(noisy()),
+        BotError::invalid("amount", noisy()),
+        BotError::insufficient_balance(1.25, 0.5),
+        BotError::slippage_exceeded(100, 250),
+        BotError::rate_limited(noisy()),
+        BotError::rate_limited_for(Duration::from_millis(2500), noisy()),
+        BotError::service_unavailable("jupiter"),
+        BotError::not_found(noisy()),
+        BotError::internal(noisy()),
+        BotError::trading(noisy()),
+        BotError::security(noisy()),
+        BotError::queue_full(noisy()),
+        BotError::hardware_wallet(noisy()),
+        BotError::config(noisy()),
+        BotError::parsing(noisy()),
+        BotError::serialization(noisy()),
+        BotError::ai_parse(noisy()),
+        BotError::jupiter_api(noisy()),
+        BotError::external_api(noisy()),
+        BotError::api(noisy()),
+        BotError::monitoring(noisy()),
+        BotError::migration(noisy()),
+        BotError::from(serde_json::from_str::<u8>("{").unwrap_err()),
+        BotError::from(std::io::Error::other(noisy())),
+        BotError::from(WalletError::InvalidPublicKey),
+        BotError::from(WalletError::DerivationFailed),
+        BotError::from(TradingError::NoRoute),
+        BotError::from(TradingError::QuoteFailed(noisy())),
+        BotError::from(TradingError::SlippageExceeded),
+        BotError::from(TradingError::InsufficientBalance { required: 1.25, available: 0.5 }),
+    ]
+}
+
+#[test]
+fn test_every_variant_renders_as_valid_markdown_v2() {
+    for error in every_variant() {
+        let rendered = error.render_for_telegram();
+        assert!(rendered.starts_with('❌') || rendered.starts_with('⏳'), "{}", rendered);
+        assert_escaped(&rendered);
+    }
+}
+
+#[test]
+fn test_internal_errors_render_as_the_generic_failure() {
+    for error in every_variant().into_iter().filter(|error| !error.is_user_visible()) {
+        assert_eq!(error.user_message(), GENERIC_FAILURE, "{:?}", error);
+        assert!(!error.render_for_telegram().contains("bad"), "{:?}", error);
+    }
+}
+
+#[test]
+fn test_user_visibility_by_variant() {
+    let visible = [
+        BotError::invalid("slippage", "Slippage can be at most 5000 bps"),
+        BotError::insufficient_balance(1.0, 0.1),
+        BotError::slippage_exceeded(100, 300),
+        BotError::rate_limited("429 from quote-api"),
+        BotError::service_unavailable("pumpfun"),
+        BotError::not_found("Order 42 not found"),
+        BotError::trading("You hold no BONK to sell"),
+        BotError::from(TradingError::NoRoute),
+        BotError::from(WalletError::WalletNotFound),
+    ];
+    for error in &visible {
+        assert!(error.is_user_visible(), "{:?}", error);
+        assert_ne!(error.user_message(), GENERIC_FAILURE, "{:?}", error);
+    }
+
+    let hidden = [
+        BotError::internal("poisoned lock"),
+        BotError::config("DATABASE_URL missing"),
+        BotError::jupiter_api("500 from https://quote-api.jup.ag"),
+        BotError::from(TradingError::TransactionFailed("blockhash not found".to_string())),
+        BotError::from(WalletError::DerivationFailed),
+    ];
+    for error in &hidden {
+        assert!(!error.is_user_visible(), "{:?}", error);
+    }
+}
+
+#[test]
+fn test_typed_variants_explain_themselves() {
+    let balance = BotError::insufficient_balance(1.25, 0.5).user_message();
+    assert!(balance.contains("1.2500") && balance.contains("0.5000"), "{}", balance);
+
+    let slippage = BotError::slippage_exceeded(100, 250).user_message();
+    assert!(slippage.contains("2.50%") && slippage.contains("1.00%"), "{}", slippage);
+
+    // Rounded up, and the log detail stays out of the message
+    let limited = BotError::rate_limited_for(Duration::from_millis(2500), "429 from quote-api");
+    assert!(limited.user_message().contains("3 seconds"), "{}", limited.user_message());
+    assert!(!limited.user_message().contains("quote-api"));
+    assert!(BotError::rate_limited_for(Duration::from_secs(150), "").user_message().contains("3 minutes"));
+
+    let unavailable = BotError::service_unavailable("pumpfun");
+    assert!(unavailable.user_message().starts_with("Pump.fun"), "{}", unavailable.user_message());
+    assert!(unavailable.render_for_telegram().starts_with("⏳ Pump\\.fun"));
+}
+
+#[test]
+fn test_validator_trading_errors_become_typed_variants() {
+    let balance = BotError::from(TradingError::InsufficientBalance { required: 1.25, available: 0.5 });
+    assert!(matches!(balance, BotError::InsufficientBalance { needed, available } if needed == 1.25 && available == 0.5));
+
+    let slippage = BotError::from(TradingError::SlippageExceeded);
+    assert!(matches!(slippage, BotError::Validation { field: Some("slippage"), .. }), "{:?}", slippage);
+}
+
+#[test]
+fn test_trade_failures_keep_internal_detail_out() {
+    let no_route = TradingHandler::failure_message("Trade", &TradingError::NoRoute.into(), "en");
+    assert!(no_route.starts_with("❌ Trade failed") && no_route.contains("route"), "{}", no_route);
+
+    let api = TradingHandler::failure_message("Trade", &BotError::jupiter_api("502 Bad Gateway"), "en");
+    assert!(!api.contains("502"), "{}", api);
+    assert!(api.contains(GENERIC_FAILURE), "{}", api);
+}
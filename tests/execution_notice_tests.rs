@@ -0,0 +1,125 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::trading::{
+    ExecutionNotice, ExecutionNotifier, ExecutionSource, Fill, FillDigest, NoticeKind, NoticeRoute, NoticeScope,
+    OutgoingNotice, Verbosity,
+};
+
+const USER: i64 = 424242;
+
+fn t0() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 6, 1, 9, 0, 0).unwrap()
+}
+
+fn fill(mint: &str, quantity: f64, price: f64, fee_sol: f64) -> Fill {
+    Fill { mint: mint.to_string(), symbol: mint.to_uppercase(), quantity, price, fee_sol }
+}
+
+fn notice(kind: NoticeKind, strategy_id: Option<&str>, order_id: Option<&str>) -> ExecutionNotice {
+    ExecutionNotice {
+        user_id: USER,
+        source: ExecutionSource::Dca,
+        label: "Weekly BONK".to_string(),
+        strategy_id: strategy_id.map(str::to_string),
+        order_id: order_id.map(str::to_string),
+        kind,
+        at: t0(),
+    }
+}
+
+#[tokio::test]
+async fn test_order_overrides_strategy_overrides_global() {
+    let notifier = ExecutionNotifier::default();
+    assert_eq!(notifier.effective(USER, Some("dca-1"), Some("ord-1")).await, (Verbosity::Full, NoticeScope::Global));
+
+    notifier.set_global(USER, Verbosity::Silent).await;
+    assert_eq!(notifier.effective(USER, Some("dca-1"), None).await, (Verbosity::Silent, NoticeScope::Global));
+
+    notifier.set_strategy("dca-1", Verbosity::Summary).await;
+    assert_eq!(
+        notifier.effective(USER, Some("dca-1"), Some("ord-1")).await,
+        (Verbosity::Summary, NoticeScope::Strategy("dca-1".to_string()))
+    );
+    // Other strategies still follow the default
+    assert_eq!(notifier.effective(USER, Some("dca-2"), None).await.0, Verbosity::Silent);
+
+    notifier.set_order("ord-1", Verbosity::Full).await;
+    assert_eq!(
+        notifier.effective(USER, Some("dca-1"), Some("ord-1")).await,
+        (Verbosity::Full, NoticeScope::Order("ord-1".to_string()))
+    );
+
+    notifier.forget_user(USER).await;
+    assert_eq!(notifier.global(USER).await, Verbosity::Full);
+}
+
+#[test]
+fn test_digest_aggregates_per_token() {
+    let lines = FillDigest::aggregate(&[
+        fill("bonk", 100.0, 0.002, 0.0001),
+        fill("wif", 5.0, 0.01, 0.0002),
+        fill("bonk", 300.0, 0.001, 0.0003),
+    ]);
+
+    assert_eq!(lines.len(), 2);
+    let bonk = &lines[0];
+    assert_eq!(bonk.mint, "bonk");
+    assert_eq!(bonk.fills, 2);
+    assert!((bonk.total_quantity - 400.0).abs() < 1e-9);
+    // (100 * 0.002 + 300 * 0.001) / 400
+    assert!((bonk.average_price - 0.00125).abs() < 1e-12);
+    assert!((bonk.total_fees_sol - 0.0004).abs() < 1e-12);
+
+    let wif = &lines[1];
+    assert_eq!((wif.fills, wif.total_quantity, wif.average_price), (1, 5.0, 0.01));
+}
+
+#[tokio::test]
+async fn test_summary_fills_flush_after_the_window() {
+    let notifier = ExecutionNotifier::new(Duration::minutes(30));
+    let mut receiver = notifier.subscribe();
+    notifier.set_strategy("dca-1", Verbosity::Summary).await;
+
+    for (quantity, price) in [(10.0, 1.0), (30.0, 2.0)] {
+        let route = notifier.notify(notice(NoticeKind::Fill(fill("bonk", quantity, price, 0.001)), Some("dca-1"), None)).await;
+        assert_eq!(route, NoticeRoute::Digested);
+    }
+    assert!(receiver.try_recv().is_err());
+
+    assert!(notifier.flush_due(t0() + Duration::minutes(29)).await.is_empty());
+
+    let digests = notifier.flush_due(t0() + Duration::minutes(30)).await;
+    assert_eq!(digests.len(), 1);
+    assert_eq!(digests[0].scope, NoticeScope::Strategy("dca-1".to_string()));
+    assert_eq!(digests[0].fill_count(), 2);
+    assert!((digests[0].lines[0].average_price - 1.75).abs() < 1e-12);
+    assert!(matches!(receiver.try_recv(), Ok(OutgoingNotice::Digest(_))));
+
+    // The window closed, so nothing is left to send
+    assert!(notifier.flush_due(t0() + Duration::hours(2)).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_failures_and_refusals_always_notify() {
+    let notifier = ExecutionNotifier::default();
+    let mut receiver = notifier.subscribe();
+    notifier.set_global(USER, Verbosity::Silent).await;
+    notifier.set_strategy("dca-1", Verbosity::Summary).await;
+
+    let fill_route = notifier.notify(notice(NoticeKind::Fill(fill("bonk", 1.0, 1.0, 0.0)), None, None)).await;
+    assert_eq!(fill_route, NoticeRoute::Suppressed);
+
+    for strategy in [None, Some("dca-1")] {
+        let failure = NoticeKind::Failure { reason: "slippage exceeded".to_string() };
+        assert_eq!(notifier.notify(notice(failure, strategy, None)).await, NoticeRoute::Sent);
+        let refusal = NoticeKind::RiskRefusal { reason: "price above max".to_string() };
+        assert_eq!(notifier.notify(notice(refusal, strategy, None)).await, NoticeRoute::Sent);
+    }
+
+    let mut sent = 0;
+    while let Ok(OutgoingNotice::Notice(notice)) = receiver.try_recv() {
+        assert!(notice.kind.always_notifies());
+        sent += 1;
+    }
+    assert_eq!(sent, 4);
+}
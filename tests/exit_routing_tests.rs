@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::analytics::TradeRecord;
+use crate::errors::{BotError, Result};
+use crate::trading::{
+    choose_usdc_path, plan_exit, ExecutionReport, ExitDenomination, ExitPath, ExitQuoter, ExitSettlement,
+    RouteQuote, TradeResult, USDC_MINT,
+};
+
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+fn route(out_amount: u64, fee_amount: u64, hops: usize) -> RouteQuote {
+    RouteQuote { out_amount, fee_amount, price_impact_pct: 0.1, hops }
+}
+
+/// Canned direct and multi-hop routes, keyed by `only_direct`
+#[derive(Default)]
+struct FixtureQuoter {
+    routes: HashMap<bool, Result<Option<RouteQuote>>>,
+    calls: std::sync::Mutex<Vec<(String, bool)>>,
+}
+
+impl FixtureQuoter {
+    fn with(mut self, only_direct: bool, quote: Result<Option<RouteQuote>>) -> Self {
+        self.routes.insert(only_direct, quote);
+        self
+    }
+
+    fn calls(&self) -> Vec<(String, bool)> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl ExitQuoter for FixtureQuoter {
+    type Quote = RouteQuote;
+
+    async fn exit_quote(
+        &self,
+        _input_mint: &str,
+        output_mint: &str,
+        _amount: u64,
+        _slippage_bps: u16,
+        only_direct: bool,
+    ) -> Result<Option<RouteQuote>> {
+        self.calls.lock().unwrap().push((output_mint.to_string(), only_direct));
+        match self.routes.get(&only_direct) {
+            Some(Ok(quote)) => Ok(*quote),
+            Some(Err(e)) => Err(BotError::trading(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    fn summarize(quote: &RouteQuote) -> RouteQuote {
+        *quote
+    }
+}
+
+#[test]
+fn test_choose_usdc_path() {
+    let direct = route(1_000_000, 0, 1);
+    let better_two_hop = route(1_050_000, 0, 2);
+    let equal_two_hop = route(1_000_000, 0, 2);
+
+    assert_eq!(choose_usdc_path(Some(&direct), Some(&better_two_hop)), Some(ExitPath::ViaSol));
+    // The single pool wins ties
+    assert_eq!(choose_usdc_path(Some(&direct), Some(&equal_two_hop)), Some(ExitPath::Direct));
+    assert_eq!(choose_usdc_path(None, Some(&equal_two_hop)), Some(ExitPath::ViaSol));
+    assert_eq!(choose_usdc_path(Some(&direct), None), Some(ExitPath::Direct));
+    assert_eq!(choose_usdc_path(None, None), None);
+}
+
+#[tokio::test]
+async fn test_usdc_exit_picks_the_better_net_route() {
+    // Thin direct pool: two hops through SOL pay more
+    let quoter = FixtureQuoter::default()
+        .with(true, Ok(Some(route(950_000, 0, 1))))
+        .with(false, Ok(Some(route(1_000_000, 0, 2))));
+    let plan = plan_exit(&quoter, BONK, 1_000, 100, ExitDenomination::Usdc).await.unwrap();
+    assert_eq!(plan.path, ExitPath::ViaSol);
+    assert_eq!(plan.quote.hops, 2);
+    assert!((plan.amount_out() - 1.0).abs() < 1e-9);
+    assert_eq!(quoter.calls(), vec![(USDC_MINT.to_string(), true), (USDC_MINT.to_string(), false)]);
+
+    // The two-hop route quotes more but its platform fee leaves less
+    let quoter = FixtureQuoter::default()
+        .with(true, Ok(Some(route(990_000, 0, 1))))
+        .with(false, Ok(Some(route(1_000_000, 20_000, 2))));
+    let plan = plan_exit(&quoter, BONK, 1_000, 100, ExitDenomination::Usdc).await.unwrap();
+    assert_eq!(plan.path, ExitPath::Direct);
+    assert_eq!(plan.summary.net_out(), 990_000);
+}
+
+#[tokio::test]
+async fn test_usdc_exit_falls_back_when_one_side_has_no_route() {
+    let quoter = FixtureQuoter::default()
+        .with(true, Ok(None))
+        .with(false, Ok(Some(route(800_000, 0, 2))));
+    let plan = plan_exit(&quoter, BONK, 1_000, 100, ExitDenomination::Usdc).await.unwrap();
+    assert_eq!(plan.path, ExitPath::ViaSol);
+
+    let quoter = FixtureQuoter::default()
+        .with(true, Ok(Some(route(800_000, 0, 1))))
+        .with(false, Err(BotError::trading("Jupiter timed out".to_string())));
+    let plan = plan_exit(&quoter, BONK, 1_000, 100, ExitDenomination::Usdc).await.unwrap();
+    assert_eq!(plan.path, ExitPath::Direct);
+
+    let quoter = FixtureQuoter::default();
+    assert!(plan_exit(&quoter, BONK, 1_000, 100, ExitDenomination::Usdc).await.is_err());
+}
+
+#[tokio::test]
+async fn test_sol_exit_takes_a_single_quote() {
+    let quoter = FixtureQuoter::default().with(false, Ok(Some(route(2_000_000_000, 0, 1))));
+    let plan = plan_exit(&quoter, BONK, 1_000, 100, ExitDenomination::Sol).await.unwrap();
+    assert_eq!(plan.path, ExitPath::Sol);
+    assert!((plan.amount_out() - 2.0).abs() < 1e-9);
+    assert_eq!(quoter.calls().len(), 1);
+    assert!(!quoter.calls()[0].1);
+}
+
+#[tokio::test]
+async fn test_usdc_exit_is_booked_in_sol_at_execution_price() {
+    let quoter = FixtureQuoter::default().with(true, Ok(Some(route(300_000_000, 0, 1))));
+    let plan = plan_exit(&quoter, BONK, 1_000_000, 100, ExitDenomination::Usdc).await.unwrap();
+    let settlement = ExitSettlement::new(&plan, 150.0);
+
+    // 300 USDC at $150/SOL
+    assert!((settlement.amount_out - 300.0).abs() < 1e-9);
+    assert!((settlement.value_sol() - 2.0).abs() < 1e-9);
+    assert!(settlement.summary().contains("direct to USDC"));
+
+    // 1M tokens for 300 USDC: 0.0003 USDC, or 0.000002 SOL, per token
+    let mut report = ExecutionReport::default().with_exit(settlement);
+    report.realized_price = Some(0.0003);
+    assert!((report.realized_price_sol().unwrap() - 0.000002).abs() < 1e-12);
+
+    let mut result = TradeResult::sell("sig".to_string(), 1_000_000.0, settlement.value_sol(), 0.000002);
+    result.execution = report;
+    let record = TradeRecord::from_trade_result(&result, "BONK/SOL", "stop_loss");
+    assert_eq!(record.exit_price.round_dp(9), rust_decimal::Decimal::new(2, 6));
+
+    // SOL exits are already in the series' unit
+    let sol = ExitSettlement { denomination: ExitDenomination::Sol, path: ExitPath::Sol, amount_out: 2.0, sol_usd: 150.0 };
+    assert_eq!(sol.value_sol(), 2.0);
+    assert_eq!(sol.price_in_sol(0.5), 0.5);
+}
@@ -0,0 +1,172 @@
+use crate::bot::handlers::TradingHandler;
+use crate::errors::BotError;
+use crate::trading::{
+    ExecutionReport, ExitDenomination, FillAmounts, QuoteGuard, RouteSummary, TradeResult, TradeType,
+    TOKEN_ACCOUNT_RENT_LAMPORTS,
+};
+use serde_json::{json, Value};
+use solana_transaction_status::EncodedTransactionWithStatusMeta;
+
+const WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const POOL: &str = "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2";
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+const WSOL: &str = "So11111111111111111111111111111111111111112";
+const FEE: u64 = 5_000;
+
+fn token_balance(account_index: u8, mint: &str, owner: &str, amount: f64) -> Value {
+    json!({
+        "accountIndex": account_index,
+        "mint": mint,
+        "owner": owner,
+        "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        "uiTokenAmount": {
+            "uiAmount": amount,
+            "decimals": 9,
+            "amount": ((amount * 1e9) as u64).to_string(),
+            "uiAmountString": amount.to_string(),
+        },
+    })
+}
+
+/// A confirmed swap as `getTransaction` returns it, with the wallet as fee payer
+fn confirmed(lamports: (u64, u64), pre_tokens: Vec<Value>, post_tokens: Vec<Value>, err: Option<Value>) -> EncodedTransactionWithStatusMeta {
+    serde_json::from_value(json!({
+        "transaction": {
+            "signatures": ["5igSig"],
+            "message": {
+                "header": { "numRequiredSignatures": 1, "numReadonlySignedAccounts": 0, "numReadonlyUnsignedAccounts": 1 },
+                "accountKeys": [WALLET, "UserBonkAta111111111111111111111111111111111", "PoolBonkVault11111111111111111111111111111", POOL],
+                "recentBlockhash": "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N",
+                "instructions": [],
+            },
+        },
+        "meta": {
+            "err": err,
+            "status": { "Ok": null },
+            "fee": FEE,
+            "preBalances": [lamports.0, 0, 2_039_280, 1_000_000_000_000u64],
+            "postBalances": [lamports.1, 2_039_280, 2_039_280, 1_000_000_000_000u64],
+            "innerInstructions": [],
+            "logMessages": [],
+            "preTokenBalances": pre_tokens,
+            "postTokenBalances": post_tokens,
+            "rewards": [],
+            "loadedAddresses": { "writable": [], "readonly": [] },
+        },
+    }))
+    .unwrap()
+}
+
+/// 1 SOL into BONK through a new token account; the pool's vault moves the other way
+fn buy(tokens_out: f64, lamports_in: u64) -> EncodedTransactionWithStatusMeta {
+    let pre = 5_000_000_000;
+    confirmed(
+        (pre, pre - lamports_in - FEE - TOKEN_ACCOUNT_RENT_LAMPORTS),
+        vec![token_balance(2, BONK, POOL, 50_000.0)],
+        vec![token_balance(1, BONK, WALLET, tokens_out), token_balance(2, BONK, POOL, 50_000.0 - tokens_out)],
+        None,
+    )
+}
+
+fn verified(report: ExecutionReport, fill: FillAmounts, trade_type: TradeType) -> TradeResult {
+    let price = fill.price(trade_type);
+    let result = match trade_type {
+        TradeType::Sell => TradeResult::sell("5igSig".to_string(), fill.amount_in, fill.amount_out, price),
+        _ => TradeResult::buy("5igSig".to_string(), fill.amount_out, fill.amount_in, price),
+    };
+    result.with_execution(report.filled(price, trade_type)).with_verified_fill()
+}
+
+#[test]
+fn test_better_than_quote_buy_is_read_from_the_transaction() {
+    // Quoted 1,000 BONK for 1 SOL; the swap delivered 1,010
+    let fill = FillAmounts::from_transaction(&buy(1_010.0, 1_000_000_000), WALLET, WSOL, BONK, 0).unwrap();
+    assert!((fill.amount_in - 1.0).abs() < 1e-9, "{:?}", fill);
+    assert!((fill.amount_out - 1_010.0).abs() < 1e-9);
+    assert!(fill.partial_fill(1.0).is_none());
+
+    let result = verified(ExecutionReport::quoted(0.001, RouteSummary::default()), fill, TradeType::Buy);
+    assert_eq!(result.quoted_price, Some(0.001));
+    assert!((result.executed_price.unwrap() - 1.0 / 1_010.0).abs() < 1e-12);
+    let slippage = result.realized_slippage_bps.unwrap();
+    assert!((slippage + 99.0).abs() < 0.1, "{}", slippage);
+
+    let text = TradingHandler::format_fill_check(&result, "en");
+    assert!(text.contains("Filled 99 bps better than quoted"), "{}", text);
+    assert!(!text.contains("Partial"));
+}
+
+#[test]
+fn test_worse_than_quote_sell_reports_realized_slippage() {
+    // Quoted 1 SOL for 1,000 BONK; 0.97 arrived, and the Jito tip isn't counted against the fill
+    let tip = 10_000;
+    let pre = 2_000_000_000;
+    let transaction = confirmed(
+        (pre, pre + 970_000_000 - FEE - tip),
+        vec![token_balance(1, BONK, WALLET, 1_000.0), token_balance(2, BONK, POOL, 50_000.0)],
+        vec![token_balance(1, BONK, WALLET, 0.0), token_balance(2, BONK, POOL, 51_000.0)],
+        None,
+    );
+    let fill = FillAmounts::from_transaction(&transaction, WALLET, BONK, WSOL, tip).unwrap();
+    assert!((fill.amount_in - 1_000.0).abs() < 1e-9);
+    assert!((fill.amount_out - 0.97).abs() < 1e-9, "{:?}", fill);
+
+    let result = verified(ExecutionReport::quoted(0.001, RouteSummary::default()), fill, TradeType::Sell);
+    assert!((result.realized_slippage_bps.unwrap() - 300.0).abs() < 0.1);
+    assert!((result.executed_price.unwrap() - 0.00097).abs() < 1e-12);
+
+    let text = TradingHandler::format_fill_check(&result, "en");
+    assert!(text.contains("⚠️ Filled 300 bps worse than quoted"), "{}", text);
+    assert!(text.contains("Quoted ") && text.contains(" · Executed "), "{}", text);
+
+    // Unverified results say nothing about the fill
+    let unverified = TradeResult::sell("5igSig".to_string(), 1_000.0, 1.0, 0.001)
+        .with_execution(ExecutionReport::quoted(0.001, RouteSummary::default()).filled(0.001, TradeType::Sell));
+    assert!(unverified.executed_price.is_none());
+    assert_eq!(TradingHandler::format_fill_check(&unverified, "en"), "");
+}
+
+#[test]
+fn test_partial_and_unreadable_fills() {
+    // Only 0.6 of the requested SOL was swapped
+    let fill = FillAmounts::from_transaction(&buy(600.0, 600_000_000), WALLET, WSOL, BONK, 0).unwrap();
+    let partial = fill.partial_fill(1.0).unwrap();
+    assert!((partial.filled_pct() - 60.0).abs() < 1e-6);
+
+    let result = TradeResult::buy("5igSig".to_string(), fill.amount_out, fill.amount_in, fill.price(TradeType::Buy))
+        .with_execution(ExecutionReport::quoted(0.001, RouteSummary::default()).with_partial_fill(Some(partial)));
+    let text = TradingHandler::format_fill_check(&result, "en");
+    assert!(text.contains("Partial fill"), "{}", text);
+    assert!(text.contains("60"), "{}", text);
+
+    // Failed swaps and wallets that only paid have no fill
+    let failed = confirmed(
+        (5_000_000_000, 5_000_000_000 - FEE),
+        vec![],
+        vec![],
+        Some(json!({ "InstructionError": [0, { "Custom": 6001 }] })),
+    );
+    assert!(FillAmounts::from_transaction(&failed, WALLET, WSOL, BONK, 0).is_none());
+    assert!(FillAmounts::from_transaction(&buy(1_000.0, 1_000_000_000), POOL, WSOL, BONK, 0).is_none());
+}
+
+#[test]
+fn test_degraded_quote_aborts_before_sending() {
+    let guard = QuoteGuard {
+        trade_type: TradeType::Buy,
+        token_mint: BONK.to_string(),
+        amount_in: 1_000_000_000,
+        quoted_out: 1_000.0,
+        exit: ExitDenomination::Sol,
+        tolerance_bps: 100,
+    };
+
+    assert!(guard.check(1_020.0).is_ok());
+    assert!(guard.check(995.0).is_ok());
+    assert!((guard.degradation_bps(1_020.0) + 200.0).abs() < 1e-9);
+
+    let err = guard.check(970.0).unwrap_err();
+    assert!(matches!(err, BotError::Trading(_)), "{:?}", err);
+    let message = err.to_string();
+    assert!(message.contains("Trade aborted") && message.contains("300 bps") && message.contains("Nothing was sent"), "{}", message);
+}
@@ -0,0 +1,131 @@
+use crate::alerts::{AnomalyDetector, EventSeverity, EventType, FlashCrashConfig, FlashCrashEvent, MarketEvent};
+use crate::websocket::{PriceSource, PriceUpdate, UpdateType};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+const SYMBOL: &str = "TKN";
+
+fn at(seconds: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap() + Duration::seconds(seconds)
+}
+
+fn tick(seconds: i64, price: &str) -> PriceUpdate {
+    PriceUpdate {
+        symbol: SYMBOL.to_string(),
+        price: Decimal::from_str(price).unwrap(),
+        timestamp: at(seconds),
+        volume: None,
+        source: PriceSource::Aggregate,
+        update_type: UpdateType::Trade,
+        metadata: None,
+    }
+}
+
+/// Feed (seconds, price) ticks; returns the events with the tick that raised each
+async fn replay(detector: &AnomalyDetector, ticks: &[(i64, &str)]) -> Vec<(i64, MarketEvent)> {
+    let mut events = Vec::new();
+    for (seconds, price) in ticks {
+        if let Some(event) = detector.check_flash_crash(&tick(*seconds, price)).await {
+            events.push((*seconds, event));
+        }
+    }
+    events
+}
+
+fn crash(event: &MarketEvent) -> &FlashCrashEvent {
+    match &event.event_type {
+        EventType::FlashCrash(crash) => crash,
+        other => panic!("expected a flash crash, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn crash_is_reported_once_then_followed_by_its_recovery() {
+    let detector = AnomalyDetector::default();
+    let events = replay(&detector, &[
+        (0, "1.00"),
+        (30, "0.99"),
+        (60, "0.95"),
+        // -10% from the 1.00 high inside two minutes
+        (90, "0.90"),
+        // Deepening and bouncing within the crash don't fire again
+        (100, "0.85"),
+        (110, "0.80"),
+        (130, "0.84"),
+        // Half of the 0.20 drop won back
+        (200, "0.90"),
+        (210, "0.95"),
+    ])
+    .await;
+
+    assert_eq!(events.len(), 2);
+    let (fired_at, detected) = &events[0];
+    assert_eq!(*fired_at, 90);
+    assert_eq!(detected.severity, EventSeverity::Medium);
+    let first = crash(detected);
+    assert_eq!(first.start_price, Decimal::ONE);
+    assert_eq!(first.low_price, Decimal::from_str("0.90").unwrap());
+    assert!((first.drop_percentage - 10.0).abs() < 1e-9);
+    assert_eq!(first.duration, Duration::seconds(90));
+    assert!(first.recovery_price.is_none());
+
+    let (recovered_at, recovered) = &events[1];
+    assert_eq!(*recovered_at, 200);
+    assert_eq!(recovered.severity, EventSeverity::Info);
+    assert_eq!(recovered.metadata["crash_event_id"], detected.event_id.as_str());
+    let follow_up = crash(recovered);
+    assert_eq!(follow_up.low_price, Decimal::from_str("0.80").unwrap());
+    assert!((follow_up.drop_percentage - 20.0).abs() < 1e-9);
+    assert_eq!(follow_up.duration, Duration::seconds(110));
+    assert_eq!(follow_up.recovery_price, Some(Decimal::from_str("0.90").unwrap()));
+    assert_eq!(follow_up.recovery_time, Some(Duration::seconds(90)));
+}
+
+#[tokio::test]
+async fn crash_without_recovery_stays_quiet_until_it_times_out() {
+    let detector = AnomalyDetector::new(FlashCrashConfig {
+        recovery_timeout: Duration::minutes(10),
+        ..FlashCrashConfig::default()
+    });
+    let mut ticks = vec![(0, "2.00"), (60, "1.60")];
+    // Drifting sideways near the low for a while
+    ticks.extend((1..=8).map(|minute| (60 + minute * 60, if minute % 2 == 0 { "1.55" } else { "1.45" })));
+    let events = replay(&detector, &ticks).await;
+
+    assert_eq!(events.len(), 1);
+    let detected = &events[0].1;
+    assert_eq!(detected.severity, EventSeverity::High);
+    assert!((crash(detected).drop_percentage - 20.0).abs() < 1e-9);
+
+    // Past the timeout the crash is dropped unrecovered, and the old 2.00 high
+    // can't start a second one
+    assert!(replay(&detector, &[(700, "1.50"), (720, "1.40")]).await.is_empty());
+
+    // A fresh drop from the new range is its own crash
+    let next = replay(&detector, &[(760, "1.50"), (800, "1.20")]).await;
+    assert_eq!(next.len(), 1);
+    assert_ne!(next[0].1.event_id, detected.event_id);
+    assert_eq!(crash(&next[0].1).start_price, Decimal::from_str("1.50").unwrap());
+}
+
+#[tokio::test]
+async fn slow_declines_and_small_dips_are_not_crashes() {
+    let detector = AnomalyDetector::default();
+    // -20% overall, but never 8% within two minutes
+    let slide: Vec<(i64, String)> = (0..=20).map(|minute| (minute * 60, format!("{:.2}", 1.0 - 0.01 * minute as f64))).collect();
+    let slide: Vec<(i64, &str)> = slide.iter().map(|(s, p)| (*s, p.as_str())).collect();
+    assert!(replay(&detector, &slide).await.is_empty());
+
+    assert!(replay(&detector, &[(1300, "0.80"), (1330, "0.75")]).await.is_empty());
+}
+
+#[tokio::test]
+async fn severity_scales_with_the_drop() {
+    for (low, severity) in [("0.91", EventSeverity::Medium), ("0.83", EventSeverity::High), ("0.70", EventSeverity::Critical)] {
+        let detector = AnomalyDetector::default();
+        let events = replay(&detector, &[(0, "1.00"), (20, low)]).await;
+        assert_eq!(events.len(), 1, "{} should be a crash", low);
+        assert_eq!(events[0].1.severity, severity, "drop to {}", low);
+    }
+}
@@ -0,0 +1,65 @@
+use serde_json::json;
+
+use crate::ai::{validate_analysis, AnalysisSchemaError, AnalysisSignal};
+
+fn answer(signal: &str, confidence: f64) -> String {
+    json!({
+        "signal": signal,
+        "confidence": confidence,
+        "summary": " Strong inflows into the token. ",
+        "key_factors": ["Volume", " ", "Listings"],
+        "risk_flags": []
+    })
+    .to_string()
+}
+
+#[test]
+fn test_valid_answer_becomes_an_analysis() {
+    let analysis = validate_analysis(&answer("sell", 0.65)).unwrap();
+
+    assert_eq!(analysis.signal, AnalysisSignal::Sell);
+    assert_eq!(analysis.confidence, 0.65);
+    assert_eq!(analysis.summary, "Strong inflows into the token.");
+    // Blank factors are dropped
+    assert_eq!(analysis.key_factors, vec!["Volume".to_string(), "Listings".to_string()]);
+    assert!(analysis.risk_flags.is_empty() && !analysis.cached);
+
+    // Both ends of the range are allowed, and risk_flags may be left out
+    assert!(validate_analysis(&answer("HOLD", 0.0)).is_ok());
+    assert!(validate_analysis(&answer("BUY", 1.0)).is_ok());
+    let without_flags = json!({ "signal": "BUY", "confidence": 0.5, "summary": "Ok", "key_factors": ["Volume"] });
+    assert!(validate_analysis(&without_flags.to_string()).is_ok());
+}
+
+#[test]
+fn test_out_of_range_confidence_is_rejected() {
+    // A percentage instead of a fraction
+    assert_eq!(validate_analysis(&answer("BUY", 80.0)).unwrap_err(), AnalysisSchemaError::ConfidenceOutOfRange(80.0));
+    assert_eq!(validate_analysis(&answer("BUY", -0.1)).unwrap_err(), AnalysisSchemaError::ConfidenceOutOfRange(-0.1));
+    assert_eq!(validate_analysis(&answer("BUY", 1.01)).unwrap_err(), AnalysisSchemaError::ConfidenceOutOfRange(1.01));
+}
+
+#[test]
+fn test_unknown_signal_is_rejected() {
+    assert_eq!(
+        validate_analysis(&answer("STRONG BUY", 0.7)).unwrap_err(),
+        AnalysisSchemaError::UnknownSignal("STRONG BUY".to_string())
+    );
+    assert_eq!(validate_analysis(&answer("", 0.7)).unwrap_err(), AnalysisSchemaError::UnknownSignal(String::new()));
+}
+
+#[test]
+fn test_prose_and_incomplete_objects_are_rejected() {
+    assert!(matches!(
+        validate_analysis("Summary|BUY|80|Volume,Momentum"),
+        Err(AnalysisSchemaError::InvalidJson(_))
+    ));
+    // Missing summary
+    let missing = json!({ "signal": "BUY", "confidence": 0.5, "key_factors": ["Volume"] });
+    assert!(matches!(validate_analysis(&missing.to_string()), Err(AnalysisSchemaError::InvalidJson(_))));
+
+    let blank = json!({ "signal": "BUY", "confidence": 0.5, "summary": "  ", "key_factors": ["Volume"] });
+    assert_eq!(validate_analysis(&blank.to_string()).unwrap_err(), AnalysisSchemaError::EmptySummary);
+    let no_factors = json!({ "signal": "BUY", "confidence": 0.5, "summary": "Ok", "key_factors": [""] });
+    assert_eq!(validate_analysis(&no_factors.to_string()).unwrap_err(), AnalysisSchemaError::NoKeyFactors);
+}
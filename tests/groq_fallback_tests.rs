@@ -0,0 +1,205 @@
+use crate::ai::{AiPriority, AnalysisOutcome, AnalysisSignal, GroqAnalyzer};
+use crate::cache::{manager::CacheConfig, CacheManager};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{net::TcpListener, sync::Mutex};
+
+const PRIMARY: &str = "primary-70b";
+const FALLBACK: &str = "fallback-8b";
+
+/// Answers each model with its configured status, after an optional delay; counts calls per model
+///
+/// Successful calls answer with the next scripted content, then a valid analysis.
+struct FakeGroq {
+    statuses: HashMap<&'static str, StatusCode>,
+    delays: HashMap<&'static str, Duration>,
+    contents: Mutex<Vec<String>>,
+    calls: Mutex<HashMap<String, usize>>,
+    requests: Mutex<Vec<serde_json::Value>>,
+}
+
+impl FakeGroq {
+    async fn calls(&self, model: &str) -> usize {
+        self.calls.lock().await.get(model).copied().unwrap_or(0)
+    }
+}
+
+async fn chat(State(groq): State<Arc<FakeGroq>>, Json(request): Json<serde_json::Value>) -> Response {
+    let model = request["model"].as_str().unwrap_or_default().to_string();
+    groq.requests.lock().await.push(request);
+    *groq.calls.lock().await.entry(model.clone()).or_default() += 1;
+    if let Some(delay) = groq.delays.get(model.as_str()) {
+        tokio::time::sleep(*delay).await;
+    }
+
+    let status = groq.statuses.get(model.as_str()).copied().unwrap_or(StatusCode::OK);
+    if status != StatusCode::OK {
+        return (status, Json(json!({ "error": { "message": "Rate limit reached" } }))).into_response();
+    }
+    let content = {
+        let mut contents = groq.contents.lock().await;
+        if contents.is_empty() { analysis_json(&model) } else { contents.remove(0) }
+    };
+    Json(json!({
+        "id": "chatcmpl-test",
+        "choices": [{
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop"
+        }],
+        "usage": { "prompt_tokens": 120, "completion_tokens": 40, "total_tokens": 160 }
+    }))
+    .into_response()
+}
+
+fn analysis_json(model: &str) -> String {
+    json!({
+        "signal": "BUY",
+        "confidence": 0.8,
+        "summary": format!("Answered by {}", model),
+        "key_factors": ["Volume", "Momentum", "Listings"],
+        "risk_flags": ["Thin liquidity"]
+    })
+    .to_string()
+}
+
+async fn fake_groq(
+    statuses: &[(&'static str, StatusCode)],
+    delays: &[(&'static str, Duration)],
+) -> (String, Arc<FakeGroq>) {
+    scripted_groq(statuses, delays, Vec::new()).await
+}
+
+async fn scripted_groq(
+    statuses: &[(&'static str, StatusCode)],
+    delays: &[(&'static str, Duration)],
+    contents: Vec<String>,
+) -> (String, Arc<FakeGroq>) {
+    let groq = Arc::new(FakeGroq {
+        statuses: statuses.iter().copied().collect(),
+        delays: delays.iter().copied().collect(),
+        contents: Mutex::new(contents),
+        calls: Mutex::new(HashMap::new()),
+        requests: Mutex::new(Vec::new()),
+    });
+    let app = Router::new()
+        .route("/openai/v1/chat/completions", post(chat))
+        .with_state(groq.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/openai/v1", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    (url, groq)
+}
+
+fn analyzer(url: &str) -> GroqAnalyzer {
+    GroqAnalyzer::new("test-key".to_string())
+        .with_base_url(url)
+        .with_model(PRIMARY)
+        .with_fallback_model(Some(FALLBACK.to_string()))
+}
+
+#[tokio::test]
+async fn test_rate_limited_primary_falls_back_to_the_secondary_model() {
+    let (url, groq) = fake_groq(&[(PRIMARY, StatusCode::TOO_MANY_REQUESTS)], &[]).await;
+    let analysis = analyzer(&url).analyze_token("BONK").await.unwrap();
+
+    assert_eq!(analysis.model_used, FALLBACK);
+    assert_eq!(analysis.signal, AnalysisSignal::Buy);
+    assert_eq!(analysis.risk_flags, vec!["Thin liquidity".to_string()]);
+    assert!(analysis.summary.contains(FALLBACK));
+    assert!(!analysis.cached && analysis.cache_note().is_none());
+    assert_eq!((groq.calls(PRIMARY).await, groq.calls(FALLBACK).await), (1, 1));
+}
+
+#[tokio::test]
+async fn test_slow_primary_times_out_into_the_fallback() {
+    let (url, groq) = fake_groq(&[], &[(PRIMARY, Duration::from_secs(5))]).await;
+    let analysis = analyzer(&url)
+        .with_timeout(Duration::from_millis(200))
+        .analyze_token("BONK")
+        .await
+        .unwrap();
+
+    assert_eq!(analysis.model_used, FALLBACK);
+    assert_eq!(groq.calls(PRIMARY).await, 1);
+}
+
+#[tokio::test]
+async fn test_error_when_every_model_fails() {
+    let (url, groq) = fake_groq(
+        &[(PRIMARY, StatusCode::TOO_MANY_REQUESTS), (FALLBACK, StatusCode::SERVICE_UNAVAILABLE)],
+        &[],
+    ).await;
+    assert!(analyzer(&url).analyze_token("BONK").await.is_err());
+
+    // Without a fallback only the primary is tried
+    let error = analyzer(&url).with_fallback_model(None).analyze_token("WIF").await.unwrap_err();
+    assert!(error.to_string().contains("429"), "{}", error);
+    assert_eq!((groq.calls(PRIMARY).await, groq.calls(FALLBACK).await), (2, 1));
+}
+
+#[tokio::test]
+async fn test_repeat_analysis_is_served_from_the_cache() {
+    let (url, groq) = fake_groq(&[], &[]).await;
+    let cache = Arc::new(CacheManager::new(CacheConfig::default()));
+    let analyzer = analyzer(&url).with_cache(cache);
+
+    let fresh = analyzer.analyze_token("BONK").await.unwrap();
+    assert_eq!((fresh.model_used.as_str(), fresh.cached), (PRIMARY, false));
+
+    let cached = analyzer.analyze_token("BONK").await.unwrap();
+    assert!(cached.cached);
+    assert_eq!(cached.model_used, PRIMARY);
+    assert_eq!(cached.summary, fresh.summary);
+    assert_eq!(cached.cache_note().as_deref(), Some("cached just now"));
+
+    // The budgeted path checks the cache before spending anything
+    match analyzer.analyze_token_with_budget("BONK", Some("42"), AiPriority::Interactive).await.unwrap() {
+        AnalysisOutcome::Ai(analysis) => assert!(analysis.cached),
+        other => panic!("expected a cached AI answer, got {:?}", other),
+    }
+    assert_eq!(groq.calls(PRIMARY).await, 1);
+
+    // Other tokens still go to the API
+    assert!(!analyzer.analyze_token("WIF").await.unwrap().cached);
+    assert_eq!(groq.calls(PRIMARY).await, 2);
+}
+
+#[tokio::test]
+async fn test_invalid_answer_is_retried_once_with_the_reason() {
+    let (url, groq) = scripted_groq(&[], &[], vec![
+        json!({ "signal": "MOON", "confidence": 0.9, "summary": "Up only", "key_factors": ["Hype"] }).to_string(),
+    ]).await;
+    let analysis = analyzer(&url).analyze_token("BONK").await.unwrap();
+
+    assert_eq!((analysis.signal, analysis.confidence), (AnalysisSignal::Buy, 0.8));
+    let requests = groq.requests.lock().await;
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0]["response_format"]["type"], "json_object");
+    let correction = requests[1]["messages"].as_array().unwrap().last().unwrap();
+    assert_eq!(correction["role"], "system");
+    assert!(correction["content"].as_str().unwrap().contains("MOON"));
+}
+
+#[tokio::test]
+async fn test_still_invalid_after_retry_is_a_parse_error() {
+    let (url, groq) = scripted_groq(&[], &[], vec![
+        "Summary|BUY|80|Volume".to_string(),
+        json!({ "signal": "BUY", "confidence": 80, "summary": "Up", "key_factors": ["Hype"] }).to_string(),
+    ]).await;
+    let error = analyzer(&url).analyze_token("BONK").await.unwrap_err();
+
+    assert!(error.to_string().contains("still invalid after retry"), "{}", error);
+    assert!(error.to_string().contains("confidence 80"), "{}", error);
+    assert_eq!(groq.calls(PRIMARY).await, 2);
+}
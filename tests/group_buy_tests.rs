@@ -0,0 +1,170 @@
+use crate::bot::group_buy::{
+    GroupBuy, GroupBuyCoordinator, GroupBuyParticipant, GuardDecision, JoinOutcome, MemberFill, MemberOutcome,
+};
+use crate::errors::{BotError, Result};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+const GROUP: i64 = -100_777;
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCaXpRjrq8jPUnZ8yw2";
+
+/// Members with scripted guards; records whose wallet was actually used
+#[derive(Default)]
+struct ScriptedMembers {
+    refusals: HashMap<i64, String>,
+    failures: Vec<i64>,
+    executed: Mutex<Vec<i64>>,
+}
+
+impl ScriptedMembers {
+    fn wallet(user_id: i64) -> String {
+        format!("Wallet{}xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx", user_id)
+    }
+}
+
+#[async_trait::async_trait]
+impl GroupBuyParticipant for ScriptedMembers {
+    async fn check_guards(&self, user_id: i64, _buy: &GroupBuy) -> GuardDecision {
+        match self.refusals.get(&user_id) {
+            Some(reason) => GuardDecision::Refuse(reason.clone()),
+            None => GuardDecision::Proceed,
+        }
+    }
+
+    async fn execute_buy(&self, user_id: i64, buy: &GroupBuy) -> Result<MemberFill> {
+        self.executed.lock().await.push(user_id);
+        if self.failures.contains(&user_id) {
+            return Err(BotError::trading("route not found".to_string()).into());
+        }
+        Ok(MemberFill {
+            amount_sol: buy.amount_each,
+            tokens_received: 1_000.0 * user_id as f64,
+            price: 0.00002 * user_id as f64,
+            tx_signature: format!("sig-{}-{}", user_id, Self::wallet(user_id)),
+        })
+    }
+}
+
+async fn open_buy(coordinator: &GroupBuyCoordinator, min: usize) -> GroupBuy {
+    coordinator.create(GROUP, 1, BONK, "BONK", 0.5, min, Some(0.00002), "🟢 Risk: Low".to_string())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_quorum_within_window() {
+    let coordinator = GroupBuyCoordinator::new(Duration::minutes(10));
+    let buy = open_buy(&coordinator, 3).await;
+    let now = Utc::now();
+
+    assert_eq!(coordinator.join(&buy.id, 1, now).await.unwrap(), JoinOutcome::Joined { count: 1 });
+    assert_eq!(coordinator.join(&buy.id, 1, now).await.unwrap(), JoinOutcome::AlreadyIn);
+    assert_eq!(coordinator.join(&buy.id, 2, now).await.unwrap(), JoinOutcome::Joined { count: 2 });
+
+    // Not executable before quorum
+    assert!(coordinator.execute(&buy.id, &ScriptedMembers::default()).await.is_err());
+    assert_eq!(coordinator.join(&buy.id, 3, now).await.unwrap(), JoinOutcome::QuorumReached { count: 3 });
+
+    let members = ScriptedMembers::default();
+    let (summary, outcomes) = coordinator.execute(&buy.id, &members).await.unwrap();
+    assert_eq!(summary.participants, 3);
+    assert_eq!(summary.filled, 3);
+    assert!((summary.total_volume_sol - 1.5).abs() < 1e-12);
+    assert_eq!(outcomes.len(), 3);
+
+    // A second trigger can't execute it again
+    assert!(coordinator.execute(&buy.id, &members).await.is_err());
+    assert_eq!(members.executed.lock().await.len(), 3);
+
+    // Joining after the window fails, and the buy expires without executing
+    let late = open_buy(&coordinator, 2).await;
+    let after_window = late.expires_at + Duration::seconds(1);
+    coordinator.join(&late.id, 1, now).await.unwrap();
+    assert!(coordinator.join(&late.id, 2, after_window).await.is_err());
+    assert!(coordinator.expire(&late.id, now).await.is_none());
+    let expired = coordinator.expire(&late.id, after_window).await.unwrap();
+    assert_eq!(expired.participants, vec![1]);
+    assert!(coordinator.get(&late.id).await.is_none());
+}
+
+#[tokio::test]
+async fn test_guard_refusal_skips_only_that_member() {
+    let coordinator = GroupBuyCoordinator::default();
+    let buy = open_buy(&coordinator, 4).await;
+    for user in 1..=4 {
+        coordinator.join(&buy.id, user, Utc::now()).await.unwrap();
+    }
+
+    let members = ScriptedMembers {
+        refusals: HashMap::from([(2, "trading is locked".to_string())]),
+        failures: vec![4],
+        ..Default::default()
+    };
+    let (summary, outcomes) = coordinator.execute(&buy.id, &members).await.unwrap();
+
+    // The refused member's wallet is never touched; the others still run
+    assert_eq!(*members.executed.lock().await, vec![1, 3, 4]);
+    assert!(matches!(&outcomes[1], MemberOutcome::Refused { user_id: 2, reason } if reason == "trading is locked"));
+    assert!(matches!(outcomes[3], MemberOutcome::Failed { user_id: 4, .. }));
+    assert_eq!(summary.participants, 4);
+    assert_eq!(summary.filled, 2);
+    assert!((summary.total_volume_sol - 1.0).abs() < 1e-12);
+
+    // Token-weighted: (1000 × 0.00002 + 3000 × 0.00006) / 4000
+    assert!((summary.average_fill_price.unwrap() - 0.00005).abs() < 1e-12);
+}
+
+#[tokio::test]
+async fn test_back_out_before_execution() {
+    let coordinator = GroupBuyCoordinator::default();
+    let buy = open_buy(&coordinator, 2).await;
+    let now = Utc::now();
+
+    coordinator.join(&buy.id, 1, now).await.unwrap();
+    assert_eq!(coordinator.back_out(&buy.id, 1).await.unwrap(), 0);
+    assert!(coordinator.back_out(&buy.id, 1).await.is_err());
+
+    // Quorum has to be reached again after someone leaves
+    coordinator.join(&buy.id, 2, now).await.unwrap();
+    assert_eq!(coordinator.join(&buy.id, 3, now).await.unwrap(), JoinOutcome::QuorumReached { count: 2 });
+
+    let members = ScriptedMembers::default();
+    let (_, outcomes) = coordinator.execute(&buy.id, &members).await.unwrap();
+    assert_eq!(*members.executed.lock().await, vec![2, 3]);
+    assert!(outcomes.iter().all(|outcome| outcome.user_id() != 1));
+
+    // Too late once execution has claimed the buy
+    assert!(coordinator.back_out(&buy.id, 2).await.is_err());
+}
+
+#[tokio::test]
+async fn test_group_messages_leak_no_member_data() {
+    let coordinator = GroupBuyCoordinator::default();
+    let buy = open_buy(&coordinator, 3).await;
+    for user in [101, 202, 303] {
+        coordinator.join(&buy.id, user, Utc::now()).await.unwrap();
+    }
+    let card = coordinator.get(&buy.id).await.unwrap().card_text();
+
+    let members = ScriptedMembers {
+        refusals: HashMap::from([(202, "insufficient balance".to_string())]),
+        ..Default::default()
+    };
+    let (summary, outcomes) = coordinator.execute(&buy.id, &members).await.unwrap();
+    let message = summary.group_message("BONK");
+
+    assert!(message.contains("2 of 3 participants"));
+    assert!(message.contains("1.0000 SOL"));
+    for text in [&card, &message] {
+        for user in [101, 202, 303] {
+            assert!(!text.contains(&user.to_string()));
+            assert!(!text.contains(&ScriptedMembers::wallet(user)));
+        }
+        assert!(!text.contains("sig-"));
+        assert!(!text.contains("insufficient balance"));
+    }
+
+    // Member-specific details exist only in the per-member outcomes
+    assert!(outcomes.iter().any(|o| matches!(o, MemberOutcome::Filled { fill, .. } if fill.tx_signature.contains("Wallet101"))));
+}
@@ -0,0 +1,251 @@
+use async_trait::async_trait;
+use solana_sdk::{
+    hash::Hash,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use crate::errors::Result;
+use crate::trading::{ExecutionNotifier, LedgerSigning, NoticeKind, OutgoingNotice, SigningRequest, SigningStrategy};
+use crate::wallet::{
+    DeviceInfo, HardwareWallet, HardwareWalletManager, LedgerModel, LedgerSolanaApp, LedgerTransport,
+    SecurityPolicies, TransactionReviewMode, WalletCapabilities, WalletStatus, WalletType,
+};
+
+const USER_ID: i64 = 775_001;
+/// 44'/501'/0'/0', as the app serializes it ahead of the message
+const PATH: [u32; 4] = [0x8000_002c, 0x8000_01f5, 0x8000_0000, 0x8000_0000];
+
+#[derive(Clone, Copy)]
+enum Answer {
+    Approve,
+    Reject,
+    Ignore,
+}
+
+/// A Ledger that signs with its own keypair once the whole message has arrived
+struct MockLedger {
+    keypair: Keypair,
+    answer: Answer,
+    received: Mutex<Vec<u8>>,
+    exchanges: AtomicUsize,
+}
+
+impl MockLedger {
+    fn new(answer: Answer) -> Arc<Self> {
+        Arc::new(Self {
+            keypair: Keypair::new(),
+            answer,
+            received: Mutex::new(Vec::new()),
+            exchanges: AtomicUsize::new(0),
+        })
+    }
+
+    fn exchanges(&self) -> usize {
+        self.exchanges.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl LedgerTransport for MockLedger {
+    async fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>> {
+        self.exchanges.fetch_add(1, Ordering::SeqCst);
+        let mut received = self.received.lock().await;
+        if apdu[2] == 0x01 {
+            received.clear();
+        }
+        received.extend_from_slice(&apdu[5..]);
+        if apdu[3] != 0x80 {
+            return Ok(vec![0x90, 0x00]);
+        }
+
+        match self.answer {
+            Answer::Approve => {
+                let message = &received[1 + 4 * PATH.len()..];
+                Ok(self.keypair.sign_message(message).as_ref().to_vec())
+            }
+            Answer::Reject => Ok(vec![0x69, 0x85]),
+            Answer::Ignore => {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(vec![0x69, 0x85])
+            }
+        }
+    }
+
+    async fn is_connected(&self) -> bool {
+        true
+    }
+}
+
+fn transfer(ledger: &MockLedger, lamports: u64) -> Transaction {
+    let from = ledger.keypair.pubkey();
+    let ix = system_instruction::transfer(&from, &Keypair::new().pubkey(), lamports);
+    let mut tx = Transaction::new_with_payer(&[ix], Some(&from));
+    tx.message.recent_blockhash = Hash::new_unique();
+    tx
+}
+
+fn request(ledger: &MockLedger, value_sol: f64) -> SigningRequest {
+    SigningRequest {
+        transaction: transfer(ledger, (value_sol * 1e9) as u64),
+        user_id: USER_ID.to_string(),
+        wallet_address: ledger.keypair.pubkey().to_string(),
+        estimated_sol_cost: 0.000005,
+        value_sol,
+        description: format!("Send {} SOL", value_sol),
+        requires_approval: true,
+    }
+}
+
+fn strategy(ledger: &Arc<MockLedger>, policies: SecurityPolicies) -> LedgerSigning {
+    LedgerSigning::new(LedgerSolanaApp::new(ledger.clone(), PATH.to_vec()), policies)
+}
+
+fn prompts(notices: &mut tokio::sync::broadcast::Receiver<OutgoingNotice>) -> Vec<String> {
+    let mut details = Vec::new();
+    while let Ok(OutgoingNotice::Notice(notice)) = notices.try_recv() {
+        if let NoticeKind::DeviceConfirmation { detail } = notice.kind {
+            assert_eq!(notice.user_id, USER_ID);
+            details.push(detail);
+        }
+    }
+    details
+}
+
+fn ledger_wallet(public_key: String) -> HardwareWallet {
+    HardwareWallet {
+        wallet_id: "ledger-1".to_string(),
+        wallet_type: WalletType::Ledger {
+            model: LedgerModel::NanoX,
+            firmware_version: "2.2.3".to_string(),
+            app_version: "1.4.0".to_string(),
+        },
+        device_info: DeviceInfo {
+            serial_number: None,
+            label: Some("Nano X".to_string()),
+            initialized: true,
+            passphrase_protection: false,
+            pin_protection: true,
+            needs_backup: false,
+        },
+        status: WalletStatus::Unlocked,
+        derivation_path: "m/44'/501'/0'/0'".to_string(),
+        public_key,
+        capabilities: WalletCapabilities {
+            blind_signing: false,
+            message_signing: true,
+            multi_account: true,
+            u2f_support: false,
+            webusb_support: true,
+            bluetooth_support: true,
+        },
+        last_used: chrono::Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn test_approved_trades_come_back_signed_by_the_wallet() {
+    let ledger = MockLedger::new(Answer::Approve);
+    let notifier = Arc::new(ExecutionNotifier::default());
+    let mut notices = notifier.subscribe();
+    let signing = strategy(&ledger, SecurityPolicies::default()).with_notifier(notifier);
+
+    let result = signing.sign(&request(&ledger, 2.0)).await.unwrap();
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(result.signing_method, "ledger");
+    let signed = result.signed_transaction.unwrap();
+    signed.verify().unwrap();
+    assert_eq!(result.signature.unwrap(), signed.signatures[0].to_string());
+
+    // Over the review threshold, so the prompt carries the standard details
+    let details = prompts(&mut notices);
+    assert_eq!(details.len(), 1);
+    assert!(details[0].contains("Send 2 SOL") && details[0].contains("2.0000 SOL"), "{}", details[0]);
+}
+
+#[tokio::test]
+async fn test_rejection_and_silence_on_the_device_fail_the_request() {
+    let ledger = MockLedger::new(Answer::Reject);
+    let result = strategy(&ledger, SecurityPolicies::default()).sign(&request(&ledger, 0.5)).await.unwrap();
+    assert!(!result.success && result.signed_transaction.is_none());
+    assert!(result.error.unwrap().contains("Rejected"));
+
+    let ledger = MockLedger::new(Answer::Ignore);
+    let result = strategy(&ledger, SecurityPolicies::default())
+        .with_timeout(Duration::from_millis(100))
+        .sign(&request(&ledger, 0.5))
+        .await
+        .unwrap();
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("within"));
+}
+
+#[tokio::test]
+async fn test_policy_refusals_happen_before_the_device_is_asked() {
+    let ledger = MockLedger::new(Answer::Approve);
+    let notifier = Arc::new(ExecutionNotifier::default());
+    let mut notices = notifier.subscribe();
+
+    // Over the wallet's limit
+    let capped = SecurityPolicies { max_transaction_value: Some(1_000_000_000), ..SecurityPolicies::default() };
+    let result = strategy(&ledger, capped).with_notifier(notifier).sign(&request(&ledger, 1.5)).await.unwrap();
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("limit"));
+
+    // Needs review, but there's nowhere to show it
+    let result = strategy(&ledger, SecurityPolicies::default()).sign(&request(&ledger, 1.5)).await.unwrap();
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("review"));
+
+    assert_eq!(ledger.exchanges(), 0);
+    assert!(prompts(&mut notices).is_empty());
+
+    // Under the threshold it signs without a notifier
+    let result = strategy(&ledger, SecurityPolicies::default()).sign(&request(&ledger, 0.5)).await.unwrap();
+    assert!(result.success);
+}
+
+#[tokio::test]
+async fn test_review_mode_sets_how_much_the_prompt_shows() {
+    let ledger = MockLedger::new(Answer::Approve);
+    let notifier = Arc::new(ExecutionNotifier::default());
+    let mut notices = notifier.subscribe();
+
+    for mode in [TransactionReviewMode::Minimal, TransactionReviewMode::Detailed, TransactionReviewMode::Expert] {
+        let policies = SecurityPolicies { transaction_review_mode: mode, ..SecurityPolicies::default() };
+        strategy(&ledger, policies).with_notifier(notifier.clone()).sign(&request(&ledger, 2.0)).await.unwrap();
+    }
+
+    let details = prompts(&mut notices);
+    assert_eq!(details.len(), 3);
+    assert_eq!(details[0], "Send 2 SOL");
+    assert!(details[1].contains("1 instructions") && !details[1].contains("Message:"));
+    assert!(details[2].contains("Message:"));
+}
+
+#[tokio::test]
+async fn test_only_attached_ledgers_are_routed_to_a_device() {
+    let manager = HardwareWalletManager::new(None);
+    let ledger = MockLedger::new(Answer::Approve);
+    let address = ledger.keypair.pubkey().to_string();
+    assert!(manager.ledger_for(&address).await.unwrap().is_none());
+
+    manager.attach_ledger(ledger_wallet(address.clone()), USER_ID, ledger.clone()).await.unwrap();
+    let (app, owner) = manager.ledger_for(&address).await.unwrap().unwrap();
+    assert_eq!(owner, USER_ID);
+    let signature = app.sign_transaction(&transfer(&ledger, 1)).await.unwrap();
+    assert_ne!(signature, solana_sdk::signature::Signature::default());
+
+    let mut trezor = ledger_wallet(Keypair::new().pubkey().to_string());
+    trezor.wallet_type = WalletType::Trezor {
+        model: crate::wallet::TrezorModel::ModelT,
+        firmware_version: "2.6.0".to_string(),
+        bootloader_version: "2.1.4".to_string(),
+    };
+    assert!(manager.attach_ledger(trezor, USER_ID, ledger).await.is_err());
+}
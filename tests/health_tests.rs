@@ -0,0 +1,143 @@
+use crate::monitoring::health::{DependencyStatus, HealthCheckConfig, ProbeOutcome};
+use crate::monitoring::{DependencyChecks, HealthCheck, HealthProbe, HealthStatus, MetricsExporter, ReadinessReport};
+use crate::testkit::TestHarness;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Serve the health routes on a free port and return the base URL
+async fn serve(health: Arc<HealthCheck>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let exporter = MetricsExporter::new(super::shared_metrics()).with_health_check(health);
+    tokio::spawn(async move {
+        let _ = exporter.serve(listener).await;
+    });
+    url
+}
+
+/// Probes for every harness dependency; Groq points at a server without `/models`
+async fn registry(harness: &TestHarness, jupiter_url: String) -> Arc<HealthCheck> {
+    let mut config = (*harness.config).clone();
+    config.jupiter_api_url = jupiter_url;
+    let health = Arc::new(HealthCheck::new("test".to_string()));
+    DependencyChecks::from_config(&config)
+        .with_database(harness.db.clone())
+        .with_groq_url(harness.jupiter.base_url())
+        .register(&health)
+        .await;
+    health
+}
+
+async fn readyz(url: &str) -> (StatusCode, ReadinessReport) {
+    let response = reqwest::get(format!("{}/readyz", url)).await.unwrap();
+    (response.status(), response.json().await.unwrap())
+}
+
+fn check<'a>(report: &'a ReadinessReport, component: &str) -> &'a DependencyStatus {
+    report.checks.iter().find(|check| check.component == component).unwrap()
+}
+
+#[tokio::test]
+async fn test_critical_dependency_down_fails_readiness_only() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let health = registry(&harness, format!("{}/missing", harness.jupiter.base_url())).await;
+    let url = serve(health).await;
+
+    let (status, report) = readyz(&url).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(!report.ready);
+    assert_eq!(report.status, HealthStatus::Unhealthy);
+
+    let components: Vec<&str> = report.checks.iter().map(|check| check.component.as_str()).collect();
+    assert_eq!(components, vec!["ai_analyzer", "database", "jupiter_api", "solana_rpc", "telegram_bot"]);
+
+    let jupiter = check(&report, "jupiter_api");
+    assert!(jupiter.critical);
+    assert_eq!(jupiter.status, HealthStatus::Unhealthy);
+    assert_eq!(jupiter.message, "Jupiter quote API returned 404 Not Found");
+    assert_eq!(check(&report, "solana_rpc").status, HealthStatus::Healthy);
+    assert_eq!(check(&report, "database").status, HealthStatus::Healthy);
+    let telegram = check(&report, "telegram_bot");
+    assert_eq!(telegram.status, HealthStatus::Healthy);
+    assert_eq!(telegram.metadata["bot_username"], "@mock_bot");
+
+    // Liveness doesn't care
+    let response = reqwest::get(format!("{}/healthz", url)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "alive");
+    assert_eq!(body["version"], "test");
+}
+
+#[tokio::test]
+async fn test_slot_lag_and_non_critical_failures_stay_ready_but_degraded() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    harness.rpc.set_slots_behind(Some(500)).await;
+    let health = registry(&harness, harness.jupiter.quote_api_url()).await;
+    let url = serve(health).await;
+
+    let (status, report) = readyz(&url).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(report.ready);
+    assert_eq!(report.status, HealthStatus::Degraded);
+
+    let rpc = check(&report, "solana_rpc");
+    assert_eq!(rpc.status, HealthStatus::Degraded);
+    assert_eq!(rpc.message, "RPC node is 500 slots behind");
+    assert_eq!(rpc.metadata["slots_behind"], "500");
+    let groq = check(&report, "ai_analyzer");
+    assert!(!groq.critical);
+    assert_eq!(groq.status, HealthStatus::Unhealthy);
+    assert_eq!(check(&report, "jupiter_api").status, HealthStatus::Healthy);
+
+    // Served from the cache until the check interval passes
+    harness.rpc.set_slots_behind(None).await;
+    let (_, again) = readyz(&url).await;
+    assert_eq!(check(&again, "solana_rpc").status, HealthStatus::Degraded);
+    assert_eq!(check(&again, "solana_rpc").checked_at, rpc.checked_at);
+}
+
+#[tokio::test]
+async fn test_lag_within_tolerance_is_healthy() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    harness.rpc.set_slots_behind(Some(20)).await;
+    let health = registry(&harness, harness.jupiter.quote_api_url()).await;
+
+    let report = health.readiness().await;
+    let rpc = check(&report, "solana_rpc");
+    assert_eq!(rpc.status, HealthStatus::Healthy);
+    assert_eq!(rpc.metadata["slots_behind"], "20");
+    assert_eq!(rpc.metadata["max_slot_lag"], "50");
+}
+
+struct Hanging;
+
+#[async_trait]
+impl HealthProbe for Hanging {
+    async fn check(&self) -> ProbeOutcome {
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        (HealthStatus::Healthy, "too late".to_string(), HashMap::new())
+    }
+}
+
+#[tokio::test]
+async fn test_probe_timeout_counts_as_unhealthy() {
+    let health = HealthCheck::new("test".to_string());
+    health.register_probe(HealthCheckConfig {
+        component: "hanging".to_string(),
+        check_interval_seconds: 30,
+        timeout_seconds: 1,
+        retries: 0,
+        critical: true,
+    }, Arc::new(Hanging)).await;
+
+    let report = health.readiness().await;
+    assert!(!report.ready);
+    assert_eq!(report.checks[0].status, HealthStatus::Unhealthy);
+    assert_eq!(report.checks[0].message, "No answer within 1s");
+}
@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, BTreeSet};
+use teloxide::types::InlineKeyboardButtonKind;
+
+use crate::bot::handlers::{CommandHandler, LanguageHandler, MenuButton, MenuCreator};
+use crate::bot::preferences::{PreferenceStore, SettingChange, UserSettings};
+use crate::utils::{language_name, t, t_args, Catalog, DEFAULT_LANG, LANGUAGES};
+
+const USER_ID: i64 = 764_001;
+
+/// `{name}` placeholders used in a text
+fn placeholders(text: &str) -> BTreeSet<&str> {
+    text.split('{').skip(1).filter_map(|rest| rest.split_once('}')).map(|(name, _)| name).collect()
+}
+
+/// Run with `just missing-keys`; fails when any shipped catalog lacks an English key
+#[test]
+fn test_missing_keys() {
+    let catalog = Catalog::embedded();
+    let missing = catalog.missing_keys();
+    assert!(missing.is_empty(), "catalogs missing English keys: {:#?}", missing);
+
+    // The picker offers exactly the shipped catalogs
+    let shipped: Vec<&str> = LANGUAGES.iter().map(|(code, _)| *code).collect();
+    let mut sorted = shipped.clone();
+    sorted.sort_unstable();
+    assert_eq!(catalog.languages(), sorted);
+
+    // Translations keep every placeholder the English text fills in
+    for lang in shipped {
+        for key in catalog.keys(DEFAULT_LANG) {
+            let english = catalog.get(DEFAULT_LANG, key).unwrap();
+            let translated = catalog.get(lang, key).unwrap();
+            assert_eq!(placeholders(translated), placeholders(english), "{} {}", lang, key);
+        }
+    }
+}
+
+#[test]
+fn test_missing_keys_are_listed_per_language() {
+    let catalog = Catalog::from_sources(&[
+        ("en", r#"{"a": "A", "b": "B", "c": "C"}"#),
+        ("fr", r#"{"a": "A fr"}"#),
+        ("de", r#"{"a": "A de", "b": "B de", "c": "C de"}"#),
+        // Regional catalogs only override what differs
+        ("fr-CA", r#"{"a": "A ca"}"#),
+    ])
+    .unwrap();
+
+    let expected = BTreeMap::from([("fr".to_string(), vec!["b".to_string(), "c".to_string()])]);
+    assert_eq!(catalog.missing_keys(), expected);
+
+    assert!(Catalog::from_sources(&[("fr", r#"{"a": "A"}"#)]).is_err());
+    assert!(Catalog::from_sources(&[("en", r#"{"a": 1}"#)]).is_err());
+}
+
+#[test]
+fn test_lookups_fall_back_from_region_to_language_to_english() {
+    assert_eq!(Catalog::fallback_chain("es-MX"), vec!["es-mx", "es", "en"]);
+    assert_eq!(Catalog::fallback_chain("pt_BR"), vec!["pt-br", "pt", "en"]);
+    assert_eq!(Catalog::fallback_chain("en-US"), vec!["en-us", "en"]);
+    assert_eq!(Catalog::fallback_chain(""), vec!["en"]);
+
+    let catalog = Catalog::from_sources(&[
+        ("en", r#"{"greeting": "Hello", "farewell": "Bye", "only_en": "English only"}"#),
+        ("es", r#"{"greeting": "Hola", "farewell": "Adiós"}"#),
+        ("es-MX", r#"{"greeting": "Qué onda"}"#),
+    ])
+    .unwrap();
+    assert_eq!(catalog.get("es-MX", "greeting"), Some("Qué onda"));
+    assert_eq!(catalog.get("es_mx", "farewell"), Some("Adiós"));
+    assert_eq!(catalog.get("es-MX", "only_en"), Some("English only"));
+    assert_eq!(catalog.get("xx", "greeting"), Some("Hello"));
+    assert_eq!(catalog.text("es", "unknown.key"), "unknown.key");
+
+    assert!(catalog.supports("es-AR"));
+    assert!(!catalog.supports("xx"));
+
+    // Telegram reports regional tags; the embedded catalogs cover them through their base language
+    assert_eq!(t("de-AT", "menu.help"), t("de", "menu.help"));
+    assert_eq!(language_name("pt-BR"), "🇧🇷 Português");
+    assert_eq!(language_name("xx"), "🇺🇸 English");
+}
+
+#[test]
+fn test_placeholders_are_filled() {
+    let text = t_args("fr", "language.changed", &[("language", "Français")]);
+    assert!(text.contains("Français"), "{}", text);
+    assert!(!text.contains('{'), "{}", text);
+
+    let text = t_args("es", "language.unknown", &[("code", "xx"), ("codes", "en, es")]);
+    assert!(text.contains("xx") && text.contains("en, es"), "{}", text);
+}
+
+#[test]
+fn test_menu_renders_and_parses_in_every_language() {
+    for (lang, _) in LANGUAGES {
+        for button in MenuButton::ALL {
+            assert_eq!(MenuButton::parse(&t(lang, button.key())), Some(button), "{} {:?}", lang, button);
+        }
+
+        let menu = MenuCreator::main_menu(lang);
+        let labels: Vec<&str> = menu.keyboard.iter().flatten().map(|button| button.text.as_str()).collect();
+        assert_eq!(labels.len(), MenuButton::ALL.len());
+        assert!(labels.contains(&t(lang, "menu.settings").as_str()), "{}", lang);
+    }
+    assert_eq!(MenuButton::parse("💰 Balance"), Some(MenuButton::Balance));
+    assert_eq!(MenuButton::parse("💰 残高"), Some(MenuButton::Balance));
+    assert_eq!(MenuButton::parse("Balance"), None);
+
+    // Catalog text is escaped for MarkdownV2
+    assert!(MenuCreator::main_menu_text("ja").contains("メインメニュー"));
+    let welcome = CommandHandler::welcome_text("en");
+    assert!(welcome.contains("Pump\\.fun"), "{}", welcome);
+    assert!(CommandHandler::welcome_text("ko").contains("핵심 기능"));
+}
+
+#[test]
+fn test_picker_offers_every_language() {
+    let picker = LanguageHandler::picker();
+    let data: Vec<String> = picker.inline_keyboard.iter().flatten()
+        .filter_map(|button| match &button.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+            _ => None,
+        })
+        .collect();
+    let expected: Vec<String> = LANGUAGES.iter().map(|(code, _)| format!("lang:{}", code)).collect();
+    assert_eq!(data, expected);
+}
+
+#[tokio::test]
+async fn test_language_choice_is_saved_with_the_settings() {
+    let preferences = PreferenceStore::default();
+    // Unset follows the Telegram app, else the default
+    assert_eq!(preferences.language(USER_ID, None).await, DEFAULT_LANG);
+
+    preferences.update(USER_ID, |settings| settings.set_language(Some("es-MX"))).await.unwrap();
+    assert_eq!(preferences.language(USER_ID, None).await, "es-mx");
+    assert_eq!(t(&preferences.language(USER_ID, None).await, "menu.help"), "📚 Ayuda");
+
+    // Unshipped languages are refused and the choice stays
+    assert!(preferences.update(USER_ID, |settings| settings.set_language(Some("xx"))).await.is_err());
+    assert_eq!(preferences.language(USER_ID, None).await, "es-mx");
+
+    // Resetting trading settings keeps it; clearing follows the app again
+    preferences.apply(USER_ID, SettingChange::Reset).await.unwrap();
+    assert_eq!(preferences.language(USER_ID, None).await, "es-mx");
+    preferences.update(USER_ID, |settings| settings.set_language(None)).await.unwrap();
+    assert_eq!(preferences.language(USER_ID, None).await, DEFAULT_LANG);
+
+    // Settings stored before the field existed load without a language
+    let mut stored = serde_json::to_value(UserSettings::default()).unwrap();
+    stored.as_object_mut().unwrap().remove("language");
+    let loaded: UserSettings = serde_json::from_value(stored).unwrap();
+    assert_eq!(loaded.language, None);
+}
@@ -0,0 +1,96 @@
+use chrono::{TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::utils::i18n::{fmt_datetime, fmt_number, fmt_number_md, DateStyle, NumberKind};
+use crate::utils::validation::Validator;
+
+const NBSP: &str = "\u{00A0}";
+const NNBSP: &str = "\u{202F}";
+
+#[test]
+fn test_german_number_fixtures() {
+    assert_eq!(fmt_number("de", 1234.56, NumberKind::Usd), format!("1.234,56{}$", NBSP));
+    assert_eq!(fmt_number("de", -1234.56, NumberKind::Usd), format!("-1.234,56{}$", NBSP));
+    assert_eq!(fmt_number("de", 1_234_567.891, NumberKind::Token), "1.234.567,89");
+    assert_eq!(fmt_number("de", 1234.56789, NumberKind::Sol), "1.234,5679");
+    assert_eq!(fmt_number("de", 12.5, NumberKind::Change), format!("+12,50{}%", NBSP));
+    assert_eq!(fmt_number("de", -3.2, NumberKind::Change), format!("-3,20{}%", NBSP));
+    assert_eq!(fmt_number("de", 45.3, NumberKind::Percent(1)), format!("45,3{}%", NBSP));
+
+    // Regional variants and casing fall back to the base language
+    assert_eq!(fmt_number("de-AT", 1234.5, NumberKind::Decimal(1)), "1.234,5");
+    assert_eq!(fmt_number("DE", 1234.5, NumberKind::Decimal(1)), "1.234,5");
+}
+
+#[test]
+fn test_french_number_fixtures() {
+    assert_eq!(fmt_number("fr", 1234.56, NumberKind::Usd), format!("1{}234,56{}$", NNBSP, NBSP));
+    assert_eq!(fmt_number("fr", 1_234_567.891, NumberKind::Token), format!("1{0}234{0}567,89", NNBSP));
+    assert_eq!(fmt_number("fr", 12.5, NumberKind::Change), format!("+12,50{}%", NNBSP));
+    assert_eq!(fmt_number("fr", 85.0, NumberKind::Percent(0)), format!("85{}%", NNBSP));
+}
+
+#[test]
+fn test_english_and_unknown_languages_keep_us_style() {
+    assert_eq!(fmt_number("en", 1234.56, NumberKind::Usd), "$1,234.56");
+    assert_eq!(fmt_number("en", -5.5, NumberKind::Usd), "-$5.50");
+    assert_eq!(fmt_number("en", 12.5, NumberKind::Change), "+12.50%");
+    assert_eq!(fmt_number("xx", 1234.56, NumberKind::Usd), "$1,234.56");
+    assert_eq!(fmt_number("", 1234.56, NumberKind::Usd), "$1,234.56");
+
+    // Values that round to zero carry no sign
+    assert_eq!(fmt_number("en", -0.001, NumberKind::Change), "0.00%");
+    assert_eq!(fmt_number("en", 0.001, NumberKind::Change), "0.00%");
+}
+
+#[test]
+fn test_sub_cent_precision_keeps_significant_digits() {
+    assert_eq!(fmt_number("en", 0.00001234, NumberKind::Usd), "$0.00001234");
+    assert_eq!(fmt_number("de", 0.00001234, NumberKind::Usd), format!("0,00001234{}$", NBSP));
+    assert_eq!(fmt_number("en", 0.5, NumberKind::Usd), "$0.5000");
+    assert_eq!(fmt_number("fr", 0.000012345678, NumberKind::Token), "0,00001235");
+
+    // Digits after the separator are never grouped
+    assert_eq!(fmt_number("en", 1234.123456789, NumberKind::Decimal(9)), "1,234.123456789");
+    assert_eq!(fmt_number("de", 1234.123456789, NumberKind::Decimal(9)), "1.234,123456789");
+    let tiny = fmt_number("fr", 0.0000001234, NumberKind::Token);
+    assert_eq!(tiny, "0,0000001234");
+    assert!(tiny.split_once(',').unwrap().1.chars().all(|c| c.is_ascii_digit()));
+}
+
+#[test]
+fn test_markdown_escaping_applies_after_formatting() {
+    assert_eq!(fmt_number_md("en", 1234.5, NumberKind::Usd), "$1,234\\.50");
+    assert_eq!(fmt_number_md("de", 1234.5, NumberKind::Usd), format!("1\\.234,50{}$", NBSP));
+    assert_eq!(fmt_number_md("de", 12.5, NumberKind::Change), format!("\\+12,50{}%", NBSP));
+    assert_eq!(fmt_number_md("en", -3.2, NumberKind::Change), "\\-3\\.20%");
+}
+
+#[test]
+fn test_datetime_fixtures() {
+    let ts = Utc.with_ymd_and_hms(2025, 9, 14, 12, 5, 0).unwrap();
+
+    assert_eq!(fmt_datetime("en", Tz::UTC, ts, DateStyle::ShortDate), "09/14");
+    assert_eq!(fmt_datetime("en", Tz::UTC, ts, DateStyle::DateTime), "09/14/2025 12:05 UTC");
+    assert_eq!(fmt_datetime("de", Tz::Europe__Berlin, ts, DateStyle::DateTime), "14.09.2025 14:05 CEST");
+    assert_eq!(fmt_datetime("de", Tz::Europe__Berlin, ts, DateStyle::ShortDate), "14.09.");
+    assert_eq!(fmt_datetime("fr", Tz::Europe__Paris, ts, DateStyle::Date), "14/09/2025");
+    assert_eq!(fmt_datetime("fr", Tz::Europe__Paris, ts, DateStyle::Time), "14:05 CEST");
+
+    // The user's zone can move the calendar date
+    let late = Utc.with_ymd_and_hms(2025, 9, 14, 23, 30, 0).unwrap();
+    assert_eq!(fmt_datetime("de", Tz::Europe__Berlin, late, DateStyle::Date), "15.09.2025");
+}
+
+#[test]
+fn test_input_parsing_is_locale_agnostic() {
+    assert_eq!(Validator::parse_amount("0.1").unwrap(), 0.1);
+    assert_eq!(Validator::parse_amount(" 50 ").unwrap(), 50.0);
+    assert_eq!(Validator::parse_amount("50%").unwrap(), 50.0);
+
+    // German and French users typing their own conventions get a clear hint
+    for input in ["0,1", "1.000,5", "1 000", "1,000", "1.000.5", "-1", "abc", ""] {
+        let err = Validator::parse_amount(input).unwrap_err().to_string();
+        assert!(err.contains("dot as the decimal separator, e.g. 0.1"), "{}: {}", input, err);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use crate::trading::{
+    bollinger_bands, ema, ema_series, macd, rsi, sma, wma, MovingAverageType, OrderIndicatorCondition,
+    OrderTechnicalIndicator, TechnicalCondition,
+};
+
+fn approx(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn condition(indicator: OrderTechnicalIndicator, condition: OrderIndicatorCondition, parameters: &[(&str, f64)]) -> TechnicalCondition {
+    TechnicalCondition {
+        indicator,
+        condition,
+        parameters: parameters.iter().map(|(name, value)| (name.to_string(), *value)).collect::<HashMap<_, _>>(),
+    }
+}
+
+#[test]
+fn test_moving_averages() {
+    let prices: Vec<f64> = (1..=10).map(f64::from).collect();
+
+    assert_eq!(sma(&prices, 3), Some(9.0));
+    // (8*1 + 9*2 + 10*3) / 6
+    assert!(approx(wma(&prices, 3).unwrap(), 56.0 / 6.0));
+    // Seeded at 2 (SMA of 1,2,3), then halfway to each new price
+    assert_eq!(ema_series(&prices, 3).unwrap(), vec![2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+    assert_eq!(ema(&prices, 3), Some(9.0));
+
+    assert_eq!(sma(&prices[..2], 3), None);
+    assert_eq!(ema(&prices[..2], 3), None);
+    assert_eq!(wma(&prices, 0), None);
+}
+
+#[test]
+fn test_rsi_uses_wilder_smoothing() {
+    // Changes +1 -1 +2 -1: seed gain 0.5 / loss 0.5, then 1.25 / 0.25, then 0.625 / 0.625
+    let prices = [10.0, 11.0, 10.0, 12.0, 11.0];
+    assert!(approx(rsi(&prices[..4], 2).unwrap(), 100.0 - 100.0 / 6.0));
+    assert!(approx(rsi(&prices, 2).unwrap(), 50.0));
+
+    assert_eq!(rsi(&[1.0, 2.0, 3.0], 2), Some(100.0));
+    assert_eq!(rsi(&[3.0, 2.0, 1.0], 2), Some(0.0));
+    // Needs period + 1 prices
+    assert_eq!(rsi(&prices[..2], 2), None);
+}
+
+#[test]
+fn test_macd_and_bollinger_bands() {
+    // On a straight line the EMAs run parallel: fast 2 leads slow 3 by 0.5
+    let line: Vec<f64> = (1..=6).map(f64::from).collect();
+    let result = macd(&line, 2, 3, 2).unwrap();
+    assert!(approx(result.macd, 0.5) && approx(result.signal, 0.5) && approx(result.histogram, 0.0));
+    assert_eq!(macd(&line[..3], 2, 3, 2), None);
+    assert_eq!(macd(&line, 3, 2, 2), None);
+
+    // Mean 5, population standard deviation 2
+    let bands = bollinger_bands(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0], 8, 2.0).unwrap();
+    assert_eq!((bands.lower, bands.middle, bands.upper), (1.0, 5.0, 9.0));
+    assert_eq!(bands.percent_b(7.0), 0.75);
+    assert_eq!(bollinger_bands(&[1.0; 4], 4, 2.0).unwrap().percent_b(1.0), 0.5);
+}
+
+#[test]
+fn test_conditions_evaluate_against_history() {
+    let prices = [10.0, 11.0, 10.0, 12.0, 11.0];
+    let rsi_below = |threshold| condition(
+        OrderTechnicalIndicator::RSI,
+        OrderIndicatorCondition::Below(threshold),
+        &[("period", 2.0)],
+    );
+    assert_eq!(rsi_below(60.0).evaluate(&prices), Some(true));
+    assert_eq!(rsi_below(40.0).evaluate(&prices), Some(false));
+
+    // Price moving through its 3-period SMA
+    let crossing = condition(
+        OrderTechnicalIndicator::MovingAverage(MovingAverageType::Simple),
+        OrderIndicatorCondition::CrossingAbove(0.0),
+        &[("period", 3.0)],
+    );
+    assert_eq!(crossing.evaluate(&[10.0, 10.0, 10.0, 10.0, 12.0]), Some(true));
+    // Already above on the previous point, so no new cross
+    assert_eq!(crossing.evaluate(&[10.0, 10.0, 10.0, 12.0, 13.0]), Some(false));
+    assert!(approx(crossing.value(&[10.0, 10.0, 12.0]).unwrap(), (12.0 / (32.0 / 3.0) - 1.0) * 100.0));
+}
+
+#[test]
+fn test_short_or_unsupported_history_stays_unevaluated() {
+    let prices: Vec<f64> = (1..=10).map(f64::from).collect();
+
+    // Default RSI period is 14
+    let rsi = condition(OrderTechnicalIndicator::RSI, OrderIndicatorCondition::Above(70.0), &[]);
+    assert_eq!(rsi.evaluate(&prices), None);
+    assert_eq!(rsi.evaluate(&[]), None);
+
+    // Crossing needs a reading on the previous point too
+    let bands = condition(
+        OrderTechnicalIndicator::BollingerBands,
+        OrderIndicatorCondition::CrossingBelow(0.0),
+        &[("period", 10.0)],
+    );
+    assert_eq!(bands.evaluate(&prices), None);
+
+    let atr = condition(OrderTechnicalIndicator::ATR, OrderIndicatorCondition::Above(0.0), &[]);
+    assert_eq!(atr.evaluate(&prices), None);
+}
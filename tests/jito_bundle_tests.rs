@@ -0,0 +1,219 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+    transaction::Transaction,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{net::TcpListener, sync::Mutex};
+
+use crate::bot::preferences::{PreferenceStore, UserSettings};
+use crate::trading::{
+    add_tip, BundleReceipt, ExecutionReport, JitoConfig, MevProtection, RecentTips, TipStrategy, TradeResult,
+    JITO_TIP_ACCOUNTS,
+};
+
+/// Block engine that accepts bundles unless told to reject, and reports each status in turn, then Pending
+struct FakeBlockEngine {
+    reject: bool,
+    statuses: Mutex<Vec<&'static str>>,
+    bundles: Mutex<Vec<serde_json::Value>>,
+}
+
+async fn bundles(State(engine): State<Arc<FakeBlockEngine>>, Json(request): Json<serde_json::Value>) -> Response {
+    if engine.reject {
+        return Json(json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32602, "message": "bundle tip too low" } }))
+            .into_response();
+    }
+    engine.bundles.lock().await.push(request["params"].clone());
+    Json(json!({ "jsonrpc": "2.0", "id": 1, "result": "bundle-1" })).into_response()
+}
+
+async fn inflight(State(engine): State<Arc<FakeBlockEngine>>) -> Response {
+    let status = {
+        let mut statuses = engine.statuses.lock().await;
+        if statuses.is_empty() { "Pending" } else { statuses.remove(0) }
+    };
+    let landed_slot = (status == "Landed").then_some(280_000_000u64);
+    Json(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "context": { "slot": 280_000_000u64 },
+            "value": [{ "bundle_id": "bundle-1", "status": status, "landed_slot": landed_slot }]
+        }
+    }))
+    .into_response()
+}
+
+async fn tip_floor() -> Response {
+    (StatusCode::OK, Json(json!([{
+        "time": "2026-10-16T00:00:00Z",
+        "landed_tips_25th_percentile": 0.000005,
+        "landed_tips_50th_percentile": 0.00001,
+        "landed_tips_75th_percentile": 0.00004,
+        "landed_tips_95th_percentile": 0.001,
+        "landed_tips_99th_percentile": 0.004,
+        "ema_landed_tips_50th_percentile": 0.00001
+    }])))
+    .into_response()
+}
+
+async fn fake_block_engine(reject: bool, statuses: Vec<&'static str>) -> (JitoConfig, Arc<FakeBlockEngine>) {
+    let engine = Arc::new(FakeBlockEngine { reject, statuses: Mutex::new(statuses), bundles: Mutex::new(Vec::new()) });
+    let app = Router::new()
+        .route("/api/v1/bundles", post(bundles))
+        .route("/api/v1/getInflightBundleStatuses", post(inflight))
+        .route("/tip_floor", get(tip_floor))
+        .with_state(engine.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+
+    let config = JitoConfig {
+        block_engine_url: url.clone(),
+        tip_floor_url: format!("{}/tip_floor", url),
+        landing_timeout: Duration::from_millis(300),
+        poll_interval: Duration::from_millis(20),
+        ..JitoConfig::default()
+    };
+    (config, engine)
+}
+
+fn recent() -> RecentTips {
+    RecentTips { p25: 5_000, p50: 10_000, p75: 40_000, p95: 1_000_000, p99: 4_000_000 }
+}
+
+fn swap(payer: &Pubkey) -> Transaction {
+    let program = Pubkey::new_unique();
+    let instruction = Instruction::new_with_bytes(program, &[7, 1, 2], vec![AccountMeta::new(*payer, true)]);
+    let mut message = Message::new(&[instruction], Some(payer));
+    message.recent_blockhash = Hash::new_unique();
+    Transaction::new_unsigned(message)
+}
+
+#[test]
+fn test_tip_strategies_size_from_recent_tips_within_limits() {
+    let config = JitoConfig::default();
+    let recent = recent();
+
+    assert_eq!(TipStrategy::Static { lamports: 20_000 }.tip_lamports(Some(&recent), &config), 20_000);
+    assert_eq!(TipStrategy::Percentile { percentile: 75.0 }.tip_lamports(Some(&recent), &config), 40_000);
+    // A fifth of the way from the 50th to the 75th
+    assert_eq!(TipStrategy::Percentile { percentile: 55.0 }.tip_lamports(Some(&recent), &config), 16_000);
+    assert_eq!(TipStrategy::Aggressive { multiplier: 1.5 }.tip_lamports(Some(&recent), &config), 1_500_000);
+
+    // Without a tip floor only the static tip is known
+    assert_eq!(TipStrategy::Percentile { percentile: 75.0 }.tip_lamports(None, &config), config.fallback_tip_lamports);
+    // Clamped to Jito's minimum and the configured cap
+    assert_eq!(TipStrategy::Static { lamports: 10 }.tip_lamports(None, &config), config.min_tip_lamports);
+    assert_eq!(TipStrategy::Aggressive { multiplier: 6.0 }.tip_lamports(Some(&recent), &config), config.max_tip_lamports);
+}
+
+#[test]
+fn test_tip_is_appended_to_the_swap_paid_by_the_user() {
+    let payer = Pubkey::new_unique();
+    let tip_account = Pubkey::from_str(JITO_TIP_ACCOUNTS[0]).unwrap();
+    let original = swap(&payer);
+    let tipped = add_tip(&original, &payer, &tip_account, 40_000);
+
+    let message = &tipped.message;
+    assert_eq!(message.account_keys[0], payer);
+    assert_eq!(message.recent_blockhash, original.message.recent_blockhash);
+    assert_eq!(message.instructions.len(), 2);
+    assert_eq!(message.instructions[0].data, vec![7, 1, 2]);
+
+    let tip = message.instructions.last().unwrap();
+    assert_eq!(message.account_keys[tip.program_id_index as usize], system_program::id());
+    assert_eq!(tip.data, system_instruction::transfer(&payer, &tip_account, 40_000).data);
+    assert_eq!(message.account_keys[tip.accounts[1] as usize], tip_account);
+}
+
+#[tokio::test]
+async fn test_landed_bundle_is_counted_with_its_tip() {
+    let (config, engine) = fake_block_engine(false, vec!["Pending", "Landed"]).await;
+    let mev = MevProtection::new(config);
+    let payer = Pubkey::new_unique();
+
+    // The default strategy reads the 75th percentile off the tip floor
+    let (tipped, tip) = mev.prepare(&swap(&payer), &payer).await;
+    assert_eq!(tip, 40_000);
+    let receipt = mev.submit(&tipped, tip).await;
+
+    assert!(receipt.landed && !receipt.fell_back_to_rpc);
+    assert_eq!((receipt.bundle_id.as_deref(), receipt.tip_lamports), (Some("bundle-1"), 40_000));
+    assert!(receipt.landing_ms.is_some());
+    assert_eq!(engine.bundles.lock().await[0][1]["encoding"], "base64");
+
+    let stats = mev.stats();
+    assert_eq!((stats.sent, stats.landed, stats.timed_out, stats.rpc_fallbacks), (1, 1, 0, 0));
+    assert_eq!(stats.tips_paid_lamports, 40_000);
+    assert_eq!(stats.landing_rate(), Some(100.0));
+
+    let trade = TradeResult::buy("sig".to_string(), 1.0, 0.1, 0.1);
+    assert!(!trade.landed_via_bundle());
+    let trade = trade.with_execution(ExecutionReport::default().with_bundle(Some(receipt)));
+    assert!(trade.landed_via_bundle());
+    assert_eq!(trade.bundle_tip_sol(), Some(0.00004));
+}
+
+#[tokio::test]
+async fn test_pending_bundle_times_out_and_falls_back_to_rpc() {
+    let (config, _engine) = fake_block_engine(false, Vec::new()).await;
+    let mev = MevProtection::new(config);
+    let payer = Pubkey::new_unique();
+
+    let mut receipt = mev.submit(&swap(&payer), 50_000).await;
+    assert!(!receipt.landed);
+    assert_eq!(receipt.bundle_id.as_deref(), Some("bundle-1"));
+
+    mev.record_fallback(&mut receipt);
+    assert!(receipt.fell_back_to_rpc);
+    let stats = mev.stats();
+    assert_eq!((stats.sent, stats.landed, stats.timed_out, stats.rpc_fallbacks), (1, 0, 1, 1));
+    // A tip only counts once a bundle lands
+    assert_eq!(stats.tips_paid_lamports, 0);
+    assert_eq!(stats.average_landing_ms, None);
+}
+
+#[tokio::test]
+async fn test_rejected_and_failed_bundles_do_not_land() {
+    let (config, _engine) = fake_block_engine(true, Vec::new()).await;
+    let mev = MevProtection::new(config);
+    let receipt = mev.submit(&swap(&Pubkey::new_unique()), 50_000).await;
+    assert_eq!(receipt, BundleReceipt { tip_lamports: 50_000, ..BundleReceipt::default() });
+    assert_eq!((mev.stats().sent, mev.stats().failed), (0, 1));
+
+    let (config, _engine) = fake_block_engine(false, vec!["Failed"]).await;
+    let mev = MevProtection::new(config);
+    assert!(!mev.submit(&swap(&Pubkey::new_unique()), 50_000).await.landed);
+    assert_eq!((mev.stats().sent, mev.stats().failed, mev.stats().timed_out), (1, 1, 0));
+}
+
+#[tokio::test]
+async fn test_protection_follows_the_user_preference() {
+    let preferences = Arc::new(PreferenceStore::default());
+    let mev = MevProtection::new(JitoConfig::default()).with_preferences(preferences.clone());
+    assert!(!mev.is_enabled_for(7).await);
+
+    preferences.set(7, UserSettings { mev_protection: true, ..Default::default() }).await;
+    assert!(mev.is_enabled_for(7).await);
+    assert!(!mev.is_enabled_for(8).await);
+
+    // Without a preference source nobody is protected
+    assert!(!MevProtection::new(JitoConfig::default()).is_enabled_for(7).await);
+}
@@ -0,0 +1,128 @@
+use crate::analytics::{CloseReason, JournalConfig, JournalTag, PositionClose, TagBreakdown, TradeJournal};
+use crate::trading::{Order, TradeResult};
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+
+const USER_ID: i64 = 424_242;
+const MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+fn sell_result(tokens_sold: f64, price: f64, pnl_percentage: f64) -> TradeResult {
+    let mut result = TradeResult::sell("sig".to_string(), tokens_sold, tokens_sold * price / 200.0, price);
+    result.pnl_percentage = pnl_percentage;
+    result
+}
+
+fn close(value_usd: f64, return_pct: f64) -> PositionClose {
+    PositionClose {
+        user_id: USER_ID,
+        mint: MINT.to_string(),
+        symbol: "BONK".to_string(),
+        reason: CloseReason::ManualSell,
+        return_pct: Some(return_pct),
+        value_usd: Some(value_usd),
+        closed_at: Utc::now(),
+        tx_signature: None,
+    }
+}
+
+#[test]
+fn test_close_detection_across_paths() {
+    // Manual and panic sells close only on a full exit
+    let full = PositionClose::from_sell(USER_ID, MINT, "BONK", 100.0, &sell_result(1_000.0, 0.02, -12.0), CloseReason::ManualSell);
+    assert_eq!(full.as_ref().unwrap().reason, CloseReason::ManualSell);
+    assert_eq!(full.unwrap().return_pct, Some(-12.0));
+    assert!(PositionClose::from_sell(USER_ID, MINT, "BONK", 50.0, &sell_result(500.0, 0.02, 5.0), CloseReason::ManualSell).is_none());
+
+    let panic = PositionClose::from_sell(USER_ID, MINT, "BONK", 100.0, &sell_result(1_000.0, 0.02, -30.0), CloseReason::PanicSell);
+    assert_eq!(panic.unwrap().reason, CloseReason::PanicSell);
+
+    // Stop-loss filling the whole position
+    let mut stop = Order::create_stop_loss(USER_ID, MINT.to_string(), Decimal::new(9, 1), Decimal::from(1_000));
+    stop.metadata.entry_price = Some(Decimal::ONE);
+    let closed = PositionClose::from_order_fill(&stop, Decimal::from(1_000), Decimal::new(88, 2), "BONK").unwrap();
+    assert_eq!(closed.reason, CloseReason::StopLoss);
+    assert!((closed.return_pct.unwrap() + 12.0).abs() < 1e-9);
+
+    // Take-profit for part of a larger position leaves it open
+    let mut take_profit = Order::create_take_profit(USER_ID, MINT.to_string(), Decimal::new(15, 1), Decimal::from(400));
+    take_profit.metadata.position_size = Some(Decimal::from(1_000));
+    assert!(PositionClose::from_order_fill(&take_profit, Decimal::from(400), Decimal::new(15, 1), "BONK").is_none());
+
+    take_profit.metadata.position_size = None;
+    let closed = PositionClose::from_order_fill(&take_profit, Decimal::from(400), Decimal::new(15, 1), "BONK").unwrap();
+    assert_eq!(closed.reason, CloseReason::TakeProfit);
+    assert_eq!(closed.return_pct, None);
+}
+
+#[test]
+fn test_tag_breakdown_math() {
+    let breakdown = TagBreakdown::compute(vec![
+        (Some(JournalTag::FollowedPlan), 10.0),
+        (Some(JournalTag::FollowedPlan), -4.0),
+        (Some(JournalTag::BrokePlan), -12.0),
+        (Some(JournalTag::BrokePlan), -18.0),
+        (Some(JournalTag::BrokePlan), 6.0),
+        (None, 2.0),
+    ]);
+
+    assert_eq!(breakdown.len(), 3);
+
+    let followed = &breakdown[0];
+    assert_eq!(followed.tag, Some(JournalTag::FollowedPlan));
+    assert_eq!((followed.trades, followed.wins), (2, 1));
+    assert!((followed.win_rate - 50.0).abs() < 1e-9);
+    assert!((followed.average_return_pct - 3.0).abs() < 1e-9);
+
+    let broke = &breakdown[1];
+    assert_eq!(broke.tag, Some(JournalTag::BrokePlan));
+    assert_eq!((broke.trades, broke.wins), (3, 1));
+    assert!((broke.average_return_pct + 8.0).abs() < 1e-9);
+
+    assert_eq!(breakdown[2].tag, None);
+}
+
+#[tokio::test]
+async fn test_prompt_suppression_for_dust_and_frequency() {
+    let journal = TradeJournal::new(JournalConfig {
+        dust_threshold_usd: 5.0,
+        max_prompts_per_day: 2,
+        ..JournalConfig::default()
+    });
+
+    // Dust is recorded but never prompted
+    assert!(journal.on_position_closed(close(1.0, 50.0)).await.is_none());
+    assert_eq!(journal.recent(USER_ID, 10).await.len(), 1);
+
+    assert!(journal.on_position_closed(close(100.0, -12.0)).await.is_some());
+    assert!(journal.on_position_closed(close(100.0, 8.0)).await.is_some());
+    // Third prompt in 24h is suppressed
+    assert!(journal.on_position_closed(close(100.0, 3.0)).await.is_none());
+
+    // The window rolls over
+    let mut tomorrow = close(100.0, 1.0);
+    tomorrow.closed_at = Utc::now() + Duration::hours(25);
+    assert!(journal.on_position_closed(tomorrow).await.is_some());
+
+    // Permanently dismissed
+    journal.set_prompts_enabled(USER_ID, false).await;
+    let mut later = close(100.0, 1.0);
+    later.closed_at = Utc::now() + Duration::days(3);
+    assert!(journal.on_position_closed(later).await.is_none());
+    assert_eq!(journal.recent(USER_ID, 10).await.len(), 6);
+}
+
+#[tokio::test]
+async fn test_retag_and_export() {
+    let journal = TradeJournal::new(JournalConfig::default());
+    let prompt = journal.on_position_closed(close(100.0, -12.0)).await.unwrap();
+    assert!(prompt.message.contains("BONK −12%"));
+
+    journal.tag(USER_ID, &prompt.entry_id, JournalTag::BrokePlan, None).await.unwrap();
+    journal.tag(USER_ID, &prompt.entry_id, JournalTag::Other, Some("chased, \"news\"".to_string())).await.unwrap();
+
+    let csv = journal.export_csv(USER_ID).await;
+    let row = csv.lines().nth(1).unwrap();
+    assert!(csv.starts_with("closed_at,symbol,mint,close_reason,return_pct,value_usd,tag,note"));
+    assert!(row.contains(",other,"));
+    assert!(row.contains("\"chased, \"\"news\"\"\""));
+}
@@ -0,0 +1,213 @@
+use crate::api::{
+    create_enhanced_swap_request, ApiTier, JupiterV6Client, QuoteRequestV6, QuoteResponseV6, RateLimits,
+};
+use crate::bot::handlers::TradingHandler;
+use crate::errors::BotError;
+use crate::monitoring::MetricsCollector;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::{net::TcpListener, sync::Mutex};
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xyybapC8G4wEGGkZwyTDt1v";
+
+/// Answers each call with the next status, then 200, after an optional delay; counts the calls
+struct FakeJupiter {
+    statuses: Mutex<Vec<StatusCode>>,
+    delay: Duration,
+    calls: Mutex<usize>,
+}
+
+fn quote_body() -> serde_json::Value {
+    json!({
+        "inputMint": SOL,
+        "inAmount": "1000000000",
+        "outputMint": USDC,
+        "outAmount": "150000000",
+        "otherAmountThreshold": "149250000",
+        "swapMode": "ExactIn",
+        "slippageBps": 50,
+        "platformFee": null,
+        "priceImpactPct": "0.001",
+        "routePlan": [],
+        "contextSlot": 287700100,
+        "timeTaken": 0.01
+    })
+}
+
+async fn respond(jupiter: &FakeJupiter, body: serde_json::Value) -> Response {
+    *jupiter.calls.lock().await += 1;
+    tokio::time::sleep(jupiter.delay).await;
+    let status = {
+        let mut statuses = jupiter.statuses.lock().await;
+        if statuses.is_empty() { StatusCode::OK } else { statuses.remove(0) }
+    };
+    if status == StatusCode::OK {
+        Json(body).into_response()
+    } else {
+        (status, Json(json!({ "error": "Too many requests" }))).into_response()
+    }
+}
+
+async fn quote(State(jupiter): State<Arc<FakeJupiter>>) -> Response {
+    respond(&jupiter, quote_body()).await
+}
+
+async fn swap(State(jupiter): State<Arc<FakeJupiter>>) -> Response {
+    respond(&jupiter, json!({ "swapTransaction": "AQID", "lastValidBlockHeight": 250_000_000u64 })).await
+}
+
+async fn fake_jupiter(statuses: Vec<StatusCode>, delay: Duration) -> (String, Arc<FakeJupiter>) {
+    let jupiter = Arc::new(FakeJupiter { statuses: Mutex::new(statuses), delay, calls: Mutex::new(0) });
+    let app = Router::new()
+        .route("/v6/quote", get(quote))
+        .route("/v6/swap", post(swap))
+        .with_state(jupiter.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    (url, jupiter)
+}
+
+fn client(url: &str) -> JupiterV6Client {
+    JupiterV6Client::new(ApiTier::Lite, None)
+        .with_base_url(url)
+        .with_retry_backoff(Duration::from_millis(10))
+}
+
+fn limits(per_minute: u32, concurrent: u32) -> RateLimits {
+    RateLimits {
+        requests_per_minute: per_minute,
+        requests_per_hour: per_minute * 60,
+        requests_per_day: per_minute * 1440,
+        concurrent_requests: concurrent,
+        quote_cache_ttl_seconds: 30,
+    }
+}
+
+fn quote_request() -> QuoteRequestV6 {
+    QuoteRequestV6 {
+        input_mint: SOL.to_string(),
+        output_mint: USDC.to_string(),
+        amount: 1_000_000_000,
+        slippage_bps: 50,
+        swap_mode: None,
+        dexes: None,
+        exclude_dexes: None,
+        max_accounts: None,
+        quote_mint: None,
+        minimize_slippage: None,
+        only_direct_routes: None,
+    }
+}
+
+fn sample_count(metrics: &MetricsCollector, family: &str, label: &str) -> u64 {
+    metrics.gather()
+        .iter()
+        .filter(|f| f.get_name() == family)
+        .flat_map(|f| f.get_metric().iter())
+        .filter(|m| m.get_label().iter().any(|l| l.get_value() == label))
+        .map(|m| m.get_histogram().get_sample_count() + m.get_counter().get_value() as u64)
+        .sum()
+}
+
+#[tokio::test]
+async fn quote_retries_through_429s_and_records_the_waits() {
+    let (url, jupiter) = fake_jupiter(vec![StatusCode::TOO_MANY_REQUESTS; 2], Duration::ZERO).await;
+    let metrics = super::shared_metrics();
+    let client = client(&url).with_metrics(metrics.clone());
+
+    let quote: QuoteResponseV6 = client.get_quote(quote_request()).await.unwrap();
+
+    assert_eq!(quote.out_amount, "150000000");
+    assert_eq!(*jupiter.calls.lock().await, 3);
+    // Every attempt waits for a permit, and both 429s were retried
+    assert!(sample_count(&metrics, "api_rate_limit_wait_ms", "jupiter_quote") >= 3);
+    assert!(sample_count(&metrics, "api_retries_total", "429") >= 2);
+}
+
+#[tokio::test]
+async fn swap_retries_server_errors_too() {
+    let (url, jupiter) = fake_jupiter(vec![StatusCode::BAD_GATEWAY, StatusCode::SERVICE_UNAVAILABLE], Duration::ZERO).await;
+    let client = client(&url);
+    let quote = client.get_quote(quote_request()).await.unwrap();
+
+    let swap = client
+        .execute_swap(create_enhanced_swap_request(quote, SOL.to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(swap.last_valid_block_height, 250_000_000);
+    assert_eq!(*jupiter.calls.lock().await, 4);
+}
+
+#[tokio::test]
+async fn retries_stop_at_the_callers_deadline() {
+    let (url, jupiter) = fake_jupiter(vec![StatusCode::TOO_MANY_REQUESTS; 100], Duration::ZERO).await;
+    let throttled = client(&url).with_retry_backoff(Duration::from_millis(40));
+
+    let started = Instant::now();
+    let err = throttled.get_quote_within(quote_request(), Duration::from_millis(300)).await.unwrap_err();
+
+    assert!(matches!(err, BotError::RateLimited { .. }), "got {:?}", err);
+    assert!(started.elapsed() < Duration::from_millis(600));
+    let calls = *jupiter.calls.lock().await;
+    assert!((2..100).contains(&calls), "made {} calls", calls);
+    assert!(TradingHandler::failure_message("Trade", &err, "en").contains("try again"));
+
+    // A server that keeps failing surfaces its status once the deadline is spent
+    let (url, _) = fake_jupiter(vec![StatusCode::SERVICE_UNAVAILABLE; 100], Duration::ZERO).await;
+    let err = client(&url).get_quote_within(quote_request(), Duration::from_millis(200)).await.unwrap_err();
+    assert!(!matches!(err, BotError::RateLimited { .. }));
+    assert!(err.to_string().contains("503"));
+    assert!(TradingHandler::failure_message("Trade", &err, "en").starts_with("❌ Trade failed"));
+}
+
+#[tokio::test]
+async fn requests_over_the_per_minute_limit_wait_then_give_up() {
+    let (url, jupiter) = fake_jupiter(vec![], Duration::ZERO).await;
+    let client = client(&url).with_rate_limits(&limits(2, 5));
+
+    client.get_quote(quote_request()).await.unwrap();
+    client.get_quote(quote_request()).await.unwrap();
+    // The next slot frees up in a minute, well past this call's deadline
+    let err = client.get_quote_within(quote_request(), Duration::from_millis(200)).await.unwrap_err();
+
+    assert!(matches!(err, BotError::RateLimited { .. }), "got {:?}", err);
+    assert_eq!(*jupiter.calls.lock().await, 2);
+}
+
+#[tokio::test]
+async fn a_full_queue_fails_fast() {
+    let (url, jupiter) = fake_jupiter(vec![], Duration::from_millis(500)).await;
+    // One call in flight, and room for two more to queue behind it
+    let client = Arc::new(client(&url).with_rate_limits(&limits(60, 1)));
+
+    let mut queued = Vec::new();
+    for _ in 0..3 {
+        let client = client.clone();
+        queued.push(tokio::spawn(async move { client.get_quote(quote_request()).await }));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let started = Instant::now();
+    let err = client.get_quote(quote_request()).await.unwrap_err();
+    assert!(matches!(err, BotError::RateLimited { .. }), "got {:?}", err);
+    assert!(started.elapsed() < Duration::from_millis(100));
+
+    // The calls already queued still go through, one at a time
+    for call in queued {
+        call.await.unwrap().unwrap();
+    }
+    assert_eq!(*jupiter.calls.lock().await, 3);
+}
@@ -0,0 +1,253 @@
+use crate::security::providers::{
+    goplus::GoPlusProvider,
+    onchain::{
+        OnChainProvider, RAYDIUM_AMM_V4_PROGRAM, RAYDIUM_AUTHORITY, RAYDIUM_BASE_MINT, RAYDIUM_LP_MINT,
+        RAYDIUM_LP_RESERVE, RAYDIUM_POOL_LEN,
+    },
+    SecurityProvider,
+};
+use crate::security::{LarpChecker, RiskLevel, WarningCategory};
+use crate::testkit::MockRpc;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+/// 1,000,000 tokens at 6 decimals
+const SUPPLY: u64 = 1_000_000_000_000;
+
+fn coption(key: Option<Pubkey>) -> Vec<u8> {
+    match key {
+        Some(key) => [&[1, 0, 0, 0][..], key.as_ref()].concat(),
+        None => vec![0; 36],
+    }
+}
+
+fn mint_data(mint_authority: Option<Pubkey>, freeze_authority: Option<Pubkey>) -> Vec<u8> {
+    let mut data = coption(mint_authority);
+    data.extend_from_slice(&SUPPLY.to_le_bytes());
+    data.extend_from_slice(&[6, 1]);
+    data.extend(coption(freeze_authority));
+    data
+}
+
+fn token_account_data(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data[..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data
+}
+
+fn with_context(value: Value) -> Value {
+    json!({ "context": { "slot": 1 }, "value": value })
+}
+
+fn token_amount(amount: u64) -> Value {
+    let ui_amount = amount as f64 / 1e6;
+    json!({ "amount": amount.to_string(), "decimals": 6, "uiAmount": ui_amount, "uiAmountString": ui_amount.to_string() })
+}
+
+/// Token accounts of `mint` held by each owner, served with their balances as the largest accounts
+async fn set_holders(rpc: &MockRpc, mint: &Pubkey, holders: &[(Pubkey, u64)]) {
+    let mut largest = Vec::new();
+    for (owner, amount) in holders {
+        let account = Pubkey::new_unique();
+        rpc.set_account(&account.to_string(), TOKEN_PROGRAM, &token_account_data(mint, owner, *amount)).await;
+        let mut entry = token_amount(*amount);
+        entry["address"] = json!(account.to_string());
+        largest.push(entry);
+    }
+    rpc.set_response("getTokenLargestAccounts", Some(&mint.to_string()), with_context(json!(largest))).await;
+}
+
+/// The mint's whole history: one transaction `age` ago
+async fn set_created(rpc: &MockRpc, mint: &Pubkey, age: Duration) {
+    let signatures = json!([{
+        "signature": Signature::new_unique().to_string(),
+        "slot": 1,
+        "err": null,
+        "memo": null,
+        "blockTime": (Utc::now() - age).timestamp(),
+        "confirmationStatus": "finalized",
+    }]);
+    rpc.set_response("getSignaturesForAddress", Some(&mint.to_string()), signatures).await;
+}
+
+/// A Raydium pool for `mint` whose LP is all burned but `lp_left` of 1,000,000
+async fn set_raydium_pool(rpc: &MockRpc, mint: &Pubkey, lp_left: u64) {
+    let lp_mint = Pubkey::new_unique();
+    let mut pool = vec![0u8; RAYDIUM_POOL_LEN];
+    pool[RAYDIUM_BASE_MINT..RAYDIUM_BASE_MINT + 32].copy_from_slice(mint.as_ref());
+    pool[RAYDIUM_LP_MINT..RAYDIUM_LP_MINT + 32].copy_from_slice(lp_mint.as_ref());
+    pool[RAYDIUM_LP_RESERVE..RAYDIUM_LP_RESERVE + 8].copy_from_slice(&1_000_000u64.to_le_bytes());
+
+    let keyed = json!([{
+        "pubkey": Pubkey::new_unique().to_string(),
+        "account": {
+            "data": [base64::encode(&pool), "base64"],
+            "executable": false,
+            "lamports": 6_124_800u64,
+            "owner": RAYDIUM_AMM_V4_PROGRAM,
+            "rentEpoch": 0,
+            "space": RAYDIUM_POOL_LEN,
+        },
+    }]);
+    rpc.set_response("getProgramAccounts", Some(RAYDIUM_AMM_V4_PROGRAM), keyed).await;
+    rpc.set_response("getTokenSupply", Some(&lp_mint.to_string()), with_context(token_amount(lp_left))).await;
+    rpc.set_response("getTokenLargestAccounts", Some(&lp_mint.to_string()), with_context(json!([]))).await;
+}
+
+/// A month-old token with its LP burned and a freeze authority: 80/100 on-chain
+async fn established_token(rpc: &MockRpc) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    rpc.set_account(&mint.to_string(), TOKEN_PROGRAM, &mint_data(None, Some(Pubkey::new_unique()))).await;
+    let raydium = RAYDIUM_AUTHORITY.parse().unwrap();
+    set_holders(rpc, &mint, &[(raydium, SUPPLY * 40 / 100), (Pubkey::new_unique(), SUPPLY * 8 / 100), (Pubkey::new_unique(), SUPPLY * 5 / 100)]).await;
+    set_raydium_pool(rpc, &mint, 1_000).await;
+    rpc.set_response("getProgramAccounts", None, json!([])).await;
+    set_created(rpc, &mint, Duration::days(30)).await;
+    mint
+}
+
+fn onchain(rpc: &MockRpc) -> Arc<dyn SecurityProvider> {
+    Arc::new(OnChainProvider::new(Arc::new(RpcClient::new(rpc.url()))))
+}
+
+async fn goplus_response(State(body): State<Option<Value>>) -> impl IntoResponse {
+    match body {
+        Some(body) => (StatusCode::OK, Json(body)).into_response(),
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+/// A GoPlus endpoint answering with `body`, or 503 when there is none
+async fn goplus(body: Option<Value>) -> Arc<dyn SecurityProvider> {
+    let app = Router::new().route("/token_security/solana", get(goplus_response)).with_state(body);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    Arc::new(GoPlusProvider::new(None).with_base_url(url))
+}
+
+#[tokio::test]
+async fn onchain_heuristics_score_a_fresh_concentrated_token() {
+    let rpc = MockRpc::start().await.unwrap();
+    let mint = Pubkey::new_unique();
+    let freezer = Pubkey::new_unique();
+    rpc.set_account(&mint.to_string(), TOKEN_PROGRAM, &mint_data(None, Some(freezer))).await;
+    set_holders(&rpc, &mint, &[(Pubkey::new_unique(), SUPPLY * 35 / 100), (Pubkey::new_unique(), SUPPLY * 25 / 100)]).await;
+    rpc.set_response("getProgramAccounts", None, json!([])).await;
+    set_created(&rpc, &mint, Duration::hours(2)).await;
+
+    let analysis = onchain(&rpc).check(&mint.to_string()).await.unwrap();
+
+    // Freeze authority 20, concentration 15, no pool 15, two hours old 15
+    assert_eq!(analysis.risk_score, 35);
+    assert_eq!(analysis.risk_level, RiskLevel::High);
+    assert_eq!(analysis.freeze_authority, Some(freezer.to_string()));
+    assert!(analysis.mint_authority.is_none());
+    assert!(analysis.passed_checks.contains(&"Mint authority revoked".to_string()));
+    assert!(analysis.failed_checks.contains(&"Freeze authority enabled".to_string()));
+    assert!(analysis.failed_checks.contains(&"Created 2 hours ago".to_string()));
+    assert!(analysis.warnings.iter().any(|w| w.message == "Top 10 holders own 60.0% of supply"));
+    assert!(analysis.warnings.iter().any(|w| w.message == "No Raydium or Orca pool found"));
+    assert_eq!(analysis.top_holders.len(), 2);
+    assert!((analysis.top_holders[0].percentage - 35.0).abs() < 1e-9);
+    assert!((analysis.token_age_hours - 2.0).abs() < 0.1);
+    assert!((analysis.total_supply - 1_000_000.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn onchain_heuristics_see_burned_lp_and_skip_checks_the_node_refuses() {
+    let rpc = MockRpc::start().await.unwrap();
+    let mint = established_token(&rpc).await;
+
+    let analysis = onchain(&rpc).check(&mint.to_string()).await.unwrap();
+    assert_eq!(analysis.risk_score, 80);
+    assert!(analysis.liquidity_locked);
+    assert!(analysis.passed_checks.contains(&"100% of Raydium LP burned or locked".to_string()));
+    // The pool's vault isn't a holder that can dump
+    assert!(analysis.passed_checks.contains(&"Top 10 holders own 13.0% of supply".to_string()));
+    assert!(analysis.top_holders[0].is_exchange);
+
+    // A node without getProgramAccounts or history costs nothing, but says so
+    let bare = MockRpc::start().await.unwrap();
+    bare.set_account(&mint.to_string(), TOKEN_PROGRAM, &mint_data(None, None)).await;
+    set_holders(&bare, &mint, &[(Pubkey::new_unique(), SUPPLY / 10)]).await;
+    let analysis = onchain(&bare).check(&mint.to_string()).await.unwrap();
+    assert_eq!(analysis.risk_score, 100);
+    let skipped: Vec<&str> = analysis.warnings.iter().map(|w| w.message.as_str()).collect();
+    assert_eq!(skipped, vec!["Liquidity pool check unavailable", "Token age unavailable"]);
+
+    assert!(onchain(&bare).check(&Pubkey::new_unique().to_string()).await.is_err());
+}
+
+#[tokio::test]
+async fn checker_scores_from_the_providers_that_answer() {
+    let rpc = MockRpc::start().await.unwrap();
+    let mint = established_token(&rpc).await;
+
+    let checker = LarpChecker::with_providers(vec![(goplus(None).await, 0.4), (onchain(&rpc), 0.4)]);
+    let analysis = checker.analyze_token(&mint.to_string()).await.unwrap();
+
+    // GoPlus is down, so the on-chain score stands alone
+    assert_eq!(analysis.risk_score, 80);
+    assert_eq!(analysis.risk_level, RiskLevel::VeryLow);
+    assert_eq!(analysis.data_sources, vec!["On-chain".to_string()]);
+    let goplus_status = &analysis.provider_status[0];
+    assert_eq!((goplus_status.provider.as_str(), goplus_status.score), ("GoPlus Security", None));
+    assert!(goplus_status.error.as_deref().unwrap().contains("503"));
+    assert_eq!(analysis.provider_status[1].score, Some(80));
+    // Age and concentration come from the on-chain provider without being counted twice
+    assert!(!analysis.warnings.iter().any(|w| w.category == WarningCategory::Age));
+    assert!(analysis.token_age_hours > 24.0 * 29.0);
+
+    let formatted = checker.format_analysis(&analysis);
+    assert!(formatted.contains("GoPlus Security: ❌ unavailable"));
+    assert!(formatted.contains("On-chain: 80/100 (weight 40%)"));
+    assert!(formatted.contains("Data from: On-chain"));
+}
+
+#[tokio::test]
+async fn checker_weights_scores_and_fails_only_when_every_provider_does() {
+    let rpc = MockRpc::start().await.unwrap();
+    let mint = established_token(&rpc).await;
+    let address = mint.to_string();
+
+    // GoPlus: sells are restricted (-30), otherwise clean
+    let body = json!({
+        "code": 0,
+        "message": "OK",
+        "result": { address.clone(): {
+            "honeypot": "0",
+            "cannot_buy": "0",
+            "cannot_sell_all": "1",
+            "liquidity": "50000",
+            "token_symbol": "TKN",
+            "token_name": "Token",
+        }},
+    });
+    let checker = LarpChecker::with_providers(vec![(goplus(Some(body)).await, 0.6), (onchain(&rpc), 0.4)]);
+    let analysis = checker.analyze_token(&address).await.unwrap();
+
+    // 70 * 0.6 + 80 * 0.4
+    assert_eq!(analysis.risk_score, 74);
+    assert_eq!(analysis.data_sources, vec!["GoPlus Security".to_string(), "On-chain".to_string()]);
+    assert_eq!(analysis.token_symbol, "TKN");
+    assert!(!analysis.can_sell);
+    // Filled in from the on-chain provider
+    assert!(analysis.liquidity_locked);
+    assert!(analysis.freeze_authority.is_some());
+    assert_eq!(analysis.top_holders.len(), 3);
+
+    let unknown = Pubkey::new_unique().to_string();
+    let checker = LarpChecker::with_providers(vec![(goplus(None).await, 0.4), (onchain(&rpc), 0.4)]);
+    assert!(checker.analyze_token(&unknown).await.is_err());
+}
@@ -0,0 +1,258 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::bot::handlers::settings::SettingsPage;
+use crate::bot::preferences::{PreferenceStore, SettingChange, UserSettings, SETTINGS_VERSION};
+use crate::testkit::TestHarness;
+use crate::trading::{
+    LeaderboardManager, LeaderboardMetric, LeaderboardPeriod, LeaderboardTradeSource, Trade, TradeProvenance,
+    TradeStatus, TradeType, TraderTrade,
+};
+
+const SHOWN: i64 = 1;
+const HIDDEN: i64 = 2;
+const NAMED: i64 = 3;
+const COPY_ONLY: i64 = 4;
+
+#[derive(Default)]
+struct MemoryTrades {
+    trades: Mutex<Vec<TraderTrade>>,
+}
+
+#[async_trait::async_trait]
+impl LeaderboardTradeSource for MemoryTrades {
+    async fn trades_since(&self, since: Option<DateTime<Utc>>) -> anyhow::Result<Vec<TraderTrade>> {
+        Ok(self.trades.lock().await.iter()
+            .filter(|t| since.map_or(true, |since| t.trade.timestamp >= since))
+            .cloned()
+            .collect())
+    }
+
+    async fn record(&self, user_id: i64, trade: &Trade) -> anyhow::Result<()> {
+        self.trades.lock().await.push(TraderTrade { user_id, username: None, trade: trade.clone() });
+        Ok(())
+    }
+}
+
+fn winning_trade(profit_sol: f64) -> Trade {
+    Trade {
+        token_symbol: "BONK".to_string(),
+        token_address: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+        entry_price: 0.00002,
+        exit_price: Some(0.00003),
+        amount_sol: 1.0,
+        profit_sol,
+        profit_percent: profit_sol * 100.0,
+        timestamp: Utc::now() - Duration::hours(1),
+        trade_type: TradeType::Sell,
+        status: TradeStatus::Closed,
+        provenance: TradeProvenance::Bot,
+    }
+}
+
+/// Four traders with five trades each; each user's Telegram username is "real_<id>"
+async fn trades() -> Arc<MemoryTrades> {
+    let source = Arc::new(MemoryTrades::default());
+    for user_id in [SHOWN, HIDDEN, NAMED, COPY_ONLY] {
+        for _ in 0..5 {
+            source.trades.lock().await.push(TraderTrade {
+                user_id,
+                username: Some(format!("real_{}", user_id)),
+                trade: winning_trade(user_id as f64 * 0.1),
+            });
+        }
+    }
+    source
+}
+
+/// SHOWN and NAMED are on the board, NAMED as "Moonboy"; COPY_ONLY is only copyable
+async fn opt_ins() -> Arc<PreferenceStore> {
+    let preferences = Arc::new(PreferenceStore::default());
+    preferences.apply(SHOWN, SettingChange::ToggleLeaderboardVisible).await.unwrap();
+    preferences.apply(NAMED, SettingChange::ToggleLeaderboardVisible).await.unwrap();
+    preferences.update(NAMED, |s| s.set_display_name(Some("Moonboy"))).await.unwrap();
+    preferences.apply(COPY_ONLY, SettingChange::ToggleCopyable).await.unwrap();
+    preferences
+}
+
+async fn board(preferences: Option<Arc<PreferenceStore>>) -> LeaderboardManager {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let board = LeaderboardManager::new(harness.db.clone()).with_source(trades().await);
+    match preferences {
+        Some(preferences) => board.with_visibility(preferences),
+        None => board,
+    }
+}
+
+#[test]
+fn test_new_users_are_hidden() {
+    let settings = UserSettings::default();
+    assert!(!settings.leaderboard_visible);
+    assert!(!settings.copyable);
+    assert_eq!(settings.display_name_override, None);
+    assert_eq!(settings.version, SETTINGS_VERSION);
+}
+
+#[test]
+fn test_settings_stored_before_versioning_migrate_to_hidden() {
+    // As written by the previous build: no privacy fields, no version
+    let mut old: UserSettings = serde_json::from_value(serde_json::json!({
+        "slippage_bps": 100,
+        "max_position_sol": null,
+        "auto_compound": false,
+        "risk_profile": "moderate",
+        "notifications": { "trades": true, "alerts": true, "daily_summary": false },
+        "mev_protection": true,
+    })).unwrap();
+    assert_eq!(old.version, 0);
+
+    assert!(old.migrate());
+    assert_eq!(old.version, SETTINGS_VERSION);
+    assert!(!old.leaderboard_visible && !old.copyable);
+    assert!(old.mev_protection, "other settings survive the migration");
+
+    // Migrating again changes nothing, and current settings keep their opt-ins
+    assert!(!old.migrate());
+    let mut current = UserSettings { leaderboard_visible: true, ..UserSettings::default() };
+    assert!(!current.migrate());
+    assert!(current.leaderboard_visible);
+}
+
+#[test]
+fn test_display_name_validation() {
+    let mut settings = UserSettings::default();
+    settings.set_display_name(Some("  Moon boy_42 ")).unwrap();
+    assert_eq!(settings.display_name_override.as_deref(), Some("Moon boy_42"));
+
+    for bad in ["ab", "a name that is far too long", "<script>", "Trader#4821", "trader 4821"] {
+        assert!(settings.set_display_name(Some(bad)).is_err(), "{}", bad);
+    }
+    assert_eq!(settings.display_name_override.as_deref(), Some("Moon boy_42"));
+
+    settings.set_display_name(None).unwrap();
+    assert_eq!(settings.display_name_override, None);
+}
+
+#[test]
+fn test_privacy_callbacks_and_page() {
+    assert_eq!(SettingChange::parse("setting:board"), Some(SettingChange::ToggleLeaderboardVisible));
+    assert_eq!(SettingChange::parse("setting:copyable"), Some(SettingChange::ToggleCopyable));
+    assert_eq!(SettingChange::parse("setting:clearname"), Some(SettingChange::ClearDisplayName));
+    assert_eq!(SettingsPage::from_callback("settings_privacy"), Some(SettingsPage::Privacy));
+    assert_eq!(SettingsPage::for_change(SettingChange::ToggleCopyable), SettingsPage::Privacy);
+    assert!(SettingChange::ToggleLeaderboardVisible.affects_visibility());
+    assert!(!SettingChange::ToggleMevProtection.affects_visibility());
+
+    let mut settings = UserSettings { copyable: true, ..UserSettings::default() };
+    let text = SettingsPage::Privacy.text(&settings, "en");
+    assert!(text.contains("Listed on leaderboards: ❌ Off"), "{}", text);
+    assert!(text.contains("Available for copy trading: ✅ On"), "{}", text);
+
+    settings.apply(SettingChange::ToggleLeaderboardVisible).unwrap();
+    settings.set_display_name(Some("Moonboy")).unwrap();
+    assert!(SettingsPage::Privacy.text(&settings, "en").contains("Shown as: Moonboy"));
+    settings.apply(SettingChange::ClearDisplayName).unwrap();
+    assert_eq!(settings.display_name_override, None);
+}
+
+#[tokio::test]
+async fn test_hidden_users_are_left_off_the_board() {
+    let board = board(Some(opt_ins().await)).await;
+    let entries = board.get_leaderboard(LeaderboardPeriod::AllTime, LeaderboardMetric::Profit, 10).await.unwrap();
+
+    let ids: Vec<i64> = entries.iter().map(|e| e.user_id).collect();
+    assert_eq!(ids, vec![NAMED, SHOWN]);
+    assert_eq!(entries[0].rank, 1);
+    assert_eq!(entries[1].rank, 2);
+
+    // Hidden users still see their own stats, unranked
+    let hidden = board.get_trader_stats(HIDDEN).await.unwrap();
+    assert_eq!(hidden.total_trades, 5);
+    assert_eq!(hidden.rank_global, 0);
+    assert!(!hidden.leaderboard_visible);
+}
+
+#[tokio::test]
+async fn test_nobody_is_listed_without_opt_ins() {
+    let board = board(None).await;
+    let entries = board.get_leaderboard(LeaderboardPeriod::AllTime, LeaderboardMetric::Volume, 10).await.unwrap();
+    assert!(entries.is_empty());
+    assert!(board.get_copyable_traders(10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_public_names_replace_usernames() {
+    let board = board(Some(opt_ins().await)).await;
+    let entries = board.get_leaderboard(LeaderboardPeriod::AllTime, LeaderboardMetric::Profit, 10).await.unwrap();
+
+    assert_eq!(entries[0].username, "Moonboy");
+    assert_eq!(entries[1].username, LeaderboardManager::pseudonym(SHOWN));
+    assert!(entries.iter().all(|e| !e.username.starts_with("real_")));
+
+    let handle = LeaderboardManager::pseudonym(SHOWN);
+    let digits = handle.strip_prefix("Trader#").unwrap();
+    assert!(digits.len() == 4 && digits.chars().all(|c| c.is_ascii_digit()), "{}", handle);
+    assert_eq!(handle, LeaderboardManager::pseudonym(SHOWN), "handles are stable");
+
+    // A forgotten user loses their display name too
+    board.anonymize_user(NAMED).await;
+    let entries = board.get_leaderboard(LeaderboardPeriod::AllTime, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert_eq!(entries[0].username, LeaderboardManager::pseudonym(NAMED));
+}
+
+#[tokio::test]
+async fn test_copying_is_a_separate_opt_in() {
+    let board = board(Some(opt_ins().await)).await;
+    let copyable = board.get_copyable_traders(10).await.unwrap();
+
+    let ids: Vec<i64> = copyable.iter().map(|e| e.user_id).collect();
+    assert_eq!(ids, vec![COPY_ONLY]);
+    assert!(copyable[0].is_copyable);
+
+    let listed = board.get_leaderboard(LeaderboardPeriod::Weekly, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert!(listed.iter().all(|e| e.user_id != COPY_ONLY && !e.is_copyable));
+}
+
+#[tokio::test]
+async fn test_opting_in_shows_after_invalidate() {
+    let preferences = opt_ins().await;
+    let board = board(Some(preferences.clone())).await;
+    let period = LeaderboardPeriod::AllTime;
+    assert_eq!(board.get_leaderboard(period, LeaderboardMetric::Profit, 10).await.unwrap().len(), 2);
+
+    preferences.apply(HIDDEN, SettingChange::ToggleLeaderboardVisible).await.unwrap();
+    board.invalidate().await;
+    let entries = board.get_leaderboard(period, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert!(entries.iter().any(|e| e.user_id == HIDDEN));
+
+    preferences.apply(SHOWN, SettingChange::ToggleLeaderboardVisible).await.unwrap();
+    board.invalidate().await;
+    let entries = board.get_leaderboard(period, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert!(entries.iter().all(|e| e.user_id != SHOWN));
+}
+
+#[tokio::test]
+async fn test_only_opted_in_masters_are_available() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let services = &harness.services;
+    for user_id in [SHOWN, HIDDEN, COPY_ONLY] {
+        harness.register_user(user_id, 5.0).await.unwrap();
+        for _ in 0..5 {
+            services.leaderboard.record_trade(user_id, winning_trade(0.2)).await.unwrap();
+        }
+    }
+    services.preferences.apply(SHOWN, SettingChange::ToggleLeaderboardVisible).await.unwrap();
+    services.preferences.apply(COPY_ONLY, SettingChange::ToggleCopyable).await.unwrap();
+    services.leaderboard.invalidate().await;
+
+    let masters = harness.copy_trading.get_available_masters(10).await.unwrap();
+    let ids: Vec<i64> = masters.iter().map(|m| m.user_id).collect();
+    assert_eq!(ids, vec![COPY_ONLY]);
+    assert_eq!(masters[0].username, LeaderboardManager::pseudonym(COPY_ONLY));
+    assert!(!masters[0].wallet_address.is_empty());
+
+    // Hidden masters can't be looked up by name either
+    let hidden_name = LeaderboardManager::pseudonym(HIDDEN);
+    assert!(harness.copy_trading.start_following(99, &hidden_name, 10.0, 1.0).await.is_err());
+}
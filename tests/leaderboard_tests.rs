@@ -0,0 +1,264 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::testkit::TestHarness;
+use crate::trading::{
+    aggregate_trader_stats, Badge, LeaderboardConfig, LeaderboardManager, LeaderboardMetric, LeaderboardPeriod,
+    LeaderboardTradeSource, Trade, TradeProvenance, TradeStatus, TradeType, TraderTrade, TraderVisibility,
+    VisibilityPreferences,
+};
+
+const ALICE: i64 = 1;
+const BOB: i64 = 2;
+const CAROL: i64 = 3;
+const DAVE: i64 = 4;
+
+/// Trades kept in memory; counts how often the board reads them
+#[derive(Default)]
+struct MemoryTrades {
+    trades: Mutex<Vec<TraderTrade>>,
+    reads: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl LeaderboardTradeSource for MemoryTrades {
+    async fn trades_since(&self, since: Option<DateTime<Utc>>) -> anyhow::Result<Vec<TraderTrade>> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        Ok(self.trades.lock().await.iter()
+            .filter(|t| since.map_or(true, |since| t.trade.timestamp >= since))
+            .cloned()
+            .collect())
+    }
+
+    async fn record(&self, user_id: i64, trade: &Trade) -> anyhow::Result<()> {
+        self.trades.lock().await.push(TraderTrade { user_id, username: None, trade: trade.clone() });
+        Ok(())
+    }
+}
+
+/// Every trader opted in, none with a display name
+struct EveryoneOptedIn;
+
+#[async_trait::async_trait]
+impl VisibilityPreferences for EveryoneOptedIn {
+    async fn visibility(&self, _user_id: i64) -> TraderVisibility {
+        TraderVisibility { leaderboard_visible: true, copyable: true, display_name: None }
+    }
+}
+
+fn closed(amount_sol: f64, profit_sol: f64, hours_ago: i64) -> Trade {
+    Trade {
+        token_symbol: "BONK".to_string(),
+        token_address: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+        entry_price: 0.00002,
+        exit_price: Some(0.00002 * (1.0 + profit_sol / amount_sol)),
+        amount_sol,
+        profit_sol,
+        profit_percent: profit_sol / amount_sol * 100.0,
+        timestamp: Utc::now() - Duration::hours(hours_ago),
+        trade_type: TradeType::Sell,
+        status: TradeStatus::Closed,
+        provenance: TradeProvenance::Bot,
+    }
+}
+
+fn by(user_id: i64, name: &str, trade: Trade) -> TraderTrade {
+    TraderTrade { user_id, username: Some(name.to_string()), trade }
+}
+
+/// Four traders, shuffled with a fixed seed:
+///
+/// - alice: 12 × 10 SOL, all +2 SOL, in the last day — 20%, 120 SOL, 100% WR
+/// - bob: 6 × 30 SOL, three +9 and three -3, three days ago — 10%, 180 SOL, 50% WR
+/// - carol: 8 × 1 SOL, six +1.5 and two -0.5, in the last day — 100%, 8 SOL, 75% WR
+/// - dave: one lucky 5 SOL trade that made 50 SOL
+fn seeded_trades() -> Vec<TraderTrade> {
+    let mut trades = Vec::new();
+    for i in 0..12 {
+        trades.push(by(ALICE, "alice", closed(10.0, 2.0, 12 - i)));
+    }
+    for i in 0..6 {
+        let profit = if i % 2 == 0 { 9.0 } else { -3.0 };
+        trades.push(by(BOB, "bob", closed(30.0, profit, 72 + i)));
+    }
+    for i in 0..8 {
+        let profit = if i < 6 { 1.5 } else { -0.5 };
+        trades.push(by(CAROL, "carol", closed(1.0, profit, 20 - i)));
+    }
+    trades.push(by(DAVE, "dave", closed(5.0, 50.0, 1)));
+
+    trades.shuffle(&mut StdRng::seed_from_u64(791));
+    trades
+}
+
+async fn board_with(source: Arc<MemoryTrades>, config: LeaderboardConfig) -> LeaderboardManager {
+    let harness = TestHarness::builder().build().await.unwrap();
+    LeaderboardManager::new(harness.db.clone())
+        .with_source(source)
+        .with_config(config)
+        .with_visibility(Arc::new(EveryoneOptedIn))
+}
+
+async fn seeded_board() -> (LeaderboardManager, Arc<MemoryTrades>) {
+    let source = Arc::new(MemoryTrades::default());
+    *source.trades.lock().await = seeded_trades();
+    (board_with(source.clone(), LeaderboardConfig::default()).await, source)
+}
+
+fn ranking(entries: &[crate::trading::LeaderboardEntry]) -> Vec<(u32, i64)> {
+    entries.iter().map(|e| (e.rank, e.user_id)).collect()
+}
+
+#[tokio::test]
+async fn test_rankings_for_each_metric() {
+    let (board, _) = seeded_board().await;
+    let period = LeaderboardPeriod::AllTime;
+
+    let profit = board.get_leaderboard(period, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert_eq!(ranking(&profit), vec![(1, CAROL), (2, ALICE), (3, BOB)]);
+
+    let volume = board.get_leaderboard(period, LeaderboardMetric::Volume, 10).await.unwrap();
+    assert_eq!(ranking(&volume), vec![(1, BOB), (2, ALICE), (3, CAROL)]);
+
+    let win_rate = board.get_leaderboard(period, LeaderboardMetric::WinRate, 10).await.unwrap();
+    assert_eq!(ranking(&win_rate), vec![(1, ALICE), (2, CAROL), (3, BOB)]);
+
+    let trade_count = board.get_leaderboard(period, LeaderboardMetric::TradeCount, 10).await.unwrap();
+    assert_eq!(ranking(&trade_count), vec![(1, ALICE), (2, CAROL), (3, BOB)]);
+
+    let top_two = board.get_leaderboard(period, LeaderboardMetric::Profit, 2).await.unwrap();
+    assert_eq!(ranking(&top_two), vec![(1, CAROL), (2, ALICE)]);
+}
+
+#[tokio::test]
+async fn test_entry_figures_come_from_the_trades() {
+    let (board, _) = seeded_board().await;
+    let entries = board.get_leaderboard(LeaderboardPeriod::AllTime, LeaderboardMetric::Volume, 10).await.unwrap();
+
+    let bob = &entries[0];
+    assert_eq!(bob.username, LeaderboardManager::pseudonym(BOB));
+    assert_eq!(bob.total_trades, 6);
+    assert!((bob.volume_sol - 180.0).abs() < 1e-9);
+    assert!((bob.profit_sol - 18.0).abs() < 1e-9);
+    assert!((bob.profit_percent - 10.0).abs() < 1e-9);
+    assert!((bob.win_rate - 50.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_traders_below_minimum_trades_are_not_ranked() {
+    let (board, _) = seeded_board().await;
+    let entries = board.get_leaderboard(LeaderboardPeriod::AllTime, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert!(entries.iter().all(|e| e.user_id != DAVE), "one lucky trade shouldn't rank");
+
+    // With the minimum lowered, dave's 1000% tops the board
+    let source = Arc::new(MemoryTrades::default());
+    *source.trades.lock().await = seeded_trades();
+    let lenient = board_with(source, LeaderboardConfig { min_trades: 1, ..LeaderboardConfig::default() }).await;
+    let entries = lenient.get_leaderboard(LeaderboardPeriod::AllTime, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert_eq!(entries[0].user_id, DAVE);
+}
+
+#[tokio::test]
+async fn test_periods_only_count_their_window() {
+    let (board, _) = seeded_board().await;
+
+    // Bob traded three days ago
+    let daily = board.get_leaderboard(LeaderboardPeriod::Daily, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert_eq!(ranking(&daily), vec![(1, CAROL), (2, ALICE)]);
+
+    let weekly = board.get_leaderboard(LeaderboardPeriod::Weekly, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert_eq!(ranking(&weekly), vec![(1, CAROL), (2, ALICE), (3, BOB)]);
+}
+
+#[tokio::test]
+async fn test_badges_are_computed_during_aggregation() {
+    let stats = aggregate_trader_stats(&seeded_trades(), Utc::now());
+    let badges_of = |user_id| stats.iter().find(|s| s.user_id == user_id).unwrap().badges.clone();
+
+    assert_eq!(badges_of(ALICE), vec![Badge::WinStreak(12), Badge::VolumeKing]);
+    assert_eq!(badges_of(BOB), vec![Badge::VolumeKing]);
+    assert_eq!(badges_of(CAROL), vec![Badge::ProfitMaster]);
+    assert_eq!(badges_of(DAVE), vec![Badge::ProfitMaster]);
+
+    let alice = stats.iter().find(|s| s.user_id == ALICE).unwrap();
+    assert_eq!((alice.streak_best, alice.streak_current), (12, 12));
+    assert!((alice.best_trade.profit_sol - 2.0).abs() < 1e-9);
+
+    // Carol's two losses came last
+    let carol = stats.iter().find(|s| s.user_id == CAROL).unwrap();
+    assert_eq!((carol.streak_best, carol.streak_current), (6, -2));
+    assert!((carol.worst_trade.profit_sol + 0.5).abs() < 1e-9);
+    assert!(carol.max_drawdown_percent < 0.0);
+}
+
+#[tokio::test]
+async fn test_open_cancelled_and_imported_trades_are_ignored() {
+    let mut trades = seeded_trades();
+    for status in [TradeStatus::Open, TradeStatus::Cancelled] {
+        let mut trade = closed(100.0, 500.0, 1);
+        trade.status = status;
+        trades.push(by(BOB, "bob", trade));
+    }
+    let mut imported = closed(100.0, 500.0, 1);
+    imported.provenance = TradeProvenance::Imported;
+    trades.push(by(BOB, "bob", imported));
+
+    let stats = aggregate_trader_stats(&trades, Utc::now());
+    let bob = stats.iter().find(|s| s.user_id == BOB).unwrap();
+    assert_eq!(bob.total_trades, 6);
+    assert!((bob.total_profit_sol - 18.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_results_are_cached_for_the_ttl() {
+    let (board, source) = seeded_board().await;
+
+    // Refresh taps within the minute reuse one aggregation
+    for _ in 0..5 {
+        board.get_leaderboard(LeaderboardPeriod::Weekly, LeaderboardMetric::Profit, 10).await.unwrap();
+        board.get_leaderboard(LeaderboardPeriod::Weekly, LeaderboardMetric::Volume, 10).await.unwrap();
+    }
+    assert_eq!(source.reads.load(Ordering::SeqCst), 1);
+
+    // Concurrent cold reads share one too
+    let (a, b) = tokio::join!(
+        board.get_leaderboard(LeaderboardPeriod::Monthly, LeaderboardMetric::Profit, 10),
+        board.get_leaderboard(LeaderboardPeriod::Monthly, LeaderboardMetric::WinRate, 10),
+    );
+    a.unwrap();
+    b.unwrap();
+    assert_eq!(source.reads.load(Ordering::SeqCst), 2);
+
+    // A zero TTL reads every time
+    let uncached_source = Arc::new(MemoryTrades::default());
+    let uncached = board_with(
+        uncached_source.clone(),
+        LeaderboardConfig { cache_ttl: std::time::Duration::ZERO, ..LeaderboardConfig::default() },
+    ).await;
+    uncached.get_leaderboard(LeaderboardPeriod::Weekly, LeaderboardMetric::Profit, 10).await.unwrap();
+    uncached.get_leaderboard(LeaderboardPeriod::Weekly, LeaderboardMetric::Profit, 10).await.unwrap();
+    assert_eq!(uncached_source.reads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_recorded_trades_show_up_immediately() {
+    let (board, _) = seeded_board().await;
+    let before = board.get_trader_stats(DAVE).await.unwrap();
+    assert_eq!(before.total_trades, 1);
+    assert_eq!(before.rank_global, 0);
+
+    for _ in 0..4 {
+        board.record_trade(DAVE, closed(5.0, 1.0, 0)).await.unwrap();
+    }
+
+    let after = board.get_trader_stats(DAVE).await.unwrap();
+    assert_eq!(after.total_trades, 5);
+    assert_eq!(after.rank_global, 1);
+    assert_eq!(after.rank_daily, 1);
+}
@@ -0,0 +1,158 @@
+use crate::analytics::{build_ledger, LedgerSide, LedgerSource};
+use crate::api::{LendingPosition, LendingVault, LiquidationInfo, PositionRecommendation};
+use crate::bot::handlers::LendingHandler;
+use crate::trading::{validate_amount, LendAmount, LendDirection, LendingHolding, LendingOperation, PendingLend, MIN_LEND_UNITS};
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const WSOL: &str = "So11111111111111111111111111111111111111112";
+
+fn vault(symbol: &str, mint: &str, decimals: u8) -> LendingVault {
+    serde_json::from_value(json!({
+        "vaultId": format!("jl{}", symbol),
+        "tokenMint": mint,
+        "tokenSymbol": symbol,
+        "tokenDecimals": decimals,
+        "totalSupply": 50_000_000_000_000u64,
+        "totalBorrowed": 40_000_000_000_000u64,
+        "utilizationRate": 0.8,
+        "supplyApr": 0.0785,
+        "borrowApr": 0.11,
+        "maxLtv": 0.8,
+        "liquidationPenalty": 0.01,
+        "isActive": true,
+        "riskTier": "conservative",
+    }))
+    .unwrap()
+}
+
+/// 250 USDC supplied with 12.5 USDC earned, borrowing against it at `health_factor`
+fn holding(health_factor: f64, recommendation: PositionRecommendation, liquidation: Option<LiquidationInfo>) -> LendingHolding {
+    let position: LendingPosition = serde_json::from_value(json!({
+        "positionId": "pos-1",
+        "userPublicKey": "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+        "vaultId": "jlUSDC",
+        "tokenMint": USDC,
+        "collateralAmount": 250_000_000u64,
+        "borrowedAmount": 150_000_000u64,
+        "currentLtv": 0.6,
+        "healthFactor": health_factor,
+        "liquidationPrice": 0.92,
+        "interestAccrued": 12_500_000u64,
+        "createdAt": "2026-01-01T00:00:00Z",
+        "lastUpdated": "2026-02-01T00:00:00Z",
+        "status": "active",
+    }))
+    .unwrap();
+    LendingHolding { position, symbol: "USDC".to_string(), decimals: 6, recommendation, liquidation }
+}
+
+#[test]
+fn test_amounts_are_checked_against_balance_and_dust() {
+    // 5 USDC available
+    let available = 5_000_000;
+    assert_eq!(validate_amount(LendAmount::Tokens(2.5), available, 6).unwrap(), 2_500_000);
+    assert_eq!(validate_amount(LendAmount::All, available, 6).unwrap(), available);
+
+    let over = validate_amount(LendAmount::Tokens(5.01), available, 6).unwrap_err().to_string();
+    assert!(over.contains("more than the 5 available"), "{}", over);
+
+    let dust = validate_amount(LendAmount::Tokens(0.0005), available, 6).unwrap_err().to_string();
+    assert!(dust.contains("too small"), "{}", dust);
+    assert!(validate_amount(LendAmount::All, MIN_LEND_UNITS - 1, 6).is_err());
+    assert!(validate_amount(LendAmount::Tokens(0.001), available, 6).is_ok());
+
+    for bad in [0.0, -1.0, f64::NAN] {
+        assert!(validate_amount(LendAmount::Tokens(bad), available, 6).is_err(), "{}", bad);
+    }
+    assert!(validate_amount(LendAmount::All, 0, 6).unwrap_err().to_string().contains("Nothing available"));
+}
+
+#[test]
+fn test_amount_arguments_parse() {
+    assert_eq!(LendAmount::parse("12.5"), Some(LendAmount::Tokens(12.5)));
+    assert_eq!(LendAmount::parse("ALL"), Some(LendAmount::All));
+    assert_eq!(LendAmount::parse("max"), Some(LendAmount::All));
+    assert_eq!(LendAmount::parse("lots"), None);
+}
+
+#[test]
+fn test_healthy_positions_show_interest_without_warnings() {
+    let healthy = holding(1.8, PositionRecommendation::Healthy, None);
+    assert!((healthy.supplied() - 250.0).abs() < 1e-9);
+    assert!((healthy.interest() - 12.5).abs() < 1e-9);
+
+    let text = LendingHandler::holding_text(&healthy, "en");
+    assert!(text.starts_with("USDC: 250.00 supplied, +12.50 interest"), "{}", text);
+    assert!(text.contains("Borrowed 150.00 · LTV 60.0%"), "{}", text);
+    assert!(!text.contains("Health") && !text.contains("Liquidation"), "{}", text);
+}
+
+#[test]
+fn test_low_health_positions_warn_with_the_liquidation_price() {
+    let watch = LendingHandler::holding_text(&holding(1.35, PositionRecommendation::MonitorClosely, None), "en");
+    assert!(watch.contains("👀 Health 1.35"), "{}", watch);
+
+    let at_risk = LendingHandler::holding_text(&holding(1.1, PositionRecommendation::AddCollateral, None), "en");
+    assert!(at_risk.contains("⚠️ Health 1.10: add collateral or repay"), "{}", at_risk);
+    assert!(at_risk.contains("Liquidation at $0.9200"), "{}", at_risk);
+
+    // The liquidation feed's price wins over the position's own estimate
+    let liquidation = LiquidationInfo {
+        position_id: "pos-1".to_string(),
+        liquidation_price: 0.97,
+        health_factor: 0.98,
+        liquidation_reward: 0.01,
+        time_to_liquidation: None,
+    };
+    let liquidatable = LendingHandler::holding_text(&holding(0.98, PositionRecommendation::Liquidatable, Some(liquidation)), "en");
+    assert!(liquidatable.contains("🚨 Health 0.98"), "{}", liquidatable);
+    assert!(liquidatable.contains("Liquidation at $0.9700"), "{}", liquidatable);
+}
+
+#[test]
+fn test_vault_list_and_confirmation_show_apy_and_risk() {
+    let usdc = vault("USDC", USDC, 6);
+    assert!((usdc.supply_apy() - 0.08166).abs() < 1e-4, "{}", usdc.supply_apy());
+
+    let list = LendingHandler::vaults_text(&[usdc.clone()], "en");
+    assert!(list.contains("USDC · 8.17% APY · 🟢 Conservative"), "{}", list);
+
+    let pending = PendingLend {
+        direction: LendDirection::Deposit,
+        user_wallet: "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string(),
+        vault: usdc,
+        amount: 2_500_000,
+        expires_at: Utc::now(),
+    };
+    let confirm = LendingHandler::confirm_text(&pending, "en");
+    assert!(confirm.contains("Amount: 2.50 USDC") && confirm.contains("APY: 8.17%"), "{}", confirm);
+}
+
+#[test]
+fn test_lending_operations_join_the_trade_history() {
+    let operation = |direction, mint: &str, symbol: &str, amount, day| LendingOperation {
+        timestamp: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+        direction,
+        vault_id: format!("jl{}", symbol),
+        mint: mint.to_string(),
+        symbol: symbol.to_string(),
+        amount,
+        tx_signature: format!("sig{}", day),
+    };
+    let lending = vec![
+        operation(LendDirection::Withdraw, WSOL, "SOL", 1.5, 9),
+        operation(LendDirection::Deposit, USDC, "USDC", 100.0, 2),
+    ];
+
+    let ledger = build_ledger(&[], &[], &[], &lending, None);
+    assert_eq!(ledger.len(), 2);
+    assert!(ledger.iter().all(|entry| entry.source == LedgerSource::Lending));
+
+    let deposit = &ledger[0];
+    assert_eq!((deposit.side, deposit.sol_amount, deposit.token_amount), (LedgerSide::Deposit, 0.0, Some(100.0)));
+    let withdrawal = &ledger[1];
+    assert_eq!((withdrawal.side, withdrawal.sol_amount), (LedgerSide::Withdraw, 1.5));
+    assert_eq!(withdrawal.tx_signature.as_deref(), Some("sig9"));
+}
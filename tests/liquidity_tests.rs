@@ -0,0 +1,199 @@
+use chrono::Utc;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::bot::callback_action::CallbackAction;
+use crate::bot::handlers::QuickBuyHandler;
+use crate::errors::{BotError, Result};
+use crate::testkit::{JupiterScenario, TestHarness};
+use crate::trading::{walk_book, ImpactQuoter, ImpactSource, LiquidityEstimator, OrderSide, TokenResolver};
+use crate::websocket::{OrderBook, OrderBookLevel, PriceStreamManager, WebSocketClient, WebSocketConfig};
+
+/// Levels as (SOL per token, tokens)
+fn book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> OrderBook {
+    let levels = |levels: &[(f64, f64)]| -> Vec<OrderBookLevel> {
+        levels.iter()
+            .map(|&(price, size)| OrderBookLevel {
+                price: Decimal::from_f64(price).unwrap(),
+                size: Decimal::from_f64(size).unwrap(),
+                orders: 1,
+            })
+            .collect()
+    };
+    OrderBook {
+        symbol: "BONK".to_string(),
+        timestamp: Utc::now(),
+        bids: levels(bids),
+        asks: levels(asks),
+        sequence: 1,
+    }
+}
+
+/// 1000 SOL of depth on each side within 0.3% of 1.0
+fn thick_book() -> OrderBook {
+    book(
+        &[(0.999, 300.0), (0.998, 300.0), (0.997, 400.0)],
+        &[(1.001, 300.0), (1.002, 300.0), (1.003, 400.0)],
+    )
+}
+
+/// 3 SOL of asks spread over 10%
+fn thin_book() -> OrderBook {
+    book(&[(0.99, 1.0)], &[(1.0, 1.0), (1.05, 1.0), (1.1, 1.0)])
+}
+
+/// Answers with a fixed impact and remembers what it was asked
+#[derive(Default)]
+struct FixedImpact {
+    impact: f64,
+    asked: Mutex<Vec<(String, OrderSide, f64)>>,
+}
+
+#[async_trait::async_trait]
+impl ImpactQuoter for FixedImpact {
+    async fn price_impact(&self, mint: &str, side: OrderSide, amount_sol: f64) -> Result<f64> {
+        self.asked.lock().await.push((mint.to_string(), side, amount_sol));
+        if self.impact < 0.0 {
+            return Err(BotError::external_api("no route".to_string()).into());
+        }
+        Ok(self.impact)
+    }
+}
+
+fn estimator(quoter: Option<Arc<FixedImpact>>) -> LiquidityEstimator {
+    let stream = Arc::new(PriceStreamManager::new(Arc::new(WebSocketClient::new(WebSocketConfig::default(), None))));
+    let estimator = LiquidityEstimator::new(stream);
+    match quoter {
+        Some(quoter) => estimator.with_quoter(quoter),
+        None => estimator,
+    }
+}
+
+#[test]
+fn test_thick_book_absorbs_the_trade() {
+    let estimate = walk_book(&thick_book(), OrderSide::Buy, 500.0).unwrap();
+
+    assert_eq!(estimate.source, ImpactSource::OrderBook);
+    assert_eq!(estimate.reference_price, 1.001);
+    assert_eq!(estimate.levels_consumed, 2);
+    assert_eq!(estimate.unfilled_sol, 0.0);
+    assert!(estimate.average_price > 1.001 && estimate.average_price < 1.002, "{}", estimate.average_price);
+    assert!(estimate.price_impact_bps <= 10, "{}", estimate.price_impact_bps);
+    assert!(!estimate.exceeds(100));
+}
+
+#[test]
+fn test_thin_book_moves_the_price() {
+    // 2 SOL takes the first ask and most of the second
+    let estimate = walk_book(&thin_book(), OrderSide::Buy, 2.0).unwrap();
+    assert_eq!(estimate.levels_consumed, 2);
+    assert_eq!(estimate.unfilled_sol, 0.0);
+    let tokens = 1.0 + 1.0 / 1.05;
+    assert!((estimate.average_price - 2.0 / tokens).abs() < 1e-9);
+    assert_eq!(estimate.price_impact_bps, 244);
+    assert!((estimate.impact_pct() - 2.44).abs() < 1e-9);
+    assert!(estimate.exceeds(100));
+    assert!(!estimate.exceeds(300));
+}
+
+#[test]
+fn test_trade_larger_than_the_book_is_left_unfilled() {
+    let estimate = walk_book(&thin_book(), OrderSide::Buy, 10.0).unwrap();
+
+    assert_eq!(estimate.levels_consumed, 3);
+    assert!((estimate.unfilled_sol - (10.0 - 1.0 - 1.05 - 1.1)).abs() < 1e-9, "{}", estimate.unfilled_sol);
+    // However generous the slippage, a trade the book can't fill needs confirming
+    assert!(estimate.exceeds(u16::MAX));
+}
+
+#[test]
+fn test_sells_walk_the_bids_from_the_top() {
+    // Deliberately out of order
+    let bids = book(&[(0.9, 10.0), (1.0, 1.0), (0.95, 10.0)], &[]);
+    let estimate = walk_book(&bids, OrderSide::Sell, 1.95).unwrap();
+
+    assert_eq!(estimate.reference_price, 1.0);
+    assert_eq!(estimate.levels_consumed, 2);
+    assert_eq!(estimate.unfilled_sol, 0.0);
+    assert_eq!(estimate.price_impact_bps, 250);
+}
+
+#[test]
+fn test_one_sided_book() {
+    let asks_only = book(&[], &[(1.0, 100.0)]);
+    let estimate = walk_book(&asks_only, OrderSide::Buy, 10.0).unwrap();
+    assert_eq!(estimate.price_impact_bps, 0);
+    assert!(walk_book(&asks_only, OrderSide::Sell, 10.0).is_none());
+
+    let bids_only = book(&[(1.0, 100.0)], &[]);
+    assert!(walk_book(&bids_only, OrderSide::Buy, 10.0).is_none());
+
+    // Levels without a price or size don't count as depth
+    let empty_levels = book(&[], &[(0.0, 100.0), (1.0, 0.0)]);
+    assert!(walk_book(&empty_levels, OrderSide::Buy, 1.0).is_none());
+}
+
+#[tokio::test]
+async fn test_quotes_impact_without_a_cached_book() {
+    let quoter = Arc::new(FixedImpact { impact: 0.035, ..FixedImpact::default() });
+    let estimator = estimator(Some(quoter.clone()));
+
+    let estimate = estimator.estimate_slippage("BONK", OrderSide::Buy, 2.0).await.unwrap();
+    assert_eq!(estimate.source, ImpactSource::JupiterQuote);
+    assert_eq!(estimate.price_impact_bps, 350);
+    assert_eq!(quoter.asked.lock().await.as_slice(), &[("BONK".to_string(), OrderSide::Buy, 2.0)]);
+
+    assert!(estimator.needs_confirmation("BONK", OrderSide::Buy, 2.0, 100).await.is_some());
+    assert!(estimator.needs_confirmation("BONK", OrderSide::Buy, 2.0, 500).await.is_none());
+}
+
+#[tokio::test]
+async fn test_trades_go_ahead_without_an_estimate() {
+    let estimator_without_quotes = estimator(None);
+    assert!(estimator_without_quotes.estimate_slippage("BONK", OrderSide::Buy, 1.0).await.is_err());
+    assert!(estimator_without_quotes.needs_confirmation("BONK", OrderSide::Buy, 1.0, 1).await.is_none());
+
+    let failing = estimator(Some(Arc::new(FixedImpact { impact: -1.0, ..FixedImpact::default() })));
+    assert!(failing.needs_confirmation("BONK", OrderSide::Buy, 1.0, 1).await.is_none());
+}
+
+#[tokio::test]
+async fn test_harness_quotes_impact_through_jupiter() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&mint, 0.00002).build().await.unwrap();
+    let liquidity = &harness.services.liquidity;
+
+    let quotes = harness.jupiter.call_count("v6_quote").await;
+    assert!(liquidity.needs_confirmation(&mint, OrderSide::Buy, 5.0, 100).await.is_none());
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, quotes + 1);
+
+    harness.jupiter.set_scenario(JupiterScenario::HighImpact { impact_pct: 0.08 }).await;
+    let estimate = liquidity.needs_confirmation(&mint, OrderSide::Buy, 5.0, 100).await.unwrap();
+    assert_eq!(estimate.source, ImpactSource::JupiterQuote);
+    assert_eq!(estimate.price_impact_bps, 800);
+}
+
+#[test]
+fn test_impact_confirmation_message_and_buttons() {
+    let estimate = walk_book(&thin_book(), OrderSide::Buy, 10.0).unwrap();
+    let text = QuickBuyHandler::impact_confirmation_text("BONK", &estimate, 100, "en");
+    assert!(text.contains("10 SOL of BONK"), "{}", text);
+    assert!(text.contains("from the order book"), "{}", text);
+    assert!(text.contains("more than your 1.00% slippage"), "{}", text);
+    assert!(text.contains("only has room for 3.1500 of those SOL"), "{}", text);
+
+    let keyboard = QuickBuyHandler::impact_confirmation_keyboard("BONK", 10.0, "en");
+    let data: Vec<String> = keyboard.inline_keyboard[0].iter()
+        .filter_map(|button| match &button.kind {
+            teloxide::types::InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        CallbackAction::parse(&data[0]),
+        Some(CallbackAction::ConfirmHighImpact { token: "BONK".to_string(), amount_sol: 10.0 })
+    );
+    assert_eq!(CallbackAction::parse(&data[1]), Some(CallbackAction::CancelQuickBuy));
+}
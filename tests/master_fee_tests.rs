@@ -0,0 +1,270 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tokio::sync::Mutex;
+
+use crate::errors::{BotError, Result};
+use crate::testkit::TestHarness;
+use crate::trading::{
+    CopyTradeExecution, CopyTradeStatus, CopyTradeType, ExecutionReport, MasterFeeKind, MasterFeeLedger, MasterProgram,
+    MasterRequirements, PayoutSender, TokenResolver, TraderStats,
+};
+
+const MASTER: i64 = 1001;
+const FOLLOWER: i64 = 761_001;
+const PAYOUT_WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+fn execution(id: &str, master: i64, fee_sol: f64, status: CopyTradeStatus) -> CopyTradeExecution {
+    CopyTradeExecution {
+        execution_id: id.to_string(),
+        master_trade_id: "master".to_string(),
+        master_user_id: master,
+        follower_user_id: FOLLOWER,
+        token_address: "A".to_string(),
+        token_symbol: "A".to_string(),
+        trade_type: CopyTradeType::Buy,
+        master_amount_sol: 1.0,
+        copied_amount_sol: 1.0 - fee_sol,
+        master_price: 1.0,
+        execution_price: 1.0,
+        slippage_percent: 0.0,
+        fee_paid_sol: fee_sol,
+        status,
+        error_message: None,
+        timestamp: Utc::now(),
+        report: ExecutionReport::default(),
+    }
+}
+
+fn eligible_stats() -> TraderStats {
+    let mut stats = TraderStats::empty(MASTER);
+    stats.total_trades = 40;
+    stats.win_rate = 62.5;
+    stats
+}
+
+/// Records transfers, failing while `failing` is set
+#[derive(Default)]
+struct MockPayouts {
+    sent: Mutex<Vec<(String, u64)>>,
+    failing: Mutex<bool>,
+}
+
+#[async_trait::async_trait]
+impl PayoutSender for MockPayouts {
+    async fn send_sol(&self, recipient: &str, lamports: u64) -> Result<String> {
+        if *self.failing.lock().await {
+            return Err(BotError::trading("RPC unavailable".to_string()).into());
+        }
+        let mut sent = self.sent.lock().await;
+        sent.push((recipient.to_string(), lamports));
+        Ok(format!("payout_sig_{}", sent.len()))
+    }
+}
+
+#[test]
+fn test_requirements_list_everything_missing() {
+    let requirements = MasterRequirements::default();
+    let now = Utc::now();
+
+    let mut stats = eligible_stats();
+    stats.total_trades = 3;
+    stats.win_rate = 40.0;
+    let unmet = requirements.unmet(&stats, Some(now - Duration::days(2)), now);
+    assert_eq!(unmet.len(), 3, "{:?}", unmet);
+    assert!(unmet[0].contains("you have 3"));
+    assert!(unmet[2].contains("yours is 2 days"));
+
+    assert!(requirements.unmet(&eligible_stats(), Some(now - Duration::days(45)), now).is_empty());
+    // No wallet on record means no account age
+    assert_eq!(requirements.unmet(&eligible_stats(), None, now).len(), 1);
+
+    assert_eq!(requirements.validate_fee(0.0).unwrap(), 0.0);
+    assert_eq!(requirements.validate_fee(20.0).unwrap(), 20.0);
+    assert!(requirements.validate_fee(20.5).is_err());
+    assert!(requirements.validate_fee(-1.0).is_err());
+    assert!(requirements.validate_fee(f64::NAN).is_err());
+}
+
+#[test]
+fn test_ledger_accrues_each_copy_once_and_reverses_refunds() {
+    let mut ledger = MasterFeeLedger::new(MASTER);
+
+    assert_eq!(ledger.accrue(&execution("a", MASTER, 0.1, CopyTradeStatus::Success)), Some(100_000_000));
+    assert_eq!(ledger.accrue(&execution("b", MASTER, 0.05, CopyTradeStatus::PartialFill)), Some(50_000_000));
+    // Retries, failures, other masters and free copies add nothing
+    assert_eq!(ledger.accrue(&execution("a", MASTER, 0.1, CopyTradeStatus::Success)), None);
+    assert_eq!(ledger.accrue(&execution("c", MASTER, 0.1, CopyTradeStatus::Failed)), None);
+    assert_eq!(ledger.accrue(&execution("d", 2002, 0.1, CopyTradeStatus::Success)), None);
+    assert_eq!(ledger.accrue(&execution("e", MASTER, 0.0, CopyTradeStatus::Success)), None);
+    assert_eq!(ledger.earned_lamports(), 150_000_000);
+
+    assert_eq!(ledger.reverse("a", Utc::now()), Some(100_000_000));
+    assert_eq!(ledger.reverse("a", Utc::now()), None);
+    assert_eq!(ledger.reverse("c", Utc::now()), None);
+    assert_eq!(ledger.earned_lamports(), 50_000_000);
+    assert_eq!(ledger.balance_lamports(), 50_000_000);
+    assert_eq!(ledger.entries.iter().filter(|e| e.kind == MasterFeeKind::Reversal).count(), 1);
+}
+
+#[test]
+fn test_reversal_after_payout_is_owed_from_later_fees() {
+    let mut ledger = MasterFeeLedger::new(MASTER);
+    ledger.accrue(&execution("a", MASTER, 0.2, CopyTradeStatus::Success));
+    assert_eq!(ledger.payout_due(300_000_000), None);
+    assert_eq!(ledger.payout_due(100_000_000), Some(200_000_000));
+
+    ledger.record_payout(200_000_000, "sig".to_string(), Utc::now());
+    assert_eq!(ledger.payout_due(1), None);
+
+    // The refunded copy was already paid out, so the master owes it back
+    ledger.reverse("a", Utc::now());
+    assert_eq!(ledger.balance_lamports(), -200_000_000);
+    ledger.accrue(&execution("b", MASTER, 0.15, CopyTradeStatus::Success));
+    assert_eq!(ledger.payout_due(1), None);
+    ledger.accrue(&execution("c", MASTER, 0.15, CopyTradeStatus::Success));
+    assert_eq!(ledger.payout_due(1), Some(100_000_000));
+    assert_eq!(ledger.paid_lamports(), 200_000_000);
+}
+
+#[tokio::test]
+async fn test_fee_changes_only_apply_to_later_copies_and_refunds_reverse() {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().build().await.unwrap();
+    let program = &harness.services.master_program;
+    let copy_trading = &harness.copy_trading;
+
+    // Ineligible traders are turned away with what they're missing
+    let err = program.apply(MASTER, &TraderStats::empty(MASTER), None, PAYOUT_WALLET, None).await.unwrap_err();
+    assert!(err.to_string().contains("closed trades"), "{}", err);
+    assert!(program.profile(MASTER).await.is_none());
+
+    let created = Some(Utc::now() - Duration::days(90));
+    assert!(program.apply(MASTER, &eligible_stats(), created, PAYOUT_WALLET, Some(25.0)).await.is_err());
+    let profile = program.apply(MASTER, &eligible_stats(), created, PAYOUT_WALLET, Some(10.0)).await.unwrap();
+    assert!(profile.accepting_followers);
+
+    copy_trading.start_following(FOLLOWER, "AlphaTrader", 50.0, 5.0).await.unwrap();
+    let first = copy_trading
+        .execute_copy_trade(MASTER, &mint, "BONK", CopyTradeType::Buy, 4.0, 0.00002)
+        .await
+        .unwrap()
+        .remove(0);
+    // 10% of the 2 SOL copy
+    assert!((first.fee_paid_sol - 0.2).abs() < 1e-9);
+
+    program.set_fee(MASTER, 2.0).await.unwrap();
+    let second = copy_trading
+        .execute_copy_trade(MASTER, &mint, "BONK", CopyTradeType::Buy, 4.0, 0.00002)
+        .await
+        .unwrap()
+        .remove(0);
+    assert!((second.fee_paid_sol - 0.04).abs() < 1e-9);
+    assert_eq!(program.ledger(MASTER).await.unwrap().earned_lamports(), 240_000_000);
+
+    // The first copy's transaction never landed
+    let refunded = copy_trading.refund_execution(&first.execution_id, "transaction dropped").await.unwrap();
+    assert_eq!(refunded.status, CopyTradeStatus::Failed);
+    assert_eq!(program.ledger(MASTER).await.unwrap().earned_lamports(), 40_000_000);
+    assert!(copy_trading.refund_execution(&first.execution_id, "again").await.is_err());
+
+    // The follower's history shows the copy as failed
+    let (configs, history) = copy_trading.get_user_stats(FOLLOWER).await.unwrap();
+    assert_eq!(configs.len(), 1);
+    assert!(history.iter().any(|e| e.execution_id == first.execution_id && e.status == CopyTradeStatus::Failed));
+}
+
+#[tokio::test]
+async fn test_payouts_send_the_balance_above_the_threshold_once() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let payouts = Arc::new(MockPayouts::default());
+    let program = MasterProgram::new(harness.db.clone())
+        .with_min_payout_sol(0.1)
+        .with_payouts(payouts.clone());
+    let created = Some(Utc::now() - Duration::days(90));
+    program.apply(MASTER, &eligible_stats(), created, PAYOUT_WALLET, None).await.unwrap();
+
+    // Fees of traders who never applied aren't theirs to collect
+    program.accrue(&[execution("other", 2002, 1.0, CopyTradeStatus::Success)]).await.unwrap();
+    assert!(program.ledger(2002).await.unwrap().entries.is_empty());
+
+    program.accrue(&[execution("a", MASTER, 0.06, CopyTradeStatus::Success)]).await.unwrap();
+    assert!(program.run_payouts().await.unwrap().is_empty());
+
+    program.accrue(&[
+        execution("a", MASTER, 0.06, CopyTradeStatus::Success),
+        execution("b", MASTER, 0.05, CopyTradeStatus::Success),
+    ]).await.unwrap();
+    *payouts.failing.lock().await = true;
+    assert!(program.run_payouts().await.unwrap().is_empty());
+    assert_eq!(program.ledger(MASTER).await.unwrap().paid_lamports(), 0);
+
+    *payouts.failing.lock().await = false;
+    let paid = program.run_payouts().await.unwrap();
+    assert_eq!(paid, vec![(MASTER, 110_000_000, "payout_sig_1".to_string())]);
+    assert_eq!(*payouts.sent.lock().await, vec![(PAYOUT_WALLET.to_string(), 110_000_000)]);
+
+    let ledger = program.ledger(MASTER).await.unwrap();
+    assert_eq!(ledger.balance_lamports(), 0);
+    assert_eq!(ledger.entries.last().unwrap().signature.as_deref(), Some("payout_sig_1"));
+    assert!(program.run_payouts().await.unwrap().is_empty());
+
+    // The ledger survives a restart, so nothing is paid twice
+    let restarted = MasterProgram::new(harness.db.clone()).with_payouts(payouts.clone());
+    assert_eq!(restarted.ledger(MASTER).await.unwrap().paid_lamports(), 110_000_000);
+    assert!(restarted.run_payouts().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_concurrent_accruals_and_payouts_share_one_ledger() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let payouts = Arc::new(MockPayouts::default());
+    let program = MasterProgram::new(harness.db.clone())
+        .with_min_payout_sol(0.1)
+        .with_payouts(payouts.clone());
+    let created = Some(Utc::now() - Duration::days(90));
+    program.apply(MASTER, &eligible_stats(), created, PAYOUT_WALLET, None).await.unwrap();
+
+    // Twenty copies settling at once each land in the ledger
+    let executions: Vec<CopyTradeExecution> = (0..20)
+        .map(|i| execution(&format!("copy-{}", i), MASTER, 0.01, CopyTradeStatus::Success))
+        .collect();
+    let accrued = futures::future::join_all(executions.iter().map(|e| program.accrue(std::slice::from_ref(e)))).await;
+    assert!(accrued.into_iter().all(|accrued| accrued.unwrap() == 10_000_000));
+    assert_eq!(program.ledger(MASTER).await.unwrap().earned_lamports(), 200_000_000);
+
+    // Overlapping runs pay the balance once
+    let (first, second) = tokio::join!(program.run_payouts(), program.run_payouts());
+    assert_eq!(first.unwrap().len() + second.unwrap().len(), 1);
+    assert_eq!(*payouts.sent.lock().await, vec![(PAYOUT_WALLET.to_string(), 200_000_000)]);
+
+    let restarted = MasterProgram::new(harness.db.clone());
+    let ledger = restarted.ledger(MASTER).await.unwrap();
+    assert_eq!(ledger.earned_lamports(), 200_000_000);
+    assert_eq!(ledger.balance_lamports(), 0);
+}
+
+#[tokio::test]
+async fn test_unreadable_ledger_is_never_replaced_by_an_empty_one() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let payouts = Arc::new(MockPayouts::default());
+    let program = MasterProgram::new(harness.db.clone())
+        .with_min_payout_sol(0.1)
+        .with_payouts(payouts.clone());
+    let created = Some(Utc::now() - Duration::days(90));
+    program.apply(MASTER, &eligible_stats(), created, PAYOUT_WALLET, None).await.unwrap();
+    harness.db.upsert_master_fee_ledger(MASTER, "{\"master_user_id\":").await.unwrap();
+
+    assert!(program.ledger(MASTER).await.is_err());
+    assert!(program.accrue(&[execution("a", MASTER, 0.5, CopyTradeStatus::Success)]).await.is_err());
+    assert!(program.run_payouts().await.unwrap().is_empty());
+    assert!(payouts.sent.lock().await.is_empty());
+    let stored = harness.db.get_master_fee_ledger(MASTER).await.unwrap();
+    assert_eq!(stored.as_deref(), Some("{\"master_user_id\":"));
+
+    // Nothing empty was cached, so the ledger loads once the row is readable again
+    let mut ledger = MasterFeeLedger::new(MASTER);
+    ledger.accrue(&execution("b", MASTER, 0.2, CopyTradeStatus::Success));
+    harness.db.upsert_master_fee_ledger(MASTER, &serde_json::to_string(&ledger).unwrap()).await.unwrap();
+    assert_eq!(program.ledger(MASTER).await.unwrap().earned_lamports(), 200_000_000);
+}
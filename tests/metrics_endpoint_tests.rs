@@ -0,0 +1,168 @@
+use crate::ai::GroqAnalyzer;
+use crate::api::{ApiTier, JupiterV6Client, QuoteRequestV6};
+use crate::monitoring::{MetricType, MetricsExporter};
+use crate::testkit::TestHarness;
+use crate::trading::{DCAStrategy, Order, ScheduleConfig, TokenResolver, TradingMode};
+use rust_decimal::Decimal;
+use solana_sdk::signature::Signer;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const USER_ID: i64 = 798_001;
+
+/// Start an exporter on a free port and return its scrape URL
+async fn exporter() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/metrics", listener.local_addr().unwrap());
+    let exporter = MetricsExporter::new(super::shared_metrics());
+    tokio::spawn(async move {
+        let _ = exporter.serve(listener).await;
+    });
+    url
+}
+
+async fn scrape(url: &str) -> String {
+    let response = reqwest::get(url).await.unwrap();
+    assert!(response.status().is_success());
+    let content_type = response.headers()[reqwest::header::CONTENT_TYPE].to_str().unwrap().to_string();
+    assert!(content_type.starts_with("text/plain; version=0.0.4"), "{}", content_type);
+    response.text().await.unwrap()
+}
+
+/// Value of the sample whose name and labels are exactly `series`
+fn sample(body: &str, series: &str) -> Option<f64> {
+    body.lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| line.strip_prefix(series)?.strip_prefix(' ')?.parse().ok())
+}
+
+fn labels(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[tokio::test]
+async fn test_scrape_after_trading_activity() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .price(WSOL_MINT, 200.0)
+        .price(&bonk, 0.00002)
+        .metrics(super::shared_metrics())
+        .build()
+        .await
+        .unwrap();
+    let wallet = harness.register_user(USER_ID, 5.0).await.unwrap().pubkey().to_string();
+    harness.trading_engine.set_trading_mode(wallet.clone(), TradingMode::Paper).await.unwrap();
+
+    // One paper buy fills; selling a token the ledger doesn't hold fails
+    harness.trading_engine.buy_with_rebate(wallet.clone(), "BONK".to_string(), 0.5).await.unwrap();
+    assert!(harness.trading_engine.sell_with_rebate(wallet, "WIF".to_string(), 50.0).await.is_err());
+
+    // A stop loss below the market waits; the pass records it as active
+    let order = Order::create_stop_loss(USER_ID, bonk.clone(), Decimal::new(1, 5), Decimal::from(1_000));
+    harness.order_manager.create_order(order).await.unwrap();
+    harness.order_manager.run_cycle().await.unwrap();
+
+    // A daily DCA schedule that isn't due yet still counts as running
+    let strategy = DCAStrategy::create_daily_dca(
+        USER_ID,
+        "BONK daily".to_string(),
+        USDC.to_string(),
+        bonk.clone(),
+        Decimal::from(1_000),
+        Decimal::from(100),
+    );
+    harness.services.dca_engine.restore_strategies(vec![strategy.clone()]).await.unwrap();
+    let mut schedule = ScheduleConfig::create_daily_schedule(strategy.strategy_id.clone(), "test".to_string(), 0, 0, "UTC".to_string());
+    schedule.schedule_id = strategy.strategy_id.clone();
+    harness.services.dca_scheduler.add_schedule(schedule).await.unwrap();
+    harness.services.dca_scheduler.run_cycle().await.unwrap();
+
+    // Jupiter answers the quote; Groq isn't there, so its call fails
+    let jupiter = JupiterV6Client::new(ApiTier::Lite, None)
+        .with_base_url(harness.jupiter.base_url())
+        .with_metrics(super::shared_metrics());
+    jupiter.get_quote(QuoteRequestV6 {
+        input_mint: WSOL_MINT.to_string(),
+        output_mint: bonk.clone(),
+        amount: 1_000_000_000,
+        slippage_bps: 50,
+        swap_mode: None,
+        dexes: None,
+        exclude_dexes: None,
+        max_accounts: None,
+        quote_mint: None,
+        minimize_slippage: None,
+        only_direct_routes: None,
+    }).await.unwrap();
+    let groq = GroqAnalyzer::new("test-key".to_string())
+        .with_base_url(harness.jupiter.base_url())
+        .with_fallback_model(None)
+        .with_metrics(super::shared_metrics());
+    assert!(groq.analyze_market_conditions().await.is_err());
+
+    // A command through the dispatcher
+    harness.start_bot();
+    harness.telegram.inject_message(USER_ID, "/orders").await;
+    harness.telegram
+        .wait_for_text(USER_ID, "", Duration::from_secs(10))
+        .await
+        .expect("bot should answer /orders");
+
+    let url = exporter().await;
+    // The command is counted once its handler returns, just after the reply
+    let mut body = scrape(&url).await;
+    for _ in 0..20 {
+        if sample(&body, r#"commands_processed_total{command="orders",status="success"}"#).is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        body = scrape(&url).await;
+    }
+
+    assert!(body.contains("# TYPE trades_executed_total counter"));
+    assert!(sample(&body, r#"trades_executed_total{type="buy"}"#).unwrap() >= 1.0);
+    assert!(body.lines().any(|l| l.starts_with("trades_failed_total{reason=") && l.contains(r#"type="sell""#)), "{}", body);
+    assert!(sample(&body, r#"commands_processed_total{command="orders",status="success"}"#).unwrap() >= 1.0);
+
+    assert!(body.contains("# TYPE api_latency_ms histogram"));
+    assert!(sample(&body, r#"api_latency_ms_count{endpoint="jupiter_quote",method="GET"}"#).unwrap() >= 1.0);
+    assert!(sample(&body, r#"api_latency_ms_count{endpoint="groq_chat",method="POST"}"#).unwrap() >= 1.0);
+    assert!(sample(&body, r#"api_calls_total{endpoint="groq_chat",status="failed"}"#).unwrap() >= 1.0);
+
+    assert_eq!(sample(&body, r#"orders_active{type="stop_loss"}"#), Some(1.0));
+    assert_eq!(sample(&body, r#"orders_active{type="bracket"}"#), Some(0.0));
+    assert_eq!(sample(&body, "dca_strategies_active"), Some(1.0));
+    assert!(body.contains("# TYPE queue_depth gauge"));
+    assert!(sample(&body, r#"queue_depth{queue="trading_engine"}"#).is_some());
+}
+
+#[tokio::test]
+async fn test_custom_metrics_export_as_their_prometheus_type() {
+    let metrics = super::shared_metrics();
+    let signal = labels(&[("strategy", "momentum")]);
+
+    metrics.add_custom_metric("custom_signals_total".to_string(), 2.0, MetricType::Counter, signal.clone()).await;
+    metrics.add_custom_metric("custom_signals_total".to_string(), 3.0, MetricType::Counter, signal.clone()).await;
+    metrics.add_custom_metric("custom_open_positions".to_string(), 7.0, MetricType::Gauge, HashMap::new()).await;
+    metrics.add_custom_metric("custom_open_positions".to_string(), 4.0, MetricType::Gauge, HashMap::new()).await;
+    metrics.add_custom_metric("custom_fill_ms".to_string(), 120.0, MetricType::Histogram, signal.clone()).await;
+    metrics.add_custom_metric("custom_hold_minutes".to_string(), 30.0, MetricType::Summary, HashMap::new()).await;
+    // Different label names or type than the first value: kept out of the export
+    metrics.add_custom_metric("custom_signals_total".to_string(), 100.0, MetricType::Counter, labels(&[("token", "BONK")])).await;
+    metrics.add_custom_metric("custom_open_positions".to_string(), 100.0, MetricType::Counter, HashMap::new()).await;
+
+    let body = scrape(&exporter().await).await;
+
+    assert!(body.contains("# TYPE custom_signals_total counter"));
+    assert_eq!(sample(&body, r#"custom_signals_total{strategy="momentum"}"#), Some(5.0));
+    assert!(!body.contains(r#"custom_signals_total{token="BONK"}"#));
+    assert!(body.contains("# TYPE custom_open_positions gauge"));
+    assert_eq!(sample(&body, "custom_open_positions"), Some(4.0));
+    assert!(body.contains("# TYPE custom_fill_ms histogram"));
+    assert_eq!(sample(&body, r#"custom_fill_ms_count{strategy="momentum"}"#), Some(1.0));
+    assert!(body.contains("# TYPE custom_hold_minutes histogram"));
+    assert_eq!(sample(&body, "custom_hold_minutes_sum"), Some(30.0));
+}
@@ -0,0 +1,259 @@
+use chrono::Utc;
+use std::time::Duration;
+
+use crate::db::{Database, Migration, MigrationOptions, Migrator, MIGRATIONS};
+use crate::errors::BotError;
+
+const WIDGETS: &[Migration] = &[
+    Migration::new(1, "widgets", "CREATE TABLE widgets (id BIGINT PRIMARY KEY)"),
+    Migration::new(2, "widget_names", "ALTER TABLE widgets ADD COLUMN name TEXT"),
+];
+const WIDGETS_EDITED: &[Migration] = &[
+    Migration::new(1, "widgets", "CREATE TABLE widgets (id BIGINT PRIMARY KEY, size BIGINT)"),
+    Migration::new(2, "widget_names", "ALTER TABLE widgets ADD COLUMN name TEXT"),
+];
+const HALF_BROKEN: &[Migration] = &[Migration::new(
+    1,
+    "half_broken",
+    "CREATE TABLE gadgets (id BIGINT PRIMARY KEY);\nINSERT INTO no_such_table VALUES (1);",
+)];
+
+fn latest() -> i64 {
+    MIGRATIONS.last().unwrap().version
+}
+
+async fn empty() -> Database {
+    Database::open("sqlite::memory:").await.unwrap()
+}
+
+async fn tables(db: &Database) -> Vec<String> {
+    sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")
+        .fetch_all(db.pool())
+        .await
+        .unwrap()
+}
+
+/// Table and column names, which fresh installs and upgrades must agree on
+async fn schema(db: &Database) -> Vec<(String, String)> {
+    sqlx::query_as(
+        "SELECT m.name, p.name FROM sqlite_master m JOIN pragma_table_info(m.name) p
+         WHERE m.type = 'table' ORDER BY m.name, p.name",
+    )
+        .fetch_all(db.pool())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_empty_database_migrates_to_the_latest_version() {
+    let db = empty().await;
+    let report = db.migrate(&MigrationOptions::default()).await.unwrap();
+    assert_eq!(report.from_version, 0);
+    assert_eq!(report.to_version, latest());
+    assert_eq!(report.migrations.len(), MIGRATIONS.len());
+
+    let tables = tables(&db).await;
+    for table in [
+        "user_wallets", "signing_sessions", "trades", "positions",
+        "orders", "order_executions", "price_alerts", "dca_strategies",
+        "user_settings", "trading_modes", "paper_ledgers",
+        "trade_records", "leaderboard_trades", "signals", "signal_stats", "blink_events",
+        "lending_operations", "send_batches",
+        "copy_executions", "copy_daily_risk", "master_profiles", "master_fee_ledgers", "master_positions",
+        "admin_state", "watchlists", "schema_version", "schema_lock",
+    ] {
+        assert!(tables.iter().any(|t| t == table), "missing {} in {:?}", table, tables);
+    }
+
+    // The next start has nothing to do
+    let again = db.migrate(&MigrationOptions::default()).await.unwrap();
+    assert!(again.migrations.is_empty());
+    assert_eq!((again.from_version, again.to_version), (latest(), latest()));
+}
+
+#[tokio::test]
+async fn test_old_version_database_upgrades_without_losing_data() {
+    let db = empty().await;
+    // What an older build left behind: its migrations and some data
+    Migrator::new(db.pool()).with_migrations(&MIGRATIONS[..2]).run(false).await.unwrap();
+    db.register_user_wallet("1001", "WalletA").await.unwrap();
+    db.upsert_order("order-1", 1001, "BonkMint", "active", r#"{"order_id":"order-1"}"#).await.unwrap();
+
+    let report = db.migrate(&MigrationOptions::default()).await.unwrap();
+    assert_eq!(report.from_version, 2);
+    let versions: Vec<i64> = report.migrations.iter().map(|(version, _)| *version).collect();
+    assert_eq!(versions, (3..=latest()).collect::<Vec<_>>());
+
+    assert_eq!(db.get_active_wallet("1001").await.unwrap().as_deref(), Some("WalletA"));
+    assert_eq!(db.get_orders_by_status(&["active", "paused"]).await.unwrap().len(), 1);
+    db.upsert_watchlist(1001, "{}").await.unwrap();
+    db.upsert_watchlist(1001, r#"{"tokens":[]}"#).await.unwrap();
+    assert_eq!(db.get_watchlists().await.unwrap(), vec![(1001, r#"{"tokens":[]}"#.to_string())]);
+
+    let fresh = Database::new("sqlite::memory:").await.unwrap();
+    assert_eq!(schema(&db).await, schema(&fresh).await);
+}
+
+#[tokio::test]
+async fn test_tables_from_before_migrations_are_adopted() {
+    let db = empty().await;
+    // The store used to create its tables itself, without recording a version
+    sqlx::query(
+        "CREATE TABLE user_wallets (
+            telegram_id TEXT NOT NULL,
+            wallet_address TEXT NOT NULL,
+            label TEXT,
+            is_active BIGINT NOT NULL DEFAULT 0,
+            created_at BIGINT NOT NULL,
+            last_used BIGINT,
+            PRIMARY KEY (telegram_id, wallet_address)
+        )",
+    )
+        .execute(db.pool())
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO user_wallets (telegram_id, wallet_address, is_active, created_at) VALUES ('1002', 'WalletB', 1, 1700000000)")
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let report = db.migrate(&MigrationOptions::default()).await.unwrap();
+    assert_eq!((report.from_version, report.to_version), (0, latest()));
+    assert_eq!(db.get_wallet_owner("WalletB").await.unwrap().as_deref(), Some("1002"));
+    assert_eq!(db.list_user_ids().await.unwrap(), vec![1002]);
+
+    let fresh = Database::new("sqlite::memory:").await.unwrap();
+    assert_eq!(schema(&db).await, schema(&fresh).await);
+}
+
+#[tokio::test]
+async fn test_adopted_tables_with_other_columns_are_refused() {
+    let db = empty().await;
+    // An older store's table: no label, and a column the migrations never declare
+    sqlx::query(
+        "CREATE TABLE user_wallets (
+            telegram_id TEXT NOT NULL,
+            wallet_address TEXT NOT NULL,
+            is_active BIGINT NOT NULL DEFAULT 0,
+            created_at BIGINT NOT NULL,
+            last_used BIGINT,
+            nickname TEXT,
+            PRIMARY KEY (telegram_id, wallet_address)
+        )",
+    )
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let err = db.migrate(&MigrationOptions::default()).await.unwrap_err();
+    assert!(matches!(err, BotError::Migration(_)), "{:?}", err);
+    let message = err.to_string();
+    assert!(message.contains("user_wallets") && message.contains("missing [label]"), "{}", message);
+    assert!(message.contains("unexpected [nickname]"), "{}", message);
+
+    // Nothing from the refused migration was kept
+    assert!(!tables(&db).await.iter().any(|t| t == "trades"));
+    assert_eq!(Migrator::new(db.pool()).current_version().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_dry_run_lists_pending_migrations_without_applying_them() {
+    let db = empty().await;
+    let options = MigrationOptions::from_args(["solana-trading-bot", "--dry-run"]);
+
+    let report = db.migrate(&options).await.unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.migrations.len(), MIGRATIONS.len());
+    assert!(tables(&db).await.is_empty());
+
+    Migrator::new(db.pool()).with_migrations(&MIGRATIONS[..3]).run(false).await.unwrap();
+    let report = db.migrate(&options).await.unwrap();
+    assert_eq!(report.from_version, 3);
+    assert_eq!(report.migrations.first().map(|(version, _)| *version), Some(4));
+    assert_eq!(Migrator::new(db.pool()).current_version().await.unwrap(), 3);
+}
+
+#[test]
+fn test_migrate_only_and_dry_run_flags() {
+    let options = MigrationOptions::from_args(["solana-trading-bot"]);
+    assert!(!options.migrate_only && !options.dry_run);
+
+    let options = MigrationOptions::from_args(["solana-trading-bot", "--migrate-only"]);
+    assert!(options.migrate_only && !options.dry_run);
+
+    // A dry run never goes on to start the bot
+    let options = MigrationOptions::from_args(["solana-trading-bot", "--dry-run"]);
+    assert!(options.migrate_only && options.dry_run);
+}
+
+#[tokio::test]
+async fn test_held_lock_blocks_until_it_goes_stale() {
+    let db = empty().await;
+    db.migrate(&MigrationOptions::default()).await.unwrap();
+    sqlx::query("INSERT INTO schema_lock (id, holder, acquired_at) VALUES (1, 'pid 42', $1)")
+        .bind(Utc::now().timestamp())
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let err = Migrator::new(db.pool())
+        .with_lock_timeout(Duration::from_millis(300))
+        .run(false)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, BotError::Migration(_)), "{:?}", err);
+    assert!(err.to_string().contains("pid 42"), "{}", err);
+
+    // Its holder died an hour ago
+    sqlx::query("UPDATE schema_lock SET acquired_at = $1")
+        .bind(Utc::now().timestamp() - 3600)
+        .execute(db.pool())
+        .await
+        .unwrap();
+    Migrator::new(db.pool()).with_lock_timeout(Duration::from_millis(300)).run(false).await.unwrap();
+
+    let holders: Vec<String> = sqlx::query_scalar("SELECT holder FROM schema_lock").fetch_all(db.pool()).await.unwrap();
+    assert!(holders.is_empty(), "lock not released: {:?}", holders);
+}
+
+#[tokio::test]
+async fn test_concurrent_migrators_apply_each_migration_once() {
+    let db = empty().await;
+    let options = MigrationOptions::default();
+
+    let (first, second) = tokio::join!(db.migrate(&options), db.migrate(&options));
+    let (first, second) = (first.unwrap(), second.unwrap());
+    assert_eq!(first.migrations.len() + second.migrations.len(), MIGRATIONS.len());
+
+    let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM schema_version").fetch_one(db.pool()).await.unwrap();
+    assert_eq!(recorded, MIGRATIONS.len() as i64);
+}
+
+#[tokio::test]
+async fn test_edited_and_unknown_migrations_are_refused() {
+    let db = empty().await;
+    Migrator::new(db.pool()).with_migrations(WIDGETS).run(false).await.unwrap();
+
+    let err = Migrator::new(db.pool()).with_migrations(WIDGETS_EDITED).run(false).await.unwrap_err();
+    assert!(err.to_string().contains("edited"), "{}", err);
+
+    // An older build against a newer schema
+    let err = Migrator::new(db.pool()).with_migrations(&WIDGETS[..1]).run(false).await.unwrap_err();
+    assert!(err.to_string().contains("doesn't know"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_failed_migration_leaves_nothing_behind() {
+    let db = empty().await;
+    let err = Migrator::new(db.pool()).with_migrations(HALF_BROKEN).run(false).await.unwrap_err();
+    assert!(err.to_string().contains("half_broken"), "{}", err);
+
+    assert!(!tables(&db).await.iter().any(|t| t == "gadgets"));
+    assert_eq!(Migrator::new(db.pool()).current_version().await.unwrap(), 0);
+}
+
+#[test]
+fn test_statements_split_without_comments() {
+    let migration = Migration::new(1, "split", "-- Two tables\nCREATE TABLE a (id BIGINT);\n\n-- and b\nCREATE TABLE b (id BIGINT);\n");
+    assert_eq!(migration.statements(), vec!["CREATE TABLE a (id BIGINT)", "CREATE TABLE b (id BIGINT)"]);
+}
@@ -1,3 +1,12 @@
+use crate::monitoring::MetricsCollector;
+use std::sync::{Arc, OnceLock};
+
+/// Prometheus metrics register globally, so tests share one collector per process
+pub(crate) fn shared_metrics() -> Arc<MetricsCollector> {
+    static METRICS: OnceLock<Arc<MetricsCollector>> = OnceLock::new();
+    METRICS.get_or_init(|| Arc::new(MetricsCollector::new().unwrap())).clone()
+}
+
 #[cfg(test)]
 mod validation_tests;
 
@@ -8,4 +17,277 @@ mod formatting_tests;
 mod trading_tests;
 
 #[cfg(test)]
-mod wallet_tests;
\ No newline at end of file
+mod wallet_tests;
+
+#[cfg(test)]
+mod journal_tests;
+
+#[cfg(test)]
+mod sandwich_tests;
+
+#[cfg(test)]
+mod dca_tests;
+
+#[cfg(test)]
+mod compute_budget_tests;
+
+#[cfg(test)]
+mod group_buy_tests;
+
+#[cfg(test)]
+mod alias_tests;
+
+#[cfg(test)]
+mod ata_cleanup_tests;
+
+#[cfg(test)]
+mod i18n_tests;
+
+#[cfg(test)]
+mod order_monitor_tests;
+
+#[cfg(test)]
+mod convex_migration_tests;
+
+#[cfg(test)]
+mod bonding_tests;
+
+#[cfg(test)]
+mod public_stats_tests;
+
+#[cfg(test)]
+mod smart_timing_tests;
+
+#[cfg(test)]
+mod data_deletion_tests;
+
+#[cfg(test)]
+mod execution_notice_tests;
+
+#[cfg(test)]
+mod exit_routing_tests;
+
+#[cfg(test)]
+mod trade_import_tests;
+
+#[cfg(test)]
+mod claim_sponsor_tests;
+
+#[cfg(test)]
+mod trading_hours_tests;
+
+#[cfg(test)]
+mod automation_auth_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod trending_cache_tests;
+
+#[cfg(test)]
+mod price_input_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod order_persistence_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod oco_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod e2e_tests;
+
+#[cfg(test)]
+mod indicator_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod order_commands_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod copy_risk_tests;
+
+#[cfg(test)]
+mod copy_monitor_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod paper_trading_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod dca_control_tests;
+
+#[cfg(test)]
+mod dca_report_tests;
+
+#[cfg(test)]
+mod flash_crash_tests;
+
+#[cfg(test)]
+mod anomaly_model_tests;
+
+#[cfg(test)]
+mod whale_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod whale_command_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod price_alert_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod alert_delivery_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod larp_provider_tests;
+
+#[cfg(test)]
+mod jupiter_rate_limit_tests;
+
+#[cfg(test)]
+mod price_cache_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod order_batch_pricing_tests;
+
+#[cfg(test)]
+mod wallet_export_tests;
+
+#[cfg(test)]
+mod hardware_signing_tests;
+
+#[cfg(test)]
+mod portfolio_transfer_fee_tests;
+
+#[cfg(test)]
+mod cost_basis_method_tests;
+
+#[cfg(test)]
+mod trade_export_tests;
+
+#[cfg(test)]
+mod benchmark_tests;
+
+#[cfg(test)]
+mod risk_metrics_tests;
+
+#[cfg(test)]
+mod signal_outcome_tests;
+
+#[cfg(test)]
+mod groq_fallback_tests;
+
+#[cfg(test)]
+mod groq_analysis_schema_tests;
+
+#[cfg(test)]
+mod jito_bundle_tests;
+
+#[cfg(test)]
+mod priority_fee_tests;
+
+#[cfg(test)]
+mod token_resolver_tests;
+
+#[cfg(test)]
+mod user_settings_tests;
+
+#[cfg(test)]
+mod callback_action_tests;
+
+#[cfg(test)]
+mod leaderboard_tests;
+
+#[cfg(test)]
+mod leaderboard_privacy_tests;
+
+#[cfg(test)]
+mod websocket_reconnect_tests;
+
+#[cfg(test)]
+mod price_aggregation_tests;
+
+#[cfg(test)]
+mod liquidity_tests;
+
+#[cfg(test)]
+mod session_store_tests;
+
+#[cfg(test)]
+mod circuit_breaker_tests;
+
+#[cfg(test)]
+mod metrics_endpoint_tests;
+
+#[cfg(test)]
+mod health_tests;
+
+#[cfg(test)]
+mod command_rate_limit_tests;
+
+#[cfg(test)]
+mod blink_action_tests;
+
+#[cfg(test)]
+mod blink_analytics_tests;
+
+#[cfg(test)]
+mod pump_fun_client_tests;
+
+#[cfg(test)]
+mod token_launch_tests;
+
+#[cfg(test)]
+mod dca_schedule_tests;
+
+#[cfg(test)]
+mod dca_sizing_tests;
+
+#[cfg(test)]
+mod convex_webhook_tests;
+
+#[cfg(test)]
+mod trading_lanes_tests;
+
+#[cfg(test)]
+mod fill_check_tests;
+
+#[cfg(test)]
+mod position_reconcile_tests;
+
+#[cfg(test)]
+mod lending_tests;
+
+#[cfg(test)]
+mod distribution_tests;
+
+#[cfg(test)]
+mod trailing_atr_tests;
+
+#[cfg(test)]
+mod panic_sell_tests;
+
+#[cfg(test)]
+mod copy_proportional_tests;
+
+#[cfg(test)]
+mod master_fee_tests;
+
+#[cfg(test)]
+mod trade_gate_tests;
+
+#[cfg(test)]
+mod migration_tests;
+
+#[cfg(test)]
+mod error_rendering_tests;
+
+#[cfg(test)]
+mod confirmation_tracker_tests;
+
+#[cfg(test)]
+mod i18n_catalog_tests;
+
+#[cfg(test)]
+mod admin_tests;
+
+#[cfg(test)]
+mod watchlist_tests;
+
+#[cfg(all(test, feature = "testkit"))]
+mod signature_watch_tests;
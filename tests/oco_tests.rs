@@ -0,0 +1,116 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::testkit::TestHarness;
+use crate::trading::{Order, OrderManager, OrderStatus, OrderType, TokenResolver};
+
+const USER_ID: i64 = 756_001;
+
+/// OCO on BONK at 1.0: stop 0.9, take-profit 1.2
+async fn oco_harness() -> (TestHarness, String, String) {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&mint, 1.0).build().await.unwrap();
+
+    let oco = Order::create_oco(USER_ID, mint.clone(), Decimal::new(9, 1), Decimal::new(12, 1), Decimal::from(1_000));
+    let oco_id = harness.order_manager.create_order(oco).await.unwrap();
+    (harness, mint, oco_id)
+}
+
+fn leg<'a>(orders: &'a [Order], stop: bool) -> Option<&'a Order> {
+    orders.iter().find(|o| matches!(o.order_type, OrderType::StopLoss { .. }) == stop)
+}
+
+#[tokio::test]
+async fn test_oco_expands_into_linked_children() {
+    let (harness, _, oco_id) = oco_harness().await;
+
+    let orders = harness.order_manager.get_user_orders(USER_ID).await;
+    assert_eq!(orders.len(), 2);
+    assert!(orders.iter().all(|o| o.parent_order_id.as_deref() == Some(oco_id.as_str())));
+    assert!(leg(&orders, true).is_some() && leg(&orders, false).is_some());
+    assert_eq!(harness.order_manager.monitor_stats().await.monitored_orders, 2);
+
+    // Cancelling the OCO cancels both children
+    assert!(harness.order_manager.cancel_order(&oco_id).await.unwrap());
+    assert!(harness.order_manager.get_user_orders(USER_ID).await.is_empty());
+    assert_eq!(harness.order_manager.monitor_stats().await.monitor_count, 0);
+}
+
+#[tokio::test]
+async fn test_take_profit_fill_cancels_stop() {
+    let (harness, mint, _) = oco_harness().await;
+
+    harness.jupiter.set_price(&mint, 1.3).await;
+    harness.price_client.clear_cache().await;
+    harness.order_manager.run_cycle().await.unwrap();
+
+    let orders = harness.order_manager.get_user_orders(USER_ID).await;
+    assert_eq!(orders.len(), 1);
+    assert!(matches!(leg(&orders, false).unwrap().status, OrderStatus::Filled));
+    assert!(leg(&orders, true).is_none());
+
+    // The cancelled stop can't sell tokens that are already gone
+    harness.jupiter.set_price(&mint, 0.5).await;
+    harness.price_client.clear_cache().await;
+    harness.order_manager.run_cycle().await.unwrap();
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 1);
+    assert_eq!(harness.order_manager.monitor_stats().await.monitor_count, 0);
+}
+
+#[tokio::test]
+async fn test_stop_fill_cancels_take_profit() {
+    let (harness, mint, _) = oco_harness().await;
+
+    harness.jupiter.set_price(&mint, 0.8).await;
+    harness.price_client.clear_cache().await;
+    harness.order_manager.run_cycle().await.unwrap();
+
+    let orders = harness.order_manager.get_user_orders(USER_ID).await;
+    assert_eq!(orders.len(), 1);
+    let stop = leg(&orders, true).unwrap();
+    assert!(matches!(stop.status, OrderStatus::Filled));
+    assert_eq!(harness.order_manager.get_order_history(&stop.order_id).await.len(), 1);
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 1);
+}
+
+#[test]
+fn test_partial_fill_shrinks_sibling_proportionally() {
+    let oco = Order::create_oco(USER_ID, "MINT".to_string(), Decimal::new(9, 1), Decimal::new(12, 1), Decimal::from(1_000));
+    let (mut stop, mut take_profit) = oco.oco_legs().unwrap();
+    take_profit.execution_config.partial_fill_enabled = true;
+    // The stop only covers half the position
+    stop.base_amount = Decimal::from(500);
+    let (stop_id, take_profit_id) = (stop.order_id.clone(), take_profit.order_id.clone());
+    let mut orders: HashMap<String, Order> = [stop, take_profit].into_iter().map(|o| (o.order_id.clone(), o)).collect();
+
+    // 40% of the take-profit fills
+    let outcome = OrderManager::apply_fill(&mut orders, &take_profit_id, Decimal::from(400));
+    assert!(matches!(orders[&take_profit_id].status, OrderStatus::PartiallyFilled));
+    assert_eq!(orders[&take_profit_id].base_amount, Decimal::from(600));
+    assert_eq!(orders[&stop_id].base_amount, Decimal::from(300));
+    assert_eq!(outcome.resized.len(), 1);
+    assert!(outcome.cancelled.is_empty());
+
+    // The rest fills: the stop goes away
+    let outcome = OrderManager::apply_fill(&mut orders, &take_profit_id, Decimal::from(600));
+    assert!(matches!(orders[&take_profit_id].status, OrderStatus::Filled));
+    assert!(!orders.contains_key(&stop_id));
+    assert_eq!(outcome.cancelled[0].order_id, stop_id);
+    assert!(matches!(outcome.cancelled[0].status, OrderStatus::Cancelled));
+}
+
+#[test]
+fn test_partial_fill_without_opt_in_counts_as_filled() {
+    let oco = Order::create_oco(USER_ID, "MINT".to_string(), Decimal::new(9, 1), Decimal::new(12, 1), Decimal::from(1_000));
+    let (stop, take_profit) = oco.oco_legs().unwrap();
+    let (stop_id, take_profit_id) = (stop.order_id.clone(), take_profit.order_id.clone());
+    let mut orders: HashMap<String, Order> = [stop, take_profit].into_iter().map(|o| (o.order_id.clone(), o)).collect();
+
+    let outcome = OrderManager::apply_fill(&mut orders, &stop_id, Decimal::from(400));
+    assert!(matches!(orders[&stop_id].status, OrderStatus::Filled));
+    assert_eq!(outcome.cancelled.len(), 1);
+    assert!(!orders.contains_key(&take_profit_id));
+
+    // Only OCO orders have legs
+    assert!(Order::create_stop_loss(USER_ID, "MINT".to_string(), Decimal::ONE, Decimal::ONE).oco_legs().is_err());
+}
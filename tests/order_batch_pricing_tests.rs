@@ -0,0 +1,91 @@
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use crate::api::{ApiTier, JupiterAuthManager, JupiterPriceV3Client, JupiterV6Client};
+use crate::testkit::TestHarness;
+use crate::trading::{
+    ExecutionNotifier, NoticeKind, Order, OrderManager, OrderPollingConfig, OutgoingNotice,
+};
+
+const USER_ID: i64 = 772_001;
+
+/// A manager with its own price cache and notifier, flagging after three stale passes
+fn manager(harness: &TestHarness, notifier: Arc<ExecutionNotifier>) -> (OrderManager, Arc<JupiterPriceV3Client>) {
+    let prices = Arc::new(
+        JupiterPriceV3Client::new(Arc::new(JupiterAuthManager::new())).with_base_url(harness.jupiter.base_url()),
+    );
+    let manager = OrderManager::new(
+        Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())),
+        prices.clone(),
+        harness.db.clone(),
+        None,
+    )
+    .with_polling_config(OrderPollingConfig { max_stale_cycles: 3, ..OrderPollingConfig::default() })
+    .with_notifier(notifier);
+    (manager, prices)
+}
+
+/// One pass against uncached prices
+async fn uncached_cycle(manager: &OrderManager, prices: &JupiterPriceV3Client) {
+    prices.clear_cache().await;
+    manager.run_cycle().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_monitored_mints_are_priced_in_batches_and_flagged_when_missing() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let notifier = Arc::new(ExecutionNotifier::default());
+    let mut notices = notifier.subscribe();
+    let (manager, prices) = manager(&harness, notifier);
+
+    // Unknown mints price at 1.0 on the mock, so these stops sit well below
+    let mints: Vec<String> = (0..150).map(|_| Pubkey::new_unique().to_string()).collect();
+    let mut order_ids = Vec::new();
+    for mint in &mints {
+        let stop = Order::create_stop_loss(USER_ID, mint.clone(), Decimal::new(5, 1), Decimal::ONE);
+        order_ids.push(manager.create_order(stop).await.unwrap());
+    }
+
+    let before = harness.jupiter.call_count("price_v3").await;
+    uncached_cycle(&manager, &prices).await;
+    assert_eq!(harness.jupiter.call_count("price_v3").await - before, 2);
+
+    // Two mints drop out of the feed
+    for mint in &mints[..2] {
+        harness.jupiter.set_unlisted(mint, true).await;
+    }
+    for _ in 0..2 {
+        uncached_cycle(&manager, &prices).await;
+    }
+    assert_eq!(manager.monitor_stats().await.stale_monitors, 2);
+    assert!(manager.get_user_orders(USER_ID).await.iter().all(|o| !o.metadata.price_stale));
+
+    uncached_cycle(&manager, &prices).await;
+    let orders = manager.get_user_orders(USER_ID).await;
+    let flagged: Vec<&String> = orders.iter().filter(|o| o.metadata.price_stale).map(|o| &o.order_id).collect();
+    assert_eq!(flagged.len(), 2);
+    assert!(flagged.contains(&&order_ids[0]) && flagged.contains(&&order_ids[1]));
+
+    let mut warned = Vec::new();
+    while let Ok(OutgoingNotice::Notice(notice)) = notices.try_recv() {
+        if let NoticeKind::Warning { reason } = &notice.kind {
+            assert!(reason.contains("3 checks"));
+            warned.push(notice.order_id.clone().unwrap());
+        }
+    }
+    assert_eq!(warned.len(), 2);
+
+    // Further stale passes don't warn again, and a stale price never triggers
+    uncached_cycle(&manager, &prices).await;
+    assert!(notices.try_recv().is_err());
+    assert_eq!(manager.get_user_orders(USER_ID).await.len(), 150);
+
+    // Prices return and the flags clear
+    for mint in &mints[..2] {
+        harness.jupiter.set_unlisted(mint, false).await;
+    }
+    uncached_cycle(&manager, &prices).await;
+    assert!(manager.get_user_orders(USER_ID).await.iter().all(|o| !o.metadata.price_stale));
+    assert_eq!(manager.monitor_stats().await.stale_monitors, 0);
+}
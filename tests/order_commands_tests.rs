@@ -0,0 +1,93 @@
+use rust_decimal::Decimal;
+
+use crate::bot::handlers::OrderHandler;
+use crate::bot::price_entry::{PriceEntry, PriceEntryTarget, PriceLeg};
+use crate::testkit::TestHarness;
+use crate::trading::{Order, OrderType, ProtectiveClass, TokenResolver};
+use crate::utils::price_input::{parse_price, PriceIntent};
+
+const USER_ID: i64 = 758_001;
+
+fn order_entry(class: ProtectiveClass, price: &str, current_price: f64) -> PriceEntry {
+    PriceEntry {
+        user_id: USER_ID,
+        chat_id: USER_ID,
+        mint: TokenResolver::resolve("BONK").unwrap(),
+        symbol: "BONK".to_string(),
+        current_price,
+        target: PriceEntryTarget::Order { class, amount: 1_000.0 },
+        legs: vec![PriceLeg::new("stop", parse_price(price).unwrap(), PriceEntry::order_intent(class))],
+    }
+}
+
+#[test]
+fn test_order_entry_builds_stop_and_take_profit() {
+    let stop = order_entry(ProtectiveClass::Stop, "0.9", 1.0).protective_order(0.9).unwrap();
+    assert!(matches!(stop.order_type, OrderType::StopLoss { stop_price, .. } if stop_price == Decimal::new(9, 1)));
+    assert_eq!(stop.base_amount, Decimal::from(1_000));
+    assert_eq!(stop.user_id, USER_ID);
+
+    let take_profit = order_entry(ProtectiveClass::TakeProfit, "1.2", 1.0).protective_order(1.2).unwrap();
+    assert!(matches!(take_profit.order_type, OrderType::TakeProfit { target_price, .. } if target_price == Decimal::new(12, 1)));
+
+    // Only /order entries build orders, and /order entries never become chart requests
+    assert_eq!(PriceEntry::order_intent(ProtectiveClass::TakeProfit), PriceIntent::Above);
+    assert!(order_entry(ProtectiveClass::Stop, "0.9", 1.0).chart_request(0.9).is_none());
+    let mut alert = order_entry(ProtectiveClass::Stop, "0.9", 1.0);
+    alert.target = PriceEntryTarget::Alert { direction: None };
+    assert!(alert.protective_order(0.9).is_err());
+}
+
+#[test]
+fn test_ids_match_exactly_or_by_unique_prefix() {
+    let mut first = Order::create_stop_loss(USER_ID, "MINT".to_string(), Decimal::ONE, Decimal::ONE);
+    let mut second = Order::create_take_profit(USER_ID, "MINT".to_string(), Decimal::TWO, Decimal::ONE);
+    first.order_id = "abcd1234-0000".to_string();
+    second.order_id = "abcd9999-0000".to_string();
+    let orders = vec![first, second];
+
+    assert_eq!(OrderHandler::match_id(orders.clone(), "abcd1234-0000").unwrap().order_id, "abcd1234-0000");
+    assert_eq!(OrderHandler::match_id(orders.clone(), "abcd9").unwrap().order_id, "abcd9999-0000");
+    // Ambiguous, too short, or unknown
+    assert!(OrderHandler::match_id(orders.clone(), "abcd").is_none());
+    assert!(OrderHandler::match_id(orders.clone(), "abc").is_none());
+    assert!(OrderHandler::match_id(orders, "ffff").is_none());
+
+    assert_eq!(OrderHandler::short_id("abcd1234-0000"), "abcd1234");
+    assert_eq!(OrderHandler::short_id("abc"), "abc");
+}
+
+#[test]
+fn test_trigger_distance_is_signed_from_current_price() {
+    let stop = Order::create_stop_loss(USER_ID, "MINT".to_string(), Decimal::new(75, 2), Decimal::ONE);
+    assert_eq!(OrderHandler::describe_trigger(&stop, Some(1.0), "en"), "Trigger: $0.75 (25.0% below current)");
+    assert_eq!(OrderHandler::describe_trigger(&stop, None, "en"), "Trigger: $0.75");
+
+    let take_profit = Order::create_take_profit(USER_ID, "MINT".to_string(), Decimal::new(15, 1), Decimal::ONE);
+    assert_eq!(OrderHandler::describe_trigger(&take_profit, Some(1.2), "en"), "Trigger: $1.5 (25.0% above current)");
+}
+
+#[tokio::test]
+async fn test_reprice_moves_an_open_stop() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&bonk, 1.0).build().await.unwrap();
+    let stop = Order::create_stop_loss(USER_ID, bonk.clone(), Decimal::new(9, 1), Decimal::from(1_000));
+    let order_id = harness.order_manager.create_order(stop).await.unwrap();
+
+    let moved = harness.order_manager.reprice_order(&order_id, Decimal::new(8, 1)).await.unwrap();
+    assert!(matches!(moved.order_type, OrderType::StopLoss { stop_price, .. } if stop_price == Decimal::new(8, 1)));
+    assert_eq!(moved.trigger_price(), Some(Decimal::new(8, 1)));
+    let listed = harness.order_manager.get_user_orders(USER_ID).await;
+    assert_eq!(listed[0].trigger_price(), Some(Decimal::new(8, 1)));
+
+    // A price at 0.85 no longer fires the moved stop
+    harness.jupiter.set_price(&bonk, 0.85).await;
+    harness.price_client.clear_cache().await;
+    harness.order_manager.run_cycle().await.unwrap();
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 0);
+
+    // Cancelled orders can't be moved
+    assert!(harness.order_manager.cancel_order(&order_id).await.unwrap());
+    assert!(harness.order_manager.reprice_order(&order_id, Decimal::new(7, 1)).await.is_err());
+    assert!(harness.order_manager.get_user_orders(USER_ID).await.is_empty());
+}
@@ -0,0 +1,138 @@
+use rust_decimal::Decimal;
+use std::time::Duration;
+
+use crate::trading::{Order, OrderPollingConfig};
+
+const USER: i64 = 6980;
+
+#[test]
+fn test_interval_tightens_as_price_approaches_trigger() {
+    let config = OrderPollingConfig::default();
+    let stop = Order::create_stop_loss(USER, "MINT".to_string(), Decimal::from(90), Decimal::ONE);
+
+    let intervals: Vec<Duration> = [150, 110, 100, 95, 92, 91]
+        .iter()
+        .map(|price| {
+            let distance = stop.trigger_distance_pct(Decimal::from(*price)).unwrap();
+            config.interval_for_distance(distance)
+        })
+        .collect();
+
+    // Far from the stop: slow cadence
+    assert_eq!(intervals[0], config.far_interval);
+    assert_eq!(intervals[1], config.far_interval);
+    assert_eq!(intervals[2], config.far_interval);
+
+    // Inside the band it tightens monotonically
+    assert!(intervals[3] < config.far_interval && intervals[3] > config.base_interval);
+    assert!(intervals.windows(2).all(|w| w[1] <= w[0]), "{:?}", intervals);
+
+    // Within 2% of triggering the staleness bound holds
+    assert!(intervals[4] <= config.max_near_staleness);
+    assert!(intervals[5] <= config.max_near_staleness);
+}
+
+#[test]
+fn test_near_cadence_never_exceeds_max_staleness() {
+    let config = OrderPollingConfig {
+        base_interval: Duration::from_secs(5),
+        max_near_staleness: Duration::from_secs(1),
+        ..OrderPollingConfig::default()
+    };
+
+    assert_eq!(config.interval_for_distance(0.0), Duration::from_secs(1));
+    assert_eq!(config.interval_for_distance(2.0), Duration::from_secs(1));
+    assert!(config.interval_for_distance(2.5) < Duration::from_secs(3));
+    assert_eq!(config.interval_for_distance(50.0), Duration::from_secs(15));
+}
+
+#[test]
+fn test_trigger_distance() {
+    let take_profit = Order::create_take_profit(USER, "MINT".to_string(), Decimal::from(110), Decimal::ONE);
+    assert_eq!(take_profit.trigger_distance_pct(Decimal::from(100)), Some(10.0));
+    assert_eq!(take_profit.trigger_distance_pct(Decimal::ZERO), None);
+
+    // No price level to measure against
+    let mut timed = take_profit.clone();
+    timed.trigger_conditions.price_conditions.clear();
+    assert_eq!(timed.trigger_distance_pct(Decimal::from(100)), None);
+}
+
+/// Feed `prices` through a trailing stop, recording the stop and whether it fired after each
+fn trail(order: &mut Order, prices: &[i64]) -> Vec<(Option<Decimal>, bool)> {
+    prices.iter()
+        .map(|price| {
+            let price = Decimal::from(*price);
+            order.observe_trailing_price(price);
+            (order.trailing.stop_price, order.trailing_stop_triggered(price).unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn test_trailing_stop_ratchets_up_never_down() {
+    let mut order = Order::create_trailing_stop(USER, "MINT".to_string(), 10.0, None, Decimal::ONE);
+    let steps = trail(&mut order, &[100, 110, 105, 120, 115, 108, 107]);
+
+    let stops: Vec<Decimal> = steps.iter().map(|(stop, _)| stop.unwrap()).collect();
+    assert_eq!(stops[..6], [90, 99, 99, 108, 108, 108].map(Decimal::from));
+    assert!(stops.windows(2).all(|w| w[1] >= w[0]), "{:?}", stops);
+
+    // Pullbacks above the stop hold; the drop through 108 fires
+    assert!(steps[..5].iter().all(|(_, fired)| !fired));
+    assert!(steps[5].1 && steps[6].1);
+    assert_eq!(order.trailing.extreme_price, Some(Decimal::from(120)));
+    assert_eq!(order.trigger_distance_pct(Decimal::from(120)), Some(10.0));
+}
+
+#[test]
+fn test_trailing_stop_waits_for_activation() {
+    let mut order = Order::create_trailing_stop(USER, "MINT".to_string(), 10.0, Some(Decimal::from(150)), Decimal::ONE);
+
+    // Nothing trails, and nothing fires, below the activation price
+    let steps = trail(&mut order, &[100, 80, 140]);
+    assert!(steps.iter().all(|(stop, fired)| stop.is_none() && !fired));
+    assert!(!order.trailing.activated);
+    assert_eq!(order.trigger_distance_pct(Decimal::from(100)), Some(50.0));
+
+    let steps = trail(&mut order, &[150, 160, 145, 143]);
+    assert!(order.trailing.activated);
+    assert_eq!(steps[0].0, Some(Decimal::from(135)));
+    assert_eq!(steps[1].0, Some(Decimal::from(144)));
+    assert!(!steps[2].1 && steps[3].1);
+}
+
+#[test]
+fn test_trailing_stop_fixed_amount_and_short_side() {
+    use crate::trading::{OrderSide, OrderType};
+
+    let mut long = Order::create_trailing_stop(USER, "MINT".to_string(), 0.0, None, Decimal::ONE);
+    long.order_type = OrderType::TrailingStop {
+        trailing_amount: Decimal::from(5),
+        trailing_percentage: 50.0,
+        activation_price: None,
+        side: OrderSide::Sell,
+    };
+    let steps = trail(&mut long, &[100, 104, 101, 99]);
+    assert_eq!(steps.iter().map(|(stop, _)| stop.unwrap()).collect::<Vec<_>>(), [95, 99, 99, 99].map(Decimal::from));
+    assert!(steps[3].1);
+
+    // A short trails the low and fires on the way up
+    let mut short = long.clone();
+    short.trailing = Default::default();
+    short.order_type = OrderType::TrailingStop {
+        trailing_amount: Decimal::from(5),
+        trailing_percentage: 0.0,
+        activation_price: Some(Decimal::from(95)),
+        side: OrderSide::Buy,
+    };
+    let steps = trail(&mut short, &[100, 95, 90, 93, 96]);
+    assert_eq!(steps[0].0, None);
+    let stops: Vec<Decimal> = steps[1..].iter().map(|(stop, _)| stop.unwrap()).collect();
+    assert_eq!(stops, [100, 95, 95, 95].map(Decimal::from));
+    assert!(!steps[3].1 && steps[4].1);
+
+    // Other order types aren't trailing stops
+    let fixed = Order::create_stop_loss(USER, "MINT".to_string(), Decimal::from(90), Decimal::ONE);
+    assert_eq!(fixed.trailing_stop_triggered(Decimal::from(80)), None);
+}
@@ -0,0 +1,69 @@
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+use crate::api::{ApiTier, JupiterV6Client};
+use crate::testkit::TestHarness;
+use crate::trading::{Order, OrderManager, OrderStatus, OrderType, TokenResolver};
+
+const USER_ID: i64 = 754_001;
+
+/// A fresh manager over the harness database, as after a restart
+fn restarted(harness: &TestHarness) -> OrderManager {
+    OrderManager::new(
+        Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())),
+        harness.price_client.clone(),
+        harness.db.clone(),
+        None,
+    )
+}
+
+#[tokio::test]
+async fn test_stop_loss_survives_restart() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&bonk, 0.00002).build().await.unwrap();
+
+    let stop = Order::create_stop_loss(USER_ID, bonk.clone(), Decimal::new(15, 6), Decimal::from(1_000_000));
+    let order_id = harness.order_manager.create_order(stop).await.unwrap();
+
+    let manager = restarted(&harness);
+    assert_eq!(manager.load_active_orders().await.unwrap(), 1);
+
+    let orders = manager.get_user_orders(USER_ID).await;
+    assert_eq!(orders.len(), 1);
+    let order = &orders[0];
+    assert_eq!(order.order_id, order_id);
+    assert!(matches!(order.status, OrderStatus::Pending));
+    assert!(matches!(order.order_type, OrderType::StopLoss { stop_price, .. } if stop_price == Decimal::new(15, 6)));
+    assert_eq!(order.trigger_conditions.price_conditions[0].target_value, Decimal::new(15, 6));
+    assert_eq!(order.base_amount, Decimal::from(1_000_000));
+
+    // Monitored again, not just listed
+    assert_eq!(manager.monitor_stats().await.monitored_orders, 1);
+}
+
+#[tokio::test]
+async fn test_terminal_orders_stay_closed_after_restart() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&bonk, 0.00002).build().await.unwrap();
+
+    let cancelled = harness.order_manager
+        .create_order(Order::create_stop_loss(USER_ID, bonk.clone(), Decimal::new(15, 6), Decimal::ONE))
+        .await
+        .unwrap();
+    assert!(harness.order_manager.cancel_order(&cancelled).await.unwrap());
+
+    // Lapsed while the bot was down
+    let mut lapsed = Order::create_take_profit(USER_ID, bonk.clone(), Decimal::new(3, 5), Decimal::ONE);
+    lapsed.expires_at = Some(Utc::now() - Duration::minutes(1));
+    harness.order_manager.create_order(lapsed).await.unwrap();
+
+    let manager = restarted(&harness);
+    assert_eq!(manager.load_active_orders().await.unwrap(), 0);
+    assert!(manager.get_user_orders(USER_ID).await.is_empty());
+    assert_eq!(manager.monitor_stats().await.monitored_orders, 0);
+
+    // The expiry was written back, so the next restart skips it too
+    let again = restarted(&harness);
+    assert_eq!(again.load_active_orders().await.unwrap(), 0);
+}
@@ -0,0 +1,155 @@
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::bot::handlers::TradingHandler;
+use crate::errors::{BotError, Result};
+use crate::trading::{
+    ExitDenomination, PanicDesk, PanicSellOutcome, PanicSeller, PanicStage, PhraseCheck, Position, TradeResult, PANIC_PHRASE,
+    SELL_ATTEMPTS,
+};
+
+const USER: i64 = 31_337;
+const WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+fn position(symbol: &str, amount: f64, value_usd: f64) -> Position {
+    Position {
+        token: symbol.to_string(),
+        symbol: symbol.to_string(),
+        mint: format!("{}-mint", symbol),
+        amount,
+        value_usd,
+        pnl_percentage: -40.0,
+        average_buy_price: 0.0,
+        current_price: value_usd / amount,
+        sort_key: 0,
+        last_updated: Utc::now(),
+        untracked_entry: false,
+    }
+}
+
+/// Quotes 0.1 SOL per $15; each symbol fails its first `failures` sells
+#[derive(Default)]
+struct MockEngine {
+    failures: HashMap<&'static str, u32>,
+    unquotable: Vec<&'static str>,
+    sells: Mutex<Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl PanicSeller for MockEngine {
+    async fn quote(&self, _user_wallet: &str, position: &Position) -> Result<f64> {
+        if self.unquotable.contains(&position.symbol.as_str()) {
+            return Err(BotError::jupiter_api("no route".to_string()).into());
+        }
+        Ok(position.value_usd / 150.0)
+    }
+
+    async fn sell(&self, _user_wallet: &str, position: &Position, _exit: ExitDenomination) -> Result<TradeResult> {
+        let attempt = {
+            let mut sells = self.sells.lock().unwrap();
+            sells.push(position.symbol.clone());
+            sells.iter().filter(|s| **s == position.symbol).count() as u32
+        };
+        if attempt <= self.failures.get(position.symbol.as_str()).copied().unwrap_or(0) {
+            return Err(BotError::trading(format!("{} sell reverted", position.symbol)).into());
+        }
+        Ok(TradeResult::sell(format!("sig-{}", position.symbol), position.amount, position.value_usd / 150.0, position.current_price))
+    }
+}
+
+fn desk(engine: MockEngine) -> (Arc<MockEngine>, PanicDesk) {
+    let engine = Arc::new(engine);
+    (engine.clone(), PanicDesk::new(engine, 1.0))
+}
+
+#[tokio::test]
+async fn test_nothing_sells_without_the_button_and_the_phrase() {
+    let (engine, desk) = desk(MockEngine::default());
+    let positions = vec![position("BONK", 1_000_000.0, 30.0), position("DUST", 5.0, 0.2)];
+
+    let plan = desk.prepare(USER, WALLET, &positions, ExitDenomination::Sol).await.unwrap();
+    assert_eq!((plan.quotes.len(), plan.skipped_dust), (1, 1));
+    assert_eq!(plan.stage, PanicStage::Button);
+
+    // Typing the phrase before tapping the button does nothing
+    assert!(matches!(desk.check_phrase(USER, PANIC_PHRASE).await, PhraseCheck::NotWaiting));
+
+    // Anything but the exact phrase cancels
+    assert_eq!(desk.arm(USER).await.unwrap().stage, PanicStage::Phrase);
+    assert!(matches!(desk.check_phrase(USER, "sell everything").await, PhraseCheck::Mismatch));
+    assert!(desk.arm(USER).await.is_err());
+    assert!(matches!(desk.check_phrase(USER, PANIC_PHRASE).await, PhraseCheck::NotWaiting));
+
+    // Another user can't confirm this one's panic
+    desk.prepare(USER, WALLET, &positions, ExitDenomination::Sol).await.unwrap();
+    desk.arm(USER).await.unwrap();
+    assert!(matches!(desk.check_phrase(USER + 1, PANIC_PHRASE).await, PhraseCheck::NotWaiting));
+    let PhraseCheck::Confirmed(plan) = desk.check_phrase(USER, &format!("  {}\n", PANIC_PHRASE)).await else {
+        panic!("the phrase should confirm");
+    };
+    assert!(engine.sells.lock().unwrap().is_empty(), "confirmation gating must not sell");
+    assert!(!plan.is_expired(Utc::now()));
+    assert!(plan.is_expired(plan.expires_at + Duration::seconds(1)));
+
+    // Only dust: nothing to plan
+    assert!(desk.prepare(USER, WALLET, &positions[1..], ExitDenomination::Sol).await.is_err());
+}
+
+#[tokio::test]
+async fn test_failures_are_retried_once_and_the_report_aggregates() {
+    let (engine, desk) = desk(MockEngine {
+        failures: HashMap::from([("WIF", 1), ("POPCAT", 5)]),
+        unquotable: vec!["POPCAT"],
+        ..MockEngine::default()
+    });
+    let positions = vec![
+        position("BONK", 1_000_000.0, 30.0),
+        position("WIF", 20.0, 45.0),
+        position("POPCAT", 100.0, 15.0),
+    ];
+
+    let plan = desk.prepare(USER, WALLET, &positions, ExitDenomination::Sol).await.unwrap();
+    assert!(plan.quotes[2].quote.as_ref().unwrap_err().contains("no route"));
+    assert!((plan.quoted_sol() - 0.5).abs() < 1e-9, "{}", plan.quoted_sol());
+
+    let report = desk.execute(&plan).await;
+    assert_eq!((report.sold(), report.failed()), (2, 1));
+    assert!((report.total_sol() - 0.5).abs() < 1e-9, "{}", report.total_sol());
+
+    let symbols: Vec<&str> = report.outcomes.iter().map(|o| o.position().symbol.as_str()).collect();
+    assert_eq!(symbols, ["BONK", "WIF", "POPCAT"]);
+    assert!(matches!(report.outcomes[0], PanicSellOutcome::Sold { attempts: 1, .. }));
+    assert!(matches!(report.outcomes[1], PanicSellOutcome::Sold { attempts: 2, .. }));
+    let PanicSellOutcome::Failed { error, attempts, .. } = &report.outcomes[2] else { panic!("POPCAT should fail") };
+    assert_eq!(*attempts, SELL_ATTEMPTS);
+    assert!(error.contains("POPCAT sell reverted"), "{}", error);
+
+    // One retry each, never more
+    let sells = engine.sells.lock().unwrap();
+    assert_eq!(sells.iter().filter(|s| *s == "POPCAT").count(), 2);
+    assert_eq!(sells.len(), 5);
+
+    let text = TradingHandler::panic_report_text(&report, "en");
+    assert!(text.starts_with("🚨 Panic sell finished: ✅ 2 sold · ❌ 1 failed"), "{}", text);
+    assert!(text.contains("✅ BONK · 0.200000 SOL\n"), "{}", text);
+    assert!(text.contains("✅ WIF · 0.300000 SOL (on retry)"), "{}", text);
+    assert!(text.contains("❌ POPCAT · "), "{}", text);
+    assert!(text.contains("(tried 2×)"), "{}", text);
+    assert!(text.contains("Recovered: 0.500000 SOL"), "{}", text);
+}
+
+#[tokio::test]
+async fn test_preview_lists_quotes_dust_and_mev_state() {
+    let (_, desk) = desk(MockEngine { unquotable: vec!["WIF"], ..MockEngine::default() });
+    let positions = vec![position("BONK", 1_000_000.0, 30.0), position("WIF", 20.0, 45.0), position("DUST", 5.0, 0.2)];
+    let plan = desk.prepare(USER, WALLET, &positions, ExitDenomination::Sol).await.unwrap();
+
+    let text = TradingHandler::panic_preview_text(&plan, true, 1.0, "en");
+    assert!(text.starts_with("🚨 Panic sell 2 position(s) into SOL"), "{}", text);
+    assert!(text.contains("BONK ≈ 0.200000 SOL"), "{}", text);
+    assert!(text.contains("WIF no quote"), "{}", text);
+    assert!(text.contains("1 position(s) under $1.00 left alone"), "{}", text);
+    assert!(text.contains("🛡️ MEV protection on"), "{}", text);
+    assert!(text.contains(PANIC_PHRASE), "{}", text);
+}
@@ -0,0 +1,178 @@
+use crate::testkit::TestHarness;
+use crate::trading::{simulate_fill, PaperLedger, TokenResolver, TradeType, TradingMode};
+use solana_sdk::signature::Signer;
+use std::time::Duration;
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USER_ID: i64 = 761_001;
+
+fn approx(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+async fn paper_harness() -> (TestHarness, String, String) {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .price(WSOL_MINT, 200.0)
+        .price(&bonk, 0.00002)
+        .build()
+        .await
+        .unwrap();
+    let wallet = harness.register_user(USER_ID, 5.0).await.unwrap().pubkey().to_string();
+    harness.trading_engine.set_trading_mode(wallet.clone(), TradingMode::Paper).await.unwrap();
+    (harness, wallet, bonk)
+}
+
+#[test]
+fn simulated_fills_move_against_the_trader() {
+    assert!(approx(simulate_fill(1.0, TradeType::Buy, 50), 1.005));
+    assert!(approx(simulate_fill(1.0, TradeType::Sell, 50), 0.995));
+    assert!(approx(simulate_fill(1.0, TradeType::Sell, 0), 1.0));
+}
+
+#[test]
+fn ledger_rejects_buys_beyond_its_balance() {
+    let mut ledger = PaperLedger::new(1.0);
+
+    assert!(ledger.buy("mint", "TKN", 1.5, 100.0).is_err());
+    assert!(approx(ledger.sol, 1.0));
+    assert!(ledger.positions.is_empty());
+}
+
+#[test]
+fn partial_sell_keeps_the_cost_basis_proportional() {
+    let mut ledger = PaperLedger::new(10.0);
+    ledger.buy("mint", "TKN", 2.0, 1_000.0).unwrap();
+
+    let sale = ledger.sell("mint", 50.0, 0.003).unwrap();
+
+    assert!(approx(sale.tokens_sold, 500.0));
+    assert!(approx(sale.sol_received, 1.5));
+    assert!(approx(sale.pnl_percentage, 50.0));
+    assert!(approx(ledger.holding("mint"), 500.0));
+    assert!(approx(ledger.positions["mint"].cost_sol, 1.0));
+    assert!(approx(ledger.realized_pnl_sol, 0.5));
+    assert!(approx(ledger.sol, 9.5));
+}
+
+#[tokio::test]
+async fn paper_buy_never_builds_a_transaction_to_sign() {
+    let (harness, wallet, bonk) = paper_harness().await;
+
+    let result = harness.trading_engine
+        .buy_with_rebate(wallet.clone(), "BONK".to_string(), 0.5)
+        .await
+        .unwrap();
+
+    assert!(result.execution.simulated);
+    assert!(result.tx_signature.starts_with("PAPER-"));
+    assert_eq!(result.execution.route.as_ref().unwrap().venues, vec!["Paper"]);
+    // No quote, no swap transaction, nothing submitted: there is nothing for a signer to see
+    assert_eq!(harness.jupiter.call_count("legacy_quote").await, 0);
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+    assert!(harness.jupiter.last_swap_transaction().await.is_none());
+    assert!(harness.rpc.submitted().await.is_empty());
+
+    // 0.00002 / 200 SOL per token, filled 50 bps worse
+    let fill = 0.00002 / 200.0 * 1.005;
+    let ledger = harness.trading_engine.paper_ledger(wallet).await.unwrap();
+    assert!(approx(ledger.sol, 9.5));
+    assert!((ledger.holding(&bonk) - 0.5 / fill).abs() < 1e-3);
+}
+
+#[tokio::test]
+async fn paper_sell_credits_the_virtual_balance() {
+    let (harness, wallet, bonk) = paper_harness().await;
+    harness.trading_engine.buy_with_rebate(wallet.clone(), "BONK".to_string(), 1.0).await.unwrap();
+
+    // BONK doubles
+    harness.jupiter.set_price(&bonk, 0.00004).await;
+    harness.price_client.clear_cache().await;
+
+    let result = harness.trading_engine
+        .sell_with_rebate(wallet.clone(), "BONK".to_string(), 100.0)
+        .await
+        .unwrap();
+
+    assert!(result.execution.simulated);
+    // 2x less 50 bps on each side
+    assert!(approx(result.sol_received, 2.0 * 0.995 / 1.005));
+    assert!(result.pnl_percentage > 95.0);
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+
+    let ledger = harness.trading_engine.paper_ledger(wallet.clone()).await.unwrap();
+    assert!(ledger.positions.is_empty());
+    assert!(approx(ledger.sol, 9.0 + result.sol_received));
+    assert_eq!(ledger.trades, 2);
+
+    let balance = harness.trading_engine.get_balance(wallet).await.unwrap();
+    assert!(approx(balance.sol, ledger.sol));
+}
+
+#[tokio::test]
+async fn paper_positions_come_from_the_ledger() {
+    let (harness, wallet, bonk) = paper_harness().await;
+    harness.trading_engine.buy_with_rebate(wallet.clone(), "BONK".to_string(), 0.5).await.unwrap();
+
+    let positions = harness.trading_engine.get_positions(wallet).await.unwrap();
+
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].mint, bonk);
+    // Valued at the unslipped price, so slightly under cost
+    assert!(positions[0].pnl_percentage < 0.0 && positions[0].pnl_percentage > -1.0);
+}
+
+#[tokio::test]
+async fn reset_restores_the_starting_balance() {
+    let (harness, wallet, _) = paper_harness().await;
+    harness.trading_engine.buy_with_rebate(wallet.clone(), "BONK".to_string(), 2.0).await.unwrap();
+
+    let ledger = harness.trading_engine.reset_paper_ledger(wallet.clone()).await.unwrap();
+
+    assert!(approx(ledger.sol, harness.config.paper_starting_balance_sol));
+    assert!(ledger.positions.is_empty());
+    assert_eq!(ledger.trades, 0);
+}
+
+#[tokio::test]
+async fn switching_back_to_live_trades_on_chain() {
+    let (harness, wallet, _) = paper_harness().await;
+
+    harness.trading_engine.set_trading_mode(wallet.clone(), TradingMode::Live).await.unwrap();
+    let result = harness.trading_engine
+        .buy_with_rebate(wallet.clone(), "BONK".to_string(), 0.5)
+        .await
+        .unwrap();
+
+    assert!(!result.execution.simulated);
+    assert_eq!(harness.jupiter.call_count("swap").await, 1);
+    assert_eq!(harness.trading_engine.trading_mode(wallet).await.unwrap(), TradingMode::Live);
+}
+
+#[tokio::test]
+async fn paper_command_marks_trades_in_telegram() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder()
+        .price(WSOL_MINT, 200.0)
+        .price(&bonk, 0.00002)
+        .build()
+        .await
+        .unwrap();
+    harness.register_user(USER_ID, 5.0).await.unwrap();
+
+    harness.start_bot();
+    harness.telegram.inject_message(USER_ID, "/paper on").await;
+    harness.telegram
+        .wait_for_text(USER_ID, "Paper trading on", Duration::from_secs(10))
+        .await
+        .expect("bot should confirm paper mode");
+
+    harness.telegram.inject_message(USER_ID, "/buy BONK 0.5").await;
+    let reply = harness.telegram
+        .wait_for_text(USER_ID, "Buy Order Executed", Duration::from_secs(10))
+        .await
+        .expect("bot should confirm the paper buy");
+    assert!(reply.contains("PAPER"));
+    assert!(!reply.contains("solscan"));
+    assert_eq!(harness.jupiter.call_count("swap").await, 0);
+}
@@ -0,0 +1,87 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::portfolio::{PortfolioAnalyzer, TokenHolding};
+use crate::trading::{Token2022Manager, TransferFee};
+
+/// Raw Token-2022 mint data carrying a TransferFeeConfig extension
+fn mint_with_fee(older: (u64, u64, u16), newer: (u64, u64, u16)) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data.push(1); // account type: mint
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&108u16.to_le_bytes());
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(&[0u8; 32]); // no withdraw authority
+    data.extend_from_slice(&42u64.to_le_bytes());
+    for (epoch, maximum_fee, basis_points) in [older, newer] {
+        data.extend_from_slice(&epoch.to_le_bytes());
+        data.extend_from_slice(&maximum_fee.to_le_bytes());
+        data.extend_from_slice(&basis_points.to_le_bytes());
+    }
+    data
+}
+
+fn holding(balance: f64, price_usd: f64, transfer_fee: Option<TransferFee>) -> TokenHolding {
+    TokenHolding {
+        mint_address: Pubkey::new_unique().to_string(),
+        symbol: "FEE".to_string(),
+        name: "Fee Token".to_string(),
+        balance,
+        decimals: 6,
+        value_usd: balance * price_usd,
+        value_sol: 0.0,
+        price_usd,
+        price_change_24h: None,
+        logo_uri: None,
+        is_verified: false,
+        transfer_fee,
+        net_value_after_fees: 0.0,
+    }
+}
+
+#[test]
+fn test_mint_transfer_fee_config_is_read_from_extension_data() {
+    let manager = Token2022Manager::new();
+    let data = mint_with_fee((0, u64::MAX, 300), (600, 5_000_000, 500));
+
+    let config = manager.parse_mint_transfer_fee(&data).unwrap().unwrap();
+    assert_eq!(config.withheld_amount, 42);
+    assert!(config.transfer_fee_config_authority.is_some());
+    assert!(config.withdraw_withheld_authority.is_none());
+    assert_eq!(config.fee_at(599).transfer_fee_basis_points, 300);
+    assert_eq!(config.fee_at(600), &TransferFee { epoch: 600, maximum_fee: 5_000_000, transfer_fee_basis_points: 500 });
+
+    // A plain mint has no extensions
+    assert!(manager.parse_mint_transfer_fee(&[0u8; 82]).unwrap().is_none());
+    // A truncated extension is an error rather than a zero fee
+    assert!(manager.parse_mint_transfer_fee(&data[..200]).is_err());
+}
+
+#[test]
+fn test_net_value_takes_the_transfer_fee_out_of_the_exit() {
+    let analyzer = PortfolioAnalyzer;
+    let fee = TransferFee { epoch: 0, maximum_fee: u64::MAX, transfer_fee_basis_points: 300 };
+    let fee_token = holding(1_000.0, 2.0, Some(fee.clone()));
+
+    assert!((analyzer.transfer_fee_tokens(&fee_token) - 30.0).abs() < 1e-9);
+    assert!((analyzer.net_value_after_fees(&fee_token) - 1_940.0).abs() < 1e-9);
+
+    // The fee is capped at the mint's maximum: 5 tokens at 6 decimals
+    let capped = holding(1_000.0, 2.0, Some(TransferFee { maximum_fee: 5_000_000, ..fee }));
+    assert!((analyzer.net_value_after_fees(&capped) - 1_990.0).abs() < 1e-9);
+
+    // Tokens without the extension keep their full value
+    let plain = holding(1_000.0, 2.0, None);
+    assert_eq!(analyzer.net_value_after_fees(&plain), 2_000.0);
+}
+
+#[test]
+fn test_pnl_is_reported_gross_and_net_of_the_fee() {
+    let analyzer = PortfolioAnalyzer;
+    let fee = TransferFee { epoch: 0, maximum_fee: u64::MAX, transfer_fee_basis_points: 300 };
+    let pnl = analyzer.position_pnl(&holding(1_000.0, 2.0, Some(fee)), 1_500.0);
+
+    assert!((pnl.gross_usd - 500.0).abs() < 1e-9);
+    assert!((pnl.net_usd - 440.0).abs() < 1e-9);
+    assert!((pnl.gross_percentage - 100.0 / 3.0).abs() < 1e-9);
+    assert!((pnl.net_percentage - 29.333_333_333).abs() < 1e-6);
+}
@@ -0,0 +1,153 @@
+use crate::bot::handlers::TradingHandler;
+use crate::trading::{hide_dust, on_chain_balances, reconcile, BalanceChange, Position};
+use chrono::{TimeZone, Utc};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+const WIF: &str = "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm";
+const JUP: &str = "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN";
+
+fn position(mint: &str, symbol: &str, amount: f64, price: f64) -> Position {
+    Position {
+        token: symbol.to_string(),
+        symbol: symbol.to_string(),
+        mint: mint.to_string(),
+        amount,
+        value_usd: amount * price,
+        pnl_percentage: 10.0,
+        average_buy_price: price / 1.1,
+        current_price: price,
+        sort_key: 1,
+        last_updated: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        untracked_entry: false,
+    }
+}
+
+/// A token account as `getTokenAccountsByOwner` returns it with jsonParsed encoding
+fn token_account(mint: &str, amount: &str) -> Value {
+    json!({
+        "type": "account",
+        "info": {
+            "isNative": false,
+            "mint": mint,
+            "owner": "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+            "state": "initialized",
+            "tokenAmount": { "amount": "0", "decimals": 5, "uiAmount": amount.parse::<f64>().unwrap(), "uiAmountString": amount },
+        },
+    })
+}
+
+#[test]
+fn test_balances_sum_accounts_per_mint_and_skip_empty_ones() {
+    let accounts = vec![
+        token_account(BONK, "1000"),
+        token_account(BONK, "250.5"),
+        token_account(WIF, "0"),
+        json!({ "type": "mint", "info": {} }),
+    ];
+    let balances = on_chain_balances(&accounts);
+
+    assert_eq!(balances.len(), 1);
+    assert!((balances[BONK] - 1_250.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_diff_covers_added_removed_and_changed_balances() {
+    let now = Utc::now();
+    let tracked = vec![
+        position(BONK, "BONK", 1_000.0, 0.00002),
+        position(WIF, "WIF", 50.0, 2.0),
+        position(JUP, "JUP", 10.0, 0.5),
+    ];
+    // BONK partly sold elsewhere, WIF gone, JUP untouched, a new token arrived
+    let airdrop = "AirDrop1111111111111111111111111111111111111";
+    let on_chain = BTreeMap::from([
+        (BONK.to_string(), 400.0),
+        (JUP.to_string(), 10.0),
+        (airdrop.to_string(), 7.0),
+    ]);
+
+    let result = reconcile(&tracked, &on_chain, now);
+    assert!(!result.is_clean());
+    assert_eq!(result.changes, vec![
+        BalanceChange::Changed { mint: BONK.to_string(), tracked: 1_000.0, on_chain: 400.0 },
+        BalanceChange::Removed { mint: WIF.to_string(), tracked: 50.0 },
+        BalanceChange::Added { mint: airdrop.to_string(), amount: 7.0 },
+    ]);
+
+    // Changed keeps its cost basis, removed is dropped, unchanged is left alone
+    let bonk = result.positions.iter().find(|p| p.mint == BONK).unwrap();
+    assert_eq!(bonk.amount, 400.0);
+    assert!((bonk.value_usd - 0.008).abs() < 1e-12);
+    assert_eq!(bonk.average_buy_price, tracked[0].average_buy_price);
+    assert_eq!(bonk.last_updated, now);
+    assert!(!result.positions.iter().any(|p| p.mint == WIF));
+    let jup = result.positions.iter().find(|p| p.mint == JUP).unwrap();
+    assert_eq!(jup.last_updated, tracked[2].last_updated);
+
+    // The external token is flagged, with no cost basis to compute P&L from
+    let added = result.positions.iter().find(|p| p.mint == airdrop).unwrap();
+    assert!(added.untracked_entry);
+    assert_eq!(added.amount, 7.0);
+    assert_eq!(added.average_buy_price, 0.0);
+    assert_eq!(added.sort_key, 2);
+}
+
+#[test]
+fn test_matching_balances_are_clean_and_prices_skip_unknown_cost_basis() {
+    let tracked = vec![position(BONK, "BONK", 1_000.0, 0.00002)];
+    let on_chain = BTreeMap::from([(BONK.to_string(), 1_000.0 + 1e-10), (WIF.to_string(), 3.0)]);
+
+    let clean = reconcile(&tracked, &BTreeMap::from([(BONK.to_string(), 1_000.0)]), Utc::now());
+    assert!(clean.is_clean());
+    assert_eq!(clean.positions, tracked);
+
+    let mut result = reconcile(&tracked, &on_chain, Utc::now());
+    assert_eq!(result.changes.len(), 1, "{:?}", result.changes);
+    result.apply_prices(&BTreeMap::from([(BONK.to_string(), 0.00003), (WIF.to_string(), 2.0)]));
+
+    let bonk = result.positions.iter().find(|p| p.mint == BONK).unwrap();
+    assert!((bonk.value_usd - 0.03).abs() < 1e-9);
+    assert!((bonk.pnl_percentage - 65.0).abs() < 1e-6, "{}", bonk.pnl_percentage);
+    let wif = result.positions.iter().find(|p| p.mint == WIF).unwrap();
+    assert_eq!(wif.value_usd, 6.0);
+    assert_eq!(wif.pnl_percentage, 0.0);
+}
+
+#[test]
+fn test_dust_is_hidden_from_the_view_but_kept_in_the_data() {
+    let mut unpriced = position(JUP, "JUP", 5.0, 0.0);
+    unpriced.untracked_entry = true;
+    let positions = vec![
+        position(BONK, "BONK", 10.0, 0.00002),
+        position(WIF, "WIF", 50.0, 2.0),
+        unpriced,
+    ];
+
+    let (visible, hidden) = hide_dust(&positions, 1.0);
+    assert_eq!(hidden, 1);
+    assert_eq!(visible.iter().map(|p| p.symbol.as_str()).collect::<Vec<_>>(), vec!["WIF", "JUP"]);
+    assert_eq!(positions.len(), 3);
+
+    assert_eq!(TradingHandler::dust_note(0, 1.0, "en"), "");
+    assert!(TradingHandler::dust_note(hidden, 1.0, "en").contains("1 balance under"));
+    assert!(TradingHandler::position_entry(visible[1], "en").contains("Untracked entry, cost basis unknown"));
+    assert!(!TradingHandler::position_entry(visible[0], "en").contains("Untracked"));
+}
+
+#[test]
+fn test_sync_summary_lists_each_correction() {
+    let tracked = vec![position(BONK, "BONK", 1_000.0, 0.00002), position(WIF, "WIF", 50.0, 2.0)];
+    let on_chain = BTreeMap::from([(BONK.to_string(), 400.0), (JUP.to_string(), 3.0)]);
+    let result = reconcile(&tracked, &on_chain, Utc::now());
+
+    let summary = TradingHandler::sync_summary(&result, "en");
+    assert!(summary.contains("3 corrected"), "{}", summary);
+    assert!(summary.contains("✏️ BONK"), "{}", summary);
+    assert!(summary.contains("➖ ") && summary.contains(" no longer held"), "{}", summary);
+    assert!(summary.contains("➕ 3 ") && summary.contains("untracked entry (cost basis unknown)"), "{}", summary);
+
+    let clean = reconcile(&tracked[..1], &BTreeMap::from([(BONK.to_string(), 1_000.0)]), Utc::now());
+    assert!(TradingHandler::sync_summary(&clean, "en").starts_with("✅"));
+}
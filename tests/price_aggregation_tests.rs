@@ -0,0 +1,273 @@
+use chrono::{Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::api::{ApiTier, JupiterAuthManager, JupiterPriceV3Client, JupiterV6Client};
+use crate::errors::{BotError, Result};
+use crate::testkit::TestHarness;
+use crate::trading::{Order, OrderManager, OrderStatus, TokenResolver};
+use crate::websocket::{
+    AggregationConfig, DefaultPriceAggregator, PriceAggregator, PriceSource, PriceStreamManager, PriceUpdate,
+    QuoteSource, UpdateType, WebSocketClient, WebSocketConfig,
+};
+
+const USER_ID: i64 = 794_001;
+
+fn quote(source: PriceSource, price: f64, age_secs: i64) -> PriceUpdate {
+    PriceUpdate {
+        symbol: "SOL".to_string(),
+        price: Decimal::from_f64(price).unwrap(),
+        timestamp: Utc::now() - Duration::seconds(age_secs),
+        volume: None,
+        source,
+        update_type: UpdateType::Quote,
+        metadata: None,
+    }
+}
+
+async fn aggregate(quotes: &[PriceUpdate]) -> crate::websocket::AggregatedPrice {
+    DefaultPriceAggregator::default().aggregate(quotes).await
+}
+
+fn dec(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap()
+}
+
+/// A polled feed whose answer the test controls
+struct FixedQuote {
+    source: PriceSource,
+    price: Mutex<Option<f64>>,
+    fail: bool,
+}
+
+impl FixedQuote {
+    fn new(source: PriceSource, price: Option<f64>) -> Arc<Self> {
+        Arc::new(Self { source, price: Mutex::new(price), fail: false })
+    }
+}
+
+#[async_trait::async_trait]
+impl QuoteSource for FixedQuote {
+    fn source(&self) -> PriceSource {
+        self.source.clone()
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<Option<PriceUpdate>> {
+        if self.fail {
+            return Err(BotError::external_api("feed down".to_string()).into());
+        }
+        Ok(self.price.lock().await.map(|price| PriceUpdate {
+            symbol: symbol.to_string(),
+            ..quote(self.source.clone(), price, 0)
+        }))
+    }
+}
+
+fn stream(sources: Vec<Arc<dyn QuoteSource>>) -> Arc<PriceStreamManager> {
+    let mut stream = PriceStreamManager::new(Arc::new(WebSocketClient::new(WebSocketConfig::default(), None)));
+    for source in sources {
+        stream = stream.with_quote_source(source);
+    }
+    Arc::new(stream)
+}
+
+#[tokio::test]
+async fn test_one_source_wildly_off_is_rejected() {
+    let aggregated = aggregate(&[
+        quote(PriceSource::Jupiter, 100.0, 1),
+        quote(PriceSource::Pyth, 101.0, 1),
+        quote(PriceSource::Birdeye, 99.5, 1),
+        quote(PriceSource::Binance, 150.0, 1),
+    ]).await;
+
+    assert!(!aggregated.stale);
+    assert_eq!(aggregated.sources_count, 3);
+    assert_eq!(aggregated.median_price, dec(100.0));
+    assert_eq!(aggregated.max_price, dec(101.0));
+    assert!(!aggregated.source_prices.contains_key(&PriceSource::Binance));
+    assert!(aggregated.is_usable(3));
+
+    assert_eq!(aggregated.breakdown.len(), 4);
+    let outlier = aggregated.breakdown.iter().find(|q| q.source == PriceSource::Binance).unwrap();
+    assert!(outlier.rejected && !outlier.stale);
+    assert!((outlier.deviation_pct - 50.0).abs() < 1e-9, "{}", outlier.deviation_pct);
+    assert!(aggregated.breakdown.iter().filter(|q| q.source != PriceSource::Binance).all(|q| !q.rejected));
+}
+
+#[tokio::test]
+async fn test_all_sources_stale() {
+    let aggregated = aggregate(&[
+        quote(PriceSource::Jupiter, 100.0, 120),
+        quote(PriceSource::Pyth, 100.5, 90),
+        quote(PriceSource::Birdeye, 99.8, 300),
+    ]).await;
+
+    assert!(aggregated.stale);
+    assert!(aggregated.breakdown.iter().all(|q| q.stale));
+    assert!(!aggregated.is_usable(1));
+    assert_eq!(aggregated.confidence, 0.0);
+    // The last known figures are still reported for display
+    assert_eq!(aggregated.median_price, dec(100.0));
+}
+
+#[tokio::test]
+async fn test_stale_quotes_are_set_aside_while_fresh_ones_exist() {
+    let aggregated = aggregate(&[
+        quote(PriceSource::Jupiter, 100.0, 1),
+        quote(PriceSource::Pyth, 101.0, 2),
+        quote(PriceSource::Birdeye, 80.0, 600),
+    ]).await;
+
+    assert!(!aggregated.stale);
+    assert_eq!(aggregated.sources_count, 2);
+    assert_eq!(aggregated.median_price, dec(100.5));
+    let old = aggregated.breakdown.iter().find(|q| q.source == PriceSource::Birdeye).unwrap();
+    assert!(old.stale && !old.rejected);
+}
+
+#[tokio::test]
+async fn test_each_source_counts_once_with_its_latest_quote() {
+    let aggregated = aggregate(&[
+        quote(PriceSource::Jupiter, 90.0, 10),
+        quote(PriceSource::Jupiter, 100.0, 1),
+        quote(PriceSource::Jupiter, 95.0, 5),
+        quote(PriceSource::Pyth, 100.0, 1),
+    ]).await;
+
+    assert_eq!(aggregated.sources_count, 2);
+    assert_eq!(aggregated.source_prices[&PriceSource::Jupiter], dec(100.0));
+    assert_eq!(aggregated.breakdown.len(), 2);
+}
+
+#[tokio::test]
+async fn test_two_feeds_that_disagree_have_no_consensus() {
+    let aggregated = aggregate(&[
+        quote(PriceSource::Jupiter, 100.0, 1),
+        quote(PriceSource::Pyth, 120.0, 1),
+    ]).await;
+
+    assert_eq!(aggregated.median_price, Decimal::ZERO);
+    assert_eq!(aggregated.sources_count, 0);
+    assert!(aggregated.breakdown.iter().all(|q| q.rejected));
+    assert!(!aggregated.is_usable(1));
+}
+
+#[tokio::test]
+async fn test_source_weights_move_the_median() {
+    let quotes = [
+        quote(PriceSource::Jupiter, 100.0, 1),
+        quote(PriceSource::Pyth, 102.0, 1),
+        quote(PriceSource::Birdeye, 103.0, 1),
+    ];
+    assert_eq!(aggregate(&quotes).await.median_price, dec(102.0));
+
+    let weighted = DefaultPriceAggregator::new(AggregationConfig {
+        source_weights: HashMap::from([(PriceSource::Jupiter, 3.0)]),
+        ..AggregationConfig::default()
+    });
+    let aggregated = weighted.aggregate(&quotes).await;
+    assert_eq!(aggregated.median_price, dec(100.0));
+    assert_eq!(aggregated.sources_count, 3);
+}
+
+#[tokio::test]
+async fn test_random_outliers_never_move_the_median() {
+    let mut rng = StdRng::seed_from_u64(794);
+    let honest = [PriceSource::Jupiter, PriceSource::Pyth, PriceSource::Birdeye, PriceSource::Coinbase];
+
+    for _ in 0..200 {
+        let base: f64 = rng.gen_range(0.0001..500.0);
+        let mut quotes: Vec<PriceUpdate> = honest.iter()
+            .map(|source| quote(source.clone(), base * (1.0 + rng.gen_range(-0.01..0.01)), rng.gen_range(0..20)))
+            .collect();
+        let skew = rng.gen_range(0.2..0.5) * if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        quotes.push(quote(PriceSource::Binance, base * (1.0 + skew), rng.gen_range(0..20)));
+
+        let aggregated = aggregate(&quotes).await;
+        assert_eq!(aggregated.sources_count, 4, "base {} skew {}", base, skew);
+        assert!(!aggregated.source_prices.contains_key(&PriceSource::Binance));
+        let median = aggregated.median_price.to_f64().unwrap();
+        assert!((median / base - 1.0).abs() <= 0.0101, "median {} base {}", median, base);
+    }
+}
+
+#[tokio::test]
+async fn test_stream_polls_quote_sources_and_skips_failures() {
+    let failing = Arc::new(FixedQuote { source: PriceSource::Chainlink, price: Mutex::new(None), fail: true });
+    let stream = stream(vec![
+        FixedQuote::new(PriceSource::Pyth, Some(100.4)),
+        FixedQuote::new(PriceSource::Birdeye, None),
+        failing,
+    ]);
+
+    let aggregated = stream.aggregate_price_with("SOL", vec![quote(PriceSource::Jupiter, 100.0, 0)]).await;
+    assert_eq!(aggregated.symbol, "SOL");
+    assert_eq!(aggregated.sources_count, 2);
+    assert_eq!(aggregated.median_price, dec(100.2));
+
+    let alone = stream.aggregate_price("SOL").await;
+    assert_eq!(alone.sources_count, 1);
+    assert!(!alone.is_usable(2));
+}
+
+/// A stop at 0.9 on BONK priced at 1.0, triggering only when two sources agree
+async fn consensus_manager(pyth: Arc<FixedQuote>) -> (TestHarness, String, OrderManager, Arc<JupiterPriceV3Client>) {
+    let mint = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&mint, 1.0).build().await.unwrap();
+    let prices = Arc::new(
+        JupiterPriceV3Client::new(Arc::new(JupiterAuthManager::new())).with_base_url(harness.jupiter.base_url()),
+    );
+    let manager = OrderManager::new(
+        Arc::new(JupiterV6Client::new(ApiTier::Lite, None).with_base_url(harness.jupiter.base_url())),
+        prices.clone(),
+        harness.db.clone(),
+        None,
+    )
+    .with_price_consensus(stream(vec![pyth]), 2);
+
+    let stop = Order::create_stop_loss(USER_ID, mint.clone(), Decimal::new(9, 1), Decimal::from(1_000));
+    manager.create_order(stop).await.unwrap();
+    (harness, mint, manager, prices)
+}
+
+async fn stop_status(manager: &OrderManager) -> OrderStatus {
+    manager.get_user_orders(USER_ID).await[0].status.clone()
+}
+
+#[tokio::test]
+async fn test_a_single_feed_cannot_fire_a_stop_loss() {
+    let pyth = FixedQuote::new(PriceSource::Pyth, None);
+    let (harness, mint, manager, prices) = consensus_manager(pyth.clone()).await;
+
+    // Only Jupiter sees the drop
+    harness.jupiter.set_price(&mint, 0.5).await;
+    prices.clear_cache().await;
+    manager.run_cycle().await.unwrap();
+    assert!(matches!(stop_status(&manager).await, OrderStatus::Active | OrderStatus::Pending));
+
+    // Pyth still has the old price: the feeds disagree, so still nothing
+    *pyth.price.lock().await = Some(1.0);
+    manager.run_cycle().await.unwrap();
+    assert!(matches!(stop_status(&manager).await, OrderStatus::Active | OrderStatus::Pending));
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 0);
+
+    // Both agree on the drop
+    *pyth.price.lock().await = Some(0.51);
+    manager.run_cycle().await.unwrap();
+    assert!(matches!(stop_status(&manager).await, OrderStatus::Filled));
+}
+
+#[tokio::test]
+async fn test_a_single_bad_feed_cannot_fire_a_stop_loss() {
+    let pyth = FixedQuote::new(PriceSource::Pyth, Some(0.4));
+    let (harness, _, manager, _) = consensus_manager(pyth).await;
+
+    manager.run_cycle().await.unwrap();
+    assert!(matches!(stop_status(&manager).await, OrderStatus::Active | OrderStatus::Pending));
+    assert_eq!(harness.jupiter.call_count("v6_quote").await, 0);
+}
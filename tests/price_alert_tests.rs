@@ -0,0 +1,247 @@
+use crate::alerts::{
+    AlertAction, AlertCondition, AlertDelivery, AlertDeliveryMethod, AlertPriority, AlertStatus, AlertTriggerType, ChangeTimeframe,
+    ChangeType, PercentageChange, PriceAlert, PriceAlertManager, PriceComparison, PriceThreshold,
+};
+use crate::testkit::TestHarness;
+use crate::trading::TokenResolver;
+use crate::websocket::{PriceStreamManager, WebSocketClient, WebSocketConfig};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+const USER_ID: i64 = 764_001;
+const SYMBOL: &str = "TKN";
+
+fn price(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn at(minutes: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+}
+
+fn alert(symbol: &str, condition: AlertCondition, trigger_type: AlertTriggerType) -> PriceAlert {
+    PriceAlert {
+        alert_id: String::new(),
+        user_id: USER_ID,
+        name: format!("{} alert", symbol),
+        symbol: symbol.to_string(),
+        conditions: vec![condition],
+        max_triggers: matches!(trigger_type, AlertTriggerType::Once).then_some(1),
+        trigger_type,
+        priority: AlertPriority::Medium,
+        actions: vec![AlertAction::Notify],
+        delivery_methods: vec![AlertDeliveryMethod::Telegram { chat_id: USER_ID }],
+        cooldown_period: None,
+        expiry_time: None,
+        enabled: true,
+        created_at: Utc::now(),
+        last_triggered: None,
+        trigger_count: 0,
+        status: AlertStatus::Active,
+        metadata: HashMap::new(),
+    }
+}
+
+fn threshold(comparison: PriceComparison, target: &str) -> AlertCondition {
+    AlertCondition::PriceThreshold(PriceThreshold { comparison, target_price: price(target), tolerance: None })
+}
+
+async fn manager(harness: &TestHarness) -> PriceAlertManager {
+    let price_stream = Arc::new(PriceStreamManager::new(Arc::new(
+        WebSocketClient::new(WebSocketConfig::default(), None),
+    )));
+    PriceAlertManager::new(harness.db.clone(), price_stream, Arc::new(AlertDelivery::default()), None)
+        .with_price_client(harness.price_client.clone())
+}
+
+/// Feed prices one minute apart starting at `start`; returns how many fired on each
+async fn feed(manager: &PriceAlertManager, start: i64, prices: &[&str]) -> Vec<usize> {
+    let mut fired = Vec::new();
+    for (offset, value) in prices.iter().enumerate() {
+        let ids = manager.observe_price(SYMBOL, price(value), None, at(start + offset as i64)).await.unwrap();
+        fired.push(ids.len());
+    }
+    fired
+}
+
+#[tokio::test]
+async fn above_alert_fires_when_the_price_crosses_up_to_it() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let manager = manager(&harness).await;
+    let id = manager
+        .create_alert(alert(SYMBOL, threshold(PriceComparison::Above, "1.2"), AlertTriggerType::Once))
+        .await
+        .unwrap();
+
+    // Below the level at creation, then climbing through it
+    assert_eq!(feed(&manager, 0, &["1.0", "1.1", "1.19", "1.25"]).await, vec![0, 0, 0, 1]);
+
+    let fired = manager.get_alert(&id).await.unwrap();
+    assert_eq!(fired.status, AlertStatus::Triggered);
+    assert_eq!(fired.trigger_count, 1);
+    assert!(!fired.enabled);
+
+    // One-shot: dipping and recrossing does nothing
+    assert_eq!(feed(&manager, 10, &["1.3", "1.0", "1.4"]).await, vec![0, 0, 0]);
+}
+
+#[tokio::test]
+async fn crossing_alert_ignores_a_price_already_past_the_level() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let manager = manager(&harness).await;
+    manager
+        .create_alert(alert(SYMBOL, threshold(PriceComparison::CrossingAbove, "1.2"), AlertTriggerType::Once))
+        .await
+        .unwrap();
+
+    // Already above when first seen, so there is nothing to cross yet
+    assert_eq!(feed(&manager, 0, &["1.5", "1.4"]).await, vec![0, 0]);
+    // Falls back under and crosses up again
+    assert_eq!(feed(&manager, 2, &["1.1", "1.3"]).await, vec![0, 1]);
+}
+
+#[tokio::test]
+async fn crossing_below_needs_a_price_from_above() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let manager = manager(&harness).await;
+    manager
+        .create_alert(alert(SYMBOL, threshold(PriceComparison::CrossingBelow, "0.8"), AlertTriggerType::Once))
+        .await
+        .unwrap();
+
+    assert_eq!(feed(&manager, 0, &["0.7", "0.75", "0.9", "0.8"]).await, vec![0, 0, 0, 1]);
+}
+
+#[tokio::test]
+async fn repeating_alert_waits_out_its_cooldown() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let manager = manager(&harness).await;
+    let mut repeating = alert(SYMBOL, threshold(PriceComparison::Below, "1.0"), AlertTriggerType::Repeating);
+    repeating.cooldown_period = Some(Duration::minutes(10));
+    let id = manager.create_alert(repeating).await.unwrap();
+
+    assert_eq!(manager.observe_price(SYMBOL, price("0.9"), None, at(0)).await.unwrap().len(), 1);
+    assert!(manager.observe_price(SYMBOL, price("0.8"), None, at(5)).await.unwrap().is_empty());
+    assert_eq!(manager.observe_price(SYMBOL, price("0.8"), None, at(11)).await.unwrap().len(), 1);
+
+    let stored = manager.get_alert(&id).await.unwrap();
+    assert_eq!(stored.status, AlertStatus::Active);
+    assert_eq!(stored.trigger_count, 2);
+}
+
+#[tokio::test]
+async fn percentage_change_measures_from_inside_the_window() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let manager = manager(&harness).await;
+    let condition = AlertCondition::PercentageChange(PercentageChange {
+        timeframe: ChangeTimeframe::Hours(1),
+        change_type: ChangeType::Increase,
+        threshold_percentage: 10.0,
+    });
+    manager.create_alert(alert(SYMBOL, condition, AlertTriggerType::Once)).await.unwrap();
+
+    // 0.5 is two hours back, outside the window; against 1.0 the move is only 8%
+    manager.observe_price(SYMBOL, price("0.5"), None, at(-120)).await.unwrap();
+    manager.observe_price(SYMBOL, price("1.0"), None, at(0)).await.unwrap();
+    assert!(manager.observe_price(SYMBOL, price("1.08"), None, at(30)).await.unwrap().is_empty());
+    assert_eq!(manager.observe_price(SYMBOL, price("1.12"), None, at(50)).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn triggers_are_persisted_and_sent_to_the_chat() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let manager = manager(&harness).await;
+    let mut notifications = manager.subscribe_notifications();
+    let fired = manager
+        .create_alert(alert(SYMBOL, threshold(PriceComparison::Above, "2"), AlertTriggerType::Once))
+        .await
+        .unwrap();
+    let waiting = manager
+        .create_alert(alert(SYMBOL, threshold(PriceComparison::Above, "5"), AlertTriggerType::Once))
+        .await
+        .unwrap();
+
+    feed(&manager, 0, &["1.5", "2.5"]).await;
+
+    let notification = tokio::time::timeout(std::time::Duration::from_secs(5), notifications.recv())
+        .await
+        .expect("alert should be delivered")
+        .unwrap();
+    assert_eq!(notification.chat_id, USER_ID);
+    assert_eq!(notification.alert_id, fired);
+    assert!(notification.message.contains("Price: $2.5"));
+
+    // A restart only brings back the alert that is still waiting
+    let restarted = self::manager(&harness).await;
+    assert_eq!(restarted.load_active_alerts().await.unwrap(), 1);
+    assert!(restarted.get_alert(&waiting).await.is_some());
+    assert!(restarted.get_alert(&fired).await.is_none());
+
+    // Deleted alerts stay deleted
+    assert!(restarted.delete_alert(&waiting).await.unwrap());
+    assert_eq!(self::manager(&harness).await.load_active_alerts().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn tokens_without_stream_coverage_are_polled() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&bonk, 0.00002).build().await.unwrap();
+    let manager = manager(&harness).await;
+    let id = manager
+        .create_alert(alert(&bonk, threshold(PriceComparison::Above, "0.00003"), AlertTriggerType::Once))
+        .await
+        .unwrap();
+
+    assert_eq!(manager.poll_prices().await.unwrap(), 1);
+    assert_eq!(manager.get_alert(&id).await.unwrap().status, AlertStatus::Active);
+
+    harness.jupiter.set_price(&bonk, 0.00004).await;
+    harness.price_client.clear_cache().await;
+    manager.poll_prices().await.unwrap();
+
+    assert_eq!(manager.get_alert(&id).await.unwrap().status, AlertStatus::Triggered);
+    // Nothing left to watch
+    assert_eq!(manager.poll_prices().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn alerts_command_lists_delivers_and_deletes() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&bonk, 0.00002).build().await.unwrap();
+    let alerts = harness.services.price_alerts.clone();
+    let mut breakout = alert(&bonk, threshold(PriceComparison::Above, "0.00003"), AlertTriggerType::Once);
+    breakout.name = "BONK breakout".to_string();
+    let breakout = alerts.create_alert(breakout).await.unwrap();
+    let mut dip = alert(&bonk, threshold(PriceComparison::Below, "0.00001"), AlertTriggerType::Once);
+    dip.name = "BONK dip".to_string();
+    let dip = alerts.create_alert(dip).await.unwrap();
+
+    harness.start_bot();
+    harness.telegram.inject_message(USER_ID, "/alerts list").await;
+    let listing = harness.telegram
+        .wait_for_text(USER_ID, "Your price alerts", std::time::Duration::from_secs(10))
+        .await
+        .expect("bot should list the alerts");
+    assert!(listing.contains("BONK breakout"));
+    assert!(listing.contains("BONK dip"));
+
+    harness.jupiter.set_price(&bonk, 0.00004).await;
+    harness.price_client.clear_cache().await;
+    alerts.poll_prices().await.unwrap();
+    harness.telegram
+        .wait_for_text(USER_ID, "Alert: BONK breakout", std::time::Duration::from_secs(10))
+        .await
+        .expect("triggered alert should reach the chat");
+
+    harness.telegram.inject_message(USER_ID, &format!("/alerts delete {}", &dip[..8])).await;
+    harness.telegram
+        .wait_for_text(USER_ID, "Deleted BONK dip", std::time::Duration::from_secs(10))
+        .await
+        .expect("bot should confirm the deletion");
+
+    let remaining: Vec<String> = alerts.get_user_alerts(USER_ID).await.into_iter().map(|a| a.alert_id).collect();
+    assert_eq!(remaining, vec![breakout]);
+}
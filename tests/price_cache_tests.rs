@@ -0,0 +1,161 @@
+use crate::api::{JupiterAuthManager, JupiterPriceV3Client, PriceCacheConfig};
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::{net::TcpListener, sync::Mutex};
+
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+const WIF: &str = "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm";
+
+/// Serves `/price/v3` from a settable price table after a delay; records each request's ids
+#[derive(Default)]
+struct CountingPrices {
+    prices: Mutex<HashMap<String, f64>>,
+    requests: Mutex<Vec<String>>,
+    delay: Duration,
+}
+
+impl CountingPrices {
+    async fn set(&self, mint: &str, price: f64) {
+        self.prices.lock().await.insert(mint.to_string(), price);
+    }
+
+    async fn requests(&self) -> Vec<String> {
+        self.requests.lock().await.clone()
+    }
+}
+
+async fn prices(State(upstream): State<Arc<CountingPrices>>, Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+    let ids = query.get("ids").cloned().unwrap_or_default();
+    upstream.requests.lock().await.push(ids.clone());
+    tokio::time::sleep(upstream.delay).await;
+
+    let table = upstream.prices.lock().await;
+    let body: serde_json::Map<String, Value> = ids
+        .split(',')
+        .filter_map(|mint| table.get(mint).map(|price| (mint.to_string(), json!({
+            "usdPrice": price,
+            "blockId": 287_700_100u64,
+            "decimals": 5,
+        }))))
+        .collect();
+    Json(Value::Object(body))
+}
+
+async fn counting_prices(delay: Duration) -> (String, Arc<CountingPrices>) {
+    let upstream = Arc::new(CountingPrices { delay, ..Default::default() });
+    upstream.set(BONK, 0.00002).await;
+    upstream.set(WIF, 1.5).await;
+    let app = Router::new().route("/price/v3", get(prices)).with_state(upstream.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    (url, upstream)
+}
+
+/// Clones share the cache, like the `Arc`ed client the bot hands around
+fn client(url: &str, ttl: Duration, max_stale: Duration) -> JupiterPriceV3Client {
+    JupiterPriceV3Client::new(Arc::new(JupiterAuthManager::new()))
+        .with_base_url(url)
+        .with_cache_config(PriceCacheConfig { ttl, max_stale, ..Default::default() })
+}
+
+async fn price_of(client: &JupiterPriceV3Client, mint: &str) -> f64 {
+    client.get_prices(vec![mint.to_string()]).await.unwrap().prices[mint].usd_price
+}
+
+#[tokio::test]
+async fn concurrent_lookups_for_one_mint_share_a_single_request() {
+    let (url, upstream) = counting_prices(Duration::from_millis(200)).await;
+    let client = client(&url, Duration::from_secs(2), Duration::from_secs(60))
+        .with_metrics(super::shared_metrics());
+
+    let lookups = (0..50).map(|_| {
+        let client = client.clone();
+        tokio::spawn(async move { price_of(&client, WIF).await })
+    });
+    for price in futures::future::join_all(lookups).await {
+        assert_eq!(price.unwrap(), 1.5);
+    }
+
+    assert_eq!(upstream.requests().await, vec![WIF.to_string()]);
+    let stats = client.get_cache_stats().await;
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.coalesced + stats.hits, 49);
+    assert!(stats.coalesced > 0);
+    assert_eq!(stats.stale_served, 0);
+
+    // Within the TTL it's a plain hit
+    assert_eq!(price_of(&client, WIF).await, 1.5);
+    assert_eq!(upstream.requests().await.len(), 1);
+}
+
+#[tokio::test]
+async fn batches_only_fetch_mints_nobody_else_is_fetching() {
+    let (url, upstream) = counting_prices(Duration::from_millis(200)).await;
+    let client = client(&url, Duration::from_secs(2), Duration::from_secs(60));
+
+    let first = {
+        let client = client.clone();
+        tokio::spawn(async move { price_of(&client, WIF).await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let both = client.get_prices(vec![WIF.to_string(), BONK.to_string()]).await.unwrap();
+
+    assert_eq!(first.await.unwrap(), 1.5);
+    assert_eq!(both.prices[WIF].usd_price, 1.5);
+    assert_eq!(both.prices[BONK].usd_price, 0.00002);
+    assert_eq!(upstream.requests().await, vec![WIF.to_string(), BONK.to_string()]);
+    assert_eq!(client.get_cache_stats().await.coalesced, 1);
+}
+
+#[tokio::test]
+async fn expired_prices_are_served_while_a_refresh_runs() {
+    let (url, upstream) = counting_prices(Duration::from_millis(300)).await;
+    let client = client(&url, Duration::from_millis(100), Duration::from_secs(60));
+    assert_eq!(price_of(&client, WIF).await, 1.5);
+
+    upstream.set(WIF, 1.8).await;
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    // The stale price comes back without waiting on the slow upstream
+    let started = Instant::now();
+    assert_eq!(price_of(&client, WIF).await, 1.5);
+    assert_eq!(price_of(&client, WIF).await, 1.5);
+    assert!(started.elapsed() < Duration::from_millis(150));
+    let stats = client.get_cache_stats().await;
+    assert_eq!(stats.stale_served, 2);
+
+    // One background refresh, after which the new price is fresh
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(upstream.requests().await.len(), 2);
+    assert_eq!(price_of(&client, WIF).await, 1.8);
+    assert_eq!(client.get_cache_stats().await.hits, 1);
+}
+
+#[tokio::test]
+async fn prices_past_the_stale_window_or_cleared_are_fetched_again() {
+    let (url, upstream) = counting_prices(Duration::ZERO).await;
+    let client = client(&url, Duration::from_millis(50), Duration::from_millis(50));
+    assert_eq!(price_of(&client, BONK).await, 0.00002);
+
+    upstream.set(BONK, 0.00003).await;
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(price_of(&client, BONK).await, 0.00003);
+
+    upstream.set(BONK, 0.00004).await;
+    client.clear_cache().await;
+    assert_eq!(price_of(&client, BONK).await, 0.00004);
+
+    let stats = client.get_cache_stats().await;
+    assert_eq!((stats.misses, stats.stale_served), (3, 0));
+    assert_eq!(upstream.requests().await.len(), 3);
+}
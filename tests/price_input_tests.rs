@@ -0,0 +1,116 @@
+use crate::bot::chart_actions::ChartActions;
+use crate::bot::handlers::PriceEntryHandler;
+use crate::bot::price_entry::{EntryResolution, PriceAnswer, PriceEntry, PriceEntryTarget, PriceLeg};
+use crate::utils::price_input::{normalize, normalize_price, parse_price, ParsedPrice, PriceInput, PriceIntent};
+
+const BONK_PRICE: f64 = 0.0000215;
+
+fn approx(a: f64, b: f64) -> bool {
+    (a - b).abs() <= b.abs() * 1e-9
+}
+
+fn bracket(current_price: f64, stop: &str, take_profit: &str) -> PriceEntry {
+    PriceEntry {
+        user_id: 42,
+        chat_id: 42,
+        mint: "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm".to_string(),
+        symbol: "WIF".to_string(),
+        current_price,
+        target: PriceEntryTarget::Bracket { amount: 100.0 },
+        legs: vec![
+            PriceLeg::new("stop", parse_price(stop).unwrap(), PriceIntent::Below),
+            PriceLeg::new("take-profit", parse_price(take_profit).unwrap(), PriceIntent::Above),
+        ],
+    }
+}
+
+#[test]
+fn test_wrong_scale_offers_both_readings() {
+    // `/alert BONK 2` almost certainly meant $0.00002
+    let PriceInput::Ambiguous { entered, candidates } = normalize_price("2", BONK_PRICE, PriceIntent::Either) else {
+        panic!("2 vs {} should be questioned", BONK_PRICE);
+    };
+    assert_eq!(entered, 2.0);
+    assert!(approx(candidates[0].price, 0.00002));
+    assert_eq!(candidates[1].price, 2.0);
+    assert_eq!(PriceEntryHandler::describe_candidate(&candidates[0], "en"), "$0.00002 (−7% from current)");
+    assert!(PriceEntryHandler::describe_candidate(&candidates[1], "en").ends_with("(+9,302,226% from current)"));
+
+    // A price typed in cents is exactly two orders of magnitude off
+    let PriceInput::Ambiguous { candidates, .. } = normalize_price("70", 0.70, PriceIntent::Below) else {
+        panic!("70 vs 0.70 should be questioned");
+    };
+    assert!(approx(candidates[0].price, 0.70));
+
+    // Within two orders of magnitude nothing is asked
+    assert_eq!(normalize_price("0.5", 0.70, PriceIntent::Below), PriceInput::Accepted(0.5));
+    assert_eq!(normalize_price("30", 0.70, PriceIntent::Above), PriceInput::Accepted(30.0));
+}
+
+#[test]
+fn test_suffixes_and_exponents_set_the_scale() {
+    assert_eq!(parse_price("20u"), Some(ParsedPrice { value: 20.0 * 1e-6, explicit_scale: true }));
+    assert_eq!(parse_price("2e-5").map(|p| p.explicit_scale), Some(true));
+    assert!(approx(parse_price("2e-5").unwrap().value, 0.00002));
+    assert!(approx(parse_price("70c").unwrap().value, 0.70));
+    assert!(approx(parse_price("20µ").unwrap().value, 0.00002));
+    assert_eq!(parse_price("1.5k").map(|p| p.value), Some(1500.0));
+    assert_eq!(parse_price("$0.95"), Some(ParsedPrice { value: 0.95, explicit_scale: false }));
+
+    for bad in ["", "0,5", "-1", "abc", "inf", "NaN", "0", "1.2.3", "$"] {
+        assert_eq!(parse_price(bad), None, "{:?} should not parse", bad);
+    }
+
+    // An explicit scale is taken at its word, however far from market
+    let micro = parse_price("20u").unwrap();
+    assert_eq!(normalize(micro, 150.0, PriceIntent::Either), PriceInput::Accepted(micro.value));
+    assert!(matches!(normalize_price("nonsense", 1.0, PriceIntent::Either), PriceInput::Invalid(_)));
+
+    // Chart replies accept the same notation
+    assert_eq!(ChartActions::parse_reply("below 20u", &[]).map(|(_, p)| approx(p, 0.00002)), Some(true));
+}
+
+#[test]
+fn test_levels_that_fire_on_creation_need_confirmation() {
+    // A stop above market and a take-profit below it both fire at once
+    assert_eq!(
+        normalize_price("1.1", 1.0, PriceIntent::Below),
+        PriceInput::TriggersImmediately { price: 1.1, current_price: 1.0 }
+    );
+    assert!(matches!(normalize_price("0.9", 1.0, PriceIntent::Above), PriceInput::TriggersImmediately { .. }));
+    // Alerts without a direction pick the side that hasn't happened yet
+    assert_eq!(normalize_price("0.9", 1.0, PriceIntent::Either), PriceInput::Accepted(0.9));
+
+    let mut entry = bracket(0.70, "0.75", "0.95");
+    let EntryResolution::Question { leg: 0, input: PriceInput::TriggersImmediately { .. } } = entry.resolve() else {
+        panic!("stop above market must be confirmed");
+    };
+    entry.answer(0, PriceAnswer::ConfirmImmediate);
+    assert_eq!(entry.resolve(), EntryResolution::Ready(vec![0.75, 0.95]));
+}
+
+#[test]
+fn test_correct_inputs_pass_through_untouched() {
+    for (typed, current) in [("0.00002", BONK_PRICE), ("150", 149.5), ("0.7", 0.71), ("$2.45", 2.4)] {
+        let value: f64 = typed.trim_start_matches('$').parse().unwrap();
+        assert_eq!(normalize_price(typed, current, PriceIntent::Either), PriceInput::Accepted(value));
+    }
+
+    // A bracket typed in the wrong scale is fixed one leg at a time
+    let mut entry = bracket(0.72, "0.006", "0.95");
+    let EntryResolution::Question { leg, input: PriceInput::Ambiguous { candidates, .. } } = entry.resolve() else {
+        panic!("a 0.006 stop on a 0.72 token should be questioned");
+    };
+    entry.answer(leg, PriceAnswer::Pick(candidates[0].price));
+    let EntryResolution::Ready(prices) = entry.resolve() else { panic!("bracket should be ready") };
+    assert!(approx(prices[0], 0.60) && prices[1] == 0.95);
+
+    let (stop, target) = entry.bracket_orders(prices[0], prices[1]).unwrap();
+    assert!(stop.parent_order_id.is_some() && stop.parent_order_id == target.parent_order_id);
+    assert!(stop.is_linked_leg(&target));
+
+    // Confirming an inverted bracket still doesn't create it
+    let mut inverted = bracket(1.0, "0.9", "0.8");
+    inverted.answer(1, PriceAnswer::ConfirmImmediate);
+    assert!(matches!(inverted.resolve(), EntryResolution::Invalid(_)));
+}
@@ -0,0 +1,119 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::trading::{FeePercentiles, GasOptimization, PriorityFeeConfig, PriorityFeeEstimator, PriorityFeeStrategy};
+use crate::wallet::TransactionPriority;
+
+/// Fees of 100..=1000 in slots 1..=10, so percentiles land on exact samples
+fn samples() -> Vec<(u64, u64)> {
+    (1..=10).map(|slot| (slot, slot * 100)).collect()
+}
+
+/// Estimator whose RPC always fails and whose estimates are always stale
+fn offline_estimator() -> PriorityFeeEstimator {
+    PriorityFeeEstimator::new(
+        Arc::new(RpcClient::new("http://127.0.0.1:1".to_string())),
+        PriorityFeeConfig { refresh_interval: Duration::ZERO, ..PriorityFeeConfig::default() },
+    )
+}
+
+#[test]
+fn test_percentiles_of_synthetic_fee_samples() {
+    let percentiles = FeePercentiles::from_samples(&samples(), 150).unwrap();
+    // Nearest rank over ten samples: round(0.25 × 9) = 2, round(4.5) = 5, round(6.75) = 7, round(8.1) = 8
+    assert_eq!(percentiles, FeePercentiles { p25: 300, p50: 600, p75: 800, p90: 900, samples: 10 });
+
+    assert_eq!(percentiles.for_priority(TransactionPriority::Low), 300);
+    assert_eq!(percentiles.for_priority(TransactionPriority::Normal), 600);
+    assert_eq!(percentiles.for_priority(TransactionPriority::High), 800);
+    assert_eq!(percentiles.for_priority(TransactionPriority::Critical), 900);
+
+    // A single sample is every percentile
+    let single = FeePercentiles::from_samples(&[(42, 7_000)], 150).unwrap();
+    assert_eq!((single.p25, single.p90), (7_000, 7_000));
+}
+
+#[test]
+fn test_percentiles_cover_only_recent_paying_slots() {
+    // The last four slots: 700..=1000
+    let recent = FeePercentiles::from_samples(&samples(), 4).unwrap();
+    assert_eq!((recent.p25, recent.p50, recent.p90, recent.samples), (800, 900, 1000, 4));
+
+    // Slots without a priority fee don't drag the percentiles down
+    let mut with_idle = samples();
+    with_idle.extend((11..=20).map(|slot| (slot, 0)));
+    assert_eq!(FeePercentiles::from_samples(&with_idle, 150).unwrap().samples, 10);
+
+    assert_eq!(FeePercentiles::from_samples(&[], 150), None);
+    assert_eq!(FeePercentiles::from_samples(&[(1, 0), (2, 0)], 150), None);
+    assert_eq!(FeePercentiles::from_samples(&samples(), 0).map(|p| p.samples), Some(1));
+}
+
+#[tokio::test]
+async fn test_failing_rpc_serves_the_last_known_estimate() {
+    let estimator = offline_estimator();
+    let pool = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    // Nothing known yet
+    assert_eq!(estimator.estimate(TransactionPriority::High).await, 10_000);
+    assert!(estimator.refresh(&[]).await.is_err());
+
+    estimator.record(&[], &samples()).await;
+    assert_eq!(estimator.estimate(TransactionPriority::High).await, 800);
+
+    // Account sets are kept apart, in any order
+    estimator.record(&pool, &[(5, 50_000)]).await;
+    let reversed = [pool[1], pool[0]];
+    assert_eq!(estimator.estimate_for(&reversed, TransactionPriority::Low).await, 50_000);
+    assert_eq!(estimator.estimate_for(&pool[..1], TransactionPriority::Low).await, 10_000);
+    assert_eq!(estimator.percentiles(&pool).await.map(|p| p.samples), Some(1));
+}
+
+#[tokio::test]
+async fn test_tracked_sets_are_capped() {
+    let estimator = PriorityFeeEstimator::new(
+        Arc::new(RpcClient::new("http://127.0.0.1:1".to_string())),
+        PriorityFeeConfig { max_tracked_sets: 2, ..PriorityFeeConfig::default() },
+    );
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    estimator.record(&[], &samples()).await;
+    estimator.record(&[first], &samples()).await;
+    estimator.record(&[second], &samples()).await;
+
+    // The cluster-wide estimate is never the one dropped
+    assert!(estimator.percentiles(&[]).await.is_some());
+    assert!(estimator.percentiles(&[first]).await.is_none());
+    assert!(estimator.percentiles(&[second]).await.is_some());
+}
+
+#[tokio::test]
+async fn test_order_gas_strategies_price_from_the_estimator() {
+    let estimator = offline_estimator();
+    estimator.record(&[], &samples()).await;
+    let gas = |strategy: PriorityFeeStrategy| GasOptimization {
+        priority_fee_strategy: strategy,
+        max_priority_fee: 10_000,
+        gas_price_multiplier: 1.0,
+        dynamic_adjustment: true,
+    };
+
+    assert_eq!(gas(PriorityFeeStrategy::Conservative).unit_price(Some(&estimator)).await, 300);
+    assert_eq!(gas(PriorityFeeStrategy::Standard).unit_price(Some(&estimator)).await, 600);
+    assert_eq!(gas(PriorityFeeStrategy::Aggressive).unit_price(Some(&estimator)).await, 800);
+
+    // Custom still overrides, even above the cap
+    assert_eq!(gas(PriorityFeeStrategy::Custom(25_000)).unit_price(Some(&estimator)).await, 25_000);
+
+    // Scaled, then capped
+    let boosted = GasOptimization { gas_price_multiplier: 1.5, ..gas(PriorityFeeStrategy::Standard) };
+    assert_eq!(boosted.unit_price(Some(&estimator)).await, 900);
+    let capped = GasOptimization { max_priority_fee: 500, ..gas(PriorityFeeStrategy::Aggressive) };
+    assert_eq!(capped.unit_price(Some(&estimator)).await, 500);
+
+    // Without an estimator, or with dynamic adjustment off, the static price applies
+    assert_eq!(gas(PriorityFeeStrategy::Aggressive).unit_price(None).await, 1_000);
+    let fixed = GasOptimization { dynamic_adjustment: false, ..gas(PriorityFeeStrategy::Aggressive) };
+    assert_eq!(fixed.unit_price(Some(&estimator)).await, 1_000);
+}
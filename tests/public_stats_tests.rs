@@ -0,0 +1,120 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::trading::{
+    AggregatePrivacy, LeaderboardEntry, LeaderboardPeriod, PublicStatsBoard,
+};
+
+fn entries(count: usize) -> Vec<LeaderboardEntry> {
+    (0..count)
+        .map(|i| LeaderboardEntry {
+            rank: i as u32 + 1,
+            user_id: 2000 + i as i64,
+            username: format!("trader{}", i),
+            profit_percent: 10.0,
+            profit_sol: 4.0,
+            total_trades: 5,
+            win_rate: 50.0 + i as f64,
+            volume_sol: 40.0 + 3.0 * i as f64,
+            badges: Vec::new(),
+            is_copyable: false,
+            copy_fee_percent: 0.0,
+        })
+        .collect()
+}
+
+#[test]
+fn test_suppressed_below_k_contributors() {
+    let privacy = AggregatePrivacy { min_contributors: 10, ..AggregatePrivacy::default() };
+    let mut rng = StdRng::seed_from_u64(7);
+
+    let few = privacy.aggregate(&entries(9));
+    assert!(privacy.publish(&few, &mut rng).is_none());
+
+    let enough = privacy.aggregate(&entries(10));
+    assert!(privacy.publish(&enough, &mut rng).is_some());
+}
+
+#[test]
+fn test_published_values_are_bucketed_and_noise_bounded() {
+    let privacy = AggregatePrivacy::default();
+    let exact = privacy.aggregate(&entries(23));
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let volume_slack = privacy.noise_bound(privacy.volume_sensitivity()) + privacy.volume_bucket_sol / 2.0;
+    let win_rate_slack = privacy.noise_bound(privacy.win_rate_sensitivity(23)) + privacy.win_rate_bucket / 2.0;
+
+    for _ in 0..500 {
+        let published = privacy.publish(&exact, &mut rng).unwrap();
+
+        // Participant counts are floored to the bucket
+        assert_eq!(published.participants, 20);
+
+        assert_eq!(published.total_volume_sol % privacy.volume_bucket_sol, 0.0);
+        assert_eq!(published.avg_win_rate % privacy.win_rate_bucket, 0.0);
+
+        assert!((published.total_volume_sol - exact.total_volume_sol).abs() <= volume_slack);
+        assert!((published.avg_win_rate - exact.avg_win_rate).abs() <= win_rate_slack);
+    }
+}
+
+#[test]
+fn test_each_trader_contribution_is_capped() {
+    let privacy = AggregatePrivacy { volume_cap_sol: 100.0, ..AggregatePrivacy::default() };
+    let mut traders = entries(12);
+    let without_whale = privacy.aggregate(&traders);
+
+    // One trader with 10,000 SOL moves the total by no more than the cap
+    traders[0].volume_sol = 10_000.0;
+    traders[0].win_rate = 250.0;
+    let with_whale = privacy.aggregate(&traders);
+    assert_eq!(with_whale.total_volume_sol - without_whale.total_volume_sol, 100.0 - 40.0);
+    assert!(with_whale.total_volume_sol - without_whale.total_volume_sol <= privacy.volume_sensitivity());
+    assert!(with_whale.avg_win_rate - without_whale.avg_win_rate <= privacy.win_rate_sensitivity(12));
+
+    // The noise is scaled to what one trader can change, not to the display bucket
+    assert_eq!(privacy.noise_bound(privacy.volume_sensitivity()), 3.0 * 100.0 / privacy.epsilon);
+    assert!(privacy.win_rate_sensitivity(20) < privacy.win_rate_sensitivity(10));
+}
+
+#[test]
+fn test_smaller_epsilon_allows_more_noise() {
+    let strict = AggregatePrivacy { epsilon: 0.5, ..AggregatePrivacy::default() };
+    let loose = AggregatePrivacy { epsilon: 2.0, ..AggregatePrivacy::default() };
+    assert!(strict.noise_bound(50.0) > loose.noise_bound(50.0));
+}
+
+#[tokio::test]
+async fn test_snapshot_renders_stably() {
+    let board = PublicStatsBoard::new(AggregatePrivacy::default());
+    let weekly = entries(17);
+
+    let first = board.snapshot(LeaderboardPeriod::Weekly, &weekly).await;
+    let rendered = first.render(10);
+    for _ in 0..20 {
+        let again = board.snapshot(LeaderboardPeriod::Weekly, &weekly).await;
+        assert_eq!(again.published, first.published);
+        assert_eq!(again.render(10), rendered);
+    }
+
+    // Exact values stay available internally, but never reach the rendered block
+    assert_eq!(first.exact.participants, 17);
+    assert!(rendered.contains("Traders: 15+"));
+    assert!(!rendered.contains("17"));
+
+    // Fresh data produces a fresh snapshot
+    let changed = board.snapshot(LeaderboardPeriod::Weekly, &entries(18)).await;
+    assert_eq!(changed.exact.participants, 18);
+    assert_eq!(board.latest(LeaderboardPeriod::Weekly).await.unwrap().exact, changed.exact);
+}
+
+#[tokio::test]
+async fn test_suppressed_snapshot_renders_threshold_notice() {
+    let board = PublicStatsBoard::new(AggregatePrivacy::default());
+    let snapshot = board.snapshot(LeaderboardPeriod::Daily, &entries(4)).await;
+
+    assert!(snapshot.published.is_none());
+    let rendered = snapshot.render(10);
+    assert!(rendered.contains("at least 10 traders"));
+    assert!(!rendered.contains("SOL"));
+}
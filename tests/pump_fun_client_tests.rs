@@ -0,0 +1,138 @@
+use crate::api::pump_fun::{curve_progress, BondingCurve, BuyTokenRequest, PumpFunClient, INITIAL_REAL_TOKEN_RESERVES};
+use crate::bot::handlers::CommandHandler;
+use crate::errors::BotError;
+use crate::middleware::{CircuitBreaker, CircuitBreakerConfig};
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+const TRENDING: &str = include_str!("fixtures/pump_trending.json");
+const SEARCH: &str = include_str!("fixtures/pump_search.json");
+const CURVE: &str = include_str!("fixtures/pump_bonding_curve.json");
+const BUY: &str = include_str!("fixtures/pump_buy.json");
+const DOGEAI: &str = "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr";
+
+type Seen = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+fn fixture(body: &'static str) -> Response {
+    ([(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+/// Serves the recorded Pump.fun responses; keeps the query of every call
+async fn pump_server() -> (String, Seen) {
+    let seen = Seen::default();
+    let app = Router::new()
+        .route("/tokens/trending", get(|State(seen): State<Seen>, Query(q): Query<HashMap<String, String>>| async move {
+            seen.lock().await.push(q);
+            fixture(TRENDING)
+        }))
+        .route("/tokens/search", get(|State(seen): State<Seen>, Query(q): Query<HashMap<String, String>>| async move {
+            seen.lock().await.push(q);
+            fixture(SEARCH)
+        }))
+        .route("/tokens/:mint/bonding-curve", get(|| async { fixture(CURVE) }))
+        .route("/trade/buy", post(|| async { fixture(BUY) }))
+        .with_state(seen.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    (url, seen)
+}
+
+#[tokio::test]
+async fn test_trending_is_sorted_by_volume() {
+    let (url, seen) = pump_server().await;
+    let pump = PumpFunClient::new().unwrap().with_api_url(&url);
+
+    let tokens = pump.get_trending(2).await.unwrap();
+    assert_eq!(tokens.iter().map(|t| t.symbol.as_str()).collect::<Vec<_>>(), vec!["PEPE26", "MEMECAT"]);
+    assert_eq!(tokens[1].address, "5z3EqYQo9HiCEs3R84RCDMu2n7anpDMxRhdK8PSWmrRC");
+    assert_eq!(tokens[1].image_url, None);
+    assert_eq!(tokens[1].holders, 523);
+    assert_eq!(tokens[1].bonding_curve_progress, 85.5);
+    assert!(tokens[0].liquidity_locked);
+
+    let query = seen.lock().await[0].clone();
+    assert_eq!(query["limit"], "2");
+    assert_eq!(query["sort"], "volume_24h");
+}
+
+#[tokio::test]
+async fn test_search_puts_exact_symbols_first() {
+    let (url, seen) = pump_server().await;
+    let pump = PumpFunClient::new().unwrap().with_api_url(&url);
+
+    let tokens = pump.search_tokens("DOGE").await.unwrap();
+    assert_eq!(tokens.iter().map(|t| t.symbol.as_str()).collect::<Vec<_>>(), vec!["doge", "SUPERDOGE"]);
+    assert_eq!(tokens[0].market_cap, 45000.0);
+    assert_eq!(seen.lock().await[0]["q"], "DOGE");
+}
+
+#[tokio::test]
+async fn test_bonding_curve_state_and_buy_fill() {
+    let (url, _) = pump_server().await;
+    let pump = PumpFunClient::new().unwrap().with_api_url(&url);
+
+    let curve = pump.get_bonding_curve(DOGEAI).await.unwrap();
+    assert_eq!(curve.mint, DOGEAI);
+    assert!(!curve.complete);
+    assert!((curve.progress_pct() - 50.0).abs() < 1e-9);
+    assert!((curve.sol_in_curve() - 17.586665681).abs() < 1e-9);
+    assert!((curve.price_sol() - 7.0347646e-8).abs() < 1e-14);
+
+    let fill = pump.buy_token(BuyTokenRequest {
+        token_address: DOGEAI.to_string(),
+        amount_sol: 0.25,
+        slippage_bps: 300,
+        user_wallet: "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string(),
+    }).await.unwrap();
+    assert_eq!(fill.tokens_received, 3_542_718.25);
+
+    // The receipt shows what was filled, not a canned amount
+    let receipt = CommandHandler::format_pump_buy("DOGEAI", 0.25, &fill, Some(&curve), "en");
+    assert!(receipt.contains("Bought: 3\\.54M DOGEAI"), "{}", receipt);
+    assert!(receipt.contains("Cost: 0\\.25 SOL"), "{}", receipt);
+    assert!(receipt.contains("Impact: 1\\.84%"), "{}", receipt);
+    assert!(receipt.contains("Bonding: 50\\.0% filled, 17\\.59 SOL in curve"), "{}", receipt);
+    assert!(receipt.contains(&fill.transaction_hash));
+    assert!(!CommandHandler::format_pump_buy("DOGEAI", 0.25, &fill, None, "en").contains("Bonding"));
+}
+
+#[test]
+fn test_curve_progress_percentage() {
+    assert_eq!(curve_progress(INITIAL_REAL_TOKEN_RESERVES), 0.0);
+    assert_eq!(curve_progress(INITIAL_REAL_TOKEN_RESERVES / 4), 75.0);
+    assert_eq!(curve_progress(0), 100.0);
+    // More left than a fresh curve starts with still reads as 0
+    assert_eq!(curve_progress(INITIAL_REAL_TOKEN_RESERVES + 1), 0.0);
+
+    let mut curve: BondingCurve = serde_json::from_str(CURVE).unwrap();
+    curve.real_token_reserves = 1;
+    assert!(curve.progress_pct() < 100.0);
+    curve.complete = true;
+    assert_eq!(curve.progress_pct(), 100.0);
+}
+
+#[tokio::test]
+async fn test_open_breaker_reads_as_unavailable() {
+    let pump = PumpFunClient::new().unwrap()
+        .with_api_url("http://127.0.0.1:9")
+        .with_circuit_breaker(Arc::new(CircuitBreaker::new("pumpfun".to_string(), CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..CircuitBreakerConfig::default()
+        })));
+
+    assert!(pump.search_tokens("doge").await.is_err());
+    let err = pump.get_bonding_curve(DOGEAI).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<BotError>(), Some(BotError::ServiceUnavailable { service }) if service == "pumpfun"), "{:?}", err);
+}
@@ -0,0 +1,171 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::analytics::{
+    daily_snapshots, snapshot_returns, DailyPerformance, Drawdown, MetricsCalculator, RiskMetrics, PERIODS_PER_YEAR,
+    WEEKLY_MIN_PERIODS,
+};
+
+/// Daily returns of +2%, -1%, +3%, -2%, +3% on 100
+///
+/// Mean 0.01; sample variance 0.0022 / 4 = 0.00055, so the daily Sharpe is 0.01 / 0.023452 = 0.42640
+/// and annualizes to 0.42640 × √365 = 8.1464. The downside deviation is √(0.0005 / 5) = 0.01, so with
+/// no risk-free rate the daily Sortino is exactly 1 and annualizes to √365.
+const RETURNS: [f64; 5] = [0.02, -0.01, 0.03, -0.02, 0.03];
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 5, day).unwrap()
+}
+
+fn calculator() -> MetricsCalculator {
+    MetricsCalculator::default().with_risk_free_rate(0.0).with_min_periods(5)
+}
+
+fn series() -> Vec<(NaiveDate, Decimal)> {
+    let mut value = Decimal::from(100);
+    let mut series = vec![(date(1), value)];
+    for (i, r) in RETURNS.iter().enumerate() {
+        value *= Decimal::ONE + Decimal::try_from(*r).unwrap();
+        series.push((date(i as u32 + 2), value));
+    }
+    series
+}
+
+fn day(date: NaiveDate, starting: i64, ending: i64) -> DailyPerformance {
+    DailyPerformance {
+        date,
+        starting_value: Decimal::from(starting),
+        ending_value: Decimal::from(ending),
+        daily_return: Decimal::from(ending - starting),
+        daily_return_percentage: 0.0,
+        trades_executed: 1,
+        winning_trades: 0,
+        losing_trades: 0,
+        total_fees: Decimal::ZERO,
+        total_volume: Decimal::ZERO,
+        best_trade: None,
+        worst_trade: None,
+        tokens_traded: Vec::new(),
+        high_water_mark: Decimal::from(ending),
+        drawdown: 0.0,
+        volatility: 0.0,
+    }
+}
+
+#[test]
+fn test_sharpe_and_sortino_match_the_hand_computed_values() {
+    let calculator = calculator();
+    let sharpe = calculator.calculate_sharpe_ratio(&RETURNS, PERIODS_PER_YEAR).unwrap();
+    assert!((sharpe - 8.146_388).abs() < 1e-5, "{}", sharpe);
+
+    let sortino = calculator.calculate_sortino_ratio(&RETURNS, PERIODS_PER_YEAR).unwrap();
+    assert!((sortino - 365f64.sqrt()).abs() < 1e-9, "{}", sortino);
+
+    // A 3.65% risk-free rate takes 0.0001 a day off the mean
+    let calculator = calculator.with_risk_free_rate(0.0365);
+    let sharpe = calculator.calculate_sharpe_ratio(&RETURNS, PERIODS_PER_YEAR).unwrap();
+    assert!((sharpe - 8.064_924).abs() < 1e-5, "{}", sharpe);
+}
+
+#[test]
+fn test_short_or_flat_histories_have_no_ratios() {
+    let calculator = calculator();
+    assert_eq!(calculator.calculate_sharpe_ratio(&RETURNS[..4], PERIODS_PER_YEAR), None);
+    assert_eq!(calculator.calculate_sortino_ratio(&RETURNS[..4], PERIODS_PER_YEAR), None);
+    assert_eq!(calculator.calculate_drawdown(&series()[..5]), None);
+    assert_eq!(calculator.calculate_annualized_return(&series()[..5]), None);
+
+    // No variation, and no losing days
+    assert_eq!(calculator.calculate_sharpe_ratio(&[0.01; 5], PERIODS_PER_YEAR), None);
+    assert_eq!(calculator.calculate_sortino_ratio(&[0.01; 5], PERIODS_PER_YEAR), None);
+    assert_eq!(calculator.calculate_calmar_ratio(40.0, 0.0), None);
+    assert_eq!(calculator.calculate_calmar_ratio(40.0, 20.0), Some(2.0));
+}
+
+#[test]
+fn test_max_drawdown_reports_its_peak_and_trough_dates() {
+    let calculator = calculator();
+    let series = series();
+    let drawdown = calculator.calculate_drawdown(&series).unwrap();
+
+    // 104.0094 on the 4th down 2% to 101.929212 on the 5th beats the 1% dip on the 3rd
+    assert!((drawdown.percentage - 2.0).abs() < 1e-9);
+    assert_eq!((drawdown.peak_date, drawdown.trough_date), (date(4), date(5)));
+    assert_eq!(calculator.calculate_current_drawdown(&series), Some(0.0));
+
+    let returns = snapshot_returns(&series);
+    for (derived, expected) in returns.iter().zip(RETURNS) {
+        assert!((derived - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_snapshots_fill_days_without_trades() {
+    let snapshots = daily_snapshots(&[day(date(2), 100, 110), day(date(5), 110, 99)]);
+    let values: Vec<(NaiveDate, i64)> = snapshots.iter()
+        .map(|(date, value)| (*date, (*value).try_into().unwrap()))
+        .collect();
+    assert_eq!(values, vec![
+        (date(1), 100),
+        (date(2), 110),
+        (date(3), 110),
+        (date(4), 110),
+        (date(5), 99),
+    ]);
+
+    let returns = snapshot_returns(&snapshots);
+    assert_eq!(returns.len(), 4);
+    assert_eq!(returns[1], 0.0);
+    assert!(daily_snapshots(&[]).is_empty());
+}
+
+#[test]
+fn test_a_full_week_has_weekly_ratios() {
+    // Monday 4 May through Sunday 10 May 2026, one trading day each
+    let values = [100, 102, 101, 104, 102, 105, 103, 106];
+    let week: Vec<DailyPerformance> = values.windows(2)
+        .enumerate()
+        .map(|(i, pair)| day(date(i as u32 + 4), pair[0], pair[1]))
+        .collect();
+    let returns = snapshot_returns(&daily_snapshots(&week));
+    assert_eq!(returns.len(), WEEKLY_MIN_PERIODS);
+
+    // The default minimum is longer than any week
+    let calculator = MetricsCalculator::default();
+    assert!(calculator.calculate_sharpe_ratio(&returns, PERIODS_PER_YEAR).is_none());
+
+    let weekly = calculator.weekly();
+    assert!(weekly.calculate_sharpe_ratio(&returns, PERIODS_PER_YEAR).is_some());
+    assert!(weekly.calculate_sortino_ratio(&returns, PERIODS_PER_YEAR).is_some());
+    // A shorter minimum is kept
+    assert_eq!(calculator.with_min_periods(3).weekly().min_periods(), 3);
+    // Part of a week still isn't enough
+    assert!(weekly.calculate_sharpe_ratio(&returns[..4], PERIODS_PER_YEAR).is_none());
+}
+
+#[test]
+fn test_report_leaves_out_missing_metrics() {
+    let mut metrics = RiskMetrics {
+        value_at_risk_95: 0.0,
+        conditional_var_95: 0.0,
+        max_drawdown: None,
+        current_drawdown: None,
+        downside_deviation: 0.0,
+        upside_potential_ratio: 0.0,
+        sharpe_ratio: None,
+        sortino_ratio: None,
+        calmar_ratio: None,
+        annualized_return: None,
+        days: 3,
+    };
+    let text = metrics.render();
+    assert!(text.contains("Not enough") && !text.contains("0.00"), "{}", text);
+
+    metrics.sharpe_ratio = Some(1.234);
+    metrics.max_drawdown = Some(Drawdown { percentage: 12.5, peak_date: date(4), trough_date: date(9) });
+    metrics.days = 20;
+    let text = metrics.render();
+    assert!(text.contains("Sharpe: 1.23"), "{}", text);
+    assert!(text.contains("Max drawdown: 12.5% (May 04 → May 09)"), "{}", text);
+    assert!(!text.contains("Sortino") && !text.contains("Calmar"), "{}", text);
+}
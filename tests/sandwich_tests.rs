@@ -0,0 +1,143 @@
+use crate::trading::{PoolReserves, PoolSwap, SandwichConfig, SandwichDetector, SandwichMonitor, SwapSide};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use std::sync::Arc;
+
+const USER: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+const ATTACKER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const TRADER: &str = "HN7cABqLq46Es1jh92dQQisAq662SmxELLLsHHe4YWrH";
+const POOL: &str = "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2";
+const OTHER_POOL: &str = "AVs9TA4nWDzfPJE9gGVNJMVhcQy3V9PGazuz33BfG2RA";
+const USER_ID: i64 = 424_242;
+const FEE_BPS: u32 = 25;
+
+/// Constant-product output after the pool fee, as the pool itself would compute it
+fn amm_out(reserve_in: f64, reserve_out: f64, amount_in: f64) -> f64 {
+    let effective_in = amount_in * (1.0 - FEE_BPS as f64 / 10_000.0);
+    reserve_out * effective_in / (reserve_in + effective_in)
+}
+
+/// Buy `quote_in` SOL of the base token, advancing the pool reserves
+fn buy(index: usize, signer: &str, pool: &str, reserves: &mut PoolReserves, quote_in: f64) -> PoolSwap {
+    let before = *reserves;
+    let base_out = amm_out(reserves.quote, reserves.base, quote_in);
+    reserves.quote += quote_in;
+    reserves.base -= base_out;
+    PoolSwap {
+        signature: format!("sig-{}", index),
+        index,
+        signer: signer.to_string(),
+        pool: pool.to_string(),
+        side: SwapSide::Buy,
+        amount_in: quote_in,
+        amount_out: base_out,
+        reserves_before: Some(before),
+    }
+}
+
+/// Sell `base_in` tokens back into the pool
+fn sell(index: usize, signer: &str, pool: &str, reserves: &mut PoolReserves, base_in: f64) -> PoolSwap {
+    let before = *reserves;
+    let quote_out = amm_out(reserves.base, reserves.quote, base_in);
+    reserves.base += base_in;
+    reserves.quote -= quote_out;
+    PoolSwap {
+        signature: format!("sig-{}", index),
+        index,
+        signer: signer.to_string(),
+        pool: pool.to_string(),
+        side: SwapSide::Sell,
+        amount_in: base_in,
+        amount_out: quote_out,
+        reserves_before: Some(before),
+    }
+}
+
+fn initial_reserves() -> PoolReserves {
+    PoolReserves { base: 1_000_000.0, quote: 100.0 }
+}
+
+/// Attacker buys 10 SOL ahead of the user's 1 SOL buy and dumps right after
+fn sandwiched_block() -> Vec<PoolSwap> {
+    let mut reserves = initial_reserves();
+    let mut other = initial_reserves();
+    let front = buy(3, ATTACKER, POOL, &mut reserves, 10.0);
+    let unrelated = buy(4, TRADER, OTHER_POOL, &mut other, 2.0);
+    let user = buy(5, USER, POOL, &mut reserves, 1.0);
+    let back = sell(6, ATTACKER, POOL, &mut reserves, front.amount_out);
+    vec![front, unrelated, user, back]
+}
+
+/// Same pool activity, but the swaps around the user come from different traders
+fn clean_block() -> Vec<PoolSwap> {
+    let mut reserves = initial_reserves();
+    let before = buy(3, TRADER, POOL, &mut reserves, 10.0);
+    let user = buy(5, USER, POOL, &mut reserves, 1.0);
+    let after = sell(6, ATTACKER, POOL, &mut reserves, 50_000.0);
+    vec![before, user, after]
+}
+
+#[test]
+fn test_detects_and_quantifies_sandwich() {
+    let config = SandwichConfig::default();
+    let finding = SandwichDetector::detect("sig-5", &sandwiched_block(), &config)
+        .expect("sandwich should be detected");
+
+    assert_eq!(finding.front_run_signature, "sig-3");
+    assert_eq!(finding.back_run_signature, "sig-6");
+    assert_eq!(finding.attacker, ATTACKER);
+    assert_eq!(finding.pool, POOL);
+
+    // Replaying the user's 1 SOL on the untouched pool gives the fair output
+    let start = initial_reserves();
+    let fair_out = amm_out(start.quote, start.base, 1.0);
+    let user = &sandwiched_block()[2];
+    let expected_pct = (fair_out - user.amount_out) / fair_out * 100.0;
+    let expected_loss = (fair_out - user.amount_out) * user.amount_in / user.amount_out;
+
+    assert!((finding.loss_pct - expected_pct).abs() < 1e-9);
+    assert!((finding.loss_quote - expected_loss).abs() < 1e-9);
+    assert!(finding.loss_pct > 15.0 && finding.loss_pct < 20.0);
+}
+
+#[test]
+fn test_clean_block_is_not_flagged() {
+    let config = SandwichConfig::default();
+    assert!(SandwichDetector::detect("sig-5", &clean_block(), &config).is_none());
+
+    // Swaps on a different pool never count, nor does a trade missing from the block
+    let mut block = sandwiched_block();
+    block[0].pool = OTHER_POOL.to_string();
+    assert!(SandwichDetector::detect("sig-5", &block, &config).is_none());
+    assert!(SandwichDetector::detect("sig-9", &sandwiched_block(), &config).is_none());
+
+    // The user's own trades around their swap are not an attack
+    let mut block = sandwiched_block();
+    block[0].signer = USER.to_string();
+    block[3].signer = USER.to_string();
+    assert!(SandwichDetector::detect("sig-5", &block, &config).is_none());
+}
+
+#[tokio::test]
+async fn test_stats_aggregate_measured_losses() {
+    // Never contacted: outcomes are recorded directly
+    let rpc_client = Arc::new(RpcClient::new("http://127.0.0.1:8899".to_string()));
+    let monitor = SandwichMonitor::new(rpc_client, SandwichConfig::default());
+    let config = SandwichConfig::default();
+
+    let finding = SandwichDetector::detect("sig-5", &sandwiched_block(), &config);
+    let clean = SandwichDetector::detect("sig-5", &clean_block(), &config);
+    let loss = finding.as_ref().unwrap().loss_quote;
+    assert!(monitor.should_notify(finding.as_ref().unwrap()));
+
+    monitor.record_outcome(USER_ID, "sig-5", finding).await;
+    monitor.record_outcome(USER_ID, "sig-clean", clean).await;
+
+    let stats = monitor.stats(USER_ID).await;
+    assert_eq!(stats.inspected, 2);
+    assert_eq!(stats.sandwiched, 1);
+    assert!((stats.total_loss_quote - loss).abs() < 1e-12);
+    assert!((stats.sandwich_rate() - 50.0).abs() < 1e-9);
+    assert!(monitor.finding("sig-5").await.is_some());
+    assert!(monitor.finding("sig-clean").await.is_none());
+    assert_eq!(monitor.stats(1).await.inspected, 0);
+}
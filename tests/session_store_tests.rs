@@ -0,0 +1,164 @@
+use chrono::Duration as ChronoDuration;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use testcontainers::runners::AsyncRunner;
+use testcontainers::ContainerAsync;
+use testcontainers_modules::redis::{Redis, REDIS_PORT};
+
+use crate::bot::wallet_transfer::{TransferPrompt, WalletTransfers};
+use crate::bot::{SetupStep, WalletSetupFlow};
+use crate::cache::{InMemorySessionStore, RedisSessionStore, SessionStore, SessionStoreExt};
+use crate::middleware::rate_limiter::{RateLimitConfig, RateLimitError, UserRateLimiter};
+use crate::testkit::TestHarness;
+
+const TTL: Duration = Duration::from_secs(60);
+
+fn memory() -> Arc<dyn SessionStore> {
+    Arc::new(InMemorySessionStore::default())
+}
+
+/// A throwaway Redis; keep the container alive for as long as the store is used
+async fn redis() -> (ContainerAsync<Redis>, Arc<dyn SessionStore>) {
+    let container = Redis::default().start().await.unwrap();
+    let host = container.get_host().await.unwrap();
+    let port = container.get_host_port_ipv4(REDIS_PORT).await.unwrap();
+    let store = RedisSessionStore::connect(&format!("redis://{}:{}", host, port)).await.unwrap();
+    (container, Arc::new(store.with_prefix("test:")))
+}
+
+/// The contract both backends have to meet
+async fn exercise(store: Arc<dyn SessionStore>) {
+    assert_eq!(store.get("a").await.unwrap(), None);
+
+    assert_eq!(store.set("a", json!({"step": 1}), TTL).await.unwrap(), 1);
+    let stored = store.get("a").await.unwrap().unwrap();
+    assert_eq!(stored.value, json!({"step": 1}));
+    assert_eq!(stored.version, 1);
+
+    // A writer holding a stale version loses
+    assert_eq!(store.compare_and_set("a", Some(1), json!({"step": 2}), TTL).await.unwrap(), Some(2));
+    assert_eq!(store.compare_and_set("a", Some(1), json!({"step": 3}), TTL).await.unwrap(), None);
+    assert_eq!(store.compare_and_set("a", None, json!({"step": 3}), TTL).await.unwrap(), None);
+    assert_eq!(store.get_as::<serde_json::Value>("a").await.unwrap(), Some(json!({"step": 2})));
+
+    // Taken exactly once
+    assert_eq!(store.take("a").await.unwrap(), Some(json!({"step": 2})));
+    assert_eq!(store.take("a").await.unwrap(), None);
+    assert_eq!(store.compare_and_set("a", None, json!(true), TTL).await.unwrap(), Some(1));
+    store.delete("a").await.unwrap();
+    assert_eq!(store.get("a").await.unwrap(), None);
+
+    assert_eq!(store.increment("hits", TTL).await.unwrap(), 1);
+    assert_eq!(store.increment("hits", TTL).await.unwrap(), 2);
+
+    // Everything expires
+    store.set("short", json!(1), Duration::from_millis(50)).await.unwrap();
+    store.increment("short_hits", Duration::from_millis(50)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(120)).await;
+    assert_eq!(store.get("short").await.unwrap(), None);
+    assert_eq!(store.take("short").await.unwrap(), None);
+    assert_eq!(store.increment("short_hits", TTL).await.unwrap(), 1);
+}
+
+/// Many instances tapping the same confirmation at once
+async fn race_claims(store: Arc<dyn SessionStore>) {
+    let claims = (0..32).map(|_| {
+        let store = store.clone();
+        tokio::spawn(async move { store.claim("confirm:1:42", TTL).await.unwrap() })
+    });
+    let winners = futures::future::join_all(claims).await.into_iter().filter(|won| *won.as_ref().unwrap()).count();
+    assert_eq!(winners, 1);
+}
+
+#[tokio::test]
+async fn test_memory_store_contract() {
+    exercise(memory()).await;
+}
+
+#[tokio::test]
+async fn test_only_one_claim_wins_in_memory() {
+    race_claims(memory()).await;
+}
+
+#[tokio::test]
+#[ignore = "starts a Redis container; run with --ignored"]
+async fn test_redis_store_contract() {
+    let (_container, store) = redis().await;
+    exercise(store).await;
+}
+
+#[tokio::test]
+#[ignore = "starts a Redis container; run with --ignored"]
+async fn test_only_one_claim_wins_in_redis() {
+    let (_container, store) = redis().await;
+    race_claims(store).await;
+}
+
+#[tokio::test]
+#[ignore = "starts a Redis container; run with --ignored"]
+async fn test_confirm_on_one_instance_exports_on_another() {
+    let (_container, store) = redis().await;
+    let first = WalletTransfers::default().with_sessions(store.clone());
+    let second = WalletTransfers::default().with_sessions(store);
+
+    first.confirm(7).await;
+    assert!(second.authorize_export(7).await);
+    assert!(!first.authorize_export(7).await, "a /confirm is spent once across instances");
+
+    first.ask(7, TransferPrompt::Export, Some(9)).await;
+    assert_eq!(second.take(7).await.unwrap().prompt_message_id, Some(9));
+    assert!(first.take(7).await.is_none());
+}
+
+#[tokio::test]
+async fn test_shared_transfers_and_cancel() {
+    let store = memory();
+    let first = WalletTransfers::default().with_sessions(store.clone());
+    let second = WalletTransfers::new(ChronoDuration::minutes(2), ChronoDuration::minutes(2)).with_sessions(store);
+
+    first.confirm(1).await;
+    first.ask(1, TransferPrompt::Export, None).await;
+    second.cancel(1).await;
+    assert!(!first.authorize_export(1).await);
+    assert!(first.take(1).await.is_none());
+}
+
+#[tokio::test]
+async fn test_setup_steps_are_shared_and_cancelled() {
+    let store = memory();
+    assert_eq!(WalletSetupFlow::step(store.as_ref(), "5").await, None);
+
+    store.put("setup:5", &SetupStep::AwaitingImport, TTL).await.unwrap();
+    assert_eq!(WalletSetupFlow::step(store.as_ref(), "5").await, Some(SetupStep::AwaitingImport));
+
+    WalletSetupFlow::cancel(store.as_ref(), "5").await;
+    assert_eq!(WalletSetupFlow::step(store.as_ref(), "5").await, None);
+}
+
+#[tokio::test]
+async fn test_rate_limits_hold_across_instances() {
+    let store = memory();
+    // Same limit per hour, so a minute rolling over mid-test can't reset it
+    let config = RateLimitConfig { requests_per_minute: 3, requests_per_hour: 3, ..RateLimitConfig::default() };
+    let first = UserRateLimiter::new(config.clone()).with_sessions(store.clone());
+    let second = UserRateLimiter::new(config).with_sessions(store);
+
+    assert!(first.check_rate_limit("9").await.is_ok());
+    assert!(second.check_rate_limit("9").await.is_ok());
+    assert!(first.check_rate_limit("9").await.is_ok());
+    assert!(matches!(second.check_rate_limit("9").await, Err(RateLimitError::RateLimitExceeded)));
+    // Other users have their own counters
+    assert!(second.check_rate_limit("10").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_harness_shares_one_store() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    let services = &harness.services;
+
+    services.wallet_transfers.confirm(3).await;
+    assert!(services.sessions.get("transfer:confirm:3").await.unwrap().is_some());
+    assert!(services.wallet_transfers.authorize_export(3).await);
+    assert!(services.sessions.get("transfer:confirm:3").await.unwrap().is_none());
+}
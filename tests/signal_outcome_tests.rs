@@ -0,0 +1,161 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::ai::{
+    evaluate_signal, HolderTrend, MarketConditions, SignalOutcome, SignalOutcomeTracker, SignalPriceHistory,
+    SignalStatus, SignalStrength, SignalType, TechnicalIndicators, TradingSignal, VolumeTrend,
+};
+use crate::errors::Result;
+
+/// Fixture prices per mint, served for whatever window is asked
+#[derive(Default)]
+struct FixtureHistory {
+    prices: HashMap<String, Vec<(DateTime<Utc>, f64)>>,
+}
+
+#[async_trait]
+impl SignalPriceHistory for FixtureHistory {
+    async fn prices_between(&self, mint: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<(DateTime<Utc>, f64)>> {
+        Ok(self.prices.get(mint)
+            .map(|prices| prices.iter().filter(|(at, _)| *at >= from && *at <= to).copied().collect())
+            .unwrap_or_default())
+    }
+}
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap()
+}
+
+/// One price per hour over the four-hour window, starting at generation
+fn hourly(prices: &[f64]) -> Vec<(DateTime<Utc>, f64)> {
+    prices.iter().enumerate().map(|(i, p)| (start() + Duration::hours(i as i64), *p)).collect()
+}
+
+fn signal(mint: &str, signal_type: SignalType, target: f64, stop: f64) -> TradingSignal {
+    TradingSignal {
+        token_address: mint.to_string(),
+        symbol: mint.to_uppercase(),
+        signal_type,
+        strength: SignalStrength::Moderate,
+        confidence: 70.0,
+        entry_price: 1.0,
+        target_price: Some(target),
+        stop_loss: Some(stop),
+        risk_reward_ratio: 2.0,
+        reasoning: "fixture".to_string(),
+        technical_indicators: TechnicalIndicators {
+            price_momentum: 0.0,
+            volume_trend: VolumeTrend::Stable,
+            liquidity_score: 50.0,
+            volatility: 0.1,
+            buy_sell_ratio: 1.0,
+            holder_trend: HolderTrend::Neutral,
+        },
+        market_conditions: MarketConditions {
+            overall_sentiment: "neutral".to_string(),
+            trending_rank: None,
+            sector_performance: "DeFi".to_string(),
+            correlation_with_sol: 0.0,
+        },
+        ai_insights: None,
+        generated_at: start(),
+        expires_at: start() + Duration::hours(4),
+    }
+}
+
+#[test]
+fn test_long_signal_hits_target_at_the_target_price() {
+    let buy = signal("long", SignalType::Buy, 1.1, 0.95);
+    let performance = evaluate_signal(&buy, &hourly(&[1.0, 0.97, 1.12, 0.9, 1.0])).unwrap();
+
+    assert_eq!(performance.outcome, SignalOutcome::HitTarget);
+    assert!(performance.hit_target && !performance.hit_stop_loss);
+    assert!((performance.return_percent - 10.0).abs() < 1e-9);
+    assert!((performance.max_drawdown_percent + 3.0).abs() < 1e-9);
+    assert_eq!(performance.duration_hours, 2.0);
+}
+
+#[test]
+fn test_short_signal_is_stopped_out_by_a_rise() {
+    let sell = signal("short", SignalType::Sell, 0.9, 1.05);
+    let performance = evaluate_signal(&sell, &hourly(&[1.0, 0.95, 1.06, 0.8])).unwrap();
+
+    assert_eq!(performance.outcome, SignalOutcome::HitStop);
+    assert!((performance.return_percent + 5.0).abs() < 1e-9);
+    // The dip to 0.95 was 5% in the short's favour
+    assert!((performance.max_profit_percent - 5.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_untouched_levels_expire_at_the_last_price_in_the_window() {
+    let buy = signal("flat", SignalType::Accumulate, 1.2, 0.8);
+    // The 1.5 print lands after the signal expired
+    let mut prices = hourly(&[1.0, 1.05, 0.98, 1.03, 1.02]);
+    prices.push((start() + Duration::hours(5), 1.5));
+    let performance = evaluate_signal(&buy, &prices).unwrap();
+
+    assert_eq!(performance.outcome, SignalOutcome::ExpiredFlat);
+    assert!((performance.return_percent - 2.0).abs() < 1e-9);
+    assert_eq!(performance.duration_hours, 4.0);
+
+    // Nothing to score without prices, or for a hold
+    assert!(evaluate_signal(&buy, &[]).is_none());
+    assert!(evaluate_signal(&signal("hold", SignalType::Hold, 1.2, 0.8), &prices).is_none());
+}
+
+#[tokio::test]
+async fn test_tracker_scores_signals_once_their_window_closes() {
+    let mut history = FixtureHistory::default();
+    history.prices.insert("win".to_string(), hourly(&[1.0, 1.05, 1.11, 1.0, 1.0]));
+    history.prices.insert("loss".to_string(), hourly(&[1.0, 0.9, 1.0, 1.0, 1.0]));
+    history.prices.insert("flat".to_string(), hourly(&[1.0, 1.0, 1.0, 1.0, 0.99]));
+    let tracker = SignalOutcomeTracker::new(Arc::new(history));
+
+    tracker.track(&signal("win", SignalType::Buy, 1.1, 0.95)).await.unwrap();
+    tracker.track(&signal("loss", SignalType::Buy, 1.1, 0.95)).await.unwrap();
+    tracker.track(&signal("flat", SignalType::StrongSell, 0.8, 1.1)).await.unwrap();
+    tracker.track(&signal("hold", SignalType::Hold, 1.1, 0.95)).await.unwrap();
+    // The same signal twice is tracked once
+    tracker.track(&signal("win", SignalType::Buy, 1.1, 0.95)).await.unwrap();
+    assert_eq!(tracker.pending_count().await, 3);
+
+    // Still inside the window
+    assert!(tracker.evaluate_due(start() + Duration::hours(3)).await.unwrap().is_empty());
+
+    let evaluated = tracker.evaluate_due(start() + Duration::hours(5)).await.unwrap();
+    assert_eq!(evaluated.len(), 3);
+    assert_eq!(tracker.pending_count().await, 0);
+    let status = |mint: &str| evaluated.iter().find(|t| t.signal.token_address == mint).unwrap().status;
+    assert_eq!(status("win"), SignalStatus::Evaluated(SignalOutcome::HitTarget));
+    assert_eq!(status("loss"), SignalStatus::Evaluated(SignalOutcome::HitStop));
+    assert_eq!(status("flat"), SignalStatus::Evaluated(SignalOutcome::ExpiredFlat));
+
+    let stats = tracker.stats().await;
+    assert_eq!((stats.total_signals, stats.overall.evaluated, stats.overall.hit_target), (3, 3, 1));
+    assert!((stats.overall.success_rate() - 100.0 / 3.0).abs() < 1e-9);
+    // +10%, -5% and +1% for the short
+    assert!((stats.overall.average_return() - 2.0).abs() < 1e-9);
+
+    let buys = &stats.by_type.iter().find(|(t, _)| *t == SignalType::Buy).unwrap().1;
+    assert_eq!((buys.evaluated, buys.hit_target, buys.hit_stop), (2, 1, 1));
+    assert_eq!(buys.success_rate(), 50.0);
+    let shorts = &stats.by_type.iter().find(|(t, _)| *t == SignalType::StrongSell).unwrap().1;
+    assert_eq!((shorts.evaluated, shorts.expired_flat), (1, 1));
+}
+
+#[tokio::test]
+async fn test_unpriced_signals_wait_then_drop_out_of_the_stats() {
+    let tracker = SignalOutcomeTracker::new(Arc::new(FixtureHistory::default()));
+    tracker.track(&signal("dark", SignalType::Buy, 1.1, 0.95)).await.unwrap();
+
+    // History may just be lagging
+    assert!(tracker.evaluate_due(start() + Duration::hours(6)).await.unwrap().is_empty());
+    assert_eq!(tracker.pending_count().await, 1);
+
+    let evaluated = tracker.evaluate_due(start() + Duration::days(4)).await.unwrap();
+    assert_eq!(evaluated[0].status, SignalStatus::Unpriced);
+    assert_eq!(tracker.pending_count().await, 0);
+    assert_eq!(tracker.stats().await.overall.evaluated, 0);
+}
@@ -0,0 +1,59 @@
+use crate::testkit::MockRpc;
+use crate::wallet::SignatureWatcher;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::sync::Arc;
+
+const PAGE: usize = 20;
+
+fn signatures(count: usize) -> Vec<String> {
+    (0..count).map(|_| Signature::new_unique().to_string()).collect()
+}
+
+fn watcher(rpc: &MockRpc) -> SignatureWatcher {
+    SignatureWatcher::new(Arc::new(RpcClient::new(rpc.url())), None, PAGE)
+}
+
+async fn pages_read(rpc: &MockRpc) -> usize {
+    rpc.methods_called().await.iter().filter(|m| *m == "getSignaturesForAddress").count()
+}
+
+#[tokio::test]
+async fn test_a_burst_bigger_than_a_page_is_read_back_to_the_last_signature() {
+    let rpc = MockRpc::start().await.unwrap();
+    let wallet = Pubkey::new_unique().to_string();
+    let watcher = watcher(&rpc);
+    let seen = signatures(3);
+    rpc.push_signatures(&wallet, &seen).await;
+
+    // The baseline only needs the newest
+    let baseline = watcher.signatures_since(&wallet, None).await.unwrap();
+    assert_eq!(baseline[0].signature, seen[2]);
+    assert_eq!(pages_read(&rpc).await, 1);
+
+    let burst = signatures(2 * PAGE + 5);
+    rpc.push_signatures(&wallet, &burst).await;
+    let found = watcher.signatures_since(&wallet, Some(&seen[2])).await.unwrap();
+
+    // Every one of them, newest first, and nothing from before the last signature
+    let newest_first: Vec<String> = burst.iter().rev().cloned().collect();
+    assert_eq!(found.iter().map(|s| s.signature.clone()).collect::<Vec<_>>(), newest_first);
+    assert_eq!(pages_read(&rpc).await, 1 + 3);
+}
+
+#[tokio::test]
+async fn test_a_quiet_wallet_costs_one_page() {
+    let rpc = MockRpc::start().await.unwrap();
+    let wallet = Pubkey::new_unique().to_string();
+    let watcher = watcher(&rpc);
+    let seen = signatures(PAGE);
+    rpc.push_signatures(&wallet, &seen).await;
+
+    assert!(watcher.signatures_since(&wallet, Some(&seen[PAGE - 1])).await.unwrap().is_empty());
+    let newer = signatures(PAGE - 1);
+    rpc.push_signatures(&wallet, &newer).await;
+    assert_eq!(watcher.signatures_since(&wallet, Some(&seen[PAGE - 1])).await.unwrap().len(), PAGE - 1);
+    assert_eq!(pages_read(&rpc).await, 2);
+
+    assert!(watcher.signatures_since("not-a-wallet", None).await.is_err());
+}
@@ -0,0 +1,143 @@
+use chrono::{Duration, TimeZone, Utc};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::errors::{BotError, Result};
+use crate::trading::{
+    MarketTick, SmartSellTimer, SmartTimingConfig, TickSource, TimingDecision, TimingReason, TimingSession,
+};
+
+const MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCaXpRjrq8jPUnZ8yw2";
+
+fn config() -> SmartTimingConfig {
+    SmartTimingConfig {
+        window: Duration::seconds(90),
+        tick_interval: Duration::zero(),
+        min_size_sol: 10.0,
+        ..SmartTimingConfig::default()
+    }
+}
+
+fn tick(secs: i64, quote_out_sol: f64, spread_bps: f64, depth_sol: f64) -> MarketTick {
+    MarketTick {
+        at: Utc.with_ymd_and_hms(2026, 5, 1, 12, 0, 0).unwrap() + Duration::seconds(secs),
+        quote_out_sol,
+        spread_bps,
+        depth_sol,
+    }
+}
+
+/// Replays ticks in order, failing once they run out
+struct ScriptedBook {
+    ticks: Mutex<Vec<MarketTick>>,
+}
+
+impl ScriptedBook {
+    fn new(ticks: Vec<MarketTick>) -> Arc<Self> {
+        Arc::new(Self { ticks: Mutex::new(ticks) })
+    }
+}
+
+#[async_trait::async_trait]
+impl TickSource for ScriptedBook {
+    async fn tick(&self, _mint: &str, _token_amount: f64, _slippage_bps: u16) -> Result<MarketTick> {
+        let mut ticks = self.ticks.lock().await;
+        if ticks.is_empty() {
+            return Err(BotError::internal("book closed".to_string()));
+        }
+        Ok(ticks.remove(0))
+    }
+}
+
+/// Thin book at the start, a tight deep moment mid-window, then thin again
+fn mid_window_script() -> Vec<MarketTick> {
+    vec![
+        tick(0, 20.0, 60.0, 400.0),
+        tick(10, 19.9, 55.0, 420.0),
+        tick(20, 20.1, 58.0, 410.0),
+        tick(30, 19.8, 70.0, 380.0),
+        tick(40, 20.0, 65.0, 400.0),
+        tick(50, 20.6, 15.0, 800.0),
+        tick(60, 20.2, 50.0, 450.0),
+        tick(70, 19.7, 80.0, 350.0),
+    ]
+}
+
+#[test]
+fn test_best_mid_window_moment_is_selected() {
+    let mut session = TimingSession::new(config());
+    let script = mid_window_script();
+
+    let mut decided_at = None;
+    for t in &script {
+        if let TimingDecision::Execute(reason) = session.observe(*t) {
+            assert_eq!(reason, TimingReason::BestMoment);
+            decided_at = Some(t.at);
+            break;
+        }
+    }
+    assert_eq!(decided_at, Some(script[5].at));
+
+    let outcome = session.expire();
+    assert_eq!(outcome.reason, TimingReason::BestMoment);
+    assert_eq!(outcome.ticks_seen, 6);
+    assert_eq!(outcome.waited(), Duration::seconds(50));
+    // Savings come from the recorded quotes: 20.6 chosen vs 20.0 at window start
+    assert!((outcome.savings_sol().unwrap() - 0.6).abs() < 1e-9);
+    assert!((outcome.savings_percent().unwrap() - 3.0).abs() < 1e-9);
+    assert!(outcome.summary().contains("+0.6000 SOL"));
+}
+
+#[test]
+fn test_expiry_fallback_when_nothing_beats_observation() {
+    let mut session = TimingSession::new(config());
+    let script = vec![
+        tick(0, 20.0, 20.0, 800.0),
+        tick(15, 19.9, 40.0, 600.0),
+        tick(30, 19.8, 60.0, 500.0),
+        tick(60, 19.6, 80.0, 400.0),
+        tick(89, 19.5, 90.0, 380.0),
+        tick(90, 19.4, 95.0, 360.0),
+        tick(100, 25.0, 5.0, 2000.0),
+    ];
+
+    let mut decisions = Vec::new();
+    for t in &script {
+        decisions.push(session.observe(*t));
+        if matches!(decisions.last(), Some(TimingDecision::Execute(_))) {
+            break;
+        }
+    }
+
+    // Never held past the window, even though a better tick follows it
+    assert_eq!(decisions.len(), 6);
+    assert_eq!(decisions.last(), Some(&TimingDecision::Execute(TimingReason::Expiry)));
+    let outcome = session.expire();
+    assert_eq!(outcome.chosen.unwrap().at, script[5].at);
+    assert!(outcome.savings_sol().unwrap() < 0.0);
+    assert!(outcome.summary().contains("window expired"));
+}
+
+#[test]
+fn test_small_sells_skip_the_window() {
+    let mut session = TimingSession::new(config());
+    assert_eq!(session.observe(tick(0, 4.0, 30.0, 100.0)), TimingDecision::Execute(TimingReason::BelowThreshold));
+    assert_eq!(session.expire().waited(), Duration::zero());
+}
+
+#[tokio::test]
+async fn test_timer_drives_scripted_book() {
+    let timer = SmartSellTimer::new(config(), ScriptedBook::new(mid_window_script()));
+    let outcome = timer.wait_for_moment(MINT, 1_000_000.0, 100).await;
+    assert_eq!(outcome.reason, TimingReason::BestMoment);
+    assert_eq!(outcome.chosen.unwrap().quote_out_sol, 20.6);
+
+    // The book stops answering mid-window: the last recorded quote stands at expiry
+    let timer = SmartSellTimer::new(config(), ScriptedBook::new(mid_window_script()[..3].to_vec()));
+    let outcome = timer.wait_for_moment(MINT, 1_000_000.0, 100).await;
+    assert_eq!(outcome.reason, TimingReason::Expiry);
+    assert_eq!(outcome.ticks_seen, 3);
+
+    let timer = SmartSellTimer::new(config(), ScriptedBook::new(Vec::new()));
+    assert_eq!(timer.wait_for_moment(MINT, 1_000_000.0, 100).await.reason, TimingReason::NoQuotes);
+}
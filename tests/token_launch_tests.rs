@@ -0,0 +1,144 @@
+use crate::bot::token_launch::{
+    parse_supply, validate_image, validate_name, validate_symbol, LaunchDraft, LaunchImage, LaunchInput, LaunchStep,
+    LaunchTransition, LaunchWizard, MAX_IMAGE_BYTES,
+};
+use crate::cache::{InMemorySessionStore, SessionStore};
+use crate::trading::TokenPreset;
+use chrono::Utc;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use std::sync::Arc;
+
+const USER: i64 = 804_001;
+
+fn text(value: &str) -> LaunchInput {
+    LaunchInput::Text(value.to_string())
+}
+
+fn image(mime_type: &str, size: u32) -> LaunchInput {
+    LaunchInput::Image(LaunchImage { file_id: "AgACAgQAAxkBAAIC".to_string(), mime_type: mime_type.to_string(), size })
+}
+
+fn rejected(transition: LaunchTransition) -> String {
+    match transition {
+        LaunchTransition::Rejected(reason) => reason,
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_wizard_walks_forward_and_back() {
+    let mut draft = LaunchDraft::new(Utc::now());
+    assert!(rejected(draft.apply(LaunchInput::Back)).contains("first step"));
+
+    assert_eq!(draft.apply(text("  Moon   Cat ")), LaunchTransition::Moved(LaunchStep::Symbol));
+    assert_eq!(draft.apply(text("$mcat")), LaunchTransition::Moved(LaunchStep::Description));
+    // Going back keeps the answer until it's replaced
+    assert_eq!(draft.apply(LaunchInput::Back), LaunchTransition::Moved(LaunchStep::Symbol));
+    assert_eq!(draft.symbol.as_deref(), Some("MCAT"));
+    assert_eq!(draft.apply(text("meow")), LaunchTransition::Moved(LaunchStep::Description));
+    assert_eq!(draft.apply(LaunchInput::Skip), LaunchTransition::Moved(LaunchStep::Image));
+
+    assert!(rejected(draft.apply(text("here's the picture"))).contains("photo"));
+    assert_eq!(draft.apply(image("image/png", 300_000)), LaunchTransition::Moved(LaunchStep::Preset));
+    assert!(rejected(draft.apply(LaunchInput::Confirm)).contains("preset"));
+    assert_eq!(draft.apply(LaunchInput::Preset(TokenPreset::CreatorToken)), LaunchTransition::Moved(LaunchStep::Supply));
+    assert_eq!(draft.apply(text("21,000,000")), LaunchTransition::Moved(LaunchStep::Review));
+    assert!(rejected(draft.apply(text("looks good"))).contains("Confirm"));
+
+    let (preset, details) = draft.details().unwrap();
+    assert_eq!(preset, TokenPreset::CreatorToken);
+    assert_eq!((details.name.as_str(), details.symbol.as_str()), ("Moon Cat", "MEOW"));
+    assert_eq!(details.description, None);
+    assert_eq!(details.initial_supply, Some(21_000_000));
+    assert_eq!(details.additional_metadata["telegram_image_file_id"], "AgACAgQAAxkBAAIC");
+
+    assert_eq!(draft.apply(LaunchInput::Confirm), LaunchTransition::Confirmed);
+    assert_eq!(draft.apply(LaunchInput::Cancel), LaunchTransition::Cancelled);
+}
+
+#[test]
+fn test_step_input_validation() {
+    assert_eq!(validate_name("Solana").unwrap_err(), "\"Solana\" is reserved for an existing token. Pick another name.");
+    assert!(validate_name("wrapped   SOL").is_err());
+    assert!(validate_name("").is_err());
+    assert!(validate_name(&"x".repeat(33)).is_err());
+    assert_eq!(validate_name(&"x".repeat(32)).unwrap().len(), 32);
+
+    assert_eq!(validate_symbol(" doge2 ").unwrap(), "DOGE2");
+    assert!(validate_symbol("A").unwrap_err().contains("2-10"));
+    assert!(validate_symbol("ABCDEFGHIJK").is_err());
+    assert!(validate_symbol("MO-ON").unwrap_err().contains("letters"));
+    assert!(validate_symbol("usdc").unwrap_err().contains("reserved"));
+
+    assert!(validate_image("image/webp", MAX_IMAGE_BYTES).is_ok());
+    assert!(validate_image("image/png", MAX_IMAGE_BYTES + 1).unwrap_err().contains("5 MB"));
+    assert!(validate_image("image/svg+xml", 1_000).is_err());
+    assert!(validate_image("", 1_000).is_err());
+
+    assert_eq!(parse_supply("1_000 000"), Ok(1_000_000));
+    assert!(parse_supply("0").is_err());
+    assert!(parse_supply("1.5").is_err());
+    assert!(parse_supply("1000000000001").is_err());
+
+    // A rejected answer leaves the draft where it was
+    let mut draft = LaunchDraft::new(Utc::now());
+    draft.apply(text("Moon Cat"));
+    let before = draft.clone();
+    rejected(draft.apply(text("BTC")));
+    rejected(draft.apply(LaunchInput::Skip));
+    rejected(draft.apply(image("image/png", 1_000)));
+    assert_eq!(draft, before);
+}
+
+#[tokio::test]
+async fn test_wizard_is_shared_and_created_once() {
+    let sessions: Arc<dyn SessionStore> = Arc::new(InMemorySessionStore::default());
+    // Two bot instances behind the same store
+    let first = Arc::new(LaunchWizard::default().with_sessions(sessions.clone()));
+    let second = Arc::new(LaunchWizard::default().with_sessions(sessions));
+    assert!(first.advance(USER, text("Moon Cat")).await.unwrap().is_none());
+
+    first.start(USER).await.unwrap();
+    for (wizard, input) in [
+        (&first, text("Moon Cat")),
+        (&second, text("MEOW")),
+        (&first, text("The cat that went to the moon")),
+        (&second, LaunchInput::Skip),
+        (&first, LaunchInput::Preset(TokenPreset::Basic)),
+        (&second, LaunchInput::Skip),
+    ] {
+        let (_, transition) = wizard.advance(USER, input).await.unwrap().unwrap();
+        assert!(matches!(transition, LaunchTransition::Moved(_)), "{:?}", transition);
+    }
+    let draft = second.draft(USER).await.unwrap();
+    assert_eq!(draft.step, LaunchStep::Review);
+
+    let (config, preview, fee) = first.review(&draft).unwrap();
+    assert_eq!((config.name.as_str(), config.symbol.as_str()), ("Moon Cat", "MEOW"));
+    assert_eq!(config.initial_supply, 1_000_000_000);
+    assert!((fee - 0.003).abs() < 1e-12, "{}", fee);
+    assert_eq!(preview.features, vec!["Rich metadata support".to_string()]);
+    assert_eq!(first.presets().iter().map(|(p, _)| *p).collect::<Vec<_>>(), vec![
+        TokenPreset::Basic,
+        TokenPreset::CreatorToken,
+        TokenPreset::CommunityToken,
+    ]);
+
+    // A double tap on Confirm across instances creates one token
+    let (a, b) = tokio::join!(first.advance(USER, LaunchInput::Confirm), second.advance(USER, LaunchInput::Confirm));
+    let confirmed: Vec<_> = [a.unwrap(), b.unwrap()].into_iter().flatten().collect();
+    assert_eq!(confirmed.len(), 1);
+    let (confirmed, transition) = confirmed.into_iter().next().unwrap();
+    assert_eq!(transition, LaunchTransition::Confirmed);
+    assert!(first.draft(USER).await.is_none());
+
+    let payer = Keypair::new();
+    let result = first.launch(&confirmed, &payer).await.unwrap();
+    assert_eq!(result.creator_info.creator_address, payer.pubkey());
+    assert_eq!(result.explorer_url, format!("https://solscan.io/token/{}", result.mint_address));
+
+    // Presets the creator doesn't offer can't be reviewed
+    let mut meme = confirmed.clone();
+    meme.preset = Some(TokenPreset::MemeToken);
+    assert!(first.review(&meme).is_err());
+}
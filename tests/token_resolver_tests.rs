@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+use crate::api::TokenDataV2;
+use crate::errors::BotError;
+use crate::trading::{TokenListConfig, TokenListSource, TokenLookup, TokenResolver};
+
+/// Token list served in pages, or failing on demand
+#[derive(Default)]
+struct FixtureList {
+    tokens: Vec<TokenDataV2>,
+    fail: AtomicBool,
+    pages_fetched: AtomicU32,
+}
+
+#[async_trait]
+impl TokenListSource for FixtureList {
+    async fn fetch_page(&self, page: u32, page_size: u32) -> anyhow::Result<Vec<TokenDataV2>> {
+        if self.fail.load(Ordering::SeqCst) {
+            return Err(BotError::jupiter_api("Token API failed with status 503").into());
+        }
+        self.pages_fetched.fetch_add(1, Ordering::SeqCst);
+        let start = ((page - 1) * page_size) as usize;
+        Ok(self.tokens.iter().skip(start).take(page_size as usize).cloned().collect())
+    }
+}
+
+fn token(mint: &str, symbol: &str, name: &str, verified: bool, strict: bool, daily_volume: u64) -> TokenDataV2 {
+    serde_json::from_value(json!({
+        "address": mint,
+        "name": name,
+        "symbol": symbol,
+        "decimals": 6,
+        "tags": [],
+        "verified": verified,
+        "strictList": strict,
+        "dailyVolume": daily_volume,
+        "marketCap": daily_volume * 10,
+    }))
+    .unwrap()
+}
+
+struct Mints {
+    bonk: String,
+    copycat: String,
+    lookalike: String,
+    jup: String,
+}
+
+fn fixture() -> (Arc<FixtureList>, Mints) {
+    let mints = Mints {
+        bonk: Pubkey::new_unique().to_string(),
+        copycat: Pubkey::new_unique().to_string(),
+        lookalike: Pubkey::new_unique().to_string(),
+        jup: Pubkey::new_unique().to_string(),
+    };
+    let list = FixtureList {
+        tokens: vec![
+            // The copycat trades more than the real one, but isn't verified
+            token(&mints.copycat, "BONK", "Bonk 2.0", false, false, 9_000_000),
+            token(&mints.bonk, "BONK", "Bonk", true, true, 4_000_000),
+            token(&mints.lookalike, "bonk", "Bonk Inu", true, false, 100_000),
+            token(&mints.jup, "JUP", "Jupiter", true, true, 20_000_000),
+        ],
+        ..FixtureList::default()
+    };
+    (Arc::new(list), mints)
+}
+
+#[tokio::test]
+async fn test_shared_symbols_need_a_choice_with_verified_tokens_first() {
+    let (list, mints) = fixture();
+    let resolver = TokenResolver::new(list, TokenListConfig::default());
+    assert_eq!(resolver.refresh().await.unwrap(), 4);
+
+    let lookup = resolver.lookup("bonk").await;
+    let TokenLookup::Ambiguous(candidates) = &lookup else { panic!("expected a choice, got {:?}", lookup) };
+    let order: Vec<&str> = candidates.iter().map(|c| c.mint.as_str()).collect();
+    assert_eq!(order, vec![mints.bonk.as_str(), mints.lookalike.as_str(), mints.copycat.as_str()]);
+
+    let prompt = lookup.prompt("bonk", "/qbuy 0.1").unwrap();
+    assert!(prompt.starts_with("🔎 3 tokens match \"bonk\""), "{}", prompt);
+    assert!(prompt.contains("1. BONK (Bonk) ✅ · $4.0M vol · $40.0M mcap"), "{}", prompt);
+    assert!(prompt.contains(&format!("/qbuy 0.1 {}", mints.copycat)), "{}", prompt);
+    assert!(prompt.contains("copycats"), "{}", prompt);
+}
+
+#[tokio::test]
+async fn test_symbols_names_mints_and_typos_resolve() {
+    let (list, mints) = fixture();
+    let resolver = TokenResolver::new(list, TokenListConfig::default());
+    resolver.refresh().await.unwrap();
+
+    let found = |lookup: TokenLookup| match lookup {
+        TokenLookup::Found(token) => token,
+        other => panic!("expected a match, got {:?}", other),
+    };
+    assert_eq!(found(resolver.lookup("jup").await).mint, mints.jup);
+    assert_eq!(found(resolver.lookup("Jupiter").await).mint, mints.jup);
+    assert_eq!(found(resolver.lookup("Bonk Inu").await).mint, mints.lookalike);
+
+    // Mints resolve as typed, with the listed symbol when there is one
+    assert_eq!(found(resolver.lookup(&mints.jup).await).symbol, "JUP");
+    let unlisted = Pubkey::new_unique().to_string();
+    assert_eq!(found(resolver.lookup(&unlisted).await).mint, unlisted);
+
+    // Close misses are offered, never bought outright
+    let TokenLookup::Suggestions(suggestions) = resolver.lookup("JUPP").await else { panic!("expected suggestions") };
+    assert_eq!(suggestions[0].mint, mints.jup);
+    let TokenLookup::Suggestions(suggestions) = resolver.lookup("jupi").await else { panic!("expected suggestions") };
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(resolver.lookup("QWERTYZ").await, TokenLookup::NotFound);
+    assert_eq!(resolver.lookup("J").await, TokenLookup::NotFound);
+}
+
+#[tokio::test]
+async fn test_built_in_tokens_resolve_before_the_list_loads() {
+    let (list, _) = fixture();
+    list.fail.store(true, Ordering::SeqCst);
+    let resolver = TokenResolver::new(list, TokenListConfig::default());
+    assert!(resolver.refresh().await.is_err());
+    assert_eq!(resolver.refreshed_at().await, None);
+
+    let TokenLookup::Found(bonk) = resolver.lookup("bonk").await else { panic!("expected the built-in BONK") };
+    assert_eq!(bonk.mint, TokenResolver::resolve("BONK").unwrap());
+}
+
+#[tokio::test]
+async fn test_failed_refresh_keeps_the_stale_list() {
+    let (list, mints) = fixture();
+    let resolver = TokenResolver::new(list.clone(), TokenListConfig::default());
+    resolver.refresh().await.unwrap();
+    let loaded_at = resolver.refreshed_at().await;
+
+    list.fail.store(true, Ordering::SeqCst);
+    assert!(resolver.refresh().await.is_err());
+
+    assert_eq!(resolver.refreshed_at().await, loaded_at);
+    assert_eq!(resolver.token_count().await, 4);
+    assert!(matches!(resolver.lookup("JUP").await, TokenLookup::Found(token) if token.mint == mints.jup));
+}
+
+#[tokio::test]
+async fn test_list_is_read_page_by_page() {
+    let (list, _) = fixture();
+    let config = TokenListConfig { page_size: 3, max_pages: 5, ..TokenListConfig::default() };
+    let resolver = TokenResolver::new(list.clone(), config);
+
+    // A short second page ends the load
+    assert_eq!(resolver.refresh().await.unwrap(), 4);
+    assert_eq!(list.pages_fetched.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,153 @@
+use chrono::{TimeZone, Utc};
+use rust_decimal::Decimal;
+
+use crate::analytics::{
+    build_ledger, write_csv, write_json, LedgerEntry, LedgerSide, LedgerSource, TradeRecord, LEDGER_HEADER,
+};
+use crate::trading::{
+    CopyTradeExecution, CopyTradeStatus, CopyTradeType, ExecutionReport, ExecutionType, NetworkCongestion, Order,
+    OrderExecution, OrderMarketConditions, TokenResolver, TradeResult, TriggerReason,
+};
+
+const USER_ID: i64 = 778_001;
+
+fn entry(symbol: &str) -> LedgerEntry {
+    LedgerEntry {
+        timestamp: Utc.with_ymd_and_hms(2025, 4, 1, 12, 0, 0).unwrap(),
+        source: LedgerSource::Trade,
+        mint: "MintAddr1111".to_string(),
+        symbol: symbol.to_string(),
+        side: LedgerSide::Sell,
+        sol_amount: 1.5,
+        token_amount: Some(1_000.0),
+        price_usd: None,
+        fee_sol: 0.000005,
+        tx_signature: Some("sig1".to_string()),
+    }
+}
+
+fn csv(entries: &[LedgerEntry]) -> String {
+    let mut out = Vec::new();
+    write_csv(entries, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn order_fill(order: &Order, year: i32, success: bool) -> OrderExecution {
+    OrderExecution {
+        execution_id: format!("exec-{}", year),
+        order_id: order.order_id.clone(),
+        executed_at: Utc.with_ymd_and_hms(year, 6, 1, 0, 0, 0).unwrap(),
+        execution_type: ExecutionType::StopMarket,
+        trigger_reason: TriggerReason::PriceConditionMet,
+        price_at_execution: Decimal::new(2, 5),
+        amount_executed: Decimal::from(500_000),
+        slippage_bps: 20,
+        gas_used: 25_000,
+        gas_price: 1_000,
+        transaction_signature: Some(format!("orderSig{}", year)),
+        market_conditions: OrderMarketConditions {
+            token_price: Decimal::new(2, 5),
+            bid_ask_spread_bps: 10,
+            volume_24h: None,
+            volatility: None,
+            liquidity_depth: None,
+            network_congestion: NetworkCongestion { average_fee: 5_000, median_confirmation_time: 1, mempool_size: None },
+        },
+        success,
+        error_message: None,
+        report: ExecutionReport { realized_price: Some(0.0000001), ..ExecutionReport::default() },
+    }
+}
+
+fn copy(status: CopyTradeStatus) -> CopyTradeExecution {
+    CopyTradeExecution {
+        execution_id: "copy-1".to_string(),
+        master_trade_id: "master-1".to_string(),
+        master_user_id: 1,
+        follower_user_id: USER_ID,
+        token_address: "CopyMint1111".to_string(),
+        token_symbol: "WIF".to_string(),
+        trade_type: CopyTradeType::Buy,
+        master_amount_sol: 2.0,
+        copied_amount_sol: 0.5,
+        master_price: 2.1,
+        execution_price: 2.2,
+        slippage_percent: 0.4,
+        fee_paid_sol: 0.01,
+        status,
+        error_message: None,
+        timestamp: Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap(),
+        report: ExecutionReport::default(),
+    }
+}
+
+#[test]
+fn test_csv_quotes_token_names_with_commas_and_quotes() {
+    let text = csv(&[entry("Dog, \"the\" Coin"), entry("Plain")]);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines[0], LEDGER_HEADER);
+    assert!(lines[1].contains(",\"Dog, \"\"the\"\" Coin\",sell,"), "{}", lines[1]);
+    assert!(lines[2].contains(",Plain,sell,"), "{}", lines[2]);
+    // Unknown values stay empty rather than zero
+    assert!(lines[2].ends_with(",1000,,0.000005,sig1"), "{}", lines[2]);
+
+    // A line break inside a name stays inside its quoted field
+    let text = csv(&[entry("Two\nLines")]);
+    assert!(text.contains("\"Two\nLines\""));
+    assert_eq!(text.matches(",sell,").count(), 1);
+}
+
+#[test]
+fn test_ledger_merges_sources_in_time_order_and_filters_by_year() {
+    let mut sell = TradeRecord::from_trade_result(
+        &TradeResult::sell("tradeSig".to_string(), 1_000_000.0, 2.0, 0.000002),
+        "BONK/SOL",
+        "manual",
+    );
+    sell.timestamp = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+
+    let order = Order::create_stop_loss(USER_ID, "OrderMint111".to_string(), Decimal::new(1, 5), Decimal::from(500_000));
+    let orders = vec![(order.clone(), vec![order_fill(&order, 2025, true), order_fill(&order, 2025, false), order_fill(&order, 2024, true)])];
+    let copies = vec![copy(CopyTradeStatus::Success), copy(CopyTradeStatus::Failed)];
+
+    let ledger = build_ledger(&[sell], &orders, &copies, &[], Some(2025));
+    let sources: Vec<LedgerSource> = ledger.iter().map(|e| e.source).collect();
+    assert_eq!(sources, vec![LedgerSource::Copy, LedgerSource::Trade, LedgerSource::Order]);
+
+    let copied = &ledger[0];
+    assert_eq!((copied.side, copied.sol_amount, copied.price_usd), (LedgerSide::Buy, 0.5, Some(2.2)));
+    assert_eq!(copied.token_amount, None);
+
+    let traded = &ledger[1];
+    assert_eq!(traded.mint, TokenResolver::resolve("BONK").unwrap());
+    assert_eq!((traded.symbol.as_str(), traded.side), ("BONK", LedgerSide::Sell));
+    assert!((traded.sol_amount - 2.0).abs() < 1e-9);
+    assert_eq!(traded.tx_signature.as_deref(), Some("tradeSig"));
+
+    let filled = &ledger[2];
+    assert_eq!(filled.side, LedgerSide::Sell);
+    assert_eq!(filled.price_usd, Some(0.00002));
+    assert!((filled.sol_amount - 0.05).abs() < 1e-9);
+    assert_eq!(filled.tx_signature.as_deref(), Some("orderSig2025"));
+
+    assert_eq!(build_ledger(&[], &orders, &[], &[], None).len(), 2);
+}
+
+#[test]
+fn test_empty_history_writes_just_the_header() {
+    let ledger = build_ledger(&[], &[], &[], &[], None);
+    assert_eq!(csv(&ledger), format!("{}\n", LEDGER_HEADER));
+
+    let mut out = Vec::new();
+    assert_eq!(write_json(&ledger, &mut out).unwrap(), 0);
+    assert_eq!(serde_json::from_slice::<Vec<LedgerEntry>>(&out).unwrap(), Vec::new());
+}
+
+#[test]
+fn test_json_round_trips_entries() {
+    let entries = vec![entry("Dog, \"the\" Coin"), entry("Plain")];
+    let mut out = Vec::new();
+    assert_eq!(write_json(&entries, &mut out).unwrap(), 2);
+    assert_eq!(serde_json::from_slice::<Vec<LedgerEntry>>(&out).unwrap(), entries);
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::bot::preferences::{PreferenceStore, SettingChange, UserSettings};
+use crate::errors::{BotError, Result};
+use crate::testkit::TestHarness;
+use crate::trading::{
+    BlockedRule, CopyTradeStatus, CopyTradeType, TokenInspector, TokenResolver, TokenTax, TradeGate, TradeRules,
+    WalletOwners,
+};
+
+const USER_ID: i64 = 762_001;
+const FOLLOWER: i64 = 762_002;
+const MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+/// Fixed answers per fact; `None` means the lookup fails
+struct MockInspector {
+    liquidity_usd: Option<f64>,
+    tax: Option<TokenTax>,
+    larp_score: Option<u8>,
+    lookups: AtomicUsize,
+}
+
+impl MockInspector {
+    fn new(liquidity_usd: f64, tax_percent: f64, larp_score: u8) -> Self {
+        Self {
+            liquidity_usd: Some(liquidity_usd),
+            tax: Some(TokenTax { buy_percent: tax_percent, sell_percent: tax_percent }),
+            larp_score: Some(larp_score),
+            lookups: AtomicUsize::new(0),
+        }
+    }
+
+    fn answer<T: Copy>(&self, value: Option<T>, what: &str) -> Result<T> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        value.ok_or_else(|| BotError::external_api(format!("{} unavailable", what)).into())
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenInspector for MockInspector {
+    async fn liquidity_usd(&self, _mint: &str) -> Result<f64> {
+        self.answer(self.liquidity_usd, "liquidity")
+    }
+
+    async fn tax(&self, _mint: &str) -> Result<TokenTax> {
+        self.answer(self.tax, "tax")
+    }
+
+    async fn larp_score(&self, _mint: &str) -> Result<u8> {
+        self.answer(self.larp_score, "LARP score")
+    }
+}
+
+/// Owners by wallet; looking up `"unreachable"` fails
+struct MockOwners(HashMap<String, i64>);
+
+#[async_trait::async_trait]
+impl WalletOwners for MockOwners {
+    async fn owner_of(&self, wallet_address: &str) -> Result<Option<i64>> {
+        if wallet_address == "unreachable" {
+            return Err(BotError::external_api("database unavailable".to_string()).into());
+        }
+        Ok(self.0.get(wallet_address).copied())
+    }
+}
+
+/// A gate over a $20k pool with a 3% tax and a LARP score of 55
+async fn gate_with(rules: TradeRules, inspector: MockInspector) -> (TradeGate, Arc<MockInspector>) {
+    let preferences = Arc::new(PreferenceStore::default());
+    preferences.set(USER_ID, UserSettings { trade_rules: rules, ..UserSettings::default() }).await;
+    let inspector = Arc::new(inspector);
+    (TradeGate::new(preferences, inspector.clone()), inspector)
+}
+
+fn typical() -> MockInspector {
+    MockInspector::new(20_000.0, 3.0, 55)
+}
+
+#[tokio::test]
+async fn test_each_rule_blocks_on_its_own() {
+    let cases = [
+        (
+            TradeRules { denied_mints: vec![MINT.to_string()], ..TradeRules::default() },
+            BlockedRule::Denied,
+        ),
+        (
+            TradeRules { min_liquidity_usd: Some(50_000.0), ..TradeRules::default() },
+            BlockedRule::MinLiquidity { liquidity_usd: Some(20_000.0), min_usd: 50_000.0 },
+        ),
+        (
+            TradeRules { max_tax_percent: Some(1.0), ..TradeRules::default() },
+            BlockedRule::MaxTax { tax: Some(TokenTax { buy_percent: 3.0, sell_percent: 3.0 }), max_percent: 1.0 },
+        ),
+        (
+            TradeRules { min_larp_score: Some(70), ..TradeRules::default() },
+            BlockedRule::MinLarpScore { score: Some(55), min_score: 70 },
+        ),
+    ];
+
+    for (rules, expected) in cases {
+        let (gate, inspector) = gate_with(rules.clone(), typical()).await;
+        let blocked = gate.evaluate(USER_ID, MINT).await.expect("rule should block");
+        assert_eq!(blocked.mint, MINT);
+        assert_eq!(blocked.rules, vec![expected], "rules: {:?}", rules);
+        // Only the enabled rule's data is fetched; the deny-list needs none
+        let expected_lookups = usize::from(rules.denied_mints.is_empty());
+        assert_eq!(inspector.lookups.load(Ordering::SeqCst), expected_lookups);
+    }
+}
+
+#[tokio::test]
+async fn test_rules_within_limits_let_the_buy_through() {
+    let rules = TradeRules {
+        denied_mints: vec!["So11111111111111111111111111111111111111112".to_string()],
+        min_liquidity_usd: Some(10_000.0),
+        max_tax_percent: Some(3.0),
+        min_larp_score: Some(55),
+    };
+    let (gate, inspector) = gate_with(rules, typical()).await;
+
+    assert!(gate.evaluate(USER_ID, MINT).await.is_none());
+    assert!(gate.check(USER_ID, MINT).await.is_ok());
+    assert_eq!(inspector.lookups.load(Ordering::SeqCst), 6);
+
+    // No rules, no lookups
+    let (gate, inspector) = gate_with(TradeRules::default(), typical()).await;
+    assert!(gate.evaluate(USER_ID, MINT).await.is_none());
+    assert!(gate.evaluate(USER_ID + 1, MINT).await.is_none());
+    assert_eq!(inspector.lookups.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_every_blocking_rule_is_listed_together() {
+    let rules = TradeRules {
+        denied_mints: vec![MINT.to_string()],
+        min_liquidity_usd: Some(50_000.0),
+        max_tax_percent: Some(1.0),
+        min_larp_score: Some(70),
+    };
+    let (gate, _) = gate_with(rules, typical()).await;
+
+    let blocked = gate.evaluate(USER_ID, MINT).await.unwrap();
+    assert_eq!(blocked.rules.len(), 4);
+    assert_eq!(blocked.rules[0], BlockedRule::Denied);
+    assert!(matches!(blocked.rules[1], BlockedRule::MinLiquidity { .. }));
+    assert!(matches!(blocked.rules[2], BlockedRule::MaxTax { .. }));
+    assert!(matches!(blocked.rules[3], BlockedRule::MinLarpScore { .. }));
+
+    let error = gate.check(USER_ID, MINT).await.unwrap_err().to_string();
+    assert!(error.contains("deny-list"), "{}", error);
+    assert!(error.contains("Liquidity $20000 is under your $50000 minimum"), "{}", error);
+    assert!(error.contains("over your 1% maximum"), "{}", error);
+    assert!(error.contains("LARP safety score 55/100"), "{}", error);
+
+    // Two of four rules failing lists just those two
+    let rules = TradeRules { min_liquidity_usd: Some(50_000.0), max_tax_percent: Some(5.0), min_larp_score: Some(70), ..TradeRules::default() };
+    let (gate, _) = gate_with(rules, typical()).await;
+    let blocked = gate.evaluate(USER_ID, MINT).await.unwrap();
+    assert_eq!(blocked.rules.len(), 2);
+    assert!(matches!(blocked.rules[0], BlockedRule::MinLiquidity { .. }));
+    assert!(matches!(blocked.rules[1], BlockedRule::MinLarpScore { .. }));
+}
+
+#[tokio::test]
+async fn test_a_rule_without_data_blocks_the_buy() {
+    let rules = TradeRules { min_liquidity_usd: Some(10_000.0), min_larp_score: Some(50), ..TradeRules::default() };
+    let inspector = MockInspector { larp_score: None, ..typical() };
+    let (gate, _) = gate_with(rules, inspector).await;
+
+    let blocked = gate.evaluate(USER_ID, MINT).await.unwrap();
+    assert_eq!(blocked.rules, vec![BlockedRule::MinLarpScore { score: None, min_score: 50 }]);
+    assert!(blocked.to_string().contains("couldn't be checked"));
+}
+
+#[tokio::test]
+async fn test_wallet_buys_use_their_owners_rules() {
+    let rules = TradeRules { denied_mints: vec![MINT.to_string()], ..TradeRules::default() };
+    let (gate, _) = gate_with(rules, typical()).await;
+    let gate = gate.with_owners(Arc::new(MockOwners(HashMap::from([("owned".to_string(), USER_ID)]))));
+
+    assert!(gate.check_wallet("owned", MINT).await.is_err());
+    // Wallets the bot doesn't know have no rules
+    assert!(gate.check_wallet("unknown", MINT).await.is_ok());
+    // But a lookup that fails can't be told apart from an owner with rules
+    let refused = gate.check_wallet("unreachable", "AnyOtherMint").await.unwrap_err();
+    assert!(refused.to_string().contains("Couldn't check your token rules"), "{}", refused);
+}
+
+#[test]
+fn test_rule_edits_parse_and_validate() {
+    assert_eq!(SettingChange::parse("setting:liq:50000"), Some(SettingChange::MinLiquidityUsd(Some(50_000.0))));
+    assert_eq!(SettingChange::parse("setting:liq:off"), Some(SettingChange::MinLiquidityUsd(None)));
+    assert_eq!(SettingChange::parse("setting:tax:5"), Some(SettingChange::MaxTaxPercent(Some(5.0))));
+    assert_eq!(SettingChange::parse("setting:larp:80"), Some(SettingChange::MinLarpScore(Some(80))));
+    assert_eq!(SettingChange::parse("setting:larp:300"), None);
+    assert_eq!(SettingChange::parse("setting:denyclear"), Some(SettingChange::ClearDeniedMints));
+
+    let mut settings = UserSettings::default();
+    assert!(settings.apply(SettingChange::MinLiquidityUsd(Some(-1.0))).is_err());
+    assert!(settings.apply(SettingChange::MaxTaxPercent(Some(101.0))).is_err());
+    assert!(settings.apply(SettingChange::MinLarpScore(Some(101))).is_err());
+    assert!(settings.trade_rules.is_empty());
+
+    settings.apply(SettingChange::MaxTaxPercent(Some(0.0))).unwrap();
+    assert_eq!(settings.trade_rules.max_tax_percent, Some(0.0));
+
+    settings.deny_mint(MINT).unwrap();
+    assert!(settings.deny_mint(MINT).is_err());
+    assert!(settings.deny_mint("not-a-mint").is_err());
+    assert!(settings.trade_rules.is_denied(MINT));
+    settings.allow_mint(MINT).unwrap();
+    assert!(settings.allow_mint(MINT).is_err());
+
+    settings.deny_mint(MINT).unwrap();
+    settings.apply(SettingChange::ClearDeniedMints).unwrap();
+    assert!(settings.trade_rules.denied_mints.is_empty());
+}
+
+#[tokio::test]
+async fn test_copied_and_engine_buys_pass_the_same_gate() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().build().await.unwrap();
+    for user_id in [USER_ID, FOLLOWER] {
+        harness.services.preferences
+            .update(user_id, |settings| settings.deny_mint(&bonk))
+            .await
+            .unwrap();
+    }
+
+    // A copied buy is cancelled with the rule that refused it
+    harness.copy_trading.start_following(FOLLOWER, "AlphaTrader", 50.0, 5.0).await.unwrap();
+    let executions = harness.copy_trading
+        .execute_copy_trade(1001, &bonk, "BONK", CopyTradeType::Buy, 2.0, 0.00002)
+        .await
+        .unwrap();
+    assert_eq!(executions[0].status, CopyTradeStatus::Cancelled);
+    assert!(executions[0].error_message.as_deref().unwrap().contains("deny-list"));
+    assert!(!harness.copy_trading.follower_risk(FOLLOWER).await.holds(&bonk));
+
+    // A buy through the engine is refused before anything is quoted
+    let wallet = harness.register_user(USER_ID, 10.0).await.unwrap();
+    let error = harness.trading_engine
+        .buy_with_rebate(solana_sdk::signature::Signer::pubkey(&wallet).to_string(), "BONK".to_string(), 0.5)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("blocked by your token rules"), "{}", error);
+    assert_eq!(harness.jupiter.call_count("legacy_quote").await, 0);
+}
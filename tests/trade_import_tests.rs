@@ -0,0 +1,145 @@
+use chrono::{TimeZone, Utc};
+
+use crate::analytics::{parse_trades, ImportFormat, KnownTrades, SkipReason, TradeImporter, NATIVE_HEADER};
+use crate::trading::TradeProvenance;
+
+const USER: i64 = 424242;
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+const WIF: &str = "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm";
+
+fn now() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap()
+}
+
+fn trojan_export() -> String {
+    format!(
+        "Date,Action,Token Name,Token Address,Token Amount,SOL Amount,Fee (SOL),Tx\n\
+        2024-05-01 10:00:00,Buy,BONK,{bonk},\"1,000,000\",2.0,0.01,sigA\n\
+        2024-05-03 12:30:00,Sell,BONK,{bonk},400000,1.2,0.005,sigB\n\
+        2024-05-04 09:15:00,Buy,WIF,{wif},50,1.5,,\n",
+        bonk = BONK,
+        wif = WIF
+    )
+}
+
+fn photon_export() -> String {
+    format!(
+        "time,type,symbol,mint,amount,total_sol,fee,signature\n\
+        1714557600,buy,BONK,{bonk},1000000,2.0,0.01,sigP1\n\
+        1714739400000,sell,BONK,{bonk},1000000,3.0,0.01,sigP2\n",
+        bonk = BONK
+    )
+}
+
+#[tokio::test]
+async fn test_detects_trojan_and_photon_exports() {
+    let trojan = parse_trades(trojan_export().as_bytes(), &KnownTrades::default(), now()).await.unwrap();
+    assert_eq!(trojan.format, ImportFormat::Trojan);
+    assert_eq!(trojan.rows.len(), 3);
+    assert!(trojan.skipped.is_empty());
+    assert_eq!(trojan.rows[0].token_amount, 1_000_000.0);
+    assert_eq!(trojan.rows[0].signature.as_deref(), Some("sigA"));
+    assert_eq!(trojan.rows[2].signature, None);
+    assert_eq!(trojan.tokens(), vec!["BONK".to_string(), "WIF".to_string()]);
+
+    let photon = parse_trades(photon_export().as_bytes(), &KnownTrades::default(), now()).await.unwrap();
+    assert_eq!(photon.format, ImportFormat::Photon);
+    assert_eq!(photon.rows.len(), 2);
+    // Unix seconds and milliseconds both parse
+    let (first, last) = photon.date_range().unwrap();
+    assert_eq!(first, Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap());
+    assert_eq!(last, Utc.with_ymd_and_hms(2024, 5, 3, 12, 30, 0).unwrap());
+
+    // The same trade from two formats hashes the same
+    assert_eq!(trojan.rows[0].row_hash, photon.rows[0].row_hash);
+
+    let unknown = parse_trades("when,what,how much\n1,2,3\n".as_bytes(), &KnownTrades::default(), now()).await;
+    assert!(unknown.is_err());
+}
+
+#[tokio::test]
+async fn test_row_validation_reports_each_skip() {
+    let csv = format!(
+        "{header}\n\
+        2024-05-01T10:00:00Z,buy,BONK,1000,0.5,0.001,sig1\n\
+        2024-05-01T10:00:00Z,buy,NOPE,1000,0.5,,\n\
+        2024-05-01T10:00:00Z,hold,BONK,1000,0.5,,\n\
+        2024-05-01T10:00:00Z,buy,BONK,-5,0.5,,\n\
+        2024-05-01T10:00:00Z,buy,BONK,1000,abc,,\n\
+        2019-01-01T00:00:00Z,buy,BONK,1000,0.5,,\n\
+        2030-01-01T00:00:00Z,buy,BONK,1000,0.5,,\n\
+        2024-05-01T10:00:00Z,buy,\"BONK,1000\n\
+        2024-05-02T10:00:00Z,sell,BONK,1000,0.6,,sig1\n",
+        header = NATIVE_HEADER
+    );
+    let preview = parse_trades(csv.as_bytes(), &KnownTrades::default(), now()).await.unwrap();
+
+    assert_eq!(preview.format, ImportFormat::Native);
+    assert_eq!(preview.rows.len(), 1);
+    let reasons: Vec<(usize, SkipReason)> = preview.skipped.iter().map(|s| (s.line, s.reason.clone())).collect();
+    assert_eq!(reasons[0], (3, SkipReason::UnknownToken("NOPE".to_string())));
+    assert_eq!(reasons[1], (4, SkipReason::UnknownSide("hold".to_string())));
+    assert_eq!(reasons[2], (5, SkipReason::NonPositiveAmount("token amount")));
+    assert!(matches!(reasons[3], (6, SkipReason::BadNumber { column: "SOL amount", .. })));
+    assert!(matches!(reasons[4], (7, SkipReason::BadTimestamp(_))));
+    assert!(matches!(reasons[5], (8, SkipReason::BadTimestamp(_))));
+    assert!(matches!(reasons[6], (9, SkipReason::Malformed(_))));
+    // A reused signature is a duplicate even when the rest differs
+    assert_eq!(reasons[7], (10, SkipReason::DuplicateInFile));
+
+    let rendered = preview.render();
+    assert!(rendered.contains("Rows to import: 1"));
+    assert!(rendered.contains("Skipped: 8"));
+}
+
+#[tokio::test]
+async fn test_reimport_does_not_duplicate() {
+    let importer = TradeImporter::default();
+
+    let preview = importer.preview(USER, trojan_export().as_bytes()).await.unwrap();
+    assert_eq!(preview.rows.len(), 3);
+    let outcome = importer.confirm(USER).await.unwrap();
+    assert_eq!((outcome.inserted, outcome.duplicates), (3, 0));
+
+    let again = importer.preview(USER, trojan_export().as_bytes()).await.unwrap();
+    assert!(again.rows.is_empty());
+    assert!(again.skipped.iter().all(|s| s.reason == SkipReason::AlreadyRecorded));
+
+    // The Photon export repeats the BONK buy under another signature
+    let photon = importer.preview(USER, photon_export().as_bytes()).await.unwrap();
+    assert_eq!(photon.rows.len(), 1);
+    assert_eq!(photon.skipped[0].reason, SkipReason::AlreadyRecorded);
+
+    // Across both formats only the distinct trades land
+    importer.cancel(USER).await;
+    let other = 7;
+    importer.preview(other, trojan_export().as_bytes()).await.unwrap();
+    let first = importer.confirm(other).await.unwrap();
+    importer.preview(other, photon_export().as_bytes()).await.unwrap();
+    let second = importer.confirm(other).await.unwrap();
+    assert_eq!(first.inserted + second.inserted, 4);
+    assert_eq!(importer.records(other).await.len(), 4);
+    assert!(importer.confirm(other).await.is_err());
+}
+
+#[tokio::test]
+async fn test_imports_feed_cost_basis_with_provenance() {
+    let importer = TradeImporter::default();
+    importer.preview(USER, trojan_export().as_bytes()).await.unwrap();
+    importer.confirm(USER).await.unwrap();
+
+    // 1M BONK for 2.01 SOL with fees, 400k sold: 600k left at the same unit cost
+    let basis = importer.cost_basis().position(USER, BONK).await.unwrap();
+    assert!((basis.quantity - 600_000.0).abs() < 1e-6);
+    assert!((basis.average_cost() - 2.01 / 1_000_000.0).abs() < 1e-15);
+
+    // 1.195 SOL net for 400k tokens that cost 0.804 SOL
+    let realized = importer.cost_basis().realized_sol(USER).await;
+    assert!((realized - 0.391).abs() < 1e-9);
+
+    let records = importer.records(USER).await;
+    assert!(records.iter().all(|r| r.provenance == TradeProvenance::Imported));
+    assert!(records.iter().all(|r| r.strategy_used == "import:trojan"));
+    let sell = &records[1];
+    assert!((sell.pnl_percentage - 0.391 / 0.804 * 100.0).abs() < 1e-6);
+}
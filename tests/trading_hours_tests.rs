@@ -0,0 +1,108 @@
+use chrono::{DateTime, Duration, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+
+use crate::analytics::{
+    render_heatmap, AttributionTime, TradeRecord, TradeType, TradingHoursConfig, TradingHoursReport,
+};
+use crate::trading::{ExecutionReport, TradeProvenance};
+
+const KATHMANDU: Tz = chrono_tz::Asia::Kathmandu;
+
+/// A realized trade that closed at `exit` after `held`
+fn trade(exit: DateTime<Utc>, held: Duration, return_pct: f64, provenance: TradeProvenance) -> TradeRecord {
+    TradeRecord {
+        trade_id: format!("t-{}", exit.timestamp()),
+        timestamp: exit,
+        token_pair: "BONK/SOL".to_string(),
+        trade_type: TradeType::Swap,
+        entry_price: Decimal::new(1, 6),
+        exit_price: Decimal::new(2, 6),
+        quantity: Decimal::from(1_000_000),
+        pnl: Decimal::ZERO,
+        pnl_percentage: return_pct,
+        fees: Decimal::ZERO,
+        holding_period: held,
+        strategy_used: "manual".to_string(),
+        risk_reward_ratio: 0.0,
+        execution: ExecutionReport::default(),
+        journal_tag: None,
+        provenance,
+    }
+}
+
+fn utc(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 5, day, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn test_buckets_follow_a_quarter_hour_offset() {
+    // Kathmandu is UTC+05:45: 18:10 UTC Monday is 23:55 local, 18:20 is 00:05 Tuesday
+    assert_eq!(TradingHoursReport::bucket(utc(6, 18, 10), KATHMANDU), (0, 23));
+    assert_eq!(TradingHoursReport::bucket(utc(6, 18, 20), KATHMANDU), (1, 0));
+
+    let trades = vec![
+        // Entered Monday 23:55 local, exited Tuesday 02:05 local
+        trade(utc(6, 20, 20), Duration::minutes(130), 10.0, TradeProvenance::Bot),
+        // Entered Tuesday 00:05 local
+        trade(utc(6, 18, 30), Duration::minutes(10), -4.0, TradeProvenance::Bot),
+    ];
+    let config = TradingHoursConfig { min_samples: 1, ..Default::default() };
+
+    let by_entry = TradingHoursReport::compute(&trades, KATHMANDU, &config);
+    assert_eq!(by_entry.grid[0][23].trades, 1);
+    assert_eq!(by_entry.grid[1][0].trades, 1);
+    assert_eq!(by_entry.best_hour().map(|(hour, _)| hour), Some(23));
+    assert_eq!(by_entry.worst_weekday().map(|(day, _)| day), Some(Weekday::Tue));
+    // 1M tokens out at 0.000002 SOL each
+    assert!((by_entry.by_hour[23].volume_sol - 2.0).abs() < 1e-9);
+
+    let by_exit = TradingHoursReport::compute(
+        &trades,
+        KATHMANDU,
+        &TradingHoursConfig { attribute_by: AttributionTime::Exit, ..config.clone() },
+    );
+    assert_eq!(by_exit.grid[1][2].trades, 1);
+    assert_eq!(by_exit.grid[1][0].trades, 1);
+    assert_eq!(by_exit.by_weekday[0].trades, 0);
+
+    // Same trades, another timezone: recomputed, nothing stored per bucket
+    let in_utc = TradingHoursReport::compute(&trades, chrono_tz::UTC, &config);
+    assert_eq!(in_utc.grid[0][18].trades, 2);
+}
+
+#[test]
+fn test_small_buckets_are_suppressed() {
+    let mut trades: Vec<TradeRecord> = (0..5)
+        .map(|i| trade(utc(6, 9, i * 5), Duration::zero(), 8.0, TradeProvenance::Bot))
+        .collect();
+    trades.extend((0..2).map(|i| trade(utc(7, 2, i * 5), Duration::zero(), -30.0, TradeProvenance::Bot)));
+    // Imported history stays out unless asked for
+    trades.extend((0..5).map(|i| trade(utc(8, 14, i * 5), Duration::zero(), -50.0, TradeProvenance::Imported)));
+
+    let report = TradingHoursReport::compute(&trades, chrono_tz::UTC, &TradingHoursConfig::default());
+    assert_eq!(report.trades, 7);
+    assert_eq!(report.excluded_imported, 5);
+
+    // The losing 02:00 bucket has only two trades, so it can't be the worst hour
+    assert_eq!(report.best_hour().map(|(hour, _)| hour), Some(9));
+    assert_eq!(report.worst_hour().map(|(hour, _)| hour), Some(9));
+    assert!(report.cell(Weekday::Tue, 2).is_none());
+    assert_eq!(report.cell(Weekday::Mon, 9).map(|stats| stats.win_rate()), Some(100.0));
+    assert_eq!(report.best_weekday().map(|(day, _)| day), Some(Weekday::Mon));
+
+    let rendered = report.render();
+    assert!(rendered.contains("Best hour: 09:00–09:59"));
+    assert!(rendered.contains("5 imported trades excluded"));
+
+    let with_imports = TradingHoursReport::compute(
+        &trades,
+        chrono_tz::UTC,
+        &TradingHoursConfig { include_imported: true, ..Default::default() },
+    );
+    assert_eq!(with_imports.worst_hour().map(|(hour, _)| hour), Some(14));
+
+    let empty = TradingHoursReport::compute(&trades[..2], chrono_tz::UTC, &TradingHoursConfig::default());
+    assert!(empty.render().contains("Best hour: insufficient data"));
+    assert!(render_heatmap(&empty).unwrap().starts_with(b"\x89PNG"));
+}
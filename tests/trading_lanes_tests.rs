@@ -0,0 +1,214 @@
+use crate::bot::handlers::TradingHandler;
+use crate::errors::BotError;
+use crate::trading::{ExitDenomination, LaneHandler, LaneMailbox, Laned, TradingMessage};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, Mutex, Semaphore};
+
+const USERS: usize = 50;
+const MESSAGES: usize = 1_000;
+
+/// What a handled message was, enough to check its place in the wallet's order
+#[derive(Debug, Clone, PartialEq)]
+enum Seen {
+    Buy(usize),
+    Sell(usize),
+    BuyWithRebate(usize),
+    SellWithRebate(usize),
+    Balance,
+}
+
+/// The `seq`th message a wallet sends; the kind rotates so every lane mixes them
+fn message(wallet: &str, seq: usize) -> (TradingMessage, Seen) {
+    let user_wallet = wallet.to_string();
+    let token = "BONK".to_string();
+    match seq % 5 {
+        0 => {
+            let (response_tx, _) = mpsc::channel(1);
+            (TradingMessage::Buy { user_wallet, token, amount_sol: seq as f64, defaults: None, response_tx }, Seen::Buy(seq))
+        }
+        1 => {
+            let (response_tx, _) = mpsc::channel(1);
+            (TradingMessage::Sell { user_wallet, token, percentage: seq as f64, response_tx }, Seen::Sell(seq))
+        }
+        2 => {
+            let (response, _) = oneshot::channel();
+            (
+                TradingMessage::BuyWithRebate { user_wallet, token, amount_sol: seq as f64, defaults: None, response },
+                Seen::BuyWithRebate(seq),
+            )
+        }
+        3 => {
+            let (response, _) = oneshot::channel();
+            (
+                TradingMessage::SellWithRebate {
+                    user_wallet, token, percentage: seq as f64, exit: ExitDenomination::Sol, defaults: None, response,
+                },
+                Seen::SellWithRebate(seq),
+            )
+        }
+        _ => {
+            let (response, _) = oneshot::channel();
+            (TradingMessage::GetBalance { user_wallet, response }, Seen::Balance)
+        }
+    }
+}
+
+/// Records what each wallet's lane ran, and how many lanes were running at once
+#[derive(Default)]
+struct Recorder {
+    seen: Mutex<HashMap<String, Vec<Seen>>>,
+    running: AtomicUsize,
+    max_running: AtomicUsize,
+}
+
+#[async_trait]
+impl LaneHandler<TradingMessage> for Recorder {
+    async fn handle(&self, message: TradingMessage) {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(running, Ordering::SeqCst);
+
+        let (wallet, seen) = match message {
+            TradingMessage::Buy { user_wallet, amount_sol, .. } => (user_wallet, Seen::Buy(amount_sol as usize)),
+            TradingMessage::Sell { user_wallet, percentage, .. } => (user_wallet, Seen::Sell(percentage as usize)),
+            TradingMessage::BuyWithRebate { user_wallet, amount_sol, .. } => {
+                (user_wallet, Seen::BuyWithRebate(amount_sol as usize))
+            }
+            TradingMessage::SellWithRebate { user_wallet, percentage, .. } => {
+                (user_wallet, Seen::SellWithRebate(percentage as usize))
+            }
+            TradingMessage::GetBalance { user_wallet, .. } => (user_wallet, Seen::Balance),
+            other => panic!("unexpected message {:?}", other.lane()),
+        };
+        // Uneven work so lanes interleave and a lane's later message could overtake
+        if seen == Seen::Balance {
+            tokio::task::yield_now().await;
+        } else {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        self.seen.lock().await.entry(wallet).or_default().push(seen);
+        self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Holds every message until the test lets it through
+struct Gate(Arc<Semaphore>);
+
+#[async_trait]
+impl LaneHandler<TradingMessage> for Gate {
+    async fn handle(&self, _message: TradingMessage) {
+        self.0.acquire().await.unwrap().forget();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_each_wallet_runs_in_send_order_under_load() {
+    let recorder = Arc::new(Recorder::default());
+    let mailbox = LaneMailbox::spawn("lanes_stress", 64, recorder.clone()).with_metrics(super::shared_metrics());
+    let retries = Arc::new(AtomicUsize::new(0));
+
+    let mut senders = Vec::new();
+    for user in 0..USERS {
+        let mailbox = mailbox.clone();
+        let retries = retries.clone();
+        senders.push(tokio::spawn(async move {
+            let wallet = format!("wallet-{:02}", user);
+            let mut sent = Vec::new();
+            for seq in 0..MESSAGES / USERS {
+                // A full mailbox rejects the message; send it again rather than skip ahead
+                loop {
+                    let (msg, seen) = message(&wallet, seq);
+                    match mailbox.try_send(msg) {
+                        Ok(()) => {
+                            sent.push(seen);
+                            break;
+                        }
+                        Err(BotError::QueueFull(_)) => {
+                            retries.fetch_add(1, Ordering::Relaxed);
+                            tokio::time::sleep(Duration::from_millis(1)).await;
+                        }
+                        Err(e) => panic!("send failed: {}", e),
+                    }
+                }
+            }
+            (wallet, sent)
+        }));
+    }
+
+    let mut expected = HashMap::new();
+    for sender in senders {
+        let (wallet, sent) = sender.await.unwrap();
+        expected.insert(wallet, sent);
+    }
+
+    for _ in 0..500 {
+        if mailbox.depth() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(mailbox.depth(), 0, "mailbox never drained");
+    // The last lane records the depth just after giving back its slot
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let seen = recorder.seen.lock().await;
+    assert_eq!(seen.len(), USERS);
+    assert_eq!(seen.values().map(Vec::len).sum::<usize>(), MESSAGES);
+    for (wallet, sent) in &expected {
+        assert_eq!(seen.get(wallet), Some(sent), "{} ran out of order", wallet);
+    }
+
+    // Wallets run side by side, and 1,000 messages can't fit in 64 slots at once
+    assert!(recorder.max_running.load(Ordering::SeqCst) > 1);
+    assert!(retries.load(Ordering::Relaxed) > 0);
+
+    let body = super::shared_metrics().encode_text().unwrap();
+    assert!(body.contains(r#"queue_depth{queue="lanes_stress"} 0"#), "{}", body);
+    assert!(body.contains(r#"lane_latency_ms_count{queue="lanes_stress",stage="wait"} 1000"#), "{}", body);
+    assert!(body.contains(r#"lane_latency_ms_count{queue="lanes_stress",stage="run"} 1000"#), "{}", body);
+}
+
+#[tokio::test]
+async fn test_full_mailbox_rejects_instead_of_waiting() {
+    let gate = Arc::new(Semaphore::new(0));
+    let mailbox = LaneMailbox::spawn("lanes_full", 2, Arc::new(Gate(gate.clone())));
+
+    mailbox.try_send(message("wallet-a", 0).0).unwrap();
+    mailbox.try_send(message("wallet-b", 0).0).unwrap();
+    assert_eq!(mailbox.depth(), 2);
+
+    let err = mailbox.try_send(message("wallet-a", 1).0).unwrap_err();
+    assert!(matches!(&err, BotError::QueueFull(queue) if queue == "lanes_full"), "{:?}", err);
+    assert_eq!(
+        TradingHandler::failure_message("Buy", &err, "en"),
+        "⏳ Buy not sent: the trading engine is at capacity. Nothing was traded, please try again in a few seconds."
+    );
+
+    // Finishing a message frees its slot
+    gate.add_permits(1);
+    for _ in 0..100 {
+        if mailbox.depth() < 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    mailbox.try_send(message("wallet-a", 1).0).unwrap();
+
+    // Shutdown gets through a full mailbox; later sends are refused but queued work still runs
+    mailbox.close(TradingMessage::Shutdown).unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let err = mailbox.try_send(message("wallet-c", 0).0).unwrap_err();
+    assert!(!matches!(err, BotError::QueueFull(_)), "{:?}", err);
+    gate.add_permits(2);
+    for _ in 0..100 {
+        if mailbox.depth() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(mailbox.depth(), 0);
+}
@@ -1,4 +1,4 @@
-use crate::trading::types::{TradeResult, TradeType, Position, Balance};
+use crate::trading::types::{TradeResult, TradeType, Position, Balance, ExecutionReport, RouteSummary};
 use chrono::Utc;
 use std::collections::BTreeMap;
 
@@ -15,6 +15,10 @@ fn test_trade_result_creation() {
         pnl_percentage: 0.0,
         timestamp: Utc::now(),
         trade_type: TradeType::Buy,
+        execution: ExecutionReport::default(),
+        quoted_price: None,
+        executed_price: None,
+        realized_slippage_bps: None,
     };
     
     assert_eq!(trade.tx_signature, "test_signature");
@@ -23,6 +27,76 @@ fn test_trade_result_creation() {
     assert_eq!(trade.trade_type, TradeType::Buy);
 }
 
+#[test]
+fn test_legacy_trade_result_deserializes_with_default_report() {
+    // Stored before the execution report existed
+    let legacy = r#"{
+        "tx_signature": "5xLegacy",
+        "tokens_received": 1000.0,
+        "tokens_sold": 0.0,
+        "sol_received": 0.0,
+        "amount_sol": 1.0,
+        "price": 0.001,
+        "rebate_earned": 0.0,
+        "pnl_percentage": 0.0,
+        "timestamp": "2024-05-01T12:00:00Z",
+        "trade_type": "Buy"
+    }"#;
+    
+    let trade: TradeResult = serde_json::from_str(legacy).unwrap();
+    assert_eq!(trade.tx_signature, "5xLegacy");
+    assert!(trade.execution.is_empty());
+    assert_eq!(trade.execution.broadcast_attempts, 0);
+    assert!(!trade.execution.simulated);
+    assert!(trade.execution.route.is_none());
+    
+    // A partially populated report keeps defaults for the rest
+    let partial = legacy.replace(
+        r#""trade_type": "Buy""#,
+        r#""trade_type": "Buy", "execution": {"broadcast_attempts": 2}"#,
+    );
+    let trade: TradeResult = serde_json::from_str(&partial).unwrap();
+    assert_eq!(trade.execution.broadcast_attempts, 2);
+    assert!(trade.execution.idempotency_key.is_none());
+    
+    // New results round-trip unchanged
+    let mut execution = ExecutionReport::quoted(0.001, RouteSummary::from_labels(vec![Some("Raydium")], Some(0.1)))
+        .filled(0.00101, TradeType::Buy)
+        .with_idempotency_key("buy:wallet:mint:1");
+    execution.record_broadcast(true);
+    let trade = TradeResult::buy("sig".to_string(), 1000.0, 1.0, 0.00101).with_execution(execution);
+    let decoded: TradeResult = serde_json::from_str(&serde_json::to_string(&trade).unwrap()).unwrap();
+    assert_eq!(decoded.execution, trade.execution);
+}
+
+#[test]
+fn test_execution_report_slippage_and_latency() {
+    // Paying more than quoted on a buy is positive slippage
+    let buy = ExecutionReport::quoted(1.0, RouteSummary::default()).filled(1.01, TradeType::Buy);
+    assert!((buy.slippage_bps.unwrap() - 100.0).abs() < 1e-6);
+    assert!(buy.latency_ms().unwrap() >= 0);
+    
+    // Receiving less than quoted on a sell is positive slippage
+    let sell = ExecutionReport::quoted(1.0, RouteSummary::default()).filled(0.98, TradeType::Sell);
+    assert!((sell.slippage_bps.unwrap() - 200.0).abs() < 1e-6);
+    
+    // No quote, no slippage
+    let unquoted = ExecutionReport::default().filled(1.0, TradeType::Buy);
+    assert!(unquoted.slippage_bps.is_none());
+    assert!(unquoted.latency_ms().is_none());
+    
+    let mut retried = ExecutionReport::default();
+    retried.record_broadcast(false);
+    retried.record_broadcast(true);
+    assert_eq!(retried.broadcast_attempts, 2);
+    assert!(retried.blockhash_refreshed);
+    
+    let route = RouteSummary::from_labels(vec![Some("Raydium"), None, Some("Raydium"), Some("Orca")], None);
+    assert_eq!(route.hops, 4);
+    assert_eq!(route.venues, vec!["Raydium", "Unknown", "Orca"]);
+    assert_eq!(route.label(), "Raydium → Unknown → Orca (4 hops)");
+}
+
 #[test]
 fn test_position_equality() {
     let pos1 = Position {
@@ -36,6 +110,7 @@ fn test_position_equality() {
         current_price: 0.1,
         sort_key: 1,
         last_updated: Utc::now(),
+        untracked_entry: false,
     };
     
     let pos2 = Position {
@@ -49,6 +124,7 @@ fn test_position_equality() {
         current_price: 0.1,
         sort_key: 2,
         last_updated: Utc::now(),
+        untracked_entry: false,
     };
     
     // Positions are equal if they have the same mint
@@ -68,6 +144,7 @@ fn test_position_ordering() {
         current_price: 0.1,
         sort_key: 1,
         last_updated: Utc::now(),
+        untracked_entry: false,
     };
     
     let pos2 = Position {
@@ -81,6 +158,7 @@ fn test_position_ordering() {
         current_price: 2.0,
         sort_key: 2,
         last_updated: Utc::now(),
+        untracked_entry: false,
     };
     
     // Positions are ordered by value_usd
@@ -139,6 +217,7 @@ fn test_portfolio_operations() {
         current_price: 0.1,
         sort_key: 1,
         last_updated: Utc::now(),
+        untracked_entry: false,
     };
     
     // Add position
@@ -179,6 +258,7 @@ fn test_portfolio_calculations() {
         current_price: 0.1,
         sort_key: 1,
         last_updated: Utc::now(),
+        untracked_entry: false,
     });
     
     portfolio.add_position(Position {
@@ -192,6 +272,7 @@ fn test_portfolio_calculations() {
         current_price: 3.0,
         sort_key: 2,
         last_updated: Utc::now(),
+        untracked_entry: false,
     });
     
     // Calculate totals
@@ -200,4 +281,66 @@ fn test_portfolio_calculations() {
     assert_eq!(portfolio.total_value_usd, 250.0);
     // Weighted average PnL: (100 * 10 + 150 * -5) / 250 = (1000 - 750) / 250 = 1.0
     assert_eq!(portfolio.total_pnl_percentage, 1.0);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_stacked_stop_loss_warns() {
+    use crate::trading::{ConflictSeverity, Order, OrderManager, OrderOverlapConfig};
+    use rust_decimal::Decimal;
+
+    let mint = "mint1".to_string();
+    let mut existing = Order::create_stop_loss(1, mint.clone(), Decimal::new(100, 2), Decimal::from(1_000));
+    existing.metadata.position_size = Some(Decimal::from(1_000));
+
+    // 1% below the existing stop on the same full position: both would fire
+    let stacked = Order::create_stop_loss(1, mint.clone(), Decimal::new(99, 2), Decimal::from(1_000));
+    let conflicts = OrderManager::detect_conflicts(&stacked, &[existing.clone()], None, &OrderOverlapConfig::default());
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].severity, ConflictSeverity::Warning);
+    assert_eq!(conflicts[0].conflicting_order_ids, vec![existing.order_id.clone()]);
+
+    // Splitting the position between two nearby stops is fine
+    let mut split = Order::create_stop_loss(1, mint.clone(), Decimal::new(99, 2), Decimal::from(400));
+    split.metadata.position_size = Some(Decimal::from(1_000));
+    existing.base_amount = Decimal::from(600);
+    assert!(OrderManager::detect_conflicts(&split, &[existing.clone()], None, &OrderOverlapConfig::default()).is_empty());
+
+    // Far away stops are never stacked, and other users' orders are ignored upstream
+    let far = Order::create_stop_loss(1, mint, Decimal::new(80, 2), Decimal::from(1_000));
+    assert!(OrderManager::detect_conflicts(&far, &[existing], None, &OrderOverlapConfig::default()).is_empty());
+}
+
+#[test]
+fn test_take_profit_below_stop_rejected() {
+    use crate::trading::{ConflictSeverity, Order, OrderManager, OrderOverlapConfig};
+    use rust_decimal::Decimal;
+
+    let stop = Order::create_stop_loss(1, "mint1".to_string(), Decimal::new(90, 2), Decimal::from(1_000));
+    let take_profit = Order::create_take_profit(1, "mint1".to_string(), Decimal::new(85, 2), Decimal::from(1_000));
+
+    let conflicts = OrderManager::detect_conflicts(&take_profit, &[stop.clone()], None, &OrderOverlapConfig::default());
+    assert_eq!(conflicts[0].severity, ConflictSeverity::Rejected);
+    assert_eq!(conflicts[0].conflicting_order_ids, vec![stop.order_id.clone()]);
+
+    let sane = Order::create_take_profit(1, "mint1".to_string(), Decimal::new(150, 2), Decimal::from(1_000));
+    assert!(OrderManager::detect_conflicts(&sane, &[stop], None, &OrderOverlapConfig::default()).is_empty());
+}
+
+#[test]
+fn test_oco_legs_exempt_from_overlap() {
+    use crate::trading::{Order, OrderManager, OrderOverlapConfig};
+    use rust_decimal::Decimal;
+
+    let parent = "oco-parent".to_string();
+    let mut stop_leg = Order::create_stop_loss(1, "mint1".to_string(), Decimal::new(100, 2), Decimal::from(1_000));
+    stop_leg.parent_order_id = Some(parent.clone());
+    let mut second_stop = Order::create_stop_loss(1, "mint1".to_string(), Decimal::new(99, 2), Decimal::from(1_000));
+    second_stop.parent_order_id = Some(parent.clone());
+
+    // Sibling legs of the same OCO never flag each other
+    assert!(OrderManager::detect_conflicts(&second_stop, &[stop_leg.clone()], None, &OrderOverlapConfig::default()).is_empty());
+
+    // An unrelated stop at the same level still does
+    let unrelated = Order::create_stop_loss(1, "mint1".to_string(), Decimal::new(99, 2), Decimal::from(1_000));
+    assert!(!OrderManager::detect_conflicts(&unrelated, &[stop_leg], None, &OrderOverlapConfig::default()).is_empty());
+}
@@ -0,0 +1,133 @@
+use chrono::{Duration, TimeZone, Utc};
+use rust_decimal::{prelude::{FromPrimitive, ToPrimitive}, Decimal};
+
+use crate::bot::handlers::OrderHandler;
+use crate::trading::{
+    average_true_range, Order, PositionSide, PriceCandle, TrailDistance, TrailingStopState, TrailingStrategy,
+};
+
+fn dec(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap()
+}
+
+fn candle(index: usize, open: f64, close: f64, half_range: f64) -> PriceCandle {
+    PriceCandle {
+        timestamp: Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap() + Duration::minutes(index as i64),
+        open: dec(open),
+        high: dec(open.max(close) + half_range),
+        low: dec(open.min(close) - half_range),
+        close: dec(close),
+        volume: None,
+    }
+}
+
+fn atr_stop(entry: f64) -> TrailingStopState {
+    let mut stop = TrailingStopState::create_percentage_trailing(
+        42,
+        "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+        PositionSide::Long,
+        dec(entry),
+        Decimal::ONE,
+        5.0,
+    );
+    stop.strategy = TrailingStrategy::ATR {
+        atr_multiplier: 2.5,
+        atr_periods: 14,
+        min_trailing_amount: Decimal::ZERO,
+        max_trailing_amount: dec(1_000.0),
+    };
+    stop.current_stop_price = dec(entry * 0.95);
+    stop
+}
+
+#[test]
+fn test_atr_needs_a_full_window_and_averages_true_ranges() {
+    let flat: Vec<PriceCandle> = (0..20).map(|i| candle(i, 100.0, 100.0, 1.0)).collect();
+    assert_eq!(average_true_range(&flat[..14], 14), None);
+    assert!((average_true_range(&flat, 14).unwrap() - 2.0).abs() < 1e-9);
+    assert_eq!(average_true_range(&flat, 0), None);
+
+    // A gap counts from the previous close, not just the candle's own range
+    let mut gapped = flat.clone();
+    gapped.push(candle(20, 110.0, 110.0, 1.0));
+    let atr = average_true_range(&gapped, 14).unwrap();
+    assert!((atr - (2.0 * 13.0 + 11.0) / 14.0).abs() < 1e-9, "{}", atr);
+}
+
+#[test]
+fn test_trail_adapts_to_volatility_while_the_stop_only_tightens() {
+    let mut stop = atr_stop(100.0);
+    let mut candles = Vec::new();
+    let mut close = 100.0;
+    let mut stops = Vec::new();
+    let mut distances = Vec::new();
+
+    // Calm drift up, then a choppy stretch, then calm again
+    for index in 0..90 {
+        let volatile = (30..60).contains(&index);
+        let (step, half_range) = match (volatile, index % 2 == 0) {
+            (false, _) => (0.2, 0.3),
+            (true, true) => (2.5, 1.5),
+            (true, false) => (-2.0, 1.5),
+        };
+        let open = close;
+        close += step;
+        candles.push(candle(index, open, close, half_range));
+
+        stop.follow_atr(&candles);
+        if let Some(trail) = &stop.trail_distance {
+            assert_eq!(trail.basis, "ATR(14)×2.5");
+            assert_eq!(trail.reference_price, stop.highest_price);
+            // Never looser than the high-water mark less the trail
+            assert!(stop.current_stop_price >= trail.reference_price - trail.amount);
+        }
+        stops.push(stop.current_stop_price);
+        distances.push(stop.trail_distance.as_ref().map(|trail| trail.amount.to_f64().unwrap()));
+    }
+
+    assert!(distances[..14].iter().all(Option::is_none), "{:?}", &distances[..14]);
+    let calm = distances[29].unwrap();
+    let choppy = distances[59].unwrap();
+    let calm_again = distances[89].unwrap();
+    assert!(choppy > calm * 5.0, "calm {} choppy {}", calm, choppy);
+    assert!(calm_again < choppy / 2.0, "choppy {} calm again {}", choppy, calm_again);
+
+    assert!(stops.windows(2).all(|pair| pair[1] >= pair[0]), "{:?}", stops);
+    // The widening in the choppy stretch left the stop where it was for a while
+    assert!(stops[31..60].windows(2).any(|pair| pair[1] == pair[0]), "{:?}", &stops[31..60]);
+    assert!(stops[89] > stops[59]);
+}
+
+#[test]
+fn test_short_stops_trail_the_low_from_above() {
+    let mut stop = atr_stop(100.0);
+    stop.position_side = PositionSide::Short;
+    stop.current_stop_price = dec(105.0);
+
+    let mut candles = Vec::new();
+    let mut close = 100.0;
+    for index in 0..30 {
+        let open = close;
+        close -= 0.5;
+        candles.push(candle(index, open, close, 0.25));
+        stop.follow_atr(&candles);
+    }
+
+    let trail = stop.trail_distance.clone().unwrap();
+    assert_eq!(trail.reference_price, stop.lowest_price);
+    assert_eq!(stop.current_stop_price, stop.lowest_price + trail.amount);
+    assert!(stop.current_stop_price < dec(close + 5.0));
+}
+
+#[test]
+fn test_orders_listing_shows_the_atr_trail() {
+    let trail = TrailDistance { basis: "ATR(14)×2.5".to_string(), amount: dec(4.2), reference_price: dec(100.0) };
+    assert_eq!(trail.describe(), "ATR(14)×2.5 ≈ 4.2%");
+
+    let mut order = Order::create_stop_loss(42, "MINT".to_string(), dec(95.8), Decimal::ONE);
+    order.metadata.trail_distance = Some(trail);
+    assert_eq!(
+        OrderHandler::describe_trigger(&order, None, "en"),
+        "Trigger: $95.8\nTrail: ATR(14)×2.5 ≈ 4.2%"
+    );
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::bot::handlers::TrendingHandler;
+use crate::bot::trending::{TrendingCache, TrendingCacheConfig, TrendingSourceKind, MAX_TRENDING_TOKENS};
+use crate::testkit::MockTrending;
+
+#[tokio::test]
+async fn test_requests_are_served_from_cache() {
+    let upstream = MockTrending::default();
+    upstream.set_delay(Duration::from_millis(200)).await;
+    let cache = Arc::new(TrendingCache::new(Arc::new(upstream.clone())));
+
+    // Concurrent cold-start callers share a single refresh
+    let (a, b) = tokio::join!(cache.warm(), cache.refresh());
+    assert_eq!(a.refreshed_at, b.refreshed_at);
+    assert_eq!(upstream.call_count(), 3);
+
+    let started = Instant::now();
+    for _ in 0..100 {
+        let snapshot = cache.cached().await.expect("cache is warm");
+        assert!(TrendingHandler::render(&snapshot, "en").contains("As of"));
+    }
+    cache.warm().await;
+    assert!(started.elapsed() < Duration::from_millis(100));
+    assert_eq!(upstream.call_count(), 3);
+}
+
+#[tokio::test]
+async fn test_partial_failures_serve_fresh_data_with_a_note() {
+    let upstream = MockTrending::default();
+    let cache = TrendingCache::new(Arc::new(upstream.clone()));
+    cache.refresh().await;
+
+    upstream.set_failing(TrendingSourceKind::Launches, true).await;
+    upstream.set_tokens((0..100).map(|i| MockTrending::token(&format!("T{}", i), i as f64)).collect()).await;
+    let snapshot = cache.refresh().await;
+
+    // Market data is fresh and capped; launches come from the previous refresh
+    assert_eq!(snapshot.tokens.len(), MAX_TRENDING_TOKENS);
+    assert_eq!(snapshot.new_launches.len(), 1);
+    assert_eq!(snapshot.stale_sources(), vec![TrendingSourceKind::Launches]);
+    let footer = snapshot.footer(snapshot.refreshed_at + chrono::Duration::seconds(90), "en");
+    assert!(footer.contains("As of 1m ago"));
+    assert!(footer.contains("New launches couldn't be refreshed"));
+    assert!(!footer.contains("Market data"));
+
+    upstream.set_failing(TrendingSourceKind::Launches, false).await;
+    assert!(cache.refresh().await.stale_sources().is_empty());
+
+    // A source that never answered is reported as unavailable
+    upstream.set_failing(TrendingSourceKind::RiskAlerts, true).await;
+    let cold = TrendingCache::new(Arc::new(upstream.clone())).warm().await;
+    assert!(cold.footer(cold.refreshed_at, "en").contains("Risk alerts unavailable"));
+}
+
+#[test]
+fn test_background_refresh_is_jittered_within_bounds() {
+    let cache = TrendingCache::new(Arc::new(MockTrending::default())).with_config(TrendingCacheConfig {
+        min_refresh: Duration::from_secs(60),
+        max_refresh: Duration::from_secs(120),
+    });
+    let delays: Vec<Duration> = (0..50).map(|_| cache.next_delay()).collect();
+    assert!(delays.iter().all(|d| *d >= Duration::from_secs(60) && *d <= Duration::from_secs(120)));
+    assert!(delays.iter().any(|d| *d != delays[0]));
+}
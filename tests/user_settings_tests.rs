@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use crate::bot::handlers::settings::SettingsPage;
+use crate::bot::preferences::{
+    NotificationKind, PreferenceStore, SettingChange, SettingsStorage, UserSettings,
+};
+use crate::errors::Result;
+use crate::trading::TradeDefaults;
+use crate::wallet::TransactionPriority;
+
+/// Storage that takes a while on every call, so racing edits interleave
+#[derive(Default)]
+struct SlowStorage {
+    rows: Mutex<HashMap<i64, UserSettings>>,
+    saves: Mutex<usize>,
+}
+
+#[async_trait::async_trait]
+impl SettingsStorage for SlowStorage {
+    async fn load(&self, user_id: i64) -> Result<Option<UserSettings>> {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Ok(self.rows.lock().await.get(&user_id).cloned())
+    }
+
+    async fn save(&self, user_id: i64, settings: &UserSettings) -> Result<()> {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.rows.lock().await.insert(user_id, settings.clone());
+        *self.saves.lock().await += 1;
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: i64) -> Result<()> {
+        self.rows.lock().await.remove(&user_id);
+        Ok(())
+    }
+}
+
+fn store_with(storage: &Arc<SlowStorage>) -> PreferenceStore {
+    PreferenceStore::default().with_storage(storage.clone())
+}
+
+#[test]
+fn test_parse_setting_callbacks() {
+    assert_eq!(SettingChange::parse("setting:slip:300"), Some(SettingChange::SlippageBps(300)));
+    assert_eq!(SettingChange::parse("setting:max:0.5"), Some(SettingChange::MaxTradeSol(0.5)));
+    assert_eq!(
+        SettingChange::parse("setting:prio:critical"),
+        Some(SettingChange::PriorityFee(TransactionPriority::Critical))
+    );
+    assert_eq!(SettingChange::parse("setting:mev"), Some(SettingChange::ToggleMevProtection));
+    assert_eq!(SettingChange::parse("setting:ai"), Some(SettingChange::ToggleAiAnalysis));
+    assert_eq!(
+        SettingChange::parse("setting:notify:daily"),
+        Some(SettingChange::ToggleNotification(NotificationKind::DailySummary))
+    );
+    assert_eq!(SettingChange::parse("setting:timeout:60"), Some(SettingChange::SessionTimeout(60)));
+    assert_eq!(SettingChange::parse("setting:reset"), Some(SettingChange::Reset));
+
+    assert_eq!(SettingChange::parse("setting:slip:lots"), None);
+    assert_eq!(SettingChange::parse("setting:max:NaN"), None);
+    assert_eq!(SettingChange::parse("setting:prio:turbo"), None);
+    assert_eq!(SettingChange::parse("setting:mev:on"), None);
+    assert_eq!(SettingChange::parse("settings_trading"), None);
+}
+
+#[test]
+fn test_out_of_range_values_are_rejected() {
+    let mut settings = UserSettings::default();
+
+    // Slippage must stay within 0.1%–10%
+    assert!(settings.apply(SettingChange::SlippageBps(5)).is_err());
+    assert!(settings.apply(SettingChange::SlippageBps(1_500)).is_err());
+    assert!(settings.apply(SettingChange::SlippageBps(10)).is_ok());
+    assert!(settings.apply(SettingChange::SlippageBps(1_000)).is_ok());
+    assert_eq!(settings.slippage_bps, 1_000);
+
+    assert!(settings.apply(SettingChange::SessionTimeout(1)).is_err());
+    assert!(settings.apply(SettingChange::MaxTradeSol(1_000_000.0)).is_err());
+    assert!(settings.apply(SettingChange::MaxTradeSol(0.0)).is_err());
+    assert_eq!(settings.session_timeout_minutes, UserSettings::default().session_timeout_minutes);
+    assert_eq!(settings.max_trade_sol, UserSettings::default().max_trade_sol);
+}
+
+#[test]
+fn test_trade_defaults_follow_settings() {
+    let mut settings = UserSettings::default();
+    settings.apply(SettingChange::SlippageBps(250)).unwrap();
+    settings.apply(SettingChange::PriorityFee(TransactionPriority::Low)).unwrap();
+
+    assert_eq!(
+        settings.trade_defaults(),
+        TradeDefaults { slippage_bps: 250, priority: TransactionPriority::Low }
+    );
+}
+
+#[tokio::test]
+async fn test_racing_edits_both_survive() {
+    let storage = Arc::new(SlowStorage::default());
+    let store = store_with(&storage);
+
+    // Two callbacks for the same user land at once
+    let (mev, slippage) = tokio::join!(
+        store.apply(7, SettingChange::ToggleMevProtection),
+        store.apply(7, SettingChange::SlippageBps(300)),
+    );
+    mev.unwrap();
+    slippage.unwrap();
+
+    let settings = store.get(7).await;
+    assert!(settings.mev_protection);
+    assert_eq!(settings.slippage_bps, 300);
+
+    let stored = storage.rows.lock().await.get(&7).cloned().unwrap();
+    assert_eq!(stored, settings);
+    assert_eq!(*storage.saves.lock().await, 2);
+}
+
+#[tokio::test]
+async fn test_racing_toggles_each_apply() {
+    let storage = Arc::new(SlowStorage::default());
+    let store = Arc::new(store_with(&storage));
+
+    // Three toggles in flight flip the flag three times: on, off, on
+    let tasks: Vec<_> = (0..3)
+        .map(|_| {
+            let store = store.clone();
+            tokio::spawn(async move { store.apply(9, SettingChange::ToggleAiAnalysis).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    assert!(!store.get(9).await.ai_analysis);
+    assert_eq!(*storage.saves.lock().await, 3);
+}
+
+#[tokio::test]
+async fn test_rejected_edit_is_not_saved() {
+    let storage = Arc::new(SlowStorage::default());
+    let store = store_with(&storage);
+
+    assert!(store.apply(3, SettingChange::SlippageBps(2_000)).await.is_err());
+
+    assert_eq!(store.get(3).await, UserSettings::default());
+    assert_eq!(*storage.saves.lock().await, 0);
+}
+
+#[tokio::test]
+async fn test_settings_are_read_back_from_storage() {
+    let storage = Arc::new(SlowStorage::default());
+    store_with(&storage).apply(5, SettingChange::SessionTimeout(120)).await.unwrap();
+
+    // A fresh store, as after a restart, sees the stored value
+    let restarted = store_with(&storage);
+    assert_eq!(restarted.get(5).await.session_timeout_minutes, 120);
+}
+
+#[tokio::test]
+async fn test_reset_restores_defaults() {
+    let storage = Arc::new(SlowStorage::default());
+    let store = store_with(&storage);
+
+    store.apply(11, SettingChange::SlippageBps(500)).await.unwrap();
+    store.apply(11, SettingChange::ToggleNotification(NotificationKind::Trades)).await.unwrap();
+    store.apply(11, SettingChange::PriorityFee(TransactionPriority::Critical)).await.unwrap();
+
+    let reset = store.apply(11, SettingChange::Reset).await.unwrap();
+    assert_eq!(reset, UserSettings::default());
+    assert_eq!(storage.rows.lock().await.get(&11), Some(&UserSettings::default()));
+}
+
+#[test]
+fn test_pages_show_current_values() {
+    let mut settings = UserSettings::default();
+    settings.apply(SettingChange::SlippageBps(300)).unwrap();
+
+    assert_eq!(SettingsPage::from_callback("settings_trading"), Some(SettingsPage::Trading));
+    assert_eq!(SettingsPage::for_change(SettingChange::SlippageBps(300)), SettingsPage::Trading);
+    assert_eq!(SettingsPage::for_change(SettingChange::Reset), SettingsPage::Overview);
+
+    assert!(SettingsPage::Trading.text(&settings, "en").contains("Slippage tolerance: 3%"));
+    assert!(SettingsPage::Overview.text(&settings, "en").contains("Session timeout: 30 minutes"));
+}
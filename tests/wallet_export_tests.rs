@@ -0,0 +1,100 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Duration;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::bot::wallet_transfer::{TransferPrompt, WalletTransfers};
+use crate::wallet::{ExportKdf, WalletExport};
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
+/// Cheap enough that the corruption cases don't each pay for the default cost
+fn light() -> ExportKdf {
+    ExportKdf { memory_kib: 1024, iterations: 1, parallelism: 1 }
+}
+
+fn reencode(blob: &str, edit: impl FnOnce(&mut Vec<u8>)) -> String {
+    let mut bytes = URL_SAFE_NO_PAD.decode(blob).unwrap();
+    edit(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[test]
+fn test_export_round_trips_with_default_cost() {
+    let keypair = Keypair::new();
+    let blob = WalletExport::encrypt(&keypair, PASSPHRASE).unwrap();
+
+    assert!(!blob.contains(&bs58::encode(keypair.to_bytes()).into_string()));
+    assert!(WalletExport::looks_like_export(&blob));
+    let restored = WalletExport::decrypt(&blob, PASSPHRASE).unwrap();
+    assert_eq!(restored.pubkey(), keypair.pubkey());
+    assert_eq!(restored.to_bytes(), keypair.to_bytes());
+
+    // Fresh salt and nonce every time
+    assert_ne!(WalletExport::encrypt(&keypair, PASSPHRASE).unwrap(), blob);
+}
+
+#[test]
+fn test_wrong_passphrase_fails_cleanly() {
+    let blob = WalletExport::encrypt_with(&Keypair::new(), PASSPHRASE, light()).unwrap();
+
+    let err = WalletExport::decrypt(&blob, "correct horse battery stapler").unwrap_err();
+    assert!(err.to_string().contains("Wrong passphrase"), "got {}", err);
+}
+
+#[test]
+fn test_corrupted_blobs_are_rejected() {
+    let blob = WalletExport::encrypt_with(&Keypair::new(), PASSPHRASE, light()).unwrap();
+
+    // A flipped ciphertext bit fails authentication
+    let flipped = reencode(&blob, |bytes| *bytes.last_mut().unwrap() ^= 0x01);
+    assert!(WalletExport::decrypt(&flipped, PASSPHRASE).is_err());
+
+    // So does a header edit, since the header is authenticated too
+    let cheaper = reencode(&blob, |bytes| bytes[5] = 2);
+    assert!(WalletExport::decrypt(&cheaper, PASSPHRASE).unwrap_err().to_string().contains("Wrong passphrase"));
+
+    // Malformed input is refused before any key derivation
+    let truncated = reencode(&blob, |bytes| bytes.truncate(bytes.len() - 1));
+    let costly = reencode(&blob, |bytes| bytes[1..5].copy_from_slice(&u32::MAX.to_le_bytes()));
+    let unknown_version = reencode(&blob, |bytes| bytes[0] = 9);
+    for bad in [truncated.as_str(), costly.as_str(), unknown_version.as_str(), "not-an-export", ""] {
+        assert!(!WalletExport::looks_like_export(bad));
+        let err = WalletExport::decrypt(bad, PASSPHRASE).unwrap_err();
+        assert!(err.to_string().contains("isn't a wallet export"), "got {}", err);
+    }
+}
+
+#[test]
+fn test_short_passphrases_are_refused() {
+    assert!(WalletExport::encrypt_with(&Keypair::new(), "hunter2", light()).is_err());
+}
+
+#[tokio::test]
+async fn test_exports_need_a_recent_confirm_each_time() {
+    let transfers = WalletTransfers::default();
+    assert!(!transfers.authorize_export(1).await);
+
+    transfers.confirm(1).await;
+    assert!(transfers.authorize_export(1).await);
+    // Spent by the first export
+    assert!(!transfers.authorize_export(1).await);
+
+    let lapsed = WalletTransfers::new(Duration::zero(), Duration::minutes(2));
+    lapsed.confirm(1).await;
+    assert!(!lapsed.authorize_export(1).await);
+}
+
+#[tokio::test]
+async fn test_passphrase_prompts_are_taken_once_and_time_out() {
+    let transfers = WalletTransfers::default();
+    transfers.ask(1, TransferPrompt::Import { blob: "blob".to_string() }, Some(42)).await;
+
+    let pending = transfers.take(1).await.unwrap();
+    assert_eq!(pending.prompt, TransferPrompt::Import { blob: "blob".to_string() });
+    assert_eq!(pending.prompt_message_id, Some(42));
+    assert!(transfers.take(1).await.is_none());
+
+    let expired = WalletTransfers::new(Duration::minutes(2), Duration::zero());
+    expired.ask(1, TransferPrompt::Export, None).await;
+    assert!(expired.take(1).await.is_none());
+}
@@ -0,0 +1,123 @@
+use crate::wallet::{
+    ActivityAction, ActivityClass, ActivityWatchConfig, TokenChange, WalletActivityWatcher, WalletTransaction,
+};
+use chrono::{Duration, Utc};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use std::sync::Arc;
+
+const WALLET: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+const ATTACKER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const USER_ID: i64 = 424_242;
+
+fn watcher() -> WalletActivityWatcher {
+    // Never contacted: these tests feed transactions directly
+    let rpc_client = Arc::new(RpcClient::new("http://127.0.0.1:8899".to_string()));
+    WalletActivityWatcher::new(rpc_client, ActivityWatchConfig::default())
+}
+
+fn foreign_outbound_transfer() -> WalletTransaction {
+    WalletTransaction {
+        signature: "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW".to_string(),
+        wallet: WALLET.to_string(),
+        slot: 250_000_123,
+        block_time: Some(Utc::now()),
+        wallet_signed: true,
+        sol_change: -1.500005,
+        token_changes: vec![],
+        counterparties: vec![ATTACKER.to_string()],
+        programs: vec!["11111111111111111111111111111111".to_string()],
+        failed: false,
+    }
+}
+
+fn inbound_deposit() -> WalletTransaction {
+    WalletTransaction {
+        signature: "3nVxD4Z2C9eKQmA8wbHyJ5tRzP7sLkMfUqG6oXcY1vTdN4hB2jE8rW5aS9uF3gK7iP1yL6mQ0xZ4cV8bN2tR5wJ".to_string(),
+        wallet: WALLET.to_string(),
+        slot: 250_000_124,
+        block_time: Some(Utc::now()),
+        wallet_signed: false,
+        sol_change: 2.0,
+        token_changes: vec![TokenChange {
+            mint: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+            change: 1_000_000.0,
+        }],
+        counterparties: vec![ATTACKER.to_string()],
+        programs: vec!["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".to_string()],
+        failed: false,
+    }
+}
+
+#[tokio::test]
+async fn test_foreign_outbound_transfer_alerts_and_locks() {
+    let watcher = watcher();
+    let transaction = foreign_outbound_transfer();
+    let now = Utc::now();
+
+    assert_eq!(watcher.classify(&transaction).await, ActivityClass::Foreign);
+    watcher.ingest(USER_ID, transaction.clone(), now).await;
+
+    // Held during the grace period in case the bot records it late
+    assert!(watcher.flush_held(now + Duration::seconds(1)).await.is_empty());
+
+    let alerts = watcher.flush_held(now + Duration::seconds(60)).await;
+    assert_eq!(alerts.len(), 1);
+    let alert = &alerts[0];
+    assert_eq!(alert.user_id, USER_ID);
+    assert!(alert.message.contains("not sent by the bot"));
+    assert!(alert.message.contains("Sent 1.5000 SOL to 9WzD…AWWM"));
+    assert!(alert.message.contains("Interacted with: System Program"));
+
+    // Lock action from the alert button
+    let data = WalletActivityWatcher::action_data(&ActivityAction::LockTrading);
+    let action = WalletActivityWatcher::parse_action(&data).unwrap();
+    assert_eq!(action, ActivityAction::LockTrading);
+    watcher.apply_action(USER_ID, &action).await;
+    assert!(watcher.is_trading_locked(USER_ID).await);
+
+    watcher.apply_action(USER_ID, &ActivityAction::UnlockTrading).await;
+    assert!(!watcher.is_trading_locked(USER_ID).await);
+}
+
+#[tokio::test]
+async fn test_inbound_deposit_goes_to_reconciliation_only() {
+    let watcher = watcher();
+    let mut deposits = watcher.subscribe_deposits();
+    let mut alerts = watcher.subscribe_alerts();
+    let now = Utc::now();
+
+    watcher.ingest(USER_ID, inbound_deposit(), now).await;
+
+    assert!(watcher.flush_held(now + Duration::hours(1)).await.is_empty());
+    assert!(alerts.try_recv().is_err());
+
+    let notice = deposits.try_recv().expect("deposit should be queued for reconciliation");
+    assert_eq!(notice.user_id, USER_ID);
+    assert_eq!(notice.transaction.sol_change, 2.0);
+}
+
+#[tokio::test]
+async fn test_late_recorded_bot_signature_is_not_alerted() {
+    let watcher = watcher();
+    let transaction = foreign_outbound_transfer();
+    let now = Utc::now();
+
+    // The second pool endpoint confirmed before the first returned the signature
+    watcher.ingest(USER_ID, transaction.clone(), now).await;
+    watcher.record_originated(&transaction.signature).await;
+
+    assert!(watcher.flush_held(now + Duration::hours(1)).await.is_empty());
+    assert_eq!(watcher.classify(&transaction).await, ActivityClass::Originated);
+}
+
+#[test]
+fn test_activity_action_data_fits_callback_limit() {
+    let signature = foreign_outbound_transfer().signature;
+    let data = WalletActivityWatcher::action_data(&ActivityAction::MarkExpected(signature.clone()));
+    assert!(data.len() <= 64);
+
+    match WalletActivityWatcher::parse_action(&data) {
+        Some(ActivityAction::MarkExpected(prefix)) => assert!(signature.starts_with(&prefix)),
+        other => panic!("unexpected action {:?}", other),
+    }
+}
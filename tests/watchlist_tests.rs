@@ -0,0 +1,185 @@
+use chrono::{Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::alerts::AlertDelivery;
+use crate::api::{RiskAssessment, WatchlistToken};
+use crate::bot::preferences::PreferenceStore;
+use crate::bot::watchlist::{
+    movers, WatchQuote, WatchlistMarket, WatchlistStore, DIGEST_MOVE_PERCENT, MAX_WATCHLIST_TOKENS,
+};
+use crate::errors::Result;
+use crate::trading::TimezoneManager;
+
+const USER: i64 = 824_001;
+const OTHER: i64 = 824_002;
+
+/// Fixed quotes; no token has a risk assessment
+#[derive(Default)]
+struct FixedMarket {
+    quotes: HashMap<String, WatchQuote>,
+}
+
+impl FixedMarket {
+    fn with(mut self, mint: &str, usd_price: f64, change: Option<f64>) -> Self {
+        self.quotes.insert(mint.to_string(), WatchQuote { usd_price, change_24h_percent: change });
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WatchlistMarket for FixedMarket {
+    async fn quotes(&self, mints: &[String]) -> Result<HashMap<String, WatchQuote>> {
+        Ok(mints.iter().filter_map(|mint| Some((mint.clone(), *self.quotes.get(mint)?))).collect())
+    }
+
+    async fn risk(&self, _mint: &str) -> Option<RiskAssessment> {
+        None
+    }
+}
+
+fn token(address: &str, symbol: &str) -> WatchlistToken {
+    WatchlistToken {
+        address: address.to_string(),
+        symbol: symbol.to_string(),
+        added_at: Utc::now(),
+        alert_price_above: None,
+        alert_price_below: None,
+        notes: None,
+    }
+}
+
+#[tokio::test]
+async fn test_add_and_remove_are_idempotent() {
+    let store = WatchlistStore::new(Arc::new(FixedMarket::default()));
+
+    assert!(store.add(USER, "BonkMint", "BONK").await.unwrap());
+    assert!(!store.add(USER, "BonkMint", "BONK").await.unwrap());
+    // Deduplicated by mint, whatever symbol it arrives with
+    assert!(!store.add(USER, "BonkMint", "bonk2").await.unwrap());
+    assert!(store.add(USER, "WifMint", "WIF").await.unwrap());
+    assert_eq!(store.get(USER).await.tokens().len(), 2);
+    assert!(store.get(OTHER).await.tokens().is_empty());
+
+    // By symbol or mint; the second removal finds nothing
+    assert_eq!(store.remove(USER, "bonk").await.unwrap().map(|t| t.address).as_deref(), Some("BonkMint"));
+    assert!(store.remove(USER, "bonk").await.unwrap().is_none());
+    assert!(store.remove(USER, "BonkMint").await.unwrap().is_none());
+    assert!(store.remove(OTHER, "WIF").await.unwrap().is_none());
+    assert_eq!(store.remove(USER, "WifMint").await.unwrap().map(|t| t.symbol).as_deref(), Some("WIF"));
+    assert!(store.get(USER).await.tokens().is_empty());
+
+    // Removed tokens can come back
+    assert!(store.add(USER, "BonkMint", "BONK").await.unwrap());
+    assert_eq!(store.forget_user(USER).await, 1);
+    assert_eq!(store.forget_user(USER).await, 0);
+}
+
+#[tokio::test]
+async fn test_watchlist_is_capped_per_user() {
+    let store = WatchlistStore::new(Arc::new(FixedMarket::default()));
+    for i in 0..MAX_WATCHLIST_TOKENS {
+        assert!(store.add(USER, &format!("Mint{}", i), &format!("T{}", i)).await.unwrap());
+    }
+
+    assert!(store.add(USER, "OneTooMany", "MORE").await.is_err());
+    // Re-adding a watched token at the cap is still a no-op, not an error
+    assert!(!store.add(USER, "Mint0", "T0").await.unwrap());
+    assert_eq!(store.get(USER).await.tokens().len(), MAX_WATCHLIST_TOKENS);
+    // The cap is per user
+    assert!(store.add(OTHER, "OneTooMany", "MORE").await.unwrap());
+
+    store.remove(USER, "Mint0").await.unwrap();
+    assert!(store.add(USER, "OneTooMany", "MORE").await.unwrap());
+}
+
+#[test]
+fn test_digest_keeps_moves_past_the_threshold() {
+    let tokens = vec![
+        token("Up", "UP"),
+        token("Down", "DOWN"),
+        token("Edge", "EDGE"),
+        token("Flat", "FLAT"),
+        token("JustUnder", "UNDER"),
+        token("NoHistory", "NEW"),
+        token("NoPrice", "GONE"),
+    ];
+    let quotes: HashMap<String, WatchQuote> = [
+        ("Up", 7.2),
+        ("Down", -12.0),
+        ("Edge", -DIGEST_MOVE_PERCENT),
+        ("Flat", 0.4),
+        ("JustUnder", 4.99),
+    ]
+    .into_iter()
+    .map(|(mint, change)| (mint.to_string(), WatchQuote { usd_price: 1.0, change_24h_percent: Some(change) }))
+    .chain([("NoHistory".to_string(), WatchQuote { usd_price: 1.0, change_24h_percent: None })])
+    .collect();
+
+    let moved = movers(&tokens, &quotes, DIGEST_MOVE_PERCENT);
+    let symbols: Vec<&str> = moved.iter().map(|mover| mover.symbol.as_str()).collect();
+    // Biggest move first, in either direction
+    assert_eq!(symbols, vec!["DOWN", "UP", "EDGE"]);
+    assert_eq!(moved[0].change_24h_percent, -12.0);
+}
+
+#[tokio::test]
+async fn test_digest_goes_out_once_a_day_at_the_local_time() {
+    let market = FixedMarket::default()
+        .with("BonkMint", 0.000021, Some(8.5))
+        .with("WifMint", 1.83, Some(-2.0))
+        .with("JupMint", 0.92, Some(-6.25));
+    let store = WatchlistStore::new(Arc::new(market));
+    for (mint, symbol) in [("BonkMint", "BONK"), ("WifMint", "WIF"), ("JupMint", "JUP")] {
+        store.add(USER, mint, symbol).await.unwrap();
+    }
+
+    let timezones = TimezoneManager::new("UTC");
+    let tz: Tz = timezones.set_user_timezone(USER, "Asia/Tokyo").await.unwrap();
+    store.set_digest(USER, Some(NaiveTime::from_hms_opt(9, 0, 0).unwrap()), tz).await.unwrap();
+
+    let delivery = AlertDelivery::default();
+    let mut received = delivery.subscribe_telegram();
+    let preferences = PreferenceStore::default();
+
+    // 08:30 and 09:05 in Tokyo the next two days
+    let tomorrow = Utc::now().with_timezone(&tz).date_naive() + Duration::days(1);
+    let tokyo = |days: i64, hour: u32, minute: u32| {
+        let local = (tomorrow + Duration::days(days)).and_hms_opt(hour, minute, 0).unwrap();
+        tz.from_local_datetime(&local).unwrap().with_timezone(&Utc)
+    };
+
+    assert_eq!(store.send_due_digests(tokyo(0, 8, 30), &timezones, &preferences, &delivery).await, 0);
+    assert_eq!(store.send_due_digests(tokyo(0, 9, 5), &timezones, &preferences, &delivery).await, 1);
+    let digest = received.try_recv().unwrap();
+    assert_eq!(digest.chat_id, USER);
+    assert!(digest.message.contains("BONK") && digest.message.contains("JUP"), "{}", digest.message);
+    assert!(!digest.message.contains("WIF"), "{}", digest.message);
+    assert!(digest.message.find("BONK") < digest.message.find("JUP"), "{}", digest.message);
+
+    // Once per local day
+    assert_eq!(store.send_due_digests(tokyo(0, 9, 10), &timezones, &preferences, &delivery).await, 0);
+    assert_eq!(store.send_due_digests(tokyo(1, 9, 0), &timezones, &preferences, &delivery).await, 1);
+
+    // Turned off, nothing more goes out
+    store.set_digest(USER, None, tz).await.unwrap();
+    assert_eq!(store.send_due_digests(tokyo(2, 9, 0), &timezones, &preferences, &delivery).await, 0);
+}
+
+#[tokio::test]
+async fn test_quiet_day_is_marked_done_without_a_message() {
+    let store = WatchlistStore::new(Arc::new(FixedMarket::default().with("WifMint", 1.83, Some(3.0))));
+    store.add(USER, "WifMint", "WIF").await.unwrap();
+    store.set_digest(USER, Some(NaiveTime::from_hms_opt(0, 0, 0).unwrap()), Tz::UTC).await.unwrap();
+
+    let delivery = AlertDelivery::default();
+    let mut received = delivery.subscribe_telegram();
+    let timezones = TimezoneManager::new("UTC");
+    let next_day = Utc::now() + Duration::days(1);
+
+    assert!(store.digest(USER).await.unwrap().is_empty());
+    assert_eq!(store.send_due_digests(next_day, &timezones, &PreferenceStore::default(), &delivery).await, 0);
+    assert!(received.try_recv().is_err());
+    assert_eq!(store.get(USER).await.last_digest, Some(next_day.date_naive()));
+}
@@ -0,0 +1,334 @@
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{accept_async, WebSocketStream};
+
+use crate::errors::Result;
+use crate::monitoring::MetricsCollector;
+use crate::websocket::{
+    ConnectionEvent, ConnectionStatus, ErrorHandler, MessageHandler, PriceStreamManager, PriceSource, PriceSubscription,
+    PriceUpdate, ReconnectStrategy, StreamData, SubscriptionRequest, SubscriptionType, UpdateType, WebSocketClient,
+    WebSocketConfig, WebSocketError, WebSocketMessage,
+};
+
+fn config(heartbeat_ms: u64, idle_ms: u64) -> WebSocketConfig {
+    WebSocketConfig {
+        reconnect_strategy: ReconnectStrategy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(50),
+            exponential_backoff: true,
+            jitter: false,
+        },
+        heartbeat_interval: Duration::from_millis(heartbeat_ms),
+        idle_timeout: Duration::from_millis(idle_ms),
+        ..WebSocketConfig::default()
+    }
+}
+
+async fn listen() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    (listener, url)
+}
+
+async fn accept(listener: &TcpListener) -> WebSocketStream<TcpStream> {
+    let (tcp, _) = listener.accept().await.unwrap();
+    accept_async(tcp).await.unwrap()
+}
+
+/// Next text frame, skipping pings
+async fn next_text(ws: &mut WebSocketStream<TcpStream>) -> Option<String> {
+    while let Some(Ok(msg)) = ws.next().await {
+        if let Message::Text(text) = msg {
+            return Some(text);
+        }
+    }
+    None
+}
+
+fn subscribed_id(text: &str) -> String {
+    match serde_json::from_str::<WebSocketMessage>(text).unwrap() {
+        WebSocketMessage::Subscribe(request) => request.id,
+        other => panic!("expected a subscribe frame, got {:?}", other),
+    }
+}
+
+fn request(id: &str) -> SubscriptionRequest {
+    SubscriptionRequest {
+        id: id.to_string(),
+        subscription_type: SubscriptionType::Price { symbols: vec!["SOL".to_string()] },
+        params: HashMap::new(),
+        filters: None,
+    }
+}
+
+async fn wait_for(
+    events: &mut broadcast::Receiver<ConnectionEvent>,
+    wanted: impl Fn(&ConnectionStatus) -> bool,
+) -> Vec<ConnectionStatus> {
+    timeout(Duration::from_secs(5), async {
+        let mut seen = Vec::new();
+        loop {
+            let status = events.recv().await.unwrap().status;
+            let done = wanted(&status);
+            seen.push(status);
+            if done {
+                return seen;
+            }
+        }
+    })
+    .await
+    .expect("connection event never arrived")
+}
+
+fn event_count(metrics: &MetricsCollector, connection: &str, event: &str) -> f64 {
+    metrics.gather()
+        .iter()
+        .filter(|f| f.get_name() == "websocket_events_total")
+        .flat_map(|f| f.get_metric().iter())
+        .filter(|m| {
+            let labels: Vec<&str> = m.get_label().iter().map(|l| l.get_value()).collect();
+            labels.contains(&connection) && labels.contains(&event)
+        })
+        .map(|m| m.get_counter().get_value())
+        .sum()
+}
+
+#[derive(Default)]
+struct RecordingErrors {
+    errors: Mutex<Vec<WebSocketError>>,
+}
+
+#[async_trait]
+impl ErrorHandler for RecordingErrors {
+    async fn handle_error(&self, error: WebSocketError) -> Result<()> {
+        self.errors.lock().await.push(error);
+        Ok(())
+    }
+
+    async fn handle_disconnection(&self, _endpoint: String) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct ForwardingHandler {
+    id: String,
+    tx: mpsc::UnboundedSender<StreamData>,
+}
+
+#[async_trait]
+impl MessageHandler for ForwardingHandler {
+    async fn handle_message(&self, message: StreamData) -> Result<()> {
+        let _ = self.tx.send(message);
+        Ok(())
+    }
+
+    fn subscription_id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+#[tokio::test]
+async fn subscriptions_are_replayed_after_the_server_drops_the_connection() {
+    let (listener, url) = listen().await;
+    let (frames_tx, mut frames) = mpsc::unbounded_channel::<(u32, String)>();
+    tokio::spawn(async move {
+        // First session: take both subscriptions, then vanish without a close frame
+        let mut ws = accept(&listener).await;
+        for _ in 0..2 {
+            frames_tx.send((0, next_text(&mut ws).await.unwrap())).unwrap();
+        }
+        drop(ws);
+
+        let mut ws = accept(&listener).await;
+        while let Some(text) = next_text(&mut ws).await {
+            let _ = frames_tx.send((1, text));
+        }
+    });
+
+    let metrics = super::shared_metrics();
+    let client = WebSocketClient::new(config(100, 1_000), None).with_metrics(metrics.clone());
+    let mut events = client.status_events();
+
+    // Subscriptions made before connecting are kept and sent on connect
+    client.subscribe("resume_feed", request("sol")).await.unwrap();
+    client.subscribe("resume_feed", request("bonk")).await.unwrap();
+    client.subscribe("resume_feed", request("dropped")).await.unwrap();
+    client.unsubscribe("resume_feed", "dropped").await.unwrap();
+    client.connect("resume_feed", &url).await.unwrap();
+
+    let seen = wait_for(&mut events, |s| matches!(s, ConnectionStatus::Resubscribed { .. })).await;
+    assert_eq!(seen, vec![
+        ConnectionStatus::Connecting,
+        ConnectionStatus::Connected,
+        ConnectionStatus::Disconnected,
+        ConnectionStatus::Reconnecting,
+        ConnectionStatus::Connected,
+        ConnectionStatus::Resubscribed { channels: 2 },
+    ]);
+
+    let mut sessions: Vec<(u32, String)> = Vec::new();
+    for _ in 0..4 {
+        let (session, text) = timeout(Duration::from_secs(5), frames.recv()).await.unwrap().unwrap();
+        sessions.push((session, subscribed_id(&text)));
+    }
+    let expected = vec![
+        (0, "sol".to_string()),
+        (0, "bonk".to_string()),
+        (1, "sol".to_string()),
+        (1, "bonk".to_string()),
+    ];
+    assert_eq!(sessions, expected);
+
+    // New subscriptions go straight out on the live connection
+    client.subscribe("resume_feed", request("wif")).await.unwrap();
+    let (session, text) = timeout(Duration::from_secs(5), frames.recv()).await.unwrap().unwrap();
+    assert_eq!((session, subscribed_id(&text)), (1, "wif".to_string()));
+    assert_eq!(client.subscriptions("resume_feed").await.len(), 3);
+
+    let stats = client.get_stats().await.remove("resume_feed").unwrap();
+    assert_eq!(stats.status, ConnectionStatus::Connected);
+    assert_eq!(stats.reconnects, 1);
+    assert_eq!(stats.reconnect_attempts, 0, "attempts reset once the connection is back up");
+    assert_eq!(stats.resubscribed_channels, 2);
+    assert_eq!(event_count(&metrics, "resume_feed", "reconnect"), 1.0);
+    assert_eq!(event_count(&metrics, "resume_feed", "resubscribed"), 2.0);
+
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn a_silent_server_trips_the_idle_timeout() {
+    let (listener, url) = listen().await;
+    tokio::spawn(async move {
+        // Never read, so pings go unanswered
+        let _silent = accept(&listener).await;
+        let mut ws = accept(&listener).await;
+        while next_text(&mut ws).await.is_some() {}
+    });
+
+    let metrics = super::shared_metrics();
+    let client = WebSocketClient::new(config(50, 250), None).with_metrics(metrics.clone());
+    let errors = Arc::new(RecordingErrors::default());
+    client.register_error_handler(errors.clone()).await;
+    let mut events = client.status_events();
+    client.connect("idle_feed", &url).await.unwrap();
+
+    let seen = wait_for(&mut events, |s| matches!(s, ConnectionStatus::Resubscribed { .. })).await;
+    assert!(seen.contains(&ConnectionStatus::Reconnecting));
+    assert_eq!(seen.last(), Some(&ConnectionStatus::Resubscribed { channels: 0 }));
+
+    let stats = client.get_stats().await.remove("idle_feed").unwrap();
+    assert_eq!(stats.reconnects, 1);
+    assert!(stats.missed_heartbeats >= 1, "{:?}", stats);
+    assert!(event_count(&metrics, "idle_feed", "missed_heartbeat") >= 1.0);
+    assert!(errors.errors.lock().await.iter().any(|e| matches!(e, WebSocketError::HeartbeatTimeout)));
+
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn data_frames_reach_the_registered_handler() {
+    let (listener, url) = listen().await;
+    tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let data = WebSocketMessage::Data(StreamData {
+            subscription_id: "ticks".to_string(),
+            timestamp: chrono::Utc::now(),
+            sequence: 7,
+            data: serde_json::json!({ "price": 142.5 }),
+        });
+        ws.send(Message::Text(serde_json::to_string(&data).unwrap())).await.unwrap();
+        while next_text(&mut ws).await.is_some() {}
+    });
+
+    let client = WebSocketClient::new(config(100, 1_000), None);
+    let (tx, mut received) = mpsc::unbounded_channel();
+    client.register_handler(Arc::new(ForwardingHandler { id: "ticks".to_string(), tx })).await;
+    client.connect("data_feed", &url).await.unwrap();
+
+    let data = timeout(Duration::from_secs(5), received.recv()).await.unwrap().unwrap();
+    assert_eq!(data.sequence, 7);
+    assert_eq!(data.data["price"], 142.5);
+    assert!(client.get_stats().await["data_feed"].messages_received >= 1);
+
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn an_unreachable_endpoint_ends_in_failed() {
+    let (listener, url) = listen().await;
+    drop(listener);
+
+    let client = WebSocketClient::new(config(100, 1_000), None);
+    let mut events = client.status_events();
+    client.connect("dead_feed", &url).await.unwrap();
+
+    let seen = wait_for(&mut events, |s| matches!(s, ConnectionStatus::Failed(_))).await;
+    assert!(!seen.contains(&ConnectionStatus::Connected));
+    assert!(matches!(client.get_status("dead_feed").await, Some(ConnectionStatus::Failed(_))));
+}
+
+#[tokio::test]
+async fn price_history_is_cleared_when_the_stream_resubscribes() {
+    let (listener, url) = listen().await;
+    tokio::spawn(async move {
+        // Answer the subscription with one tick, then drop
+        let mut ws = accept(&listener).await;
+        let id = subscribed_id(&next_text(&mut ws).await.unwrap());
+        let update = PriceUpdate {
+            symbol: "SOL".to_string(),
+            price: Decimal::new(1425, 1),
+            timestamp: chrono::Utc::now(),
+            volume: Some(Decimal::ONE),
+            source: PriceSource::Pyth,
+            update_type: UpdateType::Trade,
+            metadata: None,
+        };
+        let data = WebSocketMessage::Data(StreamData {
+            subscription_id: id,
+            timestamp: chrono::Utc::now(),
+            sequence: 1,
+            data: serde_json::to_value(&update).unwrap(),
+        });
+        ws.send(Message::Text(serde_json::to_string(&data).unwrap())).await.unwrap();
+        drop(ws);
+
+        let mut ws = accept(&listener).await;
+        while next_text(&mut ws).await.is_some() {}
+    });
+
+    let client = Arc::new(WebSocketClient::new(config(100, 1_000), None));
+    let prices = PriceStreamManager::new(client.clone());
+    let _watcher = prices.spawn_connection_watcher();
+    let mut updates = prices.subscribe_prices(PriceSubscription {
+        symbols: vec!["SOL".to_string()],
+        sources: vec![PriceSource::Pyth],
+        include_orderbook: false,
+        orderbook_depth: 0,
+        include_trades: false,
+        aggregation_interval: None,
+    }).await.unwrap();
+    client.connect("price_stream", &url).await.unwrap();
+
+    timeout(Duration::from_secs(5), updates.recv()).await.unwrap().unwrap();
+    timeout(Duration::from_secs(5), async {
+        while !prices.get_price_history("SOL").await.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("history spanning the reconnect was kept");
+    assert!(prices.calculate_vwap("SOL", 10).await.is_none());
+    assert!(prices.get_price("SOL").await.is_some(), "the last known price stays");
+
+    client.shutdown().await.unwrap();
+}
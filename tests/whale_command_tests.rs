@@ -0,0 +1,95 @@
+use crate::alerts::{WhaleAction, WhaleActivity};
+use crate::testkit::TestHarness;
+use crate::trading::TokenResolver;
+use std::time::Duration;
+
+const WATCHER: i64 = 767_001;
+const HIGH_ROLLER: i64 = 767_002;
+const WHALE: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const RECIPIENT: &str = "5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9";
+
+/// 250M BONK out of the whale's wallet; $5,000 at the test price
+fn bonk_transfer(bonk: &str, signature: &str) -> WhaleActivity {
+    WhaleActivity {
+        signature: signature.to_string(),
+        wallet: WHALE.to_string(),
+        action: WhaleAction::Transfer,
+        mint: bonk.to_string(),
+        amount: 250_000_000.0,
+        counterparty: Some(RECIPIENT.to_string()),
+        slot: 287_700_100,
+        block_time: None,
+    }
+}
+
+#[tokio::test]
+async fn whales_command_watches_wallets_and_reports_moves_over_each_users_threshold() {
+    let bonk = TokenResolver::resolve("BONK").unwrap();
+    let harness = TestHarness::builder().price(&bonk, 0.00002).build().await.unwrap();
+    let whales = harness.services.whales.clone();
+    harness.start_bot();
+
+    harness.telegram.inject_message(WATCHER, &format!("/whales add {} $1,000", WHALE)).await;
+    harness.telegram
+        .wait_for_text(WATCHER, "Watching", Duration::from_secs(10))
+        .await
+        .expect("bot should confirm the new whale");
+    harness.telegram.inject_message(WATCHER, "/whales list").await;
+    let listing = harness.telegram
+        .wait_for_text(WATCHER, "Whales you watch", Duration::from_secs(10))
+        .await
+        .expect("bot should list the whale");
+    assert!(listing.contains(WHALE));
+    assert!(listing.contains("$1000"));
+
+    whales.add(HIGH_ROLLER, WHALE, Some(10_000.into())).await.unwrap();
+
+    // Under the lowest threshold: nothing is published
+    let mut small = bonk_transfer(&bonk, "small");
+    small.amount = 10_000_000.0;
+    assert!(whales.ingest(small).await.unwrap().is_none());
+
+    let event = whales.ingest(bonk_transfer(&bonk, "large")).await.unwrap()
+        .expect("$5,000 is over the watcher's $1,000 threshold");
+    assert_eq!(event.metadata["signature"], "large");
+    let report = harness.telegram
+        .wait_for_text(WATCHER, "market event", Duration::from_secs(10))
+        .await
+        .expect("the watcher should hear about the move");
+    assert!(report.contains("250.00M"));
+
+    // $5,000 is under the high roller's $10,000
+    assert!(harness.telegram
+        .wait_for_text(HIGH_ROLLER, "market event", Duration::from_millis(500))
+        .await
+        .is_none());
+
+    harness.telegram.inject_message(WATCHER, &format!("/whales remove {}", WHALE)).await;
+    harness.telegram
+        .wait_for_text(WATCHER, "Stopped watching", Duration::from_secs(10))
+        .await
+        .expect("bot should confirm the removal");
+    assert!(whales.list(WATCHER).await.is_empty());
+    assert_eq!(whales.list(HIGH_ROLLER).await.len(), 1);
+    // Only the high roller is left, and $5,000 doesn't interest them
+    assert!(whales.ingest(bonk_transfer(&bonk, "again")).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn whales_command_rejects_bad_input() {
+    let harness = TestHarness::builder().build().await.unwrap();
+    harness.start_bot();
+
+    harness.telegram.inject_message(WATCHER, "/whales add not-a-wallet").await;
+    harness.telegram
+        .wait_for_text(WATCHER, "Invalid wallet address", Duration::from_secs(10))
+        .await
+        .expect("bot should reject the address");
+
+    harness.telegram.inject_message(WATCHER, &format!("/whales add {} lots", WHALE)).await;
+    harness.telegram
+        .wait_for_text(WATCHER, "isn't a valid USD amount", Duration::from_secs(10))
+        .await
+        .expect("bot should reject the threshold");
+    assert!(harness.services.whales.list(WATCHER).await.is_empty());
+}
@@ -0,0 +1,76 @@
+use crate::alerts::{EventSeverity, EventType, WhaleAction, WhaleActivity, WhaleWatcher};
+use chrono::DateTime;
+use rust_decimal::Decimal;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+/// `getTransaction` (jsonParsed) for a 250M BONK `transferChecked` between two wallets
+const BONK_TRANSFER: &str = include_str!("fixtures/spl_transfer.json");
+/// `getTransaction` (jsonParsed) for a 0.5 SOL → BONK buy through Jupiter
+const JUPITER_BUY: &str = include_str!("fixtures/jupiter_swap_buy.json");
+
+const WHALE: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const RECIPIENT: &str = "5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9";
+const BUYER: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+const TRANSFER_SIGNATURE: &str = "2nBhEBYYvfaAe16UMNqRHre4YNSskvuYgx3M6E4JP1oDYvZEJHvoPzyUidNgNX5r9sTyN1J9UxtbCXy2rqYcuyuv";
+
+fn parse(json: &str) -> EncodedConfirmedTransactionWithStatusMeta {
+    serde_json::from_str(json).expect("fixture is a valid getTransaction result")
+}
+
+#[test]
+fn token_transfer_out_of_a_whale_wallet() {
+    let activity = WhaleActivity::from_transaction(TRANSFER_SIGNATURE, WHALE, &parse(BONK_TRANSFER))
+        .expect("the whale's BONK left its wallet");
+
+    assert_eq!(activity.action, WhaleAction::Transfer);
+    assert_eq!(activity.mint, BONK);
+    assert!((activity.amount - 250_000_000.0).abs() < 1e-6);
+    assert_eq!(activity.counterparty.as_deref(), Some(RECIPIENT));
+    assert_eq!(activity.slot, 287_700_100);
+    assert_eq!(activity.block_time, DateTime::from_timestamp(1_726_003_600, 0));
+}
+
+#[test]
+fn the_receiving_side_is_a_transfer_in_from_the_sender() {
+    // The recipient neither signed nor appears in the account keys
+    let activity = WhaleActivity::from_transaction(TRANSFER_SIGNATURE, RECIPIENT, &parse(BONK_TRANSFER))
+        .expect("BONK arrived in the recipient's wallet");
+
+    assert_eq!(activity.action, WhaleAction::Transfer);
+    assert!((activity.amount - 250_000_000.0).abs() < 1e-6);
+    assert_eq!(activity.counterparty.as_deref(), Some(WHALE));
+}
+
+#[test]
+fn paying_sol_for_a_token_is_a_buy() {
+    let activity = WhaleActivity::from_transaction("buy", BUYER, &parse(JUPITER_BUY)).unwrap();
+
+    assert_eq!(activity.action, WhaleAction::Buy);
+    assert_eq!(activity.mint, BONK);
+    assert!((activity.amount - 17_425_000.0).abs() < 1e-6);
+    assert!(activity.counterparty.is_none());
+}
+
+#[test]
+fn failed_transactions_are_not_activity() {
+    let failed = BONK_TRANSFER.replace("\"err\": null", "\"err\": { \"InstructionError\": [1, \"InsufficientFunds\"] }");
+    assert!(WhaleActivity::from_transaction(TRANSFER_SIGNATURE, WHALE, &parse(&failed)).is_none());
+}
+
+#[test]
+fn whale_events_reach_wallet_and_token_subscribers() {
+    let activity = WhaleActivity::from_transaction(TRANSFER_SIGNATURE, WHALE, &parse(BONK_TRANSFER)).unwrap();
+
+    let event = WhaleWatcher::event(&activity, Decimal::from(1_500_000));
+    assert_eq!(event.severity, EventSeverity::High);
+    assert_eq!(event.subscription_keys(), vec![BONK, WHALE]);
+    assert!(event.details.description.contains("250.00M"));
+    assert!(event.details.description.ends_with("(~$1500000)"));
+    let EventType::Whale(whale) = &event.event_type else { panic!("expected a whale event") };
+    assert_eq!(whale.whale_address, WHALE);
+    assert_eq!(whale.value_usd, Decimal::from(1_500_000));
+
+    assert_eq!(WhaleWatcher::event(&activity, Decimal::from(60_000)).severity, EventSeverity::Low);
+    assert_eq!(WhaleWatcher::event(&activity, Decimal::from(6_000_000)).severity, EventSeverity::Critical);
+}
@@ -10,7 +10,7 @@ use tracing::{info, debug, warn, instrument};
 use std::{collections::HashMap, sync::Arc, time::{Duration, Instant}};
 use tokio::sync::{RwLock, Semaphore};
 
-use super::types::TradeResult;
+use super::types::{ExecutionFees, ExecutionReport, TradeResult, TradeType};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct HeliusResponse {
@@ -220,14 +220,29 @@ impl HeliusClient {
             signature, priority_fee, rebate_earned
         );
         
+        let mut execution = ExecutionReport::default()
+            .filled(0.001, TradeType::Swap)
+            .with_fees(ExecutionFees {
+                priority_fee_lamports: priority_fee,
+                ..ExecutionFees::default()
+            });
+        execution.record_broadcast(false);
+        
         Ok(TradeResult {
             tx_signature: signature,
             tokens_received: 100.0, // This should be calculated from actual swap
             tokens_sold: 0.0,
             sol_received: 0.0,
+            amount_sol: 0.0,
             price: 0.001,
             rebate_earned,
             pnl_percentage: 0.0,
+            timestamp: chrono::Utc::now(),
+            trade_type: TradeType::Swap,
+            execution,
+            quoted_price: None,
+            executed_price: None,
+            realized_slippage_bps: None,
         })
     }
     
@@ -0,0 +1,267 @@
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
+use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::wallet::TransactionPriority;
+use super::priority_fees::{PriorityFeeConfig, PriorityFeeEstimator};
+
+/// Hard cap the runtime accepts per transaction
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Settings for per-transaction compute budgeting
+#[derive(Debug, Clone)]
+pub struct ComputeBudgetConfig {
+    /// Limit = simulated units × margin
+    pub margin: f64,
+    /// Margin for panic sells and order executions, where a failed landing costs more
+    pub urgent_margin: f64,
+    /// Limit used when simulation is unavailable
+    pub fallback_limit: u32,
+    /// Consumed / requested at or above this counts as a near-limit execution
+    pub near_limit_ratio: f64,
+    /// Consecutive near-limit executions before warning that the margin is too thin
+    pub near_limit_alert_after: u64,
+}
+
+impl Default for ComputeBudgetConfig {
+    fn default() -> Self {
+        Self {
+            margin: 1.2,
+            urgent_margin: 1.35,
+            fallback_limit: 400_000,
+            near_limit_ratio: 0.95,
+            near_limit_alert_after: 3,
+        }
+    }
+}
+
+/// How much headroom a transaction gets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetUrgency {
+    Standard,
+    /// Panic sells and order executions
+    Urgent,
+}
+
+impl BudgetUrgency {
+    /// Recent-fee percentile paid: the 75th, or the 90th when urgent
+    pub fn priority(&self) -> TransactionPriority {
+        match self {
+            BudgetUrgency::Standard => TransactionPriority::High,
+            BudgetUrgency::Urgent => TransactionPriority::Critical,
+        }
+    }
+}
+
+/// Compute unit limit and price chosen for one transaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComputeBudget {
+    pub unit_limit: u32,
+    pub unit_price_micro_lamports: u64,
+    /// Units the simulation consumed; `None` when the fallback limit was used
+    pub simulated_units: Option<u64>,
+}
+
+impl ComputeBudget {
+    /// Limit from measured units, or the conservative fallback without a measurement
+    pub fn from_simulation(
+        simulated_units: Option<u64>,
+        unit_price_micro_lamports: u64,
+        urgency: BudgetUrgency,
+        config: &ComputeBudgetConfig,
+    ) -> Self {
+        let margin = match urgency {
+            BudgetUrgency::Standard => config.margin,
+            BudgetUrgency::Urgent => config.urgent_margin,
+        };
+        // Integer basis points so 1.35 × 180_000 is exactly 243_000, not one unit more
+        let margin_bps = (margin.max(1.0) * 10_000.0).round() as u64;
+        let unit_limit = match simulated_units.filter(|units| *units > 0) {
+            Some(units) => ((units * margin_bps).div_ceil(10_000)).min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32,
+            None => config.fallback_limit,
+        };
+
+        Self { unit_limit, unit_price_micro_lamports, simulated_units }
+    }
+
+    /// Priority fee this budget pays at full limit
+    pub fn priority_fee_lamports(&self) -> u64 {
+        (self.unit_limit as u128 * self.unit_price_micro_lamports as u128 / 1_000_000) as u64
+    }
+
+    /// Prepend the budget instructions, replacing any already present
+    ///
+    /// Idempotent: re-applying on a broadcast retry leaves exactly one limit
+    /// and one price instruction at the front.
+    pub fn apply(&self, instructions: Vec<Instruction>) -> Vec<Instruction> {
+        let mut budgeted = Vec::with_capacity(instructions.len() + 2);
+        budgeted.push(ComputeBudgetInstruction::set_compute_unit_limit(self.unit_limit));
+        budgeted.push(ComputeBudgetInstruction::set_compute_unit_price(self.unit_price_micro_lamports));
+        budgeted.extend(instructions.into_iter().filter(|ix| ix.program_id != compute_budget::id()));
+        budgeted
+    }
+
+    /// Rebuild an unsigned legacy transaction with this budget
+    pub fn apply_to_transaction(&self, transaction: &Transaction) -> Transaction {
+        let message = &transaction.message;
+        let payer = message.account_keys.first().copied();
+        let instructions = self.apply(decompile(message));
+
+        let mut rebuilt = Message::new(&instructions, payer.as_ref());
+        rebuilt.recent_blockhash = message.recent_blockhash;
+        Transaction::new_unsigned(rebuilt)
+    }
+}
+
+pub(super) fn decompile(message: &Message) -> Vec<Instruction> {
+    message.instructions.iter()
+        .filter_map(|ix| {
+            let program_id = *message.account_keys.get(ix.program_id_index as usize)?;
+            let accounts = ix.accounts.iter()
+                .filter_map(|&index| {
+                    let index = index as usize;
+                    message.account_keys.get(index).map(|pubkey| AccountMeta {
+                        pubkey: *pubkey,
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_writable(index),
+                    })
+                })
+                .collect();
+            Some(Instruction { program_id, accounts, data: ix.data.clone() })
+        })
+        .collect()
+}
+
+/// Counters for tuning the margin
+#[derive(Debug, Default)]
+struct ComputeUsageStats {
+    executions: AtomicU64,
+    near_limit: AtomicU64,
+    consecutive_near_limit: AtomicU64,
+}
+
+/// Simulates, sizes and prices the compute budget of outgoing transactions
+#[derive(Clone)]
+pub struct ComputeBudgeter {
+    rpc_client: Arc<RpcClient>,
+    estimator: Arc<PriorityFeeEstimator>,
+    config: ComputeBudgetConfig,
+    usage: Arc<ComputeUsageStats>,
+}
+
+impl ComputeBudgeter {
+    pub fn new(rpc_client: Arc<RpcClient>, config: ComputeBudgetConfig) -> Self {
+        Self {
+            estimator: Arc::new(PriorityFeeEstimator::new(rpc_client.clone(), PriorityFeeConfig::default())),
+            rpc_client,
+            config,
+            usage: Arc::new(ComputeUsageStats::default()),
+        }
+    }
+
+    /// Price from a shared estimator instead of a private one
+    pub fn with_fee_estimator(mut self, estimator: Arc<PriorityFeeEstimator>) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    pub fn config(&self) -> &ComputeBudgetConfig {
+        &self.config
+    }
+
+    /// Measure the transaction and return it with a sized budget prepended
+    ///
+    /// Simulates with the maximum limit so the measurement itself can't run
+    /// out of units; falls back to the static limit if simulation fails.
+    pub async fn budget_transaction(&self, transaction: &Transaction, urgency: BudgetUrgency) -> (Transaction, ComputeBudget) {
+        self.budget_transaction_at(transaction, urgency, urgency.priority()).await
+    }
+
+    /// `budget_transaction` priced at a chosen fee level instead of the urgency's
+    pub async fn budget_transaction_at(
+        &self,
+        transaction: &Transaction,
+        urgency: BudgetUrgency,
+        priority: TransactionPriority,
+    ) -> (Transaction, ComputeBudget) {
+        let message = &transaction.message;
+        let writable: Vec<Pubkey> = message.account_keys.iter()
+            .enumerate()
+            .filter(|(index, _)| message.is_writable(*index))
+            .map(|(_, key)| *key)
+            .collect();
+        let price = self.estimator.estimate_for(&writable, priority).await;
+
+        let probe = ComputeBudget {
+            unit_limit: MAX_COMPUTE_UNIT_LIMIT,
+            unit_price_micro_lamports: price,
+            simulated_units: None,
+        }.apply_to_transaction(transaction);
+
+        let simulation_config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let simulated_units = match self.rpc_client.simulate_transaction_with_config(&probe, simulation_config).await {
+            Ok(response) if response.value.err.is_none() => response.value.units_consumed,
+            Ok(response) => {
+                debug!("Simulation failed, using fallback compute limit: {:?}", response.value.err);
+                None
+            }
+            Err(e) => {
+                debug!("Simulation unavailable, using fallback compute limit: {}", e);
+                None
+            }
+        };
+
+        let budget = ComputeBudget::from_simulation(simulated_units, price, urgency, &self.config);
+        debug!(
+            "Compute budget: {} units at {} µlamports (simulated {:?})",
+            budget.unit_limit, budget.unit_price_micro_lamports, budget.simulated_units
+        );
+        (budget.apply_to_transaction(transaction), budget)
+    }
+
+    /// Record units consumed against the requested limit
+    ///
+    /// Called at build time with the simulated units, where a near-limit result
+    /// means the margin was clipped by the runtime cap, and again by anything
+    /// that later observes on-chain consumption. Returns whether the execution
+    /// was near its limit.
+    pub fn record_usage(&self, requested: u32, consumed: u64) -> bool {
+        self.usage.executions.fetch_add(1, Ordering::Relaxed);
+        let near_limit = requested > 0 && consumed as f64 / requested as f64 >= self.config.near_limit_ratio;
+
+        if near_limit {
+            self.usage.near_limit.fetch_add(1, Ordering::Relaxed);
+            let streak = self.usage.consecutive_near_limit.fetch_add(1, Ordering::Relaxed) + 1;
+            if streak >= self.config.near_limit_alert_after {
+                warn!(
+                    "{} consecutive executions used over {:.0}% of their compute limit; consider raising the margin",
+                    streak,
+                    self.config.near_limit_ratio * 100.0
+                );
+            }
+        } else {
+            self.usage.consecutive_near_limit.store(0, Ordering::Relaxed);
+        }
+        near_limit
+    }
+
+    /// (executions, near-limit executions)
+    pub fn usage(&self) -> (u64, u64) {
+        (
+            self.usage.executions.load(Ordering::Relaxed),
+            self.usage.near_limit.load(Ordering::Relaxed),
+        )
+    }
+}
@@ -0,0 +1,327 @@
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use solana_client::{
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    rpc_config::{RpcSignatureSubscribeConfig, RpcTransactionConfig},
+    rpc_response::RpcSignatureResult,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus, UiTransactionEncoding};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, info, warn};
+
+use crate::errors::{BotError, Result};
+use super::orders::OrderManager;
+
+/// Stages remembered for `stage` lookups before the map is reset
+const MAX_REMEMBERED: usize = 10_000;
+
+/// Settings for following sent transactions to a final status
+#[derive(Debug, Clone)]
+pub struct ConfirmationConfig {
+    /// Websocket endpoint for `signatureSubscribe`; polling only when unset
+    pub ws_url: Option<String>,
+    pub poll_interval: Duration,
+    /// Stop following a transaction after this long, whatever its stage
+    pub timeout: Duration,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: None,
+            poll_interval: Duration::from_secs(2),
+            timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Where a sent transaction stands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConfirmationStage {
+    /// Sent but not yet in a confirmed block
+    Processing,
+    Confirmed { slot: u64, fee_lamports: Option<u64> },
+    Finalized { slot: u64, fee_lamports: Option<u64> },
+    /// Landed, but the transaction returned an error
+    Failed { slot: u64, error: String },
+    /// Its blockhash lapsed before it landed, so it never will
+    Expired,
+}
+
+impl ConfirmationStage {
+    /// No later stage can follow
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Finalized { .. } | Self::Failed { .. } | Self::Expired)
+    }
+
+    /// Why the trade didn't go through, for failed and expired transactions
+    pub fn failure(&self) -> Option<String> {
+        match self {
+            Self::Failed { error, .. } => Some(format!("Failed on chain: {}", error)),
+            Self::Expired => Some("Expired before landing: the blockhash lapsed".to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn fee_lamports(&self) -> Option<u64> {
+        match self {
+            Self::Confirmed { fee_lamports, .. } | Self::Finalized { fee_lamports, .. } => *fee_lamports,
+            _ => None,
+        }
+    }
+
+    /// Stages only move forward
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Processing => 0,
+            Self::Confirmed { .. } => 1,
+            Self::Finalized { .. } | Self::Failed { .. } | Self::Expired => 2,
+        }
+    }
+
+    fn from_status(status: &TransactionStatus) -> Self {
+        if let Some(err) = &status.err {
+            return Self::Failed { slot: status.slot, error: err.to_string() };
+        }
+        match status.confirmation_status {
+            Some(TransactionConfirmationStatus::Finalized) => Self::Finalized { slot: status.slot, fee_lamports: None },
+            Some(TransactionConfirmationStatus::Confirmed) => Self::Confirmed { slot: status.slot, fee_lamports: None },
+            Some(TransactionConfirmationStatus::Processed) => Self::Processing,
+            // Nodes without confirmation status report rooted transactions with no confirmation count
+            None if status.confirmations.is_none() => Self::Finalized { slot: status.slot, fee_lamports: None },
+            None => Self::Confirmed { slot: status.slot, fee_lamports: None },
+        }
+    }
+}
+
+/// Follows sent transactions from processing to finalized, failed or expired
+///
+/// Updates come from `signatureSubscribe` when a websocket endpoint is set,
+/// with `getSignatureStatuses` polling alongside it and in its place otherwise.
+#[derive(Clone)]
+pub struct ConfirmationTracker {
+    config: ConfirmationConfig,
+    rpc_client: Arc<RpcClient>,
+    stages: Arc<RwLock<HashMap<String, ConfirmationStage>>>,
+    orders: Option<Arc<OrderManager>>,
+}
+
+impl ConfirmationTracker {
+    pub fn new(rpc_client: Arc<RpcClient>, config: ConfirmationConfig) -> Self {
+        Self {
+            config,
+            rpc_client,
+            stages: Arc::new(RwLock::new(HashMap::new())),
+            orders: None,
+        }
+    }
+
+    /// Write final stages onto the order executions that sent the transactions
+    pub fn with_orders(mut self, orders: Arc<OrderManager>) -> Self {
+        self.orders = Some(orders);
+        self
+    }
+
+    /// Latest stage seen for a tracked signature
+    pub async fn stage(&self, signature: &str) -> Option<ConfirmationStage> {
+        self.stages.read().await.get(signature).cloned()
+    }
+
+    /// Follow `signature` in the background, receiving each new stage until a final one
+    ///
+    /// Without `last_valid_block_height` the current blockhash's is used; it's never
+    /// older than the transaction's, so expiry is only ever reported late, not early.
+    pub fn track(&self, signature: &str, last_valid_block_height: Option<u64>) -> Result<mpsc::Receiver<ConfirmationStage>> {
+        let parsed = Signature::from_str(signature)
+            .map_err(|_| BotError::validation(format!("Invalid transaction signature: {}", signature)))?;
+        let (updates, receiver) = mpsc::channel(8);
+
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            tracker.follow(parsed, last_valid_block_height, updates).await;
+        });
+        Ok(receiver)
+    }
+
+    async fn follow(&self, signature: Signature, last_valid_block_height: Option<u64>, updates: mpsc::Sender<ConfirmationStage>) {
+        let deadline = tokio::time::Instant::now() + self.config.timeout;
+        let last_valid = match last_valid_block_height {
+            Some(height) => Some(height),
+            None => match self.rpc_client.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed()).await {
+                Ok((_, height)) => Some(height),
+                Err(e) => {
+                    warn!("📡 No block height limit for {}, expiry won't be detected: {}", signature, e);
+                    None
+                }
+            },
+        };
+
+        let (pushed, mut subscription) = mpsc::channel(4);
+        self.spawn_signature_subscription(signature, pushed);
+
+        let mut current = ConfirmationStage::Processing;
+        self.publish(&signature, &current, &updates).await;
+
+        while !current.is_final() {
+            if tokio::time::Instant::now() >= deadline {
+                warn!("📡 Stopped following {} after {:?} at {:?}", signature, self.config.timeout, current);
+                break;
+            }
+            let next = tokio::select! {
+                Some(stage) = subscription.recv() => Some(stage),
+                _ = tokio::time::sleep(self.config.poll_interval) => self.poll(&signature, last_valid).await,
+            };
+            let Some(next) = next.filter(|stage| stage.rank() > current.rank()) else { continue };
+
+            current = self.with_fee(&signature, next, &current).await;
+            self.publish(&signature, &current, &updates).await;
+        }
+
+        if current.is_final() {
+            info!("📡 Transaction {} ended {:?}", signature, current);
+            self.record_final(&signature, &current).await;
+        }
+    }
+
+    /// Stage from `getSignatureStatuses`, or `Expired` once the block height passes the limit unseen
+    async fn poll(&self, signature: &Signature, last_valid: Option<u64>) -> Option<ConfirmationStage> {
+        match self.status(signature).await {
+            Ok(Some(stage)) => return Some(stage),
+            Ok(None) => {}
+            Err(e) => {
+                debug!("📡 Signature status for {} unavailable: {}", signature, e);
+                return None;
+            }
+        }
+
+        let last_valid = last_valid?;
+        match self.rpc_client.get_block_height().await {
+            Ok(height) if height > last_valid => {
+                // It may have landed between the status check and the block height
+                match self.status(signature).await {
+                    Ok(None) => Some(ConfirmationStage::Expired),
+                    Ok(stage) => stage,
+                    Err(_) => None,
+                }
+            }
+            Ok(_) => None,
+            Err(e) => {
+                debug!("📡 Block height unavailable while following {}: {}", signature, e);
+                None
+            }
+        }
+    }
+
+    async fn status(&self, signature: &Signature) -> Result<Option<ConfirmationStage>> {
+        let response = self.rpc_client.get_signature_statuses(&[*signature]).await?;
+        Ok(response.value.into_iter().next().flatten().map(|status| ConfirmationStage::from_status(&status)))
+    }
+
+    /// Fill in the fee paid for a landed stage, reusing the one already read
+    async fn with_fee(&self, signature: &Signature, stage: ConfirmationStage, previous: &ConfirmationStage) -> ConfirmationStage {
+        let known = previous.fee_lamports();
+        match stage {
+            ConfirmationStage::Confirmed { slot, fee_lamports: None } => ConfirmationStage::Confirmed {
+                slot,
+                fee_lamports: match known {
+                    Some(fee) => Some(fee),
+                    None => self.fee_paid(signature).await,
+                },
+            },
+            ConfirmationStage::Finalized { slot, fee_lamports: None } => ConfirmationStage::Finalized {
+                slot,
+                fee_lamports: match known {
+                    Some(fee) => Some(fee),
+                    None => self.fee_paid(signature).await,
+                },
+            },
+            other => other,
+        }
+    }
+
+    async fn fee_paid(&self, signature: &Signature) -> Option<u64> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        match self.rpc_client.get_transaction_with_config(signature, config).await {
+            Ok(transaction) => transaction.transaction.meta.map(|meta| meta.fee),
+            Err(e) => {
+                debug!("📡 Fee of {} unavailable: {}", signature, e);
+                None
+            }
+        }
+    }
+
+    async fn publish(&self, signature: &Signature, stage: &ConfirmationStage, updates: &mpsc::Sender<ConfirmationStage>) {
+        {
+            let mut stages = self.stages.write().await;
+            if stages.len() >= MAX_REMEMBERED {
+                stages.clear();
+            }
+            stages.insert(signature.to_string(), stage.clone());
+        }
+        // The receiver going away only stops the updates, not the tracking
+        let _ = updates.send(stage.clone()).await;
+    }
+
+    async fn record_final(&self, signature: &Signature, stage: &ConfirmationStage) {
+        let Some(orders) = &self.orders else { return };
+        if let Err(e) = orders.record_confirmation(&signature.to_string(), stage).await {
+            warn!("📡 Failed to record the final status of {}: {}", signature, e);
+        }
+    }
+
+    /// Push confirmed, then finalized, notifications from `signatureSubscribe`
+    fn spawn_signature_subscription(&self, signature: Signature, pushed: mpsc::Sender<ConfirmationStage>) {
+        let Some(ws_url) = self.config.ws_url.clone() else { return };
+
+        tokio::spawn(async move {
+            let client = match PubsubClient::new(&ws_url).await {
+                Ok(client) => client,
+                Err(e) => {
+                    warn!("📡 Signature subscription unavailable for {}, polling only: {}", signature, e);
+                    return;
+                }
+            };
+
+            // Each subscription notifies once, at its commitment
+            for commitment in [CommitmentConfig::confirmed(), CommitmentConfig::finalized()] {
+                let config = RpcSignatureSubscribeConfig {
+                    commitment: Some(commitment),
+                    enable_received_notification: Some(false),
+                };
+                let (mut stream, _unsubscribe) = match client.signature_subscribe(&signature, Some(config)).await {
+                    Ok(subscription) => subscription,
+                    Err(e) => {
+                        warn!("📡 signatureSubscribe for {} failed, polling only: {}", signature, e);
+                        return;
+                    }
+                };
+
+                let Some(response) = stream.next().await else { return };
+                let slot = response.context.slot;
+                let stage = match response.value {
+                    RpcSignatureResult::ProcessedSignature(result) => match result.err {
+                        Some(err) => ConfirmationStage::Failed { slot, error: err.to_string() },
+                        None if commitment.is_finalized() => ConfirmationStage::Finalized { slot, fee_lamports: None },
+                        None => ConfirmationStage::Confirmed { slot, fee_lamports: None },
+                    },
+                    RpcSignatureResult::ReceivedSignature(_) => continue,
+                };
+                let landed_with_error = matches!(stage, ConfirmationStage::Failed { .. });
+                if pushed.send(stage).await.is_err() || landed_with_error {
+                    return;
+                }
+            }
+
+            debug!("📡 Signature subscription for {} ended", signature);
+        });
+    }
+}
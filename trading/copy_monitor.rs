@@ -1,66 +1,106 @@
 use anyhow::Result;
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+    UiInstruction, UiMessage, UiParsedInstruction,
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
 use tokio::time::{interval, Duration as TokioDuration};
-use tracing::{info, warn, error};
+use tracing::{debug, error, info, warn};
 
 use super::copy_trading::{CopyTradingManager, CopyTradeType};
-use crate::db::Database;
-use crate::trading::TradingEngineHandle;
-use crate::wallet::WalletManager;
+use super::exit_routing::WSOL_MINT;
+use crate::wallet::{SignatureWatcher, WalletTransaction};
+
+pub const JUPITER_V6_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+// Anchor discriminators of the v6 route instructions
+const ROUTE: [u8; 8] = [229, 23, 203, 151, 122, 227, 173, 42];
+const EXACT_OUT_ROUTE: [u8; 8] = [208, 51, 239, 151, 123, 43, 237, 92];
+const SHARED_ACCOUNTS_ROUTE: [u8; 8] = [193, 32, 155, 51, 65, 214, 156, 129];
+const SHARED_ACCOUNTS_EXACT_OUT_ROUTE: [u8; 8] = [176, 209, 105, 168, 154, 125, 69, 62];
+
+/// Every route variant ends with amount (u64), quoted amount (u64), slippage_bps (u16), platform_fee_bps (u8)
+const ROUTE_ARGS_TAIL: usize = 19;
 
 /// Background service that monitors copy trading activities
 pub struct CopyTradingMonitor {
     copy_manager: Arc<CopyTradingManager>,
+    trade_monitor: Option<Arc<BlockchainTradeMonitor>>,
     monitoring_interval: TokioDuration,
     position_check_interval: TokioDuration,
 }
 
 impl CopyTradingMonitor {
-    pub fn new(
-        db: Arc<Database>,
-        trading_engine: TradingEngineHandle,
-        wallet_manager: Arc<WalletManager>,
-    ) -> Self {
-        let copy_manager = Arc::new(CopyTradingManager::new(
-            db,
-            trading_engine,
-            wallet_manager,
-        ));
-        
+    /// Monitor the follows made through `copy_manager`, the one the bot's commands use
+    pub fn new(copy_manager: Arc<CopyTradingManager>) -> Self {
         Self {
             copy_manager,
-            monitoring_interval: TokioDuration::from_secs(30), // Check every 30 seconds
+            trade_monitor: None,
+            monitoring_interval: TokioDuration::from_secs(30), // Refresh followed masters every 30 seconds
             position_check_interval: TokioDuration::from_secs(60), // Check positions every minute
         }
     }
-    
+
+    /// Detect master trades on-chain with `monitor`
+    pub fn with_trade_monitor(mut self, monitor: Arc<BlockchainTradeMonitor>) -> Self {
+        self.trade_monitor = Some(monitor);
+        self
+    }
+
+    /// Master wallets the chain subscriptions should cover
+    pub async fn followed_masters(&self) -> Vec<String> {
+        self.copy_manager.followed_master_wallets().await
+    }
+
     /// Start the monitoring service
     pub async fn start(self: Arc<Self>) {
         info!("Starting copy trading monitor service");
-        
+
         // Spawn position monitoring task
         let monitor_clone = self.clone();
         tokio::spawn(async move {
             monitor_clone.monitor_positions_loop().await;
         });
-        
-        // Spawn master trade monitoring task
-        let monitor_clone = self.clone();
-        tokio::spawn(async move {
-            monitor_clone.monitor_master_trades_loop().await;
-        });
-        
+
+        match &self.trade_monitor {
+            Some(trade_monitor) => {
+                trade_monitor.start().await;
+
+                // Spawn master wallet refresh task
+                let monitor_clone = self.clone();
+                tokio::spawn(async move {
+                    monitor_clone.refresh_masters_loop().await;
+                });
+
+                // Spawn master trade copying task
+                let monitor_clone = self.clone();
+                let events = trade_monitor.subscribe_events();
+                tokio::spawn(async move {
+                    monitor_clone.copy_master_trades_loop(events).await;
+                });
+            }
+            None => warn!("No blockchain trade monitor configured, master trades will not be copied"),
+        }
+
         info!("Copy trading monitor service started");
     }
-    
+
     /// Continuously monitor positions for stop loss and take profit
     async fn monitor_positions_loop(&self) {
         let mut interval = interval(self.position_check_interval);
-        
+
         loop {
             interval.tick().await;
-            
+
             match self.copy_manager.monitor_positions().await {
                 Ok(_) => {
                     // Successfully checked positions
@@ -71,117 +111,508 @@ impl CopyTradingMonitor {
             }
         }
     }
-    
-    /// Monitor master traders for new trades to copy
-    async fn monitor_master_trades_loop(&self) {
+
+    /// Keep the chain subscriptions in line with the masters that have followers
+    async fn refresh_masters_loop(&self) {
+        let Some(trade_monitor) = &self.trade_monitor else { return };
         let mut interval = interval(self.monitoring_interval);
-        let mut last_check = Utc::now();
-        
+
         loop {
             interval.tick().await;
-            
-            // In production, this would:
-            // 1. Query blockchain for master trader transactions
-            // 2. Parse swap transactions
-            // 3. Execute copy trades for followers
-            
-            let now = Utc::now();
-            
-            // Simulate detecting a master trade (for demo purposes)
-            if now.signed_duration_since(last_check) > Duration::minutes(5) {
-                // Simulate a master trade detection
-                self.simulate_master_trade().await;
-                last_check = now;
-            }
-        }
-    }
-    
-    /// Simulate a master trade for demonstration
-    async fn simulate_master_trade(&self) {
-        // In production, this would be triggered by actual blockchain events
-        
-        let master_trades = vec![
-            (1001, "BONK", "BonkAddr123", CopyTradeType::Buy, 10.0, 0.000012),
-            (1002, "WIF", "WifAddr456", CopyTradeType::Sell, 5.0, 2.45),
-        ];
-        
-        for (master_id, symbol, address, trade_type, amount, price) in master_trades {
-            info!(
-                "Detected master trade: {} {:?} {} for {} SOL",
-                master_id, trade_type, symbol, amount
-            );
-            
-            match self.copy_manager.execute_copy_trade(
-                master_id,
-                address,
-                symbol,
-                trade_type,
-                amount,
-                price,
-            ).await {
-                Ok(executions) => {
-                    let successful = executions.iter()
-                        .filter(|e| matches!(e.status, crate::trading::CopyTradeStatus::Success))
-                        .count();
-                    
-                    info!(
-                        "Executed {} copy trades ({} successful)",
-                        executions.len(),
-                        successful
-                    );
-                }
-                Err(e) => {
-                    error!("Failed to execute copy trades: {}", e);
+
+            let wallets = self.followed_masters().await;
+            if let Err(e) = trade_monitor.subscribe_to_masters(wallets).await {
+                error!("Failed to refresh master wallet subscriptions: {}", e);
+            }
+        }
+    }
+
+    /// Copy every detected master trade to that master's followers
+    async fn copy_master_trades_loop(&self, mut events: broadcast::Receiver<MasterTradeEvent>) {
+        loop {
+            match events.recv().await {
+                Ok(event) => self.copy_master_trade(&event).await,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Copy trading fell behind, {} master trades were not copied", skipped);
                 }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    async fn copy_master_trade(&self, event: &MasterTradeEvent) {
+        info!(
+            "Detected master trade: {} {:?} {} for {} SOL ({})",
+            event.master_wallet, event.direction, event.token_mint, event.sol_amount, event.signature
+        );
+
+        match self.copy_manager.handle_master_trade(event).await {
+            Ok(executions) => {
+                let successful = executions.iter()
+                    .filter(|e| matches!(e.status, crate::trading::CopyTradeStatus::Success))
+                    .count();
+
+                info!(
+                    "Executed {} copy trades ({} successful)",
+                    executions.len(),
+                    successful
+                );
+            }
+            Err(e) => {
+                error!("Failed to execute copy trades: {}", e);
             }
         }
     }
-    
+
     /// Get copy manager for external access
     pub fn get_copy_manager(&self) -> Arc<CopyTradingManager> {
         self.copy_manager.clone()
     }
 }
 
-/// Integration with blockchain monitoring (production implementation)
+/// Settings for on-chain master trade detection
+#[derive(Debug, Clone)]
+pub struct MasterTradeWatchConfig {
+    /// Websocket endpoint for `logsSubscribe`; polling only when unset
+    pub ws_url: Option<String>,
+    /// How often wallets without a live subscription are polled
+    pub poll_interval: Duration,
+    pub signatures_per_poll: usize,
+    /// How long a signature is remembered, so repeated notifications copy once
+    pub dedupe_window: Duration,
+    /// Longest wait between websocket reconnect attempts
+    pub max_reconnect_backoff: Duration,
+}
+
+impl Default for MasterTradeWatchConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: None,
+            poll_interval: Duration::seconds(10),
+            signatures_per_poll: 20,
+            dedupe_window: Duration::minutes(30),
+            max_reconnect_backoff: Duration::seconds(60),
+        }
+    }
+}
+
+/// A swap made by a master wallet, reduced to what followers copy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MasterTradeEvent {
+    pub signature: String,
+    pub master_wallet: String,
+    pub token_mint: String,
+    /// `Buy` when SOL went in, `Sell` when SOL came out
+    pub direction: CopyTradeType,
+    /// SOL spent or received, network fee excluded
+    pub sol_amount: f64,
+    pub token_amount: f64,
+    /// SOL per token
+    pub price: f64,
+    pub slot: u64,
+    pub block_time: Option<DateTime<Utc>>,
+}
+
+/// Arguments and mints of a decoded Jupiter v6 route instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JupiterSwap {
+    /// Only the exact-out and shared-accounts variants carry the input mint
+    pub source_mint: Option<String>,
+    pub destination_mint: String,
+    /// Exact for exact-in routes, the quoted maximum for exact-out routes
+    pub in_amount: u64,
+    /// Exact for exact-out routes, the quoted amount for exact-in routes
+    pub out_amount: u64,
+    pub exact_out: bool,
+}
+
+impl JupiterSwap {
+    /// Decode a route instruction from its data and account list
+    pub fn decode(data: &[u8], accounts: &[String]) -> Option<Self> {
+        let discriminator: [u8; 8] = data.get(..8)?.try_into().ok()?;
+        let (source_index, destination_index, exact_out) = match discriminator {
+            ROUTE => (None, 5, false),
+            EXACT_OUT_ROUTE => (Some(5), 6, true),
+            SHARED_ACCOUNTS_ROUTE => (Some(7), 8, false),
+            SHARED_ACCOUNTS_EXACT_OUT_ROUTE => (Some(7), 8, true),
+            _ => return None,
+        };
+
+        if data.len() < 8 + ROUTE_ARGS_TAIL {
+            return None;
+        }
+        let tail = &data[data.len() - ROUTE_ARGS_TAIL..];
+        let amount = u64::from_le_bytes(tail[0..8].try_into().ok()?);
+        let quoted_amount = u64::from_le_bytes(tail[8..16].try_into().ok()?);
+        let (in_amount, out_amount) = if exact_out { (quoted_amount, amount) } else { (amount, quoted_amount) };
+
+        Some(Self {
+            source_mint: match source_index {
+                Some(index) => Some(accounts.get(index)?.clone()),
+                None => None,
+            },
+            destination_mint: accounts.get(destination_index)?.clone(),
+            in_amount,
+            out_amount,
+            exact_out,
+        })
+    }
+
+    /// First Jupiter route in the transaction, outer instructions before inner ones
+    fn find(encoded: &EncodedConfirmedTransactionWithStatusMeta) -> Option<Self> {
+        let meta = encoded.transaction.meta.as_ref()?;
+        let EncodedTransaction::Json(ui_transaction) = &encoded.transaction.transaction else { return None };
+        let UiMessage::Parsed(message) = &ui_transaction.message else { return None };
+        let keys: Vec<&str> = message.account_keys.iter().map(|k| k.pubkey.as_str()).collect();
+
+        let inner: Vec<&UiInstruction> = match &meta.inner_instructions {
+            OptionSerializer::Some(inner) => inner.iter().flat_map(|set| set.instructions.iter()).collect(),
+            _ => Vec::new(),
+        };
+        message.instructions.iter()
+            .chain(inner)
+            .find_map(|instruction| Self::from_instruction(instruction, &keys))
+    }
+
+    fn from_instruction(instruction: &UiInstruction, keys: &[&str]) -> Option<Self> {
+        let (program_id, accounts, data) = match instruction {
+            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) => {
+                (decoded.program_id.clone(), decoded.accounts.clone(), &decoded.data)
+            }
+            UiInstruction::Compiled(compiled) => (
+                keys.get(compiled.program_id_index as usize)?.to_string(),
+                compiled.accounts.iter()
+                    .filter_map(|index| keys.get(*index as usize).map(|k| k.to_string()))
+                    .collect(),
+                &compiled.data,
+            ),
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => return None,
+        };
+        if program_id != JUPITER_V6_PROGRAM_ID {
+            return None;
+        }
+        let data = bs58::decode(data).into_vec().ok()?;
+        Self::decode(&data, &accounts)
+    }
+}
+
+impl MasterTradeEvent {
+    /// Read a master's SOL/token swap out of a `jsonParsed` transaction
+    ///
+    /// Mints and direction come from the Jupiter instruction; amounts use the
+    /// exact side of the route where there is one and the wallet's balance
+    /// changes otherwise. Token-to-token swaps and failed transactions yield None.
+    pub fn from_transaction(
+        signature: &str,
+        master_wallet: &str,
+        encoded: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Option<Self> {
+        let meta = encoded.transaction.meta.as_ref()?;
+        if meta.err.is_some() {
+            return None;
+        }
+        let swap = JupiterSwap::find(encoded)?;
+        let changes = WalletTransaction::from_encoded(signature, master_wallet, encoded)?;
+        if !changes.wallet_signed {
+            return None;
+        }
+
+        // Wrapped SOL counts as SOL; the fee payer's fee is not part of the trade
+        let wsol_change: f64 = changes.token_changes.iter()
+            .filter(|c| c.mint == WSOL_MINT)
+            .map(|c| c.change)
+            .sum();
+        let EncodedTransaction::Json(ui_transaction) = &encoded.transaction.transaction else { return None };
+        let fee_payer = match &ui_transaction.message {
+            UiMessage::Parsed(message) => message.account_keys.first().map(|k| k.pubkey.as_str()),
+            UiMessage::Raw(message) => message.account_keys.first().map(|k| k.as_str()),
+        };
+        let fee = if fee_payer == Some(master_wallet) { meta.fee as f64 / LAMPORTS_PER_SOL } else { 0.0 };
+        let sol_change = changes.sol_change + wsol_change + fee;
+
+        let sold_token = || changes.token_changes.iter()
+            .filter(|c| c.mint != WSOL_MINT && c.change < 0.0)
+            .min_by(|a, b| a.change.total_cmp(&b.change))
+            .map(|c| c.mint.clone());
+
+        let (direction, token_mint) = if swap.destination_mint == WSOL_MINT {
+            (CopyTradeType::Sell, swap.source_mint.clone().or_else(sold_token)?)
+        } else if swap.source_mint.as_deref() == Some(WSOL_MINT) || (swap.source_mint.is_none() && sol_change < 0.0) {
+            (CopyTradeType::Buy, swap.destination_mint.clone())
+        } else {
+            return None;
+        };
+
+        let token_amount = changes.token_changes.iter()
+            .find(|c| c.mint == token_mint)
+            .map(|c| c.change.abs())
+            .filter(|amount| *amount > 0.0)?;
+
+        let sol_amount = match direction {
+            CopyTradeType::Buy if !swap.exact_out => swap.in_amount as f64 / LAMPORTS_PER_SOL,
+            CopyTradeType::Sell if swap.exact_out => swap.out_amount as f64 / LAMPORTS_PER_SOL,
+            _ => sol_change.abs(),
+        };
+        if sol_amount <= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            signature: signature.to_string(),
+            master_wallet: master_wallet.to_string(),
+            token_mint,
+            direction,
+            sol_amount,
+            token_amount,
+            price: sol_amount / token_amount,
+            slot: changes.slot,
+            block_time: changes.block_time,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct WatchedMaster {
+    last_signature: Option<String>,
+}
+
+/// Detects master trades from transaction logs on the master wallets
+///
+/// Each wallet gets a `logsSubscribe` stream that reconnects with backoff;
+/// wallets without a live stream fall back to `getSignaturesForAddress`
+/// polling. Both paths share one signature set, so a swap is published once.
+#[derive(Clone)]
 pub struct BlockchainTradeMonitor {
-    websocket_url: String,
-    master_wallets: Vec<String>,
+    config: MasterTradeWatchConfig,
+    signatures: SignatureWatcher,
+    master_wallets: Arc<RwLock<HashMap<String, WatchedMaster>>>,
+    live: Arc<RwLock<HashSet<String>>>,
+    seen: Arc<RwLock<HashMap<String, DateTime<Utc>>>>,
+    event_tx: broadcast::Sender<MasterTradeEvent>,
 }
 
 impl BlockchainTradeMonitor {
-    pub fn new(websocket_url: String) -> Self {
+    pub fn new(rpc_client: Arc<RpcClient>, config: MasterTradeWatchConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(256);
+
         Self {
-            websocket_url,
-            master_wallets: Vec::new(),
-        }
-    }
-    
-    /// Subscribe to master wallet transactions
-    pub async fn subscribe_to_masters(&mut self, wallets: Vec<String>) -> Result<()> {
-        self.master_wallets = wallets;
-        
-        // In production:
-        // 1. Connect to Solana WebSocket
-        // 2. Subscribe to account notifications for master wallets
-        // 3. Parse swap transactions
-        // 4. Trigger copy trades
-        
-        info!("Subscribed to {} master wallets", self.master_wallets.len());
+            signatures: SignatureWatcher::new(rpc_client, config.ws_url.clone(), config.signatures_per_poll),
+            config,
+            master_wallets: Arc::new(RwLock::new(HashMap::new())),
+            live: Arc::new(RwLock::new(HashSet::new())),
+            seen: Arc::new(RwLock::new(HashMap::new())),
+            event_tx,
+        }
+    }
+
+    /// Detected master trades, for the copy trading manager
+    pub fn subscribe_events(&self) -> broadcast::Receiver<MasterTradeEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Watch exactly `wallets`: new ones are subscribed, dropped ones released
+    pub async fn subscribe_to_masters(&self, wallets: Vec<String>) -> Result<()> {
+        let wallets: Vec<String> = wallets.into_iter()
+            .filter(|wallet| match Pubkey::from_str(wallet) {
+                Ok(_) => true,
+                Err(_) => {
+                    warn!("Skipping invalid master wallet {}", wallet);
+                    false
+                }
+            })
+            .collect();
+
+        let mut added = Vec::new();
+        {
+            let mut watched = self.master_wallets.write().await;
+            watched.retain(|wallet, _| wallets.contains(wallet));
+            for wallet in wallets {
+                if !watched.contains_key(&wallet) {
+                    watched.insert(wallet.clone(), WatchedMaster::default());
+                    added.push(wallet);
+                }
+            }
+        }
+
+        for wallet in added {
+            info!("Watching master wallet {}", wallet);
+            // Only trades from now on are copied; skip the existing history
+            if let Err(e) = self.poll_wallet(&wallet, true).await {
+                warn!("Initial history fetch for master {} failed: {}", wallet, e);
+            }
+            if self.config.ws_url.is_some() {
+                self.spawn_log_subscription(wallet);
+            }
+        }
+
         Ok(())
     }
-    
-    /// Parse a transaction to detect trades
-    pub fn parse_trade_from_transaction(
+
+    pub async fn is_watching(&self, wallet: &str) -> bool {
+        self.master_wallets.read().await.contains_key(wallet)
+    }
+
+    /// Whether `wallet` currently has a live log subscription
+    pub async fn is_live(&self, wallet: &str) -> bool {
+        self.live.read().await.contains(wallet)
+    }
+
+    /// Take a signature for processing; false when it was already taken within the dedupe window
+    pub async fn claim(&self, signature: &str, now: DateTime<Utc>) -> bool {
+        let mut seen = self.seen.write().await;
+        match seen.get(signature) {
+            Some(claimed_at) if now - *claimed_at < self.config.dedupe_window => false,
+            _ => {
+                seen.insert(signature.to_string(), now);
+                true
+            }
+        }
+    }
+
+    /// Give a claimed signature back after a failed fetch so the next notification retries it
+    pub async fn release(&self, signature: &str) {
+        self.seen.write().await.remove(signature);
+    }
+
+    /// Parse a fetched transaction and publish the trade, once per signature
+    pub async fn ingest(
         &self,
-        transaction: &[u8],
-    ) -> Option<(String, String, f64, f64, bool)> {
-        // In production, this would:
-        // 1. Decode the transaction
-        // 2. Check if it's a swap (Jupiter, Raydium, etc.)
-        // 3. Extract token addresses, amounts, and prices
-        // 4. Return (token_from, token_to, amount, price, is_buy)
-        
-        None
-    }
-}
\ No newline at end of file
+        master_wallet: &str,
+        signature: &str,
+        encoded: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Option<MasterTradeEvent> {
+        match MasterTradeEvent::from_transaction(signature, master_wallet, encoded) {
+            Some(event) => {
+                let _ = self.event_tx.send(event.clone());
+                Some(event)
+            }
+            None => {
+                debug!("{} from master {} is not a SOL swap", signature, master_wallet);
+                None
+            }
+        }
+    }
+
+    /// Start the polling fallback for wallets without a live subscription
+    pub async fn start(&self) {
+        let monitor = self.clone();
+        let interval_secs = self.config.poll_interval.num_seconds().max(2) as u64;
+
+        tokio::spawn(async move {
+            let mut interval = interval(TokioDuration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+
+                let wallets: Vec<String> = monitor.master_wallets.read().await.keys().cloned().collect();
+                for wallet in wallets {
+                    if monitor.is_live(&wallet).await {
+                        continue;
+                    }
+                    if let Err(e) = monitor.poll_wallet(&wallet, false).await {
+                        error!("Polling master {} failed: {}", wallet, e);
+                    }
+                }
+
+                monitor.prune(Utc::now()).await;
+            }
+        });
+    }
+
+    /// Fetch signatures newer than the last one seen and ingest their transactions
+    async fn poll_wallet(&self, wallet: &str, baseline_only: bool) -> Result<()> {
+        let Some(watched) = self.master_wallets.read().await.get(wallet).cloned() else { return Ok(()) };
+        let signatures = self.signatures.signatures_since(wallet, watched.last_signature.as_deref()).await?;
+
+        if let Some(newest) = signatures.first() {
+            if let Some(entry) = self.master_wallets.write().await.get_mut(wallet) {
+                entry.last_signature = Some(newest.signature.clone());
+            }
+        }
+        if baseline_only {
+            return Ok(());
+        }
+
+        // Oldest first so followers copy in the master's order
+        for status in signatures.iter().rev().filter(|s| s.err.is_none()) {
+            self.fetch_and_ingest(wallet, &status.signature).await;
+        }
+        Ok(())
+    }
+
+    async fn fetch_and_ingest(&self, wallet: &str, signature: &str) {
+        if !self.claim(signature, Utc::now()).await {
+            return;
+        }
+
+        match self.signatures.fetch_transaction(signature).await {
+            Ok(encoded) => {
+                self.ingest(wallet, signature, &encoded).await;
+            }
+            Err(e) => {
+                warn!("Could not fetch master transaction {}: {}", signature, e);
+                self.release(signature).await;
+            }
+        }
+    }
+
+    /// Log subscription for one wallet, reconnecting until the wallet is dropped
+    fn spawn_log_subscription(&self, wallet: String) {
+        if self.config.ws_url.is_none() {
+            return;
+        }
+        let monitor = self.clone();
+        let max_backoff = self.config.max_reconnect_backoff.to_std().unwrap_or(std::time::Duration::from_secs(60));
+
+        tokio::spawn(async move {
+            let mut backoff = std::time::Duration::from_secs(1);
+
+            while monitor.is_watching(&wallet).await {
+                match monitor.follow_logs(&wallet).await {
+                    Ok(()) => {
+                        debug!("Log subscription for master {} ended, reconnecting", wallet);
+                        backoff = std::time::Duration::from_secs(1);
+                    }
+                    Err(e) => {
+                        warn!("Log subscription for master {} unavailable, polling until reconnect: {}", wallet, e);
+                    }
+                }
+                monitor.live.write().await.remove(&wallet);
+
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(max_backoff);
+            }
+
+            debug!("Stopped watching logs for master {}", wallet);
+        });
+    }
+
+    /// Stream log notifications until the connection drops or the wallet is dropped
+    async fn follow_logs(&self, wallet: &str) -> Result<()> {
+        let mut notifications = self.signatures.subscribe_logs(wallet).await?;
+
+        self.live.write().await.insert(wallet.to_string());
+        info!("Log subscription live for master {}", wallet);
+
+        // Anything that landed while disconnected
+        if let Err(e) = self.poll_wallet(wallet, false).await {
+            warn!("Catch-up poll for master {} failed: {}", wallet, e);
+        }
+
+        while let Some(logs) = notifications.recv().await {
+            if !self.is_watching(wallet).await {
+                break;
+            }
+            if logs.err.is_some() || !logs.logs.iter().any(|line| line.contains(JUPITER_V6_PROGRAM_ID)) {
+                continue;
+            }
+            self.fetch_and_ingest(wallet, &logs.signature).await;
+        }
+
+        Ok(())
+    }
+
+    async fn prune(&self, now: DateTime<Utc>) {
+        let window = self.config.dedupe_window;
+        self.seen.write().await.retain(|_, claimed_at| now - *claimed_at < window);
+    }
+}
@@ -1,17 +1,34 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Utc, Duration, NaiveDate, TimeZone};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn, error, debug};
 
 use crate::db::Database;
 use crate::errors::BotError;
-use crate::trading::{TradingEngineHandle, TradeResult};
+use crate::trading::{TradingEngineHandle, TradeResult, ExecutionReport, TokenResolver};
+use super::copy_monitor::MasterTradeEvent;
+use super::master_program::MasterProgram;
+use super::trade_gate::TradeGate;
+use super::maintenance::{TradingPause, PAUSED_FOR_MAINTENANCE};
+use crate::trading::types::TradeType;
+use crate::trading::{ExecutionNotice, ExecutionNotifier, ExecutionSource, Fill, LeaderboardManager, NoticeKind};
 use crate::wallet::WalletManager;
 
+/// Default daily loss limit, in multiples of the max position size
+const DEFAULT_DAILY_LOSS_POSITIONS: f64 = 2.0;
+
+const DEFAULT_MAX_CONCURRENT_POSITIONS: u32 = 5;
+
+/// Smallest balance a follower needs to copy an opted-in master
+const DEFAULT_MIN_COPY_SOL: f64 = 0.5;
+
+/// Relative shortfall still treated as selling the whole position
+const FULL_EXIT_TOLERANCE: f64 = 1e-9;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CopyTradingConfig {
     pub master_wallet: String,
@@ -29,7 +46,16 @@ pub struct CopyTradingConfig {
     pub auto_take_profit: bool,
     pub take_profit_percent: f64,
     pub slippage_tolerance: f64,
+    /// Realized loss (SOL) per UTC day that pauses copying; 0 turns it off
+    #[serde(default)]
+    pub daily_loss_limit_sol: f64,
+    /// Copied positions the follower may hold at once; 0 turns it off
+    #[serde(default)]
+    pub max_concurrent_positions: u32,
     pub enabled: bool,
+    /// Set when the daily loss limit paused copying; it resumes at this time
+    #[serde(default)]
+    pub paused_until: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub performance: CopyPerformance,
@@ -46,6 +72,171 @@ pub struct CopyPerformance {
     pub last_copied_trade: Option<DateTime<Utc>>,
 }
 
+/// Tokens a follower holds from copied buys, with what they cost
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CopiedPosition {
+    pub tokens: f64,
+    pub cost_sol: f64,
+}
+
+/// A follower's realized copy-trading PnL for one UTC day
+///
+/// Open positions carry across days so sells are measured against what the
+/// tokens cost; only the PnL tally resets at midnight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowerDailyRisk {
+    pub day: NaiveDate,
+    pub realized_pnl_sol: f64,
+    #[serde(default)]
+    pub positions: HashMap<String, CopiedPosition>,
+}
+
+impl FollowerDailyRisk {
+    pub fn new(day: NaiveDate) -> Self {
+        Self { day, realized_pnl_sol: 0.0, positions: HashMap::new() }
+    }
+
+    /// Start a fresh tally when `day` is later than the current one
+    pub fn roll_to(&mut self, day: NaiveDate) {
+        if day > self.day {
+            self.day = day;
+            self.realized_pnl_sol = 0.0;
+        }
+    }
+
+    /// Fold a settled execution in and return the SOL it realized
+    ///
+    /// Buys add to the position at cost (fees included). Sells realize the
+    /// proceeds less fees against the average cost of the tokens sold; tokens
+    /// that weren't bought through copying have no known cost and only count their fee.
+    pub fn record(&mut self, execution: &CopyTradeExecution) -> f64 {
+        if !matches!(execution.status, CopyTradeStatus::Success | CopyTradeStatus::PartialFill)
+            || execution.execution_price <= 0.0
+        {
+            return 0.0;
+        }
+        self.roll_to(execution.timestamp.date_naive());
+
+        let tokens = execution.copied_amount_sol / execution.execution_price;
+        let realized = match execution.trade_type {
+            CopyTradeType::Buy => {
+                let position = self.positions.entry(execution.token_address.clone()).or_default();
+                position.tokens += tokens;
+                position.cost_sol += execution.copied_amount_sol + execution.fee_paid_sol;
+                return 0.0;
+            }
+            CopyTradeType::Sell | CopyTradeType::StopLoss | CopyTradeType::TakeProfit | CopyTradeType::Emergency => {
+                let (sold, basis) = match self.positions.get_mut(&execution.token_address) {
+                    Some(position) if position.tokens > 0.0 => {
+                        // A proportional full exit can land a rounding error short of the whole position
+                        let sold = if tokens >= position.tokens * (1.0 - FULL_EXIT_TOLERANCE) { position.tokens } else { tokens };
+                        let basis = position.cost_sol * sold / position.tokens;
+                        position.tokens -= sold;
+                        position.cost_sol -= basis;
+                        (sold, basis)
+                    }
+                    _ => (0.0, 0.0),
+                };
+                if self.positions.get(&execution.token_address).is_some_and(|p| p.tokens <= f64::EPSILON) {
+                    self.positions.remove(&execution.token_address);
+                }
+                sold * execution.execution_price - basis - execution.fee_paid_sol
+            }
+        };
+
+        self.realized_pnl_sol += realized;
+        realized
+    }
+
+    /// Tokens currently held from copied buys
+    pub fn open_positions(&self) -> usize {
+        self.positions.values().filter(|p| p.tokens > f64::EPSILON).count()
+    }
+
+    pub fn holds(&self, token_address: &str) -> bool {
+        self.positions.get(token_address).is_some_and(|p| p.tokens > f64::EPSILON)
+    }
+
+    /// Loss still allowed today under `limit_sol`; today's profits don't extend it
+    pub fn remaining_budget(&self, limit_sol: f64) -> f64 {
+        (limit_sol + self.realized_pnl_sol.min(0.0)).max(0.0)
+    }
+
+    /// Today's realized loss has reached `limit_sol`
+    pub fn limit_reached(&self, limit_sol: f64) -> bool {
+        limit_sol > 0.0 && self.realized_pnl_sol <= -limit_sol
+    }
+}
+
+/// Tokens each master holds from the trades copying has seen
+///
+/// Sells are mirrored as a fraction of what the master had left, so a
+/// scale-out over several sells takes the same share of each follower's position.
+#[derive(Debug, Clone, Default)]
+pub struct MasterPositionBook {
+    positions: HashMap<i64, HashMap<String, f64>>,
+    /// Masters whose stored positions have been read in
+    loaded: HashSet<i64>,
+}
+
+impl MasterPositionBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_loaded(&self, master_user_id: i64) -> bool {
+        self.loaded.contains(&master_user_id)
+    }
+
+    /// Take a master's stored positions; a no-op once they're loaded, so nothing tracked since is lost
+    pub fn restore(&mut self, master_user_id: i64, holdings: HashMap<String, f64>) {
+        if !self.loaded.insert(master_user_id) {
+            return;
+        }
+        let holdings: HashMap<String, f64> = holdings.into_iter().filter(|(_, tokens)| *tokens > 0.0).collect();
+        if !holdings.is_empty() {
+            self.positions.insert(master_user_id, holdings);
+        }
+    }
+
+    /// Every token the master holds, for storing
+    pub fn holdings(&self, master_user_id: i64) -> HashMap<String, f64> {
+        self.positions.get(&master_user_id).cloned().unwrap_or_default()
+    }
+
+    pub fn record_buy(&mut self, master_user_id: i64, token_address: &str, tokens: f64) {
+        if tokens <= 0.0 {
+            return;
+        }
+        *self.positions.entry(master_user_id).or_default().entry(token_address.to_string()).or_default() += tokens;
+    }
+
+    /// Take a sell off the master's position and return the fraction of it sold
+    ///
+    /// `None` when no buy of the token was seen, so there's nothing to measure
+    /// the sell against. Selling more than was tracked counts as a full exit.
+    pub fn record_sell(&mut self, master_user_id: i64, token_address: &str, tokens: f64) -> Option<f64> {
+        let holdings = self.positions.get_mut(&master_user_id)?;
+        let remaining = holdings.get(token_address).copied().filter(|r| *r > 0.0)?;
+        let fraction = if tokens >= remaining * (1.0 - FULL_EXIT_TOLERANCE) { 1.0 } else { (tokens / remaining).max(0.0) };
+
+        if fraction >= 1.0 {
+            holdings.remove(token_address);
+            if holdings.is_empty() {
+                self.positions.remove(&master_user_id);
+            }
+        } else {
+            holdings.insert(token_address.to_string(), remaining - tokens);
+        }
+        Some(fraction)
+    }
+
+    /// Tokens the master still holds; 0 when untracked
+    pub fn remaining(&self, master_user_id: i64, token_address: &str) -> f64 {
+        self.positions.get(&master_user_id).and_then(|h| h.get(token_address)).copied().unwrap_or(0.0)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MasterTrader {
     pub user_id: i64,
@@ -102,6 +293,8 @@ pub struct CopyTradeExecution {
     pub status: CopyTradeStatus,
     pub error_message: Option<String>,
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub report: ExecutionReport,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -113,6 +306,13 @@ pub enum CopyTradeType {
     Emergency,
 }
 
+impl CopyTradeType {
+    /// Every kind of sell, which is mirrored as a share of the position
+    pub fn is_exit(&self) -> bool {
+        !matches!(self, CopyTradeType::Buy)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CopyTradeStatus {
     Pending,
@@ -132,6 +332,19 @@ pub struct CopyTradingManager {
     master_traders: Arc<RwLock<HashMap<i64, MasterTrader>>>,
     active_positions: Arc<RwLock<HashMap<String, Vec<Position>>>>, // token -> positions
     execution_history: Arc<RwLock<Vec<CopyTradeExecution>>>,
+    /// Realized PnL per follower for the current UTC day, write-through to the db
+    daily_risk: Arc<RwLock<HashMap<i64, FollowerDailyRisk>>>,
+    /// Masters' positions, for sizing mirrored sells
+    master_positions: Arc<RwLock<MasterPositionBook>>,
+    notifier: Option<Arc<ExecutionNotifier>>,
+    /// Where opted-in masters are found; without it none are offered
+    leaderboard: Option<Arc<LeaderboardManager>>,
+    /// Approved masters' fees and the ledger they accrue to
+    master_program: Option<Arc<MasterProgram>>,
+    /// Followers' token rules, checked before each copied buy
+    trade_gate: Option<Arc<TradeGate>>,
+    /// Cancels copies while an operator has trading paused
+    pause: Option<Arc<dyn TradingPause>>,
 }
 
 #[derive(Debug, Clone)]
@@ -159,8 +372,45 @@ impl CopyTradingManager {
             master_traders: Arc::new(RwLock::new(HashMap::new())),
             active_positions: Arc::new(RwLock::new(HashMap::new())),
             execution_history: Arc::new(RwLock::new(Vec::new())),
+            daily_risk: Arc::new(RwLock::new(HashMap::new())),
+            master_positions: Arc::new(RwLock::new(MasterPositionBook::new())),
+            notifier: None,
+            leaderboard: None,
+            master_program: None,
+            trade_gate: None,
+            pause: None,
         }
     }
+    
+    /// Publish followers' fills and failures at each follower's verbosity
+    pub fn with_notifier(mut self, notifier: Arc<ExecutionNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Offer traders who opted in to being copied as masters
+    pub fn with_leaderboard(mut self, leaderboard: Arc<LeaderboardManager>) -> Self {
+        self.leaderboard = Some(leaderboard);
+        self
+    }
+
+    /// Charge approved masters' own fees and accrue them for payout
+    pub fn with_master_program(mut self, master_program: Arc<MasterProgram>) -> Self {
+        self.master_program = Some(master_program);
+        self
+    }
+
+    /// Skip copied buys the follower's token rules don't allow
+    pub fn with_trade_gate(mut self, trade_gate: Arc<TradeGate>) -> Self {
+        self.trade_gate = Some(trade_gate);
+        self
+    }
+
+    /// Cancel copies, with a notice, while an operator has trading paused
+    pub fn with_pause(mut self, pause: Arc<dyn TradingPause>) -> Self {
+        self.pause = Some(pause);
+        self
+    }
 
     /// Start following a master trader
     pub async fn start_following(
@@ -174,7 +424,7 @@ impl CopyTradingManager {
         
         // Validate allocation
         if allocation_percent <= 0.0 || allocation_percent > 100.0 {
-            return Err(BotError::validation("Allocation must be between 1-100%").into());
+            return Err(BotError::invalid("allocation", "Allocation must be between 1-100%").into());
         }
         
         // Find master trader
@@ -188,10 +438,7 @@ impl CopyTradingManager {
         // Check follower balance
         let follower_balance = self.get_user_balance(follower_user_id).await?;
         if follower_balance < master.min_copy_amount_sol {
-            return Err(BotError::validation(format!(
-                "Minimum balance required: {} SOL",
-                master.min_copy_amount_sol
-            )).into());
+            return Err(BotError::insufficient_balance(master.min_copy_amount_sol, follower_balance).into());
         }
         
         // Check for existing relationship
@@ -219,7 +466,10 @@ impl CopyTradingManager {
             auto_take_profit: true,
             take_profit_percent: 50.0, // Default 50% take profit
             slippage_tolerance: 2.0, // 2% slippage tolerance
+            daily_loss_limit_sol: max_position_sol * DEFAULT_DAILY_LOSS_POSITIONS,
+            max_concurrent_positions: DEFAULT_MAX_CONCURRENT_POSITIONS,
             enabled: true,
+            paused_until: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             performance: CopyPerformance {
@@ -282,7 +532,35 @@ impl CopyTradingManager {
         }
     }
 
+    /// Stop every copy relationship a user is part of, as follower or master
+    pub async fn stop_all_following(&self, user_id: i64) -> usize {
+        let mut relationships = self.relationships.write().await;
+        let mut masters = self.master_traders.write().await;
+        
+        let mut stopped = 0;
+        if let Some(configs) = relationships.remove(&user_id) {
+            for config in &configs {
+                if let Some(master) = masters.get_mut(&config.master_user_id) {
+                    master.total_followers = master.total_followers.saturating_sub(1);
+                }
+            }
+            stopped += configs.len();
+        }
+        for configs in relationships.values_mut() {
+            let before = configs.len();
+            configs.retain(|c| c.master_user_id != user_id);
+            stopped += before - configs.len();
+        }
+        relationships.retain(|_, configs| !configs.is_empty());
+        masters.remove(&user_id);
+        
+        info!("User {} left copy trading ({} relationships stopped)", user_id, stopped);
+        stopped
+    }
+    
     /// Execute a copy trade when master makes a trade
+    ///
+    /// The master's token amount is taken as `master_amount_sol / master_price`.
     pub async fn execute_copy_trade(
         &self,
         master_user_id: i64,
@@ -291,13 +569,46 @@ impl CopyTradingManager {
         trade_type: CopyTradeType,
         master_amount_sol: f64,
         master_price: f64,
+    ) -> Result<Vec<CopyTradeExecution>> {
+        let master_tokens = if master_price > 0.0 { master_amount_sol / master_price } else { 0.0 };
+        self.copy_to_followers(master_user_id, token_address, token_symbol, trade_type, master_amount_sol, master_price, master_tokens).await
+    }
+
+    /// Buys are sized from the master's SOL; sells take the share of the
+    /// master's position they sold from each follower's position
+    #[allow(clippy::too_many_arguments)]
+    async fn copy_to_followers(
+        &self,
+        master_user_id: i64,
+        token_address: &str,
+        token_symbol: &str,
+        trade_type: CopyTradeType,
+        master_amount_sol: f64,
+        master_price: f64,
+        master_tokens: f64,
     ) -> Result<Vec<CopyTradeExecution>> {
         info!(
             "Master {} executing {:?} trade: {} {} for {} SOL",
             master_user_id, trade_type, token_symbol, token_address, master_amount_sol
         );
         
+        self.load_master_positions(master_user_id).await;
+        let (sold_fraction, holdings) = {
+            let mut book = self.master_positions.write().await;
+            let sold_fraction = if trade_type.is_exit() {
+                book.record_sell(master_user_id, token_address, master_tokens)
+            } else {
+                book.record_buy(master_user_id, token_address, master_tokens);
+                None
+            };
+            (sold_fraction, book.holdings(master_user_id))
+        };
+        if let Err(e) = self.save_master_positions(master_user_id, &holdings).await {
+            warn!("Failed to store the tracked positions of master {}: {}", master_user_id, e);
+        }
+        
         let mut executions = Vec::new();
+        self.resume_paused_at(Utc::now()).await;
         
         // Get all followers of this master
         let relationships = self.relationships.read().await;
@@ -313,18 +624,106 @@ impl CopyTradingManager {
         info!("Found {} active followers for master {}", followers.len(), master_user_id);
         
         // Get master trader info for fee calculation
-        let masters = self.master_traders.read().await;
-        let master = masters.get(&master_user_id);
-        let copy_fee_percent = master.map(|m| m.copy_fee_percent).unwrap_or(5.0);
-        drop(masters);
+        let copy_fee_percent = self.copy_fee_percent(master_user_id).await;
+        // The master's positions above stay tracked so later sells size correctly
+        let paused = match &self.pause {
+            Some(pause) => pause.trading_paused().await,
+            None => false,
+        };
         
         // Execute copy trades for each follower
         for config in followers {
             // Check if this trade type should be copied
-            match trade_type {
-                CopyTradeType::Buy if !config.copy_buys => continue,
-                CopyTradeType::Sell if !config.copy_sells => continue,
-                _ => {}
+            let copied = if trade_type.is_exit() { config.copy_sells } else { config.copy_buys };
+            if !copied {
+                continue;
+            }
+            if paused {
+                executions.push(CopyTradeExecution {
+                    execution_id: uuid::Uuid::new_v4().to_string(),
+                    master_trade_id: format!("{}_{}", master_user_id, Utc::now().timestamp()),
+                    master_user_id,
+                    follower_user_id: config.follower_user_id,
+                    token_address: token_address.to_string(),
+                    token_symbol: token_symbol.to_string(),
+                    trade_type: trade_type.clone(),
+                    master_amount_sol,
+                    copied_amount_sol: 0.0,
+                    master_price,
+                    execution_price: 0.0,
+                    slippage_percent: 0.0,
+                    fee_paid_sol: 0.0,
+                    status: CopyTradeStatus::Cancelled,
+                    error_message: Some(PAUSED_FOR_MAINTENANCE.to_string()),
+                    timestamp: Utc::now(),
+                    report: ExecutionReport::default(),
+                });
+                continue;
+            }
+            if trade_type.is_exit() {
+                let execution = self.mirror_sell(&config, token_address, token_symbol, &trade_type, sold_fraction, master_amount_sol, master_price, copy_fee_percent).await;
+                executions.push(execution);
+                continue;
+            }
+            
+            // The follower's own token rules come before any sizing
+            if let (CopyTradeType::Buy, Some(gate)) = (&trade_type, &self.trade_gate) {
+                if let Some(blocked) = gate.evaluate(config.follower_user_id, token_address).await {
+                    executions.push(CopyTradeExecution {
+                        execution_id: uuid::Uuid::new_v4().to_string(),
+                        master_trade_id: format!("{}_{}", master_user_id, Utc::now().timestamp()),
+                        master_user_id,
+                        follower_user_id: config.follower_user_id,
+                        token_address: token_address.to_string(),
+                        token_symbol: token_symbol.to_string(),
+                        trade_type: trade_type.clone(),
+                        master_amount_sol,
+                        copied_amount_sol: 0.0,
+                        master_price,
+                        execution_price: 0.0,
+                        slippage_percent: 0.0,
+                        fee_paid_sol: 0.0,
+                        status: CopyTradeStatus::Cancelled,
+                        error_message: Some(blocked.to_string()),
+                        timestamp: Utc::now(),
+                        report: ExecutionReport::default(),
+                    });
+                    continue;
+                }
+            }
+            
+            // A new token needs a free position slot
+            if trade_type == CopyTradeType::Buy && config.max_concurrent_positions > 0 {
+                let risk = self.follower_risk(config.follower_user_id).await;
+                if !risk.holds(token_address) && risk.open_positions() >= config.max_concurrent_positions as usize {
+                    info!(
+                        "Follower {} already holds {} copied positions, skipping {}",
+                        config.follower_user_id, risk.open_positions(), token_symbol
+                    );
+                    executions.push(CopyTradeExecution {
+                        execution_id: uuid::Uuid::new_v4().to_string(),
+                        master_trade_id: format!("{}_{}", master_user_id, Utc::now().timestamp()),
+                        master_user_id,
+                        follower_user_id: config.follower_user_id,
+                        token_address: token_address.to_string(),
+                        token_symbol: token_symbol.to_string(),
+                        trade_type: trade_type.clone(),
+                        master_amount_sol,
+                        copied_amount_sol: 0.0,
+                        master_price,
+                        execution_price: 0.0,
+                        slippage_percent: 0.0,
+                        fee_paid_sol: 0.0,
+                        status: CopyTradeStatus::Cancelled,
+                        error_message: Some(format!(
+                            "Already holding {} copied positions (max {})",
+                            risk.open_positions(), config.max_concurrent_positions
+                        )),
+                        timestamp: Utc::now(),
+                        report: ExecutionReport::default(),
+                    });
+                    continue;
+                }
             }
             
             // Calculate copy amount based on allocation
@@ -358,8 +757,9 @@ impl CopyTradingManager {
                             slippage_percent: 0.0,
                             fee_paid_sol: 0.0,
                             status: CopyTradeStatus::Failed,
-                            error_message: Some("Insufficient balance".to_string()),
+                            error_message: Some(BotError::insufficient_balance(copy_amount * 1.05, balance).user_message()),
                             timestamp: Utc::now(),
+                            report: ExecutionReport::default(),
                         });
                         continue;
                     }
@@ -384,6 +784,24 @@ impl CopyTradingManager {
             executions.push(execution);
         }
         
+        if let Some(notifier) = &self.notifier {
+            for notice in executions.iter().filter_map(Self::notice) {
+                notifier.notify(notice).await;
+            }
+        }
+        
+        if let Err(e) = self.record_executions(&executions).await {
+            error!("Failed to record copy trade PnL: {}", e);
+        }
+        if let Some(program) = &self.master_program {
+            if let Err(e) = program.accrue(&executions).await {
+                error!("Failed to accrue copy fees: {}", e);
+            }
+        }
+        if let Err(e) = self.store_executions(&executions).await {
+            error!("Failed to store copy trade executions: {}", e);
+        }
+        
         // Store execution history
         let mut history = self.execution_history.write().await;
         history.extend(executions.clone());
@@ -396,6 +814,363 @@ impl CopyTradingManager {
         Ok(executions)
     }
 
+    /// Fee followers pay this master, fixed at the moment each copy executes
+    ///
+    /// Approved masters set their own; others keep the default.
+    async fn copy_fee_percent(&self, master_user_id: i64) -> f64 {
+        if let Some(program) = &self.master_program {
+            if let Some(profile) = program.profile(master_user_id).await {
+                return profile.fee_percent;
+            }
+        }
+        let masters = self.master_traders.read().await;
+        masters.get(&master_user_id).map(|m| m.copy_fee_percent).unwrap_or(5.0)
+    }
+
+    /// Mark a copy that settled as failed after all, e.g. its transaction was
+    /// dropped, and reverse the fee its master accrued
+    pub async fn refund_execution(&self, execution_id: &str, reason: &str) -> Result<CopyTradeExecution> {
+        let execution = {
+            let mut history = self.execution_history.write().await;
+            let execution = history.iter_mut()
+                .find(|e| e.execution_id == execution_id)
+                .ok_or_else(|| BotError::not_found(format!("Copy trade {} not found", execution_id)))?;
+            if !matches!(execution.status, CopyTradeStatus::Success | CopyTradeStatus::PartialFill) {
+                return Err(BotError::validation(format!("Copy trade {} didn't execute", execution_id)).into());
+            }
+            execution.status = CopyTradeStatus::Failed;
+            execution.error_message = Some(reason.to_string());
+            execution.clone()
+        };
+
+        if let Some(program) = &self.master_program {
+            program.reverse(&execution).await?;
+        }
+        warn!("Copy trade {} for follower {} failed after settling: {}", execution_id, execution.follower_user_id, reason);
+        if let (Some(notifier), Some(notice)) = (&self.notifier, Self::notice(&execution)) {
+            notifier.notify(notice).await;
+        }
+        Ok(execution)
+    }
+
+    /// Wallets of masters with an enabled follower, or one paused only until tomorrow
+    pub async fn followed_master_wallets(&self) -> Vec<String> {
+        let relationships = self.relationships.read().await;
+        let mut wallets: Vec<String> = relationships
+            .values()
+            .flatten()
+            .filter(|c| c.enabled || c.paused_until.is_some())
+            .map(|c| c.master_wallet.clone())
+            .collect();
+        wallets.sort();
+        wallets.dedup();
+        wallets
+    }
+
+    /// Copy a trade detected on a master's wallet to that master's followers
+    pub async fn handle_master_trade(&self, event: &MasterTradeEvent) -> Result<Vec<CopyTradeExecution>> {
+        let master_user_id = self.relationships.read().await
+            .values()
+            .flatten()
+            .find(|c| c.master_wallet == event.master_wallet)
+            .map(|c| c.master_user_id);
+        let Some(master_user_id) = master_user_id else {
+            debug!("No followers for master wallet {}, ignoring {}", event.master_wallet, event.signature);
+            return Ok(Vec::new());
+        };
+
+        self.copy_to_followers(
+            master_user_id,
+            &event.token_mint,
+            &TokenResolver::get_symbol(&event.token_mint),
+            event.direction.clone(),
+            event.sol_amount,
+            event.price,
+            event.token_amount,
+        ).await
+    }
+
+    /// Sell the share of the follower's copied position that the master sold of theirs
+    ///
+    /// Stop losses, take profits and emergency exits are sized the same way.
+    /// Skipped, with a notice, when the follower holds none of the token or the
+    /// master's position wasn't tracked so the share is unknown.
+    #[allow(clippy::too_many_arguments)]
+    async fn mirror_sell(
+        &self,
+        config: &CopyTradingConfig,
+        token_address: &str,
+        token_symbol: &str,
+        trade_type: &CopyTradeType,
+        sold_fraction: Option<f64>,
+        master_amount_sol: f64,
+        master_price: f64,
+        fee_percent: f64,
+    ) -> CopyTradeExecution {
+        let held = self.follower_risk(config.follower_user_id).await
+            .positions.get(token_address)
+            .map(|p| p.tokens)
+            .unwrap_or(0.0);
+
+        let fraction = match sold_fraction {
+            _ if held <= f64::EPSILON => Err(format!(
+                "You hold no {}, so {}'s sell was not copied", token_symbol, config.master_username
+            )),
+            _ if master_price <= 0.0 => Err(format!("No price for {}'s sell of {}", config.master_username, token_symbol)),
+            None => Err(format!(
+                "{}'s {} position wasn't tracked, so the share they sold is unknown and the sell was not copied",
+                config.master_username, token_symbol
+            )),
+            Some(fraction) => Ok(fraction),
+        };
+        let fraction = match fraction {
+            Ok(fraction) => fraction,
+            Err(reason) => {
+                info!("Follower {}: {}", config.follower_user_id, reason);
+                return CopyTradeExecution {
+                    execution_id: uuid::Uuid::new_v4().to_string(),
+                    master_trade_id: format!("{}_{}", config.master_user_id, Utc::now().timestamp()),
+                    master_user_id: config.master_user_id,
+                    follower_user_id: config.follower_user_id,
+                    token_address: token_address.to_string(),
+                    token_symbol: token_symbol.to_string(),
+                    trade_type: trade_type.clone(),
+                    master_amount_sol,
+                    copied_amount_sol: 0.0,
+                    master_price,
+                    execution_price: 0.0,
+                    slippage_percent: 0.0,
+                    fee_paid_sol: 0.0,
+                    status: CopyTradeStatus::Cancelled,
+                    error_message: Some(reason),
+                    timestamp: Utc::now(),
+                    report: ExecutionReport::default(),
+                };
+            }
+        };
+
+        let tokens = held * fraction;
+        debug!(
+            "Follower {} mirroring a {:.1}% sell of {}: {} of {} tokens",
+            config.follower_user_id, fraction * 100.0, token_symbol, tokens, held
+        );
+        let mut execution = self.execute_follower_trade(
+            config,
+            token_address,
+            token_symbol,
+            trade_type.clone(),
+            tokens * master_price,
+            master_price,
+            fee_percent,
+        ).await;
+        execution.master_amount_sol = master_amount_sol;
+        execution
+    }
+
+    /// Keep successful copies for the follower's trade history export
+    async fn store_executions(&self, executions: &[CopyTradeExecution]) -> Result<()> {
+        for execution in executions.iter().filter(|e| e.status == CopyTradeStatus::Success) {
+            let data = serde_json::to_string(execution)?;
+            self.db.insert_copy_execution(&execution.execution_id, execution.follower_user_id, &data).await?;
+        }
+        Ok(())
+    }
+
+    /// Fold settled executions into each follower's daily PnL and pause copying past the limit
+    pub async fn record_executions(&self, executions: &[CopyTradeExecution]) -> Result<()> {
+        let mut followers: Vec<i64> = executions.iter().map(|e| e.follower_user_id).collect();
+        followers.sort_unstable();
+        followers.dedup();
+
+        for follower in followers {
+            let mut risk = self.follower_risk(follower).await;
+            for execution in executions.iter().filter(|e| e.follower_user_id == follower) {
+                risk.record(execution);
+            }
+            self.daily_risk.write().await.insert(follower, risk.clone());
+            self.db.upsert_copy_daily_risk(follower, &serde_json::to_string(&risk)?).await?;
+
+            let paused = {
+                let mut relationships = self.relationships.write().await;
+                match relationships.get_mut(&follower) {
+                    Some(configs) => Self::apply_daily_limit(configs, &risk, Utc::now()),
+                    None => Vec::new(),
+                }
+            };
+            for config in paused {
+                warn!(
+                    "Follower {} lost {:.4} SOL copying today, pausing copies of {} until {}",
+                    follower, -risk.realized_pnl_sol, config.master_username,
+                    config.paused_until.map(|t| t.to_rfc3339()).unwrap_or_default()
+                );
+                if let Some(notifier) = &self.notifier {
+                    notifier.notify(Self::limit_notice(&config, &risk)).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Pause every enabled relationship whose daily loss limit `risk` has reached
+    ///
+    /// Returns the configs it paused; they resume at the next UTC midnight.
+    pub fn apply_daily_limit(
+        configs: &mut [CopyTradingConfig],
+        risk: &FollowerDailyRisk,
+        now: DateTime<Utc>,
+    ) -> Vec<CopyTradingConfig> {
+        let resume_at = Self::next_utc_midnight(now);
+        configs.iter_mut()
+            .filter(|c| c.enabled && risk.limit_reached(c.daily_loss_limit_sol))
+            .map(|config| {
+                config.enabled = false;
+                config.paused_until = Some(resume_at);
+                config.updated_at = now;
+                config.clone()
+            })
+            .collect()
+    }
+
+    /// Turn back on relationships the loss limit paused once their pause has passed
+    pub async fn resume_paused_at(&self, now: DateTime<Utc>) -> usize {
+        let mut relationships = self.relationships.write().await;
+        let mut resumed = 0;
+        for config in relationships.values_mut().flatten() {
+            if !config.enabled && config.paused_until.is_some_and(|until| until <= now) {
+                config.enabled = true;
+                config.paused_until = None;
+                config.updated_at = now;
+                resumed += 1;
+                info!("Follower {} resumed copying {} after the daily loss pause", config.follower_user_id, config.master_username);
+            }
+        }
+        resumed
+    }
+
+    /// Enable or disable a relationship by hand; this overrides a loss-limit pause either way
+    pub async fn set_enabled(&self, follower_user_id: i64, master_user_id: i64, enabled: bool) -> Result<()> {
+        let mut relationships = self.relationships.write().await;
+        let config = relationships.get_mut(&follower_user_id)
+            .and_then(|configs| configs.iter_mut().find(|c| c.master_user_id == master_user_id))
+            .ok_or_else(|| BotError::validation("Not following this trader"))?;
+        config.enabled = enabled;
+        config.paused_until = None;
+        config.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Today's tally for a follower, loaded from the db the first time it's needed
+    pub async fn follower_risk(&self, follower_user_id: i64) -> FollowerDailyRisk {
+        let today = Utc::now().date_naive();
+        if let Some(risk) = self.daily_risk.read().await.get(&follower_user_id) {
+            let mut risk = risk.clone();
+            risk.roll_to(today);
+            return risk;
+        }
+
+        let stored = match self.db.get_copy_daily_risk(follower_user_id).await {
+            Ok(stored) => stored.and_then(|json| serde_json::from_str::<FollowerDailyRisk>(&json).ok()),
+            Err(e) => {
+                warn!("Failed to load copy trading PnL for follower {}: {}", follower_user_id, e);
+                None
+            }
+        };
+        let mut risk = stored.unwrap_or_else(|| FollowerDailyRisk::new(today));
+        risk.roll_to(today);
+        self.daily_risk.write().await.entry(follower_user_id).or_insert_with(|| risk.clone());
+        risk
+    }
+
+    /// Tokens of `token_address` the master holds as far as copying has seen
+    pub async fn master_position(&self, master_user_id: i64, token_address: &str) -> f64 {
+        self.load_master_positions(master_user_id).await;
+        self.master_positions.read().await.remaining(master_user_id, token_address)
+    }
+
+    /// Read a master's tracked positions from the db the first time they're needed,
+    /// so a restart can still size sells of tokens bought before it
+    async fn load_master_positions(&self, master_user_id: i64) {
+        if self.master_positions.read().await.is_loaded(master_user_id) {
+            return;
+        }
+        let stored = match self.db.get_master_positions(master_user_id).await {
+            Ok(stored) => stored.and_then(|json| serde_json::from_str::<HashMap<String, f64>>(&json).ok()),
+            Err(e) => {
+                warn!("Failed to load the tracked positions of master {}: {}", master_user_id, e);
+                None
+            }
+        };
+        self.master_positions.write().await.restore(master_user_id, stored.unwrap_or_default());
+    }
+
+    async fn save_master_positions(&self, master_user_id: i64, holdings: &HashMap<String, f64>) -> Result<()> {
+        self.db.upsert_master_positions(master_user_id, &serde_json::to_string(holdings)?).await
+    }
+
+    /// Loss still allowed today under this relationship's limit; `None` without a limit
+    pub async fn remaining_daily_budget(&self, config: &CopyTradingConfig) -> Option<f64> {
+        if config.daily_loss_limit_sol <= 0.0 {
+            return None;
+        }
+        Some(self.follower_risk(config.follower_user_id).await.remaining_budget(config.daily_loss_limit_sol))
+    }
+
+    fn limit_notice(config: &CopyTradingConfig, risk: &FollowerDailyRisk) -> ExecutionNotice {
+        ExecutionNotice {
+            user_id: config.follower_user_id,
+            source: ExecutionSource::Copy,
+            label: format!("Copying {}", config.master_username),
+            strategy_id: None,
+            order_id: None,
+            kind: NoticeKind::RiskRefusal {
+                reason: format!(
+                    "Daily loss limit of {} SOL reached ({:.4} SOL lost today). Copying is paused and resumes at 00:00 UTC.",
+                    config.daily_loss_limit_sol, -risk.realized_pnl_sol
+                ),
+            },
+            at: Utc::now(),
+        }
+    }
+
+    fn next_utc_midnight(now: DateTime<Utc>) -> DateTime<Utc> {
+        let tomorrow = now.date_naive() + Duration::days(1);
+        Utc.from_utc_datetime(&tomorrow.and_hms_opt(0, 0, 0).expect("midnight is valid"))
+    }
+
+    /// Notice for a settled copy trade; in-flight ones have nothing to report yet
+    fn notice(execution: &CopyTradeExecution) -> Option<ExecutionNotice> {
+        let kind = match execution.status {
+            CopyTradeStatus::Pending | CopyTradeStatus::Executing => return None,
+            CopyTradeStatus::Success | CopyTradeStatus::PartialFill => NoticeKind::Fill(Fill {
+                mint: execution.token_address.clone(),
+                symbol: execution.token_symbol.clone(),
+                quantity: if execution.execution_price > 0.0 {
+                    execution.copied_amount_sol / execution.execution_price
+                } else {
+                    0.0
+                },
+                price: execution.execution_price,
+                fee_sol: execution.fee_paid_sol,
+            }),
+            CopyTradeStatus::Failed => NoticeKind::Failure {
+                reason: execution.error_message.clone().unwrap_or_else(|| format!("{:?}", execution.status)),
+            },
+            // Risk guards, token rules, maintenance and unmirrorable sells cancel a copy before it runs
+            CopyTradeStatus::Cancelled => NoticeKind::RiskRefusal {
+                reason: execution.error_message.clone().unwrap_or_else(|| format!("{:?}", execution.status)),
+            },
+        };
+        Some(ExecutionNotice {
+            user_id: execution.follower_user_id,
+            source: ExecutionSource::Copy,
+            label: format!("{:?} {}", execution.trade_type, execution.token_symbol),
+            strategy_id: None,
+            order_id: None,
+            kind,
+            at: execution.timestamp,
+        })
+    }
+    
     /// Execute individual follower trade
     async fn execute_follower_trade(
         &self,
@@ -419,28 +1194,31 @@ impl CopyTradingManager {
         // Execute via trading engine
         // In production, this would use the actual trading engine message format
         // For now, simulate the trade execution
-        let result = match trade_type {
-            CopyTradeType::Buy | CopyTradeType::Sell => {
-                // Simulate trade execution
-                Ok(TradeResult {
-                    success: true,
-                    amount: trade_amount,
-                    price: master_price * (1.0 + (rand::thread_rng().gen::<f64>() - 0.5) * 0.02), // Simulate ±1% slippage
-                    signature: Some(format!("sim_tx_{}", uuid::Uuid::new_v4())),
-                    message: format!("Copy trade executed: {} {} SOL of {}", 
-                        if matches!(trade_type, CopyTradeType::Buy) { "Bought" } else { "Sold" },
-                        trade_amount, token_symbol),
-                })
-            }
-            _ => {
-                Ok(TradeResult {
-                    success: false,
-                    amount: 0.0,
-                    price: 0.0,
-                    signature: None,
-                    message: "Trade type not implemented".to_string(),
-                })
+        let result: Result<TradeResult> = {
+            // Simulate trade execution with ±1% slippage against the master's price
+            let price = master_price * (1.0 + (rand::thread_rng().gen::<f64>() - 0.5) * 0.02);
+            let signature = format!("sim_tx_{}", uuid::Uuid::new_v4());
+            let side = if trade_type.is_exit() { TradeType::Sell } else { TradeType::Buy };
+            
+            // The master's fill is the quote the follower's decision was based on
+            let execution = ExecutionReport {
+                quoted_at: Some(Utc::now()),
+                quoted_price: Some(master_price).filter(|p| *p > 0.0),
+                ..ExecutionReport::default()
             }
+            .filled(price, side)
+            .simulated(true)
+            .with_idempotency_key(format!("copy:{}:{}:{}", config.master_user_id, config.follower_user_id, execution_id));
+            
+            // Sells are sized in tokens at the master's price; the fee comes out of the proceeds
+            let trade = match side {
+                TradeType::Sell => {
+                    let tokens = amount_sol / master_price;
+                    TradeResult::sell(signature, tokens, tokens * price, price)
+                }
+                _ => TradeResult::buy(signature, trade_amount / price, trade_amount, price),
+            };
+            Ok(trade.with_execution(execution))
         };
         
         match result {
@@ -457,44 +1235,47 @@ impl CopyTradingManager {
                     follower_user_id: config.follower_user_id,
                     token_address: token_address.to_string(),
                     token_symbol: token_symbol.to_string(),
+                    copied_amount_sol: if trade_type.is_exit() { trade_result.sol_received } else { trade_amount },
+                    // The fee is a share of what the follower actually moved
+                    fee_paid_sol: if trade_type.is_exit() {
+                        trade_result.sol_received * (fee_percent / 100.0)
+                    } else {
+                        fee_amount
+                    },
                     trade_type,
                     master_amount_sol: amount_sol,
-                    copied_amount_sol: trade_amount,
                     master_price,
                     execution_price: trade_result.price,
                     slippage_percent: slippage,
-                    fee_paid_sol: fee_amount,
-                    status: if trade_result.success {
-                        CopyTradeStatus::Success
-                    } else {
-                        CopyTradeStatus::Failed
-                    },
-                    error_message: if !trade_result.success {
-                        Some(trade_result.message)
-                    } else {
-                        None
-                    },
+                    status: CopyTradeStatus::Success,
+                    error_message: None,
                     timestamp: Utc::now(),
+                    report: trade_result.execution,
+                }
+            }
+            Err(e) => {
+                error!("Copy trade for follower {} failed: {}", config.follower_user_id, e);
+                CopyTradeExecution {
+                    execution_id,
+                    master_trade_id: format!("{}_{}", config.master_user_id, Utc::now().timestamp()),
+                    master_user_id: config.master_user_id,
+                    follower_user_id: config.follower_user_id,
+                    token_address: token_address.to_string(),
+                    token_symbol: token_symbol.to_string(),
+                    trade_type,
+                    master_amount_sol: amount_sol,
+                    copied_amount_sol: trade_amount,
+                    master_price,
+                    execution_price: 0.0,
+                    slippage_percent: 0.0,
+                    fee_paid_sol: 0.0,
+                    status: CopyTradeStatus::Failed,
+                    // Shown to the follower, so internal detail stays in the log above
+                    error_message: Some(BotError::user_message_for(&e)),
+                    timestamp: Utc::now(),
+                    report: ExecutionReport::default(),
                 }
             }
-            Err(e) => CopyTradeExecution {
-                execution_id,
-                master_trade_id: format!("{}_{}", config.master_user_id, Utc::now().timestamp()),
-                master_user_id: config.master_user_id,
-                follower_user_id: config.follower_user_id,
-                token_address: token_address.to_string(),
-                token_symbol: token_symbol.to_string(),
-                trade_type,
-                master_amount_sol: amount_sol,
-                copied_amount_sol: trade_amount,
-                master_price,
-                execution_price: 0.0,
-                slippage_percent: 0.0,
-                fee_paid_sol: 0.0,
-                status: CopyTradeStatus::Failed,
-                error_message: Some(e.to_string()),
-                timestamp: Utc::now(),
-            },
         }
     }
 
@@ -578,66 +1359,53 @@ impl CopyTradingManager {
         Ok((configs, user_executions))
     }
 
-    /// Get available master traders
+    /// Traders who opted in to being copied, most profitable this week first
+    ///
+    /// Traders without an active wallet can't be followed, so they're left out.
     pub async fn get_available_masters(&self, limit: usize) -> Result<Vec<MasterTrader>> {
-        // In production, this would query from database
-        // For now, return sample data
-        Ok(vec![
-            MasterTrader {
-                user_id: 1001,
-                username: "AlphaTrader".to_string(),
-                wallet_address: "Alpha123...xyz".to_string(),
-                copy_fee_percent: 10.0,
-                min_copy_amount_sol: 1.0,
-                total_followers: 234,
-                total_volume_copied_sol: 45678.0,
-                fees_earned_sol: 4567.8,
-                is_accepting_followers: true,
-                performance_7d: 45.2,
-                performance_30d: 127.8,
-                win_rate: 78.5,
-                avg_trade_size_sol: 25.0,
-                trading_style: TradingStyle::Sniper,
-                restrictions: vec![CopyRestriction::MinBalance(5.0)],
-            },
-            MasterTrader {
-                user_id: 1002,
-                username: "DiamondHands".to_string(),
-                wallet_address: "Diamond456...abc".to_string(),
-                copy_fee_percent: 8.0,
-                min_copy_amount_sol: 0.5,
-                total_followers: 189,
-                total_volume_copied_sol: 32100.0,
-                fees_earned_sol: 2568.0,
-                is_accepting_followers: true,
-                performance_7d: 22.1,
-                performance_30d: 89.3,
-                win_rate: 71.2,
-                avg_trade_size_sol: 15.0,
-                trading_style: TradingStyle::SwingTrader,
-                restrictions: vec![],
-            },
-            MasterTrader {
-                user_id: 1003,
-                username: "ScalpMaster".to_string(),
-                wallet_address: "Scalp789...def".to_string(),
-                copy_fee_percent: 15.0,
-                min_copy_amount_sol: 2.0,
-                total_followers: 567,
-                total_volume_copied_sol: 123456.0,
-                fees_earned_sol: 18518.4,
-                is_accepting_followers: true,
-                performance_7d: 18.9,
-                performance_30d: 76.2,
-                win_rate: 82.1,
-                avg_trade_size_sol: 5.0,
-                trading_style: TradingStyle::Scalper,
-                restrictions: vec![
-                    CopyRestriction::MinBalance(10.0),
-                    CopyRestriction::MaxFollowers(1000),
-                ],
-            },
-        ])
+        let Some(leaderboard) = &self.leaderboard else { return Ok(Vec::new()) };
+
+        let mut masters = Vec::new();
+        for entry in leaderboard.get_copyable_traders(usize::MAX).await? {
+            if masters.len() >= limit {
+                break;
+            }
+            let Some(wallet) = self.wallet_manager.get_user_wallet(&entry.user_id.to_string()).await? else {
+                debug!("Skipping copyable trader {} without an active wallet", entry.user_id);
+                continue;
+            };
+            let stats = leaderboard.get_trader_stats(entry.user_id).await?;
+            let total_followers = self.relationships.read().await.values()
+                .flatten()
+                .filter(|config| config.master_user_id == entry.user_id)
+                .count() as u32;
+            let (accepting, fees_earned_sol) = match &self.master_program {
+                Some(program) => (
+                    program.profile(entry.user_id).await.map_or(true, |p| p.accepting_followers),
+                    program.ledger(entry.user_id).await?.earned_lamports() as f64 / 1e9,
+                ),
+                None => (true, 0.0),
+            };
+
+            masters.push(MasterTrader {
+                user_id: entry.user_id,
+                username: entry.username,
+                wallet_address: wallet.public_key,
+                copy_fee_percent: self.copy_fee_percent(entry.user_id).await,
+                min_copy_amount_sol: DEFAULT_MIN_COPY_SOL,
+                total_followers,
+                total_volume_copied_sol: 0.0,
+                fees_earned_sol,
+                is_accepting_followers: accepting,
+                performance_7d: stats.performance_7d,
+                performance_30d: stats.performance_30d,
+                win_rate: entry.win_rate,
+                avg_trade_size_sol: entry.volume_sol / entry.total_trades.max(1) as f64,
+                trading_style: TradingStyle::Mixed,
+                restrictions: Vec::new(),
+            });
+        }
+        Ok(masters)
     }
 
     /// Find master trader by identifier
@@ -658,7 +1426,7 @@ impl CopyTradingManager {
                 m.username.eq_ignore_ascii_case(identifier) ||
                 m.wallet_address.starts_with(identifier)
             })
-            .ok_or_else(|| BotError::validation("Master trader not found").into())
+            .ok_or_else(|| BotError::not_found("Master trader not found").into())
     }
 
     /// Get user balance (mock implementation)
@@ -679,6 +1447,8 @@ impl CopyTradingManager {
             Copy Sells: {}\n\
             Auto Stop Loss: {} ({}%)\n\
             Auto Take Profit: {} ({}%)\n\
+            Daily Loss Limit: {}\n\
+            Max Open Positions: {}\n\
             Status: {}\n\
             \n\
             📊 **Performance**\n\
@@ -697,7 +1467,13 @@ impl CopyTradingManager {
             config.stop_loss_percent,
             if config.auto_take_profit { "✅" } else { "❌" },
             config.take_profit_percent,
-            if config.enabled { "🟢 Active" } else { "🔴 Paused" },
+            if config.daily_loss_limit_sol > 0.0 { format!("{} SOL", config.daily_loss_limit_sol) } else { "off".to_string() },
+            if config.max_concurrent_positions > 0 { config.max_concurrent_positions.to_string() } else { "unlimited".to_string() },
+            match (config.enabled, config.paused_until) {
+                (true, _) => "🟢 Active".to_string(),
+                (false, Some(until)) => format!("🟠 Paused by daily loss limit until {}", until.format("%H:%M UTC")),
+                (false, None) => "🔴 Paused".to_string(),
+            },
             config.performance.total_trades_copied,
             if config.performance.total_trades_copied > 0 {
                 (config.performance.successful_trades as f64 / 
@@ -1,4 +1,6 @@
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,6 +14,16 @@ use crate::api::jupiter_v6::{JupiterV6Client, QuoteRequestV6, SwapMode};
 use crate::api::jupiter_price_v3::JupiterPriceV3Client;
 use crate::telemetry::TelemetryService;
 use crate::db::Database;
+use crate::wallet::TransactionPriority;
+use super::dca_risk_strategies::{RiskBasedDCAManager, SizingAdjustment};
+use super::dca_scheduler::{next_cron_run, resolve_local, TimezoneManager};
+use super::execution_notices::{ExecutionNotice, ExecutionNotifier, ExecutionSource, Fill, NoticeKind};
+use super::TokenResolver;
+use super::maintenance::{TradingPause, PAUSED_FOR_MAINTENANCE};
+use super::compute_budget::{BudgetUrgency, ComputeBudget, ComputeBudgetConfig};
+use super::priority_fees::PriorityFeeEstimator;
+use super::types::{ExecutionFees, ExecutionReport, RouteSummary, TradeDefaultPreferences, TradeType};
+use super::trade_gate::{TradeBlocked, TradeGate};
 
 /// DCA (Dollar Cost Averaging) engine for automated trading
 #[derive(Clone)]
@@ -20,10 +32,20 @@ pub struct DCAEngine {
     price_client: Arc<JupiterPriceV3Client>,
     database: Arc<Database>,
     telemetry: Option<Arc<TelemetryService>>,
+    timezones: Arc<TimezoneManager>,
     strategies: Arc<RwLock<HashMap<String, DCAStrategy>>>,
     execution_history: Arc<RwLock<HashMap<String, Vec<DCAExecution>>>>,
+    notifier: Option<Arc<ExecutionNotifier>>,
+    fee_estimator: Option<Arc<PriorityFeeEstimator>>,
+    trade_defaults: Option<Arc<dyn TradeDefaultPreferences>>,
+    risk: Option<Arc<RiskBasedDCAManager>>,
+    trade_gate: Option<Arc<TradeGate>>,
+    pause: Option<Arc<dyn TradingPause>>,
 }
 
+/// Signature fee of a single-signer swap, in lamports
+const BASE_FEE_LAMPORTS: u64 = 5_000;
+
 /// DCA strategy configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DCAStrategy {
@@ -45,6 +67,15 @@ pub struct DCAStrategy {
     pub end_date: Option<DateTime<Utc>>,
     pub risk_parameters: RiskParameters,
     pub advanced_config: AdvancedDCAConfig,
+    /// Local wall-clock anchor for the interval; unanchored intervals run relative to the last tick
+    #[serde(default)]
+    pub anchor: Option<DCAAnchor>,
+    /// Last anchored slot that ran, so a restart never repeats it
+    #[serde(default)]
+    pub last_completed_slot: Option<String>,
+    /// The next due occurrence is dropped instead of run
+    #[serde(default)]
+    pub skip_next: bool,
 }
 
 /// DCA execution intervals
@@ -59,6 +90,110 @@ pub enum DCAInterval {
     Custom { cron_expression: String },
 }
 
+/// Local wall-clock anchor for daily and longer intervals
+///
+/// Runs land on `time_of_day` in the user's timezone (from `TimezoneManager`).
+/// Across DST changes the wall-clock time is kept: a time skipped by
+/// spring-forward runs an hour later (02:30 becomes 03:30), and a time repeated
+/// by fall-back runs once, at its first occurrence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DCAAnchor {
+    pub time_of_day: NaiveTime,
+    /// Weekly and biweekly runs; Monday when unset
+    pub weekday: Option<Weekday>,
+    /// Monthly runs, clamped to short months; the 1st when unset
+    pub day_of_month: Option<u32>,
+}
+
+/// One anchored run, identified by its local date
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchoredSlot {
+    pub slot_id: String,
+    pub run_at: DateTime<Utc>,
+}
+
+impl DCAAnchor {
+    pub fn daily(time_of_day: NaiveTime) -> Self {
+        Self { time_of_day, weekday: None, day_of_month: None }
+    }
+
+    pub fn weekly(weekday: Weekday, time_of_day: NaiveTime) -> Self {
+        Self { time_of_day, weekday: Some(weekday), day_of_month: None }
+    }
+
+    /// Slot id of a run: its local date
+    pub fn slot_id(run_at: DateTime<Utc>, tz: Tz) -> String {
+        run_at.with_timezone(&tz).date_naive().format("%Y-%m-%d").to_string()
+    }
+
+    fn resolve(&self, date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+        resolve_local(tz, date.and_time(self.time_of_day))
+    }
+}
+
+impl DCAInterval {
+    /// Next anchored run, computed from the anchor rather than from the last run
+    ///
+    /// With no completed slot only future slots qualify. Otherwise the first
+    /// slot after the completed one is returned even if already due, so a run
+    /// missed while the process was down (by up to a day) is caught up once and
+    /// a slot that already ran is never repeated. `None` for sub-daily and cron
+    /// intervals, which are not anchored.
+    pub fn next_anchored_slot(
+        &self,
+        anchor: &DCAAnchor,
+        tz: Tz,
+        now: DateTime<Utc>,
+        last_completed_slot: Option<&str>,
+    ) -> Option<AnchoredSlot> {
+        let mut date = now.with_timezone(&tz).date_naive().pred_opt()?;
+
+        // Long enough to reach the next monthly slot
+        for _ in 0..64 {
+            if self.is_anchor_date(anchor, date) {
+                let slot_id = date.format("%Y-%m-%d").to_string();
+                let run_at = anchor.resolve(date, tz);
+                let eligible = match last_completed_slot {
+                    Some(last) => slot_id.as_str() > last,
+                    None => run_at > now,
+                };
+                if eligible {
+                    return Some(AnchoredSlot { slot_id, run_at });
+                }
+            }
+            date = date.succ_opt()?;
+        }
+
+        None
+    }
+
+    fn is_anchor_date(&self, anchor: &DCAAnchor, date: NaiveDate) -> bool {
+        let weekday = anchor.weekday.unwrap_or(Weekday::Mon);
+        match self {
+            DCAInterval::Daily => true,
+            DCAInterval::Weekly => date.weekday() == weekday,
+            DCAInterval::Biweekly => {
+                // Even weeks counted from the first Monday of the epoch, so parity survives restarts
+                let epoch_monday = NaiveDate::from_ymd_opt(1970, 1, 5).unwrap_or_default();
+                date.weekday() == weekday && (date - epoch_monday).num_days().div_euclid(7) % 2 == 0
+            }
+            DCAInterval::Monthly => {
+                let day = anchor.day_of_month.unwrap_or(1).clamp(1, 31);
+                date.day() == day.min(days_in_month(date))
+            }
+            DCAInterval::Minutes(_) | DCAInterval::Hourly | DCAInterval::Custom { .. } => false,
+        }
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(28)
+}
+
 /// Different DCA strategy types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DCAStrategyType {
@@ -85,7 +220,7 @@ pub struct GridLevel {
 }
 
 /// DCA strategy status
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DCAStatus {
     Active,
     Paused,
@@ -94,6 +229,19 @@ pub enum DCAStatus {
     Failed,
 }
 
+impl DCAStatus {
+    /// Stored form in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Paused => "paused",
+            Self::Completed => "completed",
+            Self::Cancelled => "cancelled",
+            Self::Failed => "failed",
+        }
+    }
+}
+
 /// Risk management parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskParameters {
@@ -115,6 +263,18 @@ pub struct AdvancedDCAConfig {
     pub correlation_analysis: bool,       // Consider market correlation
     pub time_decay_factor: Option<f64>,  // Reduce amounts over time
     pub acceleration_factor: Option<f64>, // Increase amounts during opportunities
+    #[serde(default = "default_min_size_multiplier")]
+    pub min_size_multiplier: f64,         // Smallest dynamic size, as a multiple of the base amount
+    #[serde(default = "default_max_size_multiplier")]
+    pub max_size_multiplier: f64,         // Largest dynamic size
+}
+
+fn default_min_size_multiplier() -> f64 {
+    0.5
+}
+
+fn default_max_size_multiplier() -> f64 {
+    2.0
 }
 
 /// DCA execution record
@@ -133,6 +293,11 @@ pub struct DCAExecution {
     pub market_conditions: MarketConditions,
     pub success: bool,
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub report: ExecutionReport,
+    /// Set when risk-adjusted sizing resized this buy
+    #[serde(default)]
+    pub sizing: Option<SizingAdjustment>,
 }
 
 /// Reason for execution
@@ -177,6 +342,17 @@ pub struct DCAPerformance {
     pub risk_adjusted_return: Option<f64>,
 }
 
+/// Outcome of one strategy run short of an error
+enum StrategyRun {
+    Filled(DCAExecution),
+    /// Risk parameters declined to trade
+    Refused(String),
+    /// The owner's token rules don't allow buying the output token
+    Blocked(TradeBlocked),
+    /// An operator has trading paused
+    Paused,
+}
+
 impl DCAEngine {
     /// Create new DCA engine
     pub fn new(
@@ -192,18 +368,71 @@ impl DCAEngine {
             price_client,
             database,
             telemetry,
+            timezones: Arc::new(TimezoneManager::new("UTC")),
             strategies: Arc::new(RwLock::new(HashMap::new())),
             execution_history: Arc::new(RwLock::new(HashMap::new())),
+            notifier: None,
+            fee_estimator: None,
+            trade_defaults: None,
+            risk: None,
+            trade_gate: None,
+            pause: None,
         }
     }
     
+    /// Publish fills, failures and risk refusals at each user's verbosity
+    pub fn with_notifier(mut self, notifier: Arc<ExecutionNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+    
+    /// Estimate network fees from recent prioritization fees
+    pub fn with_fee_estimator(mut self, fee_estimator: Arc<PriorityFeeEstimator>) -> Self {
+        self.fee_estimator = Some(fee_estimator);
+        self
+    }
+    
+    /// Quote at each user's default slippage, within the strategy's cap, and pay their fee level
+    pub fn with_trade_defaults(mut self, trade_defaults: Arc<dyn TradeDefaultPreferences>) -> Self {
+        self.trade_defaults = Some(trade_defaults);
+        self
+    }
+    
+    /// Resize buys of strategies with dynamic sizing from regime and drawdown
+    pub fn with_risk_manager(mut self, risk: Arc<RiskBasedDCAManager>) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+    
+    /// Skip runs whose output token the owner's token rules don't allow
+    pub fn with_trade_gate(mut self, trade_gate: Arc<TradeGate>) -> Self {
+        self.trade_gate = Some(trade_gate);
+        self
+    }
+    
+    /// Hold every run while an operator has trading paused
+    pub fn with_pause(mut self, pause: Arc<dyn TradingPause>) -> Self {
+        self.pause = Some(pause);
+        self
+    }
+    
+    /// Share user timezones with the scheduler so anchored runs use local time
+    pub fn with_timezones(mut self, timezones: Arc<TimezoneManager>) -> Self {
+        self.timezones = timezones;
+        self
+    }
+    
+    pub fn timezones(&self) -> Arc<TimezoneManager> {
+        self.timezones.clone()
+    }
+    
     /// Create a new DCA strategy
     pub async fn create_strategy(&self, mut strategy: DCAStrategy) -> Result<String> {
         // Validate strategy
         self.validate_strategy(&strategy).await?;
         
         // Set next execution time
-        strategy.next_execution = self.calculate_next_execution(&strategy.interval)?;
+        strategy.next_execution = self.schedule_next(&strategy, Utc::now()).await?;
         strategy.status = DCAStatus::Active;
         strategy.created_at = Utc::now();
         
@@ -221,6 +450,268 @@ impl DCAEngine {
         Ok(strategy_id)
     }
     
+    /// Reload persisted strategies after a restart
+    ///
+    /// Anchored strategies recompute their next run from the anchor and the
+    /// last completed slot, so nothing drifts and no slot runs twice.
+    pub async fn restore_strategies(&self, restored: Vec<DCAStrategy>) -> Result<usize> {
+        let now = Utc::now();
+        let mut count = 0;
+        
+        for mut strategy in restored {
+            if strategy.anchor.is_some() && strategy.status == DCAStatus::Active {
+                strategy.next_execution = self.schedule_next(&strategy, now).await?;
+            }
+            self.strategies.write().await.insert(strategy.strategy_id.clone(), strategy);
+            count += 1;
+        }
+        
+        info!("💰 Restored {} DCA strategies", count);
+        Ok(count)
+    }
+    
+    /// Reload active and paused strategies from the database after a restart
+    pub async fn load_strategies(&self) -> Result<usize> {
+        let statuses = [DCAStatus::Active.as_str(), DCAStatus::Paused.as_str()];
+        let rows = self.database.get_dca_strategies_by_status(&statuses).await?;
+        
+        let strategies = rows.iter()
+            .filter_map(|row| match serde_json::from_str::<DCAStrategy>(row) {
+                Ok(strategy) => Some(strategy),
+                Err(e) => {
+                    warn!("💰 Skipping unreadable stored DCA strategy: {}", e);
+                    None
+                }
+            })
+            .collect();
+        
+        self.restore_strategies(strategies).await
+    }
+    
+    /// Create or update a strategy carried over from another system
+    ///
+    /// The id is kept so re-imports update in place, and progress of an
+    /// existing strategy is preserved. Nothing executes here: the first run
+    /// is always scheduled in the future. Returns true when the strategy is new.
+    pub async fn import_strategy(&self, mut strategy: DCAStrategy) -> Result<bool> {
+        if strategy.amount_per_execution <= Decimal::ZERO || strategy.total_amount <= Decimal::ZERO {
+            return Err(BotError::validation("DCA amounts must be positive".to_string()).into());
+        }
+        
+        let now = Utc::now();
+        let existing = self.strategies.read().await.get(&strategy.strategy_id).cloned();
+        let created = existing.is_none();
+        if let Some(existing) = existing {
+            strategy.created_at = existing.created_at;
+            strategy.started_at = existing.started_at;
+            strategy.execution_count = existing.execution_count;
+            strategy.last_completed_slot = existing.last_completed_slot;
+            if existing.next_execution > now {
+                strategy.next_execution = existing.next_execution;
+            }
+        }
+        if strategy.next_execution <= now {
+            strategy.next_execution = self.schedule_next(&strategy, now).await?;
+        }
+        
+        self.store_strategy(&strategy).await?;
+        self.strategies.write().await.insert(strategy.strategy_id.clone(), strategy);
+        
+        Ok(created)
+    }
+
+    /// Strategies owned by a user, soonest run first
+    pub async fn get_user_strategies(&self, user_id: i64) -> Vec<DCAStrategy> {
+        let strategies = self.strategies.read().await;
+        let mut owned: Vec<DCAStrategy> = strategies.values()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect();
+        owned.sort_by_key(|s| s.next_execution);
+        owned
+    }
+    
+    /// Strategies still scheduled to run, across every user
+    pub async fn active_strategy_count(&self) -> usize {
+        self.strategies.read().await.values()
+            .filter(|s| s.status == DCAStatus::Active)
+            .count()
+    }
+    
+    /// Stop a strategy from running until it is resumed
+    pub async fn pause_strategy(&self, strategy_id: &str) -> Result<DCAStrategy> {
+        let mut strategies = self.strategies.write().await;
+        let strategy = strategies.get_mut(strategy_id)
+            .ok_or_else(|| BotError::not_found(format!("Strategy {} not found", strategy_id)))?;
+        
+        match strategy.status {
+            DCAStatus::Active => {
+                strategy.status = DCAStatus::Paused;
+                self.store_strategy(strategy).await?;
+                info!("💰 Paused DCA strategy {}", strategy_id);
+            }
+            DCAStatus::Paused => {}
+            status => {
+                return Err(BotError::validation(format!("A {} strategy cannot be paused", status.as_str())).into());
+            }
+        }
+        
+        Ok(strategy.clone())
+    }
+    
+    /// Resume a paused strategy; runs missed while paused are dropped, not caught up
+    pub async fn resume_strategy(&self, strategy_id: &str) -> Result<DCAStrategy> {
+        let mut strategies = self.strategies.write().await;
+        let strategy = strategies.get_mut(strategy_id)
+            .ok_or_else(|| BotError::not_found(format!("Strategy {} not found", strategy_id)))?;
+        
+        match strategy.status {
+            DCAStatus::Paused => {
+                let now = Utc::now();
+                if strategy.next_execution <= now {
+                    // Without a completed slot the anchor only looks forward
+                    let fresh = DCAStrategy { last_completed_slot: None, ..strategy.clone() };
+                    strategy.next_execution = self.schedule_next(&fresh, now).await?;
+                }
+                strategy.status = DCAStatus::Active;
+                self.store_strategy(strategy).await?;
+                info!("💰 Resumed DCA strategy {}, next run {}", strategy_id, strategy.next_execution);
+            }
+            DCAStatus::Active => {}
+            status => {
+                return Err(BotError::validation(format!("A {} strategy cannot be resumed", status.as_str())).into());
+            }
+        }
+        
+        Ok(strategy.clone())
+    }
+    
+    /// Switch risk-adjusted sizing on or off for one strategy
+    pub async fn toggle_dynamic_sizing(&self, strategy_id: &str) -> Result<DCAStrategy> {
+        let mut strategies = self.strategies.write().await;
+        let strategy = strategies.get_mut(strategy_id)
+            .ok_or_else(|| BotError::not_found(format!("Strategy {} not found", strategy_id)))?;
+
+        strategy.advanced_config.dynamic_sizing = !strategy.advanced_config.dynamic_sizing;
+        self.store_strategy(strategy).await?;
+        info!("💰 Dynamic sizing {} for DCA strategy {}",
+            if strategy.advanced_config.dynamic_sizing { "enabled" } else { "disabled" }, strategy_id);
+
+        Ok(strategy.clone())
+    }
+
+    /// Drop the next due occurrence of an active or paused strategy
+    pub async fn skip_next_execution(&self, strategy_id: &str) -> Result<DCAStrategy> {
+        let mut strategies = self.strategies.write().await;
+        let strategy = strategies.get_mut(strategy_id)
+            .ok_or_else(|| BotError::not_found(format!("Strategy {} not found", strategy_id)))?;
+        
+        if !matches!(strategy.status, DCAStatus::Active | DCAStatus::Paused) {
+            return Err(BotError::validation(format!("A {} strategy has no next run", strategy.status.as_str())).into());
+        }
+        
+        strategy.skip_next = true;
+        self.store_strategy(strategy).await?;
+        info!("💰 DCA strategy {} will skip its run at {}", strategy_id, strategy.next_execution);
+        
+        Ok(strategy.clone())
+    }
+    
+    /// Pause every active strategy a user owns; returns how many changed
+    pub async fn pause_user_strategies(&self, user_id: i64) -> Result<usize> {
+        let mut count = 0;
+        for strategy in self.get_user_strategies(user_id).await {
+            if strategy.status == DCAStatus::Active {
+                self.pause_strategy(&strategy.strategy_id).await?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+    
+    /// Resume every paused strategy a user owns; returns how many changed
+    pub async fn resume_user_strategies(&self, user_id: i64) -> Result<usize> {
+        let mut count = 0;
+        for strategy in self.get_user_strategies(user_id).await {
+            if strategy.status == DCAStatus::Paused {
+                self.resume_strategy(&strategy.strategy_id).await?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+    
+    /// Current status, or `None` for an unknown strategy
+    pub async fn strategy_status(&self, strategy_id: &str) -> Option<DCAStatus> {
+        self.strategies.read().await.get(strategy_id).map(|s| s.status)
+    }
+    
+    pub async fn strategy(&self, strategy_id: &str) -> Option<DCAStrategy> {
+        self.strategies.read().await.get(strategy_id).cloned()
+    }
+    
+    /// Input spent, output received and fill count over a strategy's successful runs
+    pub async fn accumulated(&self, strategy_id: &str) -> (Decimal, Decimal, usize) {
+        let history = self.execution_history.read().await;
+        history.get(strategy_id)
+            .map(|executions| executions.iter()
+                .filter(|e| e.success)
+                .fold((Decimal::ZERO, Decimal::ZERO, 0), |(spent, received, fills), e| {
+                    (spent + e.input_amount, received + e.output_amount, fills + 1)
+                }))
+            .unwrap_or((Decimal::ZERO, Decimal::ZERO, 0))
+    }
+    
+    /// Run a strategy for the scheduler, which reports the outcome through its own channel
+    pub async fn execute_scheduled(&self, strategy_id: &str) -> Result<DCAExecution> {
+        let strategy = self.get_strategy(strategy_id).await?;
+        match self.run_strategy(&strategy).await? {
+            StrategyRun::Filled(execution) => {
+                self.update_strategy_next_execution(strategy_id, true).await?;
+                Ok(execution)
+            }
+            StrategyRun::Refused(reason) => {
+                Err(BotError::trading(format!("Risk parameters exceeded: {}", reason)).into())
+            }
+            StrategyRun::Blocked(blocked) => Err(BotError::security(blocked.to_string()).into()),
+            StrategyRun::Paused => Err(BotError::trading(PAUSED_FOR_MAINTENANCE).into()),
+        }
+    }
+    
+    /// Clear a pending skip; true when the caller must drop this occurrence
+    pub async fn consume_skip(&self, strategy_id: &str) -> Result<bool> {
+        let mut strategies = self.strategies.write().await;
+        let Some(strategy) = strategies.get_mut(strategy_id).filter(|s| s.skip_next) else {
+            return Ok(false);
+        };
+        
+        strategy.skip_next = false;
+        self.store_strategy(strategy).await?;
+        info!("💰 Skipped one run of DCA strategy {}", strategy_id);
+        Ok(true)
+    }
+    
+    /// Cancel and drop every strategy a user owns; returns how many were removed
+    pub async fn remove_user_strategies(&self, user_id: i64) -> usize {
+        let mut strategies = self.strategies.write().await;
+        let owned: Vec<String> = strategies.values()
+            .filter(|s| s.user_id == user_id)
+            .map(|s| s.strategy_id.clone())
+            .collect();
+        for strategy_id in &owned {
+            strategies.remove(strategy_id);
+        }
+        drop(strategies);
+        
+        let mut history = self.execution_history.write().await;
+        for strategy_id in &owned {
+            history.remove(strategy_id);
+        }
+        
+        info!("💰 Removed {} DCA strategies for user {}", owned.len(), user_id);
+        owned.len()
+    }
+    
     /// Execute pending DCA strategies
     pub async fn execute_pending_strategies(&self) -> Result<u32> {
         let now = Utc::now();
@@ -236,10 +727,15 @@ impl DCAEngine {
         };
         
         for strategy in strategies {
+            if self.consume_skip(&strategy.strategy_id).await? {
+                self.update_strategy_next_execution(&strategy.strategy_id, false).await?;
+                continue;
+            }
+            
             match self.execute_strategy(&strategy).await {
                 Ok(_) => {
                     executed_count += 1;
-                    self.update_strategy_next_execution(&strategy.strategy_id).await?;
+                    self.update_strategy_next_execution(&strategy.strategy_id, true).await?;
                 },
                 Err(e) => {
                     error!("💰 Failed to execute DCA strategy {}: {}", strategy.strategy_id, e);
@@ -255,37 +751,119 @@ impl DCAEngine {
         Ok(executed_count)
     }
     
-    /// Execute a specific DCA strategy
+    /// Execute a specific DCA strategy, notifying the owner of the outcome
     pub async fn execute_strategy(&self, strategy: &DCAStrategy) -> Result<DCAExecution> {
+        let result = self.run_strategy(strategy).await;
+        let kind = match &result {
+            Ok(StrategyRun::Filled(execution)) => NoticeKind::Fill(Fill {
+                mint: strategy.output_token.clone(),
+                symbol: TokenResolver::get_symbol(&strategy.output_token),
+                quantity: execution.output_amount.to_f64().unwrap_or(0.0),
+                price: execution.price_at_execution.to_f64().unwrap_or(0.0),
+                fee_sol: execution.gas_fees.to_f64().unwrap_or(0.0),
+            }),
+            Ok(StrategyRun::Refused(reason)) => NoticeKind::RiskRefusal { reason: reason.clone() },
+            Ok(StrategyRun::Blocked(blocked)) => NoticeKind::RiskRefusal { reason: blocked.to_string() },
+            Ok(StrategyRun::Paused) => NoticeKind::RiskRefusal { reason: PAUSED_FOR_MAINTENANCE.to_string() },
+            Err(e) => NoticeKind::Failure { reason: e.to_string() },
+        };
+        self.notify(strategy, kind).await;
+        
+        match result? {
+            StrategyRun::Filled(execution) => Ok(execution),
+            StrategyRun::Refused(reason) => {
+                Err(BotError::trading(format!("Risk parameters exceeded: {}", reason)).into())
+            }
+            StrategyRun::Blocked(blocked) => Err(BotError::security(blocked.to_string()).into()),
+            StrategyRun::Paused => Err(BotError::trading(PAUSED_FOR_MAINTENANCE).into()),
+        }
+    }
+    
+    async fn notify(&self, strategy: &DCAStrategy, kind: NoticeKind) {
+        let Some(notifier) = &self.notifier else { return };
+        let source = match strategy.strategy_type {
+            DCAStrategyType::Grid { .. } => ExecutionSource::Grid,
+            _ => ExecutionSource::Dca,
+        };
+        notifier.notify(ExecutionNotice {
+            user_id: strategy.user_id,
+            source,
+            label: strategy.name.clone(),
+            strategy_id: Some(strategy.strategy_id.clone()),
+            order_id: None,
+            kind,
+            at: Utc::now(),
+        }).await;
+    }
+    
+    async fn run_strategy(&self, strategy: &DCAStrategy) -> Result<StrategyRun> {
         let _span = self.telemetry.as_ref().map(|t| 
             t.create_trading_span("dca_execution", Some(&format!("{}/{}", strategy.input_token, strategy.output_token)))
         );
         
         debug!("💰 Executing DCA strategy: {}", strategy.strategy_id);
         
+        if let Some(pause) = &self.pause {
+            if pause.trading_paused().await {
+                info!("💰 Trading paused, holding strategy {}", strategy.strategy_id);
+                return Ok(StrategyRun::Paused);
+            }
+        }
+        
+        if let Some(gate) = &self.trade_gate {
+            if let Some(blocked) = gate.evaluate(strategy.user_id, &strategy.output_token).await {
+                warn!("💰 Token rules block strategy {}, skipping execution", strategy.strategy_id);
+                return Ok(StrategyRun::Blocked(blocked));
+            }
+        }
+        
         // Get current market conditions
         let market_conditions = self.get_market_conditions(&strategy.output_token).await?;
         
         // Check risk parameters
-        if !self.check_risk_parameters(strategy, &market_conditions).await? {
-            warn!("💰 Risk parameters exceeded for strategy {}, skipping execution", strategy.strategy_id);
-            return Err(BotError::trading("Risk parameters exceeded".to_string()).into());
+        if let Some(reason) = self.check_risk_parameters(strategy, &market_conditions).await? {
+            warn!("💰 Risk parameters exceeded for strategy {}, skipping execution: {}", strategy.strategy_id, reason);
+            return Ok(StrategyRun::Refused(reason));
         }
         
         // Calculate execution amount based on strategy type
-        let execution_amount = self.calculate_execution_amount(strategy, &market_conditions).await?;
+        let mut execution_amount = self.calculate_execution_amount(strategy, &market_conditions).await?;
+        
+        // Scale it by the risk recommendation when the strategy allows
+        let sizing = match &self.risk {
+            Some(risk) if strategy.advanced_config.dynamic_sizing => {
+                match risk.get_risk_adjusted_recommendation(strategy).await {
+                    Ok(recommendation) => Some(recommendation.sizing),
+                    Err(e) => {
+                        warn!("💰 No risk recommendation for strategy {}, buying the base amount: {}", strategy.strategy_id, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        if let Some(sizing) = &sizing {
+            execution_amount = (execution_amount * Decimal::from_f64_retain(sizing.multiplier).unwrap_or(Decimal::ONE)).round();
+            debug!("💰 Strategy {} sized at {:.2}x ({:?})", strategy.strategy_id, sizing.multiplier, sizing.regime);
+        }
         
         if execution_amount <= Decimal::ZERO {
             warn!("💰 Calculated execution amount is zero for strategy {}", strategy.strategy_id);
             return Err(BotError::trading("Execution amount is zero".to_string()).into());
         }
         
+        let defaults = match &self.trade_defaults {
+            Some(preferences) => Some(preferences.trade_defaults(strategy.user_id).await),
+            None => None,
+        };
+        let max_slippage_bps = strategy.risk_parameters.max_slippage_bps;
+        
         // Get quote from Jupiter
         let quote_request = QuoteRequestV6 {
             input_mint: strategy.input_token.clone(),
             output_mint: strategy.output_token.clone(),
             amount: execution_amount.to_u64().unwrap_or(0),
-            slippage_bps: strategy.risk_parameters.max_slippage_bps,
+            slippage_bps: defaults.map_or(max_slippage_bps, |defaults| defaults.slippage_bps.min(max_slippage_bps)),
             swap_mode: Some(SwapMode::ExactIn),
             dexes: None,
             exclude_dexes: None,
@@ -306,11 +884,39 @@ impl DCAEngine {
             .to_u16().unwrap_or(u16::MAX);
             
         if slippage > strategy.risk_parameters.max_slippage_bps {
-            return Err(BotError::trading(format!(
-                "Slippage {} exceeds maximum {}", slippage, strategy.risk_parameters.max_slippage_bps
-            )).into());
+            return Err(BotError::slippage_exceeded(strategy.risk_parameters.max_slippage_bps, slippage).into());
         }
         
+        // Output per unit of input: like a sell, a lower fill than the market price is slippage
+        let report = ExecutionReport::quoted(
+            market_conditions.token_price.to_f64().unwrap_or(0.0),
+            RouteSummary::from(&quote),
+        )
+        .filled((actual_output / execution_amount).to_f64().unwrap_or(0.0), TradeType::Sell)
+        .simulated(true)
+        .with_idempotency_key(format!("dca:{}:{}", strategy.strategy_id, strategy.execution_count + 1));
+        
+        // Scheduled buys aren't racing anyone, so without user settings they pay the median recent fee
+        let priority = defaults.map_or(TransactionPriority::Normal, |defaults| defaults.priority);
+        let (gas_fees, report) = match &self.fee_estimator {
+            Some(estimator) => {
+                let price = estimator.estimate(priority).await;
+                let budget = ComputeBudget::from_simulation(
+                    None,
+                    price,
+                    BudgetUrgency::Standard,
+                    &ComputeBudgetConfig::default(),
+                );
+                let fees = ExecutionFees {
+                    network_fee_sol: BASE_FEE_LAMPORTS as f64 / 1e9,
+                    ..ExecutionFees::default()
+                };
+                let lamports = BASE_FEE_LAMPORTS + budget.priority_fee_lamports();
+                (Decimal::new(lamports as i64, 9), report.with_fees(fees).with_compute_budget(&budget))
+            }
+            None => (Decimal::from_str("0.001").unwrap(), report), // Estimated
+        };
+        
         // Execute the trade (this would integrate with your existing swap logic)
         // For now, we'll simulate execution
         let execution = DCAExecution {
@@ -321,12 +927,14 @@ impl DCAEngine {
             output_amount: actual_output,
             price_at_execution: market_conditions.token_price,
             slippage_bps: slippage,
-            gas_fees: Decimal::from_str("0.001").unwrap(), // Estimated
+            gas_fees,
             transaction_signature: None, // Would be filled after actual execution
             execution_reason: self.determine_execution_reason(strategy, &market_conditions),
             market_conditions: market_conditions.clone(),
             success: true,
             error_message: None,
+            report,
+            sizing,
         };
         
         // Store execution record
@@ -342,7 +950,7 @@ impl DCAEngine {
             execution.input_amount, strategy.input_token,
             execution.output_amount, strategy.output_token);
         
-        Ok(execution)
+        Ok(StrategyRun::Filled(execution))
     }
     
     /// Get DCA strategy performance metrics
@@ -423,6 +1031,13 @@ impl DCAEngine {
             return Err(BotError::validation("Amount per execution cannot exceed total amount".to_string()).into());
         }
         
+        let config = &strategy.advanced_config;
+        if !(config.min_size_multiplier > 0.0 && config.min_size_multiplier <= config.max_size_multiplier) {
+            return Err(BotError::validation(
+                "Size multipliers need 0 < min <= max".to_string()
+            ).into());
+        }
+        
         // Validate tokens exist
         let token_prices = self.price_client
             .get_prices(vec![strategy.input_token.clone(), strategy.output_token.clone()])
@@ -444,6 +1059,26 @@ impl DCAEngine {
         Ok(())
     }
     
+    /// Next run for a strategy: from its anchor or cron expression when set, else relative to now
+    async fn schedule_next(&self, strategy: &DCAStrategy, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        if let DCAInterval::Custom { cron_expression } = &strategy.interval {
+            let tz = self.timezones.get_user_timezone(strategy.user_id).await;
+            return next_cron_run(cron_expression, tz, now);
+        }
+        if let Some(anchor) = &strategy.anchor {
+            let tz = self.timezones.get_user_timezone(strategy.user_id).await;
+            if let Some(slot) = strategy.interval.next_anchored_slot(
+                anchor,
+                tz,
+                now,
+                strategy.last_completed_slot.as_deref(),
+            ) {
+                return Ok(slot.run_at);
+            }
+        }
+        self.calculate_next_execution(&strategy.interval)
+    }
+    
     /// Calculate next execution time based on interval
     fn calculate_next_execution(&self, interval: &DCAInterval) -> Result<DateTime<Utc>> {
         let now = Utc::now();
@@ -472,7 +1107,7 @@ impl DCAEngine {
             
         let price_data = prices.prices
             .get(token_mint)
-            .ok_or_else(|| BotError::trading(format!("Price data not found for token {}", token_mint)))?;
+            .ok_or_else(|| BotError::not_found(format!("Price data not found for token {}", token_mint)))?;
         
         Ok(MarketConditions {
             token_price: Decimal::from_f64_retain(price_data.usd_price)
@@ -486,25 +1121,29 @@ impl DCAEngine {
         })
     }
     
-    /// Check if risk parameters allow execution
-    async fn check_risk_parameters(&self, strategy: &DCAStrategy, conditions: &MarketConditions) -> Result<bool> {
+    /// Why risk parameters refuse execution, or `None` when it may proceed
+    async fn check_risk_parameters(&self, strategy: &DCAStrategy, conditions: &MarketConditions) -> Result<Option<String>> {
         // Check volatility threshold
         if let Some(volatility) = conditions.volatility {
             if volatility > strategy.risk_parameters.volatility_threshold {
-                return Ok(false);
+                return Ok(Some(format!(
+                    "Volatility {:.2} above threshold {:.2}", volatility, strategy.risk_parameters.volatility_threshold
+                )));
             }
         }
         
         // Check liquidity threshold
         if let Some(volume_24h) = conditions.volume_24h {
             if Decimal::from(volume_24h) < strategy.risk_parameters.liquidity_threshold {
-                return Ok(false);
+                return Ok(Some(format!(
+                    "24h volume {} below liquidity threshold {}", volume_24h, strategy.risk_parameters.liquidity_threshold
+                )));
             }
         }
         
         // Additional risk checks would go here
         
-        Ok(true)
+        Ok(None)
     }
     
     /// Calculate execution amount based on strategy type
@@ -554,9 +1193,15 @@ impl DCAEngine {
     /// - Risk management
     /// - Market analysis
     
-    async fn store_strategy(&self, _strategy: &DCAStrategy) -> Result<()> {
-        // Database storage implementation
-        Ok(())
+    async fn store_strategy(&self, strategy: &DCAStrategy) -> Result<()> {
+        let data = serde_json::to_string(strategy)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize strategy {}: {}", strategy.strategy_id, e)))?;
+        self.database.upsert_dca_strategy(
+            &strategy.strategy_id,
+            strategy.user_id,
+            strategy.status.as_str(),
+            &data,
+        ).await
     }
     
     async fn store_execution(&self, _execution: &DCAExecution) -> Result<()> {
@@ -576,11 +1221,18 @@ impl DCAEngine {
         Ok(history.get(strategy_id).cloned().unwrap_or_default())
     }
     
-    async fn update_strategy_next_execution(&self, strategy_id: &str) -> Result<()> {
+    /// Move past the due occurrence; a skipped one does not count toward `max_executions`
+    async fn update_strategy_next_execution(&self, strategy_id: &str, executed: bool) -> Result<()> {
         let mut strategies = self.strategies.write().await;
         if let Some(strategy) = strategies.get_mut(strategy_id) {
-            strategy.next_execution = self.calculate_next_execution(&strategy.interval)?;
-            strategy.execution_count += 1;
+            if strategy.anchor.is_some() {
+                let tz = self.timezones.get_user_timezone(strategy.user_id).await;
+                strategy.last_completed_slot = Some(DCAAnchor::slot_id(strategy.next_execution, tz));
+            }
+            strategy.next_execution = self.schedule_next(strategy, Utc::now()).await?;
+            if executed {
+                strategy.execution_count += 1;
+            }
             
             // Check if strategy should be completed
             if let Some(max_executions) = strategy.max_executions {
@@ -588,6 +1240,9 @@ impl DCAEngine {
                     strategy.status = DCAStatus::Completed;
                 }
             }
+            
+            // Persist the completed slot before the next tick can see it
+            self.store_strategy(strategy).await?;
         }
         Ok(())
     }
@@ -658,8 +1313,17 @@ impl DCAStrategy {
             end_date: None,
             risk_parameters: RiskParameters::default(),
             advanced_config: AdvancedDCAConfig::default(),
+            anchor: None,
+            last_completed_slot: None,
+            skip_next: false,
         }
     }
+    
+    /// Anchor runs to a local wall-clock time in the user's timezone
+    pub fn with_anchor(mut self, anchor: DCAAnchor) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
 }
 
 impl Default for RiskParameters {
@@ -678,13 +1342,15 @@ impl Default for RiskParameters {
 impl Default for AdvancedDCAConfig {
     fn default() -> Self {
         Self {
-            dynamic_sizing: false,
+            dynamic_sizing: true,
             fear_greed_factor: false,
             social_sentiment_factor: false,
             technical_analysis: false,
             correlation_analysis: false,
             time_decay_factor: None,
             acceleration_factor: None,
+            min_size_multiplier: default_min_size_multiplier(),
+            max_size_multiplier: default_max_size_multiplier(),
         }
     }
 }
\ No newline at end of file
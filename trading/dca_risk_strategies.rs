@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc, Duration};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
@@ -130,7 +131,7 @@ pub struct MarketRegimeDetector {
 }
 
 /// Market regime types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MarketRegime {
     Bull {
         strength: f64,
@@ -188,8 +189,88 @@ pub struct RiskAdjustedRecommendation {
     pub market_conditions: MarketConditions,
     pub risk_factors: Vec<RiskFactor>,
     pub hedging_suggestions: Vec<HedgingSuggestion>,
+    /// Multiplier behind `recommended_amount` and why
+    pub sizing: SizingAdjustment,
+}
+
+/// How risk adjustment resized one DCA buy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizingAdjustment {
+    /// Applied to the strategy's amount, within its bounds
+    pub multiplier: f64,
+    pub regime: MarketRegime,
+    /// Below the recent peak, in percent (zero or negative)
+    pub drawdown_pct: f64,
+    /// What moved the size; empty when nothing did
+    pub reasons: Vec<String>,
 }
 
+impl SizingAdjustment {
+    /// Scale up in fear and drawdowns and down in euphoria, on top of the risk
+    /// model's factor, then clamp to the strategy's `(min, max)` multipliers
+    pub fn compute(
+        regime: MarketRegime,
+        drawdown_pct: f64,
+        fear_greed_index: Option<u8>,
+        model_factor: f64,
+        (min_multiplier, max_multiplier): (f64, f64),
+    ) -> Self {
+        let mut factor = model_factor;
+        let mut reasons = Vec::new();
+        if model_factor < 1.0 {
+            reasons.push("high volatility".to_string());
+        }
+
+        if drawdown_pct <= -DRAWDOWN_TRIGGER_PCT {
+            factor *= 1.0 + drawdown_pct.abs() / 100.0 * DRAWDOWN_SCALE;
+            reasons.push(format!("{:.0}% drawdown", drawdown_pct));
+        } else if let MarketRegime::Bear { strength, .. } = &regime {
+            factor *= 1.0 + 0.25 * strength;
+            reasons.push("bear market".to_string());
+        }
+        if let MarketRegime::Bull { strength, .. } = &regime {
+            factor *= 1.0 - 0.5 * strength;
+            reasons.push("euphoric rally".to_string());
+        }
+
+        match fear_greed_index {
+            Some(index) if index <= 25 => {
+                factor *= 1.2;
+                reasons.push(format!("extreme fear ({})", index));
+            }
+            Some(index) if index >= 75 => {
+                factor *= 0.8;
+                reasons.push(format!("extreme greed ({})", index));
+            }
+            _ => {}
+        }
+
+        let multiplier = ((factor * 100.0).round() / 100.0).clamp(min_multiplier, max_multiplier);
+        if multiplier == 1.0 {
+            reasons.clear();
+        }
+        Self { multiplier, regime, drawdown_pct, reasons }
+    }
+
+    /// e.g. `Bought 1.3x due to -18% drawdown`; `None` at the normal size
+    pub fn summary(&self) -> Option<String> {
+        (self.multiplier != 1.0).then(|| format!(
+            "Bought {:.1}x due to {}",
+            self.multiplier,
+            if self.reasons.is_empty() { "the risk model".to_string() } else { self.reasons.join(" and ") }
+        ))
+    }
+}
+
+/// Drawdown from the recent peak, in percent, that starts scaling buys up
+const DRAWDOWN_TRIGGER_PCT: f64 = 10.0;
+/// Extra size per unit of drawdown: -18% buys 1.27x
+const DRAWDOWN_SCALE: f64 = 1.5;
+/// Price change over the history that reads as a bull or bear regime
+const TREND_THRESHOLD: f64 = 0.15;
+/// Prices kept per token for regime detection
+const MAX_PRICE_HISTORY: usize = 500;
+
 /// Individual risk factors
 #[derive(Debug, Clone, Serialize)]
 pub struct RiskFactor {
@@ -296,6 +377,11 @@ impl RiskBasedDCAManager {
         Ok(updated_model)
     }
     
+    /// Feed a price into regime and drawdown detection
+    pub async fn record_price(&self, token_mint: &str, price: Decimal, at: DateTime<Utc>) {
+        self.market_regime_detector.record(token_mint, price, at).await;
+    }
+    
     /// Get risk-adjusted DCA recommendation
     pub async fn get_risk_adjusted_recommendation(
         &self,
@@ -308,15 +394,16 @@ impl RiskBasedDCAManager {
         // Get or create risk model
         let risk_model = self.get_or_create_risk_model(&strategy.output_token).await?;
         
+        // Get market conditions; the price joins the regime history
+        let market_conditions = self.get_current_market_conditions(&strategy.output_token).await?;
+        self.record_price(&strategy.output_token, market_conditions.token_price, Utc::now()).await;
+        
         // Detect current market regime
-        let market_regime = self.detect_market_regime(&strategy.output_token).await?;
+        let (market_regime, drawdown_pct) = self.detect_market_regime(&strategy.output_token).await?;
         
         // Calculate current volatility
         let volatility_metrics = self.calculate_volatility(&strategy.output_token).await?;
         
-        // Get market conditions
-        let market_conditions = self.get_current_market_conditions(&strategy.output_token).await?;
-        
         // Calculate risk-adjusted amount
         let base_amount = strategy.amount_per_execution;
         let risk_adjusted_amount = self.calculate_risk_adjusted_amount(
@@ -354,15 +441,30 @@ impl RiskBasedDCAManager {
             &risk_factors,
         );
         
+        // The regime and drawdown scale the model's size within the strategy's bounds
+        let model_factor = if base_amount > Decimal::ZERO {
+            (risk_adjusted_amount / base_amount).to_f64().unwrap_or(1.0)
+        } else {
+            1.0
+        };
+        let sizing = SizingAdjustment::compute(
+            market_regime,
+            drawdown_pct,
+            market_conditions.fear_greed_index,
+            model_factor,
+            (strategy.advanced_config.min_size_multiplier, strategy.advanced_config.max_size_multiplier),
+        );
+        
         let recommendation = RiskAdjustedRecommendation {
             strategy_id: strategy.strategy_id.clone(),
-            recommended_amount: risk_adjusted_amount,
+            recommended_amount: base_amount * Decimal::from_f64_retain(sizing.multiplier).unwrap_or(Decimal::ONE),
             confidence_level: risk_model.confidence_score,
             risk_score,
             execution_reason,
             market_conditions,
             risk_factors,
             hedging_suggestions,
+            sizing,
         };
         
         debug!("🎯 Generated risk-adjusted recommendation for strategy {}: {} {} (risk score: {:.2})", 
@@ -648,12 +750,15 @@ impl RiskBasedDCAManager {
         ).await
     }
     
-    async fn detect_market_regime(&self, _token_mint: &str) -> Result<MarketRegime> {
-        // Placeholder implementation
-        Ok(MarketRegime::Sideways {
-            volatility: 0.3,
-            range_bound: (Decimal::from(90), Decimal::from(110)),
-        })
+    /// Regime and drawdown from recorded prices; sideways until there's history
+    async fn detect_market_regime(&self, token_mint: &str) -> Result<(MarketRegime, f64)> {
+        Ok(self.market_regime_detector.detect(token_mint).await.unwrap_or((
+            MarketRegime::Sideways {
+                volatility: 0.3,
+                range_bound: (Decimal::from(90), Decimal::from(110)),
+            },
+            0.0,
+        )))
     }
     
     async fn calculate_volatility(&self, _token_mint: &str) -> Result<VolatilityMetrics> {
@@ -676,7 +781,7 @@ impl RiskBasedDCAManager {
             
         let price_data = prices.prices
             .get(token_mint)
-            .ok_or_else(|| BotError::trading(format!("Price data not found for token {}", token_mint)))?;
+            .ok_or_else(|| BotError::not_found(format!("Price data not found for token {}", token_mint)))?;
         
         Ok(MarketConditions {
             token_price: Decimal::from_f64_retain(price_data.usd_price)
@@ -765,6 +870,63 @@ impl RiskBasedDCAManager {
     }
 }
 
+impl MarketRegimeDetector {
+    async fn record(&self, token_mint: &str, price: Decimal, at: DateTime<Utc>) {
+        let mut history = self.price_history.write().await;
+        let prices = history.entry(token_mint.to_string()).or_default();
+        prices.push_back(PricePoint { timestamp: at, price, volume: None, returns: None, volatility: None });
+        while prices.len() > MAX_PRICE_HISTORY {
+            prices.pop_front();
+        }
+        drop(history);
+        self.regime_cache.write().await.remove(token_mint);
+    }
+    
+    async fn detect(&self, token_mint: &str) -> Option<(MarketRegime, f64)> {
+        let history = self.price_history.read().await;
+        let prices: Vec<PricePoint> = history.get(token_mint)?.iter().cloned().collect();
+        drop(history);
+        
+        let (regime, drawdown_pct) = Self::classify(&prices)?;
+        self.regime_cache.write().await.insert(token_mint.to_string(), regime.clone());
+        Some((regime, drawdown_pct))
+    }
+    
+    /// Regime and drawdown from the recent peak (percent) over prices, oldest first
+    ///
+    /// A move of 15% or more from the first price to the last is a bull or bear
+    /// market, full strength at 50%; anything smaller is sideways.
+    pub fn classify(prices: &[PricePoint]) -> Option<(MarketRegime, f64)> {
+        let (first, last) = (prices.first()?, prices.last()?);
+        if prices.len() < 2 || first.price <= Decimal::ZERO {
+            return None;
+        }
+        let peak = prices.iter().map(|p| p.price).max()?;
+        let low = prices.iter().map(|p| p.price).min()?;
+        
+        let change = (last.price / first.price).to_f64()? - 1.0;
+        let drawdown_pct = ((last.price / peak).to_f64()? - 1.0) * 100.0;
+        let duration_days = (last.timestamp - first.timestamp).num_days().max(0) as u32;
+        let trend_slope = change / (prices.len() - 1) as f64;
+        
+        let regime = if change >= TREND_THRESHOLD {
+            MarketRegime::Bull { strength: (change / 0.5).min(1.0), duration_days, trend_slope }
+        } else if change <= -TREND_THRESHOLD {
+            MarketRegime::Bear { strength: (-change / 0.5).min(1.0), duration_days, trend_slope }
+        } else {
+            let returns: Vec<f64> = prices.windows(2)
+                .filter(|pair| pair[0].price > Decimal::ZERO)
+                .filter_map(|pair| (pair[1].price / pair[0].price).to_f64().map(|ratio| ratio - 1.0))
+                .collect();
+            let mean = returns.iter().sum::<f64>() / returns.len().max(1) as f64;
+            let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len().max(1) as f64;
+            MarketRegime::Sideways { volatility: variance.sqrt(), range_bound: (low, peak) }
+        };
+        
+        Some((regime, drawdown_pct))
+    }
+}
+
 impl Default for RiskParameters {
     fn default() -> Self {
         Self {
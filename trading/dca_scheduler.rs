@@ -1,17 +1,21 @@
-use chrono::{DateTime, Utc, Duration, Timelike, Weekday, NaiveTime};
+use chrono::{DateTime, Datelike, Utc, Duration, Timelike, Weekday, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, BinaryHeap};
+use std::collections::{BTreeSet, HashMap, HashSet, BinaryHeap};
 use std::cmp::Reverse;
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use rust_decimal::Decimal;
+use tokio::sync::{broadcast, RwLock};
 use tokio::time::{sleep, Duration as TokioDuration};
 use tracing::{info, debug, warn, error};
 
 use crate::errors::{BotError, Result};
-use crate::trading::dca::{DCAEngine, DCAStrategy, DCAInterval};
+use crate::trading::dca::{DCAEngine, DCAExecution, DCAStrategy, DCAInterval, DCAStatus};
+use crate::trading::TokenResolver;
 use crate::telemetry::TelemetryService;
+use crate::monitoring::MetricsCollector;
 
 /// Advanced DCA scheduler with multiple scheduling strategies
 #[derive(Clone)]
@@ -20,9 +24,13 @@ pub struct DCAScheduler {
     telemetry: Option<Arc<TelemetryService>>,
     schedule_queue: Arc<RwLock<BinaryHeap<Reverse<ScheduledExecution>>>>,
     active_schedules: Arc<RwLock<HashMap<String, ScheduleConfig>>>,
+    /// Schedules held out of the queue while their strategy is paused
+    parked: Arc<RwLock<HashSet<String>>>,
+    executions: broadcast::Sender<DCAExecutionEvent>,
     timezone_manager: Arc<TimezoneManager>,
     market_hours: Arc<MarketHoursManager>,
     execution_stats: Arc<RwLock<ExecutionStats>>,
+    metrics: Option<Arc<MetricsCollector>>,
 }
 
 /// Scheduled execution entry
@@ -32,6 +40,27 @@ pub struct ScheduledExecution {
     pub strategy_id: String,
     pub execution_type: ExecutionType,
     pub priority: u8, // 0 = highest priority
+    /// `execute_at` on the schedule's wall clock
+    pub local_time: NaiveDateTime,
+    pub timezone: String,
+}
+
+impl ScheduledExecution {
+    pub fn regular(strategy_id: &str, execute_at: DateTime<Utc>, tz: Tz) -> Self {
+        Self {
+            execute_at,
+            strategy_id: strategy_id.to_string(),
+            execution_type: ExecutionType::Regular,
+            priority: 5, // Default priority
+            local_time: execute_at.with_timezone(&tz).naive_local(),
+            timezone: tz.name().to_string(),
+        }
+    }
+
+    /// Local run time for users, e.g. `Mon Mar 09, 09:00 America/New_York`
+    pub fn local_display(&self) -> String {
+        format!("{} {}", self.local_time.format("%a %b %d, %H:%M"), self.timezone)
+    }
 }
 
 /// Types of scheduled executions
@@ -52,6 +81,7 @@ pub struct ScheduleConfig {
     pub strategy_id: String,
     pub name: String,
     pub schedule_type: ScheduleType,
+    /// IANA name; empty follows the strategy owner's timezone
     pub timezone: String,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
@@ -59,7 +89,9 @@ pub struct ScheduleConfig {
     pub next_execution: DateTime<Utc>,
     pub execution_count: u64,
     pub max_executions: Option<u64>,
+    /// Local hours runs are restricted to
     pub execution_window: Option<TimeWindow>,
+    /// Only run while US equities (NYSE) are trading
     pub market_hours_only: bool,
     pub skip_weekends: bool,
     pub skip_holidays: bool,
@@ -75,7 +107,7 @@ pub enum ScheduleType {
         interval: DCAInterval,
         offset_minutes: Option<i32>,
     },
-    /// Cron-based scheduling
+    /// Cron-based scheduling on the local wall clock, see [`CronSchedule`]
     Cron { 
         expression: String,
         description: Option<String>,
@@ -108,6 +140,241 @@ pub enum ScheduleType {
     },
 }
 
+/// Cron subset evaluated on the local wall clock: `minute hour * * day-of-week`
+///
+/// Fields take `*`, numbers, `a-b` ranges, `/n` steps and comma lists;
+/// day-of-week is 0-7 (0 and 7 are Sunday) or `mon`..`sun`. Day-of-month and
+/// month must be `*`. Across DST changes each local slot runs once: a slot
+/// repeated by fall-back at its first occurrence, a slot skipped by
+/// spring-forward an hour later (merged with a slot already there).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    weekdays: Vec<Weekday>,
+}
+
+/// Day-of-week numbering used by cron
+const CRON_WEEKDAYS: [Weekday; 7] = [
+    Weekday::Sun, Weekday::Mon, Weekday::Tue, Weekday::Wed,
+    Weekday::Thu, Weekday::Fri, Weekday::Sat,
+];
+const DAY_NAMES: [&str; 7] = ["sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"];
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let invalid = |reason: &str| BotError::validation(format!("Invalid cron expression {}: {}", expression, reason));
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields.as_slice() else {
+            return Err(invalid("expected minute hour * * day-of-week").into());
+        };
+        if *day != "*" || *month != "*" {
+            return Err(invalid("day of month and month must be *").into());
+        }
+
+        let minutes = parse_cron_field(minute, 0, 59, false).ok_or_else(|| invalid("bad minute"))?;
+        let hours = parse_cron_field(hour, 0, 23, false).ok_or_else(|| invalid("bad hour"))?;
+        let weekdays = parse_cron_field(weekday, 0, 7, true).ok_or_else(|| invalid("bad day of week"))?;
+        let weekdays: BTreeSet<u32> = weekdays.into_iter().map(|day| day % 7).collect();
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes,
+            hours,
+            weekdays: weekdays.into_iter().map(|day| CRON_WEEKDAYS[day as usize]).collect(),
+        })
+    }
+
+    /// Plain schedules such as `daily at 9am`, `weekdays 18:30`, `mon,thu at 7:15pm`,
+    /// `hourly` or `cron 0 9 * * 1-5`
+    pub fn from_phrase(phrase: &str) -> Result<Self> {
+        let phrase = phrase.trim().to_lowercase();
+        if let Some(expression) = phrase.strip_prefix("cron ") {
+            return Self::parse(expression);
+        }
+
+        let mut words: Vec<String> = phrase.split_whitespace()
+            .filter(|word| !matches!(*word, "at" | "every" | "on"))
+            .map(str::to_string)
+            .collect();
+        // `9 am` reads as `9am`
+        if words.len() > 1 && matches!(words[words.len() - 1].as_str(), "am" | "pm") {
+            let suffix = words.pop().unwrap_or_default();
+            if let Some(time) = words.last_mut() {
+                time.push_str(&suffix);
+            }
+        }
+
+        let unknown = || BotError::validation(format!(
+            "Couldn't read the schedule \"{}\". Try \"daily at 9am\", \"weekdays at 18:30\" or \"mon,thu at 7pm\"",
+            phrase
+        ));
+        match words.as_slice() {
+            [hourly] if hourly == "hourly" => Self::parse("0 * * * *"),
+            [days, time] => {
+                let days = match days.as_str() {
+                    "daily" | "day" => "*".to_string(),
+                    "weekdays" | "weekday" => "1-5".to_string(),
+                    "weekends" | "weekend" => "0,6".to_string(),
+                    days => days.split(',')
+                        .map(|day| day_number(day).map(|n| n.to_string()))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(unknown)?
+                        .join(","),
+                };
+                let time = parse_clock(time).map_err(|_| unknown())?;
+                Self::parse(&format!("{} {} * * {}", time.minute(), time.hour(), days))
+            }
+            _ => Err(unknown().into()),
+        }
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// e.g. `daily at 09:00`, `weekdays at 08:30`, `every hour at :15 on weekends`
+    pub fn describe(&self) -> String {
+        let days = match self.weekdays.as_slice() {
+            all if all.len() == 7 => None,
+            [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri] => Some("weekdays".to_string()),
+            [Weekday::Sun, Weekday::Sat] => Some("weekends".to_string()),
+            days => Some(days.iter().map(|day| day.to_string()).collect::<Vec<_>>().join(", ")),
+        };
+        let times: Vec<String> = self.hours.iter()
+            .flat_map(|hour| self.minutes.iter().map(move |minute| format!("{:02}:{:02}", hour, minute)))
+            .collect();
+
+        match (times.as_slice(), days) {
+            ([time], days) => format!("{} at {}", days.as_deref().unwrap_or("daily"), time),
+            (_, days) => {
+                let when = if self.hours.len() == 24 && self.minutes.len() == 1 {
+                    format!("every hour at :{:02}", self.minutes[0])
+                } else if times.len() <= 3 {
+                    format!("daily at {}", times.join(", "))
+                } else {
+                    format!("{} times a day", times.len())
+                };
+                match days {
+                    Some(days) => format!("{} on {}", when, days),
+                    None => when,
+                }
+            }
+        }
+    }
+
+    /// First slot strictly after `after`, on the wall clock of `tz`
+    pub fn next_after(&self, after: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+        // From the day before, in case a shifted slot lands after midnight
+        let mut date = after.with_timezone(&tz).date_naive().pred_opt()?;
+
+        for _ in 0..9 {
+            if self.weekdays.contains(&date.weekday()) {
+                let next = self.hours.iter()
+                    .flat_map(|hour| self.minutes.iter().filter_map(move |minute| NaiveTime::from_hms_opt(*hour, *minute, 0)))
+                    .map(|time| resolve_local(tz, date.and_time(time)))
+                    .filter(|at| *at > after)
+                    .min();
+                if next.is_some() {
+                    return next;
+                }
+            }
+            date = date.succ_opt()?;
+        }
+
+        None
+    }
+}
+
+/// Values of one cron field, sorted; `None` when malformed or out of range
+fn parse_cron_field(field: &str, min: u32, max: u32, day_names: bool) -> Option<Vec<u32>> {
+    let value = |text: &str| match text.parse::<u32>() {
+        Ok(n) => Some(n),
+        Err(_) if day_names => day_number(text),
+        Err(_) => None,
+    };
+
+    let mut values = BTreeSet::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|step| *step > 0)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                None if step > 1 => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    (!values.is_empty()).then(|| values.into_iter().collect())
+}
+
+/// Cron number of a day name: `mon`, `monday` or `mondays`
+fn day_number(name: &str) -> Option<u32> {
+    let name = name.trim().to_lowercase();
+    let name = name.trim_end_matches('s');
+    if name.len() < 3 {
+        return None;
+    }
+    DAY_NAMES.iter().position(|day| day.starts_with(name)).map(|n| n as u32)
+}
+
+/// A clock time such as `9am`, `7:15pm`, `09:00` or `21:30`
+pub fn parse_clock(text: &str) -> Result<NaiveTime> {
+    let invalid = || BotError::validation(format!("Couldn't read the time {}; use 9am or 21:30", text));
+    let text = text.trim().to_lowercase();
+    let (clock, meridiem) = match (text.strip_suffix("am"), text.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, Some(0)),
+        (_, Some(clock)) => (clock, Some(12)),
+        _ => (text.as_str(), None),
+    };
+    let (hour, minute) = clock.trim().split_once(':').unwrap_or((clock.trim(), "0"));
+    let hour: u32 = hour.parse().map_err(|_| invalid())?;
+    let minute: u32 = minute.parse().map_err(|_| invalid())?;
+
+    let hour = match meridiem {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return Err(invalid().into()),
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(|| invalid().into())
+}
+
+/// UTC instant of a local wall-clock time: a time repeated by fall-back
+/// resolves to its first occurrence, one skipped by spring-forward to an hour later
+pub(crate) fn resolve_local(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(at) => at.with_timezone(&Utc),
+        LocalResult::Ambiguous(first, _) => first.with_timezone(&Utc),
+        LocalResult::None => tz.from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&local)),
+    }
+}
+
+/// Next run of a cron expression after `after` in `tz`; expressions with a
+/// seconds field go to the full cron parser
+pub(crate) fn next_cron_run(expression: &str, tz: Tz, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let next = if expression.split_whitespace().count() > 5 {
+        let schedule = Schedule::from_str(expression)
+            .map_err(|e| BotError::config(format!("Invalid cron expression: {}", e)))?;
+        schedule.after(&after.with_timezone(&tz)).next().map(|at| at.with_timezone(&Utc))
+    } else {
+        CronSchedule::parse(expression)?.next_after(after, tz)
+    };
+    next.ok_or_else(|| BotError::config("No future execution time found".to_string()).into())
+}
+
 /// Market events for scheduling
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MarketEvent {
@@ -195,13 +462,149 @@ pub struct TimeWindow {
     pub days_of_week: Vec<Weekday>,
 }
 
+impl TimeWindow {
+    /// Whether a local time falls inside; an end before the start wraps past midnight
+    /// and belongs to the day it opened on
+    pub fn contains(&self, local: NaiveDateTime) -> bool {
+        let time = local.time();
+        let opened_on = if self.start_time <= self.end_time {
+            (time >= self.start_time && time < self.end_time).then_some(local.date())
+        } else if time >= self.start_time {
+            Some(local.date())
+        } else if time < self.end_time {
+            local.date().pred_opt()
+        } else {
+            None
+        };
+        opened_on.is_some_and(|date| self.days_of_week.contains(&date.weekday()))
+    }
+
+    /// Next local time the window opens after `local`
+    pub fn next_open(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut date = local.date();
+        for _ in 0..8 {
+            let opens = date.and_time(self.start_time);
+            if opens > local && self.days_of_week.contains(&date.weekday()) {
+                return Some(opens);
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
 /// Notification configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationConfig {
     pub notify_on_execution: bool,
     pub notify_on_failure: bool,
     pub notify_on_conditions_met: bool,
+    /// Empty means the strategy owner's Telegram chat
     pub notification_channels: Vec<NotificationChannel>,
+    /// Local hours when fill reports are held back; `None` opts in to reports at any hour.
+    /// Failures are sent regardless.
+    #[serde(default = "default_quiet_hours")]
+    pub quiet_hours: Option<TimeWindow>,
+}
+
+impl NotificationConfig {
+    /// Telegram chats an execution report goes to at `local_now` in the owner's timezone
+    pub fn telegram_recipients(&self, user_id: i64, success: bool, local_now: NaiveDateTime) -> Vec<i64> {
+        let wanted = if success { self.notify_on_execution } else { self.notify_on_failure };
+        if !wanted {
+            return Vec::new();
+        }
+        if success && self.quiet_hours.as_ref().is_some_and(|window| window.contains(local_now)) {
+            return Vec::new();
+        }
+        if self.notification_channels.is_empty() {
+            return vec![user_id];
+        }
+        self.notification_channels.iter()
+            .filter_map(|channel| match channel {
+                NotificationChannel::Telegram { chat_id } => Some(*chat_id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// 22:00 to 08:00 every day
+fn default_quiet_hours() -> Option<TimeWindow> {
+    Some(TimeWindow {
+        start_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap_or_default(),
+        end_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap_or_default(),
+        days_of_week: vec![
+            Weekday::Mon, Weekday::Tue, Weekday::Wed,
+            Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun,
+        ],
+    })
+}
+
+/// What a scheduled run produced
+#[derive(Debug, Clone)]
+pub enum DCAExecutionOutcome {
+    Filled(DCAExecution),
+    Failed { reason: String },
+}
+
+/// Published after every scheduled run for the bot to report
+#[derive(Debug, Clone)]
+pub struct DCAExecutionEvent {
+    pub user_id: i64,
+    pub strategy_id: String,
+    pub strategy_name: String,
+    pub input_symbol: String,
+    pub output_symbol: String,
+    pub outcome: DCAExecutionOutcome,
+    /// Totals over every successful run so far, this one included
+    pub total_spent: Decimal,
+    pub total_received: Decimal,
+    pub fills: usize,
+    /// `None` once the schedule has finished
+    pub next_execution: Option<DateTime<Utc>>,
+    pub timezone: Tz,
+    /// Chats selected by the schedule's notification settings; empty when held back
+    pub recipients: Vec<i64>,
+}
+
+impl DCAExecutionEvent {
+    /// Input paid per output token across all fills
+    pub fn average_cost(&self) -> Option<Decimal> {
+        (self.total_received > Decimal::ZERO).then(|| self.total_spent / self.total_received)
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = match &self.outcome {
+            DCAExecutionOutcome::Filled(execution) => format!(
+                "✅ DCA filled · {}\nSpent: {} {}\nReceived: {} {}",
+                self.strategy_name,
+                execution.input_amount.normalize(), self.input_symbol,
+                execution.output_amount.normalize(), self.output_symbol,
+            ),
+            DCAExecutionOutcome::Failed { reason } => format!("❌ DCA failed · {}\n{}", self.strategy_name, reason),
+        };
+        if let DCAExecutionOutcome::Filled(execution) = &self.outcome {
+            if let Some(summary) = execution.sizing.as_ref().and_then(|sizing| sizing.summary()) {
+                text.push_str(&format!("\n📐 {}", summary));
+            }
+        }
+        if let Some(cost) = self.average_cost() {
+            text.push_str(&format!(
+                "\nAvg cost: {} {} per {} over {} fill{}",
+                cost.round_dp(9).normalize(), self.input_symbol, self.output_symbol,
+                self.fills, if self.fills == 1 { "" } else { "s" }
+            ));
+        }
+        match self.next_execution {
+            Some(next) => text.push_str(&format!(
+                "\nNext run: {}",
+                next.with_timezone(&self.timezone).format("%a %b %d, %H:%M %Z")
+            )),
+            None => text.push_str("\nNo further runs scheduled"),
+        }
+        text
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -219,6 +622,37 @@ pub struct TimezoneManager {
     default_timezone: String,
 }
 
+impl TimezoneManager {
+    pub fn new(default_timezone: &str) -> Self {
+        Self {
+            user_timezones: RwLock::new(HashMap::new()),
+            default_timezone: default_timezone.to_string(),
+        }
+    }
+    
+    /// Store a user's IANA timezone, e.g. `Asia/Tokyo`
+    pub async fn set_user_timezone(&self, user_id: i64, timezone: &str) -> Result<Tz> {
+        let tz: Tz = timezone.parse()
+            .map_err(|_| BotError::validation(format!("Unknown timezone {}; use a name like Asia/Tokyo", timezone)))?;
+        self.user_timezones.write().await.insert(user_id, tz.name().to_string());
+        Ok(tz)
+    }
+    
+    /// Forget a user's timezone; true when one was stored
+    pub async fn clear_user_timezone(&self, user_id: i64) -> bool {
+        self.user_timezones.write().await.remove(&user_id).is_some()
+    }
+    
+    /// The user's timezone, falling back to the default and then UTC
+    pub async fn get_user_timezone(&self, user_id: i64) -> Tz {
+        let stored = self.user_timezones.read().await.get(&user_id).cloned();
+        stored.as_deref()
+            .unwrap_or(&self.default_timezone)
+            .parse()
+            .unwrap_or(Tz::UTC)
+    }
+}
+
 /// Market hours management
 #[derive(Debug)]
 pub struct MarketHoursManager {
@@ -234,6 +668,54 @@ pub struct MarketSchedule {
     pub holidays: Vec<DateTime<Utc>>,
 }
 
+/// Key of the US equities schedule in [`MarketHoursManager`]
+pub const US_EQUITIES: &str = "NYSE";
+
+/// Gate openings tried before a schedule is declared unable to run
+const MAX_GATE_STEPS: usize = 64;
+
+impl MarketHoursManager {
+    /// Whether `market` is trading at `at`; unknown markets are never open
+    pub fn is_open(&self, market: &str, at: DateTime<Utc>) -> bool {
+        let Some(schedule) = self.market_schedules.get(market) else { return false };
+        let local = at.with_timezone(&schedule.tz()).naive_local();
+        schedule.is_trading_day(local.date())
+            && local.time() >= schedule.open_time
+            && local.time() < schedule.close_time
+    }
+
+    /// First opening bell of `market` after `after`
+    pub fn next_open(&self, market: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let schedule = self.market_schedules.get(market)?;
+        let tz = schedule.tz();
+        let mut date = after.with_timezone(&tz).date_naive();
+
+        // Long enough to clear a holiday weekend
+        for _ in 0..14 {
+            if schedule.is_trading_day(date) {
+                let opens = resolve_local(tz, date.and_time(schedule.open_time));
+                if opens > after {
+                    return Some(opens);
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+impl MarketSchedule {
+    fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    fn is_trading_day(&self, date: NaiveDate) -> bool {
+        let tz = self.tz();
+        self.trading_days.contains(&date.weekday())
+            && !self.holidays.iter().any(|holiday| holiday.with_timezone(&tz).date_naive() == date)
+    }
+}
+
 /// Execution statistics
 #[derive(Debug, Default)]
 pub struct ExecutionStats {
@@ -263,10 +745,7 @@ impl DCAScheduler {
     ) -> Self {
         info!("⏰ Initializing DCA scheduler");
         
-        let timezone_manager = Arc::new(TimezoneManager {
-            user_timezones: RwLock::new(HashMap::new()),
-            default_timezone: "UTC".to_string(),
-        });
+        let timezone_manager = Arc::new(TimezoneManager::new("UTC"));
         
         let mut market_schedules = HashMap::new();
         
@@ -299,12 +778,31 @@ impl DCAScheduler {
             telemetry,
             schedule_queue: Arc::new(RwLock::new(BinaryHeap::new())),
             active_schedules: Arc::new(RwLock::new(HashMap::new())),
+            parked: Arc::new(RwLock::new(HashSet::new())),
+            executions: broadcast::channel(256).0,
             timezone_manager,
             market_hours,
             execution_stats: Arc::new(RwLock::new(ExecutionStats::default())),
+            metrics: None,
         }
     }
     
+    /// Export scheduled fills, failures and the running schedule count
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    /// User timezones shared with the DCA engine for anchored runs
+    pub fn timezone_manager(&self) -> Arc<TimezoneManager> {
+        self.timezone_manager.clone()
+    }
+    
+    /// Trading hours used by `market_hours_only` schedules
+    pub fn market_hours(&self) -> Arc<MarketHoursManager> {
+        self.market_hours.clone()
+    }
+    
     /// Start the scheduler background task
     pub async fn start(&self) -> Result<()> {
         info!("⏰ Starting DCA scheduler background task");
@@ -363,8 +861,28 @@ impl DCAScheduler {
         Ok(removed)
     }
     
+    /// Run one pass over the queue
+    pub async fn run_cycle(&self) -> Result<()> {
+        self.process_scheduled_executions().await
+    }
+    
+    /// Reports of scheduled runs, filled or failed
+    pub fn subscribe_executions(&self) -> broadcast::Receiver<DCAExecutionEvent> {
+        self.executions.subscribe()
+    }
+    
+    /// Executions waiting in the queue, soonest first
+    pub async fn queued_executions(&self) -> Vec<ScheduledExecution> {
+        let queue = self.schedule_queue.read().await;
+        let mut queued: Vec<ScheduledExecution> = queue.iter().map(|Reverse(e)| e.clone()).collect();
+        queued.sort();
+        queued
+    }
+    
     /// Process scheduled executions
     async fn process_scheduled_executions(&self) -> Result<()> {
+        self.requeue_resumed().await?;
+        
         let now = Utc::now();
         let mut executions_to_process = Vec::new();
         
@@ -383,6 +901,19 @@ impl DCAScheduler {
         
         // Process each execution
         for execution in executions_to_process {
+            // Paused since it was queued: hold it until resumed
+            if self.is_paused(&execution.strategy_id).await {
+                self.parked.write().await.insert(execution.strategy_id.clone());
+                debug!("⏰ Strategy {} is paused, parking its schedule", execution.strategy_id);
+                continue;
+            }
+            
+            // A skip consumes exactly this occurrence; the next one is queued as usual
+            if self.dca_engine.consume_skip(&execution.strategy_id).await? {
+                self.requeue(&execution.strategy_id).await?;
+                continue;
+            }
+            
             let start_time = std::time::Instant::now();
             let mut success = false;
             let mut error_message = None;
@@ -392,7 +923,15 @@ impl DCAScheduler {
                 t.create_trading_span("dca_scheduled_execution", None)
             );
             
-            match self.execute_scheduled_task(&execution).await {
+            let result = self.execute_scheduled_task(&execution).await;
+            if let Some(metrics) = &self.metrics {
+                match &result {
+                    Ok(Some(_)) => metrics.record_trade_executed("dca"),
+                    Ok(None) => {}
+                    Err(e) => metrics.record_trade_failed("dca", e),
+                }
+            }
+            match &result {
                 Ok(_) => {
                     success = true;
                     debug!("⏰ Successfully executed scheduled task for strategy {}", 
@@ -410,17 +949,26 @@ impl DCAScheduler {
             // Record execution statistics
             self.record_execution_stats(&execution, duration.as_millis() as u64, success, error_message).await;
             
-            // Schedule next execution if this was a recurring schedule
-            if success {
-                self.schedule_next_execution(&execution.strategy_id).await?;
-            }
+            // A failed run retries at the next occurrence rather than ending the schedule
+            self.schedule_next_execution(&execution.strategy_id).await?;
+            
+            let outcome = match result {
+                Ok(Some(fill)) => DCAExecutionOutcome::Filled(fill),
+                Ok(None) => continue,
+                Err(e) => DCAExecutionOutcome::Failed { reason: e.to_string() },
+            };
+            self.publish_execution(&execution.strategy_id, outcome).await;
+        }
+        
+        if let Some(metrics) = &self.metrics {
+            metrics.record_active_dca_strategies(self.get_active_schedules().await.len());
         }
         
         Ok(())
     }
     
-    /// Execute a scheduled task
-    async fn execute_scheduled_task(&self, execution: &ScheduledExecution) -> Result<()> {
+    /// Execute a scheduled task; `None` when its conditions held it back
+    async fn execute_scheduled_task(&self, execution: &ScheduledExecution) -> Result<Option<DCAExecution>> {
         // Get the schedule configuration
         let schedules = self.active_schedules.read().await;
         let schedule = schedules.get(&execution.strategy_id)
@@ -431,20 +979,18 @@ impl DCAScheduler {
         // Check execution conditions
         if !self.check_execution_conditions(&schedule).await? {
             debug!("⏰ Execution conditions not met for strategy {}, skipping", execution.strategy_id);
-            return Ok(());
+            return Ok(None);
         }
         
         // Check market hours if required
         if schedule.market_hours_only && !self.is_market_open(&schedule).await? {
             debug!("⏰ Market closed for strategy {}, skipping", execution.strategy_id);
-            return Ok(());
+            return Ok(None);
         }
         
         // Execute the DCA strategy
         match execution.execution_type {
             ExecutionType::Regular => {
-                // Find the DCA strategy and execute it
-                // This would integrate with the DCA engine
                 info!("⏰ Executing regular DCA for strategy {}", execution.strategy_id);
             },
             ExecutionType::PriceAlert => {
@@ -462,19 +1008,80 @@ impl DCAScheduler {
             }
         }
         
-        // Send notifications if configured
-        self.send_execution_notification(&schedule, true).await?;
+        self.dca_engine.execute_scheduled(&execution.strategy_id).await.map(Some)
+    }
+    
+    /// Broadcast a run's report, addressed by the schedule's notification settings
+    async fn publish_execution(&self, strategy_id: &str, outcome: DCAExecutionOutcome) {
+        let Some(schedule) = self.active_schedules.read().await.get(strategy_id).cloned() else { return };
+        let Some(strategy) = self.dca_engine.strategy(strategy_id).await else { return };
         
-        Ok(())
+        let timezone = self.dca_engine.timezones().get_user_timezone(strategy.user_id).await;
+        let local_now = Utc::now().with_timezone(&timezone).naive_local();
+        let success = matches!(outcome, DCAExecutionOutcome::Filled(_));
+        let (total_spent, total_received, fills) = self.dca_engine.accumulated(strategy_id).await;
+        
+        let event = DCAExecutionEvent {
+            user_id: strategy.user_id,
+            strategy_id: strategy_id.to_string(),
+            strategy_name: strategy.name.clone(),
+            input_symbol: TokenResolver::get_symbol(&strategy.input_token),
+            output_symbol: TokenResolver::get_symbol(&strategy.output_token),
+            outcome,
+            total_spent,
+            total_received,
+            fills,
+            next_execution: schedule.is_active.then_some(schedule.next_execution),
+            timezone,
+            recipients: schedule.notifications.telegram_recipients(strategy.user_id, success, local_now),
+        };
+        if event.recipients.is_empty() {
+            debug!("⏰ Report for strategy {} held back by its notification settings", strategy_id);
+        }
+        let _ = self.executions.send(event);
     }
     
-    /// Calculate next execution time based on schedule type
+    /// Next permitted run after now
     async fn calculate_next_execution(&self, config: &ScheduleConfig) -> Result<DateTime<Utc>> {
-        let now = Utc::now();
+        self.next_run_after(config, Utc::now()).await
+    }
+    
+    /// Next run strictly after `after` that its window, market hours and weekend
+    /// settings allow, computed on the schedule's local wall clock
+    pub async fn next_run_after(&self, config: &ScheduleConfig, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let tz = self.schedule_timezone(config).await;
+        let mut next = self.next_occurrence(config, tz, after).await?;
+        
+        for _ in 0..MAX_GATE_STEPS {
+            let Some(opens) = self.gate_opens(config, tz, next)? else {
+                return Ok(next);
+            };
+            next = match &config.schedule_type {
+                // Cron runs keep to their own slots: take the first one once the gate opens
+                ScheduleType::Cron { expression, .. } => next_cron_run(expression, tz, opens - Duration::seconds(1))?,
+                _ => opens,
+            };
+        }
         
+        Err(BotError::validation(format!("Schedule {} never runs inside its allowed hours", config.name)).into())
+    }
+    
+    /// The schedule's own timezone, else its owner's
+    async fn schedule_timezone(&self, config: &ScheduleConfig) -> Tz {
+        if let Ok(tz) = config.timezone.parse::<Tz>() {
+            return tz;
+        }
+        match self.dca_engine.strategy(&config.strategy_id).await {
+            Some(strategy) => self.dca_engine.timezones().get_user_timezone(strategy.user_id).await,
+            None => Tz::UTC,
+        }
+    }
+    
+    /// Next occurrence of the schedule itself, before any gating
+    async fn next_occurrence(&self, config: &ScheduleConfig, tz: Tz, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
         let next = match &config.schedule_type {
             ScheduleType::Interval { interval, offset_minutes } => {
-                let base_next = self.calculate_interval_next(interval, now)?;
+                let base_next = self.calculate_interval_next(interval, after, tz)?;
                 if let Some(offset) = offset_minutes {
                     base_next + Duration::minutes(*offset as i64)
                 } else {
@@ -482,18 +1089,10 @@ impl DCAScheduler {
                 }
             },
             
-            ScheduleType::Cron { expression, .. } => {
-                let schedule = Schedule::from_str(expression)
-                    .map_err(|e| BotError::config(format!("Invalid cron expression: {}", e)))?;
-                    
-                schedule.upcoming(chrono::Utc)
-                    .take(1)
-                    .next()
-                    .ok_or_else(|| BotError::config("No future execution time found".to_string()))?
-            },
+            ScheduleType::Cron { expression, .. } => next_cron_run(expression, tz, after)?,
             
             ScheduleType::MarketEvent { event, delay_minutes } => {
-                let market_time = self.calculate_market_event_time(event, now).await?;
+                let market_time = self.calculate_market_event_time(event, after).await?;
                 if let Some(delay) = delay_minutes {
                     market_time + Duration::minutes(*delay as i64)
                 } else {
@@ -502,133 +1101,87 @@ impl DCAScheduler {
             },
             
             ScheduleType::PriceBased { check_interval_minutes, .. } => {
-                now + Duration::minutes(*check_interval_minutes as i64)
+                after + Duration::minutes(*check_interval_minutes as i64)
             },
             
             ScheduleType::VolumeBased { check_interval_minutes, .. } => {
-                now + Duration::minutes(*check_interval_minutes as i64)
+                after + Duration::minutes(*check_interval_minutes as i64)
             },
             
             ScheduleType::TechnicalBased { check_interval_minutes, .. } => {
-                now + Duration::minutes(*check_interval_minutes as i64)
+                after + Duration::minutes(*check_interval_minutes as i64)
             },
             
             ScheduleType::Algorithm { .. } => {
                 // Custom algorithm scheduling
-                now + Duration::hours(1) // Default fallback
+                after + Duration::hours(1) // Default fallback
             },
         };
         
-        // Apply time window restrictions if configured
-        let adjusted_next = if let Some(window) = &config.execution_window {
-            self.adjust_for_time_window(next, window)?
-        } else {
-            next
-        };
+        Ok(next)
+    }
+    
+    /// `None` when a run may happen at `at`, else when the first closed gate next opens
+    fn gate_opens(&self, config: &ScheduleConfig, tz: Tz, at: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        let local = at.with_timezone(&tz).naive_local();
         
-        // Skip weekends if configured
-        let final_next = if config.skip_weekends {
-            self.skip_weekend(adjusted_next)
-        } else {
-            adjusted_next
-        };
+        if let Some(window) = config.execution_window.as_ref().filter(|window| !window.contains(local)) {
+            let opens = window.next_open(local)
+                .ok_or_else(|| BotError::validation(format!("Schedule {} has no allowed days", config.name)))?;
+            return Ok(Some(resolve_local(tz, opens)));
+        }
         
-        Ok(final_next)
+        if config.skip_weekends && matches!(local.weekday(), Weekday::Sat | Weekday::Sun) {
+            let mut monday = local.date();
+            while monday.weekday() != Weekday::Mon {
+                monday = monday.succ_opt()
+                    .ok_or_else(|| BotError::config("Date calculation overflow".to_string()))?;
+            }
+            return Ok(Some(resolve_local(tz, monday.and_time(NaiveTime::MIN))));
+        }
+        
+        if config.market_hours_only && !self.market_hours.is_open(US_EQUITIES, at) {
+            let opens = self.market_hours.next_open(US_EQUITIES, at)
+                .ok_or_else(|| BotError::config("US equities market schedule not found".to_string()))?;
+            return Ok(Some(opens));
+        }
+        
+        Ok(None)
     }
     
     /// Helper methods for scheduling logic
-    fn calculate_interval_next(&self, interval: &DCAInterval, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    fn calculate_interval_next(&self, interval: &DCAInterval, after: DateTime<Utc>, tz: Tz) -> Result<DateTime<Utc>> {
         let next = match interval {
-            DCAInterval::Minutes(m) => now + Duration::minutes(*m as i64),
-            DCAInterval::Hourly => now + Duration::hours(1),
-            DCAInterval::Daily => now + Duration::days(1),
-            DCAInterval::Weekly => now + Duration::weeks(1),
-            DCAInterval::Biweekly => now + Duration::weeks(2),
-            DCAInterval::Monthly => now + Duration::days(30), // Approximate
-            DCAInterval::Custom { cron_expression } => {
-                let schedule = Schedule::from_str(cron_expression)
-                    .map_err(|e| BotError::config(format!("Invalid cron expression: {}", e)))?;
-                    
-                schedule.upcoming(chrono::Utc)
-                    .take(1)
-                    .next()
-                    .ok_or_else(|| BotError::config("No future execution time found".to_string()))?
-            }
+            DCAInterval::Minutes(m) => after + Duration::minutes(*m as i64),
+            DCAInterval::Hourly => after + Duration::hours(1),
+            DCAInterval::Daily => after + Duration::days(1),
+            DCAInterval::Weekly => after + Duration::weeks(1),
+            DCAInterval::Biweekly => after + Duration::weeks(2),
+            DCAInterval::Monthly => after + Duration::days(30), // Approximate
+            DCAInterval::Custom { cron_expression } => next_cron_run(cron_expression, tz, after)?,
         };
         
         Ok(next)
     }
     
-    async fn calculate_market_event_time(&self, event: &MarketEvent, _now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    async fn calculate_market_event_time(&self, event: &MarketEvent, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
         match event {
             MarketEvent::MarketOpen => {
-                // Calculate next market open time
-                let crypto_schedule = self.market_hours.market_schedules.get("CRYPTO")
-                    .ok_or_else(|| BotError::config("Crypto market schedule not found".to_string()))?;
-                    
-                // For crypto, market is always open, so return current time
-                Ok(Utc::now())
+                // US equities open; crypto never closes
+                self.market_hours.next_open(US_EQUITIES, after)
+                    .ok_or_else(|| BotError::config("US equities market schedule not found".to_string()).into())
             },
             MarketEvent::MarketClose => {
                 // Calculate next market close time
-                Ok(Utc::now() + Duration::hours(24)) // Placeholder
+                Ok(after + Duration::hours(24)) // Placeholder
             },
             _ => {
                 // Other market events would be calculated here
-                Ok(Utc::now() + Duration::hours(1))
+                Ok(after + Duration::hours(1))
             }
         }
     }
     
-    fn adjust_for_time_window(&self, datetime: DateTime<Utc>, window: &TimeWindow) -> Result<DateTime<Utc>> {
-        let weekday = datetime.weekday();
-        
-        // Check if the day is allowed
-        if !window.days_of_week.contains(&weekday) {
-            // Find the next allowed day
-            let mut next_date = datetime.date_naive();
-            loop {
-                next_date = next_date.succ_opt()
-                    .ok_or_else(|| BotError::config("Date calculation overflow".to_string()))?;
-                if window.days_of_week.contains(&next_date.weekday()) {
-                    break;
-                }
-            }
-            
-            // Set to start of time window on the next allowed day
-            return Ok(next_date.and_time(window.start_time).and_utc());
-        }
-        
-        // Check if the time is within the window
-        let time = datetime.time();
-        if time >= window.start_time && time <= window.end_time {
-            Ok(datetime) // Already within window
-        } else if time < window.start_time {
-            // Move to start of window same day
-            Ok(datetime.date_naive().and_time(window.start_time).and_utc())
-        } else {
-            // Move to start of window next allowed day
-            let mut next_date = datetime.date_naive().succ_opt()
-                .ok_or_else(|| BotError::config("Date calculation overflow".to_string()))?;
-                
-            while !window.days_of_week.contains(&next_date.weekday()) {
-                next_date = next_date.succ_opt()
-                    .ok_or_else(|| BotError::config("Date calculation overflow".to_string()))?;
-            }
-            
-            Ok(next_date.and_time(window.start_time).and_utc())
-        }
-    }
-    
-    fn skip_weekend(&self, datetime: DateTime<Utc>) -> DateTime<Utc> {
-        let weekday = datetime.weekday();
-        match weekday {
-            Weekday::Sat => datetime + Duration::days(2), // Move to Monday
-            Weekday::Sun => datetime + Duration::days(1), // Move to Monday
-            _ => datetime, // Weekday, no change needed
-        }
-    }
-    
     async fn check_execution_conditions(&self, _schedule: &ScheduleConfig) -> Result<bool> {
         // Implementation would check all conditions
         // For now, always return true
@@ -636,18 +1189,44 @@ impl DCAScheduler {
     }
     
     async fn is_market_open(&self, _schedule: &ScheduleConfig) -> Result<bool> {
-        // Implementation would check market hours
-        // For crypto, market is always open
-        Ok(true)
+        Ok(self.market_hours.is_open(US_EQUITIES, Utc::now()))
+    }
+    
+    async fn is_paused(&self, strategy_id: &str) -> bool {
+        self.dca_engine.strategy_status(strategy_id).await == Some(DCAStatus::Paused)
+    }
+    
+    /// Put schedules of strategies resumed since they were parked back in the queue
+    async fn requeue_resumed(&self) -> Result<()> {
+        let parked: Vec<String> = self.parked.read().await.iter().cloned().collect();
+        for strategy_id in parked {
+            if !self.is_paused(&strategy_id).await {
+                self.parked.write().await.remove(&strategy_id);
+                self.requeue(&strategy_id).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Queue the next occurrence after now without counting an execution
+    async fn requeue(&self, strategy_id: &str) -> Result<()> {
+        let mut schedules = self.active_schedules.write().await;
+        if let Some(config) = schedules.get_mut(strategy_id).filter(|c| c.is_active) {
+            config.next_execution = self.calculate_next_execution(config).await?;
+            self.enqueue_execution(config).await?;
+        }
+        Ok(())
     }
     
+    /// Paused strategies are parked instead of queued
     async fn enqueue_execution(&self, config: &ScheduleConfig) -> Result<()> {
-        let execution = ScheduledExecution {
-            execute_at: config.next_execution,
-            strategy_id: config.strategy_id.clone(),
-            execution_type: ExecutionType::Regular,
-            priority: 5, // Default priority
-        };
+        if self.is_paused(&config.strategy_id).await {
+            self.parked.write().await.insert(config.strategy_id.clone());
+            return Ok(());
+        }
+        
+        let tz = self.schedule_timezone(config).await;
+        let execution = ScheduledExecution::regular(&config.strategy_id, config.next_execution, tz);
         
         let mut queue = self.schedule_queue.write().await;
         queue.push(Reverse(execution));
@@ -661,14 +1240,11 @@ impl DCAScheduler {
         
         let schedules = self.active_schedules.read().await;
         for config in schedules.values() {
-            if config.is_active {
-                let execution = ScheduledExecution {
-                    execute_at: config.next_execution,
-                    strategy_id: config.strategy_id.clone(),
-                    execution_type: ExecutionType::Regular,
-                    priority: 5,
-                };
-                queue.push(Reverse(execution));
+            if self.is_paused(&config.strategy_id).await {
+                self.parked.write().await.insert(config.strategy_id.clone());
+            } else if config.is_active {
+                let tz = self.schedule_timezone(config).await;
+                queue.push(Reverse(ScheduledExecution::regular(&config.strategy_id, config.next_execution, tz)));
             }
         }
         
@@ -736,11 +1312,6 @@ impl DCAScheduler {
         }
     }
     
-    async fn send_execution_notification(&self, _schedule: &ScheduleConfig, _success: bool) -> Result<()> {
-        // Implementation would send notifications based on config
-        Ok(())
-    }
-    
     async fn validate_schedule(&self, _config: &ScheduleConfig) -> Result<()> {
         // Implementation would validate schedule configuration
         Ok(())
@@ -772,16 +1343,28 @@ impl ScheduleConfig {
         minute: u32,
         timezone: String,
     ) -> Self {
-        let cron_expression = format!("0 {} {} * * *", minute, hour);
-        
+        Self::create_cron_schedule(
+            strategy_id,
+            name,
+            format!("{} {} * * *", minute, hour),
+            Some(format!("Daily at {}:{:02}", hour, minute)),
+            timezone,
+        )
+    }
+    
+    /// Create a schedule from a cron expression, see [`CronSchedule`]
+    pub fn create_cron_schedule(
+        strategy_id: String,
+        name: String,
+        expression: String,
+        description: Option<String>,
+        timezone: String,
+    ) -> Self {
         Self {
             schedule_id: uuid::Uuid::new_v4().to_string(),
             strategy_id,
             name,
-            schedule_type: ScheduleType::Cron {
-                expression: cron_expression,
-                description: Some(format!("Daily at {}:{:02}", hour, minute)),
-            },
+            schedule_type: ScheduleType::Cron { expression, description },
             timezone,
             is_active: true,
             created_at: Utc::now(),
@@ -836,10 +1419,11 @@ impl ScheduleConfig {
 impl Default for NotificationConfig {
     fn default() -> Self {
         Self {
-            notify_on_execution: false,
+            notify_on_execution: true,
             notify_on_failure: true,
             notify_on_conditions_met: false,
             notification_channels: vec![],
+            quiet_hours: default_quiet_hours(),
         }
     }
 }
\ No newline at end of file
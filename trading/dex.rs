@@ -157,6 +157,18 @@ impl JupiterSwap {
         }
     }
     
+    /// Point the client at alternative quote and price endpoints (self-hosted proxy, test mocks)
+    pub fn with_endpoints(mut self, api_url: String, price_api_url: String) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self.price_api_url = price_api_url.trim_end_matches('/').to_string();
+        self
+    }
+    
+    /// Drop cached quotes involving `mint` so the next quote is fetched fresh
+    pub async fn forget_quotes(&self, mint: &str) {
+        self.quote_cache.write().await.retain(|key, _| !key.split(':').any(|part| part == mint));
+    }
+    
     #[instrument(skip(self), fields(input_mint, output_mint, amount, slippage_bps))]
     pub async fn get_quote(
         &self,
@@ -164,11 +176,24 @@ impl JupiterSwap {
         output_mint: &str,
         amount: f64,
         slippage_bps: u16,
+    ) -> Result<JupiterQuote> {
+        self.get_routed_quote(input_mint, output_mint, amount, slippage_bps, false).await
+    }
+    
+    /// Quote restricted to single-pool routes when `only_direct` is set
+    #[instrument(skip(self), fields(input_mint, output_mint, amount, slippage_bps, only_direct))]
+    pub async fn get_routed_quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: f64,
+        slippage_bps: u16,
+        only_direct: bool,
     ) -> Result<JupiterQuote> {
         let amount_lamports = (amount * 1e9) as u64;
         
         // Create cache key for deduplication
-        let cache_key = format!("{}:{}:{}:{}", input_mint, output_mint, amount_lamports, slippage_bps);
+        let cache_key = format!("{}:{}:{}:{}:{}", input_mint, output_mint, amount_lamports, slippage_bps, only_direct);
         
         // Check cache first
         {
@@ -210,7 +235,7 @@ impl JupiterSwap {
             .map_err(|_| TradingError::QuoteFailed("Rate limiter closed".to_string()))?;
         
         // Make the actual API call
-        let result = self.fetch_quote_from_api(input_mint, output_mint, amount_lamports, slippage_bps).await;
+        let result = self.fetch_quote_from_api(input_mint, output_mint, amount_lamports, slippage_bps, only_direct).await;
         
         // Clean up pending request and notify waiters
         {
@@ -254,14 +279,16 @@ impl JupiterSwap {
         output_mint: &str,
         amount_lamports: u64,
         slippage_bps: u16,
+        only_direct: bool,
     ) -> Result<JupiterQuote> {
         let url = format!(
-            "{}/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}&onlyDirectRoutes=false&asLegacyTransaction=false",
+            "{}/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}&onlyDirectRoutes={}&asLegacyTransaction=false",
             self.api_url,
             input_mint,
             output_mint,
             amount_lamports,
-            slippage_bps
+            slippage_bps,
+            only_direct
         );
         
         debug!("Fetching Jupiter V6 quote: {}", url);
@@ -276,6 +303,9 @@ impl JupiterSwap {
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await?;
+            if error_text.contains("COULD_NOT_FIND_ANY_ROUTE") || error_text.contains("NO_ROUTE") {
+                return Err(TradingError::NoRoute.into());
+            }
             return Err(TradingError::QuoteFailed(
                 format!("Jupiter quote failed ({}): {}", status, error_text)
             ).into());
@@ -329,7 +359,8 @@ impl JupiterSwap {
             as_legacy_transaction: false,
             use_token_ledger: false,
             destination_token_account: None,
-            dynamic_compute_unit_limit: true,
+            // Sized by the ComputeBudgeter from our own simulation
+            dynamic_compute_unit_limit: false,
             skip_user_accounts_rpc_calls: false,
         };
         
@@ -0,0 +1,386 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, info};
+
+/// How much a user hears about fills
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Verbosity {
+    /// Every fill notifies
+    #[default]
+    Full,
+    /// Fills are collected into a digest per rolling window
+    Summary,
+    /// Only failures and risk refusals notify
+    Silent,
+}
+
+impl Verbosity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "full" => Some(Self::Full),
+            "summary" | "digest" => Some(Self::Summary),
+            "silent" | "off" => Some(Self::Silent),
+            _ => None,
+        }
+    }
+
+    /// Short form for callback data
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Full => "f",
+            Self::Summary => "s",
+            Self::Silent => "q",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "f" => Some(Self::Full),
+            "s" => Some(Self::Summary),
+            "q" => Some(Self::Silent),
+            _ => None,
+        }
+    }
+
+    pub fn badge(&self) -> &'static str {
+        match self {
+            Self::Full => "🔔 Full",
+            Self::Summary => "🧾 Summary",
+            Self::Silent => "🔕 Silent",
+        }
+    }
+
+    /// Next level for toggle buttons
+    pub fn next(self) -> Self {
+        match self {
+            Self::Full => Self::Summary,
+            Self::Summary => Self::Silent,
+            Self::Silent => Self::Full,
+        }
+    }
+}
+
+/// Where a verbosity level was set; digests are kept per scope
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NoticeScope {
+    Global,
+    Strategy(String),
+    Order(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionSource {
+    Manual,
+    Dca,
+    Grid,
+    Order,
+    Copy,
+}
+
+impl ExecutionSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Manual => "Trade",
+            Self::Dca => "DCA",
+            Self::Grid => "Grid",
+            Self::Order => "Order",
+            Self::Copy => "Copy trade",
+        }
+    }
+}
+
+/// One fill, priced in SOL per token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub mint: String,
+    pub symbol: String,
+    pub quantity: f64,
+    pub price: f64,
+    pub fee_sol: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NoticeKind {
+    Fill(Fill),
+    Failure { reason: String },
+    /// A risk guard declined to trade
+    RiskRefusal { reason: String },
+    /// Nothing traded, but the user should know something is wrong
+    Warning { reason: String },
+    /// Waiting for the user to approve the transaction on their hardware wallet
+    DeviceConfirmation { detail: String },
+}
+
+impl NoticeKind {
+    /// Failures and refusals reach the user at every verbosity level
+    pub fn always_notifies(&self) -> bool {
+        !matches!(self, Self::Fill(_))
+    }
+}
+
+/// An execution event from an automated or manual trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionNotice {
+    pub user_id: i64,
+    pub source: ExecutionSource,
+    /// Strategy or order name shown to the user
+    pub label: String,
+    pub strategy_id: Option<String>,
+    pub order_id: Option<String>,
+    pub kind: NoticeKind,
+    pub at: DateTime<Utc>,
+}
+
+impl ExecutionNotice {
+    pub fn render(&self) -> String {
+        match &self.kind {
+            NoticeKind::Fill(fill) => format!(
+                "✅ {} filled · {}\n{:.4} {} at {:.9} SOL\nFee: {:.6} SOL",
+                self.source.label(), self.label, fill.quantity, fill.symbol, fill.price, fill.fee_sol
+            ),
+            NoticeKind::Failure { reason } => format!("❌ {} failed · {}\n{}", self.source.label(), self.label, reason),
+            NoticeKind::RiskRefusal { reason } => {
+                format!("🛡️ {} skipped by risk guard · {}\n{}", self.source.label(), self.label, reason)
+            }
+            NoticeKind::Warning { reason } => format!("⚠️ {} needs attention · {}\n{}", self.source.label(), self.label, reason),
+            NoticeKind::DeviceConfirmation { detail } => {
+                format!("🔐 {} · {}\nConfirm on your Ledger to continue.\n{}", self.source.label(), self.label, detail)
+            }
+        }
+    }
+}
+
+/// Fills of one token within a digest
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestLine {
+    pub mint: String,
+    pub symbol: String,
+    pub fills: usize,
+    pub total_quantity: f64,
+    /// Quantity-weighted
+    pub average_price: f64,
+    pub total_fees_sol: f64,
+}
+
+/// Fills accumulated for one scope over one window
+#[derive(Debug, Clone)]
+pub struct FillDigest {
+    pub user_id: i64,
+    pub scope: NoticeScope,
+    pub label: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub lines: Vec<DigestLine>,
+}
+
+impl FillDigest {
+    /// Per-token totals, quantity-weighted average price and summed fees
+    pub fn aggregate(fills: &[Fill]) -> Vec<DigestLine> {
+        let mut by_mint: BTreeMap<&str, DigestLine> = BTreeMap::new();
+        for fill in fills {
+            let line = by_mint.entry(fill.mint.as_str()).or_insert_with(|| DigestLine {
+                mint: fill.mint.clone(),
+                symbol: fill.symbol.clone(),
+                fills: 0,
+                total_quantity: 0.0,
+                average_price: 0.0,
+                total_fees_sol: 0.0,
+            });
+            let quantity = line.total_quantity + fill.quantity;
+            if quantity > 0.0 {
+                line.average_price = (line.average_price * line.total_quantity + fill.price * fill.quantity) / quantity;
+            }
+            line.total_quantity = quantity;
+            line.total_fees_sol += fill.fee_sol;
+            line.fills += 1;
+        }
+        by_mint.into_values().collect()
+    }
+
+    pub fn fill_count(&self) -> usize {
+        self.lines.iter().map(|l| l.fills).sum()
+    }
+
+    pub fn render(&self) -> String {
+        let minutes = (self.window_end - self.window_start).num_minutes().max(1);
+        let mut text = format!("🧾 {} · {} fills in the last {} min", self.label, self.fill_count(), minutes);
+        for line in &self.lines {
+            text.push_str(&format!(
+                "\n\n{}: {} fills\nFilled: {:.4}\nAvg price: {:.9} SOL\nFees: {:.6} SOL",
+                line.symbol, line.fills, line.total_quantity, line.average_price, line.total_fees_sol
+            ));
+        }
+        text
+    }
+}
+
+/// What the notification forwarder sends
+#[derive(Debug, Clone)]
+pub enum OutgoingNotice {
+    Notice(ExecutionNotice),
+    Digest(FillDigest),
+}
+
+/// What happened to a notice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoticeRoute {
+    Sent,
+    Digested,
+    Suppressed,
+}
+
+#[derive(Debug, Default)]
+struct VerbositySettings {
+    global: HashMap<i64, Verbosity>,
+    strategies: HashMap<String, Verbosity>,
+    orders: HashMap<String, Verbosity>,
+}
+
+#[derive(Debug)]
+struct PendingDigest {
+    label: String,
+    opened_at: DateTime<Utc>,
+    fills: Vec<Fill>,
+}
+
+/// Routes execution events by the user's verbosity: sent, digested or dropped
+///
+/// The DCA engine, order manager and copy manager publish here rather than
+/// notifying directly, so every path honors the same settings.
+#[derive(Clone)]
+pub struct ExecutionNotifier {
+    digest_window: Duration,
+    settings: Arc<RwLock<VerbositySettings>>,
+    pending: Arc<RwLock<HashMap<(i64, NoticeScope), PendingDigest>>>,
+    outgoing: broadcast::Sender<OutgoingNotice>,
+}
+
+impl Default for ExecutionNotifier {
+    fn default() -> Self {
+        Self::new(Duration::hours(1))
+    }
+}
+
+impl ExecutionNotifier {
+    pub fn new(digest_window: Duration) -> Self {
+        let (outgoing, _) = broadcast::channel(256);
+        Self {
+            digest_window,
+            settings: Arc::new(RwLock::new(VerbositySettings::default())),
+            pending: Arc::new(RwLock::new(HashMap::new())),
+            outgoing,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<OutgoingNotice> {
+        self.outgoing.subscribe()
+    }
+
+    pub fn digest_window(&self) -> Duration {
+        self.digest_window
+    }
+
+    pub async fn set_global(&self, user_id: i64, verbosity: Verbosity) {
+        self.settings.write().await.global.insert(user_id, verbosity);
+    }
+
+    pub async fn set_strategy(&self, strategy_id: &str, verbosity: Verbosity) {
+        self.settings.write().await.strategies.insert(strategy_id.to_string(), verbosity);
+    }
+
+    pub async fn set_order(&self, order_id: &str, verbosity: Verbosity) {
+        self.settings.write().await.orders.insert(order_id.to_string(), verbosity);
+    }
+
+    /// Drop a user's default level and pending digests
+    pub async fn forget_user(&self, user_id: i64) {
+        self.settings.write().await.global.remove(&user_id);
+        self.pending.write().await.retain(|(user, _), _| *user != user_id);
+    }
+
+    pub async fn global(&self, user_id: i64) -> Verbosity {
+        self.settings.read().await.global.get(&user_id).copied().unwrap_or_default()
+    }
+
+    /// Level for an event: the order's, else its strategy's, else the user's default
+    pub async fn effective(&self, user_id: i64, strategy_id: Option<&str>, order_id: Option<&str>) -> (Verbosity, NoticeScope) {
+        let settings = self.settings.read().await;
+        if let Some((id, verbosity)) = order_id.and_then(|id| settings.orders.get(id).map(|v| (id, *v))) {
+            return (verbosity, NoticeScope::Order(id.to_string()));
+        }
+        if let Some((id, verbosity)) = strategy_id.and_then(|id| settings.strategies.get(id).map(|v| (id, *v))) {
+            return (verbosity, NoticeScope::Strategy(id.to_string()));
+        }
+        (settings.global.get(&user_id).copied().unwrap_or_default(), NoticeScope::Global)
+    }
+
+    /// Publish an execution event at the level its scope resolves to
+    pub async fn notify(&self, notice: ExecutionNotice) -> NoticeRoute {
+        let (verbosity, scope) = self.effective(notice.user_id, notice.strategy_id.as_deref(), notice.order_id.as_deref()).await;
+
+        if notice.kind.always_notifies() || verbosity == Verbosity::Full {
+            let _ = self.outgoing.send(OutgoingNotice::Notice(notice));
+            return NoticeRoute::Sent;
+        }
+        let NoticeKind::Fill(fill) = notice.kind else { return NoticeRoute::Sent };
+        if verbosity == Verbosity::Silent {
+            debug!("🔕 Fill for user {} suppressed ({:?})", notice.user_id, scope);
+            return NoticeRoute::Suppressed;
+        }
+
+        let label = match scope {
+            NoticeScope::Global => "Fill digest".to_string(),
+            _ => format!("{} {}", notice.source.label(), notice.label),
+        };
+        let mut pending = self.pending.write().await;
+        pending.entry((notice.user_id, scope))
+            .or_insert_with(|| PendingDigest { label, opened_at: notice.at, fills: Vec::new() })
+            .fills
+            .push(fill);
+        NoticeRoute::Digested
+    }
+
+    /// Send and return every digest whose window has closed
+    pub async fn flush_due(&self, now: DateTime<Utc>) -> Vec<FillDigest> {
+        let mut pending = self.pending.write().await;
+        let due: Vec<(i64, NoticeScope)> = pending.iter()
+            .filter(|(_, digest)| now - digest.opened_at >= self.digest_window)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        let mut digests = Vec::new();
+        for key in due {
+            let Some(digest) = pending.remove(&key) else { continue };
+            let (user_id, scope) = key;
+            let digest = FillDigest {
+                user_id,
+                scope,
+                label: digest.label,
+                window_start: digest.opened_at,
+                window_end: now,
+                lines: FillDigest::aggregate(&digest.fills),
+            };
+            let _ = self.outgoing.send(OutgoingNotice::Digest(digest.clone()));
+            digests.push(digest);
+        }
+        digests
+    }
+
+    /// Flush closed digest windows every minute
+    pub async fn start(&self) {
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                let digests = notifier.flush_due(Utc::now()).await;
+                if !digests.is_empty() {
+                    info!("🧾 Sent {} fill digests", digests.len());
+                }
+            }
+        });
+    }
+}
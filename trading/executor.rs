@@ -1,31 +1,50 @@
-use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_account_decoder::UiAccountData;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig, rpc_request::TokenAccountsFilter};
+use solana_transaction_status::UiTransactionEncoding;
 use solana_sdk::{
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     transaction::Transaction,
     pubkey::Pubkey,
     commitment_config::CommitmentConfig,
 };
 use reqwest::ClientBuilder;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 use std::sync::Arc;
 use std::str::FromStr;
-use tracing::{info, error, debug, instrument};
-use tokio::sync::{mpsc, oneshot, Semaphore};
+use tracing::{info, warn, error, debug, instrument};
+use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio::time::{timeout, Duration as TokioDuration};
 use crate::errors::{BotError, TradingError, Result};
 use crate::constants::{DEFAULT_PRIORITY_FEE, DEFAULT_SLIPPAGE_BPS, MAX_SLIPPAGE_BPS};
 use crate::utils::validation::Validator;
 
-use crate::{utils::Config, db::Database, wallet::WalletManager};
+use crate::{utils::Config, db::Database, wallet::{TransactionPriority, WalletManager, SPL_TOKEN_PROGRAM_ID}};
+use crate::api::{JupiterAuthManager, JupiterPriceV3Client};
 use crate::middleware::{CircuitBreaker, CircuitBreakerConfig};
+use crate::monitoring::MetricsCollector;
 use super::{
-    types::{TradeResult, Balance, Position, TokenRestrictions, TradeType},
+    types::{TradeResult, Balance, Position, TokenRestrictions, TradeType, TradeDefaults, ExecutionReport, ExecutionFees, RouteSummary, BundleReceipt},
     backrun::HeliusClient,
     dex::JupiterSwap,
-    token_2022::{Token2022Manager, Token2022Info, ExtensionType, TransferFeeConfig},
+    token_2022::{Token2022Manager, Token2022Info, ExtensionType, TransferFeeConfig, TOKEN_2022_PROGRAM_ID},
     token_creator::TokenCreator,
+    compute_budget::{BudgetUrgency, ComputeBudget, ComputeBudgetConfig, ComputeBudgeter},
+    exit_routing::{plan_exit, ExitDenomination, ExitSettlement, WSOL_MINT},
+    paper::{simulate_fill, PaperLedger, TradingMode},
+    token_resolver::TokenResolver,
+    signer::{SigningRequest, SigningStrategy, TransactionSigner},
+    jito::MevProtection,
+    lanes::{LaneHandler, LaneMailbox, Laned},
+    fill_check::{FillAmounts, QuoteGuard},
+    reconcile::{on_chain_balances, reconcile, Reconciliation},
+    trade_gate::TradeGate,
 };
 
+/// How long a sent swap gets to confirm before its fill is reported from the quote
+const FILL_CONFIRM_POLLS: u32 = 30;
+const FILL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 // Actor messages for the TradingEngine
 #[derive(Debug)]
 pub enum TradingMessage {
@@ -33,6 +52,8 @@ pub enum TradingMessage {
         user_wallet: String,
         token: String,
         amount_sol: f64,
+        /// The engine's configured slippage and fee level when unset
+        defaults: Option<TradeDefaults>,
         response_tx: mpsc::Sender<Result<TradeResult>>,
     },
     Sell {
@@ -45,12 +66,15 @@ pub enum TradingMessage {
         user_wallet: String,
         token: String,
         amount_sol: f64,
+        defaults: Option<TradeDefaults>,
         response: oneshot::Sender<Result<TradeResult>>,
     },
     SellWithRebate {
         user_wallet: String,
         token: String,
         percentage: f64,
+        exit: ExitDenomination,
+        defaults: Option<TradeDefaults>,
         response: oneshot::Sender<Result<TradeResult>>,
     },
     GetBalance {
@@ -61,46 +85,97 @@ pub enum TradingMessage {
         user_wallet: String,
         response_tx: mpsc::Sender<Result<Vec<Position>>>,
     },
+    SyncPositions {
+        user_wallet: String,
+        response: oneshot::Sender<Result<Reconciliation>>,
+    },
+    /// A prepared transaction that isn't a swap, e.g. a lending deposit, signed like a trade
+    SubmitTransaction {
+        user_wallet: String,
+        transaction: Transaction,
+        description: String,
+        value_sol: f64,
+        response: oneshot::Sender<Result<Option<String>>>,
+    },
+    GetTradingMode {
+        user_wallet: String,
+        response: oneshot::Sender<Result<TradingMode>>,
+    },
+    SetTradingMode {
+        user_wallet: String,
+        mode: TradingMode,
+        response: oneshot::Sender<Result<()>>,
+    },
+    GetPaperLedger {
+        user_wallet: String,
+        response: oneshot::Sender<Result<PaperLedger>>,
+    },
+    ResetPaperLedger {
+        user_wallet: String,
+        response: oneshot::Sender<Result<PaperLedger>>,
+    },
     Shutdown,
 }
 
+impl Laned for TradingMessage {
+    /// Each wallet's messages run in the order they were sent
+    fn lane(&self) -> Option<&str> {
+        match self {
+            Self::Buy { user_wallet, .. }
+            | Self::Sell { user_wallet, .. }
+            | Self::BuyWithRebate { user_wallet, .. }
+            | Self::SellWithRebate { user_wallet, .. }
+            | Self::GetBalance { user_wallet, .. }
+            | Self::GetPositions { user_wallet, .. }
+            | Self::SyncPositions { user_wallet, .. }
+            | Self::SubmitTransaction { user_wallet, .. }
+            | Self::GetTradingMode { user_wallet, .. }
+            | Self::SetTradingMode { user_wallet, .. }
+            | Self::GetPaperLedger { user_wallet, .. }
+            | Self::ResetPaperLedger { user_wallet, .. } => Some(user_wallet),
+            Self::Shutdown => None,
+        }
+    }
+}
+
 // Actor handle for external communication with resource management
 #[derive(Clone)]
 pub struct TradingEngineHandle {
-    sender: mpsc::Sender<TradingMessage>,
-    // Resource management
-    request_semaphore: Arc<Semaphore>, // Limit concurrent requests
+    // One ordered lane per wallet; full mailboxes reject instead of waiting
+    mailbox: LaneMailbox<TradingMessage>,
     operation_timeout: Duration,
-    max_queue_size: usize,
+    metrics: Option<Arc<MetricsCollector>>,
 }
 
 #[derive(Debug)]
 pub struct ResourceConfig {
-    pub max_concurrent_requests: usize,
+    /// Messages queued or running across all wallets before senders get `QueueFull`
+    pub mailbox_capacity: usize,
     pub operation_timeout_secs: u64,
-    pub max_queue_size: usize,
-    pub channel_buffer_size: usize,
 }
 
 impl Default for ResourceConfig {
     fn default() -> Self {
         Self {
-            max_concurrent_requests: 10,
+            mailbox_capacity: 100,
             operation_timeout_secs: 30,
-            max_queue_size: 100,
-            channel_buffer_size: 100,
         }
     }
 }
 
 impl TradingEngineHandle {
+    /// Export buy and sell outcomes, the mailbox depth and lane latency
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.mailbox = self.mailbox.with_metrics(metrics.clone());
+        self.metrics = Some(metrics);
+        self
+    }
+    
     /// Send a message to the trading engine (for compatibility with command handlers)
+    ///
+    /// Fails with `BotError::QueueFull` when the engine is at capacity.
     pub fn send(&self, msg: TradingMessage) -> Result<()> {
-        self.sender.try_send(msg)
-            .map_err(|e| match e {
-                mpsc::error::TrySendError::Full(_) => BotError::internal("Trading engine queue full".to_string()),
-                mpsc::error::TrySendError::Closed(_) => BotError::internal("Trading engine unavailable".to_string()),
-            })
+        self.mailbox.try_send(msg)
     }
     
     #[instrument(skip(self))]
@@ -110,26 +185,49 @@ impl TradingEngineHandle {
         token: String,
         amount_sol: f64,
     ) -> Result<TradeResult> {
-        // Acquire resource permit (backpressure)
-        let _permit = self.request_semaphore.acquire().await
-            .map_err(|_| BotError::internal("Request semaphore closed".to_string()))?;
-        
-        // Check queue size for additional backpressure
-        if self.sender.capacity() == 0 {
-            return Err(BotError::internal("Trading engine queue full".to_string()));
-        }
-        
+        self.send_buy(user_wallet, token, amount_sol, None).await
+    }
+    
+    /// Buy at a user's own slippage and priority fee level
+    #[instrument(skip(self))]
+    pub async fn buy_with_defaults(
+        &self,
+        user_wallet: String,
+        token: String,
+        amount_sol: f64,
+        defaults: TradeDefaults,
+    ) -> Result<TradeResult> {
+        self.send_buy(user_wallet, token, amount_sol, Some(defaults)).await
+    }
+    
+    async fn send_buy(
+        &self,
+        user_wallet: String,
+        token: String,
+        amount_sol: f64,
+        defaults: Option<TradeDefaults>,
+    ) -> Result<TradeResult> {
+        let result = self.request_buy(user_wallet, token, amount_sol, defaults).await;
+        self.record_trade("buy", &result);
+        result
+    }
+    
+    async fn request_buy(
+        &self,
+        user_wallet: String,
+        token: String,
+        amount_sol: f64,
+        defaults: Option<TradeDefaults>,
+    ) -> Result<TradeResult> {
         let (tx, rx) = oneshot::channel();
         
-        self.sender
-            .send(TradingMessage::BuyWithRebate {
-                user_wallet,
-                token,
-                amount_sol,
-                response: tx,
-            })
-            .await
-            .map_err(|_| BotError::internal("Trading engine unavailable".to_string()))?;
+        self.mailbox.try_send(TradingMessage::BuyWithRebate {
+            user_wallet,
+            token,
+            amount_sol,
+            defaults,
+            response: tx,
+        })?;
         
         // Apply timeout to prevent resource leaks
         timeout(TokioDuration::from_secs(self.operation_timeout.as_secs()), rx)
@@ -145,24 +243,64 @@ impl TradingEngineHandle {
         token: String,
         percentage: f64,
     ) -> Result<TradeResult> {
-        let _permit = self.request_semaphore.acquire().await
-            .map_err(|_| BotError::internal("Request semaphore closed".to_string()))?;
-        
-        if self.sender.capacity() == 0 {
-            return Err(BotError::internal("Trading engine queue full".to_string()));
-        }
-        
+        self.sell_into(user_wallet, token, percentage, ExitDenomination::Sol).await
+    }
+    
+    /// Sell into SOL at a user's own slippage and priority fee level
+    #[instrument(skip(self))]
+    pub async fn sell_with_defaults(
+        &self,
+        user_wallet: String,
+        token: String,
+        percentage: f64,
+        defaults: TradeDefaults,
+    ) -> Result<TradeResult> {
+        self.send_sell(user_wallet, token, percentage, ExitDenomination::Sol, Some(defaults)).await
+    }
+    
+    /// Sell into SOL or USDC; USDC proceeds are also reported in SOL at the fill's price
+    #[instrument(skip(self))]
+    pub async fn sell_into(
+        &self,
+        user_wallet: String,
+        token: String,
+        percentage: f64,
+        exit: ExitDenomination,
+    ) -> Result<TradeResult> {
+        self.send_sell(user_wallet, token, percentage, exit, None).await
+    }
+    
+    async fn send_sell(
+        &self,
+        user_wallet: String,
+        token: String,
+        percentage: f64,
+        exit: ExitDenomination,
+        defaults: Option<TradeDefaults>,
+    ) -> Result<TradeResult> {
+        let result = self.request_sell(user_wallet, token, percentage, exit, defaults).await;
+        self.record_trade("sell", &result);
+        result
+    }
+    
+    async fn request_sell(
+        &self,
+        user_wallet: String,
+        token: String,
+        percentage: f64,
+        exit: ExitDenomination,
+        defaults: Option<TradeDefaults>,
+    ) -> Result<TradeResult> {
         let (tx, rx) = oneshot::channel();
         
-        self.sender
-            .send(TradingMessage::SellWithRebate {
-                user_wallet,
-                token,
-                percentage,
-                response: tx,
-            })
-            .await
-            .map_err(|_| BotError::internal("Trading engine unavailable".to_string()))?;
+        self.mailbox.try_send(TradingMessage::SellWithRebate {
+            user_wallet,
+            token,
+            percentage,
+            exit,
+            defaults,
+            response: tx,
+        })?;
         
         timeout(TokioDuration::from_secs(self.operation_timeout.as_secs()), rx)
             .await
@@ -174,13 +312,10 @@ impl TradingEngineHandle {
     pub async fn get_balance(&self, user_wallet: String) -> Result<Balance> {
         let (tx, rx) = oneshot::channel();
         
-        self.sender
-            .send(TradingMessage::GetBalance {
-                user_wallet,
-                response: tx,
-            })
-            .await
-            .map_err(|_| BotError::internal("Trading engine unavailable".to_string()))?;
+        self.mailbox.try_send(TradingMessage::GetBalance {
+            user_wallet,
+            response: tx,
+        })?;
         
         rx.await
             .map_err(|_| BotError::internal("Trading engine response failed".to_string()))?
@@ -190,45 +325,95 @@ impl TradingEngineHandle {
     pub async fn get_positions(&self, user_wallet: String) -> Result<Vec<Position>> {
         let (tx, mut rx) = mpsc::channel(1);
         
-        self.sender
-            .send(TradingMessage::GetPositions {
-                user_wallet,
-                response_tx: tx,
-            })
-            .await
-            .map_err(|_| BotError::internal("Trading engine unavailable".to_string()))?;
+        self.mailbox.try_send(TradingMessage::GetPositions {
+            user_wallet,
+            response_tx: tx,
+        })?;
         
         rx.recv().await
             .ok_or_else(|| BotError::internal("Trading engine response failed".to_string()))?
     }
     
+    /// Correct the wallet's stored positions to its on-chain token balances
+    pub async fn sync_positions(&self, user_wallet: String) -> Result<Reconciliation> {
+        let (tx, rx) = oneshot::channel();
+        self.request(TradingMessage::SyncPositions { user_wallet, response: tx }, rx).await
+    }
+    
+    /// Sign and send a prepared transaction the way trades are; `None` when it went back unsigned
+    pub async fn submit_transaction(
+        &self,
+        user_wallet: String,
+        transaction: Transaction,
+        description: String,
+        value_sol: f64,
+    ) -> Result<Option<String>> {
+        let (tx, rx) = oneshot::channel();
+        self.request(TradingMessage::SubmitTransaction { user_wallet, transaction, description, value_sol, response: tx }, rx).await
+    }
+    
+    /// Whether the wallet's trades are simulated
+    pub async fn trading_mode(&self, user_wallet: String) -> Result<TradingMode> {
+        let (tx, rx) = oneshot::channel();
+        self.request(TradingMessage::GetTradingMode { user_wallet, response: tx }, rx).await
+    }
+    
+    pub async fn set_trading_mode(&self, user_wallet: String, mode: TradingMode) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.request(TradingMessage::SetTradingMode { user_wallet, mode, response: tx }, rx).await
+    }
+    
+    /// The wallet's virtual ledger, created at the starting balance on first use
+    pub async fn paper_ledger(&self, user_wallet: String) -> Result<PaperLedger> {
+        let (tx, rx) = oneshot::channel();
+        self.request(TradingMessage::GetPaperLedger { user_wallet, response: tx }, rx).await
+    }
+    
+    /// Drop all paper positions and restore the starting balance
+    pub async fn reset_paper_ledger(&self, user_wallet: String) -> Result<PaperLedger> {
+        let (tx, rx) = oneshot::channel();
+        self.request(TradingMessage::ResetPaperLedger { user_wallet, response: tx }, rx).await
+    }
+    
+    async fn request<T>(&self, msg: TradingMessage, rx: oneshot::Receiver<Result<T>>) -> Result<T> {
+        self.mailbox.try_send(msg)?;
+        
+        rx.await
+            .map_err(|_| BotError::internal("Trading engine response failed".to_string()))?
+    }
+    
+    fn record_trade<T>(&self, trade_type: &str, result: &Result<T>) {
+        if let Some(metrics) = &self.metrics {
+            match result {
+                Ok(_) => metrics.record_trade_executed(trade_type),
+                Err(e) => metrics.record_trade_failed(trade_type, e),
+            }
+        }
+    }
+    
     pub async fn shutdown(&self) {
         info!("Initiating graceful shutdown of trading engine");
         
-        // Send shutdown message
-        if let Err(_) = self.sender.send(TradingMessage::Shutdown).await {
+        // Queued trades still run; new ones are refused
+        if self.mailbox.close(TradingMessage::Shutdown).is_err() {
             warn!("Failed to send shutdown message to trading engine");
         }
         
-        // Close the semaphore to prevent new requests
-        self.request_semaphore.close();
-        
         info!("Trading engine shutdown initiated");
     }
     
     /// Get resource utilization metrics
     pub fn get_resource_metrics(&self) -> ResourceMetrics {
+        let depth = self.mailbox.depth();
+        let capacity = self.mailbox.capacity();
         ResourceMetrics {
-            available_permits: self.request_semaphore.available_permits(),
-            max_permits: 10, // From ResourceConfig::default()
-            queue_capacity: self.sender.capacity(),
-            queue_utilization_percent: {
-                let capacity = self.sender.capacity();
-                if capacity > 0 {
-                    ((self.max_queue_size - capacity) as f64 / self.max_queue_size as f64) * 100.0
-                } else {
-                    100.0
-                }
+            queue_depth: depth,
+            queue_capacity: capacity,
+            active_lanes: self.mailbox.active_lanes(),
+            queue_utilization_percent: if capacity > 0 {
+                (depth as f64 / capacity as f64) * 100.0
+            } else {
+                100.0
             },
         }
     }
@@ -236,12 +421,13 @@ impl TradingEngineHandle {
 
 #[derive(Debug, Clone)]
 pub struct ResourceMetrics {
-    pub available_permits: usize,
-    pub max_permits: usize,
+    /// Messages queued or running
+    pub queue_depth: usize,
     pub queue_capacity: usize,
+    /// Wallets with a lane running
+    pub active_lanes: usize,
     pub queue_utilization_percent: f64,
 }
-}
 
 // TradingEngine actor
 pub struct TradingEngine {
@@ -252,36 +438,90 @@ pub struct TradingEngine {
     jupiter: JupiterSwap,
     token_2022_manager: Token2022Manager,
     token_creator: TokenCreator,
+    compute_budgeter: ComputeBudgeter,
+    price_client: Arc<JupiterPriceV3Client>,
+    // Routes wallets held on a Ledger to the device instead of returning unsigned transactions
+    signer: Option<Arc<TransactionSigner>>,
+    // Sends protected users' device-signed trades as Jito bundles
+    mev: Option<Arc<MevProtection>>,
+    // Refuses buys the buyer's token rules don't allow
+    trade_gate: Arc<TradeGate>,
+    // Paper mode state, cached from the database
+    trading_modes: RwLock<HashMap<String, TradingMode>>,
+    paper_ledgers: RwLock<HashMap<String, PaperLedger>>,
     // Circuit breakers for external services
     jupiter_breaker: CircuitBreaker,
     helius_breaker: CircuitBreaker,
     solana_rpc_breaker: CircuitBreaker,
 }
 
+#[async_trait::async_trait]
+impl LaneHandler<TradingMessage> for TradingEngine {
+    async fn handle(&self, message: TradingMessage) {
+        self.handle_message(message).await
+    }
+}
+
 impl TradingEngine {
     // Create actor and return handle with resource management
-    pub async fn spawn(config: Arc<Config>, db: Arc<Database>) -> Result<TradingEngineHandle> {
+    //
+    // There is no engine without a gate: every buy is checked against the buyer's token rules.
+    pub async fn spawn(config: Arc<Config>, db: Arc<Database>, trade_gate: Arc<TradeGate>) -> Result<TradingEngineHandle> {
+        let price_client = Arc::new(JupiterPriceV3Client::new(Arc::new(JupiterAuthManager::new())));
+        Self::spawn_with_prices(config, db, price_client, trade_gate).await
+    }
+    
+    /// Spawn with the price client paper fills are priced from
+    pub async fn spawn_with_prices(
+        config: Arc<Config>,
+        db: Arc<Database>,
+        price_client: Arc<JupiterPriceV3Client>,
+        trade_gate: Arc<TradeGate>,
+    ) -> Result<TradingEngineHandle> {
+        Self::spawn_with_signer(config, db, price_client, None, trade_gate).await
+    }
+    
+    /// Spawn with a signer that takes Ledger-held wallets' trades to the device
+    pub async fn spawn_with_signer(
+        config: Arc<Config>,
+        db: Arc<Database>,
+        price_client: Arc<JupiterPriceV3Client>,
+        signer: Option<Arc<TransactionSigner>>,
+        trade_gate: Arc<TradeGate>,
+    ) -> Result<TradingEngineHandle> {
+        Self::spawn_with_mev(config, db, price_client, signer, None, trade_gate).await
+    }
+    
+    /// Spawn with Jito bundle submission for users who turned on MEV protection
+    pub async fn spawn_with_mev(
+        config: Arc<Config>,
+        db: Arc<Database>,
+        price_client: Arc<JupiterPriceV3Client>,
+        signer: Option<Arc<TransactionSigner>>,
+        mev: Option<Arc<MevProtection>>,
+        trade_gate: Arc<TradeGate>,
+    ) -> Result<TradingEngineHandle> {
         let resource_config = ResourceConfig::default();
-        let (sender, receiver) = mpsc::channel::<TradingMessage>(resource_config.channel_buffer_size);
         
-        let engine = Self::new(config, db).await?;
+        let mut engine = Self::new(config, db, price_client, trade_gate).await?;
+        engine.signer = signer;
+        engine.mev = mev;
         let handle = TradingEngineHandle { 
-            sender,
-            request_semaphore: Arc::new(Semaphore::new(resource_config.max_concurrent_requests)),
+            mailbox: LaneMailbox::spawn("trading_engine", resource_config.mailbox_capacity, Arc::new(engine)),
             operation_timeout: Duration::from_secs(resource_config.operation_timeout_secs),
-            max_queue_size: resource_config.max_queue_size,
+            metrics: None,
         };
         
-        // Spawn the actor task
-        tokio::spawn(async move {
-            engine.run(receiver).await;
-        });
-        
-        info!("TradingEngine actor spawned with channel buffer size: 100");
+        info!("TradingEngine actor spawned with mailbox capacity: {}", resource_config.mailbox_capacity);
         Ok(handle)
     }
     
-    async fn new(config: Arc<Config>, db: Arc<Database>) -> Result<Self> {
+    async fn new(
+        config: Arc<Config>,
+        db: Arc<Database>,
+        price_client: Arc<JupiterPriceV3Client>,
+        trade_gate: Arc<TradeGate>,
+    ) -> Result<Self> {
         let rpc_url = config.get_rpc_url();
         
         // Create optimized HTTP client for Solana RPC
@@ -310,9 +550,14 @@ impl TradingEngine {
             None
         };
         let helius_client = HeliusClient::new(&config.helius_api_key, rebate_address)?;
-        let jupiter = JupiterSwap::new(rpc_url);
+        let jupiter = JupiterSwap::new(rpc_url)
+            .with_endpoints(config.jupiter_api_url.clone(), config.jupiter_price_api_url.clone());
         let token_2022_manager = Token2022Manager::new();
         let token_creator = TokenCreator::new();
+        let compute_budgeter = ComputeBudgeter::new(
+            Arc::new(RpcClient::new_with_commitment(rpc_url.clone(), CommitmentConfig::confirmed())),
+            ComputeBudgetConfig::default(),
+        );
         
         // Initialize circuit breakers with appropriate configurations
         let jupiter_breaker = CircuitBreaker::new(
@@ -321,6 +566,7 @@ impl TradingEngine {
                 failure_threshold: 3,
                 timeout: Duration::from_secs(30),
                 success_threshold: 2,
+                ..CircuitBreakerConfig::default()
             }
         );
         
@@ -330,6 +576,7 @@ impl TradingEngine {
                 failure_threshold: 5,
                 timeout: Duration::from_secs(60),
                 success_threshold: 3,
+                ..CircuitBreakerConfig::default()
             }
         );
         
@@ -339,6 +586,7 @@ impl TradingEngine {
                 failure_threshold: 3,
                 timeout: Duration::from_secs(45),
                 success_threshold: 2,
+                ..CircuitBreakerConfig::default()
             }
         );
         
@@ -352,77 +600,118 @@ impl TradingEngine {
             jupiter,
             token_2022_manager,
             token_creator,
+            compute_budgeter,
+            price_client,
+            signer: None,
+            mev: None,
+            trade_gate,
+            trading_modes: RwLock::new(HashMap::new()),
+            paper_ledgers: RwLock::new(HashMap::new()),
             jupiter_breaker,
             helius_breaker,
             solana_rpc_breaker,
         })
     }
     
-    // Actor main loop
-    async fn run(mut self, mut receiver: mpsc::Receiver<TradingMessage>) {
-        info!("TradingEngine actor started");
-        
-        while let Some(message) = receiver.recv().await {
-            match message {
-                TradingMessage::Buy {
-                    user_wallet,
-                    token,
-                    amount_sol,
-                    response_tx,
-                } => {
-                    let result = self.buy_with_rebate(&user_wallet, &token, amount_sol).await;
-                    let _ = response_tx.send(result).await;
-                }
-                TradingMessage::Sell {
-                    user_wallet,
-                    token,
-                    percentage,
-                    response_tx,
-                } => {
-                    let result = self.sell_with_rebate(&user_wallet, &token, percentage).await;
-                    let _ = response_tx.send(result).await;
-                }
-                TradingMessage::BuyWithRebate {
-                    user_wallet,
-                    token,
-                    amount_sol,
-                    response,
-                } => {
-                    let result = self.buy_with_rebate(&user_wallet, &token, amount_sol).await;
-                    let _ = response.send(result);
-                }
-                TradingMessage::SellWithRebate {
-                    user_wallet,
-                    token,
-                    percentage,
-                    response,
-                } => {
-                    let result = self.sell_with_rebate(&user_wallet, &token, percentage).await;
-                    let _ = response.send(result);
-                }
-                TradingMessage::GetBalance { user_wallet, response } => {
-                    let result = self.get_balance(&user_wallet).await;
-                    let _ = response.send(result);
-                }
-                TradingMessage::GetPositions { user_wallet, response_tx } => {
-                    let result = self.get_positions(&user_wallet).await;
-                    let _ = response_tx.send(result).await;
-                }
-                TradingMessage::Shutdown => {
-                    info!("TradingEngine actor shutting down");
-                    break;
-                }
+    // Runs one message; the mailbox keeps each wallet's messages in order
+    async fn handle_message(&self, message: TradingMessage) {
+        match message {
+            TradingMessage::Buy {
+                user_wallet,
+                token,
+                amount_sol,
+                defaults,
+                response_tx,
+            } => {
+                let defaults = self.trade_defaults(defaults);
+                let result = self.buy_with_rebate(&user_wallet, &token, amount_sol, defaults).await;
+                let _ = response_tx.send(result).await;
             }
+            TradingMessage::Sell {
+                user_wallet,
+                token,
+                percentage,
+                response_tx,
+            } => {
+                let defaults = self.trade_defaults(None);
+                let result = self.sell_with_rebate(&user_wallet, &token, percentage, ExitDenomination::Sol, defaults).await;
+                let _ = response_tx.send(result).await;
+            }
+            TradingMessage::BuyWithRebate {
+                user_wallet,
+                token,
+                amount_sol,
+                defaults,
+                response,
+            } => {
+                let defaults = self.trade_defaults(defaults);
+                let result = self.buy_with_rebate(&user_wallet, &token, amount_sol, defaults).await;
+                let _ = response.send(result);
+            }
+            TradingMessage::SellWithRebate {
+                user_wallet,
+                token,
+                percentage,
+                exit,
+                defaults,
+                response,
+            } => {
+                let defaults = self.trade_defaults(defaults);
+                let result = self.sell_with_rebate(&user_wallet, &token, percentage, exit, defaults).await;
+                let _ = response.send(result);
+            }
+            TradingMessage::GetBalance { user_wallet, response } => {
+                let result = self.get_balance(&user_wallet).await;
+                let _ = response.send(result);
+            }
+            TradingMessage::GetPositions { user_wallet, response_tx } => {
+                let result = self.get_positions(&user_wallet).await;
+                let _ = response_tx.send(result).await;
+            }
+            TradingMessage::SyncPositions { user_wallet, response } => {
+                let result = self.sync_positions(&user_wallet).await;
+                let _ = response.send(result);
+            }
+            TradingMessage::SubmitTransaction { user_wallet, transaction, description, value_sol, response } => {
+                let result = self.sign_on_device(&user_wallet, &transaction, description, value_sol, None).await
+                    .map(|signed| signed.map(|(signature, _)| signature));
+                let _ = response.send(result);
+            }
+            TradingMessage::GetTradingMode { user_wallet, response } => {
+                let result = self.trading_mode(&user_wallet).await;
+                let _ = response.send(result);
+            }
+            TradingMessage::SetTradingMode { user_wallet, mode, response } => {
+                let result = self.set_trading_mode(&user_wallet, mode).await;
+                let _ = response.send(result);
+            }
+            TradingMessage::GetPaperLedger { user_wallet, response } => {
+                let result = self.paper_ledger(&user_wallet).await;
+                let _ = response.send(result);
+            }
+            TradingMessage::ResetPaperLedger { user_wallet, response } => {
+                let result = self.reset_paper_ledger(&user_wallet).await;
+                let _ = response.send(result);
+            }
+            // Stops the mailbox before reaching a lane
+            TradingMessage::Shutdown => {}
         }
-        
-        info!("TradingEngine actor stopped");
+    }
+    
+    /// Slippage and fee level for a trade that didn't bring its own
+    fn trade_defaults(&self, defaults: Option<TradeDefaults>) -> TradeDefaults {
+        defaults.unwrap_or(TradeDefaults {
+            slippage_bps: self.config.slippage_bps,
+            priority: BudgetUrgency::Standard.priority(),
+        })
     }
     
     async fn buy_with_rebate(
-        &mut self,
+        &self,
         user_wallet: &str,
         token: &str,
         amount_sol: f64,
+        defaults: TradeDefaults,
     ) -> Result<TradeResult> {
         info!("Preparing buy order for {} with {} SOL for wallet {}", token, amount_sol, user_wallet);
         
@@ -433,6 +722,14 @@ impl TradingEngine {
         
         let token_mint = self.resolve_token_mint(token).await?;
         
+        // The buyer's token rules apply to paper buys too
+        self.trade_gate.check_wallet(user_wallet, &token_mint).await?;
+        
+        // Paper mode never reaches Jupiter or builds a transaction
+        if self.trading_mode(user_wallet).await?.is_paper() {
+            return self.paper_buy(user_wallet, &token_mint, amount_sol).await;
+        }
+        
         // Check Token-2022 restrictions before trading
         let restrictions = self.check_token_restrictions(&token_mint).await?;
         if restrictions.is_non_transferable {
@@ -443,32 +740,61 @@ impl TradingEngine {
             "So11111111111111111111111111111111111112", // SOL mint
             &token_mint,
             amount_sol,
-            self.config.slippage_bps,
+            defaults.slippage_bps,
         ).await?;
         
         // Calculate expected tokens after potential transfer fees
         let expected_tokens = quote.out_amount.parse::<u64>().unwrap_or(0);
         let (effective_tokens, transfer_fee) = self.calculate_effective_transfer_amount(&token_mint, expected_tokens).await?;
         
+        let report = ExecutionReport::quoted(amount_sol / (expected_tokens as f64 / 1e9), RouteSummary::from(&quote))
+            .with_idempotency_key(format!("buy:{}:{}:{}:{}", user_wallet, token_mint, quote.in_amount, quote.context_slot.unwrap_or_default()));
+        let guard = QuoteGuard {
+            trade_type: TradeType::Buy,
+            token_mint: token_mint.clone(),
+            amount_in: quote.in_amount.parse().unwrap_or((amount_sol * 1e9) as u64),
+            quoted_out: expected_tokens as f64 / 1e9,
+            exit: ExitDenomination::Sol,
+            tolerance_bps: defaults.slippage_bps,
+        };
+        
         // Build unsigned transaction
         let swap_tx = self.jupiter.build_swap_transaction(
             quote,
             user_wallet,
             self.config.priority_fee_lamports,
         ).await?;
+        let (swap_tx, budget) = self.budget_transaction(&swap_tx, BudgetUrgency::Standard, defaults.priority).await;
         
-        // Return transaction for user to sign
-        let result = TradeResult {
-            tx_signature: "UNSIGNED_TRANSACTION".to_string(), // User needs to sign
-            tokens_received: effective_tokens as f64 / 1e9,
-            tokens_sold: 0.0,
-            sol_received: 0.0,
-            price: amount_sol / (effective_tokens as f64 / 1e9),
-            rebate_earned: 0.0, // Will be calculated after actual execution
-            pnl_percentage: 0.0,
-            timestamp: chrono::Utc::now(),
-            trade_type: TradeType::Buy,
+        // Ledger-held wallets sign on the device; everyone else gets the transaction to sign
+        let description = format!("Buy {} with {} SOL", token, amount_sol);
+        let signed = self.sign_on_device(user_wallet, &swap_tx, description, amount_sol, Some(&guard)).await?;
+        let fill = match &signed {
+            Some((signature, bundle)) => self.verify_fill(signature, user_wallet, WSOL_MINT, &token_mint, bundle.as_ref()).await,
+            None => None,
         };
+        let (tx_signature, bundle) = signed.unwrap_or_else(|| ("UNSIGNED_TRANSACTION".to_string(), None));
+        
+        // Sent trades are priced from what actually landed; unsigned ones from the quote
+        let tokens_received = fill.map_or(effective_tokens as f64 / 1e9, |fill| fill.amount_out);
+        let price = fill.map_or(amount_sol / tokens_received, |fill| fill.price(TradeType::Buy));
+        let mut result = TradeResult::buy(
+            tx_signature,
+            tokens_received,
+            amount_sol,
+            price,
+        ).with_execution(
+            report
+                .filled(price, TradeType::Buy)
+                .with_fees(self.execution_fees(transfer_fee))
+                .with_compute_budget(&budget)
+                .with_bundle(bundle)
+                .with_partial_fill(fill.and_then(|fill| fill.partial_fill(amount_sol)))
+                .simulated(false),
+        );
+        if fill.is_some() {
+            result = result.with_verified_fill();
+        }
         
         if transfer_fee > 0 {
             info!(
@@ -486,12 +812,14 @@ impl TradingEngine {
     }
     
     async fn sell_with_rebate(
-        &mut self,
+        &self,
         user_wallet: &str,
         token: &str,
         percentage: f64,
+        exit: ExitDenomination,
+        defaults: TradeDefaults,
     ) -> Result<TradeResult> {
-        info!("Executing sell order for {}% of {}", percentage, token);
+        info!("Executing sell order for {}% of {} into {}", percentage, token, exit.label());
         
         Validator::validate_percentage(percentage)?;
         
@@ -500,6 +828,10 @@ impl TradingEngine {
         
         let token_mint = self.resolve_token_mint(token).await?;
         
+        if self.trading_mode(user_wallet).await?.is_paper() {
+            return self.paper_sell(user_wallet, &token_mint, percentage).await;
+        }
+        
         // Check Token-2022 restrictions before trading
         let restrictions = self.check_token_restrictions(&token_mint).await?;
         if restrictions.is_non_transferable {
@@ -522,33 +854,68 @@ impl TradingEngine {
             info!("Transfer fee will be deducted: {} tokens", transfer_fee as f64 / 1e9);
         }
         
-        let quote = self.jupiter.get_quote(
-            &token_mint,
-            "So11111111111111111111111111111111111112",
-            effective_amount as f64 / 1e9, // Use effective amount for quote
-            self.config.slippage_bps,
-        ).await?;
+        // Quote on the effective amount; USDC exits pick the better of direct and via-SOL
+        let plan = plan_exit(&self.jupiter, &token_mint, effective_amount, defaults.slippage_bps, exit).await?;
+        let settlement = ExitSettlement::new(&plan, self.get_sol_price().await?);
+        let amount_out = plan.amount_out();
+        let quote = plan.quote;
+        
+        let report = ExecutionReport::quoted(
+            amount_out / (effective_amount as f64 / 1e9),
+            RouteSummary::from(&quote),
+        ).with_idempotency_key(format!("sell:{}:{}:{}:{}", user_wallet, token_mint, quote.in_amount, quote.context_slot.unwrap_or_default()));
+        let guard = QuoteGuard {
+            trade_type: TradeType::Sell,
+            token_mint: token_mint.clone(),
+            amount_in: effective_amount,
+            quoted_out: amount_out,
+            exit,
+            tolerance_bps: defaults.slippage_bps,
+        };
         
         let swap_tx = self.jupiter.build_swap_transaction(
             quote,
             user_wallet,
             self.config.priority_fee_lamports,
         ).await?;
+        let (swap_tx, budget) = self.budget_transaction(&swap_tx, BudgetUrgency::Standard, defaults.priority).await;
         
-        // Return transaction for user to sign - in non-custodial mode
-        let mut result = TradeResult {
-            tx_signature: "UNSIGNED_TRANSACTION".to_string(), // User needs to sign
-            tokens_received: 0.0,
-            tokens_sold: amount_to_sell,
-            sol_received: quote.out_amount.parse::<f64>().unwrap_or(0.0) / 1e9,
-            price: (quote.out_amount.parse::<f64>().unwrap_or(1.0) / 1e9) / (effective_amount as f64 / 1e9),
-            rebate_earned: 0.0, // Will be calculated after actual execution
-            pnl_percentage: 0.0,
-            timestamp: chrono::Utc::now(),
-            trade_type: TradeType::Sell,
+        // SOL-denominated fields stay in SOL: USDC proceeds convert at the fill's SOL price
+        let sol_received = settlement.value_sol();
+        
+        // Ledger-held wallets sign on the device; everyone else gets the transaction to sign
+        let description = format!("Sell {}% of {} into {}", percentage, token, exit.label());
+        let signed = self.sign_on_device(user_wallet, &swap_tx, description, sol_received, Some(&guard)).await?;
+        let fill = match &signed {
+            Some((signature, bundle)) => self.verify_fill(signature, user_wallet, &token_mint, exit.mint(), bundle.as_ref()).await,
+            None => None,
         };
+        let (tx_signature, bundle) = signed.unwrap_or_else(|| ("UNSIGNED_TRANSACTION".to_string(), None));
         
-        result.tokens_sold = amount_to_sell;
+        // Sent trades are priced from what actually landed; unsigned ones from the quote
+        let (tokens_sold, proceeds) = fill.map_or((amount_to_sell, amount_out), |fill| (fill.amount_in, fill.amount_out));
+        let sol_received = sol_received * proceeds / amount_out;
+        let mut result = TradeResult::sell(
+            tx_signature,
+            tokens_sold,
+            sol_received,
+            fill.map_or(sol_received / (effective_amount as f64 / 1e9), |_| sol_received / tokens_sold),
+        );
+        
+        // Realized price is per token actually sold (in the exit token), so transfer fees show up as slippage
+        result = result.with_execution(
+            report
+                .filled(proceeds / tokens_sold, TradeType::Sell)
+                .with_fees(self.execution_fees(transfer_fee))
+                .with_compute_budget(&budget)
+                .with_exit(settlement)
+                .with_bundle(bundle)
+                .with_partial_fill(fill.and_then(|fill| fill.partial_fill(amount_to_sell)))
+                .simulated(false),
+        );
+        if fill.is_some() {
+            result = result.with_verified_fill();
+        }
         
         let pnl = self.db.calculate_pnl(
             user_wallet,
@@ -573,6 +940,12 @@ impl TradingEngine {
     }
     
     async fn get_balance(&self, user_wallet: &str) -> Result<Balance> {
+        if self.trading_mode(user_wallet).await?.is_paper() {
+            let ledger = self.paper_ledger(user_wallet).await?;
+            let (sol_usd, prices) = self.paper_prices(&ledger).await;
+            return Ok(ledger.balance(sol_usd, &prices));
+        }
+        
         let user_pubkey = Pubkey::from_str(user_wallet)?;
         let sol_balance = self.rpc_client
             .get_balance(&user_pubkey).await?;
@@ -594,9 +967,179 @@ impl TradingEngine {
     }
     
     async fn get_positions(&self, user_wallet: &str) -> Result<Vec<Position>> {
+        if self.trading_mode(user_wallet).await?.is_paper() {
+            let ledger = self.paper_ledger(user_wallet).await?;
+            let (sol_usd, prices) = self.paper_prices(&ledger).await;
+            return Ok(ledger.positions(sol_usd, &prices));
+        }
+        
         self.db.get_user_positions(user_wallet).await
     }
     
+    /// Diff stored positions against the wallet's token accounts and store the corrected set
+    async fn sync_positions(&self, user_wallet: &str) -> Result<Reconciliation> {
+        if self.trading_mode(user_wallet).await?.is_paper() {
+            return Err(BotError::validation("Paper positions live in the virtual ledger, so there is nothing on-chain to sync".to_string()));
+        }
+        
+        let owner = Pubkey::from_str(user_wallet)?;
+        let mut accounts = Vec::new();
+        for program in [SPL_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+            let program_id = Pubkey::from_str(program)
+                .map_err(|e| BotError::internal(format!("Invalid token program id: {}", e)))?;
+            for keyed in self.rpc_client.get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(program_id)).await? {
+                if let UiAccountData::Json(parsed) = keyed.account.data {
+                    accounts.push(parsed.parsed);
+                }
+            }
+        }
+        
+        let tracked = self.db.get_user_positions(user_wallet).await?;
+        let mut reconciliation = reconcile(&tracked, &on_chain_balances(&accounts), chrono::Utc::now());
+        let mints: Vec<String> = reconciliation.positions.iter().map(|p| p.mint.clone()).collect();
+        if !mints.is_empty() {
+            match self.price_client.get_prices(mints).await {
+                Ok(response) => {
+                    let prices = response.prices.into_iter().map(|(mint, data)| (mint, data.usd_price)).collect();
+                    reconciliation.apply_prices(&prices);
+                }
+                Err(e) => debug!("Position sync prices unavailable: {}", e),
+            }
+        }
+        
+        if !reconciliation.is_clean() {
+            self.db.replace_user_positions(user_wallet, &reconciliation.positions).await?;
+            info!("🔄 Synced positions for {}: {} changes", user_wallet, reconciliation.changes.len());
+        }
+        Ok(reconciliation)
+    }
+    
+    /// The wallet's stored mode; wallets that never chose follow `enable_paper_trading`
+    async fn trading_mode(&self, user_wallet: &str) -> Result<TradingMode> {
+        if let Some(mode) = self.trading_modes.read().await.get(user_wallet) {
+            return Ok(*mode);
+        }
+        
+        let stored = self.db.get_trading_mode(user_wallet).await?
+            .and_then(|code| TradingMode::from_code(&code));
+        let mode = stored.unwrap_or(if self.config.enable_paper_trading { TradingMode::Paper } else { TradingMode::Live });
+        self.trading_modes.write().await.insert(user_wallet.to_string(), mode);
+        Ok(mode)
+    }
+    
+    async fn set_trading_mode(&self, user_wallet: &str, mode: TradingMode) -> Result<()> {
+        self.db.set_trading_mode(user_wallet, mode.code()).await?;
+        self.trading_modes.write().await.insert(user_wallet.to_string(), mode);
+        info!("Trading mode for {} set to {}", user_wallet, mode.code());
+        Ok(())
+    }
+    
+    async fn paper_ledger(&self, user_wallet: &str) -> Result<PaperLedger> {
+        if let Some(ledger) = self.paper_ledgers.read().await.get(user_wallet) {
+            return Ok(ledger.clone());
+        }
+        
+        let stored = match self.db.get_paper_ledger(user_wallet).await? {
+            Some(data) => serde_json::from_str(&data)
+                .map_err(|e| BotError::parsing(format!("Failed to parse paper ledger for {}: {}", user_wallet, e)))?,
+            None => PaperLedger::new(self.config.paper_starting_balance_sol),
+        };
+        self.paper_ledgers.write().await.insert(user_wallet.to_string(), stored.clone());
+        Ok(stored)
+    }
+    
+    async fn store_paper_ledger(&self, user_wallet: &str, ledger: &PaperLedger) -> Result<()> {
+        let data = serde_json::to_string(ledger)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize paper ledger for {}: {}", user_wallet, e)))?;
+        self.db.upsert_paper_ledger(user_wallet, &data).await
+    }
+    
+    async fn reset_paper_ledger(&self, user_wallet: &str) -> Result<PaperLedger> {
+        let ledger = PaperLedger::new(self.config.paper_starting_balance_sol);
+        self.store_paper_ledger(user_wallet, &ledger).await?;
+        self.paper_ledgers.write().await.insert(user_wallet.to_string(), ledger.clone());
+        info!("Paper ledger for {} reset to {} SOL", user_wallet, ledger.starting_sol);
+        Ok(ledger)
+    }
+    
+    /// Simulated buy at the current price plus synthetic slippage
+    async fn paper_buy(&self, user_wallet: &str, token_mint: &str, amount_sol: f64) -> Result<TradeResult> {
+        let price = self.paper_price_sol(token_mint).await?;
+        let fill = simulate_fill(price, TradeType::Buy, self.config.paper_slippage_bps);
+        let tokens = amount_sol / fill;
+        let symbol = TokenResolver::get_symbol(token_mint);
+        
+        let mut ledger = self.paper_ledger(user_wallet).await?;
+        ledger.buy(token_mint, &symbol, amount_sol, tokens)?;
+        self.store_paper_ledger(user_wallet, &ledger).await?;
+        self.paper_ledgers.write().await.insert(user_wallet.to_string(), ledger);
+        
+        info!("Paper buy: {} {} for {} SOL at {} SOL each", tokens, symbol, amount_sol, fill);
+        Ok(TradeResult::buy(Self::paper_signature(), tokens, amount_sol, fill)
+            .with_execution(Self::paper_report(price, fill, TradeType::Buy, user_wallet, token_mint)))
+    }
+    
+    /// Simulated sell of `percentage` of the paper position
+    async fn paper_sell(&self, user_wallet: &str, token_mint: &str, percentage: f64) -> Result<TradeResult> {
+        let mut ledger = self.paper_ledger(user_wallet).await?;
+        if ledger.holding(token_mint) <= 0.0 {
+            return Err(TradingError::no_tokens_to_sell(token_mint).into());
+        }
+        
+        let price = self.paper_price_sol(token_mint).await?;
+        let fill = simulate_fill(price, TradeType::Sell, self.config.paper_slippage_bps);
+        let sale = ledger.sell(token_mint, percentage, fill)?;
+        self.store_paper_ledger(user_wallet, &ledger).await?;
+        self.paper_ledgers.write().await.insert(user_wallet.to_string(), ledger);
+        
+        info!("Paper sell: {} of {} for {} SOL, P&L: {:.2}%", sale.tokens_sold, token_mint, sale.sol_received, sale.pnl_percentage);
+        let mut result = TradeResult::sell(Self::paper_signature(), sale.tokens_sold, sale.sol_received, fill)
+            .with_execution(Self::paper_report(price, fill, TradeType::Sell, user_wallet, token_mint));
+        result.pnl_percentage = sale.pnl_percentage;
+        Ok(result)
+    }
+    
+    fn paper_report(price: f64, fill: f64, trade_type: TradeType, user_wallet: &str, token_mint: &str) -> ExecutionReport {
+        let side = if trade_type == TradeType::Sell { "sell" } else { "buy" };
+        ExecutionReport::quoted(price, RouteSummary { venues: vec!["Paper".to_string()], hops: 0, price_impact_pct: None })
+            .filled(fill, trade_type)
+            .simulated(true)
+            .with_idempotency_key(format!("paper:{}:{}:{}:{}", side, user_wallet, token_mint, chrono::Utc::now().timestamp_millis()))
+    }
+    
+    fn paper_signature() -> String {
+        format!("PAPER-{}", uuid::Uuid::new_v4())
+    }
+    
+    /// Current token price in SOL from the price API
+    async fn paper_price_sol(&self, token_mint: &str) -> Result<f64> {
+        let response = self.price_client.get_prices(vec![token_mint.to_string(), WSOL_MINT.to_string()]).await?;
+        let usd = |mint: &str| response.prices.get(mint).map(|p| p.usd_price).filter(|p| *p > 0.0);
+        match (usd(token_mint), usd(WSOL_MINT)) {
+            (Some(token_usd), Some(sol_usd)) => Ok(token_usd / sol_usd),
+            _ => Err(BotError::external_api(format!("No price available for {}", token_mint))),
+        }
+    }
+    
+    /// SOL and token USD prices for valuing a ledger; unpriced tokens are left out
+    async fn paper_prices(&self, ledger: &PaperLedger) -> (f64, BTreeMap<String, f64>) {
+        let mut mints: Vec<String> = ledger.positions.keys().cloned().collect();
+        mints.push(WSOL_MINT.to_string());
+        match self.price_client.get_prices(mints).await {
+            Ok(response) => {
+                let mut prices: BTreeMap<String, f64> = response.prices.into_iter()
+                    .map(|(mint, data)| (mint, data.usd_price))
+                    .collect();
+                let sol_usd = prices.remove(WSOL_MINT).unwrap_or(0.0);
+                (sol_usd, prices)
+            }
+            Err(e) => {
+                debug!("Paper ledger prices unavailable: {}", e);
+                (0.0, BTreeMap::new())
+            }
+        }
+    }
+    
     async fn get_token_balance_for_user(&self, user_pubkey: &Pubkey, mint: &str) -> Result<f64> {
         // For now, return a placeholder balance since we removed SPL dependencies
         // In production, you'd implement proper SPL token balance checking
@@ -656,19 +1199,174 @@ impl TradingEngine {
     async fn send_regular_transaction(&self, tx: Transaction) -> Result<TradeResult> {
         let signature = self.rpc_client.send_and_confirm_transaction(&tx).await?;
         
+        let mut execution = ExecutionReport::default().filled(0.001, TradeType::Swap);
+        execution.record_broadcast(false);
+        
         Ok(TradeResult {
             tx_signature: signature.to_string(),
             tokens_received: 100.0,
             tokens_sold: 0.0,
             sol_received: 0.0,
+            amount_sol: 0.0,
             price: 0.001,
             rebate_earned: 0.0,
             pnl_percentage: 0.0,
             timestamp: chrono::Utc::now(),
             trade_type: TradeType::Swap,
+            execution,
+            quoted_price: None,
+            executed_price: None,
+            realized_slippage_bps: None,
         })
     }
     
+    /// Sign and send on the user's Ledger when their wallet is held on one
+    ///
+    /// `None` means the wallet isn't device-held and the transaction goes back unsigned;
+    /// a policy refusal, rejection on the device, timeout, or a quote that moved past
+    /// `guard`'s tolerance while the user approved aborts the trade. Transactions without
+    /// a quote, such as lending deposits, pass no guard. With MEV
+    /// protection on, the transaction carries a Jito tip and goes out as a bundle,
+    /// falling back to plain RPC if the bundle doesn't land in time.
+    async fn sign_on_device(
+        &self,
+        user_wallet: &str,
+        tx: &Transaction,
+        description: String,
+        value_sol: f64,
+        guard: Option<&QuoteGuard>,
+    ) -> Result<Option<(String, Option<BundleReceipt>)>> {
+        let Some(signer) = &self.signer else { return Ok(None) };
+        let Some((strategy, owner)) = signer.device_for(user_wallet).await
+            .map_err(|e| BotError::trading(format!("Trade aborted: {}", e)))?
+        else {
+            return Ok(None);
+        };
+        
+        let mev = match &self.mev {
+            Some(mev) if mev.is_enabled_for(owner).await => Some(mev),
+            _ => None,
+        };
+        let (tx, tip) = match mev {
+            Some(mev) => {
+                let (tipped, tip) = mev.prepare(tx, &Pubkey::from_str(user_wallet)?).await;
+                (tipped, Some(tip))
+            }
+            None => (tx.clone(), None),
+        };
+        
+        let request = SigningRequest {
+            transaction: tx,
+            user_id: owner.to_string(),
+            wallet_address: user_wallet.to_string(),
+            estimated_sol_cost: (self.config.priority_fee_lamports + tip.unwrap_or(0)) as f64 / 1e9,
+            value_sol,
+            description,
+            requires_approval: true,
+        };
+        let signed = strategy.sign(&request).await
+            .map_err(|e| BotError::trading(format!("Trade aborted: {}", e)))?;
+        let Some(signed_tx) = signed.signed_transaction.filter(|_| signed.success) else {
+            let reason = signed.error.unwrap_or_else(|| "the device didn't sign".to_string());
+            return Err(BotError::trading(format!("Trade aborted: {}", reason)));
+        };
+        
+        // Approving on the device takes a while; don't send if the market moved past tolerance meanwhile
+        if let Some(guard) = guard {
+            self.recheck_quote(guard).await?;
+        }
+        
+        let mut bundle = None;
+        if let (Some(mev), Some(tip)) = (mev, tip) {
+            let mut receipt = mev.submit(&signed_tx, tip).await;
+            if receipt.landed {
+                let signature = signed_tx.signatures.first().copied().unwrap_or_default();
+                info!("🛡️ Ledger-signed trade landed via Jito bundle for {}: {}", user_wallet, signature);
+                return Ok(Some((signature.to_string(), Some(receipt))));
+            }
+            // Same signature, so the bundle landing late can't execute the swap twice
+            mev.record_fallback(&mut receipt);
+            bundle = Some(receipt);
+        }
+        
+        let signature = self.rpc_client.send_transaction(&signed_tx).await
+            .map_err(|e| BotError::trading(format!("Signed on the Ledger but failed to send: {}", e)))?;
+        info!("🔐 Ledger-signed trade sent for {}: {}", user_wallet, signature);
+        Ok(Some((signature.to_string(), bundle)))
+    }
+    
+    /// Quote the guarded trade again and fail if it degraded past the user's tolerance
+    async fn recheck_quote(&self, guard: &QuoteGuard) -> Result<()> {
+        self.jupiter.forget_quotes(&guard.token_mint).await;
+        let refreshed_out = match guard.trade_type {
+            TradeType::Sell => plan_exit(&self.jupiter, &guard.token_mint, guard.amount_in, guard.tolerance_bps, guard.exit).await?.amount_out(),
+            TradeType::Buy | TradeType::Swap => {
+                let quote = self.jupiter.get_quote(WSOL_MINT, &guard.token_mint, guard.amount_in as f64 / 1e9, guard.tolerance_bps).await?;
+                quote.out_amount.parse::<u64>().unwrap_or(0) as f64 / 1e9
+            }
+        };
+        
+        if let Err(e) = guard.check(refreshed_out) {
+            warn!(
+                "🛑 Aborting {:?} of {}: quote fell from {} to {} ({:.0} bps)",
+                guard.trade_type, guard.token_mint, guard.quoted_out, refreshed_out, guard.degradation_bps(refreshed_out)
+            );
+            return Err(e);
+        }
+        Ok(())
+    }
+    
+    /// What the sent swap actually moved, once confirmed; `None` if it can't be read in time
+    async fn verify_fill(
+        &self,
+        signature: &str,
+        user_wallet: &str,
+        input_mint: &str,
+        output_mint: &str,
+        bundle: Option<&BundleReceipt>,
+    ) -> Option<FillAmounts> {
+        let parsed = Signature::from_str(signature).ok()?;
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let tip_lamports = bundle.map_or(0, |bundle| bundle.tip_lamports);
+        
+        for _ in 0..FILL_CONFIRM_POLLS {
+            match self.rpc_client.get_transaction_with_config(&parsed, config).await {
+                Ok(confirmed) => {
+                    let fill = FillAmounts::from_transaction(&confirmed.transaction, user_wallet, input_mint, output_mint, tip_lamports);
+                    if fill.is_none() {
+                        warn!("Couldn't read the fill of {} from its transaction", signature);
+                    }
+                    return fill;
+                }
+                Err(_) => tokio::time::sleep(FILL_POLL_INTERVAL).await,
+            }
+        }
+        warn!("Fill of {} not confirmed in time, reporting the quoted amounts", signature);
+        None
+    }
+    
+    /// Size the compute budget from a simulation and prepend it to the swap
+    async fn budget_transaction(&self, tx: &Transaction, urgency: BudgetUrgency, priority: TransactionPriority) -> (Transaction, ComputeBudget) {
+        let (budgeted, budget) = self.compute_budgeter.budget_transaction_at(tx, urgency, priority).await;
+        if let Some(units) = budget.simulated_units {
+            self.compute_budgeter.record_usage(budget.unit_limit, units);
+        }
+        (budgeted, budget)
+    }
+    
+    fn execution_fees(&self, transfer_fee: u64) -> ExecutionFees {
+        ExecutionFees {
+            network_fee_sol: 0.000005, // Base signature fee
+            priority_fee_lamports: self.config.priority_fee_lamports,
+            platform_fee_sol: 0.0,
+            transfer_fee_tokens: transfer_fee as f64 / 1e9,
+        }
+    }
+    
     fn load_wallet(private_key: &str) -> Result<Keypair> {
         let decoded = bs58::decode(private_key).into_vec()?;
         Ok(Keypair::from_bytes(&decoded)?)
@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::api::jupiter_v6::{JupiterV6Client, QuoteRequestV6, QuoteResponseV6, SwapMode};
+use crate::errors::{BotError, Result};
+use super::dex::{JupiterQuote, JupiterSwap};
+
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// What protective exits and panic sells are paid out in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExitDenomination {
+    #[default]
+    Sol,
+    /// Locks gains in against a later SOL drawdown
+    Usdc,
+}
+
+impl ExitDenomination {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "sol" => Some(Self::Sol),
+            "usdc" | "stables" | "stable" => Some(Self::Usdc),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Sol => "SOL",
+            Self::Usdc => "USDC",
+        }
+    }
+
+    pub fn mint(&self) -> &'static str {
+        match self {
+            Self::Sol => WSOL_MINT,
+            Self::Usdc => USDC_MINT,
+        }
+    }
+
+    pub fn decimals(&self) -> u32 {
+        match self {
+            Self::Sol => 9,
+            Self::Usdc => 6,
+        }
+    }
+
+    /// Short form for callback data
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Sol => "sol",
+            Self::Usdc => "usdc",
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Self::Sol => Self::Usdc,
+            Self::Usdc => Self::Sol,
+        }
+    }
+}
+
+/// Route an exit took to its denomination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitPath {
+    /// Token → SOL
+    Sol,
+    /// Token → USDC in a single pool
+    Direct,
+    /// Token → SOL → USDC as one Jupiter route
+    ViaSol,
+}
+
+impl ExitPath {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Sol => "to SOL",
+            Self::Direct => "direct to USDC",
+            Self::ViaSol => "via SOL to USDC",
+        }
+    }
+}
+
+/// What a candidate route pays out, in the output token's smallest unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteQuote {
+    /// Output after LP fees and price impact
+    pub out_amount: u64,
+    /// Platform fee taken from the output
+    pub fee_amount: u64,
+    pub price_impact_pct: f64,
+    pub hops: usize,
+}
+
+impl RouteQuote {
+    /// What actually lands in the wallet
+    pub fn net_out(&self) -> u64 {
+        self.out_amount.saturating_sub(self.fee_amount)
+    }
+}
+
+/// Quotes a sell of `amount` (input token units) into `output_mint`
+#[async_trait::async_trait]
+pub trait ExitQuoter: Send + Sync {
+    /// The quote the caller builds its swap from
+    type Quote: Send;
+
+    /// `None` when no route exists
+    async fn exit_quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+        only_direct: bool,
+    ) -> Result<Option<Self::Quote>>;
+
+    fn summarize(quote: &Self::Quote) -> RouteQuote;
+}
+
+/// The chosen route for one exit
+#[derive(Debug, Clone)]
+pub struct ExitPlan<Q> {
+    pub denomination: ExitDenomination,
+    pub path: ExitPath,
+    pub quote: Q,
+    pub summary: RouteQuote,
+}
+
+impl<Q> ExitPlan<Q> {
+    /// Proceeds in whole exit tokens
+    pub fn amount_out(&self) -> f64 {
+        self.summary.net_out() as f64 / 10f64.powi(self.denomination.decimals() as i32)
+    }
+}
+
+/// The better of a direct and a two-hop USDC route; the direct pool wins ties
+pub fn choose_usdc_path(direct: Option<&RouteQuote>, two_hop: Option<&RouteQuote>) -> Option<ExitPath> {
+    match (direct, two_hop) {
+        (Some(direct), Some(two_hop)) if two_hop.net_out() > direct.net_out() => Some(ExitPath::ViaSol),
+        (Some(_), _) => Some(ExitPath::Direct),
+        (None, Some(_)) => Some(ExitPath::ViaSol),
+        (None, None) => None,
+    }
+}
+
+/// Route a sell of `amount` of `input_mint` into `denomination`
+///
+/// USDC exits compare a single-pool route with Jupiter's multi-hop route
+/// (through SOL for most long-tail tokens) and keep the one that nets more.
+pub async fn plan_exit<E: ExitQuoter + ?Sized>(
+    quoter: &E,
+    input_mint: &str,
+    amount: u64,
+    slippage_bps: u16,
+    denomination: ExitDenomination,
+) -> Result<ExitPlan<E::Quote>> {
+    let no_route = || BotError::trading(format!("No route from {} to {}", input_mint, denomination.label()));
+
+    if denomination == ExitDenomination::Sol {
+        let quote = quoter.exit_quote(input_mint, WSOL_MINT, amount, slippage_bps, false).await?.ok_or_else(no_route)?;
+        let summary = E::summarize(&quote);
+        return Ok(ExitPlan { denomination, path: ExitPath::Sol, quote, summary });
+    }
+
+    // Either candidate failing is fine as long as the other routes
+    let direct = quoter.exit_quote(input_mint, USDC_MINT, amount, slippage_bps, true).await;
+    let two_hop = quoter.exit_quote(input_mint, USDC_MINT, amount, slippage_bps, false).await;
+    let (direct, two_hop) = match (direct, two_hop) {
+        (Err(e), Err(_)) => return Err(e),
+        (direct, two_hop) => (direct.ok().flatten(), two_hop.ok().flatten()),
+    };
+
+    let direct_summary = direct.as_ref().map(E::summarize);
+    let two_hop_summary = two_hop.as_ref().map(E::summarize);
+    let path = choose_usdc_path(direct_summary.as_ref(), two_hop_summary.as_ref()).ok_or_else(no_route)?;
+    debug!(
+        "🏦 USDC exit for {}: direct {:?}, two-hop {:?}, chose {:?}",
+        input_mint, direct_summary.map(|q| q.net_out()), two_hop_summary.map(|q| q.net_out()), path
+    );
+
+    let (quote, summary) = match path {
+        ExitPath::ViaSol => (two_hop, two_hop_summary),
+        _ => (direct, direct_summary),
+    };
+    match (quote, summary) {
+        (Some(quote), Some(summary)) => Ok(ExitPlan { denomination, path, quote, summary }),
+        _ => Err(no_route().into()),
+    }
+}
+
+/// An exit as it settled, for SOL-based accounting
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExitSettlement {
+    pub denomination: ExitDenomination,
+    pub path: ExitPath,
+    /// Proceeds in whole exit tokens
+    pub amount_out: f64,
+    /// SOL price in USD when the exit executed
+    pub sol_usd: f64,
+}
+
+impl ExitSettlement {
+    pub fn new<Q>(plan: &ExitPlan<Q>, sol_usd: f64) -> Self {
+        Self { denomination: plan.denomination, path: plan.path, amount_out: plan.amount_out(), sol_usd }
+    }
+
+    /// Proceeds in SOL at the execution-time price
+    ///
+    /// Converting at fill time keeps a USDC exit's PnL fixed in the SOL
+    /// performance series; later SOL moves don't rewrite it.
+    pub fn value_sol(&self) -> f64 {
+        self.price_in_sol(self.amount_out)
+    }
+
+    /// An amount or per-token price in the exit token, in SOL
+    pub fn price_in_sol(&self, value: f64) -> f64 {
+        match self.denomination {
+            ExitDenomination::Sol => value,
+            ExitDenomination::Usdc if self.sol_usd > 0.0 => value / self.sol_usd,
+            ExitDenomination::Usdc => 0.0,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self.denomination {
+            ExitDenomination::Sol => format!("{:.4} SOL", self.amount_out),
+            ExitDenomination::Usdc => format!(
+                "{:.2} USDC ({}, ≈{:.4} SOL at ${:.2})",
+                self.amount_out, self.path.label(), self.value_sol(), self.sol_usd
+            ),
+        }
+    }
+}
+
+/// Each user's default exit denomination
+#[async_trait::async_trait]
+pub trait ExitPreferences: Send + Sync {
+    async fn exit_denomination(&self, user_id: i64) -> ExitDenomination;
+}
+
+#[async_trait::async_trait]
+impl ExitQuoter for JupiterV6Client {
+    type Quote = QuoteResponseV6;
+
+    async fn exit_quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+        only_direct: bool,
+    ) -> Result<Option<QuoteResponseV6>> {
+        let request = QuoteRequestV6 {
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            amount,
+            slippage_bps,
+            swap_mode: Some(SwapMode::ExactIn),
+            dexes: None,
+            exclude_dexes: None,
+            max_accounts: Some(32),
+            quote_mint: None,
+            minimize_slippage: Some(true),
+            only_direct_routes: Some(only_direct),
+        };
+        match self.get_quote(request).await {
+            Ok(quote) => Ok(Some(quote)),
+            Err(e) if is_no_route(&e.to_string()) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn summarize(quote: &QuoteResponseV6) -> RouteQuote {
+        RouteQuote {
+            out_amount: quote.out_amount.parse().unwrap_or(0),
+            fee_amount: quote.platform_fee.as_ref().and_then(|f| f.amount.parse().ok()).unwrap_or(0),
+            price_impact_pct: quote.price_impact_pct.parse().unwrap_or(0.0),
+            hops: quote.route_plan.len(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ExitQuoter for JupiterSwap {
+    type Quote = JupiterQuote;
+
+    async fn exit_quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u16,
+        only_direct: bool,
+    ) -> Result<Option<JupiterQuote>> {
+        match self.get_routed_quote(input_mint, output_mint, amount as f64 / 1e9, slippage_bps, only_direct).await {
+            Ok(quote) => Ok(Some(quote)),
+            Err(e) if is_no_route(&e.to_string()) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn summarize(quote: &JupiterQuote) -> RouteQuote {
+        RouteQuote {
+            out_amount: quote.out_amount.parse().unwrap_or(0),
+            fee_amount: 0,
+            price_impact_pct: quote.price_impact_pct,
+            hops: quote.route_plan.len(),
+        }
+    }
+}
+
+/// Jupiter answers "no route" with an error rather than an empty quote
+fn is_no_route(error: &str) -> bool {
+    let error = error.to_uppercase();
+    error.contains("NO_ROUTE") || error.contains("COULD_NOT_FIND_ANY_ROUTE")
+}
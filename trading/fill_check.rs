@@ -0,0 +1,142 @@
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedTransaction, EncodedTransactionWithStatusMeta, UiMessage,
+    UiTransactionTokenBalance,
+};
+
+use crate::errors::{BotError, Result};
+use super::exit_routing::{ExitDenomination, WSOL_MINT};
+use super::types::{PartialFill, TradeType};
+
+/// Rent locked in a new SPL token account, so opening one isn't counted as swap input
+pub const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
+
+/// Input consumed below this share of the quote is reported as a partial fill
+const PARTIAL_FILL_THRESHOLD: f64 = 0.995;
+
+/// What a confirmed swap actually moved for the trader, in whole tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillAmounts {
+    pub amount_in: f64,
+    pub amount_out: f64,
+}
+
+impl FillAmounts {
+    /// Read the trader's side of a swap from its confirmed transaction
+    ///
+    /// SOL moves are native lamports plus any wrapped SOL left open; the network fee,
+    /// `tip_lamports` and rent for token accounts the swap opened are taken out first.
+    /// Failed transactions, or ones where the trader didn't both pay and receive, yield `None`.
+    pub fn from_transaction(
+        transaction: &EncodedTransactionWithStatusMeta,
+        owner: &str,
+        input_mint: &str,
+        output_mint: &str,
+        tip_lamports: u64,
+    ) -> Option<Self> {
+        let meta = transaction.meta.as_ref()?;
+        if meta.err.is_some() {
+            return None;
+        }
+        let EncodedTransaction::Json(ui_transaction) = &transaction.transaction else { return None };
+        let mut keys: Vec<String> = match &ui_transaction.message {
+            UiMessage::Raw(message) => message.account_keys.clone(),
+            UiMessage::Parsed(message) => message.account_keys.iter().map(|k| k.pubkey.clone()).collect(),
+        };
+        if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+            keys.extend(loaded.writable.iter().cloned());
+            keys.extend(loaded.readonly.iter().cloned());
+        }
+
+        let pre_tokens = owned_balances(&meta.pre_token_balances, &keys, owner);
+        let post_tokens = owned_balances(&meta.post_token_balances, &keys, owner);
+        let token_delta = |mint: &str| -> f64 {
+            let sum = |set: &[&UiTransactionTokenBalance]| {
+                set.iter().filter(|b| b.mint == mint).map(|b| b.ui_token_amount.ui_amount.unwrap_or(0.0)).sum::<f64>()
+            };
+            sum(&post_tokens) - sum(&pre_tokens)
+        };
+        let opened = post_tokens.iter()
+            .filter(|post| !pre_tokens.iter().any(|pre| pre.account_index == post.account_index))
+            .count() as u64;
+
+        let owner_index = keys.iter().position(|k| k == owner)?;
+        let pre = *meta.pre_balances.get(owner_index)? as i128;
+        let post = *meta.post_balances.get(owner_index)? as i128;
+        // The trader pays the fee, so the fee, tip and new account rent all left the same account
+        let excluded = meta.fee as i128 + tip_lamports as i128 + (opened * TOKEN_ACCOUNT_RENT_LAMPORTS) as i128;
+        let native_sol = (post - pre + excluded) as f64 / 1e9;
+
+        let change = |mint: &str| {
+            if mint == WSOL_MINT { native_sol + token_delta(mint) } else { token_delta(mint) }
+        };
+        let amount_in = -change(input_mint);
+        let amount_out = change(output_mint);
+
+        (amount_in > 0.0 && amount_out > 0.0).then_some(Self { amount_in, amount_out })
+    }
+
+    /// Price per token: input per token bought for buys, output per token sold for sells
+    pub fn price(&self, trade_type: TradeType) -> f64 {
+        match trade_type {
+            TradeType::Sell => self.amount_out / self.amount_in,
+            TradeType::Buy | TradeType::Swap => self.amount_in / self.amount_out,
+        }
+    }
+
+    /// Set when the swap consumed noticeably less input than the quote asked for
+    pub fn partial_fill(&self, requested_in: f64) -> Option<PartialFill> {
+        (requested_in > 0.0 && self.amount_in < requested_in * PARTIAL_FILL_THRESHOLD)
+            .then_some(PartialFill { requested_in, filled_in: self.amount_in })
+    }
+}
+
+/// Token balances held by `owner`, resolving owners the RPC left out from the account keys
+fn owned_balances<'a>(
+    set: &'a OptionSerializer<Vec<UiTransactionTokenBalance>>,
+    keys: &[String],
+    owner: &str,
+) -> Vec<&'a UiTransactionTokenBalance> {
+    let OptionSerializer::Some(set) = set else { return Vec::new() };
+    set.iter()
+        .filter(|balance| match &balance.owner {
+            OptionSerializer::Some(balance_owner) => balance_owner == owner,
+            _ => keys.get(balance.account_index as usize).is_some_and(|key| key == owner),
+        })
+        .collect()
+}
+
+/// The quote a signed trade was accepted on, checked again before it is sent
+#[derive(Debug, Clone)]
+pub struct QuoteGuard {
+    pub trade_type: TradeType,
+    pub token_mint: String,
+    /// Raw input amount the swap was built for
+    pub amount_in: u64,
+    /// Output the accepted quote promised, in whole tokens
+    pub quoted_out: f64,
+    /// Where a sell pays out; buys always spend SOL
+    pub exit: ExitDenomination,
+    pub tolerance_bps: u16,
+}
+
+impl QuoteGuard {
+    /// How far `refreshed_out` fell short of the accepted quote; negative when it improved
+    pub fn degradation_bps(&self, refreshed_out: f64) -> f64 {
+        if self.quoted_out <= 0.0 {
+            return 0.0;
+        }
+        (self.quoted_out - refreshed_out) / self.quoted_out * 10_000.0
+    }
+
+    /// Fails when the refreshed quote lost more than the user's slippage tolerance
+    pub fn check(&self, refreshed_out: f64) -> Result<()> {
+        let degradation = self.degradation_bps(refreshed_out);
+        if degradation > self.tolerance_bps as f64 {
+            return Err(BotError::trading(format!(
+                "Trade aborted: the quote moved {:.0} bps against you since you accepted it, over your {} bps slippage tolerance. Nothing was sent.",
+                degradation, self.tolerance_bps
+            )));
+        }
+        Ok(())
+    }
+}
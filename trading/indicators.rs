@@ -0,0 +1,126 @@
+//! Technical indicators over a price series, oldest first
+//!
+//! Every function returns `None` when the series is too short for the period
+//! asked for, so callers can tell "not enough data" from a real reading.
+
+/// MACD line, its signal line and the gap between them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Macd {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// Bollinger bands around a simple moving average
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerBands {
+    pub lower: f64,
+    pub middle: f64,
+    pub upper: f64,
+}
+
+impl BollingerBands {
+    /// Where `price` sits in the bands: 0 at the lower band, 1 at the upper
+    pub fn percent_b(&self, price: f64) -> f64 {
+        let width = self.upper - self.lower;
+        if width == 0.0 {
+            0.5
+        } else {
+            (price - self.lower) / width
+        }
+    }
+}
+
+/// Simple moving average of the last `period` prices
+pub fn sma(prices: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || prices.len() < period {
+        return None;
+    }
+    Some(prices[prices.len() - period..].iter().sum::<f64>() / period as f64)
+}
+
+/// Linearly weighted moving average of the last `period` prices, newest weighted most
+pub fn wma(prices: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || prices.len() < period {
+        return None;
+    }
+    let window = &prices[prices.len() - period..];
+    let weighted: f64 = window.iter().enumerate().map(|(i, price)| (i + 1) as f64 * price).sum();
+    Some(weighted / (period * (period + 1) / 2) as f64)
+}
+
+/// Exponential moving average series, seeded with the SMA of the first `period` prices
+///
+/// The first value lines up with `prices[period - 1]`.
+pub fn ema_series(prices: &[f64], period: usize) -> Option<Vec<f64>> {
+    let seed = sma(&prices[..period.min(prices.len())], period)?;
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let mut series = Vec::with_capacity(prices.len() - period + 1);
+    series.push(seed);
+    for price in &prices[period..] {
+        let previous = series[series.len() - 1];
+        series.push(alpha * price + (1.0 - alpha) * previous);
+    }
+    Some(series)
+}
+
+/// Latest exponential moving average
+pub fn ema(prices: &[f64], period: usize) -> Option<f64> {
+    ema_series(prices, period)?.last().copied()
+}
+
+/// Relative strength index with Wilder's smoothing; needs `period + 1` prices
+pub fn rsi(prices: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || prices.len() <= period {
+        return None;
+    }
+    let changes: Vec<f64> = prices.windows(2).map(|w| w[1] - w[0]).collect();
+
+    let (mut gain, mut loss) = changes[..period].iter().fold((0.0, 0.0), |(gain, loss), change| {
+        (gain + change.max(0.0), loss + (-change).max(0.0))
+    });
+    gain /= period as f64;
+    loss /= period as f64;
+
+    for change in &changes[period..] {
+        gain = (gain * (period as f64 - 1.0) + change.max(0.0)) / period as f64;
+        loss = (loss * (period as f64 - 1.0) + (-change).max(0.0)) / period as f64;
+    }
+
+    Some(if loss == 0.0 {
+        if gain == 0.0 { 50.0 } else { 100.0 }
+    } else {
+        100.0 - 100.0 / (1.0 + gain / loss)
+    })
+}
+
+/// MACD from fast and slow EMAs, with an EMA of the MACD line as signal
+///
+/// Needs `slow + signal - 1` prices.
+pub fn macd(prices: &[f64], fast: usize, slow: usize, signal: usize) -> Option<Macd> {
+    if fast == 0 || fast >= slow || signal == 0 {
+        return None;
+    }
+    let fast_series = ema_series(prices, fast)?;
+    let slow_series = ema_series(prices, slow)?;
+
+    // Align the fast series with the slow one, which starts later
+    let offset = slow - fast;
+    let macd_line: Vec<f64> = slow_series.iter()
+        .zip(&fast_series[offset..])
+        .map(|(slow, fast)| fast - slow)
+        .collect();
+    let signal_line = ema(&macd_line, signal)?;
+    let macd = *macd_line.last()?;
+
+    Some(Macd { macd, signal: signal_line, histogram: macd - signal_line })
+}
+
+/// Bollinger bands: SMA of `period` prices plus/minus `std_devs` population standard deviations
+pub fn bollinger_bands(prices: &[f64], period: usize, std_devs: f64) -> Option<BollingerBands> {
+    let middle = sma(prices, period)?;
+    let window = &prices[prices.len() - period..];
+    let variance = window.iter().map(|price| (price - middle).powi(2)).sum::<f64>() / period as f64;
+    let spread = std_devs * variance.sqrt();
+    Some(BollingerBands { lower: middle - spread, middle, upper: middle + spread })
+}
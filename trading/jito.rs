@@ -0,0 +1,392 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_sdk::{message::Message, pubkey::Pubkey, system_instruction, transaction::Transaction};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::errors::{BotError, Result};
+use super::compute_budget::decompile;
+use super::types::BundleReceipt;
+
+pub const JITO_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf";
+pub const JITO_TIP_FLOOR_URL: &str = "https://bundles.jito.wtf/api/v1/bundles/tip_floor";
+/// Jito's published tip accounts; one is picked per bundle to spread write locks
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// Whether a user's trades go out as Jito bundles
+#[async_trait::async_trait]
+pub trait MevPreferences: Send + Sync {
+    async fn mev_protection(&self, user_id: i64) -> bool;
+}
+
+/// How the tip on each bundle is sized
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TipStrategy {
+    /// The same tip every time
+    Static { lamports: u64 },
+    /// A percentile of tips that landed recently
+    Percentile { percentile: f64 },
+    /// Above the 95th percentile of recent landed tips, for trades that must land first
+    Aggressive { multiplier: f64 },
+}
+
+impl Default for TipStrategy {
+    fn default() -> Self {
+        TipStrategy::Percentile { percentile: 75.0 }
+    }
+}
+
+impl TipStrategy {
+    pub fn label(&self) -> String {
+        match self {
+            TipStrategy::Static { lamports } => format!("static {:.6} SOL", *lamports as f64 / 1e9),
+            TipStrategy::Percentile { percentile } => format!("{:.0}th percentile of recent tips", percentile),
+            TipStrategy::Aggressive { multiplier } => format!("aggressive ({:.1}× the 95th percentile)", multiplier),
+        }
+    }
+
+    /// Tip for the next bundle, clamped to the configured floor and cap
+    ///
+    /// Recent-tip strategies use the fallback tip when the tip floor is unavailable.
+    pub fn tip_lamports(&self, recent: Option<&RecentTips>, config: &JitoConfig) -> u64 {
+        let tip = match (self, recent) {
+            (TipStrategy::Static { lamports }, _) => *lamports,
+            (TipStrategy::Percentile { percentile }, Some(recent)) => recent.percentile(*percentile),
+            (TipStrategy::Aggressive { multiplier }, Some(recent)) => {
+                (recent.percentile(95.0) as f64 * multiplier.max(1.0)).round() as u64
+            }
+            (_, None) => config.fallback_tip_lamports,
+        };
+        tip.clamp(config.min_tip_lamports, config.max_tip_lamports.max(config.min_tip_lamports))
+    }
+}
+
+/// Landed tip percentiles from the tip floor API, in lamports
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RecentTips {
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p95: u64,
+    pub p99: u64,
+}
+
+/// One entry of the tip floor response; values are in SOL
+#[derive(Debug, Deserialize)]
+struct TipFloor {
+    landed_tips_25th_percentile: f64,
+    landed_tips_50th_percentile: f64,
+    landed_tips_75th_percentile: f64,
+    landed_tips_95th_percentile: f64,
+    landed_tips_99th_percentile: f64,
+}
+
+impl RecentTips {
+    fn from_tip_floor(floor: &TipFloor) -> Self {
+        let lamports = |sol: f64| (sol.max(0.0) * 1e9).round() as u64;
+        Self {
+            p25: lamports(floor.landed_tips_25th_percentile),
+            p50: lamports(floor.landed_tips_50th_percentile),
+            p75: lamports(floor.landed_tips_75th_percentile),
+            p95: lamports(floor.landed_tips_95th_percentile),
+            p99: lamports(floor.landed_tips_99th_percentile),
+        }
+    }
+
+    /// Interpolated between the published percentiles; below the 25th uses the 25th
+    pub fn percentile(&self, percentile: f64) -> u64 {
+        let points = [(25.0, self.p25), (50.0, self.p50), (75.0, self.p75), (95.0, self.p95), (99.0, self.p99)];
+        let percentile = percentile.clamp(25.0, 99.0);
+        for pair in points.windows(2) {
+            let ((low_p, low), (high_p, high)) = (pair[0], pair[1]);
+            if percentile <= high_p {
+                let t = (percentile - low_p) / (high_p - low_p);
+                return (low as f64 + (high as f64 - low as f64) * t).round() as u64;
+            }
+        }
+        self.p99
+    }
+}
+
+/// Settings for bundle submission
+#[derive(Debug, Clone)]
+pub struct JitoConfig {
+    pub block_engine_url: String,
+    pub tip_floor_url: String,
+    pub strategy: TipStrategy,
+    /// Jito drops bundles tipping less than this
+    pub min_tip_lamports: u64,
+    pub max_tip_lamports: u64,
+    /// Used by recent-tip strategies when the tip floor can't be fetched
+    pub fallback_tip_lamports: u64,
+    /// How long to wait for the bundle before sending through RPC instead
+    pub landing_timeout: Duration,
+    pub poll_interval: Duration,
+}
+
+impl Default for JitoConfig {
+    fn default() -> Self {
+        Self {
+            block_engine_url: JITO_BLOCK_ENGINE_URL.to_string(),
+            tip_floor_url: JITO_TIP_FLOOR_URL.to_string(),
+            strategy: TipStrategy::default(),
+            min_tip_lamports: 1_000,
+            max_tip_lamports: 5_000_000,
+            fallback_tip_lamports: 50_000,
+            landing_timeout: Duration::from_secs(20),
+            poll_interval: Duration::from_millis(750),
+        }
+    }
+}
+
+/// Where a submitted bundle stands with the block engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleStatus {
+    Pending,
+    Landed { slot: Option<u64> },
+    Failed,
+    /// Unknown to the block engine, or older than its five-minute lookback
+    Invalid,
+}
+
+/// Append a tip transfer to an unsigned swap transaction
+///
+/// The tip rides in the swap transaction itself, so it is paid however the
+/// transaction lands and can't be unbundled from the swap.
+pub fn add_tip(transaction: &Transaction, payer: &Pubkey, tip_account: &Pubkey, lamports: u64) -> Transaction {
+    let message = &transaction.message;
+    let mut instructions = decompile(message);
+    instructions.push(system_instruction::transfer(payer, tip_account, lamports));
+
+    let mut rebuilt = Message::new(&instructions, Some(payer));
+    rebuilt.recent_blockhash = message.recent_blockhash;
+    Transaction::new_unsigned(rebuilt)
+}
+
+/// Counters behind `/mev stats`
+#[derive(Debug, Default)]
+struct BundleCounters {
+    sent: AtomicU64,
+    landed: AtomicU64,
+    failed: AtomicU64,
+    timed_out: AtomicU64,
+    rpc_fallbacks: AtomicU64,
+    tips_paid_lamports: AtomicU64,
+    landing_ms_total: AtomicU64,
+}
+
+/// Snapshot of bundle submission since start-up
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BundleStats {
+    pub sent: u64,
+    pub landed: u64,
+    /// Rejected or dropped by the block engine
+    pub failed: u64,
+    /// Still pending at the landing timeout
+    pub timed_out: u64,
+    /// Trades sent through plain RPC after their bundle didn't land
+    pub rpc_fallbacks: u64,
+    /// Tips on landed bundles
+    pub tips_paid_lamports: u64,
+    pub average_landing_ms: Option<u64>,
+}
+
+impl BundleStats {
+    /// Landed as a share of bundles sent, in percent
+    pub fn landing_rate(&self) -> Option<f64> {
+        (self.sent > 0).then(|| self.landed as f64 / self.sent as f64 * 100.0)
+    }
+}
+
+/// Submits protected users' trades to the Jito block engine as tipped bundles
+pub struct MevProtection {
+    client: Client,
+    config: JitoConfig,
+    preferences: Option<Arc<dyn MevPreferences>>,
+    counters: BundleCounters,
+    next_tip_account: AtomicU64,
+}
+
+impl MevProtection {
+    pub fn new(config: JitoConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config,
+            preferences: None,
+            counters: BundleCounters::default(),
+            next_tip_account: AtomicU64::new(0),
+        }
+    }
+
+    /// Source of each user's protection setting; without one nobody is protected
+    pub fn with_preferences(mut self, preferences: Arc<dyn MevPreferences>) -> Self {
+        self.preferences = Some(preferences);
+        self
+    }
+
+    pub fn config(&self) -> &JitoConfig {
+        &self.config
+    }
+
+    pub async fn is_enabled_for(&self, user_id: i64) -> bool {
+        match &self.preferences {
+            Some(preferences) => preferences.mev_protection(user_id).await,
+            None => false,
+        }
+    }
+
+    /// Landed tip percentiles, or `None` when the tip floor is unavailable
+    pub async fn recent_tips(&self) -> Option<RecentTips> {
+        let response = match self.client.get(&self.config.tip_floor_url).timeout(Duration::from_secs(5)).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                debug!("Jito tip floor unavailable: {}", e);
+                return None;
+            }
+        };
+        match response.json::<Vec<TipFloor>>().await {
+            Ok(floors) => floors.first().map(RecentTips::from_tip_floor),
+            Err(e) => {
+                debug!("Unreadable Jito tip floor: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Size the tip from the configured strategy and append it to the swap
+    pub async fn prepare(&self, transaction: &Transaction, payer: &Pubkey) -> (Transaction, u64) {
+        let recent = match self.config.strategy {
+            TipStrategy::Static { .. } => None,
+            _ => self.recent_tips().await,
+        };
+        let tip = self.config.strategy.tip_lamports(recent.as_ref(), &self.config);
+        let index = self.next_tip_account.fetch_add(1, Ordering::Relaxed) as usize % JITO_TIP_ACCOUNTS.len();
+        let tip_account = Pubkey::from_str(JITO_TIP_ACCOUNTS[index]).expect("valid tip account");
+        (add_tip(transaction, payer, &tip_account, tip), tip)
+    }
+
+    /// Send a signed transaction as a one-transaction bundle and wait for it to land
+    ///
+    /// A receipt that hasn't landed means the caller should broadcast through RPC
+    /// and then call `record_fallback`.
+    pub async fn submit(&self, signed: &Transaction, tip_lamports: u64) -> BundleReceipt {
+        let mut receipt = BundleReceipt { tip_lamports, ..BundleReceipt::default() };
+        let started = Instant::now();
+
+        let bundle_id = match self.send_bundle(signed).await {
+            Ok(bundle_id) => bundle_id,
+            Err(e) => {
+                warn!("Jito bundle rejected: {}", e);
+                self.counters.failed.fetch_add(1, Ordering::Relaxed);
+                return receipt;
+            }
+        };
+        self.counters.sent.fetch_add(1, Ordering::Relaxed);
+        receipt.bundle_id = Some(bundle_id.clone());
+
+        loop {
+            match self.bundle_status(&bundle_id).await {
+                Ok(BundleStatus::Landed { slot }) => {
+                    let elapsed = started.elapsed().as_millis() as u64;
+                    self.counters.landed.fetch_add(1, Ordering::Relaxed);
+                    self.counters.tips_paid_lamports.fetch_add(tip_lamports, Ordering::Relaxed);
+                    self.counters.landing_ms_total.fetch_add(elapsed, Ordering::Relaxed);
+                    info!("📦 Bundle {} landed in slot {:?} after {}ms", bundle_id, slot, elapsed);
+                    receipt.landed = true;
+                    receipt.landing_ms = Some(elapsed);
+                    return receipt;
+                }
+                Ok(BundleStatus::Failed) => {
+                    warn!("Jito bundle {} failed", bundle_id);
+                    self.counters.failed.fetch_add(1, Ordering::Relaxed);
+                    return receipt;
+                }
+                Ok(BundleStatus::Pending) | Ok(BundleStatus::Invalid) => {}
+                Err(e) => debug!("Bundle status for {} unavailable: {}", bundle_id, e),
+            }
+
+            if started.elapsed() + self.config.poll_interval > self.config.landing_timeout {
+                warn!("Jito bundle {} not landed after {:?}", bundle_id, self.config.landing_timeout);
+                self.counters.timed_out.fetch_add(1, Ordering::Relaxed);
+                return receipt;
+            }
+            tokio::time::sleep(self.config.poll_interval).await;
+        }
+    }
+
+    /// Count a trade that went out through RPC after its bundle didn't land
+    pub fn record_fallback(&self, receipt: &mut BundleReceipt) {
+        receipt.fell_back_to_rpc = true;
+        self.counters.rpc_fallbacks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> BundleStats {
+        let landed = self.counters.landed.load(Ordering::Relaxed);
+        BundleStats {
+            sent: self.counters.sent.load(Ordering::Relaxed),
+            landed,
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            timed_out: self.counters.timed_out.load(Ordering::Relaxed),
+            rpc_fallbacks: self.counters.rpc_fallbacks.load(Ordering::Relaxed),
+            tips_paid_lamports: self.counters.tips_paid_lamports.load(Ordering::Relaxed),
+            average_landing_ms: (landed > 0)
+                .then(|| self.counters.landing_ms_total.load(Ordering::Relaxed) / landed),
+        }
+    }
+
+    async fn send_bundle(&self, signed: &Transaction) -> Result<String> {
+        let encoded = STANDARD.encode(bincode::serialize(signed)?);
+        let result = self.rpc("bundles", "sendBundle", json!([[encoded], { "encoding": "base64" }])).await?;
+        result.as_str()
+            .map(String::from)
+            .ok_or_else(|| BotError::external_api("No bundle ID returned by Jito"))
+    }
+
+    async fn bundle_status(&self, bundle_id: &str) -> Result<BundleStatus> {
+        let result = self.rpc("getInflightBundleStatuses", "getInflightBundleStatuses", json!([[bundle_id]])).await?;
+        let Some(status) = result["value"].get(0) else {
+            return Ok(BundleStatus::Invalid);
+        };
+        Ok(match status["status"].as_str() {
+            Some("Landed") => BundleStatus::Landed { slot: status["landed_slot"].as_u64() },
+            Some("Failed") => BundleStatus::Failed,
+            Some("Pending") => BundleStatus::Pending,
+            _ => BundleStatus::Invalid,
+        })
+    }
+
+    async fn rpc(&self, path: &str, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let response = self.client
+            .post(format!("{}/api/v1/{}", self.config.block_engine_url, path))
+            .timeout(Duration::from_secs(5))
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::external_api(format!("Jito {} failed ({}): {}", method, status, body)));
+        }
+
+        let mut body: serde_json::Value = response.json().await?;
+        if let Some(error) = body.get("error").filter(|e| !e.is_null()) {
+            return Err(BotError::external_api(format!("Jito {} error: {}", method, error)));
+        }
+        Ok(body["result"].take())
+    }
+}
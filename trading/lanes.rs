@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use async_trait::async_trait;
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore, TryAcquireError};
+use tracing::{debug, info};
+
+use crate::errors::{BotError, Result};
+use crate::monitoring::MetricsCollector;
+
+/// A message that must run in order with the others in its lane
+pub trait Laned: Send + 'static {
+    /// Lane key, e.g. the user's wallet; `None` stops the mailbox
+    fn lane(&self) -> Option<&str>;
+}
+
+/// Runs one message; a lane awaits it before starting the next
+#[async_trait]
+pub trait LaneHandler<M>: Send + Sync + 'static {
+    async fn handle(&self, message: M);
+}
+
+struct Envelope<M> {
+    message: M,
+    queued_at: Instant,
+    // Held until the message has run, so the capacity covers queued and running work
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+struct Lane<M> {
+    sender: mpsc::UnboundedSender<Envelope<M>>,
+    pending: Arc<AtomicUsize>,
+}
+
+struct Shared {
+    name: &'static str,
+    capacity: usize,
+    permits: Arc<Semaphore>,
+    lanes: AtomicUsize,
+    metrics: OnceLock<Arc<MetricsCollector>>,
+}
+
+impl Shared {
+    fn depth(&self) -> usize {
+        self.capacity - self.permits.available_permits()
+    }
+
+    fn record_depth(&self) {
+        if let Some(metrics) = self.metrics.get() {
+            metrics.record_queue_depth(self.name, self.depth());
+            metrics.record_queue_depth(&format!("{}_lanes", self.name), self.lanes.load(Ordering::Relaxed));
+        }
+    }
+}
+
+/// Bounded mailbox that runs each lane's messages strictly in order
+///
+/// Different lanes run concurrently. At most `capacity` messages can be queued or
+/// running at once; past that, `try_send` fails with `BotError::QueueFull` instead
+/// of waiting. Idle lanes are dropped and restarted on their next message.
+pub struct LaneMailbox<M> {
+    sender: mpsc::UnboundedSender<Envelope<M>>,
+    shared: Arc<Shared>,
+}
+
+impl<M> Clone for LaneMailbox<M> {
+    fn clone(&self) -> Self {
+        Self { sender: self.sender.clone(), shared: self.shared.clone() }
+    }
+}
+
+impl<M: Laned> LaneMailbox<M> {
+    /// Start the router task; `name` labels the queue in metrics and errors
+    pub fn spawn<H: LaneHandler<M>>(name: &'static str, capacity: usize, handler: Arc<H>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let shared = Arc::new(Shared {
+            name,
+            capacity,
+            permits: Arc::new(Semaphore::new(capacity)),
+            lanes: AtomicUsize::new(0),
+            metrics: OnceLock::new(),
+        });
+        tokio::spawn(Self::route(receiver, handler, shared.clone()));
+        Self { sender, shared }
+    }
+
+    /// Export depth, active lanes and lane latency; the first collector set wins
+    pub fn with_metrics(self, metrics: Arc<MetricsCollector>) -> Self {
+        let _ = self.shared.metrics.set(metrics);
+        self
+    }
+
+    /// Queue a message, or fail straight away when the mailbox is full
+    pub fn try_send(&self, message: M) -> Result<()> {
+        let permit = self.shared.permits.clone().try_acquire_owned()
+            .map_err(|e| match e {
+                TryAcquireError::NoPermits => BotError::queue_full(self.shared.name),
+                TryAcquireError::Closed => BotError::internal(format!("{} unavailable", self.shared.name)),
+            })?;
+        self.enqueue(message, Some(permit))
+    }
+
+    /// Stop taking messages; lanes finish what they already hold
+    ///
+    /// Skips the capacity check so a full mailbox can still be shut down.
+    pub fn close(&self, message: M) -> Result<()> {
+        self.enqueue(message, None)
+    }
+
+    fn enqueue(&self, message: M, permit: Option<OwnedSemaphorePermit>) -> Result<()> {
+        self.sender
+            .send(Envelope { message, queued_at: Instant::now(), permit })
+            .map_err(|_| BotError::internal(format!("{} unavailable", self.shared.name)))?;
+        self.shared.record_depth();
+        Ok(())
+    }
+
+    /// Messages queued or running across all lanes
+    pub fn depth(&self) -> usize {
+        self.shared.depth()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// Lanes with a worker running
+    pub fn active_lanes(&self) -> usize {
+        self.shared.lanes.load(Ordering::Relaxed)
+    }
+
+    async fn route<H: LaneHandler<M>>(
+        mut receiver: mpsc::UnboundedReceiver<Envelope<M>>,
+        handler: Arc<H>,
+        shared: Arc<Shared>,
+    ) {
+        let mut lanes: HashMap<String, Lane<M>> = HashMap::new();
+
+        while let Some(envelope) = receiver.recv().await {
+            let Some(key) = envelope.message.lane().map(str::to_string) else {
+                break;
+            };
+
+            if !lanes.contains_key(&key) {
+                // Drop lanes with nothing queued or running; their workers exit
+                lanes.retain(|_, lane| lane.pending.load(Ordering::Acquire) > 0);
+                let (sender, lane_receiver) = mpsc::unbounded_channel();
+                let pending = Arc::new(AtomicUsize::new(0));
+                tokio::spawn(Self::run_lane(lane_receiver, pending.clone(), handler.clone(), shared.clone()));
+                lanes.insert(key.clone(), Lane { sender, pending });
+                shared.lanes.store(lanes.len(), Ordering::Relaxed);
+            }
+
+            let lane = &lanes[&key];
+            lane.pending.fetch_add(1, Ordering::AcqRel);
+            if lane.sender.send(envelope).is_err() {
+                lane.pending.fetch_sub(1, Ordering::AcqRel);
+                debug!("Lane {} of {} stopped before its message arrived", key, shared.name);
+            }
+        }
+
+        // Later sends fail as unavailable; queued work still runs
+        shared.permits.close();
+        receiver.close();
+        info!("{} stopped taking messages, draining {} lanes", shared.name, lanes.len());
+    }
+
+    async fn run_lane<H: LaneHandler<M>>(
+        mut receiver: mpsc::UnboundedReceiver<Envelope<M>>,
+        pending: Arc<AtomicUsize>,
+        handler: Arc<H>,
+        shared: Arc<Shared>,
+    ) {
+        while let Some(envelope) = receiver.recv().await {
+            let started = Instant::now();
+            let Envelope { message, queued_at, permit } = envelope;
+            handler.handle(message).await;
+
+            if let Some(metrics) = shared.metrics.get() {
+                metrics.record_lane_latency(shared.name, started - queued_at, started.elapsed());
+            }
+            drop(permit);
+            pending.fetch_sub(1, Ordering::AcqRel);
+            shared.record_depth();
+        }
+    }
+}
@@ -1,13 +1,17 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc, Duration};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{info, warn, debug};
 
 use crate::db::Database;
 use crate::errors::BotError;
+use super::public_stats::{AggregatePrivacy, AggregateSnapshot, PublicStatsBoard};
+use super::types::TradeProvenance;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraderStats {
@@ -36,6 +40,12 @@ pub struct TraderStats {
     pub performance_30d: f64,
     pub sharpe_ratio: f64,
     pub max_drawdown_percent: f64,
+    /// Opted in to the public leaderboards
+    #[serde(default)]
+    pub leaderboard_visible: bool,
+    /// Opted in to being copied
+    #[serde(default)]
+    pub is_copyable: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +60,8 @@ pub struct Trade {
     pub timestamp: DateTime<Utc>,
     pub trade_type: TradeType,
     pub status: TradeStatus,
+    #[serde(default)]
+    pub provenance: TradeProvenance,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -89,6 +101,9 @@ pub struct LeaderboardEntry {
     pub user_id: i64,
     pub username: String,
     pub profit_percent: f64,
+    /// Realized profit in SOL over the period
+    #[serde(default)]
+    pub profit_sol: f64,
     pub total_trades: u32,
     pub win_rate: f64,
     pub volume_sol: f64,
@@ -97,7 +112,7 @@ pub struct LeaderboardEntry {
     pub copy_fee_percent: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LeaderboardPeriod {
     Daily,
     Weekly,
@@ -105,7 +120,7 @@ pub enum LeaderboardPeriod {
     AllTime,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LeaderboardMetric {
     Profit,
     Volume,
@@ -114,34 +129,406 @@ pub enum LeaderboardMetric {
     SharpeRatio,
 }
 
+impl LeaderboardPeriod {
+    /// Start of the window, `None` for all time
+    pub fn since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Daily => Some(now - Duration::days(1)),
+            Self::Weekly => Some(now - Duration::days(7)),
+            Self::Monthly => Some(now - Duration::days(30)),
+            Self::AllTime => None,
+        }
+    }
+}
+
+/// Longest winning run that earns `Badge::WinStreak`
+pub const WIN_STREAK_BADGE: i32 = 10;
+/// SOL traded that earns `Badge::VolumeKing`
+pub const VOLUME_BADGE_SOL: f64 = 100.0;
+/// A single trade this large earns `Badge::Whale`
+pub const WHALE_TRADE_SOL: f64 = 50.0;
+/// Return on volume that earns `Badge::ProfitMaster`
+pub const PROFIT_MASTER_PERCENT: f64 = 100.0;
+/// `Badge::Consistent` needs this win rate over at least `CONSISTENT_MIN_TRADES`
+pub const CONSISTENT_WIN_RATE: f64 = 60.0;
+pub const CONSISTENT_MIN_TRADES: u32 = 20;
+/// Profitable snipes that earn `Badge::Sniper`
+pub const SNIPER_WINS: u32 = 5;
+
+/// Settings for leaderboard aggregation
+#[derive(Debug, Clone)]
+pub struct LeaderboardConfig {
+    /// Traders with fewer closed trades in the period aren't ranked
+    pub min_trades: u32,
+    /// How long an aggregated period is served before it's recomputed
+    pub cache_ttl: std::time::Duration,
+}
+
+impl Default for LeaderboardConfig {
+    fn default() -> Self {
+        Self {
+            min_trades: 5,
+            cache_ttl: std::time::Duration::from_secs(60),
+        }
+    }
+}
+
+/// What a trader lets others see; everything is hidden by default
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TraderVisibility {
+    pub leaderboard_visible: bool,
+    pub copyable: bool,
+    /// Shown instead of the anonymous handle
+    pub display_name: Option<String>,
+}
+
+/// Each user's leaderboard and copy-trading opt-ins
+#[async_trait::async_trait]
+pub trait VisibilityPreferences: Send + Sync {
+    async fn visibility(&self, user_id: i64) -> TraderVisibility;
+}
+
+/// A persisted trade and who made it
+#[derive(Debug, Clone)]
+pub struct TraderTrade {
+    pub user_id: i64,
+    /// Telegram username, when the user has one
+    pub username: Option<String>,
+    pub trade: Trade,
+}
+
+/// Where the trades behind the leaderboard are kept
+#[async_trait::async_trait]
+pub trait LeaderboardTradeSource: Send + Sync {
+    /// Trades made at or after `since`, every trade when `None`
+    async fn trades_since(&self, since: Option<DateTime<Utc>>) -> Result<Vec<TraderTrade>>;
+    async fn record(&self, user_id: i64, trade: &Trade) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl LeaderboardTradeSource for Database {
+    async fn trades_since(&self, since: Option<DateTime<Utc>>) -> Result<Vec<TraderTrade>> {
+        let rows = self.get_leaderboard_trades(since).await?;
+        let mut trades = Vec::with_capacity(rows.len());
+        for (user_id, username, data) in rows {
+            match serde_json::from_str(&data) {
+                Ok(trade) => trades.push(TraderTrade { user_id, username, trade }),
+                Err(e) => warn!("Skipping unreadable leaderboard trade for {}: {}", user_id, e),
+            }
+        }
+        Ok(trades)
+    }
+
+    async fn record(&self, user_id: i64, trade: &Trade) -> Result<()> {
+        let data = serde_json::to_string(trade)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize trade for {}: {}", user_id, e)))?;
+        self.insert_leaderboard_trade(user_id, trade.timestamp, &data).await?;
+        Ok(())
+    }
+}
+
+/// Aggregate per-trader stats over closed, bot-executed trades
+///
+/// Every trader with at least one such trade is included, best profit first;
+/// ranks are left at zero. `now` anchors the 7 and 30 day performance.
+pub fn aggregate_trader_stats(trades: &[TraderTrade], now: DateTime<Utc>) -> Vec<TraderStats> {
+    let mut by_trader: HashMap<i64, Vec<&TraderTrade>> = HashMap::new();
+    for trade in trades.iter().filter(|t| counts_toward_leaderboard(&t.trade)) {
+        by_trader.entry(trade.user_id).or_default().push(trade);
+    }
+
+    let mut stats: Vec<TraderStats> = by_trader.into_iter()
+        .map(|(user_id, mut trades)| {
+            trades.sort_by_key(|t| t.trade.timestamp);
+            trader_stats(user_id, &trades, now)
+        })
+        .collect();
+    stats.sort_by(|a, b| b.total_profit_percent.total_cmp(&a.total_profit_percent).then(a.user_id.cmp(&b.user_id)));
+    stats
+}
+
+fn counts_toward_leaderboard(trade: &Trade) -> bool {
+    trade.status == TradeStatus::Closed && trade.provenance != TradeProvenance::Imported
+}
+
+/// Stats over one trader's trades, oldest first and never empty
+fn trader_stats(user_id: i64, trades: &[&TraderTrade], now: DateTime<Utc>) -> TraderStats {
+    let mut stats = TraderStats::empty(user_id);
+    stats.username = trades.iter().rev()
+        .find_map(|t| t.username.clone())
+        .unwrap_or_else(|| LeaderboardManager::pseudonym(user_id));
+    stats.best_trade = trades[0].trade.clone();
+    stats.worst_trade = trades[0].trade.clone();
+
+    let mut snipe_wins = 0;
+    let mut returns = Vec::with_capacity(trades.len());
+    let mut peak_return = f64::NEG_INFINITY;
+    for TraderTrade { trade, .. } in trades.iter().copied() {
+        stats.total_trades += 1;
+        if trade.profit_sol > 0.0 {
+            stats.winning_trades += 1;
+            stats.streak_current = stats.streak_current.max(0) + 1;
+            stats.streak_best = stats.streak_best.max(stats.streak_current);
+            if trade.trade_type == TradeType::Snipe {
+                snipe_wins += 1;
+            }
+        } else {
+            stats.losing_trades += 1;
+            stats.streak_current = stats.streak_current.min(0) - 1;
+        }
+        stats.total_volume_sol += trade.amount_sol;
+        stats.total_profit_sol += trade.profit_sol;
+        stats.last_trade_time = trade.timestamp;
+        if trade.profit_sol > stats.best_trade.profit_sol {
+            stats.best_trade = trade.clone();
+        }
+        if trade.profit_sol < stats.worst_trade.profit_sol {
+            stats.worst_trade = trade.clone();
+        }
+        returns.push(trade.profit_percent);
+
+        // Drawdown of the running return on volume from its best point so far
+        let running_return = percent_of(stats.total_profit_sol, stats.total_volume_sol);
+        peak_return = peak_return.max(running_return);
+        stats.max_drawdown_percent = stats.max_drawdown_percent.min(running_return - peak_return);
+    }
+
+    stats.total_profit_percent = percent_of(stats.total_profit_sol, stats.total_volume_sol);
+    stats.win_rate = stats.winning_trades as f64 / stats.total_trades as f64 * 100.0;
+    stats.avg_profit_per_trade = stats.total_profit_sol / stats.total_trades as f64;
+    stats.performance_7d = window_return(trades, now - Duration::days(7));
+    stats.performance_30d = window_return(trades, now - Duration::days(30));
+    stats.sharpe_ratio = sharpe_ratio(&returns);
+    stats.badges = badges(&stats, trades, snipe_wins);
+    stats
+}
+
+fn percent_of(profit_sol: f64, volume_sol: f64) -> f64 {
+    if volume_sol > 0.0 { profit_sol / volume_sol * 100.0 } else { 0.0 }
+}
+
+/// Return on volume of the trades made since `since`
+fn window_return(trades: &[&TraderTrade], since: DateTime<Utc>) -> f64 {
+    let (profit, volume) = trades.iter()
+        .filter(|t| t.trade.timestamp >= since)
+        .fold((0.0, 0.0), |(profit, volume), t| (profit + t.trade.profit_sol, volume + t.trade.amount_sol));
+    percent_of(profit, volume)
+}
+
+/// Mean per-trade return over its standard deviation; zero without spread
+fn sharpe_ratio(returns: &[f64]) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    let deviation = variance.sqrt();
+    if deviation > 0.0 { mean / deviation } else { 0.0 }
+}
+
+fn badges(stats: &TraderStats, trades: &[&TraderTrade], snipe_wins: u32) -> Vec<Badge> {
+    let mut badges = Vec::new();
+    if stats.streak_best >= WIN_STREAK_BADGE {
+        badges.push(Badge::WinStreak(stats.streak_best as u32));
+    }
+    if stats.total_volume_sol >= VOLUME_BADGE_SOL {
+        badges.push(Badge::VolumeKing);
+    }
+    if stats.total_profit_percent >= PROFIT_MASTER_PERCENT {
+        badges.push(Badge::ProfitMaster);
+    }
+    if stats.total_trades >= CONSISTENT_MIN_TRADES && stats.win_rate >= CONSISTENT_WIN_RATE {
+        badges.push(Badge::Consistent);
+    }
+    if snipe_wins >= SNIPER_WINS {
+        badges.push(Badge::Sniper);
+    }
+    if trades.iter().any(|t| t.trade.amount_sol >= WHALE_TRADE_SOL) {
+        badges.push(Badge::Whale);
+    }
+    badges
+}
+
+impl TraderStats {
+    /// Stats for a trader with no ranked trades
+    pub fn empty(user_id: i64) -> Self {
+        let no_trade = Trade {
+            token_symbol: String::new(),
+            token_address: String::new(),
+            entry_price: 0.0,
+            exit_price: None,
+            amount_sol: 0.0,
+            profit_sol: 0.0,
+            profit_percent: 0.0,
+            timestamp: DateTime::<Utc>::MIN_UTC,
+            trade_type: TradeType::Buy,
+            status: TradeStatus::Cancelled,
+            provenance: TradeProvenance::Bot,
+        };
+        Self {
+            user_id,
+            username: LeaderboardManager::pseudonym(user_id),
+            wallet_address: String::new(),
+            total_trades: 0,
+            winning_trades: 0,
+            losing_trades: 0,
+            total_volume_sol: 0.0,
+            total_profit_sol: 0.0,
+            total_profit_percent: 0.0,
+            win_rate: 0.0,
+            avg_profit_per_trade: 0.0,
+            best_trade: no_trade.clone(),
+            worst_trade: no_trade,
+            streak_current: 0,
+            streak_best: 0,
+            last_trade_time: DateTime::<Utc>::MIN_UTC,
+            rank_global: 0,
+            rank_weekly: 0,
+            rank_daily: 0,
+            badges: Vec::new(),
+            copy_traders_count: 0,
+            performance_7d: 0.0,
+            performance_30d: 0.0,
+            sharpe_ratio: 0.0,
+            max_drawdown_percent: 0.0,
+            leaderboard_visible: false,
+            is_copyable: false,
+        }
+    }
+
+    fn entry(&self, rank: u32) -> LeaderboardEntry {
+        LeaderboardEntry {
+            rank,
+            user_id: self.user_id,
+            username: self.username.clone(),
+            profit_percent: self.total_profit_percent,
+            profit_sol: self.total_profit_sol,
+            total_trades: self.total_trades,
+            win_rate: self.win_rate,
+            volume_sol: self.total_volume_sol,
+            badges: self.badges.clone(),
+            is_copyable: self.is_copyable,
+            copy_fee_percent: 0.0,
+        }
+    }
+}
+
+/// Rank traders with at least `min_trades` trades by `metric`, best first
+///
+/// Ties go to the higher profit, then the lower user id.
+pub fn rank_traders(stats: &[TraderStats], metric: LeaderboardMetric, min_trades: u32) -> Vec<LeaderboardEntry> {
+    let mut eligible: Vec<&TraderStats> = stats.iter().filter(|s| s.total_trades >= min_trades).collect();
+    eligible.sort_by(|a, b| {
+        let primary = match metric {
+            LeaderboardMetric::Profit => b.total_profit_percent.total_cmp(&a.total_profit_percent),
+            LeaderboardMetric::Volume => b.total_volume_sol.total_cmp(&a.total_volume_sol),
+            LeaderboardMetric::WinRate => b.win_rate.total_cmp(&a.win_rate),
+            LeaderboardMetric::TradeCount => b.total_trades.cmp(&a.total_trades),
+            LeaderboardMetric::SharpeRatio => b.sharpe_ratio.total_cmp(&a.sharpe_ratio),
+        };
+        primary
+            .then(b.total_profit_percent.total_cmp(&a.total_profit_percent))
+            .then(a.user_id.cmp(&b.user_id))
+    });
+    eligible.into_iter()
+        .enumerate()
+        .map(|(i, stats)| stats.entry(i as u32 + 1))
+        .collect()
+}
+
 /// Manages trading leaderboards and trader statistics
 pub struct LeaderboardManager {
-    db: Arc<Database>,
-    cache: Arc<RwLock<LeaderboardCache>>,
-    stats_cache: Arc<RwLock<HashMap<i64, TraderStats>>>,
+    source: Arc<dyn LeaderboardTradeSource>,
+    config: LeaderboardConfig,
+    cache: Arc<RwLock<HashMap<LeaderboardPeriod, CachedPeriod>>>,
+    /// Held while a period is recomputed so concurrent refreshes share one read
+    refresh_lock: Arc<Mutex<()>>,
+    public_stats: PublicStatsBoard,
+    /// Users who asked to be forgotten; shown only by pseudonym
+    anonymized: Arc<RwLock<HashSet<i64>>>,
+    /// Without it nobody has opted in, so nobody is listed
+    visibility: Option<Arc<dyn VisibilityPreferences>>,
 }
 
-struct LeaderboardCache {
-    daily: Vec<LeaderboardEntry>,
-    weekly: Vec<LeaderboardEntry>,
-    monthly: Vec<LeaderboardEntry>,
-    all_time: Vec<LeaderboardEntry>,
-    last_update: DateTime<Utc>,
+/// Every trader's stats for one period, best profit first
+struct CachedPeriod {
+    stats: Arc<Vec<TraderStats>>,
+    computed_at: Instant,
 }
 
 impl LeaderboardManager {
     pub fn new(db: Arc<Database>) -> Self {
         Self {
-            db,
-            cache: Arc::new(RwLock::new(LeaderboardCache {
-                daily: Vec::new(),
-                weekly: Vec::new(),
-                monthly: Vec::new(),
-                all_time: Vec::new(),
-                last_update: Utc::now() - Duration::hours(1),
-            })),
-            stats_cache: Arc::new(RwLock::new(HashMap::new())),
+            source: db,
+            config: LeaderboardConfig::default(),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            refresh_lock: Arc::new(Mutex::new(())),
+            public_stats: PublicStatsBoard::new(AggregatePrivacy::from_env()),
+            anonymized: Arc::new(RwLock::new(HashSet::new())),
+            visibility: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: LeaderboardConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_source(mut self, source: Arc<dyn LeaderboardTradeSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn with_visibility(mut self, visibility: Arc<dyn VisibilityPreferences>) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    /// Stable anonymous handle, e.g. `Trader#4821`, shown for users without a display name
+    pub fn pseudonym(user_id: i64) -> String {
+        let digest = Sha256::digest(user_id.to_le_bytes());
+        let number = u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]]) % 10_000;
+        format!("Trader#{:04}", number)
+    }
+
+    /// Recompute every period on the next read, e.g. after a user changes their visibility
+    pub async fn invalidate(&self) {
+        self.cache.write().await.clear();
+    }
+
+    /// Replace a user's name everywhere on the leaderboard, now and on every refresh
+    pub async fn anonymize_user(&self, user_id: i64) -> usize {
+        self.anonymized.write().await.insert(user_id);
+        
+        let mut cache = self.cache.write().await;
+        let mut renamed = 0;
+        for period in cache.values_mut() {
+            let mut stats = period.stats.as_ref().clone();
+            renamed += Self::apply_pseudonyms(&mut stats, &HashSet::from([user_id]));
+            period.stats = Arc::new(stats);
+        }
+        renamed
+    }
+
+    fn apply_pseudonyms(stats: &mut [TraderStats], anonymized: &HashSet<i64>) -> usize {
+        let mut renamed = 0;
+        for trader in stats.iter_mut().filter(|s| anonymized.contains(&s.user_id)) {
+            trader.username = Self::pseudonym(trader.user_id);
+            renamed += 1;
         }
+        renamed
+    }
+
+    /// Privacy-protected aggregate over every trader active in `period`
+    pub async fn market_stats(&self, period: LeaderboardPeriod) -> Result<AggregateSnapshot> {
+        let entries = self.get_leaderboard(period, LeaderboardMetric::Profit, usize::MAX).await?;
+        Ok(self.public_stats.snapshot(period, &entries).await)
+    }
+
+    /// Market stats block built from the published snapshot only
+    pub async fn format_market_stats(&self, period: LeaderboardPeriod) -> Result<String> {
+        let snapshot = self.market_stats(period).await?;
+        Ok(snapshot.render(self.public_stats.privacy().min_contributors))
     }
 
     /// Get leaderboard for a specific period and metric
@@ -151,274 +538,116 @@ impl LeaderboardManager {
         metric: LeaderboardMetric,
         limit: usize,
     ) -> Result<Vec<LeaderboardEntry>> {
-        // Check cache
-        let cache = self.cache.read().await;
-        if cache.last_update > Utc::now() - Duration::minutes(5) {
-            let cached = match period {
-                LeaderboardPeriod::Daily => &cache.daily,
-                LeaderboardPeriod::Weekly => &cache.weekly,
-                LeaderboardPeriod::Monthly => &cache.monthly,
-                LeaderboardPeriod::AllTime => &cache.all_time,
-            };
-            
-            if !cached.is_empty() {
-                return Ok(cached.iter().take(limit).cloned().collect());
-            }
+        let stats = self.period_stats(period).await?;
+        let mut entries = self.rank_opted_in(&stats, metric, |s| s.leaderboard_visible);
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// Every trader's stats for a period, served from cache within `cache_ttl`
+    async fn period_stats(&self, period: LeaderboardPeriod) -> Result<Arc<Vec<TraderStats>>> {
+        if let Some(stats) = self.cached(period).await {
+            return Ok(stats);
         }
-        drop(cache);
 
-        // Fetch from database
-        self.update_leaderboard_cache().await?;
-        
-        let cache = self.cache.read().await;
-        let leaderboard = match period {
-            LeaderboardPeriod::Daily => &cache.daily,
-            LeaderboardPeriod::Weekly => &cache.weekly,
-            LeaderboardPeriod::Monthly => &cache.monthly,
-            LeaderboardPeriod::AllTime => &cache.all_time,
-        };
-        
-        // Sort by specified metric
-        let mut sorted = leaderboard.clone();
-        match metric {
-            LeaderboardMetric::Profit => {
-                sorted.sort_by(|a, b| b.profit_percent.partial_cmp(&a.profit_percent).unwrap());
-            }
-            LeaderboardMetric::Volume => {
-                sorted.sort_by(|a, b| b.volume_sol.partial_cmp(&a.volume_sol).unwrap());
-            }
-            LeaderboardMetric::WinRate => {
-                sorted.sort_by(|a, b| b.win_rate.partial_cmp(&a.win_rate).unwrap());
-            }
-            LeaderboardMetric::TradeCount => {
-                sorted.sort_by(|a, b| b.total_trades.cmp(&a.total_trades));
-            }
-            LeaderboardMetric::SharpeRatio => {
-                // Would need Sharpe ratio in LeaderboardEntry
-                sorted.sort_by(|a, b| b.profit_percent.partial_cmp(&a.profit_percent).unwrap());
-            }
+        let _refreshing = self.refresh_lock.lock().await;
+        // Another caller may have refreshed while we waited
+        if let Some(stats) = self.cached(period).await {
+            return Ok(stats);
         }
-        
-        Ok(sorted.into_iter().take(limit).collect())
+
+        let now = Utc::now();
+        let trades = self.source.trades_since(period.since(now)).await?;
+        let mut stats = aggregate_trader_stats(&trades, now);
+        self.apply_visibility(&mut stats).await;
+        Self::apply_pseudonyms(&mut stats, &*self.anonymized.read().await);
+        info!("🏆 Aggregated {} leaderboard trades from {} traders ({:?})", trades.len(), stats.len(), period);
+
+        let stats = Arc::new(stats);
+        self.cache.write().await.insert(period, CachedPeriod { stats: stats.clone(), computed_at: Instant::now() });
+        Ok(stats)
     }
 
-    /// Update leaderboard cache from database
-    async fn update_leaderboard_cache(&self) -> Result<()> {
-        info!("Updating leaderboard cache");
-        
-        // In production, these would be actual database queries
-        // For now, generate sample data
-        let daily = self.generate_sample_leaderboard(LeaderboardPeriod::Daily);
-        let weekly = self.generate_sample_leaderboard(LeaderboardPeriod::Weekly);
-        let monthly = self.generate_sample_leaderboard(LeaderboardPeriod::Monthly);
-        let all_time = self.generate_sample_leaderboard(LeaderboardPeriod::AllTime);
-        
-        let mut cache = self.cache.write().await;
-        cache.daily = daily;
-        cache.weekly = weekly;
-        cache.monthly = monthly;
-        cache.all_time = all_time;
-        cache.last_update = Utc::now();
-        
-        Ok(())
+    /// Mark who opted in and swap usernames for public names
+    async fn apply_visibility(&self, stats: &mut [TraderStats]) {
+        for trader in stats.iter_mut() {
+            let visibility = match &self.visibility {
+                Some(preferences) => preferences.visibility(trader.user_id).await,
+                None => TraderVisibility::default(),
+            };
+            trader.leaderboard_visible = visibility.leaderboard_visible;
+            trader.is_copyable = visibility.copyable;
+            trader.username = visibility.display_name.unwrap_or_else(|| Self::pseudonym(trader.user_id));
+        }
     }
 
-    /// Generate sample leaderboard data
-    fn generate_sample_leaderboard(&self, period: LeaderboardPeriod) -> Vec<LeaderboardEntry> {
-        let base_multiplier = match period {
-            LeaderboardPeriod::Daily => 1.0,
-            LeaderboardPeriod::Weekly => 2.5,
-            LeaderboardPeriod::Monthly => 5.0,
-            LeaderboardPeriod::AllTime => 10.0,
-        };
-        
-        vec![
-            LeaderboardEntry {
-                rank: 1,
-                user_id: 1001,
-                username: "AlphaTrader".to_string(),
-                profit_percent: 127.5 * base_multiplier,
-                total_trades: (23.0 * base_multiplier) as u32,
-                win_rate: 78.3,
-                volume_sol: 1250.0 * base_multiplier,
-                badges: vec![Badge::TopTrader, Badge::WinStreak(12), Badge::ProfitMaster],
-                is_copyable: true,
-                copy_fee_percent: 10.0,
-            },
-            LeaderboardEntry {
-                rank: 2,
-                user_id: 1002,
-                username: "DiamondHands".to_string(),
-                profit_percent: 89.2 * base_multiplier,
-                total_trades: (31.0 * base_multiplier) as u32,
-                win_rate: 71.0,
-                volume_sol: 890.5 * base_multiplier,
-                badges: vec![Badge::DiamondHands, Badge::Consistent],
-                is_copyable: true,
-                copy_fee_percent: 8.0,
-            },
-            LeaderboardEntry {
-                rank: 3,
-                user_id: 1003,
-                username: "DegenKing".to_string(),
-                profit_percent: 76.8 * base_multiplier,
-                total_trades: (45.0 * base_multiplier) as u32,
-                win_rate: 62.2,
-                volume_sol: 2100.0 * base_multiplier,
-                badges: vec![Badge::VolumeKing, Badge::RiskTaker],
-                is_copyable: true,
-                copy_fee_percent: 7.0,
-            },
-            LeaderboardEntry {
-                rank: 4,
-                user_id: 1004,
-                username: "SnipeMaster".to_string(),
-                profit_percent: 65.3 * base_multiplier,
-                total_trades: (18.0 * base_multiplier) as u32,
-                win_rate: 83.3,
-                volume_sol: 450.0 * base_multiplier,
-                badges: vec![Badge::Sniper, Badge::WinStreak(8)],
-                is_copyable: true,
-                copy_fee_percent: 12.0,
-            },
-            LeaderboardEntry {
-                rank: 5,
-                user_id: 1005,
-                username: "HODLLegend".to_string(),
-                profit_percent: 58.9 * base_multiplier,
-                total_trades: (12.0 * base_multiplier) as u32,
-                win_rate: 75.0,
-                volume_sol: 3500.0 * base_multiplier,
-                badges: vec![Badge::DiamondHands, Badge::Whale],
-                is_copyable: false,
-                copy_fee_percent: 0.0,
-            },
-        ]
+    async fn cached(&self, period: LeaderboardPeriod) -> Option<Arc<Vec<TraderStats>>> {
+        let cache = self.cache.read().await;
+        let cached = cache.get(&period)?;
+        (cached.computed_at.elapsed() < self.config.cache_ttl).then(|| cached.stats.clone())
     }
 
     /// Get trader statistics
+    ///
+    /// All-time stats, ranked by profit on the all-time, weekly and daily
+    /// boards; a rank of zero means not ranked there.
     pub async fn get_trader_stats(&self, user_id: i64) -> Result<TraderStats> {
-        // Check cache
-        let cache = self.stats_cache.read().await;
-        if let Some(stats) = cache.get(&user_id) {
-            return Ok(stats.clone());
-        }
-        drop(cache);
+        let all_time = self.period_stats(LeaderboardPeriod::AllTime).await?;
+        let mut stats = all_time.iter()
+            .find(|s| s.user_id == user_id)
+            .cloned()
+            .unwrap_or_else(|| TraderStats::empty(user_id));
 
-        // Fetch from database or generate sample
-        let stats = self.generate_sample_trader_stats(user_id);
-        
-        // Update cache
-        let mut cache = self.stats_cache.write().await;
-        cache.insert(user_id, stats.clone());
-        
+        stats.rank_global = self.rank_of(user_id, LeaderboardPeriod::AllTime).await?;
+        stats.rank_weekly = self.rank_of(user_id, LeaderboardPeriod::Weekly).await?;
+        stats.rank_daily = self.rank_of(user_id, LeaderboardPeriod::Daily).await?;
         Ok(stats)
     }
 
-    /// Generate sample trader statistics
-    fn generate_sample_trader_stats(&self, user_id: i64) -> TraderStats {
-        TraderStats {
-            user_id,
-            username: format!("Trader{}", user_id),
-            wallet_address: format!("{}...{}", "Demo", user_id),
-            total_trades: 47,
-            winning_trades: 28,
-            losing_trades: 19,
-            total_volume_sol: 587.5,
-            total_profit_sol: 45.2,
-            total_profit_percent: 12.3,
-            win_rate: 59.6,
-            avg_profit_per_trade: 0.96,
-            best_trade: Trade {
-                token_symbol: "BONK".to_string(),
-                token_address: "DemoAddr123".to_string(),
-                entry_price: 0.000012,
-                exit_price: Some(0.000028),
-                amount_sol: 10.0,
-                profit_sol: 13.3,
-                profit_percent: 133.0,
-                timestamp: Utc::now() - Duration::days(2),
-                trade_type: TradeType::Snipe,
-                status: TradeStatus::Closed,
-            },
-            worst_trade: Trade {
-                token_symbol: "SCAM".to_string(),
-                token_address: "DemoAddr456".to_string(),
-                entry_price: 0.15,
-                exit_price: Some(0.03),
-                amount_sol: 5.0,
-                profit_sol: -4.0,
-                profit_percent: -80.0,
-                timestamp: Utc::now() - Duration::days(5),
-                trade_type: TradeType::QuickBuy,
-                status: TradeStatus::Closed,
-            },
-            streak_current: 3,
-            streak_best: 7,
-            last_trade_time: Utc::now() - Duration::hours(2),
-            rank_global: 47,
-            rank_weekly: 23,
-            rank_daily: 12,
-            badges: vec![Badge::EarlyAdopter, Badge::Consistent],
-            copy_traders_count: 0,
-            performance_7d: 8.5,
-            performance_30d: 12.3,
-            sharpe_ratio: 1.45,
-            max_drawdown_percent: -15.2,
-        }
+    async fn rank_of(&self, user_id: i64, period: LeaderboardPeriod) -> Result<u32> {
+        let stats = self.period_stats(period).await?;
+        Ok(self.rank_opted_in(&stats, LeaderboardMetric::Profit, |s| s.leaderboard_visible)
+            .iter()
+            .find(|entry| entry.user_id == user_id)
+            .map_or(0, |entry| entry.rank))
     }
 
     /// Record a new trade
     pub async fn record_trade(&self, user_id: i64, trade: Trade) -> Result<()> {
-        // Update database
-        // In production, this would be an actual database insert
-        debug!("Recording trade for user {}: {:?}", user_id, trade);
-        
-        // Update cached stats
-        let mut cache = self.stats_cache.write().await;
-        if let Some(stats) = cache.get_mut(&user_id) {
-            stats.total_trades += 1;
-            
-            if trade.profit_sol > 0.0 {
-                stats.winning_trades += 1;
-                stats.streak_current = stats.streak_current.max(0) + 1;
-                stats.streak_best = stats.streak_best.max(stats.streak_current);
-            } else {
-                stats.losing_trades += 1;
-                stats.streak_current = stats.streak_current.min(0) - 1;
-            }
-            
-            stats.total_volume_sol += trade.amount_sol;
-            stats.total_profit_sol += trade.profit_sol;
-            stats.total_profit_percent = (stats.total_profit_sol / stats.total_volume_sol) * 100.0;
-            stats.win_rate = (stats.winning_trades as f64 / stats.total_trades as f64) * 100.0;
-            stats.avg_profit_per_trade = stats.total_profit_sol / stats.total_trades as f64;
-            stats.last_trade_time = trade.timestamp;
-            
-            if trade.profit_sol > stats.best_trade.profit_sol {
-                stats.best_trade = trade.clone();
-            }
-            if trade.profit_sol < stats.worst_trade.profit_sol {
-                stats.worst_trade = trade.clone();
-            }
+        // Imported history can't be verified, so it never ranks or earns badges
+        if trade.provenance == TradeProvenance::Imported {
+            debug!("Skipping imported trade for user {} on the leaderboard", user_id);
+            return Ok(());
         }
         
+        debug!("Recording trade for user {}: {:?}", user_id, trade);
+        self.source.record(user_id, &trade).await?;
+        
+        // The next read aggregates again, including this trade
+        self.invalidate().await;
         Ok(())
     }
 
-    /// Get top traders for copy trading
+    /// This week's most profitable traders who opted in to being copied
+    ///
+    /// Copying is a separate opt-in, so traders hidden from the leaderboard
+    /// can still be offered here.
     pub async fn get_copyable_traders(&self, limit: usize) -> Result<Vec<LeaderboardEntry>> {
-        let leaderboard = self.get_leaderboard(
-            LeaderboardPeriod::Weekly,
-            LeaderboardMetric::Profit,
-            limit * 2,
-        ).await?;
-        
-        Ok(leaderboard
-            .into_iter()
-            .filter(|entry| entry.is_copyable)
-            .take(limit)
-            .collect())
+        let stats = self.period_stats(LeaderboardPeriod::Weekly).await?;
+        let mut entries = self.rank_opted_in(&stats, LeaderboardMetric::Profit, |s| s.is_copyable);
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// `rank_traders` over the traders `opted_in` accepts
+    fn rank_opted_in(
+        &self,
+        stats: &[TraderStats],
+        metric: LeaderboardMetric,
+        opted_in: impl Fn(&TraderStats) -> bool,
+    ) -> Vec<LeaderboardEntry> {
+        let eligible: Vec<TraderStats> = stats.iter().filter(|s| opted_in(s)).cloned().collect();
+        rank_traders(&eligible, metric, self.config.min_trades)
     }
 
     /// Format leaderboard for display
@@ -488,9 +717,16 @@ impl LeaderboardManager {
                 _ => stats.rank_global,
             };
             
+            let rank = if stats.total_trades > 0 && !stats.leaderboard_visible {
+                "hidden, opt in under /settings → Privacy".to_string()
+            } else if rank == 0 {
+                "unranked".to_string()
+            } else {
+                format!("#{}", rank)
+            };
             message.push_str(&format!(
                 "\n📍 **Your Position**\n\
-                Rank: #{} (+{:.1}%, {} trades)\n\
+                Rank: {} (+{:.1}%, {} trades)\n\
                 Win Rate: {:.1}%\n\
                 Current Streak: {}\n",
                 rank,
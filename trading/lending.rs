@@ -0,0 +1,373 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::UiAccountData;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
+use solana_sdk::{pubkey::Pubkey, transaction::Transaction};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::api::{JupiterLendingClient, LendingAction, LendingPosition, LendingRequest, LendingVault, LiquidationInfo, PositionRecommendation, PositionStatus};
+use crate::db::Database;
+use crate::errors::{BotError, Result};
+use super::executor::TradingEngineHandle;
+use super::exit_routing::WSOL_MINT;
+
+/// Smallest deposit or withdrawal in raw token units; less costs more in fees than it earns
+pub const MIN_LEND_UNITS: u64 = 1_000;
+/// SOL left in the wallet on SOL deposits so the transaction fee can still be paid
+pub const SOL_FEE_RESERVE_LAMPORTS: u64 = 10_000_000;
+/// Positions below this health factor get their liquidation details looked up
+pub const AT_RISK_HEALTH_FACTOR: f64 = 1.2;
+
+/// How long a previewed deposit or withdrawal waits for its confirmation
+fn pending_ttl() -> Duration {
+    Duration::minutes(2)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LendDirection {
+    Deposit,
+    Withdraw,
+}
+
+impl LendDirection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Deposit => "Deposit",
+            Self::Withdraw => "Withdraw",
+        }
+    }
+
+    fn action(&self) -> LendingAction {
+        match self {
+            Self::Deposit => LendingAction::Deposit,
+            Self::Withdraw => LendingAction::Withdraw,
+        }
+    }
+}
+
+/// What the user asked to move: whole tokens, or everything available
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LendAmount {
+    Tokens(f64),
+    All,
+}
+
+impl LendAmount {
+    pub fn parse(text: &str) -> Option<Self> {
+        if text.eq_ignore_ascii_case("all") || text.eq_ignore_ascii_case("max") {
+            return Some(Self::All);
+        }
+        text.parse().ok().map(Self::Tokens)
+    }
+}
+
+fn whole(raw: u64, decimals: u8) -> f64 {
+    raw as f64 / 10f64.powi(decimals as i32)
+}
+
+/// Raw units to send for `amount`, checked against the `available` raw units
+pub fn validate_amount(amount: LendAmount, available: u64, decimals: u8) -> Result<u64> {
+    let raw = match amount {
+        LendAmount::All if available == 0 => {
+            return Err(BotError::validation("Nothing available to move".to_string()));
+        }
+        LendAmount::All => available,
+        LendAmount::Tokens(tokens) if tokens.is_finite() && tokens > 0.0 => {
+            (tokens * 10f64.powi(decimals as i32)).round() as u64
+        }
+        LendAmount::Tokens(_) => {
+            return Err(BotError::validation("Amount must be greater than 0".to_string()));
+        }
+    };
+
+    if raw > available {
+        return Err(BotError::validation(format!(
+            "That's more than the {} available",
+            whole(available, decimals)
+        )));
+    }
+    if raw < MIN_LEND_UNITS {
+        return Err(BotError::validation(format!(
+            "{} is too small to lend; the minimum is {}",
+            whole(raw, decimals),
+            whole(MIN_LEND_UNITS, decimals)
+        )));
+    }
+    Ok(raw)
+}
+
+/// A previewed deposit or withdrawal waiting for the user's confirmation
+#[derive(Debug, Clone)]
+pub struct PendingLend {
+    pub direction: LendDirection,
+    pub user_wallet: String,
+    pub vault: LendingVault,
+    /// Raw token units
+    pub amount: u64,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PendingLend {
+    pub fn tokens(&self) -> f64 {
+        whole(self.amount, self.vault.token_decimals)
+    }
+
+    /// SOL the transaction moves, for signing policies; unknown for other tokens
+    fn value_sol(&self) -> f64 {
+        if self.vault.token_mint == WSOL_MINT { self.tokens() } else { 0.0 }
+    }
+}
+
+/// A landed deposit or withdrawal, kept for the trade history export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LendingOperation {
+    pub timestamp: DateTime<Utc>,
+    pub direction: LendDirection,
+    pub vault_id: String,
+    pub mint: String,
+    pub symbol: String,
+    /// Whole tokens
+    pub amount: f64,
+    pub tx_signature: String,
+}
+
+impl LendingOperation {
+    /// SOL moved, for vaults that lend SOL
+    pub fn sol_amount(&self) -> f64 {
+        if self.mint == WSOL_MINT { self.amount } else { 0.0 }
+    }
+}
+
+/// How a confirmed deposit or withdrawal ended
+#[derive(Debug, Clone)]
+pub enum LendOutcome {
+    /// Signed and sent; recorded in the trade history
+    Sent(LendingOperation),
+    /// The wallet isn't device-held, so the transaction went back for the user to sign
+    Unsigned(PendingLend),
+}
+
+/// A lending position with what's needed to show it
+#[derive(Debug, Clone)]
+pub struct LendingHolding {
+    pub position: LendingPosition,
+    pub symbol: String,
+    pub decimals: u8,
+    pub recommendation: PositionRecommendation,
+    /// Present when the position is close enough to liquidation for the API to list it
+    pub liquidation: Option<LiquidationInfo>,
+}
+
+impl LendingHolding {
+    pub fn supplied(&self) -> f64 {
+        whole(self.position.collateral_amount, self.decimals)
+    }
+
+    pub fn borrowed(&self) -> f64 {
+        whole(self.position.borrowed_amount, self.decimals)
+    }
+
+    pub fn interest(&self) -> f64 {
+        whole(self.position.interest_accrued, self.decimals)
+    }
+}
+
+/// `/lend`: Jupiter Lend deposits and withdrawals, signed through the trading engine
+pub struct LendingDesk {
+    client: Arc<JupiterLendingClient>,
+    engine: TradingEngineHandle,
+    rpc_client: Arc<RpcClient>,
+    database: Arc<Database>,
+    pending: RwLock<HashMap<i64, PendingLend>>,
+}
+
+impl LendingDesk {
+    pub fn new(
+        client: Arc<JupiterLendingClient>,
+        engine: TradingEngineHandle,
+        rpc_client: Arc<RpcClient>,
+        database: Arc<Database>,
+    ) -> Self {
+        Self {
+            client,
+            engine,
+            rpc_client,
+            database,
+            pending: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Active vaults, best supply APY first
+    pub async fn vaults(&self) -> Result<Vec<LendingVault>> {
+        let mut vaults: Vec<LendingVault> = self.client.get_vaults().await?
+            .into_iter()
+            .filter(|v| v.is_active)
+            .collect();
+        vaults.sort_by(|a, b| b.supply_apr.total_cmp(&a.supply_apr));
+        Ok(vaults)
+    }
+
+    /// A vault by id or token symbol
+    pub async fn find_vault(&self, query: &str) -> Result<LendingVault> {
+        self.vaults().await?
+            .into_iter()
+            .find(|v| v.vault_id == query || v.token_symbol.eq_ignore_ascii_case(query))
+            .ok_or_else(|| BotError::validation(format!("No active vault matches {}; /lend lists them", query)))
+    }
+
+    /// Check the amount and hold the operation until the user confirms it
+    pub async fn prepare(
+        &self,
+        telegram_id: i64,
+        user_wallet: &str,
+        direction: LendDirection,
+        vault_query: &str,
+        amount: LendAmount,
+    ) -> Result<PendingLend> {
+        if self.engine.trading_mode(user_wallet.to_string()).await?.is_paper() {
+            return Err(BotError::validation("Lending uses real funds; turn paper mode off with /paper off first".to_string()));
+        }
+        if direction == LendDirection::Deposit && amount == LendAmount::All {
+            return Err(BotError::validation("Deposit a specific amount so some is left for fees".to_string()));
+        }
+
+        let vault = self.find_vault(vault_query).await?;
+        let available = match direction {
+            LendDirection::Deposit => self.wallet_balance(user_wallet, &vault.token_mint).await?,
+            LendDirection::Withdraw => self.supplied(user_wallet, &vault.vault_id).await?,
+        };
+        let amount = validate_amount(amount, available, vault.token_decimals)?;
+
+        let pending = PendingLend {
+            direction,
+            user_wallet: user_wallet.to_string(),
+            vault,
+            amount,
+            expires_at: Utc::now() + pending_ttl(),
+        };
+        self.pending.write().await.insert(telegram_id, pending.clone());
+        Ok(pending)
+    }
+
+    pub async fn cancel(&self, telegram_id: i64) -> Option<PendingLend> {
+        self.pending.write().await.remove(&telegram_id)
+    }
+
+    /// Build, sign and send the confirmed operation
+    pub async fn confirm(&self, telegram_id: i64, user_wallet: &str) -> Result<LendOutcome> {
+        let pending = self.pending.write().await.remove(&telegram_id)
+            .filter(|p| p.user_wallet == user_wallet && p.expires_at > Utc::now())
+            .ok_or_else(|| BotError::validation("This confirmation expired; run /lend again".to_string()))?;
+
+        let request = LendingRequest {
+            vault_id: pending.vault.vault_id.clone(),
+            user_public_key: user_wallet.to_string(),
+            action: pending.direction.action(),
+            amount: pending.amount,
+            token_mint: pending.vault.token_mint.clone(),
+            max_ltv: None,
+            slippage_bps: Some(50),
+            priority_fee_lamports: Some(5000),
+        };
+        let response = self.client.execute_lending_action(request).await?;
+        let tx_bytes = base64::decode(&response.transaction)?;
+        let transaction: Transaction = bincode::deserialize(&tx_bytes)?;
+
+        let description = format!("{} {} {} (Jupiter Lend)", pending.direction.label(), pending.tokens(), pending.vault.token_symbol);
+        let Some(tx_signature) = self.engine
+            .submit_transaction(user_wallet.to_string(), transaction, description, pending.value_sol())
+            .await?
+        else {
+            return Ok(LendOutcome::Unsigned(pending));
+        };
+
+        let operation = LendingOperation {
+            timestamp: Utc::now(),
+            direction: pending.direction,
+            vault_id: pending.vault.vault_id.clone(),
+            mint: pending.vault.token_mint.clone(),
+            symbol: pending.vault.token_symbol.clone(),
+            amount: pending.tokens(),
+            tx_signature,
+        };
+        match serde_json::to_string(&operation) {
+            Ok(data) => {
+                if let Err(e) = self.database.record_lending_operation(telegram_id, &data).await {
+                    warn!("🏦 Failed to record lending operation {}: {}", operation.tx_signature, e);
+                }
+            }
+            Err(e) => warn!("🏦 Failed to serialize lending operation {}: {}", operation.tx_signature, e),
+        }
+        info!("🏦 {} of {} {} sent for {}: {}", operation.direction.label(), operation.amount, operation.symbol, user_wallet, operation.tx_signature);
+        Ok(LendOutcome::Sent(operation))
+    }
+
+    /// Open positions, with liquidation details for the ones at risk
+    pub async fn positions(&self, user_wallet: &str) -> Result<Vec<LendingHolding>> {
+        let positions: Vec<LendingPosition> = self.client.get_user_positions(user_wallet).await?
+            .into_iter()
+            .filter(|p| !matches!(p.status, PositionStatus::Closed | PositionStatus::Liquidated))
+            .collect();
+        if positions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let vaults: HashMap<String, LendingVault> = self.client.get_vaults().await?
+            .into_iter()
+            .map(|v| (v.vault_id.clone(), v))
+            .collect();
+        let liquidations: Vec<LiquidationInfo> = if positions.iter().any(|p| p.health_factor < AT_RISK_HEALTH_FACTOR) {
+            self.client.get_liquidatable_positions().await.unwrap_or_else(|e| {
+                warn!("🏦 Liquidation details unavailable: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+
+        Ok(positions.into_iter()
+            .map(|position| {
+                let vault = vaults.get(&position.vault_id);
+                LendingHolding {
+                    symbol: vault.map(|v| v.token_symbol.clone()).unwrap_or_else(|| position.vault_id.clone()),
+                    decimals: vault.map(|v| v.token_decimals).unwrap_or(9),
+                    recommendation: self.client.get_position_recommendation(&position),
+                    liquidation: liquidations.iter().find(|l| l.position_id == position.position_id).cloned(),
+                    position,
+                }
+            })
+            .collect())
+    }
+
+    /// Raw units the wallet can deposit; SOL keeps a reserve for fees
+    async fn wallet_balance(&self, user_wallet: &str, mint: &str) -> Result<u64> {
+        let owner = Pubkey::from_str(user_wallet)?;
+        if mint == WSOL_MINT {
+            let lamports = self.rpc_client.get_balance(&owner).await?;
+            return Ok(lamports.saturating_sub(SOL_FEE_RESERVE_LAMPORTS));
+        }
+
+        let mint_pubkey = Pubkey::from_str(mint)?;
+        let accounts = self.rpc_client.get_token_accounts_by_owner(&owner, TokenAccountsFilter::Mint(mint_pubkey)).await?;
+        Ok(accounts.iter()
+            .filter_map(|keyed| match &keyed.account.data {
+                UiAccountData::Json(parsed) => parsed.parsed.pointer("/info/tokenAmount/amount")?.as_str()?.parse::<u64>().ok(),
+                _ => None,
+            })
+            .sum())
+    }
+
+    /// Raw units supplied to the vault, interest included
+    async fn supplied(&self, user_wallet: &str, vault_id: &str) -> Result<u64> {
+        Ok(self.client.get_user_positions(user_wallet).await?
+            .iter()
+            .filter(|p| p.vault_id == vault_id && !matches!(p.status, PositionStatus::Closed | PositionStatus::Liquidated))
+            .map(|p| p.collateral_amount + p.interest_accrued)
+            .sum())
+    }
+}
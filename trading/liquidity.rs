@@ -0,0 +1,200 @@
+use rust_decimal::prelude::ToPrimitive;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::api::jupiter_v6::{JupiterV6Client, QuoteRequestV6, SwapMode};
+use crate::errors::{BotError, Result};
+use crate::websocket::{OrderBook, OrderBookLevel, PriceStreamManager};
+use super::exit_routing::WSOL_MINT;
+use super::orders::OrderSide;
+
+/// Where a slippage estimate came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactSource {
+    /// Walked the cached order book
+    OrderBook,
+    /// Jupiter's quoted price impact
+    JupiterQuote,
+}
+
+/// Expected fill for a trade of `amount_sol`
+#[derive(Debug, Clone)]
+pub struct SlippageEstimate {
+    pub token: String,
+    pub side: OrderSide,
+    pub amount_sol: f64,
+    /// Average fill price in SOL per token; zero for quote-based estimates
+    pub average_price: f64,
+    /// Best price on the side walked
+    pub reference_price: f64,
+    /// How much worse than the reference the average fill is
+    pub price_impact_bps: u32,
+    pub levels_consumed: usize,
+    /// SOL the book couldn't absorb
+    pub unfilled_sol: f64,
+    pub source: ImpactSource,
+}
+
+impl SlippageEstimate {
+    /// The trade would move the price past the user's slippage, or can't fill at all
+    pub fn exceeds(&self, slippage_bps: u16) -> bool {
+        self.unfilled_sol > 0.0 || self.price_impact_bps > slippage_bps as u32
+    }
+
+    pub fn impact_pct(&self) -> f64 {
+        self.price_impact_bps as f64 / 100.0
+    }
+}
+
+/// Walk `book` for a trade worth `amount_sol`
+///
+/// Book prices are SOL per token and sizes are tokens. Buys consume asks from
+/// the lowest, sells consume bids from the highest. `None` when the side
+/// that would be walked is empty.
+pub fn walk_book(book: &OrderBook, side: OrderSide, amount_sol: f64) -> Option<SlippageEstimate> {
+    let mut levels: Vec<(f64, f64)> = match side {
+        OrderSide::Buy => &book.asks,
+        OrderSide::Sell => &book.bids,
+    }
+    .iter()
+    .filter_map(level_values)
+    .collect();
+    match side {
+        OrderSide::Buy => levels.sort_by(|a, b| a.0.total_cmp(&b.0)),
+        OrderSide::Sell => levels.sort_by(|a, b| b.0.total_cmp(&a.0)),
+    }
+
+    let reference_price = levels.first()?.0;
+    let mut remaining = amount_sol;
+    let mut tokens = 0.0;
+    let mut levels_consumed = 0;
+
+    for (price, size) in levels {
+        if remaining <= f64::EPSILON {
+            break;
+        }
+        let take = remaining.min(price * size);
+        tokens += take / price;
+        remaining -= take;
+        levels_consumed += 1;
+    }
+
+    let filled_sol = amount_sol - remaining;
+    let average_price = if tokens > 0.0 { filled_sol / tokens } else { reference_price };
+    let impact = match side {
+        OrderSide::Buy => average_price / reference_price - 1.0,
+        OrderSide::Sell => 1.0 - average_price / reference_price,
+    };
+
+    Some(SlippageEstimate {
+        token: book.symbol.clone(),
+        side,
+        amount_sol,
+        average_price,
+        reference_price,
+        price_impact_bps: (impact.max(0.0) * 10_000.0).round() as u32,
+        levels_consumed,
+        unfilled_sol: if remaining > f64::EPSILON { remaining } else { 0.0 },
+        source: ImpactSource::OrderBook,
+    })
+}
+
+/// Price and size of a usable level
+fn level_values(level: &OrderBookLevel) -> Option<(f64, f64)> {
+    let price = level.price.to_f64()?;
+    let size = level.size.to_f64()?;
+    (price > 0.0 && size > 0.0).then_some((price, size))
+}
+
+/// Quoted price impact for a trade, as a fraction
+#[async_trait::async_trait]
+pub trait ImpactQuoter: Send + Sync {
+    async fn price_impact(&self, mint: &str, side: OrderSide, amount_sol: f64) -> Result<f64>;
+}
+
+#[async_trait::async_trait]
+impl ImpactQuoter for JupiterV6Client {
+    async fn price_impact(&self, mint: &str, side: OrderSide, amount_sol: f64) -> Result<f64> {
+        // Buys spend exactly `amount_sol`; sells must bring exactly that much back
+        let (input_mint, output_mint, swap_mode) = match side {
+            OrderSide::Buy => (WSOL_MINT, mint, SwapMode::ExactIn),
+            OrderSide::Sell => (mint, WSOL_MINT, SwapMode::ExactOut),
+        };
+        let quote = self.get_quote(QuoteRequestV6 {
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            amount: (amount_sol * 1e9) as u64,
+            slippage_bps: 50,
+            swap_mode: Some(swap_mode),
+            dexes: None,
+            exclude_dexes: None,
+            max_accounts: None,
+            quote_mint: None,
+            minimize_slippage: None,
+            only_direct_routes: None,
+        }).await?;
+
+        Ok(quote.price_impact_pct.parse::<f64>().unwrap_or(0.0).abs())
+    }
+}
+
+/// Pre-trade price impact from cached order books, falling back to Jupiter quotes
+pub struct LiquidityEstimator {
+    price_stream: Arc<PriceStreamManager>,
+    quoter: Option<Arc<dyn ImpactQuoter>>,
+}
+
+impl LiquidityEstimator {
+    pub fn new(price_stream: Arc<PriceStreamManager>) -> Self {
+        Self { price_stream, quoter: None }
+    }
+
+    /// Quote the impact when no order book is cached for the token
+    pub fn with_quoter(mut self, quoter: Arc<dyn ImpactQuoter>) -> Self {
+        self.quoter = Some(quoter);
+        self
+    }
+
+    /// Expected average fill and price impact of trading `amount_sol` of `token`
+    pub async fn estimate_slippage(&self, token: &str, side: OrderSide, amount_sol: f64) -> Result<SlippageEstimate> {
+        if let Some(book) = self.price_stream.get_orderbook(token).await {
+            if let Some(estimate) = walk_book(&book, side, amount_sol) {
+                return Ok(estimate);
+            }
+            debug!("💧 Order book for {} has no {:?} side, quoting instead", token, side);
+        }
+
+        let quoter = self.quoter.as_ref()
+            .ok_or_else(|| BotError::trading(format!("No order book or quote source for {}", token)))?;
+        let impact = quoter.price_impact(token, side, amount_sol).await?;
+
+        Ok(SlippageEstimate {
+            token: token.to_string(),
+            side,
+            amount_sol,
+            average_price: 0.0,
+            reference_price: 0.0,
+            price_impact_bps: (impact * 10_000.0).round() as u32,
+            levels_consumed: 0,
+            unfilled_sol: 0.0,
+            source: ImpactSource::JupiterQuote,
+        })
+    }
+
+    /// The estimate when it exceeds `slippage_bps`; trades without one go ahead
+    pub async fn needs_confirmation(
+        &self,
+        token: &str,
+        side: OrderSide,
+        amount_sol: f64,
+        slippage_bps: u16,
+    ) -> Option<SlippageEstimate> {
+        match self.estimate_slippage(token, side, amount_sol).await {
+            Ok(estimate) => estimate.exceeds(slippage_bps).then_some(estimate),
+            Err(e) => {
+                warn!("💧 No slippage estimate for a {:?} of {} SOL of {}: {}", side, amount_sol, token, e);
+                None
+            }
+        }
+    }
+}
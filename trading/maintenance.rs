@@ -0,0 +1,13 @@
+//! Holding automated trades while an operator has trading paused
+
+/// Whether automated trades should wait; the bot's admin desk answers from maintenance mode
+///
+/// DCA runs, copied trades, order fills and Convex trade commands check it before submitting anything,
+/// so a pause covers what runs in the background as well as what users type.
+#[async_trait::async_trait]
+pub trait TradingPause: Send + Sync {
+    async fn trading_paused(&self) -> bool;
+}
+
+/// Reason recorded on automated trades held by a pause
+pub const PAUSED_FOR_MAINTENANCE: &str = "Trading is paused for maintenance";
@@ -0,0 +1,535 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction, transaction::Transaction};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
+use tracing::{debug, error, info, warn};
+
+use crate::db::Database;
+use crate::errors::{BotError, Result};
+use crate::utils::Config;
+use super::copy_trading::{CopyTradeExecution, CopyTradeStatus};
+use super::leaderboard::TraderStats;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Closed trades a trader needs before they can be copied for a fee
+pub const MIN_MASTER_TRADES: u32 = 25;
+pub const MIN_MASTER_WIN_RATE: f64 = 50.0;
+/// Days since the trader's first wallet was registered
+pub const MIN_MASTER_ACCOUNT_AGE_DAYS: i64 = 30;
+pub const DEFAULT_COPY_FEE_PERCENT: f64 = 5.0;
+pub const MAX_COPY_FEE_PERCENT: f64 = 20.0;
+/// Accrued fees below this wait for the next payout
+pub const DEFAULT_MIN_PAYOUT_SOL: f64 = 0.1;
+const PAYOUT_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+fn to_lamports(sol: f64) -> u64 {
+    (sol * LAMPORTS_PER_SOL).round().max(0.0) as u64
+}
+
+/// What a trader has to show before followers pay them
+#[derive(Debug, Clone)]
+pub struct MasterRequirements {
+    pub min_trades: u32,
+    pub min_win_rate: f64,
+    pub min_account_age: Duration,
+    pub max_fee_percent: f64,
+}
+
+impl Default for MasterRequirements {
+    fn default() -> Self {
+        Self {
+            min_trades: MIN_MASTER_TRADES,
+            min_win_rate: MIN_MASTER_WIN_RATE,
+            min_account_age: Duration::days(MIN_MASTER_ACCOUNT_AGE_DAYS),
+            max_fee_percent: MAX_COPY_FEE_PERCENT,
+        }
+    }
+}
+
+impl MasterRequirements {
+    /// Every requirement `stats` and the account's age fall short of; empty when eligible
+    pub fn unmet(&self, stats: &TraderStats, account_created: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Vec<String> {
+        let mut unmet = Vec::new();
+        if stats.total_trades < self.min_trades {
+            unmet.push(format!("{} closed trades (you have {})", self.min_trades, stats.total_trades));
+        }
+        if stats.win_rate < self.min_win_rate {
+            unmet.push(format!("{:.0}% win rate (you have {:.1}%)", self.min_win_rate, stats.win_rate));
+        }
+        let age = account_created.map(|created| now - created).unwrap_or_else(Duration::zero);
+        if age < self.min_account_age {
+            unmet.push(format!(
+                "an account at least {} days old (yours is {} days)",
+                self.min_account_age.num_days(), age.num_days()
+            ));
+        }
+        unmet
+    }
+
+    pub fn validate_fee(&self, fee_percent: f64) -> Result<f64> {
+        if !fee_percent.is_finite() || fee_percent < 0.0 || fee_percent > self.max_fee_percent {
+            return Err(BotError::validation(format!("The copy fee must be between 0% and {}%", self.max_fee_percent)).into());
+        }
+        Ok(fee_percent)
+    }
+}
+
+/// An approved master and the terms followers copy them on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterProfile {
+    pub user_id: i64,
+    /// Where accrued fees are paid out
+    pub payout_wallet: String,
+    /// Taken from each copy as it executes, so a change never reprices past copies
+    pub fee_percent: f64,
+    pub accepting_followers: bool,
+    pub approved_at: DateTime<Utc>,
+    pub fee_updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MasterFeeKind {
+    /// A follower's fee on a copy that executed
+    Accrual,
+    /// An accrued fee handed back because its copy failed after all
+    Reversal,
+    Payout,
+}
+
+/// One movement on a master's fee balance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterFeeEntry {
+    pub kind: MasterFeeKind,
+    pub lamports: u64,
+    pub execution_id: Option<String>,
+    pub follower_user_id: Option<i64>,
+    /// Transfer signature, for payouts
+    pub signature: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// Copy fees a master has earned and been paid, entry by entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterFeeLedger {
+    pub master_user_id: i64,
+    pub entries: Vec<MasterFeeEntry>,
+}
+
+impl MasterFeeLedger {
+    pub fn new(master_user_id: i64) -> Self {
+        Self { master_user_id, entries: Vec::new() }
+    }
+
+    /// Accrue the fee a settled copy of this master paid; returns the lamports accrued
+    ///
+    /// Copies that didn't execute, paid no fee, belong to another master or
+    /// were accrued already add nothing.
+    pub fn accrue(&mut self, execution: &CopyTradeExecution) -> Option<u64> {
+        if execution.master_user_id != self.master_user_id
+            || !matches!(execution.status, CopyTradeStatus::Success | CopyTradeStatus::PartialFill)
+            || self.has(MasterFeeKind::Accrual, &execution.execution_id)
+        {
+            return None;
+        }
+        let lamports = to_lamports(execution.fee_paid_sol);
+        if lamports == 0 {
+            return None;
+        }
+        self.entries.push(MasterFeeEntry {
+            kind: MasterFeeKind::Accrual,
+            lamports,
+            execution_id: Some(execution.execution_id.clone()),
+            follower_user_id: Some(execution.follower_user_id),
+            signature: None,
+            at: execution.timestamp,
+        });
+        Some(lamports)
+    }
+
+    /// Take back what a copy accrued once it's known to have failed; returns the lamports reversed
+    pub fn reverse(&mut self, execution_id: &str, at: DateTime<Utc>) -> Option<u64> {
+        if self.has(MasterFeeKind::Reversal, execution_id) {
+            return None;
+        }
+        let accrual = self.entries.iter()
+            .find(|e| e.kind == MasterFeeKind::Accrual && e.execution_id.as_deref() == Some(execution_id))?;
+        let reversal = MasterFeeEntry {
+            kind: MasterFeeKind::Reversal,
+            lamports: accrual.lamports,
+            execution_id: accrual.execution_id.clone(),
+            follower_user_id: accrual.follower_user_id,
+            signature: None,
+            at,
+        };
+        let lamports = reversal.lamports;
+        self.entries.push(reversal);
+        Some(lamports)
+    }
+
+    pub fn record_payout(&mut self, lamports: u64, signature: String, at: DateTime<Utc>) {
+        self.entries.push(MasterFeeEntry {
+            kind: MasterFeeKind::Payout,
+            lamports,
+            execution_id: None,
+            follower_user_id: None,
+            signature: Some(signature),
+            at,
+        });
+    }
+
+    fn has(&self, kind: MasterFeeKind, execution_id: &str) -> bool {
+        self.entries.iter().any(|e| e.kind == kind && e.execution_id.as_deref() == Some(execution_id))
+    }
+
+    fn total(&self, kind: MasterFeeKind) -> u64 {
+        self.entries.iter().filter(|e| e.kind == kind).map(|e| e.lamports).sum()
+    }
+
+    /// Fees kept after reversals
+    pub fn earned_lamports(&self) -> u64 {
+        self.total(MasterFeeKind::Accrual).saturating_sub(self.total(MasterFeeKind::Reversal))
+    }
+
+    pub fn paid_lamports(&self) -> u64 {
+        self.total(MasterFeeKind::Payout)
+    }
+
+    /// Earned but not yet paid; negative when a reversal lands after its fee was paid out
+    pub fn balance_lamports(&self) -> i64 {
+        self.total(MasterFeeKind::Accrual) as i64
+            - self.total(MasterFeeKind::Reversal) as i64
+            - self.paid_lamports() as i64
+    }
+
+    /// The whole balance, once it reaches `min_lamports`
+    pub fn payout_due(&self, min_lamports: u64) -> Option<u64> {
+        let balance = self.balance_lamports();
+        (balance > 0 && balance as u64 >= min_lamports.max(1)).then_some(balance as u64)
+    }
+}
+
+/// Sends accrued fees to masters; mocked in tests
+#[async_trait::async_trait]
+pub trait PayoutSender: Send + Sync {
+    /// Transfer `lamports` of SOL to `recipient` and return the signature
+    async fn send_sol(&self, recipient: &str, lamports: u64) -> Result<String>;
+}
+
+/// Pays out from the treasury wallet copy fees are collected in
+pub struct TreasuryPayouts {
+    rpc_client: Arc<RpcClient>,
+    treasury: Keypair,
+}
+
+impl TreasuryPayouts {
+    pub fn new(rpc_client: Arc<RpcClient>, treasury: Keypair) -> Self {
+        Self { rpc_client, treasury }
+    }
+
+    /// Treasury from a base58 secret key
+    pub fn from_secret(rpc_client: Arc<RpcClient>, secret: &str) -> Result<Self> {
+        let decoded = bs58::decode(secret).into_vec()
+            .map_err(|_| BotError::config("COPY_FEE_TREASURY_KEY is not base58"))?;
+        let treasury = Keypair::from_bytes(&decoded)
+            .map_err(|_| BotError::config("COPY_FEE_TREASURY_KEY is not a keypair"))?;
+        Ok(Self::new(rpc_client, treasury))
+    }
+}
+
+#[async_trait::async_trait]
+impl PayoutSender for TreasuryPayouts {
+    async fn send_sol(&self, recipient: &str, lamports: u64) -> Result<String> {
+        let recipient = Pubkey::from_str(recipient)
+            .map_err(|_| BotError::validation(format!("Invalid payout wallet: {}", recipient)))?;
+        let blockhash = self.rpc_client.get_latest_blockhash().await
+            .map_err(|e| BotError::external_api(format!("Failed to fetch blockhash: {}", e)))?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(&self.treasury.pubkey(), &recipient, lamports)],
+            Some(&self.treasury.pubkey()),
+            &[&self.treasury],
+            blockhash,
+        );
+        let signature = self.rpc_client.send_and_confirm_transaction(&transaction).await
+            .map_err(|e| BotError::trading(format!("Payout transfer failed: {}", e)))?;
+        Ok(signature.to_string())
+    }
+}
+
+/// `/master`: who may be copied for a fee, what they've earned and paying it out
+///
+/// Profiles and ledgers are write-through to the db, so a restart neither
+/// forgets an accrual nor pays one twice.
+pub struct MasterProgram {
+    db: Arc<Database>,
+    requirements: MasterRequirements,
+    min_payout_lamports: u64,
+    profiles: RwLock<HashMap<i64, MasterProfile>>,
+    ledgers: RwLock<HashMap<i64, MasterFeeLedger>>,
+    /// One per master, held across each read-modify-write of their ledger
+    ledger_locks: Mutex<HashMap<i64, Arc<Mutex<()>>>>,
+    /// Masters paid with the ledger not yet saved; nothing more is paid them until it is
+    unsaved_payouts: RwLock<HashSet<i64>>,
+    /// Without it fees accrue but nothing is paid out
+    payouts: Option<Arc<dyn PayoutSender>>,
+}
+
+impl MasterProgram {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            requirements: MasterRequirements::default(),
+            min_payout_lamports: to_lamports(DEFAULT_MIN_PAYOUT_SOL),
+            profiles: RwLock::new(HashMap::new()),
+            ledgers: RwLock::new(HashMap::new()),
+            ledger_locks: Mutex::new(HashMap::new()),
+            unsaved_payouts: RwLock::new(HashSet::new()),
+            payouts: None,
+        }
+    }
+
+    /// Payout threshold and treasury from the config; payouts stay off without a treasury key
+    pub fn from_config(db: Arc<Database>, rpc_client: Arc<RpcClient>, config: &Config) -> Self {
+        let program = Self::new(db).with_min_payout_sol(config.master_payout_min_sol);
+        match config.copy_fee_treasury_key.as_deref().map(|key| TreasuryPayouts::from_secret(rpc_client, key)) {
+            Some(Ok(treasury)) => program.with_payouts(Arc::new(treasury)),
+            Some(Err(e)) => {
+                warn!("💸 Master fee payouts are off: {}", e);
+                program
+            }
+            None => program,
+        }
+    }
+
+    pub fn with_requirements(mut self, requirements: MasterRequirements) -> Self {
+        self.requirements = requirements;
+        self
+    }
+
+    pub fn with_min_payout_sol(mut self, min_payout_sol: f64) -> Self {
+        self.min_payout_lamports = to_lamports(min_payout_sol);
+        self
+    }
+
+    pub fn with_payouts(mut self, payouts: Arc<dyn PayoutSender>) -> Self {
+        self.payouts = Some(payouts);
+        self
+    }
+
+    pub fn requirements(&self) -> &MasterRequirements {
+        &self.requirements
+    }
+
+    pub fn min_payout_sol(&self) -> f64 {
+        self.min_payout_lamports as f64 / LAMPORTS_PER_SOL
+    }
+
+    /// Approve a trader who meets the requirements and start taking followers
+    ///
+    /// Applying again updates the payout wallet and fee of an approved master.
+    pub async fn apply(
+        &self,
+        user_id: i64,
+        stats: &TraderStats,
+        account_created: Option<DateTime<Utc>>,
+        payout_wallet: &str,
+        fee_percent: Option<f64>,
+    ) -> Result<MasterProfile> {
+        let now = Utc::now();
+        let unmet = self.requirements.unmet(stats, account_created, now);
+        if !unmet.is_empty() {
+            return Err(BotError::validation(format!("Not eligible yet. You need:\n• {}", unmet.join("\n• "))).into());
+        }
+        let fee_percent = self.requirements.validate_fee(fee_percent.unwrap_or(DEFAULT_COPY_FEE_PERCENT))?;
+
+        let profile = match self.profile(user_id).await {
+            Some(mut profile) => {
+                if profile.fee_percent != fee_percent {
+                    profile.fee_updated_at = now;
+                }
+                profile.fee_percent = fee_percent;
+                profile.payout_wallet = payout_wallet.to_string();
+                profile.accepting_followers = true;
+                profile
+            }
+            None => MasterProfile {
+                user_id,
+                payout_wallet: payout_wallet.to_string(),
+                fee_percent,
+                accepting_followers: true,
+                approved_at: now,
+                fee_updated_at: now,
+            },
+        };
+        self.save_profile(&profile).await?;
+        info!("👑 User {} is a copy-trading master at a {}% fee", user_id, fee_percent);
+        Ok(profile)
+    }
+
+    /// Change an approved master's fee; copies already made keep the fee they paid
+    pub async fn set_fee(&self, user_id: i64, fee_percent: f64) -> Result<MasterProfile> {
+        let fee_percent = self.requirements.validate_fee(fee_percent)?;
+        let mut profile = self.profile(user_id).await
+            .ok_or_else(|| BotError::not_found("You're not a master yet. Use /master apply".to_string()))?;
+        profile.fee_percent = fee_percent;
+        profile.fee_updated_at = Utc::now();
+        self.save_profile(&profile).await?;
+        Ok(profile)
+    }
+
+    /// An approved master's profile, loaded from the db the first time it's needed
+    pub async fn profile(&self, user_id: i64) -> Option<MasterProfile> {
+        if let Some(profile) = self.profiles.read().await.get(&user_id) {
+            return Some(profile.clone());
+        }
+        let profile = match self.db.get_master_profile(user_id).await {
+            Ok(stored) => stored.and_then(|json| serde_json::from_str::<MasterProfile>(&json).ok())?,
+            Err(e) => {
+                warn!("Failed to load master profile for {}: {}", user_id, e);
+                return None;
+            }
+        };
+        self.profiles.write().await.insert(user_id, profile.clone());
+        Some(profile)
+    }
+
+    async fn save_profile(&self, profile: &MasterProfile) -> Result<()> {
+        self.db.upsert_master_profile(profile.user_id, &serde_json::to_string(profile)?).await?;
+        self.profiles.write().await.insert(profile.user_id, profile.clone());
+        Ok(())
+    }
+
+    /// A master's fee ledger, empty for traders who never earned
+    ///
+    /// A ledger that can't be read is an error rather than an empty one:
+    /// saving an empty ledger over the stored one would erase what the master
+    /// is owed and what they were already paid.
+    pub async fn ledger(&self, master_user_id: i64) -> Result<MasterFeeLedger> {
+        if let Some(ledger) = self.ledgers.read().await.get(&master_user_id) {
+            return Ok(ledger.clone());
+        }
+        let ledger = match self.db.get_master_fee_ledger(master_user_id).await? {
+            Some(json) => serde_json::from_str::<MasterFeeLedger>(&json).map_err(|e| {
+                BotError::parsing(format!("Stored fee ledger of master {} is unreadable: {}", master_user_id, e))
+            })?,
+            None => MasterFeeLedger::new(master_user_id),
+        };
+        Ok(self.ledgers.write().await.entry(master_user_id).or_insert(ledger).clone())
+    }
+
+    async fn save_ledger(&self, ledger: &MasterFeeLedger) -> Result<()> {
+        self.db.upsert_master_fee_ledger(ledger.master_user_id, &serde_json::to_string(ledger)?).await?;
+        self.ledgers.write().await.insert(ledger.master_user_id, ledger.clone());
+        self.unsaved_payouts.write().await.remove(&ledger.master_user_id);
+        Ok(())
+    }
+
+    /// Exclusive use of a master's ledger until the guard drops
+    async fn lock_ledger(&self, master_user_id: i64) -> OwnedMutexGuard<()> {
+        let lock = self.ledger_locks.lock().await.entry(master_user_id).or_default().clone();
+        lock.lock_owned().await
+    }
+
+    /// Accrue the fees of settled copies to approved masters; returns the lamports accrued
+    pub async fn accrue(&self, executions: &[CopyTradeExecution]) -> Result<u64> {
+        let mut masters: Vec<i64> = executions.iter().map(|e| e.master_user_id).collect();
+        masters.sort_unstable();
+        masters.dedup();
+
+        let mut accrued = 0;
+        for master in masters {
+            if self.profile(master).await.is_none() {
+                continue;
+            }
+            let _guard = self.lock_ledger(master).await;
+            let mut ledger = self.ledger(master).await?;
+            let added: u64 = executions.iter()
+                .filter(|e| e.master_user_id == master)
+                .filter_map(|e| ledger.accrue(e))
+                .sum();
+            if added > 0 {
+                debug!("💸 Accrued {} lamports of copy fees to master {}", added, master);
+                self.save_ledger(&ledger).await?;
+                accrued += added;
+            }
+        }
+        Ok(accrued)
+    }
+
+    /// Reverse the fee a copy accrued after it turned out to have failed
+    pub async fn reverse(&self, execution: &CopyTradeExecution) -> Result<Option<u64>> {
+        let _guard = self.lock_ledger(execution.master_user_id).await;
+        let mut ledger = self.ledger(execution.master_user_id).await?;
+        let Some(lamports) = ledger.reverse(&execution.execution_id, Utc::now()) else {
+            return Ok(None);
+        };
+        info!("💸 Reversed {} lamports of copy fees from master {} ({})",
+            lamports, execution.master_user_id, execution.execution_id);
+        self.save_ledger(&ledger).await?;
+        Ok(Some(lamports))
+    }
+
+    /// Pay every master whose balance reached the threshold; returns (master, lamports, signature) per payout
+    pub async fn run_payouts(&self) -> Result<Vec<(i64, u64, String)>> {
+        let Some(payouts) = &self.payouts else { return Ok(Vec::new()) };
+
+        let mut paid = Vec::new();
+        for json in self.db.list_master_profiles().await? {
+            let Ok(profile) = serde_json::from_str::<MasterProfile>(&json) else { continue };
+            let _guard = self.lock_ledger(profile.user_id).await;
+            let mut ledger = match self.ledger(profile.user_id).await {
+                Ok(ledger) => ledger,
+                Err(e) => {
+                    warn!("💸 Skipping payouts to master {} until their ledger loads: {}", profile.user_id, e);
+                    continue;
+                }
+            };
+            if self.unsaved_payouts.read().await.contains(&profile.user_id) {
+                if let Err(e) = self.save_ledger(&ledger).await {
+                    warn!("💸 Payouts to master {} stay held until their last one is saved: {}", profile.user_id, e);
+                    continue;
+                }
+            }
+            let Some(lamports) = ledger.payout_due(self.min_payout_lamports) else { continue };
+
+            match payouts.send_sol(&profile.payout_wallet, lamports).await {
+                Ok(signature) => {
+                    ledger.record_payout(lamports, signature.clone(), Utc::now());
+                    // The transfer landed: kept in memory whatever the db says, so this balance is never paid twice
+                    self.ledgers.write().await.insert(profile.user_id, ledger.clone());
+                    if let Err(e) = self.save_ledger(&ledger).await {
+                        self.unsaved_payouts.write().await.insert(profile.user_id);
+                        error!("💸 Paid master {} ({}) but couldn't record it, holding their payouts: {}",
+                            profile.user_id, signature, e);
+                    }
+                    info!("💸 Paid {} lamports of copy fees to master {} ({})", lamports, profile.user_id, signature);
+                    paid.push((profile.user_id, lamports, signature));
+                }
+                Err(e) => warn!("💸 Payout to master {} failed, retrying next run: {}", profile.user_id, e),
+            }
+        }
+        Ok(paid)
+    }
+
+    /// Pay out accrued fees every few hours
+    pub fn spawn_payouts(self: &Arc<Self>) {
+        if self.payouts.is_none() {
+            info!("💸 No COPY_FEE_TREASURY_KEY; master fees accrue without payouts");
+            return;
+        }
+        let program = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(PAYOUT_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                if let Err(e) = program.run_payouts().await {
+                    error!("💸 Master payout run stopped: {}", e);
+                }
+            }
+        });
+    }
+}
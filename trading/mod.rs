@@ -1,4 +1,5 @@
 mod executor;
+mod lanes;
 mod backrun;
 mod dex;
 mod types;
@@ -6,6 +7,7 @@ mod token_resolver;
 mod token_2022;
 mod token_creator;
 mod leaderboard;
+mod public_stats;
 mod copy_trading;
 mod copy_monitor;
 mod swaps;
@@ -14,22 +16,45 @@ mod dca;
 mod dca_scheduler;
 mod dca_risk_strategies;
 mod orders;
+mod indicators;
 mod trailing_stops;
+mod sandwich;
+mod compute_budget;
+mod smart_timing;
+mod execution_notices;
+mod exit_routing;
+mod paper;
+mod jito;
+mod priority_fees;
+mod liquidity;
+mod fill_check;
+mod reconcile;
+mod lending;
+mod panic_sell;
+mod master_program;
+mod trade_gate;
+mod maintenance;
+mod confirmation;
 
-pub use executor::{TradingEngine, TradingEngineHandle, TradingMessage};
-pub use types::{TradeResult, Balance, Position, TokenRestrictions};
-pub use token_resolver::TokenResolver;
-pub use token_2022::{Token2022Manager, Token2022Info, ExtensionType, TransferFeeConfig, InterestBearingConfig, TokenMetadata};
-pub use token_creator::{TokenCreator, TokenCreationConfig, TokenCreationResult, TokenPreset};
-pub use leaderboard::{LeaderboardManager, LeaderboardEntry, LeaderboardPeriod, LeaderboardMetric, TraderStats, Trade, TradeType, TradeStatus, Badge};
-pub use copy_trading::{CopyTradingManager, CopyTradingConfig, MasterTrader, CopyTradeExecution, CopyTradeType, CopyTradeStatus, TradingStyle};
-pub use copy_monitor::{CopyTradingMonitor, BlockchainTradeMonitor};
+pub use indicators::{sma, wma, ema, ema_series, rsi, macd, bollinger_bands, Macd, BollingerBands};
+pub use executor::{TradingEngine, TradingEngineHandle, TradingMessage, ResourceConfig, ResourceMetrics};
+pub use lanes::{LaneMailbox, LaneHandler, Laned};
+pub use types::{TradeResult, TradeHistory, ExecutionReport, RouteSummary, ExecutionFees, PartialFill, SandwichFinding, BundleReceipt, Balance, Position, TokenRestrictions, TradeProvenance, TradeDefaults, TradeDefaultPreferences};
+pub use token_resolver::{TokenResolver, TokenListSource, TokenListConfig, TokenCandidate, TokenLookup};
+pub use token_2022::{Token2022Manager, Token2022Info, ExtensionType, TransferFee, TransferFeeConfig, InterestBearingConfig, TokenMetadata, TOKEN_2022_PROGRAM_ID};
+pub use token_creator::{TokenCreator, TokenCreationConfig, TokenCreationResult, TokenDetails, TokenPreset, TokenPreview};
+pub use leaderboard::{LeaderboardManager, LeaderboardConfig, LeaderboardEntry, LeaderboardPeriod, LeaderboardMetric, LeaderboardTradeSource, TraderStats, TraderTrade, TraderVisibility, VisibilityPreferences, Trade, TradeType, TradeStatus, Badge, aggregate_trader_stats, rank_traders};
+pub use public_stats::{PublicStatsBoard, AggregatePrivacy, AggregateSnapshot, ExactAggregate, PublishedAggregate};
+pub use copy_trading::{CopyTradingManager, CopyTradingConfig, FollowerDailyRisk, CopiedPosition, MasterPositionBook, MasterTrader, CopyTradeExecution, CopyTradeType, CopyTradeStatus, TradingStyle};
+pub use copy_monitor::{CopyTradingMonitor, BlockchainTradeMonitor, MasterTradeWatchConfig, MasterTradeEvent, JupiterSwap, JUPITER_V6_PROGRAM_ID};
 pub use swaps::{JupiterSwapClient, SwapRequest, SwapResult, JupiterQuote, TokenInfo};
-pub use signer::{TransactionSigner, SigningOptions, SigningRequest, SigningResult};
+pub use signer::{TransactionSigner, SigningOptions, SigningRequest, SigningResult, SigningStrategy, LedgerSigning};
 pub use dca::{
     DCAEngine, 
     DCAStrategy, 
     DCAInterval, 
+    DCAAnchor,
+    AnchoredSlot,
     DCAStrategyType, 
     DCAStatus,
     RiskParameters,
@@ -46,6 +71,8 @@ pub use dca_scheduler::{
     ExecutionType,
     ScheduleConfig,
     ScheduleType,
+    CronSchedule,
+    parse_clock,
     MarketEvent,
     PriceCondition,
     PriceConditionType,
@@ -59,11 +86,15 @@ pub use dca_scheduler::{
     NotificationChannel,
     TimezoneManager,
     MarketHoursManager,
+    US_EQUITIES,
     ExecutionStats,
-    ExecutionRecord
+    ExecutionRecord,
+    DCAExecutionEvent,
+    DCAExecutionOutcome
 };
 pub use dca_risk_strategies::{
     RiskBasedDCAManager,
+    SizingAdjustment,
     RiskModel,
     RiskModelType,
     RiskParameters as DCARiskParameters,
@@ -83,9 +114,17 @@ pub use dca_risk_strategies::{
 };
 pub use orders::{
     OrderManager,
+    OrderOverlapConfig,
+    OrderPollingConfig,
+    OrderMonitorStats,
+    FillOutcome,
+    OrderConflict,
+    ConflictSeverity,
+    ProtectiveClass,
     Order,
     OrderType,
     OrderSide,
+    TrailingState,
     TimeInForce,
     OrderStatus,
     TriggerConditions,
@@ -98,8 +137,8 @@ pub use orders::{
     TimeCondition,
     TimeConditionType,
     TechnicalCondition,
-    TechnicalIndicator,
-    IndicatorCondition,
+    TechnicalIndicator as OrderTechnicalIndicator,
+    IndicatorCondition as OrderIndicatorCondition,
     ConditionLogic,
     ExecutionConfig,
     RetryConfig,
@@ -123,6 +162,10 @@ pub use trailing_stops::{
     TrailingStopManager,
     TrailingStopState,
     TrailingStrategy,
+    TrailDistance,
+    average_true_range,
+    CANDLE_INTERVAL_SECS,
+    DEFAULT_ATR_PERIODS,
     PositionSide,
     TrailingStopStatus,
     TrailingPerformanceMetrics,
@@ -134,4 +177,33 @@ pub use trailing_stops::{
     SupportResistanceLevel,
     TrendDirection,
     TimeCurveType
-};
\ No newline at end of file
+};
+pub use sandwich::{SandwichMonitor, SandwichConfig, SandwichDetector, PoolSwap, PoolReserves, SwapSide, MevStats};
+pub use compute_budget::{ComputeBudgeter, ComputeBudgetConfig, ComputeBudget, BudgetUrgency, MAX_COMPUTE_UNIT_LIMIT};
+pub use priority_fees::{PriorityFeeEstimator, PriorityFeeConfig, FeePercentiles};
+pub use fill_check::{FillAmounts, QuoteGuard, TOKEN_ACCOUNT_RENT_LAMPORTS};
+pub use reconcile::{PositionSync, Reconciliation, BalanceChange, reconcile, on_chain_balances, parsed_token_amount, hide_dust};
+pub use lending::{
+    LendingDesk, LendDirection, LendAmount, LendOutcome, PendingLend, LendingOperation, LendingHolding,
+    validate_amount, MIN_LEND_UNITS, SOL_FEE_RESERVE_LAMPORTS, AT_RISK_HEALTH_FACTOR,
+};
+pub use panic_sell::{
+    PanicDesk, PanicSeller, EngineSeller, PanicStage, PanicQuote, PanicPlan, PhraseCheck, PanicSellOutcome, PanicReport,
+    PANIC_PHRASE, MAX_CONCURRENT_SELLS, SELL_ATTEMPTS,
+};
+pub use master_program::{
+    MasterProgram, MasterProfile, MasterRequirements, MasterFeeLedger, MasterFeeEntry, MasterFeeKind, PayoutSender, TreasuryPayouts,
+    MIN_MASTER_TRADES, MIN_MASTER_WIN_RATE, MIN_MASTER_ACCOUNT_AGE_DAYS, DEFAULT_COPY_FEE_PERCENT, MAX_COPY_FEE_PERCENT, DEFAULT_MIN_PAYOUT_SOL,
+};
+pub use trade_gate::{
+    TradeGate, TradeRules, TradeRulePreferences, WalletOwners, TokenInspector, LiveTokenInspector, TokenTax, BlockedRule, TradeBlocked,
+    MAX_DENIED_MINTS,
+};
+pub use maintenance::{TradingPause, PAUSED_FOR_MAINTENANCE};
+pub use confirmation::{ConfirmationTracker, ConfirmationConfig, ConfirmationStage};
+pub use liquidity::{LiquidityEstimator, SlippageEstimate, ImpactSource, ImpactQuoter, walk_book};
+pub use smart_timing::{SmartSellTimer, SmartTimingConfig, TimingSession, TimingDecision, TimingOutcome, TimingReason, MarketTick, TickSource};
+pub use execution_notices::{ExecutionNotifier, ExecutionNotice, ExecutionSource, NoticeKind, NoticeRoute, NoticeScope, OutgoingNotice, Fill, FillDigest, DigestLine, Verbosity};
+pub use paper::{TradingMode, PaperLedger, PaperPosition, PaperSale, simulate_fill};
+pub use jito::{MevProtection, MevPreferences, JitoConfig, TipStrategy, RecentTips, BundleStatus, BundleStats, add_tip, JITO_TIP_ACCOUNTS};
+pub use exit_routing::{ExitDenomination, ExitPath, ExitPlan, ExitPreferences, ExitQuoter, ExitSettlement, RouteQuote, choose_usdc_path, plan_exit, USDC_MINT};
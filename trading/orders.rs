@@ -1,17 +1,29 @@
 use chrono::{DateTime, Utc, Duration};
-use std::str::FromStr;
-use rust_decimal::Decimal;
+use rust_decimal::{prelude::{FromPrimitive, ToPrimitive}, Decimal};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 use tracing::{info, debug, warn, error};
 
 use crate::errors::{BotError, Result};
-use crate::api::jupiter_v6::{JupiterV6Client, QuoteRequestV6, SwapMode};
-use crate::api::jupiter_price_v3::{JupiterPriceV3Client, PriceDataV3};
+use crate::api::jupiter_v6::JupiterV6Client;
+use crate::api::jupiter_price_v3::{JupiterPriceV3Client, PriceDataV3, MAX_PRICE_BATCH};
 use crate::telemetry::TelemetryService;
+use crate::monitoring::MetricsCollector;
 use crate::db::Database;
+use crate::analytics::{PositionClose, TradeJournal};
+use crate::wallet::TransactionPriority;
+use crate::websocket::{self, PriceStreamManager};
+use super::confirmation::ConfirmationStage;
+use super::execution_notices::{ExecutionNotice, ExecutionNotifier, ExecutionSource, Fill, NoticeKind};
+use super::exit_routing::{plan_exit, ExitDenomination, ExitPreferences, ExitSettlement, WSOL_MINT};
+use super::priority_fees::PriorityFeeEstimator;
+use super::trailing_stops::TrailDistance;
+use super::types::{ExecutionReport, RouteSummary, TradeType};
+use super::indicators;
+use super::TokenResolver;
+use super::maintenance::TradingPause;
 
 /// Advanced order management system for stop-loss, take-profit, and limit orders
 #[derive(Clone)]
@@ -20,9 +32,147 @@ pub struct OrderManager {
     price_client: Arc<JupiterPriceV3Client>,
     database: Arc<Database>,
     telemetry: Option<Arc<TelemetryService>>,
+    metrics: Option<Arc<MetricsCollector>>,
     active_orders: Arc<RwLock<HashMap<String, Order>>>,
     order_history: Arc<RwLock<HashMap<String, Vec<OrderExecution>>>>,
     price_monitors: Arc<RwLock<HashMap<String, PriceMonitor>>>,
+    overlap_config: OrderOverlapConfig,
+    polling_config: OrderPollingConfig,
+    journal: Option<Arc<TradeJournal>>,
+    notifier: Option<Arc<ExecutionNotifier>>,
+    exit_preferences: Option<Arc<dyn ExitPreferences>>,
+    fee_estimator: Option<Arc<PriorityFeeEstimator>>,
+    price_consensus: Option<PriceConsensus>,
+    pause: Option<Arc<dyn TradingPause>>,
+    /// Wakes the monitoring loop when an order is created
+    wakeup: Arc<Notify>,
+}
+
+/// Feeds that must agree before a price can fire a trigger
+#[derive(Clone)]
+struct PriceConsensus {
+    price_stream: Arc<PriceStreamManager>,
+    min_sources: usize,
+}
+
+/// Compute unit price paid without an estimate, in micro-lamports
+const STATIC_UNIT_PRICE_MICRO_LAMPORTS: u64 = 1_000;
+
+/// Settings for duplicate/conflicting order detection at creation time
+#[derive(Debug, Clone)]
+pub struct OrderOverlapConfig {
+    /// Same-class triggers closer than this (percent of price) count as stacked
+    pub proximity_pct: f64,
+}
+
+impl Default for OrderOverlapConfig {
+    fn default() -> Self {
+        Self { proximity_pct: 2.0 }
+    }
+}
+
+/// Adaptive cadence of the price/trigger monitoring loop
+///
+/// The loop sleeps while no orders are active and polls slower the further
+/// the nearest trigger is from the current price.
+#[derive(Debug, Clone)]
+pub struct OrderPollingConfig {
+    /// Cadence for orders just outside the near band
+    pub base_interval: std::time::Duration,
+    /// Cadence when every trigger is at least `far_distance_pct` away
+    pub far_interval: std::time::Duration,
+    /// Distance (percent of price) from which the far cadence applies
+    pub far_distance_pct: f64,
+    /// Distance (percent of price) within which an order is about to trigger
+    pub near_distance_pct: f64,
+    /// Longest a price may go unchecked while an order is in the near band
+    pub max_near_staleness: std::time::Duration,
+    /// Consecutive passes without a price before a mint's orders are flagged and the user warned
+    pub max_stale_cycles: u32,
+}
+
+impl Default for OrderPollingConfig {
+    fn default() -> Self {
+        Self {
+            base_interval: std::time::Duration::from_secs(2),
+            far_interval: std::time::Duration::from_secs(15),
+            far_distance_pct: 10.0,
+            near_distance_pct: 2.0,
+            max_near_staleness: std::time::Duration::from_secs(2),
+            max_stale_cycles: 5,
+        }
+    }
+}
+
+impl OrderPollingConfig {
+    /// Poll interval when the nearest trigger is `distance_pct` away
+    ///
+    /// Tightens linearly from `far_interval` to the near cadence across the
+    /// band between `far_distance_pct` and `near_distance_pct`.
+    pub fn interval_for_distance(&self, distance_pct: f64) -> std::time::Duration {
+        let near = self.base_interval.min(self.max_near_staleness);
+        let far = self.far_interval.max(near);
+
+        if distance_pct <= self.near_distance_pct {
+            return near;
+        }
+        if distance_pct >= self.far_distance_pct {
+            return far;
+        }
+        let span = (self.far_distance_pct - self.near_distance_pct).max(f64::EPSILON);
+        let t = (distance_pct - self.near_distance_pct) / span;
+        near + (far - near).mul_f64(t)
+    }
+}
+
+/// Orders changed by one fill
+#[derive(Debug, Clone, Default)]
+pub struct FillOutcome {
+    /// The executed order after the fill
+    pub order: Option<Order>,
+    /// Linked legs cancelled because the order filled completely
+    pub cancelled: Vec<Order>,
+    /// Linked legs shrunk after a partial fill
+    pub resized: Vec<Order>,
+}
+
+/// Snapshot of the monitoring loop for metrics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrderMonitorStats {
+    /// Mints with a live price monitor
+    pub monitor_count: usize,
+    /// Orders attached to those monitors
+    pub monitored_orders: usize,
+    /// Current sleep between passes; zero while idle
+    pub poll_interval_ms: u64,
+    /// Passes per minute at the current cadence; zero while idle
+    pub polls_per_minute: f64,
+    /// Monitors whose mint was missing from the latest price batch
+    pub stale_monitors: usize,
+}
+
+/// Protective class of an order leg, for overlap detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProtectiveClass {
+    Stop,
+    TakeProfit,
+}
+
+/// How serious a detected overlap is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConflictSeverity {
+    /// Allowed once the user confirms (`OrderMetadata::overlap_confirmed`)
+    Warning,
+    /// Can never work as intended; the order is refused
+    Rejected,
+}
+
+/// A single problem found between a new order and the user's existing ones
+#[derive(Debug, Clone)]
+pub struct OrderConflict {
+    pub severity: ConflictSeverity,
+    pub conflicting_order_ids: Vec<String>,
+    pub reason: String,
 }
 
 /// Order types supported by the system
@@ -42,6 +192,9 @@ pub struct Order {
     pub expires_at: Option<DateTime<Utc>>,
     pub parent_order_id: Option<String>, // For OCO orders
     pub metadata: OrderMetadata,
+    /// Trail progress for `OrderType::TrailingStop`
+    #[serde(default)]
+    pub trailing: TrailingState,
 }
 
 /// Different types of orders
@@ -67,10 +220,15 @@ pub enum OrderType {
         time_in_force: TimeInForce,
     },
     /// Trailing stop order that adjusts with price movement
+    ///
+    /// A non-zero `trailing_amount` takes precedence over `trailing_percentage`.
     TrailingStop {
         trailing_amount: Decimal,
         trailing_percentage: f64,
         activation_price: Option<Decimal>,
+        /// `Sell` protects a long, `Buy` covers a short
+        #[serde(default)]
+        side: OrderSide,
     },
     /// One-Cancels-Other order combining stop-loss and take-profit
     OCO {
@@ -87,12 +245,24 @@ pub enum OrderType {
 }
 
 /// Order side (buy/sell)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderSide {
     Buy,
+    #[default]
     Sell,
 }
 
+/// Where a trailing stop stands; stored with the order so the trail survives restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrailingState {
+    /// The activation price was touched, or none was set
+    pub activated: bool,
+    /// Best price since activation: the high for longs, the low for shorts
+    pub extreme_price: Option<Decimal>,
+    /// Current stop level; only ever tightens
+    pub stop_price: Option<Decimal>,
+}
+
 /// Time in force for limit orders
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TimeInForce {
@@ -115,6 +285,30 @@ pub enum OrderStatus {
     Failed,
 }
 
+impl OrderStatus {
+    /// Statuses that still need monitoring after a restart
+    pub const OPEN: [OrderStatus; 4] = [
+        OrderStatus::Pending,
+        OrderStatus::Active,
+        OrderStatus::Triggered,
+        OrderStatus::PartiallyFilled,
+    ];
+
+    /// Value stored in the orders table's status column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Pending => "pending",
+            OrderStatus::Active => "active",
+            OrderStatus::Triggered => "triggered",
+            OrderStatus::PartiallyFilled => "partially_filled",
+            OrderStatus::Filled => "filled",
+            OrderStatus::Cancelled => "cancelled",
+            OrderStatus::Expired => "expired",
+            OrderStatus::Failed => "failed",
+        }
+    }
+}
+
 /// Trigger conditions for order execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TriggerConditions {
@@ -204,6 +398,7 @@ pub enum TechnicalIndicator {
     RSI,
     MACD,
     BollingerBands,
+    MovingAverage(MovingAverageType),
     StochasticOscillator,
     ATR,
     VolumeWeightedAveragePrice,
@@ -221,6 +416,74 @@ pub enum IndicatorCondition {
     Convergence,
 }
 
+impl TechnicalCondition {
+    fn parameter(&self, name: &str, default: f64) -> f64 {
+        self.parameters.get(name).copied().unwrap_or(default)
+    }
+    
+    fn period(&self, name: &str, default: usize) -> usize {
+        self.parameters.get(name).map_or(default, |value| value.max(0.0) as usize)
+    }
+    
+    /// Indicator reading at the end of `prices` (oldest first)
+    ///
+    /// RSI reads 0-100. Moving averages read as the percent distance of the
+    /// latest price from the average, so 0 is the crossover. MACD reads as its
+    /// histogram (MACD minus signal) and Bollinger bands as %B. `None` when the
+    /// history is too short, or for indicators that need volume or candles.
+    ///
+    /// Parameters: `period` (RSI 14, averages and bands 20), `fast`/`slow`/`signal`
+    /// (MACD 12/26/9) and `std_dev` (bands 2).
+    pub fn value(&self, prices: &[f64]) -> Option<f64> {
+        let price = *prices.last()?;
+        match &self.indicator {
+            TechnicalIndicator::RSI => indicators::rsi(prices, self.period("period", 14)),
+            TechnicalIndicator::MovingAverage(ma_type) => {
+                let average: fn(&[f64], usize) -> Option<f64> = match ma_type {
+                    MovingAverageType::Simple => indicators::sma,
+                    MovingAverageType::Exponential => indicators::ema,
+                    MovingAverageType::Weighted => indicators::wma,
+                };
+                let average = average(prices, self.period("period", 20))?;
+                (average > 0.0).then(|| (price / average - 1.0) * 100.0)
+            }
+            TechnicalIndicator::MACD => indicators::macd(
+                prices,
+                self.period("fast", 12),
+                self.period("slow", 26),
+                self.period("signal", 9),
+            ).map(|macd| macd.histogram),
+            TechnicalIndicator::BollingerBands => indicators::bollinger_bands(
+                prices,
+                self.period("period", 20),
+                self.parameter("std_dev", 2.0),
+            ).map(|bands| bands.percent_b(price)),
+            TechnicalIndicator::StochasticOscillator
+            | TechnicalIndicator::ATR
+            | TechnicalIndicator::VolumeWeightedAveragePrice
+            | TechnicalIndicator::RelativeVolumeRatio => None,
+        }
+    }
+    
+    /// Whether the condition holds at the end of `prices`; `None` when it can't be evaluated
+    pub fn evaluate(&self, prices: &[f64]) -> Option<bool> {
+        let current = self.value(prices)?;
+        let previous = || self.value(&prices[..prices.len() - 1]);
+        match self.condition {
+            IndicatorCondition::Above(threshold) => Some(current > threshold),
+            IndicatorCondition::Below(threshold) => Some(current < threshold),
+            IndicatorCondition::Between(low, high) => Some(current >= low && current <= high),
+            IndicatorCondition::CrossingAbove(threshold) => {
+                previous().map(|previous| previous <= threshold && current > threshold)
+            }
+            IndicatorCondition::CrossingBelow(threshold) => {
+                previous().map(|previous| previous >= threshold && current < threshold)
+            }
+            IndicatorCondition::Divergence | IndicatorCondition::Convergence => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConditionLogic {
     And,
@@ -261,16 +524,42 @@ pub enum RetryCondition {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GasOptimization {
     pub priority_fee_strategy: PriorityFeeStrategy,
+    /// Cap on the estimated compute unit price, in micro-lamports
     pub max_priority_fee: u64,
     pub gas_price_multiplier: f64,
+    /// Price from recent prioritization fees; off pays the static price
     pub dynamic_adjustment: bool,
 }
 
+impl GasOptimization {
+    /// Compute unit price for an execution, in micro-lamports
+    ///
+    /// A custom price is paid as given. Otherwise the strategy's percentile of
+    /// recent fees is scaled by the multiplier and capped.
+    pub async fn unit_price(&self, estimator: Option<&PriorityFeeEstimator>) -> u64 {
+        let priority = match self.priority_fee_strategy {
+            PriorityFeeStrategy::Custom(price) => return price,
+            PriorityFeeStrategy::Conservative => TransactionPriority::Low,
+            PriorityFeeStrategy::Standard => TransactionPriority::Normal,
+            PriorityFeeStrategy::Aggressive => TransactionPriority::High,
+        };
+        let estimated = match estimator {
+            Some(estimator) if self.dynamic_adjustment => estimator.estimate(priority).await,
+            _ => STATIC_UNIT_PRICE_MICRO_LAMPORTS,
+        };
+        ((estimated as f64 * self.gas_price_multiplier.max(0.0)).round() as u64).min(self.max_priority_fee)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PriorityFeeStrategy {
+    /// 25th percentile of recent fees
     Conservative,
+    /// Median of recent fees
     Standard,
+    /// 75th percentile of recent fees
     Aggressive,
+    /// Fixed price in micro-lamports, ignoring recent fees
     Custom(u64),
 }
 
@@ -316,6 +605,24 @@ pub struct OrderMetadata {
     pub notes: Option<String>,
     pub client_order_id: Option<String>,
     pub performance_tracking: bool,
+    /// Size of the position the order protects, when known
+    #[serde(default)]
+    pub position_size: Option<Decimal>,
+    /// User acknowledged overlap warnings for this order
+    #[serde(default)]
+    pub overlap_confirmed: bool,
+    /// Average entry of the protected position, for realized return
+    #[serde(default)]
+    pub entry_price: Option<Decimal>,
+    /// Overrides the user's default exit denomination
+    #[serde(default)]
+    pub exit_denomination: Option<ExitDenomination>,
+    /// The price feed for this order's mint went quiet; cleared once prices return
+    #[serde(default)]
+    pub price_stale: bool,
+    /// Distance a managed trailing stop is currently keeping this order at
+    #[serde(default)]
+    pub trail_distance: Option<TrailDistance>,
 }
 
 /// Order execution record
@@ -335,6 +642,8 @@ pub struct OrderExecution {
     pub market_conditions: MarketConditions,
     pub success: bool,
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub report: ExecutionReport,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -382,6 +691,8 @@ pub struct PriceMonitor {
     pub price_history: Vec<PricePoint>,
     pub last_updated: DateTime<Utc>,
     pub monitoring_orders: Vec<String>,
+    /// Consecutive passes the mint was missing from the price batch
+    pub stale_cycles: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -406,46 +717,187 @@ impl OrderManager {
             price_client,
             database,
             telemetry,
+            metrics: None,
             active_orders: Arc::new(RwLock::new(HashMap::new())),
             order_history: Arc::new(RwLock::new(HashMap::new())),
             price_monitors: Arc::new(RwLock::new(HashMap::new())),
+            overlap_config: OrderOverlapConfig::default(),
+            polling_config: OrderPollingConfig::default(),
+            journal: None,
+            notifier: None,
+            exit_preferences: None,
+            fee_estimator: None,
+            price_consensus: None,
+            pause: None,
+            wakeup: Arc::new(Notify::new()),
         }
     }
     
+    pub fn with_overlap_config(mut self, overlap_config: OrderOverlapConfig) -> Self {
+        self.overlap_config = overlap_config;
+        self
+    }
+    
+    pub fn with_polling_config(mut self, polling_config: OrderPollingConfig) -> Self {
+        self.polling_config = polling_config;
+        self
+    }
+    
+    /// Export monitor count and poll rate
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
+    /// Offer journal prompts when a protective order closes a position
+    pub fn with_journal(mut self, journal: Arc<TradeJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+    
+    /// Publish fills and failures at each user's verbosity
+    pub fn with_notifier(mut self, notifier: Arc<ExecutionNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+    
+    /// Default exit denomination for orders without their own
+    pub fn with_exit_preferences(mut self, exit_preferences: Arc<dyn ExitPreferences>) -> Self {
+        self.exit_preferences = Some(exit_preferences);
+        self
+    }
+    
+    /// Price executions from recent prioritization fees
+    pub fn with_fee_estimator(mut self, fee_estimator: Arc<PriorityFeeEstimator>) -> Self {
+        self.fee_estimator = Some(fee_estimator);
+        self
+    }
+    
+    /// Evaluate triggers on the aggregated price, only once `min_sources` feeds agree
+    ///
+    /// The monitor's Jupiter price counts as one source, so a single bad feed
+    /// can't fire a stop-loss.
+    pub fn with_price_consensus(mut self, price_stream: Arc<PriceStreamManager>, min_sources: usize) -> Self {
+        self.price_consensus = Some(PriceConsensus { price_stream, min_sources });
+        self
+    }
+    
+    /// Leave triggered orders active, unfilled, while an operator has trading paused
+    pub fn with_pause(mut self, pause: Arc<dyn TradingPause>) -> Self {
+        self.pause = Some(pause);
+        self
+    }
+    
     /// Start the order monitoring background task
     pub async fn start(&self) -> Result<()> {
         info!("📋 Starting order monitoring background task");
+        self.load_active_orders().await?;
         
         let manager = self.clone();
         tokio::spawn(async move {
             loop {
-                if let Err(e) = manager.monitor_orders().await {
-                    error!("📋 Order monitoring error: {}", e);
+                // Hibernate until an order is created
+                if manager.price_monitors.read().await.is_empty() {
+                    debug!("📋 No active orders, order monitoring idle");
+                    manager.record_monitor_stats().await;
+                    manager.wakeup.notified().await;
                 }
                 
-                // Check orders every 5 seconds
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-            }
-        });
-        
-        // Start price monitoring task
-        let manager = self.clone();
-        tokio::spawn(async move {
-            loop {
                 if let Err(e) = manager.update_price_monitors().await {
                     error!("📋 Price monitoring error: {}", e);
                 }
+                if let Err(e) = manager.monitor_orders().await {
+                    error!("📋 Order monitoring error: {}", e);
+                }
                 
-                // Update prices every 2 seconds
-                tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                // A new order may need a tighter cadence than the current one
+                let interval = manager.next_poll_interval().await;
+                manager.record_monitor_stats().await;
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = manager.wakeup.notified() => {},
+                }
             }
         });
         
         Ok(())
     }
     
+    /// Restore open orders and their executions from the database
+    ///
+    /// Returns how many orders are monitored again. Orders that expired while
+    /// the bot was down are marked expired instead.
+    pub async fn load_active_orders(&self) -> Result<usize> {
+        let statuses: Vec<&str> = OrderStatus::OPEN.iter().map(OrderStatus::as_str).collect();
+        let rows = self.database.get_orders_by_status(&statuses).await?;
+        
+        let mut restored = 0;
+        for row in rows {
+            let mut order: Order = match serde_json::from_str(&row) {
+                Ok(order) => order,
+                Err(e) => {
+                    warn!("📋 Skipping unreadable stored order: {}", e);
+                    continue;
+                }
+            };
+            
+            if order.expires_at.is_some_and(|expires_at| Utc::now() > expires_at) {
+                order.status = OrderStatus::Expired;
+                order.updated_at = Utc::now();
+                self.update_order_status(&order).await?;
+                info!("📋 Order {} expired while offline", order.order_id);
+                continue;
+            }
+            
+            let executions = self.load_executions(&order.order_id).await?;
+            if !executions.is_empty() {
+                self.order_history.write().await.insert(order.order_id.clone(), executions);
+            }
+            
+            self.active_orders.write().await.insert(order.order_id.clone(), order.clone());
+            self.setup_price_monitoring(&order).await?;
+            restored += 1;
+        }
+        
+        if restored > 0 {
+            info!("📋 Restored {} open orders from the database", restored);
+            self.wakeup.notify_one();
+        }
+        Ok(restored)
+    }
+    
+    /// Run a single price refresh and trigger evaluation pass
+    pub async fn run_cycle(&self) -> Result<()> {
+        self.update_price_monitors().await?;
+        let result = self.monitor_orders().await;
+        self.record_monitor_stats().await;
+        result
+    }
+    
     /// Create a new order
-    pub async fn create_order(&self, mut order: Order) -> Result<String> {
+    ///
+    /// An OCO order is placed as two linked children and its own id is returned.
+    pub async fn create_order(&self, order: Order) -> Result<String> {
+        if matches!(order.order_type, OrderType::OCO { .. }) {
+            return self.create_oco(order).await;
+        }
+        self.place_order(order).await
+    }
+    
+    /// Place the stop-loss and take-profit children of an OCO, or neither
+    async fn create_oco(&self, order: Order) -> Result<String> {
+        let (stop_leg, take_profit_leg) = order.oco_legs()?;
+        let stop_id = self.place_order(stop_leg).await?;
+        if let Err(e) = self.place_order(take_profit_leg).await {
+            self.cancel_order(&stop_id).await?;
+            return Err(e);
+        }
+        
+        info!("📋 Created OCO order {} for token {}", order.order_id, order.token_mint);
+        Ok(order.order_id)
+    }
+    
+    async fn place_order(&self, mut order: Order) -> Result<String> {
         let _span = self.telemetry.as_ref().map(|t| 
             t.create_trading_span("create_order", Some(&order.token_mint))
         );
@@ -463,23 +915,44 @@ impl OrderManager {
         
         // Add to active orders
         let order_id = order.order_id.clone();
-        let mut orders = self.active_orders.write().await;
-        orders.insert(order_id.clone(), order.clone());
+        self.active_orders.write().await.insert(order_id.clone(), order.clone());
         
         // Set up price monitoring if needed
         self.setup_price_monitoring(&order).await?;
+        self.wakeup.notify_one();
         
         info!("📋 Created order: {} for token {}", order_id, order.token_mint);
         
         Ok(order_id)
     }
     
-    /// Cancel an order
+    /// Cancel an order; an OCO id cancels its children
     pub async fn cancel_order(&self, order_id: &str) -> Result<bool> {
+        if self.cancel_single(order_id).await? {
+            return Ok(true);
+        }
+        
+        let children: Vec<String> = {
+            let orders = self.active_orders.read().await;
+            orders.values()
+                .filter(|o| o.parent_order_id.as_deref() == Some(order_id))
+                .map(|o| o.order_id.clone())
+                .collect()
+        };
+        let mut cancelled = false;
+        for child in children {
+            cancelled |= self.cancel_single(&child).await?;
+        }
+        Ok(cancelled)
+    }
+    
+    async fn cancel_single(&self, order_id: &str) -> Result<bool> {
         let mut orders = self.active_orders.write().await;
         if let Some(mut order) = orders.remove(order_id) {
             order.status = OrderStatus::Cancelled;
             order.updated_at = Utc::now();
+            drop(orders);
+            self.release_monitor(&order).await;
             
             // Update in database
             self.update_order_status(&order).await?;
@@ -490,23 +963,71 @@ impl OrderManager {
             Ok(false)
         }
     }
+
+    /// Move an open stop-loss or take-profit to `price`, with the same overlap checks as a new order
+    pub async fn reprice_order(&self, order_id: &str, price: Decimal) -> Result<Order> {
+        let mut order = self.active_orders.read().await.get(order_id).cloned()
+            .ok_or_else(|| BotError::not_found(format!("Order {} is not open", order_id)))?;
+        if !matches!(order.status, OrderStatus::Pending | OrderStatus::Active) {
+            return Err(BotError::validation(format!("{} is already {:?}", order.label(), order.status)).into());
+        }
+
+        match &mut order.order_type {
+            OrderType::StopLoss { stop_price, .. } => *stop_price = price,
+            OrderType::TakeProfit { target_price, .. } => *target_price = price,
+            _ => return Err(BotError::validation(format!("{} can't be re-priced", order.label())).into()),
+        }
+        for condition in &mut order.trigger_conditions.price_conditions {
+            condition.target_value = price;
+        }
+        order.updated_at = Utc::now();
+        self.validate_order(&order).await?;
+
+        {
+            let mut orders = self.active_orders.write().await;
+            // It may have triggered while we were validating
+            let Some(current) = orders.get_mut(order_id).filter(|o| matches!(o.status, OrderStatus::Pending | OrderStatus::Active)) else {
+                return Err(BotError::validation(format!("{} just triggered", order.label())).into());
+            };
+            *current = order.clone();
+        }
+        self.store_order(&order).await?;
+        self.wakeup.notify_one();
+
+        info!("📋 Re-priced order {} to {}", order_id, price);
+        Ok(order)
+    }
     
     /// Monitor active orders for trigger conditions
     async fn monitor_orders(&self) -> Result<()> {
         let orders: Vec<Order> = {
             let orders_lock = self.active_orders.read().await;
             orders_lock.values()
-                .filter(|o| matches!(o.status, OrderStatus::Active | OrderStatus::Pending))
+                .filter(|o| matches!(o.status, OrderStatus::Active | OrderStatus::Pending | OrderStatus::PartiallyFilled))
                 .cloned()
                 .collect()
         };
+        let paused = match &self.pause {
+            Some(pause) => pause.trading_paused().await,
+            None => false,
+        };
         
         for order in orders {
             match self.check_trigger_conditions(&order).await {
+                Ok(true) if paused => {
+                    debug!("📋 Trading paused, holding triggered order {}", order.order_id);
+                },
                 Ok(true) => {
-                    if let Err(e) = self.execute_order(&order).await {
+                    let executed = self.execute_order(&order).await;
+                    if let Some(metrics) = &self.metrics {
+                        match &executed {
+                            Ok(_) => metrics.record_trade_executed(order.order_type.metric_label()),
+                            Err(e) => metrics.record_trade_failed(order.order_type.metric_label(), e),
+                        }
+                    }
+                    if let Err(e) = executed {
                         error!("📋 Failed to execute order {}: {}", order.order_id, e);
-                        self.handle_execution_failure(&order, &e.to_string()).await?;
+                        self.handle_execution_failure(&order, &e).await?;
                     }
                 },
                 Ok(false) => {
@@ -528,15 +1049,18 @@ impl OrderManager {
     
     /// Check if trigger conditions are met for an order
     async fn check_trigger_conditions(&self, order: &Order) -> Result<bool> {
-        let current_price = self.get_current_price(&order.token_mint).await?;
-        let market_conditions = self.get_market_conditions(&order.token_mint).await?;
+        let current_price = self.trigger_price(&order.token_mint).await?;
+        let market_conditions = Self::market_conditions_at(current_price);
         
-        // Check price conditions
-        let price_conditions_met = self.check_price_conditions(
-            &order.trigger_conditions.price_conditions,
-            current_price,
-            &order.token_mint,
-        ).await?;
+        // Check price conditions; a trailing stop's level moves, so it's checked on its own
+        let price_conditions_met = match order.trailing_stop_triggered(current_price) {
+            Some(triggered) => triggered,
+            None => self.check_price_conditions(
+                &order.trigger_conditions.price_conditions,
+                current_price,
+                &order.token_mint,
+            ).await?,
+        };
         
         // Check volume conditions
         let volume_conditions_met = self.check_volume_conditions(
@@ -603,36 +1127,48 @@ impl OrderManager {
         // Calculate execution amount
         let execution_amount = self.calculate_execution_amount(order, &market_conditions).await?;
         
-        // Get quote from Jupiter
-        let quote_request = QuoteRequestV6 {
-            input_mint: order.token_mint.clone(),
-            output_mint: "USDC".to_string(), // Simplified - would be dynamic
-            amount: execution_amount.to_u64().unwrap_or(0),
-            slippage_bps: order.execution_config.max_slippage_bps,
-            swap_mode: Some(SwapMode::ExactIn),
-            dexes: None,
-            exclude_dexes: None,
-            max_accounts: Some(32),
-            quote_mint: None,
-            minimize_slippage: Some(true),
-            only_direct_routes: Some(false),
-        };
-        
-        let quote = self.jupiter_client.get_quote(quote_request).await?;
+        // Route the proceeds into the order's exit denomination
+        let denomination = self.exit_denomination(order).await;
+        let plan = plan_exit(
+            self.jupiter_client.as_ref(),
+            &order.token_mint,
+            execution_amount.to_u64().unwrap_or(0),
+            order.execution_config.max_slippage_bps,
+            denomination,
+        ).await?;
+        let sol_usd = self.get_current_price(WSOL_MINT).await?;
+        let settlement = ExitSettlement::new(&plan, sol_usd.to_f64().unwrap_or(0.0));
+        let quote = plan.quote;
         
-        // Validate slippage
-        let actual_price = Decimal::from_str(&quote.out_amount)
-            .map_err(|e| BotError::parsing(format!("Invalid output amount: {}", e)))?;
-        let expected_price = execution_amount * market_conditions.token_price;
+        // Validate slippage against the market price in the exit token
+        let actual_price = Decimal::from(plan.summary.net_out());
+        let exit_token_usd = match denomination {
+            ExitDenomination::Sol if sol_usd > Decimal::ZERO => sol_usd,
+            _ => Decimal::ONE,
+        };
+        let expected_price = execution_amount * market_conditions.token_price / exit_token_usd;
         let slippage = ((expected_price - actual_price) / expected_price * Decimal::from(10000))
             .to_u16().unwrap_or(u16::MAX);
             
         if slippage > order.execution_config.max_slippage_bps {
-            return Err(BotError::trading(format!(
-                "Slippage {} exceeds maximum {}", slippage, order.execution_config.max_slippage_bps
-            )).into());
+            return Err(BotError::slippage_exceeded(order.execution_config.max_slippage_bps, slippage).into());
         }
         
+        // Realized price is output per token sold, so a fill below the market price is slippage
+        let realized_price = (actual_price / execution_amount).to_f64().unwrap_or(0.0);
+        let report = ExecutionReport::quoted(
+            (market_conditions.token_price / exit_token_usd).to_f64().unwrap_or(0.0),
+            RouteSummary::from(&quote),
+        )
+        .filled(realized_price, TradeType::Sell)
+        .with_exit(settlement)
+        .simulated(true) // No transaction is sent yet
+        .with_idempotency_key(format!("order:{}", order.order_id));
+        
+        let gas_price = order.execution_config.gas_optimization
+            .unit_price(self.fee_estimator.as_deref())
+            .await;
+        
         // Execute the trade (would integrate with actual swap execution)
         let execution = OrderExecution {
             execution_id: uuid::Uuid::new_v4().to_string(),
@@ -644,11 +1180,12 @@ impl OrderManager {
             amount_executed: execution_amount,
             slippage_bps: slippage,
             gas_used: 25000, // Estimated
-            gas_price: 1000, // Estimated
+            gas_price,
             transaction_signature: None, // Would be filled after actual execution
             market_conditions: market_conditions.clone(),
             success: true,
             error_message: None,
+            report,
         };
         
         // Store execution record
@@ -657,12 +1194,77 @@ impl OrderManager {
         // Update order status
         self.update_order_after_execution(order, &execution).await?;
         
-        info!("📋 Order executed: {} at price {}", 
-            order.order_id, execution.price_at_execution);
+        if let Some(journal) = &self.journal {
+            let symbol = TokenResolver::get_symbol(&order.token_mint);
+            if let Some(close) = PositionClose::from_order_execution(order, &execution, &symbol) {
+                journal.on_position_closed(close).await;
+            }
+        }
+        
+        info!("📋 Order executed: {} at price {}, proceeds {}", 
+            order.order_id, execution.price_at_execution, settlement.summary());
+        
+        self.notify(order, NoticeKind::Fill(Fill {
+            mint: order.token_mint.clone(),
+            symbol: TokenResolver::get_symbol(&order.token_mint),
+            quantity: execution.amount_executed.to_f64().unwrap_or(0.0),
+            price: execution.price_at_execution.to_f64().unwrap_or(0.0),
+            fee_sol: (execution.gas_used * execution.gas_price) as f64 / 1e15, // micro-lamports per CU
+        })).await;
         
         Ok(execution)
     }
     
+    /// The order's own exit denomination, else the user's default
+    pub async fn exit_denomination(&self, order: &Order) -> ExitDenomination {
+        if let Some(denomination) = order.metadata.exit_denomination {
+            return denomination;
+        }
+        match &self.exit_preferences {
+            Some(preferences) => preferences.exit_denomination(order.user_id).await,
+            None => ExitDenomination::default(),
+        }
+    }
+    
+    /// Override where one order's proceeds go; `None` follows the user's default again
+    pub async fn set_exit_denomination(&self, order_id: &str, denomination: Option<ExitDenomination>) -> Result<()> {
+        let mut orders = self.active_orders.write().await;
+        let order = orders.get_mut(order_id)
+            .ok_or_else(|| BotError::not_found(format!("Order {} not found", order_id)))?;
+        order.metadata.exit_denomination = denomination;
+        order.updated_at = Utc::now();
+        let order = order.clone();
+        drop(orders);
+        
+        self.store_order(&order).await
+    }
+    
+    /// Record the trail distance a trailing stop manager is keeping this order at
+    pub async fn set_trail_distance(&self, order_id: &str, distance: Option<TrailDistance>) -> Result<()> {
+        let mut orders = self.active_orders.write().await;
+        let order = orders.get_mut(order_id)
+            .ok_or_else(|| BotError::not_found(format!("Order {} not found", order_id)))?;
+        order.metadata.trail_distance = distance;
+        order.updated_at = Utc::now();
+        let order = order.clone();
+        drop(orders);
+        
+        self.store_order(&order).await
+    }
+    
+    async fn notify(&self, order: &Order, kind: NoticeKind) {
+        let Some(notifier) = &self.notifier else { return };
+        notifier.notify(ExecutionNotice {
+            user_id: order.user_id,
+            source: ExecutionSource::Order,
+            label: order.label(),
+            strategy_id: order.strategy_id().map(str::to_string),
+            order_id: Some(order.order_id.clone()),
+            kind,
+            at: Utc::now(),
+        }).await;
+    }
+    
     // Helper methods for condition checking and order management
     async fn check_price_conditions(
         &self,
@@ -736,19 +1338,44 @@ impl OrderManager {
         Ok(true)
     }
     
+    /// Evaluate indicators on the mint's monitored price history
+    ///
+    /// A condition that can't be evaluated yet (short history, unsupported
+    /// indicator) counts as unmet, so the order waits instead of firing.
     async fn check_technical_conditions(
         &self,
-        _conditions: &[TechnicalCondition],
-        _token_mint: &str,
+        conditions: &[TechnicalCondition],
+        token_mint: &str,
     ) -> Result<bool> {
-        // Placeholder for technical indicator checking
+        if conditions.is_empty() {
+            return Ok(true);
+        }
+        
+        let prices: Vec<f64> = {
+            let monitors = self.price_monitors.read().await;
+            monitors.get(token_mint)
+                .map(|monitor| monitor.price_history.iter().filter_map(|point| point.price.to_f64()).collect())
+                .unwrap_or_default()
+        };
+        
+        for condition in conditions {
+            match condition.evaluate(&prices) {
+                Some(true) => {}
+                Some(false) => return Ok(false),
+                None => {
+                    debug!("📋 {:?} on {} can't be evaluated with {} price points yet",
+                        condition.indicator, token_mint, prices.len());
+                    return Ok(false);
+                }
+            }
+        }
+        
         Ok(true)
     }
     
     // Additional helper methods would be implemented here for:
     // - Price monitoring updates
     // - Order validation
-    // - Database operations
     // - Market conditions retrieval
     // - Risk management checks
     
@@ -759,15 +1386,68 @@ impl OrderManager {
             
         let price_data = prices.prices
             .get(token_mint)
-            .ok_or_else(|| BotError::trading(format!("Price data not found for token {}", token_mint)))?;
+            .ok_or_else(|| BotError::not_found(format!("Price data not found for token {}", token_mint)))?;
         
         Ok(Decimal::from_f64_retain(price_data.usd_price).unwrap_or(Decimal::ZERO))
     }
     
+    /// The monitor's price from the latest pass; an error while its feed is stale
+    async fn monitored_price(&self, token_mint: &str) -> Result<Decimal> {
+        {
+            let monitors = self.price_monitors.read().await;
+            if let Some(monitor) = monitors.get(token_mint) {
+                if monitor.stale_cycles > 0 {
+                    return Err(BotError::trading(format!(
+                        "Price for {} is stale ({} missed updates)", token_mint, monitor.stale_cycles
+                    )).into());
+                }
+                return Ok(monitor.current_price);
+            }
+        }
+        self.get_current_price(token_mint).await
+    }
+    
+    /// The price triggers are checked against: the monitored price, or the
+    /// aggregated median when a consensus is required
+    async fn trigger_price(&self, token_mint: &str) -> Result<Decimal> {
+        let Some(consensus) = &self.price_consensus else {
+            return self.monitored_price(token_mint).await;
+        };
+        
+        // Refreshed this pass, so it's as fresh as the streamed quotes
+        let own_quote = self.monitored_price(token_mint).await.ok().map(|price| websocket::PriceUpdate {
+            symbol: token_mint.to_string(),
+            price,
+            timestamp: Utc::now(),
+            volume: None,
+            source: websocket::PriceSource::Jupiter,
+            update_type: websocket::UpdateType::Aggregate,
+            metadata: None,
+        });
+        
+        let aggregated = consensus.price_stream
+            .aggregate_price_with(token_mint, own_quote.into_iter().collect())
+            .await;
+        if aggregated.stale {
+            return Err(BotError::service_unavailable(format!("Price quotes for {}", token_mint)).into());
+        }
+        if !aggregated.is_usable(consensus.min_sources) {
+            return Err(BotError::trading(format!(
+                "Only {} of {} required price sources agree on {}",
+                aggregated.sources_count, consensus.min_sources, token_mint
+            )).into());
+        }
+        
+        Ok(aggregated.median_price)
+    }
+    
     async fn get_market_conditions(&self, token_mint: &str) -> Result<MarketConditions> {
         let price = self.get_current_price(token_mint).await?;
-        
-        Ok(MarketConditions {
+        Ok(Self::market_conditions_at(price))
+    }
+    
+    fn market_conditions_at(price: Decimal) -> MarketConditions {
+        MarketConditions {
             token_price: price,
             bid_ask_spread_bps: 10, // Placeholder
             volume_24h: Some(1000000), // Would fetch actual volume
@@ -778,7 +1458,7 @@ impl OrderManager {
                 median_confirmation_time: 2,
                 mempool_size: None,
             },
-        })
+        }
     }
     
     async fn calculate_execution_amount(&self, order: &Order, _conditions: &MarketConditions) -> Result<Decimal> {
@@ -803,36 +1483,290 @@ impl OrderManager {
                 }
             },
             OrderType::Limit { .. } => ExecutionType::Limit,
+            OrderType::TrailingStop { .. } => ExecutionType::StopMarket,
             _ => ExecutionType::Market,
         }
     }
     
-    // Placeholder implementations for database and state management
-    async fn validate_order(&self, _order: &Order) -> Result<()> {
+    // Validation, persistence and state management
+    /// Reject impossible orders, and overlapping ones the user hasn't confirmed
+    async fn validate_order(&self, order: &Order) -> Result<()> {
+        let conflicts = self.check_conflicts(order).await;
+        
+        let rejected: Vec<&OrderConflict> = conflicts.iter()
+            .filter(|c| c.severity == ConflictSeverity::Rejected)
+            .collect();
+        if !rejected.is_empty() {
+            return Err(BotError::validation(Self::describe_conflicts(&rejected)).into());
+        }
+        
+        if !conflicts.is_empty() && !order.metadata.overlap_confirmed {
+            let warnings: Vec<&OrderConflict> = conflicts.iter().collect();
+            return Err(BotError::validation(format!(
+                "{} — confirm to place it anyway",
+                Self::describe_conflicts(&warnings)
+            )).into());
+        }
+        
+        if !conflicts.is_empty() {
+            info!("📋 Order {} overlaps existing orders; placed after confirmation", order.order_id);
+        }
+        
         Ok(())
     }
     
-    async fn store_order(&self, _order: &Order) -> Result<()> {
-        Ok(())
+    /// Overlaps and conflicts between `order` and the user's open orders on the same mint
+    pub async fn check_conflicts(&self, order: &Order) -> Vec<OrderConflict> {
+        let existing: Vec<Order> = {
+            let orders = self.active_orders.read().await;
+            orders.values()
+                .filter(|o| o.user_id == order.user_id && o.token_mint == order.token_mint)
+                .filter(|o| matches!(o.status, OrderStatus::Pending | OrderStatus::Active | OrderStatus::PartiallyFilled))
+                .cloned()
+                .collect()
+        };
+        
+        // Market price only matters for buy limits
+        let market_price = if matches!(order.order_type, OrderType::Limit { side: OrderSide::Buy, .. }) {
+            match self.get_current_price(&order.token_mint).await {
+                Ok(price) if price > Decimal::ZERO => Some(price),
+                Ok(_) => None,
+                Err(e) => {
+                    warn!("📋 No market price for overlap check on {}: {}", order.token_mint, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        
+        Self::detect_conflicts(order, &existing, market_price, &self.overlap_config)
     }
     
-    async fn store_execution(&self, _execution: &OrderExecution) -> Result<()> {
-        Ok(())
+    /// Pure overlap detection used by `check_conflicts`
+    pub fn detect_conflicts(
+        order: &Order,
+        existing: &[Order],
+        market_price: Option<Decimal>,
+        config: &OrderOverlapConfig,
+    ) -> Vec<OrderConflict> {
+        let mut conflicts = Vec::new();
+        let new_legs = order.order_type.protective_legs();
+        
+        // An OCO/bracket whose own take-profit sits at or below its stop
+        let own_stop = new_legs.iter().filter(|(c, _)| *c == ProtectiveClass::Stop).map(|(_, p)| *p).max();
+        let own_target = new_legs.iter().filter(|(c, _)| *c == ProtectiveClass::TakeProfit).map(|(_, p)| *p).min();
+        if let (Some(stop), Some(target)) = (own_stop, own_target) {
+            if target <= stop {
+                conflicts.push(OrderConflict {
+                    severity: ConflictSeverity::Rejected,
+                    conflicting_order_ids: vec![],
+                    reason: format!("Take-profit {} is not above stop {}", target, stop),
+                });
+            }
+        }
+        
+        let mut stacked: HashMap<ProtectiveClass, Vec<String>> = HashMap::new();
+        let mut impossible = Vec::new();
+        
+        for other in existing.iter().filter(|o| o.order_id != order.order_id && !order.is_linked_leg(o)) {
+            for (class, price) in &new_legs {
+                for (other_class, other_price) in other.order_type.protective_legs() {
+                    if *class == other_class {
+                        let reference = (*price).max(other_price);
+                        let near = reference > Decimal::ZERO
+                            && ((*price - other_price).abs() / reference * Decimal::from(100))
+                                <= Decimal::from_f64_retain(config.proximity_pct).unwrap_or(Decimal::ZERO);
+                        let oversized = match order.metadata.position_size.or(other.metadata.position_size) {
+                            Some(position) => order.base_amount + other.base_amount > position,
+                            // Without a position size we can't rule out a double sell
+                            None => true,
+                        };
+                        if near && oversized {
+                            let ids = stacked.entry(*class).or_default();
+                            if !ids.contains(&other.order_id) {
+                                ids.push(other.order_id.clone());
+                            }
+                        }
+                        continue;
+                    }
+                    
+                    let (stop, target) = match class {
+                        ProtectiveClass::Stop => (*price, other_price),
+                        ProtectiveClass::TakeProfit => (other_price, *price),
+                    };
+                    if target <= stop && !impossible.contains(&other.order_id) {
+                        impossible.push(other.order_id.clone());
+                    }
+                }
+            }
+        }
+        
+        for (class, ids) in stacked {
+            let label = match class {
+                ProtectiveClass::Stop => "stop-loss",
+                ProtectiveClass::TakeProfit => "take-profit",
+            };
+            conflicts.push(OrderConflict {
+                severity: ConflictSeverity::Warning,
+                conflicting_order_ids: ids,
+                reason: format!(
+                    "Another {} within {}% would also fire and could sell more than the position",
+                    label, config.proximity_pct
+                ),
+            });
+        }
+        
+        if !impossible.is_empty() {
+            conflicts.push(OrderConflict {
+                severity: ConflictSeverity::Rejected,
+                conflicting_order_ids: impossible,
+                reason: "Take-profit would sit at or below an active stop-loss on this position".to_string(),
+            });
+        }
+        
+        if let (OrderType::Limit { limit_price, side: OrderSide::Buy, .. }, Some(market)) = (&order.order_type, market_price) {
+            if *limit_price > market && !order.is_stop_entry() {
+                conflicts.push(OrderConflict {
+                    severity: ConflictSeverity::Warning,
+                    conflicting_order_ids: vec![],
+                    reason: format!("Buy limit {} is above the market ({}) and would fill immediately", limit_price, market),
+                });
+            }
+        }
+        
+        conflicts.sort_by(|a, b| b.severity.cmp(&a.severity));
+        conflicts
     }
     
-    async fn update_order_status(&self, _order: &Order) -> Result<()> {
+    fn describe_conflicts(conflicts: &[&OrderConflict]) -> String {
+        conflicts.iter()
+            .map(|c| if c.conflicting_order_ids.is_empty() {
+                c.reason.clone()
+            } else {
+                format!("{} (orders: {})", c.reason, c.conflicting_order_ids.join(", "))
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+    
+    /// Write the whole order, keyed by order id; type, conditions and config are stored as JSON
+    async fn store_order(&self, order: &Order) -> Result<()> {
+        let data = serde_json::to_string(order)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize order {}: {}", order.order_id, e)))?;
+        self.database.upsert_order(
+            &order.order_id,
+            order.user_id,
+            &order.token_mint,
+            order.status.as_str(),
+            &data,
+        ).await
+    }
+    
+    async fn store_execution(&self, execution: &OrderExecution) -> Result<()> {
+        let data = serde_json::to_string(execution)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize execution {}: {}", execution.execution_id, e)))?;
+        self.database.insert_order_execution(&execution.execution_id, &execution.order_id, &data).await?;
+        
+        let mut history = self.order_history.write().await;
+        history.entry(execution.order_id.clone())
+            .or_insert_with(Vec::new)
+            .push(execution.clone());
         Ok(())
     }
     
-    async fn update_order_after_execution(&self, order: &Order, _execution: &OrderExecution) -> Result<()> {
-        let mut orders = self.active_orders.write().await;
-        if let Some(mut stored_order) = orders.get_mut(&order.order_id) {
-            stored_order.status = OrderStatus::Filled;
-            stored_order.updated_at = Utc::now();
+    async fn load_executions(&self, order_id: &str) -> Result<Vec<OrderExecution>> {
+        let rows = self.database.get_order_executions(order_id).await?;
+        Ok(rows.iter()
+            .filter_map(|row| match serde_json::from_str(row) {
+                Ok(execution) => Some(execution),
+                Err(e) => {
+                    warn!("📋 Skipping unreadable execution for order {}: {}", order_id, e);
+                    None
+                }
+            })
+            .collect())
+    }
+    
+    /// Status changes rewrite the full row so a restart sees the latest state
+    async fn update_order_status(&self, order: &Order) -> Result<()> {
+        self.store_order(order).await
+    }
+    
+    async fn update_order_after_execution(&self, order: &Order, execution: &OrderExecution) -> Result<()> {
+        // Fill and sibling changes happen under one lock so no sibling can trigger in between
+        let outcome = {
+            let mut orders = self.active_orders.write().await;
+            Self::apply_fill(&mut orders, &order.order_id, execution.amount_executed)
+        };
+        
+        if let Some(filled) = &outcome.order {
+            if matches!(filled.status, OrderStatus::Filled) {
+                self.release_monitor(filled).await;
+            }
+            self.update_order_status(filled).await?;
+        }
+        for sibling in &outcome.cancelled {
+            self.release_monitor(sibling).await;
+            self.update_order_status(sibling).await?;
+            info!("📋 Cancelled order {}: linked order {} filled", sibling.order_id, order.order_id);
+        }
+        for sibling in &outcome.resized {
+            self.update_order_status(sibling).await?;
+            info!("📋 Reduced order {} to {} after partial fill of {}", sibling.order_id, sibling.base_amount, order.order_id);
         }
         Ok(())
     }
     
+    /// Record a fill of `amount_executed` on `order_id` and adjust its linked legs
+    ///
+    /// A complete fill cancels open siblings and removes them from `orders`. A
+    /// partial fill, when the order allows them, leaves the order open with the
+    /// remainder and shrinks open siblings by the same proportion.
+    pub fn apply_fill(orders: &mut HashMap<String, Order>, order_id: &str, amount_executed: Decimal) -> FillOutcome {
+        let now = Utc::now();
+        let Some(order) = orders.get_mut(order_id) else {
+            return FillOutcome::default();
+        };
+        
+        let before = order.base_amount;
+        let partial = order.execution_config.partial_fill_enabled
+            && amount_executed > Decimal::ZERO
+            && amount_executed < before;
+        if partial {
+            order.base_amount = before - amount_executed;
+            order.status = OrderStatus::PartiallyFilled;
+        } else {
+            order.status = OrderStatus::Filled;
+        }
+        order.updated_at = now;
+        let order = order.clone();
+        
+        let siblings: Vec<String> = orders.values()
+            .filter(|o| o.order_id != order.order_id && order.is_linked_leg(o))
+            .filter(|o| matches!(o.status, OrderStatus::Pending | OrderStatus::Active | OrderStatus::PartiallyFilled))
+            .map(|o| o.order_id.clone())
+            .collect();
+        
+        let mut outcome = FillOutcome::default();
+        for id in siblings {
+            if partial {
+                if let Some(sibling) = orders.get_mut(&id) {
+                    sibling.base_amount = sibling.base_amount * order.base_amount / before;
+                    sibling.updated_at = now;
+                    outcome.resized.push(sibling.clone());
+                }
+            } else if let Some(mut sibling) = orders.remove(&id) {
+                // Nothing left for the other legs to protect
+                sibling.status = OrderStatus::Cancelled;
+                sibling.updated_at = now;
+                outcome.cancelled.push(sibling);
+            }
+        }
+        outcome.order = Some(order);
+        outcome
+    }
+    
     async fn setup_price_monitoring(&self, order: &Order) -> Result<()> {
         let mut monitors = self.price_monitors.write().await;
         
@@ -843,6 +1777,7 @@ impl OrderManager {
                 price_history: Vec::new(),
                 last_updated: Utc::now(),
                 monitoring_orders: vec![order.order_id.clone()],
+                stale_cycles: 0,
             };
             monitors.insert(order.token_mint.clone(), monitor);
         } else if let Some(monitor) = monitors.get_mut(&order.token_mint) {
@@ -852,32 +1787,133 @@ impl OrderManager {
         Ok(())
     }
     
+    /// Detach a terminal order from its mint's monitor, dropping the monitor once unused
+    async fn release_monitor(&self, order: &Order) {
+        let mut monitors = self.price_monitors.write().await;
+        if let Some(monitor) = monitors.get_mut(&order.token_mint) {
+            monitor.monitoring_orders.retain(|id| *id != order.order_id);
+            if monitor.monitoring_orders.is_empty() {
+                monitors.remove(&order.token_mint);
+                debug!("📋 Stopped price monitoring for {}", order.token_mint);
+            }
+        }
+    }
+    
+    /// Refresh every monitor from batched price requests
+    ///
+    /// Mints missing from the response keep their last price and count a stale
+    /// pass; after `max_stale_cycles` of those their orders are flagged.
     async fn update_price_monitors(&self) -> Result<()> {
         let token_mints: Vec<String> = {
             let monitors = self.price_monitors.read().await;
             monitors.keys().cloned().collect()
         };
+        if token_mints.is_empty() {
+            return Ok(());
+        }
+        
+        let mut prices = HashMap::with_capacity(token_mints.len());
+        for batch in token_mints.chunks(MAX_PRICE_BATCH) {
+            match self.price_client.get_prices(batch.to_vec()).await {
+                Ok(response) => prices.extend(response.prices),
+                Err(e) => warn!("📋 Price batch of {} mints failed: {}", batch.len(), e),
+            }
+        }
         
-        for token_mint in token_mints {
-            let current_price = self.get_current_price(&token_mint).await?;
+        let max_stale_cycles = self.polling_config.max_stale_cycles;
+        let now = Utc::now();
+        let mut refreshed = Vec::new();
+        let mut went_stale = Vec::new();
+        let mut recovered = Vec::new();
+        {
             let mut monitors = self.price_monitors.write().await;
-            
-            if let Some(monitor) = monitors.get_mut(&token_mint) {
+            for token_mint in &token_mints {
+                let Some(monitor) = monitors.get_mut(token_mint) else { continue };
+                let price = prices.get(token_mint).and_then(|data| Decimal::from_f64_retain(data.usd_price));
+                
+                let Some(current_price) = price else {
+                    monitor.stale_cycles += 1;
+                    if monitor.stale_cycles == max_stale_cycles {
+                        warn!("📋 No price for {} in {} passes", token_mint, monitor.stale_cycles);
+                        went_stale.extend(monitor.monitoring_orders.iter().cloned());
+                    }
+                    continue;
+                };
+                
+                if monitor.stale_cycles >= max_stale_cycles {
+                    recovered.extend(monitor.monitoring_orders.iter().cloned());
+                }
+                monitor.stale_cycles = 0;
                 monitor.current_price = current_price;
                 monitor.price_history.push(PricePoint {
-                    timestamp: Utc::now(),
+                    timestamp: now,
                     price: current_price,
                     volume: None,
                 });
-                monitor.last_updated = Utc::now();
+                monitor.last_updated = now;
                 
                 // Keep only last 1000 price points
                 if monitor.price_history.len() > 1000 {
                     monitor.price_history.drain(0..monitor.price_history.len() - 1000);
                 }
+                refreshed.push((monitor.monitoring_orders.clone(), current_price));
+            }
+        }
+        
+        for (order_ids, current_price) in refreshed {
+            self.ratchet_trailing_stops(&order_ids, current_price).await?;
+        }
+        self.set_price_stale(&went_stale, true).await?;
+        self.set_price_stale(&recovered, false).await?;
+        
+        Ok(())
+    }
+    
+    /// Flag or clear orders whose mint lost its price feed, warning the owner when it's lost
+    async fn set_price_stale(&self, order_ids: &[String], stale: bool) -> Result<()> {
+        let changed: Vec<Order> = {
+            let mut orders = self.active_orders.write().await;
+            order_ids.iter()
+                .filter_map(|id| orders.get_mut(id))
+                .filter(|order| order.metadata.price_stale != stale)
+                .map(|order| {
+                    order.metadata.price_stale = stale;
+                    order.updated_at = Utc::now();
+                    order.clone()
+                })
+                .collect()
+        };
+        
+        for order in changed {
+            self.store_order(&order).await?;
+            if stale {
+                self.notify(&order, NoticeKind::Warning {
+                    reason: format!(
+                        "No price for this token in the last {} checks. The order can't trigger until prices return.",
+                        self.polling_config.max_stale_cycles
+                    ),
+                }).await;
+            } else {
+                info!("📋 Prices are back for order {}", order.order_id);
             }
         }
+        Ok(())
+    }
+    
+    /// Move trailing stops on `order_ids` with the latest price, persisting any that tightened
+    async fn ratchet_trailing_stops(&self, order_ids: &[String], price: Decimal) -> Result<()> {
+        let moved: Vec<Order> = {
+            let mut orders = self.active_orders.write().await;
+            order_ids.iter()
+                .filter_map(|id| orders.get_mut(id))
+                .filter_map(|order| order.observe_trailing_price(price).then(|| order.clone()))
+                .collect()
+        };
         
+        for order in moved {
+            debug!("📋 Trailing stop {} now at {:?}", order.order_id, order.trailing.stop_price);
+            self.store_order(&order).await?;
+        }
         Ok(())
     }
     
@@ -886,6 +1922,8 @@ impl OrderManager {
         if let Some(mut order) = orders.remove(order_id) {
             order.status = OrderStatus::Expired;
             order.updated_at = Utc::now();
+            drop(orders);
+            self.release_monitor(&order).await;
             
             self.update_order_status(&order).await?;
             info!("📋 Expired order: {}", order_id);
@@ -893,8 +1931,9 @@ impl OrderManager {
         Ok(())
     }
     
-    async fn handle_execution_failure(&self, order: &Order, error: &str) -> Result<()> {
-        warn!("📋 Order execution failed for {}: {}", order.order_id, error);
+    async fn handle_execution_failure(&self, order: &Order, error: &BotError) -> Result<()> {
+        warn!("📋 Order execution failed for {}: {:?}", order.order_id, error);
+        self.notify(order, NoticeKind::Failure { reason: error.user_message() }).await;
         
         // Implement retry logic based on order configuration
         if order.execution_config.retry_config.max_retries > 0 {
@@ -904,6 +1943,80 @@ impl OrderManager {
         Ok(())
     }
     
+    /// Distance (percent) from the current price to the nearest trigger of any monitored order
+    ///
+    /// `None` while nothing is monitored. Orders without a price level count as
+    /// about to trigger, so they always get the near cadence.
+    pub async fn nearest_trigger_distance(&self) -> Option<f64> {
+        let monitors = self.price_monitors.read().await;
+        if monitors.is_empty() {
+            return None;
+        }
+        let orders = self.active_orders.read().await;
+        
+        let nearest = monitors.values()
+            .flat_map(|monitor| monitor.monitoring_orders.iter()
+                .filter_map(|id| orders.get(id))
+                .filter(|o| matches!(o.status, OrderStatus::Active | OrderStatus::Pending))
+                .map(move |o| o.trigger_distance_pct(monitor.current_price).unwrap_or(0.0)))
+            .fold(f64::INFINITY, f64::min);
+        Some(nearest)
+    }
+    
+    /// Sleep before the next monitoring pass
+    pub async fn next_poll_interval(&self) -> std::time::Duration {
+        match self.nearest_trigger_distance().await {
+            Some(distance) => self.polling_config.interval_for_distance(distance),
+            None => self.polling_config.far_interval,
+        }
+    }
+    
+    /// Monitor count and effective poll rate
+    pub async fn monitor_stats(&self) -> OrderMonitorStats {
+        let (monitor_count, monitored_orders, stale_monitors) = {
+            let monitors = self.price_monitors.read().await;
+            (
+                monitors.len(),
+                monitors.values().map(|m| m.monitoring_orders.len()).sum(),
+                monitors.values().filter(|m| m.stale_cycles > 0).count(),
+            )
+        };
+        if monitor_count == 0 {
+            return OrderMonitorStats::default();
+        }
+        
+        let interval = self.next_poll_interval().await;
+        OrderMonitorStats {
+            monitor_count,
+            monitored_orders,
+            poll_interval_ms: interval.as_millis() as u64,
+            polls_per_minute: 60.0 / interval.as_secs_f64().max(f64::EPSILON),
+            stale_monitors,
+        }
+    }
+    
+    async fn record_monitor_stats(&self) {
+        if let Some(metrics) = &self.metrics {
+            let stats = self.monitor_stats().await;
+            metrics.record_order_monitoring(stats.monitor_count, stats.monitored_orders, stats.polls_per_minute);
+            metrics.record_active_orders(&self.active_order_counts().await);
+        }
+    }
+    
+    /// Live orders per order type, zero for types with none
+    pub async fn active_order_counts(&self) -> Vec<(&'static str, usize)> {
+        let mut counts: Vec<(&'static str, usize)> = OrderType::METRIC_LABELS.iter().map(|label| (*label, 0)).collect();
+        for order in self.active_orders.read().await.values() {
+            if !matches!(order.status, OrderStatus::Active | OrderStatus::Pending | OrderStatus::PartiallyFilled) {
+                continue;
+            }
+            if let Some((_, count)) = counts.iter_mut().find(|(label, _)| *label == order.order_type.metric_label()) {
+                *count += 1;
+            }
+        }
+        counts
+    }
+    
     /// Get all active orders for a user
     pub async fn get_user_orders(&self, user_id: i64) -> Vec<Order> {
         let orders = self.active_orders.read().await;
@@ -913,15 +2026,220 @@ impl OrderManager {
             .collect()
     }
     
-    /// Get order execution history
+    /// Write a sent transaction's final stage onto the execution that sent it; false if none did
+    pub async fn record_confirmation(&self, signature: &str, stage: &ConfirmationStage) -> Result<bool> {
+        let updated = {
+            let mut history = self.order_history.write().await;
+            let Some(execution) = history.values_mut()
+                .flatten()
+                .find(|execution| execution.transaction_signature.as_deref() == Some(signature))
+            else {
+                return Ok(false);
+            };
+            execution.report.confirmation = Some(stage.clone());
+            if let Some(reason) = stage.failure() {
+                execution.success = false;
+                execution.error_message = Some(reason);
+            }
+            execution.clone()
+        };
+        
+        let data = serde_json::to_string(&updated)
+            .map_err(|e| BotError::parsing(format!("Failed to serialize execution {}: {}", updated.execution_id, e)))?;
+        self.database.update_order_execution(&updated.execution_id, &data).await?;
+        info!("📋 Execution {} of order {} ended {:?}", updated.execution_id, updated.order_id, stage);
+        Ok(true)
+    }
+    
+    /// Get order execution history, falling back to the database for orders no longer in memory
     pub async fn get_order_history(&self, order_id: &str) -> Vec<OrderExecution> {
-        let history = self.order_history.read().await;
-        history.get(order_id).cloned().unwrap_or_default()
+        if let Some(executions) = self.order_history.read().await.get(order_id) {
+            return executions.clone();
+        }
+        self.load_executions(order_id).await.unwrap_or_else(|e| {
+            warn!("📋 Failed to load history for order {}: {}", order_id, e);
+            Vec::new()
+        })
+    }
+}
+
+impl OrderType {
+    /// Every value `metric_label` returns
+    pub const METRIC_LABELS: [&'static str; 6] = ["stop_loss", "take_profit", "limit", "trailing_stop", "oco", "bracket"];
+    
+    /// Label for the order type in exported metrics
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            OrderType::StopLoss { .. } => "stop_loss",
+            OrderType::TakeProfit { .. } => "take_profit",
+            OrderType::Limit { .. } => "limit",
+            OrderType::TrailingStop { .. } => "trailing_stop",
+            OrderType::OCO { .. } => "oco",
+            OrderType::Bracket { .. } => "bracket",
+        }
+    }
+    
+    /// Trigger prices of the protective legs in this order type
+    pub fn protective_legs(&self) -> Vec<(ProtectiveClass, Decimal)> {
+        match self {
+            OrderType::StopLoss { stop_price, .. } => vec![(ProtectiveClass::Stop, *stop_price)],
+            OrderType::TakeProfit { target_price, .. } => vec![(ProtectiveClass::TakeProfit, *target_price)],
+            // Trailing stops move, so only a fixed activation price can be compared
+            OrderType::TrailingStop { activation_price: Some(price), .. } => vec![(ProtectiveClass::Stop, *price)],
+            OrderType::OCO { stop_loss_order, take_profit_order } => {
+                let mut legs = stop_loss_order.protective_legs();
+                legs.extend(take_profit_order.protective_legs());
+                legs
+            }
+            OrderType::Bracket { stop_loss_price, take_profit_price, .. } => vec![
+                (ProtectiveClass::Stop, *stop_loss_price),
+                (ProtectiveClass::TakeProfit, *take_profit_price),
+            ],
+            OrderType::TrailingStop { .. } | OrderType::Limit { .. } => vec![],
+        }
     }
 }
 
 /// Helper functions for creating common order types
 impl Order {
+    /// Short name for messages, e.g. "Stop loss BONK"
+    pub fn label(&self) -> String {
+        let kind = match &self.order_type {
+            OrderType::StopLoss { .. } => "Stop loss",
+            OrderType::TakeProfit { .. } => "Take profit",
+            OrderType::Limit { .. } => "Limit",
+            OrderType::TrailingStop { .. } => "Trailing stop",
+            OrderType::OCO { .. } => "OCO",
+            OrderType::Bracket { .. } => "Bracket",
+        };
+        format!("{} {}", kind, TokenResolver::get_symbol(&self.token_mint))
+    }
+    
+    /// Pay this order's proceeds out in `denomination` instead of the user's default
+    pub fn with_exit_denomination(mut self, denomination: ExitDenomination) -> Self {
+        self.metadata.exit_denomination = Some(denomination);
+        self
+    }
+    
+    /// Strategy that placed the order, if it wasn't placed by hand
+    pub fn strategy_id(&self) -> Option<&str> {
+        Some(self.metadata.strategy_source.as_str()).filter(|source| !source.is_empty() && *source != "manual")
+    }
+    
+    /// Whether `other` is a sibling leg of the same OCO/bracket, or its parent/child
+    pub fn is_linked_leg(&self, other: &Order) -> bool {
+        match (&self.parent_order_id, &other.parent_order_id) {
+            (Some(a), Some(b)) if a == b => true,
+            (Some(parent), _) if *parent == other.order_id => true,
+            (_, Some(parent)) if *parent == self.order_id => true,
+            _ => false,
+        }
+    }
+    
+    /// Price level the order is waiting for, if it has one
+    ///
+    /// Trailing stops report their current stop, or the activation price before that.
+    pub fn trigger_price(&self) -> Option<Decimal> {
+        if let OrderType::TrailingStop { activation_price, .. } = &self.order_type {
+            return self.trailing.stop_price.or(*activation_price);
+        }
+        self.trigger_conditions.price_conditions.first().map(|c| c.target_value)
+    }
+
+    /// Distance (percent of `price`) to the closest price-level trigger
+    ///
+    /// `None` when the order has no price level to compare against. Trailing
+    /// stops measure to their current stop, or to the activation price before that.
+    pub fn trigger_distance_pct(&self, price: Decimal) -> Option<f64> {
+        if price <= Decimal::ZERO {
+            return None;
+        }
+        if let OrderType::TrailingStop { activation_price, .. } = &self.order_type {
+            let level = self.trailing.stop_price.or(*activation_price)?;
+            return ((level - price).abs() / price * Decimal::from(100)).to_f64();
+        }
+        self.trigger_conditions.price_conditions.iter()
+            .filter(|c| matches!(
+                c.condition_type,
+                PriceConditionType::Above
+                    | PriceConditionType::Below
+                    | PriceConditionType::CrossingAbove
+                    | PriceConditionType::CrossingBelow
+            ))
+            .filter_map(|c| ((c.target_value - price).abs() / price * Decimal::from(100)).to_f64())
+            .reduce(f64::min)
+    }
+    
+    /// Feed the latest price to a trailing stop; true when its stop level moved
+    ///
+    /// Nothing happens until the activation price is touched. After that the
+    /// stop follows the best price by the trail distance and never loosens.
+    pub fn observe_trailing_price(&mut self, price: Decimal) -> bool {
+        let OrderType::TrailingStop { trailing_amount, trailing_percentage, activation_price, side } = &self.order_type else {
+            return false;
+        };
+        if price <= Decimal::ZERO {
+            return false;
+        }
+        let long = matches!(side, OrderSide::Sell);
+        let (trailing_amount, trailing_percentage, activation_price) = (*trailing_amount, *trailing_percentage, *activation_price);
+        
+        let state = &mut self.trailing;
+        if !state.activated {
+            let touched = match activation_price {
+                Some(activation) if long => price >= activation,
+                Some(activation) => price <= activation,
+                None => true,
+            };
+            if !touched {
+                return false;
+            }
+            state.activated = true;
+        }
+        
+        let extreme = match state.extreme_price {
+            Some(extreme) if long => extreme.max(price),
+            Some(extreme) => extreme.min(price),
+            None => price,
+        };
+        state.extreme_price = Some(extreme);
+        
+        let offset = if trailing_amount > Decimal::ZERO {
+            trailing_amount
+        } else {
+            extreme * Decimal::from_f64(trailing_percentage).unwrap_or_default() / Decimal::from(100)
+        };
+        let candidate = if long { extreme - offset } else { extreme + offset };
+        let stop = match state.stop_price {
+            Some(current) if long => current.max(candidate),
+            Some(current) => current.min(candidate),
+            None => candidate,
+        };
+        
+        let moved = state.stop_price != Some(stop);
+        state.stop_price = Some(stop);
+        moved
+    }
+    
+    /// Whether `price` crosses the trailing stop; `None` for other order types
+    pub fn trailing_stop_triggered(&self, price: Decimal) -> Option<bool> {
+        let OrderType::TrailingStop { side, .. } = &self.order_type else {
+            return None;
+        };
+        Some(match (self.trailing.stop_price, side) {
+            (Some(stop), OrderSide::Sell) => price <= stop,
+            (Some(stop), OrderSide::Buy) => price >= stop,
+            (None, _) => false,
+        })
+    }
+    
+    /// Buy limit meant to trigger on a breakout rather than a dip
+    pub fn is_stop_entry(&self) -> bool {
+        self.trigger_conditions.price_conditions.iter().any(|c| {
+            matches!(c.condition_type, PriceConditionType::Above | PriceConditionType::CrossingAbove)
+        })
+    }
+    
     /// Create a simple stop-loss order
     pub fn create_stop_loss(
         user_id: i64,
@@ -960,9 +2278,83 @@ impl Order {
             expires_at: None,
             parent_order_id: None,
             metadata: OrderMetadata::default(),
+            trailing: TrailingState::default(),
         }
     }
     
+    /// Stop-loss and take-profit children of an OCO order, linked to it by `parent_order_id`
+    pub fn oco_legs(&self) -> Result<(Order, Order)> {
+        let OrderType::OCO { stop_loss_order, take_profit_order } = &self.order_type else {
+            return Err(BotError::validation(format!("Order {} is not an OCO order", self.order_id)).into());
+        };
+        
+        let leg = |order_type: &OrderType| {
+            let mut leg = match order_type {
+                OrderType::StopLoss { stop_price, .. } => {
+                    Self::create_stop_loss(self.user_id, self.token_mint.clone(), *stop_price, self.base_amount)
+                }
+                OrderType::TakeProfit { target_price, .. } => {
+                    Self::create_take_profit(self.user_id, self.token_mint.clone(), *target_price, self.base_amount)
+                }
+                _ => return None,
+            };
+            leg.order_type = order_type.clone();
+            leg.execution_config = self.execution_config.clone();
+            leg.risk_management = self.risk_management.clone();
+            leg.expires_at = self.expires_at;
+            leg.parent_order_id = Some(self.order_id.clone());
+            leg.metadata = self.metadata.clone();
+            Some(leg)
+        };
+        
+        match (leg(stop_loss_order), leg(take_profit_order)) {
+            (Some(stop), Some(take_profit))
+                if matches!(stop.order_type, OrderType::StopLoss { .. })
+                    && matches!(take_profit.order_type, OrderType::TakeProfit { .. }) => Ok((stop, take_profit)),
+            _ => Err(BotError::validation("An OCO order needs a stop-loss and a take-profit leg".to_string()).into()),
+        }
+    }
+    
+    /// Create an OCO order: whichever of the stop-loss and take-profit fills first cancels the other
+    pub fn create_oco(
+        user_id: i64,
+        token_mint: String,
+        stop_price: Decimal,
+        target_price: Decimal,
+        amount: Decimal,
+    ) -> Self {
+        let stop_loss = Self::create_stop_loss(user_id, token_mint.clone(), stop_price, amount);
+        let take_profit = Self::create_take_profit(user_id, token_mint, target_price, amount);
+        let mut order = stop_loss.clone();
+        order.order_type = OrderType::OCO {
+            stop_loss_order: Box::new(stop_loss.order_type),
+            take_profit_order: Box::new(take_profit.order_type),
+        };
+        // Each child carries its own trigger
+        order.trigger_conditions.price_conditions.clear();
+        order
+    }
+    
+    /// Create a trailing stop that sells once price falls `trailing_percentage` from its high
+    pub fn create_trailing_stop(
+        user_id: i64,
+        token_mint: String,
+        trailing_percentage: f64,
+        activation_price: Option<Decimal>,
+        amount: Decimal,
+    ) -> Self {
+        let mut order = Self::create_stop_loss(user_id, token_mint, Decimal::ZERO, amount);
+        order.order_type = OrderType::TrailingStop {
+            trailing_amount: Decimal::ZERO,
+            trailing_percentage,
+            activation_price,
+            side: OrderSide::Sell,
+        };
+        // The level moves, so there is no fixed price condition
+        order.trigger_conditions.price_conditions.clear();
+        order
+    }
+    
     /// Create a simple take-profit order
     pub fn create_take_profit(
         user_id: i64,
@@ -1000,6 +2392,7 @@ impl Order {
             expires_at: None,
             parent_order_id: None,
             metadata: OrderMetadata::default(),
+            trailing: TrailingState::default(),
         }
     }
 }
@@ -1082,6 +2475,12 @@ impl Default for OrderMetadata {
             notes: None,
             client_order_id: None,
             performance_tracking: true,
+            position_size: None,
+            overlap_confirmed: false,
+            entry_price: None,
+            exit_denomination: None,
+            price_stale: false,
+            trail_distance: None,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,286 @@
+use chrono::{DateTime, Duration, Utc};
+use futures::stream::{self, StreamExt};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::api::{JupiterV6Client, QuoteRequestV6, SwapMode};
+use crate::errors::{BotError, Result};
+use super::executor::TradingEngineHandle;
+use super::exit_routing::{ExitDenomination, WSOL_MINT};
+use super::reconcile::hide_dust;
+use super::types::{Position, TradeResult};
+
+/// What the user has to type before anything is sold
+pub const PANIC_PHRASE: &str = "SELL EVERYTHING";
+/// Sells in flight at once; the engine still lands one wallet's transactions in order
+pub const MAX_CONCURRENT_SELLS: usize = 3;
+/// A failed sell is retried once before it's reported
+pub const SELL_ATTEMPTS: u32 = 2;
+
+/// How long each confirmation step stays open
+fn confirm_ttl() -> Duration {
+    Duration::seconds(90)
+}
+
+/// Quotes positions and sells them for the panic flow; mocked in tests
+#[async_trait::async_trait]
+pub trait PanicSeller: Send + Sync {
+    /// SOL a full sell of `position` is quoted to bring back
+    async fn quote(&self, user_wallet: &str, position: &Position) -> Result<f64>;
+    /// Sell all of `position` into `exit`
+    async fn sell(&self, user_wallet: &str, position: &Position, exit: ExitDenomination) -> Result<TradeResult>;
+}
+
+/// Quotes through Jupiter and sells through the trading engine, MEV protection included when the user has it on
+pub struct EngineSeller {
+    engine: TradingEngineHandle,
+    jupiter: Arc<JupiterV6Client>,
+    rpc_client: Arc<RpcClient>,
+}
+
+impl EngineSeller {
+    pub fn new(engine: TradingEngineHandle, jupiter: Arc<JupiterV6Client>, rpc_client: Arc<RpcClient>) -> Self {
+        Self { engine, jupiter, rpc_client }
+    }
+}
+
+#[async_trait::async_trait]
+impl PanicSeller for EngineSeller {
+    async fn quote(&self, _user_wallet: &str, position: &Position) -> Result<f64> {
+        let decimals = self.rpc_client.get_token_supply(&Pubkey::from_str(&position.mint)?).await?.decimals;
+        let amount = (position.amount * 10f64.powi(decimals as i32)) as u64;
+        if amount == 0 {
+            return Err(BotError::validation("Nothing to sell".to_string()).into());
+        }
+        let quote = self.jupiter.get_quote(QuoteRequestV6 {
+            input_mint: position.mint.clone(),
+            output_mint: WSOL_MINT.to_string(),
+            amount,
+            slippage_bps: 100,
+            swap_mode: Some(SwapMode::ExactIn),
+            dexes: None,
+            exclude_dexes: None,
+            max_accounts: None,
+            quote_mint: None,
+            minimize_slippage: None,
+            only_direct_routes: None,
+        }).await?;
+        let lamports = quote.out_amount.parse::<u64>()
+            .map_err(|_| BotError::parsing(format!("Bad quote amount {}", quote.out_amount)))?;
+        Ok(lamports as f64 / 1e9)
+    }
+
+    async fn sell(&self, user_wallet: &str, position: &Position, exit: ExitDenomination) -> Result<TradeResult> {
+        self.engine.sell_into(user_wallet.to_string(), position.mint.clone(), 100.0, exit).await
+    }
+}
+
+/// Which confirmation a panic sell is waiting for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicStage {
+    /// The preview's 🚨 button
+    Button,
+    /// `PANIC_PHRASE`, typed
+    Phrase,
+}
+
+/// One position in a panic sell, with its quote into SOL
+#[derive(Debug, Clone)]
+pub struct PanicQuote {
+    pub position: Position,
+    /// The quoted SOL, or why Jupiter couldn't quote it; the sell is tried either way
+    pub quote: std::result::Result<f64, String>,
+}
+
+/// A previewed panic sell waiting for both confirmations
+#[derive(Debug, Clone)]
+pub struct PanicPlan {
+    pub user_wallet: String,
+    pub exit: ExitDenomination,
+    pub quotes: Vec<PanicQuote>,
+    /// Positions under the dust threshold, left alone
+    pub skipped_dust: usize,
+    pub stage: PanicStage,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PanicPlan {
+    /// SOL the quoted positions should bring back
+    pub fn quoted_sol(&self) -> f64 {
+        self.quotes.iter().filter_map(|q| q.quote.as_ref().ok()).sum()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Outcome of typing into an open panic confirmation
+#[derive(Debug)]
+pub enum PhraseCheck {
+    /// No panic sell is waiting for its phrase; the text is someone else's
+    NotWaiting,
+    Expired,
+    /// Anything but the phrase cancels the panic sell
+    Mismatch,
+    Confirmed(PanicPlan),
+}
+
+/// How one position's sell ended, after its retry
+#[derive(Debug, Clone)]
+pub enum PanicSellOutcome {
+    Sold { position: Position, result: TradeResult, attempts: u32 },
+    Failed { position: Position, error: String, attempts: u32 },
+}
+
+impl PanicSellOutcome {
+    pub fn position(&self) -> &Position {
+        match self {
+            PanicSellOutcome::Sold { position, .. } | PanicSellOutcome::Failed { position, .. } => position,
+        }
+    }
+}
+
+/// Every position's outcome, in the plan's order
+#[derive(Debug, Clone, Default)]
+pub struct PanicReport {
+    pub outcomes: Vec<PanicSellOutcome>,
+}
+
+impl PanicReport {
+    pub fn sold(&self) -> usize {
+        self.outcomes.iter().filter(|o| matches!(o, PanicSellOutcome::Sold { .. })).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.outcomes.len() - self.sold()
+    }
+
+    /// SOL recovered across the sold positions; USDC exits count at their fill's SOL value
+    pub fn total_sol(&self) -> f64 {
+        self.outcomes.iter()
+            .filter_map(|o| match o {
+                PanicSellOutcome::Sold { result, .. } => Some(result.sol_received),
+                PanicSellOutcome::Failed { .. } => None,
+            })
+            .sum()
+    }
+}
+
+/// `/panic`: previews, double-confirms and runs sell-everything exits
+pub struct PanicDesk {
+    seller: Arc<dyn PanicSeller>,
+    dust_threshold_usd: f64,
+    pending: RwLock<HashMap<i64, PanicPlan>>,
+}
+
+impl PanicDesk {
+    pub fn new(seller: Arc<dyn PanicSeller>, dust_threshold_usd: f64) -> Self {
+        Self {
+            seller,
+            dust_threshold_usd,
+            pending: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Quote every position above the dust threshold and hold the plan for its first confirmation
+    pub async fn prepare(&self, user_id: i64, user_wallet: &str, positions: &[Position], exit: ExitDenomination) -> Result<PanicPlan> {
+        let (sellable, skipped_dust) = hide_dust(positions, self.dust_threshold_usd);
+        if sellable.is_empty() {
+            return Err(BotError::validation("No positions above the dust threshold to sell".to_string()).into());
+        }
+
+        let quotes = stream::iter(sellable)
+            .map(|position| async move {
+                let quote = self.seller.quote(user_wallet, position).await.map_err(|e| e.to_string());
+                PanicQuote { position: position.clone(), quote }
+            })
+            .buffered(MAX_CONCURRENT_SELLS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let plan = PanicPlan {
+            user_wallet: user_wallet.to_string(),
+            exit,
+            quotes,
+            skipped_dust,
+            stage: PanicStage::Button,
+            expires_at: Utc::now() + confirm_ttl(),
+        };
+        self.pending.write().await.insert(user_id, plan.clone());
+        Ok(plan)
+    }
+
+    /// First confirmation: the button was tapped, now the phrase has to be typed
+    pub async fn arm(&self, user_id: i64) -> Result<PanicPlan> {
+        let mut pending = self.pending.write().await;
+        let plan = match pending.get_mut(&user_id) {
+            Some(plan) if plan.is_expired(Utc::now()) => {
+                pending.remove(&user_id);
+                return Err(BotError::validation("This panic sell expired. Send /panic again".to_string()).into());
+            }
+            Some(plan) if plan.stage == PanicStage::Button => plan,
+            _ => return Err(BotError::not_found("No panic sell is waiting for confirmation".to_string()).into()),
+        };
+        plan.stage = PanicStage::Phrase;
+        plan.expires_at = Utc::now() + confirm_ttl();
+        Ok(plan.clone())
+    }
+
+    /// Second confirmation: `text` must be the phrase; the plan is consumed either way
+    pub async fn check_phrase(&self, user_id: i64, text: &str) -> PhraseCheck {
+        let mut pending = self.pending.write().await;
+        if !pending.get(&user_id).is_some_and(|plan| plan.stage == PanicStage::Phrase) {
+            return PhraseCheck::NotWaiting;
+        }
+        let Some(plan) = pending.remove(&user_id) else {
+            return PhraseCheck::NotWaiting;
+        };
+        if plan.is_expired(Utc::now()) {
+            PhraseCheck::Expired
+        } else if text.trim() == PANIC_PHRASE {
+            PhraseCheck::Confirmed(plan)
+        } else {
+            PhraseCheck::Mismatch
+        }
+    }
+
+    pub async fn cancel(&self, user_id: i64) -> bool {
+        self.pending.write().await.remove(&user_id).is_some()
+    }
+
+    /// Sell every position in `plan`, a few at a time, retrying each failure once
+    pub async fn execute(&self, plan: &PanicPlan) -> PanicReport {
+        let outcomes = stream::iter(&plan.quotes)
+            .map(|quote| self.sell_with_retry(&plan.user_wallet, &quote.position, plan.exit))
+            .buffered(MAX_CONCURRENT_SELLS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let report = PanicReport { outcomes };
+        info!("🚨 Panic sold {}/{} positions for {} ({:.4} SOL)",
+            report.sold(), report.outcomes.len(), plan.user_wallet, report.total_sol());
+        report
+    }
+
+    async fn sell_with_retry(&self, user_wallet: &str, position: &Position, exit: ExitDenomination) -> PanicSellOutcome {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self.seller.sell(user_wallet, position, exit).await {
+                Ok(result) => return PanicSellOutcome::Sold { position: position.clone(), result, attempts },
+                Err(e) if attempts < SELL_ATTEMPTS => {
+                    warn!("🚨 Panic sell of {} failed, retrying: {}", position.symbol, e);
+                }
+                Err(e) => {
+                    return PanicSellOutcome::Failed { position: position.clone(), error: e.to_string(), attempts };
+                }
+            }
+        }
+    }
+}
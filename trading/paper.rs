@@ -0,0 +1,201 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::errors::{BotError, Result};
+use super::types::{Balance, Position, TokenBalance, TradeType};
+
+/// Whether a user's trades go on-chain or into the virtual ledger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TradingMode {
+    #[default]
+    Live,
+    Paper,
+}
+
+impl TradingMode {
+    pub fn is_paper(&self) -> bool {
+        matches!(self, Self::Paper)
+    }
+
+    /// Stored form in the database
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Live => "live",
+            Self::Paper => "paper",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "live" => Some(Self::Live),
+            "paper" => Some(Self::Paper),
+            _ => None,
+        }
+    }
+}
+
+/// Virtual holding of one token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaperPosition {
+    pub mint: String,
+    pub symbol: String,
+    pub amount: f64,
+    /// SOL spent on what is still held
+    pub cost_sol: f64,
+    pub opened_at: DateTime<Utc>,
+}
+
+impl PaperPosition {
+    /// Average entry in SOL per token
+    pub fn average_price(&self) -> f64 {
+        if self.amount > 0.0 { self.cost_sol / self.amount } else { 0.0 }
+    }
+}
+
+/// Outcome of a simulated sell
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaperSale {
+    pub tokens_sold: f64,
+    pub sol_received: f64,
+    /// Against the average entry of the tokens sold
+    pub pnl_percentage: f64,
+}
+
+/// Virtual SOL balance and positions for one wallet in paper mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaperLedger {
+    pub starting_sol: f64,
+    pub sol: f64,
+    pub positions: BTreeMap<String, PaperPosition>,
+    pub realized_pnl_sol: f64,
+    pub trades: u32,
+    pub started_at: DateTime<Utc>,
+}
+
+impl PaperLedger {
+    pub fn new(starting_sol: f64) -> Self {
+        Self {
+            starting_sol,
+            sol: starting_sol,
+            positions: BTreeMap::new(),
+            realized_pnl_sol: 0.0,
+            trades: 0,
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Spend `amount_sol` on `tokens` of `mint`
+    pub fn buy(&mut self, mint: &str, symbol: &str, amount_sol: f64, tokens: f64) -> Result<()> {
+        if amount_sol > self.sol + f64::EPSILON {
+            return Err(BotError::validation(format!(
+                "Insufficient paper balance: {:.4} SOL available, {:.4} SOL needed",
+                self.sol, amount_sol
+            )));
+        }
+
+        self.sol -= amount_sol;
+        let position = self.positions.entry(mint.to_string()).or_insert_with(|| PaperPosition {
+            mint: mint.to_string(),
+            symbol: symbol.to_string(),
+            amount: 0.0,
+            cost_sol: 0.0,
+            opened_at: Utc::now(),
+        });
+        position.amount += tokens;
+        position.cost_sol += amount_sol;
+        self.trades += 1;
+        Ok(())
+    }
+
+    /// Sell `percentage` of the `mint` position at `price_sol` per token
+    pub fn sell(&mut self, mint: &str, percentage: f64, price_sol: f64) -> Result<PaperSale> {
+        let position = self.positions.get_mut(mint)
+            .filter(|p| p.amount > 0.0)
+            .ok_or_else(|| BotError::validation("No paper position in this token".to_string()))?;
+
+        let fraction = (percentage / 100.0).clamp(0.0, 1.0);
+        let tokens_sold = position.amount * fraction;
+        let cost_sold = position.cost_sol * fraction;
+        let sol_received = tokens_sold * price_sol;
+
+        position.amount -= tokens_sold;
+        position.cost_sol -= cost_sold;
+        if fraction >= 1.0 || position.amount <= f64::EPSILON {
+            self.positions.remove(mint);
+        }
+
+        self.sol += sol_received;
+        self.realized_pnl_sol += sol_received - cost_sold;
+        self.trades += 1;
+
+        Ok(PaperSale {
+            tokens_sold,
+            sol_received,
+            pnl_percentage: if cost_sold > 0.0 { (sol_received - cost_sold) / cost_sold * 100.0 } else { 0.0 },
+        })
+    }
+
+    pub fn holding(&self, mint: &str) -> f64 {
+        self.positions.get(mint).map(|p| p.amount).unwrap_or(0.0)
+    }
+
+    /// Virtual balance; `prices_usd` holds token prices, missing ones value at zero
+    pub fn balance(&self, sol_usd: f64, prices_usd: &BTreeMap<String, f64>) -> Balance {
+        let token_balances: BTreeMap<String, TokenBalance> = self.positions.values()
+            .map(|p| {
+                let price = prices_usd.get(&p.mint).copied().unwrap_or(0.0);
+                (p.mint.clone(), TokenBalance {
+                    mint: p.mint.clone(),
+                    symbol: p.symbol.clone(),
+                    amount: p.amount,
+                    value_usd: p.amount * price,
+                    price_per_token: price,
+                })
+            })
+            .collect();
+        let tokens_usd: f64 = token_balances.values().map(|b| b.value_usd).sum();
+
+        Balance {
+            sol: self.sol,
+            usdc: 0.0,
+            total_usd_value: self.sol * sol_usd + tokens_usd,
+            token_balances,
+            last_updated: Utc::now(),
+        }
+    }
+
+    /// Open paper positions valued at `prices_usd`, P&L against the SOL cost basis
+    pub fn positions(&self, sol_usd: f64, prices_usd: &BTreeMap<String, f64>) -> Vec<Position> {
+        self.positions.values()
+            .enumerate()
+            .map(|(index, p)| {
+                let price_usd = prices_usd.get(&p.mint).copied().unwrap_or(0.0);
+                let value_usd = p.amount * price_usd;
+                let cost_usd = p.cost_sol * sol_usd;
+                Position {
+                    token: p.symbol.clone(),
+                    symbol: p.symbol.clone(),
+                    mint: p.mint.clone(),
+                    amount: p.amount,
+                    value_usd,
+                    pnl_percentage: if cost_usd > 0.0 { (value_usd - cost_usd) / cost_usd * 100.0 } else { 0.0 },
+                    average_buy_price: p.average_price() * sol_usd,
+                    current_price: price_usd,
+                    sort_key: index as u64,
+                    last_updated: Utc::now(),
+                    untracked_entry: false,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Fill price after synthetic slippage: buys pay more, sells receive less
+pub fn simulate_fill(price: f64, trade_type: TradeType, slippage_bps: u16) -> f64 {
+    let slippage = slippage_bps as f64 / 10_000.0;
+    match trade_type {
+        TradeType::Sell => price * (1.0 - slippage),
+        TradeType::Buy | TradeType::Swap => price * (1.0 + slippage),
+    }
+}
@@ -0,0 +1,216 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::errors::{BotError, Result};
+use crate::wallet::TransactionPriority;
+
+/// Settings for prioritization fee sampling
+#[derive(Debug, Clone)]
+pub struct PriorityFeeConfig {
+    /// How often tracked account sets are re-sampled, and how long an estimate stays fresh
+    pub refresh_interval: Duration,
+    /// Only samples this many slots back from the newest one count
+    pub lookback_slots: u64,
+    /// Compute unit price before any estimate exists, in micro-lamports
+    pub fallback_micro_lamports: u64,
+    /// Account sets kept warm by the refresh loop; the stalest is dropped beyond this
+    pub max_tracked_sets: usize,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(10),
+            lookback_slots: 150,
+            fallback_micro_lamports: 10_000,
+            max_tracked_sets: 64,
+        }
+    }
+}
+
+/// Recent prioritization fee percentiles, in micro-lamports per compute unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeePercentiles {
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p90: u64,
+    /// Non-zero samples the percentiles were taken over
+    pub samples: usize,
+}
+
+impl FeePercentiles {
+    /// Percentiles over `(slot, fee)` samples within `lookback_slots` of the newest slot
+    ///
+    /// Slots where nobody paid for priority are left out; `None` when no slot in
+    /// the window had a fee.
+    pub fn from_samples(samples: &[(u64, u64)], lookback_slots: u64) -> Option<Self> {
+        let newest = samples.iter().map(|(slot, _)| *slot).max()?;
+        let oldest = newest.saturating_sub(lookback_slots.saturating_sub(1));
+        let mut fees: Vec<u64> = samples.iter()
+            .filter(|(slot, fee)| *slot >= oldest && *fee > 0)
+            .map(|(_, fee)| *fee)
+            .collect();
+        if fees.is_empty() {
+            return None;
+        }
+        fees.sort_unstable();
+
+        Some(Self {
+            p25: percentile_sorted(&fees, 25.0),
+            p50: percentile_sorted(&fees, 50.0),
+            p75: percentile_sorted(&fees, 75.0),
+            p90: percentile_sorted(&fees, 90.0),
+            samples: fees.len(),
+        })
+    }
+
+    pub fn for_priority(&self, priority: TransactionPriority) -> u64 {
+        match priority {
+            TransactionPriority::Low => self.p25,
+            TransactionPriority::Normal => self.p50,
+            TransactionPriority::High => self.p75,
+            TransactionPriority::Critical => self.p90,
+        }
+    }
+}
+
+/// Nearest-rank percentile of already sorted, non-empty samples
+fn percentile_sorted(sorted: &[u64], percentile: f64) -> u64 {
+    let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrackedEstimate {
+    percentiles: Option<FeePercentiles>,
+    updated_at: Instant,
+}
+
+/// Compute unit prices from recent prioritization fees, kept per writable account set
+///
+/// Sets are sampled on first use and re-sampled by `start`'s loop. When the RPC
+/// fails the last known percentiles keep being served.
+#[derive(Clone)]
+pub struct PriorityFeeEstimator {
+    rpc_client: Arc<RpcClient>,
+    config: PriorityFeeConfig,
+    /// Keyed by the sorted account set; the empty set is the cluster-wide estimate
+    tracked: Arc<RwLock<HashMap<Vec<Pubkey>, TrackedEstimate>>>,
+}
+
+impl PriorityFeeEstimator {
+    pub fn new(rpc_client: Arc<RpcClient>, config: PriorityFeeConfig) -> Self {
+        Self {
+            rpc_client,
+            config,
+            tracked: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn config(&self) -> &PriorityFeeConfig {
+        &self.config
+    }
+
+    /// Cluster-wide price for a priority
+    pub async fn estimate(&self, priority: TransactionPriority) -> u64 {
+        self.estimate_for(&[], priority).await
+    }
+
+    /// Price for a transaction writing these accounts
+    pub async fn estimate_for(&self, writable_accounts: &[Pubkey], priority: TransactionPriority) -> u64 {
+        let key = account_set(writable_accounts);
+        let cached = self.tracked.read().await.get(&key).copied();
+        let percentiles = match cached {
+            Some(tracked) if tracked.updated_at.elapsed() < self.config.refresh_interval => tracked.percentiles,
+            _ => match self.refresh(&key).await {
+                Ok(percentiles) => percentiles,
+                Err(_) => cached.and_then(|tracked| tracked.percentiles),
+            },
+        };
+        percentiles
+            .map(|percentiles| percentiles.for_priority(priority))
+            .unwrap_or(self.config.fallback_micro_lamports)
+    }
+
+    /// Last known percentiles for an account set
+    pub async fn percentiles(&self, writable_accounts: &[Pubkey]) -> Option<FeePercentiles> {
+        self.tracked.read().await.get(&account_set(writable_accounts))?.percentiles
+    }
+
+    /// Sample an account set now; the previous estimate is kept on failure
+    pub async fn refresh(&self, writable_accounts: &[Pubkey]) -> Result<Option<FeePercentiles>> {
+        let key = account_set(writable_accounts);
+        match self.rpc_client.get_recent_prioritization_fees(&key).await {
+            Ok(fees) => {
+                let samples: Vec<(u64, u64)> = fees.iter().map(|fee| (fee.slot, fee.prioritization_fee)).collect();
+                Ok(self.record(&key, &samples).await)
+            }
+            Err(e) => {
+                debug!("Prioritization fees unavailable for {} accounts: {}", key.len(), e);
+                Err(BotError::external_api(format!("getRecentPrioritizationFees failed: {}", e)).into())
+            }
+        }
+    }
+
+    /// Replace an account set's estimate with percentiles over these `(slot, fee)` samples
+    pub async fn record(&self, writable_accounts: &[Pubkey], samples: &[(u64, u64)]) -> Option<FeePercentiles> {
+        let key = account_set(writable_accounts);
+        let percentiles = FeePercentiles::from_samples(samples, self.config.lookback_slots);
+        let mut tracked = self.tracked.write().await;
+        if !tracked.contains_key(&key) && tracked.len() >= self.config.max_tracked_sets {
+            let stalest = tracked.iter()
+                .filter(|(set, _)| !set.is_empty())
+                .min_by_key(|(_, estimate)| estimate.updated_at)
+                .map(|(set, _)| set.clone());
+            if let Some(stalest) = stalest {
+                tracked.remove(&stalest);
+            }
+        }
+        tracked.insert(key, TrackedEstimate { percentiles, updated_at: Instant::now() });
+        percentiles
+    }
+
+    /// Re-sample the cluster-wide estimate and every tracked account set in the background
+    pub async fn start(&self) {
+        let estimator = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(estimator.config.refresh_interval);
+            loop {
+                interval.tick().await;
+                let mut sets: Vec<Vec<Pubkey>> = estimator.tracked.read().await.keys().cloned().collect();
+                if !sets.iter().any(|set| set.is_empty()) {
+                    sets.push(Vec::new());
+                }
+                for set in sets {
+                    if let Err(e) = estimator.refresh(&set).await {
+                        warn!("⛽ Priority fee refresh failed, keeping last estimate: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Nearest-rank percentile of non-zero samples
+    pub fn percentile_of(mut samples: Vec<u64>, percentile: f64) -> Option<u64> {
+        samples.retain(|fee| *fee > 0);
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        Some(percentile_sorted(&samples, percentile))
+    }
+}
+
+fn account_set(accounts: &[Pubkey]) -> Vec<Pubkey> {
+    let mut set = accounts.to_vec();
+    set.sort_unstable();
+    set.dedup();
+    set
+}
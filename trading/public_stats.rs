@@ -0,0 +1,224 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use super::leaderboard::{LeaderboardEntry, LeaderboardPeriod};
+
+/// Win rates are percentages, so no trader can move the sum by more than this
+const WIN_RATE_CAP: f64 = 100.0;
+
+/// Privacy rules for aggregates shown to everyone
+///
+/// Per-user views stay exact; only the public totals go through this.
+#[derive(Debug, Clone)]
+pub struct AggregatePrivacy {
+    /// Aggregates are hidden until at least this many traders contribute
+    pub min_contributors: usize,
+    /// Privacy budget for each published statistic; smaller means more noise
+    pub epsilon: f64,
+    /// Most volume one trader adds to the public total, which is also its sensitivity
+    pub volume_cap_sol: f64,
+    pub volume_bucket_sol: f64,
+    pub win_rate_bucket: f64,
+    pub participant_bucket: u32,
+}
+
+impl Default for AggregatePrivacy {
+    fn default() -> Self {
+        Self {
+            min_contributors: 10,
+            epsilon: 1.0,
+            volume_cap_sol: 100.0,
+            volume_bucket_sol: 50.0,
+            win_rate_bucket: 5.0,
+            participant_bucket: 5,
+        }
+    }
+}
+
+impl AggregatePrivacy {
+    /// `PUBLIC_STATS_MIN_CONTRIBUTORS`, `PUBLIC_STATS_EPSILON`, `PUBLIC_STATS_VOLUME_CAP_SOL`
+    pub fn from_env() -> Self {
+        let mut privacy = Self::default();
+        if let Some(k) = std::env::var("PUBLIC_STATS_MIN_CONTRIBUTORS").ok().and_then(|k| k.parse().ok()) {
+            privacy.min_contributors = k;
+        }
+        if let Some(epsilon) = std::env::var("PUBLIC_STATS_EPSILON").ok().and_then(|e| e.parse::<f64>().ok()) {
+            if epsilon > 0.0 {
+                privacy.epsilon = epsilon;
+            }
+        }
+        if let Some(cap) = std::env::var("PUBLIC_STATS_VOLUME_CAP_SOL").ok().and_then(|c| c.parse::<f64>().ok()) {
+            if cap > 0.0 {
+                privacy.volume_cap_sol = cap;
+            }
+        }
+        privacy
+    }
+
+    /// Exact aggregate with each trader's contribution capped first
+    pub fn aggregate(&self, entries: &[LeaderboardEntry]) -> ExactAggregate {
+        let participants = entries.len();
+        let avg_win_rate = if participants == 0 {
+            0.0
+        } else {
+            entries.iter().map(|e| e.win_rate.clamp(0.0, WIN_RATE_CAP)).sum::<f64>() / participants as f64
+        };
+        ExactAggregate {
+            participants,
+            total_volume_sol: entries.iter().map(|e| e.volume_sol.clamp(0.0, self.volume_cap_sol)).sum(),
+            avg_win_rate,
+        }
+    }
+
+    /// How far one trader can move the total volume
+    pub fn volume_sensitivity(&self) -> f64 {
+        self.volume_cap_sol
+    }
+
+    /// How far one trader can move the average win rate of `participants`
+    pub fn win_rate_sensitivity(&self, participants: usize) -> f64 {
+        WIN_RATE_CAP / participants.max(1) as f64
+    }
+
+    /// Largest noise added to a statistic with the given sensitivity
+    pub fn noise_bound(&self, sensitivity: f64) -> f64 {
+        3.0 * sensitivity / self.epsilon
+    }
+
+    /// Noised, bucketed aggregate, or `None` when too few traders contribute
+    pub fn publish<R: Rng>(&self, exact: &ExactAggregate, rng: &mut R) -> Option<PublishedAggregate> {
+        if exact.participants < self.min_contributors {
+            return None;
+        }
+
+        let participant_bucket = self.participant_bucket.max(1) as f64;
+        let volume = self.noised(exact.total_volume_sol, self.volume_sensitivity(), rng);
+        let win_rate = self.noised(exact.avg_win_rate, self.win_rate_sensitivity(exact.participants), rng);
+        Some(PublishedAggregate {
+            // Counts are floored so the published value never overstates the K threshold
+            participants: ((exact.participants as f64 / participant_bucket).floor() * participant_bucket) as u32,
+            total_volume_sol: round_to_bucket(volume, self.volume_bucket_sol).max(0.0),
+            avg_win_rate: round_to_bucket(win_rate, self.win_rate_bucket).clamp(0.0, 100.0),
+        })
+    }
+
+    /// Truncated Laplace noise with scale `sensitivity / epsilon`
+    fn noised<R: Rng>(&self, value: f64, sensitivity: f64, rng: &mut R) -> f64 {
+        let scale = sensitivity / self.epsilon;
+        let u: f64 = rng.gen_range(-0.5..0.5);
+        let laplace = -scale * u.signum() * (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE).ln();
+        let bound = self.noise_bound(sensitivity);
+        value + laplace.clamp(-bound, bound)
+    }
+}
+
+pub fn round_to_bucket(value: f64, bucket: f64) -> f64 {
+    if bucket <= 0.0 {
+        return value;
+    }
+    (value / bucket).round() * bucket
+}
+
+/// Exact aggregate of capped contributions, kept for internal use only
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExactAggregate {
+    pub participants: usize,
+    pub total_volume_sol: f64,
+    pub avg_win_rate: f64,
+}
+
+/// What public pages are allowed to show
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublishedAggregate {
+    pub participants: u32,
+    pub total_volume_sol: f64,
+    pub avg_win_rate: f64,
+}
+
+/// Exact and published aggregate taken together, so re-renders reuse the same noise
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateSnapshot {
+    pub period: LeaderboardPeriod,
+    pub taken_at: DateTime<Utc>,
+    pub exact: ExactAggregate,
+    /// `None` while fewer than K traders contribute
+    pub published: Option<PublishedAggregate>,
+}
+
+impl AggregateSnapshot {
+    /// Market stats block for public messages; only published values are used
+    pub fn render(&self, min_contributors: usize) -> String {
+        match &self.published {
+            Some(stats) => format!(
+                "\n\n📈 **Market Stats**\n\
+                Traders: {}+\n\
+                Total Volume: ~{:.0} SOL\n\
+                Avg Win Rate: ~{:.0}%\n",
+                stats.participants,
+                stats.total_volume_sol,
+                stats.avg_win_rate
+            ),
+            None => format!(
+                "\n\n📈 **Market Stats**\n\
+                Shown once at least {} traders are active this period\n",
+                min_contributors
+            ),
+        }
+    }
+}
+
+/// Published aggregate snapshots per leaderboard period
+pub struct PublicStatsBoard {
+    privacy: AggregatePrivacy,
+    snapshots: RwLock<HashMap<LeaderboardPeriod, AggregateSnapshot>>,
+}
+
+impl PublicStatsBoard {
+    pub fn new(privacy: AggregatePrivacy) -> Self {
+        Self {
+            privacy,
+            snapshots: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn privacy(&self) -> &AggregatePrivacy {
+        &self.privacy
+    }
+
+    /// Snapshot for `entries`; the stored published values are reused while the
+    /// exact aggregate is unchanged, so repeated renders can't average the noise away
+    pub async fn snapshot(&self, period: LeaderboardPeriod, entries: &[LeaderboardEntry]) -> AggregateSnapshot {
+        self.snapshot_with(period, entries, &mut rand::thread_rng()).await
+    }
+
+    pub async fn snapshot_with<R: Rng>(
+        &self,
+        period: LeaderboardPeriod,
+        entries: &[LeaderboardEntry],
+        rng: &mut R,
+    ) -> AggregateSnapshot {
+        let exact = self.privacy.aggregate(entries);
+        let mut snapshots = self.snapshots.write().await;
+        if let Some(existing) = snapshots.get(&period) {
+            if existing.exact == exact {
+                return existing.clone();
+            }
+        }
+
+        let snapshot = AggregateSnapshot {
+            period,
+            taken_at: Utc::now(),
+            published: self.privacy.publish(&exact, rng),
+            exact,
+        };
+        snapshots.insert(period, snapshot.clone());
+        snapshot
+    }
+
+    pub async fn latest(&self, period: LeaderboardPeriod) -> Option<AggregateSnapshot> {
+        self.snapshots.read().await.get(&period).cloned()
+    }
+}
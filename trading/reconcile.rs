@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::errors::Result;
+use crate::utils::Config;
+use super::executor::TradingEngineHandle;
+use super::token_resolver::TokenResolver;
+use super::types::Position;
+
+/// Amounts this close are the same balance after UI-amount rounding
+const AMOUNT_TOLERANCE: f64 = 1e-9;
+
+/// Mint and whole-token amount of a jsonParsed token account
+pub fn parsed_token_amount(parsed: &Value) -> Option<(String, f64)> {
+    let info = parsed.get("info")?;
+    let mint = info.get("mint")?.as_str()?.to_string();
+    let amount = info.pointer("/tokenAmount/uiAmountString")?.as_str()?.parse().ok()?;
+    Some((mint, amount))
+}
+
+/// Holdings per mint across the wallet's token accounts; empty accounts are left out
+pub fn on_chain_balances<'a>(accounts: impl IntoIterator<Item = &'a Value>) -> BTreeMap<String, f64> {
+    let mut balances = BTreeMap::new();
+    for (mint, amount) in accounts.into_iter().filter_map(parsed_token_amount) {
+        *balances.entry(mint).or_insert(0.0) += amount;
+    }
+    balances.retain(|_, amount| *amount > AMOUNT_TOLERANCE);
+    balances
+}
+
+/// How a mint's on-chain balance differed from its tracked position
+#[derive(Debug, Clone, PartialEq)]
+pub enum BalanceChange {
+    /// Held with no tracked position, e.g. bought elsewhere or airdropped
+    Added { mint: String, amount: f64 },
+    /// Tracked but no longer held, e.g. sold or sent outside the bot
+    Removed { mint: String, tracked: f64 },
+    /// Held in a different amount than tracked
+    Changed { mint: String, tracked: f64, on_chain: f64 },
+}
+
+impl BalanceChange {
+    pub fn mint(&self) -> &str {
+        match self {
+            Self::Added { mint, .. } | Self::Removed { mint, .. } | Self::Changed { mint, .. } => mint,
+        }
+    }
+}
+
+/// Tracked positions corrected to what the wallet holds on-chain
+#[derive(Debug, Clone, Default)]
+pub struct Reconciliation {
+    pub positions: Vec<Position>,
+    pub changes: Vec<BalanceChange>,
+}
+
+impl Reconciliation {
+    /// Nothing differed, so the stored positions can stay as they are
+    pub fn is_clean(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Value positions at `prices_usd`; P&L only where a cost basis is known
+    pub fn apply_prices(&mut self, prices_usd: &BTreeMap<String, f64>) {
+        for position in &mut self.positions {
+            let Some(price) = prices_usd.get(&position.mint).copied().filter(|p| *p > 0.0) else { continue };
+            position.current_price = price;
+            position.value_usd = position.amount * price;
+            if !position.untracked_entry && position.average_buy_price > 0.0 {
+                position.pnl_percentage = (price - position.average_buy_price) / position.average_buy_price * 100.0;
+            }
+        }
+    }
+}
+
+/// Diff tracked positions against on-chain balances
+///
+/// Changed positions keep their cost basis. Mints held without a tracked position
+/// become untracked entries with an unknown cost basis; positions no longer held are dropped.
+pub fn reconcile(tracked: &[Position], on_chain: &BTreeMap<String, f64>, now: DateTime<Utc>) -> Reconciliation {
+    let mut reconciliation = Reconciliation::default();
+
+    for position in tracked {
+        match on_chain.get(&position.mint).copied() {
+            None => reconciliation.changes.push(BalanceChange::Removed {
+                mint: position.mint.clone(),
+                tracked: position.amount,
+            }),
+            Some(amount) if (amount - position.amount).abs() > AMOUNT_TOLERANCE * amount.max(1.0) => {
+                reconciliation.changes.push(BalanceChange::Changed {
+                    mint: position.mint.clone(),
+                    tracked: position.amount,
+                    on_chain: amount,
+                });
+                reconciliation.positions.push(Position {
+                    amount,
+                    value_usd: amount * position.current_price,
+                    last_updated: now,
+                    ..position.clone()
+                });
+            }
+            Some(_) => reconciliation.positions.push(position.clone()),
+        }
+    }
+
+    let tracked_mints: HashSet<&str> = tracked.iter().map(|p| p.mint.as_str()).collect();
+    let mut sort_key = tracked.iter().map(|p| p.sort_key + 1).max().unwrap_or(0);
+    for (mint, amount) in on_chain {
+        if tracked_mints.contains(mint.as_str()) {
+            continue;
+        }
+        let symbol = TokenResolver::get_symbol(mint);
+        reconciliation.changes.push(BalanceChange::Added { mint: mint.clone(), amount: *amount });
+        reconciliation.positions.push(Position {
+            token: symbol.clone(),
+            symbol,
+            mint: mint.clone(),
+            amount: *amount,
+            value_usd: 0.0,
+            pnl_percentage: 0.0,
+            average_buy_price: 0.0,
+            current_price: 0.0,
+            sort_key,
+            last_updated: now,
+            untracked_entry: true,
+        });
+        sort_key += 1;
+    }
+
+    reconciliation
+}
+
+/// Positions worth showing, and how many dust holdings were left out
+///
+/// Unpriced positions stay visible: their value is unknown, not small.
+pub fn hide_dust(positions: &[Position], threshold_usd: f64) -> (Vec<&Position>, usize) {
+    let (visible, dust): (Vec<&Position>, Vec<&Position>) = positions.iter()
+        .partition(|p| p.current_price <= 0.0 || p.value_usd >= threshold_usd);
+    (visible, dust.len())
+}
+
+/// Keeps tracked positions in line with the wallet's on-chain balances
+///
+/// Wallets sync on demand, and on an interval once they have opened /portfolio.
+pub struct PositionSync {
+    engine: TradingEngineHandle,
+    interval: Duration,
+    dust_threshold_usd: f64,
+    wallets: RwLock<HashSet<String>>,
+}
+
+impl PositionSync {
+    pub fn new(engine: TradingEngineHandle, interval: Duration, dust_threshold_usd: f64) -> Self {
+        Self {
+            engine,
+            interval,
+            dust_threshold_usd,
+            wallets: RwLock::new(HashSet::new()),
+        }
+    }
+
+    pub fn from_config(engine: TradingEngineHandle, config: &Config) -> Self {
+        Self::new(engine, Duration::from_secs(config.position_sync_interval_secs), config.dust_threshold_usd)
+    }
+
+    pub fn dust_threshold_usd(&self) -> f64 {
+        self.dust_threshold_usd
+    }
+
+    /// Include the wallet in the periodic sync
+    pub async fn watch(&self, user_wallet: &str) {
+        self.wallets.write().await.insert(user_wallet.to_string());
+    }
+
+    pub async fn watched(&self) -> usize {
+        self.wallets.read().await.len()
+    }
+
+    /// Sync one wallet now, through its trading lane so it can't race a trade
+    pub async fn sync(&self, user_wallet: &str) -> Result<Reconciliation> {
+        self.watch(user_wallet).await;
+        self.engine.sync_positions(user_wallet.to_string()).await
+    }
+
+    /// Sync every watched wallet each interval
+    pub fn spawn_periodic(self: &Arc<Self>) {
+        let sync = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sync.interval);
+            // The first tick fires immediately; /portfolio syncs on demand until then
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                let wallets: Vec<String> = sync.wallets.read().await.iter().cloned().collect();
+                let mut changed = 0;
+                for wallet in &wallets {
+                    match sync.engine.sync_positions(wallet.clone()).await {
+                        Ok(reconciliation) if !reconciliation.is_clean() => changed += 1,
+                        Ok(_) => {}
+                        Err(e) => debug!("🔄 Position sync skipped for {}: {}", wallet, e),
+                    }
+                }
+                if changed > 0 {
+                    info!("🔄 Position sync corrected {} of {} wallets", changed, wallets.len());
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,399 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcBlockConfig, RpcTransactionConfig},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedTransaction, EncodedTransactionWithStatusMeta,
+    TransactionDetails, UiMessage, UiTransactionEncoding,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::errors::{BotError, Result};
+use super::types::SandwichFinding;
+
+/// Direction of a swap relative to the base token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapSide {
+    /// Quote in, base out
+    Buy,
+    /// Base in, quote out
+    Sell,
+}
+
+impl SwapSide {
+    fn opposite(self) -> Self {
+        match self {
+            SwapSide::Buy => SwapSide::Sell,
+            SwapSide::Sell => SwapSide::Buy,
+        }
+    }
+}
+
+/// Pool balances of the traded pair
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PoolReserves {
+    pub base: f64,
+    pub quote: f64,
+}
+
+/// One swap against a pool, read from a block transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolSwap {
+    pub signature: String,
+    /// Position in the block
+    pub index: usize,
+    /// Fee payer
+    pub signer: String,
+    /// Owner of the pool's token vaults
+    pub pool: String,
+    pub side: SwapSide,
+    /// Quote for buys, base for sells
+    pub amount_in: f64,
+    /// Base for buys, quote for sells
+    pub amount_out: f64,
+    pub reserves_before: Option<PoolReserves>,
+}
+
+impl PoolSwap {
+    /// Read a swap of `base_mint`/`quote_mint` from a block transaction
+    ///
+    /// The pool is the account owner whose base and quote balances moved in
+    /// opposite directions; its pre balances are the reserves the swap saw.
+    /// Failed transactions and transactions that touch no such owner yield `None`.
+    pub fn from_block_transaction(
+        index: usize,
+        transaction: &EncodedTransactionWithStatusMeta,
+        base_mint: &str,
+        quote_mint: &str,
+    ) -> Option<Self> {
+        let meta = transaction.meta.as_ref()?;
+        if meta.err.is_some() {
+            return None;
+        }
+        let EncodedTransaction::Json(ui_transaction) = &transaction.transaction else { return None };
+        let UiMessage::Parsed(message) = &ui_transaction.message else { return None };
+        let signature = ui_transaction.signatures.first()?.clone();
+        let signer = message.account_keys.iter().find(|k| k.signer)?.pubkey.clone();
+        let keys: Vec<&str> = message.account_keys.iter().map(|k| k.pubkey.as_str()).collect();
+
+        // (owner, mint) -> (pre, post)
+        let mut balances: HashMap<(String, String), (f64, f64)> = HashMap::new();
+        let token_sets = [(&meta.pre_token_balances, true), (&meta.post_token_balances, false)];
+        for (set, is_pre) in token_sets {
+            if let OptionSerializer::Some(set) = set {
+                for balance in set {
+                    if balance.mint != base_mint && balance.mint != quote_mint {
+                        continue;
+                    }
+                    let owner = match &balance.owner {
+                        OptionSerializer::Some(owner) => owner.clone(),
+                        _ => keys.get(balance.account_index as usize).map(|k| k.to_string()).unwrap_or_default(),
+                    };
+                    let amount = balance.ui_token_amount.ui_amount.unwrap_or(0.0);
+                    let entry = balances.entry((owner, balance.mint.clone())).or_insert((0.0, 0.0));
+                    if is_pre { entry.0 += amount } else { entry.1 += amount }
+                }
+            }
+        }
+
+        let mut owners: Vec<&String> = balances.keys().map(|(owner, _)| owner).collect();
+        owners.sort();
+        owners.dedup();
+
+        for owner in owners {
+            if *owner == signer {
+                continue;
+            }
+            let Some(&(base_pre, base_post)) = balances.get(&(owner.clone(), base_mint.to_string())) else { continue };
+            let Some(&(quote_pre, quote_post)) = balances.get(&(owner.clone(), quote_mint.to_string())) else { continue };
+            let base_delta = base_post - base_pre;
+            let quote_delta = quote_post - quote_pre;
+
+            let (side, amount_in, amount_out) = if base_delta < 0.0 && quote_delta > 0.0 {
+                (SwapSide::Buy, quote_delta, -base_delta)
+            } else if base_delta > 0.0 && quote_delta < 0.0 {
+                (SwapSide::Sell, base_delta, -quote_delta)
+            } else {
+                continue;
+            };
+
+            let reserves_before = (base_pre > 0.0 && quote_pre > 0.0)
+                .then_some(PoolReserves { base: base_pre, quote: quote_pre });
+
+            return Some(Self {
+                signature,
+                index,
+                signer,
+                pool: owner.clone(),
+                side,
+                amount_in,
+                amount_out,
+                reserves_before,
+            });
+        }
+
+        None
+    }
+}
+
+/// Settings for post-trade sandwich detection
+#[derive(Debug, Clone)]
+pub struct SandwichConfig {
+    /// Pool fee applied when replaying the user's swap without the front-run
+    pub pool_fee_bps: u32,
+    /// Loss, as a percentage of the fair output, above which the user is told
+    pub notify_loss_pct: f64,
+    /// How long to wait for an unsigned trade to land before giving up
+    pub confirm_timeout: Duration,
+    pub poll_interval: Duration,
+}
+
+impl Default for SandwichConfig {
+    fn default() -> Self {
+        Self {
+            pool_fee_bps: 25,
+            notify_loss_pct: 0.5,
+            confirm_timeout: Duration::from_secs(120),
+            poll_interval: Duration::from_secs(3),
+        }
+    }
+}
+
+/// Pattern matching over the swaps of one block
+pub struct SandwichDetector;
+
+impl SandwichDetector {
+    /// Find a sandwich around `user_signature`
+    ///
+    /// The classic shape: the nearest swap before the user's on the same pool
+    /// goes the same way, the nearest one after goes the other way, and both
+    /// come from one signer who is not the user.
+    pub fn detect(user_signature: &str, swaps: &[PoolSwap], config: &SandwichConfig) -> Option<SandwichFinding> {
+        let user = swaps.iter().find(|s| s.signature == user_signature)?;
+        let same_pool = |s: &&PoolSwap| s.pool == user.pool && s.signature != user.signature;
+
+        let front = swaps.iter()
+            .filter(same_pool)
+            .filter(|s| s.index < user.index)
+            .max_by_key(|s| s.index)?;
+        let back = swaps.iter()
+            .filter(same_pool)
+            .filter(|s| s.index > user.index)
+            .min_by_key(|s| s.index)?;
+
+        if front.signer == user.signer
+            || front.signer != back.signer
+            || front.side != user.side
+            || back.side != user.side.opposite()
+        {
+            return None;
+        }
+
+        let (loss_quote, loss_pct) = front.reserves_before
+            .map(|reserves| Self::quantify_loss(user, reserves, config.pool_fee_bps))
+            .unwrap_or((0.0, 0.0));
+
+        Some(SandwichFinding {
+            front_run_signature: front.signature.clone(),
+            back_run_signature: back.signature.clone(),
+            attacker: front.signer.clone(),
+            pool: user.pool.clone(),
+            loss_quote,
+            loss_pct,
+        })
+    }
+
+    /// User's shortfall against replaying the same swap on the pre-attack reserves
+    ///
+    /// Returns the loss in quote units and as a percentage of the fair output.
+    pub fn quantify_loss(user: &PoolSwap, reserves: PoolReserves, pool_fee_bps: u32) -> (f64, f64) {
+        let (reserve_in, reserve_out) = match user.side {
+            SwapSide::Buy => (reserves.quote, reserves.base),
+            SwapSide::Sell => (reserves.base, reserves.quote),
+        };
+        let fair_out = Self::constant_product_out(reserve_in, reserve_out, user.amount_in, pool_fee_bps);
+        let shortfall = (fair_out - user.amount_out).max(0.0);
+        if fair_out <= 0.0 || shortfall == 0.0 {
+            return (0.0, 0.0);
+        }
+
+        let loss_quote = match user.side {
+            // Value the missing tokens at the price the user actually paid
+            SwapSide::Buy if user.amount_out > 0.0 => shortfall * user.amount_in / user.amount_out,
+            SwapSide::Buy => 0.0,
+            SwapSide::Sell => shortfall,
+        };
+        (loss_quote, shortfall / fair_out * 100.0)
+    }
+
+    fn constant_product_out(reserve_in: f64, reserve_out: f64, amount_in: f64, fee_bps: u32) -> f64 {
+        let effective_in = amount_in * (1.0 - fee_bps as f64 / 10_000.0);
+        if reserve_in + effective_in <= 0.0 {
+            return 0.0;
+        }
+        reserve_out * effective_in / (reserve_in + effective_in)
+    }
+}
+
+/// Measured sandwich exposure for one user
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MevStats {
+    /// Trades whose block was inspected
+    pub inspected: u64,
+    pub sandwiched: u64,
+    /// Sum of measured losses in quote units (SOL for SOL-paired trades)
+    pub total_loss_quote: f64,
+    pub largest_loss_quote: f64,
+    pub last_sandwiched_at: Option<DateTime<Utc>>,
+}
+
+impl MevStats {
+    pub fn sandwich_rate(&self) -> f64 {
+        if self.inspected == 0 {
+            return 0.0;
+        }
+        self.sandwiched as f64 / self.inspected as f64 * 100.0
+    }
+}
+
+/// Post-trade sandwich inspection of confirmed swaps
+///
+/// Best-effort: callers spawn [`SandwichMonitor::inspect_trade`] after the
+/// trade message is sent and only hear back if something was found.
+#[derive(Clone)]
+pub struct SandwichMonitor {
+    rpc_client: Arc<RpcClient>,
+    config: SandwichConfig,
+    stats: Arc<RwLock<HashMap<i64, MevStats>>>,
+    findings: Arc<RwLock<HashMap<String, SandwichFinding>>>,
+}
+
+impl SandwichMonitor {
+    pub fn new(rpc_client: Arc<RpcClient>, config: SandwichConfig) -> Self {
+        Self {
+            rpc_client,
+            config,
+            stats: Arc::new(RwLock::new(HashMap::new())),
+            findings: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn config(&self) -> &SandwichConfig {
+        &self.config
+    }
+
+    /// Wait for the trade to confirm, then check its block for a sandwich
+    pub async fn inspect_trade(
+        &self,
+        user_id: i64,
+        signature: &str,
+        base_mint: &str,
+        quote_mint: &str,
+    ) -> Result<Option<SandwichFinding>> {
+        let parsed = Signature::from_str(signature)
+            .map_err(|e| BotError::parsing(format!("Invalid signature {}: {}", signature, e)))?;
+        let slot = self.wait_for_slot(&parsed).await?;
+
+        let config = RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::JsonParsed),
+            transaction_details: Some(TransactionDetails::Full),
+            rewards: Some(false),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let block = self.rpc_client.get_block_with_config(slot, config).await
+            .map_err(|e| BotError::internal(format!("Failed to fetch block {}: {}", slot, e)))?;
+
+        let swaps: Vec<PoolSwap> = block.transactions.unwrap_or_default().iter()
+            .enumerate()
+            .filter_map(|(index, tx)| PoolSwap::from_block_transaction(index, tx, base_mint, quote_mint))
+            .collect();
+        debug!("🥪 {} swaps on the pair in slot {}", swaps.len(), slot);
+
+        let finding = SandwichDetector::detect(signature, &swaps, &self.config);
+        self.record_outcome(user_id, signature, finding.clone()).await;
+        Ok(finding)
+    }
+
+    async fn wait_for_slot(&self, signature: &Signature) -> Result<u64> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let deadline = tokio::time::Instant::now() + self.config.confirm_timeout;
+
+        loop {
+            match self.rpc_client.get_transaction_with_config(signature, config).await {
+                Ok(transaction) => return Ok(transaction.slot),
+                Err(e) if tokio::time::Instant::now() >= deadline => {
+                    return Err(BotError::not_found(format!("Transaction {} never confirmed: {}", signature, e)).into());
+                }
+                Err(_) => tokio::time::sleep(self.config.poll_interval).await,
+            }
+        }
+    }
+
+    /// Fold one inspected trade into the user's stats
+    pub async fn record_outcome(&self, user_id: i64, signature: &str, finding: Option<SandwichFinding>) {
+        let mut stats = self.stats.write().await;
+        let entry = stats.entry(user_id).or_default();
+        entry.inspected += 1;
+
+        if let Some(finding) = finding {
+            info!(
+                "🥪 Trade {} for user {} sandwiched by {} ({:.4} lost, {:.2}%)",
+                signature, user_id, finding.attacker, finding.loss_quote, finding.loss_pct
+            );
+            entry.sandwiched += 1;
+            entry.total_loss_quote += finding.loss_quote;
+            entry.largest_loss_quote = entry.largest_loss_quote.max(finding.loss_quote);
+            entry.last_sandwiched_at = Some(Utc::now());
+            self.findings.write().await.insert(signature.to_string(), finding);
+        }
+    }
+
+    pub async fn stats(&self, user_id: i64) -> MevStats {
+        self.stats.read().await.get(&user_id).cloned().unwrap_or_default()
+    }
+
+    pub async fn finding(&self, signature: &str) -> Option<SandwichFinding> {
+        self.findings.read().await.get(signature).cloned()
+    }
+
+    /// Whether a finding is worth interrupting the user for
+    pub fn should_notify(&self, finding: &SandwichFinding) -> bool {
+        finding.loss_pct >= self.config.notify_loss_pct
+    }
+
+    /// Plain-text follow-up explaining the attack
+    pub fn notification_message(finding: &SandwichFinding, symbol: &str) -> String {
+        format!(
+            "🥪 Your {} trade was sandwiched\n\n\
+            A bot bought just before your swap and sold right after it in the same block, \
+            pushing your price up and keeping the difference.\n\n\
+            Estimated loss: {:.6} SOL ({:.2}% of a fair fill)\n\
+            Attacker: {}\n\
+            Front-run: https://solscan.io/tx/{}\n\
+            Back-run: https://solscan.io/tx/{}\n\n\
+            To avoid this next time:\n\
+            • Send trades as Jito bundles so they can't be wrapped\n\
+            • Tighten your slippage; a sandwich can only take what slippage allows",
+            symbol,
+            finding.loss_quote,
+            finding.loss_pct,
+            finding.attacker,
+            finding.front_run_signature,
+            finding.back_run_signature,
+        )
+    }
+}
+
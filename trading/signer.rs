@@ -5,13 +5,20 @@ use solana_sdk::{
     signer::keypair::read_keypair_file,
     pubkey::Pubkey,
 };
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{info, warn, error, debug};
 use tokio::sync::RwLock;
 
 use crate::errors::BotError;
-use crate::wallet::WalletManager;
+use crate::wallet::{HardwareWalletManager, LedgerSolanaApp, SecurityPolicies, TransactionReviewMode, WalletManager};
+use super::execution_notices::{ExecutionNotice, ExecutionNotifier, ExecutionSource, NoticeKind};
+
+/// How long a device prompt waits for the user by default
+const DEFAULT_DEVICE_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Security options for transaction signing
 #[derive(Debug, Clone)]
@@ -42,6 +49,8 @@ pub struct SigningRequest {
     pub user_id: String,
     pub wallet_address: String,
     pub estimated_sol_cost: f64,
+    /// SOL the transaction moves, checked against hardware wallet policies
+    pub value_sol: f64,
     pub description: String,
     pub requires_approval: bool,
 }
@@ -57,11 +66,164 @@ pub struct SigningResult {
     pub signing_method: String,
 }
 
+impl SigningResult {
+    fn failed(signing_method: &str, error: String, user_approved: bool) -> Self {
+        Self {
+            signed_transaction: None,
+            signature: None,
+            success: false,
+            error: Some(error),
+            user_approved,
+            signing_method: signing_method.to_string(),
+        }
+    }
+}
+
+/// Where a signing request gets its signature
+#[async_trait::async_trait]
+pub trait SigningStrategy: Send + Sync {
+    /// Recorded as the result's `signing_method`
+    fn method(&self) -> &'static str;
+    async fn sign(&self, request: &SigningRequest) -> Result<SigningResult>;
+}
+
+/// Signs on the user's Ledger after the wallet's policies allow it
+///
+/// Refusals by policy happen before the user is prompted; a rejection on the
+/// device or no answer within the timeout fails the request.
+pub struct LedgerSigning {
+    app: LedgerSolanaApp,
+    policies: SecurityPolicies,
+    notifier: Option<Arc<ExecutionNotifier>>,
+    timeout: Duration,
+}
+
+impl LedgerSigning {
+    pub fn new(app: LedgerSolanaApp, policies: SecurityPolicies) -> Self {
+        Self {
+            app,
+            policies,
+            notifier: None,
+            timeout: DEFAULT_DEVICE_TIMEOUT,
+        }
+    }
+    
+    /// Tell the user to look at their device through the notice forwarder
+    pub fn with_notifier(mut self, notifier: Arc<ExecutionNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+    
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    /// What the prompt shows, by review level
+    fn review_detail(request: &SigningRequest, review: Option<TransactionReviewMode>) -> String {
+        let mut detail = request.description.clone();
+        let Some(review) = review.filter(|mode| *mode != TransactionReviewMode::Minimal) else { return detail };
+        
+        detail.push_str(&format!("\nMoves {:.4} SOL · fee ≈ {:.6} SOL", request.value_sol, request.estimated_sol_cost));
+        if matches!(review, TransactionReviewMode::Detailed | TransactionReviewMode::Expert) {
+            let message = &request.transaction.message;
+            let programs: Vec<String> = message.instructions.iter()
+                .filter_map(|ix| message.account_keys.get(ix.program_id_index as usize))
+                .map(|program| program.to_string())
+                .collect();
+            detail.push_str(&format!("\n{} instructions · programs: {}", programs.len(), programs.join(", ")));
+        }
+        if review == TransactionReviewMode::Expert {
+            detail.push_str(&format!("\nMessage: {}", STANDARD.encode(request.transaction.message_data())));
+        }
+        detail
+    }
+    
+    async fn prompt(&self, request: &SigningRequest, detail: String) -> bool {
+        let (Some(notifier), Ok(user_id)) = (&self.notifier, request.user_id.parse::<i64>()) else {
+            return false;
+        };
+        notifier.notify(ExecutionNotice {
+            user_id,
+            source: ExecutionSource::Manual,
+            label: format!("Signing with {}…", &request.wallet_address[..8.min(request.wallet_address.len())]),
+            strategy_id: None,
+            order_id: None,
+            kind: NoticeKind::DeviceConfirmation { detail },
+            at: Utc::now(),
+        }).await;
+        true
+    }
+}
+
+#[async_trait::async_trait]
+impl SigningStrategy for LedgerSigning {
+    fn method(&self) -> &'static str {
+        "ledger"
+    }
+    
+    async fn sign(&self, request: &SigningRequest) -> Result<SigningResult> {
+        let review = match self.policies.review_for(request.value_sol) {
+            Ok(review) => review,
+            Err(e) => return Ok(SigningResult::failed(self.method(), format!("Refused by wallet policy: {}", e), false)),
+        };
+        
+        // A transaction that needs review can't go to the device unless the user was shown it
+        let prompted = self.prompt(request, Self::review_detail(request, review)).await;
+        if review.is_some() && !prompted {
+            return Ok(SigningResult::failed(
+                self.method(),
+                format!("Over {:.4} SOL needs review, but there's no way to show it to the user", self.policies.review_above_sol.unwrap_or_default()),
+                false,
+            ));
+        }
+        
+        info!("🔐 Waiting up to {}s for Ledger approval from user {}", self.timeout.as_secs(), request.user_id);
+        let signature = match tokio::time::timeout(self.timeout, self.app.sign_transaction(&request.transaction)).await {
+            Ok(Ok(signature)) => signature,
+            Ok(Err(e)) => return Ok(SigningResult::failed(self.method(), e.to_string(), false)),
+            Err(_) => {
+                return Ok(SigningResult::failed(
+                    self.method(),
+                    format!("No answer from the Ledger within {}s", self.timeout.as_secs()),
+                    false,
+                ));
+            }
+        };
+        
+        let mut transaction = request.transaction.clone();
+        let wallet = Pubkey::from_str(&request.wallet_address)?;
+        let signers = transaction.message.header.num_required_signatures as usize;
+        let Some(index) = transaction.message.account_keys[..signers.min(transaction.message.account_keys.len())]
+            .iter()
+            .position(|key| *key == wallet)
+        else {
+            return Ok(SigningResult::failed(self.method(), "The wallet isn't a signer of this transaction".to_string(), true));
+        };
+        transaction.signatures[index] = signature;
+        if !signature.verify(wallet.as_ref(), &transaction.message_data()) {
+            return Ok(SigningResult::failed(self.method(), "The device's signature doesn't match the wallet".to_string(), true));
+        }
+        
+        Ok(SigningResult {
+            signed_transaction: Some(transaction),
+            signature: Some(signature.to_string()),
+            success: true,
+            error: None,
+            user_approved: true,
+            signing_method: self.method().to_string(),
+        })
+    }
+}
+
 /// Secure transaction signer
 pub struct TransactionSigner {
     wallet_manager: Arc<WalletManager>,
     pending_requests: Arc<RwLock<std::collections::HashMap<String, SigningRequest>>>,
     options: SigningOptions,
+    hardware_wallets: Option<Arc<HardwareWalletManager>>,
+    notifier: Option<Arc<ExecutionNotifier>>,
+    device_timeout: Duration,
 }
 
 impl TransactionSigner {
@@ -70,9 +232,47 @@ impl TransactionSigner {
             wallet_manager,
             pending_requests: Arc::new(RwLock::new(std::collections::HashMap::new())),
             options,
+            hardware_wallets: None,
+            notifier: None,
+            device_timeout: DEFAULT_DEVICE_TIMEOUT,
         }
     }
     
+    /// Route wallets held on an attached Ledger to the device
+    pub fn with_hardware_wallets(mut self, hardware_wallets: Arc<HardwareWalletManager>) -> Self {
+        self.hardware_wallets = Some(hardware_wallets);
+        self
+    }
+    
+    /// Where "confirm on your device" prompts are published
+    pub fn with_notifier(mut self, notifier: Arc<ExecutionNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+    
+    pub fn with_device_timeout(mut self, device_timeout: Duration) -> Self {
+        self.device_timeout = device_timeout;
+        self
+    }
+    
+    /// The device strategy and owning user when `wallet_address` is held on an attached Ledger
+    pub async fn device_for(&self, wallet_address: &str) -> Result<Option<(LedgerSigning, i64)>> {
+        let Some(hardware_wallets) = &self.hardware_wallets else { return Ok(None) };
+        let Some((app, owner)) = hardware_wallets.ledger_for(wallet_address).await? else { return Ok(None) };
+        
+        let mut strategy = LedgerSigning::new(app, hardware_wallets.security_policies().await)
+            .with_timeout(self.device_timeout);
+        if let Some(notifier) = &self.notifier {
+            strategy = strategy.with_notifier(notifier.clone());
+        }
+        Ok(Some((strategy, owner)))
+    }
+    
+    /// Sign `request` right away with whatever method its wallet uses
+    pub async fn sign(&self, request: SigningRequest) -> Result<SigningResult> {
+        self.sign_transaction(request).await
+    }
+    
     /// Create a signing request for user approval
     pub async fn create_signing_request(
         &self,
@@ -109,6 +309,7 @@ impl TransactionSigner {
             user_id: user_id.to_string(),
             wallet_address: wallet.public_key.clone(),
             estimated_sol_cost: estimated_cost,
+            value_sol: estimated_cost,
             description,
             requires_approval: self.options.require_confirmation || estimated_cost > 0.1,
         };
@@ -186,7 +387,9 @@ impl TransactionSigner {
     
     /// Get appropriate signing method based on security settings
     async fn get_signing_method(&self, request: &SigningRequest) -> Result<SigningMethod> {
-        if self.options.use_secure_enclave {
+        if self.device_for(&request.wallet_address).await?.is_some() {
+            Ok(SigningMethod::HardwareWallet)
+        } else if self.options.use_secure_enclave {
             Ok(SigningMethod::SecureEnclave)
         } else if self.options.enable_hardware_wallet {
             Ok(SigningMethod::HardwareWallet)
@@ -226,6 +429,10 @@ impl TransactionSigner {
     async fn sign_with_hardware(&self, request: &SigningRequest) -> Result<SigningResult> {
         info!("Using hardware wallet signing for user {}", request.user_id);
         
+        if let Some((strategy, _)) = self.device_for(&request.wallet_address).await? {
+            return strategy.sign(request).await;
+        }
+        
         // In production, this would integrate with hardware wallets:
         // - Ledger: Use ledger-transport and solana-ledger-app
         // - Trezor: Use trezor-connect or similar
@@ -0,0 +1,329 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::api::jupiter_v6::{JupiterV6Client, QuoteRequestV6, SwapMode};
+use crate::errors::Result;
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+/// Consecutive failed ticks after which the sell stops waiting on the book
+const MAX_TICK_FAILURES: usize = 3;
+
+/// Smart sell timing configuration
+#[derive(Debug, Clone)]
+pub struct SmartTimingConfig {
+    /// Longest a sell is ever held back
+    pub window: Duration,
+    pub tick_interval: Duration,
+    /// Sells quoted below this go out immediately
+    pub min_size_sol: f64,
+    /// Share of the window spent only observing, to learn what a good tick looks like
+    pub observe_fraction: f64,
+    pub spread_weight: f64,
+    pub depth_weight: f64,
+    pub momentum_weight: f64,
+}
+
+impl Default for SmartTimingConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::seconds(90),
+            tick_interval: Duration::seconds(3),
+            min_size_sol: 10.0,
+            observe_fraction: 0.3,
+            spread_weight: 0.4,
+            depth_weight: 0.4,
+            momentum_weight: 0.2,
+        }
+    }
+}
+
+impl SmartTimingConfig {
+    /// `SMART_SELL_WINDOW_SECS`, `SMART_SELL_MIN_SOL`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(secs) = std::env::var("SMART_SELL_WINDOW_SECS").ok().and_then(|s| s.parse::<i64>().ok()) {
+            config.window = Duration::seconds(secs.clamp(5, 90));
+        }
+        if let Some(min) = std::env::var("SMART_SELL_MIN_SOL").ok().and_then(|m| m.parse().ok()) {
+            config.min_size_sol = min;
+        }
+        config
+    }
+}
+
+/// One look at the book for the full sell size
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MarketTick {
+    pub at: DateTime<Utc>,
+    /// SOL the whole sell would receive right now
+    pub quote_out_sol: f64,
+    /// Cost of a tiny probe sell, a proxy for the spread
+    pub spread_bps: f64,
+    /// SOL of liquidity on the bid side
+    pub depth_sol: f64,
+}
+
+/// Quotes the pending sell on every tick
+#[async_trait::async_trait]
+pub trait TickSource: Send + Sync {
+    async fn tick(&self, mint: &str, token_amount: f64, slippage_bps: u16) -> Result<MarketTick>;
+}
+
+#[async_trait::async_trait]
+impl TickSource for JupiterV6Client {
+    async fn tick(&self, mint: &str, token_amount: f64, slippage_bps: u16) -> Result<MarketTick> {
+        let request = |amount: f64| QuoteRequestV6 {
+            input_mint: mint.to_string(),
+            output_mint: WSOL_MINT.to_string(),
+            amount: (amount * 1e9) as u64,
+            slippage_bps,
+            swap_mode: Some(SwapMode::ExactIn),
+            dexes: None,
+            exclude_dexes: None,
+            max_accounts: Some(32),
+            quote_mint: None,
+            minimize_slippage: Some(true),
+            only_direct_routes: Some(false),
+        };
+
+        let full = self.get_quote(request(token_amount)).await?;
+        let probe = self.get_quote(request((token_amount / 100.0).max(1e-9))).await?;
+
+        let quote_out_sol = full.out_amount.parse::<f64>().unwrap_or(0.0) / 1e9;
+        let impact = full.price_impact_pct.parse::<f64>().unwrap_or(0.0).abs().max(1e-4);
+        Ok(MarketTick {
+            at: Utc::now(),
+            quote_out_sol,
+            spread_bps: probe.price_impact_pct.parse::<f64>().unwrap_or(0.0).abs() * 10_000.0,
+            // Constant-product pools lose roughly size/depth to impact
+            depth_sol: quote_out_sol / impact,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimingReason {
+    /// A tick beat everything seen while observing
+    BestMoment,
+    /// The window ran out
+    Expiry,
+    /// Too small to be worth waiting for
+    BelowThreshold,
+    /// No quote could be read at window start
+    NoQuotes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimingDecision {
+    Wait,
+    Execute(TimingReason),
+}
+
+/// What the timing window chose, with the quotes it was judged on
+#[derive(Debug, Clone)]
+pub struct TimingOutcome {
+    pub reason: TimingReason,
+    pub start: Option<MarketTick>,
+    pub chosen: Option<MarketTick>,
+    pub ticks_seen: usize,
+}
+
+impl TimingOutcome {
+    /// SOL gained (positive) or lost versus selling at window start, from recorded quotes
+    pub fn savings_sol(&self) -> Option<f64> {
+        Some(self.chosen?.quote_out_sol - self.start?.quote_out_sol)
+    }
+
+    pub fn savings_percent(&self) -> Option<f64> {
+        let start = self.start?.quote_out_sol;
+        if start <= 0.0 {
+            return None;
+        }
+        Some(self.savings_sol()? / start * 100.0)
+    }
+
+    pub fn waited(&self) -> Duration {
+        match (self.start, self.chosen) {
+            (Some(start), Some(chosen)) => chosen.at - start.at,
+            _ => Duration::zero(),
+        }
+    }
+
+    /// Result message line, e.g. "Smart timing: sold after 42s at the best moment, +0.12 SOL (+0.80%) vs immediate"
+    pub fn summary(&self) -> String {
+        let when = match self.reason {
+            TimingReason::BestMoment => format!("sold after {}s at the best moment", self.waited().num_seconds()),
+            TimingReason::Expiry => format!("window expired after {}s", self.waited().num_seconds()),
+            TimingReason::BelowThreshold => return "Smart timing: below size threshold, sold immediately".to_string(),
+            TimingReason::NoQuotes => return "Smart timing: no quotes available, sold immediately".to_string(),
+        };
+        match (self.savings_sol(), self.savings_percent()) {
+            (Some(sol), Some(pct)) => format!("Smart timing: {}, {:+.4} SOL ({:+.2}%) vs immediate", when, sol, pct),
+            _ => format!("Smart timing: {}", when),
+        }
+    }
+}
+
+/// Scores ticks within one sell's timing window
+///
+/// The first part of the window only observes; after that the first tick that
+/// beats every observed one is taken, and the window's end forces execution.
+pub struct TimingSession {
+    config: SmartTimingConfig,
+    ticks: Vec<MarketTick>,
+    best_observed: f64,
+    decided: Option<TimingOutcome>,
+}
+
+impl TimingSession {
+    pub fn new(config: SmartTimingConfig) -> Self {
+        Self {
+            config,
+            ticks: Vec::new(),
+            best_observed: f64::MIN,
+            decided: None,
+        }
+    }
+
+    pub fn start(&self) -> Option<MarketTick> {
+        self.ticks.first().copied()
+    }
+
+    /// Score of the latest tick; higher is a better moment to sell
+    pub fn score(&self, tick: &MarketTick) -> f64 {
+        let spread = 1.0 / (1.0 + tick.spread_bps.max(0.0) / 50.0);
+
+        let start_depth = self.start().map(|s| s.depth_sol).unwrap_or(tick.depth_sol);
+        let depth = if start_depth > 0.0 { (tick.depth_sol / start_depth).min(2.0) / 2.0 } else { 0.5 };
+
+        // Rising quotes favour selling now; ±2% over the last few ticks spans the range
+        let momentum = match self.ticks.iter().rev().take(4).last() {
+            Some(oldest) if oldest.quote_out_sol > 0.0 => {
+                (tick.quote_out_sol - oldest.quote_out_sol) / oldest.quote_out_sol
+            }
+            _ => 0.0,
+        };
+        let momentum = (0.5 + momentum * 25.0).clamp(0.0, 1.0);
+
+        self.config.spread_weight * spread
+            + self.config.depth_weight * depth
+            + self.config.momentum_weight * momentum
+    }
+
+    /// Feed the next tick and decide whether to sell on it
+    pub fn observe(&mut self, tick: MarketTick) -> TimingDecision {
+        if let Some(outcome) = &self.decided {
+            return TimingDecision::Execute(outcome.reason);
+        }
+
+        let score = self.score(&tick);
+        self.ticks.push(tick);
+        let start = self.ticks[0];
+        let elapsed = tick.at - start.at;
+
+        let reason = if self.ticks.len() == 1 && tick.quote_out_sol < self.config.min_size_sol {
+            Some(TimingReason::BelowThreshold)
+        } else if elapsed >= self.config.window {
+            Some(TimingReason::Expiry)
+        } else if elapsed.num_milliseconds() as f64 <= self.config.window.num_milliseconds() as f64 * self.config.observe_fraction {
+            self.best_observed = self.best_observed.max(score);
+            None
+        } else if score > self.best_observed {
+            Some(TimingReason::BestMoment)
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => {
+                debug!("⏱️ Smart sell executing on tick {} ({:?}, score {:.3})", self.ticks.len(), reason, score);
+                self.decided = Some(TimingOutcome {
+                    reason,
+                    start: Some(start),
+                    chosen: Some(tick),
+                    ticks_seen: self.ticks.len(),
+                });
+                TimingDecision::Execute(reason)
+            }
+            None => TimingDecision::Wait,
+        }
+    }
+
+    /// Close the window without a chosen tick; the latest quote stands in for the fill
+    pub fn expire(mut self) -> TimingOutcome {
+        if let Some(outcome) = self.decided.take() {
+            return outcome;
+        }
+        TimingOutcome {
+            reason: if self.ticks.is_empty() { TimingReason::NoQuotes } else { TimingReason::Expiry },
+            start: self.ticks.first().copied(),
+            chosen: self.ticks.last().copied(),
+            ticks_seen: self.ticks.len(),
+        }
+    }
+
+    pub fn outcome(&self) -> Option<&TimingOutcome> {
+        self.decided.as_ref()
+    }
+}
+
+/// Holds a large sell back for a short window and picks the moment to send it
+pub struct SmartSellTimer {
+    config: SmartTimingConfig,
+    source: Arc<dyn TickSource>,
+}
+
+impl SmartSellTimer {
+    pub fn new(config: SmartTimingConfig, source: Arc<dyn TickSource>) -> Self {
+        Self { config, source }
+    }
+
+    pub fn config(&self) -> &SmartTimingConfig {
+        &self.config
+    }
+
+    /// Wait for the best moment to sell `token_amount` of `mint`, never past the window
+    ///
+    /// The caller executes right after this returns, with a fresh quote at the
+    /// user's slippage, so recorded tick quotes are never executed stale.
+    pub async fn wait_for_moment(&self, mint: &str, token_amount: f64, slippage_bps: u16) -> TimingOutcome {
+        let window = self.config.window.to_std().unwrap_or_default();
+        let interval = self.config.tick_interval.to_std().unwrap_or_default();
+        let deadline = tokio::time::Instant::now() + window;
+        let mut session = TimingSession::new(self.config.clone());
+        let mut failures = 0;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+
+            match tokio::time::timeout(remaining, self.source.tick(mint, token_amount, slippage_bps)).await {
+                Ok(Ok(tick)) => {
+                    failures = 0;
+                    if let TimingDecision::Execute(reason) = session.observe(tick) {
+                        info!("⏱️ Smart sell of {} released: {:?}", mint, reason);
+                        break;
+                    }
+                }
+                Ok(Err(e)) => {
+                    warn!("⏱️ Smart sell tick failed for {}: {}", mint, e);
+                    failures += 1;
+                    // Without a starting quote there is nothing to time against
+                    if session.start().is_none() || failures >= MAX_TICK_FAILURES {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            tokio::time::sleep(interval.min(remaining)).await;
+        }
+
+        session.expire()
+    }
+}
@@ -12,8 +12,9 @@ use tokio::sync::RwLock;
 use tracing::{info, debug, warn, error};
 use chrono::Utc;
 
-use crate::errors::BotError;
-use crate::wallet::WalletManager;
+use crate::errors::{BotError, TradingError};
+use crate::wallet::{TransactionPriority, WalletManager};
+use crate::trading::priority_fees::PriorityFeeEstimator;
 use crate::trading::signer::{TransactionSigner, SigningOptions};
 
 /// Jupiter swap client for executing real trades
@@ -24,6 +25,7 @@ pub struct JupiterSwapClient {
     wallet_manager: Arc<WalletManager>,
     swap_cache: Arc<RwLock<SwapCache>>,
     transaction_signer: Arc<TransactionSigner>,
+    fee_estimator: Option<Arc<PriorityFeeEstimator>>,
 }
 
 /// Swap request parameters
@@ -175,9 +177,16 @@ impl JupiterSwapClient {
                 rate_limit_tracker: std::collections::HashMap::new(),
             })),
             transaction_signer,
+            fee_estimator: None,
         }
     }
     
+    /// Price swaps from recent prioritization fees instead of a fixed fee
+    pub fn with_fee_estimator(mut self, fee_estimator: Arc<PriorityFeeEstimator>) -> Self {
+        self.fee_estimator = Some(fee_estimator);
+        self
+    }
+    
     /// Get a quote for a swap
     pub async fn get_quote(&self, request: &SwapRequest) -> Result<JupiterQuote> {
         info!("Getting quote for {} {} -> {}", 
@@ -219,7 +228,10 @@ impl JupiterSwapClient {
         if !response.status().is_success() {
             let error_text = response.text().await?;
             error!("Jupiter quote failed: {}", error_text);
-            return Err(BotError::trading(format!("Quote failed: {}", error_text)).into());
+            if error_text.contains("COULD_NOT_FIND_ANY_ROUTE") || error_text.contains("NO_ROUTE") {
+                return Err(BotError::from(TradingError::NoRoute).into());
+            }
+            return Err(BotError::jupiter_api(format!("Quote failed: {}", error_text)).into());
         }
         
         let quote: JupiterQuote = response.json().await?;
@@ -315,14 +327,22 @@ impl JupiterSwapClient {
         quote: &JupiterQuote,
         user_public_key: &str,
     ) -> Result<SwapInstructionResponse> {
+        // An estimated unit price replaces the fixed total fee, which Jupiter would otherwise prefer
+        let (compute_unit_price_micro_lamports, prioritization_fee_lamports) = match &self.fee_estimator {
+            Some(estimator) => (Some(estimator.estimate(TransactionPriority::Normal).await), None),
+            None => (
+                Some(1000), // 0.001 SOL per compute unit
+                Some(10000), // 0.00001 SOL priority fee
+            ),
+        };
         let request = SwapInstructionRequest {
             quote_response: quote.clone(),
             user_public_key: user_public_key.to_string(),
             wrap_and_unwrap_sol: true,
             use_shared_accounts: true,
             fee_account: None,
-            compute_unit_price_micro_lamports: Some(1000), // 0.001 SOL per compute unit
-            prioritization_fee_lamports: Some(10000), // 0.00001 SOL priority fee
+            compute_unit_price_micro_lamports,
+            prioritization_fee_lamports,
         };
         
         let response = self.client
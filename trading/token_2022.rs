@@ -12,6 +12,12 @@ use crate::errors::{BotError, Result};
 // Token-2022 Program ID
 pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
 
+// Mint extension layout: base mint padded to the account size, then the account type byte and TLV entries
+const EXTENSIONS_OFFSET: usize = 165;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const TRANSFER_FEE_CONFIG_LEN: usize = 108;
+
 /// Token-2022 Extension Types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ExtensionType {
@@ -49,13 +55,32 @@ pub struct TransferFeeConfig {
     pub newer_transfer_fee: TransferFee,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TransferFee {
     pub epoch: u64,
     pub maximum_fee: u64,
     pub transfer_fee_basis_points: u16,
 }
 
+impl TransferFee {
+    /// Fee withheld from a transfer of `amount` raw units, capped at `maximum_fee`
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        let fee = (amount as u128 * self.transfer_fee_basis_points as u128).div_ceil(10000);
+        std::cmp::min(fee, self.maximum_fee as u128) as u64
+    }
+}
+
+impl TransferFeeConfig {
+    /// The fee in force at `epoch`; the newer fee takes over from its epoch onward
+    pub fn fee_at(&self, epoch: u64) -> &TransferFee {
+        if epoch >= self.newer_transfer_fee.epoch {
+            &self.newer_transfer_fee
+        } else {
+            &self.older_transfer_fee
+        }
+    }
+}
+
 /// Interest Bearing Configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterestBearingConfig {
@@ -141,17 +166,7 @@ impl Token2022Manager {
         transfer_fee_config: &TransferFeeConfig,
     ) -> Result<u64> {
         let current_epoch = 0; // In real implementation, get current epoch
-        
-        // Use newer or older transfer fee based on epoch
-        let transfer_fee = if current_epoch >= transfer_fee_config.newer_transfer_fee.epoch {
-            &transfer_fee_config.newer_transfer_fee
-        } else {
-            &transfer_fee_config.older_transfer_fee
-        };
-        
-        // Calculate fee: (amount * basis_points) / 10000
-        let fee = (amount as u128 * transfer_fee.transfer_fee_basis_points as u128) / 10000;
-        let fee = std::cmp::min(fee as u64, transfer_fee.maximum_fee);
+        let fee = transfer_fee_config.fee_at(current_epoch).fee_for(amount);
         
         debug!("Calculated transfer fee: {} lamports for amount: {}", fee, amount);
         Ok(fee)
@@ -219,6 +234,61 @@ impl Token2022Manager {
         })
     }
     
+    /// Read the TransferFeeConfig extension from raw Token-2022 mint account data
+    ///
+    /// `None` for mints without the extension, including plain SPL mints.
+    pub fn parse_mint_transfer_fee(&self, mint_data: &[u8]) -> Result<Option<TransferFeeConfig>> {
+        if mint_data.len() <= EXTENSIONS_OFFSET {
+            return Ok(None);
+        }
+        if mint_data[EXTENSIONS_OFFSET] != ACCOUNT_TYPE_MINT {
+            return Err(BotError::validation("Account is not a Token-2022 mint".to_string()));
+        }
+        
+        let mut offset = EXTENSIONS_OFFSET + 1;
+        while offset + 4 <= mint_data.len() {
+            let extension = u16::from_le_bytes([mint_data[offset], mint_data[offset + 1]]);
+            let len = u16::from_le_bytes([mint_data[offset + 2], mint_data[offset + 3]]) as usize;
+            let value = mint_data.get(offset + 4..offset + 4 + len)
+                .ok_or_else(|| BotError::validation("Truncated Token-2022 extension data".to_string()))?;
+            
+            if extension == EXTENSION_TRANSFER_FEE_CONFIG {
+                if len != TRANSFER_FEE_CONFIG_LEN {
+                    return Err(BotError::validation(format!("TransferFeeConfig has length {}", len)));
+                }
+                return Ok(Some(Self::read_transfer_fee_config(value)));
+            }
+            // Uninitialized entries pad out the rest of the account
+            if extension == 0 {
+                break;
+            }
+            offset += 4 + len;
+        }
+        
+        Ok(None)
+    }
+    
+    fn read_transfer_fee_config(value: &[u8]) -> TransferFeeConfig {
+        let pubkey = |at: usize| {
+            let key = Pubkey::new_from_array(value[at..at + 32].try_into().expect("32 bytes"));
+            (key != Pubkey::default()).then_some(key)
+        };
+        let u64_at = |at: usize| u64::from_le_bytes(value[at..at + 8].try_into().expect("8 bytes"));
+        let fee_at = |at: usize| TransferFee {
+            epoch: u64_at(at),
+            maximum_fee: u64_at(at + 8),
+            transfer_fee_basis_points: u16::from_le_bytes([value[at + 16], value[at + 17]]),
+        };
+        
+        TransferFeeConfig {
+            transfer_fee_config_authority: pubkey(0),
+            withdraw_withheld_authority: pubkey(32),
+            withheld_amount: u64_at(64),
+            older_transfer_fee: fee_at(72),
+            newer_transfer_fee: fee_at(90),
+        }
+    }
+    
     /// Create a new Token-2022 mint with specified extensions
     pub fn create_token_2022_mint_instruction(
         &self,
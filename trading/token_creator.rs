@@ -81,7 +81,7 @@ pub struct RevenueStream {
 }
 
 /// Token creation presets for common use cases
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TokenPreset {
     Basic,           // Simple token, no extensions
     CreatorToken,    // With transfer fees and metadata
@@ -91,6 +91,56 @@ pub enum TokenPreset {
     StakingToken,    // Interest bearing with rewards
 }
 
+impl TokenPreset {
+    pub const ALL: [TokenPreset; 6] = [
+        TokenPreset::Basic,
+        TokenPreset::CreatorToken,
+        TokenPreset::CommunityToken,
+        TokenPreset::UtilityToken,
+        TokenPreset::MemeToken,
+        TokenPreset::StakingToken,
+    ];
+
+    /// Stable short name, e.g. for callback data
+    pub fn key(&self) -> &'static str {
+        match self {
+            TokenPreset::Basic => "basic",
+            TokenPreset::CreatorToken => "creator",
+            TokenPreset::CommunityToken => "community",
+            TokenPreset::UtilityToken => "utility",
+            TokenPreset::MemeToken => "meme",
+            TokenPreset::StakingToken => "staking",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.key() == key)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TokenPreset::Basic => "Basic",
+            TokenPreset::CreatorToken => "Creator",
+            TokenPreset::CommunityToken => "Community",
+            TokenPreset::UtilityToken => "Utility",
+            TokenPreset::MemeToken => "Meme",
+            TokenPreset::StakingToken => "Staking",
+        }
+    }
+}
+
+/// What the launcher chose on top of a preset
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenDetails {
+    pub name: String,
+    pub symbol: String,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    /// Replaces the preset's supply when set
+    pub initial_supply: Option<u64>,
+    pub additional_metadata: HashMap<String, String>,
+}
+
 /// Advanced Token-2022 Creator Interface
 pub struct TokenCreator {
     token_2022_manager: Token2022Manager,
@@ -180,6 +230,33 @@ impl TokenCreator {
         Ok(result)
     }
     
+    /// Create a token from a preset with the launcher's details on top
+    pub async fn create(
+        &self,
+        preset: TokenPreset,
+        details: TokenDetails,
+        payer: &Keypair,
+    ) -> Result<TokenCreationResult> {
+        let config = self.config_for(preset, details, payer.pubkey())?;
+        self.create_token(config, payer).await
+    }
+    
+    /// The preset's configuration carrying the launcher's details, validated
+    pub fn config_for(&self, preset: TokenPreset, details: TokenDetails, creator: Pubkey) -> Result<TokenCreationConfig> {
+        let mut config = self.get_preset(preset)
+            .ok_or_else(|| BotError::validation(format!("The {} preset isn't available", preset.label())))?;
+        config.name = details.name;
+        config.symbol = details.symbol;
+        config.description = details.description.or(config.description);
+        config.image_url = details.image_url;
+        config.initial_supply = details.initial_supply.unwrap_or(config.initial_supply);
+        config.additional_metadata.extend(details.additional_metadata);
+        config.creator_address = creator;
+        
+        self.validate_config(&config)?;
+        Ok(config)
+    }
+    
     /// Get preset configuration for common token types
     pub fn get_preset(&self, preset: TokenPreset) -> Option<TokenCreationConfig> {
         self.presets.get(&preset).cloned()
@@ -1,10 +1,261 @@
 use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use solana_sdk::pubkey::Pubkey;
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use crate::api::jupiter_token_v2::{JupiterTokenV2Client, SortBy, SortOrder, TokenDataV2, TokenSearchRequest};
 use crate::constants::KNOWN_TOKENS;
 use crate::errors::{TradingError, BotError};
+use crate::utils::formatting::{format_address, format_volume};
 
-pub struct TokenResolver;
+/// Fuzzy lookups offer at most this many tokens
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Resolves what users type into mint addresses
+///
+/// The associated functions only know the built-in tokens. An instance also
+/// indexes the Jupiter token list, kept fresh by `start`.
+pub struct TokenResolver {
+    source: Arc<dyn TokenListSource>,
+    config: TokenListConfig,
+    index: RwLock<TokenIndex>,
+}
+
+/// Where the token list comes from
+#[async_trait]
+pub trait TokenListSource: Send + Sync {
+    /// One page of tokens, most traded first
+    async fn fetch_page(&self, page: u32, page_size: u32) -> Result<Vec<TokenDataV2>>;
+}
+
+#[async_trait]
+impl TokenListSource for JupiterTokenV2Client {
+    async fn fetch_page(&self, page: u32, page_size: u32) -> Result<Vec<TokenDataV2>> {
+        let response = self.get_tokens(Some(TokenSearchRequest {
+            query: None,
+            tags: None,
+            verified_only: None,
+            min_daily_volume: None,
+            min_liquidity: None,
+            min_organic_score: None,
+            risk_level: None,
+            sort_by: Some(SortBy::DailyVolume),
+            order: Some(SortOrder::Desc),
+            page: Some(page),
+            page_size: Some(page_size),
+        })).await?;
+        Ok(response.tokens)
+    }
+}
+
+/// Settings for loading the token list
+#[derive(Debug, Clone)]
+pub struct TokenListConfig {
+    pub refresh_interval: Duration,
+    pub page_size: u32,
+    /// Pages fetched per refresh; tokens past these are left to mint lookups
+    pub max_pages: u32,
+}
+
+impl Default for TokenListConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(6 * 60 * 60),
+            page_size: 1000,
+            max_pages: 5,
+        }
+    }
+}
+
+/// A token offered by a lookup, with what users need to tell copycats apart
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenCandidate {
+    pub mint: String,
+    pub symbol: String,
+    pub name: String,
+    pub verified: bool,
+    /// On Jupiter's strict list
+    pub strict: bool,
+    /// USD traded in the last day
+    pub daily_volume: Option<u64>,
+    pub market_cap: Option<u64>,
+}
+
+impl TokenCandidate {
+    /// Stand-in for a built-in token or a mint the list doesn't have
+    fn bare(mint: &str, symbol: String) -> Self {
+        Self {
+            mint: mint.to_string(),
+            name: symbol.clone(),
+            symbol,
+            verified: false,
+            strict: false,
+            daily_volume: None,
+            market_cap: None,
+        }
+    }
+
+    /// `BONK (Bonk) ✅ · $12.3M vol · $1.20B mcap · DezX...B263`
+    pub fn label(&self) -> String {
+        let badge = if self.strict {
+            " ✅"
+        } else if self.verified {
+            " ☑️"
+        } else {
+            " ⚠️"
+        };
+        let mut label = format!("{} ({}){}", self.symbol, self.name, badge);
+        if let Some(volume) = self.daily_volume {
+            label.push_str(&format!(" · ${} vol", format_volume(volume as f64)));
+        }
+        if let Some(market_cap) = self.market_cap {
+            label.push_str(&format!(" · ${} mcap", format_volume(market_cap as f64)));
+        }
+        label.push_str(&format!(" · {}", format_address(&self.mint)));
+        label
+    }
+
+    /// Strict-list tokens first, then verified, then the most traded
+    fn rank(&self) -> (Reverse<bool>, Reverse<bool>, Reverse<u64>, Reverse<u64>) {
+        (
+            Reverse(self.strict),
+            Reverse(self.verified),
+            Reverse(self.daily_volume.unwrap_or(0)),
+            Reverse(self.market_cap.unwrap_or(0)),
+        )
+    }
+}
+
+impl From<&TokenDataV2> for TokenCandidate {
+    fn from(token: &TokenDataV2) -> Self {
+        Self {
+            mint: token.address.clone(),
+            symbol: token.symbol.clone(),
+            name: token.name.clone(),
+            verified: token.verified,
+            strict: token.strict_list.unwrap_or(false),
+            daily_volume: token.daily_volume,
+            market_cap: token.market_cap,
+        }
+    }
+}
+
+/// Outcome of resolving what a user typed
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenLookup {
+    Found(TokenCandidate),
+    /// Several tokens share the symbol or name; the user has to pick
+    Ambiguous(Vec<TokenCandidate>),
+    /// No exact match; close symbols and names, best first
+    Suggestions(Vec<TokenCandidate>),
+    NotFound,
+}
+
+impl TokenLookup {
+    /// Plain-text prompt listing the tokens to choose from, each with the command that picks it
+    ///
+    /// `None` unless the lookup needs the user to choose.
+    pub fn prompt(&self, query: &str, command: &str) -> Option<String> {
+        let (header, candidates) = match self {
+            TokenLookup::Ambiguous(candidates) => (
+                format!("🔎 {} tokens match \"{}\". Verified ones are listed first:", candidates.len(), query),
+                candidates,
+            ),
+            TokenLookup::Suggestions(candidates) => (format!("🔎 No token called \"{}\". Did you mean:", query), candidates),
+            TokenLookup::Found(_) | TokenLookup::NotFound => return None,
+        };
+        let mut prompt = header;
+        for (i, candidate) in candidates.iter().enumerate() {
+            prompt.push_str(&format!("\n\n{}. {}\n{} {}", i + 1, candidate.label(), command, candidate.mint));
+        }
+        if candidates.iter().any(|candidate| !candidate.verified && !candidate.strict) {
+            prompt.push_str("\n\n⚠️ Unverified tokens are often copycats. Check the mint before buying.");
+        }
+        Some(prompt)
+    }
+}
+
+#[derive(Default)]
+struct TokenIndex {
+    tokens: Vec<TokenCandidate>,
+    by_mint: HashMap<String, usize>,
+    by_symbol: HashMap<String, Vec<usize>>,
+    by_name: HashMap<String, Vec<usize>>,
+    refreshed_at: Option<DateTime<Utc>>,
+}
+
+impl TokenIndex {
+    fn build(tokens: Vec<TokenCandidate>, refreshed_at: DateTime<Utc>) -> Self {
+        let mut index = Self { refreshed_at: Some(refreshed_at), ..Self::default() };
+        for token in tokens {
+            if index.by_mint.contains_key(&token.mint) {
+                continue;
+            }
+            let position = index.tokens.len();
+            index.by_mint.insert(token.mint.clone(), position);
+            index.by_symbol.entry(token.symbol.to_uppercase()).or_default().push(position);
+            index.by_name.entry(token.name.to_uppercase()).or_default().push(position);
+            index.tokens.push(token);
+        }
+        index
+    }
+
+    fn matches(&self, positions: Option<&Vec<usize>>) -> Vec<TokenCandidate> {
+        let mut matches: Vec<TokenCandidate> = positions.into_iter()
+            .flatten()
+            .map(|position| self.tokens[*position].clone())
+            .collect();
+        matches.sort_by_key(TokenCandidate::rank);
+        matches
+    }
+
+    /// Prefix matches on symbol or name, then symbols within a typo or two
+    fn fuzzy(&self, query: &str) -> Vec<TokenCandidate> {
+        let length = query.chars().count();
+        if length < 2 {
+            return Vec::new();
+        }
+        let max_distance = match length {
+            2 => 0,
+            3..=5 => 1,
+            _ => 2,
+        };
+        let mut scored: Vec<(usize, &TokenCandidate)> = self.tokens.iter()
+            .filter_map(|token| {
+                let symbol = token.symbol.to_uppercase();
+                let name = token.name.to_uppercase();
+                if symbol.starts_with(query) || name.starts_with(query) {
+                    return Some((0, token));
+                }
+                let distance = edit_distance(&symbol, query);
+                (distance <= max_distance).then_some((distance, token))
+            })
+            .collect();
+        scored.sort_by_key(|(distance, token)| (*distance, token.rank()));
+        scored.into_iter().take(MAX_SUGGESTIONS).map(|(_, token)| token.clone()).collect()
+    }
+}
+
+/// Levenshtein distance over characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
 
 impl TokenResolver {
     /// Resolve token symbol or mint address to a valid mint address
@@ -51,4 +302,99 @@ impl TokenResolver {
             .map(|(symbol, mint)| (symbol.to_string(), mint.to_string()))
             .collect()
     }
-}
\ No newline at end of file
+}
+
+impl TokenResolver {
+    pub fn new(source: Arc<dyn TokenListSource>, config: TokenListConfig) -> Self {
+        Self {
+            source,
+            config,
+            index: RwLock::new(TokenIndex::default()),
+        }
+    }
+
+    /// Resolve a mint address, symbol or name against the token list
+    ///
+    /// Exact symbol matches win over exact names, which win over fuzzy matches.
+    /// Built-in tokens cover symbols the list doesn't have, including before
+    /// the first load.
+    pub async fn lookup(&self, query: &str) -> TokenLookup {
+        let query = query.trim();
+        let index = self.index.read().await;
+
+        if (32..=44).contains(&query.len()) && Pubkey::from_str(query).is_ok() {
+            let candidate = index.by_mint.get(query)
+                .map(|position| index.tokens[*position].clone())
+                .unwrap_or_else(|| TokenCandidate::bare(query, Self::get_symbol(query)));
+            return TokenLookup::Found(candidate);
+        }
+
+        let upper = query.to_uppercase();
+        let mut by_symbol = index.matches(index.by_symbol.get(&upper));
+        match by_symbol.len() {
+            0 => {}
+            1 => return TokenLookup::Found(by_symbol.remove(0)),
+            _ => return TokenLookup::Ambiguous(by_symbol),
+        }
+
+        if let Some((symbol, mint)) = KNOWN_TOKENS.iter().find(|(symbol, _)| *symbol == upper) {
+            return TokenLookup::Found(TokenCandidate::bare(mint, symbol.to_string()));
+        }
+
+        let mut by_name = index.matches(index.by_name.get(&upper));
+        match by_name.len() {
+            0 => {}
+            1 => return TokenLookup::Found(by_name.remove(0)),
+            _ => return TokenLookup::Ambiguous(by_name),
+        }
+
+        let suggestions = index.fuzzy(&upper);
+        if suggestions.is_empty() {
+            TokenLookup::NotFound
+        } else {
+            TokenLookup::Suggestions(suggestions)
+        }
+    }
+
+    /// Reload the token list; on failure the previous list stays in use
+    pub async fn refresh(&self) -> Result<usize> {
+        let mut tokens = Vec::new();
+        for page in 1..=self.config.max_pages.max(1) {
+            let batch = self.source.fetch_page(page, self.config.page_size).await?;
+            let last_page = (batch.len() as u32) < self.config.page_size;
+            tokens.extend(batch.iter().map(TokenCandidate::from));
+            if last_page {
+                break;
+            }
+        }
+
+        let index = TokenIndex::build(tokens, Utc::now());
+        let count = index.tokens.len();
+        *self.index.write().await = index;
+        Ok(count)
+    }
+
+    /// When the token list was last loaded; `None` before the first load
+    pub async fn refreshed_at(&self) -> Option<DateTime<Utc>> {
+        self.index.read().await.refreshed_at
+    }
+
+    pub async fn token_count(&self) -> usize {
+        self.index.read().await.tokens.len()
+    }
+
+    /// Load the token list now and then on every refresh interval
+    pub async fn start(self: &Arc<Self>) {
+        let resolver = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(resolver.config.refresh_interval);
+            loop {
+                interval.tick().await;
+                match resolver.refresh().await {
+                    Ok(count) => info!("🪙 Token list refreshed: {} tokens", count),
+                    Err(e) => warn!("🪙 Token list refresh failed, keeping the previous list: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,341 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::errors::{BotError, Result};
+use crate::security::LarpChecker;
+use super::token_2022::Token2022Manager;
+
+const DEXSCREENER_TOKENS_URL: &str = "https://api.dexscreener.com/latest/dex/tokens";
+/// Mints one user may deny
+pub const MAX_DENIED_MINTS: usize = 100;
+
+/// A user's own limits on which tokens they may buy; every rule is off by default
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeRules {
+    /// Mints that are never bought
+    #[serde(default)]
+    pub denied_mints: Vec<String>,
+    /// Liquidity of the token's deepest pool, in USD
+    #[serde(default)]
+    pub min_liquidity_usd: Option<f64>,
+    /// Highest buy or sell transfer tax tolerated, in percent
+    #[serde(default)]
+    pub max_tax_percent: Option<f64>,
+    /// Lowest LARP safety score (0-100, higher is safer) tolerated
+    #[serde(default)]
+    pub min_larp_score: Option<u8>,
+}
+
+impl TradeRules {
+    /// No rule is on, so nothing needs checking
+    pub fn is_empty(&self) -> bool {
+        self.denied_mints.is_empty()
+            && self.min_liquidity_usd.is_none()
+            && self.max_tax_percent.is_none()
+            && self.min_larp_score.is_none()
+    }
+
+    pub fn is_denied(&self, mint: &str) -> bool {
+        self.denied_mints.iter().any(|denied| denied == mint)
+    }
+
+    /// Add `mint` to the deny-list; false when it was already there
+    pub fn deny(&mut self, mint: &str) -> Result<bool> {
+        Pubkey::from_str(mint).map_err(|_| BotError::validation(format!("{} is not a token mint", mint)))?;
+        if self.is_denied(mint) {
+            return Ok(false);
+        }
+        if self.denied_mints.len() >= MAX_DENIED_MINTS {
+            return Err(BotError::validation(format!("The deny-list holds at most {} mints", MAX_DENIED_MINTS)).into());
+        }
+        self.denied_mints.push(mint.to_string());
+        Ok(true)
+    }
+
+    /// Take `mint` off the deny-list; false when it wasn't on it
+    pub fn allow(&mut self, mint: &str) -> bool {
+        let before = self.denied_mints.len();
+        self.denied_mints.retain(|denied| denied != mint);
+        self.denied_mints.len() != before
+    }
+}
+
+/// Transfer tax a token takes on each side of a trade, in percent
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TokenTax {
+    pub buy_percent: f64,
+    pub sell_percent: f64,
+}
+
+impl TokenTax {
+    /// The side that costs more
+    pub fn highest(&self) -> f64 {
+        self.buy_percent.max(self.sell_percent)
+    }
+}
+
+/// One of the user's rules refusing a buy
+///
+/// A rule whose data couldn't be fetched refuses too, with no value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockedRule {
+    Denied,
+    MinLiquidity { liquidity_usd: Option<f64>, min_usd: f64 },
+    MaxTax { tax: Option<TokenTax>, max_percent: f64 },
+    MinLarpScore { score: Option<u8>, min_score: u8 },
+}
+
+impl fmt::Display for BlockedRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Denied => write!(f, "🚫 The token is on your deny-list"),
+            Self::MinLiquidity { liquidity_usd: Some(liquidity), min_usd } => {
+                write!(f, "💧 Liquidity ${:.0} is under your ${:.0} minimum", liquidity, min_usd)
+            }
+            Self::MinLiquidity { liquidity_usd: None, min_usd } => {
+                write!(f, "💧 Liquidity couldn't be checked against your ${:.0} minimum", min_usd)
+            }
+            Self::MaxTax { tax: Some(tax), max_percent } => write!(
+                f, "🧾 Transfer tax {}% buy / {}% sell is over your {}% maximum",
+                tax.buy_percent, tax.sell_percent, max_percent
+            ),
+            Self::MaxTax { tax: None, max_percent } => {
+                write!(f, "🧾 Transfer tax couldn't be checked against your {}% maximum", max_percent)
+            }
+            Self::MinLarpScore { score: Some(score), min_score } => {
+                write!(f, "🕵️ LARP safety score {}/100 is under your minimum of {}", score, min_score)
+            }
+            Self::MinLarpScore { score: None, min_score } => {
+                write!(f, "🕵️ LARP safety score couldn't be checked against your minimum of {}", min_score)
+            }
+        }
+    }
+}
+
+/// Every rule that refused a buy of `mint`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeBlocked {
+    pub mint: String,
+    pub rules: Vec<BlockedRule>,
+}
+
+impl fmt::Display for TradeBlocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Buy of {} blocked by your token rules:", self.mint)?;
+        for rule in &self.rules {
+            write!(f, "\n• {}", rule)?;
+        }
+        write!(f, "\nChange them in /settings → Token rules")
+    }
+}
+
+/// Source of each user's token rules
+#[async_trait::async_trait]
+pub trait TradeRulePreferences: Send + Sync {
+    async fn trade_rules(&self, user_id: i64) -> TradeRules;
+}
+
+/// Which user a wallet belongs to, for trades that only carry the wallet
+#[async_trait::async_trait]
+pub trait WalletOwners: Send + Sync {
+    /// `Ok(None)` only when the wallet is known to have no owner
+    async fn owner_of(&self, wallet_address: &str) -> Result<Option<i64>>;
+}
+
+/// Market and safety facts the rules are checked against; mocked in tests
+#[async_trait::async_trait]
+pub trait TokenInspector: Send + Sync {
+    /// Liquidity of the token's deepest pool, in USD
+    async fn liquidity_usd(&self, mint: &str) -> Result<f64>;
+    async fn tax(&self, mint: &str) -> Result<TokenTax>;
+    async fn larp_score(&self, mint: &str) -> Result<u8>;
+}
+
+#[derive(Deserialize)]
+struct DexScreenerTokens {
+    pairs: Option<Vec<DexScreenerPair>>,
+}
+
+#[derive(Deserialize)]
+struct DexScreenerPair {
+    liquidity: Option<DexScreenerLiquidity>,
+}
+
+#[derive(Deserialize)]
+struct DexScreenerLiquidity {
+    usd: Option<f64>,
+}
+
+/// DexScreener pool liquidity, Token-2022 transfer fees and the LARP checker
+pub struct LiveTokenInspector {
+    client: reqwest::Client,
+    token_2022: Token2022Manager,
+    larp: Arc<LarpChecker>,
+}
+
+impl LiveTokenInspector {
+    pub fn new(larp: Arc<LarpChecker>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            token_2022: Token2022Manager::new(),
+            larp,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenInspector for LiveTokenInspector {
+    async fn liquidity_usd(&self, mint: &str) -> Result<f64> {
+        let response = self.client
+            .get(format!("{}/{}", DEXSCREENER_TOKENS_URL, mint))
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| BotError::external_api(format!("DexScreener unavailable: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(BotError::external_api(format!("DexScreener returned {}", response.status())).into());
+        }
+        let tokens: DexScreenerTokens = response.json().await
+            .map_err(|e| BotError::parsing(format!("Unreadable DexScreener response: {}", e)))?;
+
+        // A token with no pools has no liquidity at all
+        Ok(tokens.pairs.unwrap_or_default()
+            .iter()
+            .filter_map(|pair| pair.liquidity.as_ref().and_then(|l| l.usd))
+            .fold(0.0, f64::max))
+    }
+
+    async fn tax(&self, mint: &str) -> Result<TokenTax> {
+        let info = self.token_2022.get_token_info(&Pubkey::from_str(mint)?).await?;
+        // A Token-2022 transfer fee is withheld both when the pool sends tokens and when it receives them
+        let percent = info.transfer_fee_config
+            .map_or(0.0, |config| config.newer_transfer_fee.transfer_fee_basis_points as f64 / 100.0);
+        Ok(TokenTax { buy_percent: percent, sell_percent: percent })
+    }
+
+    async fn larp_score(&self, mint: &str) -> Result<u8> {
+        let analysis = self.larp.analyze_token(mint).await
+            .map_err(|e| BotError::external_api(format!("LARP check failed: {}", e)))?;
+        Ok(analysis.risk_score)
+    }
+}
+
+/// Checks a buy against the buyer's token rules before it is quoted
+///
+/// Snipes, quick buys and /buy are checked in the trading engine; copy trading
+/// and DCA check the same gate before they trade. Sells are never blocked, so
+/// a position can always be exited.
+pub struct TradeGate {
+    rules: Arc<dyn TradeRulePreferences>,
+    inspector: Arc<dyn TokenInspector>,
+    owners: Option<Arc<dyn WalletOwners>>,
+}
+
+impl TradeGate {
+    pub fn new(rules: Arc<dyn TradeRulePreferences>, inspector: Arc<dyn TokenInspector>) -> Self {
+        Self {
+            rules,
+            inspector,
+            owners: None,
+        }
+    }
+
+    /// Find the owner of wallets buying through the engine; without it those buys aren't checked
+    pub fn with_owners(mut self, owners: Arc<dyn WalletOwners>) -> Self {
+        self.owners = Some(owners);
+        self
+    }
+
+    /// Every rule of `user_id`'s that refuses a buy of `mint`, or `None` when it may go ahead
+    ///
+    /// Only the data enabled rules need is fetched, all at once.
+    pub async fn evaluate(&self, user_id: i64, mint: &str) -> Option<TradeBlocked> {
+        let rules = self.rules.trade_rules(user_id).await;
+        if rules.is_empty() {
+            return None;
+        }
+
+        let (liquidity, tax, larp) = tokio::join!(
+            async {
+                match rules.min_liquidity_usd {
+                    Some(_) => Some(self.inspector.liquidity_usd(mint).await),
+                    None => None,
+                }
+            },
+            async {
+                match rules.max_tax_percent {
+                    Some(_) => Some(self.inspector.tax(mint).await),
+                    None => None,
+                }
+            },
+            async {
+                match rules.min_larp_score {
+                    Some(_) => Some(self.inspector.larp_score(mint).await),
+                    None => None,
+                }
+            },
+        );
+
+        let mut blocked = Vec::new();
+        if rules.is_denied(mint) {
+            blocked.push(BlockedRule::Denied);
+        }
+        if let (Some(min_usd), Some(liquidity)) = (rules.min_liquidity_usd, liquidity) {
+            let liquidity_usd = Self::fetched("liquidity", mint, liquidity);
+            if liquidity_usd.map_or(true, |liquidity| liquidity < min_usd) {
+                blocked.push(BlockedRule::MinLiquidity { liquidity_usd, min_usd });
+            }
+        }
+        if let (Some(max_percent), Some(tax)) = (rules.max_tax_percent, tax) {
+            let tax = Self::fetched("transfer tax", mint, tax);
+            if tax.map_or(true, |tax| tax.highest() > max_percent) {
+                blocked.push(BlockedRule::MaxTax { tax, max_percent });
+            }
+        }
+        if let (Some(min_score), Some(larp)) = (rules.min_larp_score, larp) {
+            let score = Self::fetched("LARP score", mint, larp);
+            if score.map_or(true, |score| score < min_score) {
+                blocked.push(BlockedRule::MinLarpScore { score, min_score });
+            }
+        }
+
+        if blocked.is_empty() {
+            return None;
+        }
+        info!("🚫 Buy of {} blocked for {} by {} token rule(s)", mint, user_id, blocked.len());
+        Some(TradeBlocked { mint: mint.to_string(), rules: blocked })
+    }
+
+    /// Err listing the blocking rules when `user_id` may not buy `mint`
+    pub async fn check(&self, user_id: i64, mint: &str) -> Result<()> {
+        match self.evaluate(user_id, mint).await {
+            Some(blocked) => Err(BotError::security(blocked.to_string()).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// `check` for the owner of `user_wallet`; wallets with no owner have no rules
+    ///
+    /// A failed owner lookup refuses the buy, like any other rule data that can't be fetched.
+    pub async fn check_wallet(&self, user_wallet: &str, mint: &str) -> Result<()> {
+        let Some(owners) = &self.owners else { return Ok(()) };
+        let owner = owners.owner_of(user_wallet).await.map_err(|e| {
+            warn!("🚫 No owner for {}, refusing the buy of {}: {}", user_wallet, mint, e);
+            BotError::security("Couldn't check your token rules, so the buy was refused. Try again shortly.".to_string())
+        })?;
+        match owner {
+            Some(user_id) => self.check(user_id, mint).await,
+            None => Ok(()),
+        }
+    }
+
+    /// A rule's data, or `None` when it couldn't be fetched and the rule fails closed
+    fn fetched<T>(what: &str, mint: &str, result: Result<T>) -> Option<T> {
+        result.map_err(|e| warn!("🚫 No {} for {}, refusing the buy: {}", what, mint, e)).ok()
+    }
+}
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc, Duration};
-use rust_decimal::Decimal;
+use rust_decimal::{prelude::{FromPrimitive, ToPrimitive}, Decimal};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -10,6 +10,14 @@ use crate::errors::{BotError, Result};
 use crate::api::jupiter_price_v3::JupiterPriceV3Client;
 use crate::telemetry::TelemetryService;
 use crate::trading::orders::{OrderManager, Order, OrderType, OrderStatus};
+use crate::trading::exit_routing::ExitDenomination;
+
+/// Length of the candles the price tracker builds from ticks
+pub const CANDLE_INTERVAL_SECS: i64 = 60;
+/// ATR length behind the tracker's own volatility metrics
+pub const DEFAULT_ATR_PERIODS: u32 = 14;
+/// Closed candles kept per token
+const MAX_CANDLE_HISTORY: usize = 200;
 
 /// Advanced trailing stop manager with multiple trailing strategies
 #[derive(Clone)]
@@ -40,6 +48,37 @@ pub struct TrailingStopState {
     pub status: TrailingStopStatus,
     pub performance_metrics: TrailingPerformanceMetrics,
     pub risk_controls: TrailingRiskControls,
+    /// Where the exit's proceeds go; the user's default when unset
+    #[serde(default)]
+    pub exit_denomination: Option<ExitDenomination>,
+    /// Distance the stop trails by, once the strategy has computed one
+    #[serde(default)]
+    pub trail_distance: Option<TrailDistance>,
+}
+
+/// How far a stop trails its best price, and what that distance came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrailDistance {
+    /// Strategy label, e.g. "ATR(14)×2.5"
+    pub basis: String,
+    pub amount: Decimal,
+    /// High-water mark (low for shorts) the distance is measured from
+    pub reference_price: Decimal,
+}
+
+impl TrailDistance {
+    /// Distance as a percent of the reference price
+    pub fn percent(&self) -> f64 {
+        if self.reference_price <= Decimal::ZERO {
+            return 0.0;
+        }
+        (self.amount / self.reference_price * Decimal::from(100)).to_f64().unwrap_or(0.0)
+    }
+
+    /// "ATR(14)×2.5 ≈ 4.2%"
+    pub fn describe(&self) -> String {
+        format!("{} ≈ {:.1}%", self.basis, self.percent())
+    }
 }
 
 /// Different trailing stop strategies
@@ -148,6 +187,8 @@ pub struct PriceTracker {
     pub volatility_metrics: VolatilityMetrics,
     pub technical_levels: TechnicalLevels,
     pub last_updated: DateTime<Utc>,
+    /// Candle still collecting ticks; joins `price_history` when it closes
+    pub forming_candle: Option<PriceCandle>,
 }
 
 #[derive(Debug, Clone)]
@@ -251,6 +292,7 @@ impl TrailingStopManager {
         entry_price: Decimal,
         position_size: Decimal,
         risk_controls: TrailingRiskControls,
+        exit_denomination: Option<ExitDenomination>,
     ) -> Result<String> {
         let _span = self.telemetry.as_ref().map(|t| 
             t.create_trading_span("create_trailing_stop", Some(&token_mint))
@@ -264,12 +306,15 @@ impl TrailingStopManager {
         ).await?;
         
         // Create underlying order
-        let order = Order::create_stop_loss(
+        let mut order = Order::create_stop_loss(
             user_id,
             token_mint.clone(),
             initial_stop_price,
             position_size,
         );
+        if let Some(denomination) = exit_denomination {
+            order = order.with_exit_denomination(denomination);
+        }
         
         let order_id = self.order_manager.create_order(order).await?;
         
@@ -292,6 +337,8 @@ impl TrailingStopManager {
             status: TrailingStopStatus::Active,
             performance_metrics: TrailingPerformanceMetrics::new(),
             risk_controls,
+            exit_denomination,
+            trail_distance: None,
         };
         
         // Store trailing stop
@@ -328,23 +375,27 @@ impl TrailingStopManager {
     /// Update a specific trailing stop based on current price
     async fn update_trailing_stop(&self, stop: &TrailingStopState) -> Result<()> {
         let current_price = self.get_current_price(&stop.token_mint).await?;
-        let price_tracker = self.get_price_tracker(&stop.token_mint).await?;
         
-        // Calculate new stop price based on strategy
-        let new_stop_price = self.calculate_new_stop_price(
-            stop,
-            current_price,
-            &price_tracker,
-        ).await?;
-        
-        // Check if stop should be updated
-        let should_update = match stop.position_side {
-            PositionSide::Long => new_stop_price > stop.current_stop_price,
-            PositionSide::Short => new_stop_price < stop.current_stop_price,
-        };
-        
-        if should_update {
-            self.update_stop_price(&stop.stop_id, new_stop_price, current_price).await?;
+        // ATR stops move on candle close instead, see `on_candle_close`
+        if !matches!(stop.strategy, TrailingStrategy::ATR { .. }) {
+            let price_tracker = self.get_price_tracker(&stop.token_mint).await?;
+            
+            // Calculate new stop price based on strategy
+            let new_stop_price = self.calculate_new_stop_price(
+                stop,
+                current_price,
+                &price_tracker,
+            ).await?;
+            
+            // Check if stop should be updated
+            let should_update = match stop.position_side {
+                PositionSide::Long => new_stop_price > stop.current_stop_price,
+                PositionSide::Short => new_stop_price < stop.current_stop_price,
+            };
+            
+            if should_update {
+                self.update_stop_price(&stop.stop_id, new_stop_price, current_price).await?;
+            }
         }
         
         // Update performance metrics
@@ -401,15 +452,9 @@ impl TrailingStopManager {
                 }
             },
             
-            TrailingStrategy::ATR { atr_multiplier, min_trailing_amount, max_trailing_amount, .. } => {
-                let atr_value = Decimal::from_f64_retain(price_tracker.volatility_metrics.atr * atr_multiplier)
-                    .unwrap_or(Decimal::ZERO);
-                let trail_amount = atr_value.max(*min_trailing_amount).min(*max_trailing_amount);
-                
-                match stop.position_side {
-                    PositionSide::Long => Ok(current_price - trail_amount),
-                    PositionSide::Short => Ok(current_price + trail_amount),
-                }
+            TrailingStrategy::ATR { .. } => {
+                // Re-evaluated per closed candle by `TrailingStopState::follow_atr`
+                Ok(stop.current_stop_price)
             },
             
             TrailingStrategy::VolatilityAdjusted { 
@@ -550,11 +595,7 @@ impl TrailingStopManager {
                 },
             }
             
-            stop.performance_metrics.total_adjustments += 1;
-            let adjustment_size = (new_stop_price - old_stop_price).abs();
-            stop.performance_metrics.average_adjustment_size = 
-                (stop.performance_metrics.average_adjustment_size * Decimal::from(stop.performance_metrics.total_adjustments - 1) + adjustment_size) 
-                / Decimal::from(stop.performance_metrics.total_adjustments);
+            stop.performance_metrics.record_adjustment(new_stop_price - old_stop_price);
             
             debug!("🔄 Updated trailing stop {} from {} to {}", 
                 stop_id, old_stop_price, new_stop_price);
@@ -602,7 +643,7 @@ impl TrailingStopManager {
             
         let price_data = prices.prices
             .get(token_mint)
-            .ok_or_else(|| BotError::trading(format!("Price data not found for token {}", token_mint)))?;
+            .ok_or_else(|| BotError::not_found(format!("Price data not found for token {}", token_mint)))?;
         
         Ok(Decimal::from_f64_retain(price_data.usd_price).unwrap_or(Decimal::ZERO))
     }
@@ -636,6 +677,7 @@ impl TrailingStopManager {
                     trend_strength: 0.5,
                 },
                 last_updated: Utc::now(),
+                forming_candle: None,
             };
             
             trackers.insert(token_mint.to_string(), tracker);
@@ -643,8 +685,64 @@ impl TrailingStopManager {
         Ok(())
     }
     
+    /// Feed the latest price into each tracked token's candles, re-evaluating stops as candles close
     async fn update_price_tracking(&self) -> Result<()> {
-        // Implementation would update price data and calculate technical indicators
+        let mints: Vec<String> = self.price_tracker.read().await.keys().cloned().collect();
+        for mint in mints {
+            let price = match self.get_current_price(&mint).await {
+                Ok(price) => price,
+                Err(e) => {
+                    debug!("🔄 No price for {}: {}", mint, e);
+                    continue;
+                }
+            };
+            let closed = {
+                let mut trackers = self.price_tracker.write().await;
+                trackers.get_mut(&mint).and_then(|tracker| tracker.observe_price(price, Utc::now()))
+            };
+            if closed.is_some() {
+                self.on_candle_close(&mint).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Re-evaluate the ATR stops on `token_mint` against its latest closed candle
+    ///
+    /// Moved stops re-price their underlying order, and the order carries the
+    /// current distance so /orders can show it.
+    pub async fn on_candle_close(&self, token_mint: &str) -> Result<()> {
+        let candles = self.get_price_tracker(token_mint).await?.price_history;
+        
+        let changed: Vec<(String, Option<Decimal>, Option<TrailDistance>)> = {
+            let mut stops = self.active_trailing_stops.write().await;
+            stops.values_mut()
+                .filter(|stop| stop.token_mint == token_mint && matches!(stop.status, TrailingStopStatus::Active))
+                .filter_map(|stop| {
+                    let old_stop_price = stop.current_stop_price;
+                    let old_distance = stop.trail_distance.clone();
+                    let moved = stop.follow_atr(&candles);
+                    if moved {
+                        stop.performance_metrics.record_adjustment(stop.current_stop_price - old_stop_price);
+                    }
+                    let distance = (stop.trail_distance != old_distance).then(|| stop.trail_distance.clone()).flatten();
+                    (moved || distance.is_some())
+                        .then(|| (stop.order_id.clone(), moved.then_some(stop.current_stop_price), distance))
+                })
+                .collect()
+        };
+        
+        for (order_id, stop_price, distance) in changed {
+            if let Some(stop_price) = stop_price {
+                if let Err(e) = self.order_manager.reprice_order(&order_id, stop_price).await {
+                    warn!("🔄 Couldn't move order {} to {}: {}", order_id, stop_price, e);
+                }
+            }
+            if let Some(distance) = distance {
+                debug!("🔄 Order {} now trails {}", order_id, distance.describe());
+                self.order_manager.set_trail_distance(&order_id, Some(distance)).await?;
+            }
+        }
         Ok(())
     }
     
@@ -658,6 +756,18 @@ impl TrailingStopManager {
         Ok(())
     }
     
+    /// Change where a trailing stop's exit is paid out; `None` follows the user's default
+    pub async fn set_exit_denomination(&self, stop_id: &str, denomination: Option<ExitDenomination>) -> Result<()> {
+        let order_id = {
+            let mut stops = self.active_trailing_stops.write().await;
+            let stop = stops.get_mut(stop_id)
+                .ok_or_else(|| BotError::not_found(format!("Trailing stop {} not found", stop_id)))?;
+            stop.exit_denomination = denomination;
+            stop.order_id.clone()
+        };
+        self.order_manager.set_exit_denomination(&order_id, denomination).await
+    }
+    
     /// Cancel a trailing stop
     pub async fn cancel_trailing_stop(&self, stop_id: &str) -> Result<bool> {
         let mut stops = self.active_trailing_stops.write().await;
@@ -682,6 +792,12 @@ impl TrailingStopManager {
 }
 
 impl TrailingPerformanceMetrics {
+    fn record_adjustment(&mut self, size: Decimal) {
+        self.total_adjustments += 1;
+        self.average_adjustment_size = (self.average_adjustment_size * Decimal::from(self.total_adjustments - 1) + size.abs())
+            / Decimal::from(self.total_adjustments);
+    }
+    
     fn new() -> Self {
         Self {
             max_favorable_excursion: Decimal::ZERO,
@@ -726,8 +842,132 @@ impl TrailingStopState {
             status: TrailingStopStatus::Active,
             performance_metrics: TrailingPerformanceMetrics::new(),
             risk_controls: TrailingRiskControls::default(),
+            exit_denomination: None,
+            trail_distance: None,
+        }
+    }
+}
+
+impl TrailingStopState {
+    /// Trail the latest closed candle by k×ATR; true when the stop moved
+    ///
+    /// The high-water mark (low for shorts) takes in the candle first, then the
+    /// stop is placed k×ATR behind it. A volatile stretch widens the recorded
+    /// distance, but the stop itself only ever tightens.
+    pub fn follow_atr(&mut self, candles: &[PriceCandle]) -> bool {
+        let TrailingStrategy::ATR { atr_multiplier, atr_periods, min_trailing_amount, max_trailing_amount } = &self.strategy else {
+            return false;
+        };
+        let Some(candle) = candles.last() else {
+            return false;
+        };
+        let long = matches!(self.position_side, PositionSide::Long);
+        if long {
+            self.highest_price = self.highest_price.max(candle.high);
+        } else {
+            self.lowest_price = self.lowest_price.min(candle.low);
+        }
+        
+        // Too little history yet; the initial stop stands
+        let Some(atr) = average_true_range(candles, *atr_periods) else {
+            return false;
+        };
+        let amount = Decimal::from_f64(atr * atr_multiplier)
+            .unwrap_or(Decimal::ZERO)
+            .max(*min_trailing_amount)
+            .min(*max_trailing_amount);
+        let reference_price = if long { self.highest_price } else { self.lowest_price };
+        self.trail_distance = Some(TrailDistance {
+            basis: self.strategy.label(),
+            amount,
+            reference_price,
+        });
+        
+        let candidate = if long { reference_price - amount } else { reference_price + amount };
+        let tightens = if long { candidate > self.current_stop_price } else { candidate < self.current_stop_price };
+        if tightens {
+            self.current_stop_price = candidate;
+            self.last_updated = candle.timestamp;
+        }
+        tightens
+    }
+}
+
+impl TrailingStrategy {
+    /// Short label for listings, e.g. "ATR(14)×2.5" or "5%"
+    pub fn label(&self) -> String {
+        match self {
+            TrailingStrategy::FixedAmount { trailing_amount, .. } => format!("${}", trailing_amount),
+            TrailingStrategy::Percentage { trailing_percentage, .. } => format!("{}%", trailing_percentage),
+            TrailingStrategy::ATR { atr_multiplier, atr_periods, .. } => format!("ATR({})×{}", atr_periods, atr_multiplier),
+            TrailingStrategy::VolatilityAdjusted { .. } => "Volatility-adjusted".to_string(),
+            TrailingStrategy::Adaptive { .. } => "Adaptive".to_string(),
+            TrailingStrategy::TimeBased { .. } => "Time-based".to_string(),
+            TrailingStrategy::TechnicalLevels { .. } => "Support/resistance".to_string(),
+        }
+    }
+}
+
+impl PriceTracker {
+    /// Fold a tick into the forming candle; returns the candle it closed, if any
+    pub fn observe_price(&mut self, price: Decimal, at: DateTime<Utc>) -> Option<PriceCandle> {
+        self.current_price = price;
+        self.last_updated = at;
+        
+        let closed = match &mut self.forming_candle {
+            Some(candle) if at - candle.timestamp < Duration::seconds(CANDLE_INTERVAL_SECS) => {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+                return None;
+            }
+            forming => forming.take(),
+        };
+        self.forming_candle = Some(PriceCandle { timestamp: at, open: price, high: price, low: price, close: price, volume: None });
+        
+        let closed = closed?;
+        self.record_candle(closed.clone());
+        Some(closed)
+    }
+    
+    /// Append a closed candle and refresh the ATR
+    pub fn record_candle(&mut self, candle: PriceCandle) {
+        self.price_history.push(candle);
+        if self.price_history.len() > MAX_CANDLE_HISTORY {
+            let excess = self.price_history.len() - MAX_CANDLE_HISTORY;
+            self.price_history.drain(..excess);
         }
+        if let Some(atr) = average_true_range(&self.price_history, DEFAULT_ATR_PERIODS) {
+            self.volatility_metrics.atr = atr;
+        }
+    }
+}
+
+/// Wilder's Average True Range over `periods` candles, in price units
+///
+/// `None` until there are `periods` candles after the first, since each true
+/// range needs the previous close.
+pub fn average_true_range(candles: &[PriceCandle], periods: u32) -> Option<f64> {
+    let periods = periods as usize;
+    if periods == 0 || candles.len() <= periods {
+        return None;
     }
+    
+    let true_ranges: Vec<f64> = candles.windows(2)
+        .filter_map(|pair| {
+            let (previous_close, candle) = (pair[0].close, &pair[1]);
+            let range = (candle.high - candle.low)
+                .max((candle.high - previous_close).abs())
+                .max((candle.low - previous_close).abs());
+            range.to_f64()
+        })
+        .collect();
+    if true_ranges.len() < periods {
+        return None;
+    }
+    
+    let seed = true_ranges[..periods].iter().sum::<f64>() / periods as f64;
+    Some(true_ranges[periods..].iter().fold(seed, |atr, range| (atr * (periods - 1) as f64 + range) / periods as f64))
 }
 
 impl Default for TrailingRiskControls {
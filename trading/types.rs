@@ -3,6 +3,13 @@ use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 use indexmap::IndexMap;
 
+use crate::api::jupiter_v6::QuoteResponseV6;
+use crate::wallet::TransactionPriority;
+use super::compute_budget::ComputeBudget;
+use super::confirmation::ConfirmationStage;
+use super::exit_routing::ExitSettlement;
+use super::dex::JupiterQuote;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeResult {
     pub tx_signature: String,
@@ -17,6 +24,436 @@ pub struct TradeResult {
     pub timestamp: chrono::DateTime<chrono::Utc>,
     // Add trade type for better categorization
     pub trade_type: TradeType,
+    /// Route, timing and retry metadata; absent on results stored before it existed
+    #[serde(default)]
+    pub execution: ExecutionReport,
+    /// Price per token the trade was accepted on
+    #[serde(default)]
+    pub quoted_price: Option<f64>,
+    /// Price per token decoded from the confirmed transaction; `None` until the fill is verified
+    #[serde(default)]
+    pub executed_price: Option<f64>,
+    /// Executed vs quoted, positive when the fill was worse than the quote
+    #[serde(default)]
+    pub realized_slippage_bps: Option<f64>,
+}
+
+impl TradeResult {
+    /// Buy result with the original fields and an empty execution report
+    pub fn buy(tx_signature: String, tokens_received: f64, amount_sol: f64, price: f64) -> Self {
+        Self {
+            tx_signature,
+            tokens_received,
+            tokens_sold: 0.0,
+            sol_received: 0.0,
+            amount_sol,
+            price,
+            rebate_earned: 0.0,
+            pnl_percentage: 0.0,
+            timestamp: chrono::Utc::now(),
+            trade_type: TradeType::Buy,
+            execution: ExecutionReport::default(),
+            quoted_price: None,
+            executed_price: None,
+            realized_slippage_bps: None,
+        }
+    }
+    
+    /// Sell result with the original fields and an empty execution report
+    pub fn sell(tx_signature: String, tokens_sold: f64, sol_received: f64, price: f64) -> Self {
+        Self {
+            tx_signature,
+            tokens_received: 0.0,
+            tokens_sold,
+            sol_received,
+            amount_sol: sol_received,
+            price,
+            rebate_earned: 0.0,
+            pnl_percentage: 0.0,
+            timestamp: chrono::Utc::now(),
+            trade_type: TradeType::Sell,
+            execution: ExecutionReport::default(),
+            quoted_price: None,
+            executed_price: None,
+            realized_slippage_bps: None,
+        }
+    }
+    
+    pub fn with_execution(mut self, execution: ExecutionReport) -> Self {
+        self.quoted_price = execution.quoted_price;
+        self.execution = execution;
+        self
+    }
+    
+    /// Take the executed price and slippage from the report once its fill was read from chain
+    pub fn with_verified_fill(mut self) -> Self {
+        self.executed_price = self.execution.realized_price;
+        self.realized_slippage_bps = self.execution.slippage_bps;
+        self
+    }
+    
+    /// Record where the transaction ended up once followed to a final stage
+    pub fn record_confirmation(&mut self, stage: ConfirmationStage) {
+        self.execution.confirmation = Some(stage);
+    }
+    
+    /// Whether the trade landed through a Jito bundle rather than plain RPC
+    pub fn landed_via_bundle(&self) -> bool {
+        self.execution.bundle.as_ref().is_some_and(|bundle| bundle.landed)
+    }
+    
+    /// Jito tip carried by the transaction, in SOL
+    pub fn bundle_tip_sol(&self) -> Option<f64> {
+        self.execution.bundle.as_ref().map(|bundle| bundle.tip_lamports as f64 / 1e9)
+    }
+}
+
+/// How a trade was routed, priced and broadcast
+///
+/// Every field defaults so historical results deserialize unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExecutionReport {
+    /// When the quote the decision was based on was fetched
+    pub quoted_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the trade filled (or the transaction was built, for unsigned trades)
+    pub executed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub route: Option<RouteSummary>,
+    /// Price per token implied by the quote
+    pub quoted_price: Option<f64>,
+    /// Price per token actually obtained
+    pub realized_price: Option<f64>,
+    /// Realized vs quoted, positive when the fill was worse than the quote
+    pub slippage_bps: Option<f64>,
+    pub broadcast_attempts: u32,
+    pub blockhash_refreshed: bool,
+    pub fees: Option<ExecutionFees>,
+    /// Compute unit limit the transaction requested
+    pub compute_unit_limit: Option<u32>,
+    /// Units consumed when measured (simulation before broadcast)
+    pub compute_units_consumed: Option<u64>,
+    /// Paper trade or simulated fill, no transaction on chain
+    pub simulated: bool,
+    pub idempotency_key: Option<String>,
+    /// Set by post-trade MEV inspection when the swap was sandwiched
+    pub sandwich: Option<SandwichFinding>,
+    /// Exit paid out in a denomination chosen by the user; prices above are in that token
+    pub exit: Option<ExitSettlement>,
+    /// Set when the trade was submitted as a Jito bundle
+    pub bundle: Option<BundleReceipt>,
+    /// Set when the confirmed swap spent noticeably less than quoted
+    pub partial_fill: Option<PartialFill>,
+    /// Where the sent transaction ended up once followed to a final stage
+    pub confirmation: Option<ConfirmationStage>,
+}
+
+/// Venues the quote routed through
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RouteSummary {
+    pub venues: Vec<String>,
+    pub hops: usize,
+    pub price_impact_pct: Option<f64>,
+}
+
+/// A front-run/back-run pair around the user's swap in the same block
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SandwichFinding {
+    pub front_run_signature: String,
+    pub back_run_signature: String,
+    pub attacker: String,
+    pub pool: String,
+    /// Lost versus the pool state absent the attack, in the quote token
+    pub loss_quote: f64,
+    /// Loss as a percentage of the fair output
+    pub loss_pct: f64,
+}
+
+/// How a protected trade went through the Jito block engine
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BundleReceipt {
+    /// Absent when the block engine rejected the bundle outright
+    pub bundle_id: Option<String>,
+    /// Tip transfer included in the swap transaction
+    pub tip_lamports: u64,
+    pub landed: bool,
+    /// Broadcast through RPC after the bundle failed or timed out
+    pub fell_back_to_rpc: bool,
+    pub landing_ms: Option<u64>,
+}
+
+/// Input the quote asked for versus what the confirmed swap consumed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PartialFill {
+    pub requested_in: f64,
+    pub filled_in: f64,
+}
+
+impl PartialFill {
+    pub fn filled_pct(&self) -> f64 {
+        if self.requested_in > 0.0 { self.filled_in / self.requested_in * 100.0 } else { 0.0 }
+    }
+}
+
+/// Fee components of a single execution
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExecutionFees {
+    pub network_fee_sol: f64,
+    pub priority_fee_lamports: u64,
+    pub platform_fee_sol: f64,
+    /// Token-2022 transfer fee withheld, in tokens
+    pub transfer_fee_tokens: f64,
+}
+
+impl RouteSummary {
+    /// Build from the AMM labels of a quote's route plan, one per hop
+    pub fn from_labels<I, S>(labels: I, price_impact_pct: Option<f64>) -> Self
+    where
+        I: IntoIterator<Item = Option<S>>,
+        S: Into<String>,
+    {
+        let mut venues = Vec::new();
+        let mut hops = 0;
+        for label in labels {
+            hops += 1;
+            let venue = label.map(Into::into).unwrap_or_else(|| "Unknown".to_string());
+            if !venues.contains(&venue) {
+                venues.push(venue);
+            }
+        }
+        
+        Self { venues, hops, price_impact_pct }
+    }
+    
+    pub fn label(&self) -> String {
+        if self.venues.is_empty() {
+            return "direct".to_string();
+        }
+        if self.hops > 1 {
+            format!("{} ({} hops)", self.venues.join(" → "), self.hops)
+        } else {
+            self.venues.join(" → ")
+        }
+    }
+}
+
+impl From<&JupiterQuote> for RouteSummary {
+    fn from(quote: &JupiterQuote) -> Self {
+        Self::from_labels(
+            quote.route_plan.iter().map(|step| step.swap_info.label.clone()),
+            Some(quote.price_impact_pct),
+        )
+    }
+}
+
+impl From<&QuoteResponseV6> for RouteSummary {
+    fn from(quote: &QuoteResponseV6) -> Self {
+        Self::from_labels(
+            quote.route_plan.iter().map(|step| Some(step.swap_info.label.clone())),
+            quote.price_impact_pct.parse().ok(),
+        )
+    }
+}
+
+impl ExecutionReport {
+    /// Report for a freshly fetched quote
+    pub fn quoted(quoted_price: f64, route: RouteSummary) -> Self {
+        Self {
+            quoted_at: Some(chrono::Utc::now()),
+            route: Some(route),
+            quoted_price: Some(quoted_price).filter(|p| p.is_finite() && *p > 0.0),
+            ..Self::default()
+        }
+    }
+    
+    /// Record the fill and derive slippage against the quote
+    ///
+    /// For buys the price is SOL per token, so a higher realized price is worse;
+    /// for sells it's SOL received per token, so a lower one is.
+    pub fn filled(mut self, realized_price: f64, trade_type: TradeType) -> Self {
+        self.executed_at = Some(chrono::Utc::now());
+        if realized_price.is_finite() && realized_price > 0.0 {
+            self.realized_price = Some(realized_price);
+            self.slippage_bps = self.quoted_price.map(|quoted| {
+                let diff = match trade_type {
+                    TradeType::Sell => quoted - realized_price,
+                    TradeType::Buy | TradeType::Swap => realized_price - quoted,
+                };
+                diff / quoted * 10_000.0
+            });
+        }
+        self
+    }
+    
+    pub fn with_fees(mut self, fees: ExecutionFees) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+    
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+    
+    /// Record the compute budget the transaction was built with
+    pub fn with_compute_budget(mut self, budget: &ComputeBudget) -> Self {
+        self.compute_unit_limit = Some(budget.unit_limit);
+        self.compute_units_consumed = budget.simulated_units;
+        if let Some(fees) = self.fees.as_mut() {
+            fees.priority_fee_lamports = budget.priority_fee_lamports();
+        }
+        self
+    }
+    
+    pub fn with_sandwich(mut self, finding: SandwichFinding) -> Self {
+        self.sandwich = Some(finding);
+        self
+    }
+    
+    pub fn with_exit(mut self, exit: ExitSettlement) -> Self {
+        self.exit = Some(exit);
+        self
+    }
+    
+    pub fn with_bundle(mut self, bundle: Option<BundleReceipt>) -> Self {
+        self.bundle = bundle;
+        self
+    }
+    
+    pub fn with_partial_fill(mut self, partial_fill: Option<PartialFill>) -> Self {
+        self.partial_fill = partial_fill;
+        self
+    }
+    
+    /// Realized price in SOL per token, converted at execution time for USDC exits
+    pub fn realized_price_sol(&self) -> Option<f64> {
+        let price = self.realized_price?;
+        Some(self.exit.as_ref().map_or(price, |exit| exit.price_in_sol(price)))
+    }
+    
+    pub fn simulated(mut self, simulated: bool) -> Self {
+        self.simulated = simulated;
+        self
+    }
+    
+    /// Count one broadcast of the transaction, noting whether it needed a fresh blockhash
+    pub fn record_broadcast(&mut self, blockhash_refreshed: bool) {
+        self.broadcast_attempts += 1;
+        self.blockhash_refreshed |= blockhash_refreshed;
+    }
+    
+    /// Decision-to-fill latency
+    pub fn latency_ms(&self) -> Option<i64> {
+        match (self.quoted_at, self.executed_at) {
+            (Some(quoted), Some(executed)) => Some((executed - quoted).num_milliseconds().max(0)),
+            _ => None,
+        }
+    }
+    
+    /// Whether anything beyond the defaults was recorded
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+    
+    /// Shape of the Convex `trades.report` field; Convex rejects nulls, so unset fields are omitted
+    pub fn to_convex(&self) -> serde_json::Value {
+        let mut report = serde_json::Map::new();
+        let mut put = |key: &str, value: serde_json::Value| {
+            report.insert(key.to_string(), value);
+        };
+        
+        if let Some(quoted_at) = self.quoted_at {
+            put("quotedAt", quoted_at.timestamp_millis().into());
+        }
+        if let Some(executed_at) = self.executed_at {
+            put("executedAt", executed_at.timestamp_millis().into());
+        }
+        if let Some(latency) = self.latency_ms() {
+            put("latencyMs", latency.into());
+        }
+        if let Some(route) = &self.route {
+            put("route", serde_json::json!({
+                "venues": route.venues,
+                "hops": route.hops,
+                "label": route.label(),
+            }));
+            if let Some(impact) = route.price_impact_pct {
+                put("priceImpactPct", impact.into());
+            }
+        }
+        if let Some(quoted) = self.quoted_price {
+            put("quotedPrice", quoted.to_string().into());
+        }
+        if let Some(realized) = self.realized_price {
+            put("realizedPrice", realized.to_string().into());
+        }
+        if let Some(slippage) = self.slippage_bps {
+            put("slippageBps", slippage.into());
+        }
+        put("broadcastAttempts", self.broadcast_attempts.into());
+        put("blockhashRefreshed", self.blockhash_refreshed.into());
+        if let Some(fees) = &self.fees {
+            put("fees", serde_json::json!({
+                "networkSol": fees.network_fee_sol.to_string(),
+                "priorityLamports": fees.priority_fee_lamports,
+                "platformSol": fees.platform_fee_sol.to_string(),
+                "transferFeeTokens": fees.transfer_fee_tokens.to_string(),
+            }));
+        }
+        if let Some(limit) = self.compute_unit_limit {
+            put("computeUnitLimit", limit.into());
+        }
+        if let Some(consumed) = self.compute_units_consumed {
+            put("computeUnitsConsumed", consumed.into());
+        }
+        put("simulated", self.simulated.into());
+        if let Some(key) = &self.idempotency_key {
+            put("idempotencyKey", key.clone().into());
+        }
+        if let Some(sandwich) = &self.sandwich {
+            put("sandwich", serde_json::json!({
+                "frontRunSignature": sandwich.front_run_signature,
+                "backRunSignature": sandwich.back_run_signature,
+                "attacker": sandwich.attacker,
+                "pool": sandwich.pool,
+                "lossQuote": sandwich.loss_quote.to_string(),
+                "lossPct": sandwich.loss_pct,
+            }));
+        }
+        if let Some(exit) = &self.exit {
+            put("exit", serde_json::json!({
+                "denomination": exit.denomination.label(),
+                "amountOut": exit.amount_out.to_string(),
+                "solUsd": exit.sol_usd.to_string(),
+            }));
+        }
+        if let Some(bundle) = &self.bundle {
+            let mut value = serde_json::json!({
+                "tipLamports": bundle.tip_lamports,
+                "landed": bundle.landed,
+                "fellBackToRpc": bundle.fell_back_to_rpc,
+            });
+            if let Some(bundle_id) = &bundle.bundle_id {
+                value["bundleId"] = bundle_id.clone().into();
+            }
+            if let Some(landing_ms) = bundle.landing_ms {
+                value["landingMs"] = landing_ms.into();
+            }
+            put("bundle", value);
+        }
+        if let Some(partial) = &self.partial_fill {
+            put("partialFill", serde_json::json!({
+                "requestedIn": partial.requested_in.to_string(),
+                "filledIn": partial.filled_in.to_string(),
+            }));
+        }
+        
+        serde_json::Value::Object(report)
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -26,6 +463,31 @@ pub enum TradeType {
     Swap,
 }
 
+/// Where a trade record came from
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TradeProvenance {
+    /// Executed by the bot
+    #[default]
+    Bot,
+    /// Uploaded from another bot's or frontend's history; counts toward cost
+    /// basis and PnL but never toward leaderboards or badges
+    Imported,
+}
+
+/// Slippage and fee level a trade is quoted and priced at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeDefaults {
+    pub slippage_bps: u16,
+    /// Recent-fee percentile the compute unit price is taken from
+    pub priority: TransactionPriority,
+}
+
+/// Each user's default slippage and priority fee level
+#[async_trait::async_trait]
+pub trait TradeDefaultPreferences: Send + Sync {
+    async fn trade_defaults(&self, user_id: i64) -> TradeDefaults;
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Balance {
     pub sol: f64,
@@ -59,6 +521,9 @@ pub struct Position {
     pub sort_key: u64,
     // Add last update timestamp
     pub last_updated: chrono::DateTime<chrono::Utc>,
+    /// Found on-chain without a bot trade behind it, so the cost basis is unknown
+    #[serde(default)]
+    pub untracked_entry: bool,
 }
 
 impl PartialEq for Position {
@@ -172,6 +637,17 @@ impl TradeHistory {
         }
     }
     
+    /// Record the final stage of the trade sent as `tx_signature`; false if it isn't held
+    pub fn record_confirmation(&mut self, tx_signature: &str, stage: ConfirmationStage) -> bool {
+        match self.trades.get_mut(tx_signature) {
+            Some(trade) => {
+                trade.record_confirmation(stage);
+                true
+            }
+            None => false,
+        }
+    }
+    
     pub fn get_recent_trades(&self, limit: usize) -> Vec<&TradeResult> {
         self.trades.values().rev().take(limit).collect()
     }
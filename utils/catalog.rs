@@ -0,0 +1,5 @@
+//! Translated bot text, loaded from the JSON catalogs under `locales/`
+//!
+//! The catalogs and their lookups live in the `bot-locales` crate so the
+//! Convex integration's bot reads the same text; this module re-exports them.
+pub use bot_locales::{language_name, normalize, t, t_args, Catalog, CatalogError, LANGUAGES};
@@ -4,6 +4,13 @@ use std::env;
 use crate::constants::{DEFAULT_SLIPPAGE_BPS, DEFAULT_PRIORITY_FEE};
 use crate::errors::BotError;
 
+const DEFAULT_PAPER_SLIPPAGE_BPS: u16 = 50;
+const DEFAULT_PAPER_BALANCE_SOL: f64 = 10.0;
+const DEFAULT_DUST_THRESHOLD_USD: f64 = 1.0;
+const DEFAULT_POSITION_SYNC_SECS: u64 = 900;
+const DEFAULT_SEND_EXPIRY_HOURS: u32 = 72;
+const DEFAULT_MASTER_PAYOUT_MIN_SOL: f64 = 0.1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     // API Keys
@@ -15,6 +22,10 @@ pub struct Config {
     
     // Network Settings
     pub network: NetworkType,
+    pub rpc_url_override: Option<String>,
+    pub jupiter_api_url: String,
+    pub jupiter_price_api_url: String,
+    pub telegram_api_url: Option<String>,
     
     // Trading Configuration
     pub max_trade_size_sol: f64,
@@ -30,6 +41,63 @@ pub struct Config {
     // Feature Flags
     pub enable_ai_analysis: bool,
     pub enable_paper_trading: bool,
+    /// Synthetic slippage applied to paper fills
+    pub paper_slippage_bps: u16,
+    /// Virtual SOL a paper ledger starts (and resets) with
+    pub paper_starting_balance_sol: f64,
+    
+    // Portfolio
+    /// Holdings worth less than this are hidden from /portfolio but kept in the data
+    pub dust_threshold_usd: f64,
+    /// Seconds between on-chain syncs of positions for wallets that opened /portfolio
+    pub position_sync_interval_secs: u64,
+    
+    // Token distribution
+    /// Hours a /send claim link stays open; unclaimed sends can be reclaimed after it
+    pub send_expiry_hours: u32,
+    
+    // Copy trading
+    /// Accrued master fees below this wait for the next payout run
+    pub master_payout_min_sol: f64,
+    /// Base58 secret of the wallet copy fees are collected in; payouts stay off when unset
+    pub copy_fee_treasury_key: Option<String>,
+    
+    // Shared State
+    /// Where sessions, pending confirmations and rate-limit counters live
+    pub session_backend: SessionBackend,
+    pub redis_url: Option<String>,
+    
+    // Rate Limiting
+    /// Command bucket refill; commands cost 1–5 tokens by weight
+    pub command_tokens_per_minute: u32,
+    pub command_burst: u32,
+    /// Separate bucket every trade-executing command also draws one token from
+    pub trade_commands_per_minute: u32,
+    pub trade_burst: u32,
+    
+    // Monitoring
+    /// Port serving Prometheus `/metrics`; unset keeps the exporter off
+    pub metrics_port: Option<u16>,
+    
+    // Blinks
+    /// Public URL the Solana Actions endpoints are reachable under
+    pub blinks_base_url: Option<String>,
+    /// Port serving the Actions endpoints; unset keeps /blink links off
+    pub blinks_port: Option<u16>,
+    
+    // Convex webhook
+    /// Port receiving Convex commands; needs `convex_webhook_secret` too
+    pub convex_webhook_port: Option<u16>,
+    /// Bearer token Convex sends with every webhook request
+    pub convex_webhook_secret: Option<String>,
+}
+
+/// Redis lets several instances run behind the same bot token
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionBackend {
+    #[default]
+    Memory,
+    Redis,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +124,12 @@ impl Config {
             
             // Network Settings
             network: Self::parse_network(&env::var("NETWORK").unwrap_or_else(|_| "mainnet".to_string())),
+            rpc_url_override: env::var("SOLANA_RPC_URL").ok().filter(|s| !s.is_empty()),
+            jupiter_api_url: env::var("JUPITER_API_URL")
+                .unwrap_or_else(|_| "https://quote-api.jup.ag/v6".to_string()),
+            jupiter_price_api_url: env::var("JUPITER_PRICE_API_URL")
+                .unwrap_or_else(|_| "https://price.jup.ag/v6".to_string()),
+            telegram_api_url: env::var("TELEGRAM_API_URL").ok().filter(|s| !s.is_empty()),
             
             // Trading Configuration
             max_trade_size_sol: env::var("MAX_TRADE_SIZE_SOL")
@@ -102,9 +176,80 @@ impl Config {
                 .unwrap_or_else(|_| "false".to_string())
                 .parse()
                 .unwrap_or(false),
+            paper_slippage_bps: env::var("PAPER_SLIPPAGE_BPS")
+                .unwrap_or_else(|_| DEFAULT_PAPER_SLIPPAGE_BPS.to_string())
+                .parse()
+                .unwrap_or(DEFAULT_PAPER_SLIPPAGE_BPS),
+            paper_starting_balance_sol: env::var("PAPER_STARTING_BALANCE_SOL")
+                .unwrap_or_else(|_| DEFAULT_PAPER_BALANCE_SOL.to_string())
+                .parse()
+                .unwrap_or(DEFAULT_PAPER_BALANCE_SOL),
+            
+            // Portfolio
+            dust_threshold_usd: env::var("DUST_THRESHOLD_USD")
+                .unwrap_or_else(|_| DEFAULT_DUST_THRESHOLD_USD.to_string())
+                .parse()
+                .unwrap_or(DEFAULT_DUST_THRESHOLD_USD),
+            position_sync_interval_secs: env::var("POSITION_SYNC_INTERVAL_SECS")
+                .unwrap_or_else(|_| DEFAULT_POSITION_SYNC_SECS.to_string())
+                .parse()
+                .unwrap_or(DEFAULT_POSITION_SYNC_SECS),
+            
+            // Token distribution
+            send_expiry_hours: env::var("SEND_EXPIRY_HOURS")
+                .unwrap_or_else(|_| DEFAULT_SEND_EXPIRY_HOURS.to_string())
+                .parse()
+                .unwrap_or(DEFAULT_SEND_EXPIRY_HOURS),
+            
+            // Copy trading
+            master_payout_min_sol: env::var("MASTER_PAYOUT_MIN_SOL")
+                .unwrap_or_else(|_| DEFAULT_MASTER_PAYOUT_MIN_SOL.to_string())
+                .parse()
+                .unwrap_or(DEFAULT_MASTER_PAYOUT_MIN_SOL),
+            copy_fee_treasury_key: env::var("COPY_FEE_TREASURY_KEY").ok().filter(|s| !s.is_empty()),
+            
+            // Shared State
+            session_backend: Self::parse_session_backend(&env::var("SESSION_BACKEND").unwrap_or_default()),
+            redis_url: env::var("REDIS_URL").ok().filter(|s| !s.is_empty()),
+            
+            // Rate Limiting
+            command_tokens_per_minute: env::var("COMMAND_TOKENS_PER_MINUTE")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            command_burst: env::var("COMMAND_BURST")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap_or(20),
+            trade_commands_per_minute: env::var("TRADE_COMMANDS_PER_MINUTE")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            trade_burst: env::var("TRADE_BURST")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            
+            // Monitoring
+            metrics_port: env::var("METRICS_PORT").ok().and_then(|s| s.parse().ok()),
+            
+            // Blinks
+            blinks_base_url: env::var("BLINKS_BASE_URL").ok().filter(|s| !s.is_empty()),
+            blinks_port: env::var("BLINKS_PORT").ok().and_then(|s| s.parse().ok()),
+            
+            // Convex webhook
+            convex_webhook_port: env::var("CONVEX_WEBHOOK_PORT").ok().and_then(|s| s.parse().ok()),
+            convex_webhook_secret: env::var("CONVEX_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
         })
     }
     
+    fn parse_session_backend(backend: &str) -> SessionBackend {
+        match backend.to_lowercase().as_str() {
+            "redis" => SessionBackend::Redis,
+            _ => SessionBackend::Memory,
+        }
+    }
+    
     fn parse_network(network: &str) -> NetworkType {
         match network.to_lowercase().as_str() {
             "mainnet" | "mainnet-beta" => NetworkType::Mainnet,
@@ -123,6 +268,10 @@ impl Config {
     }
     
     pub fn get_rpc_url(&self) -> String {
+        if let Some(url) = &self.rpc_url_override {
+            return url.clone();
+        }
+        
         match self.network {
             NetworkType::Mainnet => {
                 if self.enable_backrun_rebates && !self.rebate_wallet_address.is_empty() {
@@ -152,6 +301,10 @@ impl Config {
             return Err(BotError::Config(format!("Slippage cannot exceed {}%", MAX_SLIPPAGE_BPS / 100)).into());
         }
         
+        if self.session_backend == SessionBackend::Redis && self.redis_url.is_none() {
+            return Err(BotError::Config("SESSION_BACKEND=redis needs REDIS_URL".into()).into());
+        }
+        
         Ok(())
     }
     
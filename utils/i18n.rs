@@ -0,0 +1,243 @@
+//! Locale-aware rendering of numbers and dates in bot output
+//!
+//! Only output is localized. User input is always parsed with `.` as the
+//! decimal separator, see `Validator::parse_amount`.
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use teloxide::{types::User, utils::markdown::escape};
+
+/// Language used when the user's is unknown or unsupported
+pub use bot_locales::DEFAULT_LANG;
+
+/// Significant digits kept for values below one
+const SMALL_VALUE_SIGNIFICANT_DIGITS: i32 = 4;
+/// Upper bound on decimals, enough for the smallest SPL token units
+const MAX_DECIMALS: usize = 12;
+
+const NO_BREAK_SPACE: char = '\u{00A0}';
+const NARROW_NO_BREAK_SPACE: char = '\u{202F}';
+
+/// What a number represents, which decides precision and decoration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberKind {
+    /// Plain number with a fixed number of decimals
+    Decimal(usize),
+    /// Percentage with a fixed number of decimals, e.g. confidence or allocation
+    Percent(usize),
+    /// Signed percentage change, e.g. P&L or 24h performance
+    Change,
+    /// US dollar value; prices below a cent keep their significant digits
+    Usd,
+    /// Token quantity; small balances keep their significant digits
+    Token,
+    /// SOL amount
+    Sol,
+}
+
+/// How a timestamp is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStyle {
+    /// Day, month and year
+    Date,
+    /// Day and month
+    ShortDate,
+    /// Hours and minutes with the zone abbreviation
+    Time,
+    /// Full date and time with the zone abbreviation
+    DateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateOrder {
+    /// 09/14/2025
+    MonthFirst,
+    /// 14.09.2025 or 14/09/2025
+    DayFirst(char),
+}
+
+/// Separators and placement conventions for one language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LocaleFormat {
+    decimal: char,
+    group: char,
+    /// `$1.00` rather than `1,00 $`
+    currency_prefix: bool,
+    /// Placed between the number and `%`, if any
+    percent_space: Option<char>,
+    date_order: DateOrder,
+}
+
+impl LocaleFormat {
+    fn for_lang(lang: &str) -> Self {
+        let english = Self {
+            decimal: '.',
+            group: ',',
+            currency_prefix: true,
+            percent_space: None,
+            date_order: DateOrder::MonthFirst,
+        };
+
+        match Self::base_lang(lang).as_str() {
+            "de" => Self {
+                decimal: ',',
+                group: '.',
+                currency_prefix: false,
+                percent_space: Some(NO_BREAK_SPACE),
+                date_order: DateOrder::DayFirst('.'),
+            },
+            "fr" => Self {
+                decimal: ',',
+                group: NARROW_NO_BREAK_SPACE,
+                currency_prefix: false,
+                percent_space: Some(NARROW_NO_BREAK_SPACE),
+                date_order: DateOrder::DayFirst('/'),
+            },
+            "es" => Self {
+                decimal: ',',
+                group: '.',
+                currency_prefix: false,
+                percent_space: Some(NO_BREAK_SPACE),
+                date_order: DateOrder::DayFirst('/'),
+            },
+            "it" => Self {
+                decimal: ',',
+                group: '.',
+                currency_prefix: false,
+                percent_space: None,
+                date_order: DateOrder::DayFirst('/'),
+            },
+            "ru" => Self {
+                decimal: ',',
+                group: NO_BREAK_SPACE,
+                currency_prefix: false,
+                percent_space: Some(NO_BREAK_SPACE),
+                date_order: DateOrder::DayFirst('.'),
+            },
+            _ => english,
+        }
+    }
+
+    /// `de-AT`, `de_DE` and `DE` all format as German
+    fn base_lang(lang: &str) -> String {
+        lang.split(['-', '_']).next().unwrap_or(DEFAULT_LANG).to_lowercase()
+    }
+}
+
+/// The Telegram client language of a message's sender
+pub fn lang_of(user: Option<&User>) -> &str {
+    user.and_then(|u| u.language_code.as_deref()).unwrap_or(DEFAULT_LANG)
+}
+
+/// Format a number for the user's language
+///
+/// Digits after the decimal separator are never grouped, so long token
+/// amounts stay unambiguous in every locale.
+pub fn fmt_number(lang: &str, value: f64, kind: NumberKind) -> String {
+    if !value.is_finite() {
+        return "—".to_string();
+    }
+    let locale = LocaleFormat::for_lang(lang);
+
+    match kind {
+        NumberKind::Decimal(decimals) => render(&locale, value, decimals),
+        NumberKind::Percent(decimals) => with_percent(&locale, render(&locale, value, decimals)),
+        NumberKind::Change => {
+            let rendered = render(&locale, value, 2);
+            let signed = if value > 0.0 && !rounds_to_zero(value, 2) {
+                format!("+{}", rendered)
+            } else {
+                rendered
+            };
+            with_percent(&locale, signed)
+        }
+        NumberKind::Usd => {
+            let decimals = if value.abs() >= 1.0 { 2 } else { small_value_decimals(value).max(4) };
+            let rendered = render(&locale, value.abs(), decimals);
+            let sign = if value < 0.0 && !rounds_to_zero(value, decimals) { "-" } else { "" };
+            if locale.currency_prefix {
+                format!("{}${}", sign, rendered)
+            } else {
+                format!("{}{}{}$", sign, rendered, NO_BREAK_SPACE)
+            }
+        }
+        NumberKind::Token => {
+            let decimals = if value.abs() >= 1.0 { 2 } else { small_value_decimals(value).max(2) };
+            render(&locale, value, decimals)
+        }
+        NumberKind::Sol => {
+            let decimals = if value.abs() >= 1.0 { 4 } else { 6 };
+            render(&locale, value, decimals)
+        }
+    }
+}
+
+/// `fmt_number` escaped for MarkdownV2 messages
+pub fn fmt_number_md(lang: &str, value: f64, kind: NumberKind) -> String {
+    escape(&fmt_number(lang, value, kind))
+}
+
+/// Format a timestamp in the user's time zone and date order
+pub fn fmt_datetime(lang: &str, tz: Tz, ts: DateTime<Utc>, style: DateStyle) -> String {
+    let local = ts.with_timezone(&tz);
+    let (date, short_date) = match LocaleFormat::for_lang(lang).date_order {
+        DateOrder::MonthFirst => ("%m/%d/%Y", "%m/%d"),
+        DateOrder::DayFirst('.') => ("%d.%m.%Y", "%d.%m."),
+        DateOrder::DayFirst(_) => ("%d/%m/%Y", "%d/%m"),
+    };
+
+    let pattern = match style {
+        DateStyle::Date => date.to_string(),
+        DateStyle::ShortDate => short_date.to_string(),
+        DateStyle::Time => "%H:%M %Z".to_string(),
+        DateStyle::DateTime => format!("{} %H:%M %Z", date),
+    };
+    local.format(&pattern).to_string()
+}
+
+/// `fmt_datetime` escaped for MarkdownV2 messages
+pub fn fmt_datetime_md(lang: &str, tz: Tz, ts: DateTime<Utc>, style: DateStyle) -> String {
+    escape(&fmt_datetime(lang, tz, ts, style))
+}
+
+/// Decimals needed to show a value below one with its significant digits
+fn small_value_decimals(value: f64) -> usize {
+    let value = value.abs();
+    if value == 0.0 || value >= 1.0 {
+        return 0;
+    }
+    let leading_zeros = (-value.log10()).floor() as i32;
+    (leading_zeros + SMALL_VALUE_SIGNIFICANT_DIGITS).clamp(0, MAX_DECIMALS as i32) as usize
+}
+
+fn rounds_to_zero(value: f64, decimals: usize) -> bool {
+    format!("{:.*}", decimals.min(MAX_DECIMALS), value.abs()).chars().all(|c| c == '0' || c == '.')
+}
+
+fn with_percent(locale: &LocaleFormat, number: String) -> String {
+    match locale.percent_space {
+        Some(space) => format!("{}{}%", number, space),
+        None => format!("{}%", number),
+    }
+}
+
+/// Round, group the integer part and swap in the locale's decimal separator
+fn render(locale: &LocaleFormat, value: f64, decimals: usize) -> String {
+    let fixed = format!("{:.*}", decimals.min(MAX_DECIMALS), value.abs());
+    let (integer, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+    let mut grouped = String::with_capacity(fixed.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(locale.group);
+        }
+        grouped.push(digit);
+    }
+
+    let mut out = if value < 0.0 && !rounds_to_zero(value, decimals) { "-".to_string() } else { String::new() };
+    out.push_str(&grouped);
+    if !fraction.is_empty() {
+        out.push(locale.decimal);
+        out.push_str(fraction);
+    }
+    out
+}
@@ -2,14 +2,19 @@ mod config;
 mod validation;
 pub mod formatting;
 pub mod timeout;
+pub mod i18n;
+pub mod catalog;
+pub mod price_input;
 
-pub use config::{Config, NetworkType};
+pub use config::{Config, NetworkType, SessionBackend};
 pub use validation::Validator;
 pub use formatting::{
     format_market_cap, format_volume, format_sol, format_usd,
     format_percentage, format_token_amount, format_duration,
     truncate_string, format_address
 };
+pub use i18n::{fmt_number, fmt_number_md, fmt_datetime, fmt_datetime_md, lang_of, NumberKind, DateStyle, DEFAULT_LANG};
+pub use catalog::{Catalog, LANGUAGES, language_name, t, t_args};
 pub use timeout::{
     with_timeout, with_timeout_retry, TimeoutConfig, TimeoutClient,
     adaptive_timeout, OperationType
@@ -0,0 +1,152 @@
+//! Normalization for prices typed by users
+//!
+//! Shared by every path that turns typed text into a price level (alerts, stops,
+//! brackets) so a level entered in the wrong scale is questioned the same way
+//! everywhere.
+
+/// Entered and market price this many times apart (or more) are questioned
+pub const MAGNITUDE_THRESHOLD: f64 = 100.0;
+
+/// Scale suffixes accepted after a number
+const SUFFIXES: [(&str, f64); 8] = [
+    ("n", 1e-9),
+    ("u", 1e-6),
+    ("µ", 1e-6),
+    ("μ", 1e-6),
+    ("m", 1e-3),
+    ("c", 1e-2),
+    ("¢", 1e-2),
+    ("k", 1e3),
+];
+
+/// How a level fires relative to the market
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceIntent {
+    /// Fires once the price rises to the level (above alerts, take-profits)
+    Above,
+    /// Fires once the price falls to the level (below alerts, stops, buy limits)
+    Below,
+    /// Direction follows from where the level sits, so it never fires on creation
+    Either,
+}
+
+/// A number read from user input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParsedPrice {
+    pub value: f64,
+    /// The user spelled out the scale with a suffix or an exponent
+    pub explicit_scale: bool,
+}
+
+impl ParsedPrice {
+    /// A value the user picked or confirmed, which needs no further scale check
+    pub fn explicit(value: f64) -> Self {
+        Self { value, explicit_scale: true }
+    }
+}
+
+/// One reading of an ambiguous price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceCandidate {
+    pub price: f64,
+    /// Distance from the market in percent
+    pub change_pct: f64,
+}
+
+/// What to do with an entered price
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceInput {
+    /// Plausible and won't fire on creation
+    Accepted(f64),
+    /// Two or more orders of magnitude from the market; ask which reading was meant
+    Ambiguous { entered: f64, candidates: Vec<PriceCandidate> },
+    /// Would fire as soon as it's created; needs an explicit confirmation
+    TriggersImmediately { price: f64, current_price: f64 },
+    Invalid(String),
+}
+
+/// Parse `0.00002`, `$2`, `2e-5`, `20u`, `70c`, `1.5k`
+///
+/// Suffixes: `n` nano, `u`/`µ` micro, `m` milli, `c`/`¢` cents, `k` thousands.
+/// Only `.` separates decimals; digit grouping is rejected rather than guessed.
+pub fn parse_price(input: &str) -> Option<ParsedPrice> {
+    let text = input.trim().trim_start_matches('$').trim().to_lowercase();
+    if text.is_empty() || text.contains(',') {
+        return None;
+    }
+
+    let (number, multiplier) = SUFFIXES.iter()
+        .find_map(|(suffix, multiplier)| text.strip_suffix(suffix).map(|number| (number, Some(*multiplier))))
+        .unwrap_or((text.as_str(), None));
+
+    let number = number.trim();
+    let well_formed = !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | '-' | '+'))
+        && number.chars().next().is_some_and(|c| c.is_ascii_digit() || c == '.');
+    if !well_formed {
+        return None;
+    }
+
+    let value = number.parse::<f64>().ok()? * multiplier.unwrap_or(1.0);
+    (value.is_finite() && value > 0.0).then_some(ParsedPrice {
+        value,
+        explicit_scale: multiplier.is_some() || number.contains('e'),
+    })
+}
+
+/// Parse and check a typed price against the market
+pub fn normalize_price(input: &str, current_price: f64, intent: PriceIntent) -> PriceInput {
+    match parse_price(input) {
+        Some(parsed) => normalize(parsed, current_price, intent),
+        None => PriceInput::Invalid(format!(
+            "Couldn't read \"{}\" as a price: use digits with a dot, e.g. 0.00002, 2e-5 or 20u",
+            input.trim()
+        )),
+    }
+}
+
+/// Check a parsed price against the market
+///
+/// Scale is only questioned when the user didn't spell it out; a level that
+/// would fire on creation is always held for confirmation.
+pub fn normalize(parsed: ParsedPrice, current_price: f64, intent: PriceIntent) -> PriceInput {
+    if !parsed.value.is_finite() || parsed.value <= 0.0 {
+        return PriceInput::Invalid("Price must be a positive number".to_string());
+    }
+    if !current_price.is_finite() || current_price <= 0.0 {
+        // Nothing to compare against
+        return PriceInput::Accepted(parsed.value);
+    }
+
+    let ratio = parsed.value / current_price;
+    let plausible = ratio > 1.0 / MAGNITUDE_THRESHOLD && ratio < MAGNITUDE_THRESHOLD;
+    if !parsed.explicit_scale && !plausible {
+        return PriceInput::Ambiguous { entered: parsed.value, candidates: candidates(parsed.value, current_price) };
+    }
+
+    check_trigger(parsed.value, current_price, intent)
+}
+
+/// Hold a level that would fire as soon as it's created
+pub fn check_trigger(price: f64, current_price: f64, intent: PriceIntent) -> PriceInput {
+    let immediate = match intent {
+        PriceIntent::Above => current_price >= price,
+        PriceIntent::Below => current_price <= price,
+        PriceIntent::Either => false,
+    };
+    if immediate {
+        PriceInput::TriggersImmediately { price, current_price }
+    } else {
+        PriceInput::Accepted(price)
+    }
+}
+
+/// The entered value shifted by the power of ten that lands nearest the market, then the value as typed
+fn candidates(entered: f64, current_price: f64) -> Vec<PriceCandidate> {
+    let shift = (current_price / entered).log10().round() as i32;
+    let rescaled = entered * 10f64.powi(shift);
+    [rescaled, entered]
+        .into_iter()
+        .map(|price| PriceCandidate { price, change_pct: (price / current_price - 1.0) * 100.0 })
+        .collect()
+}
@@ -74,6 +74,25 @@ impl Validator {
         Ok(())
     }
     
+    /// Parse a number typed by the user
+    ///
+    /// Input is locale-agnostic: `.` is the only decimal separator and digit
+    /// grouping is rejected, whatever language the bot replies in.
+    pub fn parse_amount(input: &str) -> Result<f64> {
+        let input = input.trim().trim_end_matches('%');
+        let well_formed = !input.is_empty()
+            && input.chars().all(|c| c.is_ascii_digit() || c == '.')
+            && input.matches('.').count() <= 1;
+
+        match input.parse::<f64>() {
+            Ok(value) if well_formed && value.is_finite() => Ok(value),
+            _ => Err(BotError::validation(format!(
+                "Invalid number \"{}\": use digits with a dot as the decimal separator, e.g. 0.1",
+                input
+            ))),
+        }
+    }
+
     /// Validate a percentage value with proper bounds
     pub fn validate_percentage(percentage: f64) -> Result<()> {
         if percentage.is_nan() || percentage.is_infinite() {
@@ -103,16 +122,16 @@ impl Validator {
     /// Validate a transaction signature with proper format checking
     pub fn validate_signature(signature: &str) -> Result<()> {
         if signature.is_empty() {
-            return Err(BotError::ValidationError("Transaction signature cannot be empty".to_string()));
+            return Err(BotError::validation("Transaction signature cannot be empty"));
         }
         
         if signature.len() < 86 || signature.len() > 90 {
-            return Err(BotError::ValidationError("Invalid transaction signature length".to_string()));
+            return Err(BotError::validation("Invalid transaction signature length"));
         }
         
         // Check if it's valid base58
         bs58::decode(signature).into_vec()
-            .map_err(|_| BotError::ValidationError("Invalid transaction signature encoding".to_string()))?;
+            .map_err(|_| BotError::validation("Invalid transaction signature encoding"))?;
         
         Ok(())
     }
@@ -120,11 +139,11 @@ impl Validator {
     /// Validate session duration with bounds
     pub fn validate_session_duration(minutes: i64) -> Result<()> {
         if minutes <= 0 {
-            return Err(BotError::ValidationError("Session duration must be positive".to_string()));
+            return Err(BotError::validation("Session duration must be positive"));
         }
         
         if minutes > MAX_SESSION_DURATION_MINUTES {
-            return Err(BotError::ValidationError(
+            return Err(BotError::validation(
                 format!("Session duration cannot exceed {} minutes", MAX_SESSION_DURATION_MINUTES)
             ));
         }
@@ -148,16 +167,16 @@ impl Validator {
         let cleaned = Self::sanitize_input(symbol).to_uppercase();
         
         if cleaned.is_empty() {
-            return Err(BotError::ValidationError("Token symbol cannot be empty".into()));
+            return Err(BotError::validation("Token symbol cannot be empty"));
         }
         
         if cleaned.len() > 20 {
-            return Err(BotError::ValidationError("Token symbol too long".into()));
+            return Err(BotError::validation("Token symbol too long"));
         }
         
         // Check if symbol contains only alphanumeric characters
         if !cleaned.chars().all(|c| c.is_alphanumeric()) {
-            return Err(BotError::ValidationError("Token symbol must contain only alphanumeric characters".into()));
+            return Err(BotError::validation("Token symbol must contain only alphanumeric characters"));
         }
         
         Ok(cleaned)
@@ -168,7 +187,7 @@ impl Validator {
         const MAX_PRIORITY_FEE: u64 = 10_000_000; // 0.01 SOL
         
         if fee_lamports > MAX_PRIORITY_FEE {
-            return Err(BotError::ValidationError(
+            return Err(BotError::validation(
                 format!("Priority fee cannot exceed {} lamports", MAX_PRIORITY_FEE)
             ));
         }
@@ -179,16 +198,16 @@ impl Validator {
     /// Validate user ID format
     pub fn validate_user_id(user_id: &str) -> Result<()> {
         if user_id.is_empty() {
-            return Err(BotError::ValidationError("User ID cannot be empty".to_string()));
+            return Err(BotError::validation("User ID cannot be empty"));
         }
         
         if user_id.len() > 20 {
-            return Err(BotError::ValidationError("User ID too long".to_string()));
+            return Err(BotError::validation("User ID too long"));
         }
         
         // Check if user_id contains only digits (Telegram user IDs are numeric)
         if !user_id.chars().all(|c| c.is_ascii_digit()) {
-            return Err(BotError::ValidationError("Invalid user ID format".to_string()));
+            return Err(BotError::validation("Invalid user ID format"));
         }
         
         Ok(())
@@ -209,11 +228,11 @@ impl Validator {
     /// Validate HTTP URL format
     pub fn validate_url(url: &str) -> Result<()> {
         if url.is_empty() {
-            return Err(BotError::ValidationError("URL cannot be empty".into()));
+            return Err(BotError::validation("URL cannot be empty"));
         }
         
         if !url.starts_with("http://") && !url.starts_with("https://") {
-            return Err(BotError::ValidationError("URL must start with http:// or https://".into()));
+            return Err(BotError::validation("URL must start with http:// or https://"));
         }
         
         Ok(())
@@ -242,11 +261,11 @@ impl Validator {
         let sanitized = input.trim();
         
         if sanitized.is_empty() {
-            return Err(BotError::ValidationError("Command arguments cannot be empty".to_string()));
+            return Err(BotError::validation("Command arguments cannot be empty"));
         }
         
         if sanitized.len() > 200 {
-            return Err(BotError::ValidationError("Command arguments too long".to_string()));
+            return Err(BotError::validation("Command arguments too long"));
         }
         
         // Remove potentially dangerous characters
@@ -264,15 +283,15 @@ impl Validator {
     /// Validate rate limiting parameters
     pub fn validate_rate_limit(requests: u32, time_window_minutes: u32) -> Result<()> {
         if requests == 0 {
-            return Err(BotError::ValidationError("Request count must be positive".to_string()));
+            return Err(BotError::validation("Request count must be positive"));
         }
         
         if time_window_minutes == 0 {
-            return Err(BotError::ValidationError("Time window must be positive".to_string()));
+            return Err(BotError::validation("Time window must be positive"));
         }
         
         if requests > 1000 {
-            return Err(BotError::ValidationError("Too many requests per time window".to_string()));
+            return Err(BotError::validation("Too many requests per time window"));
         }
         
         Ok(())
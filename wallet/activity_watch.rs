@@ -0,0 +1,592 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+    UiInstruction, UiMessage, UiParsedInstruction,
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, error, info, warn};
+
+use super::signature_watch::SignatureWatcher;
+use crate::errors::{BotError, Result};
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const SIGNATURE_PREFIX_LEN: usize = 20;
+
+/// Settings for the external wallet activity watcher
+#[derive(Debug, Clone)]
+pub struct ActivityWatchConfig {
+    /// Websocket endpoint for `logsSubscribe`; polling only when unset
+    pub ws_url: Option<String>,
+    pub poll_interval: Duration,
+    /// How long an unrecognised signature is held before alerting, so a bot
+    /// submission whose signature is recorded late (second pool endpoint
+    /// confirming first) is not reported as foreign
+    pub grace_period: Duration,
+    /// How long bot-originated signatures are remembered
+    pub originated_ttl: Duration,
+    pub signatures_per_poll: usize,
+}
+
+impl Default for ActivityWatchConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: None,
+            poll_interval: Duration::seconds(20),
+            grace_period: Duration::seconds(15),
+            originated_ttl: Duration::hours(2),
+            signatures_per_poll: 20,
+        }
+    }
+}
+
+/// Net effect of one confirmed transaction on a watched wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletTransaction {
+    pub signature: String,
+    pub wallet: String,
+    pub slot: u64,
+    pub block_time: Option<DateTime<Utc>>,
+    /// Whether the wallet signed the transaction
+    pub wallet_signed: bool,
+    /// Net SOL change for the wallet, fee included
+    pub sol_change: f64,
+    pub token_changes: Vec<TokenChange>,
+    /// Accounts that gained what the wallet lost (or vice versa)
+    pub counterparties: Vec<String>,
+    /// Programs invoked, outermost first
+    pub programs: Vec<String>,
+    pub failed: bool,
+}
+
+/// Token balance change for a watched wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenChange {
+    pub mint: String,
+    pub change: f64,
+}
+
+/// How a confirmed transaction relates to the bot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityClass {
+    /// Submitted by the bot itself
+    Originated,
+    /// Inbound-only transfer; goes to reconciliation, not to the user
+    Deposit,
+    /// Anything else the bot did not send
+    Foreign,
+}
+
+/// High-priority alert for activity the bot did not originate
+#[derive(Debug, Clone)]
+pub struct WalletActivityAlert {
+    pub user_id: i64,
+    pub transaction: WalletTransaction,
+    pub message: String,
+}
+
+/// Inbound deposit handed to balance reconciliation
+#[derive(Debug, Clone)]
+pub struct DepositNotice {
+    pub user_id: i64,
+    pub transaction: WalletTransaction,
+}
+
+/// One-tap actions attached to an activity alert
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActivityAction {
+    LockTrading,
+    UnlockTrading,
+    MarkExpected(String),
+}
+
+#[derive(Debug, Clone)]
+struct WatchedWallet {
+    user_id: i64,
+    last_signature: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct HeldActivity {
+    user_id: i64,
+    transaction: WalletTransaction,
+    seen_at: DateTime<Utc>,
+}
+
+/// Watches bot wallets for transactions the bot did not send
+#[derive(Clone)]
+pub struct WalletActivityWatcher {
+    config: ActivityWatchConfig,
+    signatures: SignatureWatcher,
+    watched: Arc<RwLock<HashMap<String, WatchedWallet>>>,
+    originated: Arc<RwLock<HashMap<String, DateTime<Utc>>>>,
+    processed: Arc<RwLock<HashSet<String>>>,
+    held: Arc<RwLock<HashMap<String, HeldActivity>>>,
+    expected: Arc<RwLock<HashSet<String>>>,
+    locked_users: Arc<RwLock<HashSet<i64>>>,
+    alert_tx: broadcast::Sender<WalletActivityAlert>,
+    deposit_tx: broadcast::Sender<DepositNotice>,
+}
+
+impl WalletActivityWatcher {
+    pub fn new(rpc_client: Arc<RpcClient>, config: ActivityWatchConfig) -> Self {
+        info!("👁️ Initializing wallet activity watcher");
+
+        let (alert_tx, _) = broadcast::channel(256);
+        let (deposit_tx, _) = broadcast::channel(256);
+
+        Self {
+            signatures: SignatureWatcher::new(rpc_client, config.ws_url.clone(), config.signatures_per_poll),
+            config,
+            watched: Arc::new(RwLock::new(HashMap::new())),
+            originated: Arc::new(RwLock::new(HashMap::new())),
+            processed: Arc::new(RwLock::new(HashSet::new())),
+            held: Arc::new(RwLock::new(HashMap::new())),
+            expected: Arc::new(RwLock::new(HashSet::new())),
+            locked_users: Arc::new(RwLock::new(HashSet::new())),
+            alert_tx,
+            deposit_tx,
+        }
+    }
+
+    /// Alerts for foreign activity, for the Telegram forwarder
+    pub fn subscribe_alerts(&self) -> broadcast::Receiver<WalletActivityAlert> {
+        self.alert_tx.subscribe()
+    }
+
+    /// Inbound deposits, for balance reconciliation
+    pub fn subscribe_deposits(&self) -> broadcast::Receiver<DepositNotice> {
+        self.deposit_tx.subscribe()
+    }
+
+    /// Start watching a user's bot wallet
+    pub async fn watch(&self, user_id: i64, wallet: &str) -> Result<()> {
+        Pubkey::from_str(wallet)
+            .map_err(|_| BotError::validation(format!("Invalid wallet address: {}", wallet)))?;
+
+        let newly_added = {
+            let mut watched = self.watched.write().await;
+            let added = !watched.contains_key(wallet);
+            watched.entry(wallet.to_string())
+                .or_insert(WatchedWallet { user_id, last_signature: None })
+                .user_id = user_id;
+            added
+        };
+
+        if newly_added {
+            info!("👁️ Watching wallet {} for user {}", wallet, user_id);
+            // Only activity from now on matters; skip the existing history
+            if let Err(e) = self.poll_wallet(wallet, true).await {
+                warn!("👁️ Initial history fetch for {} failed: {}", wallet, e);
+            }
+            if self.config.ws_url.is_some() {
+                self.spawn_log_subscription(wallet.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn unwatch(&self, wallet: &str) {
+        self.watched.write().await.remove(wallet);
+    }
+
+    /// Record a signature the bot is about to broadcast
+    ///
+    /// Call before sending: the signature is fixed by the signed transaction,
+    /// so it matches whichever pool endpoint lands it.
+    pub async fn record_originated(&self, signature: &str) {
+        self.originated.write().await.insert(signature.to_string(), Utc::now());
+        // Late recording releases a held alert for the same transaction
+        self.held.write().await.remove(signature);
+    }
+
+    pub async fn is_originated(&self, signature: &str) -> bool {
+        self.originated.read().await.contains_key(signature)
+    }
+
+    /// Decide how a confirmed transaction relates to the bot
+    pub async fn classify(&self, transaction: &WalletTransaction) -> ActivityClass {
+        if self.is_originated(&transaction.signature).await {
+            return ActivityClass::Originated;
+        }
+        Self::classify_foreign(transaction)
+    }
+
+    /// Classification for a transaction the bot did not send
+    pub fn classify_foreign(transaction: &WalletTransaction) -> ActivityClass {
+        let outflow = transaction.sol_change < 0.0 || transaction.token_changes.iter().any(|c| c.change < 0.0);
+        if !transaction.wallet_signed && !outflow {
+            ActivityClass::Deposit
+        } else {
+            ActivityClass::Foreign
+        }
+    }
+
+    /// Route a confirmed transaction: ignore ours, reconcile deposits, hold the rest
+    pub async fn ingest(&self, user_id: i64, transaction: WalletTransaction, now: DateTime<Utc>) {
+        if !self.processed.write().await.insert(transaction.signature.clone()) {
+            return;
+        }
+
+        match self.classify(&transaction).await {
+            ActivityClass::Originated => {
+                debug!("👁️ {} was sent by the bot", transaction.signature);
+            }
+            ActivityClass::Deposit => {
+                debug!("👁️ Deposit {} to {} queued for reconciliation", transaction.signature, transaction.wallet);
+                let _ = self.deposit_tx.send(DepositNotice { user_id, transaction });
+            }
+            ActivityClass::Foreign => {
+                self.held.write().await.insert(
+                    transaction.signature.clone(),
+                    HeldActivity { user_id, transaction, seen_at: now },
+                );
+            }
+        }
+    }
+
+    /// Alert on held transactions that are still unrecognised after the grace period
+    pub async fn flush_held(&self, now: DateTime<Utc>) -> Vec<WalletActivityAlert> {
+        let due: Vec<HeldActivity> = {
+            let mut held = self.held.write().await;
+            let due_ids: Vec<String> = held.iter()
+                .filter(|(_, h)| now - h.seen_at >= self.config.grace_period)
+                .map(|(signature, _)| signature.clone())
+                .collect();
+            due_ids.iter().filter_map(|signature| held.remove(signature)).collect()
+        };
+
+        let mut alerts = Vec::new();
+        for held in due {
+            if self.is_originated(&held.transaction.signature).await {
+                continue;
+            }
+
+            let alert = WalletActivityAlert {
+                user_id: held.user_id,
+                message: Self::describe(&held.transaction),
+                transaction: held.transaction,
+            };
+            warn!("🚨 Foreign activity on {} for user {}: {}",
+                alert.transaction.wallet, alert.user_id, alert.transaction.signature);
+            let _ = self.alert_tx.send(alert.clone());
+            alerts.push(alert);
+        }
+
+        alerts
+    }
+
+    /// Alert text: what moved, to whom, and through which programs
+    pub fn describe(transaction: &WalletTransaction) -> String {
+        let mut lines = vec![
+            "🚨 Wallet activity not sent by the bot".to_string(),
+            format!("Wallet: {}", short_address(&transaction.wallet)),
+        ];
+
+        if transaction.failed {
+            lines.push("Status: failed on-chain (fees were still paid)".to_string());
+        }
+
+        let counterparty = transaction.counterparties.first()
+            .map(|address| format!(" to {}", short_address(address)))
+            .unwrap_or_default();
+
+        if transaction.sol_change > 0.0 {
+            lines.push(format!("Received {:.4} SOL", transaction.sol_change));
+        } else if transaction.sol_change < 0.0 {
+            lines.push(format!("Sent {:.4} SOL{}", -transaction.sol_change, counterparty));
+        }
+
+        for change in &transaction.token_changes {
+            if change.change > 0.0 {
+                lines.push(format!("Received {} {}", format_amount(change.change), short_address(&change.mint)));
+            } else if change.change < 0.0 {
+                lines.push(format!("Sent {} {}{}", format_amount(-change.change), short_address(&change.mint), counterparty));
+            }
+        }
+
+        let programs: Vec<String> = transaction.programs.iter()
+            .map(|program| program_label(program))
+            .collect();
+        if !programs.is_empty() {
+            lines.push(format!("Interacted with: {}", programs.join(", ")));
+        }
+
+        lines.push(String::new());
+        lines.push("If this wasn't you, lock trading now and move remaining funds to a new wallet.".to_string());
+        lines.join("\n")
+    }
+
+    /// Callback data for the alert buttons
+    ///
+    /// Signatures are cut to a prefix to fit Telegram's 64-byte callback limit.
+    pub fn action_data(action: &ActivityAction) -> String {
+        match action {
+            ActivityAction::LockTrading => "wact:lock".to_string(),
+            ActivityAction::UnlockTrading => "wact:unlock".to_string(),
+            ActivityAction::MarkExpected(signature) => {
+                format!("wact:ok:{}", &signature[..signature.len().min(SIGNATURE_PREFIX_LEN)])
+            }
+        }
+    }
+
+    pub fn parse_action(data: &str) -> Option<ActivityAction> {
+        let rest = data.strip_prefix("wact:")?;
+        match rest.split_once(':') {
+            Some(("ok", signature)) if !signature.is_empty() => Some(ActivityAction::MarkExpected(signature.to_string())),
+            None if rest == "lock" => Some(ActivityAction::LockTrading),
+            None if rest == "unlock" => Some(ActivityAction::UnlockTrading),
+            _ => None,
+        }
+    }
+
+    /// Apply an alert action for `user_id`
+    pub async fn apply_action(&self, user_id: i64, action: &ActivityAction) {
+        match action {
+            ActivityAction::LockTrading => self.lock_trading(user_id).await,
+            ActivityAction::UnlockTrading => self.unlock_trading(user_id).await,
+            ActivityAction::MarkExpected(signature) => {
+                info!("👁️ User {} marked {} as expected", user_id, signature);
+                self.expected.write().await.insert(signature.clone());
+            }
+        }
+    }
+
+    pub async fn lock_trading(&self, user_id: i64) {
+        warn!("🔒 Trading locked for user {}", user_id);
+        self.locked_users.write().await.insert(user_id);
+    }
+
+    pub async fn unlock_trading(&self, user_id: i64) {
+        info!("🔓 Trading unlocked for user {}", user_id);
+        self.locked_users.write().await.remove(&user_id);
+    }
+
+    pub async fn is_trading_locked(&self, user_id: i64) -> bool {
+        self.locked_users.read().await.contains(&user_id)
+    }
+
+    pub async fn is_expected(&self, signature: &str) -> bool {
+        self.expected.read().await.iter().any(|prefix| signature.starts_with(prefix.as_str()))
+    }
+
+    /// Start the polling fallback and the held-alert flush loop
+    pub async fn start(&self) {
+        let watcher = self.clone();
+        let interval_secs = self.config.poll_interval.num_seconds().max(5) as u64;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+
+                let wallets: Vec<String> = watcher.watched.read().await.keys().cloned().collect();
+                for wallet in wallets {
+                    if let Err(e) = watcher.poll_wallet(&wallet, false).await {
+                        error!("👁️ Polling {} failed: {}", wallet, e);
+                    }
+                }
+
+                watcher.flush_held(Utc::now()).await;
+                watcher.prune(Utc::now()).await;
+            }
+        });
+    }
+
+    /// Fetch signatures newer than the last one seen and ingest their transactions
+    async fn poll_wallet(&self, wallet: &str, baseline_only: bool) -> Result<()> {
+        let Some(watched) = self.watched.read().await.get(wallet).cloned() else { return Ok(()) };
+        let signatures = self.signatures.signatures_since(wallet, watched.last_signature.as_deref()).await?;
+
+        if let Some(newest) = signatures.first() {
+            if let Some(entry) = self.watched.write().await.get_mut(wallet) {
+                entry.last_signature = Some(newest.signature.clone());
+            }
+        }
+        if baseline_only {
+            return Ok(());
+        }
+
+        // Oldest first so alerts read in order
+        for status in signatures.iter().rev() {
+            self.fetch_and_ingest(watched.user_id, wallet, &status.signature).await;
+        }
+        Ok(())
+    }
+
+    async fn fetch_and_ingest(&self, user_id: i64, wallet: &str, signature: &str) {
+        if self.processed.read().await.contains(signature) {
+            return;
+        }
+        match self.signatures.fetch_transaction(signature).await {
+            Ok(encoded) => match WalletTransaction::from_encoded(signature, wallet, &encoded) {
+                Some(transaction) => self.ingest(user_id, transaction, Utc::now()).await,
+                None => debug!("👁️ {} has no readable balance changes for {}", signature, wallet),
+            },
+            Err(e) => warn!("👁️ Could not fetch {}: {}", signature, e),
+        }
+    }
+
+    /// Push notifications for a wallet; ends quietly and leaves polling in charge on failure
+    fn spawn_log_subscription(&self, wallet: String) {
+        if self.config.ws_url.is_none() {
+            return;
+        }
+        let watcher = self.clone();
+
+        tokio::spawn(async move {
+            let mut notifications = match watcher.signatures.subscribe_logs(&wallet).await {
+                Ok(notifications) => notifications,
+                Err(e) => {
+                    warn!("👁️ Log subscription unavailable for {}, polling only: {}", wallet, e);
+                    return;
+                }
+            };
+
+            while let Some(logs) = notifications.recv().await {
+                let Some(user_id) = watcher.watched.read().await.get(&wallet).map(|w| w.user_id) else { break };
+                watcher.fetch_and_ingest(user_id, &wallet, &logs.signature).await;
+            }
+
+            debug!("👁️ Log subscription for {} ended", wallet);
+        });
+    }
+
+    async fn prune(&self, now: DateTime<Utc>) {
+        let ttl = self.config.originated_ttl;
+        self.originated.write().await.retain(|_, recorded_at| now - *recorded_at < ttl);
+
+        let mut processed = self.processed.write().await;
+        if processed.len() > 10_000 {
+            processed.clear();
+        }
+    }
+}
+
+impl WalletTransaction {
+    /// Reduce a `jsonParsed` transaction to its effect on `wallet`
+    pub fn from_encoded(
+        signature: &str,
+        wallet: &str,
+        encoded: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Option<Self> {
+        let meta = encoded.transaction.meta.as_ref()?;
+        let EncodedTransaction::Json(ui_transaction) = &encoded.transaction.transaction else { return None };
+        let UiMessage::Parsed(message) = &ui_transaction.message else { return None };
+
+        let keys: Vec<&str> = message.account_keys.iter().map(|k| k.pubkey.as_str()).collect();
+        let wallet_index = keys.iter().position(|k| *k == wallet);
+        let wallet_signed = message.account_keys.iter().any(|k| k.pubkey == wallet && k.signer);
+
+        let lamport_delta = |index: usize| -> i128 {
+            meta.post_balances.get(index).copied().unwrap_or(0) as i128
+                - meta.pre_balances.get(index).copied().unwrap_or(0) as i128
+        };
+        let sol_change = wallet_index.map(|i| lamport_delta(i) as f64 / LAMPORTS_PER_SOL).unwrap_or(0.0);
+
+        // Token balances are keyed by account index; sum them per (owner, mint)
+        let mut token_deltas: HashMap<(String, String), f64> = HashMap::new();
+        let token_sets = [(&meta.pre_token_balances, -1.0), (&meta.post_token_balances, 1.0)];
+        for (balances, sign) in token_sets {
+            if let OptionSerializer::Some(balances) = balances {
+                for balance in balances {
+                    let owner = match &balance.owner {
+                        OptionSerializer::Some(owner) => owner.clone(),
+                        _ => keys.get(balance.account_index as usize).map(|k| k.to_string()).unwrap_or_default(),
+                    };
+                    let amount = balance.ui_token_amount.ui_amount.unwrap_or(0.0);
+                    *token_deltas.entry((owner, balance.mint.clone())).or_insert(0.0) += sign * amount;
+                }
+            }
+        }
+
+        let token_changes: Vec<TokenChange> = token_deltas.iter()
+            .filter(|((owner, _), change)| owner == wallet && change.abs() > f64::EPSILON)
+            .map(|((_, mint), change)| TokenChange { mint: mint.clone(), change: *change })
+            .collect();
+
+        if sol_change == 0.0 && token_changes.is_empty() && !wallet_signed {
+            return None;
+        }
+
+        // Counterparties move opposite to the wallet
+        let sending = sol_change < 0.0 || token_changes.iter().any(|c| c.change < 0.0);
+        let mut counterparties: Vec<String> = Vec::new();
+        for (index, key) in keys.iter().enumerate() {
+            let delta = lamport_delta(index);
+            if Some(index) != wallet_index && ((sending && delta > 0) || (!sending && delta < 0)) {
+                counterparties.push(key.to_string());
+            }
+        }
+        for ((owner, mint), change) in &token_deltas {
+            let opposite = if sending { *change > 0.0 } else { *change < 0.0 };
+            let moved_mint = token_changes.iter().any(|c| &c.mint == mint);
+            if owner != wallet && opposite && moved_mint && !counterparties.contains(owner) {
+                counterparties.insert(0, owner.clone());
+            }
+        }
+
+        let mut programs: Vec<String> = Vec::new();
+        for instruction in &message.instructions {
+            let program = match instruction {
+                UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => parsed.program_id.clone(),
+                UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) => decoded.program_id.clone(),
+                UiInstruction::Compiled(compiled) => keys.get(compiled.program_id_index as usize)
+                    .map(|k| k.to_string())
+                    .unwrap_or_default(),
+            };
+            if !program.is_empty() && !programs.contains(&program) {
+                programs.push(program);
+            }
+        }
+
+        Some(Self {
+            signature: signature.to_string(),
+            wallet: wallet.to_string(),
+            slot: encoded.slot,
+            block_time: encoded.block_time.and_then(|t| DateTime::from_timestamp(t, 0)),
+            wallet_signed,
+            sol_change,
+            token_changes,
+            counterparties,
+            programs,
+            failed: meta.err.is_some(),
+        })
+    }
+}
+
+fn short_address(address: &str) -> String {
+    if address.len() > 12 {
+        format!("{}…{}", &address[..4], &address[address.len() - 4..])
+    } else {
+        address.to_string()
+    }
+}
+
+fn format_amount(amount: f64) -> String {
+    if amount >= 1.0 {
+        format!("{:.2}", amount)
+    } else {
+        format!("{:.6}", amount)
+    }
+}
+
+/// Friendly name for well-known programs
+fn program_label(program_id: &str) -> String {
+    match program_id {
+        "11111111111111111111111111111111" => "System Program".to_string(),
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" => "Token Program".to_string(),
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb" => "Token-2022".to_string(),
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL" => "Associated Token Account".to_string(),
+        "ComputeBudget111111111111111111111111111111" => "Compute Budget".to_string(),
+        "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4" => "Jupiter".to_string(),
+        "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P" => "Pump.fun".to_string(),
+        other => short_address(other),
+    }
+}
@@ -0,0 +1,391 @@
+use chrono::{DateTime, Duration, Utc};
+use solana_account_decoder::UiAccountData;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::{
+    analytics::{FeeKind, FeeLedger},
+    errors::{BotError, Result},
+    trading::{DCAStatus, DCAStrategy, Order, OrderStatus},
+};
+
+pub const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+/// `CloseAccount` in both token programs
+const CLOSE_ACCOUNT_INSTRUCTION: u8 = 9;
+
+/// Settings for empty token account cleanup
+#[derive(Debug, Clone)]
+pub struct AtaCleanupConfig {
+    /// Close instructions per transaction, well inside the size limit
+    pub max_closes_per_tx: usize,
+    /// Automatic mode only closes accounts seen empty for at least this long
+    pub auto_close_after: Duration,
+    /// Token-2022 account extensions known to be safe to close; anything else is left alone
+    pub closable_extensions: HashSet<String>,
+}
+
+impl Default for AtaCleanupConfig {
+    fn default() -> Self {
+        Self {
+            max_closes_per_tx: 10,
+            auto_close_after: Duration::days(14),
+            closable_extensions: ["immutableOwner", "transferFeeAmount"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// A token account owned by the user, as the RPC reports it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenAccountSnapshot {
+    pub address: Pubkey,
+    pub mint: String,
+    pub program_id: Pubkey,
+    /// Raw token amount
+    pub amount: u64,
+    /// Rent held by the account
+    pub lamports: u64,
+    pub is_frozen: bool,
+    /// Token-2022 account extension names
+    pub extensions: Vec<String>,
+    /// Transfer fees withheld in the account, which block closing
+    pub withheld_amount: u64,
+}
+
+impl TokenAccountSnapshot {
+    /// Build from a jsonParsed token account
+    pub fn from_parsed(address: &str, lamports: u64, program_id: Pubkey, parsed: &serde_json::Value) -> Option<Self> {
+        let info = parsed.get("info")?;
+        let extensions = info.get("extensions").and_then(|e| e.as_array()).cloned().unwrap_or_default();
+        let withheld_amount = extensions.iter()
+            .filter(|ext| ext.get("extension").and_then(|e| e.as_str()) == Some("transferFeeAmount"))
+            .filter_map(|ext| ext.pointer("/state/withheldAmount").and_then(|a| a.as_u64()))
+            .sum();
+
+        Some(Self {
+            address: Pubkey::from_str(address).ok()?,
+            mint: info.get("mint")?.as_str()?.to_string(),
+            program_id,
+            amount: info.pointer("/tokenAmount/amount")?.as_str()?.parse().ok()?,
+            lamports,
+            is_frozen: info.get("state").and_then(|s| s.as_str()) == Some("frozen"),
+            extensions: extensions.iter()
+                .filter_map(|ext| ext.get("extension").and_then(|e| e.as_str()).map(str::to_string))
+                .collect(),
+            withheld_amount,
+        })
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.program_id.to_string() == TOKEN_2022_PROGRAM_ID
+    }
+}
+
+/// Why an empty account was left open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    ActiveOrder,
+    DcaStrategy,
+    Watchlist,
+    Frozen,
+    WithheldTransferFees,
+    /// Token-2022 extension not on the closable list
+    UnlistedExtension(String),
+}
+
+impl SkipReason {
+    pub fn label(&self) -> String {
+        match self {
+            SkipReason::ActiveOrder => "active order".to_string(),
+            SkipReason::DcaStrategy => "DCA strategy".to_string(),
+            SkipReason::Watchlist => "watchlist".to_string(),
+            SkipReason::Frozen => "frozen".to_string(),
+            SkipReason::WithheldTransferFees => "withheld transfer fees".to_string(),
+            SkipReason::UnlistedExtension(ext) => format!("Token-2022 extension {}", ext),
+        }
+    }
+}
+
+/// Mints whose accounts stay open even when empty, to avoid close/reopen churn
+#[derive(Debug, Clone, Default)]
+pub struct CleanupExclusions {
+    order_mints: HashSet<String>,
+    dca_mints: HashSet<String>,
+    watchlist_mints: HashSet<String>,
+}
+
+impl CleanupExclusions {
+    /// Orders that can still execute
+    pub fn with_orders(mut self, orders: &[Order]) -> Self {
+        self.order_mints.extend(orders.iter()
+            .filter(|order| matches!(
+                order.status,
+                OrderStatus::Pending | OrderStatus::Active | OrderStatus::Triggered | OrderStatus::PartiallyFilled
+            ))
+            .map(|order| order.token_mint.clone()));
+        self
+    }
+
+    /// Both legs of strategies that will run again
+    pub fn with_dca(mut self, strategies: &[DCAStrategy]) -> Self {
+        self.dca_mints.extend(strategies.iter()
+            .filter(|strategy| matches!(strategy.status, DCAStatus::Active | DCAStatus::Paused))
+            .flat_map(|strategy| [strategy.input_token.clone(), strategy.output_token.clone()]));
+        self
+    }
+
+    pub fn with_watchlist<I: IntoIterator<Item = String>>(mut self, mints: I) -> Self {
+        self.watchlist_mints.extend(mints);
+        self
+    }
+
+    pub fn reason(&self, mint: &str) -> Option<SkipReason> {
+        if self.order_mints.contains(mint) {
+            Some(SkipReason::ActiveOrder)
+        } else if self.dca_mints.contains(mint) {
+            Some(SkipReason::DcaStrategy)
+        } else if self.watchlist_mints.contains(mint) {
+            Some(SkipReason::Watchlist)
+        } else {
+            None
+        }
+    }
+}
+
+/// Empty accounts to close and those deliberately left open
+#[derive(Debug, Clone, Default)]
+pub struct CleanupPlan {
+    pub closable: Vec<TokenAccountSnapshot>,
+    pub skipped: Vec<(TokenAccountSnapshot, SkipReason)>,
+}
+
+impl CleanupPlan {
+    /// Sort a wallet's token accounts into closable and skipped; non-empty accounts are ignored
+    pub fn build(accounts: &[TokenAccountSnapshot], exclusions: &CleanupExclusions, config: &AtaCleanupConfig) -> Self {
+        let mut plan = Self::default();
+
+        for account in accounts.iter().filter(|account| account.amount == 0) {
+            let reason = if account.is_frozen {
+                Some(SkipReason::Frozen)
+            } else if account.withheld_amount > 0 {
+                Some(SkipReason::WithheldTransferFees)
+            } else if let Some(ext) = account.extensions.iter().find(|ext| !config.closable_extensions.contains(*ext)) {
+                Some(SkipReason::UnlistedExtension(ext.clone()))
+            } else {
+                exclusions.reason(&account.mint)
+            };
+
+            match reason {
+                Some(reason) => plan.skipped.push((account.clone(), reason)),
+                None => plan.closable.push(account.clone()),
+            }
+        }
+        plan
+    }
+
+    pub fn reclaimable_lamports(&self) -> u64 {
+        self.closable.iter().map(|account| account.lamports).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.closable.is_empty()
+    }
+
+    /// Closable accounts grouped per transaction
+    pub fn batches(&self, max_per_tx: usize) -> Vec<&[TokenAccountSnapshot]> {
+        self.closable.chunks(max_per_tx.max(1)).collect()
+    }
+
+    /// Unsigned transactions closing every closable account, rent returned to the owner
+    pub fn build_transactions(&self, owner: &Pubkey, recent_blockhash: Hash, max_per_tx: usize) -> Vec<Transaction> {
+        self.batches(max_per_tx).into_iter()
+            .map(|batch| {
+                let instructions: Vec<Instruction> = batch.iter()
+                    .map(|account| close_account_instruction(&account.program_id, &account.address, owner, owner))
+                    .collect();
+                let mut message = Message::new(&instructions, Some(owner));
+                message.recent_blockhash = recent_blockhash;
+                Transaction::new_unsigned(message)
+            })
+            .collect()
+    }
+}
+
+/// `CloseAccount` for either token program
+pub fn close_account_instruction(program_id: &Pubkey, account: &Pubkey, destination: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![CLOSE_ACCOUNT_INSTRUCTION],
+    }
+}
+
+/// Signs and lands a cleanup transaction for the user; returns its signature
+#[async_trait::async_trait]
+pub trait CleanupSubmitter: Send + Sync {
+    async fn submit(&self, user_id: i64, transaction: Transaction) -> Result<String>;
+}
+
+/// What a cleanup run closed
+#[derive(Debug, Clone, Default)]
+pub struct CleanupOutcome {
+    pub closed: usize,
+    pub reclaimed_lamports: u64,
+    pub signatures: Vec<String>,
+    pub failed_batches: usize,
+}
+
+/// Finds and closes empty token accounts
+pub struct AtaJanitor {
+    rpc_client: Arc<RpcClient>,
+    config: AtaCleanupConfig,
+    /// First time each account was seen empty
+    empty_since: RwLock<HashMap<Pubkey, DateTime<Utc>>>,
+    /// Users who opted into automatic cleanup, with their wallet
+    auto_users: RwLock<HashMap<i64, Pubkey>>,
+}
+
+impl AtaJanitor {
+    pub fn new(rpc_client: Arc<RpcClient>, config: AtaCleanupConfig) -> Self {
+        Self {
+            rpc_client,
+            config,
+            empty_since: RwLock::new(HashMap::new()),
+            auto_users: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &AtaCleanupConfig {
+        &self.config
+    }
+
+    pub fn rpc_client(&self) -> Arc<RpcClient> {
+        self.rpc_client.clone()
+    }
+
+    /// Token accounts of both token programs owned by the wallet
+    pub async fn scan(&self, owner: &Pubkey) -> Result<Vec<TokenAccountSnapshot>> {
+        let mut snapshots = Vec::new();
+
+        for program in [SPL_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+            let program_id = Pubkey::from_str(program)
+                .map_err(|e| BotError::internal(format!("Invalid token program id: {}", e)))?;
+            let accounts = self.rpc_client
+                .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(program_id))
+                .await
+                .map_err(|e| BotError::internal(format!("Token account scan failed: {}", e)))?;
+
+            for keyed in accounts {
+                let UiAccountData::Json(parsed) = &keyed.account.data else { continue };
+                match TokenAccountSnapshot::from_parsed(&keyed.pubkey, keyed.account.lamports, program_id, &parsed.parsed) {
+                    Some(snapshot) => snapshots.push(snapshot),
+                    None => debug!("🧹 Skipping unparseable token account {}", keyed.pubkey),
+                }
+            }
+        }
+
+        self.note_empty(&snapshots, Utc::now()).await;
+        Ok(snapshots)
+    }
+
+    /// Track when accounts became empty; accounts that refilled or vanished are forgotten
+    pub async fn note_empty(&self, accounts: &[TokenAccountSnapshot], now: DateTime<Utc>) {
+        let mut empty_since = self.empty_since.write().await;
+        for account in accounts {
+            if account.amount == 0 {
+                empty_since.entry(account.address).or_insert(now);
+            } else {
+                empty_since.remove(&account.address);
+            }
+        }
+    }
+
+    /// Only the closable accounts that have been empty long enough for automatic mode
+    pub async fn auto_plan(&self, plan: CleanupPlan, now: DateTime<Utc>) -> CleanupPlan {
+        let empty_since = self.empty_since.read().await;
+        let closable = plan.closable.into_iter()
+            .filter(|account| {
+                empty_since.get(&account.address)
+                    .map(|since| now - *since >= self.config.auto_close_after)
+                    .unwrap_or(false)
+            })
+            .collect();
+        CleanupPlan { closable, skipped: plan.skipped }
+    }
+
+    /// Close the planned accounts batch by batch, crediting reclaimed rent to the fee ledger
+    ///
+    /// A failed batch is reported and the remaining batches still run.
+    pub async fn execute(
+        &self,
+        user_id: i64,
+        owner: &Pubkey,
+        plan: &CleanupPlan,
+        recent_blockhash: Hash,
+        submitter: &dyn CleanupSubmitter,
+        ledger: &FeeLedger,
+    ) -> CleanupOutcome {
+        let mut outcome = CleanupOutcome::default();
+        let max_per_tx = self.config.max_closes_per_tx;
+        let transactions = plan.build_transactions(owner, recent_blockhash, max_per_tx);
+
+        for (batch, transaction) in plan.batches(max_per_tx).into_iter().zip(transactions) {
+            let rent: u64 = batch.iter().map(|account| account.lamports).sum();
+            match submitter.submit(user_id, transaction).await {
+                Ok(signature) => {
+                    ledger.record(user_id, FeeKind::RentReclaimed, rent, Some(&signature)).await;
+                    let mut empty_since = self.empty_since.write().await;
+                    for account in batch {
+                        empty_since.remove(&account.address);
+                    }
+                    outcome.closed += batch.len();
+                    outcome.reclaimed_lamports += rent;
+                    outcome.signatures.push(signature);
+                }
+                Err(e) => {
+                    warn!("🧹 Cleanup batch of {} accounts failed for user {}: {}", batch.len(), user_id, e);
+                    outcome.failed_batches += 1;
+                }
+            }
+        }
+
+        info!("🧹 User {} closed {} empty token accounts, reclaimed {} lamports",
+            user_id, outcome.closed, outcome.reclaimed_lamports);
+        outcome
+    }
+
+    pub async fn set_auto(&self, user_id: i64, wallet: Option<Pubkey>) {
+        let mut auto_users = self.auto_users.write().await;
+        match wallet {
+            Some(wallet) => auto_users.insert(user_id, wallet),
+            None => auto_users.remove(&user_id),
+        };
+    }
+
+    pub async fn auto_users(&self) -> Vec<(i64, Pubkey)> {
+        self.auto_users.read().await.iter().map(|(user, wallet)| (*user, *wallet)).collect()
+    }
+
+    pub async fn latest_blockhash(&self) -> Result<Hash> {
+        self.rpc_client.get_latest_blockhash().await
+            .map_err(|e| BotError::internal(format!("Failed to fetch blockhash: {}", e)).into())
+    }
+}
@@ -0,0 +1,447 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::{
+    analytics::{FeeKind, FeeLedger},
+    api::{ClaimRecord, ClaimTransactionRequest},
+    errors::{BotError, Result},
+};
+
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Who covers a claim when the recipient has no SOL; the sender picks at send time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SponsorMode {
+    /// The sponsor wallet pays and the cost is booked to the sender
+    #[default]
+    Sponsor,
+    /// The sponsor wallet fronts the fee and the claim repays it; SOL sends only
+    DeductFromAmount,
+}
+
+impl SponsorMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "sponsor" | "sponsored" => Some(Self::Sponsor),
+            "deduct" | "deducted" => Some(Self::DeductFromAmount),
+            _ => None,
+        }
+    }
+}
+
+/// Sponsor wallet and the limits on what it pays
+#[derive(Debug, Clone)]
+pub struct ClaimSponsorConfig {
+    /// No sponsored or deducted claims without one
+    pub sponsor_wallet: Option<Pubkey>,
+    /// Signature fee plus a priority margin
+    pub network_fee_lamports: u64,
+    /// Rent for the recipient's token account on non-SOL claims
+    pub token_account_rent_lamports: u64,
+    pub max_per_claim_lamports: u64,
+    /// Spend across all claims per UTC day
+    pub daily_budget_lamports: u64,
+    pub claims_per_recipient_per_hour: usize,
+}
+
+impl Default for ClaimSponsorConfig {
+    fn default() -> Self {
+        Self {
+            sponsor_wallet: None,
+            network_fee_lamports: 10_000,
+            token_account_rent_lamports: 2_039_280,
+            max_per_claim_lamports: 3_000_000,
+            daily_budget_lamports: 250_000_000,
+            claims_per_recipient_per_hour: 3,
+        }
+    }
+}
+
+impl ClaimSponsorConfig {
+    /// `CLAIM_SPONSOR_WALLET`, `CLAIM_SPONSOR_MAX_PER_CLAIM_LAMPORTS`, `CLAIM_SPONSOR_DAILY_BUDGET_LAMPORTS`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        config.sponsor_wallet = std::env::var("CLAIM_SPONSOR_WALLET").ok().and_then(|w| Pubkey::from_str(&w).ok());
+        if let Some(cap) = std::env::var("CLAIM_SPONSOR_MAX_PER_CLAIM_LAMPORTS").ok().and_then(|c| c.parse().ok()) {
+            config.max_per_claim_lamports = cap;
+        }
+        if let Some(budget) = std::env::var("CLAIM_SPONSOR_DAILY_BUDGET_LAMPORTS").ok().and_then(|b| b.parse().ok()) {
+            config.daily_budget_lamports = budget;
+        }
+        config
+    }
+
+    /// Fee plus token account rent, which native SOL claims don't need
+    pub fn claim_cost(&self, token_mint: &str) -> u64 {
+        if token_mint == WSOL_MINT {
+            self.network_fee_lamports
+        } else {
+            self.network_fee_lamports + self.token_account_rent_lamports
+        }
+    }
+}
+
+/// What the sender committed to when creating the link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendTerms {
+    pub send_id: String,
+    pub sender_user_id: i64,
+    pub token_mint: String,
+    /// In the token's smallest unit
+    pub amount: u64,
+    pub mode: SponsorMode,
+}
+
+/// Sponsorship shown to the sender before the link goes out
+#[derive(Debug, Clone, PartialEq)]
+pub struct SponsorQuote {
+    pub mode: SponsorMode,
+    pub cost_lamports: u64,
+    /// Whether a recipient without SOL can claim without funding first
+    pub applies: bool,
+    pub reason: Option<String>,
+}
+
+impl SponsorQuote {
+    pub fn describe(&self) -> String {
+        let cost = self.cost_lamports as f64 / LAMPORTS_PER_SOL;
+        match (self.applies, self.mode) {
+            (true, SponsorMode::Sponsor) => format!(
+                "⛽ If the recipient has no SOL, the claim is sponsored (≈{:.6} SOL, booked to you).", cost
+            ),
+            (true, SponsorMode::DeductFromAmount) => format!(
+                "⛽ If the recipient has no SOL, {:.6} SOL of the amount covers the claim.", cost
+            ),
+            (false, _) => format!(
+                "⛽ No claim sponsorship ({}). Recipients need ≈{:.6} SOL to claim.",
+                self.reason.as_deref().unwrap_or("unavailable"),
+                cost
+            ),
+        }
+    }
+}
+
+/// How a claim's fee and rent get paid
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaimFunding {
+    /// The recipient has enough SOL
+    Recipient,
+    Sponsored { lamports: u64 },
+    Deducted { lamports: u64 },
+    /// The recipient must fund their wallet first
+    Blocked(String),
+}
+
+/// A claim ready to build, or the reason it can't be built gas-less
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimPlan {
+    pub send_id: String,
+    pub claim_id: String,
+    pub sender_user_id: i64,
+    pub recipient: Pubkey,
+    pub funding: ClaimFunding,
+    pub fee_payer: Option<Pubkey>,
+}
+
+impl ClaimPlan {
+    pub fn transaction_request(&self) -> ClaimTransactionRequest {
+        ClaimTransactionRequest {
+            send_id: self.send_id.clone(),
+            claim_id: self.claim_id.clone(),
+            recipient_public_key: self.recipient.to_string(),
+            fee_payer: self.fee_payer.map(|payer| payer.to_string()),
+            fee_payer_repayment_lamports: match self.funding {
+                ClaimFunding::Deducted { lamports } => Some(lamports),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SponsorEvent {
+    /// Budget held for a claim in flight
+    Reserved,
+    Settled,
+    /// The claim failed and its budget came back
+    Released,
+    Refused,
+}
+
+/// One sponsorship decision, kept for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SponsorAuditEntry {
+    pub at: DateTime<Utc>,
+    pub event: SponsorEvent,
+    pub send_id: String,
+    pub claim_id: String,
+    pub sender_user_id: i64,
+    pub recipient: String,
+    pub lamports: u64,
+    pub signature: Option<String>,
+    pub note: Option<String>,
+}
+
+/// A wallet's SOL balance
+#[async_trait::async_trait]
+pub trait RecipientBalance: Send + Sync {
+    async fn lamports(&self, wallet: &Pubkey) -> Result<u64>;
+}
+
+#[async_trait::async_trait]
+impl RecipientBalance for RpcClient {
+    async fn lamports(&self, wallet: &Pubkey) -> Result<u64> {
+        self.get_balance(wallet).await
+            .map_err(|e| BotError::internal(format!("Balance lookup failed: {}", e)).into())
+    }
+}
+
+/// Builds, co-signs and lands a planned claim; returns its signature
+#[async_trait::async_trait]
+pub trait ClaimSubmitter: Send + Sync {
+    async fn submit(&self, plan: &ClaimPlan) -> Result<String>;
+}
+
+/// Pays claims for recipients with no SOL, within per-claim and daily caps
+pub struct ClaimSponsor {
+    config: ClaimSponsorConfig,
+    balances: Arc<dyn RecipientBalance>,
+    ledger: Arc<FeeLedger>,
+    sends: RwLock<HashMap<String, SendTerms>>,
+    /// UTC day and lamports committed on it
+    spent: RwLock<(NaiveDate, u64)>,
+    attempts: RwLock<HashMap<Pubkey, VecDeque<DateTime<Utc>>>>,
+    /// Claims with a sponsorship in flight or settled, so none is paid twice
+    funded_claims: RwLock<HashSet<String>>,
+    audit: RwLock<Vec<SponsorAuditEntry>>,
+}
+
+impl ClaimSponsor {
+    pub fn new(config: ClaimSponsorConfig, balances: Arc<dyn RecipientBalance>, ledger: Arc<FeeLedger>) -> Self {
+        Self {
+            config,
+            balances,
+            ledger,
+            sends: RwLock::new(HashMap::new()),
+            spent: RwLock::new((NaiveDate::MIN, 0)),
+            attempts: RwLock::new(HashMap::new()),
+            funded_claims: RwLock::new(HashSet::new()),
+            audit: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ClaimSponsorConfig {
+        &self.config
+    }
+
+    /// What a recipient without SOL would face, for the sender to see before sending
+    pub fn quote(&self, token_mint: &str, amount: u64, mode: SponsorMode) -> Result<SponsorQuote> {
+        let cost_lamports = self.config.claim_cost(token_mint);
+        if mode == SponsorMode::DeductFromAmount {
+            if token_mint != WSOL_MINT {
+                return Err(BotError::validation("Only SOL sends can cover the claim out of the amount".to_string()).into());
+            }
+            if amount <= cost_lamports {
+                return Err(BotError::validation("The amount is too small to cover the claim".to_string()).into());
+            }
+        }
+
+        let reason = if self.config.sponsor_wallet.is_none() {
+            Some("no sponsor wallet configured".to_string())
+        } else if cost_lamports > self.config.max_per_claim_lamports {
+            Some("claim cost is over the per-claim cap".to_string())
+        } else {
+            None
+        };
+        Ok(SponsorQuote { mode, cost_lamports, applies: reason.is_none(), reason })
+    }
+
+    /// Remember the sender's choice for when the link is claimed
+    pub async fn register_send(&self, terms: SendTerms) -> Result<SponsorQuote> {
+        let quote = self.quote(&terms.token_mint, terms.amount, terms.mode)?;
+        self.sends.write().await.insert(terms.send_id.clone(), terms);
+        Ok(quote)
+    }
+
+    pub async fn spent_today(&self, now: DateTime<Utc>) -> u64 {
+        let spent = self.spent.read().await;
+        if spent.0 == now.date_naive() { spent.1 } else { 0 }
+    }
+
+    pub async fn audit_log(&self) -> Vec<SponsorAuditEntry> {
+        self.audit.read().await.clone()
+    }
+
+    /// Decide who pays for a claim and hold sponsorship budget for it
+    ///
+    /// The claim must be the claimant's own record and within the recipient's
+    /// hourly limit. A recipient who can pay never gets sponsored.
+    pub async fn prepare(&self, send_id: &str, claim: &ClaimRecord, claimant: &Pubkey, now: DateTime<Utc>) -> Result<ClaimPlan> {
+        let terms = self.sends.read().await.get(send_id).cloned()
+            .ok_or_else(|| BotError::not_found(format!("Send {} not found", send_id)))?;
+        if claim.recipient_public_key != claimant.to_string() {
+            warn!("⛽ Claim {} on send {} presented by {}, recorded for {}", claim.claim_id, send_id, claimant, claim.recipient_public_key);
+            return Err(BotError::security("This claim belongs to another wallet".to_string()).into());
+        }
+        self.check_rate(claimant, now).await?;
+
+        let mut plan = ClaimPlan {
+            send_id: send_id.to_string(),
+            claim_id: claim.claim_id.clone(),
+            sender_user_id: terms.sender_user_id,
+            recipient: *claimant,
+            funding: ClaimFunding::Recipient,
+            fee_payer: None,
+        };
+
+        let cost = self.config.claim_cost(&terms.token_mint);
+        if self.balances.lamports(claimant).await? >= cost {
+            return Ok(plan);
+        }
+
+        plan.funding = match self.sponsorship(&terms, &plan.claim_id, cost, now).await {
+            Ok(funding) => funding,
+            Err(reason) => {
+                self.log(&plan, SponsorEvent::Refused, cost, None, Some(reason.clone()), now).await;
+                return Ok(ClaimPlan { funding: ClaimFunding::Blocked(reason), ..plan });
+            }
+        };
+        plan.fee_payer = self.config.sponsor_wallet;
+        self.log(&plan, SponsorEvent::Reserved, cost, None, None, now).await;
+        Ok(plan)
+    }
+
+    async fn check_rate(&self, claimant: &Pubkey, now: DateTime<Utc>) -> Result<()> {
+        let mut attempts = self.attempts.write().await;
+        let recent = attempts.entry(*claimant).or_default();
+        while recent.front().is_some_and(|at| now - *at >= Duration::hours(1)) {
+            recent.pop_front();
+        }
+        if recent.len() >= self.config.claims_per_recipient_per_hour {
+            return Err(BotError::rate_limited("Too many claim attempts, try again within the hour".to_string()).into());
+        }
+        recent.push_back(now);
+        Ok(())
+    }
+
+    /// Funding for a recipient who can't pay, or why there is none
+    async fn sponsorship(&self, terms: &SendTerms, claim_id: &str, cost: u64, now: DateTime<Utc>) -> std::result::Result<ClaimFunding, String> {
+        if self.config.sponsor_wallet.is_none() {
+            return Err("no sponsor wallet configured".to_string());
+        }
+        if cost > self.config.max_per_claim_lamports {
+            return Err("claim cost is over the per-claim cap".to_string());
+        }
+        if self.funded_claims.read().await.contains(claim_id) {
+            return Err("this claim was already sponsored".to_string());
+        }
+
+        let funding = match terms.mode {
+            SponsorMode::DeductFromAmount if terms.amount <= cost => {
+                return Err("the amount is too small to cover the claim".to_string());
+            }
+            // Repaid inside the claim itself, so it doesn't draw on the budget
+            SponsorMode::DeductFromAmount => ClaimFunding::Deducted { lamports: cost },
+            SponsorMode::Sponsor => {
+                let mut spent = self.spent.write().await;
+                if spent.0 != now.date_naive() {
+                    *spent = (now.date_naive(), 0);
+                }
+                if spent.1 + cost > self.config.daily_budget_lamports {
+                    return Err("today's sponsorship budget is used up".to_string());
+                }
+                spent.1 += cost;
+                ClaimFunding::Sponsored { lamports: cost }
+            }
+        };
+        self.funded_claims.write().await.insert(claim_id.to_string());
+        Ok(funding)
+    }
+
+    /// Book a landed sponsored claim to the sender
+    pub async fn settle(&self, plan: &ClaimPlan, signature: &str, now: DateTime<Utc>) {
+        match plan.funding {
+            ClaimFunding::Sponsored { lamports } => {
+                self.ledger.record(plan.sender_user_id, FeeKind::ClaimSponsorship, lamports, Some(signature)).await;
+                self.log(plan, SponsorEvent::Settled, lamports, Some(signature), None, now).await;
+                info!("⛽ Sponsored claim {} for {} lamports ({})", plan.claim_id, lamports, signature);
+            }
+            ClaimFunding::Deducted { lamports } => {
+                self.log(plan, SponsorEvent::Settled, lamports, Some(signature), Some("repaid from the claim".to_string()), now).await;
+            }
+            ClaimFunding::Recipient | ClaimFunding::Blocked(_) => {}
+        }
+    }
+
+    /// Return a failed claim's budget
+    pub async fn release(&self, plan: &ClaimPlan, reason: &str, now: DateTime<Utc>) {
+        let lamports = match plan.funding {
+            ClaimFunding::Sponsored { lamports } => {
+                let mut spent = self.spent.write().await;
+                if spent.0 == now.date_naive() {
+                    spent.1 = spent.1.saturating_sub(lamports);
+                }
+                lamports
+            }
+            ClaimFunding::Deducted { lamports } => lamports,
+            ClaimFunding::Recipient | ClaimFunding::Blocked(_) => return,
+        };
+        self.funded_claims.write().await.remove(&plan.claim_id);
+        self.log(plan, SponsorEvent::Released, lamports, None, Some(reason.to_string()), now).await;
+    }
+
+    /// Prepare, submit and account for one claim
+    pub async fn claim(
+        &self,
+        send_id: &str,
+        claim: &ClaimRecord,
+        claimant: &Pubkey,
+        submitter: &dyn ClaimSubmitter,
+    ) -> Result<(ClaimPlan, String)> {
+        let plan = self.prepare(send_id, claim, claimant, Utc::now()).await?;
+        if let ClaimFunding::Blocked(reason) = &plan.funding {
+            return Err(BotError::validation(format!("Add some SOL to claim: {}", reason)).into());
+        }
+
+        match submitter.submit(&plan).await {
+            Ok(signature) => {
+                self.settle(&plan, &signature, Utc::now()).await;
+                Ok((plan, signature))
+            }
+            Err(e) => {
+                self.release(&plan, &e.to_string(), Utc::now()).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn log(
+        &self,
+        plan: &ClaimPlan,
+        event: SponsorEvent,
+        lamports: u64,
+        signature: Option<&str>,
+        note: Option<String>,
+        now: DateTime<Utc>,
+    ) {
+        self.audit.write().await.push(SponsorAuditEntry {
+            at: now,
+            event,
+            send_id: plan.send_id.clone(),
+            claim_id: plan.claim_id.clone(),
+            sender_user_id: plan.sender_user_id,
+            recipient: plan.recipient.to_string(),
+            lamports,
+            signature: signature.map(str::to_string),
+            note,
+        });
+    }
+}
@@ -0,0 +1,138 @@
+use crate::errors::{BotError, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use rand::RngCore;
+use solana_sdk::signature::{Keypair, Signer};
+use tracing::debug;
+use zeroize::Zeroizing;
+
+/// Bumped whenever the blob layout or cipher changes
+const EXPORT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// version, memory, iterations, parallelism
+const PARAMS_LEN: usize = 1 + 4 + 4 + 1;
+const HEADER_LEN: usize = PARAMS_LEN + SALT_LEN + NONCE_LEN;
+/// A 64-byte keypair plus the Poly1305 tag
+const SEALED_LEN: usize = 64 + 16;
+/// Blobs asking for more than this are rejected before any hashing
+const MAX_MEMORY_KIB: u32 = 256 * 1024;
+const MAX_ITERATIONS: u32 = 10;
+
+pub const MIN_PASSPHRASE_LEN: usize = 10;
+
+/// Argon2id cost, written into each blob so it can be raised without breaking old exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportKdf {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u8,
+}
+
+impl Default for ExportKdf {
+    /// OWASP's Argon2id baseline
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// Passphrase-encrypted keypair exports: Argon2id for the key, XChaCha20-Poly1305 for the seal
+///
+/// The header (version, KDF cost, salt and nonce) is authenticated as associated
+/// data, so a blob can't be downgraded to cheaper parameters.
+pub struct WalletExport;
+
+impl WalletExport {
+    pub fn encrypt(keypair: &Keypair, passphrase: &str) -> Result<String> {
+        Self::encrypt_with(keypair, passphrase, ExportKdf::default())
+    }
+
+    pub fn encrypt_with(keypair: &Keypair, passphrase: &str, kdf: ExportKdf) -> Result<String> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(BotError::validation(format!(
+                "Passphrase must be at least {} characters", MIN_PASSPHRASE_LEN
+            )).into());
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let mut blob = Vec::with_capacity(HEADER_LEN + SEALED_LEN);
+        blob.push(EXPORT_VERSION);
+        blob.extend_from_slice(&kdf.memory_kib.to_le_bytes());
+        blob.extend_from_slice(&kdf.iterations.to_le_bytes());
+        blob.push(kdf.parallelism);
+        blob.extend_from_slice(&salt);
+        blob.extend_from_slice(&nonce);
+
+        let key = Self::derive_key(passphrase, &salt, kdf)?;
+        let secret = Zeroizing::new(keypair.to_bytes());
+        let sealed = XChaCha20Poly1305::new(key.as_slice().into())
+            .encrypt(&nonce, Payload { msg: secret.as_slice(), aad: &blob })
+            .map_err(|_| BotError::internal("Failed to encrypt the export".to_string()))?;
+        blob.extend_from_slice(&sealed);
+
+        debug!("🔐 Encrypted export for {}", keypair.pubkey());
+        Ok(URL_SAFE_NO_PAD.encode(blob))
+    }
+
+    /// Recover the keypair; a wrong passphrase and a tampered blob fail the same way
+    pub fn decrypt(blob: &str, passphrase: &str) -> Result<Keypair> {
+        let (kdf, bytes) = Self::parse(blob)?;
+        let (header, sealed) = bytes.split_at(HEADER_LEN);
+        let salt = &header[PARAMS_LEN..PARAMS_LEN + SALT_LEN];
+        let nonce = XNonce::from_slice(&header[PARAMS_LEN + SALT_LEN..]);
+
+        let key = Self::derive_key(passphrase, salt, kdf)?;
+        let secret = Zeroizing::new(
+            XChaCha20Poly1305::new(key.as_slice().into())
+                .decrypt(nonce, Payload { msg: sealed, aad: header })
+                .map_err(|_| BotError::validation("Wrong passphrase, or the export was altered".to_string()))?,
+        );
+
+        Keypair::from_bytes(&secret)
+            .map_err(|_| BotError::validation("The export doesn't hold a valid keypair".to_string()).into())
+    }
+
+    /// Whether `blob` is shaped like an export, without needing the passphrase
+    pub fn looks_like_export(blob: &str) -> bool {
+        Self::parse(blob).is_ok()
+    }
+
+    fn parse(blob: &str) -> Result<(ExportKdf, Vec<u8>)> {
+        let not_an_export = || BotError::validation("That isn't a wallet export".to_string());
+        let bytes = URL_SAFE_NO_PAD.decode(blob.trim()).map_err(|_| not_an_export())?;
+        if bytes.len() != HEADER_LEN + SEALED_LEN || bytes[0] != EXPORT_VERSION {
+            return Err(not_an_export().into());
+        }
+
+        let kdf = ExportKdf {
+            memory_kib: u32::from_le_bytes(bytes[1..5].try_into().unwrap()),
+            iterations: u32::from_le_bytes(bytes[5..9].try_into().unwrap()),
+            parallelism: bytes[9],
+        };
+        if kdf.memory_kib > MAX_MEMORY_KIB || kdf.iterations > MAX_ITERATIONS || kdf.parallelism == 0 {
+            return Err(not_an_export().into());
+        }
+        Ok((kdf, bytes))
+    }
+
+    fn derive_key(passphrase: &str, salt: &[u8], kdf: ExportKdf) -> Result<Zeroizing<[u8; 32]>> {
+        let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism as u32, Some(32))
+            .map_err(|e| BotError::validation(format!("Invalid export parameters: {}", e)))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, key.as_mut_slice())
+            .map_err(|e| BotError::internal(format!("Key derivation failed: {}", e)))?;
+        Ok(key)
+    }
+}
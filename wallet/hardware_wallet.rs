@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, debug, warn, error};
@@ -22,6 +23,15 @@ pub struct HardwareWalletManager {
     active_wallet: Arc<RwLock<Option<String>>>,
     security_policies: Arc<RwLock<SecurityPolicies>>,
     transaction_cache: Arc<RwLock<TransactionCache>>,
+    /// Attached Ledgers by public key
+    ledgers: Arc<RwLock<HashMap<String, LedgerBinding>>>,
+}
+
+/// A Ledger's transport and the Telegram user it signs for
+#[derive(Clone)]
+struct LedgerBinding {
+    owner_user_id: i64,
+    transport: Arc<dyn LedgerTransport>,
 }
 
 /// Represents a connected hardware wallet
@@ -114,9 +124,31 @@ pub struct SecurityPolicies {
     pub require_2fa: bool,
     pub auto_lock_timeout_seconds: u32,
     pub transaction_review_mode: TransactionReviewMode,
+    /// Transactions moving more than this are shown for review at `transaction_review_mode`
+    #[serde(default)]
+    pub review_above_sol: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl SecurityPolicies {
+    /// The review a transaction moving `value_sol` needs; an error when policy forbids it outright
+    pub fn review_for(&self, value_sol: f64) -> Result<Option<TransactionReviewMode>> {
+        if let Some(max_value) = self.max_transaction_value {
+            let lamports = (value_sol * 1e9) as u64;
+            if lamports > max_value {
+                return Err(BotError::hardware_wallet(format!(
+                    "{:.4} SOL is over the wallet's {:.4} SOL limit",
+                    value_sol, max_value as f64 / 1e9
+                )).into());
+            }
+        }
+        
+        Ok(self.review_above_sol
+            .filter(|threshold| value_sol > *threshold)
+            .map(|_| self.transaction_review_mode))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransactionReviewMode {
     Minimal,    // Show only essential details
     Standard,   // Show standard transaction details
@@ -178,7 +210,7 @@ pub enum TransactionType {
     Custom(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransactionPriority {
     Low,
     Normal,
@@ -186,6 +218,17 @@ pub enum TransactionPriority {
     Critical,
 }
 
+impl TransactionPriority {
+    /// One level up, for resending a transaction that didn't land; `Critical` stays put
+    pub fn raised(self) -> Self {
+        match self {
+            Self::Low => Self::Normal,
+            Self::Normal => Self::High,
+            Self::High | Self::Critical => Self::Critical,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RiskLevel {
     Low,
@@ -225,6 +268,8 @@ impl LedgerSolanaApp {
     const INS_GET_PUBKEY: u8 = 0x02;
     const INS_SIGN_MESSAGE: u8 = 0x03;
     const INS_SIGN_OFFCHAIN_MESSAGE: u8 = 0x04;
+    /// Status word the app answers with when the user declines on the device
+    const SW_USER_REJECTED: u16 = 0x6985;
     
     /// Create new Ledger Solana app instance
     pub fn new(transport: Arc<dyn LedgerTransport>, derivation_path: Vec<u32>) -> Self {
@@ -287,6 +332,16 @@ impl LedgerSolanaApp {
             let apdu = self.build_apdu(Self::INS_SIGN_MESSAGE, p1, p2, &data);
             let response = self.transport.exchange(&apdu).await?;
             
+            // A bare status word means the device refused the request
+            if let [hi, lo] = response[..] {
+                let status = u16::from_be_bytes([hi, lo]);
+                return Err(BotError::hardware_wallet(if status == Self::SW_USER_REJECTED {
+                    "Rejected on the device".to_string()
+                } else {
+                    format!("Device returned status {:#06x}", status)
+                }).into());
+            }
+            
             if i == chunks.len() - 1 {
                 // Last chunk contains the signature
                 if response.len() < 64 {
@@ -333,6 +388,13 @@ pub struct AppConfiguration {
     pub settings_mask: u8,
 }
 
+impl AppConfiguration {
+    /// The app signs transactions it can't display in full
+    pub fn blind_signing_enabled(&self) -> bool {
+        self.settings_mask & 0x01 != 0
+    }
+}
+
 impl HardwareWalletManager {
     /// Create new hardware wallet manager
     pub fn new(telemetry: Option<Arc<TelemetryService>>) -> Self {
@@ -348,9 +410,51 @@ impl HardwareWalletManager {
                 signed_transactions: Vec::new(),
                 rejected_transactions: Vec::new(),
             })),
+            ledgers: Arc::new(RwLock::new(HashMap::new())),
         }
     }
     
+    /// Register a Ledger reachable over `transport` as `owner_user_id`'s signer for its public key
+    pub async fn attach_ledger(&self, wallet: HardwareWallet, owner_user_id: i64, transport: Arc<dyn LedgerTransport>) -> Result<()> {
+        if !matches!(wallet.wallet_type, WalletType::Ledger { .. }) {
+            return Err(BotError::hardware_wallet(format!("{} is not a Ledger", wallet.wallet_id)).into());
+        }
+        
+        self.ledgers.write().await.insert(wallet.public_key.clone(), LedgerBinding {
+            owner_user_id,
+            transport,
+        });
+        let mut wallets = self.wallets.write().await;
+        wallets.retain(|w| w.wallet_id != wallet.wallet_id);
+        info!("🔐 Attached Ledger {} for {}", wallet.wallet_id, wallet.public_key);
+        wallets.push(wallet);
+        Ok(())
+    }
+    
+    /// The Solana app and owning user for a Ledger-held `public_key`
+    pub async fn ledger_for(&self, public_key: &str) -> Result<Option<(LedgerSolanaApp, i64)>> {
+        let wallet = {
+            let wallets = self.wallets.read().await;
+            wallets.iter().find(|w| w.public_key == public_key).cloned()
+        };
+        match wallet {
+            Some(wallet) => self.ledger_app(&wallet).await,
+            None => Ok(None),
+        }
+    }
+    
+    async fn ledger_app(&self, wallet: &HardwareWallet) -> Result<Option<(LedgerSolanaApp, i64)>> {
+        let Some(binding) = self.ledgers.read().await.get(&wallet.public_key).cloned() else {
+            return Ok(None);
+        };
+        let derivation_path = self.parse_derivation_path(&wallet.derivation_path)?;
+        Ok(Some((LedgerSolanaApp::new(binding.transport, derivation_path), binding.owner_user_id)))
+    }
+    
+    pub async fn security_policies(&self) -> SecurityPolicies {
+        self.security_policies.read().await.clone()
+    }
+    
     /// Scan for connected hardware wallets
     pub async fn scan_for_wallets(&self) -> Result<Vec<HardwareWallet>> {
         let _span = self.telemetry.as_ref().map(|t| 
@@ -528,20 +632,11 @@ impl HardwareWalletManager {
     }
     
     async fn sign_with_ledger(&self, wallet: &HardwareWallet, transaction: &Transaction) -> Result<Signature> {
-        // In production, this would use the LedgerSolanaApp to sign
-        // For now, return a dummy signature
         debug!("🔐 Signing transaction with Ledger wallet: {}", wallet.wallet_id);
         
-        // Parse derivation path
-        let derivation_path = self.parse_derivation_path(&wallet.derivation_path)?;
-        
-        // Create Ledger app instance (would use actual transport in production)
-        // let transport = Arc::new(LedgerHIDTransport { device_path: "".to_string() });
-        // let app = LedgerSolanaApp::new(transport, derivation_path);
-        // let signature = app.sign_transaction(transaction).await?;
-        
-        // Placeholder signature
-        Ok(Signature::default())
+        let (app, _) = self.ledger_app(wallet).await?
+            .ok_or_else(|| BotError::hardware_wallet(format!("Ledger {} has no transport attached", wallet.wallet_id)))?;
+        app.sign_transaction(transaction).await
     }
     
     async fn sign_with_trezor(&self, wallet: &HardwareWallet, _transaction: &Transaction) -> Result<Signature> {
@@ -630,6 +725,7 @@ impl Default for SecurityPolicies {
             require_2fa: false,
             auto_lock_timeout_seconds: 300, // 5 minutes
             transaction_review_mode: TransactionReviewMode::Standard,
+            review_above_sol: Some(1.0),
         }
     }
 }
@@ -7,6 +7,8 @@ use std::str::FromStr;
 use tracing::{info, warn, debug};
 
 use crate::db::Database;
+use crate::trading::WalletOwners;
+use super::activity_watch::WalletActivityWatcher;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletInfo {
@@ -30,12 +32,47 @@ pub struct WalletSession {
 
 pub struct WalletManager {
     db: Arc<Database>,
+    activity_watch: Option<Arc<WalletActivityWatcher>>,
 }
 
 impl WalletManager {
     pub fn new(db: Arc<Database>) -> Self {
         Self {
             db,
+            activity_watch: None,
+        }
+    }
+    
+    /// Watch registered wallets for activity the bot did not originate
+    pub fn with_activity_watch(mut self, watcher: Arc<WalletActivityWatcher>) -> Self {
+        self.activity_watch = Some(watcher);
+        self
+    }
+    
+    pub fn activity_watch(&self) -> Option<&Arc<WalletActivityWatcher>> {
+        self.activity_watch.as_ref()
+    }
+    
+    /// Remember a signature the bot sent so the activity watch ignores it
+    pub async fn record_originated(&self, signature: &str) {
+        if let Some(watcher) = &self.activity_watch {
+            watcher.record_originated(signature).await;
+        }
+    }
+    
+    /// Whether trading was locked from an activity alert
+    pub async fn is_trading_locked(&self, telegram_id: &str) -> bool {
+        match (&self.activity_watch, telegram_id.parse::<i64>()) {
+            (Some(watcher), Ok(user_id)) => watcher.is_trading_locked(user_id).await,
+            _ => false,
+        }
+    }
+    
+    async fn watch_wallet(&self, telegram_id: &str, wallet_address: &str) {
+        if let (Some(watcher), Ok(user_id)) = (&self.activity_watch, telegram_id.parse::<i64>()) {
+            if let Err(e) = watcher.watch(user_id, wallet_address).await {
+                warn!("Could not watch wallet {} for user {}: {}", wallet_address, telegram_id, e);
+            }
         }
     }
     
@@ -51,6 +88,7 @@ impl WalletManager {
         
         // Store in database
         self.db.register_user_wallet(telegram_id, wallet_address).await?;
+        self.watch_wallet(telegram_id, wallet_address).await;
         
         info!("Registered wallet {} for user {}", wallet_address, telegram_id);
         
@@ -88,6 +126,7 @@ impl WalletManager {
     /// Set active wallet for a user
     pub async fn set_active_wallet(&self, telegram_id: &str, wallet_address: &str) -> Result<()> {
         self.db.set_active_wallet(telegram_id, wallet_address).await?;
+        self.watch_wallet(telegram_id, wallet_address).await;
         
         info!("Set active wallet {} for user {}", wallet_address, telegram_id);
         
@@ -162,6 +201,25 @@ impl WalletManager {
         Err(WalletError::WalletNotFound.into())
     }
     
+    /// Delete every wallet record and signing session a user has; returns the wallet count
+    ///
+    /// Only public addresses and encrypted session blobs are stored, and both go.
+    pub async fn forget_user(&self, telegram_id: &str) -> Result<usize> {
+        let wallets = self.get_user_wallets(telegram_id).await?;
+        if let Some(watcher) = &self.activity_watch {
+            for wallet in &wallets {
+                watcher.unwatch(&wallet.public_key).await;
+            }
+        }
+        
+        self.db.delete_user_sessions(telegram_id).await?;
+        self.db.delete_user_wallets(telegram_id).await?;
+        
+        info!("Deleted {} wallets and all sessions for user {}", wallets.len(), telegram_id);
+        
+        Ok(wallets.len())
+    }
+    
     /// Update wallet balance (cached value only)
     pub async fn update_wallet_balance(&self, telegram_id: &str, wallet_address: &str, balance_sol: f64) -> Result<()> {
         // For now, we don't cache balances in database
@@ -197,6 +255,14 @@ impl WalletManager {
     }
 }
 
+#[async_trait::async_trait]
+impl WalletOwners for WalletManager {
+    async fn owner_of(&self, wallet_address: &str) -> Result<Option<i64>> {
+        let owner = self.db.get_wallet_owner(wallet_address).await?;
+        Ok(owner.and_then(|telegram_id| telegram_id.parse().ok()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,10 +2,52 @@ mod generator;
 mod manager;
 mod security;
 mod hardware_wallet;
+mod activity_watch;
+mod signature_watch;
+mod ata_cleanup;
+mod claim_sponsor;
+mod export;
 
 pub use generator::{WalletGenerator, WalletCredentials};
 pub use manager::{WalletManager, WalletInfo, WalletSession};
 pub use security::{WalletSecurity, SecurityLevel};
+pub use export::{WalletExport, ExportKdf, MIN_PASSPHRASE_LEN};
+pub use activity_watch::{
+    WalletActivityWatcher,
+    ActivityWatchConfig,
+    WalletTransaction,
+    TokenChange,
+    ActivityClass,
+    ActivityAction,
+    WalletActivityAlert,
+    DepositNotice,
+};
+pub use signature_watch::SignatureWatcher;
+pub use ata_cleanup::{
+    AtaJanitor,
+    AtaCleanupConfig,
+    TokenAccountSnapshot,
+    CleanupExclusions,
+    CleanupPlan,
+    CleanupOutcome,
+    CleanupSubmitter,
+    SkipReason,
+    close_account_instruction,
+    SPL_TOKEN_PROGRAM_ID,
+};
+pub use claim_sponsor::{
+    ClaimSponsor,
+    ClaimSponsorConfig,
+    SponsorMode,
+    SendTerms,
+    SponsorQuote,
+    ClaimFunding,
+    ClaimPlan,
+    SponsorEvent,
+    SponsorAuditEntry,
+    RecipientBalance,
+    ClaimSubmitter,
+};
 pub use hardware_wallet::{
     HardwareWalletManager,
     HardwareWallet,
@@ -0,0 +1,129 @@
+use futures::StreamExt;
+use solana_client::{
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::{RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+    rpc_response::{RpcConfirmedTransactionStatusWithSignature, RpcLogsResponse},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, warn};
+
+use crate::errors::{BotError, Result};
+
+/// Pages read in one catch-up before the oldest signatures are given up on
+const MAX_PAGES: usize = 50;
+
+/// Finds the transactions of a wallet, by polling its signatures or following its logs
+///
+/// Shared by the whale, wallet activity and copy trading watchers, which
+/// each keep their own last signature and decide what a transaction means.
+#[derive(Clone)]
+pub struct SignatureWatcher {
+    rpc_client: Arc<RpcClient>,
+    ws_url: Option<String>,
+    page_size: usize,
+}
+
+impl SignatureWatcher {
+    pub fn new(rpc_client: Arc<RpcClient>, ws_url: Option<String>, page_size: usize) -> Self {
+        Self { rpc_client, ws_url, page_size: page_size.clamp(1, 1000) }
+    }
+
+    /// Signatures newer than `last_signature`, newest first
+    ///
+    /// Pages back until `last_signature` is reached, so a burst bigger than
+    /// one page isn't skipped. Without a last signature only the newest page
+    /// is read, which is all a baseline needs.
+    pub async fn signatures_since(
+        &self,
+        wallet: &str,
+        last_signature: Option<&str>,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let address = Pubkey::from_str(wallet)
+            .map_err(|_| BotError::validation(format!("Invalid wallet address: {}", wallet)))?;
+        let until = last_signature.and_then(|s| Signature::from_str(s).ok());
+
+        let mut signatures: Vec<RpcConfirmedTransactionStatusWithSignature> = Vec::new();
+        let mut before = None;
+        for _ in 0..MAX_PAGES {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(self.page_size),
+                commitment: Some(CommitmentConfig::confirmed()),
+            };
+            let page = self.rpc_client.get_signatures_for_address_with_config(&address, config).await?;
+            let full = page.len() >= self.page_size;
+            signatures.extend(page);
+
+            // A short page stopped at `until` or at the wallet's first transaction
+            if !full || until.is_none() {
+                return Ok(signatures);
+            }
+            before = signatures.last().and_then(|s| Signature::from_str(&s.signature).ok());
+        }
+
+        warn!("📡 {} is more than {} signatures behind; older ones are skipped", wallet, MAX_PAGES * self.page_size);
+        Ok(signatures)
+    }
+
+    /// The confirmed transaction behind `signature`, `jsonParsed`
+    pub async fn fetch_transaction(&self, signature: &str) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+        let parsed = Signature::from_str(signature)
+            .map_err(|_| BotError::validation(format!("Invalid signature: {}", signature)))?;
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::JsonParsed),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        Ok(self.rpc_client.get_transaction_with_config(&parsed, config).await?)
+    }
+
+    /// Log notifications mentioning `wallet`, once the subscription is live
+    ///
+    /// The receiver ends when the websocket drops; dropping it unsubscribes.
+    pub async fn subscribe_logs(&self, wallet: &str) -> Result<mpsc::UnboundedReceiver<RpcLogsResponse>> {
+        let ws_url = self.ws_url.clone()
+            .ok_or_else(|| BotError::config("No websocket URL for log subscriptions"))?;
+        let wallet = wallet.to_string();
+        let (live_tx, live_rx) = oneshot::channel();
+        let (logs_tx, logs_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let client = match PubsubClient::new(&ws_url).await {
+                Ok(client) => client,
+                Err(e) => {
+                    let _ = live_tx.send(Err(BotError::external_api(format!("Websocket unavailable: {}", e))));
+                    return;
+                }
+            };
+            let subscription = client.logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![wallet.clone()]),
+                RpcTransactionLogsConfig { commitment: Some(CommitmentConfig::confirmed()) },
+            ).await;
+            let (mut stream, _unsubscribe) = match subscription {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    let _ = live_tx.send(Err(BotError::external_api(format!("logsSubscribe failed: {}", e))));
+                    return;
+                }
+            };
+            let _ = live_tx.send(Ok(()));
+
+            while let Some(response) = stream.next().await {
+                if logs_tx.send(response.value).is_err() {
+                    break;
+                }
+            }
+            debug!("📡 Log subscription for {} ended", wallet);
+        });
+
+        live_rx.await
+            .map_err(|_| BotError::internal("Log subscription task ended before subscribing"))??;
+        Ok(logs_rx)
+    }
+}
@@ -6,6 +6,7 @@ pub use realtime_client::{
     WebSocketClient,
     WebSocketConfig,
     ConnectionStatus,
+    ConnectionEvent,
     ReconnectStrategy,
     WebSocketMessage,
     MessageHandler,
@@ -13,13 +14,21 @@ pub use realtime_client::{
     SubscriptionRequest,
     StreamData,
     ErrorHandler,
+    WebSocketError,
+    ConnectionState,
 };
 
 pub use price_stream::{
     PriceStreamManager,
     PriceUpdate,
+    UpdateType,
     PriceSubscription,
     AggregatedPrice,
+    AggregationConfig,
+    SourceQuote,
+    QuoteSource,
+    PriceAggregator,
+    DefaultPriceAggregator,
     PriceSource,
     OHLCV,
     TickData,
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
@@ -7,9 +8,11 @@ use std::sync::Arc;
 use tokio::sync::{RwLock, broadcast};
 use tracing::{info, debug, warn};
 
+use crate::api::jupiter_price_v3::JupiterPriceV3Client;
 use crate::errors::Result;
 use crate::websocket::realtime_client::{
     WebSocketClient, StreamData, MessageHandler, SubscriptionRequest, SubscriptionType,
+    ConnectionStatus,
 };
 
 /// Real-time price stream manager
@@ -21,6 +24,9 @@ pub struct PriceStreamManager {
     orderbook_cache: Arc<RwLock<HashMap<String, OrderBook>>>,
     subscribers: Arc<RwLock<HashMap<String, broadcast::Sender<PriceUpdate>>>>,
     aggregators: Arc<RwLock<Vec<Arc<dyn PriceAggregator>>>>,
+    /// Polled alongside the streamed quotes when aggregating
+    quote_sources: Vec<Arc<dyn QuoteSource>>,
+    aggregation: AggregationConfig,
 }
 
 /// Price data cache
@@ -67,7 +73,7 @@ pub struct PriceMetadata {
 }
 
 /// Price source enumeration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum PriceSource {
     Jupiter,
     Pyth,
@@ -145,19 +151,77 @@ pub struct MarketDepth {
 }
 
 /// Aggregated price from multiple sources
+///
+/// Prices are computed over the accepted quotes: the latest per source,
+/// fresh, and within `max_deviation_pct` of the median.
 #[derive(Debug, Clone)]
 pub struct AggregatedPrice {
     pub symbol: String,
     pub timestamp: DateTime<Utc>,
     pub mean_price: Decimal,
+    /// Confidence-weighted median
     pub median_price: Decimal,
+    /// Volume-weighted, or the mean without volumes
     pub weighted_price: Decimal,
     pub min_price: Decimal,
     pub max_price: Decimal,
     pub std_deviation: f64,
     pub confidence: f64,
+    /// Sources that made it into the price
     pub sources_count: usize,
     pub source_prices: HashMap<PriceSource, Decimal>,
+    /// Every source's latest quote, including stale and rejected ones
+    pub breakdown: Vec<SourceQuote>,
+    /// No source had a fresh quote; the figures come from stale ones
+    pub stale: bool,
+}
+
+impl AggregatedPrice {
+    /// Fresh and agreed on by at least `min_sources` sources
+    pub fn is_usable(&self, min_sources: usize) -> bool {
+        !self.stale && self.sources_count >= min_sources.max(1)
+    }
+}
+
+/// One source's latest quote within an aggregate
+#[derive(Debug, Clone)]
+pub struct SourceQuote {
+    pub source: PriceSource,
+    pub price: Decimal,
+    pub timestamp: DateTime<Utc>,
+    pub weight: f64,
+    /// Distance from the median of the considered quotes, in percent
+    pub deviation_pct: f64,
+    pub stale: bool,
+    /// Too far from the median to be trusted
+    pub rejected: bool,
+}
+
+/// How quotes from several sources are combined
+#[derive(Debug, Clone)]
+pub struct AggregationConfig {
+    /// Quotes further than this (percent) from the median are rejected
+    pub max_deviation_pct: f64,
+    /// Quotes older than this are stale
+    pub max_age: std::time::Duration,
+    /// Relative confidence per source; unlisted sources weigh 1.0
+    pub source_weights: HashMap<PriceSource, f64>,
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self {
+            max_deviation_pct: 5.0,
+            max_age: std::time::Duration::from_secs(30),
+            source_weights: HashMap::new(),
+        }
+    }
+}
+
+impl AggregationConfig {
+    fn weight(&self, source: &PriceSource) -> f64 {
+        self.source_weights.get(source).copied().unwrap_or(1.0).max(0.0)
+    }
 }
 
 /// Price aggregator trait
@@ -167,6 +231,13 @@ pub trait PriceAggregator: Send + Sync {
     fn name(&self) -> String;
 }
 
+/// A price feed polled on demand, e.g. Jupiter's price API or Pyth
+#[async_trait]
+pub trait QuoteSource: Send + Sync {
+    fn source(&self) -> PriceSource;
+    async fn quote(&self, symbol: &str) -> Result<Option<PriceUpdate>>;
+}
+
 /// WebSocket message handler for price updates
 pub struct PriceMessageHandler {
     manager: Arc<PriceStreamManager>,
@@ -202,9 +273,22 @@ impl PriceStreamManager {
             orderbook_cache: Arc::new(RwLock::new(HashMap::new())),
             subscribers: Arc::new(RwLock::new(HashMap::new())),
             aggregators: Arc::new(RwLock::new(Vec::new())),
+            quote_sources: Vec::new(),
+            aggregation: AggregationConfig::default(),
         }
     }
     
+    /// Poll `source` whenever a price is aggregated
+    pub fn with_quote_source(mut self, source: Arc<dyn QuoteSource>) -> Self {
+        self.quote_sources.push(source);
+        self
+    }
+    
+    pub fn with_aggregation(mut self, aggregation: AggregationConfig) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+    
     /// Subscribe to price updates for symbols
     pub async fn subscribe_prices(&self, subscription: PriceSubscription) -> Result<broadcast::Receiver<PriceUpdate>> {
         info!("📈 Subscribing to prices for {} symbols", subscription.symbols.len());
@@ -330,30 +414,59 @@ impl PriceStreamManager {
     
     /// Run price aggregators
     async fn run_aggregators(&self, symbol: &str) -> Result<()> {
-        let history = self.price_history.read().await;
+        let quotes = self.latest_quotes(symbol).await;
         
-        if let Some(symbol_history) = history.get(symbol) {
-            // Get recent prices from different sources
-            let recent_prices: Vec<PriceUpdate> = symbol_history.iter()
-                .rev()
-                .take(10)
-                .cloned()
-                .collect();
+        if quotes.len() >= 2 {
+            let aggregators = self.aggregators.read().await;
             
-            if recent_prices.len() >= 2 {
-                let aggregators = self.aggregators.read().await;
-                
-                for aggregator in aggregators.iter() {
-                    let aggregated = aggregator.aggregate(&recent_prices).await;
-                    debug!("📈 Aggregated price for {}: {} (confidence: {:.2}%)", 
-                        symbol, aggregated.weighted_price, aggregated.confidence * 100.0);
-                }
+            for aggregator in aggregators.iter() {
+                let aggregated = aggregator.aggregate(&quotes).await;
+                debug!("📈 Aggregated price for {}: {} (confidence: {:.2}%)", 
+                    symbol, aggregated.median_price, aggregated.confidence * 100.0);
             }
         }
         
         Ok(())
     }
     
+    /// Latest streamed quote from each source
+    async fn latest_quotes(&self, symbol: &str) -> Vec<PriceUpdate> {
+        let history = self.price_history.read().await;
+        let mut latest: Vec<PriceUpdate> = Vec::new();
+        
+        for update in history.get(symbol).into_iter().flatten().rev() {
+            if !latest.iter().any(|q| q.source == update.source) {
+                latest.push(update.clone());
+            }
+        }
+        latest
+    }
+    
+    /// Combine streamed quotes with a fresh poll of every quote source
+    pub async fn aggregate_price(&self, symbol: &str) -> AggregatedPrice {
+        self.aggregate_price_with(symbol, Vec::new()).await
+    }
+    
+    /// As `aggregate_price`, also counting quotes the caller already holds
+    pub async fn aggregate_price_with(&self, symbol: &str, extra: Vec<PriceUpdate>) -> AggregatedPrice {
+        let polls = self.quote_sources.iter().map(|source| source.quote(symbol));
+        let polled = futures::future::join_all(polls).await;
+        
+        let mut quotes = self.latest_quotes(symbol).await;
+        for (source, result) in self.quote_sources.iter().zip(polled) {
+            match result {
+                Ok(Some(update)) => quotes.push(update),
+                Ok(None) => {},
+                Err(e) => warn!("📈 {:?} quote for {} failed: {}", source.source(), symbol, e),
+            }
+        }
+        quotes.extend(extra);
+        
+        let mut aggregated = DefaultPriceAggregator::new(self.aggregation.clone()).aggregate(&quotes).await;
+        aggregated.symbol = symbol.to_string();
+        aggregated
+    }
+    
     /// Get current price for symbol
     pub async fn get_price(&self, symbol: &str) -> Option<PriceData> {
         let cache = self.price_cache.read().await;
@@ -447,6 +560,41 @@ impl PriceStreamManager {
         Some(weighted_sum / total_volume)
     }
     
+    /// Drop data that spans a gap in the stream: price history (and so VWAP and
+    /// aggregates) for `price_stream`, order books for `orderbook_stream`
+    pub async fn invalidate_stale(&self, connection: &str) {
+        match connection {
+            "price_stream" => self.price_history.write().await.clear(),
+            "orderbook_stream" => self.orderbook_cache.write().await.clear(),
+            _ => return,
+        }
+        info!("📈 Cleared stale {} data after reconnect", connection);
+    }
+    
+    /// Invalidate stale data whenever a stream is resubscribed after a reconnect
+    pub fn spawn_connection_watcher(&self) -> tokio::task::JoinHandle<()> {
+        let manager = self.clone();
+        let mut events = self.ws_client.status_events();
+        
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        if let ConnectionStatus::Resubscribed { .. } = event.status {
+                            manager.invalidate_stale(&event.connection).await;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("📈 Missed {} connection events, clearing stream data", skipped);
+                        manager.invalidate_stale("price_stream").await;
+                        manager.invalidate_stale("orderbook_stream").await;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+    
     /// Register custom price aggregator
     pub async fn register_aggregator(&self, aggregator: Arc<dyn PriceAggregator>) {
         let mut aggregators = self.aggregators.write().await;
@@ -465,16 +613,98 @@ impl PriceStreamManager {
 }
 
 /// Default price aggregator implementation
-pub struct DefaultPriceAggregator;
+///
+/// Takes each source's latest quote, sets stale ones aside, and rejects
+/// quotes too far from the confidence-weighted median before computing prices.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultPriceAggregator {
+    config: AggregationConfig,
+}
+
+impl DefaultPriceAggregator {
+    pub fn new(config: AggregationConfig) -> Self {
+        Self { config }
+    }
+    
+    /// Median where each quote counts with its weight; ties split the difference
+    fn weighted_median(quotes: &[&SourceQuote]) -> Decimal {
+        let mut sorted: Vec<(Decimal, f64)> = quotes.iter().map(|q| (q.price, q.weight)).collect();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        let mut total: f64 = sorted.iter().map(|(_, w)| w).sum();
+        if total <= 0.0 {
+            sorted.iter_mut().for_each(|(_, w)| *w = 1.0);
+            total = sorted.len() as f64;
+        }
+        
+        let half = total / 2.0;
+        let mut cumulative = 0.0;
+        for (i, (price, weight)) in sorted.iter().enumerate() {
+            cumulative += weight;
+            if (cumulative - half).abs() < 1e-9 {
+                return match sorted.get(i + 1) {
+                    Some((next, _)) => (*price + *next) / Decimal::from(2),
+                    None => *price,
+                };
+            }
+            if cumulative > half {
+                return *price;
+            }
+        }
+        sorted.last().map(|(price, _)| *price).unwrap_or(Decimal::ZERO)
+    }
+}
 
 #[async_trait]
 impl PriceAggregator for DefaultPriceAggregator {
     async fn aggregate(&self, prices: &[PriceUpdate]) -> AggregatedPrice {
         let symbol = prices.first().map(|p| p.symbol.clone()).unwrap_or_default();
         let timestamp = Utc::now();
+        let max_age = chrono::Duration::from_std(self.config.max_age).unwrap_or(chrono::Duration::MAX);
+        
+        // Latest quote per source
+        let mut latest: HashMap<PriceSource, &PriceUpdate> = HashMap::new();
+        for update in prices {
+            let newer = latest.get(&update.source).map_or(true, |current| update.timestamp >= current.timestamp);
+            if newer {
+                latest.insert(update.source.clone(), update);
+            }
+        }
+        
+        let mut breakdown: Vec<SourceQuote> = latest.values()
+            .map(|update| SourceQuote {
+                source: update.source.clone(),
+                price: update.price,
+                timestamp: update.timestamp,
+                weight: self.config.weight(&update.source),
+                deviation_pct: 0.0,
+                stale: timestamp - update.timestamp > max_age,
+                rejected: false,
+            })
+            .collect();
+        breakdown.sort_by(|a, b| a.price.cmp(&b.price));
+        
+        // Fall back to stale quotes only when nothing is fresh
+        let stale = !breakdown.iter().any(|q| !q.stale);
+        let considered = |q: &SourceQuote| stale || !q.stale;
+        
+        // Reject outliers against the median of everything considered
+        let reference = Self::weighted_median(&breakdown.iter().filter(|q| considered(q)).collect::<Vec<_>>());
+        for quote in breakdown.iter_mut().filter(|q| considered(q)) {
+            quote.deviation_pct = if reference > Decimal::ZERO {
+                ((quote.price - reference).abs() / reference * Decimal::from(100)).to_f64().unwrap_or(f64::MAX)
+            } else {
+                0.0
+            };
+            quote.rejected = quote.deviation_pct > self.config.max_deviation_pct;
+        }
         
-        let mut price_values: Vec<Decimal> = prices.iter().map(|p| p.price).collect();
-        price_values.sort();
+        let accepted: Vec<&SourceQuote> = breakdown.iter().filter(|q| considered(q) && !q.rejected).collect();
+        let volumes: HashMap<&PriceSource, Decimal> = latest.iter()
+            .filter_map(|(source, update)| update.volume.map(|v| (source, v)))
+            .collect();
+        
+        let price_values: Vec<Decimal> = accepted.iter().map(|q| q.price).collect();
         
         let mean_price = if !price_values.is_empty() {
             price_values.iter().sum::<Decimal>() / Decimal::from(price_values.len())
@@ -482,20 +712,20 @@ impl PriceAggregator for DefaultPriceAggregator {
             Decimal::ZERO
         };
         
-        let median_price = if !price_values.is_empty() {
-            price_values[price_values.len() / 2]
+        let median_price = if !accepted.is_empty() {
+            Self::weighted_median(&accepted)
         } else {
             Decimal::ZERO
         };
         
         // Volume-weighted price
-        let total_volume: Decimal = prices.iter()
-            .filter_map(|p| p.volume)
+        let total_volume: Decimal = accepted.iter()
+            .filter_map(|q| volumes.get(&q.source))
             .sum();
         
         let weighted_price = if total_volume > Decimal::ZERO {
-            prices.iter()
-                .filter_map(|p| p.volume.map(|v| p.price * v))
+            accepted.iter()
+                .filter_map(|q| volumes.get(&q.source).map(|v| q.price * v))
                 .sum::<Decimal>() / total_volume
         } else {
             mean_price
@@ -517,27 +747,25 @@ impl PriceAggregator for DefaultPriceAggregator {
         };
         
         let std_deviation = variance.sqrt();
+        let sources_count = accepted.len();
         
         // Calculate confidence based on source diversity and consistency
-        let sources_count = prices.iter()
-            .map(|p| &p.source)
-            .collect::<std::collections::HashSet<_>>()
-            .len();
+        let relative_spread = mean_price.to_f64()
+            .filter(|mean| *mean > 0.0)
+            .map_or(0.0, |mean| std_deviation / mean);
+        let consistency_factor = 1.0 / (1.0 + relative_spread * 100.0);
         
-        let consistency_factor = if std_deviation > 0.0 {
-            1.0 / (1.0 + std_deviation)
+        let diversity_factor = sources_count as f64 / 5.0; // Assume 5 sources is ideal
+        let confidence = if stale || sources_count == 0 {
+            0.0
         } else {
-            1.0
+            (consistency_factor * 0.6 + diversity_factor.min(1.0) * 0.4).min(1.0)
         };
         
-        let diversity_factor = sources_count as f64 / 5.0; // Assume 5 sources is ideal
-        let confidence = (consistency_factor * 0.6 + diversity_factor.min(1.0) * 0.4).min(1.0);
-        
         // Collect source prices
-        let mut source_prices = HashMap::new();
-        for update in prices {
-            source_prices.insert(update.source.clone(), update.price);
-        }
+        let source_prices = accepted.iter()
+            .map(|q| (q.source.clone(), q.price))
+            .collect();
         
         AggregatedPrice {
             symbol,
@@ -551,10 +779,36 @@ impl PriceAggregator for DefaultPriceAggregator {
             confidence,
             sources_count,
             source_prices,
+            breakdown,
+            stale,
         }
     }
     
     fn name(&self) -> String {
         "DefaultPriceAggregator".to_string()
     }
-}
\ No newline at end of file
+}
+
+/// Jupiter's price API as a polled source, keyed by mint
+#[async_trait]
+impl QuoteSource for JupiterPriceV3Client {
+    fn source(&self) -> PriceSource {
+        PriceSource::Jupiter
+    }
+    
+    async fn quote(&self, symbol: &str) -> Result<Option<PriceUpdate>> {
+        let response = self.get_prices(vec![symbol.to_string()]).await?;
+        
+        Ok(response.prices.get(symbol)
+            .and_then(|data| Decimal::from_f64(data.usd_price))
+            .map(|price| PriceUpdate {
+                symbol: symbol.to_string(),
+                price,
+                timestamp: Utc::now(),
+                volume: None,
+                source: PriceSource::Jupiter,
+                update_type: UpdateType::Aggregate,
+                metadata: None,
+            }))
+    }
+}
@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{RwLock, mpsc};
-use tokio::time::{interval, sleep};
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
+use tokio::time::{interval, sleep, timeout, MissedTickBehavior};
 use tokio_tungstenite::{
     connect_async,
     tungstenite::{Message, Error as WsError},
@@ -16,6 +16,7 @@ use tracing::{info, debug, warn, error};
 use url::Url;
 
 use crate::errors::{BotError, Result};
+use crate::monitoring::MetricsCollector;
 use crate::telemetry::TelemetryService;
 
 /// WebSocket client for real-time data streaming
@@ -23,10 +24,16 @@ use crate::telemetry::TelemetryService;
 pub struct WebSocketClient {
     config: Arc<WebSocketConfig>,
     connections: Arc<RwLock<HashMap<String, ConnectionState>>>,
+    /// Active subscriptions per connection, replayed whenever it (re)connects
+    subscriptions: Arc<RwLock<HashMap<String, Vec<SubscriptionRequest>>>>,
+    /// Frames queued for each live connection's writer
+    outbound: Arc<RwLock<HashMap<String, mpsc::Sender<Message>>>>,
     message_handlers: Arc<RwLock<HashMap<String, Arc<dyn MessageHandler>>>>,
     error_handlers: Arc<RwLock<Vec<Arc<dyn ErrorHandler>>>>,
+    status_tx: broadcast::Sender<ConnectionEvent>,
     telemetry: Option<Arc<TelemetryService>>,
-    shutdown_tx: Arc<RwLock<Option<mpsc::Sender<()>>>>,
+    metrics: Option<Arc<MetricsCollector>>,
+    shutdown: Arc<watch::Sender<bool>>,
 }
 
 /// WebSocket configuration
@@ -34,8 +41,10 @@ pub struct WebSocketClient {
 pub struct WebSocketConfig {
     pub endpoints: HashMap<String, String>,
     pub reconnect_strategy: ReconnectStrategy,
+    /// How often a ping is sent; a heartbeat is missed when nothing arrived in between
     pub heartbeat_interval: Duration,
-    pub message_timeout: Duration,
+    /// Reconnect when nothing, not even a pong, arrives for this long
+    pub idle_timeout: Duration,
     pub max_message_size: usize,
     pub compression: bool,
     pub tls_config: Option<TlsConfig>,
@@ -65,15 +74,37 @@ pub struct ReconnectStrategy {
 pub struct ConnectionState {
     pub endpoint: String,
     pub status: ConnectionStatus,
-    pub subscriptions: Vec<SubscriptionRequest>,
+    /// Last time anything, a pong or data, arrived
     pub last_heartbeat: chrono::DateTime<chrono::Utc>,
+    /// Failed sessions since the connection was last up
     pub reconnect_attempts: u32,
+    pub reconnects: u64,
+    pub missed_heartbeats: u64,
+    pub resubscribed_channels: u64,
     pub messages_sent: u64,
     pub messages_received: u64,
     pub bytes_sent: u64,
     pub bytes_received: u64,
 }
 
+impl ConnectionState {
+    fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            status: ConnectionStatus::Connecting,
+            last_heartbeat: chrono::Utc::now(),
+            reconnect_attempts: 0,
+            reconnects: 0,
+            missed_heartbeats: 0,
+            resubscribed_channels: 0,
+            messages_sent: 0,
+            messages_received: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+        }
+    }
+}
+
 /// Connection status
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionStatus {
@@ -81,9 +112,19 @@ pub enum ConnectionStatus {
     Connected,
     Disconnected,
     Reconnecting,
+    /// Back up after a reconnect with this many subscriptions replayed; data
+    /// from before the gap may be stale
+    Resubscribed { channels: usize },
     Failed(String),
 }
 
+/// A status change on one connection, from `WebSocketClient::status_events`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionEvent {
+    pub connection: String,
+    pub status: ConnectionStatus,
+}
+
 /// WebSocket message types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -183,16 +224,29 @@ impl WebSocketClient {
     ) -> Self {
         info!("🔌 Initializing WebSocket client");
         
+        let (status_tx, _) = broadcast::channel(64);
+        let (shutdown, _) = watch::channel(false);
+        
         Self {
             config: Arc::new(config),
             connections: Arc::new(RwLock::new(HashMap::new())),
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            outbound: Arc::new(RwLock::new(HashMap::new())),
             message_handlers: Arc::new(RwLock::new(HashMap::new())),
             error_handlers: Arc::new(RwLock::new(Vec::new())),
+            status_tx,
             telemetry,
-            shutdown_tx: Arc::new(RwLock::new(None)),
+            metrics: None,
+            shutdown: Arc::new(shutdown),
         }
     }
     
+    /// Count reconnects, missed heartbeats and resubscriptions
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    
     /// Connect to WebSocket endpoint
     pub async fn connect(&self, name: &str, endpoint: &str) -> Result<()> {
         let _span = self.telemetry.as_ref().map(|t| 
@@ -205,46 +259,98 @@ impl WebSocketClient {
         let url = Url::parse(endpoint)
             .map_err(|e| BotError::config(format!("Invalid WebSocket URL: {}", e)))?;
         
-        // Create connection state
-        let state = ConnectionState {
-            endpoint: endpoint.to_string(),
-            status: ConnectionStatus::Connecting,
-            subscriptions: Vec::new(),
-            last_heartbeat: chrono::Utc::now(),
-            reconnect_attempts: 0,
-            messages_sent: 0,
-            messages_received: 0,
-            bytes_sent: 0,
-            bytes_received: 0,
-        };
-        
-        // Store connection state
+        // Keep counters from earlier sessions of the same connection
         {
             let mut connections = self.connections.write().await;
-            connections.insert(name.to_string(), state);
+            let state = connections.entry(name.to_string())
+                .or_insert_with(|| ConnectionState::new(endpoint));
+            state.endpoint = endpoint.to_string();
+            state.status = ConnectionStatus::Connecting;
         }
+        self.notify(name, ConnectionStatus::Connecting);
         
-        // Spawn connection handler
+        // Spawn connection loop
         let client = self.clone();
         let name = name.to_string();
-        let endpoint = endpoint.to_string();
         
         tokio::spawn(async move {
-            if let Err(e) = client.handle_connection(&name, &endpoint).await {
-                error!("🔌 WebSocket connection error for {}: {}", name, e);
-                client.handle_connection_error(&name, e).await;
-            }
+            client.run_connection(&name, url).await;
         });
         
         Ok(())
     }
     
-    /// Handle WebSocket connection
-    async fn handle_connection(&self, name: &str, endpoint: &str) -> Result<()> {
-        let url = Url::parse(endpoint)?;
+    /// Keep a connection up until shutdown or the reconnect budget runs out
+    async fn run_connection(&self, name: &str, url: Url) {
+        let mut shutdown_rx = self.shutdown.subscribe();
+        let mut resumed = false;
         
-        // Connect with retry logic
-        let ws_stream = self.connect_with_retry(&url).await?;
+        loop {
+            let ws_stream = match self.connect_with_retry(&url).await {
+                Ok(ws_stream) => ws_stream,
+                Err(e) => {
+                    error!("🔌 WebSocket connection error for {}: {}", name, e);
+                    self.handle_connection_error(name, e).await;
+                    return;
+                }
+            };
+            
+            if let Err(e) = self.handle_connection(name, ws_stream, resumed, &mut shutdown_rx).await {
+                warn!("🔌 WebSocket session error for {}: {}", name, e);
+            }
+            
+            if let Err(e) = self.handle_disconnection(name).await {
+                warn!("🔌 Disconnection handler failed for {}: {}", name, e);
+            }
+            
+            if !self.should_reconnect(name).await {
+                break;
+            }
+            
+            let attempt = {
+                let mut connections = self.connections.write().await;
+                match connections.get_mut(name) {
+                    Some(state) => {
+                        state.reconnect_attempts += 1;
+                        state.reconnects += 1;
+                        state.reconnect_attempts
+                    }
+                    None => break,
+                }
+            };
+            self.record_event(name, "reconnect", 1);
+            self.set_status(name, ConnectionStatus::Reconnecting).await;
+            
+            info!("🔌 Attempting to reconnect WebSocket: {} (attempt {})", name, attempt);
+            tokio::select! {
+                _ = sleep(self.reconnect_delay(attempt)) => {},
+                _ = shutdown_rx.changed() => break,
+            }
+            resumed = true;
+        }
+    }
+    
+    /// Run one session: replay subscriptions, send heartbeats and read until
+    /// the stream ends, goes idle or the client shuts down
+    async fn handle_connection(
+        &self,
+        name: &str,
+        ws_stream: WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
+        resumed: bool,
+        shutdown_rx: &mut watch::Receiver<bool>,
+    ) -> Result<()> {
+        // Split stream
+        let (mut write, mut read) = ws_stream.split();
+        
+        // Create channel for outgoing frames
+        let (tx, mut rx) = mpsc::channel::<Message>(100);
+        
+        // Register the sender and snapshot the subscriptions to replay
+        let replay = {
+            let subscriptions = self.subscriptions.read().await;
+            self.outbound.write().await.insert(name.to_string(), tx.clone());
+            subscriptions.get(name).cloned().unwrap_or_default()
+        };
         
         // Update connection status
         {
@@ -252,58 +358,18 @@ impl WebSocketClient {
             if let Some(state) = connections.get_mut(name) {
                 state.status = ConnectionStatus::Connected;
                 state.reconnect_attempts = 0;
+                state.last_heartbeat = chrono::Utc::now();
             }
         }
+        self.notify(name, ConnectionStatus::Connected);
         
         info!("🔌 WebSocket connected: {}", name);
         
-        // Split stream
-        let (mut write, mut read) = ws_stream.split();
-        
-        // Create channels for communication
-        let (tx, mut rx) = mpsc::channel::<Message>(100);
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
-        
-        // Store shutdown sender
-        {
-            let mut shutdown = self.shutdown_tx.write().await;
-            *shutdown = Some(shutdown_tx);
-        }
-        
-        // Spawn heartbeat task
-        let client = self.clone();
-        let name_clone = name.to_string();
-        let tx_clone = tx.clone();
-        
-        tokio::spawn(async move {
-            let mut heartbeat = interval(client.config.heartbeat_interval);
-            
-            loop {
-                tokio::select! {
-                    _ = heartbeat.tick() => {
-                        let ping = Message::Ping(vec![]);
-                        if tx_clone.send(ping).await.is_err() {
-                            break;
-                        }
-                        
-                        // Update heartbeat timestamp
-                        let mut connections = client.connections.write().await;
-                        if let Some(state) = connections.get_mut(&name_clone) {
-                            state.last_heartbeat = chrono::Utc::now();
-                        }
-                    }
-                    _ = shutdown_rx.recv() => {
-                        break;
-                    }
-                }
-            }
-        });
-        
         // Spawn write task
         let client = self.clone();
         let name_clone = name.to_string();
         
-        tokio::spawn(async move {
+        let mut writer = tokio::spawn(async move {
             while let Some(msg) = rx.recv().await {
                 if let Err(e) = write.send(msg.clone()).await {
                     error!("🔌 Failed to send WebSocket message: {}", e);
@@ -319,37 +385,116 @@ impl WebSocketClient {
             }
         });
         
+        // Replay subscriptions
+        for request in &replay {
+            let json = serde_json::to_string(&WebSocketMessage::Subscribe(request.clone()))
+                .map_err(|e| BotError::serialization(e))?;
+            if tx.send(Message::Text(json)).await.is_err() {
+                break;
+            }
+        }
+        
+        if resumed {
+            info!("🔌 Resubscribed {} channels on {}", replay.len(), name);
+            {
+                let mut connections = self.connections.write().await;
+                if let Some(state) = connections.get_mut(name) {
+                    state.resubscribed_channels += replay.len() as u64;
+                }
+            }
+            self.record_event(name, "resubscribed", replay.len() as u64);
+            self.notify(name, ConnectionStatus::Resubscribed { channels: replay.len() });
+        }
+        
+        // Spawn heartbeat task
+        let client = self.clone();
+        let name_clone = name.to_string();
+        let tx_clone = tx.clone();
+        let period = self.config.heartbeat_interval;
+        
+        let heartbeat = tokio::spawn(async move {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker.tick().await;
+            
+            loop {
+                ticker.tick().await;
+                
+                if client.heartbeat_missed(&name_clone, period).await {
+                    warn!("🔌 Missed heartbeat on {}", name_clone);
+                    client.record_event(&name_clone, "missed_heartbeat", 1);
+                }
+                
+                if tx_clone.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+        });
+        
         // Handle incoming messages
-        while let Some(result) = read.next().await {
-            match result {
-                Ok(msg) => {
-                    // Update stats
-                    {
-                        let mut connections = self.connections.write().await;
-                        if let Some(state) = connections.get_mut(name) {
-                            state.messages_received += 1;
-                            state.bytes_received += msg.len() as u64;
+        if !*shutdown_rx.borrow() {
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => break,
+                    next = timeout(self.config.idle_timeout, read.next()) => match next {
+                        Ok(Some(Ok(msg))) => {
+                            // Update stats
+                            {
+                                let mut connections = self.connections.write().await;
+                                if let Some(state) = connections.get_mut(name) {
+                                    state.messages_received += 1;
+                                    state.bytes_received += msg.len() as u64;
+                                    state.last_heartbeat = chrono::Utc::now();
+                                }
+                            }
+                            
+                            // Process message
+                            if let Err(e) = self.process_message(name, msg).await {
+                                warn!("🔌 Failed to process WebSocket message: {}", e);
+                            }
+                        },
+                        Ok(Some(Err(e))) => {
+                            error!("🔌 WebSocket error: {}", e);
+                            break;
+                        },
+                        Ok(None) => break,
+                        Err(_) => {
+                            warn!("🔌 Nothing received on {} for {:?}, reconnecting", name, self.config.idle_timeout);
+                            self.notify_error(WebSocketError::HeartbeatTimeout).await;
+                            break;
                         }
                     }
-                    
-                    // Process message
-                    if let Err(e) = self.process_message(name, msg).await {
-                        warn!("🔌 Failed to process WebSocket message: {}", e);
-                    }
-                },
-                Err(e) => {
-                    error!("🔌 WebSocket error: {}", e);
-                    break;
                 }
             }
         }
         
-        // Handle disconnection
-        self.handle_disconnection(name).await?;
+        // Stop the session's tasks; the socket closes once both halves drop
+        heartbeat.abort();
+        self.outbound.write().await.remove(name);
+        drop(tx);
+        if timeout(self.config.heartbeat_interval, &mut writer).await.is_err() {
+            writer.abort();
+        }
         
         Ok(())
     }
     
+    /// Count a missed heartbeat when nothing arrived during the last interval
+    async fn heartbeat_missed(&self, name: &str, period: Duration) -> bool {
+        let mut connections = self.connections.write().await;
+        let Some(state) = connections.get_mut(name) else {
+            return false;
+        };
+        
+        let silent = chrono::Utc::now() - state.last_heartbeat;
+        if silent.to_std().map_or(false, |silent| silent > period) {
+            state.missed_heartbeats += 1;
+            true
+        } else {
+            false
+        }
+    }
+    
     /// Connect with retry logic
     async fn connect_with_retry(&self, url: &Url) -> Result<WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>> {
         let mut attempts = 0;
@@ -387,6 +532,21 @@ impl WebSocketClient {
         }
     }
     
+    /// Delay before reconnect attempt `attempt` (1-based)
+    fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let strategy = &self.config.reconnect_strategy;
+        let mut delay = strategy.initial_delay;
+        if strategy.exponential_backoff {
+            delay = delay
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+                .min(strategy.max_delay);
+        }
+        if strategy.jitter {
+            delay = delay.mul_f64(1.0 + rand::random::<f64>() * 0.3);
+        }
+        delay
+    }
+    
     /// Process incoming WebSocket message
     async fn process_message(&self, name: &str, msg: Message) -> Result<()> {
         match msg {
@@ -417,7 +577,7 @@ impl WebSocketClient {
                 debug!("🔌 Received binary message from {}: {} bytes", name, data.len());
                 // Handle binary data if needed
             },
-            Message::Ping(data) => {
+            Message::Ping(_) => {
                 debug!("🔌 Received ping from {}", name);
                 // Pong is usually sent automatically by the library
             },
@@ -468,17 +628,22 @@ impl WebSocketClient {
         Ok(())
     }
     
+    /// Pass a client-side error to the error handlers
+    async fn notify_error(&self, error: WebSocketError) {
+        let handlers = self.error_handlers.read().await;
+        for handler in handlers.iter() {
+            if let Err(e) = handler.handle_error(error.clone()).await {
+                warn!("🔌 Error handler failed: {}", e);
+            }
+        }
+    }
+    
     /// Handle disconnection
     async fn handle_disconnection(&self, name: &str) -> Result<()> {
         warn!("🔌 WebSocket disconnected: {}", name);
         
         // Update connection status
-        {
-            let mut connections = self.connections.write().await;
-            if let Some(state) = connections.get_mut(name) {
-                state.status = ConnectionStatus::Disconnected;
-            }
-        }
+        self.set_status(name, ConnectionStatus::Disconnected).await;
         
         // Notify error handlers
         let handlers = self.error_handlers.read().await;
@@ -486,11 +651,6 @@ impl WebSocketClient {
             handler.handle_disconnection(name.to_string()).await?;
         }
         
-        // Attempt reconnection if configured
-        if self.should_reconnect(name).await {
-            self.reconnect(name).await?;
-        }
-        
         Ok(())
     }
     
@@ -499,14 +659,15 @@ impl WebSocketClient {
         error!("🔌 Connection error for {}: {}", name, error);
         
         // Update connection status
-        let mut connections = self.connections.write().await;
-        if let Some(state) = connections.get_mut(name) {
-            state.status = ConnectionStatus::Failed(error.to_string());
-        }
+        self.set_status(name, ConnectionStatus::Failed(error.to_string())).await;
     }
     
     /// Check if should reconnect
     async fn should_reconnect(&self, name: &str) -> bool {
+        if *self.shutdown.borrow() {
+            return false;
+        }
+        
         let connections = self.connections.read().await;
         
         if let Some(state) = connections.get(name) {
@@ -516,41 +677,41 @@ impl WebSocketClient {
         }
     }
     
-    /// Reconnect to WebSocket
-    async fn reconnect(&self, name: &str) -> Result<()> {
-        info!("🔌 Attempting to reconnect WebSocket: {}", name);
-        
-        // Get endpoint
-        let endpoint = {
-            let connections = self.connections.read().await;
-            connections.get(name)
-                .map(|s| s.endpoint.clone())
-                .ok_or_else(|| BotError::not_found(format!("Connection {} not found", name)))?
-        };
-        
-        // Update reconnect attempts
+    /// Update a connection's status and broadcast the change
+    async fn set_status(&self, name: &str, status: ConnectionStatus) {
         {
             let mut connections = self.connections.write().await;
             if let Some(state) = connections.get_mut(name) {
-                state.status = ConnectionStatus::Reconnecting;
-                state.reconnect_attempts += 1;
+                state.status = status.clone();
             }
         }
-        
-        // Reconnect
-        self.connect(name, &endpoint).await
+        self.notify(name, status);
+    }
+    
+    fn notify(&self, name: &str, status: ConnectionStatus) {
+        // No receivers is fine
+        let _ = self.status_tx.send(ConnectionEvent {
+            connection: name.to_string(),
+            status,
+        });
     }
     
-    /// Subscribe to data stream
+    fn record_event(&self, name: &str, event: &str, count: u64) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_websocket_event(name, event, count);
+        }
+    }
+    
+    /// Subscribe to data stream; the request is replayed on every reconnect
     pub async fn subscribe(&self, connection: &str, request: SubscriptionRequest) -> Result<()> {
         info!("🔌 Subscribing to: {:?}", request.subscription_type);
         
         // Store subscription
         {
-            let mut connections = self.connections.write().await;
-            if let Some(state) = connections.get_mut(connection) {
-                state.subscriptions.push(request.clone());
-            }
+            let mut subscriptions = self.subscriptions.write().await;
+            let active = subscriptions.entry(connection.to_string()).or_default();
+            active.retain(|s| s.id != request.id);
+            active.push(request.clone());
         }
         
         // Send subscription message
@@ -564,9 +725,9 @@ impl WebSocketClient {
         
         // Remove subscription
         {
-            let mut connections = self.connections.write().await;
-            if let Some(state) = connections.get_mut(connection) {
-                state.subscriptions.retain(|s| s.id != subscription_id);
+            let mut subscriptions = self.subscriptions.write().await;
+            if let Some(active) = subscriptions.get_mut(connection) {
+                active.retain(|s| s.id != subscription_id);
             }
         }
         
@@ -577,13 +738,26 @@ impl WebSocketClient {
         self.send_message(connection, msg).await
     }
     
+    /// Active subscriptions on a connection
+    pub async fn subscriptions(&self, connection: &str) -> Vec<SubscriptionRequest> {
+        let subscriptions = self.subscriptions.read().await;
+        subscriptions.get(connection).cloned().unwrap_or_default()
+    }
+    
     /// Send message to WebSocket
-    async fn send_message(&self, _connection: &str, msg: WebSocketMessage) -> Result<()> {
+    async fn send_message(&self, connection: &str, msg: WebSocketMessage) -> Result<()> {
         let json = serde_json::to_string(&msg)
             .map_err(|e| BotError::serialization(e))?;
         
-        // In production, would send through the actual WebSocket connection
-        debug!("🔌 Sending message: {}", json);
+        let sender = self.outbound.read().await.get(connection).cloned();
+        if let Some(tx) = sender {
+            if tx.send(Message::Text(json)).await.is_ok() {
+                return Ok(());
+            }
+        } else {
+            // Subscriptions go out when the connection comes up
+            debug!("🔌 {} not connected, not sending: {}", connection, json);
+        }
         
         Ok(())
     }
@@ -601,6 +775,11 @@ impl WebSocketClient {
         handlers.push(handler);
     }
     
+    /// Status changes on every connection, including `Resubscribed` after a reconnect
+    pub fn status_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.status_tx.subscribe()
+    }
+    
     /// Get connection status
     pub async fn get_status(&self, name: &str) -> Option<ConnectionStatus> {
         let connections = self.connections.read().await;
@@ -618,14 +797,12 @@ impl WebSocketClient {
         info!("🔌 Shutting down WebSocket client");
         
         // Send shutdown signal
-        if let Some(tx) = self.shutdown_tx.write().await.take() {
-            let _ = tx.send(()).await;
-        }
+        self.shutdown.send_replace(true);
         
         // Update all connections to disconnected
-        let mut connections = self.connections.write().await;
-        for (_, state) in connections.iter_mut() {
-            state.status = ConnectionStatus::Disconnected;
+        let names: Vec<String> = self.connections.read().await.keys().cloned().collect();
+        for name in names {
+            self.set_status(&name, ConnectionStatus::Disconnected).await;
         }
         
         Ok(())
@@ -638,7 +815,7 @@ impl Default for WebSocketConfig {
             endpoints: HashMap::new(),
             reconnect_strategy: ReconnectStrategy::default(),
             heartbeat_interval: Duration::from_secs(30),
-            message_timeout: Duration::from_secs(60),
+            idle_timeout: Duration::from_secs(60),
             max_message_size: 10 * 1024 * 1024, // 10MB
             compression: true,
             tls_config: None,